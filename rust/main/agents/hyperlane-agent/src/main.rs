@@ -0,0 +1,79 @@
+//! A single binary running any of the agents, plus operational tools.
+//!
+//! ```text
+//! hyperlane-agent <relayer|validator|scraper> [run|config validate] [FLAGS]
+//! hyperlane-agent tools metrics-reference
+//! ```
+//!
+//! The flags are shared by all agents and documented in
+//! [`hyperlane_base::cli`].
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::ffi::OsString;
+
+use eyre::{bail, eyre, Result};
+use hyperlane_base::{agent_main_with_args, cli::AgentArgs, BaseAgent};
+use hyperlane_core::metrics::definitions::metrics_reference;
+use relayer::Relayer;
+use scraper::Scraper;
+use tokio::runtime::{Builder, Runtime};
+use validator::Validator;
+
+const USAGE: &str = "\
+Usage:
+  hyperlane-agent <relayer|validator|scraper> [run|config validate] [--config <paths>] \
+[--log-format <format>] [--log-level <level>] [--metrics-port <port>] [--<config key> <value>...]
+  hyperlane-agent tools metrics-reference";
+
+fn main() -> Result<()> {
+    let mut args = std::env::args_os().skip(1);
+    let Some(command) = args.next() else {
+        bail!("Missing command\n\n{USAGE}");
+    };
+    let args: Vec<OsString> = args.collect();
+
+    match command.to_str() {
+        Some("relayer") => run_agent::<Relayer>(runtime(true)?, args),
+        Some("validator") => run_agent::<Validator>(runtime(false)?, args),
+        Some("scraper") => run_agent::<Scraper>(runtime(false)?, args),
+        Some("tools") => run_tool(args),
+        Some("help" | "--help" | "-h") => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => bail!("Unknown command {command:?}\n\n{USAGE}"),
+    }
+}
+
+/// The relayer runs on as many worker threads as its own binary does, the
+/// other agents on the current thread
+fn runtime(multi_thread: bool) -> Result<Runtime> {
+    let mut builder = if multi_thread {
+        let mut builder = Builder::new_multi_thread();
+        builder.worker_threads(20);
+        builder
+    } else {
+        Builder::new_current_thread()
+    };
+    Ok(builder.enable_all().build()?)
+}
+
+fn run_agent<A: BaseAgent>(runtime: Runtime, args: Vec<OsString>) -> Result<()> {
+    let args = AgentArgs::parse(&format!("hyperlane-agent {}", A::AGENT_NAME), args)?;
+    // Logging is not initialised at this point, so, using `println!`
+    println!("Starting {}...", A::AGENT_NAME);
+    runtime.block_on(agent_main_with_args::<A>(args))
+}
+
+fn run_tool(args: Vec<OsString>) -> Result<()> {
+    match args.first().and_then(|tool| tool.to_str()) {
+        Some("metrics-reference") => {
+            print!("{}", metrics_reference());
+            Ok(())
+        }
+        Some(tool) => bail!("Unknown tool `{tool}`\n\n{USAGE}"),
+        None => Err(eyre!("Missing tool\n\n{USAGE}")),
+    }
+}
@@ -1,7 +1,6 @@
 mod merkle_tree;
 mod msg;
 mod processor;
-mod prover;
 mod relayer;
 mod settings;
 
@@ -1,32 +1,30 @@
 use std::fmt::Display;
 
 use eyre::{Context, Result};
-use tracing::{debug, error, instrument};
+use tracing::{debug, instrument, warn};
 
-use hyperlane_base::db::DbError;
+use hyperlane_base::db::{DbError, HyperlaneDb, HyperlaneRocksDB};
 use hyperlane_core::{
-    accumulator::{incremental::IncrementalMerkle, merkle::Proof},
+    accumulator::{
+        merkle::{MerkleTreeError, Proof},
+        snapshot_prover::{MerkleTreeSnapshotStore, SnapshotProver, SnapshotProverError},
+    },
     ChainCommunicationError, H256,
 };
 
-use crate::prover::{Prover, ProverError};
+/// Number of leaves ingested after which a snapshot of the tree is persisted,
+/// unless the tree caught up with the indexed insertions before
+const SNAPSHOT_INTERVAL: usize = 1_000;
 
 /// Struct to sync prover.
 #[derive(Debug)]
 pub struct MerkleTreeBuilder {
-    prover: Prover,
-    incremental: IncrementalMerkle,
+    prover: SnapshotProver,
 }
 
 impl Display for MerkleTreeBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "MerkleTreeBuilder {{ ")?;
-        write!(
-            f,
-            "incremental: {{ root: {:?}, size: {} }}, ",
-            self.incremental.root(),
-            self.incremental.count()
-        )?;
         write!(
             f,
             "prover: {{ root: {:?}, size: {} }} ",
@@ -41,17 +39,12 @@ impl Display for MerkleTreeBuilder {
 /// MerkleTreeBuilder errors
 #[derive(Debug, thiserror::Error)]
 pub enum MerkleTreeBuilderError {
-    /// Local tree up-to-date but root does not match signed checkpoint"
-    #[error("Prover root does not match incremental root: {prover_root}, incremental: {incremental_root}")]
-    MismatchedRoots {
-        /// Root of prover's local merkle tree
-        prover_root: H256,
-        /// Root of the incremental merkle tree
-        incremental_root: H256,
-    },
+    /// The full and the incremental tree disagree, or the tree is full
+    #[error(transparent)]
+    MerkleTreeError(#[from] MerkleTreeError),
     /// MerkleTreeBuilder attempts Prover operation and receives ProverError
     #[error(transparent)]
-    ProverError(#[from] ProverError),
+    ProverError(#[from] SnapshotProverError),
     /// MerkleTreeBuilder receives ChainCommunicationError from chain API
     #[error(transparent)]
     ChainCommunicationError(#[from] ChainCommunicationError),
@@ -61,13 +54,44 @@ pub enum MerkleTreeBuilderError {
 }
 
 impl MerkleTreeBuilder {
-    pub fn new() -> Self {
-        let prover = Prover::default();
-        let incremental = IncrementalMerkle::default();
-        Self {
-            prover,
-            incremental,
+    /// Restore the tree of an origin from the snapshot in its db, so that
+    /// only the leaves inserted since are ingested again. Leaves of the
+    /// snapshot whose insertions were since reorged out of the chain are
+    /// removed.
+    pub fn restore(db: &HyperlaneRocksDB) -> Result<Self, MerkleTreeBuilderError> {
+        let mut builder = Self {
+            prover: SnapshotProver::restore(db)?,
+        };
+        let count = Self::indexed_leaf_count(db, builder.count())?;
+        if count < builder.count() {
+            warn!(
+                count,
+                snapshot_count = builder.count(),
+                "Merkle tree snapshot has leaves that aren't indexed insertions"
+            );
+            builder.rewind(count, db)?;
+        }
+        debug!(
+            count = builder.count(),
+            "Restored merkle tree from snapshot"
+        );
+        Ok(builder)
+    }
+
+    /// The number of the first `count` leaves of the snapshot that match the
+    /// indexed insertions. Reorgs only remove insertions from the end of the
+    /// tree, so the leaves are checked from the last one backwards until one
+    /// matches its insertion.
+    fn indexed_leaf_count(db: &HyperlaneRocksDB, mut count: u32) -> Result<u32, DbError> {
+        while let Some(leaf_index) = count.checked_sub(1) {
+            let leaf = db.retrieve_merkle_tree_snapshot_leaf(leaf_index)?;
+            let insertion = db.retrieve_merkle_tree_insertion_by_leaf_index(&leaf_index)?;
+            if leaf.is_some() && leaf == insertion.map(|insertion| insertion.message_id()) {
+                break;
+            }
+            count = leaf_index;
         }
+        Ok(count)
     }
 
     #[instrument(err, skip(self), level="debug", fields(prover_latest_index=self.count()-1))]
@@ -77,7 +101,7 @@ impl MerkleTreeBuilder {
         root_index: u32,
     ) -> Result<Proof, MerkleTreeBuilderError> {
         self.prover
-            .prove_against_previous(leaf_index as usize, root_index as usize)
+            .prove(leaf_index, root_index)
             .map_err(MerkleTreeBuilderError::from)
     }
 
@@ -88,15 +112,86 @@ impl MerkleTreeBuilder {
     pub async fn ingest_message_id(&mut self, message_id: H256) -> Result<()> {
         const CTX: &str = "When ingesting message id";
         debug!(?message_id, "Ingesting leaf");
-        self.prover.ingest(message_id).expect("tree full");
-        self.incremental.ingest(message_id);
-        match self.prover.root().eq(&self.incremental.root()) {
-            true => Ok(()),
-            false => Err(MerkleTreeBuilderError::MismatchedRoots {
-                prover_root: self.prover.root(),
-                incremental_root: self.incremental.root(),
-            }),
+        self.prover
+            .ingest(message_id)
+            .map_err(MerkleTreeBuilderError::from)
+            .context(CTX)
+    }
+
+    /// Remove the leaves from `count` onwards, e.g. because their insertions
+    /// were reorged out of the chain, and persist the remaining tree so that
+    /// the removed leaves aren't restored after a restart
+    pub fn rewind(
+        &mut self,
+        count: u32,
+        db: &HyperlaneRocksDB,
+    ) -> Result<(), MerkleTreeBuilderError> {
+        if count >= self.count() {
+            return Ok(());
         }
-        .context(CTX)
+        self.prover.truncate(count as usize);
+        self.prover.persist(db)?;
+        debug!(count, "Rewound merkle tree");
+        Ok(())
+    }
+
+    /// Persist the leaves ingested since the last snapshot once there are
+    /// enough of them, or once `caught_up` with the indexed insertions.
+    /// Only the new leaves and the branch of the tree are written.
+    pub fn persist_snapshot(
+        &mut self,
+        db: &HyperlaneRocksDB,
+        caught_up: bool,
+    ) -> Result<(), MerkleTreeBuilderError> {
+        let unpersisted = self.prover.unpersisted_count();
+        if unpersisted >= SNAPSHOT_INTERVAL || (caught_up && unpersisted > 0) {
+            self.prover.persist(db)?;
+            debug!(
+                count = self.prover.count(),
+                "Persisted merkle tree snapshot"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyperlane_base::db::DB;
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain, MerkleTreeInsertion};
+
+    use super::*;
+
+    fn leaf(i: u32) -> H256 {
+        H256::from_low_u64_be(i as u64 + 1)
+    }
+
+    #[tokio::test]
+    async fn test_restore_removes_reorged_leaves() {
+        hyperlane_base::db::test_utils::run_test_db(|db: DB| async move {
+            let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Test1);
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let mut builder = MerkleTreeBuilder::restore(&db).unwrap();
+            for i in 0..5 {
+                db.process_tree_insertion(&MerkleTreeInsertion::new(i, leaf(i)), 0)
+                    .unwrap();
+                builder.ingest_message_id(leaf(i)).await.unwrap();
+            }
+            builder.persist_snapshot(&db, true).unwrap();
+            let root = builder.prover.root();
+            assert_eq!(MerkleTreeBuilder::restore(&db).unwrap().prover.root(), root);
+
+            // The last two insertions are reorged out, and another insertion
+            // of the canonical chain is indexed in place of the first of them
+            db.invalidate_tree_insertions([3, 4]).unwrap();
+            db.process_tree_insertion(&MerkleTreeInsertion::new(3, leaf(9)), 0)
+                .unwrap();
+
+            let restored = MerkleTreeBuilder::restore(&db).unwrap();
+            assert_eq!(restored.count(), 3);
+            // The rewind is persisted
+            assert_eq!(MerkleTreeBuilder::restore(&db).unwrap().count(), 3);
+        })
+        .await;
     }
 }
@@ -14,7 +14,7 @@ use hyperlane_base::{
 use hyperlane_core::{HyperlaneDomain, MerkleTreeInsertion};
 use prometheus::IntGauge;
 use tokio::sync::RwLock;
-use tracing::trace;
+use tracing::{trace, warn};
 
 use crate::processor::ProcessorExt;
 
@@ -28,6 +28,9 @@ pub struct MerkleTreeProcessor {
     prover_sync: Arc<RwLock<MerkleTreeBuilder>>,
     #[new(default)]
     leaf_index: u32,
+    /// Number of reorgs of tree insertions the prover sync was rewound for
+    #[new(default)]
+    seen_reorgs: Option<u32>,
 }
 
 impl Debug for MerkleTreeProcessor {
@@ -50,17 +53,27 @@ impl ProcessorExt for MerkleTreeProcessor {
     /// One round of processing, extracted from infinite work loop for
     /// testing purposes.
     async fn tick(&mut self) -> Result<()> {
+        self.rewind_to_reorged_leaves().await?;
+
+        // Leaves in the snapshot the prover sync was restored from don't need
+        // to be ingested again
+        self.leaf_index = self.leaf_index.max(self.prover_sync.read().await.count());
+
         if let Some(insertion) = self.next_unprocessed_leaf()? {
             // Feed the message to the prover sync
-            self.prover_sync
-                .write()
-                .await
+            let mut prover_sync = self.prover_sync.write().await;
+            prover_sync
                 .ingest_message_id(insertion.message_id())
                 .await?;
+            prover_sync.persist_snapshot(&self.db, false)?;
 
             // Increase the leaf index to move on to the next leaf
             self.leaf_index += 1;
         } else {
+            self.prover_sync
+                .write()
+                .await
+                .persist_snapshot(&self.db, true)?;
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
         Ok(())
@@ -68,6 +81,39 @@ impl ProcessorExt for MerkleTreeProcessor {
 }
 
 impl MerkleTreeProcessor {
+    /// Rewind the prover sync to the lowest leaf index removed by the reorgs
+    /// recorded since the last rewind, so that the insertions of the
+    /// canonical chain are ingested in place of the reorged ones
+    async fn rewind_to_reorged_leaves(&mut self) -> Result<()> {
+        let reorgs = self
+            .db
+            .retrieve_tree_insertion_reorg_count()?
+            .unwrap_or_default();
+        let seen_reorgs = *self.seen_reorgs.get_or_insert(reorgs);
+        let mut lowest_reorged_leaf_index: Option<u32> = None;
+        for reorg_index in seen_reorgs..reorgs {
+            if let Some(leaf_index) = self
+                .db
+                .retrieve_lowest_reorged_leaf_index_by_reorg_index(&reorg_index)?
+            {
+                lowest_reorged_leaf_index = Some(
+                    lowest_reorged_leaf_index.map_or(leaf_index, |lowest| lowest.min(leaf_index)),
+                );
+            }
+        }
+        self.seen_reorgs = Some(reorgs);
+        let Some(leaf_index) = lowest_reorged_leaf_index else {
+            return Ok(());
+        };
+        self.prover_sync
+            .write()
+            .await
+            .rewind(leaf_index, &self.db)?;
+        self.leaf_index = self.leaf_index.min(leaf_index);
+        warn!(leaf_index, "Rewound merkle tree to reorged leaf");
+        Ok(())
+    }
+
     fn next_unprocessed_leaf(&mut self) -> Result<Option<MerkleTreeInsertion>> {
         let leaf = if let Some(insertion) = self
             .db
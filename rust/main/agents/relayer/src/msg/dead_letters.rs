@@ -0,0 +1,168 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use derive_new::new;
+use eyre::{eyre, Result};
+use hyperlane_base::db::{DbResult, DeadLetter, DeadLetterReason, HyperlaneDb, HyperlaneRocksDB};
+use hyperlane_core::{HyperlaneMessage, PendingOperationStatus, UndeliverableReason, H256};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{info, warn};
+
+use crate::settings::matching_list::MatchingList;
+
+/// Outcome of importing dead letters, e.g. exported by another relayer
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct DeadLetterImport {
+    /// how many dead letters were imported
+    pub imported: usize,
+    /// how many dead letters were rejected, because their origin isn't
+    /// relayed from or their message doesn't match their id
+    pub rejected: usize,
+}
+
+/// Outcome of re-injecting dead letters
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct DeadLetterReinjection {
+    /// how many dead letters were evaluated
+    pub evaluated: usize,
+    /// how many of the dead letters matched the pattern and were handed to
+    /// the processors of their origins to be re-injected
+    pub reinjected: usize,
+}
+
+/// Move `message` to the dead-letter queue in the database of its origin, so
+/// that it isn't picked up again until it's re-injected
+pub(crate) fn store_dead_letter(
+    db: &HyperlaneRocksDB,
+    message: &HyperlaneMessage,
+    reason: DeadLetterReason,
+    last_status: String,
+    num_retries: u32,
+) -> DbResult<()> {
+    let dead_letter = DeadLetter {
+        message_id: message.id(),
+        origin: message.origin,
+        destination: message.destination,
+        nonce: message.nonce,
+        message: message.clone(),
+        reason,
+        last_status,
+        num_retries,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default(),
+    };
+    db.store_dead_letter(&dead_letter)?;
+    db.store_status_by_message_id(
+        &dead_letter.message_id,
+        &PendingOperationStatus::Undeliverable(UndeliverableReason::DeadLettered),
+    )
+}
+
+/// The messages the relayer gave up on delivering after they exhausted their
+/// retries, persisted in the databases of their origins.
+///
+/// Operators can list dead letters, import dead letters exported elsewhere,
+/// and re-inject them. Re-injected dead letters are handed back to the
+/// processor of their origin, which runs them through its filters and
+/// ordering gate like indexed messages, resets their retries and removes them
+/// from the queue once they were sent on.
+#[derive(new)]
+pub struct DeadLetterQueue {
+    /// Databases by origin domain id
+    dbs: HashMap<u32, HyperlaneRocksDB>,
+    /// Channels to the message processors by origin domain id
+    reinject_channels: HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+}
+
+impl DeadLetterQueue {
+    /// The dead letters of all origins, or only of `origin` if set
+    pub fn list(&self, origin: Option<u32>) -> Result<Vec<DeadLetter>> {
+        let mut dead_letters = vec![];
+        for (domain, db) in &self.dbs {
+            if origin.is_some_and(|origin| origin != *domain) {
+                continue;
+            }
+            dead_letters.extend(db.retrieve_dead_letters()?);
+        }
+        dead_letters.sort_by_key(|dead_letter| (dead_letter.origin, dead_letter.nonce));
+        Ok(dead_letters)
+    }
+
+    /// Store dead letters in the databases of their origins, so that they
+    /// can be inspected and re-injected
+    pub fn import(&self, dead_letters: Vec<DeadLetter>) -> Result<DeadLetterImport> {
+        let mut outcome = DeadLetterImport::default();
+        for dead_letter in dead_letters {
+            let Some(db) = self.dbs.get(&dead_letter.origin) else {
+                warn!(message_id = ?dead_letter.message_id, origin = dead_letter.origin, "Rejecting dead letter from an origin that isn't relayed from");
+                outcome.rejected += 1;
+                continue;
+            };
+            if !dead_letter.is_consistent() {
+                warn!(message_id = ?dead_letter.message_id, "Rejecting dead letter whose message doesn't match its id");
+                outcome.rejected += 1;
+                continue;
+            }
+            db.store_dead_letter(&dead_letter)?;
+            db.store_status_by_message_id(
+                &dead_letter.message_id,
+                &PendingOperationStatus::Undeliverable(UndeliverableReason::DeadLettered),
+            )?;
+            outcome.imported += 1;
+        }
+        Ok(outcome)
+    }
+
+    /// Move a message that was dropped from a prepare queue on request to the
+    /// dead-letter queue, so that it isn't picked up again on restart
+    pub fn store_dropped(&self, origin: u32, message_id: H256, last_status: String) -> Result<()> {
+        let db = self
+            .dbs
+            .get(&origin)
+            .ok_or_else(|| eyre!("No database found for domain {origin}"))?;
+        let message = db
+            .retrieve_message_by_id(&message_id)?
+            .ok_or_else(|| eyre!("Message {message_id:?} not found in the database"))?;
+        let num_retries = db
+            .retrieve_pending_message_retry_count_by_message_id(&message_id)?
+            .unwrap_or_default();
+        store_dead_letter(
+            db,
+            &message,
+            DeadLetterReason::DroppedOnRequest,
+            last_status,
+            num_retries,
+        )?;
+        Ok(())
+    }
+
+    /// Hand the dead letters matching `pattern` to the processors of their
+    /// origins, which re-inject them unless they're filtered out
+    pub fn reinject(&self, pattern: &MatchingList) -> Result<DeadLetterReinjection> {
+        let mut outcome = DeadLetterReinjection::default();
+        for (origin, db) in &self.dbs {
+            for dead_letter in db.retrieve_dead_letters()? {
+                outcome.evaluated += 1;
+                let message = dead_letter.message;
+                if !pattern.msg_matches(&message, false) {
+                    continue;
+                }
+                let Some(reinject_channel) = self.reinject_channels.get(origin) else {
+                    warn!(%message, "Not re-injecting dead letter from an origin that isn't relayed from");
+                    continue;
+                };
+                info!(%message, "Re-injecting dead letter on request");
+                reinject_channel
+                    .send(message)
+                    .map_err(|_| eyre!("Message processor of origin {origin} stopped"))?;
+                outcome.reinjected += 1;
+            }
+        }
+        Ok(outcome)
+    }
+}
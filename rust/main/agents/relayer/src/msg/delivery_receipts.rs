@@ -0,0 +1,289 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use eyre::{Context, Result};
+use hyperlane_base::db::{DeliveryReceipt, HyperlaneRocksDB};
+use serde_json::json;
+use tokio::{sync::Notify, task::JoinHandle, time::sleep};
+use tracing::{debug, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::DeliveryReceiptSink;
+
+/// How often the exporter checks for new receipts if it isn't notified of
+/// them, e.g. because they were appended before a restart.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Delay before retrying a failed export for the first time
+const EXPORT_RETRY_MIN_BACKOFF: Duration = Duration::from_secs(1);
+/// Maximum delay between retries of a failed export
+const EXPORT_RETRY_MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// The log of receipts of messages from an origin delivered by this relayer,
+/// persisted in the origin's database. Receipts are exported from the log by a
+/// `DeliveryReceiptExporter`.
+#[derive(Debug)]
+pub struct DeliveryReceiptLog {
+    db: HyperlaneRocksDB,
+    /// Offset the next receipt is appended at
+    next_offset: Mutex<u64>,
+    appended: Notify,
+}
+
+impl DeliveryReceiptLog {
+    pub fn new(db: HyperlaneRocksDB) -> Result<Self> {
+        let next_offset = db.retrieve_delivery_receipt_count()?.unwrap_or_default();
+        Ok(Self {
+            db,
+            next_offset: Mutex::new(next_offset),
+            appended: Notify::new(),
+        })
+    }
+
+    /// Durably mark the message of a receipt as processed and append the
+    /// receipt to the log, atomically
+    pub fn append_processed(&self, receipt: &DeliveryReceipt) -> Result<()> {
+        {
+            let mut next_offset = self.next_offset.lock().expect("receipt log lock poisoned");
+            self.db.store_processed_by_nonce_with_delivery_receipt(
+                &receipt.nonce,
+                *next_offset,
+                receipt,
+            )?;
+            *next_offset += 1;
+        }
+        self.appended.notify_one();
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        *self.next_offset.lock().expect("receipt log lock poisoned")
+    }
+
+    /// Up to `limit` receipts starting at `offset`
+    fn read(&self, offset: u64, limit: u64) -> Result<Vec<DeliveryReceipt>> {
+        let end = self.len().min(offset.saturating_add(limit));
+        (offset..end)
+            .map(|offset| {
+                self.db
+                    .retrieve_delivery_receipt_by_offset(offset)?
+                    .ok_or_else(|| eyre::eyre!("Delivery receipt {offset} missing from the log"))
+            })
+            .collect()
+    }
+}
+
+/// Exports the receipts of a `DeliveryReceiptLog` to a sink, in order and at
+/// least once. The offset of the exported receipts is only persisted once the
+/// sink accepted them, so that export resumes where it left off after a
+/// restart. Receipts may be exported again if the relayer stops in between,
+/// so consumers should deduplicate them by message id. Exported receipts are
+/// deleted from the log.
+#[derive(Debug)]
+pub struct DeliveryReceiptExporter {
+    origin: String,
+    log: Arc<DeliveryReceiptLog>,
+    sink: DeliveryReceiptSink,
+    batch_size: u64,
+    client: reqwest::Client,
+}
+
+impl DeliveryReceiptExporter {
+    pub fn new(
+        origin: String,
+        log: Arc<DeliveryReceiptLog>,
+        sink: DeliveryReceiptSink,
+        batch_size: u64,
+    ) -> Self {
+        Self {
+            origin,
+            log,
+            sink,
+            batch_size: batch_size.max(1),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("DeliveryReceiptExporter", origin = %self.origin, sink = %self.sink);
+        tokio::spawn(async move { self.run().await }).instrument(span)
+    }
+
+    async fn run(self) {
+        let mut backoff = EXPORT_RETRY_MIN_BACKOFF;
+        loop {
+            match self.export_batch().await {
+                Ok(0) => {
+                    backoff = EXPORT_RETRY_MIN_BACKOFF;
+                    let _ = tokio::time::timeout(POLL_INTERVAL, self.log.appended.notified()).await;
+                }
+                Ok(exported) => {
+                    backoff = EXPORT_RETRY_MIN_BACKOFF;
+                    debug!(exported, "Exported delivery receipts");
+                }
+                Err(err) => {
+                    warn!(?err, ?backoff, "Failed to export delivery receipts");
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(EXPORT_RETRY_MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    /// Export the next batch of receipts, returning how many were exported
+    async fn export_batch(&self) -> Result<usize> {
+        let offset = self
+            .log
+            .db
+            .retrieve_exported_delivery_receipt_count()?
+            .unwrap_or_default();
+        let receipts = self.log.read(offset, self.batch_size)?;
+        if receipts.is_empty() {
+            return Ok(0);
+        }
+        self.write(&receipts).await?;
+        let exported = offset..offset + receipts.len() as u64;
+        self.log
+            .db
+            .store_exported_delivery_receipt_count(exported.end, exported)?;
+        Ok(receipts.len())
+    }
+
+    async fn write(&self, receipts: &[DeliveryReceipt]) -> Result<()> {
+        match &self.sink {
+            DeliveryReceiptSink::File(path) => {
+                let mut lines = Vec::new();
+                for receipt in receipts {
+                    serde_json::to_writer(&mut lines, receipt)?;
+                    lines.push(b'\n');
+                }
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Failed to open {}", path.display()))?;
+                file.write_all(&lines)?;
+                file.sync_data()?;
+            }
+            DeliveryReceiptSink::Http(url) => {
+                self.client
+                    .post(url.clone())
+                    .json(receipts)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            DeliveryReceiptSink::Kafka { rest_proxy, topic } => {
+                let url = rest_proxy.join(&format!("topics/{topic}"))?;
+                self.client
+                    .post(url)
+                    .header("Content-Type", "application/vnd.kafka.json.v2+json")
+                    .body(serde_json::to_vec(&kafka_records(receipts))?)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The body of a produce request to a Kafka REST proxy
+fn kafka_records(receipts: &[DeliveryReceipt]) -> serde_json::Value {
+    json!({
+        "records": receipts
+            .iter()
+            .map(|receipt| json!({ "key": receipt.message_id, "value": receipt }))
+            .collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::{test_utils, HyperlaneDb};
+    use hyperlane_core::{HyperlaneDomain, H256, H512, U256};
+
+    use super::*;
+
+    fn receipt(nonce: u32) -> DeliveryReceipt {
+        DeliveryReceipt {
+            message_id: H256::from_low_u64_be(nonce as u64),
+            origin: 1,
+            destination: 2,
+            nonce,
+            tx_hash: H512::from_low_u64_be(nonce as u64),
+            block_number: Some(100),
+            gas_used: U256::from(50_000),
+            effective_gas_cost: U256::from(1_000_000),
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_resumes_from_exported_offset() {
+        test_utils::run_test_db(|db| async move {
+            let domain = HyperlaneDomain::new_test_domain("test");
+            let db = HyperlaneRocksDB::new(&domain, db);
+            let path =
+                std::env::temp_dir().join(format!("delivery-receipts-{}.jsonl", H256::random()));
+            let log = Arc::new(DeliveryReceiptLog::new(db.clone()).unwrap());
+            for nonce in 0..3 {
+                log.append_processed(&receipt(nonce)).unwrap();
+            }
+
+            let exporter = DeliveryReceiptExporter::new(
+                "test".to_owned(),
+                log.clone(),
+                DeliveryReceiptSink::File(path.clone()),
+                2,
+            );
+            assert_eq!(exporter.export_batch().await.unwrap(), 2);
+
+            // A restarted relayer picks up the log and the exported offset
+            let log = Arc::new(DeliveryReceiptLog::new(db.clone()).unwrap());
+            log.append_processed(&receipt(3)).unwrap();
+            let exporter = DeliveryReceiptExporter::new(
+                "test".to_owned(),
+                log,
+                DeliveryReceiptSink::File(path.clone()),
+                2,
+            );
+            assert_eq!(exporter.export_batch().await.unwrap(), 2);
+            assert_eq!(exporter.export_batch().await.unwrap(), 0);
+            assert_eq!(
+                db.retrieve_exported_delivery_receipt_count().unwrap(),
+                Some(4)
+            );
+            // Exported receipts are pruned, and their messages marked as processed
+            for nonce in 0..4 {
+                assert_eq!(
+                    db.retrieve_delivery_receipt_by_offset(nonce as u64)
+                        .unwrap(),
+                    None
+                );
+                assert_eq!(db.retrieve_processed_by_nonce(&nonce).unwrap(), Some(true));
+            }
+
+            let exported = std::fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<DeliveryReceipt>(line).unwrap())
+                .collect::<Vec<_>>();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(exported, (0..4).map(receipt).collect::<Vec<_>>());
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_kafka_records_are_keyed_by_message_id() {
+        let records = kafka_records(&[receipt(7)]);
+        assert_eq!(
+            records["records"][0]["key"],
+            serde_json::to_value(H256::from_low_u64_be(7)).unwrap()
+        );
+        assert_eq!(records["records"][0]["value"]["nonce"], 7);
+    }
+}
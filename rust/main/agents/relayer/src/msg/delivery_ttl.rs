@@ -0,0 +1,359 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hyperlane_base::db::{DeadLetterReason, HyperlaneDb, HyperlaneRocksDB};
+use hyperlane_core::{
+    rpc_clients::call_and_retry_n_times, HyperlaneMessage, HyperlaneProvider, U256,
+};
+use tracing::warn;
+
+use crate::settings::{BodyDeadline, DeliveryTtlConf};
+
+/// Bounds the attempts to fetch the block a message was dispatched in, before
+/// giving up until the message is next checked for expiry
+const DISPATCH_TIMESTAMP_RETRIES: usize = 3;
+
+/// Drops messages from an origin that are too old to be delivered, e.g.
+/// airdrop or mint messages that would be surprising if delivered weeks late.
+///
+/// The first policy whose matching list matches a message applies to it. A
+/// message expires once it was dispatched longer ago than the max age of its
+/// policy, or once the deadline its app encoded in the body passed.
+#[derive(Debug)]
+pub struct DeliveryTtlPolicy {
+    confs: Vec<DeliveryTtlConf>,
+    /// Used to look up when messages were dispatched
+    origin_provider: Arc<dyn HyperlaneProvider>,
+}
+
+impl DeliveryTtlPolicy {
+    pub fn new(confs: Vec<DeliveryTtlConf>, origin_provider: Arc<dyn HyperlaneProvider>) -> Self {
+        Self {
+            confs,
+            origin_provider,
+        }
+    }
+
+    /// Why the message expired, if it did. `dispatched_at` caches the unix
+    /// timestamp of the block the message was dispatched in, which is only
+    /// looked up if the applicable policy has a max age. If it can't be
+    /// looked up, it's looked up again the next time the message is checked.
+    pub async fn expiry(
+        &self,
+        message: &HyperlaneMessage,
+        origin_db: &HyperlaneRocksDB,
+        dispatched_at: &mut Option<u64>,
+    ) -> Option<DeadLetterReason> {
+        let conf = self
+            .confs
+            .iter()
+            .find(|conf| conf.matching_list.msg_matches(message, true))?;
+        let now = unix_now();
+
+        if let Some(deadline) = conf
+            .deadline
+            .as_ref()
+            .and_then(|deadline| body_deadline(&message.body, deadline))
+        {
+            if now > deadline {
+                return Some(DeadLetterReason::DeadlinePassed);
+            }
+        }
+
+        let max_age = conf.max_age?;
+        if dispatched_at.is_none() {
+            *dispatched_at = self.dispatch_timestamp(message, origin_db).await;
+        }
+        let age = Duration::from_secs(now.saturating_sub((*dispatched_at)?));
+        (age > max_age).then_some(DeadLetterReason::MaxAgeExceeded)
+    }
+
+    async fn dispatch_timestamp(
+        &self,
+        message: &HyperlaneMessage,
+        origin_db: &HyperlaneRocksDB,
+    ) -> Option<u64> {
+        let block_number = match origin_db.retrieve_dispatched_block_number_by_nonce(&message.nonce)
+        {
+            Ok(Some(block_number)) => block_number,
+            Ok(None) => {
+                warn!(
+                    nonce = message.nonce,
+                    "Dispatch block number of message not found, can't tell its age"
+                );
+                return None;
+            }
+            Err(err) => {
+                warn!(?err, "Failed to retrieve dispatch block number");
+                return None;
+            }
+        };
+        let provider = self.origin_provider.clone();
+        match call_and_retry_n_times(
+            || {
+                let provider = provider.clone();
+                Box::pin(async move { provider.get_block_by_height(block_number).await })
+            },
+            DISPATCH_TIMESTAMP_RETRIES,
+        )
+        .await
+        {
+            Ok(block) => Some(block.timestamp),
+            Err(err) => {
+                warn!(?err, block_number, "Failed to fetch dispatch block");
+                None
+            }
+        }
+    }
+}
+
+/// The deadline encoded in a message body, if the body is long enough to
+/// encode one. Deadlines beyond `u64::MAX` never pass.
+fn body_deadline(body: &[u8], deadline: &BodyDeadline) -> Option<u64> {
+    let bytes = body.get(deadline.offset..deadline.offset.checked_add(deadline.length)?)?;
+    let deadline = U256::from_big_endian(bytes);
+    Some(if deadline > U256::from(u64::MAX) {
+        u64::MAX
+    } else {
+        deadline.as_u64()
+    })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use async_trait::async_trait;
+    use hyperlane_base::db::test_utils;
+    use hyperlane_core::{
+        test_utils::dummy_domain, BlockInfo, ChainCommunicationError, ChainInfo, ChainResult,
+        HyperlaneChain, HyperlaneDomain, TxnInfo, H256, H512,
+    };
+
+    use crate::settings::matching_list::MatchingList;
+
+    use super::*;
+
+    mockall::mock! {
+        pub Provider {}
+
+        impl std::fmt::Debug for Provider {
+            fn fmt<'a>(&self, f: &mut std::fmt::Formatter<'a>) -> std::fmt::Result;
+        }
+
+        impl HyperlaneChain for Provider {
+            fn domain(&self) -> &HyperlaneDomain;
+            fn provider(&self) -> Box<dyn HyperlaneProvider>;
+        }
+
+        #[async_trait]
+        impl HyperlaneProvider for Provider {
+            async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo>;
+            async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo>;
+            async fn is_contract(&self, address: &H256) -> ChainResult<bool>;
+            async fn get_balance(&self, address: String) -> ChainResult<U256>;
+            async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>>;
+        }
+    }
+
+    const DISPATCH_BLOCK: u64 = 100;
+
+    fn block(timestamp: u64) -> BlockInfo {
+        BlockInfo {
+            hash: H256::zero(),
+            timestamp,
+            number: DISPATCH_BLOCK,
+        }
+    }
+
+    fn max_age_conf(matching_list: MatchingList, max_age: u64) -> DeliveryTtlConf {
+        DeliveryTtlConf {
+            matching_list,
+            max_age: Some(Duration::from_secs(max_age)),
+            deadline: None,
+        }
+    }
+
+    fn dispatched_message(db: &HyperlaneRocksDB) -> HyperlaneMessage {
+        let message = HyperlaneMessage {
+            nonce: 3,
+            ..Default::default()
+        };
+        db.store_dispatched_block_number_by_nonce(&message.nonce, &DISPATCH_BLOCK)
+            .unwrap();
+        message
+    }
+
+    #[test]
+    fn test_body_deadline() {
+        let abi_encoded = BodyDeadline {
+            offset: 4,
+            length: 32,
+        };
+        let mut encoded = [0; 32];
+        U256::from(1_700_000_000u64).to_big_endian(&mut encoded);
+        let mut body = vec![0xff; 4];
+        body.extend(encoded);
+        assert_eq!(body_deadline(&body, &abi_encoded), Some(1_700_000_000));
+
+        // Too short to encode a deadline
+        assert_eq!(body_deadline(&body[..35], &abi_encoded), None);
+
+        // Too far in the future to ever pass
+        let mut far_future = vec![0; 4];
+        far_future.extend([0xff; 32]);
+        assert_eq!(body_deadline(&far_future, &abi_encoded), Some(u64::MAX));
+
+        let packed = BodyDeadline {
+            offset: 0,
+            length: 8,
+        };
+        assert_eq!(body_deadline(&42u64.to_be_bytes(), &packed), Some(42));
+    }
+
+    #[tokio::test]
+    async fn test_max_age_expiry() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(1, "origin"), db);
+            let message = dispatched_message(&db);
+            let dispatched_at = unix_now() - 100;
+            let mut provider = MockProvider::new();
+            // The dispatch timestamp is only looked up once
+            provider
+                .expect_get_block_by_height()
+                .withf(|height| *height == DISPATCH_BLOCK)
+                .times(1)
+                .returning(move |_| Ok(block(dispatched_at)));
+            let provider: Arc<dyn HyperlaneProvider> = Arc::new(provider);
+
+            let mut cached = None;
+            let young = DeliveryTtlPolicy::new(
+                vec![max_age_conf(MatchingList::default(), 1_000)],
+                provider.clone(),
+            );
+            assert_eq!(young.expiry(&message, &db, &mut cached).await, None);
+            assert_eq!(cached, Some(dispatched_at));
+
+            let old =
+                DeliveryTtlPolicy::new(vec![max_age_conf(MatchingList::default(), 50)], provider);
+            assert_eq!(
+                old.expiry(&message, &db, &mut cached).await,
+                Some(DeadLetterReason::MaxAgeExceeded)
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_first_matching_policy_applies() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(1, "origin"), db);
+            let message = dispatched_message(&db);
+            let mut provider = MockProvider::new();
+            provider
+                .expect_get_block_by_height()
+                .returning(|_| Ok(block(unix_now() - 100)));
+            let other_destination = serde_json::from_str(r#"[{"destinationdomain": 9}]"#).unwrap();
+            let policy = DeliveryTtlPolicy::new(
+                vec![
+                    max_age_conf(other_destination, 50),
+                    max_age_conf(MatchingList::default(), 1_000),
+                    max_age_conf(MatchingList::default(), 50),
+                ],
+                Arc::new(provider),
+            );
+            assert_eq!(policy.expiry(&message, &db, &mut None).await, None);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_deadline_expiry() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(1, "origin"), db);
+            // The dispatch timestamp isn't looked up without a max age
+            let policy = DeliveryTtlPolicy::new(
+                vec![DeliveryTtlConf {
+                    matching_list: MatchingList::default(),
+                    max_age: None,
+                    deadline: Some(BodyDeadline {
+                        offset: 0,
+                        length: 8,
+                    }),
+                }],
+                Arc::new(MockProvider::new()),
+            );
+            let message = |deadline: u64| HyperlaneMessage {
+                body: deadline.to_be_bytes().to_vec(),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                policy
+                    .expiry(&message(unix_now() - 1), &db, &mut None)
+                    .await,
+                Some(DeadLetterReason::DeadlinePassed)
+            );
+            assert_eq!(
+                policy
+                    .expiry(&message(unix_now() + 1_000), &db, &mut None)
+                    .await,
+                None
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_timestamp_lookup_is_retried() {
+        test_utils::run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(&dummy_domain(1, "origin"), db);
+            let message = dispatched_message(&db);
+            let dispatched_at = unix_now() - 100;
+            let mut provider = MockProvider::new();
+            let mut sequence = mockall::Sequence::new();
+            provider
+                .expect_get_block_by_height()
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_| {
+                    Err(ChainCommunicationError::CustomError(
+                        "RPC unavailable".to_owned(),
+                    ))
+                });
+            provider
+                .expect_get_block_by_height()
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(move |_| Ok(block(dispatched_at)));
+            let policy = DeliveryTtlPolicy::new(
+                vec![max_age_conf(MatchingList::default(), 50)],
+                Arc::new(provider),
+            );
+
+            let mut cached = None;
+            assert_eq!(
+                policy.expiry(&message, &db, &mut cached).await,
+                Some(DeadLetterReason::MaxAgeExceeded)
+            );
+            assert_eq!(cached, Some(dispatched_at));
+
+            // Messages whose dispatch block isn't known are checked again later
+            let unknown = HyperlaneMessage {
+                nonce: 4,
+                ..Default::default()
+            };
+            let mut cached = None;
+            assert_eq!(policy.expiry(&unknown, &db, &mut cached).await, None);
+            assert_eq!(cached, None);
+        })
+        .await;
+    }
+}
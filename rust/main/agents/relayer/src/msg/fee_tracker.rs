@@ -0,0 +1,274 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use prometheus::{CounterVec, GaugeVec};
+use serde::Serialize;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::FeeBudgetConf;
+
+/// Windows fees are always tracked over, whether or not a budget is set for
+/// them.
+const DEFAULT_WINDOWS: [Duration; 2] = [
+    Duration::from_secs(60 * 60),
+    Duration::from_secs(24 * 60 * 60),
+];
+
+/// How often the window gauges are refreshed, so that fees leaving a window
+/// are reflected even if no new fees are paid.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tracks the fees paid on a Cosmos destination over trailing windows, and
+/// warns when the fees paid within a window exceed its budget.
+#[derive(Debug)]
+pub struct FeeTracker {
+    chain: String,
+    denom: String,
+    windows: Vec<FeeWindow>,
+    state: Mutex<FeeState>,
+    fees_paid_total: CounterVec,
+    fees_paid_in_window: GaugeVec,
+}
+
+#[derive(Debug)]
+struct FeeWindow {
+    length: Duration,
+    budget: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct FeeState {
+    /// Fees paid within the longest window, oldest first
+    payments: VecDeque<(Instant, f64)>,
+    total: f64,
+    /// Whether each window was over its budget when last refreshed, so that
+    /// crossing the budget is only warned about once
+    over_budget: Vec<bool>,
+}
+
+/// Fees paid on a chain, as served by the relayer API
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeTotals {
+    pub chain: String,
+    pub denom: String,
+    /// Fees paid since the relayer started
+    pub total: f64,
+    pub windows: Vec<WindowFees>,
+}
+
+/// Fees paid within a trailing window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WindowFees {
+    pub window_secs: u64,
+    pub fees: f64,
+    pub budget: Option<f64>,
+}
+
+impl FeeTracker {
+    pub fn new(
+        chain: String,
+        denom: String,
+        budgets: &[FeeBudgetConf],
+        fees_paid_total: CounterVec,
+        fees_paid_in_window: GaugeVec,
+    ) -> Self {
+        let mut windows: Vec<FeeWindow> = DEFAULT_WINDOWS
+            .iter()
+            .filter(|length| !budgets.iter().any(|budget| budget.window == **length))
+            .map(|&length| FeeWindow {
+                length,
+                budget: None,
+            })
+            .chain(budgets.iter().map(|budget| FeeWindow {
+                length: budget.window,
+                budget: Some(budget.max_fee),
+            }))
+            .collect();
+        windows.sort_by_key(|window| window.length);
+        let state = FeeState {
+            over_budget: vec![false; windows.len()],
+            ..Default::default()
+        };
+        Self {
+            chain,
+            denom,
+            windows,
+            state: Mutex::new(state),
+            fees_paid_total,
+            fees_paid_in_window,
+        }
+    }
+
+    /// Record a fee paid, in the smallest unit of the tracked denom
+    pub fn record(&self, fee: f64) {
+        self.record_at(fee, Instant::now());
+    }
+
+    fn record_at(&self, fee: f64, now: Instant) {
+        {
+            let mut state = self.state.lock().expect("fee tracker lock poisoned");
+            state.payments.push_back((now, fee));
+            state.total += fee;
+        }
+        self.fees_paid_total
+            .with_label_values(&[&self.chain, &self.denom])
+            .inc_by(fee);
+        self.refresh_at(now);
+    }
+
+    /// Drop fees that left all windows and update the window gauges
+    pub fn refresh(&self) {
+        self.refresh_at(Instant::now());
+    }
+
+    fn refresh_at(&self, now: Instant) {
+        let mut state = self.state.lock().expect("fee tracker lock poisoned");
+        if let Some(longest) = self.windows.last() {
+            while state.payments.front().is_some_and(|(paid_at, _)| {
+                now.saturating_duration_since(*paid_at) > longest.length
+            }) {
+                state.payments.pop_front();
+            }
+        }
+
+        for (i, window) in self.windows.iter().enumerate() {
+            let fees = fees_within(&state.payments, window.length, now);
+            self.fees_paid_in_window
+                .with_label_values(&[&self.chain, &self.denom, &window_label(window.length)])
+                .set(fees);
+
+            let Some(budget) = window.budget else {
+                continue;
+            };
+            let over_budget = fees > budget;
+            if over_budget && !state.over_budget[i] {
+                warn!(
+                    chain = %self.chain,
+                    denom = %self.denom,
+                    window = window_label(window.length),
+                    fees,
+                    budget,
+                    "Fees paid exceeded budget"
+                );
+            }
+            state.over_budget[i] = over_budget;
+        }
+    }
+
+    /// The fees paid since the relayer started and within each window
+    pub fn totals(&self) -> FeeTotals {
+        let now = Instant::now();
+        let state = self.state.lock().expect("fee tracker lock poisoned");
+        FeeTotals {
+            chain: self.chain.clone(),
+            denom: self.denom.clone(),
+            total: state.total,
+            windows: self
+                .windows
+                .iter()
+                .map(|window| WindowFees {
+                    window_secs: window.length.as_secs(),
+                    fees: fees_within(&state.payments, window.length, now),
+                    budget: window.budget,
+                })
+                .collect(),
+        }
+    }
+
+    /// Periodically refresh the window gauges
+    pub fn spawn(self: Arc<Self>) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("FeeTracker", chain = %self.chain);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(REFRESH_INTERVAL);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.refresh();
+            }
+        })
+        .instrument(span)
+    }
+}
+
+fn fees_within(payments: &VecDeque<(Instant, f64)>, window: Duration, now: Instant) -> f64 {
+    payments
+        .iter()
+        .rev()
+        .take_while(|(paid_at, _)| now.saturating_duration_since(*paid_at) <= window)
+        .map(|(_, fee)| fee)
+        .sum()
+}
+
+fn window_label(window: Duration) -> String {
+    format!("{}s", window.as_secs())
+}
+
+#[cfg(test)]
+mod test {
+    use prometheus::opts;
+
+    use super::*;
+
+    fn tracker(budgets: &[FeeBudgetConf]) -> FeeTracker {
+        let total = CounterVec::new(opts!("fees_paid_total", "help"), &["chain", "denom"]).unwrap();
+        let window = GaugeVec::new(
+            opts!("fees_paid_in_window", "help"),
+            &["chain", "denom", "window"],
+        )
+        .unwrap();
+        FeeTracker::new(
+            "neutron".to_owned(),
+            "untrn".to_owned(),
+            budgets,
+            total,
+            window,
+        )
+    }
+
+    #[test]
+    fn test_fees_leave_windows() {
+        let tracker = tracker(&[FeeBudgetConf {
+            window: Duration::from_secs(600),
+            max_fee: 100.0,
+        }]);
+        let start = Instant::now();
+
+        tracker.record_at(40.0, start);
+        tracker.record_at(70.0, start + Duration::from_secs(300));
+        assert!(tracker.state.lock().unwrap().over_budget[0]);
+
+        let later = start + Duration::from_secs(700);
+        tracker.refresh_at(later);
+        let state = tracker.state.lock().unwrap();
+        assert_eq!(
+            fees_within(&state.payments, Duration::from_secs(600), later),
+            70.0
+        );
+        assert_eq!(
+            fees_within(&state.payments, DEFAULT_WINDOWS[0], later),
+            110.0
+        );
+        assert!(!state.over_budget[0]);
+        assert_eq!(state.total, 110.0);
+        drop(state);
+
+        assert_eq!(
+            tracker
+                .fees_paid_in_window
+                .with_label_values(&["neutron", "untrn", "600s"])
+                .get(),
+            70.0
+        );
+        assert_eq!(
+            tracker
+                .fees_paid_total
+                .with_label_values(&["neutron", "untrn"])
+                .get(),
+            110.0
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::str::FromStr;
+
+use hyperlane_core::{FixedPointNumber, TxCostEstimate, U256};
+use tracing::warn;
+
+use crate::settings::GasEscalationConf;
+
+/// Outcome of applying a [`GasEscalationPolicy`] to a cost estimate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GasEscalation {
+    /// Submit with the given gas limit
+    GasLimit(U256),
+    /// Even the unescalated estimate exceeds the fee cap of the destination
+    ExceedsMaxFee,
+}
+
+/// Bounds how the relayer spends gas when it keeps failing to deliver a
+/// message to a destination.
+///
+/// Each failed submission of a message (an error submitting, or a reverted /
+/// reorged delivery transaction) bumps the gas limit of the next attempt by
+/// `multiplier`. The escalated gas limit is capped so that the fee implied by
+/// the estimated gas price never exceeds `max_fee`, and messages that were
+/// retried more than `max_retries` times are dropped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GasEscalationPolicy {
+    conf: GasEscalationConf,
+}
+
+impl GasEscalationPolicy {
+    pub fn new(conf: GasEscalationConf) -> Self {
+        Self { conf }
+    }
+
+    /// Whether a message that has been retried `num_retries` times should
+    /// still be attempted.
+    pub fn allows_retry(&self, num_retries: u32) -> bool {
+        self.conf
+            .max_retries
+            .map(|max_retries| num_retries <= max_retries)
+            .unwrap_or(true)
+    }
+
+    /// Gas limit to submit with, given the approved `gas_limit` and the
+    /// number of prior failed submission attempts.
+    pub fn escalate(
+        &self,
+        gas_limit: U256,
+        tx_cost_estimate: &TxCostEstimate,
+        failed_submissions: u32,
+    ) -> GasEscalation {
+        let escalated = escalated_gas_limit(gas_limit, self.conf.multiplier, failed_submissions);
+
+        let Some(max_fee) = self.conf.max_fee else {
+            return GasEscalation::GasLimit(escalated);
+        };
+        let Some(max_gas_limit) = max_gas_limit_for_fee(max_fee, &tx_cost_estimate.gas_price)
+        else {
+            return GasEscalation::GasLimit(escalated);
+        };
+        if gas_limit > max_gas_limit {
+            return GasEscalation::ExceedsMaxFee;
+        }
+        GasEscalation::GasLimit(escalated.min(max_gas_limit))
+    }
+}
+
+/// `gas_limit * multiplier ^ failed_submissions`, saturating at `U256::MAX`.
+fn escalated_gas_limit(gas_limit: U256, multiplier: f64, failed_submissions: u32) -> U256 {
+    if failed_submissions == 0 || multiplier <= 1.0 {
+        return gas_limit;
+    }
+    let factor = multiplier.powi(failed_submissions.min(i32::MAX as u32) as i32);
+    let escalated: Result<U256, _> = FixedPointNumber::try_from(gas_limit)
+        .and_then(|limit| Ok(limit * FixedPointNumber::from_str(&factor.to_string())?))
+        .and_then(|limit| limit.ceil_to_integer().try_into());
+    match escalated {
+        Ok(escalated) => escalated.max(gas_limit),
+        Err(err) => {
+            warn!(?err, ?gas_limit, factor, "Failed to escalate gas limit");
+            U256::MAX
+        }
+    }
+}
+
+/// The largest gas limit whose fee at `gas_price` stays within `max_fee`.
+/// Returns `None` if the gas price is unknown (zero).
+fn max_gas_limit_for_fee(max_fee: U256, gas_price: &FixedPointNumber) -> Option<U256> {
+    if gas_price <= &FixedPointNumber::zero() {
+        return None;
+    }
+    let max_fee = FixedPointNumber::try_from(max_fee).ok()?;
+    (max_fee / gas_price.clone()).try_into().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn estimate(gas_limit: u64, gas_price: u64) -> TxCostEstimate {
+        TxCostEstimate {
+            gas_limit: gas_limit.into(),
+            gas_price: FixedPointNumber::try_from(U256::from(gas_price)).unwrap(),
+            l2_gas_limit: None,
+        }
+    }
+
+    #[test]
+    fn test_default_policy_is_a_noop() {
+        let policy = GasEscalationPolicy::default();
+        assert!(policy.allows_retry(u32::MAX));
+        assert_eq!(
+            policy.escalate(100_000.into(), &estimate(100_000, 10), 5),
+            GasEscalation::GasLimit(100_000.into())
+        );
+    }
+
+    #[test]
+    fn test_max_retries() {
+        let policy = GasEscalationPolicy::new(GasEscalationConf {
+            max_retries: Some(3),
+            ..Default::default()
+        });
+        assert!(policy.allows_retry(0));
+        assert!(policy.allows_retry(3));
+        assert!(!policy.allows_retry(4));
+    }
+
+    #[test]
+    fn test_escalation_is_capped_by_max_fee() {
+        let policy = GasEscalationPolicy::new(GasEscalationConf {
+            max_retries: None,
+            multiplier: 1.5,
+            max_fee: Some(2_000_000.into()),
+        });
+        let estimate = estimate(100_000, 10);
+
+        assert_eq!(
+            policy.escalate(100_000.into(), &estimate, 0),
+            GasEscalation::GasLimit(100_000.into())
+        );
+        assert_eq!(
+            policy.escalate(100_000.into(), &estimate, 1),
+            GasEscalation::GasLimit(150_000.into())
+        );
+        assert_eq!(
+            policy.escalate(100_000.into(), &estimate, 2),
+            GasEscalation::GasLimit(200_000.into())
+        );
+        assert_eq!(
+            policy.escalate(300_000.into(), &estimate, 0),
+            GasEscalation::ExceedsMaxFee
+        );
+    }
+}
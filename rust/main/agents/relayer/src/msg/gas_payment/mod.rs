@@ -81,6 +81,15 @@ impl GasPaymentEnforcer {
 }
 
 impl GasPaymentEnforcer {
+    /// The policy that applies to `message`, if any of the policies' whitelists
+    /// match it.
+    pub fn policy_for(&self, message: &HyperlaneMessage) -> Option<&dyn GasPaymentPolicy> {
+        self.policies
+            .iter()
+            .find(|(_, whitelist)| whitelist.msg_matches(message, true))
+            .map(|(policy, _)| policy.as_ref())
+    }
+
     /// Returns Some(gas_limit) if the enforcer has approved the transaction or
     /// None if the transaction is not approved.
     pub async fn message_meets_gas_payment_requirement(
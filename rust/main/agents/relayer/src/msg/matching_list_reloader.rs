@@ -0,0 +1,178 @@
+use std::{
+    fmt::{Display, Formatter},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use convert_case::Case;
+use eyre::{Context, Result};
+use hyperlane_base::settings::parser::recase_json_value;
+use serde_json::Value;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info, info_span, instrument::Instrumented, warn, Instrument};
+
+use crate::settings::matching_list::{MatchingList, MatchingListSource};
+
+/// A matching list that can be swapped out while the relayer is running.
+#[derive(Debug, Clone, Default)]
+pub struct ReloadableMatchingList(Arc<RwLock<Arc<MatchingList>>>);
+
+impl ReloadableMatchingList {
+    pub fn new(list: MatchingList) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(list))))
+    }
+
+    /// A snapshot of the current list.
+    pub fn current(&self) -> Arc<MatchingList> {
+        self.0.read().expect("matching list lock poisoned").clone()
+    }
+
+    fn replace(&self, list: MatchingList) {
+        *self.0.write().expect("matching list lock poisoned") = Arc::new(list);
+    }
+}
+
+impl Display for ReloadableMatchingList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.current())
+    }
+}
+
+/// Periodically reloads a matching list from a remote URL or a local file.
+///
+/// Until the first successful load the list keeps its statically configured
+/// value. Failed reloads are logged and leave the last loaded list in place,
+/// so an unreachable source never widens or narrows the filter by accident.
+#[derive(Debug)]
+pub struct MatchingListReloader {
+    name: &'static str,
+    source: MatchingListSource,
+    interval: Duration,
+    list: ReloadableMatchingList,
+    client: reqwest::Client,
+}
+
+impl MatchingListReloader {
+    pub fn new(
+        name: &'static str,
+        source: MatchingListSource,
+        interval: Duration,
+        list: ReloadableMatchingList,
+    ) -> Self {
+        Self {
+            name,
+            source,
+            interval,
+            list,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn spawn(self) -> Instrumented<JoinHandle<()>> {
+        let span = info_span!("MatchingListReloader", list = self.name, source = %self.source);
+        tokio::spawn(async move { self.run().await }).instrument(span)
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match self.load().await {
+                Ok(list) => {
+                    info!(%list, "Reloaded matching list");
+                    self.list.replace(list);
+                }
+                Err(err) => warn!(?err, "Failed to reload matching list, keeping previous one"),
+            }
+        }
+    }
+
+    async fn load(&self) -> Result<MatchingList> {
+        let raw = match &self.source {
+            MatchingListSource::Url(url) => {
+                self.client
+                    .get(url.clone())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await?
+            }
+            MatchingListSource::File(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("Reading matching list from {}", path.display()))?,
+        };
+        parse_matching_list(&raw)
+    }
+}
+
+/// Parses a matching list from JSON, accepting the same camelCase keys as the
+/// agent config.
+fn parse_matching_list(raw: &str) -> Result<MatchingList> {
+    let value: Value = serde_json::from_str(raw).context("Expected JSON matching list")?;
+    serde_json::from_value(recase_json_value(value, Case::Flat)).context("Invalid matching list")
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{HyperlaneMessage, H256};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_matching_list() {
+        let list = parse_matching_list(
+            r#"[{"originDomain": 1, "senderAddress": "*", "destinationDomain": [2, 3], "recipientAddress": "*"}]"#,
+        )
+        .unwrap();
+        let msg = HyperlaneMessage {
+            origin: 1,
+            destination: 3,
+            sender: H256::random(),
+            recipient: H256::random(),
+            ..Default::default()
+        };
+        assert!(list.msg_matches(&msg, false));
+        assert!(!list.msg_matches(
+            &HyperlaneMessage {
+                destination: 4,
+                ..msg
+            },
+            false
+        ));
+
+        assert!(parse_matching_list("not json").is_err());
+    }
+
+    #[test]
+    fn test_replace_matching_list() {
+        let list = ReloadableMatchingList::default();
+        let snapshot = list.current();
+        assert!(snapshot.0.is_none());
+
+        list.replace(MatchingList::with_destination_domain(5));
+        assert!(list.current().0.is_some());
+        // Snapshots taken before a reload are unaffected
+        assert!(snapshot.0.is_none());
+    }
+
+    #[test]
+    fn test_parse_matching_list_source() {
+        assert_eq!(
+            "https://example.com/blacklist.json"
+                .parse::<MatchingListSource>()
+                .unwrap(),
+            MatchingListSource::Url("https://example.com/blacklist.json".parse().unwrap())
+        );
+        assert_eq!(
+            "file:///etc/relayer/blacklist.json"
+                .parse::<MatchingListSource>()
+                .unwrap(),
+            MatchingListSource::File("/etc/relayer/blacklist.json".into())
+        );
+        assert_eq!(
+            "./blacklist.json".parse::<MatchingListSource>().unwrap(),
+            MatchingListSource::File("./blacklist.json".into())
+        );
+    }
+}
@@ -4,6 +4,7 @@
 use std::{
     collections::HashMap,
     fmt::Debug,
+    future::Future,
     ops::Deref,
     str::FromStr,
     sync::Arc,
@@ -14,8 +15,8 @@ use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::metadata::{
         multisig::{MerkleRootMultisigMetadataBuilder, MessageIdMultisigMetadataBuilder},
-        AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, NullMetadataBuilder,
-        RoutingIsmMetadataBuilder,
+        AggregationIsmMetadataBuilder, CcipReadIsmMetadataBuilder, IsmConfigCache,
+        IsmConfigMonitor, NullMetadataBuilder, RoutingIsmCache, RoutingIsmMetadataBuilder,
     },
     settings::matching_list::MatchingList,
 };
@@ -28,9 +29,9 @@ use hyperlane_base::{
     CheckpointSyncer, CoreMetrics, MultisigCheckpointSyncer,
 };
 use hyperlane_core::{
-    accumulator::merkle::Proof, AggregationIsm, CcipReadIsm, Checkpoint, HyperlaneDomain,
-    HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm, RoutingIsm,
-    ValidatorAnnounce, H160, H256,
+    accumulator::merkle::Proof, AggregationIsm, CcipReadIsm, ChainResult, Checkpoint,
+    HyperlaneDomain, HyperlaneMessage, InterchainSecurityModule, Mailbox, ModuleType, MultisigIsm,
+    RoutingIsm, ValidatorAnnounce, H160, H256,
 };
 
 use tokio::sync::RwLock;
@@ -239,8 +240,8 @@ impl MessageMetadataBuilder {
             .await
             .context("When building ISM")?;
 
-        let module_type = ism
-            .module_type()
+        let module_type = self
+            .module_type(ism_address, &*ism)
             .await
             .context("When fetching module type")?;
         let cloned = self.clone_with_incremented_depth()?;
@@ -282,6 +283,9 @@ pub struct BaseMetadataBuilder {
     metrics: Arc<CoreMetrics>,
     db: HyperlaneRocksDB,
     app_context_classifier: IsmAwareAppContextClassifier,
+    routing_ism_cache: Option<Arc<RoutingIsmCache>>,
+    ism_config_monitor: Arc<IsmConfigMonitor>,
+    ism_config_cache: Option<Arc<IsmConfigCache>>,
     #[new(value = "7")]
     max_depth: u32,
 }
@@ -305,6 +309,56 @@ impl BaseMetadataBuilder {
         &self.destination_chain_setup.domain
     }
 
+    pub fn routing_ism_cache(&self) -> Option<&RoutingIsmCache> {
+        self.routing_ism_cache.as_deref()
+    }
+
+    pub fn ism_config_monitor(&self) -> &IsmConfigMonitor {
+        &self.ism_config_monitor
+    }
+
+    /// The type of `ism`, served from the ISM config cache if enabled
+    pub async fn module_type(
+        &self,
+        ism_address: H256,
+        ism: &dyn InterchainSecurityModule,
+    ) -> Result<ModuleType> {
+        let Some(cache) = self.ism_config_cache.as_deref() else {
+            return Ok(ism.module_type().await?);
+        };
+        let destination = self.destination_domain();
+        if let Some(module_type) = cache.module_type(destination, ism_address) {
+            return Ok(module_type);
+        }
+        let module_type = ism.module_type().await?;
+        cache.insert_module_type(destination, ism_address, module_type);
+        Ok(module_type)
+    }
+
+    /// The validators or modules and threshold an ISM has for messages from
+    /// `origin`, served from the ISM config cache if enabled. Configurations
+    /// that are fetched are reported to the ISM config monitor.
+    pub async fn ism_config(
+        &self,
+        ism_address: H256,
+        origin: u32,
+        fetch: impl Future<Output = ChainResult<(Vec<H256>, u8)>>,
+    ) -> Result<(Vec<H256>, u8)> {
+        let cache = self.ism_config_cache.as_deref();
+        let destination = self.destination_domain();
+        if let Some(config) = cache.and_then(|cache| cache.config(destination, ism_address, origin))
+        {
+            return Ok(config);
+        }
+        let config = fetch.await?;
+        self.ism_config_monitor
+            .observe(ism_address, origin, &config);
+        if let Some(cache) = cache {
+            cache.insert_config(destination, ism_address, origin, config.clone());
+        }
+        Ok(config)
+    }
+
     pub async fn get_proof(&self, leaf_index: u32, checkpoint: Checkpoint) -> Result<Proof> {
         const CTX: &str = "When fetching message proof";
         let proof = self
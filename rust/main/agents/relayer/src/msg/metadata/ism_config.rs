@@ -0,0 +1,319 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::{HyperlaneDomain, ModuleType, ReprepareReason, H256};
+use prometheus::IntCounterVec;
+use tokio::{
+    sync::{broadcast::Sender, mpsc},
+    time::timeout,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    msg::op_submitter::SUBMITTER_QUEUE_COUNT,
+    server::MessageRetryRequest,
+    settings::matching_list::{Filter, ListElement, MatchingList},
+};
+
+/// How long to wait for each op queue to report the operations it requeued.
+const REQUEUE_RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Watches the configuration of the ISMs verifying messages on a destination,
+/// i.e. the validators and threshold of multisig ISMs and the modules routing
+/// ISMs route to.
+///
+/// When the configuration an ISM has for an origin changes, messages from that
+/// origin that are waiting to be reprepared because their metadata couldn't be
+/// built are requeued, since they may be deliverable now. What is cached for
+/// that ISM is evicted as well.
+#[derive(Debug)]
+pub struct IsmConfigMonitor {
+    destination: HyperlaneDomain,
+    /// Hash of the last seen configuration by (ISM, origin)
+    configs: Mutex<HashMap<(H256, u32), u64>>,
+    retry_sender: Sender<MessageRetryRequest>,
+    config_cache: Option<Arc<IsmConfigCache>>,
+}
+
+impl IsmConfigMonitor {
+    pub fn new(destination: HyperlaneDomain, retry_sender: Sender<MessageRetryRequest>) -> Self {
+        Self {
+            destination,
+            configs: Default::default(),
+            retry_sender,
+            config_cache: None,
+        }
+    }
+
+    /// Evict what `config_cache` has for an ISM when its configuration
+    /// changes
+    pub fn with_config_cache(mut self, config_cache: Arc<IsmConfigCache>) -> Self {
+        self.config_cache = Some(config_cache);
+        self
+    }
+
+    /// Records the configuration `ism` has for messages from `origin`, and
+    /// requeues the affected messages if it differs from the one last seen.
+    pub fn observe(&self, ism: H256, origin: u32, config: impl Hash) {
+        let mut hasher = DefaultHasher::new();
+        config.hash(&mut hasher);
+        let config_hash = hasher.finish();
+
+        let previous = self
+            .configs
+            .lock()
+            .expect("ISM config lock poisoned")
+            .insert((ism, origin), config_hash);
+        if matches!(previous, Some(previous) if previous != config_hash) {
+            info!(
+                ?ism,
+                origin,
+                destination = self.destination.name(),
+                "ISM configuration changed, requeueing messages that failed on metadata"
+            );
+            if let Some(config_cache) = &self.config_cache {
+                config_cache.invalidate(&self.destination, ism, origin);
+            }
+            self.requeue(origin);
+        }
+    }
+
+    fn requeue(&self, origin: u32) {
+        let pattern = MatchingList(Some(vec![ListElement::new(
+            Filter::Wildcard,
+            Filter::Enumerated(vec![origin]),
+            Filter::Wildcard,
+            Filter::Enumerated(vec![self.destination.id()]),
+            Filter::Wildcard,
+        )]));
+        let (transmitter, mut receiver) = mpsc::channel(SUBMITTER_QUEUE_COUNT);
+        let request = MessageRetryRequest {
+            uuid: uuid::Uuid::new_v4().to_string(),
+            pattern,
+            reprepare_reasons: Some(vec![
+                ReprepareReason::CouldNotFetchMetadata,
+                ReprepareReason::ErrorBuildingMetadata,
+            ]),
+            transmitter,
+        };
+        let uuid = request.uuid.clone();
+        if let Err(err) = self.retry_sender.send(request) {
+            warn!(?err, "Failed to send requeue request for ISM config change");
+            return;
+        }
+
+        // Drain the responses so the op queues don't block on them
+        tokio::spawn(async move {
+            let mut matched = 0;
+            while let Ok(Some(response)) = timeout(REQUEUE_RESPONSE_TIMEOUT, receiver.recv()).await
+            {
+                matched += response.matched;
+            }
+            info!(uuid, matched, "Requeued messages after ISM config change");
+        });
+    }
+}
+
+/// Caches the type of ISMs and the validators or modules and threshold that
+/// multisig and aggregation ISMs have for an origin, saving the RPC calls
+/// needed to look them up for every message of recursive ISM trees.
+///
+/// A single cache is shared by all destinations, so ISMs are keyed by their
+/// destination too. Like the `RoutingIsmCache`, this is only correct for ISMs
+/// whose configuration depends on the message origin alone. Entries expire
+/// after `ttl`, and are evicted early when the `IsmConfigMonitor` sees the
+/// configuration of an ISM change.
+#[derive(Debug)]
+pub struct IsmConfigCache {
+    ttl: Duration,
+    /// Cached ISMs by (destination, ISM)
+    isms: Mutex<HashMap<(u32, H256), CachedIsm>>,
+    lookups: IntCounterVec,
+}
+
+/// What is cached for an ISM, with when it was cached
+#[derive(Debug, Default)]
+struct CachedIsm {
+    module_type: Option<(ModuleType, Instant)>,
+    /// Validators or modules and threshold by origin
+    configs: HashMap<u32, ((Vec<H256>, u8), Instant)>,
+}
+
+impl IsmConfigCache {
+    pub fn new(ttl: Duration, lookups: IntCounterVec) -> Self {
+        Self {
+            ttl,
+            isms: Default::default(),
+            lookups,
+        }
+    }
+
+    /// The cached type of `ism`, if it has not expired
+    pub fn module_type(&self, destination: &HyperlaneDomain, ism: H256) -> Option<ModuleType> {
+        let module_type = self
+            .isms
+            .lock()
+            .expect("ISM config cache lock poisoned")
+            .get(&(destination.id(), ism))
+            .and_then(|cached| cached.module_type)
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.ttl)
+            .map(|(module_type, _)| module_type);
+        self.record_lookup(destination, module_type.is_some());
+        module_type
+    }
+
+    pub fn insert_module_type(
+        &self,
+        destination: &HyperlaneDomain,
+        ism: H256,
+        module_type: ModuleType,
+    ) {
+        self.isms
+            .lock()
+            .expect("ISM config cache lock poisoned")
+            .entry((destination.id(), ism))
+            .or_default()
+            .module_type = Some((module_type, Instant::now()));
+    }
+
+    /// The cached validators or modules and threshold `ism` has for messages
+    /// from `origin`, if they have not expired
+    pub fn config(
+        &self,
+        destination: &HyperlaneDomain,
+        ism: H256,
+        origin: u32,
+    ) -> Option<(Vec<H256>, u8)> {
+        let config = self
+            .isms
+            .lock()
+            .expect("ISM config cache lock poisoned")
+            .get(&(destination.id(), ism))
+            .and_then(|cached| cached.configs.get(&origin))
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.ttl)
+            .map(|(config, _)| config.clone());
+        self.record_lookup(destination, config.is_some());
+        config
+    }
+
+    pub fn insert_config(
+        &self,
+        destination: &HyperlaneDomain,
+        ism: H256,
+        origin: u32,
+        config: (Vec<H256>, u8),
+    ) {
+        debug!(?ism, origin, ?config, "Caching ISM configuration");
+        self.isms
+            .lock()
+            .expect("ISM config cache lock poisoned")
+            .entry((destination.id(), ism))
+            .or_default()
+            .configs
+            .insert(origin, (config, Instant::now()));
+    }
+
+    /// Evict everything cached for `ism` on `destination`, after its
+    /// configuration for `origin` changed. The configurations other ISMs have
+    /// for `origin` are evicted as well, since they may route to or aggregate
+    /// `ism`.
+    pub fn invalidate(&self, destination: &HyperlaneDomain, ism: H256, origin: u32) {
+        let mut isms = self.isms.lock().expect("ISM config cache lock poisoned");
+        isms.remove(&(destination.id(), ism));
+        isms.iter_mut()
+            .filter(|((cached_destination, _), _)| *cached_destination == destination.id())
+            .for_each(|(_, cached)| {
+                cached.configs.remove(&origin);
+            });
+    }
+
+    fn record_lookup(&self, destination: &HyperlaneDomain, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.lookups
+            .with_label_values(&[destination.name(), result])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyperlane_core::KnownHyperlaneDomain;
+    use tokio::sync::broadcast;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requeues_only_on_config_change() {
+        let (retry_sender, mut retry_receiver) = broadcast::channel(10);
+        let monitor = IsmConfigMonitor::new(
+            HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum),
+            retry_sender,
+        );
+        let ism = H256::repeat_byte(1);
+        let validators = vec![H256::repeat_byte(2), H256::repeat_byte(3)];
+
+        monitor.observe(ism, 1, (&validators, 2u8));
+        monitor.observe(ism, 1, (&validators, 2u8));
+        // The same ISM may be configured differently for another origin
+        monitor.observe(ism, 2, (&validators, 1u8));
+        assert!(retry_receiver.try_recv().is_err());
+
+        monitor.observe(ism, 1, (&validators, 1u8));
+        let request = retry_receiver.try_recv().unwrap();
+        assert_eq!(
+            request.reprepare_reasons,
+            Some(vec![
+                ReprepareReason::CouldNotFetchMetadata,
+                ReprepareReason::ErrorBuildingMetadata,
+            ])
+        );
+        assert!(retry_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_config_change_invalidates_cache() {
+        let (retry_sender, _retry_receiver) = broadcast::channel(10);
+        let destination = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let lookups = IntCounterVec::new(
+            prometheus::opts!("lookups", "lookups"),
+            &["destination", "result"],
+        )
+        .unwrap();
+        let cache = Arc::new(IsmConfigCache::new(Duration::from_secs(60), lookups));
+        let monitor = IsmConfigMonitor::new(destination.clone(), retry_sender)
+            .with_config_cache(cache.clone());
+        let (ism, other_ism) = (H256::repeat_byte(1), H256::repeat_byte(4));
+        let config = (vec![H256::repeat_byte(2)], 1u8);
+
+        cache.insert_module_type(&destination, ism, ModuleType::MessageIdMultisig);
+        cache.insert_module_type(&destination, other_ism, ModuleType::Aggregation);
+        cache.insert_config(&destination, ism, 1, config.clone());
+        cache.insert_config(&destination, other_ism, 1, config.clone());
+        cache.insert_config(&destination, ism, 2, config.clone());
+        cache.insert_config(&destination, other_ism, 2, config.clone());
+        monitor.observe(ism, 1, &config);
+        assert_eq!(cache.config(&destination, ism, 1), Some(config.clone()));
+
+        monitor.observe(ism, 1, (vec![H256::repeat_byte(3)], 1u8));
+        // Everything cached for the ISM is evicted, as are the configs other
+        // ISMs have for the origin, but not their module types or their
+        // configs for other origins
+        assert_eq!(cache.config(&destination, ism, 1), None);
+        assert_eq!(cache.config(&destination, ism, 2), None);
+        assert_eq!(cache.module_type(&destination, ism), None);
+        assert_eq!(cache.config(&destination, other_ism, 1), None);
+        assert_eq!(cache.config(&destination, other_ism, 2), Some(config));
+        assert_eq!(
+            cache.module_type(&destination, other_ism),
+            Some(ModuleType::Aggregation)
+        );
+
+        let hits = cache.lookups.with_label_values(&["arbitrum", "hit"]).get();
+        let misses = cache.lookups.with_label_values(&["arbitrum", "miss"]).get();
+        assert_eq!((hits, misses), (3, 4));
+    }
+}
@@ -1,6 +1,7 @@
 mod aggregation;
 mod base;
 mod ccip_read;
+mod ism_config;
 mod multisig;
 mod null_metadata;
 mod routing;
@@ -11,5 +12,7 @@ pub(crate) use base::{
     AppContextClassifier, BaseMetadataBuilder, IsmAwareAppContextClassifier, MessageMetadataBuilder,
 };
 use ccip_read::CcipReadIsmMetadataBuilder;
+pub(crate) use ism_config::{IsmConfigCache, IsmConfigMonitor};
 use null_metadata::NullMetadataBuilder;
+pub(crate) use routing::RoutingIsmCache;
 use routing::RoutingIsmMetadataBuilder;
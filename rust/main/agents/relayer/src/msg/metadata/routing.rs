@@ -1,9 +1,16 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use async_trait::async_trait;
 use derive_more::Deref;
 use derive_new::new;
 use eyre::Context;
 use hyperlane_core::{HyperlaneMessage, H256};
-use tracing::instrument;
+use prometheus::IntCounter;
+use tokio::sync::RwLock;
+use tracing::{debug, instrument, warn};
 
 use super::{MessageMetadataBuilder, MetadataBuilder};
 
@@ -22,8 +29,146 @@ impl MetadataBuilder for RoutingIsmMetadataBuilder {
         message: &HyperlaneMessage,
     ) -> eyre::Result<Option<Vec<u8>>> {
         const CTX: &str = "When fetching RoutingIsm metadata";
-        let ism = self.build_routing_ism(ism_address).await.context(CTX)?;
-        let module = ism.route(message).await.context(CTX)?;
+        let Some(cache) = self.routing_ism_cache() else {
+            let module = self.route(ism_address, message).await.context(CTX)?;
+            return self.base.build(module, message).await.context(CTX);
+        };
+
+        // The module that was cached but couldn't build metadata
+        let mut stale_module = None;
+        if let Some(module) = cache.get(ism_address, message.origin).await {
+            // The route may have changed since it was cached, so look it up
+            // again before giving up, whether the cached module failed or
+            // couldn't build metadata.
+            match self.base.build(module, message).await {
+                Ok(Some(metadata)) => return Ok(Some(metadata)),
+                Ok(None) => {
+                    debug!(?module, "No metadata for cached route, invalidating");
+                    stale_module = Some(module);
+                }
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        ?module,
+                        "Failed to build metadata for cached route, invalidating"
+                    );
+                }
+            }
+            cache.invalidate(ism_address, message.origin).await;
+        }
+
+        let module = self.route(ism_address, message).await.context(CTX)?;
+        cache.insert(ism_address, message.origin, module).await;
+        // The route didn't change, so building metadata again would only
+        // yield none again
+        if stale_module == Some(module) {
+            return Ok(None);
+        }
         self.base.build(module, message).await.context(CTX)
     }
 }
+
+impl RoutingIsmMetadataBuilder {
+    async fn route(&self, ism_address: H256, message: &HyperlaneMessage) -> eyre::Result<H256> {
+        let ism = self.build_routing_ism(ism_address).await?;
+        let module = ism.route(message).await?;
+        self.ism_config_monitor()
+            .observe(ism_address, message.origin, module);
+        Ok(module)
+    }
+}
+
+/// Caches the module a routing ISM routes messages from an origin to, saving
+/// the RPC calls needed to look it up for every message.
+///
+/// Entries are keyed by the routing ISM and the message origin, so this is only
+/// correct for routing ISMs that route on the origin alone, like the
+/// `DomainRoutingIsm`. Entries expire after `ttl` so that route changes are
+/// eventually picked up, and are invalidated early when building metadata for
+/// the cached module fails or yields none.
+#[derive(Debug)]
+pub struct RoutingIsmCache {
+    ttl: Duration,
+    routes: RwLock<HashMap<(H256, u32), (H256, Instant)>>,
+    hits: IntCounter,
+    misses: IntCounter,
+}
+
+impl RoutingIsmCache {
+    pub fn new(ttl: Duration, hits: IntCounter, misses: IntCounter) -> Self {
+        Self {
+            ttl,
+            routes: RwLock::new(HashMap::new()),
+            hits,
+            misses,
+        }
+    }
+
+    /// The cached module for messages from `origin`, if it has not expired.
+    pub async fn get(&self, routing_ism: H256, origin: u32) -> Option<H256> {
+        let module = self
+            .routes
+            .read()
+            .await
+            .get(&(routing_ism, origin))
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.ttl)
+            .map(|(module, _)| *module);
+        match module {
+            Some(_) => self.hits.inc(),
+            None => self.misses.inc(),
+        }
+        module
+    }
+
+    pub async fn insert(&self, routing_ism: H256, origin: u32, module: H256) {
+        debug!(?routing_ism, origin, ?module, "Caching routing ISM route");
+        self.routes
+            .write()
+            .await
+            .insert((routing_ism, origin), (module, Instant::now()));
+    }
+
+    pub async fn invalidate(&self, routing_ism: H256, origin: u32) {
+        self.routes.write().await.remove(&(routing_ism, origin));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cache(ttl: Duration) -> RoutingIsmCache {
+        RoutingIsmCache::new(
+            ttl,
+            IntCounter::new("hits", "hits").unwrap(),
+            IntCounter::new("misses", "misses").unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_routing_ism_cache() {
+        let cache = cache(Duration::from_secs(60));
+        let (routing_ism, module) = (H256::random(), H256::random());
+
+        assert_eq!(cache.get(routing_ism, 1).await, None);
+        cache.insert(routing_ism, 1, module).await;
+        assert_eq!(cache.get(routing_ism, 1).await, Some(module));
+        // Routes are cached per origin
+        assert_eq!(cache.get(routing_ism, 2).await, None);
+
+        cache.invalidate(routing_ism, 1).await;
+        assert_eq!(cache.get(routing_ism, 1).await, None);
+
+        assert_eq!(cache.hits.get(), 1);
+        assert_eq!(cache.misses.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_routing_ism_cache_expiry() {
+        let cache = cache(Duration::ZERO);
+        let routing_ism = H256::random();
+
+        cache.insert(routing_ism, 1, H256::random()).await;
+        assert_eq!(cache.get(routing_ism, 1).await, None);
+    }
+}
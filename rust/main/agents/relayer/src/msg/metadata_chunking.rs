@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, MetadataChunkStore, TxOutcome, H256, U256,
+};
+use tracing::{debug, warn};
+
+use crate::settings::MetadataChunkingConf;
+
+/// Metadata stored in chunks, ready for the message to be processed.
+#[derive(Debug, Clone)]
+pub struct StoredMetadata {
+    /// The metadata to process the message with, which points the ISM at the
+    /// stored metadata
+    pub pointer: Vec<u8>,
+    /// Outcomes of the transactions that stored the chunks
+    pub outcomes: Vec<TxOutcome>,
+}
+
+/// Failure to store the chunks of some metadata.
+#[derive(Debug)]
+pub struct ChunkingFailure {
+    pub error: ChainCommunicationError,
+    /// Outcomes of the transactions sent before the failure, including the
+    /// rollback, which still have to be paid for
+    pub outcomes: Vec<TxOutcome>,
+}
+
+/// Delivers messages whose ISM metadata is too large to be submitted in a
+/// single transaction to destinations whose ISMs support pointer-based
+/// metadata.
+///
+/// Metadata longer than `threshold` is split into chunks of at most
+/// `chunk_size` bytes, which are stored in the destination's metadata chunk
+/// store one transaction at a time. The message is then processed with
+/// metadata pointing at the stored chunks. If any chunk fails to be stored,
+/// or processing fails afterwards, the chunks are cleared again so that a
+/// later attempt starts over.
+#[derive(Debug, Clone)]
+pub struct MetadataChunker {
+    store: Arc<dyn MetadataChunkStore>,
+    threshold: usize,
+    chunk_size: usize,
+}
+
+impl MetadataChunker {
+    pub fn new(store: Arc<dyn MetadataChunkStore>, conf: &MetadataChunkingConf) -> Self {
+        Self {
+            store,
+            threshold: conf.threshold,
+            chunk_size: conf.chunk_size,
+        }
+    }
+
+    /// Whether `metadata` has to be stored in chunks before the message is
+    /// processed.
+    pub fn needs_chunking(&self, metadata: &[u8]) -> bool {
+        metadata.len() > self.threshold
+    }
+
+    /// Estimate the total gas limit of the transactions storing `metadata` in
+    /// chunks under `key`
+    pub async fn estimate_store_gas(&self, key: H256, metadata: &[u8]) -> ChainResult<U256> {
+        let mut gas = U256::zero();
+        for (index, chunk) in metadata.chunks(self.chunk_size).enumerate() {
+            gas = gas.saturating_add(
+                self.store
+                    .estimate_store_chunk(key, index as u32, chunk)
+                    .await?,
+            );
+        }
+        Ok(gas)
+    }
+
+    /// Store `metadata` in chunks under `key`. If a chunk fails to be stored,
+    /// the chunks stored so far are cleared.
+    pub async fn store(
+        &self,
+        key: H256,
+        metadata: &[u8],
+    ) -> Result<StoredMetadata, ChunkingFailure> {
+        let mut outcomes = vec![];
+        for (index, chunk) in metadata.chunks(self.chunk_size).enumerate() {
+            let error = match self.store.store_chunk(key, index as u32, chunk).await {
+                Ok(outcome) if outcome.executed => {
+                    outcomes.push(outcome);
+                    continue;
+                }
+                Ok(outcome) => {
+                    let error = ChainCommunicationError::from_other_str(&format!(
+                        "Storing metadata chunk {index} reverted in {:?}",
+                        outcome.transaction_id
+                    ));
+                    outcomes.push(outcome);
+                    error
+                }
+                Err(error) => error,
+            };
+            warn!(?key, index, ?error, "Failed to store metadata chunk");
+            // Only roll back if any chunks were stored
+            if index > 0 {
+                outcomes.extend(self.clear(key).await);
+            }
+            return Err(ChunkingFailure { error, outcomes });
+        }
+
+        let pointer = match self.store.pointer_metadata(key).await {
+            Ok(pointer) => pointer,
+            Err(error) => {
+                outcomes.extend(self.clear(key).await);
+                return Err(ChunkingFailure { error, outcomes });
+            }
+        };
+        debug!(
+            ?key,
+            metadata_len = metadata.len(),
+            chunks = outcomes.len(),
+            "Stored metadata in chunks"
+        );
+        Ok(StoredMetadata { pointer, outcomes })
+    }
+
+    /// Clear the chunks stored under `key`, returning the outcome of the
+    /// transaction if one was sent.
+    pub async fn clear(&self, key: H256) -> Option<TxOutcome> {
+        match self.store.clear(key).await {
+            Ok(outcome) => Some(outcome),
+            Err(error) => {
+                warn!(?key, ?error, "Failed to clear metadata chunks");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::{FixedPointNumber, H512};
+    use hyperlane_test::mocks::MockMetadataChunkStoreContract;
+    use mockall::predicate::eq;
+
+    use super::*;
+
+    fn outcome(executed: bool) -> TxOutcome {
+        TxOutcome {
+            transaction_id: H512::zero(),
+            executed,
+            gas_used: U256::from(50_000),
+            gas_price: FixedPointNumber::zero(),
+            block_number: None,
+        }
+    }
+
+    fn chunker(store: MockMetadataChunkStoreContract) -> MetadataChunker {
+        MetadataChunker::new(
+            Arc::new(store),
+            &MetadataChunkingConf {
+                store: H256::zero(),
+                threshold: 4,
+                chunk_size: 4,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_stores_metadata_in_chunks() {
+        let key = H256::repeat_byte(1);
+        let mut store = MockMetadataChunkStoreContract::new();
+        store
+            .expect__store_chunk()
+            .withf(|_, index, chunk| match *index {
+                0 => chunk.to_vec() == [1, 2, 3, 4],
+                1 => chunk.to_vec() == [5],
+                _ => false,
+            })
+            .times(2)
+            .returning(|_, _, _| Ok(outcome(true)));
+        store
+            .expect__pointer_metadata()
+            .with(eq(key))
+            .returning(|key| Ok(key.as_bytes().to_vec()));
+        store.expect__clear().never();
+        let chunker = chunker(store);
+
+        assert!(!chunker.needs_chunking(&[1, 2, 3, 4]));
+        assert!(chunker.needs_chunking(&[1, 2, 3, 4, 5]));
+        let stored = chunker.store(key, &[1, 2, 3, 4, 5]).await.unwrap();
+        assert_eq!(stored.pointer, key.as_bytes());
+        assert_eq!(stored.outcomes.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_estimates_gas_per_chunk() {
+        let key = H256::repeat_byte(1);
+        let mut store = MockMetadataChunkStoreContract::new();
+        store
+            .expect__estimate_store_chunk()
+            .times(3)
+            .returning(|_, _, chunk| Ok(U256::from(20_000 + chunk.len())));
+        store.expect__store_chunk().never();
+        let chunker = chunker(store);
+
+        let gas = chunker
+            .estimate_store_gas(key, &[1, 2, 3, 4, 5, 6, 7, 8, 9])
+            .await
+            .unwrap();
+        assert_eq!(gas, U256::from(60_009));
+    }
+
+    #[tokio::test]
+    async fn test_rolls_back_partially_stored_metadata() {
+        let key = H256::repeat_byte(1);
+        let mut store = MockMetadataChunkStoreContract::new();
+        store
+            .expect__store_chunk()
+            .returning(|_, index, _| Ok(outcome(index == 0)));
+        store
+            .expect__clear()
+            .with(eq(key))
+            .times(1)
+            .returning(|_| Ok(outcome(true)));
+        store.expect__pointer_metadata().never();
+        let chunker = chunker(store);
+
+        let failure = chunker.store(key, &[1, 2, 3, 4, 5]).await.unwrap_err();
+        // The stored chunk, the reverted chunk and the rollback are all paid for
+        assert_eq!(failure.outcomes.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_roll_back_if_nothing_was_stored() {
+        let key = H256::repeat_byte(1);
+        let mut store = MockMetadataChunkStoreContract::new();
+        store
+            .expect__store_chunk()
+            .returning(|_, _, _| Err(ChainCommunicationError::SignerUnavailable));
+        store.expect__clear().never();
+        let chunker = chunker(store);
+
+        let failure = chunker.store(key, &[1, 2, 3, 4, 5]).await.unwrap_err();
+        assert!(failure.outcomes.is_empty());
+    }
+}
@@ -28,11 +28,22 @@
 //!   switch everyone to new one)
 
 pub(crate) mod blacklist;
+pub(crate) mod dead_letters;
+pub(crate) mod delivery_receipts;
+pub(crate) mod delivery_ttl;
+pub(crate) mod fee_tracker;
+pub(crate) mod gas_escalation;
 pub(crate) mod gas_payment;
+pub(crate) mod matching_list_reloader;
 pub(crate) mod metadata;
+pub(crate) mod metadata_chunking;
 pub(crate) mod op_queue;
 pub(crate) mod op_submitter;
+pub(crate) mod ordering;
 pub(crate) mod pending_message;
 pub(crate) mod processor;
+pub(crate) mod retry;
+pub(crate) mod route_simulation;
+pub(crate) mod skipped_recipients;
 
 pub use gas_payment::GAS_EXPENDITURE_LOG_MESSAGE;
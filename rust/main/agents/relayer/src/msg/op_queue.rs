@@ -140,6 +140,12 @@ impl OpQueue {
                         if !retry_req.pattern.op_matches(&op) {
                             return;
                         }
+                        if let Some(reasons) = &retry_req.reprepare_reasons {
+                            if !matches!(op.status(), PendingOperationStatus::Retry(reason) if reasons.contains(&reason))
+                            {
+                                return;
+                            }
+                        }
                         // update retry metrics
                         retry_response.matched += 1;
                         matched = true;
@@ -439,6 +445,7 @@ pub mod test {
             .send(MessageRetryRequest {
                 uuid: "59400966-e7fa-4fb9-9372-9a671d4392c3".to_string(),
                 pattern: MatchingList::with_message_id(op_ids[1]),
+                reprepare_reasons: None,
                 transmitter: transmitter.clone(),
             })
             .unwrap();
@@ -446,6 +453,7 @@ pub mod test {
             .send(MessageRetryRequest {
                 uuid: "59400966-e7fa-4fb9-9372-9a671d4392c3".to_string(),
                 pattern: MatchingList::with_message_id(op_ids[2]),
+                reprepare_reasons: None,
                 transmitter,
             })
             .unwrap();
@@ -505,6 +513,7 @@ pub mod test {
             .send(MessageRetryRequest {
                 uuid: "a5b39473-7cc5-48a1-8bed-565454ba1037".to_string(),
                 pattern: MatchingList::with_destination_domain(destination_domain_2.id()),
+                reprepare_reasons: None,
                 transmitter,
             })
             .unwrap();
@@ -556,6 +565,7 @@ pub mod test {
             .send(MessageRetryRequest {
                 uuid: "0e92ace7-ba5d-4a1f-8501-51b6d9d500cf".to_string(),
                 pattern: MatchingList::with_message_id(op_ids[1]),
+                reprepare_reasons: None,
                 transmitter: transmitter.clone(),
             })
             .unwrap();
@@ -582,6 +592,7 @@ pub mod test {
                 pattern: MatchingList::with_destination_domain(
                     KnownHyperlaneDomain::Arbitrum as u32,
                 ),
+                reprepare_reasons: None,
                 transmitter: transmitter.clone(),
             })
             .unwrap();
@@ -624,6 +635,7 @@ pub mod test {
                     Filter::Wildcard,
                     Filter::Wildcard,
                 )])),
+                reprepare_reasons: None,
                 transmitter: transmitter.clone(),
             })
             .unwrap();
@@ -680,6 +692,7 @@ pub mod test {
                     Filter::Enumerated(vec![KnownHyperlaneDomain::Optimism as u32]),
                     Filter::Wildcard,
                 )])),
+                reprepare_reasons: None,
                 transmitter: transmitter.clone(),
             })
             .unwrap();
@@ -745,6 +758,7 @@ pub mod test {
                         Filter::Wildcard,
                     ),
                 ])),
+                reprepare_reasons: None,
                 transmitter: transmitter.clone(),
             })
             .unwrap();
@@ -809,6 +823,7 @@ pub mod test {
                     Filter::Wildcard,
                 ),
             ])),
+            reprepare_reasons: None,
             transmitter: transmitter.clone(),
         };
 
@@ -2,7 +2,7 @@
 #![allow(clippy::doc_lazy_continuation)] // TODO: `rustc` 1.80.1 clippy issue
 
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use derive_new::new;
 use futures::future::join_all;
@@ -260,6 +260,7 @@ async fn prepare_task(
             sleep(Duration::from_millis(100)).await;
             continue;
         }
+        check_deliveries(&mut batch).await;
         let mut task_prep_futures = vec![];
         let op_refs = batch.iter_mut().map(|op| op.as_mut()).collect::<Vec<_>>();
         for op in op_refs {
@@ -316,6 +317,46 @@ async fn prepare_task(
     }
 }
 
+/// Looks up whether the operations of a batch that are ready for another
+/// attempt were delivered with a single `delivered_batch` call, rather than
+/// one call per operation. Operations fall back to their own lookup if this
+/// fails.
+pub(crate) async fn check_deliveries(batch: &mut [QueueOperation]) {
+    let now = Instant::now();
+    let mut ready = batch
+        .iter_mut()
+        .filter(|op| op.next_attempt_after().map_or(true, |after| now >= after))
+        .collect::<Vec<_>>();
+    // We already assume that the relayer submits to a single mailbox per
+    // destination, like when submitting batches
+    if ready.len() < 2 {
+        return;
+    }
+    let Some(mailbox) = ready[0].try_get_mailbox() else {
+        return;
+    };
+    let Some(messages) = ready
+        .iter()
+        .map(|op| op.try_get_message().cloned())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+    match mailbox.delivered_batch(&messages).await {
+        Ok(delivered) if delivered.len() == ready.len() => {
+            for (op, delivered) in ready.iter_mut().zip(delivered) {
+                op.set_delivered(delivered);
+            }
+        }
+        Ok(delivered) => warn!(
+            expected = ready.len(),
+            got = delivered.len(),
+            "Batched delivery check returned the wrong number of results"
+        ),
+        Err(err) => warn!(?err, "Batched delivery check failed"),
+    }
+}
+
 #[instrument(skip_all, fields(%domain))]
 async fn submit_task(
     domain: HyperlaneDomain,
@@ -419,13 +460,14 @@ async fn confirm_task(
     let recv_limit = max_batch_size as usize;
     loop {
         // Pick the next message to try confirming.
-        let batch = confirm_queue.pop_many(recv_limit).await;
+        let mut batch = confirm_queue.pop_many(recv_limit).await;
 
         if batch.is_empty() {
             // queue is empty so give some time before checking again to prevent burning CPU
             sleep(Duration::from_millis(200)).await;
             continue;
         }
+        check_deliveries(&mut batch).await;
 
         let futures = batch.into_iter().map(|op| {
             confirm_operation(
@@ -0,0 +1,432 @@
+//! Ordered delivery of messages that share an application specified ordering
+//! key.
+//!
+//! Messages are normally relayed as soon as they are indexed, and retries and
+//! batching on the destination can reorder them. Messages matching an ordering
+//! key config are instead held back by the processor of their origin and
+//! released one at a time: the next message with a key is only sent to the
+//! submitter once the previous one was delivered, dropped or moved to the
+//! dead-letter queue. Messages from the same origin are released in nonce
+//! order. The set of in-flight keys is shared by the processors of all
+//! origins, so a key has at most one message in flight across origins.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use eyre::{eyre, Result};
+use hyperlane_base::db::{HyperlaneDb, HyperlaneRocksDB, OrderedMessage};
+use hyperlane_core::HyperlaneMessage;
+use tracing::{debug, warn};
+
+use crate::settings::{OrderingKeyConf, OrderingKeySource};
+
+/// The ordering key of a message
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OrderingKey {
+    /// Index of the ordering key config the key was derived with, so that keys
+    /// of different configs never collide
+    rule: usize,
+    value: Vec<u8>,
+}
+
+/// Keys that have a message in flight, mapped to the origin domain and nonce of
+/// that message. Shared by the processors of all origins.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightOrderingKeys(Arc<Mutex<HashMap<OrderingKey, (u32, u32)>>>);
+
+impl InFlightOrderingKeys {
+    /// Claims `key` for the message with `nonce` from `origin`. Succeeds if
+    /// the key is free or already claimed by that message.
+    fn try_claim(&self, key: &OrderingKey, origin: u32, nonce: u32) -> Result<bool> {
+        let mut in_flight = self
+            .0
+            .lock()
+            .map_err(|_| eyre!("In-flight ordering keys lock poisoned"))?;
+        match in_flight.get(key) {
+            Some(claim) => Ok(*claim == (origin, nonce)),
+            None => {
+                in_flight.insert(key.clone(), (origin, nonce));
+                Ok(true)
+            }
+        }
+    }
+
+    /// Frees `key` if it is claimed by the message with `nonce` from `origin`
+    fn free(&self, key: &OrderingKey, origin: u32, nonce: u32) {
+        // A poisoned lock is still freed, as a stuck key blocks all messages
+        // sharing it
+        let mut in_flight = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        if in_flight.get(key) == Some(&(origin, nonce)) {
+            in_flight.remove(key);
+        }
+    }
+}
+
+/// Claim of a released message on its ordering key. The key is freed when the
+/// claim is dropped, i.e. when the message reaches a terminal outcome: it was
+/// delivered, dropped by prepare or through the admin API, or moved to the
+/// dead-letter queue, e.g. because it expired.
+#[derive(Debug)]
+pub struct OrderingKeyClaim {
+    key: OrderingKey,
+    nonce: u32,
+    in_flight: InFlightOrderingKeys,
+    db: HyperlaneRocksDB,
+}
+
+impl Drop for OrderingKeyClaim {
+    fn drop(&mut self) {
+        let origin = self.db.domain().id();
+        self.in_flight.free(&self.key, origin, self.nonce);
+        if let Err(err) = self.db.delete_ordered_message_by_nonce(self.nonce) {
+            warn!(?err, nonce = self.nonce, "Failed to delete ordered message");
+        }
+        debug!(key = ?self.key, nonce = self.nonce, "Freed ordering key");
+    }
+}
+
+/// Holds back the keyed messages of an origin until they can be delivered in
+/// order. Held messages are persisted in the origin's database and restored
+/// on startup.
+pub struct OrderingGate {
+    db: HyperlaneRocksDB,
+    confs: Vec<OrderingKeyConf>,
+    in_flight: InFlightOrderingKeys,
+    /// Keyed messages that were not released yet, by key and nonce
+    held: HashMap<OrderingKey, BTreeMap<u32, HyperlaneMessage>>,
+    /// Nonces of the messages restored from the database, which the processor
+    /// finds again when scanning for unprocessed messages
+    restored: HashSet<u32>,
+}
+
+impl OrderingGate {
+    pub fn new(
+        db: HyperlaneRocksDB,
+        confs: Vec<OrderingKeyConf>,
+        in_flight: InFlightOrderingKeys,
+    ) -> Self {
+        let mut gate = Self {
+            db,
+            confs,
+            in_flight,
+            held: HashMap::new(),
+            restored: HashSet::new(),
+        };
+        if let Err(err) = gate.restore() {
+            warn!(?err, "Failed to restore ordered messages");
+        }
+        gate
+    }
+
+    /// Restores the messages held before a restart. Messages that were in
+    /// flight claim their key again, so that the messages of other origins
+    /// don't overtake them.
+    fn restore(&mut self) -> Result<()> {
+        let origin = self.db.domain().id();
+        for ordered in self.db.retrieve_ordered_messages()? {
+            let message = match self.db.retrieve_message_by_nonce(ordered.nonce)? {
+                Some(message)
+                    if !self
+                        .db
+                        .retrieve_processed_by_nonce(&ordered.nonce)?
+                        .unwrap_or(false) =>
+                {
+                    message
+                }
+                // Processed while the relayer was down
+                _ => {
+                    self.db.delete_ordered_message_by_nonce(ordered.nonce)?;
+                    continue;
+                }
+            };
+            let key = OrderingKey {
+                rule: ordered.rule as usize,
+                value: ordered.key,
+            };
+            if ordered.in_flight && !self.in_flight.try_claim(&key, origin, ordered.nonce)? {
+                warn!(?key, nonce = ordered.nonce, "Ordering key claimed twice");
+            }
+            self.restored.insert(ordered.nonce);
+            self.held
+                .entry(key)
+                .or_default()
+                .insert(message.nonce, message);
+        }
+        Ok(())
+    }
+
+    /// Derives the ordering key of a message from the first config it matches.
+    /// Returns `None` if the message isn't ordered.
+    pub fn ordering_key(&self, message: &HyperlaneMessage) -> Option<OrderingKey> {
+        let (rule, conf) = self
+            .confs
+            .iter()
+            .enumerate()
+            .find(|(_, conf)| conf.matching_list.msg_matches(message, false))?;
+        let value = match conf.source {
+            OrderingKeySource::Sender => message.sender.as_bytes().to_vec(),
+            OrderingKeySource::Body { offset, length } => {
+                // Bodies too short to contain the whole key use what they have
+                let start = offset.min(message.body.len());
+                let end = offset.saturating_add(length).min(message.body.len());
+                message.body[start..end].to_vec()
+            }
+        };
+        Some(OrderingKey { rule, value })
+    }
+
+    /// Holds back a message until it is released.
+    pub fn hold(&mut self, key: OrderingKey, message: HyperlaneMessage) -> Result<()> {
+        // Restored messages are already held
+        if self.restored.remove(&message.nonce) {
+            return Ok(());
+        }
+        self.db
+            .store_ordered_message(&ordered_message(&key, message.nonce, false))?;
+        self.held
+            .entry(key)
+            .or_default()
+            .insert(message.nonce, message);
+        Ok(())
+    }
+
+    /// The messages held back, e.g. to check whether they expired
+    pub fn held(&self) -> Vec<HyperlaneMessage> {
+        self.held
+            .values()
+            .flat_map(|messages| messages.values().cloned())
+            .collect()
+    }
+
+    /// Stops holding back a message that won't be delivered, e.g. because it
+    /// expired, so that it doesn't keep the messages behind it waiting
+    pub fn remove(&mut self, message: &HyperlaneMessage) -> Result<()> {
+        self.db.delete_ordered_message_by_nonce(message.nonce)?;
+        self.restored.remove(&message.nonce);
+        for messages in self.held.values_mut() {
+            messages.remove(&message.nonce);
+        }
+        self.held.retain(|_, messages| !messages.is_empty());
+        Ok(())
+    }
+
+    /// Releases the lowest nonce held message of every free key, along with
+    /// its claim on the key.
+    pub fn release(&mut self) -> Result<Vec<(HyperlaneMessage, OrderingKeyClaim)>> {
+        let origin = self.db.domain().id();
+        let mut released = vec![];
+        for (key, messages) in self.held.iter_mut() {
+            let Some(nonce) = messages.keys().next().copied() else {
+                continue;
+            };
+            if !self.in_flight.try_claim(key, origin, nonce)? {
+                continue;
+            }
+            if let Err(err) = self
+                .db
+                .store_ordered_message(&ordered_message(key, nonce, true))
+            {
+                self.in_flight.free(key, origin, nonce);
+                return Err(err.into());
+            }
+            let claim = OrderingKeyClaim {
+                key: key.clone(),
+                nonce,
+                in_flight: self.in_flight.clone(),
+                db: self.db.clone(),
+            };
+            if let Some(message) = messages.remove(&nonce) {
+                released.push((message, claim));
+            }
+        }
+        self.held.retain(|_, messages| !messages.is_empty());
+        Ok(released)
+    }
+}
+
+fn ordered_message(key: &OrderingKey, nonce: u32, in_flight: bool) -> OrderedMessage {
+    OrderedMessage {
+        nonce,
+        rule: key.rule as u32,
+        key: key.value.clone(),
+        in_flight,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_base::db::{test_utils, HyperlaneRocksDB};
+    use hyperlane_core::{test_utils::dummy_domain, H256};
+
+    use crate::settings::matching_list::MatchingList;
+
+    use super::*;
+
+    fn conf(source: OrderingKeySource) -> OrderingKeyConf {
+        OrderingKeyConf {
+            matching_list: serde_json::from_str(r#"[{"origindomain": 1}]"#).unwrap(),
+            source,
+        }
+    }
+
+    fn message(origin: u32, nonce: u32, sender: u64, body: &[u8]) -> HyperlaneMessage {
+        HyperlaneMessage {
+            nonce,
+            origin,
+            sender: H256::from_low_u64_be(sender),
+            destination: 2,
+            body: body.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ordering_key_derivation() {
+        let gate = OrderingGate::new(
+            1,
+            vec![
+                conf(OrderingKeySource::Body {
+                    offset: 1,
+                    length: 2,
+                }),
+                OrderingKeyConf {
+                    matching_list: MatchingList::default(),
+                    source: OrderingKeySource::Sender,
+                },
+            ],
+            Default::default(),
+        );
+
+        let key = gate.ordering_key(&message(1, 0, 5, &[1, 2, 3, 4])).unwrap();
+        assert_eq!(key.rule, 0);
+        assert_eq!(key.value, vec![2, 3]);
+
+        // Short bodies use what they have
+        let key = gate.ordering_key(&message(1, 0, 5, &[1, 2])).unwrap();
+        assert_eq!(key.value, vec![2]);
+
+        // The second config matches nothing, so other origins aren't ordered
+        assert!(gate.ordering_key(&message(3, 0, 5, &[1, 2, 3])).is_none());
+    }
+
+    fn nonces(released: &[(HyperlaneMessage, OrderingKeyClaim)]) -> Vec<u32> {
+        let mut nonces = released.iter().map(|(m, _)| m.nonce).collect::<Vec<_>>();
+        nonces.sort();
+        nonces
+    }
+
+    fn store_message(db: &HyperlaneRocksDB, message: &HyperlaneMessage) {
+        db.store_message(message, 0).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_held_messages_released_in_nonce_order() {
+        test_utils::run_test_db(|db| async move {
+            let other_db = HyperlaneRocksDB::new(&dummy_domain(3, "other"), db.clone());
+            let db = HyperlaneRocksDB::new(&dummy_domain(1, "origin"), db);
+            let in_flight = InFlightOrderingKeys::default();
+            let mut gate = OrderingGate::new(
+                db.clone(),
+                vec![conf(OrderingKeySource::Sender)],
+                in_flight.clone(),
+            );
+            let mut other_origin_gate = OrderingGate::new(
+                other_db,
+                vec![conf(OrderingKeySource::Sender)],
+                in_flight.clone(),
+            );
+
+            for nonce in [2, 0, 1] {
+                let msg = message(1, nonce, 5, &[]);
+                gate.hold(gate.ordering_key(&msg).unwrap(), msg).unwrap();
+            }
+            let unrelated = message(1, 3, 6, &[]);
+            gate.hold(gate.ordering_key(&unrelated).unwrap(), unrelated)
+                .unwrap();
+
+            // One message per key is released
+            let released = gate.release().unwrap();
+            assert_eq!(nonces(&released), vec![0, 3]);
+            // Nothing is released while the released messages are in flight
+            assert!(gate.release().unwrap().is_empty());
+
+            // Other origins can't claim keys in flight
+            let key = gate.ordering_key(&message(1, 0, 5, &[])).unwrap();
+            other_origin_gate.hold(key, message(3, 0, 5, &[])).unwrap();
+            assert!(other_origin_gate.release().unwrap().is_empty());
+
+            // Dropping a released message, e.g. once it was delivered or
+            // moved to the dead-letter queue, frees its key
+            drop(released);
+            let other_released = other_origin_gate.release().unwrap();
+            assert_eq!(other_released.len(), 1);
+            assert!(gate.release().unwrap().is_empty());
+
+            drop(other_released);
+            let released = gate.release().unwrap();
+            assert_eq!(nonces(&released), vec![1]);
+            drop(released);
+            assert_eq!(nonces(&gate.release().unwrap()), vec![2]);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_held_messages_restored() {
+        test_utils::run_test_db(|db| async move {
+            let other_db = HyperlaneRocksDB::new(&dummy_domain(3, "other"), db.clone());
+            let db = HyperlaneRocksDB::new(&dummy_domain(1, "origin"), db);
+            let confs = vec![conf(OrderingKeySource::Sender)];
+            let messages = (0..4)
+                .map(|nonce| message(1, nonce, 5, &[]))
+                .collect::<Vec<_>>();
+            for msg in &messages {
+                store_message(&db, msg);
+            }
+
+            let mut gate = OrderingGate::new(db.clone(), confs.clone(), Default::default());
+            for msg in &messages {
+                gate.hold(gate.ordering_key(msg).unwrap(), msg.clone())
+                    .unwrap();
+            }
+            let released = gate.release().unwrap();
+            assert_eq!(nonces(&released), vec![0]);
+            // Restart while message 0 is in flight, without dropping it, and
+            // after message 1 was delivered by another relayer
+            std::mem::forget(released);
+            drop(gate);
+            db.store_processed_by_nonce(&1, &true).unwrap();
+
+            let in_flight = InFlightOrderingKeys::default();
+            let mut gate = OrderingGate::new(db.clone(), confs, in_flight.clone());
+            let mut other_origin_gate =
+                OrderingGate::new(other_db, vec![conf(OrderingKeySource::Sender)], in_flight);
+            let key = gate.ordering_key(&messages[0]).unwrap();
+            other_origin_gate.hold(key, message(3, 0, 5, &[])).unwrap();
+            // The restored in-flight message kept its key
+            assert!(other_origin_gate.release().unwrap().is_empty());
+
+            // Unprocessed messages found again by the processor aren't held
+            // twice
+            for msg in messages.iter().filter(|msg| msg.nonce != 1) {
+                gate.hold(gate.ordering_key(msg).unwrap(), msg.clone())
+                    .unwrap();
+            }
+            let released = gate.release().unwrap();
+            assert_eq!(nonces(&released), vec![0]);
+            drop(released);
+            // The processed message was forgotten
+            assert_eq!(
+                db.retrieve_ordered_messages()
+                    .unwrap()
+                    .iter()
+                    .map(|ordered| ordered.nonce)
+                    .collect::<Vec<_>>(),
+                vec![2, 3]
+            );
+        })
+        .await;
+    }
+}
@@ -1,31 +1,45 @@
 #![allow(clippy::clone_on_ref_ptr)] // TODO: `rustc` 1.80.1 clippy issue
 
 use std::{
+    collections::HashMap,
     fmt::{Debug, Formatter},
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
 use derive_new::new;
 use eyre::Result;
 use hyperlane_base::{
-    db::{HyperlaneDb, HyperlaneRocksDB},
+    db::{DeadLetterReason, DeliveryReceipt, HyperlaneDb, HyperlaneRocksDB},
     CoreMetrics,
 };
 use hyperlane_core::{
     gas_used_by_operation, BatchItem, ChainCommunicationError, ChainResult, ConfirmReason,
-    HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox, MessageSubmissionData,
-    PendingOperation, PendingOperationResult, PendingOperationStatus, ReprepareReason, TryBatchAs,
-    TxOutcome, H256, U256,
+    DeliveryDryRun, FixedPointNumber, HyperlaneChain, HyperlaneDomain, HyperlaneMessage, Mailbox,
+    MessageDeliverySimulator, MessageSubmissionData, PendingOperation, PendingOperationResult,
+    PendingOperationStatus, ReprepareReason, TryBatchAs, TxCostEstimate, TxOutcome,
+    UndeliverableReason, H256, U256,
 };
-use prometheus::{IntCounter, IntGauge};
+use prometheus::{IntCounter, IntCounterVec, IntGauge};
 use serde::Serialize;
-use tracing::{debug, error, info, info_span, instrument, trace, warn, Instrument};
+use strum::IntoEnumIterator;
+use tracing::{debug, error, info, info_span, instrument, trace, warn, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use super::{
+    dead_letters::store_dead_letter,
+    delivery_receipts::DeliveryReceiptLog,
+    delivery_ttl::DeliveryTtlPolicy,
+    fee_tracker::FeeTracker,
+    gas_escalation::{GasEscalation, GasEscalationPolicy},
     gas_payment::{GasPaymentEnforcer, GasPolicyStatus},
+    matching_list_reloader::ReloadableMatchingList,
     metadata::{BaseMetadataBuilder, MessageMetadataBuilder, MetadataBuilder},
+    metadata_chunking::MetadataChunker,
+    ordering::OrderingKeyClaim,
+    retry::RetryClass,
+    skipped_recipients::SkippedRecipients,
 };
 
 pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
@@ -36,6 +50,14 @@ pub const CONFIRM_DELAY: Duration = if cfg!(any(test, feature = "test-utils")) {
     Duration::from_secs(60 * 10)
 };
 
+/// How soon a message whose metadata was warmed up is retried, to check
+/// whether its gas payment was topped up
+pub const WARM_METADATA_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// How often a message to a skipped recipient program checks whether the
+/// program was removed from the skip list
+pub const SKIPPED_RECIPIENT_RECHECK_DELAY: Duration = Duration::from_secs(60);
+
 /// The message context contains the links needed to submit a message. Each
 /// instance is for a unique origin -> destination pairing.
 pub struct MessageContext {
@@ -52,6 +74,39 @@ pub struct MessageContext {
     /// Hard limit on transaction gas when submitting a transaction to the
     /// destination.
     pub transaction_gas_limit: Option<U256>,
+    /// Bounds gas spent on repeatedly failing deliveries to the destination.
+    pub gas_escalation_policy: GasEscalationPolicy,
+    /// Tracks the fees paid on the destination, if it is a Cosmos chain.
+    pub fee_tracker: Option<Arc<FeeTracker>>,
+    /// Log of receipts of messages from the origin delivered by the relayer,
+    /// if receipts are exported.
+    pub delivery_receipts: Option<Arc<DeliveryReceiptLog>>,
+    /// How long the metadata of a message waiting for its gas payment is kept
+    /// for reuse. Metadata is always rebuilt if not set.
+    pub metadata_warm_up_ttl: Option<Duration>,
+    /// Stores metadata too large to be submitted with the process call in
+    /// chunks, if the destination's ISMs support pointer-based metadata.
+    pub metadata_chunker: Option<MetadataChunker>,
+    /// Messages retried more often than this are moved to the dead-letter
+    /// queue. Messages are retried forever if not set.
+    pub dead_letter_after_retries: Option<u32>,
+    /// Drops messages from the origin that are too old to be delivered, if
+    /// any delivery TTL policies are configured.
+    pub delivery_ttl: Option<Arc<DeliveryTtlPolicy>>,
+    /// Dry-runs deliveries that fail gas estimation to tell apart ISM and
+    /// recipient failures, if the destination supports it.
+    pub delivery_simulator: Option<Arc<dyn MessageDeliverySimulator>>,
+    /// Matching list of messages that should be whitelisted. Checked again
+    /// before every attempt, since it may be reloaded while the message is
+    /// queued.
+    pub message_whitelist: ReloadableMatchingList,
+    /// Matching list of messages that should be blacklisted. Checked again
+    /// before every attempt, like the whitelist.
+    pub message_blacklist: ReloadableMatchingList,
+    /// Sealevel recipient programs that messages aren't delivered to. Checked
+    /// again before every attempt, since it may be changed through the admin
+    /// API while the message is queued.
+    pub skipped_recipients: Arc<SkippedRecipients>,
     pub metrics: MessageSubmissionMetrics,
 }
 
@@ -70,6 +125,11 @@ pub struct PendingMessage {
     submission_data: Option<Box<MessageSubmissionData>>,
     #[new(default)]
     num_retries: u32,
+    /// Number of submissions of this message that errored or were reverted
+    /// since the relayer started, used to escalate the gas limit.
+    #[new(default)]
+    #[serde(skip_serializing)]
+    failed_submissions: u32,
     #[new(value = "Instant::now()")]
     #[serde(skip_serializing)]
     last_attempted_at: Instant,
@@ -82,9 +142,36 @@ pub struct PendingMessage {
     #[new(default)]
     #[serde(skip_serializing)]
     metadata: Option<Vec<u8>>,
+    /// Metadata built while the message was only failing the gas payment
+    /// policy, and when it was built
+    #[new(default)]
+    #[serde(skip_serializing)]
+    warm_metadata: Option<(Vec<u8>, Instant)>,
+    /// Unix timestamp of the block the message was dispatched in, once
+    /// looked up to apply a delivery TTL policy
+    #[new(default)]
+    #[serde(skip_serializing)]
+    dispatched_at: Option<u64>,
     #[new(default)]
     #[serde(skip_serializing)]
     metric: Option<Arc<IntGauge>>,
+    /// Trace context of the root span of the delivery of this message, which
+    /// the spans of all its pipeline stages are children of, so that exported
+    /// traces follow a message from indexing to confirmation. Only the
+    /// context is kept, as spans are only exported once they're closed.
+    #[new(default)]
+    #[serde(skip_serializing)]
+    trace_context: opentelemetry::Context,
+    /// Whether the message was delivered, if a delivery check of its whole
+    /// queue looked it up since the last attempt
+    #[new(default)]
+    #[serde(skip_serializing)]
+    delivered: Option<bool>,
+    /// Claim on the ordering key of the message, if it has one. Dropped
+    /// along with the message, which frees the key for the next message.
+    #[new(default)]
+    #[serde(skip_serializing)]
+    ordering_key_claim: Option<OrderingKeyClaim>,
 }
 
 impl Debug for PendingMessage {
@@ -124,6 +211,17 @@ impl TryBatchAs<HyperlaneMessage> for PendingMessage {
                 warn!("Cannot batch message without submission data, returning BatchingFailed");
                 Err(ChainCommunicationError::BatchingFailed)
             }
+            // Chunked metadata has to be stored before the message is
+            // processed, which batches can't do
+            Some(data)
+                if self
+                    .ctx
+                    .metadata_chunker
+                    .as_ref()
+                    .is_some_and(|chunker| chunker.needs_chunking(&data.metadata)) =>
+            {
+                Err(ChainCommunicationError::BatchingFailed)
+            }
             Some(data) => Ok(BatchItem::new(
                 self.message.clone(),
                 data.as_ref().clone(),
@@ -191,20 +289,39 @@ impl PendingOperation for PendingMessage {
 
     #[instrument(skip(self), fields(id=?self.id()), level = "debug")]
     async fn prepare(&mut self) -> PendingOperationResult {
+        self.join_trace("prepare");
         if !self.is_ready() {
             trace!("Message is not ready to be submitted yet");
             return PendingOperationResult::NotReady;
         }
 
+        // The matching lists may have been reloaded since the message was queued
+        if !self
+            .ctx
+            .message_whitelist
+            .current()
+            .msg_matches(&self.message, true)
+        {
+            info!("Dropping message because it's no longer whitelisted");
+            return PendingOperationResult::Drop;
+        }
+        if self
+            .ctx
+            .message_blacklist
+            .current()
+            .msg_matches(&self.message, false)
+        {
+            info!("Dropping message because it was blacklisted");
+            return PendingOperationResult::Drop;
+        }
+        if self.ctx.skipped_recipients.is_skipped(&self.message) {
+            return self.on_skipped_recipient();
+        }
+
         // If the message has already been processed, e.g. due to another relayer having
         // already processed, then mark it as already-processed, and move on to
         // the next tick.
-        let is_already_delivered = match self
-            .ctx
-            .destination_mailbox
-            .delivered(self.message.id())
-            .await
-        {
+        let is_already_delivered = match self.is_delivered().await {
             Ok(is_delivered) => is_delivered,
             Err(err) => {
                 return self.on_reprepare(Some(err), ReprepareReason::ErrorCheckingDeliveryStatus);
@@ -217,6 +334,24 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted);
         }
 
+        if let Some(reason) = self.expiry().await {
+            warn!(
+                ?reason,
+                "Moving message to the dead-letter queue because it expired"
+            );
+            self.ctx.metrics.messages_expired.inc();
+            return self.dead_letter(reason);
+        }
+
+        if let Some(reason) = self.exhausted_retries() {
+            warn!(
+                num_retries = self.num_retries,
+                ?reason,
+                "Moving message to the dead-letter queue because it exhausted its retries"
+            );
+            return self.dead_letter(reason);
+        }
+
         let provider = self.ctx.destination_mailbox.provider();
 
         // We cannot deliver to an address that is not a contract so check and drop if it isn't.
@@ -237,45 +372,58 @@ impl PendingOperation for PendingMessage {
             return PendingOperationResult::Drop;
         }
 
-        let ism_address = match self
-            .ctx
-            .destination_mailbox
-            .recipient_ism(self.message.recipient)
-            .await
-        {
-            Ok(ism_address) => ism_address,
-            Err(err) => {
-                return self.on_reprepare(Some(err), ReprepareReason::ErrorFetchingIsmAddress);
-            }
-        };
-
-        let message_metadata_builder = match MessageMetadataBuilder::new(
-            ism_address,
-            &self.message,
-            self.ctx.metadata_builder.clone(),
-        )
-        .await
-        {
-            Ok(message_metadata_builder) => message_metadata_builder,
-            Err(err) => {
-                return self.on_reprepare(Some(err), ReprepareReason::ErrorGettingMetadataBuilder);
+        let (metadata, built_at) = match self.take_warm_metadata() {
+            Some(warm_metadata) => {
+                debug!("Reusing metadata built while waiting for gas payment");
+                warm_metadata
             }
-        };
-
-        let metadata = match message_metadata_builder
-            .build(ism_address, &self.message)
-            .await
-        {
-            Ok(metadata) => metadata,
-            Err(err) => {
-                return self.on_reprepare(Some(err), ReprepareReason::ErrorBuildingMetadata);
+            None => {
+                let ism_address = match self
+                    .ctx
+                    .destination_mailbox
+                    .recipient_ism(self.message.recipient)
+                    .await
+                {
+                    Ok(ism_address) => ism_address,
+                    Err(err) => {
+                        return self
+                            .on_reprepare(Some(err), ReprepareReason::ErrorFetchingIsmAddress);
+                    }
+                };
+
+                let message_metadata_builder = match MessageMetadataBuilder::new(
+                    ism_address,
+                    &self.message,
+                    self.ctx.metadata_builder.clone(),
+                )
+                .await
+                {
+                    Ok(message_metadata_builder) => message_metadata_builder,
+                    Err(err) => {
+                        return self
+                            .on_reprepare(Some(err), ReprepareReason::ErrorGettingMetadataBuilder);
+                    }
+                };
+
+                let metadata = match message_metadata_builder
+                    .build(ism_address, &self.message)
+                    .await
+                {
+                    Ok(metadata) => metadata,
+                    Err(err) => {
+                        return self
+                            .on_reprepare(Some(err), ReprepareReason::ErrorBuildingMetadata);
+                    }
+                };
+                let Some(metadata) = metadata else {
+                    self.metadata = None;
+                    return self
+                        .on_reprepare::<String>(None, ReprepareReason::CouldNotFetchMetadata);
+                };
+                (metadata, Instant::now())
             }
         };
-        self.metadata = metadata.clone();
-
-        let Some(metadata) = metadata else {
-            return self.on_reprepare::<String>(None, ReprepareReason::CouldNotFetchMetadata);
-        };
+        self.metadata = Some(metadata.clone());
 
         // Estimate transaction costs for the process call. If there are issues, it's
         // likely that gas estimation has failed because the message is
@@ -288,16 +436,34 @@ impl PendingOperation for PendingMessage {
             .await
         {
             Ok(tx_cost_estimate) => tx_cost_estimate,
+            Err(err) => {
+                let reason = self.gas_estimation_failure_reason(&err, &metadata).await;
+                return self.on_reprepare(Some(err), reason);
+            }
+        };
+
+        // Metadata stored in chunks is paid for by the message's gas payment
+        // too, so the gas of storing every chunk is part of the estimate the
+        // payment is checked against.
+        let chunks_gas = match self.estimate_metadata_chunks_gas(&metadata).await {
+            Ok(chunks_gas) => chunks_gas,
             Err(err) => {
                 return self.on_reprepare(Some(err), ReprepareReason::ErrorEstimatingGas);
             }
         };
+        let payment_cost_estimate = TxCostEstimate {
+            gas_limit: tx_cost_estimate.gas_limit.saturating_add(chunks_gas),
+            l2_gas_limit: tx_cost_estimate
+                .l2_gas_limit
+                .map(|l2_gas_limit| l2_gas_limit.saturating_add(chunks_gas)),
+            ..tx_cost_estimate.clone()
+        };
 
         // If the gas payment requirement hasn't been met, move to the next tick.
         let gas_limit = match self
             .ctx
             .origin_gas_payment_enforcer
-            .message_meets_gas_payment_requirement(&self.message, &tx_cost_estimate)
+            .message_meets_gas_payment_requirement(&self.message, &payment_cost_estimate)
             .await
         {
             Ok(gas_limit) => gas_limit,
@@ -308,13 +474,24 @@ impl PendingOperation for PendingMessage {
 
         let gas_limit = match gas_limit {
             GasPolicyStatus::NoPaymentFound => {
-                return self.on_reprepare::<String>(None, ReprepareReason::GasPaymentNotFound)
+                return self.on_gas_payment_pending(
+                    metadata,
+                    built_at,
+                    ReprepareReason::GasPaymentNotFound,
+                );
             }
             GasPolicyStatus::PolicyNotMet => {
-                return self
-                    .on_reprepare::<String>(None, ReprepareReason::GasPaymentRequirementNotMet)
+                return self.on_gas_payment_pending(
+                    metadata,
+                    built_at,
+                    ReprepareReason::GasPaymentRequirementNotMet,
+                );
             }
-            GasPolicyStatus::PolicyMet(gas_limit) => gas_limit,
+            // The process transaction only needs what's left after storing
+            // the chunks
+            GasPolicyStatus::PolicyMet(gas_limit) => gas_limit
+                .saturating_sub(chunks_gas)
+                .max(tx_cost_estimate.gas_limit),
         };
 
         // Go ahead and attempt processing of message to destination chain.
@@ -331,6 +508,23 @@ impl PendingOperation for PendingMessage {
             }
         }
 
+        let gas_limit = match self.ctx.gas_escalation_policy.escalate(
+            gas_limit,
+            &tx_cost_estimate,
+            self.failed_submissions,
+        ) {
+            GasEscalation::GasLimit(escalated) => {
+                // Escalation must never push the gas limit past the hard limit
+                match self.ctx.transaction_gas_limit {
+                    Some(max_limit) => escalated.min(max_limit),
+                    None => escalated,
+                }
+            }
+            GasEscalation::ExceedsMaxFee => {
+                return self.on_reprepare::<String>(None, ReprepareReason::ExceedsMaxFee);
+            }
+        };
+
         self.submission_data = Some(Box::new(MessageSubmissionData {
             metadata,
             gas_limit,
@@ -340,6 +534,7 @@ impl PendingOperation for PendingMessage {
 
     #[instrument(skip(self), fields(id=?self.id(), domain=%self.destination_domain()))]
     async fn submit(&mut self) -> PendingOperationResult {
+        self.join_trace("submit");
         if self.submitted {
             // this message has already been submitted, possibly not by us
             return PendingOperationResult::Success;
@@ -363,20 +558,76 @@ impl PendingOperation for PendingMessage {
             }
         }
 
-        // We use the estimated gas limit from the prior call to
-        // `process_estimate_costs` to avoid a second gas estimation.
+        // Metadata too large for the process call is stored in chunks first,
+        // and the message processed with metadata pointing at them
+        let chunker = self
+            .ctx
+            .metadata_chunker
+            .clone()
+            .filter(|chunker| chunker.needs_chunking(&state.metadata));
+        let (metadata, tx_gas_limit) = match &chunker {
+            Some(chunker) => match chunker.store(self.message.id(), &state.metadata).await {
+                Ok(stored) => {
+                    self.record_metadata_chunk_outcomes(stored.outcomes);
+                    // Reading the stored metadata costs a different amount of
+                    // gas than passing it in calldata, so estimate again
+                    let estimate = match self
+                        .ctx
+                        .destination_mailbox
+                        .process_estimate_costs(&self.message, &stored.pointer)
+                        .await
+                    {
+                        Ok(estimate) => estimate,
+                        Err(err) => {
+                            error!(error=?err, "Error when estimating gas with stored metadata chunks");
+                            let rollback = chunker.clear(self.message.id()).await;
+                            self.record_metadata_chunk_outcomes(rollback.into_iter().collect());
+                            return self
+                                .on_reprepare(Some(err), ReprepareReason::ErrorEstimatingGas);
+                        }
+                    };
+                    // The process transaction is the whole operation, so its
+                    // estimate is the operation's too
+                    if let Some(submission_data) = self.submission_data.as_mut() {
+                        submission_data.gas_limit = estimate.gas_limit;
+                    }
+                    (stored.pointer, estimate.gas_limit)
+                }
+                Err(failure) => {
+                    error!(error=?failure.error, "Error when storing metadata chunks");
+                    self.record_metadata_chunk_outcomes(failure.outcomes);
+                    self.failed_submissions += 1;
+                    return PendingOperationResult::Reprepare(
+                        ReprepareReason::ErrorStoringMetadataChunks,
+                    );
+                }
+            },
+            // We use the estimated gas limit from the prior call to
+            // `process_estimate_costs` to avoid a second gas estimation.
+            None => (state.metadata.clone(), state.gas_limit),
+        };
+
         let tx_outcome = self
             .ctx
             .destination_mailbox
-            .process(&self.message, &state.metadata, Some(state.gas_limit))
+            .process(&self.message, &metadata, Some(tx_gas_limit))
             .await;
         match tx_outcome {
             Ok(outcome) => {
-                self.set_operation_outcome(outcome, state.gas_limit);
+                if let Some(chunker) = chunker.as_ref().filter(|_| !outcome.executed) {
+                    let rollback = chunker.clear(self.message.id()).await;
+                    self.record_metadata_chunk_outcomes(rollback.into_iter().collect());
+                }
+                self.set_operation_outcome(outcome, tx_gas_limit);
                 PendingOperationResult::Confirm(ConfirmReason::SubmittedBySelf)
             }
             Err(e) => {
                 error!(error=?e, "Error when processing message");
+                if let Some(chunker) = &chunker {
+                    let rollback = chunker.clear(self.message.id()).await;
+                    self.record_metadata_chunk_outcomes(rollback.into_iter().collect());
+                }
+                self.failed_submissions += 1;
                 return PendingOperationResult::Reprepare(ReprepareReason::ErrorSubmitting);
             }
         }
@@ -390,17 +641,14 @@ impl PendingOperation for PendingMessage {
         self.submission_data.as_ref().map(|d| d.gas_limit)
     }
 
+    #[instrument(skip(self), fields(id=?self.id()), level = "debug")]
     async fn confirm(&mut self) -> PendingOperationResult {
+        self.join_trace("confirm");
         if !self.is_ready() {
             return PendingOperationResult::NotReady;
         }
 
-        let is_delivered = match self
-            .ctx
-            .destination_mailbox
-            .delivered(self.message.id())
-            .await
-        {
+        let is_delivered = match self.is_delivered().await {
             Ok(is_delivered) => is_delivered,
             Err(err) => {
                 return self.on_reconfirm(Some(err), "Error confirming message delivery");
@@ -423,6 +671,7 @@ impl PendingOperation for PendingMessage {
                 tx_outcome=?self.submission_outcome,
                 message_id=?self.message.id()
             );
+            self.failed_submissions += 1;
             self.on_reprepare::<String>(None, ReprepareReason::RevertedOrReorged)
                 .instrument(span)
                 .into_inner()
@@ -462,6 +711,12 @@ impl PendingOperation for PendingMessage {
         {
             error!(error=?e, "Error when recording tx outcome");
         }
+        if let Some(fee_tracker) = &self.ctx.fee_tracker {
+            match fee_paid(&operation_outcome) {
+                Ok(fee) => fee_tracker.record(fee),
+                Err(e) => warn!(error = %e, "Error when calculating fee paid by operation"),
+            }
+        }
         // set the outcome in `Self` as well, for later logging
         self.set_submission_outcome(operation_outcome);
         debug!(
@@ -493,6 +748,14 @@ impl PendingOperation for PendingMessage {
         Some(self.ctx.destination_mailbox.clone())
     }
 
+    fn try_get_message(&self) -> Option<&HyperlaneMessage> {
+        Some(&self.message)
+    }
+
+    fn set_delivered(&mut self, delivered: bool) {
+        self.delivered = Some(delivered);
+    }
+
     fn get_metric(&self) -> Option<Arc<IntGauge>> {
         self.metric.clone()
     }
@@ -517,6 +780,17 @@ impl PendingMessage {
             PendingOperationStatus::FirstPrepareAttempt,
             app_context,
         );
+        let span = info_span!(
+            parent: None,
+            "message",
+            id = ?pm.message.id(),
+            nonce = pm.message.nonce,
+            origin = pm.ctx.origin_db.domain().name(),
+            destination = pm.message.destination,
+        );
+        span.in_scope(|| debug!("Indexed message picked up for delivery"));
+        // The root span is closed, and so exported, when it's dropped here
+        pm.trace_context = span.context();
         match pm
             .ctx
             .origin_db
@@ -535,12 +809,30 @@ impl PendingMessage {
         pm
     }
 
+    /// Attaches the claim of a released ordered message on its ordering key
+    pub fn with_ordering_key_claim(mut self, claim: Option<OrderingKeyClaim>) -> Self {
+        self.ordering_key_claim = claim;
+        self
+    }
+
+    /// Whether the message was delivered, looked up unless a delivery check
+    /// of the whole queue just did
+    async fn is_delivered(&mut self) -> ChainResult<bool> {
+        if let Some(delivered) = self.delivered.take() {
+            return Ok(delivered);
+        }
+        self.ctx
+            .destination_mailbox
+            .delivered_message(&self.message)
+            .await
+    }
+
     fn on_reprepare<E: Debug>(
         &mut self,
         err: Option<E>,
         reason: ReprepareReason,
     ) -> PendingOperationResult {
-        self.inc_attempts();
+        self.inc_attempts(RetryClass::from(&reason));
         self.submitted = false;
         if let Some(e) = err {
             warn!(error = ?e, "Repreparing message: {}", reason.clone());
@@ -550,8 +842,175 @@ impl PendingMessage {
         PendingOperationResult::Reprepare(reason)
     }
 
+    /// Keeps a message to a skipped recipient program queued without
+    /// delivering it, so that it is delivered once the program is removed from
+    /// the skip list. Messages sharing its ordering key aren't held back by it
+    /// meanwhile.
+    fn on_skipped_recipient(&mut self) -> PendingOperationResult {
+        let status = PendingOperationStatus::Undeliverable(UndeliverableReason::SkippedRecipient);
+        if self.status != status {
+            info!(
+                recipient = ?self.message.recipient,
+                "Not delivering message to skipped recipient program"
+            );
+            if let Err(err) = self
+                .ctx
+                .origin_db
+                .store_status_by_message_id(&self.message.id(), &status)
+            {
+                warn!(?err, %status, "Persisting `status` failed for message");
+            }
+            self.status = status;
+        }
+        self.ordering_key_claim = None;
+        self.set_next_attempt_after(SKIPPED_RECIPIENT_RECHECK_DELAY);
+        PendingOperationResult::NotReady
+    }
+
+    /// Why the message shouldn't be delivered anymore, if it expired
+    async fn expiry(&mut self) -> Option<DeadLetterReason> {
+        let policy = self.ctx.delivery_ttl.as_ref()?;
+        policy
+            .expiry(&self.message, &self.ctx.origin_db, &mut self.dispatched_at)
+            .await
+    }
+
+    /// Why the message shouldn't be retried anymore, if it exhausted its
+    /// retries
+    fn exhausted_retries(&self) -> Option<DeadLetterReason> {
+        if self
+            .ctx
+            .dead_letter_after_retries
+            .is_some_and(|max_retries| self.num_retries > max_retries)
+        {
+            Some(DeadLetterReason::RetriesExhausted)
+        } else if !self
+            .ctx
+            .gas_escalation_policy
+            .allows_retry(self.num_retries)
+        {
+            Some(DeadLetterReason::GasEscalationRetriesExhausted)
+        } else {
+            None
+        }
+    }
+
+    /// Move the message to the dead-letter queue of its origin and drop it,
+    /// freeing its ordering key for the messages behind it
+    fn dead_letter(&mut self, reason: DeadLetterReason) -> PendingOperationResult {
+        if let Err(err) = store_dead_letter(
+            &self.ctx.origin_db,
+            &self.message,
+            reason,
+            self.status.to_string(),
+            self.num_retries,
+        ) {
+            // Dropping the message anyway, as it would be dropped without a
+            // dead-letter queue too. It is picked up again on restart.
+            error!(?err, "Failed to move message to the dead-letter queue");
+        }
+        self.status = PendingOperationStatus::Undeliverable(UndeliverableReason::DeadLettered);
+        self.ordering_key_claim = None;
+        PendingOperationResult::Drop
+    }
+
+    /// Why gas estimation failed, telling apart failures of the ISM and of
+    /// the recipient if the destination's error says so or if it can dry-run
+    /// deliveries
+    async fn gas_estimation_failure_reason(
+        &self,
+        err: &ChainCommunicationError,
+        metadata: &[u8],
+    ) -> ReprepareReason {
+        if let Some(reason) = reprepare_reason_of_error(err) {
+            return reason;
+        }
+        let Some(simulator) = &self.ctx.delivery_simulator else {
+            return ReprepareReason::ErrorEstimatingGas;
+        };
+        reprepare_reason_of_dry_run(simulator.dry_run_delivery(&self.message, metadata).await)
+    }
+
+    /// The gas of storing the metadata in chunks, or zero if it's submitted
+    /// with the process call
+    async fn estimate_metadata_chunks_gas(&self, metadata: &[u8]) -> ChainResult<U256> {
+        match &self.ctx.metadata_chunker {
+            Some(chunker) if chunker.needs_chunking(metadata) => {
+                chunker
+                    .estimate_store_gas(self.message.id(), metadata)
+                    .await
+            }
+            _ => Ok(U256::zero()),
+        }
+    }
+
+    /// Charge the transactions that stored or cleared the metadata chunks of
+    /// the message to its gas payment, like the process transaction.
+    fn record_metadata_chunk_outcomes(&self, outcomes: Vec<TxOutcome>) {
+        for outcome in outcomes {
+            if let Err(e) = self
+                .ctx
+                .origin_gas_payment_enforcer
+                .record_tx_outcome(&self.message, outcome)
+            {
+                error!(error=?e, "Error when recording metadata chunk tx outcome");
+            }
+        }
+    }
+
+    /// Reprepare a message whose gas payment doesn't meet the policy yet. If
+    /// metadata warm-up is enabled, the metadata built at `built_at` is kept
+    /// and the message retried within `WARM_METADATA_RETRY_DELAY`, so that it
+    /// is submitted soon after its gas payment is topped up.
+    /// `pub(crate)` for testing purposes
+    pub(crate) fn on_gas_payment_pending(
+        &mut self,
+        metadata: Vec<u8>,
+        built_at: Instant,
+        reason: ReprepareReason,
+    ) -> PendingOperationResult {
+        let result = self.on_reprepare::<String>(None, reason);
+        let Some(ttl) = self.ctx.metadata_warm_up_ttl else {
+            return result;
+        };
+        let retry_at = Instant::now() + WARM_METADATA_RETRY_DELAY;
+        // Metadata that expires before the retry is rebuilt anyway
+        if retry_at < built_at + ttl {
+            self.warm_metadata = Some((metadata, built_at));
+            self.next_attempt_after = Some(
+                self.next_attempt_after
+                    .map_or(retry_at, |next_attempt_after| {
+                        next_attempt_after.min(retry_at)
+                    }),
+            );
+        }
+        result
+    }
+
+    /// Take the warmed-up metadata, unless it is older than the warm-up TTL.
+    /// `pub(crate)` for testing purposes
+    pub(crate) fn take_warm_metadata(&mut self) -> Option<(Vec<u8>, Instant)> {
+        let ttl = self.ctx.metadata_warm_up_ttl?;
+        self.warm_metadata
+            .take()
+            .filter(|(_, built_at)| built_at.elapsed() < ttl)
+    }
+
+    /// Makes the current span a child of the root span of the delivery of
+    /// this message, if it's the span of the pipeline `stage`. Otherwise the
+    /// stage's span is disabled, and the span of the caller is left as is.
+    fn join_trace(&self, stage: &str) {
+        let span = Span::current();
+        if span
+            .metadata()
+            .is_some_and(|metadata| metadata.name() == stage)
+        {
+            span.set_parent(self.trace_context.clone());
+        }
+    }
+
     fn on_reconfirm<E: Debug>(&mut self, err: Option<E>, reason: &str) -> PendingOperationResult {
-        self.inc_attempts();
+        self.inc_attempts(RetryClass::Default);
         if let Some(e) = err {
             warn!(error = ?e, id = ?self.id(), "Reconfirming message: {}", reason);
         } else {
@@ -573,25 +1032,55 @@ impl PendingMessage {
     /// `return Ok(())`, then without a wiped HyperlaneDB, we will never
     /// re-attempt processing for this message again, even after the relayer
     /// restarts.
+    ///
+    /// If receipts are exported and the message was delivered by this
+    /// relayer, the receipt of the delivery is appended to the exported log
+    /// along with marking the message as processed.
     fn record_message_process_success(&mut self) -> Result<()> {
-        self.ctx
-            .origin_db
-            .store_processed_by_nonce(&self.message.nonce, &true)?;
+        match (&self.ctx.delivery_receipts, &self.submission_outcome) {
+            (Some(log), Some(outcome)) => log.append_processed(&self.delivery_receipt(outcome)?)?,
+            _ => self
+                .ctx
+                .origin_db
+                .store_processed_by_nonce(&self.message.nonce, &true)?,
+        }
         self.ctx.metrics.update_nonce(&self.message);
         self.ctx.metrics.messages_processed.inc();
         Ok(())
     }
 
+    /// The receipt of the delivery of the message by `outcome`
+    fn delivery_receipt(&self, outcome: &TxOutcome) -> Result<DeliveryReceipt> {
+        let effective_gas_cost = (FixedPointNumber::try_from(outcome.gas_used)?
+            * outcome.gas_price.clone())
+        .ceil_to_integer()
+        .try_into()?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        Ok(DeliveryReceipt {
+            message_id: self.message.id(),
+            origin: self.message.origin,
+            destination: self.message.destination,
+            nonce: self.message.nonce,
+            tx_hash: outcome.transaction_id,
+            block_number: outcome.block_number,
+            gas_used: outcome.gas_used,
+            effective_gas_cost,
+            timestamp,
+        })
+    }
+
     fn reset_attempts(&mut self) {
         self.next_attempt_after = None;
         self.last_attempted_at = Instant::now();
     }
 
-    fn inc_attempts(&mut self) {
+    fn inc_attempts(&mut self, retry_class: RetryClass) {
         self.set_retries(self.num_retries + 1);
         self.last_attempted_at = Instant::now();
-        self.next_attempt_after = PendingMessage::calculate_msg_backoff(self.num_retries)
+        self.next_attempt_after = retry_class
+            .backoff(self.num_retries)
             .map(|dur| self.last_attempted_at + dur);
+        self.ctx.metrics.record_retry(retry_class);
     }
 
     fn set_retries(&mut self, retries: u32) {
@@ -643,6 +1132,10 @@ pub struct MessageSubmissionMetrics {
     // Fields are public for testing purposes
     pub last_known_nonce: IntGauge,
     pub messages_processed: IntCounter,
+    /// Messages dropped by a delivery TTL policy
+    pub messages_expired: IntCounter,
+    /// Retries by the class of the failure that caused them
+    pub retries: HashMap<RetryClass, IntCounter>,
 }
 
 impl MessageSubmissionMetrics {
@@ -650,10 +1143,19 @@ impl MessageSubmissionMetrics {
         metrics: &CoreMetrics,
         origin: &HyperlaneDomain,
         destination: &HyperlaneDomain,
+        operation_retries: &IntCounterVec,
+        messages_expired: &IntCounterVec,
     ) -> Self {
         let origin = origin.name();
         let destination = destination.name();
         Self {
+            retries: RetryClass::iter()
+                .map(|class| {
+                    let counter =
+                        operation_retries.with_label_values(&[origin, destination, class.into()]);
+                    (class, counter)
+                })
+                .collect(),
             last_known_nonce: metrics.last_known_message_nonce().with_label_values(&[
                 "message_processed",
                 origin,
@@ -662,6 +1164,13 @@ impl MessageSubmissionMetrics {
             messages_processed: metrics
                 .messages_processed_count()
                 .with_label_values(&[origin, destination]),
+            messages_expired: messages_expired.with_label_values(&[origin, destination]),
+        }
+    }
+
+    fn record_retry(&self, retry_class: RetryClass) {
+        if let Some(retries) = self.retries.get(&retry_class) {
+            retries.inc();
         }
     }
 
@@ -673,3 +1182,108 @@ impl MessageSubmissionMetrics {
             .set(std::cmp::max(self.last_known_nonce.get(), msg.nonce as i64));
     }
 }
+
+/// The fee paid for an operation, in the smallest unit of the fee denom
+fn fee_paid(outcome: &TxOutcome) -> ChainResult<f64> {
+    let fee = (FixedPointNumber::try_from(outcome.gas_used)? * outcome.gas_price.clone())
+        .ceil_to_integer();
+    let fee: u128 = fee.try_into()?;
+    Ok(fee as f64)
+}
+
+/// Why gas estimation failed, if the destination's error tells which party
+/// failed
+fn reprepare_reason_of_error(err: &ChainCommunicationError) -> Option<ReprepareReason> {
+    match err {
+        ChainCommunicationError::IsmVerificationFailed(source) => {
+            warn!(%source, "ISM would fail to verify message");
+            Some(ReprepareReason::IsmVerificationFailed)
+        }
+        ChainCommunicationError::RecipientHandleFailed(source) => {
+            warn!(%source, "Recipient would fail to handle message");
+            Some(ReprepareReason::RecipientHandleReverted)
+        }
+        ChainCommunicationError::OutOfGas(source) => {
+            warn!(%source, "Message delivery would run out of gas");
+            Some(ReprepareReason::SimulationOutOfGas)
+        }
+        _ => None,
+    }
+}
+
+/// Why gas estimation failed, according to a dry run of the delivery
+fn reprepare_reason_of_dry_run(dry_run: ChainResult<DeliveryDryRun>) -> ReprepareReason {
+    match dry_run {
+        Ok(DeliveryDryRun::IsmVerificationFailed(reason)) => {
+            warn!(reason, "ISM would fail to verify message");
+            ReprepareReason::IsmVerificationFailed
+        }
+        Ok(DeliveryDryRun::HandleReverted(reason)) => {
+            warn!(reason, "Recipient would fail to handle message");
+            ReprepareReason::RecipientHandleReverted
+        }
+        Ok(dry_run) => {
+            debug!(?dry_run, "Dry run didn't attribute gas estimation failure");
+            ReprepareReason::ErrorEstimatingGas
+        }
+        Err(err) => {
+            debug!(?err, "Failed to dry run message delivery");
+            ReprepareReason::ErrorEstimatingGas
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chain_err(msg: &str) -> Box<ChainCommunicationError> {
+        Box::new(ChainCommunicationError::from_other_str(msg))
+    }
+
+    #[test]
+    fn test_reprepare_reason_of_error() {
+        assert_eq!(
+            reprepare_reason_of_error(&ChainCommunicationError::IsmVerificationFailed(chain_err(
+                "ism verify failed"
+            ))),
+            Some(ReprepareReason::IsmVerificationFailed)
+        );
+        assert_eq!(
+            reprepare_reason_of_error(&ChainCommunicationError::RecipientHandleFailed(chain_err(
+                "dispatch: submessages: invalid route"
+            ))),
+            Some(ReprepareReason::RecipientHandleReverted)
+        );
+        assert_eq!(
+            reprepare_reason_of_error(&ChainCommunicationError::OutOfGas(chain_err("out of gas"))),
+            Some(ReprepareReason::SimulationOutOfGas)
+        );
+        assert_eq!(
+            reprepare_reason_of_error(&ChainCommunicationError::from_other_str("unauthorized")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reprepare_reason_of_dry_run() {
+        assert_eq!(
+            reprepare_reason_of_dry_run(Ok(DeliveryDryRun::IsmVerificationFailed(
+                "rejected".to_owned()
+            ))),
+            ReprepareReason::IsmVerificationFailed
+        );
+        assert_eq!(
+            reprepare_reason_of_dry_run(Ok(DeliveryDryRun::HandleReverted("reverted".to_owned()))),
+            ReprepareReason::RecipientHandleReverted
+        );
+        assert_eq!(
+            reprepare_reason_of_dry_run(Ok(DeliveryDryRun::Failed("already delivered".to_owned()))),
+            ReprepareReason::ErrorEstimatingGas
+        );
+        assert_eq!(
+            reprepare_reason_of_dry_run(Err(ChainCommunicationError::from_other_str("rpc failed"))),
+            ReprepareReason::ErrorEstimatingGas
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     collections::HashMap,
     fmt::{Debug, Formatter},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
@@ -14,24 +14,42 @@ use hyperlane_base::{
     db::{HyperlaneDb, HyperlaneRocksDB},
     CoreMetrics,
 };
-use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, QueueOperation};
+use hyperlane_core::{
+    HyperlaneDomain, HyperlaneMessage, PendingOperationStatus, QueueOperation, UndeliverableReason,
+};
 use prometheus::IntGauge;
-use tokio::sync::mpsc::UnboundedSender;
-use tracing::{debug, instrument, trace};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::{debug, info, instrument, trace, warn};
+
+use super::{
+    blacklist::AddressBlacklist,
+    dead_letters::store_dead_letter,
+    matching_list_reloader::ReloadableMatchingList,
+    metadata::AppContextClassifier,
+    ordering::{InFlightOrderingKeys, OrderingGate, OrderingKeyClaim},
+    pending_message::*,
+    skipped_recipients::SkippedRecipients,
+};
+use crate::{
+    processor::ProcessorExt,
+    settings::{matching_list::MatchingList, OrderingKeyConf},
+};
 
-use super::{blacklist::AddressBlacklist, metadata::AppContextClassifier, pending_message::*};
-use crate::{processor::ProcessorExt, settings::matching_list::MatchingList};
+/// How often messages held back by the ordering gate are checked for expiry
+const HELD_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Finds unprocessed messages from an origin and submits then through a channel
 /// for to the appropriate destination.
 #[allow(clippy::too_many_arguments)]
 pub struct MessageProcessor {
     /// A matching list of messages that should be whitelisted.
-    message_whitelist: Arc<MatchingList>,
+    message_whitelist: ReloadableMatchingList,
     /// A matching list of messages that should be blacklisted.
-    message_blacklist: Arc<MatchingList>,
+    message_blacklist: ReloadableMatchingList,
     /// Addresses that messages may not interact with.
     address_blacklist: Arc<AddressBlacklist>,
+    /// Sealevel recipient programs that messages aren't delivered to.
+    skipped_recipients: Arc<SkippedRecipients>,
     metrics: MessageProcessorMetrics,
     /// channel for each destination chain to send operations (i.e. message
     /// submissions) to
@@ -39,6 +57,17 @@ pub struct MessageProcessor {
     /// Needed context to send a message for each destination chain
     destination_ctxs: HashMap<u32, Arc<MessageContext>>,
     metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Holds back messages with an ordering key until they can be delivered
+    /// in order
+    ordering_gate: OrderingGate,
+    /// Dispatch timestamps of held messages, looked up to check whether they
+    /// expired
+    held_dispatched_at: HashMap<u32, u64>,
+    /// When held messages were last checked for expiry
+    held_checked_at: Option<Instant>,
+    /// Messages re-injected from the dead-letter queue of the origin
+    reinjected: UnboundedReceiver<HyperlaneMessage>,
+    origin_db: HyperlaneRocksDB,
     nonce_iterator: ForwardBackwardIterator,
 }
 
@@ -46,6 +75,8 @@ pub struct MessageProcessor {
 struct ForwardBackwardIterator {
     low_nonce_iter: DirectionalNonceIterator,
     high_nonce_iter: DirectionalNonceIterator,
+    /// Number of reorgs of dispatched messages the iterators were rewound for
+    seen_reorgs: u32,
     // here for debugging purposes
     _domain: String,
 }
@@ -54,6 +85,12 @@ impl ForwardBackwardIterator {
     #[instrument(skip(db), ret)]
     fn new(db: Arc<dyn HyperlaneDb>) -> Self {
         let high_nonce = db.retrieve_highest_seen_message_nonce().ok().flatten();
+        // Reorgs before startup are accounted for by the highest seen nonce
+        let seen_reorgs = db
+            .retrieve_message_reorg_count()
+            .ok()
+            .flatten()
+            .unwrap_or_default();
         let domain = db.domain().name().to_owned();
         let high_nonce_iter = DirectionalNonceIterator::new(
             // If the high nonce is None, we start from the beginning
@@ -75,14 +112,58 @@ impl ForwardBackwardIterator {
         Self {
             low_nonce_iter,
             high_nonce_iter,
+            seen_reorgs,
             _domain: domain,
         }
     }
 
+    /// Rewind the iterators to the lowest nonce removed by the reorgs recorded
+    /// since the last rewind, so that the messages dispatched at the reorged
+    /// nonces on the canonical chain are processed once they are indexed.
+    fn rewind_to_reorged_nonces(&mut self) -> Result<()> {
+        let db = &self.high_nonce_iter.db;
+        let reorgs = db.retrieve_message_reorg_count()?.unwrap_or_default();
+        let mut lowest_reorged_nonce: Option<u32> = None;
+        for reorg_index in self.seen_reorgs..reorgs {
+            if let Some(nonce) = db.retrieve_lowest_reorged_nonce_by_reorg_index(&reorg_index)? {
+                lowest_reorged_nonce =
+                    Some(lowest_reorged_nonce.map_or(nonce, |lowest| lowest.min(nonce)));
+            }
+        }
+        self.seen_reorgs = reorgs;
+        let Some(nonce) = lowest_reorged_nonce else {
+            return Ok(());
+        };
+        // The high nonce iterator goes over the reorged nonces again, so the
+        // low nonce iterator only needs to cover the nonces below them
+        self.high_nonce_iter.nonce = Some(
+            self.high_nonce_iter
+                .nonce
+                .map_or(nonce, |high_nonce| high_nonce.min(nonce)),
+        );
+        if self
+            .low_nonce_iter
+            .nonce
+            .is_some_and(|low_nonce| low_nonce >= nonce)
+        {
+            self.low_nonce_iter.nonce = nonce.checked_sub(1);
+        }
+        warn!(nonce, iterator = ?self, "Rewound message iterator to reorged nonce");
+        Ok(())
+    }
+
+    /// Whether the message with `nonce` was already scanned, so that it isn't
+    /// returned again
+    fn has_passed(&self, nonce: u32) -> bool {
+        self.high_nonce_iter.nonce.is_some_and(|high| nonce < high)
+            && self.low_nonce_iter.nonce.map_or(true, |low| nonce > low)
+    }
+
     async fn try_get_next_message(
         &mut self,
         metrics: &MessageProcessorMetrics,
     ) -> Result<Option<HyperlaneMessage>> {
+        self.rewind_to_reorged_nonces()?;
         loop {
             let high_nonce_message_status = self.high_nonce_iter.try_get_next_nonce(metrics)?;
             let low_nonce_message_status = self.low_nonce_iter.try_get_next_nonce(metrics)?;
@@ -250,6 +331,30 @@ impl ProcessorExt for MessageProcessor {
         // satisfied or the message is disqualified, push the message onto
         // self.tx_msg and then continue the scan at the next highest
         // nonce.
+        // Held messages that expired are dropped, so that they don't keep the
+        // messages behind them waiting
+        if self.held_checked_at.map_or(true, |checked_at| {
+            checked_at.elapsed() >= HELD_EXPIRY_CHECK_INTERVAL
+        }) {
+            self.expire_held_messages().await?;
+            self.held_checked_at = Some(Instant::now());
+        }
+
+        // Messages with an ordering key are only released once every lower
+        // nonce was seen, i.e. once the backward iterator is done.
+        if self.nonce_iterator.low_nonce_iter.nonce.is_none() {
+            for (msg, claim) in self.ordering_gate.release()? {
+                debug!(%msg, "Releasing ordered message");
+                self.send_to_submitter(msg, Some(claim)).await?;
+            }
+        }
+
+        // Messages re-injected from the dead-letter queue go through the same
+        // filters and ordering gate as indexed ones
+        while let Ok(msg) = self.reinjected.try_recv() {
+            self.reinject(msg).await?;
+        }
+
         // Scan until we find next nonce without delivery confirmation.
         if let Some(msg) = self.try_get_unprocessed_message().await? {
             debug!(
@@ -257,50 +362,9 @@ impl ProcessorExt for MessageProcessor {
                 cursor = ?self.nonce_iterator,
                 "Processor working on message"
             );
-            let destination = msg.destination;
-
-            // Skip if not whitelisted.
-            if !self.message_whitelist.msg_matches(&msg, true) {
-                debug!(?msg, whitelist=?self.message_whitelist, "Message not whitelisted, skipping");
-                return Ok(());
+            if self.is_routable(&msg, false)? {
+                self.route(msg).await?;
             }
-
-            // Skip if the message is blacklisted
-            if self.message_blacklist.msg_matches(&msg, false) {
-                debug!(?msg, blacklist=?self.message_blacklist, "Message blacklisted, skipping");
-                return Ok(());
-            }
-
-            // Skip if the message involves a blacklisted address
-            if let Some(blacklisted_address) = self.address_blacklist.find_blacklisted_address(&msg)
-            {
-                debug!(
-                    ?msg,
-                    blacklisted_address = hex::encode(blacklisted_address),
-                    "Message involves blacklisted address, skipping"
-                );
-                return Ok(());
-            }
-
-            // Skip if the message is intended for a destination we do not service
-            if !self.send_channels.contains_key(&destination) {
-                debug!(?msg, "Message destined for unknown domain, skipping");
-                return Ok(());
-            }
-
-            debug!(%msg, "Sending message to submitter");
-
-            let app_context_classifier =
-                AppContextClassifier::new(self.metric_app_contexts.clone());
-
-            let app_context = app_context_classifier.get_app_context(&msg).await?;
-            // Finally, build the submit arg and dispatch it to the submitter.
-            let pending_msg = PendingMessage::from_persisted_retries(
-                msg,
-                self.destination_ctxs[&destination].clone(),
-                app_context,
-            );
-            self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
         } else {
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
@@ -312,26 +376,191 @@ impl MessageProcessor {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: HyperlaneRocksDB,
-        message_whitelist: Arc<MatchingList>,
-        message_blacklist: Arc<MatchingList>,
+        message_whitelist: ReloadableMatchingList,
+        message_blacklist: ReloadableMatchingList,
         address_blacklist: Arc<AddressBlacklist>,
+        skipped_recipients: Arc<SkippedRecipients>,
         metrics: MessageProcessorMetrics,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
         destination_ctxs: HashMap<u32, Arc<MessageContext>>,
         metric_app_contexts: Vec<(MatchingList, String)>,
+        ordering_keys: Vec<OrderingKeyConf>,
+        in_flight_ordering_keys: InFlightOrderingKeys,
+        reinjected: UnboundedReceiver<HyperlaneMessage>,
     ) -> Self {
+        let ordering_gate = OrderingGate::new(db.clone(), ordering_keys, in_flight_ordering_keys);
         Self {
             message_whitelist,
             message_blacklist,
             address_blacklist,
+            skipped_recipients,
             metrics,
             send_channels,
             destination_ctxs,
             metric_app_contexts,
+            ordering_gate,
+            held_dispatched_at: HashMap::new(),
+            held_checked_at: None,
+            reinjected,
+            origin_db: db.clone(),
             nonce_iterator: ForwardBackwardIterator::new(Arc::new(db) as Arc<dyn HyperlaneDb>),
         }
     }
 
+    /// Whether a message passes the filters and is destined for a domain
+    /// that is relayed to. Messages in the dead-letter queue are skipped
+    /// unless they're being re-injected.
+    fn is_routable(&self, msg: &HyperlaneMessage, reinjected: bool) -> Result<bool> {
+        // Skip if not whitelisted.
+        let whitelist = self.message_whitelist.current();
+        if !whitelist.msg_matches(msg, true) {
+            debug!(?msg, ?whitelist, "Message not whitelisted, skipping");
+            return Ok(false);
+        }
+
+        // Skip if the message is blacklisted
+        let blacklist = self.message_blacklist.current();
+        if blacklist.msg_matches(msg, false) {
+            debug!(?msg, ?blacklist, "Message blacklisted, skipping");
+            return Ok(false);
+        }
+
+        // Skip if the message involves a blacklisted address
+        if let Some(blacklisted_address) = self.address_blacklist.find_blacklisted_address(msg) {
+            debug!(
+                ?msg,
+                blacklisted_address = hex::encode(blacklisted_address),
+                "Message involves blacklisted address, skipping"
+            );
+            return Ok(false);
+        }
+
+        // Skip if the message was moved to the dead-letter queue, until it's re-injected
+        if !reinjected
+            && self.origin_db.retrieve_status_by_message_id(&msg.id())?
+                == Some(PendingOperationStatus::Undeliverable(
+                    UndeliverableReason::DeadLettered,
+                ))
+        {
+            debug!(%msg, "Message is in the dead-letter queue, skipping");
+            return Ok(false);
+        }
+
+        // Skip if the message is intended for a destination we do not service
+        if !self.send_channels.contains_key(&msg.destination) {
+            debug!(?msg, "Message destined for unknown domain, skipping");
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Sends a message to the submitter of its destination, or holds it back
+    /// if it must be delivered in order
+    async fn route(&mut self, msg: HyperlaneMessage) -> Result<()> {
+        // Messages to skipped recipient programs are queued without being
+        // held back, so that they don't claim the ordering key of the
+        // messages behind them. They are delivered once the program is
+        // removed from the skip list.
+        if self.skipped_recipients.is_skipped(&msg) {
+            info!(%msg, recipient = ?msg.recipient, "Message to skipped recipient program, queueing it without ordering");
+            return self.send_to_submitter(msg, None).await;
+        }
+
+        // Hold back the message if it must be delivered in order
+        if let Some(key) = self.ordering_gate.ordering_key(&msg) {
+            debug!(%msg, ?key, "Holding ordered message");
+            return self.ordering_gate.hold(key, msg);
+        }
+
+        self.send_to_submitter(msg, None).await
+    }
+
+    /// Moves the held messages that expired to the dead-letter queue
+    async fn expire_held_messages(&mut self) -> Result<()> {
+        for msg in self.ordering_gate.held() {
+            let Some(ctx) = self.destination_ctxs.get(&msg.destination) else {
+                continue;
+            };
+            let Some(policy) = ctx.delivery_ttl.as_ref() else {
+                continue;
+            };
+            let mut dispatched_at = self.held_dispatched_at.get(&msg.nonce).copied();
+            let expiry = policy
+                .expiry(&msg, &self.origin_db, &mut dispatched_at)
+                .await;
+            if let Some(dispatched_at) = dispatched_at {
+                self.held_dispatched_at.insert(msg.nonce, dispatched_at);
+            }
+            let Some(reason) = expiry else {
+                continue;
+            };
+            warn!(
+                %msg,
+                ?reason,
+                "Moving held message to the dead-letter queue because it expired"
+            );
+            ctx.metrics.messages_expired.inc();
+            let num_retries = self
+                .origin_db
+                .retrieve_pending_message_retry_count_by_message_id(&msg.id())?
+                .unwrap_or_default();
+            store_dead_letter(
+                &self.origin_db,
+                &msg,
+                reason,
+                PendingOperationStatus::FirstPrepareAttempt.to_string(),
+                num_retries,
+            )?;
+            self.ordering_gate.remove(&msg)?;
+            self.held_dispatched_at.remove(&msg.nonce);
+        }
+        Ok(())
+    }
+
+    /// Routes a message re-injected from the dead-letter queue with its
+    /// retries reset, and removes it from the queue once it was handed on.
+    /// Messages the nonce iterator didn't reach yet are left to it. Dead
+    /// letters that are filtered out stay in the queue.
+    async fn reinject(&mut self, msg: HyperlaneMessage) -> Result<()> {
+        let id = msg.id();
+        if !self.is_routable(&msg, true)? {
+            warn!(message_id = ?id, "Not re-injecting dead letter that is filtered out or destined for a domain that isn't relayed to");
+            return Ok(());
+        }
+        self.origin_db
+            .store_pending_message_retry_count_by_message_id(&id, &0)?;
+        self.origin_db
+            .store_status_by_message_id(&id, &PendingOperationStatus::FirstPrepareAttempt)?;
+        if self.nonce_iterator.has_passed(msg.nonce) {
+            self.route(msg).await?;
+        }
+        self.origin_db.delete_dead_letter_by_message_id(&id)?;
+        info!(message_id = ?id, "Re-injected dead letter on request");
+        Ok(())
+    }
+
+    async fn send_to_submitter(
+        &self,
+        msg: HyperlaneMessage,
+        ordering_key_claim: Option<OrderingKeyClaim>,
+    ) -> Result<()> {
+        debug!(%msg, "Sending message to submitter");
+        let destination = msg.destination;
+
+        let app_context_classifier = AppContextClassifier::new(self.metric_app_contexts.clone());
+
+        let app_context = app_context_classifier.get_app_context(&msg).await?;
+        // Finally, build the submit arg and dispatch it to the submitter.
+        let pending_msg = PendingMessage::from_persisted_retries(
+            msg,
+            self.destination_ctxs[&destination].clone(),
+            app_context,
+        )
+        .with_ordering_key_claim(ordering_key_claim);
+        self.send_channels[&destination].send(Box::new(pending_msg) as QueueOperation)?;
+        Ok(())
+    }
+
     async fn try_get_unprocessed_message(&mut self) -> Result<Option<HyperlaneMessage>> {
         trace!(nonce_iterator=?self.nonce_iterator, "Trying to get the next processor message");
         let next_message = self
@@ -383,33 +612,39 @@ impl MessageProcessorMetrics {
 
 #[cfg(test)]
 mod test {
-    use std::time::Instant;
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
     use crate::{
         merkle_tree::builder::MerkleTreeBuilder,
         msg::{
+            delivery_ttl::{test::MockProvider, DeliveryTtlPolicy},
             gas_payment::GasPaymentEnforcer,
-            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+            metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier, IsmConfigMonitor},
         },
         processor::Processor,
+        settings::{BodyDeadline, DeliveryTtlConf, OrderingKeySource},
     };
 
     use super::*;
     use hyperlane_base::{
         db::{
-            test_utils, DbResult, HyperlaneRocksDB, InterchainGasExpenditureData,
+            test_utils, DbResult, DeadLetterReason, HyperlaneRocksDB, InterchainGasExpenditureData,
             InterchainGasPaymentData,
         },
         settings::{ChainConf, ChainConnectionConf, Settings},
     };
     use hyperlane_core::{
-        test_utils::dummy_domain, GasPaymentKey, InterchainGasPayment, InterchainGasPaymentMeta,
-        MerkleTreeInsertion, PendingOperationStatus, H256,
+        test_utils::dummy_domain, ConfirmReason, GasPaymentKey, InterchainGasPayment,
+        InterchainGasPaymentMeta, Mailbox, MerkleTreeInsertion, PendingOperation,
+        PendingOperationResult, PendingOperationStatus, ReprepareReason, UndeliverableReason, H256,
     };
     use hyperlane_test::mocks::{MockMailboxContract, MockValidatorAnnounceContract};
+
+    use crate::msg::op_submitter::check_deliveries;
     use prometheus::{IntCounter, Registry};
     use tokio::{
         sync::{
+            broadcast,
             mpsc::{self, UnboundedReceiver},
             RwLock,
         },
@@ -435,6 +670,8 @@ mod test {
         MessageSubmissionMetrics {
             last_known_nonce: IntGauge::new("last_known_nonce_gauge", "help string").unwrap(),
             messages_processed: IntCounter::new("message_processed_gauge", "help string").unwrap(),
+            messages_expired: IntCounter::new("messages_expired", "help string").unwrap(),
+            retries: Default::default(),
         }
     }
 
@@ -450,6 +687,9 @@ mod test {
                 },
                 transaction_overrides: Default::default(),
                 operation_batch: Default::default(),
+                log_pruning_fallback: false,
+                rate_limit: None,
+                custom_errors: Default::default(),
             }),
             metrics_conf: Default::default(),
             index: Default::default(),
@@ -475,43 +715,122 @@ mod test {
         BaseMetadataBuilder::new(
             origin_domain.clone(),
             destination_chain_conf.clone(),
-            Arc::new(RwLock::new(MerkleTreeBuilder::new())),
+            Arc::new(RwLock::new(MerkleTreeBuilder::restore(db).unwrap())),
             Arc::new(MockValidatorAnnounceContract::default()),
             false,
             Arc::new(core_metrics),
             db.clone(),
             IsmAwareAppContextClassifier::new(Arc::new(MockMailboxContract::default()), vec![]),
+            None,
+            Arc::new(IsmConfigMonitor::new(
+                destination_domain.clone(),
+                broadcast::Sender::new(1),
+            )),
+            None,
         )
     }
 
-    fn dummy_message_processor(
+    fn dummy_message_context(
         origin_domain: &HyperlaneDomain,
         destination_domain: &HyperlaneDomain,
         db: &HyperlaneRocksDB,
-    ) -> (MessageProcessor, UnboundedReceiver<QueueOperation>) {
+        message_blacklist: ReloadableMatchingList,
+    ) -> Arc<MessageContext> {
+        dummy_message_context_with_mailbox(
+            origin_domain,
+            destination_domain,
+            db,
+            message_blacklist,
+            Arc::new(MockMailboxContract::default()),
+        )
+    }
+
+    fn dummy_message_context_with_mailbox(
+        origin_domain: &HyperlaneDomain,
+        destination_domain: &HyperlaneDomain,
+        db: &HyperlaneRocksDB,
+        message_blacklist: ReloadableMatchingList,
+        destination_mailbox: Arc<dyn Mailbox>,
+    ) -> Arc<MessageContext> {
+        Arc::new(dummy_message_context_inner(
+            origin_domain,
+            destination_domain,
+            db,
+            message_blacklist,
+            destination_mailbox,
+        ))
+    }
+
+    fn dummy_message_context_inner(
+        origin_domain: &HyperlaneDomain,
+        destination_domain: &HyperlaneDomain,
+        db: &HyperlaneRocksDB,
+        message_blacklist: ReloadableMatchingList,
+        destination_mailbox: Arc<dyn Mailbox>,
+    ) -> MessageContext {
         let base_metadata_builder = dummy_metadata_builder(origin_domain, destination_domain, db);
-        let message_context = Arc::new(MessageContext {
-            destination_mailbox: Arc::new(MockMailboxContract::default()),
+        MessageContext {
+            destination_mailbox,
             origin_db: db.clone(),
             metadata_builder: Arc::new(base_metadata_builder),
             origin_gas_payment_enforcer: Arc::new(GasPaymentEnforcer::new([], db.clone())),
             transaction_gas_limit: Default::default(),
+            gas_escalation_policy: Default::default(),
+            fee_tracker: None,
+            delivery_receipts: None,
+            metadata_warm_up_ttl: None,
+            metadata_chunker: None,
+            dead_letter_after_retries: None,
+            delivery_ttl: None,
+            delivery_simulator: None,
+            message_whitelist: Default::default(),
+            message_blacklist,
+            skipped_recipients: Default::default(),
             metrics: dummy_submission_metrics(),
-        });
+        }
+    }
+
+    fn dummy_message_processor(
+        origin_domain: &HyperlaneDomain,
+        destination_domain: &HyperlaneDomain,
+        db: &HyperlaneRocksDB,
+    ) -> (MessageProcessor, UnboundedReceiver<QueueOperation>) {
+        let (message_processor, receive_channel, _) =
+            dummy_reinjecting_message_processor(origin_domain, destination_domain, db);
+        (message_processor, receive_channel)
+    }
+
+    fn dummy_reinjecting_message_processor(
+        origin_domain: &HyperlaneDomain,
+        destination_domain: &HyperlaneDomain,
+        db: &HyperlaneRocksDB,
+    ) -> (
+        MessageProcessor,
+        UnboundedReceiver<QueueOperation>,
+        UnboundedSender<HyperlaneMessage>,
+    ) {
+        let message_context =
+            dummy_message_context(origin_domain, destination_domain, db, Default::default());
 
         let (send_channel, receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
+        let (reinject_channel, reinjected) = mpsc::unbounded_channel();
         (
             MessageProcessor::new(
                 db.clone(),
                 Default::default(),
                 Default::default(),
                 Default::default(),
+                Default::default(),
                 dummy_processor_metrics(origin_domain.id()),
                 HashMap::from([(destination_domain.id(), send_channel)]),
                 HashMap::from([(destination_domain.id(), message_context)]),
                 vec![],
+                vec![],
+                Default::default(),
+                reinjected,
             ),
             receive_channel,
+            reinject_channel,
         )
     }
 
@@ -602,6 +921,13 @@ mod test {
             /// Get the origin domain of the database
             fn domain(&self) -> &HyperlaneDomain;
 
+            fn retrieve_message_reorg_count(&self) -> DbResult<Option<u32>>;
+
+            fn retrieve_lowest_reorged_nonce_by_reorg_index(
+                &self,
+                reorg_index: &u32,
+            ) -> DbResult<Option<u32>>;
+
             fn store_message_id_by_nonce(&self, nonce: &u32, id: &H256) -> DbResult<()>;
 
             fn retrieve_message_id_by_nonce(&self, nonce: &u32) -> DbResult<Option<H256>>;
@@ -794,6 +1120,318 @@ mod test {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_queued_message_dropped_once_blacklisted() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let blacklist = ReloadableMatchingList::new(MatchingList::with_destination_domain(
+                destination_domain.id(),
+            ));
+            let ctx = dummy_message_context(&origin_domain, &destination_domain, &db, blacklist);
+
+            let mut pending_message = PendingMessage::from_persisted_retries(
+                dummy_hyperlane_message(&destination_domain, 0),
+                ctx,
+                None,
+            );
+            assert!(matches!(
+                pending_message.prepare().await,
+                PendingOperationResult::Drop
+            ));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_skipped_recipients_are_rechecked_while_queued() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let message = dummy_hyperlane_message(&destination_domain, 0);
+            let skipped_recipients = Arc::new(SkippedRecipients::new(
+                [destination_domain.id()],
+                [message.recipient],
+            ));
+            let mut mailbox = MockMailboxContract::new();
+            mailbox.expect__delivered().times(1).returning(|_| Ok(true));
+            let ctx = Arc::new(MessageContext {
+                skipped_recipients: skipped_recipients.clone(),
+                ..dummy_message_context_inner(
+                    &origin_domain,
+                    &destination_domain,
+                    &db,
+                    Default::default(),
+                    Arc::new(mailbox),
+                )
+            });
+            let mut pending_message =
+                PendingMessage::from_persisted_retries(message.clone(), ctx, None);
+
+            // Messages to skipped recipients stay queued without being delivered
+            assert!(matches!(
+                pending_message.prepare().await,
+                PendingOperationResult::NotReady
+            ));
+            let skipped =
+                PendingOperationStatus::Undeliverable(UndeliverableReason::SkippedRecipient);
+            assert_eq!(pending_message.status(), skipped);
+            assert_eq!(
+                db.retrieve_status_by_message_id(&message.id()).unwrap(),
+                Some(skipped)
+            );
+            assert!(pending_message.next_attempt_after().unwrap() > Instant::now());
+
+            // and are delivered once the recipient is removed from the list
+            skipped_recipients.remove(&[message.recipient]);
+            pending_message.set_next_attempt_after(Duration::ZERO);
+            assert!(matches!(
+                pending_message.prepare().await,
+                PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted)
+            ));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_are_reinjected_through_the_processor() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let messages = (0..2)
+                .map(|nonce| dummy_hyperlane_message(&destination_domain, nonce))
+                .collect::<Vec<_>>();
+            for message in &messages {
+                add_db_entry(&db, message, 5);
+                store_dead_letter(
+                    &db,
+                    message,
+                    DeadLetterReason::RetriesExhausted,
+                    "FirstPrepareAttempt".to_owned(),
+                    5,
+                )
+                .unwrap();
+            }
+            let (mut message_processor, mut receive_channel, reinject_channel) =
+                dummy_reinjecting_message_processor(&origin_domain, &destination_domain, &db);
+
+            // Dead letters aren't picked up by the scan
+            message_processor.tick().await.unwrap();
+            assert!(receive_channel.try_recv().is_err());
+
+            // Message 1 was scanned already so it's routed right away, while
+            // message 0 is left to the scan, which doesn't skip it anymore
+            for message in messages.iter().rev() {
+                reinject_channel.send(message.clone()).unwrap();
+            }
+            message_processor.tick().await.unwrap();
+            let reinjected = [
+                receive_channel.try_recv().unwrap(),
+                receive_channel.try_recv().unwrap(),
+            ];
+            assert!(receive_channel.try_recv().is_err());
+            assert_eq!(
+                reinjected.map(|op| op.id()),
+                [messages[1].id(), messages[0].id()]
+            );
+            for message in &messages {
+                assert_eq!(
+                    db.retrieve_dead_letter_by_message_id(&message.id())
+                        .unwrap(),
+                    None
+                );
+                assert_eq!(
+                    db.retrieve_pending_message_retry_count_by_message_id(&message.id())
+                        .unwrap(),
+                    Some(0)
+                );
+                assert_eq!(
+                    db.retrieve_status_by_message_id(&message.id()).unwrap(),
+                    Some(PendingOperationStatus::FirstPrepareAttempt)
+                );
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_held_messages_expire() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let deadline = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                - 1;
+            let message = HyperlaneMessage {
+                body: deadline.to_be_bytes().to_vec(),
+                ..dummy_hyperlane_message(&destination_domain, 0)
+            };
+            add_db_entry(&db, &message, 0);
+            let delivery_ttl = DeliveryTtlPolicy::new(
+                vec![DeliveryTtlConf {
+                    matching_list: MatchingList::default(),
+                    max_age: None,
+                    deadline: Some(BodyDeadline {
+                        offset: 0,
+                        length: 8,
+                    }),
+                }],
+                Arc::new(MockProvider::new()),
+            );
+            let ctx = Arc::new(MessageContext {
+                delivery_ttl: Some(Arc::new(delivery_ttl)),
+                ..dummy_message_context_inner(
+                    &origin_domain,
+                    &destination_domain,
+                    &db,
+                    Default::default(),
+                    Arc::new(MockMailboxContract::new()),
+                )
+            });
+            let (send_channel, mut receive_channel) = mpsc::unbounded_channel::<QueueOperation>();
+            let mut message_processor = MessageProcessor::new(
+                db.clone(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                dummy_processor_metrics(origin_domain.id()),
+                HashMap::from([(destination_domain.id(), send_channel)]),
+                HashMap::from([(destination_domain.id(), ctx)]),
+                vec![],
+                vec![OrderingKeyConf {
+                    matching_list: serde_json::from_str(r#"[{"destinationdomain": 1}]"#).unwrap(),
+                    source: OrderingKeySource::Sender,
+                }],
+                Default::default(),
+                mpsc::unbounded_channel().1,
+            );
+
+            // The message is held back until the previous messages were seen
+            message_processor.tick().await.unwrap();
+            assert_eq!(
+                message_processor.ordering_gate.held(),
+                vec![message.clone()]
+            );
+
+            // Held messages are dropped once they expire, instead of keeping
+            // the messages behind them waiting
+            message_processor.expire_held_messages().await.unwrap();
+            assert!(message_processor.ordering_gate.held().is_empty());
+            assert!(db.retrieve_ordered_messages().unwrap().is_empty());
+            assert_eq!(
+                db.retrieve_dead_letter_by_message_id(&message.id())
+                    .unwrap()
+                    .map(|dead_letter| dead_letter.reason),
+                Some(DeadLetterReason::DeadlinePassed)
+            );
+            assert!(receive_channel.try_recv().is_err());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_delivery_checked_once_per_batch() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let mut mailbox = MockMailboxContract::new();
+            mailbox.expect__delivered().times(2).returning(|_| Ok(true));
+            let ctx = dummy_message_context_with_mailbox(
+                &origin_domain,
+                &destination_domain,
+                &db,
+                Default::default(),
+                Arc::new(mailbox),
+            );
+
+            let mut batch = (0..2)
+                .map(|nonce| {
+                    Box::new(PendingMessage::from_persisted_retries(
+                        dummy_hyperlane_message(&destination_domain, nonce),
+                        ctx.clone(),
+                        None,
+                    )) as QueueOperation
+                })
+                .collect::<Vec<_>>();
+            check_deliveries(&mut batch).await;
+
+            // Preparing reuses the batched lookup instead of looking the
+            // messages up again
+            for op in batch.iter_mut() {
+                assert!(matches!(
+                    op.prepare().await,
+                    PendingOperationResult::Confirm(ConfirmReason::AlreadySubmitted)
+                ));
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_warm_metadata_shortens_gas_payment_backoff() {
+        test_utils::run_test_db(|db| async move {
+            let origin_domain = dummy_domain(0, "dummy_origin_domain");
+            let destination_domain = dummy_domain(1, "dummy_destination_domain");
+            let db = HyperlaneRocksDB::new(&origin_domain, db);
+            let ttl = Duration::from_secs(60);
+            let ctx = |metadata_warm_up_ttl| {
+                Arc::new(MessageContext {
+                    metadata_warm_up_ttl,
+                    ..dummy_message_context_inner(
+                        &origin_domain,
+                        &destination_domain,
+                        &db,
+                        Default::default(),
+                        Arc::new(MockMailboxContract::default()),
+                    )
+                })
+            };
+            // Retried often enough to back off for 30min
+            let message = dummy_hyperlane_message(&destination_domain, 0);
+            add_db_entry(&db, &message, 29);
+            let pending_message =
+                |ctx| PendingMessage::from_persisted_retries(message.clone(), ctx, None);
+            let soon = || Instant::now() + WARM_METADATA_RETRY_DELAY;
+
+            // Without warm-up, the message backs off as usual
+            let mut op = pending_message(ctx(None));
+            op.on_gas_payment_pending(vec![1], Instant::now(), ReprepareReason::GasPaymentNotFound);
+            assert!(op.next_attempt_after().unwrap() > soon());
+            assert!(op.take_warm_metadata().is_none());
+
+            // With warm-up, it is retried soon and reuses its metadata
+            let mut op = pending_message(ctx(Some(ttl)));
+            let built_at = Instant::now();
+            op.on_gas_payment_pending(
+                vec![1],
+                built_at,
+                ReprepareReason::GasPaymentRequirementNotMet,
+            );
+            assert!(op.next_attempt_after().unwrap() <= soon());
+            assert_eq!(op.take_warm_metadata(), Some((vec![1], built_at)));
+            assert!(op.take_warm_metadata().is_none());
+
+            // Unless the metadata expires before the retry
+            let mut op = pending_message(ctx(Some(ttl)));
+            op.on_gas_payment_pending(
+                vec![1],
+                Instant::now() - ttl,
+                ReprepareReason::GasPaymentNotFound,
+            );
+            assert!(op.next_attempt_after().unwrap() > soon());
+            assert!(op.take_warm_metadata().is_none());
+        })
+        .await;
+    }
+
     #[tokio::test]
     async fn test_forward_backward_iterator() {
         let mut mock_db = MockDb::new();
@@ -809,6 +1447,9 @@ mod test {
         mock_db
             .expect_retrieve_highest_seen_message_nonce()
             .returning(|| Ok(Some(MOCK_HIGHEST_SEEN_NONCE)));
+        mock_db
+            .expect_retrieve_message_reorg_count()
+            .returning(|| Ok(None));
         mock_db
             .expect_retrieve_message_by_nonce()
             .returning(move |nonce| {
@@ -866,4 +1507,52 @@ mod test {
             Some(MAX_ONCHAIN_NONCE + 1)
         );
     }
+
+    #[tokio::test]
+    async fn test_forward_backward_iterator_rewinds_to_reorged_nonce() {
+        const MAX_ONCHAIN_NONCE: u32 = 4;
+        let mut mock_db = MockDb::new();
+        let reorg_count = Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+        mock_db
+            .expect_domain()
+            .return_const(dummy_domain(0, "dummy_domain"));
+        mock_db
+            .expect_retrieve_highest_seen_message_nonce()
+            .returning(|| Ok(Some(MAX_ONCHAIN_NONCE)));
+        let count = reorg_count.clone();
+        mock_db
+            .expect_retrieve_message_reorg_count()
+            .returning(move || Ok(Some(count.load(std::sync::atomic::Ordering::SeqCst))));
+        mock_db
+            .expect_retrieve_lowest_reorged_nonce_by_reorg_index()
+            .returning(|_| Ok(Some(2)));
+        mock_db
+            .expect_retrieve_message_by_nonce()
+            .returning(|nonce| {
+                Ok((nonce <= MAX_ONCHAIN_NONCE)
+                    .then(|| dummy_hyperlane_message(&dummy_domain(1, "dummy_domain"), nonce)))
+            });
+        mock_db
+            .expect_retrieve_processed_by_nonce()
+            .returning(|_| Ok(Some(false)));
+        let dummy_metrics = dummy_processor_metrics(0);
+        let mut iterator = ForwardBackwardIterator::new(Arc::new(mock_db));
+
+        let mut messages = vec![];
+        while let Some(msg) = iterator.try_get_next_message(&dummy_metrics).await.unwrap() {
+            messages.push(msg.nonce);
+        }
+        assert_eq!(messages, vec![4, 3, 2, 1, 0]);
+
+        // The messages from nonce 2 onwards are reorged and indexed again
+        reorg_count.store(1, std::sync::atomic::Ordering::SeqCst);
+        let mut messages = vec![];
+        while let Some(msg) = iterator.try_get_next_message(&dummy_metrics).await.unwrap() {
+            messages.push(msg.nonce);
+        }
+        assert_eq!(messages, vec![2, 3, 4]);
+        assert_eq!(iterator.low_nonce_iter.nonce, None);
+        assert_eq!(iterator.high_nonce_iter.nonce, Some(MAX_ONCHAIN_NONCE + 1));
+    }
 }
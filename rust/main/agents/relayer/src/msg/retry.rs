@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use hyperlane_core::ReprepareReason;
+use strum::{EnumIter, IntoStaticStr};
+
+use super::pending_message::PendingMessage;
+
+/// Backoff of the first retries after a fast failure
+const FAST_BACKOFF: Duration = Duration::from_secs(3);
+/// Number of retries after which fast failures are retried like any other
+const FAST_RETRIES: u32 = 12;
+/// Minimum backoff after a slow failure
+const SLOW_MIN_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The class of the failure an operation is retried after, which determines
+/// how soon it is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum RetryClass {
+    /// Failures that usually resolve within seconds, e.g. gas estimation or
+    /// submission errors caused by stale gas prices
+    Fast,
+    /// Failures waiting on third parties, e.g. validators to sign the
+    /// checkpoint the ISM verifies, the sender to pay for gas or the app to
+    /// fix a recipient that reverts
+    Slow,
+    /// Any other failure
+    Default,
+}
+
+impl From<&ReprepareReason> for RetryClass {
+    fn from(reason: &ReprepareReason) -> Self {
+        match reason {
+            ReprepareReason::ErrorEstimatingGas
+            | ReprepareReason::ErrorSubmitting
+            | ReprepareReason::ErrorStoringMetadataChunks
+            | ReprepareReason::RevertedOrReorged => RetryClass::Fast,
+            ReprepareReason::CouldNotFetchMetadata
+            | ReprepareReason::ErrorBuildingMetadata
+            | ReprepareReason::GasPaymentNotFound
+            | ReprepareReason::GasPaymentRequirementNotMet
+            | ReprepareReason::ExceedsMaxGasLimit
+            | ReprepareReason::ExceedsMaxFee
+            | ReprepareReason::IsmVerificationFailed
+            | ReprepareReason::RecipientHandleReverted
+            | ReprepareReason::SimulationOutOfGas => RetryClass::Slow,
+            ReprepareReason::ErrorCheckingDeliveryStatus
+            | ReprepareReason::ErrorCheckingIfRecipientIsContract
+            | ReprepareReason::ErrorFetchingIsmAddress
+            | ReprepareReason::ErrorGettingMetadataBuilder
+            | ReprepareReason::ErrorCheckingGasRequirement => RetryClass::Default,
+        }
+    }
+}
+
+impl RetryClass {
+    /// How long to wait before retrying an operation that failed with this
+    /// class of failure `num_retries` times.
+    /// The schedule of `Default` is `PendingMessage::calculate_msg_backoff`,
+    /// which the other classes speed up or slow down.
+    pub(crate) fn backoff(&self, num_retries: u32) -> Option<Duration> {
+        let backoff = PendingMessage::calculate_msg_backoff(num_retries)?;
+        Some(match self {
+            RetryClass::Fast if num_retries < FAST_RETRIES => FAST_BACKOFF,
+            RetryClass::Slow => backoff.max(SLOW_MIN_BACKOFF),
+            _ => backoff,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_retry_class_backoff() {
+        assert_eq!(RetryClass::Fast.backoff(0), None);
+        assert_eq!(RetryClass::Fast.backoff(1), Some(FAST_BACKOFF));
+        assert_eq!(
+            RetryClass::Fast.backoff(FAST_RETRIES),
+            PendingMessage::calculate_msg_backoff(FAST_RETRIES)
+        );
+
+        assert_eq!(RetryClass::Slow.backoff(1), Some(SLOW_MIN_BACKOFF));
+        assert_eq!(
+            RetryClass::Slow.backoff(30),
+            PendingMessage::calculate_msg_backoff(30)
+        );
+
+        for num_retries in 0..40 {
+            assert_eq!(
+                RetryClass::Default.backoff(num_retries),
+                PendingMessage::calculate_msg_backoff(num_retries)
+            );
+        }
+    }
+
+    #[test]
+    fn test_retry_class_from_reprepare_reason() {
+        assert_eq!(
+            RetryClass::from(&ReprepareReason::ErrorEstimatingGas),
+            RetryClass::Fast
+        );
+        assert_eq!(
+            RetryClass::from(&ReprepareReason::CouldNotFetchMetadata),
+            RetryClass::Slow
+        );
+        assert_eq!(
+            RetryClass::from(&ReprepareReason::RecipientHandleReverted),
+            RetryClass::Slow
+        );
+        assert_eq!(
+            RetryClass::from(&ReprepareReason::ErrorCheckingDeliveryStatus),
+            RetryClass::Default
+        );
+    }
+}
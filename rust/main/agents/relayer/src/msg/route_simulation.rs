@@ -0,0 +1,464 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ethers::utils::hex;
+use eyre::Result;
+use hyperlane_core::{DeliveryDryRun, HyperlaneMessage, ModuleType, TxCostEstimate, H256};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::{
+    blacklist::AddressBlacklist,
+    matching_list_reloader::ReloadableMatchingList,
+    metadata::{MessageMetadataBuilder, MetadataBuilder},
+    pending_message::MessageContext,
+    skipped_recipients::SkippedRecipients,
+};
+
+/// Routing ISMs followed before giving up, matching the default max depth of
+/// metadata builders
+const MAX_ROUTING_DEPTH: usize = 7;
+
+/// The outcome of a step of a route simulation.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StepOutcome {
+    /// The message would get past the step
+    Passed,
+    /// The message would be stopped at the step
+    Failed,
+    /// The step can't be simulated before the message is dispatched
+    Skipped,
+}
+
+/// A step of the relayer's decision pipeline.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationStep {
+    pub name: String,
+    pub outcome: StepOutcome,
+    pub detail: String,
+}
+
+/// Step-by-step report of how the relayer would handle a message.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteSimulationReport {
+    pub message_id: H256,
+    /// Whether none of the steps failed
+    pub deliverable: bool,
+    pub steps: Vec<SimulationStep>,
+}
+
+impl RouteSimulationReport {
+    fn new(message_id: H256) -> Self {
+        Self {
+            message_id,
+            deliverable: true,
+            steps: vec![],
+        }
+    }
+
+    fn push(&mut self, name: &str, outcome: StepOutcome, detail: impl Into<String>) {
+        self.deliverable &= outcome != StepOutcome::Failed;
+        self.steps.push(SimulationStep {
+            name: name.to_owned(),
+            outcome,
+            detail: detail.into(),
+        });
+    }
+
+    fn pass(&mut self, name: &str, detail: impl Into<String>) {
+        self.push(name, StepOutcome::Passed, detail);
+    }
+
+    fn fail(&mut self, name: &str, detail: impl Into<String>) {
+        self.push(name, StepOutcome::Failed, detail);
+    }
+
+    fn skip(&mut self, name: &str, detail: impl Into<String>) {
+        self.push(name, StepOutcome::Skipped, detail);
+    }
+}
+
+/// Walks a hypothetical message through the checks the relayer makes before
+/// delivering it, without dispatching or submitting anything.
+///
+/// Policy checks, the recipient's ISM and the gas payment policy are
+/// evaluated against the running configuration. Metadata and gas estimates
+/// are only available for ISMs that don't need the message to be dispatched
+/// first, e.g. the null ISM; for multisig ISMs the validators publishing
+/// checkpoints are checked against the threshold instead.
+pub struct RouteSimulator {
+    msg_ctxs: HashMap<(u32, u32), Arc<MessageContext>>,
+    message_whitelist: ReloadableMatchingList,
+    message_blacklist: ReloadableMatchingList,
+    address_blacklist: Arc<AddressBlacklist>,
+    skipped_recipients: Arc<SkippedRecipients>,
+}
+
+impl RouteSimulator {
+    /// `msg_ctxs` are the message contexts keyed by origin and destination
+    /// domain
+    pub fn new(
+        msg_ctxs: HashMap<(u32, u32), Arc<MessageContext>>,
+        message_whitelist: ReloadableMatchingList,
+        message_blacklist: ReloadableMatchingList,
+        address_blacklist: Arc<AddressBlacklist>,
+        skipped_recipients: Arc<SkippedRecipients>,
+    ) -> Self {
+        Self {
+            msg_ctxs,
+            message_whitelist,
+            message_blacklist,
+            address_blacklist,
+            skipped_recipients,
+        }
+    }
+
+    pub async fn simulate(&self, message: &HyperlaneMessage) -> RouteSimulationReport {
+        let mut report = RouteSimulationReport::new(message.id());
+        debug!(hyp_message=?message, "Simulating route of message");
+
+        let Some(ctx) = self.msg_ctxs.get(&(message.origin, message.destination)) else {
+            report.fail(
+                "route",
+                format!(
+                    "Messages from {} to {} aren't relayed",
+                    message.origin, message.destination
+                ),
+            );
+            return report;
+        };
+        report.pass(
+            "route",
+            format!(
+                "Messages from {} to {} are relayed",
+                ctx.metadata_builder.origin_domain().name(),
+                ctx.metadata_builder.destination_domain().name()
+            ),
+        );
+
+        self.check_policies(message, &mut report);
+        if !Self::check_recipient(ctx, message, &mut report).await {
+            return report;
+        }
+
+        let ism_address = match ctx
+            .destination_mailbox
+            .recipient_ism(message.recipient)
+            .await
+        {
+            Ok(ism_address) => ism_address,
+            Err(err) => {
+                report.fail("ism", format!("Failed to fetch the recipient's ISM: {err}"));
+                return report;
+            }
+        };
+        if let Err(err) = Self::interrogate_ism(ctx, ism_address, message, &mut report).await {
+            report.fail(
+                "ism",
+                format!("Failed to interrogate ISM {ism_address:?}: {err:#}"),
+            );
+        }
+
+        let estimate = match Self::build_metadata(ctx, ism_address, message, &mut report).await {
+            Some(metadata) => Self::estimate_gas(ctx, message, &metadata, &mut report).await,
+            None => {
+                report.skip("gasEstimate", "Gas can only be estimated with metadata");
+                None
+            }
+        };
+        if let (Some(estimate), Some(max_limit)) = (estimate, ctx.transaction_gas_limit) {
+            if estimate.gas_limit > max_limit {
+                report.fail(
+                    "gasLimit",
+                    format!(
+                        "The gas limit of {} exceeds the transaction gas limit of {max_limit}",
+                        estimate.gas_limit
+                    ),
+                );
+            } else {
+                report.pass("gasLimit", format!("Within the limit of {max_limit} gas"));
+            }
+        }
+
+        match ctx.origin_gas_payment_enforcer.policy_for(message) {
+            Some(policy) => report.pass("gasPaymentPolicy", format!("{policy:?}")),
+            None => report.fail(
+                "gasPaymentPolicy",
+                "No gas payment policy matches the message",
+            ),
+        }
+        report
+    }
+
+    fn check_policies(&self, message: &HyperlaneMessage, report: &mut RouteSimulationReport) {
+        if self.message_whitelist.current().msg_matches(message, true) {
+            report.pass("whitelist", "Matches the whitelist");
+        } else {
+            report.fail("whitelist", "Doesn't match the whitelist");
+        }
+        if self.message_blacklist.current().msg_matches(message, false) {
+            report.fail("blacklist", "Matches the blacklist");
+        } else {
+            report.pass("blacklist", "Doesn't match the blacklist");
+        }
+        match self.address_blacklist.find_blacklisted_address(message) {
+            Some(address) => report.fail(
+                "addressBlacklist",
+                format!("Involves blacklisted address 0x{}", hex::encode(address)),
+            ),
+            None => report.pass("addressBlacklist", "Involves no blacklisted address"),
+        }
+        if self.skipped_recipients.is_skipped(message) {
+            report.fail("skippedRecipients", "The recipient program is skipped");
+        } else {
+            report.pass("skippedRecipients", "The recipient isn't skipped");
+        }
+    }
+
+    /// Whether the recipient is a contract messages can be delivered to
+    async fn check_recipient(
+        ctx: &MessageContext,
+        message: &HyperlaneMessage,
+        report: &mut RouteSimulationReport,
+    ) -> bool {
+        let provider = ctx.destination_mailbox.provider();
+        match provider.is_contract(&message.recipient).await {
+            Ok(true) => {
+                report.pass("recipient", "The recipient is a contract");
+                true
+            }
+            Ok(false) => {
+                report.fail(
+                    "recipient",
+                    "The recipient isn't a contract, so the message would be dropped",
+                );
+                false
+            }
+            Err(err) => {
+                report.fail(
+                    "recipient",
+                    format!("Failed to check if the recipient is a contract: {err}"),
+                );
+                false
+            }
+        }
+    }
+
+    /// Follows routing ISMs to the ISM verifying the message and reports
+    /// whether it can be satisfied
+    async fn interrogate_ism(
+        ctx: &MessageContext,
+        mut ism_address: H256,
+        message: &HyperlaneMessage,
+        report: &mut RouteSimulationReport,
+    ) -> Result<()> {
+        let base = &ctx.metadata_builder;
+        for _ in 0..MAX_ROUTING_DEPTH {
+            let ism = base.build_ism(ism_address).await?;
+            let module_type = base.module_type(ism_address, &*ism).await?;
+            match module_type {
+                ModuleType::Routing => {
+                    let routing_ism = base.build_routing_ism(ism_address).await?;
+                    let route = routing_ism.route(message).await?;
+                    report.pass(
+                        "ism",
+                        format!("Routing ISM {ism_address:?} routes the message to {route:?}"),
+                    );
+                    ism_address = route;
+                }
+                ModuleType::MerkleRootMultisig | ModuleType::MessageIdMultisig => {
+                    let multisig_ism = base.build_multisig_ism(ism_address).await?;
+                    let (validators, threshold) = base
+                        .ism_config(
+                            ism_address,
+                            message.origin,
+                            multisig_ism.validators_and_threshold(message),
+                        )
+                        .await?;
+                    report.pass(
+                        "ism",
+                        format!(
+                            "{module_type:?} ISM {ism_address:?} requires {threshold} of {} validators",
+                            validators.len()
+                        ),
+                    );
+                    let checkpoint_syncer = base
+                        .build_checkpoint_syncer(message, &validators, None)
+                        .await?;
+                    let signing = checkpoint_syncer
+                        .get_validator_latest_checkpoints_and_update_metrics(
+                            &validators,
+                            base.origin_domain(),
+                            base.destination_domain(),
+                        )
+                        .await
+                        .len();
+                    let detail = format!(
+                        "{signing} of {} validators publish checkpoints, {threshold} are required",
+                        validators.len()
+                    );
+                    if threshold > 0 && signing >= threshold as usize {
+                        report.pass("validators", detail);
+                    } else {
+                        report.fail("validators", detail);
+                    }
+                    return Ok(());
+                }
+                ModuleType::Aggregation => {
+                    let aggregation_ism = base.build_aggregation_ism(ism_address).await?;
+                    let (modules, threshold) = base
+                        .ism_config(
+                            ism_address,
+                            message.origin,
+                            aggregation_ism.modules_and_threshold(message),
+                        )
+                        .await?;
+                    report.pass(
+                        "ism",
+                        format!(
+                            "Aggregation ISM {ism_address:?} requires {threshold} of {modules:?}"
+                        ),
+                    );
+                    return Ok(());
+                }
+                ModuleType::Null | ModuleType::CcipRead => {
+                    report.pass("ism", format!("{module_type:?} ISM {ism_address:?}"));
+                    return Ok(());
+                }
+                _ => {
+                    report.fail(
+                        "ism",
+                        format!("ISM {ism_address:?} has unsupported type {module_type:?}"),
+                    );
+                    return Ok(());
+                }
+            }
+        }
+        report.fail(
+            "ism",
+            format!("Gave up after following {MAX_ROUTING_DEPTH} routing ISMs"),
+        );
+        Ok(())
+    }
+
+    async fn build_metadata(
+        ctx: &MessageContext,
+        ism_address: H256,
+        message: &HyperlaneMessage,
+        report: &mut RouteSimulationReport,
+    ) -> Option<Vec<u8>> {
+        let metadata =
+            match MessageMetadataBuilder::new(ism_address, message, ctx.metadata_builder.clone())
+                .await
+            {
+                Ok(builder) => builder.build(ism_address, message).await,
+                Err(err) => Err(err),
+            };
+        match metadata {
+            Ok(Some(metadata)) => {
+                report.pass(
+                    "metadata",
+                    format!("Built {} bytes of metadata", metadata.len()),
+                );
+                Some(metadata)
+            }
+            Ok(None) => {
+                report.skip(
+                    "metadata",
+                    "Metadata can only be built once the message is dispatched and signed",
+                );
+                None
+            }
+            Err(err) => {
+                report.fail("metadata", format!("Failed to build metadata: {err:#}"));
+                None
+            }
+        }
+    }
+
+    async fn estimate_gas(
+        ctx: &MessageContext,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        report: &mut RouteSimulationReport,
+    ) -> Option<TxCostEstimate> {
+        match ctx
+            .destination_mailbox
+            .process_estimate_costs(message, metadata)
+            .await
+        {
+            Ok(estimate) => {
+                report.pass(
+                    "gasEstimate",
+                    format!(
+                        "Processing would use {} gas at a price of {:?}",
+                        estimate.gas_limit, estimate.gas_price
+                    ),
+                );
+                Some(estimate)
+            }
+            Err(err) => {
+                report.fail("gasEstimate", format!("Processing would fail: {err}"));
+                if let Some(simulator) = &ctx.delivery_simulator {
+                    match simulator.dry_run_delivery(message, metadata).await {
+                        Ok(DeliveryDryRun::IsmVerificationFailed(reason)) => {
+                            report.fail("dryRun", format!("The ISM would reject: {reason}"))
+                        }
+                        Ok(DeliveryDryRun::HandleReverted(reason)) => report.fail(
+                            "dryRun",
+                            format!("The recipient would fail to handle: {reason}"),
+                        ),
+                        Ok(dry_run) => report.skip("dryRun", format!("{dry_run:?}")),
+                        Err(err) => report.skip("dryRun", format!("Failed to dry run: {err}")),
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::settings::matching_list::MatchingList;
+
+    #[tokio::test]
+    async fn test_unrelayed_route_fails() {
+        let simulator = RouteSimulator::new(
+            HashMap::new(),
+            ReloadableMatchingList::new(MatchingList::default()),
+            ReloadableMatchingList::new(MatchingList::default()),
+            Arc::new(AddressBlacklist::new(vec![])),
+            Arc::new(SkippedRecipients::new([], [])),
+        );
+        let message = HyperlaneMessage::default();
+
+        let report = simulator.simulate(&message).await;
+        assert_eq!(report.message_id, message.id());
+        assert!(!report.deliverable);
+        assert_eq!(report.steps.len(), 1);
+        assert_eq!(report.steps[0].name, "route");
+        assert_eq!(report.steps[0].outcome, StepOutcome::Failed);
+    }
+
+    #[test]
+    fn test_failed_step_makes_route_undeliverable() {
+        let mut report = RouteSimulationReport::new(H256::zero());
+        report.pass("whitelist", "Matches the whitelist");
+        report.skip("metadata", "Not dispatched");
+        assert!(report.deliverable);
+        report.fail("blacklist", "Matches the blacklist");
+        assert!(!report.deliverable);
+        assert_eq!(
+            serde_json::to_value(&report.steps[2]).unwrap(),
+            serde_json::json!({
+                "name": "blacklist",
+                "outcome": "failed",
+                "detail": "Matches the blacklist"
+            })
+        );
+    }
+}
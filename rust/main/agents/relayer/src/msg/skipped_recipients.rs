@@ -0,0 +1,105 @@
+use std::{
+    collections::HashSet,
+    sync::{PoisonError, RwLock},
+};
+
+use hyperlane_core::{HyperlaneMessage, H256};
+
+/// Sealevel recipient programs that messages aren't delivered to, e.g. programs
+/// that panic in `handle` and would waste compute budget on every attempt.
+/// The list is configured on startup and can be changed through the admin API.
+#[derive(Debug, Default)]
+pub struct SkippedRecipients {
+    /// Domain ids of the Sealevel destinations the list applies to
+    sealevel_destinations: HashSet<u32>,
+    program_ids: RwLock<HashSet<H256>>,
+}
+
+impl SkippedRecipients {
+    pub fn new(
+        sealevel_destinations: impl IntoIterator<Item = u32>,
+        program_ids: impl IntoIterator<Item = H256>,
+    ) -> Self {
+        Self {
+            sealevel_destinations: sealevel_destinations.into_iter().collect(),
+            program_ids: RwLock::new(program_ids.into_iter().collect()),
+        }
+    }
+
+    /// Whether the message is to a skipped program on a Sealevel destination
+    pub fn is_skipped(&self, message: &HyperlaneMessage) -> bool {
+        self.sealevel_destinations.contains(&message.destination)
+            && self
+                .program_ids
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .contains(&message.recipient)
+    }
+
+    /// Skip messages to the given programs
+    pub fn add(&self, program_ids: impl IntoIterator<Item = H256>) {
+        self.program_ids
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(program_ids);
+    }
+
+    /// Deliver messages to the given programs again
+    pub fn remove(&self, program_ids: &[H256]) {
+        let mut skipped = self
+            .program_ids
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        for program_id in program_ids {
+            skipped.remove(program_id);
+        }
+    }
+
+    /// The skipped programs, in ascending order
+    pub fn program_ids(&self) -> Vec<H256> {
+        let mut program_ids = self
+            .program_ids
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        program_ids.sort();
+        program_ids
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SEALEVEL_DOMAIN: u32 = 1399811149;
+
+    fn message(destination: u32, recipient: H256) -> HyperlaneMessage {
+        HyperlaneMessage {
+            destination,
+            recipient,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_skips_listed_programs_on_sealevel_destinations() {
+        let poisoned = H256::repeat_byte(1);
+        let healthy = H256::repeat_byte(2);
+        let skipped = SkippedRecipients::new([SEALEVEL_DOMAIN], [poisoned]);
+
+        assert!(skipped.is_skipped(&message(SEALEVEL_DOMAIN, poisoned)));
+        assert!(!skipped.is_skipped(&message(SEALEVEL_DOMAIN, healthy)));
+        // The same recipient on a non-Sealevel destination is delivered
+        assert!(!skipped.is_skipped(&message(1, poisoned)));
+
+        skipped.add([healthy]);
+        assert!(skipped.is_skipped(&message(SEALEVEL_DOMAIN, healthy)));
+        assert_eq!(skipped.program_ids(), vec![poisoned, healthy]);
+
+        skipped.remove(&[poisoned]);
+        assert!(!skipped.is_skipped(&message(SEALEVEL_DOMAIN, poisoned)));
+        assert_eq!(skipped.program_ids(), vec![healthy]);
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{Debug, Formatter},
     sync::Arc,
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -12,19 +13,20 @@ use hyperlane_base::{
     broadcast::BroadcastMpscSender,
     db::{HyperlaneRocksDB, DB},
     metrics::{AgentMetrics, MetricsUpdater},
-    settings::{ChainConf, IndexSettings},
+    settings::{ChainConf, ChainConnectionConf, IndexSettings},
     AgentMetadata, BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics,
     HyperlaneAgentCore, SyncOptions,
 };
 use hyperlane_core::{
-    rpc_clients::call_and_retry_n_times, ChainCommunicationError, ContractSyncCursor,
-    HyperlaneDomain, HyperlaneMessage, InterchainGasPayment, Mailbox, MerkleTreeInsertion,
-    QueueOperation, ValidatorAnnounce, H512, U256,
+    metrics::definitions, rpc_clients::call_and_retry_n_times, ChainCommunicationError,
+    ContractSyncCursor, HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage,
+    InterchainGasPayment, Mailbox, MerkleTreeInsertion, MessageDeliverySimulator, QueueOperation,
+    ValidatorAnnounce, H512, U256,
 };
 use tokio::{
     sync::{
         broadcast::Sender as BroadcastSender,
-        mpsc::{self, Receiver as MpscReceiver, UnboundedSender},
+        mpsc::{self, Receiver as MpscReceiver, UnboundedReceiver, UnboundedSender},
         RwLock,
     },
     task::JoinHandle,
@@ -36,20 +38,39 @@ use crate::{
     merkle_tree::builder::MerkleTreeBuilder,
     msg::{
         blacklist::AddressBlacklist,
+        dead_letters::DeadLetterQueue,
+        delivery_receipts::{DeliveryReceiptExporter, DeliveryReceiptLog},
+        delivery_ttl::DeliveryTtlPolicy,
+        fee_tracker::FeeTracker,
+        gas_escalation::GasEscalationPolicy,
         gas_payment::GasPaymentEnforcer,
-        metadata::{BaseMetadataBuilder, IsmAwareAppContextClassifier},
+        matching_list_reloader::{MatchingListReloader, ReloadableMatchingList},
+        metadata::{
+            BaseMetadataBuilder, IsmAwareAppContextClassifier, IsmConfigCache, IsmConfigMonitor,
+            RoutingIsmCache,
+        },
+        metadata_chunking::MetadataChunker,
         op_submitter::{SerialSubmitter, SerialSubmitterMetrics},
+        ordering::InFlightOrderingKeys,
         pending_message::{MessageContext, MessageSubmissionMetrics},
         processor::{MessageProcessor, MessageProcessorMetrics},
+        route_simulation::RouteSimulator,
+        skipped_recipients::SkippedRecipients,
     },
     server::{self as relayer_server},
-    settings::{matching_list::MatchingList, RelayerSettings},
+    settings::{
+        matching_list::{MatchingList, MatchingListSource},
+        DbValueMigration, OrderingKeyConf, RelayerSettings,
+    },
 };
 use crate::{
     merkle_tree::processor::{MerkleTreeProcessor, MerkleTreeProcessorMetrics},
     processor::ProcessorExt,
 };
-use crate::{processor::Processor, server::ENDPOINT_MESSAGES_QUEUE_SIZE};
+use crate::{
+    processor::Processor,
+    server::{MessageRetryRequest, OriginCursorRewinders, ENDPOINT_MESSAGES_QUEUE_SIZE},
+};
 
 const CURSOR_BUILDING_ERROR: &str = "Error building cursor for origin";
 const CURSOR_INSTANTIATION_ATTEMPTS: usize = 10;
@@ -76,13 +97,32 @@ pub struct Relayer {
     prover_syncs: HashMap<HyperlaneDomain, Arc<RwLock<MerkleTreeBuilder>>>,
     merkle_tree_hook_syncs: HashMap<HyperlaneDomain, Arc<dyn ContractSyncer<MerkleTreeInsertion>>>,
     dbs: HashMap<HyperlaneDomain, HyperlaneRocksDB>,
-    message_whitelist: Arc<MatchingList>,
-    message_blacklist: Arc<MatchingList>,
+    /// Rewind the cursors of the syncs of each origin on request
+    cursor_rewinders: HashMap<HyperlaneDomain, OriginCursorRewinders>,
+    message_whitelist: ReloadableMatchingList,
+    message_blacklist: ReloadableMatchingList,
+    whitelist_source: Option<MatchingListSource>,
+    blacklist_source: Option<MatchingListSource>,
+    matching_list_reload_interval: Duration,
     address_blacklist: Arc<AddressBlacklist>,
+    /// Sealevel recipient programs whose messages aren't delivered
+    skipped_recipients: Arc<SkippedRecipients>,
     transaction_gas_limit: Option<U256>,
     skip_transaction_gas_limit_for: HashSet<u32>,
     allow_local_checkpoint_syncers: bool,
     metric_app_contexts: Vec<(MatchingList, String)>,
+    ordering_keys: Vec<OrderingKeyConf>,
+    /// Ordering keys with a message in flight, shared by all message processors
+    in_flight_ordering_keys: InFlightOrderingKeys,
+    admin_api_token: Option<String>,
+    /// Fees paid on Cosmos destinations
+    fee_trackers: Vec<Arc<FeeTracker>>,
+    /// Exports the receipts of delivered messages of each origin, if configured
+    delivery_receipt_exporters: Vec<DeliveryReceiptExporter>,
+    /// Whether re-encoded database values are committed
+    db_value_migration: DbValueMigration,
+    /// Sends message retry requests to the op queues of all destinations
+    retry_sender: BroadcastSender<MessageRetryRequest>,
     core_metrics: Arc<CoreMetrics>,
     // TODO: decide whether to consolidate `agent_metrics` and `chain_metrics` into a single struct
     // or move them in `core_metrics`, like the validator metrics
@@ -134,6 +174,13 @@ impl BaseAgent for Relayer {
             .iter()
             .map(|origin| (origin.clone(), HyperlaneRocksDB::new(origin, db.clone())))
             .collect::<HashMap<_, _>>();
+        if settings.db_value_migration == DbValueMigration::Rollback {
+            for (origin, db) in &dbs {
+                let restored = db.restore_legacy_values()?;
+                info!(%origin, restored, "Restored the legacy encoding of database values");
+            }
+            eyre::bail!("Rolled back the database value migration, the previous relayer version can be started now");
+        }
 
         let mailboxes = Self::build_mailboxes(&settings, &core_metrics, &chain_metrics).await;
 
@@ -187,32 +234,79 @@ impl BaseAgent for Relayer {
             .map(|(k, v)| (k, v as _))
             .collect();
 
-        let message_whitelist = Arc::new(settings.whitelist);
-        let message_blacklist = Arc::new(settings.blacklist);
+        let message_whitelist = ReloadableMatchingList::new(settings.whitelist);
+        let message_blacklist = ReloadableMatchingList::new(settings.blacklist);
         let address_blacklist = Arc::new(AddressBlacklist::new(settings.address_blacklist));
+        let skipped_recipients = Arc::new(SkippedRecipients::new(
+            settings
+                .destination_chains
+                .iter()
+                .filter(|domain| domain.domain_protocol() == HyperlaneDomainProtocol::Sealevel)
+                .map(HyperlaneDomain::id),
+            settings.sealevel_skipped_recipients,
+        ));
         let skip_transaction_gas_limit_for = settings.skip_transaction_gas_limit_for;
         let transaction_gas_limit = settings.transaction_gas_limit;
+        let gas_escalation = settings.gas_escalation;
+        let fee_budgets = settings.fee_budgets;
+        let metadata_chunking = settings.metadata_chunking;
+        let routing_ism_cache_for = settings.routing_ism_cache_for;
+        let routing_ism_cache_ttl = settings.routing_ism_cache_ttl;
+        let metadata_warm_up_ttl = settings.metadata_warm_up_ttl;
+        let dead_letter_after_retries = settings.dead_letter_after_retries;
 
         info!(
             %message_whitelist,
             %message_blacklist,
+            whitelist_source = ?settings.whitelist_source,
+            blacklist_source = ?settings.blacklist_source,
             ?address_blacklist,
+            skipped_recipients = ?skipped_recipients.program_ids(),
             ?transaction_gas_limit,
             ?skip_transaction_gas_limit_for,
+            ?gas_escalation,
             "Whitelist configuration"
         );
-
-        // provers by origin chain
+        info!(
+            ?routing_ism_cache_for,
+            ?routing_ism_cache_ttl,
+            "Routing ISM cache configuration"
+        );
+        info!(?metadata_warm_up_ttl, "Metadata warm-up configuration");
+        info!(
+            ?dead_letter_after_retries,
+            "Dead-letter queue configuration"
+        );
+        info!(?metadata_chunking, "Metadata chunking configuration");
+        let routing_ism_cache_lookups =
+            core_metrics.new_int_counter(&definitions::ROUTING_ISM_CACHE_LOOKUPS)?;
+        let ism_config_cache_for = settings.ism_config_cache_for;
+        info!(
+            ?ism_config_cache_for,
+            ism_config_cache_ttl = ?settings.ism_config_cache_ttl,
+            "ISM config cache configuration"
+        );
+        // Shared by all destinations, entries are keyed by destination
+        let ism_config_cache = Arc::new(IsmConfigCache::new(
+            settings.ism_config_cache_ttl,
+            core_metrics.new_int_counter(&definitions::ISM_CONFIG_CACHE_LOOKUPS)?,
+        ));
+        let operation_retries = core_metrics.new_int_counter(&definitions::OPERATION_RETRIES)?;
+        let messages_expired =
+            core_metrics.new_int_counter(&definitions::MESSAGES_EXPIRED_COUNT)?;
+        info!(?fee_budgets, "Fee budget configuration");
+        let fees_paid_total = core_metrics.new_counter(&definitions::FEES_PAID_TOTAL)?;
+        let fees_paid_in_window = core_metrics.new_gauge(&definitions::FEES_PAID_IN_WINDOW)?;
+
+        // provers by origin chain, restored from the snapshots of their trees
         let prover_syncs = settings
             .origin_chains
             .iter()
             .map(|origin| {
-                (
-                    origin.clone(),
-                    Arc::new(RwLock::new(MerkleTreeBuilder::new())),
-                )
+                let builder = MerkleTreeBuilder::restore(&dbs[origin])?;
+                Ok((origin.clone(), Arc::new(RwLock::new(builder))))
             })
-            .collect::<HashMap<_, _>>();
+            .collect::<Result<HashMap<_, _>>>()?;
 
         info!(gas_enforcement_policies=?settings.gas_payment_enforcement, "Gas enforcement configuration");
 
@@ -232,8 +326,55 @@ impl BaseAgent for Relayer {
             })
             .collect();
 
+        let retry_sender = BroadcastSender::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
         let mut msg_ctxs = HashMap::new();
         let mut destination_chains = HashMap::new();
+        let mut fee_trackers = vec![];
+
+        info!(delivery_receipts = ?settings.delivery_receipts, "Delivery receipt configuration");
+        let mut delivery_receipt_logs = HashMap::new();
+        let mut delivery_receipt_exporters = vec![];
+        if let Some(conf) = &settings.delivery_receipts {
+            for origin in validator_announces.keys() {
+                let log = Arc::new(DeliveryReceiptLog::new(dbs[origin].clone())?);
+                delivery_receipt_exporters.push(DeliveryReceiptExporter::new(
+                    origin.name().to_owned(),
+                    log.clone(),
+                    conf.sink.clone(),
+                    conf.batch_size,
+                ));
+                delivery_receipt_logs.insert(origin.clone(), log);
+            }
+        }
+
+        info!(delivery_ttls = ?settings.delivery_ttls, "Delivery TTL configuration");
+        let mut delivery_ttls = HashMap::new();
+        if !settings.delivery_ttls.is_empty() {
+            for origin in validator_announces.keys() {
+                let origin_provider = match core.settings.chain_setup(origin) {
+                    Ok(chain_setup) => chain_setup.build_provider(&core_metrics).await,
+                    Err(err) => Err(err),
+                };
+                let origin_provider = match origin_provider {
+                    Ok(origin_provider) => origin_provider,
+                    Err(err) => {
+                        warn!(
+                            ?err,
+                            %origin,
+                            "Failed to build origin provider, not applying delivery TTLs to messages from this origin"
+                        );
+                        continue;
+                    }
+                };
+                delivery_ttls.insert(
+                    origin.clone(),
+                    Arc::new(DeliveryTtlPolicy::new(
+                        settings.delivery_ttls.clone(),
+                        origin_provider.into(),
+                    )),
+                );
+            }
+        }
 
         // only iterate through destination chains that were successfully instantiated
         for (destination, dest_mailbox) in mailboxes.iter() {
@@ -245,6 +386,69 @@ impl BaseAgent for Relayer {
                 } else {
                     transaction_gas_limit
                 };
+            let gas_escalation_policy = GasEscalationPolicy::new(
+                gas_escalation
+                    .get(&destination.id())
+                    .cloned()
+                    .unwrap_or_default(),
+            );
+            let routing_ism_cache = routing_ism_cache_for.contains(&destination.id()).then(|| {
+                Arc::new(RoutingIsmCache::new(
+                    routing_ism_cache_ttl,
+                    routing_ism_cache_lookups.with_label_values(&[destination.name(), "hit"]),
+                    routing_ism_cache_lookups.with_label_values(&[destination.name(), "miss"]),
+                ))
+            });
+            let fee_tracker = match &destination_chain_setup.connection {
+                ChainConnectionConf::Cosmos(conf) => {
+                    let fee_tracker = Arc::new(FeeTracker::new(
+                        destination.name().to_owned(),
+                        conf.get_minimum_gas_price().denom,
+                        fee_budgets
+                            .get(&destination.id())
+                            .map(Vec::as_slice)
+                            .unwrap_or_default(),
+                        fees_paid_total.clone(),
+                        fees_paid_in_window.clone(),
+                    ));
+                    fee_trackers.push(fee_tracker.clone());
+                    Some(fee_tracker)
+                }
+                _ => None,
+            };
+            let metadata_chunker = match metadata_chunking.get(&destination.id()) {
+                Some(conf) => {
+                    let store = destination_chain_setup
+                        .build_metadata_chunk_store(conf.store, &core_metrics)
+                        .await?;
+                    Some(MetadataChunker::new(store.into(), conf))
+                }
+                None => None,
+            };
+            let delivery_simulator: Option<Arc<dyn MessageDeliverySimulator>> =
+                match destination_chain_setup
+                    .build_message_delivery_simulator(&core_metrics)
+                    .await
+                {
+                    Ok(simulator) => Some(simulator.into()),
+                    Err(err) => {
+                        warn!(
+                            ?err,
+                            %destination,
+                            "Delivery dry runs not supported, gas estimation failures won't be attributed"
+                        );
+                        None
+                    }
+                };
+            let destination_ism_config_cache = ism_config_cache_for
+                .contains(&destination.id())
+                .then(|| ism_config_cache.clone());
+            let mut ism_config_monitor =
+                IsmConfigMonitor::new(destination.clone(), retry_sender.clone());
+            if let Some(cache) = &destination_ism_config_cache {
+                ism_config_monitor = ism_config_monitor.with_config_cache(cache.clone());
+            }
+            let ism_config_monitor = Arc::new(ism_config_monitor);
 
             // only iterate through origin chains that were successfully instantiated
             for (origin, validator_announce) in validator_announces.iter() {
@@ -261,6 +465,9 @@ impl BaseAgent for Relayer {
                         dest_mailbox.clone(),
                         settings.metric_app_contexts.clone(),
                     ),
+                    routing_ism_cache.clone(),
+                    ism_config_monitor.clone(),
+                    destination_ism_config_cache.clone(),
                 );
 
                 msg_ctxs.insert(
@@ -274,14 +481,38 @@ impl BaseAgent for Relayer {
                         metadata_builder: Arc::new(metadata_builder),
                         origin_gas_payment_enforcer: gas_payment_enforcers[origin].clone(),
                         transaction_gas_limit,
-                        metrics: MessageSubmissionMetrics::new(&core_metrics, origin, destination),
+                        gas_escalation_policy: gas_escalation_policy.clone(),
+                        fee_tracker: fee_tracker.clone(),
+                        delivery_receipts: delivery_receipt_logs.get(origin).cloned(),
+                        metadata_warm_up_ttl,
+                        metadata_chunker: metadata_chunker.clone(),
+                        dead_letter_after_retries,
+                        delivery_ttl: delivery_ttls.get(origin).cloned(),
+                        delivery_simulator: delivery_simulator.clone(),
+                        message_whitelist: message_whitelist.clone(),
+                        message_blacklist: message_blacklist.clone(),
+                        skipped_recipients: skipped_recipients.clone(),
+                        metrics: MessageSubmissionMetrics::new(
+                            &core_metrics,
+                            origin,
+                            destination,
+                            &operation_retries,
+                            &messages_expired,
+                        ),
                     }),
                 );
             }
         }
 
+        let cursor_rewinders = settings
+            .origin_chains
+            .iter()
+            .map(|origin| (origin.clone(), OriginCursorRewinders::default()))
+            .collect();
+
         Ok(Self {
             dbs,
+            cursor_rewinders,
             origin_chains: settings.origin_chains,
             destination_chains,
             msg_ctxs,
@@ -292,11 +523,22 @@ impl BaseAgent for Relayer {
             merkle_tree_hook_syncs,
             message_whitelist,
             message_blacklist,
+            whitelist_source: settings.whitelist_source,
+            blacklist_source: settings.blacklist_source,
+            matching_list_reload_interval: settings.matching_list_reload_interval,
             address_blacklist,
+            skipped_recipients,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
             allow_local_checkpoint_syncers: settings.allow_local_checkpoint_syncers,
             metric_app_contexts: settings.metric_app_contexts,
+            ordering_keys: settings.ordering_keys,
+            in_flight_ordering_keys: Default::default(),
+            admin_api_token: settings.admin_api_token,
+            fee_trackers,
+            delivery_receipt_exporters,
+            db_value_migration: settings.db_value_migration,
+            retry_sender,
             core_metrics,
             agent_metrics,
             chain_metrics,
@@ -319,7 +561,7 @@ impl BaseAgent for Relayer {
                 }));
             tasks.push(console_server.instrument(info_span!("Tokio console server")));
         }
-        let sender = BroadcastSender::new(ENDPOINT_MESSAGES_QUEUE_SIZE);
+        let sender = self.retry_sender.clone();
         // send channels by destination chain
         let mut send_channels = HashMap::with_capacity(self.destination_chains.len());
         let mut prep_queues = HashMap::with_capacity(self.destination_chains.len());
@@ -363,32 +605,73 @@ impl BaseAgent for Relayer {
 
         for origin in &self.origin_chains {
             self.chain_metrics.set_critical_error(origin.name(), false);
-            let maybe_broadcaster = self
-                .message_syncs
-                .get(origin)
-                .and_then(|sync| sync.get_broadcaster());
-            tasks.push(self.run_message_sync(origin, task_monitor.clone()).await);
-            tasks.push(
-                self.run_interchain_gas_payment_sync(
-                    origin,
-                    BroadcastMpscSender::map_get_receiver(maybe_broadcaster.as_ref()).await,
-                    task_monitor.clone(),
-                )
-                .await,
-            );
-            tasks.push(
-                self.run_merkle_tree_hook_syncs(
-                    origin,
-                    BroadcastMpscSender::map_get_receiver(maybe_broadcaster.as_ref()).await,
-                    task_monitor.clone(),
-                )
-                .await,
-            );
+            tasks.push(self.run_message_sync(origin, task_monitor.clone()));
+            tasks.push(self.run_interchain_gas_payment_sync(origin, task_monitor.clone()));
+            tasks.push(self.run_merkle_tree_hook_syncs(origin, task_monitor.clone()));
+        }
+        // reload the whitelist and blacklist from their remote sources, if any
+        for (name, source, list) in [
+            ("whitelist", &self.whitelist_source, &self.message_whitelist),
+            ("blacklist", &self.blacklist_source, &self.message_blacklist),
+        ] {
+            if let Some(source) = source {
+                tasks.push(
+                    MatchingListReloader::new(
+                        name,
+                        source.clone(),
+                        self.matching_list_reload_interval,
+                        list.clone(),
+                    )
+                    .spawn(),
+                );
+            }
+        }
+
+        // keep the fees paid within trailing windows up to date
+        for fee_tracker in &self.fee_trackers {
+            tasks.push(fee_tracker.clone().spawn());
+        }
+
+        // export the receipts of delivered messages
+        for exporter in std::mem::take(&mut self.delivery_receipt_exporters) {
+            tasks.push(exporter.spawn());
+        }
+
+        // rewrite values stored in outdated encodings
+        for origin in &self.origin_chains {
+            tasks.push(self.run_value_reencoding(origin, self.db_value_migration));
+        }
+
+        // channels re-injecting dead letters into the message processors, by origin
+        let mut reinject_channels = HashMap::with_capacity(self.origin_chains.len());
+        let mut reinjected_receivers = HashMap::with_capacity(self.origin_chains.len());
+        for origin in &self.origin_chains {
+            let (reinject_channel, reinjected) = mpsc::unbounded_channel::<HyperlaneMessage>();
+            reinject_channels.insert(origin.id(), reinject_channel);
+            reinjected_receivers.insert(origin.id(), reinjected);
         }
+
         // run server
         let custom_routes = relayer_server::Server::new(self.destination_chains.len())
             .with_op_retry(sender.clone())
             .with_message_queue(prep_queues)
+            .with_dbs(
+                self.dbs
+                    .iter()
+                    .map(|(domain, db)| (domain.id(), db.clone()))
+                    .collect(),
+            )
+            .with_cursor_rewinders(
+                self.cursor_rewinders
+                    .iter()
+                    .map(|(domain, rewinders)| (domain.id(), rewinders.clone()))
+                    .collect(),
+            )
+            .with_fee_trackers(self.fee_trackers.clone())
+            .with_skipped_recipients(self.skipped_recipients.clone())
+            .with_route_simulator(Arc::new(self.route_simulator()))
+            .with_dead_letters(Arc::new(self.dead_letter_queue(reinject_channels)))
+            .with_admin_token(self.admin_api_token.clone())
             .routes();
 
         let server = self
@@ -403,9 +686,13 @@ impl BaseAgent for Relayer {
 
         // each message process attempts to send messages from a chain
         for origin in &self.origin_chains {
+            let reinjected = reinjected_receivers
+                .remove(&origin.id())
+                .expect("Missing dead-letter re-injection channel");
             tasks.push(self.run_message_processor(
                 origin,
                 send_channels.clone(),
+                reinjected,
                 task_monitor.clone(),
             ));
             tasks.push(self.run_merkle_tree_processor(origin, task_monitor.clone()));
@@ -422,13 +709,13 @@ impl BaseAgent for Relayer {
 
 impl Relayer {
     fn record_critical_error(
-        &self,
+        chain_metrics: &ChainMetrics,
         origin: &HyperlaneDomain,
         err: ChainCommunicationError,
         message: &str,
     ) {
         error!(?err, origin=?origin, "{message}");
-        self.chain_metrics.set_critical_error(origin.name(), true);
+        chain_metrics.set_critical_error(origin.name(), true);
     }
 
     async fn instantiate_cursor_with_retries<T: 'static>(
@@ -449,36 +736,56 @@ impl Relayer {
         .await
     }
 
-    async fn run_message_sync(
+    /// A receiver of the IDs of the transactions the message sync of an
+    /// origin indexes, once that sync is built
+    async fn message_tx_id_receiver(
+        message_sync: Option<Arc<dyn ContractSyncer<HyperlaneMessage>>>,
+    ) -> Option<MpscReceiver<H512>> {
+        let broadcaster = message_sync?.wait_for_broadcaster().await;
+        BroadcastMpscSender::map_get_receiver(broadcaster.as_ref()).await
+    }
+
+    fn run_message_sync(
         &self,
         origin: &HyperlaneDomain,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
         let contract_sync = self.message_syncs.get(origin).unwrap().clone();
-        let cursor_instantiation_result =
-            Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings.clone())
-                .await;
-        let cursor = match cursor_instantiation_result {
-            Ok(cursor) => cursor,
-            Err(err) => {
-                self.record_critical_error(origin, err, CURSOR_BUILDING_ERROR);
-                return tokio::spawn(async {}).instrument(info_span!("MessageSync"));
-            }
-        };
-        let origin_name = origin.name().to_string();
+        let rewinder = self.cursor_rewinders[origin].messages.clone();
+        let chain_metrics = self.chain_metrics.clone();
+        let origin = origin.clone();
+        // The cursor is instantiated within the task, as the contract sync of an
+        // origin that was unavailable at startup is only built once it recovers
         tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+            let cursor =
+                match Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings)
+                    .await
+                {
+                    Ok(cursor) => cursor,
+                    Err(err) => {
+                        Self::record_critical_error(
+                            &chain_metrics,
+                            &origin,
+                            err,
+                            CURSOR_BUILDING_ERROR,
+                        );
+                        return;
+                    }
+                };
             let label = "dispatched_messages";
-            contract_sync.clone().sync(label, cursor.into()).await;
-            info!(chain = origin_name, label, "contract sync task exit");
+            contract_sync
+                .clone()
+                .sync(label, SyncOptions::from(cursor).with_rewinder(rewinder))
+                .await;
+            info!(chain = origin.name(), label, "contract sync task exit");
         }))
         .instrument(info_span!("MessageSync"))
     }
 
-    async fn run_interchain_gas_payment_sync(
+    fn run_interchain_gas_payment_sync(
         &self,
         origin: &HyperlaneDomain,
-        tx_id_receiver: Option<MpscReceiver<H512>>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index_settings();
@@ -487,54 +794,77 @@ impl Relayer {
             .get(origin)
             .unwrap()
             .clone();
-        let cursor_instantiation_result =
-            Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings.clone())
-                .await;
-        let cursor = match cursor_instantiation_result {
-            Ok(cursor) => cursor,
-            Err(err) => {
-                self.record_critical_error(origin, err, CURSOR_BUILDING_ERROR);
-                return tokio::spawn(async {}).instrument(info_span!("IgpSync"));
-            }
-        };
-        let origin_name = origin.name().to_string();
+        let rewinder = self.cursor_rewinders[origin].gas_payments.clone();
+        let message_sync = self.message_syncs.get(origin).cloned();
+        let chain_metrics = self.chain_metrics.clone();
+        let origin = origin.clone();
         tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+            let tx_id_receiver = Self::message_tx_id_receiver(message_sync).await;
+            let cursor =
+                match Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings)
+                    .await
+                {
+                    Ok(cursor) => cursor,
+                    Err(err) => {
+                        Self::record_critical_error(
+                            &chain_metrics,
+                            &origin,
+                            err,
+                            CURSOR_BUILDING_ERROR,
+                        );
+                        return;
+                    }
+                };
             let label = "gas_payments";
             contract_sync
                 .clone()
-                .sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
+                .sync(
+                    label,
+                    SyncOptions::new(Some(cursor), tx_id_receiver).with_rewinder(rewinder),
+                )
                 .await;
-            info!(chain = origin_name, label, "contract sync task exit");
+            info!(chain = origin.name(), label, "contract sync task exit");
         }))
         .instrument(info_span!("IgpSync"))
     }
 
-    async fn run_merkle_tree_hook_syncs(
+    fn run_merkle_tree_hook_syncs(
         &self,
         origin: &HyperlaneDomain,
-        tx_id_receiver: Option<MpscReceiver<H512>>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let index_settings = self.as_ref().settings.chains[origin.name()].index.clone();
         let contract_sync = self.merkle_tree_hook_syncs.get(origin).unwrap().clone();
-        let cursor_instantiation_result =
-            Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings.clone())
-                .await;
-        let cursor = match cursor_instantiation_result {
-            Ok(cursor) => cursor,
-            Err(err) => {
-                self.record_critical_error(origin, err, CURSOR_BUILDING_ERROR);
-                return tokio::spawn(async {}).instrument(info_span!("MerkleTreeHookSync"));
-            }
-        };
-        let origin_name = origin.name().to_string();
+        let rewinder = self.cursor_rewinders[origin].merkle_tree_insertions.clone();
+        let message_sync = self.message_syncs.get(origin).cloned();
+        let chain_metrics = self.chain_metrics.clone();
+        let origin = origin.clone();
         tokio::spawn(TaskMonitor::instrument(&task_monitor, async move {
+            let tx_id_receiver = Self::message_tx_id_receiver(message_sync).await;
+            let cursor =
+                match Self::instantiate_cursor_with_retries(contract_sync.clone(), index_settings)
+                    .await
+                {
+                    Ok(cursor) => cursor,
+                    Err(err) => {
+                        Self::record_critical_error(
+                            &chain_metrics,
+                            &origin,
+                            err,
+                            CURSOR_BUILDING_ERROR,
+                        );
+                        return;
+                    }
+                };
             let label = "merkle_tree_hook";
             contract_sync
                 .clone()
-                .sync(label, SyncOptions::new(Some(cursor), tx_id_receiver))
+                .sync(
+                    label,
+                    SyncOptions::new(Some(cursor), tx_id_receiver).with_rewinder(rewinder),
+                )
                 .await;
-            info!(chain = origin_name, label, "contract sync task exit");
+            info!(chain = origin.name(), label, "contract sync task exit");
         }))
         .instrument(info_span!("MerkleTreeHookSync"))
     }
@@ -543,6 +873,7 @@ impl Relayer {
         &self,
         origin: &HyperlaneDomain,
         send_channels: HashMap<u32, UnboundedSender<QueueOperation>>,
+        reinjected: UnboundedReceiver<HyperlaneMessage>,
         task_monitor: TaskMonitor,
     ) -> Instrumented<JoinHandle<()>> {
         let metrics = MessageProcessorMetrics::new(
@@ -570,10 +901,14 @@ impl Relayer {
             self.message_whitelist.clone(),
             self.message_blacklist.clone(),
             self.address_blacklist.clone(),
+            self.skipped_recipients.clone(),
             metrics,
             send_channels,
             destination_ctxs,
             self.metric_app_contexts.clone(),
+            self.ordering_keys.clone(),
+            self.in_flight_ordering_keys.clone(),
+            reinjected,
         );
 
         let span = info_span!("MessageProcessor", origin=%message_processor.domain());
@@ -582,6 +917,57 @@ impl Relayer {
         processor.spawn().instrument(span)
     }
 
+    fn route_simulator(&self) -> RouteSimulator {
+        let msg_ctxs = self
+            .msg_ctxs
+            .iter()
+            .map(|(key, ctx)| ((key.origin, key.destination), ctx.clone()))
+            .collect();
+        RouteSimulator::new(
+            msg_ctxs,
+            self.message_whitelist.clone(),
+            self.message_blacklist.clone(),
+            self.address_blacklist.clone(),
+            self.skipped_recipients.clone(),
+        )
+    }
+
+    fn dead_letter_queue(
+        &self,
+        reinject_channels: HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+    ) -> DeadLetterQueue {
+        let dbs = self
+            .dbs
+            .iter()
+            .map(|(domain, db)| (domain.id(), db.clone()))
+            .collect();
+        DeadLetterQueue::new(dbs, reinject_channels)
+    }
+
+    fn run_value_reencoding(
+        &self,
+        origin: &HyperlaneDomain,
+        migration: DbValueMigration,
+    ) -> Instrumented<JoinHandle<()>> {
+        let db = self.dbs[origin].clone();
+        tokio::task::spawn_blocking(move || {
+            match db.reencode_values() {
+                Ok(reencoded) => info!(reencoded, "Re-encoded database values"),
+                Err(err) => {
+                    warn!(?err, "Failed to re-encode database values");
+                    return;
+                }
+            }
+            if migration == DbValueMigration::Commit {
+                match db.commit_reencoded_values() {
+                    Ok(deleted) => info!(deleted, "Committed re-encoded database values"),
+                    Err(err) => warn!(?err, "Failed to commit re-encoded database values"),
+                }
+            }
+        })
+        .instrument(info_span!("ValueReencoding", origin=%origin))
+    }
+
     fn run_merkle_tree_processor(
         &self,
         origin: &HyperlaneDomain,
@@ -675,6 +1061,7 @@ mod test {
     use std::{
         collections::{HashMap, HashSet},
         path::PathBuf,
+        time::Duration,
     };
 
     use crate::settings::{matching_list::MatchingList, RelayerSettings};
@@ -745,11 +1132,18 @@ mod test {
                         gas_limit: None,
                         max_fee_per_gas: None,
                         max_priority_fee_per_gas: None,
+                        max_fee_per_gas_multiplier: None,
+                        max_priority_fee_per_gas_multiplier: None,
+                        max_fee_per_gas_cap: None,
+                        max_priority_fee_per_gas_cap: None,
                     },
                     operation_batch: OperationBatchConfig {
                         batch_contract_address: None,
                         max_batch_size: 1,
                     },
+                    log_pruning_fallback: false,
+                    rate_limit: None,
+                    custom_errors: Default::default(),
                 }),
                 metrics_conf: PrometheusMiddlewareConf {
                     contracts: HashMap::new(),
@@ -767,7 +1161,12 @@ mod test {
             base: Settings {
                 chains: chains.into_iter().collect(),
                 metrics_port: 5000,
+                metrics_push: None,
+                wallet_balance_watcher: None,
                 tracing: TracingConfig::default(),
+                block_height_watchers: Default::default(),
+                chain_statuses: Default::default(),
+                http: Default::default(),
             },
             db: PathBuf::new(),
             origin_chains: [
@@ -787,11 +1186,29 @@ mod test {
             gas_payment_enforcement: Vec::new(),
             whitelist: MatchingList::default(),
             blacklist: MatchingList::default(),
+            whitelist_source: None,
+            blacklist_source: None,
+            matching_list_reload_interval: Duration::from_secs(60),
             address_blacklist: Vec::new(),
+            sealevel_skipped_recipients: Vec::new(),
             transaction_gas_limit: None,
             skip_transaction_gas_limit_for: HashSet::new(),
+            gas_escalation: HashMap::new(),
+            fee_budgets: HashMap::new(),
+            metadata_chunking: HashMap::new(),
+            routing_ism_cache_for: HashSet::new(),
+            routing_ism_cache_ttl: Duration::from_secs(60),
+            ism_config_cache_for: HashSet::new(),
+            ism_config_cache_ttl: Duration::from_secs(60),
+            metadata_warm_up_ttl: None,
+            dead_letter_after_retries: None,
             allow_local_checkpoint_syncers: true,
             metric_app_contexts: Vec::new(),
+            ordering_keys: Vec::new(),
+            delivery_ttls: Vec::new(),
+            admin_api_token: None,
+            delivery_receipts: None,
+            db_value_migration: Default::default(),
         }
     }
 
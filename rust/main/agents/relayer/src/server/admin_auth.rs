@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header::AUTHORIZATION, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+
+/// Requires requests to `router` to carry `Authorization: Bearer <token>`.
+pub fn with_admin_auth(router: Router, token: Arc<String>) -> Router {
+    router.route_layer(middleware::from_fn_with_state(token, require_admin_token))
+}
+
+async fn require_admin_token<B>(
+    State(token): State<Arc<String>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()))
+        .unwrap_or(false);
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing;
+    use std::net::SocketAddr;
+
+    fn setup_test_server() -> SocketAddr {
+        let router = Router::new().route("/", routing::get(|| async { "ok" }));
+        let app = Router::new().nest(
+            "/admin",
+            with_admin_auth(router, Arc::new("secret".to_owned())),
+        );
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_admin_auth() {
+        let addr = setup_test_server();
+        let client = reqwest::Client::new();
+        let url = format!("http://{}/admin", addr);
+
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client.get(&url).bearer_auth("wrong").send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = client.get(&url).bearer_auth("secret").send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
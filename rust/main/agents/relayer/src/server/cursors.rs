@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing, Json, Router,
+};
+use derive_new::new;
+use hyperlane_base::{
+    db::{HyperlaneDb, HyperlaneRocksDB},
+    CursorRewinder,
+};
+use hyperlane_core::{HyperlaneWatermarkedLogStore, InterchainGasPayment};
+use serde::{Deserialize, Serialize};
+
+const CURSORS_API_BASE: &str = "/cursors";
+
+/// Rewinds the cursors of the running syncs of an origin chain.
+#[derive(Debug, Clone, Default)]
+pub struct OriginCursorRewinders {
+    pub messages: CursorRewinder,
+    pub merkle_tree_insertions: CursorRewinder,
+    pub gas_payments: CursorRewinder,
+}
+
+impl OriginCursorRewinders {
+    fn all(&self) -> [&CursorRewinder; 3] {
+        [
+            &self.messages,
+            &self.merkle_tree_insertions,
+            &self.gas_payments,
+        ]
+    }
+}
+
+/// Inspects the indexing state of origin chains and rewinds their cursors.
+///
+/// Rewinds apply to the running syncs of the origin, which index messages,
+/// merkle tree insertions and gas payments again from the requested block
+/// before indexing their next range.
+#[derive(new, Clone)]
+pub struct CursorsApi {
+    dbs: HashMap<u32, HyperlaneRocksDB>,
+    rewinders: HashMap<u32, OriginCursorRewinders>,
+}
+
+#[derive(Clone)]
+struct CursorsState {
+    dbs: HashMap<u32, HyperlaneRocksDB>,
+    rewinders: HashMap<u32, OriginCursorRewinders>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct InspectCursorsRequest {
+    origin_domain: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CursorsResponse {
+    /// The highest message nonce indexed from the origin
+    pub highest_seen_message_nonce: Option<u32>,
+    /// The block up to which gas payments have been indexed on the origin
+    pub gas_payment_high_watermark: Option<u32>,
+    /// The lowest block a cursor of the origin is about to be rewound to
+    pub pending_rewind_block: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ResetCursorsRequest {
+    origin_domain: u32,
+    /// The block to index messages, merkle tree insertions and gas payments
+    /// again from
+    block: u32,
+}
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn origin_db(
+    dbs: &HashMap<u32, HyperlaneRocksDB>,
+    origin_domain: u32,
+) -> Result<&HyperlaneRocksDB, (StatusCode, String)> {
+    dbs.get(&origin_domain).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("No database found for domain {}", origin_domain),
+        )
+    })
+}
+
+fn internal_error(err: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+fn origin_rewinders(
+    rewinders: &HashMap<u32, OriginCursorRewinders>,
+    origin_domain: u32,
+) -> Result<&OriginCursorRewinders, (StatusCode, String)> {
+    rewinders.get(&origin_domain).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("No syncs found for domain {}", origin_domain),
+        )
+    })
+}
+
+async fn read_cursors(
+    db: &HyperlaneRocksDB,
+    rewinders: &OriginCursorRewinders,
+) -> ApiResult<CursorsResponse> {
+    let highest_seen_message_nonce = db
+        .retrieve_highest_seen_message_nonce()
+        .map_err(internal_error)?;
+    let gas_payment_high_watermark =
+        HyperlaneWatermarkedLogStore::<InterchainGasPayment>::retrieve_high_watermark(db)
+            .await
+            .map_err(internal_error)?;
+    let pending_rewind_block = rewinders
+        .all()
+        .into_iter()
+        .filter_map(CursorRewinder::pending_block)
+        .min();
+    Ok(Json(CursorsResponse {
+        highest_seen_message_nonce,
+        gas_payment_high_watermark,
+        pending_rewind_block,
+    }))
+}
+
+async fn inspect_cursors(
+    State(state): State<CursorsState>,
+    Query(request): Query<InspectCursorsRequest>,
+) -> ApiResult<CursorsResponse> {
+    read_cursors(
+        origin_db(&state.dbs, request.origin_domain)?,
+        origin_rewinders(&state.rewinders, request.origin_domain)?,
+    )
+    .await
+}
+
+async fn reset_cursors(
+    State(state): State<CursorsState>,
+    Json(request): Json<ResetCursorsRequest>,
+) -> ApiResult<CursorsResponse> {
+    let db = origin_db(&state.dbs, request.origin_domain)?;
+    let rewinders = origin_rewinders(&state.rewinders, request.origin_domain)?;
+    tracing::info!(?request, "Rewinding cursors on request");
+    for rewinder in rewinders.all() {
+        rewinder.rewind_to_block(request.block);
+    }
+    read_cursors(db, rewinders).await
+}
+
+impl CursorsApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(inspect_cursors).post(reset_cursors))
+            .with_state(CursorsState {
+                dbs: self.dbs.clone(),
+                rewinders: self.rewinders.clone(),
+            })
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (CURSORS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperlane_base::db::DB;
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain};
+    use serde_json::json;
+    use std::net::SocketAddr;
+
+    const DUMMY_DOMAIN: KnownHyperlaneDomain = KnownHyperlaneDomain::Arbitrum;
+
+    fn setup_test_server(db: DB) -> (SocketAddr, OriginCursorRewinders) {
+        let domain = HyperlaneDomain::Known(DUMMY_DOMAIN);
+        let dbs = HashMap::from([(domain.id(), HyperlaneRocksDB::new(&domain, db))]);
+        let rewinders = OriginCursorRewinders::default();
+        let (path, router) =
+            CursorsApi::new(dbs, HashMap::from([(domain.id(), rewinders.clone())])).get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        (addr, rewinders)
+    }
+
+    #[tokio::test]
+    async fn test_inspect_and_reset_cursors() {
+        hyperlane_base::db::test_utils::run_test_db(|db| async move {
+            let (addr, rewinders) = setup_test_server(db);
+            let client = reqwest::Client::new();
+            let url = format!("http://{}{}", addr, CURSORS_API_BASE);
+
+            let response = client
+                .get(format!("{}?origin_domain={}", url, DUMMY_DOMAIN as u32))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.json::<CursorsResponse>().await.unwrap(),
+                CursorsResponse {
+                    highest_seen_message_nonce: None,
+                    gas_payment_high_watermark: None,
+                    pending_rewind_block: None,
+                }
+            );
+
+            let response = client
+                .post(&url)
+                .json(&json!({
+                    "origin_domain": DUMMY_DOMAIN as u32,
+                    "block": 100,
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response
+                    .json::<CursorsResponse>()
+                    .await
+                    .unwrap()
+                    .pending_rewind_block,
+                Some(100)
+            );
+            // Every sync of the origin is rewound
+            for rewinder in rewinders.all() {
+                assert_eq!(rewinder.pending_block(), Some(100));
+            }
+
+            let response = client
+                .get(format!("{}?origin_domain=1", url))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        })
+        .await;
+    }
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing, Json, Router,
+};
+use derive_new::new;
+use hyperlane_base::db::DeadLetter;
+use serde::Deserialize;
+
+use crate::{
+    msg::dead_letters::{DeadLetterImport, DeadLetterQueue, DeadLetterReinjection},
+    settings::matching_list::MatchingList,
+};
+
+const DEAD_LETTERS_API_BASE: &str = "/dead_letters";
+
+/// Lists, imports and re-injects the messages the relayer gave up on
+/// delivering after they exhausted their retries.
+///
+/// Listed dead letters are exported as JSON that can be attached to support
+/// escalations and imported into another relayer.
+#[derive(new, Clone)]
+pub struct DeadLettersApi {
+    dead_letters: Arc<DeadLetterQueue>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ListDeadLettersRequest {
+    origin_domain: Option<u32>,
+}
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn internal_error(err: impl std::fmt::Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+async fn list_dead_letters(
+    State(dead_letters): State<Arc<DeadLetterQueue>>,
+    Query(request): Query<ListDeadLettersRequest>,
+) -> ApiResult<Vec<DeadLetter>> {
+    dead_letters
+        .list(request.origin_domain)
+        .map(Json)
+        .map_err(internal_error)
+}
+
+async fn import_dead_letters(
+    State(dead_letters): State<Arc<DeadLetterQueue>>,
+    Json(imported): Json<Vec<DeadLetter>>,
+) -> ApiResult<DeadLetterImport> {
+    tracing::info!(count = imported.len(), "Importing dead letters on request");
+    dead_letters
+        .import(imported)
+        .map(Json)
+        .map_err(internal_error)
+}
+
+async fn reinject_dead_letters(
+    State(dead_letters): State<Arc<DeadLetterQueue>>,
+    Json(pattern): Json<MatchingList>,
+) -> ApiResult<DeadLetterReinjection> {
+    dead_letters
+        .reinject(&pattern)
+        .map(Json)
+        .map_err(internal_error)
+}
+
+impl DeadLettersApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_dead_letters))
+            .route("/import", routing::post(import_dead_letters))
+            .route("/reinject", routing::post(reinject_dead_letters))
+            .with_state(self.dead_letters.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DEAD_LETTERS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperlane_base::db::{DeadLetterReason, HyperlaneRocksDB, DB};
+    use hyperlane_core::{HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain};
+    use serde_json::json;
+    use std::{collections::HashMap, net::SocketAddr};
+    use tokio::sync::mpsc::{self, UnboundedSender};
+
+    const DUMMY_DOMAIN: KnownHyperlaneDomain = KnownHyperlaneDomain::Arbitrum;
+
+    fn setup_test_server(
+        db: DB,
+        reinject_channels: HashMap<u32, UnboundedSender<HyperlaneMessage>>,
+    ) -> SocketAddr {
+        let domain = HyperlaneDomain::Known(DUMMY_DOMAIN);
+        let dbs = HashMap::from([(domain.id(), HyperlaneRocksDB::new(&domain, db))]);
+        let dead_letters = DeadLetterQueue::new(dbs, reinject_channels);
+        let (path, router) = DeadLettersApi::new(Arc::new(dead_letters)).get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    fn dead_letter(origin: u32) -> DeadLetter {
+        let message = HyperlaneMessage {
+            origin,
+            destination: 1,
+            ..Default::default()
+        };
+        DeadLetter {
+            message_id: message.id(),
+            origin,
+            destination: 1,
+            nonce: 0,
+            message,
+            reason: DeadLetterReason::RetriesExhausted,
+            last_status: "FirstPrepareAttempt".to_owned(),
+            num_retries: 20,
+            timestamp: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_and_list_dead_letters() {
+        hyperlane_base::db::test_utils::run_test_db(|db| async move {
+            let addr = setup_test_server(db, HashMap::new());
+            let client = reqwest::Client::new();
+            let url = format!("http://{}{}", addr, DEAD_LETTERS_API_BASE);
+            let relayed = dead_letter(DUMMY_DOMAIN as u32);
+            let unknown_origin = dead_letter(2);
+
+            let response = client
+                .post(format!("{url}/import"))
+                .json(&[&relayed, &unknown_origin])
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.json::<DeadLetterImport>().await.unwrap(),
+                DeadLetterImport {
+                    imported: 1,
+                    rejected: 1
+                }
+            );
+
+            let response = client
+                .get(format!("{url}?origin_domain={}", DUMMY_DOMAIN as u32))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.json::<Vec<DeadLetter>>().await.unwrap(),
+                vec![relayed.clone()]
+            );
+
+            // The origin has no message processor, so the dead letter stays
+            let response = client
+                .post(format!("{url}/reinject"))
+                .json(&json!([{ "messageid": relayed.message_id }]))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.json::<DeadLetterReinjection>().await.unwrap(),
+                DeadLetterReinjection {
+                    evaluated: 1,
+                    reinjected: 0
+                }
+            );
+            let response = client.get(&url).send().await.unwrap();
+            assert_eq!(response.json::<Vec<DeadLetter>>().await.unwrap().len(), 1);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_reinject_dead_letters() {
+        hyperlane_base::db::test_utils::run_test_db(|db| async move {
+            let (reinject_channel, mut reinjected) = mpsc::unbounded_channel();
+            let addr =
+                setup_test_server(db, HashMap::from([(DUMMY_DOMAIN as u32, reinject_channel)]));
+            let client = reqwest::Client::new();
+            let url = format!("http://{}{}", addr, DEAD_LETTERS_API_BASE);
+            let matching = dead_letter(DUMMY_DOMAIN as u32);
+            let other_message = HyperlaneMessage {
+                nonce: 1,
+                ..matching.message.clone()
+            };
+            let other = DeadLetter {
+                message_id: other_message.id(),
+                nonce: 1,
+                message: other_message,
+                ..matching.clone()
+            };
+            client
+                .post(format!("{url}/import"))
+                .json(&[&matching, &other])
+                .send()
+                .await
+                .unwrap();
+
+            let response = client
+                .post(format!("{url}/reinject"))
+                .json(&json!([{ "messageid": matching.message_id }]))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.json::<DeadLetterReinjection>().await.unwrap(),
+                DeadLetterReinjection {
+                    evaluated: 2,
+                    reinjected: 1
+                }
+            );
+            // The matching dead letter was handed to the message processor,
+            // which removes it from the queue once it was sent on
+            assert_eq!(reinjected.try_recv().unwrap(), matching.message);
+            assert!(reinjected.try_recv().is_err());
+            let response = client.get(&url).send().await.unwrap();
+            assert_eq!(response.json::<Vec<DeadLetter>>().await.unwrap().len(), 2);
+        })
+        .await;
+    }
+}
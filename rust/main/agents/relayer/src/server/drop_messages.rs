@@ -0,0 +1,188 @@
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+use axum::{extract::State, routing, Json, Router};
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    msg::{dead_letters::DeadLetterQueue, op_queue::OperationPriorityQueue},
+    settings::matching_list::MatchingList,
+};
+
+const DROP_MESSAGES_API_BASE: &str = "/drop_messages";
+
+/// Removes operations matching a pattern from the prepare queues.
+///
+/// Dropped messages are moved to the dead-letter queue, so that they aren't
+/// picked up again when the relayer restarts and can be re-injected later.
+#[derive(new, Clone)]
+pub struct DropMessagesApi {
+    op_queues: HashMap<u32, OperationPriorityQueue>,
+    dead_letters: Arc<DeadLetterQueue>,
+}
+
+#[derive(Clone)]
+struct DropMessagesState {
+    op_queues: HashMap<u32, OperationPriorityQueue>,
+    dead_letters: Arc<DeadLetterQueue>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct DropMessagesResponse {
+    /// how many pending operations were evaluated
+    pub evaluated: usize,
+    /// how many of the pending operations matched the pattern and were dropped
+    pub dropped: usize,
+}
+
+async fn drop_messages(
+    State(state): State<DropMessagesState>,
+    Json(pattern): Json<MatchingList>,
+) -> Json<DropMessagesResponse> {
+    let mut resp = DropMessagesResponse::default();
+    for queue in state.op_queues.values() {
+        let mut queue = queue.lock().await;
+        resp.evaluated += queue.len();
+        let (dropped, kept): (Vec<_>, Vec<_>) = queue
+            .drain()
+            .partition(|Reverse(op)| pattern.op_matches(op));
+        queue.extend(kept);
+
+        for Reverse(op) in dropped {
+            tracing::info!(id = ?op.id(), "Dropping operation on request");
+            if let Err(err) = state.dead_letters.store_dropped(
+                op.origin_domain_id(),
+                op.id(),
+                op.status().to_string(),
+            ) {
+                tracing::warn!(?err, id = ?op.id(), "Failed to move dropped operation to the dead-letter queue");
+            }
+            op.decrement_metric_if_exists();
+            resp.dropped += 1;
+        }
+    }
+    Json(resp)
+}
+
+impl DropMessagesApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::post(drop_messages))
+            .with_state(DropMessagesState {
+                op_queues: self.op_queues.clone(),
+                dead_letters: self.dead_letters.clone(),
+            })
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (DROP_MESSAGES_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::msg::op_queue::{
+        test::{dummy_metrics_and_label, MockPendingOperation},
+        OpQueue,
+    };
+
+    use super::*;
+    use axum::http::StatusCode;
+    use hyperlane_base::db::{DeadLetterReason, HyperlaneDb, HyperlaneRocksDB, DB};
+    use hyperlane_core::{
+        HyperlaneDomain, HyperlaneMessage, KnownHyperlaneDomain, PendingOperationStatus,
+        QueueOperation, UndeliverableReason,
+    };
+    use serde_json::json;
+    use std::net::SocketAddr;
+    use tokio::sync::{self, Mutex};
+
+    const DUMMY_DOMAIN: KnownHyperlaneDomain = KnownHyperlaneDomain::Arbitrum;
+
+    fn setup_test_server(db: HyperlaneRocksDB) -> (SocketAddr, OperationPriorityQueue) {
+        let (metrics, queue_metrics_label) = dummy_metrics_and_label();
+        let broadcaster = sync::broadcast::Sender::new(100);
+        let op_queue = OpQueue::new(
+            metrics,
+            queue_metrics_label,
+            Arc::new(Mutex::new(broadcaster.subscribe())),
+        );
+        let op_queues_map = HashMap::from([(1, op_queue.queue.clone())]);
+        let dbs = HashMap::from([(DUMMY_DOMAIN as u32, db)]);
+        let dead_letters = DeadLetterQueue::new(dbs, HashMap::new());
+
+        let (path, router) =
+            DropMessagesApi::new(op_queues_map, Arc::new(dead_letters)).get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+
+        (addr, op_queue.queue.clone())
+    }
+
+    #[tokio::test]
+    async fn test_drop_message_by_id() {
+        hyperlane_base::db::test_utils::run_test_db(|db: DB| async move {
+            let db = HyperlaneRocksDB::new(&HyperlaneDomain::Known(DUMMY_DOMAIN), db);
+            let (addr, op_queue) = setup_test_server(db.clone());
+            let message = HyperlaneMessage {
+                origin: DUMMY_DOMAIN as u32,
+                ..Default::default()
+            };
+            let other_message = HyperlaneMessage {
+                nonce: 1,
+                ..message.clone()
+            };
+            for message in [&message, &other_message] {
+                db.store_message(message, 0).unwrap();
+                let op = Box::new(MockPendingOperation::with_message_data(message.clone()))
+                    as QueueOperation;
+                op_queue.lock().await.push(Reverse(op));
+            }
+
+            let response = reqwest::Client::new()
+                .post(format!("http://{}{}", addr, DROP_MESSAGES_API_BASE))
+                .json(&json!([{ "messageid": message.id() }]))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let resp: DropMessagesResponse = response.json().await.unwrap();
+            assert_eq!(
+                resp,
+                DropMessagesResponse {
+                    evaluated: 2,
+                    dropped: 1
+                }
+            );
+            let queue = op_queue.lock().await;
+            assert_eq!(queue.len(), 1);
+            assert_eq!(queue.peek().unwrap().0.id(), other_message.id());
+
+            // The dropped message is moved to the dead-letter queue, so it
+            // isn't picked up again on restart
+            let dead_letter = db
+                .retrieve_dead_letter_by_message_id(&message.id())
+                .unwrap()
+                .unwrap();
+            assert_eq!(dead_letter.reason, DeadLetterReason::DroppedOnRequest);
+            assert_eq!(dead_letter.message, message);
+            assert_eq!(
+                db.retrieve_status_by_message_id(&message.id()).unwrap(),
+                Some(PendingOperationStatus::Undeliverable(
+                    UndeliverableReason::DeadLettered
+                ))
+            );
+            assert!(db
+                .retrieve_dead_letter_by_message_id(&other_message.id())
+                .unwrap()
+                .is_none());
+        })
+        .await;
+    }
+}
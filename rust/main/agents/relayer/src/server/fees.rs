@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing, Json, Router};
+use derive_new::new;
+
+use crate::msg::fee_tracker::{FeeTotals, FeeTracker};
+
+const FEES_API_BASE: &str = "/fees";
+
+/// Serves the fees paid on Cosmos destinations, since the relayer started and
+/// within trailing windows.
+#[derive(new, Clone)]
+pub struct FeesApi {
+    fee_trackers: Vec<Arc<FeeTracker>>,
+}
+
+async fn list_fees(State(fee_trackers): State<Vec<Arc<FeeTracker>>>) -> Json<Vec<FeeTotals>> {
+    Json(
+        fee_trackers
+            .iter()
+            .map(|tracker| tracker.totals())
+            .collect(),
+    )
+}
+
+impl FeesApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::get(list_fees))
+            .with_state(self.fee_trackers.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (FEES_API_BASE, self.router())
+    }
+}
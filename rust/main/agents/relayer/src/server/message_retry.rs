@@ -1,6 +1,7 @@
 use crate::{msg::op_submitter::SUBMITTER_QUEUE_COUNT, settings::matching_list::MatchingList};
 use axum::{extract::State, routing, Json, Router};
 use derive_new::new;
+use hyperlane_core::ReprepareReason;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast::Sender, mpsc};
 
@@ -16,6 +17,8 @@ pub struct MessageRetryApi {
 pub struct MessageRetryRequest {
     pub uuid: String,
     pub pattern: MatchingList,
+    /// Only retry operations waiting to be reprepared for one of these reasons
+    pub reprepare_reasons: Option<Vec<ReprepareReason>>,
     pub transmitter: mpsc::Sender<MessageRetryQueueResponse>,
 }
 
@@ -57,6 +60,7 @@ async fn retry_message(
         .send(MessageRetryRequest {
             uuid: uuid_string.clone(),
             pattern: retry_req_payload,
+            reprepare_reasons: None,
             transmitter,
         })
         .map_err(|err| {
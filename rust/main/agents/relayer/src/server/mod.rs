@@ -1,17 +1,34 @@
 use axum::Router;
 use derive_new::new;
-use std::collections::HashMap;
+use hyperlane_base::db::HyperlaneRocksDB;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::broadcast::Sender;
 
-use crate::msg::op_queue::OperationPriorityQueue;
+use crate::msg::{
+    dead_letters::DeadLetterQueue, fee_tracker::FeeTracker, op_queue::OperationPriorityQueue,
+    route_simulation::RouteSimulator, skipped_recipients::SkippedRecipients,
+};
 
 pub const ENDPOINT_MESSAGES_QUEUE_SIZE: usize = 100;
 
+pub use cursors::*;
+pub use dead_letters::*;
+pub use drop_messages::*;
+pub use fees::*;
 pub use list_messages::*;
 pub use message_retry::*;
+pub use simulate_route::*;
+pub use skipped_recipients::*;
 
+mod admin_auth;
+mod cursors;
+mod dead_letters;
+mod drop_messages;
+mod fees;
 mod list_messages;
 mod message_retry;
+mod simulate_route;
+mod skipped_recipients;
 
 #[derive(new)]
 pub struct Server {
@@ -20,6 +37,20 @@ pub struct Server {
     retry_transmitter: Option<Sender<MessageRetryRequest>>,
     #[new(default)]
     op_queues: Option<HashMap<u32, OperationPriorityQueue>>,
+    #[new(default)]
+    dbs: Option<HashMap<u32, HyperlaneRocksDB>>,
+    #[new(default)]
+    cursor_rewinders: Option<HashMap<u32, OriginCursorRewinders>>,
+    #[new(default)]
+    fee_trackers: Option<Vec<Arc<FeeTracker>>>,
+    #[new(default)]
+    skipped_recipients: Option<Arc<SkippedRecipients>>,
+    #[new(default)]
+    route_simulator: Option<Arc<RouteSimulator>>,
+    #[new(default)]
+    dead_letters: Option<Arc<DeadLetterQueue>>,
+    #[new(default)]
+    admin_token: Option<String>,
 }
 
 impl Server {
@@ -33,6 +64,47 @@ impl Server {
         self
     }
 
+    pub fn with_dbs(mut self, dbs: HashMap<u32, HyperlaneRocksDB>) -> Self {
+        self.dbs = Some(dbs);
+        self
+    }
+
+    pub fn with_cursor_rewinders(
+        mut self,
+        cursor_rewinders: HashMap<u32, OriginCursorRewinders>,
+    ) -> Self {
+        self.cursor_rewinders = Some(cursor_rewinders);
+        self
+    }
+
+    pub fn with_fee_trackers(mut self, fee_trackers: Vec<Arc<FeeTracker>>) -> Self {
+        self.fee_trackers = Some(fee_trackers);
+        self
+    }
+
+    pub fn with_skipped_recipients(mut self, skipped_recipients: Arc<SkippedRecipients>) -> Self {
+        self.skipped_recipients = Some(skipped_recipients);
+        self
+    }
+
+    pub fn with_route_simulator(mut self, route_simulator: Arc<RouteSimulator>) -> Self {
+        self.route_simulator = Some(route_simulator);
+        self
+    }
+
+    pub fn with_dead_letters(mut self, dead_letters: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letters = Some(dead_letters);
+        self
+    }
+
+    /// Requires a bearer token for all relayer routes. Routes that drop
+    /// messages, reset cursors, skip recipients, simulate routes or manage
+    /// dead letters are only served when a token is set.
+    pub fn with_admin_token(mut self, admin_token: Option<String>) -> Self {
+        self.admin_token = admin_token;
+        self
+    }
+
     /// Returns a vector of agent-specific endpoint routes to be served.
     /// Can be extended with additional routes and feature flags to enable/disable individually.
     pub fn routes(self) -> Vec<(&'static str, Router)> {
@@ -41,9 +113,40 @@ impl Server {
             routes.push(MessageRetryApi::new(tx, self.destination_chains).get_route());
         }
         if let Some(op_queues) = self.op_queues {
-            routes.push(ListOperationsApi::new(op_queues).get_route());
+            routes.push(ListOperationsApi::new(op_queues.clone()).get_route());
+            if let (Some(dead_letters), Some(_)) = (&self.dead_letters, &self.admin_token) {
+                routes.push(DropMessagesApi::new(op_queues, dead_letters.clone()).get_route());
+            }
+        }
+        if let Some(fee_trackers) = self.fee_trackers {
+            routes.push(FeesApi::new(fee_trackers).get_route());
+        }
+        if let (Some(dbs), Some(cursor_rewinders), Some(_)) =
+            (self.dbs, self.cursor_rewinders, &self.admin_token)
+        {
+            routes.push(CursorsApi::new(dbs, cursor_rewinders).get_route());
+        }
+        if let (Some(skipped_recipients), Some(_)) = (self.skipped_recipients, &self.admin_token) {
+            routes.push(SkippedRecipientsApi::new(skipped_recipients).get_route());
+        }
+        if let (Some(route_simulator), Some(_)) = (self.route_simulator, &self.admin_token) {
+            routes.push(SimulateRouteApi::new(route_simulator).get_route());
+        }
+        if let (Some(dead_letters), Some(_)) = (self.dead_letters, &self.admin_token) {
+            routes.push(DeadLettersApi::new(dead_letters).get_route());
         }
 
-        routes
+        match self.admin_token {
+            Some(token) => {
+                let token = Arc::new(token);
+                routes
+                    .into_iter()
+                    .map(|(path, router)| {
+                        (path, admin_auth::with_admin_auth(router, token.clone()))
+                    })
+                    .collect()
+            }
+            None => routes,
+        }
     }
 }
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, routing, Json, Router};
+use derive_new::new;
+use ethers::utils::hex;
+use hyperlane_core::{utils::hex_or_base58_to_h256, HyperlaneMessage, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::msg::route_simulation::{RouteSimulationReport, RouteSimulator};
+
+const SIMULATE_ROUTE_API_BASE: &str = "/simulate_route";
+
+/// Simulates how the relayer would handle a hypothetical message, as a
+/// preflight check for apps onboarding to a route. Nothing is dispatched or
+/// submitted.
+#[derive(new, Clone)]
+pub struct SimulateRouteApi {
+    simulator: Arc<RouteSimulator>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateRouteRequest {
+    pub origin: u32,
+    pub destination: u32,
+    /// Hex or base58 address of the sender, zero if not set
+    #[serde(default)]
+    pub sender: Option<String>,
+    /// Hex or base58 address of the recipient
+    pub recipient: String,
+    /// Hex encoded message body
+    #[serde(default)]
+    pub body: String,
+    /// Nonce of the message, which only affects its id
+    #[serde(default)]
+    pub nonce: u32,
+}
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn parse_address(address: &str) -> Result<H256, (StatusCode, String)> {
+    hex_or_base58_to_h256(address).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid address {address}: {err}"),
+        )
+    })
+}
+
+impl TryFrom<SimulateRouteRequest> for HyperlaneMessage {
+    type Error = (StatusCode, String);
+
+    fn try_from(request: SimulateRouteRequest) -> Result<Self, Self::Error> {
+        let body = hex::decode(request.body.trim_start_matches("0x"))
+            .map_err(|err| (StatusCode::BAD_REQUEST, format!("Invalid body: {err}")))?;
+        Ok(HyperlaneMessage {
+            nonce: request.nonce,
+            origin: request.origin,
+            sender: request
+                .sender
+                .as_deref()
+                .map(parse_address)
+                .transpose()?
+                .unwrap_or_default(),
+            destination: request.destination,
+            recipient: parse_address(&request.recipient)?,
+            body,
+            ..Default::default()
+        })
+    }
+}
+
+async fn simulate_route(
+    State(simulator): State<Arc<RouteSimulator>>,
+    Json(request): Json<SimulateRouteRequest>,
+) -> ApiResult<RouteSimulationReport> {
+    let message = HyperlaneMessage::try_from(request)?;
+    tracing::info!(hyp_message=?message, "Simulating route on request");
+    Ok(Json(simulator.simulate(&message).await))
+}
+
+impl SimulateRouteApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", routing::post(simulate_route))
+            .with_state(self.simulator.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (SIMULATE_ROUTE_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        msg::{
+            blacklist::AddressBlacklist, matching_list_reloader::ReloadableMatchingList,
+            route_simulation::StepOutcome, skipped_recipients::SkippedRecipients,
+        },
+        settings::matching_list::MatchingList,
+    };
+    use serde_json::json;
+    use std::{collections::HashMap, net::SocketAddr};
+
+    fn setup_test_server() -> SocketAddr {
+        let simulator = RouteSimulator::new(
+            HashMap::new(),
+            ReloadableMatchingList::new(MatchingList::default()),
+            ReloadableMatchingList::new(MatchingList::default()),
+            Arc::new(AddressBlacklist::new(vec![])),
+            Arc::new(SkippedRecipients::new(vec![], vec![])),
+        );
+        let (path, router) = SimulateRouteApi::new(Arc::new(simulator)).get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_simulate_route() {
+        let addr = setup_test_server();
+        let url = format!("http://{}{}", addr, SIMULATE_ROUTE_API_BASE);
+        let recipient = H256::repeat_byte(1);
+
+        let response = reqwest::Client::new()
+            .post(&url)
+            .json(&json!({
+                "origin": 1,
+                "destination": 2,
+                "recipient": format!("{recipient:?}"),
+                "body": "0x0102",
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let report: RouteSimulationReport = response.json().await.unwrap();
+        let expected_message = HyperlaneMessage {
+            origin: 1,
+            destination: 2,
+            recipient,
+            body: vec![1, 2],
+            ..Default::default()
+        };
+        assert_eq!(report.message_id, expected_message.id());
+        assert!(!report.deliverable);
+        assert_eq!(report.steps[0].outcome, StepOutcome::Failed);
+
+        let response = reqwest::Client::new()
+            .post(&url)
+            .json(&json!({
+                "origin": 1,
+                "destination": 2,
+                "recipient": format!("{recipient:?}"),
+                "body": "not hex",
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
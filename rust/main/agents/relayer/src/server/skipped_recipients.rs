@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, routing, Json, Router};
+use derive_new::new;
+use hyperlane_core::{utils::hex_or_base58_to_h256, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::msg::skipped_recipients::SkippedRecipients;
+
+const SKIPPED_RECIPIENTS_API_BASE: &str = "/skipped_recipients";
+
+/// Inspects and updates the Sealevel recipient programs that messages aren't
+/// delivered to.
+///
+/// Updates are only kept in memory, so the configured list applies again
+/// when the relayer restarts.
+#[derive(new, Clone)]
+pub struct SkippedRecipientsApi {
+    skipped_recipients: Arc<SkippedRecipients>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSkippedRecipientsRequest {
+    /// Base58 or hex program ids to skip
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Base58 or hex program ids to deliver messages to again
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedRecipientsResponse {
+    /// The base58 ids of the skipped programs
+    pub program_ids: Vec<String>,
+}
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn parse_program_ids(program_ids: &[String]) -> Result<Vec<H256>, (StatusCode, String)> {
+    program_ids
+        .iter()
+        .map(|program_id| {
+            hex_or_base58_to_h256(program_id).map_err(|err| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid program id {program_id}: {err}"),
+                )
+            })
+        })
+        .collect()
+}
+
+fn list(skipped_recipients: &SkippedRecipients) -> Json<SkippedRecipientsResponse> {
+    Json(SkippedRecipientsResponse {
+        program_ids: skipped_recipients
+            .program_ids()
+            .iter()
+            .map(|program_id| bs58::encode(program_id.as_bytes()).into_string())
+            .collect(),
+    })
+}
+
+async fn list_skipped_recipients(
+    State(skipped_recipients): State<Arc<SkippedRecipients>>,
+) -> Json<SkippedRecipientsResponse> {
+    list(&skipped_recipients)
+}
+
+async fn update_skipped_recipients(
+    State(skipped_recipients): State<Arc<SkippedRecipients>>,
+    Json(request): Json<UpdateSkippedRecipientsRequest>,
+) -> ApiResult<SkippedRecipientsResponse> {
+    let add = parse_program_ids(&request.add)?;
+    let remove = parse_program_ids(&request.remove)?;
+    tracing::info!(?request, "Updating skipped recipients on request");
+    skipped_recipients.add(add);
+    skipped_recipients.remove(&remove);
+    Ok(list(&skipped_recipients))
+}
+
+impl SkippedRecipientsApi {
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(
+                "/",
+                routing::get(list_skipped_recipients).post(update_skipped_recipients),
+            )
+            .with_state(self.skipped_recipients.clone())
+    }
+
+    pub fn get_route(&self) -> (&'static str, Router) {
+        (SKIPPED_RECIPIENTS_API_BASE, self.router())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::net::SocketAddr;
+
+    const SEALEVEL_DOMAIN: u32 = 1399811149;
+
+    fn setup_test_server(skipped_recipients: Arc<SkippedRecipients>) -> SocketAddr {
+        let (path, router) = SkippedRecipientsApi::new(skipped_recipients).get_route();
+        let app = Router::new().nest(path, router);
+
+        // Running the app in the background using a test server
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_update_skipped_recipients() {
+        let configured = H256::repeat_byte(1);
+        let poisoned = H256::repeat_byte(2);
+        let skipped_recipients = Arc::new(SkippedRecipients::new([SEALEVEL_DOMAIN], [configured]));
+        let addr = setup_test_server(skipped_recipients.clone());
+        let url = format!("http://{}{}", addr, SKIPPED_RECIPIENTS_API_BASE);
+        let base58 = |program_id: H256| bs58::encode(program_id.as_bytes()).into_string();
+
+        let response = reqwest::Client::new()
+            .post(&url)
+            .json(&json!({ "add": [base58(poisoned)], "remove": [format!("{configured:?}")] }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response: SkippedRecipientsResponse = response.json().await.unwrap();
+        assert_eq!(response.program_ids, vec![base58(poisoned)]);
+        assert_eq!(skipped_recipients.program_ids(), vec![poisoned]);
+
+        let response = reqwest::Client::new()
+            .post(&url)
+            .json(&json!({ "add": ["not a program id"] }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response: SkippedRecipientsResponse =
+            reqwest::get(&url).await.unwrap().json().await.unwrap();
+        assert_eq!(response.program_ids, vec![base58(poisoned)]);
+    }
+}
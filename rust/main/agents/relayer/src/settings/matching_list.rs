@@ -3,15 +3,19 @@
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
 use std::{
+    convert::Infallible,
     fmt,
     fmt::{Debug, Display, Formatter},
     marker::PhantomData,
+    path::PathBuf,
+    str::FromStr,
 };
 
 use derive_new::new;
 use hyperlane_core::{
     config::StrOrInt, utils::hex_or_base58_to_h256, HyperlaneMessage, QueueOperation, H256,
 };
+use reqwest::Url;
 use serde::{
     de::{Error, SeqAccess, Visitor},
     Deserialize, Deserializer,
@@ -353,6 +357,41 @@ impl Display for MatchingList {
     }
 }
 
+/// Where a matching list that is reloaded at runtime is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchingListSource {
+    /// Fetched with a GET request to an http(s) URL
+    Url(Url),
+    /// Read from a local file
+    File(PathBuf),
+}
+
+impl FromStr for MatchingListSource {
+    type Err = Infallible;
+
+    /// `http://` and `https://` URLs are fetched remotely, `file://` URLs and
+    /// anything else are treated as a local file path.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Url::parse(s) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(Self::Url(url)),
+            Ok(url) if url.scheme() == "file" => Ok(url
+                .to_file_path()
+                .map(Self::File)
+                .unwrap_or_else(|_| Self::File(PathBuf::from(url.path())))),
+            _ => Ok(Self::File(PathBuf::from(s))),
+        }
+    }
+}
+
+impl Display for MatchingListSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url(url) => write!(f, "{url}"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
 fn to_serde_err<IE: ToString, OE: Error>(e: IE) -> OE {
     OE::custom(e.to_string())
 }
@@ -4,7 +4,12 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+    path::PathBuf,
+    time::Duration,
+};
 
 use convert_case::Case;
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
@@ -14,15 +19,18 @@ use hyperlane_base::{
     impl_loadable_from_settings,
     settings::{
         parser::{recase_json_value, RawAgentConf, ValueParser},
-        Settings,
+        ChainConnectionConf, Settings,
     },
 };
-use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain, U256};
+use hyperlane_core::{
+    cfg_unwrap_all, config::*, utils::hex_or_base58_to_h256, HyperlaneDomain, H256, U256,
+};
 use itertools::Itertools;
+use reqwest::Url;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::settings::matching_list::MatchingList;
+use crate::settings::matching_list::{MatchingList, MatchingListSource};
 
 pub mod matching_list;
 
@@ -47,20 +55,88 @@ pub struct RelayerSettings {
     pub whitelist: MatchingList,
     /// Filter for what messages to block.
     pub blacklist: MatchingList,
+    /// Optional URL or file path the whitelist is periodically reloaded from.
+    /// Once loaded, it replaces the static `whitelist`.
+    pub whitelist_source: Option<MatchingListSource>,
+    /// Optional URL or file path the blacklist is periodically reloaded from.
+    /// Once loaded, it replaces the static `blacklist`.
+    pub blacklist_source: Option<MatchingListSource>,
+    /// How often the whitelist and blacklist sources are reloaded.
+    pub matching_list_reload_interval: Duration,
     /// Filter for what addresses to block interactions with.
     /// This is intentionally not an H256 to allow for addresses of any length without
     /// adding any padding.
     pub address_blacklist: Vec<Vec<u8>>,
+    /// Sealevel recipient program ids whose messages aren't delivered, e.g.
+    /// programs that panic in `handle`.
+    pub sealevel_skipped_recipients: Vec<H256>,
     /// This is optional. If not specified, any amount of gas will be valid, otherwise this
     /// is the max allowed gas in wei to relay a transaction.
     pub transaction_gas_limit: Option<U256>,
     /// List of domain ids to skip transaction gas for.
     pub skip_transaction_gas_limit_for: HashSet<u32>,
+    /// Gas escalation policies, keyed by destination domain id. Destinations
+    /// without a policy are retried indefinitely without escalating gas.
+    pub gas_escalation: HashMap<u32, GasEscalationConf>,
+    /// Budgets for the fees paid on Cosmos destinations, keyed by destination
+    /// domain id. Exceeding a budget is logged as a warning.
+    pub fee_budgets: HashMap<u32, Vec<FeeBudgetConf>>,
+    /// Metadata chunking configs, keyed by destination domain id. Only for
+    /// destinations whose ISMs support pointer-based metadata.
+    pub metadata_chunking: HashMap<u32, MetadataChunkingConf>,
+    /// Destination domain ids to cache routing ISM routes for. Only suitable
+    /// for destinations whose routing ISMs route on the message origin alone.
+    pub routing_ism_cache_for: HashSet<u32>,
+    /// How long a cached routing ISM route is used before it is looked up again.
+    pub routing_ism_cache_ttl: Duration,
+    /// Destination domain ids to cache ISM types and the validators or modules
+    /// and thresholds of multisig and aggregation ISMs for. Only suitable for
+    /// destinations whose ISMs are configured per message origin alone.
+    pub ism_config_cache_for: HashSet<u32>,
+    /// How long a cached ISM configuration is used before it is looked up again.
+    pub ism_config_cache_ttl: Duration,
+    /// If set, the metadata of messages that only fail the gas payment policy
+    /// is kept for this long, and such messages are rechecked every 10
+    /// seconds, so that they can be submitted as soon as their gas payment is
+    /// topped up.
+    pub metadata_warm_up_ttl: Option<Duration>,
+    /// If set, messages retried more often than this are moved to the
+    /// dead-letter queue instead of being retried forever.
+    pub dead_letter_after_retries: Option<u32>,
     /// If true, allows local storage based checkpoint syncers.
     /// Not intended for production use.
     pub allow_local_checkpoint_syncers: bool,
     /// App contexts used for metrics.
     pub metric_app_contexts: Vec<(MatchingList, String)>,
+    /// Opt-in ordering keys. Messages sharing a key are delivered one at a
+    /// time, in dispatch order.
+    pub ordering_keys: Vec<OrderingKeyConf>,
+    /// Messages matching a policy that are older than its max age or past the
+    /// deadline encoded in their body are moved to the dead-letter queue
+    /// instead of being delivered.
+    pub delivery_ttls: Vec<DeliveryTtlConf>,
+    /// Bearer token required by the relayer's HTTP API. Endpoints that drop
+    /// messages or reset cursors are only served if this is set.
+    pub admin_api_token: Option<String>,
+    /// If set, receipts of delivered messages are exported to this sink.
+    pub delivery_receipts: Option<DeliveryReceiptsConf>,
+    /// What to do on startup with database values stored in outdated
+    /// encodings.
+    pub db_value_migration: DbValueMigration,
+}
+
+/// What to do on startup with database values stored in outdated encodings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DbValueMigration {
+    /// Re-encode outdated values, keeping their previous encoding so that the
+    /// migration can be rolled back
+    #[default]
+    Migrate,
+    /// Re-encode outdated values and delete their previous encoding
+    Commit,
+    /// Rewrite all values in the encoding of relayers from before the
+    /// migration, so that they can be downgraded to, and exit
+    Rollback,
 }
 
 /// Config for gas payment enforcement
@@ -89,6 +165,122 @@ pub enum GasPaymentEnforcementPolicy {
     },
 }
 
+/// Config for escalating gas when delivery to a destination keeps failing
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasEscalationConf {
+    /// Number of retries after which a message is dropped. If not specified,
+    /// messages are retried indefinitely.
+    pub max_retries: Option<u32>,
+    /// Factor the gas limit is multiplied by for every failed submission of
+    /// a message.
+    pub multiplier: f64,
+    /// Maximum fee, in the smallest unit of the destination's native token,
+    /// that may be spent on a single delivery transaction.
+    pub max_fee: Option<U256>,
+}
+
+/// Budget for the fees paid on a destination within a trailing window
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeBudgetConf {
+    /// Length of the trailing window
+    pub window: Duration,
+    /// Maximum fees, in the smallest unit of the destination's fee denom,
+    /// expected to be paid within the window.
+    pub max_fee: f64,
+}
+
+/// Config for storing metadata too large to be submitted in a single
+/// transaction in chunks, ahead of processing the message with a pointer to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataChunkingConf {
+    /// Address of the metadata chunk store on the destination
+    pub store: H256,
+    /// Metadata longer than this many bytes is stored in chunks
+    pub threshold: usize,
+    /// Maximum size of a chunk in bytes
+    pub chunk_size: usize,
+}
+
+/// Config for deriving the ordering key of messages
+#[derive(Debug, Clone)]
+pub struct OrderingKeyConf {
+    /// Messages matching this list are ordered by this key
+    pub matching_list: MatchingList,
+    /// Where the key is read from
+    pub source: OrderingKeySource,
+}
+
+/// Where the ordering key of a message is read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderingKeySource {
+    /// The message sender
+    Sender,
+    /// A byte range of the message body
+    Body { offset: usize, length: usize },
+}
+
+/// Config for dropping messages that are too old to be delivered
+#[derive(Debug, Clone)]
+pub struct DeliveryTtlConf {
+    /// Messages matching this list are subject to the policy
+    pub matching_list: MatchingList,
+    /// Messages dispatched longer ago than this are dropped
+    pub max_age: Option<Duration>,
+    /// Where the message body encodes a unix timestamp in seconds after which
+    /// the message is dropped
+    pub deadline: Option<BodyDeadline>,
+}
+
+/// A big-endian unsigned integer at a byte range of the message body
+#[derive(Debug, Clone, PartialEq)]
+pub struct BodyDeadline {
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Config for exporting receipts of delivered messages
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryReceiptsConf {
+    /// Where receipts are exported to
+    pub sink: DeliveryReceiptSink,
+    /// Maximum number of receipts exported at once
+    pub batch_size: u64,
+}
+
+/// Where delivery receipts are exported to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryReceiptSink {
+    /// Receipts are appended to a file, one JSON object per line
+    File(PathBuf),
+    /// Receipts are POSTed to a URL as a JSON array
+    Http(Url),
+    /// Receipts are produced to a Kafka topic via a Kafka REST proxy, keyed by
+    /// message id
+    Kafka { rest_proxy: Url, topic: String },
+}
+
+impl Display for DeliveryReceiptSink {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryReceiptSink::File(path) => write!(f, "{}", path.display()),
+            DeliveryReceiptSink::Http(url) => write!(f, "{url}"),
+            DeliveryReceiptSink::Kafka { rest_proxy, topic } => {
+                write!(f, "kafka topic {topic} via {rest_proxy}")
+            }
+        }
+    }
+}
+
+impl Default for GasEscalationConf {
+    fn default() -> Self {
+        Self {
+            max_retries: None,
+            multiplier: 1.0,
+            max_fee: None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(transparent)]
 struct RawRelayerSettings(Value);
@@ -230,6 +422,29 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .and_then(parse_matching_list)
             .unwrap_or_default();
 
+        let whitelist_source = p
+            .chain(&mut err)
+            .get_opt_key("whitelistSource")
+            .parse_from_str("Expected whitelist URL or file path")
+            .end();
+        let blacklist_source = p
+            .chain(&mut err)
+            .get_opt_key("blacklistSource")
+            .parse_from_str("Expected blacklist URL or file path")
+            .end();
+        let matching_list_reload_interval = p
+            .chain(&mut err)
+            .get_opt_key("matchingListReloadInterval")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60));
+        if matching_list_reload_interval.is_zero() {
+            err.push(
+                &p.cwp + "matching_list_reload_interval",
+                eyre!("Matching list reload interval must be greater than zero"),
+            );
+        }
+
         let address_blacklist = p
             .chain(&mut err)
             .get_opt_key("addressBlacklist")
@@ -238,6 +453,16 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .map(|str| parse_address_list(str, &mut err, || &p.cwp + "address_blacklist"))
             .unwrap_or_default();
 
+        let sealevel_skipped_recipients = p
+            .chain(&mut err)
+            .get_opt_key("sealevelSkippedRecipients")
+            .parse_string()
+            .end()
+            .map(|str| {
+                parse_program_id_list(str, &mut err, || &p.cwp + "sealevel_skipped_recipients")
+            })
+            .unwrap_or_default();
+
         let transaction_gas_limit = p
             .chain(&mut err)
             .get_opt_key("transactionGasLimit")
@@ -251,12 +476,144 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .map(|v| v.split(',').collect())
             .unwrap_or_default();
 
+        let routing_ism_cache_for_names: HashSet<&str> = p
+            .chain(&mut err)
+            .get_opt_key("routingIsmCacheFor")
+            .parse_string()
+            .map(|v| v.split(',').collect())
+            .unwrap_or_default();
+
+        let routing_ism_cache_ttl = p
+            .chain(&mut err)
+            .get_opt_key("routingIsmCacheTtl")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60 * 10));
+
+        let ism_config_cache_for_names: HashSet<&str> = p
+            .chain(&mut err)
+            .get_opt_key("ismConfigCacheFor")
+            .parse_string()
+            .map(|v| v.split(',').collect())
+            .unwrap_or_default();
+
+        let ism_config_cache_ttl = p
+            .chain(&mut err)
+            .get_opt_key("ismConfigCacheTtl")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60 * 10));
+
+        let metadata_warm_up_ttl = p
+            .chain(&mut err)
+            .get_opt_key("metadataWarmUpTtl")
+            .parse_u64()
+            .end()
+            .map(Duration::from_secs);
+
+        let dead_letter_after_retries = p
+            .chain(&mut err)
+            .get_opt_key("deadLetterAfterRetries")
+            .parse_u32()
+            .end();
+
         let allow_local_checkpoint_syncers = p
             .chain(&mut err)
             .get_opt_key("allowLocalCheckpointSyncers")
             .parse_bool()
             .unwrap_or(false);
 
+        let admin_api_token = p
+            .chain(&mut err)
+            .get_opt_key("adminApiToken")
+            .parse_string()
+            .end()
+            .map(|token| token.to_owned());
+        if admin_api_token
+            .as_deref()
+            .is_some_and(|token| token.trim().is_empty())
+        {
+            err.push(
+                &p.cwp + "admin_api_token",
+                eyre!("Admin API token must not be empty"),
+            );
+        }
+
+        let delivery_receipts = p
+            .chain(&mut err)
+            .get_opt_key("deliveryReceipts")
+            .end()
+            .and_then(|conf| parse_delivery_receipts_conf(conf, &mut err));
+
+        let db_value_migration = match p
+            .chain(&mut err)
+            .get_opt_key("dbValueMigration")
+            .parse_string()
+            .end()
+        {
+            None | Some("migrate") => DbValueMigration::Migrate,
+            Some("commit") => DbValueMigration::Commit,
+            Some("rollback") => DbValueMigration::Rollback,
+            Some(other) => {
+                err.push(
+                    &p.cwp + "db_value_migration",
+                    eyre!("Unknown database value migration `{other}`"),
+                );
+                DbValueMigration::Migrate
+            }
+        };
+
+        let raw_gas_escalation = p
+            .chain(&mut err)
+            .get_opt_key("gasEscalation")
+            .into_obj_iter()
+            .map(|policies| {
+                policies
+                    .map(|(chain, policy)| {
+                        let cwp = policy.cwp.clone();
+                        (chain, cwp, parse_gas_escalation_conf(policy, &mut err))
+                    })
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
+        let raw_fee_budgets = p
+            .chain(&mut err)
+            .get_opt_key("feeBudgets")
+            .into_obj_iter()
+            .map(|budgets| {
+                budgets
+                    .map(|(chain, budgets)| {
+                        let cwp = budgets.cwp.clone();
+                        let budgets = budgets
+                            .chain(&mut err)
+                            .into_array_iter()
+                            .map(|budgets| {
+                                budgets
+                                    .filter_map(|budget| parse_fee_budget_conf(budget, &mut err))
+                                    .collect_vec()
+                            })
+                            .unwrap_or_default();
+                        (chain, cwp, budgets)
+                    })
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
+        let raw_metadata_chunking = p
+            .chain(&mut err)
+            .get_opt_key("metadataChunking")
+            .into_obj_iter()
+            .map(|confs| {
+                confs
+                    .map(|(chain, conf)| {
+                        let cwp = conf.cwp.clone();
+                        (chain, cwp, parse_metadata_chunking_conf(conf, &mut err))
+                    })
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [base]);
 
         let skip_transaction_gas_limit_for = skip_transaction_gas_limit_for_names
@@ -270,6 +627,84 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             .map(|d| d.id())
             .collect();
 
+        let routing_ism_cache_for = routing_ism_cache_for_names
+            .into_iter()
+            .filter_map(|chain| {
+                base.lookup_domain(chain)
+                    .context("Missing configuration for a chain in `routingIsmCacheFor`")
+                    .into_config_result(|| cwp + "routing_ism_cache_for")
+                    .take_config_err(&mut err)
+            })
+            .map(|d| d.id())
+            .collect();
+
+        let ism_config_cache_for = ism_config_cache_for_names
+            .into_iter()
+            .filter_map(|chain| {
+                base.lookup_domain(chain)
+                    .context("Missing configuration for a chain in `ismConfigCacheFor`")
+                    .into_config_result(|| cwp + "ism_config_cache_for")
+                    .take_config_err(&mut err)
+            })
+            .map(|d| d.id())
+            .collect();
+
+        let gas_escalation = raw_gas_escalation
+            .into_iter()
+            .filter_map(|(chain, policy_cwp, policy)| {
+                let domain = base
+                    .lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `gasEscalation`")
+                    .into_config_result(|| policy_cwp)
+                    .take_config_err(&mut err)?;
+                Some((domain.id(), policy))
+            })
+            .collect();
+
+        let fee_budgets = raw_fee_budgets
+            .into_iter()
+            .filter_map(|(chain, budgets_cwp, budgets)| {
+                let domain = base
+                    .lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `feeBudgets`")
+                    .into_config_result(|| budgets_cwp.clone())
+                    .take_config_err(&mut err)?;
+                let is_cosmos = base
+                    .chain_setup(&domain)
+                    .is_ok_and(|conf| matches!(conf.connection, ChainConnectionConf::Cosmos(_)));
+                if !is_cosmos {
+                    Err::<(), eyre::Report>(eyre!(
+                        "Fee budgets are only supported on Cosmos chains"
+                    ))
+                    .take_err(&mut err, || budgets_cwp);
+                    return None;
+                }
+                Some((domain.id(), budgets))
+            })
+            .collect();
+
+        let metadata_chunking = raw_metadata_chunking
+            .into_iter()
+            .filter_map(|(chain, conf_cwp, conf)| {
+                let domain = base
+                    .lookup_domain(&chain)
+                    .context("Missing configuration for a chain in `metadataChunking`")
+                    .into_config_result(|| conf_cwp.clone())
+                    .take_config_err(&mut err)?;
+                let is_ethereum = base
+                    .chain_setup(&domain)
+                    .is_ok_and(|conf| matches!(conf.connection, ChainConnectionConf::Ethereum(_)));
+                if !is_ethereum {
+                    Err::<(), eyre::Report>(eyre!(
+                        "Metadata chunking is only supported on Ethereum chains"
+                    ))
+                    .take_err(&mut err, || conf_cwp);
+                    return None;
+                }
+                Some((domain.id(), conf?))
+            })
+            .collect();
+
         let relay_chains: HashSet<HyperlaneDomain> = relay_chain_names
             .unwrap_or_default()
             .into_iter()
@@ -307,6 +742,36 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             })
             .unwrap_or_default();
 
+        let (raw_ordering_keys_path, raw_ordering_keys) = p
+            .get_opt_key("orderingKeys")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "ordering_keys", Value::Array(vec![])));
+
+        let ordering_keys_parser = ValueParser::new(raw_ordering_keys_path, &raw_ordering_keys);
+        let ordering_keys = ordering_keys_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|conf| parse_ordering_key_conf(conf, &mut err))
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
+        let (raw_delivery_ttls_path, raw_delivery_ttls) = p
+            .get_opt_key("deliveryTtls")
+            .take_config_err_flat(&mut err)
+            .and_then(parse_json_array)
+            .unwrap_or_else(|| (&p.cwp + "delivery_ttls", Value::Array(vec![])));
+
+        let delivery_ttls_parser = ValueParser::new(raw_delivery_ttls_path, &raw_delivery_ttls);
+        let delivery_ttls = delivery_ttls_parser
+            .into_array_iter()
+            .map(|itr| {
+                itr.filter_map(|conf| parse_delivery_ttl_conf(conf, &mut err))
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+
         err.into_result(RelayerSettings {
             base,
             db,
@@ -315,15 +780,239 @@ impl FromRawConf<RawRelayerSettings> for RelayerSettings {
             gas_payment_enforcement,
             whitelist,
             blacklist,
+            whitelist_source,
+            blacklist_source,
+            matching_list_reload_interval,
             address_blacklist,
+            sealevel_skipped_recipients,
             transaction_gas_limit,
             skip_transaction_gas_limit_for,
+            gas_escalation,
+            fee_budgets,
+            metadata_chunking,
+            routing_ism_cache_for,
+            routing_ism_cache_ttl,
+            ism_config_cache_for,
+            ism_config_cache_ttl,
+            metadata_warm_up_ttl,
+            dead_letter_after_retries,
             allow_local_checkpoint_syncers,
             metric_app_contexts,
+            ordering_keys,
+            delivery_ttls,
+            admin_api_token,
+            delivery_receipts,
+            db_value_migration,
         })
     }
 }
 
+fn parse_delivery_ttl_conf(
+    p: ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<DeliveryTtlConf> {
+    let matching_list = p
+        .chain(err)
+        .get_opt_key("matchingList")
+        .and_then(parse_matching_list)
+        .unwrap_or_default();
+
+    let max_age = p
+        .chain(err)
+        .get_opt_key("maxAge")
+        .parse_u64()
+        .end()
+        .map(Duration::from_secs);
+
+    let deadline = match p.chain(err).get_opt_key("deadline").end() {
+        Some(deadline) => {
+            let offset = deadline
+                .chain(err)
+                .get_opt_key("offset")
+                .parse_u64()
+                .unwrap_or(0);
+            // Deadlines are usually ABI encoded `uint256`s
+            let length = deadline
+                .chain(err)
+                .get_opt_key("length")
+                .parse_u64()
+                .unwrap_or(32);
+            if !(1..=32).contains(&length) {
+                Err::<(), eyre::Report>(eyre!("Deadline length must be between 1 and 32 bytes"))
+                    .take_err(err, || &deadline.cwp + "length");
+                return None;
+            }
+            Some(BodyDeadline {
+                offset: offset as usize,
+                length: length as usize,
+            })
+        }
+        None => None,
+    };
+
+    if max_age.is_none() && deadline.is_none() {
+        Err::<(), eyre::Report>(eyre!("Delivery TTL must set a max age or a deadline"))
+            .take_err(err, || p.cwp.clone());
+        return None;
+    }
+
+    Some(DeliveryTtlConf {
+        matching_list,
+        max_age,
+        deadline,
+    })
+}
+
+fn parse_ordering_key_conf(
+    p: ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<OrderingKeyConf> {
+    let matching_list = p
+        .chain(err)
+        .get_key("matchingList")
+        .and_then(parse_matching_list)
+        .unwrap_or_default();
+
+    let source = match p.chain(err).get_key("source").parse_string().end()? {
+        "sender" => OrderingKeySource::Sender,
+        "body" => {
+            let offset = p.chain(err).get_opt_key("offset").parse_u64().unwrap_or(0);
+            let length = p.chain(err).get_key("length").parse_u64().end()?;
+            if length == 0 {
+                Err::<(), eyre::Report>(eyre!("Ordering key length must be greater than 0"))
+                    .take_err(err, || &p.cwp + "length");
+                return None;
+            }
+            OrderingKeySource::Body {
+                offset: offset as usize,
+                length: length as usize,
+            }
+        }
+        other => {
+            Err::<(), eyre::Report>(eyre!("Unknown ordering key source `{other}`"))
+                .take_err(err, || &p.cwp + "source");
+            return None;
+        }
+    };
+
+    Some(OrderingKeyConf {
+        matching_list,
+        source,
+    })
+}
+
+fn parse_gas_escalation_conf(p: ValueParser, err: &mut ConfigParsingError) -> GasEscalationConf {
+    let default = GasEscalationConf::default();
+
+    let max_retries = p.chain(err).get_opt_key("maxRetries").parse_u32().end();
+    let multiplier = p
+        .chain(err)
+        .get_opt_key("multiplier")
+        .parse_f64()
+        .unwrap_or(default.multiplier);
+    if multiplier < 1.0 {
+        Err::<(), eyre::Report>(eyre!("Gas escalation multiplier must be at least 1"))
+            .take_err(err, || &p.cwp + "multiplier");
+    }
+    let max_fee = p.chain(err).get_opt_key("maxFee").parse_u256().end();
+
+    GasEscalationConf {
+        max_retries,
+        multiplier,
+        max_fee,
+    }
+}
+
+fn parse_fee_budget_conf(p: ValueParser, err: &mut ConfigParsingError) -> Option<FeeBudgetConf> {
+    let window = p
+        .chain(err)
+        .get_key("window")
+        .parse_u64()
+        .end()
+        .map(Duration::from_secs);
+    if window.is_some_and(|window| window.is_zero()) {
+        Err::<(), eyre::Report>(eyre!("Fee budget window must not be empty"))
+            .take_err(err, || &p.cwp + "window");
+    }
+    let max_fee = p.chain(err).get_key("maxFee").parse_f64().end();
+    if max_fee.is_some_and(|max_fee| max_fee < 0.0) {
+        Err::<(), eyre::Report>(eyre!("Fee budget must not be negative"))
+            .take_err(err, || &p.cwp + "max_fee");
+    }
+
+    Some(FeeBudgetConf {
+        window: window?,
+        max_fee: max_fee?,
+    })
+}
+
+fn parse_metadata_chunking_conf(
+    p: ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<MetadataChunkingConf> {
+    let store = p.chain(err).get_key("store").parse_address_hash().end();
+    let threshold = p.chain(err).get_key("threshold").parse_u64().end();
+    let chunk_size = p.chain(err).get_key("chunkSize").parse_u64().end();
+    if chunk_size == Some(0) {
+        Err::<(), eyre::Report>(eyre!("Metadata chunk size must not be zero"))
+            .take_err(err, || &p.cwp + "chunk_size");
+        return None;
+    }
+
+    Some(MetadataChunkingConf {
+        store: store?,
+        threshold: threshold? as usize,
+        chunk_size: chunk_size? as usize,
+    })
+}
+
+fn parse_delivery_receipts_conf(
+    p: ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<DeliveryReceiptsConf> {
+    let sink = match p.chain(err).get_key("sink").parse_string().end()? {
+        "file" => DeliveryReceiptSink::File(PathBuf::from(
+            p.chain(err).get_key("path").parse_string().end()?,
+        )),
+        "http" => DeliveryReceiptSink::Http(
+            p.chain(err)
+                .get_key("url")
+                .parse_from_str::<Url>("Expected URL")
+                .end()?,
+        ),
+        "kafka" => DeliveryReceiptSink::Kafka {
+            rest_proxy: p
+                .chain(err)
+                .get_key("url")
+                .parse_from_str::<Url>("Expected Kafka REST proxy URL")
+                .end()?,
+            topic: p
+                .chain(err)
+                .get_key("topic")
+                .parse_string()
+                .end()?
+                .to_owned(),
+        },
+        other => {
+            Err::<(), eyre::Report>(eyre!("Unknown delivery receipt sink `{other}`"))
+                .take_err(err, || &p.cwp + "sink");
+            return None;
+        }
+    };
+    let batch_size = p
+        .chain(err)
+        .get_opt_key("batchSize")
+        .parse_u64()
+        .unwrap_or(100);
+    if batch_size == 0 {
+        Err::<(), eyre::Report>(eyre!("Delivery receipt batch size must be greater than 0"))
+            .take_err(err, || &p.cwp + "batch_size");
+        return None;
+    }
+
+    Some(DeliveryReceiptsConf { sink, batch_size })
+}
+
 fn parse_json_array(p: ValueParser) -> Option<(ConfigPath, Value)> {
     let mut err = ConfigParsingError::default();
 
@@ -376,6 +1065,19 @@ fn parse_address_list(
         .collect_vec()
 }
 
+/// Parses a comma-separated list of base58 or hex program ids
+fn parse_program_id_list(
+    str: &str,
+    err: &mut ConfigParsingError,
+    err_path: impl Fn() -> ConfigPath,
+) -> Vec<H256> {
+    str.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| hex_or_base58_to_h256(s).take_err(err, &err_path))
+        .collect_vec()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -408,4 +1110,22 @@ mod test {
         assert_eq!(res, vec![valid_address1, valid_address2]);
         assert!(!err.is_ok());
     }
+
+    #[test]
+    fn test_parse_program_id_list() {
+        let program_id = H256::random();
+        let base58 = bs58::encode(program_id.as_bytes()).into_string();
+
+        let input = format!("{base58}, {program_id:?},");
+        let mut err = ConfigParsingError::default();
+        let res = parse_program_id_list(&input, &mut err, ConfigPath::default);
+        assert_eq!(res, vec![program_id, program_id]);
+        assert!(err.is_ok());
+
+        let mut err = ConfigParsingError::default();
+        let res =
+            parse_program_id_list(&format!("{base58}, 0xaazz"), &mut err, ConfigPath::default);
+        assert_eq!(res, vec![program_id]);
+        assert!(!err.is_ok());
+    }
 }
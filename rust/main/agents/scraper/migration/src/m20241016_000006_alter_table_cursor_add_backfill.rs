@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230309_000003_create_table_cursor::Cursor;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Cursor::Table)
+                    .add_column(ColumnDef::new(BackfillCursor::BackfillFrom).big_unsigned())
+                    .add_column(ColumnDef::new(BackfillCursor::BackfillTo).big_unsigned())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .table(Cursor::Table)
+                    .name("cursor_domain_backfill_idx")
+                    .col(Cursor::Domain)
+                    .col(BackfillCursor::BackfillFrom)
+                    .col(BackfillCursor::BackfillTo)
+                    .index_type(IndexType::BTree)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Cursor::Table)
+                    .name("cursor_domain_backfill_idx")
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Cursor::Table)
+                    .drop_column(BackfillCursor::BackfillFrom)
+                    .drop_column(BackfillCursor::BackfillTo)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub enum BackfillCursor {
+    /// First block of the backfill range this cursor row belongs to. Null for
+    /// rows written by the live indexing cursor.
+    BackfillFrom,
+    /// Last block of the backfill range this cursor row belongs to. Null for
+    /// rows written by the live indexing cursor.
+    BackfillTo,
+}
@@ -8,12 +8,16 @@ use tokio::{sync::mpsc::Receiver as MpscReceiver, task::JoinHandle};
 use tracing::{info, info_span, instrument::Instrumented, trace, Instrument};
 
 use hyperlane_base::{
-    broadcast::BroadcastMpscSender, metrics::AgentMetrics, settings::IndexSettings, AgentMetadata,
-    BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
-    MetricsUpdater, SyncOptions,
+    broadcast::BroadcastMpscSender,
+    metrics::AgentMetrics,
+    settings::{ChainConf, IndexSettings},
+    AgentMetadata, BaseAgent, ChainMetrics, ContractSyncMetrics, ContractSyncer, CoreMetrics,
+    HyperlaneAgentCore, MetricsUpdater, SyncOptions,
 };
 
-use crate::{db::ScraperDb, settings::ScraperSettings, store::HyperlaneDbStore};
+use crate::{
+    backfill::Backfiller, db::ScraperDb, settings::ScraperSettings, store::HyperlaneDbStore,
+};
 
 /// A message explorer scraper agent
 #[derive(Debug, AsRef)]
@@ -22,6 +26,7 @@ pub struct Scraper {
     #[as_ref]
     core: HyperlaneAgentCore,
     contract_sync_metrics: Arc<ContractSyncMetrics>,
+    db: ScraperDb,
     scrapers: HashMap<u32, ChainScraper>,
     settings: ScraperSettings,
     core_metrics: Arc<CoreMetrics>,
@@ -66,6 +71,7 @@ impl BaseAgent for Scraper {
         Ok(Self {
             core,
             contract_sync_metrics,
+            db,
             scrapers,
             settings,
             core_metrics: metrics,
@@ -87,6 +93,15 @@ impl BaseAgent for Scraper {
         let server_task = server.run().instrument(info_span!("Relayer server"));
         tasks.push(server_task);
 
+        for domain in self.settings.backfill.ranges.keys() {
+            if !self.scrapers.contains_key(&domain.id()) {
+                tracing::error!(
+                    ?domain,
+                    "Not backfilling domain, its scraper could not be started"
+                );
+            }
+        }
+
         for scraper in self.scrapers.values() {
             let chain_conf = match self.settings.chain_setup(&scraper.domain) {
                 Ok(s) => s,
@@ -128,6 +143,14 @@ impl BaseAgent for Scraper {
                 }
             }
             tasks.push(metrics_updater.spawn());
+
+            match self.backfill(scraper, chain_conf).await {
+                Ok(Some(backfill_task)) => tasks.push(backfill_task),
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::error!(?err, ?scraper.domain, "Failed to start backfill");
+                }
+            }
         }
         if let Err(err) = try_join_all(tasks).await {
             tracing::error!(error = ?err, "Scraper task panicked");
@@ -185,6 +208,37 @@ impl Scraper {
         .instrument(info_span!("Scraper Tasks")))
     }
 
+    /// Spawn a task backfilling the configured historical block range of the
+    /// domain, if there is one. Backfill failures are logged but do not affect
+    /// live indexing, the backfill resumes from its checkpoint on restart.
+    async fn backfill(
+        &self,
+        scraper: &ChainScraper,
+        chain_conf: &ChainConf,
+    ) -> eyre::Result<Option<Instrumented<JoinHandle<()>>>> {
+        let Some(range) = self.settings.backfill.ranges.get(&scraper.domain) else {
+            return Ok(None);
+        };
+        let backfiller = Backfiller::new(
+            chain_conf,
+            *range,
+            &self.settings.backfill,
+            self.db.clone(),
+            scraper.store.clone(),
+            &self.core_metrics,
+        )
+        .await?;
+        let domain = scraper.domain.clone();
+        Ok(Some(
+            tokio::spawn(async move {
+                if let Err(err) = backfiller.run().await {
+                    tracing::error!(?err, "Backfill failed");
+                }
+            })
+            .instrument(info_span!("Backfill", chain=%domain.name())),
+        ))
+    }
+
     async fn build_chain_scraper(
         domain: &HyperlaneDomain,
         settings: &ScraperSettings,
@@ -367,9 +421,7 @@ mod test {
     use reqwest::Url;
 
     use hyperlane_base::{
-        settings::{
-            ChainConf, ChainConnectionConf, CoreContractAddresses, Settings, TracingConfig,
-        },
+        settings::{ChainConnectionConf, CoreContractAddresses, Settings, TracingConfig},
         BLOCK_HEIGHT_HELP, BLOCK_HEIGHT_LABELS, CRITICAL_ERROR_HELP, CRITICAL_ERROR_LABELS,
     };
     use hyperlane_core::{
@@ -426,11 +478,18 @@ mod test {
                         gas_limit: None,
                         max_fee_per_gas: None,
                         max_priority_fee_per_gas: None,
+                        max_fee_per_gas_multiplier: None,
+                        max_priority_fee_per_gas_multiplier: None,
+                        max_fee_per_gas_cap: None,
+                        max_priority_fee_per_gas_cap: None,
                     },
                     operation_batch: OperationBatchConfig {
                         batch_contract_address: None,
                         max_batch_size: 1,
                     },
+                    log_pruning_fallback: false,
+                    rate_limit: None,
+                    custom_errors: Default::default(),
                 }),
                 metrics_conf: PrometheusMiddlewareConf {
                     contracts: HashMap::new(),
@@ -448,10 +507,16 @@ mod test {
             base: Settings {
                 chains: chains.into_iter().collect(),
                 metrics_port: 5000,
+                metrics_push: None,
+                wallet_balance_watcher: None,
+                http: Default::default(),
                 tracing: TracingConfig::default(),
+                block_height_watchers: Default::default(),
+                chain_statuses: Default::default(),
             },
             db: String::new(),
             chains_to_scrape: vec![],
+            backfill: Default::default(),
         }
     }
 
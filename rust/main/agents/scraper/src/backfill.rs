@@ -0,0 +1,240 @@
+//! Backfilling of historical block ranges.
+//!
+//! The regular indexing cursors only move forward from the configured `index.from`
+//! height (or from where they left off). A backfill re-indexes an explicit,
+//! bounded block range per domain, e.g. to recover from a period where an RPC
+//! provider returned incomplete data. Progress is checkpointed in the `cursor`
+//! table, tagged with the backfill range, so an interrupted backfill resumes
+//! from the last fully indexed chunk on restart.
+
+use std::{collections::HashMap, ops::RangeInclusive, sync::Arc, time::Duration};
+
+use eyre::{eyre, Result};
+use futures::{stream, StreamExt};
+use tokio::{
+    sync::Mutex,
+    time::{interval, sleep, Interval, MissedTickBehavior},
+};
+use tracing::{debug, info, instrument, warn};
+
+use hyperlane_base::{settings::ChainConf, CoreMetrics};
+use hyperlane_core::{
+    Delivery, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, Indexer, InterchainGasPayment,
+    SequenceAwareIndexer,
+};
+
+use crate::{db::ScraperDb, store::HyperlaneDbStore};
+
+/// Number of times a single chunk is attempted before the backfill is aborted.
+const MAX_CHUNK_ATTEMPTS: u32 = 5;
+/// Time to wait between attempts of a failing chunk.
+const CHUNK_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// An inclusive range of blocks to backfill on a single domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillRange {
+    /// The first block to index
+    pub from_block: u32,
+    /// The last block to index
+    pub to_block: u32,
+}
+
+/// Settings for backfilling historical block ranges.
+#[derive(Debug, Clone)]
+pub struct BackfillSettings {
+    /// The block range to backfill for each domain
+    pub ranges: HashMap<HyperlaneDomain, BackfillRange>,
+    /// The maximum number of chunks indexed concurrently per domain
+    pub concurrency: usize,
+    /// The minimum time between starting two chunks on the same domain
+    pub chunk_interval: Duration,
+}
+
+impl Default for BackfillSettings {
+    fn default() -> Self {
+        Self {
+            ranges: HashMap::new(),
+            concurrency: 4,
+            chunk_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Re-indexes a historical block range of a single domain into the scraper
+/// database.
+#[derive(Debug)]
+pub struct Backfiller {
+    domain: HyperlaneDomain,
+    range: BackfillRange,
+    chunk_size: u32,
+    concurrency: usize,
+    chunk_interval: Duration,
+    db: ScraperDb,
+    store: HyperlaneDbStore,
+    message_indexer: Arc<dyn SequenceAwareIndexer<HyperlaneMessage>>,
+    delivery_indexer: Arc<dyn SequenceAwareIndexer<Delivery>>,
+    igp_indexer: Arc<dyn SequenceAwareIndexer<InterchainGasPayment>>,
+}
+
+impl Backfiller {
+    /// Build the indexers needed to backfill `range` on the chain described by
+    /// `chain_conf`.
+    pub async fn new(
+        chain_conf: &ChainConf,
+        range: BackfillRange,
+        settings: &BackfillSettings,
+        db: ScraperDb,
+        store: HyperlaneDbStore,
+        metrics: &CoreMetrics,
+    ) -> Result<Self> {
+        if range.from_block > range.to_block {
+            return Err(eyre!(
+                "Invalid backfill range for {}: fromBlock {} is greater than toBlock {}",
+                chain_conf.domain,
+                range.from_block,
+                range.to_block
+            ));
+        }
+        let message_indexer = chain_conf.build_message_indexer(metrics, true).await?;
+        let delivery_indexer = chain_conf.build_delivery_indexer(metrics, true).await?;
+        let igp_indexer = chain_conf
+            .build_interchain_gas_payment_indexer(metrics, true)
+            .await?;
+        Ok(Self {
+            domain: chain_conf.domain.clone(),
+            range,
+            chunk_size: chain_conf.index.chunk_size.max(1),
+            concurrency: settings.concurrency.max(1),
+            chunk_interval: settings.chunk_interval,
+            db,
+            store,
+            message_indexer: message_indexer.into(),
+            delivery_indexer: delivery_indexer.into(),
+            igp_indexer: igp_indexer.into(),
+        })
+    }
+
+    /// Index the whole backfill range, resuming from the last checkpoint if
+    /// there is one.
+    #[instrument(skip(self), fields(domain = self.domain.name(), range = ?self.range))]
+    pub async fn run(self) -> Result<()> {
+        let (from, to) = (self.range.from_block as u64, self.range.to_block as u64);
+        let checkpoint = self
+            .db
+            .retrieve_backfill_checkpoint(self.domain.id(), from, to)
+            .await?;
+        let start = match checkpoint {
+            Some(height) if height >= to => {
+                info!(height, "Backfill already completed");
+                return Ok(());
+            }
+            Some(height) => {
+                info!(height, "Resuming backfill from checkpoint");
+                height as u32 + 1
+            }
+            None => self.range.from_block,
+        };
+
+        let chunks = chunk_ranges(start, self.range.to_block, self.chunk_size);
+        info!(chunks = chunks.len(), start, "Starting backfill");
+
+        let mut ticker = interval(self.chunk_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let ticker = Mutex::new(ticker);
+
+        // `buffered` yields results in chunk order, so every completed chunk
+        // extends the contiguous indexed prefix and can be checkpointed.
+        let mut results = stream::iter(chunks)
+            .map(|range| self.index_chunk_with_retries(range, &ticker))
+            .buffered(self.concurrency);
+        while let Some(result) = results.next().await {
+            let range = result?;
+            self.db
+                .store_backfill_checkpoint(self.domain.id(), from, to, *range.end() as u64)
+                .await?;
+            debug!(?range, "Backfilled chunk");
+        }
+
+        info!("Backfill completed");
+        Ok(())
+    }
+
+    async fn index_chunk_with_retries(
+        &self,
+        range: RangeInclusive<u32>,
+        ticker: &Mutex<Interval>,
+    ) -> Result<RangeInclusive<u32>> {
+        let mut attempt = 1;
+        loop {
+            ticker.lock().await.tick().await;
+            match self.index_chunk(range.clone()).await {
+                Ok(stored) => {
+                    debug!(?range, stored, "Stored backfilled logs");
+                    return Ok(range);
+                }
+                Err(err) if attempt < MAX_CHUNK_ATTEMPTS => {
+                    warn!(?err, ?range, attempt, "Failed to backfill chunk, retrying");
+                    attempt += 1;
+                    sleep(CHUNK_RETRY_DELAY).await;
+                }
+                Err(err) => {
+                    return Err(err.wrap_err(format!(
+                        "Failed to backfill chunk {range:?} after {attempt} attempts"
+                    )))
+                }
+            }
+        }
+    }
+
+    async fn index_chunk(&self, range: RangeInclusive<u32>) -> Result<u32> {
+        let messages = self
+            .message_indexer
+            .fetch_logs_in_range(range.clone())
+            .await?;
+        let deliveries = self
+            .delivery_indexer
+            .fetch_logs_in_range(range.clone())
+            .await?;
+        let payments = self.igp_indexer.fetch_logs_in_range(range).await?;
+
+        let mut stored =
+            HyperlaneLogStore::<HyperlaneMessage>::store_logs(&self.store, &messages).await?;
+        stored += HyperlaneLogStore::<Delivery>::store_logs(&self.store, &deliveries).await?;
+        stored +=
+            HyperlaneLogStore::<InterchainGasPayment>::store_logs(&self.store, &payments).await?;
+        Ok(stored)
+    }
+}
+
+/// Split `from..=to` into consecutive inclusive ranges of at most `chunk_size`
+/// blocks.
+fn chunk_ranges(from: u32, to: u32, chunk_size: u32) -> Vec<RangeInclusive<u32>> {
+    let mut chunks = vec![];
+    let mut start = from;
+    while start <= to {
+        let end = start.saturating_add(chunk_size - 1).min(to);
+        chunks.push(start..=end);
+        if end == u32::MAX {
+            break;
+        }
+        start = end + 1;
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_chunk_ranges() {
+        assert_eq!(chunk_ranges(0, 9, 5), vec![0..=4, 5..=9]);
+        assert_eq!(chunk_ranges(10, 22, 5), vec![10..=14, 15..=19, 20..=22]);
+        assert_eq!(chunk_ranges(7, 7, 100), vec![7..=7]);
+        assert_eq!(chunk_ranges(8, 7, 100), Vec::<RangeInclusive<u32>>::new());
+        assert_eq!(
+            chunk_ranges(u32::MAX - 1, u32::MAX, 1),
+            vec![u32::MAX - 1..=u32::MAX - 1, u32::MAX..=u32::MAX]
+        );
+    }
+}
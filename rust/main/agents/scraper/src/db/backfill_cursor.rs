@@ -0,0 +1,60 @@
+use eyre::Result;
+use sea_orm::{prelude::*, ActiveValue, Insert, Order, QueryOrder, QuerySelect};
+use tracing::{debug, instrument};
+
+use crate::db::ScraperDb;
+
+use super::generated::cursor;
+
+impl ScraperDb {
+    /// Get the highest block of the given backfill range that has been fully
+    /// indexed, if any progress has been recorded for it.
+    #[instrument(skip(self))]
+    pub async fn retrieve_backfill_checkpoint(
+        &self,
+        domain: u32,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Option<u64>> {
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+        enum QueryAs {
+            Height,
+        }
+
+        let height = cursor::Entity::find()
+            .filter(cursor::Column::Domain.eq(domain))
+            .filter(cursor::Column::BackfillFrom.eq(from_block as i64))
+            .filter(cursor::Column::BackfillTo.eq(to_block as i64))
+            .order_by(cursor::Column::Height, Order::Desc)
+            .select_only()
+            .column_as(cursor::Column::Height, QueryAs::Height)
+            .into_values::<i64, QueryAs>()
+            .one(&self.0)
+            .await?
+            .map(|h| h as u64);
+        Ok(height)
+    }
+
+    /// Record that every block of the backfill range up to and including
+    /// `height` has been indexed.
+    #[instrument(skip(self))]
+    pub async fn store_backfill_checkpoint(
+        &self,
+        domain: u32,
+        from_block: u64,
+        to_block: u64,
+        height: u64,
+    ) -> Result<()> {
+        let model = cursor::ActiveModel {
+            id: ActiveValue::NotSet,
+            domain: ActiveValue::Set(domain as i32),
+            time_created: ActiveValue::NotSet,
+            height: ActiveValue::Set(height as i64),
+            backfill_from: ActiveValue::Set(Some(from_block as i64)),
+            backfill_to: ActiveValue::Set(Some(to_block as i64)),
+        };
+        debug!(?model, "Inserting backfill checkpoint");
+        Insert::one(model).exec(&self.0).await?;
+        Ok(())
+    }
+}
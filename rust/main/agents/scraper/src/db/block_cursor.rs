@@ -39,6 +39,9 @@ impl BlockCursor {
 
         let height = (cursor::Entity::find())
             .filter(cursor::Column::Domain.eq(domain))
+            // Rows written by backfills track their own ranges and must not
+            // move the live cursor.
+            .filter(cursor::Column::BackfillTo.is_null())
             .order_by(cursor::Column::Height, Order::Desc)
             .select_only()
             .column_as(cursor::Column::Height, QueryAs::Height)
@@ -88,6 +91,8 @@ impl BlockCursor {
                 domain: ActiveValue::Set(self.domain as i32),
                 time_created: ActiveValue::NotSet,
                 height: ActiveValue::Set(height as i64),
+                backfill_from: ActiveValue::NotSet,
+                backfill_to: ActiveValue::NotSet,
             };
             debug!(?model, "Inserting cursor");
             if let Err(e) = Insert::one(model).exec(&self.db).await {
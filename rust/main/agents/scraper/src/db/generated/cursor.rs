@@ -17,6 +17,8 @@ pub struct Model {
     pub domain: i32,
     pub time_created: TimeDateTime,
     pub height: i64,
+    pub backfill_from: Option<i64>,
+    pub backfill_to: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -25,6 +27,8 @@ pub enum Column {
     Domain,
     TimeCreated,
     Height,
+    BackfillFrom,
+    BackfillTo,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -52,6 +56,8 @@ impl ColumnTrait for Column {
             Self::Domain => ColumnType::Integer.def(),
             Self::TimeCreated => ColumnType::DateTime.def(),
             Self::Height => ColumnType::BigInteger.def(),
+            Self::BackfillFrom => ColumnType::BigInteger.def().null(),
+            Self::BackfillTo => ColumnType::BigInteger.def().null(),
         }
     }
 }
@@ -335,4 +335,29 @@ impl ScraperDb {
         );
         Ok(new_dispatch_count)
     }
+
+    /// Delete messages dispatched by a mailbox, e.g. as the blocks they were
+    /// dispatched in were reorged.
+    #[instrument(skip(self))]
+    pub async fn delete_dispatched_messages(
+        &self,
+        origin_domain: u32,
+        origin_mailbox: &H256,
+        nonces: &[u32],
+    ) -> Result<u64> {
+        if nonces.is_empty() {
+            return Ok(0);
+        }
+        let result = message::Entity::delete_many()
+            .filter(message::Column::Origin.eq(origin_domain))
+            .filter(message::Column::OriginMailbox.eq(address_to_bytes(origin_mailbox)))
+            .filter(message::Column::Nonce.is_in(nonces.iter().copied()))
+            .exec(&self.0)
+            .await?;
+        debug!(
+            messages = result.rows_affected,
+            "Deleted reorged messages from database"
+        );
+        Ok(result.rows_affected)
+    }
 }
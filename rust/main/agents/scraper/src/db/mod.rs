@@ -11,6 +11,7 @@ pub use txn::*;
 mod generated;
 
 // These modules implement additional functionality for the ScraperDb
+mod backfill_cursor;
 mod block;
 mod block_cursor;
 mod message;
@@ -13,16 +13,9 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-use agent::Scraper;
 use eyre::Result;
 use hyperlane_base::agent_main;
-
-mod agent;
-mod conversions;
-mod date_time;
-mod db;
-mod settings;
-mod store;
+use scraper::Scraper;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
@@ -4,10 +4,10 @@
 //! and validations it defines are not applied here, we should mirror them.
 //! ANY CHANGES HERE NEED TO BE REFLECTED IN THE TYPESCRIPT SDK.
 
-use std::{collections::HashSet, default::Default};
+use std::{collections::HashSet, default::Default, time::Duration};
 
 use derive_more::{AsMut, AsRef, Deref, DerefMut};
-use eyre::Context;
+use eyre::{eyre, Context};
 use hyperlane_base::{
     impl_loadable_from_settings,
     settings::{
@@ -19,6 +19,8 @@ use hyperlane_core::{cfg_unwrap_all, config::*, HyperlaneDomain};
 use serde::Deserialize;
 use serde_json::Value;
 
+use crate::backfill::{BackfillRange, BackfillSettings};
+
 /// Settings for `Scraper`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
 pub struct ScraperSettings {
@@ -30,6 +32,8 @@ pub struct ScraperSettings {
 
     pub db: String,
     pub chains_to_scrape: Vec<HyperlaneDomain>,
+    /// Historical block ranges to re-index alongside the live cursors
+    pub backfill: BackfillSettings,
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,12 +87,81 @@ impl FromRawConf<RawScraperSettings> for ScraperSettings {
             Default::default()
         };
 
+        let backfill_ranges = p
+            .chain(&mut err)
+            .get_opt_key("backfill")
+            .into_obj_iter()
+            .map(|ranges| {
+                ranges
+                    .filter_map(|(chain, range)| {
+                        let from_block = range
+                            .chain(&mut err)
+                            .get_key("fromBlock")
+                            .parse_u32()
+                            .end();
+                        let to_block = range
+                            .chain(&mut err)
+                            .get_key("toBlock")
+                            .parse_u32()
+                            .end();
+                        let scraped = chains_names_to_scrape
+                            .as_ref()
+                            .map_or(false, |chains| chains.contains(chain.as_str()));
+                        if !scraped {
+                            err.push(
+                                range.cwp.clone(),
+                                eyre!("Chain `{chain}` has a backfill range but is not listed in `chainsToScrape`"),
+                            );
+                            return None;
+                        }
+                        let domain = base.as_ref().and_then(|base| {
+                            base.lookup_domain(&chain)
+                                .into_config_result(|| range.cwp.clone())
+                                .take_config_err(&mut err)
+                        })?;
+                        Some((
+                            domain,
+                            BackfillRange {
+                                from_block: from_block?,
+                                to_block: to_block?,
+                            },
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let default_backfill = BackfillSettings::default();
+        let backfill_concurrency = p
+            .chain(&mut err)
+            .get_opt_key("backfillConcurrency")
+            .parse_u32()
+            .map(|v| v as usize)
+            .unwrap_or(default_backfill.concurrency);
+        let backfill_chunk_interval = p
+            .chain(&mut err)
+            .get_opt_key("backfillChunkIntervalMs")
+            .parse_u64()
+            .map(Duration::from_millis)
+            .unwrap_or(default_backfill.chunk_interval);
+        if backfill_chunk_interval.is_zero() {
+            err.push(
+                &p.cwp + "backfill_chunk_interval_ms",
+                eyre!("Backfill chunk interval must be greater than zero"),
+            );
+        }
+
         cfg_unwrap_all!(&p.cwp, err: [base, db]);
 
         err.into_result(Self {
             base,
             db,
             chains_to_scrape,
+            backfill: BackfillSettings {
+                ranges: backfill_ranges,
+                concurrency: backfill_concurrency,
+                chunk_interval: backfill_chunk_interval,
+            },
         })
     }
 }
@@ -38,6 +38,23 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneDbStore {
             .await?;
         Ok(stored as u32)
     }
+
+    /// Delete reorged messages from the database, so that they are scraped
+    /// again once the cursor reaches their blocks.
+    async fn invalidate_logs(
+        &self,
+        messages: &[(Indexed<HyperlaneMessage>, LogMeta)],
+    ) -> Result<u32> {
+        let nonces = messages
+            .iter()
+            .map(|(message, _)| message.inner().nonce)
+            .collect::<Vec<_>>();
+        let deleted = self
+            .db
+            .delete_dispatched_messages(self.domain.id(), &self.mailbox_address, &nonces)
+            .await?;
+        Ok(deleted as u32)
+    }
 }
 
 #[async_trait]
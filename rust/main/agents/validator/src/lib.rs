@@ -0,0 +1,12 @@
+//! The validator signs Mailbox checkpoints that have reached finality.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod server;
+mod settings;
+mod signing_throttle;
+mod submit;
+mod validator;
+
+pub use validator::Validator;
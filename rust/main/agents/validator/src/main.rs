@@ -7,12 +7,7 @@ use eyre::Result;
 
 use hyperlane_base::agent_main;
 
-use crate::validator::Validator;
-
-mod server;
-mod settings;
-mod submit;
-mod validator;
+use validator::Validator;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<()> {
@@ -14,12 +14,17 @@ use hyperlane_base::{
         parser::{RawAgentConf, RawAgentSignerConf, ValueParser},
         CheckpointSyncerConf, Settings, SignerConf,
     },
+    ArweaveStorage, DEFAULT_ARWEAVE_GATEWAY_URL, DEFAULT_ARWEAVE_UPLOAD_URL,
+    DEFAULT_IPFS_GATEWAY_URL,
 };
 use hyperlane_core::{
     cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol, ReorgPeriod,
 };
 use serde::Deserialize;
 use serde_json::Value;
+use url::Url;
+
+use crate::signing_throttle::CheckpointSigningThrottleConf;
 
 /// Settings for `Validator`
 #[derive(Debug, AsRef, AsMut, Deref, DerefMut)]
@@ -40,8 +45,16 @@ pub struct ValidatorSettings {
     pub checkpoint_syncer: CheckpointSyncerConf,
     /// The reorg configuration
     pub reorg_period: ReorgPeriod,
+    /// How long a checkpoint must have been observed before it is signed, on
+    /// top of the reorg period
+    pub reorg_lag: Duration,
     /// How frequently to check for new checkpoints
     pub interval: Duration,
+    /// An RPC of the origin chain, independent of the configured ones, that
+    /// checkpoints are cross-checked against before they are signed
+    pub verification_rpc_url: Option<Url>,
+    /// Limits on how fast checkpoints are signed
+    pub checkpoint_signing_throttle: CheckpointSigningThrottleConf,
 }
 
 #[derive(Debug, Deserialize)]
@@ -116,6 +129,18 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .map(Duration::from_secs)
             .unwrap_or(Duration::from_secs(5));
 
+        let verification_rpc_url = p
+            .chain(&mut err)
+            .get_opt_key("verificationRpcUrl")
+            .parse_from_str::<Url>("Expected verification RPC url")
+            .end();
+
+        let checkpoint_signing_throttle = p
+            .chain(&mut err)
+            .get_opt_key("checkpointSigningThrottle")
+            .and_then(parse_checkpoint_signing_throttle)
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [origin_chain_name]);
 
         let reorg_period = p
@@ -127,6 +152,16 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             .parse_value("Invalid reorgPeriod")
             .unwrap_or(ReorgPeriod::from_blocks(1));
 
+        let reorg_lag = p
+            .chain(&mut err)
+            .get_key("chains")
+            .get_key(origin_chain_name)
+            .get_opt_key("blocks")
+            .get_opt_key("reorgLagSeconds")
+            .parse_u64()
+            .map(Duration::from_secs)
+            .unwrap_or_default();
+
         cfg_unwrap_all!(cwp, err: [base, origin_chain, validator, checkpoint_syncer]);
 
         let mut base: Settings = base;
@@ -144,11 +179,51 @@ impl FromRawConf<RawValidatorSettings> for ValidatorSettings {
             validator,
             checkpoint_syncer,
             reorg_period,
+            reorg_lag,
             interval,
+            verification_rpc_url,
+            checkpoint_signing_throttle,
         })
     }
 }
 
+/// Expects ValidatorAgentConfig.checkpointSigningThrottle
+fn parse_checkpoint_signing_throttle(
+    throttle: ValueParser,
+) -> ConfigResult<CheckpointSigningThrottleConf> {
+    let mut err = ConfigParsingError::default();
+    let default = CheckpointSigningThrottleConf::default();
+
+    let max_per_second = throttle
+        .chain(&mut err)
+        .get_opt_key("maxPerSecond")
+        .parse_f64()
+        .end();
+    let burst = throttle
+        .chain(&mut err)
+        .get_opt_key("burst")
+        .parse_u32()
+        .unwrap_or(default.burst);
+    let sparse_threshold = throttle
+        .chain(&mut err)
+        .get_opt_key("sparseSigningThreshold")
+        .parse_u64()
+        .end()
+        .map(|threshold| threshold as usize);
+    let sparse_interval = throttle
+        .chain(&mut err)
+        .get_opt_key("sparseSigningInterval")
+        .parse_u32()
+        .unwrap_or(default.sparse_interval);
+
+    err.into_result(CheckpointSigningThrottleConf {
+        max_per_second,
+        burst,
+        sparse_threshold,
+        sparse_interval,
+    })
+}
+
 /// Expects ValidatorAgentConfig.checkpointSyncer
 fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncerConf> {
     let mut err = ConfigParsingError::default();
@@ -215,6 +290,11 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
                 .parse_string()
                 .end()
                 .map(str::to_owned);
+            let workload_identity = syncer
+                .chain(&mut err)
+                .get_opt_key("workload_identity")
+                .parse_bool()
+                .unwrap_or(false);
 
             cfg_unwrap_all!(&syncer.cwp, err: [bucket]);
             err.into_result(CheckpointSyncerConf::Gcs {
@@ -222,6 +302,120 @@ fn parse_checkpoint_syncer(syncer: ValueParser) -> ConfigResult<CheckpointSyncer
                 folder,
                 service_account_key,
                 user_secrets,
+                workload_identity,
+            })
+        }
+        Some("azblob") => {
+            let account = syncer
+                .chain(&mut err)
+                .get_key("account")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let container = syncer
+                .chain(&mut err)
+                .get_key("container")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let folder = syncer
+                .chain(&mut err)
+                .get_opt_key("folder")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let sas_token = syncer
+                .chain(&mut err)
+                .get_opt_key("sas_token")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let managed_identity = syncer
+                .chain(&mut err)
+                .get_opt_key("managed_identity")
+                .parse_bool()
+                .unwrap_or(false);
+
+            cfg_unwrap_all!(&syncer.cwp, err: [account, container]);
+            err.into_result(CheckpointSyncerConf::Azure {
+                account,
+                container,
+                folder,
+                sas_token,
+                managed_identity,
+            })
+        }
+        Some("ipfs") => {
+            let ipns_name = syncer
+                .chain(&mut err)
+                .get_key("ipns_name")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let api_url = syncer
+                .chain(&mut err)
+                .get_key("api_url")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let pinning_service_url = syncer
+                .chain(&mut err)
+                .get_opt_key("pinning_service_url")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let pinning_service_token = syncer
+                .chain(&mut err)
+                .get_opt_key("pinning_service_token")
+                .parse_string()
+                .end()
+                .map(str::to_owned);
+            let gateway_url = syncer
+                .chain(&mut err)
+                .get_opt_key("gateway_url")
+                .parse_string()
+                .unwrap_or(DEFAULT_IPFS_GATEWAY_URL)
+                .to_owned();
+
+            cfg_unwrap_all!(&syncer.cwp, err: [ipns_name, api_url]);
+            err.into_result(CheckpointSyncerConf::Ipfs {
+                ipns_name,
+                api_url: Some(api_url),
+                pinning_service_url,
+                pinning_service_token,
+                gateway_url,
+            })
+        }
+        Some("arweave") => {
+            let key = syncer
+                .chain(&mut err)
+                .get_key("key")
+                .parse_private_key()
+                .end();
+            let owner = key.as_ref().and_then(|key| {
+                ArweaveStorage::owner_of_key(key)
+                    .into_config_result(|| &syncer.cwp + "key")
+                    .take_config_err(&mut err)
+            });
+            let upload_url = syncer
+                .chain(&mut err)
+                .get_opt_key("upload_url")
+                .parse_string()
+                .unwrap_or(DEFAULT_ARWEAVE_UPLOAD_URL)
+                .to_owned();
+            let gateway_url = syncer
+                .chain(&mut err)
+                .get_opt_key("gateway_url")
+                .parse_string()
+                .unwrap_or(DEFAULT_ARWEAVE_GATEWAY_URL)
+                .to_owned();
+
+            cfg_unwrap_all!(&syncer.cwp, err: [key, owner]);
+            err.into_result(CheckpointSyncerConf::Arweave {
+                owner,
+                key: Some(key),
+                upload_url,
+                gateway_url,
             })
         }
         Some(_) => {
@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use tokio::time::sleep;
+
+/// Limits on how fast the validator signs checkpoints, which protect the
+/// checkpoint storage and the relayers fetching from it during bursts of
+/// messages on the origin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointSigningThrottleConf {
+    /// The maximum number of checkpoints signed per second, unlimited if
+    /// `None`
+    pub max_per_second: Option<f64>,
+    /// The number of checkpoints that can be signed at once before the rate
+    /// limit applies
+    pub burst: u32,
+    /// The number of checkpoints queued at once above which only every
+    /// `sparse_interval`th one is signed right away, if any. The skipped
+    /// checkpoints are deferred and signed once the burst is over.
+    pub sparse_threshold: Option<usize>,
+    /// Only checkpoints whose index is a multiple of this are signed right
+    /// away during a burst, besides the latest one
+    pub sparse_interval: u32,
+}
+
+impl Default for CheckpointSigningThrottleConf {
+    fn default() -> Self {
+        Self {
+            max_per_second: None,
+            burst: 1,
+            sparse_threshold: None,
+            sparse_interval: 10,
+        }
+    }
+}
+
+impl CheckpointSigningThrottleConf {
+    /// Whether a queue of `queue_len` checkpoints is a burst, during which
+    /// checkpoints are signed sparsely
+    pub fn is_burst(&self, queue_len: usize) -> bool {
+        self.sparse_threshold
+            .map_or(false, |threshold| queue_len > threshold)
+    }
+
+    /// Splits a burst of checkpoints by ascending index into the ones to sign
+    /// right away, which always include the latest one, and the ones to defer
+    pub fn split_burst<T>(
+        &self,
+        checkpoints: Vec<T>,
+        index: impl Fn(&T) -> u32,
+    ) -> (Vec<T>, Vec<T>) {
+        let interval = self.sparse_interval.max(1);
+        let last = checkpoints.len().saturating_sub(1);
+        let (sign, defer): (Vec<_>, Vec<_>) =
+            checkpoints
+                .into_iter()
+                .enumerate()
+                .partition(|(position, checkpoint)| {
+                    *position == last || index(checkpoint) % interval == 0
+                });
+        (
+            sign.into_iter().map(|(_, checkpoint)| checkpoint).collect(),
+            defer
+                .into_iter()
+                .map(|(_, checkpoint)| checkpoint)
+                .collect(),
+        )
+    }
+}
+
+/// A token bucket limiting the rate checkpoints are signed at
+#[derive(Debug)]
+pub struct SigningRateLimiter {
+    /// Tokens added per second, unlimited if `None`
+    rate: Option<f64>,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl SigningRateLimiter {
+    pub fn new(conf: &CheckpointSigningThrottleConf) -> Self {
+        let capacity = conf.burst.max(1) as f64;
+        Self {
+            rate: conf.max_per_second.filter(|rate| *rate > 0.),
+            capacity,
+            tokens: capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Wait until a checkpoint may be signed
+    pub async fn acquire(&mut self) {
+        while let Some(wait) = self.try_acquire(Instant::now()) {
+            sleep(wait).await;
+        }
+    }
+
+    /// Takes a token if there is one, or returns how long to wait for one
+    fn try_acquire(&mut self, now: Instant) -> Option<Duration> {
+        let rate = self.rate?;
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1. {
+            self.tokens -= 1.;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1. - self.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_allows_bursts() {
+        let mut limiter = SigningRateLimiter::new(&CheckpointSigningThrottleConf {
+            max_per_second: Some(2.),
+            burst: 3,
+            ..Default::default()
+        });
+        let start = limiter.last_refill;
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire(start), None);
+        }
+        assert_eq!(limiter.try_acquire(start), Some(Duration::from_millis(500)));
+        assert_eq!(
+            limiter.try_acquire(start + Duration::from_millis(500)),
+            None
+        );
+
+        let mut unlimited = SigningRateLimiter::new(&Default::default());
+        for _ in 0..100 {
+            assert_eq!(unlimited.try_acquire(start), None);
+        }
+    }
+
+    #[test]
+    fn test_split_burst() {
+        let conf = CheckpointSigningThrottleConf {
+            sparse_threshold: Some(4),
+            sparse_interval: 3,
+            ..Default::default()
+        };
+        assert!(!conf.is_burst(4));
+        assert!(conf.is_burst(5));
+
+        let (sign, defer) = conf.split_burst((1..=7).collect(), |index| *index);
+        assert_eq!(sign, vec![3, 6, 7]);
+        assert_eq!(defer, vec![1, 2, 4, 5]);
+    }
+}
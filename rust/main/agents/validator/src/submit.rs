@@ -1,13 +1,15 @@
-use std::sync::Arc;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, Instant};
 use std::vec;
 
-use prometheus::IntGauge;
+use prometheus::{IntCounter, IntGauge};
 use tokio::time::sleep;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use hyperlane_base::db::HyperlaneDb;
 use hyperlane_base::{CheckpointSyncer, CoreMetrics};
+use hyperlane_core::metrics::definitions;
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle, Checkpoint, CheckpointWithMessageId,
@@ -16,14 +18,24 @@ use hyperlane_core::{
 use hyperlane_core::{ChainResult, MerkleTreeHook, ReorgEvent, ReorgPeriod};
 use hyperlane_ethereum::SingletonSignerHandle;
 
+use crate::signing_throttle::{CheckpointSigningThrottleConf, SigningRateLimiter};
+
 #[derive(Clone)]
 pub(crate) struct ValidatorSubmitter {
     interval: Duration,
     reorg_period: ReorgPeriod,
+    reorg_lag: Duration,
     signer: SingletonSignerHandle,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
+    /// The merkle tree hook as seen through an RPC independent of the one
+    /// `merkle_tree_hook` uses, to cross-check checkpoints against
+    verification_merkle_tree_hook: Option<Arc<dyn MerkleTreeHook>>,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
     db: Arc<dyn HyperlaneDb>,
+    signing_throttle: CheckpointSigningThrottleConf,
+    rate_limiter: Arc<tokio::sync::Mutex<SigningRateLimiter>>,
+    /// Checkpoints skipped during bursts, by index, which are yet to be signed
+    deferred_checkpoints: Arc<Mutex<BTreeMap<u32, CheckpointWithMessageId>>>,
     metrics: ValidatorSubmitterMetrics,
 }
 
@@ -31,19 +43,29 @@ impl ValidatorSubmitter {
     pub(crate) fn new(
         interval: Duration,
         reorg_period: ReorgPeriod,
+        reorg_lag: Duration,
         merkle_tree_hook: Arc<dyn MerkleTreeHook>,
+        verification_merkle_tree_hook: Option<Arc<dyn MerkleTreeHook>>,
         signer: SingletonSignerHandle,
         checkpoint_syncer: Arc<dyn CheckpointSyncer>,
         db: Arc<dyn HyperlaneDb>,
+        signing_throttle: CheckpointSigningThrottleConf,
         metrics: ValidatorSubmitterMetrics,
     ) -> Self {
+        metrics.reorg_lag_seconds.set(reorg_lag.as_secs() as i64);
+        let rate_limiter = SigningRateLimiter::new(&signing_throttle);
         Self {
             reorg_period,
+            reorg_lag,
             interval,
             merkle_tree_hook,
+            verification_merkle_tree_hook,
             signer,
             checkpoint_syncer,
             db,
+            signing_throttle,
+            rate_limiter: Arc::new(tokio::sync::Mutex::new(rate_limiter)),
+            deferred_checkpoints: Default::default(),
             metrics,
         }
     }
@@ -60,6 +82,10 @@ impl ValidatorSubmitter {
     /// Submits signed checkpoints from index 0 until the target checkpoint (inclusive).
     /// Runs idly forever once the target checkpoint is reached to avoid exiting the task.
     pub(crate) async fn backfill_checkpoint_submitter(self, target_checkpoint: Checkpoint) {
+        // The target checkpoint was only just observed, so wait for the reorg lag before
+        // signing it, in case it gets reorged out in the meantime.
+        let target_checkpoint = self.lagged_checkpoint(target_checkpoint).await;
+
         let mut tree = IncrementalMerkle::default();
         self.submit_checkpoints_until_correctness_checkpoint(&mut tree, &target_checkpoint)
             .await;
@@ -70,6 +96,26 @@ impl ValidatorSubmitter {
         );
     }
 
+    /// Keeps observing the latest checkpoint, starting from `checkpoint`, until one has
+    /// been observed for the reorg lag, and returns it.
+    async fn lagged_checkpoint(&self, checkpoint: Checkpoint) -> Checkpoint {
+        let mut lagged_checkpoints = LaggedCheckpoints::new(self.reorg_lag);
+        let mut latest_checkpoint = checkpoint;
+        loop {
+            if let Some(checkpoint) = lagged_checkpoints.observe(latest_checkpoint, Instant::now())
+            {
+                return checkpoint;
+            }
+            sleep(self.interval).await;
+            latest_checkpoint = call_and_retry_indefinitely(|| {
+                let merkle_tree_hook = self.merkle_tree_hook.clone();
+                let reorg_period = self.reorg_period.clone();
+                Box::pin(async move { merkle_tree_hook.latest_checkpoint(&reorg_period).await })
+            })
+            .await;
+        }
+    }
+
     /// Submits signed checkpoints indefinitely, starting from the `tree`.
     pub(crate) async fn checkpoint_submitter(self, mut tree: IncrementalMerkle) {
         // How often to log checkpoint info - once every minute
@@ -88,6 +134,7 @@ impl ValidatorSubmitter {
             latest_checkpoint_info_log = Some(Instant::now());
             true
         };
+        let mut lagged_checkpoints = LaggedCheckpoints::new(self.reorg_lag);
 
         loop {
             // Lag by reorg period because this is our correctness checkpoint.
@@ -110,6 +157,22 @@ impl ValidatorSubmitter {
                 );
             }
 
+            // Additionally lag by reorg lag, so that only checkpoints that have been
+            // observed for long enough get signed.
+            let Some(latest_checkpoint) =
+                lagged_checkpoints.observe(latest_checkpoint, Instant::now())
+            else {
+                debug!(
+                    reorg_lag = ?self.reorg_lag,
+                    "No checkpoint observed for longer than the reorg lag yet, sleeping briefly"
+                );
+                sleep(self.interval).await;
+                continue;
+            };
+            self.metrics
+                .latest_checkpoint_signable
+                .set(latest_checkpoint.index as i64);
+
             // This may occur e.g. if RPC providers are unreliable and make calls against
             // inconsistent block tips.
             //
@@ -131,6 +194,8 @@ impl ValidatorSubmitter {
                 .latest_checkpoint_processed
                 .set(latest_checkpoint.index as i64);
 
+            self.sign_deferred_checkpoints().await;
+
             sleep(self.interval).await;
         }
     }
@@ -240,7 +305,9 @@ impl ValidatorSubmitter {
                 queue_len = checkpoint_queue.len(),
                 "Reached tree consistency"
             );
-            self.sign_and_submit_checkpoints(checkpoint_queue).await;
+            self.verify_checkpoint(tree).await;
+            self.sign_and_submit_queued_checkpoints(checkpoint_queue)
+                .await;
 
             info!(
                 index = checkpoint.index,
@@ -249,6 +316,55 @@ impl ValidatorSubmitter {
         }
     }
 
+    /// Cross-checks the tree against the latest checkpoint of the verification
+    /// merkle tree hook, if any, so that a corrupted primary RPC doesn't get the
+    /// validator to sign checkpoints that aren't on chain.
+    /// Waits while the checkpoints can't be compared yet, and refuses to return,
+    /// and so to let any checkpoint be signed, while their roots differ.
+    async fn verify_checkpoint(&self, tree: &IncrementalMerkle) {
+        let Some(verification_merkle_tree_hook) = &self.verification_merkle_tree_hook else {
+            return;
+        };
+        loop {
+            let verification_checkpoint = call_and_retry_indefinitely(|| {
+                let merkle_tree_hook = verification_merkle_tree_hook.clone();
+                let reorg_period = self.reorg_period.clone();
+                Box::pin(async move { merkle_tree_hook.latest_checkpoint(&reorg_period).await })
+            })
+            .await;
+            match compare_with_verification_checkpoint(
+                self.db.as_ref(),
+                tree,
+                &verification_checkpoint,
+            ) {
+                Some(true) => {
+                    debug!(
+                        index = tree.index(),
+                        ?verification_checkpoint,
+                        "Checkpoint matches the verification RPC"
+                    );
+                    return;
+                }
+                Some(false) => {
+                    self.metrics.checkpoint_verification_mismatches.inc();
+                    error!(
+                        checkpoint = ?self.checkpoint(tree),
+                        ?verification_checkpoint,
+                        "Tree root differs from the one the verification RPC reports, refusing to sign checkpoints. The primary or the verification RPC may be corrupted"
+                    );
+                }
+                None => {
+                    warn!(
+                        index = tree.index(),
+                        ?verification_checkpoint,
+                        "Checkpoint can't be verified yet, as the verification RPC or the indexer is behind, sleeping briefly"
+                    );
+                }
+            }
+            sleep(self.interval).await;
+        }
+    }
+
     async fn sign_and_submit_checkpoint(
         &self,
         checkpoint: CheckpointWithMessageId,
@@ -261,6 +377,7 @@ impl ValidatorSubmitter {
             debug!(index = checkpoint.index, "Checkpoint already submitted");
             return Ok(());
         }
+        self.rate_limiter.lock().await.acquire().await;
         let signed_checkpoint = self.signer.sign(checkpoint).await?;
         self.checkpoint_syncer
             .write_checkpoint(&signed_checkpoint)
@@ -273,6 +390,87 @@ impl ValidatorSubmitter {
         Ok(())
     }
 
+    /// Signs and submits the checkpoints queued until the correctness
+    /// checkpoint. If there are so many of them that this is a burst, only
+    /// some of them are signed, and the others are deferred.
+    async fn sign_and_submit_queued_checkpoints(&self, checkpoints: Vec<CheckpointWithMessageId>) {
+        if !self.signing_throttle.is_burst(checkpoints.len()) {
+            self.sign_and_submit_checkpoints(checkpoints).await;
+            return;
+        }
+        let (checkpoints, deferred) = self
+            .signing_throttle
+            .split_burst(checkpoints, |checkpoint| checkpoint.index);
+        info!(
+            signed = checkpoints.len(),
+            deferred = deferred.len(),
+            "Burst of checkpoints, deferring some of them"
+        );
+        self.metrics
+            .checkpoints_deferred
+            .inc_by(deferred.len() as u64);
+        {
+            let mut deferred_checkpoints = self
+                .deferred_checkpoints
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            deferred_checkpoints.extend(
+                deferred
+                    .into_iter()
+                    .map(|checkpoint| (checkpoint.index, checkpoint)),
+            );
+            self.metrics
+                .deferred_checkpoints_queued
+                .set(deferred_checkpoints.len() as i64);
+        }
+        self.sign_and_submit_checkpoints(checkpoints).await;
+    }
+
+    /// Signs some of the checkpoints deferred during bursts, lowest index
+    /// first, so that all of them eventually get signed.
+    async fn sign_deferred_checkpoints(&self) {
+        let batch_size = self.signing_throttle.sparse_threshold.unwrap_or(usize::MAX);
+        let checkpoints = self
+            .deferred_checkpoints
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .take(batch_size)
+            .copied()
+            .collect::<Vec<_>>();
+        if checkpoints.is_empty() {
+            return;
+        }
+        debug!(
+            count = checkpoints.len(),
+            "Signing checkpoints deferred during a burst"
+        );
+        for checkpoint in checkpoints {
+            self.sign_and_submit_checkpoint_with_retries(checkpoint)
+                .await;
+            let mut deferred_checkpoints = self
+                .deferred_checkpoints
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            deferred_checkpoints.remove(&checkpoint.index);
+            self.metrics
+                .deferred_checkpoints_queued
+                .set(deferred_checkpoints.len() as i64);
+        }
+    }
+
+    async fn sign_and_submit_checkpoint_with_retries(&self, checkpoint: CheckpointWithMessageId) {
+        // certain checkpoint stores rate limit very aggressively, so we retry indefinitely
+        call_and_retry_indefinitely(|| {
+            let self_clone = self.clone();
+            Box::pin(async move {
+                self_clone.sign_and_submit_checkpoint(checkpoint).await?;
+                Ok(())
+            })
+        })
+        .await;
+    }
+
     /// Signs and submits any previously unsubmitted checkpoints.
     async fn sign_and_submit_checkpoints(&self, checkpoints: Vec<CheckpointWithMessageId>) {
         let last_checkpoint = checkpoints.as_slice()[checkpoints.len() - 1];
@@ -280,17 +478,8 @@ impl ValidatorSubmitter {
         // since those are the most likely to make messages become processable.
         // A side effect is that new checkpoints will also be submitted in reverse order.
         for queued_checkpoint in checkpoints.into_iter().rev() {
-            // certain checkpoint stores rate limit very aggressively, so we retry indefinitely
-            call_and_retry_indefinitely(|| {
-                let self_clone = self.clone();
-                Box::pin(async move {
-                    self_clone
-                        .sign_and_submit_checkpoint(queued_checkpoint)
-                        .await?;
-                    Ok(())
-                })
-            })
-            .await;
+            self.sign_and_submit_checkpoint_with_retries(queued_checkpoint)
+                .await;
         }
 
         call_and_retry_indefinitely(|| {
@@ -314,10 +503,100 @@ fn tree_exceeds_checkpoint(checkpoint: &Checkpoint, tree: &IncrementalMerkle) ->
     checkpoint.index + 1 < tree.count() as u32
 }
 
+/// Compares the tree with a checkpoint of the verification RPC. If the
+/// checkpoint is ahead of the tree, a copy of the tree is extended with the
+/// indexed insertions up to it, as its root commits to the tree's leaves too.
+/// Returns whether the roots match, or `None` if the checkpoint is behind the
+/// tree or the insertions up to it aren't indexed yet.
+fn compare_with_verification_checkpoint(
+    db: &dyn HyperlaneDb,
+    tree: &IncrementalMerkle,
+    verification_checkpoint: &Checkpoint,
+) -> Option<bool> {
+    if tree_exceeds_checkpoint(verification_checkpoint, tree) {
+        return None;
+    }
+    let mut tree = tree.clone();
+    while tree.count() as u32 <= verification_checkpoint.index {
+        let insertion = db
+            .retrieve_merkle_tree_insertion_by_leaf_index(&(tree.count() as u32))
+            .ok()
+            .flatten()?;
+        tree.ingest(insertion.message_id());
+    }
+    Some(tree.root() == verification_checkpoint.root)
+}
+
+/// Checkpoints observed within the reorg lag, used to only sign checkpoints
+/// that have remained the latest correctness checkpoint, or been superseded
+/// by a later one, for at least the reorg lag.
+#[derive(Debug)]
+struct LaggedCheckpoints {
+    lag: Duration,
+    /// Observed checkpoints by ascending index, with the instant they were
+    /// first observed at
+    observed: VecDeque<(Instant, Checkpoint)>,
+}
+
+impl LaggedCheckpoints {
+    fn new(lag: Duration) -> Self {
+        Self {
+            lag,
+            observed: VecDeque::new(),
+        }
+    }
+
+    /// Records `checkpoint` as observed at `now`, and returns the latest
+    /// checkpoint that was observed at least the lag ago, if any.
+    fn observe(&mut self, checkpoint: Checkpoint, now: Instant) -> Option<Checkpoint> {
+        if self.lag.is_zero() {
+            return Some(checkpoint);
+        }
+
+        match self
+            .observed
+            .iter()
+            .find(|(_, observed)| observed.index == checkpoint.index)
+        {
+            Some((_, observed)) if *observed == checkpoint => {}
+            // A different checkpoint was observed at this index, so it and any later ones
+            // have been reorged out and the new one must be observed for the full lag.
+            Some(_) => {
+                self.observed
+                    .retain(|(_, observed)| observed.index < checkpoint.index);
+                self.observed.push_back((now, checkpoint));
+            }
+            None if self
+                .observed
+                .back()
+                .map_or(true, |(_, last)| last.index < checkpoint.index) =>
+            {
+                self.observed.push_back((now, checkpoint));
+            }
+            // An older checkpoint than the latest observed one, e.g. from a lagging RPC
+            None => {}
+        }
+
+        let signable_count = self
+            .observed
+            .iter()
+            .take_while(|(observed_at, _)| now.saturating_duration_since(*observed_at) >= self.lag)
+            .count();
+        // Only the latest signable checkpoint is kept, as it supersedes the earlier ones
+        self.observed.drain(..signable_count.saturating_sub(1));
+        (signable_count > 0).then(|| self.observed[0].1)
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct ValidatorSubmitterMetrics {
     latest_checkpoint_observed: IntGauge,
+    latest_checkpoint_signable: IntGauge,
     latest_checkpoint_processed: IntGauge,
+    reorg_lag_seconds: IntGauge,
+    checkpoint_verification_mismatches: IntCounter,
+    checkpoints_deferred: IntCounter,
+    deferred_checkpoints_queued: IntGauge,
 }
 
 impl ValidatorSubmitterMetrics {
@@ -327,9 +606,28 @@ impl ValidatorSubmitterMetrics {
             latest_checkpoint_observed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_observed", chain_name]),
+            latest_checkpoint_signable: metrics
+                .latest_checkpoint()
+                .with_label_values(&["validator_signable", chain_name]),
             latest_checkpoint_processed: metrics
                 .latest_checkpoint()
                 .with_label_values(&["validator_processed", chain_name]),
+            reorg_lag_seconds: metrics
+                .new_int_gauge(&definitions::VALIDATOR_REORG_LAG_SECONDS)
+                .expect("failed to register validator_reorg_lag_seconds metric")
+                .with_label_values(&[chain_name]),
+            checkpoint_verification_mismatches: metrics
+                .new_int_counter(&definitions::VALIDATOR_CHECKPOINT_VERIFICATION_MISMATCHES)
+                .expect("failed to register validator_checkpoint_verification_mismatches metric")
+                .with_label_values(&[chain_name]),
+            checkpoints_deferred: metrics
+                .new_int_counter(&definitions::VALIDATOR_CHECKPOINTS_DEFERRED)
+                .expect("failed to register validator_checkpoints_deferred metric")
+                .with_label_values(&[chain_name]),
+            deferred_checkpoints_queued: metrics
+                .new_int_gauge(&definitions::VALIDATOR_DEFERRED_CHECKPOINTS_QUEUED)
+                .expect("failed to register validator_deferred_checkpoints_queued metric")
+                .with_label_values(&[chain_name]),
         }
     }
 }
@@ -367,6 +665,11 @@ mod test {
             fn retrieve_message_by_nonce(&self, nonce: u32) -> DbResult<Option<HyperlaneMessage>>;
             fn retrieve_processed_by_nonce(&self, nonce: &u32) -> DbResult<Option<bool>>;
             fn domain(&self) -> &HyperlaneDomain;
+            fn retrieve_message_reorg_count(&self) -> DbResult<Option<u32>>;
+            fn retrieve_lowest_reorged_nonce_by_reorg_index(
+                &self,
+                reorg_index: &u32,
+            ) -> DbResult<Option<u32>>;
             fn store_message_id_by_nonce(&self, nonce: &u32, id: &H256) -> DbResult<()>;
             fn retrieve_message_id_by_nonce(&self, nonce: &u32) -> DbResult<Option<H256>>;
             fn store_message_by_id(&self, id: &H256, message: &HyperlaneMessage) -> DbResult<()>;
@@ -553,6 +856,139 @@ mod test {
         assert_eq!(reorg_event.reorg_period, expected_reorg_period);
     }
 
+    #[test]
+    fn lagged_checkpoints_are_signable_once_observed_for_the_lag() {
+        let checkpoint = |index, root_byte| Checkpoint {
+            root: H256::repeat_byte(root_byte),
+            index,
+            merkle_tree_hook_address: H256::zero(),
+            mailbox_domain: 0,
+        };
+        let lag = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut lagged = LaggedCheckpoints::new(lag);
+
+        assert_eq!(lagged.observe(checkpoint(1, 1), start), None);
+        assert_eq!(
+            lagged.observe(checkpoint(2, 2), start + Duration::from_secs(30)),
+            None
+        );
+        assert_eq!(
+            lagged.observe(checkpoint(2, 2), start + lag),
+            Some(checkpoint(1, 1))
+        );
+
+        // checkpoint 2 is reorged out before reaching the lag
+        assert_eq!(
+            lagged.observe(checkpoint(2, 3), start + Duration::from_secs(80)),
+            Some(checkpoint(1, 1))
+        );
+        assert_eq!(
+            lagged.observe(checkpoint(2, 3), start + Duration::from_secs(100)),
+            Some(checkpoint(1, 1))
+        );
+        // a stale read doesn't reset the lag
+        assert_eq!(
+            lagged.observe(checkpoint(1, 1), start + Duration::from_secs(120)),
+            Some(checkpoint(1, 1))
+        );
+        assert_eq!(
+            lagged.observe(checkpoint(3, 4), start + Duration::from_secs(140)),
+            Some(checkpoint(2, 3))
+        );
+
+        let mut unlagged = LaggedCheckpoints::new(Duration::ZERO);
+        assert_eq!(
+            unlagged.observe(checkpoint(1, 1), start),
+            Some(checkpoint(1, 1))
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_target_is_refetched_after_the_reorg_lag() {
+        let checkpoint = |root: u64, index| Checkpoint {
+            root: H256::from_low_u64_be(root),
+            index,
+            merkle_tree_hook_address: H256::zero(),
+            mailbox_domain: 0,
+        };
+        // the observed target is reorged out and replaced at the same index
+        let mut mock_merkle_tree_hook = MockMerkleTreeHook::new();
+        mock_merkle_tree_hook
+            .expect_latest_checkpoint()
+            .returning(move |_| Ok(checkpoint(2, 1)));
+
+        let validator_submitter = ValidatorSubmitter::new(
+            Duration::from_millis(10),
+            ReorgPeriod::from_blocks(0),
+            Duration::from_millis(50),
+            Arc::new(mock_merkle_tree_hook),
+            None,
+            dummy_singleton_handle(),
+            Arc::new(MockCheckpointSyncer::new()),
+            Arc::new(MockDb::new()),
+            Default::default(),
+            dummy_metrics(),
+        );
+
+        let start = Instant::now();
+        assert_eq!(
+            validator_submitter
+                .lagged_checkpoint(checkpoint(1, 1))
+                .await,
+            checkpoint(2, 1)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_compare_with_verification_checkpoint() {
+        let insertions = [
+            MerkleTreeInsertion::new(0, H256::random()),
+            MerkleTreeInsertion::new(1, H256::random()),
+            MerkleTreeInsertion::new(2, H256::random()),
+        ];
+        let mut db = MockDb::new();
+        db.expect_retrieve_merkle_tree_insertion_by_leaf_index()
+            .returning(move |leaf_index| Ok(insertions.get(*leaf_index as usize).copied()));
+
+        let mut tree = IncrementalMerkle::default();
+        tree.ingest(insertions[0].message_id());
+        tree.ingest(insertions[1].message_id());
+        let mut full_tree = tree.clone();
+        full_tree.ingest(insertions[2].message_id());
+        let checkpoint = |root, index| Checkpoint {
+            root,
+            index,
+            merkle_tree_hook_address: H256::zero(),
+            mailbox_domain: 0,
+        };
+
+        // The verification RPC is at the tree's index, or ahead of it
+        assert_eq!(
+            compare_with_verification_checkpoint(&db, &tree, &checkpoint(tree.root(), 1)),
+            Some(true)
+        );
+        assert_eq!(
+            compare_with_verification_checkpoint(&db, &tree, &checkpoint(full_tree.root(), 2)),
+            Some(true)
+        );
+        assert_eq!(
+            compare_with_verification_checkpoint(&db, &tree, &checkpoint(H256::random(), 2)),
+            Some(false)
+        );
+
+        // The verification RPC is behind the tree, or ahead of the indexer
+        assert_eq!(
+            compare_with_verification_checkpoint(&db, &tree, &checkpoint(H256::random(), 0)),
+            None
+        );
+        assert_eq!(
+            compare_with_verification_checkpoint(&db, &tree, &checkpoint(H256::random(), 3)),
+            None
+        );
+    }
+
     #[tokio::test]
     #[should_panic(expected = "Incorrect tree root, something went wrong.")]
     async fn reorg_is_detected_and_persisted_to_checkpoint_storage() {
@@ -624,10 +1060,13 @@ mod test {
         let validator_submitter = ValidatorSubmitter::new(
             Duration::from_secs(1),
             ReorgPeriod::from_blocks(expected_reorg_period),
+            Duration::ZERO,
             Arc::new(mock_merkle_tree_hook),
+            None,
             dummy_singleton_handle(),
             Arc::new(mock_checkpoint_syncer),
             Arc::new(db),
+            Default::default(),
             dummy_metrics(),
         );
 
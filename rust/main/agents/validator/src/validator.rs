@@ -26,6 +26,7 @@ use hyperlane_ethereum::{SingletonSigner, SingletonSignerHandle};
 
 use crate::{
     settings::ValidatorSettings,
+    signing_throttle::CheckpointSigningThrottleConf,
     submit::{ValidatorSubmitter, ValidatorSubmitterMetrics},
 };
 
@@ -40,12 +41,15 @@ pub struct Validator {
     merkle_tree_hook_sync: Arc<SequencedDataContractSync<MerkleTreeInsertion>>,
     mailbox: Arc<dyn Mailbox>,
     merkle_tree_hook: Arc<dyn MerkleTreeHook>,
+    verification_merkle_tree_hook: Option<Arc<dyn MerkleTreeHook>>,
     validator_announce: Arc<dyn ValidatorAnnounce>,
     signer: SingletonSignerHandle,
     // temporary holder until `run` is called
     signer_instance: Option<Box<SingletonSigner>>,
     reorg_period: ReorgPeriod,
+    reorg_lag: Duration,
     interval: Duration,
+    checkpoint_signing_throttle: CheckpointSigningThrottleConf,
     checkpoint_syncer: Arc<dyn CheckpointSyncer>,
     core_metrics: Arc<CoreMetrics>,
     agent_metrics: AgentMetrics,
@@ -101,6 +105,18 @@ impl BaseAgent for Validator {
             .unwrap()
             .clone();
 
+        let verification_merkle_tree_hook: Option<Arc<dyn MerkleTreeHook>> =
+            match &settings.verification_rpc_url {
+                Some(url) => Some(
+                    origin_chain_conf
+                        .with_rpc_url(url.clone())
+                        .build_merkle_tree_hook(&metrics)
+                        .await?
+                        .into(),
+                ),
+                None => None,
+            };
+
         let contract_sync_metrics = Arc::new(ContractSyncMetrics::new(&metrics));
 
         let merkle_tree_hook_sync = settings
@@ -120,12 +136,15 @@ impl BaseAgent for Validator {
             db: msg_db,
             mailbox: mailbox.into(),
             merkle_tree_hook: merkle_tree_hook.into(),
+            verification_merkle_tree_hook,
             merkle_tree_hook_sync,
             validator_announce: validator_announce.into(),
             signer,
             signer_instance: Some(Box::new(signer_instance)),
             reorg_period: settings.reorg_period,
+            reorg_lag: settings.reorg_lag,
             interval: settings.interval,
+            checkpoint_signing_throttle: settings.checkpoint_signing_throttle,
             checkpoint_syncer,
             agent_metrics,
             chain_metrics,
@@ -241,10 +260,13 @@ impl Validator {
         let submitter = ValidatorSubmitter::new(
             self.interval,
             self.reorg_period.clone(),
+            self.reorg_lag,
             self.merkle_tree_hook.clone(),
+            self.verification_merkle_tree_hook.clone(),
             self.signer.clone(),
             self.checkpoint_syncer.clone(),
             Arc::new(self.db.clone()) as Arc<dyn HyperlaneDb>,
+            self.checkpoint_signing_throttle.clone(),
             ValidatorSubmitterMetrics::new(&self.core.metrics, &self.origin_chain),
         );
 
@@ -0,0 +1,458 @@
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use reqwest_utils::RetryPolicy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use tracing::instrument;
+use url::Url;
+
+use crate::error::HyperlaneAptosError;
+use crate::utils::{decode_bytes, decode_h256, to_aptos_address};
+use crate::ConnectionConf;
+
+/// Max number of events requested per page
+const EVENTS_PAGE_LIMIT: u64 = 100;
+
+/// Client of the REST API of an Aptos fullnode
+#[derive(Clone, Debug)]
+pub struct AptosClient {
+    client: Client,
+    url: Url,
+}
+
+impl AptosClient {
+    /// Create a client of the fullnode of the connection config
+    pub fn new(conf: &ConnectionConf) -> ChainResult<Self> {
+        let client = reqwest_utils::client_for(&conf.url).map_err(HyperlaneAptosError::from)?;
+        Ok(Self {
+            client,
+            url: conf.url.clone(),
+        })
+    }
+
+    /// The latest state of the ledger
+    pub async fn ledger_info(&self) -> ChainResult<LedgerInfo> {
+        self.send(self.client.get(self.url.clone())).await
+    }
+
+    /// Call a view function and return the values it returned
+    pub async fn view(&self, function: &str, arguments: Vec<Value>) -> ChainResult<Vec<Value>> {
+        self.view_generic(function, vec![], arguments).await
+    }
+
+    /// Call a generic view function and return the values it returned
+    #[instrument(skip(self, arguments), err)]
+    pub async fn view_generic(
+        &self,
+        function: &str,
+        type_arguments: Vec<String>,
+        arguments: Vec<Value>,
+    ) -> ChainResult<Vec<Value>> {
+        let request = ViewRequest {
+            function,
+            type_arguments,
+            arguments,
+        };
+        self.send(self.client.post(self.endpoint("view")?).json(&request))
+            .await
+    }
+
+    /// An account
+    pub async fn account(&self, address: H256) -> ChainResult<Account> {
+        let path = format!("accounts/{}", to_aptos_address(address));
+        self.send(self.client.get(self.endpoint(&path)?)).await
+    }
+
+    /// Whether any modules are published at an account
+    pub async fn has_modules(&self, address: H256) -> ChainResult<bool> {
+        let path = format!("accounts/{}/modules", to_aptos_address(address));
+        let request = self
+            .client
+            .get(self.endpoint(&path)?)
+            .query(&[("limit", "1")]);
+        let modules: Option<Vec<Value>> = self.send_opt(request).await?;
+        Ok(modules.map_or(false, |modules| !modules.is_empty()))
+    }
+
+    /// The data of a resource of an account
+    pub async fn account_resource<T: DeserializeOwned>(
+        &self,
+        address: H256,
+        resource_type: &str,
+    ) -> ChainResult<T> {
+        let path = format!(
+            "accounts/{}/resource/{resource_type}",
+            to_aptos_address(address)
+        );
+        let resource: Resource<T> = self.send(self.client.get(self.endpoint(&path)?)).await?;
+        Ok(resource.data)
+    }
+
+    /// The events of the event handle `field` of the resource
+    /// `event_handle` of an account, starting with the sequence number
+    /// `start`, up to `count` events
+    #[instrument(skip(self), err)]
+    pub async fn events_by_handle(
+        &self,
+        address: H256,
+        event_handle: &str,
+        field: &str,
+        start: u64,
+        count: u64,
+    ) -> ChainResult<Vec<Event>> {
+        let path = format!(
+            "accounts/{}/events/{event_handle}/{field}",
+            to_aptos_address(address)
+        );
+        let endpoint = self.endpoint(&path)?;
+        let mut events = Vec::with_capacity(count as usize);
+        while (events.len() as u64) < count {
+            let limit = EVENTS_PAGE_LIMIT.min(count - events.len() as u64);
+            let request = self.client.get(endpoint.clone()).query(&[
+                ("start", (start + events.len() as u64).to_string()),
+                ("limit", limit.to_string()),
+            ]);
+            let page: Vec<Event> = self.send(request).await?;
+            let page_len = page.len() as u64;
+            events.extend(page);
+            if page_len < limit {
+                break;
+            }
+        }
+        Ok(events)
+    }
+
+    /// A block by its height, without its transactions
+    pub async fn block_by_height(&self, height: u64) -> ChainResult<Block> {
+        let path = format!("blocks/by_height/{height}");
+        self.send(self.client.get(self.endpoint(&path)?)).await
+    }
+
+    /// The block containing the transaction with the given version, without
+    /// its transactions
+    pub async fn block_by_version(&self, version: u64) -> ChainResult<Block> {
+        let path = format!("blocks/by_version/{version}");
+        self.send(self.client.get(self.endpoint(&path)?)).await
+    }
+
+    /// The block containing the transaction with the given version, with
+    /// its transactions
+    pub async fn block_with_transactions_by_version(&self, version: u64) -> ChainResult<Block> {
+        let path = format!("blocks/by_version/{version}");
+        let request = self
+            .client
+            .get(self.endpoint(&path)?)
+            .query(&[("with_transactions", "true")]);
+        self.send(request).await
+    }
+
+    /// A transaction by its hash, which may still be pending
+    pub async fn transaction_by_hash(&self, hash: H256) -> ChainResult<Option<Transaction>> {
+        let path = format!("transactions/by_hash/{hash:#x}");
+        self.send_opt(self.client.get(self.endpoint(&path)?)).await
+    }
+
+    /// A committed transaction by its version
+    pub async fn transaction_by_version(&self, version: u64) -> ChainResult<Transaction> {
+        let path = format!("transactions/by_version/{version}");
+        self.send(self.client.get(self.endpoint(&path)?)).await
+    }
+
+    /// The gas unit price estimated to get a transaction committed quickly
+    pub async fn estimate_gas_price(&self) -> ChainResult<u64> {
+        let estimate: GasEstimation = self
+            .send(self.client.get(self.endpoint("estimate_gas_price")?))
+            .await?;
+        Ok(estimate.gas_estimate)
+    }
+
+    /// The message to sign to submit a transaction
+    pub async fn encode_submission(&self, request: &TransactionRequest) -> ChainResult<Vec<u8>> {
+        let message: String = self
+            .send(
+                self.client
+                    .post(self.endpoint("transactions/encode_submission")?)
+                    .json(request),
+            )
+            .await?;
+        decode_bytes(&message)
+    }
+
+    /// Submit a signed transaction and return its hash
+    pub async fn submit(&self, request: &SubmitTransactionRequest) -> ChainResult<H256> {
+        let pending: PendingTransaction = self
+            .send(
+                self.client
+                    .post(self.endpoint("transactions")?)
+                    .json(request),
+            )
+            .await?;
+        decode_h256(&pending.hash)
+    }
+
+    /// Simulate a transaction, which must carry an invalid signature
+    pub async fn simulate(&self, request: &SubmitTransactionRequest) -> ChainResult<Transaction> {
+        let transactions: Vec<Transaction> = self
+            .send(
+                self.client
+                    .post(self.endpoint("transactions/simulate")?)
+                    .json(request),
+            )
+            .await?;
+        transactions.into_iter().next().ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Simulation returned no transaction")
+        })
+    }
+
+    fn endpoint(&self, path: &str) -> ChainResult<Url> {
+        self.url
+            .join(path)
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn execute(&self, request: RequestBuilder) -> ChainResult<Response> {
+        Ok(RetryPolicy::default()
+            .send(request)
+            .await
+            .map_err(HyperlaneAptosError::from)?)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> ChainResult<T> {
+        let response = self.execute(request).await?;
+        Self::parse(response).await
+    }
+
+    /// Like `send`, but resources which weren't found are `None`
+    async fn send_opt<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> ChainResult<Option<T>> {
+        let response = self.execute(request).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Self::parse(response).await.map(Some)
+    }
+
+    async fn parse<T: DeserializeOwned>(response: Response) -> ChainResult<T> {
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(HyperlaneAptosError::Api { status, body }.into());
+        }
+        Ok(response.json().await.map_err(HyperlaneAptosError::from)?)
+    }
+}
+
+/// A 64 bit integer, which the API encodes as a string
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct U64(pub u64);
+
+impl Serialize for U64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for U64 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map(U64).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The latest state of the ledger
+#[derive(Debug, Clone, Deserialize)]
+pub struct LedgerInfo {
+    /// Height of the latest block
+    pub block_height: U64,
+    /// Version of the latest transaction
+    pub ledger_version: U64,
+}
+
+/// A block
+#[derive(Debug, Clone, Deserialize)]
+pub struct Block {
+    /// Height of the block
+    pub block_height: U64,
+    /// Hex encoded hash of the block
+    pub block_hash: String,
+    /// Unix time of the block in microseconds
+    pub block_timestamp: U64,
+    /// Version of the first transaction of the block
+    pub first_version: U64,
+    /// Version of the last transaction of the block
+    pub last_version: U64,
+    /// Transactions of the block, if they were requested. Only the first
+    /// page of the transactions of large blocks is returned.
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+impl Block {
+    /// Whether the transaction with the given version is in the block
+    pub fn contains(&self, version: u64) -> bool {
+        (self.first_version.0..=self.last_version.0).contains(&version)
+    }
+}
+
+/// A transaction, which is pending until it's committed
+#[derive(Debug, Clone, Deserialize)]
+pub struct Transaction {
+    /// Kind of the transaction, e.g. `pending_transaction` or
+    /// `user_transaction`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Hex encoded hash of the transaction
+    pub hash: String,
+    /// Version of committed transactions
+    pub version: Option<U64>,
+    /// Whether a committed transaction executed successfully
+    pub success: Option<bool>,
+    /// Status of the Move VM after a committed transaction
+    pub vm_status: Option<String>,
+    /// Sender of user transactions
+    pub sender: Option<String>,
+    /// Sequence number of the sender of user transactions
+    pub sequence_number: Option<U64>,
+    /// Max gas units of user transactions
+    pub max_gas_amount: Option<U64>,
+    /// Price of a gas unit of user transactions, in octas
+    pub gas_unit_price: Option<U64>,
+    /// Gas units used by committed transactions
+    pub gas_used: Option<U64>,
+    /// The payload of user transactions
+    pub payload: Option<TransactionPayload>,
+    /// Events emitted by committed transactions
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+impl Transaction {
+    /// Kind of transactions which aren't committed yet
+    pub const PENDING: &'static str = "pending_transaction";
+
+    /// Whether the transaction is committed
+    pub fn is_committed(&self) -> bool {
+        self.kind != Self::PENDING
+    }
+}
+
+/// The payload of a user transaction
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionPayload {
+    /// The called entry function, if any
+    pub function: Option<String>,
+}
+
+/// An event emitted to an event handle
+#[derive(Debug, Clone, Deserialize)]
+pub struct Event {
+    /// Identifier of the event handle
+    pub guid: EventGuid,
+    /// Sequence number of the event within its handle
+    pub sequence_number: U64,
+    /// Move type of the event
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// The event
+    pub data: Value,
+    /// Version of the transaction emitting the event, which is only known
+    /// when querying events by their handle
+    pub version: Option<U64>,
+}
+
+/// Identifier of an event handle
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EventGuid {
+    /// Creation number of the handle within its account
+    pub creation_number: U64,
+    /// Account of the handle
+    pub account_address: String,
+}
+
+/// An account
+#[derive(Debug, Clone, Deserialize)]
+pub struct Account {
+    /// Sequence number of the next transaction of the account
+    pub sequence_number: U64,
+}
+
+/// An event handle of a resource
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventHandle {
+    /// Number of events emitted to the handle
+    pub counter: U64,
+}
+
+/// A user transaction to submit, without its signature
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionRequest {
+    /// Sender of the transaction
+    pub sender: String,
+    /// Sequence number of the sender
+    pub sequence_number: U64,
+    /// Max gas units of the transaction
+    pub max_gas_amount: U64,
+    /// Price of a gas unit, in octas
+    pub gas_unit_price: U64,
+    /// Unix time in seconds after which the transaction is discarded
+    pub expiration_timestamp_secs: U64,
+    /// The entry function called by the transaction
+    pub payload: EntryFunctionPayload,
+}
+
+/// The call of an entry function
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename = "entry_function_payload")]
+pub struct EntryFunctionPayload {
+    /// Identifier of the function, i.e. `address::module::function`
+    pub function: String,
+    /// Type arguments of the function
+    pub type_arguments: Vec<String>,
+    /// Arguments of the function, encoded as JSON
+    pub arguments: Vec<Value>,
+}
+
+/// A signed user transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmitTransactionRequest {
+    /// The transaction
+    #[serde(flatten)]
+    pub transaction: TransactionRequest,
+    /// Signature of the transaction
+    pub signature: Ed25519Signature,
+}
+
+/// An ed25519 signature of a transaction
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename = "ed25519_signature")]
+pub struct Ed25519Signature {
+    /// Hex encoded public key of the signer
+    pub public_key: String,
+    /// Hex encoded signature
+    pub signature: String,
+}
+
+#[derive(Serialize)]
+struct ViewRequest<'a> {
+    function: &'a str,
+    type_arguments: Vec<String>,
+    arguments: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct Resource<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct GasEstimation {
+    gas_estimate: u64,
+}
+
+#[derive(Deserialize)]
+struct PendingTransaction {
+    hash: String,
+}
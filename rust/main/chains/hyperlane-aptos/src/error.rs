@@ -0,0 +1,57 @@
+use hyperlane_core::{ChainCommunicationError, H256, U256};
+use reqwest::StatusCode;
+
+/// Errors from the crates specific to the hyperlane-aptos
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneAptosError {
+    /// The HTTP client couldn't be built
+    #[error(transparent)]
+    HttpClient(#[from] reqwest_utils::HttpClientError),
+    /// A request to the fullnode failed
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// The fullnode rejected a request
+    #[error("Aptos API request failed with status {status}: {body}")]
+    Api {
+        /// Status of the response
+        status: StatusCode,
+        /// Body of the response
+        body: String,
+    },
+    /// A view function returned values of an unexpected shape
+    #[error("Unexpected view function result: {0}")]
+    UnexpectedViewResult(String),
+    /// The data of an event couldn't be decoded
+    #[error("Invalid event data: {0}")]
+    InvalidEvent(#[from] serde_json::Error),
+    /// An address couldn't be parsed
+    #[error("Invalid Aptos address `{0}`")]
+    InvalidAddress(String),
+    /// Hex decoding error
+    #[error("{0}")]
+    Hex(#[from] hex::FromHexError),
+    /// The key of the signer is invalid
+    #[error("Invalid ed25519 key: {0}")]
+    InvalidKey(#[from] ed25519_dalek::SignatureError),
+    /// The sequence number of an event doesn't fit the indexed range
+    #[error("Event sequence number {0} out of range")]
+    SequenceOutOfRange(u64),
+    /// A gas limit doesn't fit the max gas amount of a transaction
+    #[error("Gas limit {0} out of range")]
+    GasLimitOutOfRange(U256),
+    /// The message was already delivered
+    #[error("Message {0:?} was already delivered")]
+    AlreadyDelivered(H256),
+    /// The recipient of a message has no modules published
+    #[error("Recipient {0:?} isn't a deployed contract")]
+    RecipientNotDeployed(H256),
+}
+
+impl From<HyperlaneAptosError> for ChainCommunicationError {
+    fn from(value: HyperlaneAptosError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
@@ -0,0 +1,360 @@
+//! The event streams of the mailbox and parsing of their events. The
+//! mailbox emits its events to the event handles of its `MailboxState`
+//! resource, so the sequence numbers of the events are the indices of the
+//! dispatched and processed messages.
+
+use std::ops::RangeInclusive;
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, Decode, HyperlaneMessage, LogMeta, H256, H512, U256,
+};
+use serde::Deserialize;
+
+use crate::client::{Block, Event, EventHandle, Transaction, U64};
+use crate::error::HyperlaneAptosError;
+use crate::utils::{decode_bytes, decode_h256, parse_address, to_aptos_address};
+use crate::AptosClient;
+
+/// Name of the module of the mailbox
+pub(crate) const MAILBOX_MODULE: &str = "mailbox";
+
+/// An event stream of the mailbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EventStream {
+    /// Messages dispatched by the mailbox
+    Dispatch,
+    /// Messages processed by the mailbox
+    Process,
+}
+
+impl EventStream {
+    /// The field of the event handle in the `MailboxState` resource
+    fn field(self) -> &'static str {
+        match self {
+            Self::Dispatch => "dispatch_events",
+            Self::Process => "process_events",
+        }
+    }
+
+    /// The name of the Move struct of the events
+    fn event_name(self) -> &'static str {
+        match self {
+            Self::Dispatch => "DispatchEvent",
+            Self::Process => "ProcessEvent",
+        }
+    }
+
+    /// Whether `event` was emitted to this stream by the mailbox
+    fn contains(self, mailbox: H256, event: &Event) -> bool {
+        let emitted_by_mailbox =
+            parse_address(&event.guid.account_address).map_or(false, |address| address == mailbox);
+        emitted_by_mailbox
+            && event
+                .event_type
+                .ends_with(&format!("::{MAILBOX_MODULE}::{}", self.event_name()))
+    }
+}
+
+#[derive(Deserialize)]
+struct MailboxState {
+    dispatch_events: EventHandle,
+    process_events: EventHandle,
+}
+
+#[derive(Deserialize)]
+struct DispatchEvent {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct ProcessEvent {
+    message_id: String,
+}
+
+fn mailbox_state(mailbox: H256) -> String {
+    format!(
+        "{}::{MAILBOX_MODULE}::MailboxState",
+        to_aptos_address(mailbox)
+    )
+}
+
+/// The number of events emitted to a stream of the mailbox
+pub(crate) async fn event_count(
+    client: &AptosClient,
+    mailbox: H256,
+    stream: EventStream,
+) -> ChainResult<u32> {
+    let state: MailboxState = client
+        .account_resource(mailbox, &mailbox_state(mailbox))
+        .await?;
+    let handle = match stream {
+        EventStream::Dispatch => state.dispatch_events,
+        EventStream::Process => state.process_events,
+    };
+    to_sequence(handle.counter.0)
+}
+
+/// Fetch the events of a stream of the mailbox with the sequence numbers of
+/// `range`
+pub(crate) async fn fetch_events_in_range(
+    client: &AptosClient,
+    mailbox: H256,
+    stream: EventStream,
+    range: RangeInclusive<u32>,
+) -> ChainResult<Vec<(Event, LogMeta)>> {
+    if range.is_empty() {
+        return Ok(vec![]);
+    }
+    let start = u64::from(*range.start());
+    let count = u64::from(*range.end()) - start + 1;
+    let events = client
+        .events_by_handle(
+            mailbox,
+            &mailbox_state(mailbox),
+            stream.field(),
+            start,
+            count,
+        )
+        .await?;
+
+    // Blocks are fetched with their transactions, so events of the same
+    // block take a single request
+    let mut blocks: Vec<Block> = vec![];
+    let mut logs = Vec::with_capacity(events.len());
+    for event in events {
+        let version = event
+            .version
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str("Event without a transaction version")
+            })?
+            .0;
+        let block_index = match blocks.iter().position(|block| block.contains(version)) {
+            Some(index) => index,
+            None => {
+                blocks.push(client.block_with_transactions_by_version(version).await?);
+                blocks.len() - 1
+            }
+        };
+        let block = &mut blocks[block_index];
+        let tx_index = match block
+            .transactions
+            .iter()
+            .position(|transaction| transaction.version == Some(U64(version)))
+        {
+            Some(index) => index,
+            None => {
+                // The transaction is past the page of transactions returned
+                // with the block
+                let transaction = client.transaction_by_version(version).await?;
+                block.transactions.push(transaction);
+                block.transactions.len() - 1
+            }
+        };
+        let block = &blocks[block_index];
+        let meta = log_meta(mailbox, block, &block.transactions[tx_index], &event)?;
+        logs.push((event, meta));
+    }
+    Ok(logs)
+}
+
+/// Fetch the events of a stream of the mailbox emitted by the transaction
+/// with the given hash
+pub(crate) async fn fetch_events_in_tx(
+    client: &AptosClient,
+    mailbox: H256,
+    stream: EventStream,
+    tx_hash: H512,
+) -> ChainResult<Vec<(Event, LogMeta)>> {
+    let transaction = match client.transaction_by_hash(tx_hash.into()).await? {
+        Some(transaction) if transaction.is_committed() => transaction,
+        _ => return Ok(vec![]),
+    };
+    let Some(version) = transaction.version else {
+        return Ok(vec![]);
+    };
+    let block = client.block_by_version(version.0).await?;
+    transaction
+        .events
+        .iter()
+        .filter(|event| stream.contains(mailbox, event))
+        .map(|event| {
+            Ok((
+                event.clone(),
+                log_meta(mailbox, &block, &transaction, event)?,
+            ))
+        })
+        .collect()
+}
+
+/// The log meta of an event emitted by a transaction of a block. The
+/// transaction index is its position in the block and the log index the
+/// position of the event in the transaction.
+fn log_meta(
+    mailbox: H256,
+    block: &Block,
+    transaction: &Transaction,
+    event: &Event,
+) -> ChainResult<LogMeta> {
+    let version = transaction.version.unwrap_or_default().0;
+    let log_index = transaction
+        .events
+        .iter()
+        .position(|emitted| {
+            emitted.guid == event.guid && emitted.sequence_number == event.sequence_number
+        })
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str("Event not emitted by its transaction")
+        })?;
+    Ok(LogMeta {
+        address: mailbox,
+        block_number: block.block_height.0,
+        block_hash: decode_h256(&block.block_hash)?,
+        transaction_id: decode_h256(&transaction.hash)?.into(),
+        transaction_index: version.saturating_sub(block.first_version.0),
+        log_index: U256::from(log_index),
+    })
+}
+
+/// The message dispatched by a dispatch event
+pub(crate) fn parse_dispatch(event: &Event) -> ChainResult<HyperlaneMessage> {
+    let dispatch: DispatchEvent =
+        serde_json::from_value(event.data.clone()).map_err(HyperlaneAptosError::from)?;
+    let bytes = decode_bytes(&dispatch.message)?;
+    Ok(HyperlaneMessage::read_from(&mut bytes.as_slice())?)
+}
+
+/// The id of the message processed by a process event
+pub(crate) fn parse_process(event: &Event) -> ChainResult<H256> {
+    let process: ProcessEvent =
+        serde_json::from_value(event.data.clone()).map_err(HyperlaneAptosError::from)?;
+    decode_h256(&process.message_id)
+}
+
+/// The sequence number of an event as indexed by the agents
+pub(crate) fn to_sequence(sequence_number: u64) -> ChainResult<u32> {
+    u32::try_from(sequence_number)
+        .map_err(|_| HyperlaneAptosError::SequenceOutOfRange(sequence_number).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use hyperlane_core::Encode;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_mailbox_events() {
+        let mailbox = H256::repeat_byte(0xab);
+        let message = HyperlaneMessage {
+            nonce: 3,
+            origin: 14402,
+            body: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let event: Event = serde_json::from_value(json!({
+            "guid": {
+                "creation_number": "4",
+                "account_address": format!("{mailbox:#x}"),
+            },
+            "sequence_number": "3",
+            "type": format!("{mailbox:#x}::mailbox::DispatchEvent"),
+            "data": {
+                "message": format!("0x{}", hex::encode(message.to_vec())),
+                "message_id": format!("{:#x}", message.id()),
+            },
+            "version": "1024",
+        }))
+        .unwrap();
+
+        assert!(EventStream::Dispatch.contains(mailbox, &event));
+        assert!(!EventStream::Process.contains(mailbox, &event));
+        assert!(!EventStream::Dispatch.contains(H256::zero(), &event));
+        assert_eq!(parse_dispatch(&event).unwrap(), message);
+        assert!(parse_process(&event).is_err());
+    }
+
+    #[test]
+    fn test_parse_process_event() {
+        let mailbox = H256::repeat_byte(0xab);
+        let message_id = H256::repeat_byte(0x42);
+        let event: Event = serde_json::from_value(json!({
+            "guid": {
+                "creation_number": "5",
+                "account_address": format!("{mailbox:#x}"),
+            },
+            "sequence_number": "7",
+            "type": format!("{mailbox:#x}::mailbox::ProcessEvent"),
+            "data": {
+                "message_id": format!("{message_id:#x}"),
+                "origin": 1,
+                "sender": format!("{:#x}", H256::zero()),
+                "recipient": format!("{:#x}", H256::zero()),
+            },
+        }))
+        .unwrap();
+
+        assert!(EventStream::Process.contains(mailbox, &event));
+        assert!(!EventStream::Dispatch.contains(mailbox, &event));
+        assert_eq!(parse_process(&event).unwrap(), message_id);
+        assert!(parse_dispatch(&event).is_err());
+        assert_eq!(to_sequence(event.sequence_number.0).unwrap(), 7);
+        assert!(to_sequence(u64::from(u32::MAX) + 1).is_err());
+    }
+
+    fn event_json(mailbox: H256, sequence_number: u64) -> Value {
+        json!({
+            "guid": {
+                "creation_number": "4",
+                "account_address": format!("{mailbox:#x}"),
+            },
+            "sequence_number": sequence_number.to_string(),
+            "type": format!("{mailbox:#x}::mailbox::DispatchEvent"),
+            "data": {},
+        })
+    }
+
+    fn event(mailbox: H256, sequence_number: u64) -> Event {
+        serde_json::from_value(event_json(mailbox, sequence_number)).unwrap()
+    }
+
+    #[test]
+    fn test_log_meta() {
+        let mailbox = H256::repeat_byte(0xab);
+        let block_hash = H256::repeat_byte(0x01);
+        let tx_hash = H256::repeat_byte(0x02);
+        let block: Block = serde_json::from_value(json!({
+            "block_height": "50",
+            "block_hash": format!("{block_hash:#x}"),
+            "block_timestamp": "1700000000000000",
+            "first_version": "1000",
+            "last_version": "1009",
+            "transactions": [{
+                "type": "user_transaction",
+                "hash": format!("{tx_hash:#x}"),
+                "version": "1004",
+                "success": true,
+                "events": [
+                    event_json(H256::zero(), 0),
+                    event_json(mailbox, 3),
+                    event_json(mailbox, 4),
+                ],
+            }],
+        }))
+        .unwrap();
+        assert!(block.contains(1000) && block.contains(1009));
+        assert!(!block.contains(999) && !block.contains(1010));
+
+        let transaction = &block.transactions[0];
+        let meta = log_meta(mailbox, &block, transaction, &event(mailbox, 4)).unwrap();
+        assert_eq!(meta.address, mailbox);
+        assert_eq!(meta.block_number, 50);
+        assert_eq!(meta.block_hash, block_hash);
+        assert_eq!(meta.transaction_id, H512::from(tx_hash));
+        assert_eq!(meta.transaction_index, 4);
+        assert_eq!(meta.log_index, U256::from(2));
+
+        // Events the transaction didn't emit have no log index
+        assert!(log_meta(mailbox, &block, transaction, &event(mailbox, 5)).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType, H256, U256,
+};
+use num_traits::FromPrimitive;
+use tracing::{instrument, warn};
+
+use crate::utils::function_id;
+use crate::view::{as_u64, value};
+use crate::{AptosProvider, ConnectionConf};
+
+/// Name of the module every ISM package exposes its type through
+const ISM_MODULE: &str = "interchain_security_module";
+
+/// A reference to an ISM contract on some Aptos chain
+#[derive(Clone, Debug)]
+pub struct AptosInterchainSecurityModule {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: AptosProvider,
+}
+
+impl AptosInterchainSecurityModule {
+    /// Create a new Aptos ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = AptosProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for AptosInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for AptosInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let values = self
+            .provider
+            .client()
+            .view(
+                &function_id(self.address, ISM_MODULE, "get_module_type"),
+                vec![],
+            )
+            .await?;
+        let module = as_u64(value(&values, 0)?)?;
+        if let Some(module_type) = ModuleType::from_u64(module) {
+            Ok(module_type)
+        } else {
+            warn!(%module, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    async fn dry_run_verify(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        // Verification is part of `inbox_process`, whose gas is estimated
+        // by simulating it
+        Ok(Some(U256::zero()))
+    }
+}
@@ -0,0 +1,33 @@
+//! Implementation of hyperlane for Aptos.
+//!
+//! The chain is read and written through the REST API of an Aptos fullnode.
+//! Hyperlane contracts are Move packages, addressed by the account they're
+//! published at, and their events are indexed by the sequence numbers of
+//! the event handles of the mailbox.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub use client::AptosClient;
+pub use interchain_security_module::*;
+pub use mailbox::*;
+pub use merkle_tree_hook::*;
+pub use multisig_ism::*;
+pub use provider::*;
+pub use signer::AptosSigner;
+pub use trait_builder::*;
+pub use validator_announce::*;
+
+mod client;
+mod error;
+mod events;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+mod provider;
+mod signer;
+mod trait_builder;
+mod utils;
+mod validator_announce;
+mod view;
@@ -0,0 +1,395 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Encode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, ReorgPeriod, SequenceAwareIndexer,
+    TxCostEstimate, TxOutcome, H256, H512, U256,
+};
+use serde_json::Value;
+use tracing::instrument;
+
+use crate::client::Event;
+use crate::error::HyperlaneAptosError;
+use crate::events::{
+    event_count, fetch_events_in_range, fetch_events_in_tx, parse_dispatch, parse_process,
+    to_sequence, EventStream, MAILBOX_MODULE,
+};
+use crate::utils::{encode_bytes, function_id, to_aptos_address};
+use crate::view::{as_address, as_bool, as_u64, value};
+use crate::{AptosClient, AptosProvider, AptosSigner, ConnectionConf};
+
+/// Percentage added to the gas used by a simulated `process` call, as the
+/// gas used may change between the simulation and the submission
+const GAS_ESTIMATE_BUFFER_PERCENT: u64 = 20;
+
+/// A reference to a Mailbox contract on some Aptos chain
+#[derive(Clone, Debug)]
+pub struct AptosMailbox {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: AptosProvider,
+    signer: Option<AptosSigner>,
+    max_gas_amount: u64,
+}
+
+impl AptosMailbox {
+    /// Create a new Aptos mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<AptosSigner>,
+    ) -> ChainResult<Self> {
+        let provider = AptosProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+            signer,
+            max_gas_amount: conf.max_gas_amount,
+        })
+    }
+
+    pub(crate) fn client(&self) -> &AptosClient {
+        self.provider.client()
+    }
+
+    /// Call a view function of the mailbox module
+    pub(crate) async fn view(
+        &self,
+        function: &str,
+        arguments: Vec<Value>,
+    ) -> ChainResult<Vec<Value>> {
+        self.client()
+            .view(
+                &function_id(self.address, MAILBOX_MODULE, function),
+                arguments,
+            )
+            .await
+    }
+
+    /// The number of dispatched messages, i.e. the nonce of the next one
+    pub(crate) async fn outbox_count(&self) -> ChainResult<u32> {
+        let values = self.view("outbox_get_count", vec![]).await?;
+        let count = as_u64(value(&values, 0)?)?;
+        u32::try_from(count).map_err(ChainCommunicationError::from_other)
+    }
+
+    fn process_function(&self) -> String {
+        function_id(self.address, MAILBOX_MODULE, "inbox_process")
+    }
+
+    fn process_arguments(message: &HyperlaneMessage, metadata: &[u8]) -> Vec<Value> {
+        vec![encode_bytes(&message.to_vec()), encode_bytes(metadata)]
+    }
+}
+
+impl HyperlaneContract for AptosMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl Mailbox for AptosMailbox {
+    /// Aptos has instant finality, so the reorg period is ignored
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, _reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        self.outbox_count().await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let values = self
+            .view("delivered", vec![encode_bytes(id.as_bytes())])
+            .await?;
+        as_bool(value(&values, 0)?)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let values = self.view("get_default_ism", vec![]).await?;
+        as_address(value(&values, 0)?)
+    }
+
+    /// The mailbox falls back to the default ISM for recipients without an
+    /// ISM of their own
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let values = self
+            .view(
+                "get_recipient_ism",
+                vec![Value::String(to_aptos_address(recipient))],
+            )
+            .await?;
+        as_address(value(&values, 0)?)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let max_gas_amount = match tx_gas_limit {
+            Some(limit) => {
+                u64::try_from(limit).map_err(|_| HyperlaneAptosError::GasLimitOutOfRange(limit))?
+            }
+            None => self.max_gas_amount,
+        };
+        signer
+            .submit(
+                self.client(),
+                self.process_function(),
+                Self::process_arguments(message, metadata),
+                max_gas_amount,
+            )
+            .await
+    }
+
+    /// The estimate is the gas used by a simulated `process` call plus a
+    /// buffer. Without a signer to simulate the call from, only whether the
+    /// message can be delivered at all is checked, and the estimate is the
+    /// configured max gas amount.
+    #[instrument(err, ret, skip(self), fields(hyp_message=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let Some(signer) = &self.signer else {
+            if self.delivered(message.id()).await? {
+                return Err(HyperlaneAptosError::AlreadyDelivered(message.id()).into());
+            }
+            if !self.provider.is_contract(&message.recipient).await? {
+                let err = HyperlaneAptosError::RecipientNotDeployed(message.recipient);
+                return Err(ChainCommunicationError::RecipientHandleFailed(Box::new(
+                    err.into(),
+                )));
+            }
+            let gas_price = self.client().estimate_gas_price().await?;
+            return Ok(TxCostEstimate {
+                gas_limit: self.max_gas_amount.into(),
+                gas_price: FixedPointNumber::from(gas_price),
+                l2_gas_limit: None,
+            });
+        };
+        let simulation = signer
+            .simulate(
+                self.client(),
+                self.process_function(),
+                Self::process_arguments(message, metadata),
+                self.max_gas_amount,
+            )
+            .await?;
+        if simulation.success != Some(true) {
+            return Err(ChainCommunicationError::from_other_str(&format!(
+                "Simulation of process failed: {}",
+                simulation.vm_status.unwrap_or_default()
+            )));
+        }
+        let gas_used = simulation.gas_used.unwrap_or_default().0;
+        Ok(TxCostEstimate {
+            gas_limit: buffered_gas_limit(gas_used, self.max_gas_amount).into(),
+            gas_price: FixedPointNumber::from(simulation.gas_unit_price.unwrap_or_default().0),
+            l2_gas_limit: None,
+        })
+    }
+
+    /// Entry function calls are encoded by the fullnode, so the calldata
+    /// are the arguments of `inbox_process` as JSON
+    fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
+        Ok(serde_json::to_vec(&Self::process_arguments(
+            message, metadata,
+        ))?)
+    }
+}
+
+/// The gas used by a simulation plus the buffer, up to the max gas amount
+/// the simulation was run with
+fn buffered_gas_limit(gas_used: u64, max_gas_amount: u64) -> u64 {
+    let buffer = gas_used.saturating_mul(GAS_ESTIMATE_BUFFER_PERCENT) / 100;
+    gas_used.saturating_add(buffer).min(max_gas_amount)
+}
+
+/// Struct that retrieves dispatched messages and deliveries from the event
+/// streams of an Aptos mailbox
+#[derive(Debug, Clone)]
+pub struct AptosMailboxIndexer {
+    mailbox: AptosMailbox,
+}
+
+impl AptosMailboxIndexer {
+    /// Create a new Aptos mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let mailbox = AptosMailbox::new(conf, locator, None)?;
+        Ok(Self { mailbox })
+    }
+
+    async fn events_in_range(
+        &self,
+        stream: EventStream,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Event, LogMeta)>> {
+        fetch_events_in_range(self.mailbox.client(), self.mailbox.address, stream, range).await
+    }
+
+    async fn events_in_tx(
+        &self,
+        stream: EventStream,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Event, LogMeta)>> {
+        fetch_events_in_tx(self.mailbox.client(), self.mailbox.address, stream, tx_hash).await
+    }
+
+    /// The height of the latest block. Aptos has instant finality, so it's
+    /// final.
+    pub(crate) async fn latest_block(&self) -> ChainResult<u32> {
+        let ledger = self.mailbox.client().ledger_info().await?;
+        u32::try_from(ledger.block_height.0).map_err(ChainCommunicationError::from_other)
+    }
+
+    pub(crate) async fn dispatches_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        dispatches(self.events_in_range(EventStream::Dispatch, range).await?)
+    }
+
+    pub(crate) async fn dispatches_in_tx(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        dispatches(self.events_in_tx(EventStream::Dispatch, tx_hash).await?)
+    }
+
+    pub(crate) async fn dispatch_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.latest_block().await?;
+        let count = event_count(
+            self.mailbox.client(),
+            self.mailbox.address,
+            EventStream::Dispatch,
+        )
+        .await?;
+        Ok((Some(count), tip))
+    }
+}
+
+fn dispatches(
+    events: Vec<(Event, LogMeta)>,
+) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+    events
+        .into_iter()
+        .map(|(event, meta)| Ok((parse_dispatch(&event)?.into(), meta)))
+        .collect()
+}
+
+fn deliveries(events: Vec<(Event, LogMeta)>) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+    events
+        .into_iter()
+        .map(|(event, meta)| {
+            let message_id = parse_process(&event)?;
+            let sequence = to_sequence(event.sequence_number.0)?;
+            Ok((Indexed::new(message_id).with_sequence(sequence), meta))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for AptosMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        self.dispatches_in_range(range).await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.latest_block().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        self.dispatches_in_tx(tx_hash).await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for AptosMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        self.dispatch_count_and_tip().await
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for AptosMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        deliveries(self.events_in_range(EventStream::Process, range).await?)
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.latest_block().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        deliveries(self.events_in_tx(EventStream::Process, tx_hash).await?)
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for AptosMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.latest_block().await?;
+        let count = event_count(
+            self.mailbox.client(),
+            self.mailbox.address,
+            EventStream::Process,
+        )
+        .await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffered_gas_limit() {
+        assert_eq!(buffered_gas_limit(1_000, 100_000), 1_200);
+        assert_eq!(buffered_gas_limit(0, 100_000), 0);
+        // The limit never exceeds the max gas amount
+        assert_eq!(buffered_gas_limit(90_000, 100_000), 100_000);
+        assert_eq!(buffered_gas_limit(u64::MAX, u64::MAX), u64::MAX);
+    }
+}
@@ -0,0 +1,112 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::accumulator::incremental::IncrementalMerkle;
+use hyperlane_core::accumulator::TREE_DEPTH;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneMessage, Indexed, Indexer, LogMeta, MerkleTreeHook,
+    MerkleTreeInsertion, ReorgPeriod, SequenceAwareIndexer, H256, H512,
+};
+use tracing::instrument;
+
+use crate::error::HyperlaneAptosError;
+use crate::view::{as_array, as_h256, as_u64, value};
+use crate::{AptosMailbox, AptosMailboxIndexer, ConnectionConf};
+
+/// The merkle tree of dispatched messages is kept by the mailbox itself, and
+/// Aptos has instant finality, so reorg periods are ignored
+#[async_trait]
+impl MerkleTreeHook for AptosMailbox {
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, _reorg_period: &ReorgPeriod) -> ChainResult<IncrementalMerkle> {
+        let values = self.view("outbox_get_tree", vec![]).await?;
+        let tree = value(&values, 0)?;
+        let branch_values = as_array(&tree["branch"])?;
+        if branch_values.len() != TREE_DEPTH {
+            return Err(HyperlaneAptosError::UnexpectedViewResult(format!(
+                "expected a branch of {TREE_DEPTH} nodes, got {}",
+                branch_values.len()
+            ))
+            .into());
+        }
+        let mut branch = [H256::zero(); TREE_DEPTH];
+        for (node, node_value) in branch.iter_mut().zip(branch_values) {
+            *node = as_h256(node_value)?;
+        }
+        let count = as_u64(&tree["count"])? as usize;
+        Ok(IncrementalMerkle { branch, count })
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, _reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        self.outbox_count().await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn latest_checkpoint(&self, _reorg_period: &ReorgPeriod) -> ChainResult<Checkpoint> {
+        let values = self.view("outbox_latest_checkpoint", vec![]).await?;
+        let root = as_h256(value(&values, 0)?)?;
+        let index = as_u64(value(&values, 1)?)?;
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address(),
+            mailbox_domain: self.domain().id(),
+            root,
+            index: u32::try_from(index).map_err(ChainCommunicationError::from_other)?,
+        })
+    }
+}
+
+/// Struct that retrieves insertions into the merkle tree of an Aptos
+/// mailbox, which are the dispatched messages
+#[derive(Debug, Clone)]
+pub struct AptosMerkleTreeHookIndexer(AptosMailboxIndexer);
+
+impl AptosMerkleTreeHookIndexer {
+    /// Create a new Aptos merkle tree hook indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        Ok(Self(AptosMailboxIndexer::new(conf, locator)?))
+    }
+}
+
+fn insertions(
+    messages: Vec<(Indexed<HyperlaneMessage>, LogMeta)>,
+) -> Vec<(Indexed<MerkleTreeInsertion>, LogMeta)> {
+    messages
+        .into_iter()
+        .map(|(message, meta)| {
+            let message = message.inner();
+            let insertion = MerkleTreeInsertion::new(message.nonce, message.id());
+            (insertion.into(), meta)
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for AptosMerkleTreeHookIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        Ok(insertions(self.0.dispatches_in_range(range).await?))
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.0.latest_block().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        Ok(insertions(self.0.dispatches_in_tx(tx_hash).await?))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for AptosMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        self.0.dispatch_count_and_tip().await
+    }
+}
@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+use serde_json::Value;
+use tracing::instrument;
+
+use crate::utils::function_id;
+use crate::view::{as_address, as_array, as_u64, value};
+use crate::{AptosProvider, ConnectionConf};
+
+/// Name of the module of the multisig ISM
+const MULTISIG_ISM_MODULE: &str = "multisig_ism";
+
+/// A reference to a MultisigIsm contract on some Aptos chain
+#[derive(Clone, Debug)]
+pub struct AptosMultisigIsm {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: AptosProvider,
+}
+
+impl AptosMultisigIsm {
+    /// Create a new Aptos multisig ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = AptosProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for AptosMultisigIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for AptosMultisigIsm {
+    /// Validators are ethereum addresses, which the module stores as
+    /// addresses left padded with zeros
+    #[instrument(err, ret, skip(self))]
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let values = self
+            .provider
+            .client()
+            .view(
+                &function_id(
+                    self.address,
+                    MULTISIG_ISM_MODULE,
+                    "validators_and_threshold",
+                ),
+                vec![Value::from(message.origin)],
+            )
+            .await?;
+        let validators = as_array(value(&values, 0)?)?
+            .iter()
+            .map(as_address)
+            .collect::<ChainResult<Vec<_>>>()?;
+        let threshold = as_u64(value(&values, 1)?)?;
+        let threshold = u8::try_from(threshold).map_err(ChainCommunicationError::from_other)?;
+        Ok((validators, threshold))
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider,
+    HyperlaneProviderError, TxnInfo, TxnReceiptInfo, H256, H512, U256,
+};
+
+use crate::client::Block;
+use crate::signer::balance;
+use crate::utils::{decode_h256, parse_address};
+use crate::{AptosClient, ConnectionConf};
+
+/// A wrapper around an Aptos REST API client to get generic blockchain
+/// information.
+#[derive(Debug, Clone)]
+pub struct AptosProvider {
+    domain: HyperlaneDomain,
+    client: Arc<AptosClient>,
+}
+
+impl AptosProvider {
+    /// Create a new Aptos provider.
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf) -> ChainResult<Self> {
+        let client = Arc::new(AptosClient::new(conf)?);
+        Ok(Self { domain, client })
+    }
+
+    /// Get the REST API client
+    pub fn client(&self) -> &Arc<AptosClient> {
+        &self.client
+    }
+
+    fn block_info(block: Block) -> ChainResult<BlockInfo> {
+        Ok(BlockInfo {
+            hash: decode_h256(&block.block_hash)?,
+            // Block timestamps are in microseconds
+            timestamp: block.block_timestamp.0 / 1_000_000,
+            number: block.block_height.0,
+        })
+    }
+}
+
+impl HyperlaneChain for AptosProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for AptosProvider {
+    async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo> {
+        let block = self.client.block_by_height(height).await?;
+        Self::block_info(block)
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let transaction = self
+            .client
+            .transaction_by_hash(H256::from(*hash))
+            .await?
+            .filter(|transaction| transaction.is_committed())
+            .ok_or(HyperlaneProviderError::CouldNotFindTransactionByHash(*hash))?;
+
+        // Only user transactions have a sender, which pays for their gas
+        let sender = match &transaction.sender {
+            Some(sender) => parse_address(sender)?,
+            None => H256::zero(),
+        };
+        // The recipient is the account of the module of the called entry
+        // function
+        let recipient = transaction
+            .payload
+            .as_ref()
+            .and_then(|payload| payload.function.as_deref())
+            .and_then(|function| function.split("::").next())
+            .map(parse_address)
+            .transpose()?;
+        let gas_limit = U256::from(transaction.max_gas_amount.unwrap_or_default().0);
+        let gas_used = U256::from(transaction.gas_used.unwrap_or_default().0);
+        let gas_price = transaction.gas_unit_price.map(|price| U256::from(price.0));
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price,
+            nonce: transaction.sequence_number.unwrap_or_default().0,
+            sender,
+            recipient,
+            receipt: Some(TxnReceiptInfo {
+                gas_used,
+                cumulative_gas_used: gas_used,
+                effective_gas_price: gas_price,
+            }),
+            raw_input_data: None,
+        })
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        self.client.has_modules(*address).await
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        balance(&self.client, parse_address(&address)?).await
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        let ledger = self.client.ledger_info().await?;
+        let block = self.client.block_by_height(ledger.block_height.0).await?;
+        let gas_price = self.client.estimate_gas_price().await?;
+        Ok(Some(ChainInfo {
+            latest_block: Self::block_info(block)?,
+            min_gas_price: Some(gas_price.into()),
+        }))
+    }
+}
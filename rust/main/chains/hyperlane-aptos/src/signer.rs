@@ -0,0 +1,213 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use hyperlane_core::{ChainResult, FixedPointNumber, TxOutcome, H256, U256};
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Instant};
+use tracing::{debug, instrument, warn};
+
+use crate::client::{
+    Ed25519Signature, EntryFunctionPayload, SubmitTransactionRequest, Transaction,
+    TransactionRequest, U64,
+};
+use crate::error::HyperlaneAptosError;
+use crate::utils::to_aptos_address;
+use crate::view::{as_u64, value};
+use crate::AptosClient;
+
+/// Scheme byte of single ed25519 keys, appended to the public key to derive
+/// the authentication key, and thereby the address, of an account
+const ED25519_SCHEME: u8 = 0;
+/// Type of the native coin
+const APTOS_COIN: &str = "0x1::aptos_coin::AptosCoin";
+/// How long submitted transactions are valid for
+const TRANSACTION_TTL: Duration = Duration::from_secs(60);
+/// Interval of polling for the commitment of a submitted transaction
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A signer of Aptos transactions, i.e. an account with a single ed25519 key
+#[derive(Clone)]
+pub struct AptosSigner {
+    keypair: Arc<Keypair>,
+    address: H256,
+    /// Transactions are submitted one at a time, since every transaction of
+    /// the account must carry the next sequence number
+    submit_lock: Arc<Mutex<()>>,
+}
+
+impl AptosSigner {
+    /// Create a signer from the 32 byte private key of an ed25519 key
+    pub fn new(secret: &[u8]) -> ChainResult<Self> {
+        let secret = SecretKey::from_bytes(secret).map_err(HyperlaneAptosError::from)?;
+        let public = PublicKey::from(&secret);
+        let address = H256::from_slice(&Sha3_256::digest(
+            [public.as_bytes().as_slice(), &[ED25519_SCHEME]].concat(),
+        ));
+        Ok(Self {
+            keypair: Arc::new(Keypair { secret, public }),
+            address,
+            submit_lock: Default::default(),
+        })
+    }
+
+    /// The address of the account
+    pub fn address_h256(&self) -> H256 {
+        self.address
+    }
+
+    /// The hex form of the address of the account
+    pub fn address_string(&self) -> String {
+        to_aptos_address(self.address)
+    }
+
+    /// Submit a transaction calling `function` with `arguments` and wait
+    /// for it to be committed
+    #[instrument(skip(self, client, arguments), fields(sender = %self.address_string()))]
+    pub(crate) async fn submit(
+        &self,
+        client: &AptosClient,
+        function: String,
+        arguments: Vec<Value>,
+        max_gas_amount: u64,
+    ) -> ChainResult<TxOutcome> {
+        let _guard = self.submit_lock.lock().await;
+
+        let transaction = self
+            .transaction(client, function, arguments, max_gas_amount)
+            .await?;
+        let message = client.encode_submission(&transaction).await?;
+        let signature = self.keypair.sign(&message);
+        let request = SubmitTransactionRequest {
+            transaction,
+            signature: self.signature(&signature.to_bytes()),
+        };
+        let hash = client.submit(&request).await?;
+        debug!(?hash, "Submitted transaction");
+
+        let Some(committed) = self.wait_for_commitment(client, hash).await else {
+            warn!(?hash, "Transaction wasn't committed in time");
+            return Ok(TxOutcome {
+                transaction_id: hash.into(),
+                executed: false,
+                gas_used: U256::zero(),
+                gas_price: FixedPointNumber::zero(),
+                block_number: None,
+            });
+        };
+        let block_number = match committed.version {
+            Some(version) => client
+                .block_by_version(version.0)
+                .await
+                .ok()
+                .map(|block| block.block_height.0),
+            None => None,
+        };
+        if committed.success != Some(true) {
+            warn!(?hash, vm_status = ?committed.vm_status, "Transaction failed");
+        }
+        Ok(TxOutcome {
+            transaction_id: hash.into(),
+            executed: committed.success == Some(true),
+            gas_used: committed.gas_used.unwrap_or_default().0.into(),
+            gas_price: FixedPointNumber::from(committed.gas_unit_price.unwrap_or_default().0),
+            block_number,
+        })
+    }
+
+    /// Simulate a transaction calling `function` with `arguments`
+    pub(crate) async fn simulate(
+        &self,
+        client: &AptosClient,
+        function: String,
+        arguments: Vec<Value>,
+        max_gas_amount: u64,
+    ) -> ChainResult<Transaction> {
+        let transaction = self
+            .transaction(client, function, arguments, max_gas_amount)
+            .await?;
+        // Simulated transactions must not carry a valid signature
+        let request = SubmitTransactionRequest {
+            transaction,
+            signature: self.signature(&[0; ed25519_dalek::SIGNATURE_LENGTH]),
+        };
+        client.simulate(&request).await
+    }
+
+    /// The balance of the account in octas
+    pub(crate) async fn balance(&self, client: &AptosClient) -> ChainResult<U256> {
+        balance(client, self.address).await
+    }
+
+    async fn transaction(
+        &self,
+        client: &AptosClient,
+        function: String,
+        arguments: Vec<Value>,
+        max_gas_amount: u64,
+    ) -> ChainResult<TransactionRequest> {
+        let account = client.account(self.address).await?;
+        let gas_unit_price = client.estimate_gas_price().await?;
+        let expiration = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now + TRANSACTION_TTL)
+            .unwrap_or(TRANSACTION_TTL);
+        Ok(TransactionRequest {
+            sender: self.address_string(),
+            sequence_number: account.sequence_number,
+            max_gas_amount: U64(max_gas_amount),
+            gas_unit_price: U64(gas_unit_price),
+            expiration_timestamp_secs: U64(expiration.as_secs()),
+            payload: EntryFunctionPayload {
+                function,
+                type_arguments: vec![],
+                arguments,
+            },
+        })
+    }
+
+    fn signature(&self, signature: &[u8]) -> Ed25519Signature {
+        Ed25519Signature {
+            public_key: format!("0x{}", hex::encode(self.keypair.public.as_bytes())),
+            signature: format!("0x{}", hex::encode(signature)),
+        }
+    }
+
+    /// Wait until the transaction with the given hash is committed, or
+    /// expired
+    async fn wait_for_commitment(&self, client: &AptosClient, hash: H256) -> Option<Transaction> {
+        let deadline = Instant::now() + TRANSACTION_TTL;
+        while Instant::now() < deadline {
+            sleep(POLL_INTERVAL).await;
+            match client.transaction_by_hash(hash).await {
+                Ok(Some(transaction)) if transaction.is_committed() => return Some(transaction),
+                Ok(_) => {}
+                Err(err) => warn!(?err, "Failed to get transaction"),
+            }
+        }
+        None
+    }
+}
+
+impl Debug for AptosSigner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AptosSigner")
+            .field("address", &self.address_string())
+            .finish()
+    }
+}
+
+/// The balance of APT of an account in octas
+pub(crate) async fn balance(client: &AptosClient, address: H256) -> ChainResult<U256> {
+    let values = client
+        .view_generic(
+            "0x1::coin::balance",
+            vec![APTOS_COIN.to_owned()],
+            vec![Value::String(to_aptos_address(address))],
+        )
+        .await?;
+    Ok(as_u64(value(&values, 0)?)?.into())
+}
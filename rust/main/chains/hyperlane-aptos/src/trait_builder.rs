@@ -0,0 +1,30 @@
+use hyperlane_core::NativeToken;
+use url::Url;
+
+/// Aptos connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Base url of the REST API of a fullnode, e.g.
+    /// `https://fullnode.mainnet.aptoslabs.com/v1/`
+    pub url: Url,
+    /// Native token and its denomination
+    pub native_token: NativeToken,
+    /// Max gas units of submitted transactions
+    pub max_gas_amount: u64,
+}
+
+impl ConnectionConf {
+    /// The default max gas units of submitted transactions
+    pub const DEFAULT_MAX_GAS_AMOUNT: u64 = 200_000;
+}
+
+/// An error type when parsing a connection configuration.
+#[derive(thiserror::Error, Debug)]
+pub enum ConnectionConfError {
+    /// Missing `url` for connection configuration
+    #[error("Missing `url` for connection configuration")]
+    MissingConnectionUrl,
+    /// Invalid `url` for connection configuration
+    #[error("Invalid `url` for connection configuration: `{0}` ({1})")]
+    InvalidConnectionUrl(String, url::ParseError),
+}
@@ -0,0 +1,84 @@
+use hyperlane_core::{ChainResult, H256};
+use serde_json::Value;
+
+use crate::error::HyperlaneAptosError;
+
+/// The full, zero padded form of an address, e.g. to pass it to view
+/// functions
+pub(crate) fn to_aptos_address(address: H256) -> String {
+    format!("{address:#x}")
+}
+
+/// Parse an address in either its full or its short form, e.g. `0x1`
+pub(crate) fn parse_address(address: &str) -> ChainResult<H256> {
+    let hex_str = address.strip_prefix("0x").unwrap_or(address);
+    if hex_str.is_empty() || hex_str.len() > 64 {
+        return Err(HyperlaneAptosError::InvalidAddress(address.to_owned()).into());
+    }
+    let bytes = hex::decode(format!("{hex_str:0>64}"))
+        .map_err(|_| HyperlaneAptosError::InvalidAddress(address.to_owned()))?;
+    Ok(H256::from_slice(&bytes))
+}
+
+/// The identifier of a function of a module published at `address`
+pub(crate) fn function_id(address: H256, module: &str, function: &str) -> String {
+    format!("{}::{module}::{function}", to_aptos_address(address))
+}
+
+/// Encode bytes as a `vector<u8>` argument, which the API expects as a hex
+/// string
+pub(crate) fn encode_bytes(bytes: &[u8]) -> Value {
+    Value::String(format!("0x{}", hex::encode(bytes)))
+}
+
+/// Decode a hex string, as the API returns `vector<u8>` values
+pub(crate) fn decode_bytes(bytes: &str) -> ChainResult<Vec<u8>> {
+    let bytes = hex::decode(bytes.strip_prefix("0x").unwrap_or(bytes))
+        .map_err(HyperlaneAptosError::from)?;
+    Ok(bytes)
+}
+
+/// Decode a hex string of 32 bytes, e.g. a message id or transaction hash
+pub(crate) fn decode_h256(hash: &str) -> ChainResult<H256> {
+    let bytes = decode_bytes(hash)?;
+    if bytes.len() != 32 {
+        return Err(HyperlaneAptosError::Hex(hex::FromHexError::InvalidStringLength).into());
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_forms() {
+        let framework = parse_address("0x1").unwrap();
+        assert_eq!(framework, H256::from_low_u64_be(1));
+        assert_eq!(
+            to_aptos_address(framework),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            parse_address(&to_aptos_address(framework)).unwrap(),
+            framework
+        );
+        assert_eq!(
+            function_id(framework, "coin", "balance"),
+            format!("{}::coin::balance", to_aptos_address(framework))
+        );
+
+        assert!(parse_address("0x").is_err());
+        assert!(parse_address("0xzz").is_err());
+        assert!(parse_address(&format!("0x{}", "1".repeat(65))).is_err());
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let bytes = vec![0xde, 0xad, 0xbe, 0xef];
+        let encoded = encode_bytes(&bytes);
+        assert_eq!(encoded, Value::String("0xdeadbeef".to_owned()));
+        assert_eq!(decode_bytes(encoded.as_str().unwrap()).unwrap(), bytes);
+        assert!(decode_h256("0xdeadbeef").is_err());
+    }
+}
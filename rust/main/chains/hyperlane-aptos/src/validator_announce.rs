@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedType, TxOutcome,
+    ValidatorAnnounce, H256, U256,
+};
+use serde_json::Value;
+use tracing::{instrument, trace};
+
+use crate::utils::{encode_bytes, function_id, to_aptos_address};
+use crate::view::{as_array, as_str, value};
+use crate::{AptosClient, AptosProvider, AptosSigner, ConnectionConf};
+
+/// Name of the module of the validator announce
+const VALIDATOR_ANNOUNCE_MODULE: &str = "validator_announce";
+
+/// A reference to a ValidatorAnnounce contract on some Aptos chain
+#[derive(Clone, Debug)]
+pub struct AptosValidatorAnnounce {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: AptosProvider,
+    signer: Option<AptosSigner>,
+    max_gas_amount: u64,
+}
+
+impl AptosValidatorAnnounce {
+    /// Create a new Aptos validator announce
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<AptosSigner>,
+    ) -> ChainResult<Self> {
+        let provider = AptosProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+            signer,
+            max_gas_amount: conf.max_gas_amount,
+        })
+    }
+
+    fn client(&self) -> &AptosClient {
+        self.provider.client()
+    }
+}
+
+impl HyperlaneContract for AptosValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for AptosValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for AptosValidatorAnnounce {
+    #[instrument(err, ret, skip(self))]
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        let validators = validators
+            .iter()
+            .map(|validator| Value::String(to_aptos_address(*validator)))
+            .collect();
+        let values = self
+            .client()
+            .view(
+                &function_id(
+                    self.address,
+                    VALIDATOR_ANNOUNCE_MODULE,
+                    "get_announced_storage_locations",
+                ),
+                vec![Value::Array(validators)],
+            )
+            .await?;
+        as_array(value(&values, 0)?)?
+            .iter()
+            .map(|locations| {
+                as_array(locations)?
+                    .iter()
+                    .map(|location| as_str(location).map(str::to_owned))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Validators are ethereum addresses, which are passed as addresses
+    /// left padded with zeros
+    #[instrument(err, ret, skip(self))]
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let arguments = vec![
+            Value::String(to_aptos_address(announcement.value.validator.into())),
+            encode_bytes(&announcement.signature.to_vec()),
+            Value::String(announcement.value.storage_location),
+        ];
+        signer
+            .submit(
+                self.client(),
+                function_id(self.address, VALIDATOR_ANNOUNCE_MODULE, "announce"),
+                arguments,
+                self.max_gas_amount,
+            )
+            .await
+    }
+
+    async fn announce_tokens_needed(
+        &self,
+        _announcement: SignedType<Announcement>,
+    ) -> Option<U256> {
+        let Some(signer) = &self.signer else {
+            trace!("No signer to announce with");
+            return None;
+        };
+        let Ok(balance) = signer.balance(self.client()).await else {
+            trace!("Unable to query balance");
+            return None;
+        };
+        let Ok(gas_price) = self.client().estimate_gas_price().await else {
+            trace!("Unable to estimate gas price");
+            return None;
+        };
+        let max_cost = U256::from(self.max_gas_amount) * U256::from(gas_price);
+        Some(max_cost.saturating_sub(balance))
+    }
+}
@@ -0,0 +1,98 @@
+//! Decoding of the values returned by view functions. The API encodes them
+//! as JSON: integers wider than 32 bits and `vector<u8>` as strings,
+//! addresses as hex strings and vectors as arrays.
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use serde_json::Value;
+
+use crate::error::HyperlaneAptosError;
+use crate::utils::{decode_bytes, decode_h256, parse_address};
+
+/// The value returned at `index`
+pub(crate) fn value(values: &[Value], index: usize) -> ChainResult<&Value> {
+    values
+        .get(index)
+        .ok_or_else(|| unexpected(format!("no value at index {index}")))
+}
+
+/// An integer, which is a string if it's wider than 32 bits
+pub(crate) fn as_u64(value: &Value) -> ChainResult<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(number) => number.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| unexpected(format!("expected an integer, got {value}")))
+}
+
+/// A boolean
+pub(crate) fn as_bool(value: &Value) -> ChainResult<bool> {
+    value
+        .as_bool()
+        .ok_or_else(|| unexpected(format!("expected a bool, got {value}")))
+}
+
+/// A string
+pub(crate) fn as_str(value: &Value) -> ChainResult<&str> {
+    value
+        .as_str()
+        .ok_or_else(|| unexpected(format!("expected a string, got {value}")))
+}
+
+/// A vector
+pub(crate) fn as_array(value: &Value) -> ChainResult<&Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| unexpected(format!("expected a vector, got {value}")))
+}
+
+/// An address
+pub(crate) fn as_address(value: &Value) -> ChainResult<H256> {
+    parse_address(as_str(value)?)
+}
+
+/// A `vector<u8>`
+pub(crate) fn as_bytes(value: &Value) -> ChainResult<Vec<u8>> {
+    decode_bytes(as_str(value)?)
+}
+
+/// A `vector<u8>` of 32 bytes, e.g. a hash
+pub(crate) fn as_h256(value: &Value) -> ChainResult<H256> {
+    decode_h256(as_str(value)?)
+}
+
+fn unexpected(reason: String) -> ChainCommunicationError {
+    HyperlaneAptosError::UnexpectedViewResult(reason).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_decode_values() {
+        let values = vec![
+            json!("18446744073709551615"),
+            json!(7),
+            json!(true),
+            json!("0x1"),
+            json!(["0xdead", "0xbeef"]),
+        ];
+
+        assert_eq!(as_u64(value(&values, 0).unwrap()).unwrap(), u64::MAX);
+        assert_eq!(as_u64(value(&values, 1).unwrap()).unwrap(), 7);
+        assert!(as_bool(value(&values, 2).unwrap()).unwrap());
+        assert_eq!(
+            as_address(value(&values, 3).unwrap()).unwrap(),
+            H256::from_low_u64_be(1)
+        );
+        let vectors = as_array(value(&values, 4).unwrap()).unwrap();
+        assert_eq!(as_bytes(&vectors[1]).unwrap(), vec![0xbe, 0xef]);
+
+        assert!(as_u64(value(&values, 2).unwrap()).is_err());
+        assert!(as_h256(&vectors[0]).is_err());
+        assert!(value(&values, 5).is_err());
+    }
+}
@@ -26,6 +26,10 @@ pub enum HyperlaneCosmosError {
     /// Cosmos error
     #[error("{0}")]
     CosmosError(#[from] cosmrs::Error),
+    /// The HTTP client couldn't be built with the configured proxy or
+    /// certificates
+    #[error(transparent)]
+    HttpClient(#[from] reqwest_utils::HttpClientError),
     /// Cosmos error report
     #[error("{0}")]
     CosmosErrorReport(#[from] cosmrs::ErrorReport),
@@ -59,6 +63,14 @@ pub enum HyperlaneCosmosError {
     /// Address error
     #[error("{0}")]
     AddressError(String),
+    /// An address doesn't have any of the bech32 prefixes of the chain
+    #[error("Address `{address}` doesn't have any of the bech32 prefixes {expected:?}")]
+    Bech32PrefixMismatch {
+        /// The address
+        address: String,
+        /// The prefixes of the chain
+        expected: Vec<String>,
+    },
     /// Signer info error
     #[error("{0}")]
     SignerInfoError(String),
@@ -71,9 +83,76 @@ pub enum HyperlaneCosmosError {
     /// Parsing error
     #[error("{0}")]
     ParsingFailed(String),
+    /// Verification of an RPC response failed
+    #[error("Verification failed: {0}")]
+    VerificationFailed(String),
+    /// Signing error
+    #[error("{0}")]
+    SigningError(#[from] k256::ecdsa::Error),
+    /// EIP-712 typed data error
+    #[error("EIP-712 encoding failed: {0}")]
+    Eip712Error(String),
+    /// The deployed contract version has an unknown event schema
+    #[error("Unsupported contract version: {0}")]
+    UnsupportedContractVersion(String),
+    /// The authz granter hasn't authorized the signer to execute messages of
+    /// a type on its behalf
+    #[error("No authz grant of `{granter}` to `{grantee}` for `{msg_type_url}` messages was found: {reason}")]
+    MissingAuthzGrant {
+        /// The account messages are executed on behalf of
+        granter: String,
+        /// The signer
+        grantee: String,
+        /// The type of the messages
+        msg_type_url: String,
+        /// Why no grant was found
+        reason: String,
+    },
+    /// The authz grant of the granter to the signer expired
+    #[error("The authz grant of `{granter}` to `{grantee}` for `{msg_type_url}` messages expired at unix timestamp {expiration}")]
+    ExpiredAuthzGrant {
+        /// The account messages are executed on behalf of
+        granter: String,
+        /// The signer
+        grantee: String,
+        /// The type of the messages
+        msg_type_url: String,
+        /// When the grant expired, in seconds since the unix epoch
+        expiration: i64,
+    },
+    /// The fee granter doesn't have a feegrant allowance for the signer
+    #[error("No fee allowance of `{granter}` for `{grantee}` was found: {reason}")]
+    MissingFeeAllowance {
+        /// The account fees are charged to
+        granter: String,
+        /// The signer
+        grantee: String,
+        /// Why no allowance was found
+        reason: String,
+    },
+    /// The feegrant allowance of the fee granter for the signer expired
+    #[error(
+        "The fee allowance of `{granter}` for `{grantee}` expired at unix timestamp {expiration}"
+    )]
+    ExpiredFeeAllowance {
+        /// The account fees are charged to
+        granter: String,
+        /// The signer
+        grantee: String,
+        /// When the allowance expired, in seconds since the unix epoch
+        expiration: i64,
+    },
     /// Parsing attempt failed
     #[error("Parsing attempt failed. (Errors: {0:?})")]
     ParsingAttemptsFailed(Vec<HyperlaneCosmosError>),
+    /// Reading or writing the light client store failed
+    #[error("Light client store `{path}`: {source}")]
+    LightClientStoreError {
+        /// Path of the store
+        path: String,
+        /// The underlying error
+        source: std::io::Error,
+    },
 }
 
 impl From<HyperlaneCosmosError> for ChainCommunicationError {
@@ -65,6 +65,28 @@ impl CosmosAddress {
         Ok(CosmosAddress::new(account_id, digest))
     }
 
+    /// Parses a bech32 encoded address, checking that it has one of
+    /// `prefixes`
+    pub fn from_str_with_prefixes(s: &str, prefixes: &[String]) -> ChainResult<Self> {
+        let address = Self::from_str(s)?;
+        if !prefixes
+            .iter()
+            .any(|prefix| prefix == address.account_id.prefix())
+        {
+            return Err(HyperlaneCosmosError::Bech32PrefixMismatch {
+                address: s.to_owned(),
+                expected: prefixes.to_vec(),
+            }
+            .into());
+        }
+        Ok(address)
+    }
+
+    /// The bech32 prefix of the address
+    pub fn prefix(&self) -> &str {
+        self.account_id.prefix()
+    }
+
     /// String representation of a cosmos AccountId
     pub fn address(&self) -> String {
         self.account_id.to_string()
@@ -76,7 +98,7 @@ impl CosmosAddress {
     }
 
     /// Calculates an account address depending on prefix and account address type
-    fn from_pubkey(
+    pub fn from_pubkey(
         pubkey: PublicKey,
         prefix: &str,
         account_address_type: &AccountAddressType,
@@ -164,4 +186,17 @@ pub mod test {
             "dual1rnw0v45t86qt2tegqmsphzdrfhys4esk9ktul7"
         );
     }
+
+    #[test]
+    fn test_bech32_prefix_validation() {
+        let addr = "neutron1kknekjxg0ear00dky5ykzs8wwp2gz62z9s6aaj";
+        let prefixes = ["neutron".to_owned(), "neutronvaloper".to_owned()];
+        let address = CosmosAddress::from_str_with_prefixes(addr, &prefixes).unwrap();
+        assert_eq!(address.prefix(), "neutron");
+
+        let err = CosmosAddress::from_str_with_prefixes(addr, &prefixes[1..]).unwrap_err();
+        assert!(err.to_string().contains("neutronvaloper"));
+
+        assert!(CosmosAddress::from_str_with_prefixes("neutron1invalid", &prefixes).is_err());
+    }
 }
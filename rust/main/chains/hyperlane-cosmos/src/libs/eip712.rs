@@ -0,0 +1,540 @@
+//! EIP-712 signing of transactions for Ethermint based chains.
+//!
+//! Ethermint chains accept `ethsecp256k1` signatures over EIP-712 typed data
+//! in place of signatures over the legacy amino JSON sign doc of a
+//! transaction. The typed data is derived from the amino JSON sign doc the
+//! same way the chain derives it when verifying the signature: the messages
+//! of the sign doc are flattened into `msg0`, `msg1`, ... fields of the `Tx`
+//! primary type, and the types of each message are inferred from its JSON.
+
+use std::collections::BTreeMap;
+
+use cosmrs::proto::cosmwasm::wasm::v1::MsgExecuteContract;
+use cosmrs::proto::traits::Message;
+use cosmrs::{tx::Fee, Any};
+use hyperlane_core::ChainResult;
+use hyperlane_cosmwasm_interface::types::keccak256_hash;
+use serde_json::{json, Value};
+
+use crate::HyperlaneCosmosError;
+
+const MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+const MSG_EXECUTE_CONTRACT_AMINO_TYPE: &str = "wasm/MsgExecuteContract";
+
+/// The prefix of the path of a message's root type
+const ROOT_PREFIX: &str = "_";
+/// The prefix of the names of the types inferred from messages
+const TYPE_PREFIX: &str = "Type";
+/// The maximum number of distinct types sharing a name
+const MAX_DUPLICATE_TYPE_DEFS: usize = 1000;
+
+/// The fields of each type, as (name, type) pairs.
+type Types = BTreeMap<String, Vec<(String, String)>>;
+
+/// Parses the EIP-155 chain ID out of an Ethermint chain ID, which has the
+/// format `{identifier}_{EIP155 chain ID}-{version}`, e.g. `evmos_9001-2`.
+pub fn eip155_chain_id(chain_id: &str) -> ChainResult<u64> {
+    chain_id
+        .rsplit_once('_')
+        .and_then(|(_, rest)| rest.split_once('-'))
+        .and_then(|(eip155_chain_id, _)| eip155_chain_id.parse().ok())
+        .ok_or_else(|| {
+            eip712_error(format!(
+                "chain ID `{chain_id}` isn't an Ethermint chain ID of the format `{{identifier}}_{{EIP155 chain ID}}-{{version}}`"
+            ))
+        })
+}
+
+/// The legacy amino JSON sign doc of a transaction, with its keys sorted as
+/// the cosmos SDK sorts them.
+pub fn amino_json_sign_doc(
+    chain_id: &str,
+    account_number: u64,
+    sequence: u64,
+    fee: &Fee,
+    memo: &str,
+    msgs: &[Any],
+) -> ChainResult<Value> {
+    let amount: Vec<Value> = fee
+        .amount
+        .iter()
+        .map(|coin| json!({"amount": coin.amount.to_string(), "denom": coin.denom.to_string()}))
+        .collect();
+    let msgs = msgs
+        .iter()
+        .map(amino_json_msg)
+        .collect::<ChainResult<Vec<Value>>>()?;
+    Ok(json!({
+        "account_number": account_number.to_string(),
+        "chain_id": chain_id,
+        "fee": {
+            "amount": amount,
+            "gas": fee.gas_limit.to_string(),
+        },
+        "memo": memo,
+        "msgs": msgs,
+        "sequence": sequence.to_string(),
+    }))
+}
+
+/// The amino JSON encoding of a message. Only wasm contract executions are
+/// supported.
+fn amino_json_msg(msg: &Any) -> ChainResult<Value> {
+    if msg.type_url != MSG_EXECUTE_CONTRACT_TYPE_URL {
+        return Err(eip712_error(format!(
+            "can't amino JSON encode message of type `{}`",
+            msg.type_url
+        )));
+    }
+    let msg =
+        MsgExecuteContract::decode(msg.value.as_slice()).map_err(HyperlaneCosmosError::from)?;
+    let funds: Vec<Value> = msg
+        .funds
+        .iter()
+        .map(|coin| json!({"amount": coin.amount, "denom": coin.denom}))
+        .collect();
+    Ok(json!({
+        "type": MSG_EXECUTE_CONTRACT_AMINO_TYPE,
+        "value": {
+            "contract": msg.contract,
+            "funds": funds,
+            "msg": serde_json::from_slice::<Value>(&msg.msg).map_err(HyperlaneCosmosError::from)?,
+            "sender": msg.sender,
+        },
+    }))
+}
+
+/// The EIP-712 hash of the typed data derived from an amino JSON sign doc,
+/// i.e. the digest an `ethsecp256k1` key signs in EIP-712 sign mode.
+pub fn typed_data_hash(eip155_chain_id: u64, sign_doc: &Value) -> ChainResult<[u8; 32]> {
+    let (types, message) = typed_data(sign_doc)?;
+
+    let mut domain_types = Types::new();
+    domain_types.insert(
+        "EIP712Domain".to_owned(),
+        fields(&[
+            ("name", "string"),
+            ("version", "string"),
+            ("chainId", "uint256"),
+            ("verifyingContract", "string"),
+            ("salt", "string"),
+        ]),
+    );
+    let domain = json!({
+        "name": "Cosmos Web3",
+        "version": "1.0.0",
+        "chainId": eip155_chain_id,
+        "verifyingContract": "cosmos",
+        "salt": "0",
+    });
+
+    let mut bytes = vec![0x19, 0x01];
+    bytes.extend(hash_struct(&domain_types, "EIP712Domain", &domain)?);
+    bytes.extend(hash_struct(&types, "Tx", &message)?);
+    Ok(keccak256(&bytes))
+}
+
+/// The types and `Tx` message of the typed data derived from an amino JSON
+/// sign doc.
+fn typed_data(sign_doc: &Value) -> ChainResult<(Types, Value)> {
+    let mut message = sign_doc
+        .as_object()
+        .cloned()
+        .ok_or_else(|| eip712_error("sign doc isn't an object".to_owned()))?;
+    let msgs = match message.remove("msgs") {
+        Some(Value::Array(msgs)) => msgs,
+        _ => return Err(eip712_error("sign doc has no messages".to_owned())),
+    };
+
+    let mut types = Types::new();
+    types.insert(
+        "Tx".to_owned(),
+        fields(&[
+            ("account_number", "string"),
+            ("chain_id", "string"),
+            ("fee", "Fee"),
+            ("memo", "string"),
+            ("sequence", "string"),
+        ]),
+    );
+    types.insert(
+        "Fee".to_owned(),
+        fields(&[("amount", "Coin[]"), ("gas", "string")]),
+    );
+    types.insert(
+        "Coin".to_owned(),
+        fields(&[("denom", "string"), ("amount", "string")]),
+    );
+
+    for (index, msg) in msgs.into_iter().enumerate() {
+        let field = format!("msg{index}");
+        let root_type = msg_root_type(&msg)?;
+        let msg_type = add_types(&mut types, &root_type, ROOT_PREFIX, &msg)?;
+        types
+            .get_mut("Tx")
+            .expect("Tx type was inserted above")
+            .push((field.clone(), msg_type));
+        message.insert(field, msg);
+    }
+
+    Ok((types, Value::Object(message)))
+}
+
+/// The name of the root type of a message, e.g. `TypeWasmMsgExecuteContract`
+/// for a `wasm/MsgExecuteContract` message.
+fn msg_root_type(msg: &Value) -> ChainResult<String> {
+    let msg_type = msg
+        .get("type")
+        .and_then(Value::as_str)
+        .filter(|msg_type| !msg_type.is_empty())
+        .ok_or_else(|| eip712_error("message has no type".to_owned()))?;
+    let signature: String = msg_type
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(capitalize)
+        .collect();
+    Ok(format!("{TYPE_PREFIX}{signature}"))
+}
+
+/// Infers the type of the object `payload` at `prefix`, adding it and the
+/// types of its nested objects to `types`, and returns its name.
+fn add_types(
+    types: &mut Types,
+    root_type: &str,
+    prefix: &str,
+    payload: &Value,
+) -> ChainResult<String> {
+    let Value::Object(payload) = payload else {
+        return Err(eip712_error(format!("`{prefix}` isn't an object")));
+    };
+
+    let mut fields = vec![];
+    // Object keys are sorted, so the types are deterministic
+    for (name, value) in payload {
+        let (value, is_array) = match value {
+            Value::Array(values) => match values.first() {
+                Some(value) => (value, true),
+                None => {
+                    // The element type of an empty array can't be inferred
+                    fields.push((name.clone(), "string[]".to_owned()));
+                    continue;
+                }
+            },
+            value => (value, false),
+        };
+
+        let field_type = match value {
+            Value::Bool(_) => "bool".to_owned(),
+            Value::Number(_) => "int64".to_owned(),
+            Value::String(_) => "string".to_owned(),
+            Value::Object(_) => {
+                let field_prefix = format!("{prefix}.{name}");
+                add_types(types, root_type, &field_prefix, value)?
+            }
+            // Nulls and nested arrays have no type
+            Value::Null | Value::Array(_) => continue,
+        };
+        let field_type = if is_array {
+            format!("{field_type}[]")
+        } else {
+            field_type
+        };
+        fields.push((name.clone(), field_type));
+    }
+
+    let type_name = if prefix == ROOT_PREFIX {
+        root_type.to_owned()
+    } else {
+        sanitize_type_name(prefix)
+    };
+    add_type(types, &type_name, fields)
+}
+
+/// Adds a type named `type_name` followed by the lowest index that doesn't
+/// clash with a different type, and returns its indexed name.
+fn add_type(
+    types: &mut Types,
+    type_name: &str,
+    fields: Vec<(String, String)>,
+) -> ChainResult<String> {
+    for index in 0..MAX_DUPLICATE_TYPE_DEFS {
+        let indexed_name = format!("{type_name}{index}");
+        match types.get(&indexed_name) {
+            Some(existing) if *existing == fields => return Ok(indexed_name),
+            Some(_) => continue,
+            None => {
+                types.insert(indexed_name.clone(), fields);
+                return Ok(indexed_name);
+            }
+        }
+    }
+    Err(eip712_error(format!(
+        "too many distinct types named `{type_name}`"
+    )))
+}
+
+/// Converts the path of a type to a type name, e.g. `_.value.msg` to
+/// `TypeValueMsg`.
+fn sanitize_type_name(path: &str) -> String {
+    path.split('.')
+        .map(|part| {
+            if part == ROOT_PREFIX {
+                TYPE_PREFIX.to_owned()
+            } else {
+                part.split('_').map(capitalize).collect()
+            }
+        })
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn fields(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .map(|(name, field_type)| (name.to_string(), field_type.to_string()))
+        .collect()
+}
+
+/// `keccak256(typeHash ‖ encodeData(value))` of a value of type `type_name`.
+fn hash_struct(types: &Types, type_name: &str, value: &Value) -> ChainResult<[u8; 32]> {
+    let fields = types
+        .get(type_name)
+        .ok_or_else(|| eip712_error(format!("unknown type `{type_name}`")))?;
+
+    let mut bytes = keccak256(encode_type(types, type_name).as_bytes()).to_vec();
+    for (name, field_type) in fields {
+        let field = value
+            .get(name)
+            .ok_or_else(|| eip712_error(format!("missing field `{name}` of `{type_name}`")))?;
+        bytes.extend(encode_value(types, field_type, field)?);
+    }
+    Ok(keccak256(&bytes))
+}
+
+/// The encoding of a type and the types it references, e.g.
+/// `Fee(Coin[] amount,string gas)Coin(string denom,string amount)`.
+fn encode_type(types: &Types, type_name: &str) -> String {
+    let mut dependencies = std::collections::BTreeSet::new();
+    collect_dependencies(types, type_name, &mut dependencies);
+    dependencies.remove(type_name);
+
+    std::iter::once(type_name)
+        .chain(dependencies.iter().map(String::as_str))
+        .map(|name| {
+            let fields: Vec<String> = types[name]
+                .iter()
+                .map(|(name, field_type)| format!("{field_type} {name}"))
+                .collect();
+            format!("{name}({})", fields.join(","))
+        })
+        .collect()
+}
+
+fn collect_dependencies(
+    types: &Types,
+    type_name: &str,
+    dependencies: &mut std::collections::BTreeSet<String>,
+) {
+    let type_name = type_name.trim_end_matches("[]");
+    if !types.contains_key(type_name) || !dependencies.insert(type_name.to_owned()) {
+        return;
+    }
+    for (_, field_type) in &types[type_name] {
+        collect_dependencies(types, field_type, dependencies);
+    }
+}
+
+/// The 32 byte encoding of a field value.
+fn encode_value(types: &Types, field_type: &str, value: &Value) -> ChainResult<[u8; 32]> {
+    if let Some(element_type) = field_type.strip_suffix("[]") {
+        let elements = value
+            .as_array()
+            .ok_or_else(|| eip712_error(format!("expected an array of `{element_type}`")))?;
+        let mut bytes = vec![];
+        for element in elements {
+            bytes.extend(encode_value(types, element_type, element)?);
+        }
+        return Ok(keccak256(&bytes));
+    }
+    if types.contains_key(field_type) {
+        return hash_struct(types, field_type, value);
+    }
+
+    let mut word = [0u8; 32];
+    match (field_type, value) {
+        ("string", Value::String(string)) => return Ok(keccak256(string.as_bytes())),
+        ("bool", Value::Bool(boolean)) => word[31] = *boolean as u8,
+        ("address", Value::String(address)) => {
+            let address = hex::decode(address.trim_start_matches("0x"))
+                .ok()
+                .filter(|address| address.len() == 20)
+                .ok_or_else(|| eip712_error(format!("`{address}` isn't an address")))?;
+            word[12..].copy_from_slice(&address);
+        }
+        ("int64" | "uint256", Value::Number(number)) => {
+            if let Some(number) = number.as_u64() {
+                word[24..].copy_from_slice(&number.to_be_bytes());
+            } else if let Some(number) = number.as_i64() {
+                // Sign extended two's complement
+                word = [0xff; 32];
+                word[24..].copy_from_slice(&number.to_be_bytes());
+            } else {
+                return Err(eip712_error(format!("`{number}` isn't an integer")));
+            }
+        }
+        _ => {
+            return Err(eip712_error(format!(
+                "`{value}` isn't a value of type `{field_type}`"
+            )))
+        }
+    }
+    Ok(word)
+}
+
+fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(keccak256_hash(bytes).as_slice());
+    hash
+}
+
+fn eip712_error(reason: String) -> HyperlaneCosmosError {
+    HyperlaneCosmosError::Eip712Error(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eip155_chain_id() {
+        assert_eq!(eip155_chain_id("evmos_9001-2").unwrap(), 9001);
+        assert_eq!(eip155_chain_id("injective_888-1").unwrap(), 888);
+        assert!(eip155_chain_id("osmosis-1").is_err());
+    }
+
+    #[test]
+    fn test_infers_message_types() {
+        let sign_doc = json!({
+            "account_number": "1",
+            "chain_id": "evmos_9001-2",
+            "fee": {"amount": [{"amount": "100", "denom": "aevmos"}], "gas": "200000"},
+            "memo": "",
+            "msgs": [
+                {
+                    "type": "wasm/MsgExecuteContract",
+                    "value": {
+                        "contract": "evmos1contract",
+                        "funds": [],
+                        "msg": {"process": {"message": "0x01", "metadata": "0x02"}},
+                        "sender": "evmos1sender",
+                    },
+                },
+                {
+                    "type": "wasm/MsgExecuteContract",
+                    "value": {
+                        "contract": "evmos1contract",
+                        "funds": [],
+                        "msg": {"announce": {"validator": "0x03", "nonce": 4}},
+                        "sender": "evmos1sender",
+                    },
+                },
+            ],
+            "sequence": "5",
+        });
+
+        let (types, message) = typed_data(&sign_doc).unwrap();
+
+        assert_eq!(
+            types["Tx"][5..],
+            fields(&[
+                ("msg0", "TypeWasmMsgExecuteContract0"),
+                ("msg1", "TypeWasmMsgExecuteContract1"),
+            ])
+        );
+        assert_eq!(
+            types["TypeValueMsgProcess0"],
+            fields(&[("message", "string"), ("metadata", "string")])
+        );
+        assert_eq!(
+            types["TypeValueMsgAnnounce0"],
+            fields(&[("nonce", "int64"), ("validator", "string")])
+        );
+        assert_eq!(
+            types["TypeValue0"],
+            fields(&[
+                ("contract", "string"),
+                ("funds", "string[]"),
+                ("msg", "TypeValueMsg0"),
+                ("sender", "string"),
+            ])
+        );
+        // Different types at the same path are disambiguated by their index
+        assert_eq!(
+            types["TypeValueMsg1"],
+            fields(&[("announce", "TypeValueMsgAnnounce0")])
+        );
+        assert!(message.get("msgs").is_none());
+        assert_eq!(message["msg1"], sign_doc["msgs"][1]);
+
+        assert_eq!(
+            encode_type(&types, "Fee"),
+            "Fee(Coin[] amount,string gas)Coin(string denom,string amount)"
+        );
+        assert_eq!(
+            hex::encode(typed_data_hash(9001, &sign_doc).unwrap()),
+            "21a6e0567ed336eaa9e191516589108c8a52f99456170a892c473d015b9fdbc2"
+        );
+    }
+
+    /// The `Mail` example of the EIP-712 specification
+    #[test]
+    fn test_hash_struct_matches_eip712_example() {
+        let mut types = Types::new();
+        types.insert(
+            "EIP712Domain".to_owned(),
+            fields(&[
+                ("name", "string"),
+                ("version", "string"),
+                ("chainId", "uint256"),
+                ("verifyingContract", "address"),
+            ]),
+        );
+        types.insert(
+            "Person".to_owned(),
+            fields(&[("name", "string"), ("wallet", "address")]),
+        );
+        types.insert(
+            "Mail".to_owned(),
+            fields(&[("from", "Person"), ("to", "Person"), ("contents", "string")]),
+        );
+        let domain = json!({
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+        });
+        let mail = json!({
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+            "contents": "Hello, Bob!",
+        });
+
+        assert_eq!(
+            encode_type(&types, "Mail"),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        let mut bytes = vec![0x19, 0x01];
+        bytes.extend(hash_struct(&types, "EIP712Domain", &domain).unwrap());
+        bytes.extend(hash_struct(&types, "Mail", &mail).unwrap());
+        assert_eq!(
+            hex::encode(keccak256(&bytes)),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+}
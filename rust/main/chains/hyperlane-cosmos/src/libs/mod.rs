@@ -6,3 +6,6 @@ mod account;
 
 /// This module contains all the verification variables the libraries used by the Hyperlane Cosmos chain.
 mod address;
+
+/// This module contains the EIP-712 signing of transactions for Ethermint based chains.
+pub(crate) mod eip712;
@@ -5,3 +5,4 @@ pub use dispatch_indexer::CosmosMailboxDispatchIndexer;
 mod contract;
 mod delivery_indexer;
 mod dispatch_indexer;
+mod process_error;
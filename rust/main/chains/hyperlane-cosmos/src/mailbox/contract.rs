@@ -2,23 +2,27 @@ use std::num::NonZeroU64;
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use cosmrs::proto::cosmos::base::abci::v1beta1::TxResponse;
+use cosmrs::{proto::cosmos::base::abci::v1beta1::TxResponse, Any};
 use tracing::instrument;
 
 use hyperlane_core::{
-    utils::bytes_to_hex, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
-    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Mailbox, RawHyperlaneMessage,
-    ReorgPeriod, TxCostEstimate, TxOutcome, H256, U256,
+    utils::bytes_to_hex, ChainResult, ContractLocator, DeliveryDryRun, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider,
+    InterchainSecurityModule, Mailbox, MessageDeliverySimulator, RawHyperlaneMessage, ReorgPeriod,
+    TxCostEstimate, TxOutcome, H256, U256,
 };
 
 use crate::grpc::WasmProvider;
+use crate::mailbox::process_error::{attributed_dry_run, decode_process_error};
 use crate::payloads::general;
 use crate::payloads::mailbox::{
     GeneralMailboxQuery, ProcessMessageRequest, ProcessMessageRequestInner,
 };
 use crate::types::tx_response_to_outcome;
 use crate::utils::get_block_height_for_reorg_period;
-use crate::{payloads, ConnectionConf, CosmosAddress, CosmosProvider, Signer};
+use crate::{
+    payloads, ConnectionConf, CosmosAddress, CosmosInterchainSecurityModule, CosmosProvider, Signer,
+};
 
 #[derive(Clone, Debug)]
 /// A reference to a Mailbox contract on some Cosmos chain
@@ -60,6 +64,24 @@ impl CosmosMailbox {
     fn contract_address_bytes(&self) -> usize {
         self.config.get_contract_address_bytes()
     }
+
+    /// The messages processing `message`, executed on behalf of the authz
+    /// granter if one is configured
+    async fn process_msgs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<Any>> {
+        let process_message = ProcessMessageRequest {
+            process: ProcessMessageRequestInner {
+                message: hex::encode(RawHyperlaneMessage::from(message)),
+                metadata: hex::encode(metadata),
+            },
+        };
+        let grpc = self.provider.grpc();
+        let msg = grpc.wasm_execute_msg(&process_message, grpc.delivering_account()?)?;
+        grpc.delegate_msgs(vec![msg]).await
+    }
 }
 
 impl HyperlaneContract for CosmosMailbox {
@@ -161,20 +183,15 @@ impl Mailbox for CosmosMailbox {
         metadata: &[u8],
         tx_gas_limit: Option<U256>,
     ) -> ChainResult<TxOutcome> {
-        let process_message = ProcessMessageRequest {
-            process: ProcessMessageRequestInner {
-                message: hex::encode(RawHyperlaneMessage::from(message)),
-                metadata: hex::encode(metadata),
-            },
-        };
-
-        let response: TxResponse = self
-            .provider
-            .grpc()
-            .wasm_send(process_message, tx_gas_limit)
-            .await?;
-
-        Ok(tx_response_to_outcome(response)?)
+        let msgs = self.process_msgs(message, metadata).await?;
+        // Gas limits that don't fit in a u64 fall back to estimation
+        let gas_limit = tx_gas_limit.and_then(|limit| u64::try_from(limit).ok());
+        let response: TxResponse = self.provider.grpc().send_msgs(msgs, gas_limit).await?;
+
+        Ok(tx_response_to_outcome(
+            response,
+            self.provider.grpc().gas_price(),
+        )?)
     }
 
     #[instrument(err, ret, skip(self), fields(hyp_message=%message, metadata=%bytes_to_hex(metadata)))]
@@ -184,18 +201,13 @@ impl Mailbox for CosmosMailbox {
         message: &HyperlaneMessage,
         metadata: &[u8],
     ) -> ChainResult<TxCostEstimate> {
-        let process_message = ProcessMessageRequest {
-            process: ProcessMessageRequestInner {
-                message: hex::encode(RawHyperlaneMessage::from(message)),
-                metadata: hex::encode(metadata),
-            },
-        };
-
+        let msgs = self.process_msgs(message, metadata).await?;
         let gas_limit = self
             .provider
             .grpc()
-            .wasm_estimate_gas(process_message)
-            .await?;
+            .estimate_gas(msgs)
+            .await
+            .map_err(decode_process_error)?;
 
         let result = TxCostEstimate {
             gas_limit: gas_limit.into(),
@@ -206,11 +218,61 @@ impl Mailbox for CosmosMailbox {
         Ok(result)
     }
 
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+    fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
         todo!() // not required
     }
 }
 
+#[async_trait]
+impl MessageDeliverySimulator for CosmosMailbox {
+    #[instrument(err, ret, skip(self), fields(hyp_message=%message, metadata=%bytes_to_hex(metadata)))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn dry_run_delivery(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<DeliveryDryRun> {
+        let msgs = self.process_msgs(message, metadata).await?;
+        // A failed simulation carries the error of the contract that failed
+        let process_err = match self.provider.grpc().estimate_gas(msgs).await {
+            Ok(_) => return Ok(DeliveryDryRun::Delivered),
+            Err(err) => decode_process_error(err),
+        };
+        if let Some(dry_run) = attributed_dry_run(&process_err) {
+            return Ok(dry_run);
+        }
+
+        // Tell apart why processing failed, by checking what the mailbox
+        // checks before calling the recipient
+        if self.delivered(message.id()).await? {
+            return Ok(DeliveryDryRun::Failed(
+                "The message was already delivered".to_owned(),
+            ));
+        }
+        let ism_address = self.recipient_ism(message.recipient).await?;
+        let ism = CosmosInterchainSecurityModule::new(
+            &self.config,
+            ContractLocator {
+                domain: &self.domain,
+                address: ism_address,
+            },
+            None,
+        )?;
+        // ISMs reject metadata either by not verifying it or by failing the query
+        match ism.dry_run_verify(message, metadata).await {
+            Ok(Some(_)) => Ok(DeliveryDryRun::HandleReverted(process_err.to_string())),
+            Ok(None) => Ok(DeliveryDryRun::IsmVerificationFailed(format!(
+                "ISM {ism_address:?} rejected the metadata"
+            ))),
+            Err(err) => Ok(DeliveryDryRun::IsmVerificationFailed(err.to_string())),
+        }
+    }
+}
+
 impl CosmosMailbox {
     #[instrument(level = "debug", err, ret, skip(self))]
     pub(crate) async fn nonce_at_block(&self, block_height: Option<u64>) -> ChainResult<u32> {
@@ -15,8 +15,8 @@ use hyperlane_core::{
 
 use crate::rpc::{CosmosWasmRpcProvider, ParsedEvent, WasmRpcProvider};
 use crate::utils::{
-    execute_and_parse_log_futures, parse_logs_in_range, parse_logs_in_tx,
-    CONTRACT_ADDRESS_ATTRIBUTE_KEY, CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64,
+    get_logs_in_range, parse_logs_in_tx, CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+    CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64,
 };
 use crate::{ConnectionConf, HyperlaneCosmosError, Signer};
 
@@ -120,14 +120,13 @@ impl Indexer<H256> for CosmosMailboxDeliveryIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
-        let logs_futures = parse_logs_in_range(
+        get_logs_in_range(
             range,
             self.provider.clone(),
             Self::hyperlane_delivery_parser,
             "DeliveryCursor",
-        );
-
-        execute_and_parse_log_futures(logs_futures).await
+        )
+        .await
     }
 
     async fn get_finalized_block_number(&self) -> ChainResult<u32> {
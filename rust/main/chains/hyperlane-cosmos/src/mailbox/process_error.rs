@@ -0,0 +1,94 @@
+use hyperlane_core::{ChainCommunicationError, DeliveryDryRun};
+
+/// Error of the cw-hyperlane mailbox when the ISM doesn't verify the metadata
+const ISM_VERIFY_FAILED: &str = "ism verify failed";
+/// Prefix wasmd adds to errors of messages dispatched by a contract, i.e. to
+/// errors of the recipient's `handle` dispatched by the mailbox
+const DISPATCH_FAILED: &str = "dispatch: submessages:";
+/// Part of the errors of both the SDK gas meter and the wasm VM when running
+/// out of gas
+const OUT_OF_GAS: &str = "out of gas";
+
+/// Decode the error of a simulated `process` call into the typed error of the
+/// party that failed, which wraps the original error. CosmWasm errors only
+/// reach us as strings, so errors that can't be attributed are returned as is.
+pub(crate) fn decode_process_error(err: ChainCommunicationError) -> ChainCommunicationError {
+    let lowercase = err.to_string().to_lowercase();
+    if lowercase.contains(OUT_OF_GAS) {
+        ChainCommunicationError::OutOfGas(Box::new(err))
+    } else if lowercase.contains(DISPATCH_FAILED) {
+        // Checked before the ISM, whose error a recipient could bubble up
+        ChainCommunicationError::RecipientHandleFailed(Box::new(err))
+    } else if lowercase.contains(ISM_VERIFY_FAILED) {
+        ChainCommunicationError::IsmVerificationFailed(Box::new(err))
+    } else {
+        err
+    }
+}
+
+/// The outcome of a dry run whose simulated `process` call failed with the
+/// decoded `err`, if the error tells which party failed
+pub(crate) fn attributed_dry_run(err: &ChainCommunicationError) -> Option<DeliveryDryRun> {
+    match err {
+        ChainCommunicationError::IsmVerificationFailed(source) => {
+            Some(DeliveryDryRun::IsmVerificationFailed(source.to_string()))
+        }
+        ChainCommunicationError::RecipientHandleFailed(source) => {
+            Some(DeliveryDryRun::HandleReverted(source.to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(msg: &str) -> ChainCommunicationError {
+        decode_process_error(ChainCommunicationError::from_other_str(msg))
+    }
+
+    #[test]
+    fn test_decode_process_error() {
+        let ism_err = "failed to execute message; message index: 0: ism verify failed: execute wasm contract failed";
+        match decode(ism_err) {
+            ChainCommunicationError::IsmVerificationFailed(source) => {
+                // The original error is kept
+                assert!(matches!(*source, ChainCommunicationError::ContractError(_)));
+                assert_eq!(source.to_string(), ism_err);
+            }
+            err => panic!("unexpected error {err:?}"),
+        }
+        assert!(matches!(
+            decode("failed to execute message; message index: 0: dispatch: submessages: Generic error: invalid route: execute wasm contract failed"),
+            ChainCommunicationError::RecipientHandleFailed(_)
+        ));
+        assert!(matches!(
+            decode(
+                "out of gas in location: wasm contract; gasWanted: 0, gasUsed: 300000: out of gas"
+            ),
+            ChainCommunicationError::OutOfGas(_)
+        ));
+        assert!(matches!(
+            decode("account sequence mismatch, expected 5, got 4"),
+            ChainCommunicationError::ContractError(_)
+        ));
+    }
+
+    #[test]
+    fn test_attributed_dry_run() {
+        let ism_err = "message index: 0: ism verify failed";
+        assert_eq!(
+            attributed_dry_run(&decode(ism_err)),
+            Some(DeliveryDryRun::IsmVerificationFailed(ism_err.to_owned()))
+        );
+        let handle_err = "message index: 0: dispatch: submessages: invalid route";
+        assert_eq!(
+            attributed_dry_run(&decode(handle_err)),
+            Some(DeliveryDryRun::HandleReverted(handle_err.to_owned()))
+        );
+        // Errors that don't tell which party failed are dry run further
+        assert_eq!(attributed_dry_run(&decode("out of gas")), None);
+        assert_eq!(attributed_dry_run(&decode("unauthorized")), None);
+    }
+}
@@ -17,8 +17,8 @@ use crate::grpc::WasmProvider;
 use crate::payloads::{general, merkle_tree_hook};
 use crate::rpc::{CosmosWasmRpcProvider, ParsedEvent, WasmRpcProvider};
 use crate::utils::{
-    execute_and_parse_log_futures, get_block_height_for_reorg_period, parse_logs_in_range,
-    parse_logs_in_tx, CONTRACT_ADDRESS_ATTRIBUTE_KEY, CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64,
+    get_block_height_for_reorg_period, get_logs_in_range, parse_logs_in_tx,
+    CONTRACT_ADDRESS_ATTRIBUTE_KEY, CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64,
 };
 use crate::{ConnectionConf, CosmosProvider, HyperlaneCosmosError, Signer};
 
@@ -320,14 +320,13 @@ impl Indexer<MerkleTreeInsertion> for CosmosMerkleTreeHookIndexer {
         &self,
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
-        let logs_futures = parse_logs_in_range(
+        get_logs_in_range(
             range,
             self.provider.clone(),
             Self::merkle_tree_insertion_parser,
             "MerkleTreeInsertionCursor",
-        );
-
-        execute_and_parse_log_futures(logs_futures).await
+        )
+        .await
     }
 
     /// Get the chain's latest block number that has reached finality
@@ -0,0 +1,61 @@
+//! Implementations over the native `x/hyperlane` Cosmos SDK module, which
+//! exposes Hyperlane through protobuf Query and Msg services rather than
+//! CosmWasm contracts.
+
+use std::str::FromStr;
+
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+
+pub use aggregation_ism::CosmosNativeAggregationIsm;
+pub use interchain_gas::{
+    CosmosNativeInterchainGasPaymaster, CosmosNativeInterchainGasPaymasterIndexer,
+};
+pub use interchain_security_module::CosmosNativeInterchainSecurityModule;
+pub use mailbox::{
+    CosmosNativeMailbox, CosmosNativeMailboxDeliveryIndexer, CosmosNativeMailboxDispatchIndexer,
+};
+pub use merkle_tree_hook::{CosmosNativeMerkleTreeHook, CosmosNativeMerkleTreeHookIndexer};
+pub use multisig_ism::CosmosNativeMultisigIsm;
+pub use routing_ism::CosmosNativeRoutingIsm;
+pub use validator_announce::CosmosNativeValidatorAnnounce;
+
+mod aggregation_ism;
+mod events;
+mod interchain_gas;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+pub(crate) mod proto;
+mod routing_ism;
+mod validator_announce;
+
+/// Formats an H256 as the `0x` prefixed hex ID the module identifies
+/// mailboxes, ISMs and hooks by.
+pub(crate) fn hex_id(id: H256) -> String {
+    format!("0x{}", hex::encode(id))
+}
+
+/// Parses an ID formatted by [`hex_id`].
+pub(crate) fn parse_hex_id(id: &str) -> ChainResult<H256> {
+    H256::from_str(id.trim_start_matches("0x")).map_err(|err| {
+        ChainCommunicationError::from_other_str(&format!("invalid hex ID `{id}`: {err}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_id_round_trip() {
+        let id = H256::from_low_u64_be(0xdead_beef);
+        let formatted = hex_id(id);
+        assert_eq!(
+            formatted,
+            "0x00000000000000000000000000000000000000000000000000000000deadbeef"
+        );
+        assert_eq!(parse_hex_id(&formatted).unwrap(), id);
+        assert!(parse_hex_id("0x1234").is_err());
+    }
+}
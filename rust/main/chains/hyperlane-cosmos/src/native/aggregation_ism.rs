@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use prost::{Message, Name};
+use tracing::instrument;
+
+use hyperlane_core::{
+    AggregationIsm, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, H256,
+};
+
+use crate::native::interchain_security_module::query_ism;
+use crate::native::parse_hex_id;
+use crate::native::proto;
+use crate::{ConnectionConf, CosmosProvider, HyperlaneCosmosError, Signer};
+
+/// A reference to an aggregation ISM of the native `x/hyperlane` module
+#[derive(Debug)]
+pub struct CosmosNativeAggregationIsm {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeAggregationIsm {
+    /// Create a reference to the aggregation ISM with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for CosmosNativeAggregationIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeAggregationIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl AggregationIsm for CosmosNativeAggregationIsm {
+    /// Returns the modules and threshold of the ISM, which are the same for
+    /// every message
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    #[instrument(err)]
+    async fn modules_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let ism = query_ism(&self.provider, self.address).await?;
+        if ism.type_url != proto::AggregationIsm::type_url() {
+            return Err(ChainCommunicationError::from_other_str(&format!(
+                "ISM type `{}` is not an aggregation ISM",
+                ism.type_url
+            )));
+        }
+        let ism = proto::AggregationIsm::decode(ism.value.as_slice())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        modules_and_threshold(&ism)
+    }
+}
+
+fn modules_and_threshold(ism: &proto::AggregationIsm) -> ChainResult<(Vec<H256>, u8)> {
+    let modules = ism
+        .modules
+        .iter()
+        .map(|module| parse_hex_id(module))
+        .collect::<ChainResult<Vec<H256>>>()?;
+    let threshold = ism
+        .threshold
+        .try_into()
+        .map_err(ChainCommunicationError::from_other)?;
+
+    Ok((modules, threshold))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::hex_id;
+
+    use super::*;
+
+    #[test]
+    fn test_modules_and_threshold() {
+        let modules = vec![H256::from_low_u64_be(2), H256::from_low_u64_be(3)];
+        let mut ism = proto::AggregationIsm {
+            id: hex_id(H256::from_low_u64_be(1)),
+            owner: String::new(),
+            modules: modules.iter().copied().map(hex_id).collect(),
+            threshold: 2,
+        };
+        assert_eq!(modules_and_threshold(&ism).unwrap(), (modules, 2));
+
+        ism.threshold = 256;
+        assert!(modules_and_threshold(&ism).is_err());
+    }
+}
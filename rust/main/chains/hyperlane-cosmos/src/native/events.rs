@@ -0,0 +1,278 @@
+//! Parsers of the typed events the native `x/hyperlane` module emits.
+//!
+//! The attributes of typed events are the fields of their protobuf message,
+//! with JSON encoded values, e.g. `"0x01..."` for IDs and `5` for `uint32`s.
+
+use std::io::Cursor;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use tendermint::abci::EventAttribute;
+
+use hyperlane_core::{
+    ChainResult, Decode, HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, H256, U256,
+};
+
+use crate::native::parse_hex_id;
+use crate::rpc::ParsedEvent;
+use crate::HyperlaneCosmosError;
+
+/// Event emitted when a mailbox dispatches a message
+pub(crate) const DISPATCH_EVENT_KIND: &str = "hyperlane.core.v1.EventDispatch";
+/// Event emitted when a mailbox processes a message
+pub(crate) const PROCESS_EVENT_KIND: &str = "hyperlane.core.v1.EventProcess";
+/// Event emitted when a merkle tree hook inserts a message ID
+pub(crate) const INSERTED_INTO_TREE_EVENT_KIND: &str =
+    "hyperlane.core.post_dispatch.v1.EventInsertedIntoTree";
+/// Event emitted when an IGP is paid for the gas of a message
+pub(crate) const GAS_PAYMENT_EVENT_KIND: &str = "hyperlane.core.post_dispatch.v1.EventGasPayment";
+
+/// Attribute of mailbox events identifying the mailbox
+pub(crate) const MAILBOX_ID_ATTRIBUTE_KEY: &str = "origin_mailbox_id";
+/// Attribute of insertion events identifying the merkle tree hook
+pub(crate) const MERKLE_TREE_HOOK_ID_ATTRIBUTE_KEY: &str = "merkle_tree_hook_id";
+/// Attribute of gas payment events identifying the IGP
+pub(crate) const IGP_ID_ATTRIBUTE_KEY: &str = "igp_id";
+
+#[derive(Deserialize)]
+struct EventDispatch {
+    origin_mailbox_id: String,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct EventProcess {
+    origin_mailbox_id: String,
+    message_id: String,
+}
+
+#[derive(Deserialize)]
+struct EventInsertedIntoTree {
+    message_id: String,
+    index: u32,
+    merkle_tree_hook_id: String,
+}
+
+#[derive(Deserialize)]
+struct EventGasPayment {
+    message_id: String,
+    destination: u32,
+    #[serde(deserialize_with = "deserialize_amount")]
+    gas_amount: U256,
+    #[serde(deserialize_with = "deserialize_amount")]
+    payment: U256,
+    igp_id: String,
+}
+
+/// Parses a dispatched message out of the attributes of an `EventDispatch`
+pub(crate) fn dispatch_parser(
+    attrs: &Vec<EventAttribute>,
+) -> ChainResult<ParsedEvent<HyperlaneMessage>> {
+    let event: EventDispatch = parse_typed_event(attrs)?;
+    // Intentionally using read_from to get a Result::Err if there's an issue
+    // with the message.
+    let mut reader = Cursor::new(hex::decode(event.message.trim_start_matches("0x"))?);
+    let message = HyperlaneMessage::read_from(&mut reader)?;
+    Ok(ParsedEvent::new(event.origin_mailbox_id, message))
+}
+
+/// Parses the ID of a delivered message out of the attributes of an
+/// `EventProcess`
+pub(crate) fn process_parser(attrs: &Vec<EventAttribute>) -> ChainResult<ParsedEvent<H256>> {
+    let event: EventProcess = parse_typed_event(attrs)?;
+    Ok(ParsedEvent::new(
+        event.origin_mailbox_id,
+        parse_hex_id(&event.message_id)?,
+    ))
+}
+
+/// Parses a merkle tree insertion out of the attributes of an
+/// `EventInsertedIntoTree`
+pub(crate) fn inserted_into_tree_parser(
+    attrs: &Vec<EventAttribute>,
+) -> ChainResult<ParsedEvent<MerkleTreeInsertion>> {
+    let event: EventInsertedIntoTree = parse_typed_event(attrs)?;
+    Ok(ParsedEvent::new(
+        event.merkle_tree_hook_id,
+        MerkleTreeInsertion::new(event.index, parse_hex_id(&event.message_id)?),
+    ))
+}
+
+/// Parses a gas payment out of the attributes of an `EventGasPayment`
+pub(crate) fn gas_payment_parser(
+    attrs: &Vec<EventAttribute>,
+) -> ChainResult<ParsedEvent<InterchainGasPayment>> {
+    let event: EventGasPayment = parse_typed_event(attrs)?;
+    Ok(ParsedEvent::new(
+        event.igp_id,
+        InterchainGasPayment {
+            message_id: parse_hex_id(&event.message_id)?,
+            payment: event.payment,
+            gas_amount: event.gas_amount,
+            destination: event.destination,
+        },
+    ))
+}
+
+/// Deserializes the JSON encoded attributes of a typed event into the fields
+/// of `T`. Attributes are plain as of Tendermint 0.37 and base64 encoded
+/// before.
+fn parse_typed_event<T: DeserializeOwned>(attrs: &[EventAttribute]) -> ChainResult<T> {
+    let mut fields = Map::new();
+    for attr in attrs {
+        let (key, value) = match attr {
+            EventAttribute::V037(a) => match (BASE64.decode(&a.key), BASE64.decode(&a.value)) {
+                // Plain keys never happen to be the base64 encoding of a
+                // snake case field name
+                (Ok(key), Ok(value)) if is_field_name(&key) => (key, value),
+                _ => (a.key.clone().into_bytes(), a.value.clone().into_bytes()),
+            },
+            EventAttribute::V034(a) => (a.key.clone(), a.value.clone()),
+        };
+        let Ok(key) = String::from_utf8(key) else {
+            continue;
+        };
+        // Attributes that aren't JSON, e.g. the `mode` the SDK adds to every
+        // event, aren't fields of the event
+        if let Ok(value) = serde_json::from_slice::<Value>(&value) {
+            fields.insert(key, value);
+        }
+    }
+    serde_json::from_value(Value::Object(fields))
+        .map_err(|err| HyperlaneCosmosError::from(err).into())
+}
+
+fn is_field_name(key: &[u8]) -> bool {
+    !key.is_empty()
+        && key
+            .iter()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || *byte == b'_')
+}
+
+/// Deserializes an amount, which is JSON encoded as a decimal string, e.g.
+/// `"25000"`, optionally followed by a denom, e.g. `"2uatom"`.
+fn deserialize_amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+    let amount = match Value::deserialize(deserializer)? {
+        Value::String(amount) => amount,
+        Value::Number(amount) => amount.to_string(),
+        value => {
+            return Err(serde::de::Error::custom(format!(
+                "`{value}` isn't an amount"
+            )))
+        }
+    };
+    let digits = amount
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(amount.as_str(), |end| &amount[..end]);
+    U256::from_dec_str(digits)
+        .map_err(|_| serde::de::Error::custom(format!("`{amount}` isn't an amount")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::native::hex_id;
+    use crate::utils::event_attributes_from_str;
+
+    use super::*;
+
+    const MAILBOX_ID: &str = "0x68797065726c616e650000000000000000000000000000000000000000000000";
+    const MESSAGE_ID: &str = "0x5dcf6120f8adf4f267eb1a122a85c42eae257fbc872671e93929fbf63daed19b";
+    const MESSAGE: &str = "03000000006e74726e0000000000000000000000006ba6343a09a60ac048d0e99f50b76fd99eff1063000000a9000000000000000000000000281973b53c9aacec128ac964a6f750fea40912aa48656c6c6f";
+
+    fn attrs(attrs: &[(&str, &str)]) -> Vec<EventAttribute> {
+        let attrs: Vec<Value> = attrs
+            .iter()
+            .map(|(key, value)| serde_json::json!({"key": key, "value": value, "index": true}))
+            .collect();
+        event_attributes_from_str(&serde_json::to_string(&attrs).unwrap())
+    }
+
+    #[test]
+    fn test_dispatch_parser() {
+        let message = format!("\"0x{MESSAGE}\"");
+        let mailbox_id = format!("\"{MAILBOX_ID}\"");
+        let expected = ParsedEvent::new(
+            MAILBOX_ID.to_owned(),
+            HyperlaneMessage::from(hex::decode(MESSAGE).unwrap()),
+        );
+
+        let plain = attrs(&[
+            ("destination", "169"),
+            ("message", &message),
+            ("origin_mailbox_id", &mailbox_id),
+            ("mode", "EndBlock"),
+        ]);
+        assert_eq!(dispatch_parser(&plain).unwrap(), expected);
+
+        // Tendermint 0.34
+        let base64 = attrs(&[
+            ("ZGVzdGluYXRpb24=", &BASE64.encode("169")),
+            ("bWVzc2FnZQ==", &BASE64.encode(&message)),
+            ("b3JpZ2luX21haWxib3hfaWQ=", &BASE64.encode(&mailbox_id)),
+        ]);
+        assert_eq!(dispatch_parser(&base64).unwrap(), expected);
+
+        assert!(dispatch_parser(&attrs(&[("origin_mailbox_id", &mailbox_id)])).is_err());
+    }
+
+    #[test]
+    fn test_process_parser() {
+        let parsed = process_parser(&attrs(&[
+            ("origin", "169"),
+            ("origin_mailbox_id", &format!("\"{MAILBOX_ID}\"")),
+            ("message_id", &format!("\"{MESSAGE_ID}\"")),
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            ParsedEvent::new(MAILBOX_ID.to_owned(), H256::from_str(MESSAGE_ID).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_inserted_into_tree_parser() {
+        let hook_id = hex_id(H256::from_low_u64_be(7));
+        let parsed = inserted_into_tree_parser(&attrs(&[
+            ("index", "4"),
+            ("merkle_tree_hook_id", &format!("\"{hook_id}\"")),
+            ("message_id", &format!("\"{MESSAGE_ID}\"")),
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            ParsedEvent::new(
+                hook_id,
+                MerkleTreeInsertion::new(4, H256::from_str(MESSAGE_ID).unwrap())
+            )
+        );
+    }
+
+    #[test]
+    fn test_gas_payment_parser() {
+        let igp_id = hex_id(H256::from_low_u64_be(9));
+        let parsed = gas_payment_parser(&attrs(&[
+            ("destination", "169"),
+            ("gas_amount", "\"25000\""),
+            ("igp_id", &format!("\"{igp_id}\"")),
+            ("message_id", &format!("\"{MESSAGE_ID}\"")),
+            ("payment", "\"2uatom\""),
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            ParsedEvent::new(
+                igp_id,
+                InterchainGasPayment {
+                    message_id: H256::from_str(MESSAGE_ID).unwrap(),
+                    payment: U256::from(2),
+                    gas_amount: U256::from(25000),
+                    destination: 169,
+                }
+            )
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneProvider, Indexed, Indexer, InterchainGasPaymaster, InterchainGasPayment, LogMeta,
+    SequenceAwareIndexer, H256, H512,
+};
+
+use crate::native::events::{gas_payment_parser, GAS_PAYMENT_EVENT_KIND, IGP_ID_ATTRIBUTE_KEY};
+use crate::rpc::{CosmosWasmRpcProvider, WasmRpcProvider};
+use crate::utils::{get_logs_in_range, parse_logs_in_tx};
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+/// A reference to an IGP of the native `x/hyperlane` module on some Cosmos
+/// chain
+#[derive(Debug)]
+pub struct CosmosNativeInterchainGasPaymaster {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl HyperlaneContract for CosmosNativeInterchainGasPaymaster {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeInterchainGasPaymaster {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+impl InterchainGasPaymaster for CosmosNativeInterchainGasPaymaster {}
+
+impl CosmosNativeInterchainGasPaymaster {
+    /// Create a reference to the IGP with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+/// Struct that retrieves the gas payments of an IGP of the native
+/// `x/hyperlane` module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeInterchainGasPaymasterIndexer {
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeInterchainGasPaymasterIndexer {
+    /// Create an indexer of the IGP with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf,
+            locator,
+            GAS_PAYMENT_EVENT_KIND,
+            IGP_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            provider: Box::new(provider),
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<InterchainGasPayment> for CosmosNativeInterchainGasPaymasterIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        get_logs_in_range(
+            range,
+            self.provider.clone(),
+            gas_payment_parser,
+            "InterchainGasPaymentCursor",
+        )
+        .await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            gas_payment_parser,
+            "InterchainGasPaymentReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<InterchainGasPayment> for CosmosNativeInterchainGasPaymasterIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // Gas payments aren't sequenced
+        let tip = self.get_finalized_block_number().await?;
+        Ok((None, tip))
+    }
+}
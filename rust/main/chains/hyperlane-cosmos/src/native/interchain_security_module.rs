@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use prost::Name;
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType,
+    RawHyperlaneMessage, H256, U256,
+};
+
+use crate::native::hex_id;
+use crate::native::proto::{
+    AggregationIsm, MerkleRootMultisigIsm, MessageIdMultisigIsm, NoopIsm, QueryIsmRequest,
+    QueryIsmResponse, QueryVerifyDryRunRequest, QueryVerifyDryRunResponse, RoutingIsm,
+    QUERY_ISM_PATH, QUERY_VERIFY_DRY_RUN_PATH,
+};
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+/// Queries the ISM with ID `id`, returning it as a protobuf `Any`.
+pub(crate) async fn query_ism(provider: &CosmosProvider, id: H256) -> ChainResult<cosmrs::Any> {
+    provider
+        .grpc()
+        .module_query::<_, QueryIsmResponse>(
+            QUERY_ISM_PATH,
+            QueryIsmRequest { id: hex_id(id) },
+            None,
+        )
+        .await?
+        .ism
+        .ok_or_else(|| ChainCommunicationError::from_other_str("ISM not present"))
+}
+
+#[derive(Debug)]
+/// An ISM of the native `x/hyperlane` module.
+pub struct CosmosNativeInterchainSecurityModule {
+    /// The domain of the ISM.
+    domain: HyperlaneDomain,
+    /// The ID of the ISM.
+    address: H256,
+    /// The provider for the ISM.
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeInterchainSecurityModule {
+    /// Creates a reference to the ISM with ID `locator.address`.
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for CosmosNativeInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for CosmosNativeInterchainSecurityModule {
+    /// Returns the module type of the ISM, derived from the protobuf type it
+    /// is stored as.
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let ism = query_ism(&self.provider, self.address).await?;
+        match ism.type_url.as_str() {
+            url if url == MessageIdMultisigIsm::type_url() => Ok(ModuleType::MessageIdMultisig),
+            url if url == MerkleRootMultisigIsm::type_url() => Ok(ModuleType::MerkleRootMultisig),
+            url if url == NoopIsm::type_url() => Ok(ModuleType::Null),
+            url if url == RoutingIsm::type_url() => Ok(ModuleType::Routing),
+            url if url == AggregationIsm::type_url() => Ok(ModuleType::Aggregation),
+            url => Err(ChainCommunicationError::from_other_str(&format!(
+                "unsupported ISM type `{url}`"
+            ))),
+        }
+    }
+
+    /// Dry runs the ISM's verification of `message` with `metadata`, returning
+    /// `Some` if it succeeds.
+    async fn dry_run_verify(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        let response: QueryVerifyDryRunResponse = self
+            .provider
+            .grpc()
+            .module_query(
+                QUERY_VERIFY_DRY_RUN_PATH,
+                QueryVerifyDryRunRequest {
+                    ism_id: hex_id(self.address),
+                    message: hex::encode(RawHyperlaneMessage::from(message)),
+                    metadata: hex::encode(metadata),
+                    gas_limit: String::new(),
+                },
+                None,
+            )
+            .await?;
+        // The query doesn't report the gas used, so as with the CosmWasm ISMs
+        // a dummy gas value is returned
+        let dummy_gas_value = U256::one();
+        Ok(response.verified.then_some(dummy_gas_value))
+    }
+}
@@ -0,0 +1,369 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use cosmrs::Any;
+use prost::Message;
+use tracing::instrument;
+
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexed, Indexer,
+    LogMeta, Mailbox, RawHyperlaneMessage, ReorgPeriod, SequenceAwareIndexer, TxCostEstimate,
+    TxOutcome, H256, H512, U256,
+};
+
+use crate::native::events::{
+    dispatch_parser, process_parser, DISPATCH_EVENT_KIND, MAILBOX_ID_ATTRIBUTE_KEY,
+    PROCESS_EVENT_KIND,
+};
+use crate::native::proto::{
+    self, MsgProcessMessage, QueryDeliveredRequest, QueryDeliveredResponse, QueryMailboxRequest,
+    QueryMailboxResponse, QueryRecipientIsmRequest, QueryRecipientIsmResponse,
+    QUERY_DELIVERED_PATH, QUERY_MAILBOX_PATH, QUERY_RECIPIENT_ISM_PATH,
+};
+use crate::native::{hex_id, parse_hex_id};
+use crate::rpc::{CosmosWasmRpcProvider, WasmRpcProvider};
+use crate::types::tx_response_to_outcome;
+use crate::utils::{get_block_height_for_reorg_period, get_logs_in_range, parse_logs_in_tx};
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+#[derive(Clone, Debug)]
+/// A reference to a mailbox of the native `x/hyperlane` module on some Cosmos
+/// chain
+pub struct CosmosNativeMailbox {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeMailbox {
+    /// Create a reference to the mailbox with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+
+    async fn mailbox(&self, block_height: Option<u64>) -> ChainResult<proto::Mailbox> {
+        self.provider
+            .grpc()
+            .module_query::<_, QueryMailboxResponse>(
+                QUERY_MAILBOX_PATH,
+                QueryMailboxRequest {
+                    id: hex_id(self.address),
+                },
+                block_height,
+            )
+            .await?
+            .mailbox
+            .ok_or_else(|| ChainCommunicationError::from_other_str("mailbox not present"))
+    }
+
+    /// The message processing `message`, sent by `relayer`
+    fn process_message(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        relayer: String,
+    ) -> MsgProcessMessage {
+        MsgProcessMessage {
+            mailbox_id: hex_id(self.address),
+            relayer,
+            metadata: hex::encode(metadata),
+            message: hex::encode(RawHyperlaneMessage::from(message)),
+        }
+    }
+
+    /// The messages processing `message`, executed on behalf of the authz
+    /// granter if one is configured
+    async fn process_msgs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<Any>> {
+        let grpc = self.provider.grpc();
+        let msg = self.process_message(message, metadata, grpc.delivering_account()?);
+        let msg = Any::from_msg(&msg).map_err(ChainCommunicationError::from_other)?;
+        grpc.delegate_msgs(vec![msg]).await
+    }
+}
+
+impl HyperlaneContract for CosmosNativeMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl Mailbox for CosmosNativeMailbox {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        let block_height =
+            get_block_height_for_reorg_period(self.provider.grpc(), reorg_period).await?;
+        Ok(self.mailbox(block_height).await?.message_sent)
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let response: QueryDeliveredResponse = self
+            .provider
+            .grpc()
+            .module_query(
+                QUERY_DELIVERED_PATH,
+                QueryDeliveredRequest {
+                    id: hex_id(self.address),
+                    message_id: hex_id(id),
+                },
+                None,
+            )
+            .await?;
+
+        Ok(response.delivered)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn default_ism(&self) -> ChainResult<H256> {
+        parse_hex_id(&self.mailbox(None).await?.default_ism)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let response: QueryRecipientIsmResponse = self
+            .provider
+            .grpc()
+            .module_query(
+                QUERY_RECIPIENT_ISM_PATH,
+                QueryRecipientIsmRequest {
+                    recipient: hex_id(recipient),
+                },
+                None,
+            )
+            .await?;
+
+        parse_hex_id(&response.ism_id)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let msgs = self.process_msgs(message, metadata).await?;
+        // Gas limits that don't fit in a u64 fall back to estimation
+        let gas_limit = tx_gas_limit.and_then(|limit| u64::try_from(limit).ok());
+        let response = self.provider.grpc().send_msgs(msgs, gas_limit).await?;
+
+        Ok(tx_response_to_outcome(
+            response,
+            self.provider.grpc().gas_price(),
+        )?)
+    }
+
+    #[instrument(err, ret, skip(self), fields(hyp_message=%message, metadata=%bytes_to_hex(metadata)))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        let msgs = self.process_msgs(message, metadata).await?;
+        let gas_limit = self.provider.grpc().estimate_gas(msgs).await?;
+
+        Ok(TxCostEstimate {
+            gas_limit: gas_limit.into(),
+            gas_price: self.provider.grpc().gas_price(),
+            l2_gas_limit: None,
+        })
+    }
+
+    /// The protobuf encoded `MsgProcessMessage`. The relayer is left empty,
+    /// as it's the account the transaction is sent from.
+    fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
+        Ok(self
+            .process_message(message, metadata, String::new())
+            .encode_to_vec())
+    }
+}
+
+/// Struct that retrieves the messages dispatched by a mailbox of the native
+/// `x/hyperlane` module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeMailboxDispatchIndexer {
+    mailbox: CosmosNativeMailbox,
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeMailboxDispatchIndexer {
+    /// Create an indexer of the mailbox with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let mailbox = CosmosNativeMailbox::new(conf.clone(), locator.clone(), signer)?;
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf,
+            locator,
+            DISPATCH_EVENT_KIND,
+            MAILBOX_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            mailbox,
+            provider: Box::new(provider),
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for CosmosNativeMailboxDispatchIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        get_logs_in_range(
+            range,
+            self.provider.clone(),
+            dispatch_parser,
+            "HyperlaneMessageCursor",
+        )
+        .await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            dispatch_parser,
+            "HyperlaneMessageReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for CosmosNativeMailboxDispatchIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&self).await?;
+        let sequence = self.mailbox.mailbox(Some(tip.into())).await?.message_sent;
+
+        Ok((Some(sequence), tip))
+    }
+}
+
+/// Struct that retrieves the deliveries of a mailbox of the native
+/// `x/hyperlane` module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeMailboxDeliveryIndexer {
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeMailboxDeliveryIndexer {
+    /// Create an indexer of the deliveries of the mailbox with ID
+    /// `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf,
+            locator,
+            PROCESS_EVENT_KIND,
+            MAILBOX_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            provider: Box::new(provider),
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for CosmosNativeMailboxDeliveryIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        get_logs_in_range(
+            range,
+            self.provider.clone(),
+            process_parser,
+            "DeliveryCursor",
+        )
+        .await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            process_parser,
+            "DeliveryReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for CosmosNativeMailboxDeliveryIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = Indexer::<H256>::get_finalized_block_number(&self).await?;
+
+        // No sequence for message deliveries.
+        Ok((None, tip))
+    }
+}
@@ -0,0 +1,282 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use tracing::instrument;
+
+use hyperlane_core::accumulator::incremental::IncrementalMerkle;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod, SequenceAwareIndexer, H256, H512,
+};
+
+use crate::native::events::{
+    inserted_into_tree_parser, INSERTED_INTO_TREE_EVENT_KIND, MERKLE_TREE_HOOK_ID_ATTRIBUTE_KEY,
+};
+use crate::native::hex_id;
+use crate::native::proto::{
+    self, QueryMerkleTreeHookRequest, QueryMerkleTreeHookResponse, QUERY_MERKLE_TREE_HOOK_PATH,
+};
+use crate::rpc::{CosmosWasmRpcProvider, WasmRpcProvider};
+use crate::utils::{get_block_height_for_reorg_period, get_logs_in_range, parse_logs_in_tx};
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+/// A reference to a merkle tree hook of the native `x/hyperlane` module on
+/// some Cosmos chain
+#[derive(Debug, Clone)]
+pub struct CosmosNativeMerkleTreeHook {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeMerkleTreeHook {
+    /// Create a reference to the merkle tree hook with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+
+    async fn merkle_tree(&self, block_height: Option<u64>) -> ChainResult<proto::MerkleTree> {
+        self.provider
+            .grpc()
+            .module_query::<_, QueryMerkleTreeHookResponse>(
+                QUERY_MERKLE_TREE_HOOK_PATH,
+                QueryMerkleTreeHookRequest {
+                    id: hex_id(self.address),
+                },
+                block_height,
+            )
+            .await?
+            .merkle_tree_hook
+            .and_then(|hook| hook.merkle_tree)
+            .ok_or_else(|| ChainCommunicationError::from_other_str("merkle tree hook not present"))
+    }
+
+    async fn merkle_tree_at_reorg_period(
+        &self,
+        reorg_period: &ReorgPeriod,
+    ) -> ChainResult<proto::MerkleTree> {
+        let block_height =
+            get_block_height_for_reorg_period(self.provider.grpc(), reorg_period).await?;
+        self.merkle_tree(block_height).await
+    }
+}
+
+impl HyperlaneContract for CosmosNativeMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl MerkleTreeHook for CosmosNativeMerkleTreeHook {
+    /// Return the incremental merkle tree in storage
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn tree(&self, reorg_period: &ReorgPeriod) -> ChainResult<IncrementalMerkle> {
+        let tree = self.merkle_tree_at_reorg_period(reorg_period).await?;
+        incremental_merkle(&tree)
+    }
+
+    /// Gets the current leaf count of the merkle tree
+    async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        Ok(self.merkle_tree_at_reorg_period(reorg_period).await?.count)
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn latest_checkpoint(&self, reorg_period: &ReorgPeriod) -> ChainResult<Checkpoint> {
+        let tree = self.merkle_tree_at_reorg_period(reorg_period).await?;
+        checkpoint(&tree, self.address, self.domain.id())
+    }
+}
+
+/// The incremental merkle tree of the module's representation of a tree
+fn incremental_merkle(tree: &proto::MerkleTree) -> ChainResult<IncrementalMerkle> {
+    let branch = tree
+        .leafs
+        .iter()
+        .map(|node| {
+            (node.len() == 32)
+                .then(|| H256::from_slice(node))
+                .ok_or_else(|| {
+                    ChainCommunicationError::from_other_str("merkle branch node isn't 32 bytes")
+                })
+        })
+        .collect::<ChainResult<Vec<H256>>>()?;
+    let branch: [H256; 32] = branch.try_into().map_err(|_| {
+        ChainCommunicationError::from_other_str("Failed to build merkle branch array")
+    })?;
+
+    Ok(IncrementalMerkle::new(branch, tree.count as usize))
+}
+
+/// The checkpoint of the latest message inserted into `tree`. As with the
+/// merkle tree hook contracts, an empty tree has no checkpoint.
+fn checkpoint(
+    tree: &proto::MerkleTree,
+    merkle_tree_hook_address: H256,
+    mailbox_domain: u32,
+) -> ChainResult<Checkpoint> {
+    let index = tree.count.checked_sub(1).ok_or_else(|| {
+        ChainCommunicationError::from_other_str("merkle tree is empty, so it has no checkpoint")
+    })?;
+    if tree.root.len() != 32 {
+        return Err(ChainCommunicationError::from_other_str(
+            "merkle root isn't 32 bytes",
+        ));
+    }
+
+    Ok(Checkpoint {
+        merkle_tree_hook_address,
+        mailbox_domain,
+        root: H256::from_slice(&tree.root),
+        index,
+    })
+}
+
+/// Struct that retrieves the insertions into a merkle tree hook of the native
+/// `x/hyperlane` module
+#[derive(Debug, Clone)]
+pub struct CosmosNativeMerkleTreeHookIndexer {
+    merkle_tree_hook: CosmosNativeMerkleTreeHook,
+    provider: Box<CosmosWasmRpcProvider>,
+}
+
+impl CosmosNativeMerkleTreeHookIndexer {
+    /// Create an indexer of the merkle tree hook with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let provider = CosmosWasmRpcProvider::new_native(
+            conf.clone(),
+            locator.clone(),
+            INSERTED_INTO_TREE_EVENT_KIND,
+            MERKLE_TREE_HOOK_ID_ATTRIBUTE_KEY,
+            reorg_period,
+        )?;
+
+        Ok(Self {
+            merkle_tree_hook: CosmosNativeMerkleTreeHook::new(conf, locator, signer)?,
+            provider: Box::new(provider),
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for CosmosNativeMerkleTreeHookIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        get_logs_in_range(
+            range,
+            self.provider.clone(),
+            inserted_into_tree_parser,
+            "MerkleTreeInsertionCursor",
+        )
+        .await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        parse_logs_in_tx(
+            &tx_hash.into(),
+            self.provider.clone(),
+            inserted_into_tree_parser,
+            "MerkleTreeInsertionReceiver",
+        )
+        .await
+        .map(|v| v.into_iter().map(|(m, l)| (m.into(), l)).collect())
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for CosmosNativeMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.get_finalized_block_number().await?;
+        let count = self
+            .merkle_tree_hook
+            .merkle_tree(Some(tip.into()))
+            .await?
+            .count;
+
+        Ok((Some(count), tip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(count: u32) -> proto::MerkleTree {
+        proto::MerkleTree {
+            leafs: (0..32u64)
+                .map(|i| H256::from_low_u64_be(i).as_bytes().to_vec())
+                .collect(),
+            root: H256::repeat_byte(0xab).as_bytes().to_vec(),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_incremental_merkle() {
+        let merkle = incremental_merkle(&tree(3)).unwrap();
+        assert_eq!(merkle.count(), 3);
+        assert_eq!(merkle.branch()[5], H256::from_low_u64_be(5));
+
+        let mut short = tree(3);
+        short.leafs.pop();
+        assert!(incremental_merkle(&short).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let hook = H256::from_low_u64_be(1);
+        let checkpoint = checkpoint(&tree(3), hook, 169).unwrap();
+        assert_eq!(
+            checkpoint,
+            Checkpoint {
+                merkle_tree_hook_address: hook,
+                mailbox_domain: 169,
+                root: H256::repeat_byte(0xab),
+                index: 2,
+            }
+        );
+        assert!(super::checkpoint(&tree(0), hook, 169).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use prost::{Message, Name};
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, H160, H256,
+};
+
+use crate::native::interchain_security_module::query_ism;
+use crate::native::proto::{MerkleRootMultisigIsm, MessageIdMultisigIsm};
+use crate::{ConnectionConf, CosmosProvider, HyperlaneCosmosError, Signer};
+
+/// A reference to a multisig ISM of the native `x/hyperlane` module
+#[derive(Debug)]
+pub struct CosmosNativeMultisigIsm {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeMultisigIsm {
+    /// Create a reference to the multisig ISM with ID `locator.address`
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for CosmosNativeMultisigIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for CosmosNativeMultisigIsm {
+    /// Returns the validator and threshold needed to verify message. Both
+    /// multisig ISM types are configured the same way for every message.
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let ism = query_ism(&self.provider, self.address).await?;
+        let (validators, threshold) = match ism.type_url.as_str() {
+            url if url == MessageIdMultisigIsm::type_url() => {
+                let ism = MessageIdMultisigIsm::decode(ism.value.as_slice())
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                (ism.validators, ism.threshold)
+            }
+            url if url == MerkleRootMultisigIsm::type_url() => {
+                let ism = MerkleRootMultisigIsm::decode(ism.value.as_slice())
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                (ism.validators, ism.threshold)
+            }
+            url => {
+                return Err(ChainCommunicationError::from_other_str(&format!(
+                    "ISM type `{url}` is not a multisig ISM"
+                )))
+            }
+        };
+
+        let validators: ChainResult<Vec<H256>> = validators
+            .iter()
+            .map(|v| {
+                H160::from_str(v.trim_start_matches("0x"))
+                    .map(H256::from)
+                    .map_err(Into::into)
+            })
+            .collect();
+        let threshold = threshold
+            .try_into()
+            .map_err(ChainCommunicationError::from_other)?;
+
+        Ok((validators?, threshold))
+    }
+}
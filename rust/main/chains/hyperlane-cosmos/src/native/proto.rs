@@ -0,0 +1,298 @@
+//! Protobuf types of the `x/hyperlane` module's Query and Msg services.
+//!
+//! Only the messages used by the agents are defined. Object IDs and message
+//! bodies are hex encoded strings, as in the module's own protobuf definitions.
+
+use prost::Name;
+
+/// gRPC path of the `Mailbox` query
+pub const QUERY_MAILBOX_PATH: &str = "/hyperlane.core.v1.Query/Mailbox";
+/// gRPC path of the `Delivered` query
+pub const QUERY_DELIVERED_PATH: &str = "/hyperlane.core.v1.Query/Delivered";
+/// gRPC path of the `RecipientIsm` query
+pub const QUERY_RECIPIENT_ISM_PATH: &str = "/hyperlane.core.v1.Query/RecipientIsm";
+/// gRPC path of the `VerifyDryRun` query
+pub const QUERY_VERIFY_DRY_RUN_PATH: &str = "/hyperlane.core.v1.Query/VerifyDryRun";
+/// gRPC path of the `Ism` query
+pub const QUERY_ISM_PATH: &str = "/hyperlane.core.interchain_security.v1.Query/Ism";
+/// gRPC path of the `AnnouncedStorageLocations` query
+pub const QUERY_ANNOUNCED_STORAGE_LOCATIONS_PATH: &str =
+    "/hyperlane.core.interchain_security.v1.Query/AnnouncedStorageLocations";
+/// gRPC path of the `MerkleTreeHook` query
+pub const QUERY_MERKLE_TREE_HOOK_PATH: &str =
+    "/hyperlane.core.post_dispatch.v1.Query/MerkleTreeHook";
+
+const CORE_PACKAGE: &str = "hyperlane.core.v1";
+const INTERCHAIN_SECURITY_PACKAGE: &str = "hyperlane.core.interchain_security.v1";
+
+/// A mailbox
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Mailbox {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(uint32, tag = "3")]
+    pub message_sent: u32,
+    #[prost(uint32, tag = "4")]
+    pub message_received: u32,
+    #[prost(string, tag = "5")]
+    pub default_ism: String,
+    #[prost(string, tag = "6")]
+    pub default_hook: String,
+    #[prost(string, tag = "7")]
+    pub required_hook: String,
+    #[prost(uint32, tag = "8")]
+    pub local_domain: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMailboxRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMailboxResponse {
+    #[prost(message, optional, tag = "1")]
+    pub mailbox: Option<Mailbox>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryDeliveredRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub message_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryDeliveredResponse {
+    #[prost(bool, tag = "1")]
+    pub delivered: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRecipientIsmRequest {
+    #[prost(string, tag = "1")]
+    pub recipient: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRecipientIsmResponse {
+    #[prost(string, tag = "1")]
+    pub ism_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryVerifyDryRunRequest {
+    #[prost(string, tag = "1")]
+    pub ism_id: String,
+    #[prost(string, tag = "2")]
+    pub message: String,
+    #[prost(string, tag = "3")]
+    pub metadata: String,
+    #[prost(string, tag = "4")]
+    pub gas_limit: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryVerifyDryRunResponse {
+    #[prost(bool, tag = "1")]
+    pub verified: bool,
+}
+
+/// Processes a message, relaying it to its recipient
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgProcessMessage {
+    #[prost(string, tag = "1")]
+    pub mailbox_id: String,
+    #[prost(string, tag = "2")]
+    pub relayer: String,
+    #[prost(string, tag = "3")]
+    pub metadata: String,
+    #[prost(string, tag = "4")]
+    pub message: String,
+}
+
+impl Name for MsgProcessMessage {
+    const NAME: &'static str = "MsgProcessMessage";
+    const PACKAGE: &'static str = CORE_PACKAGE;
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIsmRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryIsmResponse {
+    /// One of the ISM types, e.g. [`MessageIdMultisigIsm`]
+    #[prost(message, optional, tag = "1")]
+    pub ism: Option<cosmrs::Any>,
+}
+
+/// A message ID multisig ISM
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MessageIdMultisigIsm {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(string, repeated, tag = "3")]
+    pub validators: Vec<String>,
+    #[prost(uint32, tag = "4")]
+    pub threshold: u32,
+}
+
+impl Name for MessageIdMultisigIsm {
+    const NAME: &'static str = "MessageIdMultisigISM";
+    const PACKAGE: &'static str = INTERCHAIN_SECURITY_PACKAGE;
+}
+
+/// A merkle root multisig ISM
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MerkleRootMultisigIsm {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(string, repeated, tag = "3")]
+    pub validators: Vec<String>,
+    #[prost(uint32, tag = "4")]
+    pub threshold: u32,
+}
+
+impl Name for MerkleRootMultisigIsm {
+    const NAME: &'static str = "MerkleRootMultisigISM";
+    const PACKAGE: &'static str = INTERCHAIN_SECURITY_PACKAGE;
+}
+
+/// An ISM routing messages to the ISM configured for their origin domain
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RoutingIsm {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(message, repeated, tag = "3")]
+    pub routes: Vec<Route>,
+}
+
+impl Name for RoutingIsm {
+    const NAME: &'static str = "RoutingISM";
+    const PACKAGE: &'static str = INTERCHAIN_SECURITY_PACKAGE;
+}
+
+/// The ISM of an origin domain of a [`RoutingIsm`]
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Route {
+    #[prost(string, tag = "1")]
+    pub ism: String,
+    #[prost(uint32, tag = "2")]
+    pub domain: u32,
+}
+
+/// An ISM requiring `threshold` of its modules to verify a message
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AggregationIsm {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(string, repeated, tag = "3")]
+    pub modules: Vec<String>,
+    #[prost(uint32, tag = "4")]
+    pub threshold: u32,
+}
+
+impl Name for AggregationIsm {
+    const NAME: &'static str = "AggregationISM";
+    const PACKAGE: &'static str = INTERCHAIN_SECURITY_PACKAGE;
+}
+
+/// An ISM accepting every message
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct NoopIsm {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+}
+
+impl Name for NoopIsm {
+    const NAME: &'static str = "NoopISM";
+    const PACKAGE: &'static str = INTERCHAIN_SECURITY_PACKAGE;
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAnnouncedStorageLocationsRequest {
+    #[prost(string, tag = "1")]
+    pub mailbox_id: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryAnnouncedStorageLocationsResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub storage_locations: Vec<String>,
+}
+
+/// Announces the storage location of a validator's signatures
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MsgAnnounceValidator {
+    #[prost(string, tag = "1")]
+    pub validator: String,
+    #[prost(string, tag = "2")]
+    pub storage_location: String,
+    #[prost(string, tag = "3")]
+    pub signature: String,
+    #[prost(string, tag = "4")]
+    pub mailbox_id: String,
+    #[prost(string, tag = "5")]
+    pub creator: String,
+}
+
+impl Name for MsgAnnounceValidator {
+    const NAME: &'static str = "MsgAnnounceValidator";
+    const PACKAGE: &'static str = INTERCHAIN_SECURITY_PACKAGE;
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMerkleTreeHookRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryMerkleTreeHookResponse {
+    #[prost(message, optional, tag = "1")]
+    pub merkle_tree_hook: Option<MerkleTreeHook>,
+}
+
+/// A merkle tree hook
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MerkleTreeHook {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub owner: String,
+    #[prost(string, tag = "3")]
+    pub mailbox_id: String,
+    #[prost(message, optional, tag = "4")]
+    pub merkle_tree: Option<MerkleTree>,
+}
+
+/// The incremental merkle tree of a merkle tree hook
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MerkleTree {
+    /// The branch of the tree, 32 nodes of 32 bytes
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub leafs: Vec<Vec<u8>>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub root: Vec<u8>,
+    #[prost(uint32, tag = "3")]
+    pub count: u32,
+}
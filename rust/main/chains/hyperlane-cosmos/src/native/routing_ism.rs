@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use prost::{Message, Name};
+
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RoutingIsm, H256,
+};
+
+use crate::native::interchain_security_module::query_ism;
+use crate::native::parse_hex_id;
+use crate::native::proto;
+use crate::{ConnectionConf, CosmosProvider, HyperlaneCosmosError, Signer};
+
+/// A reference to a routing ISM of the native `x/hyperlane` module
+#[derive(Debug)]
+pub struct CosmosNativeRoutingIsm {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeRoutingIsm {
+    /// Create a reference to the routing ISM with ID `locator.address`
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for CosmosNativeRoutingIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeRoutingIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl RoutingIsm for CosmosNativeRoutingIsm {
+    /// Returns the ISM routed to for the origin of `message`
+    async fn route(&self, message: &HyperlaneMessage) -> ChainResult<H256> {
+        let ism = query_ism(&self.provider, self.address).await?;
+        if ism.type_url != proto::RoutingIsm::type_url() {
+            return Err(ChainCommunicationError::from_other_str(&format!(
+                "ISM type `{}` is not a routing ISM",
+                ism.type_url
+            )));
+        }
+        let ism = proto::RoutingIsm::decode(ism.value.as_slice())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        route(&ism, message.origin)
+    }
+}
+
+fn route(ism: &proto::RoutingIsm, origin: u32) -> ChainResult<H256> {
+    let route = ism
+        .routes
+        .iter()
+        .find(|route| route.domain == origin)
+        .ok_or_else(|| {
+            ChainCommunicationError::from_other_str(&format!(
+                "routing ISM {} has no route for origin {origin}",
+                ism.id
+            ))
+        })?;
+    parse_hex_id(&route.ism)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::native::hex_id;
+
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        let ism = proto::RoutingIsm {
+            id: hex_id(H256::from_low_u64_be(1)),
+            owner: String::new(),
+            routes: vec![
+                proto::Route {
+                    ism: hex_id(H256::from_low_u64_be(2)),
+                    domain: 169,
+                },
+                proto::Route {
+                    ism: hex_id(H256::from_low_u64_be(3)),
+                    domain: 1,
+                },
+            ],
+        };
+        assert_eq!(route(&ism, 1).unwrap(), H256::from_low_u64_be(3));
+        assert!(route(&ism, 2).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use cosmrs::Any;
+use futures::future::try_join_all;
+
+use hyperlane_core::{
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedType, TxOutcome,
+    ValidatorAnnounce, H160, H256, U256,
+};
+
+use crate::native::hex_id;
+use crate::native::proto::{
+    MsgAnnounceValidator, QueryAnnouncedStorageLocationsRequest,
+    QueryAnnouncedStorageLocationsResponse, QUERY_ANNOUNCED_STORAGE_LOCATIONS_PATH,
+};
+use crate::types::tx_response_to_outcome;
+use crate::{ConnectionConf, CosmosProvider, Signer};
+
+/// A reference to the validator announcements of a mailbox of the native
+/// `x/hyperlane` module. Announcements are stored per mailbox, so the
+/// validator announce address is the mailbox ID.
+#[derive(Debug)]
+pub struct CosmosNativeValidatorAnnounce {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: CosmosProvider,
+}
+
+impl CosmosNativeValidatorAnnounce {
+    /// create a new instance of CosmosNativeValidatorAnnounce
+    pub fn new(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<Signer>,
+    ) -> ChainResult<Self> {
+        let provider = CosmosProvider::new(
+            locator.domain.clone(),
+            conf.clone(),
+            locator.clone(),
+            signer,
+        )?;
+
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for CosmosNativeValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for CosmosNativeValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for CosmosNativeValidatorAnnounce {
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        // The module is queried one validator at a time
+        let queries = validators.iter().map(|validator| {
+            self.provider
+                .grpc()
+                .module_query::<_, QueryAnnouncedStorageLocationsResponse>(
+                    QUERY_ANNOUNCED_STORAGE_LOCATIONS_PATH,
+                    QueryAnnouncedStorageLocationsRequest {
+                        mailbox_id: hex_id(self.address),
+                        validator_address: format!(
+                            "0x{}",
+                            hex::encode(H160::from(*validator).as_bytes())
+                        ),
+                    },
+                    None,
+                )
+        });
+
+        Ok(try_join_all(queries)
+            .await?
+            .into_iter()
+            .map(|response| response.storage_locations)
+            .collect())
+    }
+
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        let grpc = self.provider.grpc();
+        let msg = MsgAnnounceValidator {
+            validator: format!("0x{}", hex::encode(announcement.value.validator)),
+            storage_location: announcement.value.storage_location,
+            signature: format!("0x{}", hex::encode(announcement.signature.to_vec())),
+            mailbox_id: hex_id(announcement.value.mailbox_address),
+            creator: grpc.get_signer()?.address.clone(),
+        };
+        let msgs = vec![Any::from_msg(&msg).map_err(ChainCommunicationError::from_other)?];
+
+        // TODO: consider transaction overrides for Cosmos.
+        let response = grpc.send_msgs(msgs, None).await?;
+
+        Ok(tx_response_to_outcome(response, grpc.gas_price())?)
+    }
+
+    async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
+        // TODO: check user balance. For now, just try announcing and
+        // allow the announce attempt to fail if there are not enough tokens.
+        Some(0u64.into())
+    }
+}
@@ -1,5 +1,7 @@
 pub use cosmos::CosmosProvider;
 
+/// caching wasm provider
+pub mod caching;
 /// cosmos provider
 mod cosmos;
 /// cosmos grpc provider
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use cosmrs::proto::cosmos::base::abci::v1beta1::TxResponse;
+use cosmrs::proto::cosmwasm::wasm::v1::ContractInfo;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::trace;
+
+use hyperlane_core::{ChainResult, U256};
+
+use crate::grpc::WasmProvider;
+
+/// The name of the latest block height "query" in [`QueryCacheConfig`].
+pub const LATEST_BLOCK_HEIGHT_QUERY: &str = "latest_block_height";
+
+/// How long the results of each kind of query are cached for.
+///
+/// Wasm queries are named after their innermost query message, e.g.
+/// `{"ism": {"module_type": {}}}` is a `module_type` query. Queries without a
+/// TTL aren't cached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCacheConfig {
+    ttls: HashMap<String, Duration>,
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        Self::new([
+            // Immutable for a given contract
+            ("module_type", Duration::MAX),
+            ("local_domain", Duration::MAX),
+            // Only change when a validator announces a new location
+            ("get_announce_storage_locations", Duration::from_secs(60)),
+            (LATEST_BLOCK_HEIGHT_QUERY, Duration::from_secs(1)),
+        ])
+    }
+}
+
+impl QueryCacheConfig {
+    /// Create a config caching the given queries for the given TTLs.
+    pub fn new<'a>(ttls: impl IntoIterator<Item = (&'a str, Duration)>) -> Self {
+        Self {
+            ttls: ttls
+                .into_iter()
+                .map(|(query, ttl)| (normalize_query_name(query), ttl))
+                .collect(),
+        }
+    }
+
+    /// Set the TTL of a query, overriding the default. A zero TTL disables
+    /// caching of the query.
+    pub fn with_ttl(mut self, query: &str, ttl: Duration) -> Self {
+        self.ttls.insert(normalize_query_name(query), ttl);
+        self
+    }
+
+    /// The TTL of a query, if it is cached.
+    pub fn ttl(&self, query: &str) -> Option<Duration> {
+        self.ttls
+            .get(&normalize_query_name(query))
+            .copied()
+            .filter(|ttl| !ttl.is_zero())
+    }
+}
+
+/// Query names are matched regardless of casing and separators, as config
+/// keys are flattened when loaded.
+fn normalize_query_name(query: &str) -> String {
+    query
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The name of a wasm query, i.e. the innermost key of its nested single key
+/// objects.
+fn query_name(payload: &Value) -> Option<&str> {
+    let mut name = None;
+    let mut value = payload;
+    while let Value::Object(map) = value {
+        let mut entries = map.iter();
+        match (entries.next(), entries.next()) {
+            (Some((key, inner @ Value::Object(_))), None) => {
+                name = Some(key.as_str());
+                value = inner;
+            }
+            _ => break,
+        }
+    }
+    name
+}
+
+#[derive(Debug)]
+struct CachedResult {
+    expires_at: Option<Instant>,
+    data: Vec<u8>,
+}
+
+impl CachedResult {
+    fn new(data: Vec<u8>, ttl: Duration) -> Self {
+        Self {
+            expires_at: Instant::now().checked_add(ttl),
+            data,
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= Instant::now())
+    }
+}
+
+/// A [`WasmProvider`] caching query results according to a
+/// [`QueryCacheConfig`], to reduce the load on RPC endpoints. Clones share the
+/// same cache.
+#[derive(Debug, Clone)]
+pub struct CachingWasmProvider<P> {
+    inner: P,
+    config: QueryCacheConfig,
+    /// Cached results by query payload and block height
+    cache: Arc<Mutex<HashMap<(String, Option<u64>), CachedResult>>>,
+}
+
+impl<P> CachingWasmProvider<P> {
+    /// Wrap `inner`, caching its query results according to `config`.
+    pub fn new(inner: P, config: QueryCacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            cache: Default::default(),
+        }
+    }
+
+    fn get_cached(&self, key: &(String, Option<u64>)) -> Option<Vec<u8>> {
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(key) {
+            Some(cached) if cached.is_expired() => {
+                cache.remove(key);
+                None
+            }
+            Some(cached) => Some(cached.data.clone()),
+            None => None,
+        }
+    }
+
+    fn cache(&self, key: (String, Option<u64>), data: Vec<u8>, ttl: Duration) {
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(key, CachedResult::new(data, ttl));
+    }
+}
+
+impl<P> Deref for CachingWasmProvider<P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<P: WasmProvider> WasmProvider for CachingWasmProvider<P> {
+    async fn latest_block_height(&self) -> ChainResult<u64> {
+        let Some(ttl) = self.config.ttl(LATEST_BLOCK_HEIGHT_QUERY) else {
+            return self.inner.latest_block_height().await;
+        };
+        let key = (LATEST_BLOCK_HEIGHT_QUERY.to_owned(), None);
+        if let Some(cached) = self.get_cached(&key) {
+            return Ok(u64::from_be_bytes(cached.try_into().unwrap()));
+        }
+        let height = self.inner.latest_block_height().await?;
+        self.cache(key, height.to_be_bytes().to_vec(), ttl);
+        Ok(height)
+    }
+
+    async fn wasm_query<T: Serialize + Sync + Send + Clone + Debug>(
+        &self,
+        payload: T,
+        block_height: Option<u64>,
+    ) -> ChainResult<Vec<u8>> {
+        let json = serde_json::to_value(&payload)?;
+        let Some(ttl) = query_name(&json).and_then(|name| self.config.ttl(name)) else {
+            return self.inner.wasm_query(payload, block_height).await;
+        };
+        let key = (json.to_string(), block_height);
+        if let Some(cached) = self.get_cached(&key) {
+            trace!(
+                query = %key.0,
+                ?block_height,
+                "Using cached wasm query result"
+            );
+            return Ok(cached);
+        }
+        let data = self.inner.wasm_query(payload, block_height).await?;
+        self.cache(key, data.clone(), ttl);
+        Ok(data)
+    }
+
+    async fn wasm_contract_info(&self) -> ChainResult<ContractInfo> {
+        self.inner.wasm_contract_info().await
+    }
+
+    async fn wasm_send<T: Serialize + Sync + Send + Clone + Debug>(
+        &self,
+        payload: T,
+        gas_limit: Option<U256>,
+    ) -> ChainResult<TxResponse> {
+        self.inner.wasm_send(payload, gas_limit).await
+    }
+
+    async fn wasm_estimate_gas<T: Serialize + Sync + Send + Clone + Debug>(
+        &self,
+        payload: T,
+    ) -> ChainResult<u64> {
+        self.inner.wasm_estimate_gas(payload).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct CountingProvider {
+        queries: AtomicU64,
+    }
+
+    #[async_trait]
+    impl WasmProvider for CountingProvider {
+        async fn latest_block_height(&self) -> ChainResult<u64> {
+            Ok(self.queries.fetch_add(1, Ordering::SeqCst))
+        }
+
+        async fn wasm_query<T: Serialize + Sync + Send + Clone + Debug>(
+            &self,
+            _payload: T,
+            _block_height: Option<u64>,
+        ) -> ChainResult<Vec<u8>> {
+            Ok(self
+                .queries
+                .fetch_add(1, Ordering::SeqCst)
+                .to_be_bytes()
+                .to_vec())
+        }
+
+        async fn wasm_contract_info(&self) -> ChainResult<ContractInfo> {
+            unimplemented!()
+        }
+
+        async fn wasm_send<T: Serialize + Sync + Send + Clone + Debug>(
+            &self,
+            _payload: T,
+            _gas_limit: Option<U256>,
+        ) -> ChainResult<TxResponse> {
+            unimplemented!()
+        }
+
+        async fn wasm_estimate_gas<T: Serialize + Sync + Send + Clone + Debug>(
+            &self,
+            _payload: T,
+        ) -> ChainResult<u64> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn test_query_name() {
+        assert_eq!(
+            query_name(&json!({"ism": {"module_type": {}}})),
+            Some("module_type")
+        );
+        assert_eq!(
+            query_name(&json!({"get_announce_storage_locations": {"validators": ["00"]}})),
+            Some("get_announce_storage_locations")
+        );
+        assert_eq!(
+            query_name(&json!({"mailbox": {"message_delivered": {"id": "00"}}})),
+            Some("message_delivered")
+        );
+        assert_eq!(query_name(&json!({})), None);
+    }
+
+    #[tokio::test]
+    async fn test_caches_configured_queries() {
+        let provider = CachingWasmProvider::new(
+            CountingProvider::default(),
+            QueryCacheConfig::default().with_ttl("moduleType", Duration::from_secs(60)),
+        );
+        let module_type = json!({"ism": {"module_type": {}}});
+        let delivered = json!({"mailbox": {"message_delivered": {"id": "00"}}});
+
+        let first = provider
+            .wasm_query(module_type.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(provider.wasm_query(module_type, None).await.unwrap(), first);
+        assert_ne!(
+            provider.wasm_query(delivered.clone(), None).await.unwrap(),
+            provider.wasm_query(delivered, None).await.unwrap()
+        );
+        assert_eq!(provider.queries.load(Ordering::SeqCst), 3);
+    }
+}
@@ -24,25 +24,24 @@ use hyperlane_core::{
     H512, U256,
 };
 
+use crate::caching::CachingWasmProvider;
 use crate::grpc::{WasmGrpcProvider, WasmProvider};
 use crate::providers::cosmos::provider::parse::PacketData;
 use crate::providers::rpc::CosmosRpcClient;
 use crate::rpc_clients::CosmosFallbackProvider;
+use crate::signers::{ETHERMINT_PUBLIC_KEY_TYPE_URL, INJECTIVE_PUBLIC_KEY_TYPE_URL};
 use crate::{
     ConnectionConf, CosmosAccountId, CosmosAddress, CosmosAmount, HyperlaneCosmosError, Signer,
 };
 
 mod parse;
 
-/// Injective public key type URL for protobuf Any
-const INJECTIVE_PUBLIC_KEY_TYPE_URL: &str = "/injective.crypto.v1beta1.ethsecp256k1.PubKey";
-
 /// Abstraction over a connection to a Cosmos chain
 #[derive(Debug, Clone)]
 pub struct CosmosProvider {
     domain: HyperlaneDomain,
     connection_conf: ConnectionConf,
-    grpc_provider: WasmGrpcProvider,
+    grpc_provider: CachingWasmProvider<WasmGrpcProvider>,
     rpc_client: CosmosFallbackProvider<CosmosRpcClient>,
 }
 
@@ -62,11 +61,12 @@ impl CosmosProvider {
             locator,
             signer,
         )?;
+        let grpc_provider = CachingWasmProvider::new(grpc_provider, conf.get_query_cache().clone());
 
         let providers = conf
             .get_rpc_urls()
             .iter()
-            .map(CosmosRpcClient::new)
+            .map(|url| CosmosRpcClient::new(url, conf.get_rate_limit(), domain.name()))
             .collect::<Result<Vec<_>, _>>()?;
         let provider = CosmosFallbackProvider::new(
             FallbackProvider::builder().add_providers(providers).build(),
@@ -81,7 +81,7 @@ impl CosmosProvider {
     }
 
     /// Get a grpc client
-    pub fn grpc(&self) -> &WasmGrpcProvider {
+    pub fn grpc(&self) -> &CachingWasmProvider<WasmGrpcProvider> {
         &self.grpc_provider
     }
 
@@ -135,44 +135,46 @@ impl CosmosProvider {
                 (SignerPublicKey::from(pk), AccountAddressType::Bitcoin)
             }
             SignerPublicKey::Any(pk) => {
+                let is_eth_public_key = pk.type_url == INJECTIVE_PUBLIC_KEY_TYPE_URL
+                    || pk.type_url == ETHERMINT_PUBLIC_KEY_TYPE_URL;
                 if pk.type_url != PublicKey::ED25519_TYPE_URL
                     && pk.type_url != PublicKey::SECP256K1_TYPE_URL
-                    && pk.type_url != INJECTIVE_PUBLIC_KEY_TYPE_URL
+                    && !is_eth_public_key
                 {
                     let msg = format!(
-                        "can only normalize public keys with a known TYPE_URL: {}, {}, {}",
+                        "can only normalize public keys with a known TYPE_URL: {}, {}, {}, {}",
                         PublicKey::ED25519_TYPE_URL,
                         PublicKey::SECP256K1_TYPE_URL,
-                        INJECTIVE_PUBLIC_KEY_TYPE_URL
+                        INJECTIVE_PUBLIC_KEY_TYPE_URL,
+                        ETHERMINT_PUBLIC_KEY_TYPE_URL
                     );
                     warn!(pk.type_url, msg);
                     Err(HyperlaneCosmosError::PublicKeyError(msg.to_owned()))?
                 }
 
-                let (pub_key, account_address_type) =
-                    if pk.type_url == INJECTIVE_PUBLIC_KEY_TYPE_URL {
-                        let any = Any {
-                            type_url: PublicKey::SECP256K1_TYPE_URL.to_owned(),
-                            value: pk.value,
-                        };
+                let (pub_key, account_address_type) = if is_eth_public_key {
+                    let any = Any {
+                        type_url: PublicKey::SECP256K1_TYPE_URL.to_owned(),
+                        value: pk.value,
+                    };
 
-                        let proto: proto::cosmos::crypto::secp256k1::PubKey =
-                            any.to_msg().map_err(Into::<HyperlaneCosmosError>::into)?;
+                    let proto: proto::cosmos::crypto::secp256k1::PubKey =
+                        any.to_msg().map_err(Into::<HyperlaneCosmosError>::into)?;
 
-                        let decompressed = decompress_public_key(&proto.key)
-                            .map_err(|e| HyperlaneCosmosError::PublicKeyError(e.to_string()))?;
+                    let decompressed = decompress_public_key(&proto.key)
+                        .map_err(|e| HyperlaneCosmosError::PublicKeyError(e.to_string()))?;
 
-                        let tendermint = tendermint::PublicKey::from_raw_secp256k1(&decompressed)
-                            .ok_or_else(|| {
+                    let tendermint = tendermint::PublicKey::from_raw_secp256k1(&decompressed)
+                        .ok_or_else(|| {
                             HyperlaneCosmosError::PublicKeyError(
                                 "cannot create tendermint public key".to_owned(),
                             )
                         })?;
 
-                        (PublicKey::from(tendermint), AccountAddressType::Ethereum)
-                    } else {
-                        (PublicKey::try_from(pk)?, AccountAddressType::Bitcoin)
-                    };
+                    (PublicKey::from(tendermint), AccountAddressType::Ethereum)
+                } else {
+                    (PublicKey::try_from(pk)?, AccountAddressType::Bitcoin)
+                };
 
                 (SignerPublicKey::Single(pub_key), account_address_type)
             }
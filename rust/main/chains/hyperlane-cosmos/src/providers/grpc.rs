@@ -1,4 +1,8 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use cosmrs::{
@@ -7,14 +11,20 @@ use cosmrs::{
             auth::v1beta1::{
                 query_client::QueryClient as QueryAccountClient, BaseAccount, QueryAccountRequest,
             },
+            authz::v1beta1::{MsgExec, QueryGrantsRequest, QueryGrantsResponse},
             bank::v1beta1::{query_client::QueryClient as QueryBalanceClient, QueryBalanceRequest},
+            base::v1beta1::Coin as ProtoCoin,
             base::{
                 abci::v1beta1::TxResponse,
                 tendermint::v1beta1::{service_client::ServiceClient, GetLatestBlockRequest},
             },
+            feegrant::v1beta1::{
+                AllowedMsgAllowance, BasicAllowance, PeriodicAllowance, QueryAllowanceRequest,
+                QueryAllowanceResponse,
+            },
             tx::v1beta1::{
                 service_client::ServiceClient as TxServiceClient, BroadcastMode,
-                BroadcastTxRequest, SimulateRequest, TxRaw,
+                BroadcastTxRequest, SimulateRequest, SimulateResponse, TxRaw,
             },
         },
         cosmwasm::wasm::v1::{
@@ -23,26 +33,32 @@ use cosmrs::{
         },
         traits::Message,
     },
-    tx::{self, Fee, MessageExt, SignDoc, SignerInfo},
-    Any, Coin,
+    tx::{self, Fee, MessageExt, ModeInfo, SignDoc, SignMode, SignerInfo},
+    AccountId, Any, Coin,
 };
 use derive_new::new;
 use protobuf::Message as _;
+use reqwest_utils::HttpClientConf;
 use serde::Serialize;
+use sha256::digest;
 use tonic::{
-    transport::{Channel, Endpoint},
+    body::BoxBody,
+    codegen::{BoxFuture, Service},
+    transport::{Certificate, Channel, ClientTlsConfig, Endpoint},
     GrpcMethod, IntoRequest,
 };
-use tracing::{debug, instrument};
+use tracing::{debug, info, instrument};
 use url::Url;
 
 use hyperlane_core::{
-    rpc_clients::{BlockNumberGetter, FallbackProvider},
+    rpc_clients::{BlockNumberGetter, FallbackProvider, RateLimiter, RpcEndpointMetrics},
     ChainCommunicationError, ChainResult, ContractLocator, FixedPointNumber, HyperlaneDomain, U256,
 };
 
+use crate::libs::eip712;
+use crate::signers::{ETHERMINT_PUBLIC_KEY_TYPE_URL, INJECTIVE_PUBLIC_KEY_TYPE_URL};
 use crate::{rpc_clients::CosmosFallbackProvider, HyperlaneCosmosError};
-use crate::{signers::Signer, ConnectionConf};
+use crate::{signers::Signer, ConnectionConf, CosmosSignMode};
 use crate::{CosmosAddress, CosmosAmount};
 
 /// A multiplier applied to a simulated transaction's gas usage to
@@ -51,19 +67,99 @@ const GAS_ESTIMATE_MULTIPLIER: f64 = 1.25;
 /// The number of blocks in the future in which a transaction will
 /// be valid for.
 const TIMEOUT_BLOCKS: u64 = 1000;
+/// The type URL of authz `MsgExec`, which executes messages on behalf of the
+/// authz granter.
+const MSG_EXEC_TYPE_URL: &str = "/cosmos.authz.v1beta1.MsgExec";
+/// The gRPC path of the query for the authz grants of a granter to a grantee.
+const QUERY_AUTHZ_GRANTS_PATH: &str = "/cosmos.authz.v1beta1.Query/Grants";
+/// The gRPC path of the query for the feegrant allowance of a granter to a
+/// grantee.
+const QUERY_FEE_ALLOWANCE_PATH: &str = "/cosmos.feegrant.v1beta1.Query/Allowance";
+
+const BASIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.BasicAllowance";
+const PERIODIC_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.PeriodicAllowance";
+const ALLOWED_MSG_ALLOWANCE_TYPE_URL: &str = "/cosmos.feegrant.v1beta1.AllowedMsgAllowance";
+
+/// Applies the HTTP client config of the agent to the gRPC `endpoint` at
+/// `url`. tonic doesn't support proxies, so gRPC requests are sent directly
+/// even if a proxy is configured.
+fn configure_endpoint(endpoint: Endpoint, url: &Url) -> Result<Endpoint, HyperlaneCosmosError> {
+    let conf = HttpClientConf::current();
+    let mut endpoint = endpoint.timeout(conf.timeout_for(url));
+    if let Some(connect_timeout) = conf.connect_timeout {
+        endpoint = endpoint.connect_timeout(connect_timeout);
+    }
+    if url.scheme() == "https" && !conf.root_certificates.is_empty() {
+        let mut tls = ClientTlsConfig::new().with_enabled_roots();
+        for pem in conf.root_certificate_pems()? {
+            tls = tls.ca_certificate(Certificate::from_pem(pem));
+        }
+        endpoint = endpoint.tls_config(tls)?;
+    }
+    Ok(endpoint)
+}
+
+/// A transaction along with what's needed to sign it in any sign mode.
+struct UnsignedTx {
+    sign_doc: SignDoc,
+    fee: Fee,
+    msgs: Vec<cosmrs::Any>,
+    account_number: u64,
+    sequence: u64,
+}
 
 #[derive(Debug, Clone, new)]
 struct CosmosChannel {
     channel: Channel,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    metrics: RpcEndpointMetrics,
     /// The url that this channel is connected to.
     /// Not explicitly used, but useful for debugging.
     _url: Url,
 }
 
+impl CosmosChannel {
+    /// The channel, once the rate limit of the endpoint allows another request
+    async fn channel(&self) -> MeteredChannel {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        MeteredChannel {
+            channel: self.channel.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// A channel that records the metrics of every request sent through it,
+/// labeled by the gRPC method
+#[derive(Debug, Clone)]
+struct MeteredChannel {
+    channel: Channel,
+    metrics: RpcEndpointMetrics,
+}
+
+impl Service<http::Request<BoxBody>> for MeteredChannel {
+    type Response = <Channel as Service<http::Request<BoxBody>>>::Response;
+    type Error = <Channel as Service<http::Request<BoxBody>>>::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.channel.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<BoxBody>) -> Self::Future {
+        let method = request.uri().path().to_owned();
+        let metrics = self.metrics.clone();
+        let response = self.channel.call(request);
+        Box::pin(async move { metrics.track(&method, response).await })
+    }
+}
+
 #[async_trait]
 impl BlockNumberGetter for CosmosChannel {
     async fn get_block_number(&self) -> Result<u64, ChainCommunicationError> {
-        let mut client = ServiceClient::new(self.channel.clone());
+        let mut client = ServiceClient::new(self.channel().await);
         let request = tonic::Request::new(GetLatestBlockRequest {});
 
         let response = client
@@ -132,6 +228,9 @@ pub struct WasmGrpcProvider {
     /// See `<https://docs.rs/tonic/latest/tonic/transport/struct.Channel.html#multiplexing-requests>`
     provider: CosmosFallbackProvider<CosmosChannel>,
     gas_price: CosmosAmount,
+    /// The expirations of the authz grants found for each message type, in
+    /// seconds since the unix epoch, or `None` if the grant never expires
+    authz_grants: Arc<Mutex<HashMap<String, Option<i64>>>>,
 }
 
 impl WasmGrpcProvider {
@@ -148,9 +247,17 @@ impl WasmGrpcProvider {
             .get_grpc_urls()
             .into_iter()
             .map(|url| {
-                Endpoint::new(url.to_string())
-                    .map(|e| CosmosChannel::new(e.connect_lazy(), url))
-                    .map_err(Into::<HyperlaneCosmosError>::into)
+                let rate_limiter = conf
+                    .get_rate_limit()
+                    .map(|rate_limit| RateLimiter::for_endpoint(url.as_str(), rate_limit));
+                let metrics = RpcEndpointMetrics::new(url.as_str(), domain.name());
+                let endpoint = configure_endpoint(Endpoint::new(url.to_string())?, &url)?;
+                Ok::<_, HyperlaneCosmosError>(CosmosChannel::new(
+                    endpoint.connect_lazy(),
+                    rate_limiter,
+                    metrics,
+                    url,
+                ))
             })
             .collect();
         let mut builder = FallbackProvider::builder();
@@ -171,11 +278,12 @@ impl WasmGrpcProvider {
             signer,
             provider,
             gas_price,
+            authz_grants: Default::default(),
         })
     }
 
     /// Gets a signer, or returns an error if one is not available.
-    fn get_signer(&self) -> ChainResult<&Signer> {
+    pub(crate) fn get_signer(&self) -> ChainResult<&Signer> {
         self.signer
             .as_ref()
             .ok_or(ChainCommunicationError::SignerUnavailable)
@@ -186,27 +294,51 @@ impl WasmGrpcProvider {
         self.gas_price.amount.clone()
     }
 
-    /// Generates an unsigned SignDoc for a transaction and the Coin amount
-    /// required to pay for tx fees.
-    async fn generate_unsigned_sign_doc_and_fee(
+    /// The type URL of `ethsecp256k1` public keys on this chain.
+    fn eth_public_key_type_url(&self) -> &'static str {
+        if self.domain.is_injective() {
+            INJECTIVE_PUBLIC_KEY_TYPE_URL
+        } else {
+            ETHERMINT_PUBLIC_KEY_TYPE_URL
+        }
+    }
+
+    /// Generates an unsigned transaction including `msgs`, and the fee
+    /// required to pay for it.
+    async fn generate_unsigned_tx(
         &self,
         msgs: Vec<cosmrs::Any>,
         gas_limit: u64,
-    ) -> ChainResult<(SignDoc, Coin)> {
+    ) -> ChainResult<UnsignedTx> {
         // As this function is only used for estimating gas or sending transactions,
         // we can reasonably expect to have a signer.
         let signer = self.get_signer()?;
         let account_info = self.account_query(signer.address.clone()).await?;
-        let current_height = self.latest_block_height().await?;
-        let timeout_height = current_height + TIMEOUT_BLOCKS;
+        let sign_mode = self.conf.get_sign_mode();
+        let timeout_height = match sign_mode {
+            CosmosSignMode::Direct => self.latest_block_height().await? + TIMEOUT_BLOCKS,
+            // The timeout height isn't part of the EIP-712 typed data, so it
+            // wouldn't be signed
+            CosmosSignMode::Eip712 => 0,
+        };
 
         let tx_body = tx::Body::new(
-            msgs,
+            msgs.clone(),
             String::default(),
             TryInto::<u32>::try_into(timeout_height)
                 .map_err(ChainCommunicationError::from_other)?,
         );
-        let signer_info = SignerInfo::single_direct(Some(signer.public_key), account_info.sequence);
+        let signer_info = SignerInfo {
+            public_key: Some(
+                signer
+                    .signer_public_key(self.conf.get_key_type(), self.eth_public_key_type_url())?,
+            ),
+            mode_info: ModeInfo::single(match sign_mode {
+                CosmosSignMode::Direct => SignMode::Direct,
+                CosmosSignMode::Eip712 => SignMode::LegacyAminoJson,
+            }),
+            sequence: account_info.sequence,
+        };
 
         let amount: u128 = (FixedPointNumber::from(gas_limit) * self.gas_price())
             .ceil_to_integer()
@@ -217,8 +349,16 @@ impl WasmGrpcProvider {
             self.conf.get_canonical_asset().as_str(),
         )
         .map_err(Into::<HyperlaneCosmosError>::into)?;
-        let auth_info =
-            signer_info.auth_info(Fee::from_amount_and_gas(fee_coin.clone(), gas_limit));
+        let mut fee = Fee::from_amount_and_gas(fee_coin, gas_limit);
+        // The fees are deducted from the granter's allowance for the signer
+        // rather than from the signer's balance
+        fee.granter = self
+            .conf
+            .get_fee_granter()
+            .map(|granter| granter.parse::<AccountId>())
+            .transpose()
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let auth_info = signer_info.auth_info(fee.clone());
 
         let chain_id = self
             .conf
@@ -226,11 +366,43 @@ impl WasmGrpcProvider {
             .parse()
             .map_err(Into::<HyperlaneCosmosError>::into)?;
 
-        Ok((
-            SignDoc::new(&tx_body, &auth_info, &chain_id, account_info.account_number)
+        Ok(UnsignedTx {
+            sign_doc: SignDoc::new(&tx_body, &auth_info, &chain_id, account_info.account_number)
                 .map_err(Into::<HyperlaneCosmosError>::into)?,
-            fee_coin,
-        ))
+            fee,
+            msgs,
+            account_number: account_info.account_number,
+            sequence: account_info.sequence,
+        })
+    }
+
+    /// Signs `unsigned_tx` with the configured key type and sign mode.
+    async fn sign_tx(&self, unsigned_tx: &UnsignedTx) -> ChainResult<Vec<u8>> {
+        let signer = self.get_signer()?;
+        match self.conf.get_sign_mode() {
+            CosmosSignMode::Direct => {
+                let sign_bytes = unsigned_tx
+                    .sign_doc
+                    .clone()
+                    .into_bytes()
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                signer.sign(&sign_bytes, self.conf.get_key_type()).await
+            }
+            CosmosSignMode::Eip712 => {
+                let chain_id = self.conf.get_chain_id();
+                let sign_doc = eip712::amino_json_sign_doc(
+                    &chain_id,
+                    unsigned_tx.account_number,
+                    unsigned_tx.sequence,
+                    &unsigned_tx.fee,
+                    "",
+                    &unsigned_tx.msgs,
+                )?;
+                let digest =
+                    eip712::typed_data_hash(eip712::eip155_chain_id(&chain_id)?, &sign_doc)?;
+                signer.sign_prehash(&digest).await
+            }
+        }
     }
 
     /// Generates a raw signed transaction including `msgs`, estimating gas if a limit is not provided,
@@ -246,26 +418,31 @@ impl WasmGrpcProvider {
             self.estimate_gas(msgs.clone()).await?
         };
 
-        let (sign_doc, fee) = self
-            .generate_unsigned_sign_doc_and_fee(msgs, gas_limit)
-            .await?;
-
-        let signer = self.get_signer()?;
-        let tx_signed = sign_doc
-            .sign(&signer.signing_key()?)
-            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let unsigned_tx = self.generate_unsigned_tx(msgs, gas_limit).await?;
+        let signature = self.sign_tx(&unsigned_tx).await?;
+        let raw_tx = TxRaw {
+            body_bytes: unsigned_tx.sign_doc.body_bytes,
+            auth_info_bytes: unsigned_tx.sign_doc.auth_info_bytes,
+            signatures: vec![signature],
+        };
+        let fee = unsigned_tx
+            .fee
+            .amount
+            .into_iter()
+            .next()
+            .ok_or_else(|| ChainCommunicationError::from_other_str("fee amount not present"))?;
         Ok((
-            tx_signed
+            raw_tx
                 .to_bytes()
-                .map_err(Into::<HyperlaneCosmosError>::into)?,
+                .map_err(ChainCommunicationError::from_other)?,
             fee,
         ))
     }
 
     /// Estimates gas for a transaction containing `msgs`.
-    async fn estimate_gas(&self, msgs: Vec<cosmrs::Any>) -> ChainResult<u64> {
+    pub(crate) async fn estimate_gas(&self, msgs: Vec<cosmrs::Any>) -> ChainResult<u64> {
         // Get a sign doc with 0 gas, because we plan to simulate
-        let (sign_doc, _) = self.generate_unsigned_sign_doc_and_fee(msgs, 0).await?;
+        let sign_doc = self.generate_unsigned_tx(msgs.clone(), 0).await?.sign_doc;
 
         let raw_tx = TxRaw {
             body_bytes: sign_doc.body_bytes,
@@ -278,37 +455,75 @@ impl WasmGrpcProvider {
         let tx_bytes = raw_tx
             .to_bytes()
             .map_err(ChainCommunicationError::from_other)?;
-        let gas_used = self
-            .provider
+        let simulated = self.simulate_tx(tx_bytes).await;
+        self.evict_authz_grants_on_failure(&msgs, simulated.is_err());
+        let gas_used = simulated?
+            .gas_info
+            .ok_or_else(|| ChainCommunicationError::from_other_str("gas info not present"))?
+            .gas_used;
+
+        let gas_estimate = (gas_used as f64 * GAS_ESTIMATE_MULTIPLIER) as u64;
+
+        Ok(gas_estimate)
+    }
+
+    /// Simulates the execution of the encoded transaction `tx_bytes` without
+    /// broadcasting it.
+    async fn simulate_tx(&self, tx_bytes: Vec<u8>) -> ChainResult<SimulateResponse> {
+        self.provider
             .call(move |provider| {
                 let tx_bytes_clone = tx_bytes.clone();
                 let future = async move {
-                    let mut client = TxServiceClient::new(provider.channel.clone());
+                    let mut client = TxServiceClient::new(provider.channel().await);
                     #[allow(deprecated)]
                     let sim_req = tonic::Request::new(SimulateRequest {
                         tx: None,
                         tx_bytes: tx_bytes_clone,
                     });
-                    let gas_used = client
+                    let response = client
                         .simulate(sim_req)
                         .await
-                        .map_err(ChainCommunicationError::from_other)?
-                        .into_inner()
-                        .gas_info
-                        .ok_or_else(|| {
-                            ChainCommunicationError::from_other_str("gas info not present")
-                        })?
-                        .gas_used;
-
-                    Ok(gas_used)
+                        .map_err(Into::<HyperlaneCosmosError>::into)?
+                        .into_inner();
+                    Ok(response)
                 };
                 Box::pin(future)
             })
-            .await?;
-
-        let gas_estimate = (gas_used as f64 * GAS_ESTIMATE_MULTIPLIER) as u64;
+            .await
+    }
 
-        Ok(gas_estimate)
+    /// Simulates a signed transaction in place of broadcasting it. A failed
+    /// simulation is returned as an error, a successful one as the response
+    /// of the simulation, which has no block height since nothing was
+    /// included in a block.
+    async fn dry_run_tx(&self, tx_bytes: Vec<u8>, fee: &Coin) -> ChainResult<TxResponse> {
+        let txhash = digest(tx_bytes.as_slice()).to_uppercase();
+        let response = self.simulate_tx(tx_bytes).await?;
+        let gas_info = response
+            .gas_info
+            .ok_or_else(|| ChainCommunicationError::from_other_str("gas info not present"))?;
+        let result = response.result.ok_or_else(|| {
+            ChainCommunicationError::from_other_str("simulation result not present")
+        })?;
+        info!(
+            domain=?self.domain,
+            %txhash,
+            gas_used = gas_info.gas_used,
+            ?fee,
+            events=?result.events,
+            "Dry run enabled, simulated wasm transaction instead of sending it"
+        );
+        #[allow(deprecated)]
+        let data = hex::encode_upper(&result.data);
+        Ok(TxResponse {
+            txhash,
+            data,
+            raw_log: result.log,
+            gas_wanted: gas_info.gas_wanted as i64,
+            gas_used: gas_info.gas_used as i64,
+            events: result.events,
+            ..Default::default()
+        })
     }
 
     /// Fetches balance for a given `address` and `denom`
@@ -319,7 +534,7 @@ impl WasmGrpcProvider {
                 let address = address.clone();
                 let denom = denom.clone();
                 let future = async move {
-                    let mut client = QueryBalanceClient::new(provider.channel.clone());
+                    let mut client = QueryBalanceClient::new(provider.channel().await);
                     let balance_request =
                         tonic::Request::new(QueryBalanceRequest { address, denom });
                     let response = client
@@ -353,7 +568,7 @@ impl WasmGrpcProvider {
             .call(move |provider| {
                 let address = account.clone();
                 let future = async move {
-                    let mut client = QueryAccountClient::new(provider.channel.clone());
+                    let mut client = QueryAccountClient::new(provider.channel().await);
                     let request = tonic::Request::new(QueryAccountRequest { address });
                     let response = client
                         .account(request)
@@ -392,7 +607,7 @@ impl WasmGrpcProvider {
 
                     // Borrowed from the logic of `QueryAccountClient` in `cosmrs`, but using injective types.
 
-                    let mut grpc_client = tonic::client::Grpc::new(provider.channel.clone());
+                    let mut grpc_client = tonic::client::Grpc::new(provider.channel().await);
                     grpc_client
                         .ready()
                         .await
@@ -444,6 +659,310 @@ impl WasmGrpcProvider {
         })
     }
 
+    /// Signs and broadcasts a transaction including `msgs`, estimating gas if
+    /// a limit is not provided. In dry run mode the transaction is only
+    /// simulated. The `gas_wanted` of the response is the gas limit the fee
+    /// was paid for, which sync broadcasts don't report.
+    pub(crate) async fn send_msgs(
+        &self,
+        msgs: Vec<cosmrs::Any>,
+        gas_limit: Option<u64>,
+    ) -> ChainResult<TxResponse> {
+        let signer = self.get_signer()?;
+        let gas_limit = match gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => self.estimate_gas(msgs.clone()).await?,
+        };
+        let (tx_bytes, fee) = self
+            .generate_raw_signed_tx_and_fee(msgs.clone(), Some(gas_limit))
+            .await?;
+
+        if self.conf.is_dry_run() {
+            return self.dry_run_tx(tx_bytes, &fee).await;
+        }
+
+        // Check if the signer, or its allowance from the fee granter if there
+        // is one, covers the fee so we can get a more informative error.
+        let fee_amount: U256 = fee.amount.into();
+        let available = match self.conf.get_fee_granter() {
+            Some(granter) => {
+                self.fee_allowance_spend_limit(&granter, &signer.address, fee.denom.as_ref())
+                    .await?
+            }
+            None => Some(
+                self.get_balance(signer.address.clone(), fee.denom.to_string())
+                    .await?,
+            ),
+        };
+        if let Some(available) = available.filter(|available| *available < fee_amount) {
+            return Err(ChainCommunicationError::InsufficientFunds {
+                required: fee_amount,
+                available,
+            });
+        }
+
+        let response = self
+            .provider
+            .call(move |provider| {
+                let tx_bytes = tx_bytes.clone();
+                let future = async move {
+                    let mut client = TxServiceClient::new(provider.channel().await);
+                    // We often use U256s to represent gas limits, but Cosmos expects u64s. Try to convert,
+                    // and if it fails, just fallback to None which will result in gas estimation.
+                    let tx_req = BroadcastTxRequest {
+                        tx_bytes,
+                        mode: BroadcastMode::Sync as i32,
+                    };
+                    client
+                        .broadcast_tx(tx_req)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?
+                        .into_inner()
+                        .tx_response
+                        .ok_or_else(|| ChainCommunicationError::from_other_str("Empty tx_response"))
+                };
+                Box::pin(future)
+            })
+            .await
+            .map(|mut response| {
+                if response.gas_wanted == 0 {
+                    response.gas_wanted = i64::try_from(gas_limit).unwrap_or(i64::MAX);
+                }
+                response
+            });
+        self.evict_authz_grants_on_failure(
+            &msgs,
+            response
+                .as_ref()
+                .map_or(true, |response| response.code != 0),
+        );
+        response
+    }
+
+    /// Performs a unary query against the gRPC query service of a Cosmos SDK
+    /// module, e.g. `/hyperlane.core.v1.Query/Mailbox`, optionally at a
+    /// specific block height.
+    pub(crate) async fn module_query<Req, Res>(
+        &self,
+        path: &'static str,
+        request: Req,
+        block_height: Option<u64>,
+    ) -> ChainResult<Res>
+    where
+        Req: prost::Message + Clone + 'static,
+        Res: prost::Message + Default + 'static,
+    {
+        let (service, method) = path
+            .trim_start_matches('/')
+            .split_once('/')
+            .ok_or_else(|| ChainCommunicationError::from_other_str("invalid gRPC query path"))?;
+        self.provider
+            .call(move |provider| {
+                let request = request.clone();
+                let future = async move {
+                    let mut grpc_client = tonic::client::Grpc::new(provider.channel().await);
+                    grpc_client
+                        .ready()
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?;
+
+                    let codec = tonic::codec::ProstCodec::default();
+                    let mut req = tonic::Request::new(request);
+                    req.extensions_mut()
+                        .insert(GrpcMethod::new(service, method));
+                    if let Some(block_height) = block_height {
+                        req.metadata_mut()
+                            .insert("x-cosmos-block-height", block_height.into());
+                    }
+
+                    let response: tonic::Response<Res> = grpc_client
+                        .unary(req, http::uri::PathAndQuery::from_static(path), codec)
+                        .await
+                        .map_err(Into::<HyperlaneCosmosError>::into)?;
+                    Ok(response.into_inner())
+                };
+                Box::pin(future)
+            })
+            .await
+    }
+
+    /// The account that messages delivering Hyperlane messages are sent
+    /// from: the authz granter the signer executes them on behalf of if one
+    /// is configured, and the signer otherwise.
+    pub(crate) fn delivering_account(&self) -> ChainResult<String> {
+        match self.conf.get_authz_granter() {
+            Some(granter) => Ok(granter),
+            None => Ok(self.get_signer()?.address.clone()),
+        }
+    }
+
+    /// Wraps `msgs` sent from [`Self::delivering_account`] in an authz
+    /// `MsgExec` executed by the signer, if an authz granter is configured,
+    /// after checking that the granter authorized the signer to execute them.
+    pub(crate) async fn delegate_msgs(&self, msgs: Vec<Any>) -> ChainResult<Vec<Any>> {
+        let Some(granter) = self.conf.get_authz_granter() else {
+            return Ok(msgs);
+        };
+        let grantee = self.get_signer()?.address.clone();
+        for msg in &msgs {
+            self.check_authz_grant(&granter, &grantee, &msg.type_url)
+                .await?;
+        }
+        let exec = MsgExec { grantee, msgs };
+        Ok(vec![
+            Any::from_msg(&exec).map_err(ChainCommunicationError::from_other)?
+        ])
+    }
+
+    /// Checks that `granter` granted `grantee` the execution of messages of
+    /// type `msg_type_url` and that the grant didn't expire. Found grants are
+    /// remembered until they expire, so that they aren't queried for every
+    /// transaction.
+    async fn check_authz_grant(
+        &self,
+        granter: &str,
+        grantee: &str,
+        msg_type_url: &str,
+    ) -> ChainResult<()> {
+        let cached = self.authz_grants.lock().unwrap().get(msg_type_url).copied();
+        let expiration = match cached {
+            Some(expiration) => expiration,
+            None => {
+                let missing_grant = |reason: String| HyperlaneCosmosError::MissingAuthzGrant {
+                    granter: granter.to_owned(),
+                    grantee: grantee.to_owned(),
+                    msg_type_url: msg_type_url.to_owned(),
+                    reason,
+                };
+                // The query fails if there is no grant
+                let grants = self
+                    .module_query::<_, QueryGrantsResponse>(
+                        QUERY_AUTHZ_GRANTS_PATH,
+                        QueryGrantsRequest {
+                            granter: granter.to_owned(),
+                            grantee: grantee.to_owned(),
+                            msg_type_url: msg_type_url.to_owned(),
+                            pagination: None,
+                        },
+                        None,
+                    )
+                    .await
+                    .map_err(|err| missing_grant(err.to_string()))?
+                    .grants;
+                if grants.is_empty() {
+                    return Err(missing_grant("the granter has no grants".to_owned()).into());
+                }
+                // The grant that expires last applies
+                let expiration = if grants.iter().any(|grant| grant.expiration.is_none()) {
+                    None
+                } else {
+                    grants
+                        .iter()
+                        .filter_map(|grant| grant.expiration.as_ref())
+                        .map(|expiration| expiration.seconds)
+                        .max()
+                };
+                self.authz_grants
+                    .lock()
+                    .unwrap()
+                    .insert(msg_type_url.to_owned(), expiration);
+                expiration
+            }
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        match expiration {
+            Some(expiration) if expiration <= now => {
+                // Look the grant up again next time, in case it was renewed
+                self.authz_grants.lock().unwrap().remove(msg_type_url);
+                Err(HyperlaneCosmosError::ExpiredAuthzGrant {
+                    granter: granter.to_owned(),
+                    grantee: grantee.to_owned(),
+                    msg_type_url: msg_type_url.to_owned(),
+                    expiration,
+                }
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Forgets the authz grants found so far if a transaction executing `msgs`
+    /// on behalf of the authz granter failed, as the grants may have been
+    /// revoked since they were found. They're looked up again before the
+    /// next transaction.
+    fn evict_authz_grants_on_failure(&self, msgs: &[Any], failed: bool) {
+        if failed && msgs.iter().any(|msg| msg.type_url == MSG_EXEC_TYPE_URL) {
+            self.authz_grants.lock().unwrap().clear();
+        }
+    }
+
+    /// How much of `denom` the feegrant allowance of `granter` lets `grantee`
+    /// spend on fees, or `None` if the allowance doesn't limit it. Fails if
+    /// there is no allowance or it expired.
+    async fn fee_allowance_spend_limit(
+        &self,
+        granter: &str,
+        grantee: &str,
+        denom: &str,
+    ) -> ChainResult<Option<U256>> {
+        let missing_allowance = |reason: String| HyperlaneCosmosError::MissingFeeAllowance {
+            granter: granter.to_owned(),
+            grantee: grantee.to_owned(),
+            reason,
+        };
+        // The query fails if there is no allowance
+        let allowance = self
+            .module_query::<_, QueryAllowanceResponse>(
+                QUERY_FEE_ALLOWANCE_PATH,
+                QueryAllowanceRequest {
+                    granter: granter.to_owned(),
+                    grantee: grantee.to_owned(),
+                },
+                None,
+            )
+            .await
+            .map_err(|err| missing_allowance(err.to_string()))?
+            .allowance
+            .and_then(|grant| grant.allowance)
+            .ok_or_else(|| missing_allowance("the granter has no allowance".to_owned()))?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        let allowance = FeeAllowance::decode(&allowance, denom, now)?;
+        match allowance.expiration {
+            Some(expiration) if expiration <= now => {
+                Err(HyperlaneCosmosError::ExpiredFeeAllowance {
+                    granter: granter.to_owned(),
+                    grantee: grantee.to_owned(),
+                    expiration,
+                }
+                .into())
+            }
+            _ => Ok(allowance.spend_limit),
+        }
+    }
+
+    /// The message executing `payload` on the contract, sent from `sender`.
+    pub(crate) fn wasm_execute_msg<T: Serialize>(
+        &self,
+        payload: &T,
+        sender: String,
+    ) -> ChainResult<Any> {
+        let msg = MsgExecuteContract {
+            sender,
+            contract: self.get_contract_address().address(),
+            msg: serde_json::to_string(payload)?.as_bytes().to_vec(),
+            funds: vec![],
+        };
+        Any::from_msg(&msg).map_err(ChainCommunicationError::from_other)
+    }
+
     fn get_contract_address(&self) -> &CosmosAddress {
         &self.contract_address
     }
@@ -456,7 +975,7 @@ impl WasmProvider for WasmGrpcProvider {
             .provider
             .call(move |provider| {
                 let future = async move {
-                    let mut client = ServiceClient::new(provider.channel.clone());
+                    let mut client = ServiceClient::new(provider.channel().await);
                     let request = tonic::Request::new(GetLatestBlockRequest {});
                     let response = client
                         .get_latest_block(request)
@@ -491,7 +1010,7 @@ impl WasmProvider for WasmGrpcProvider {
                 let to = contract_address.address().clone();
                 let query_data = query_data.clone();
                 let future = async move {
-                    let mut client = WasmQueryClient::new(provider.channel.clone());
+                    let mut client = WasmQueryClient::new(provider.channel().await);
 
                     let mut request = tonic::Request::new(QuerySmartContractStateRequest {
                         address: to,
@@ -523,7 +1042,7 @@ impl WasmProvider for WasmGrpcProvider {
             .call(move |provider| {
                 let to = contract_address.address().clone();
                 let future = async move {
-                    let mut client = WasmQueryClient::new(provider.channel.clone());
+                    let mut client = WasmQueryClient::new(provider.channel().await);
 
                     let request = tonic::Request::new(QueryContractInfoRequest { address: to });
 
@@ -552,14 +1071,7 @@ impl WasmProvider for WasmGrpcProvider {
         T: Serialize + Send + Sync + Clone + Debug,
     {
         let signer = self.get_signer()?;
-        let contract_address = self.get_contract_address();
-        let msg = MsgExecuteContract {
-            sender: signer.address.clone(),
-            contract: contract_address.address(),
-            msg: serde_json::to_string(&payload)?.as_bytes().to_vec(),
-            funds: vec![],
-        };
-        let msgs = vec![Any::from_msg(&msg).map_err(ChainCommunicationError::from_other)?];
+        let msgs = vec![self.wasm_execute_msg(&payload, signer.address.clone())?];
         let gas_limit: Option<u64> = gas_limit.and_then(|limit| match limit.try_into() {
             Ok(limit) => Some(limit),
             Err(err) => {
@@ -570,44 +1082,7 @@ impl WasmProvider for WasmGrpcProvider {
                 None
             }
         });
-        let (tx_bytes, fee) = self.generate_raw_signed_tx_and_fee(msgs, gas_limit).await?;
-
-        // Check if the signer has enough funds to pay for the fee so we can get
-        // a more informative error.
-        let signer_balance = self
-            .get_balance(signer.address.clone(), fee.denom.to_string())
-            .await?;
-        let fee_amount: U256 = fee.amount.into();
-        if signer_balance < fee_amount {
-            return Err(ChainCommunicationError::InsufficientFunds {
-                required: fee_amount,
-                available: signer_balance,
-            });
-        }
-
-        let tx_res = self
-            .provider
-            .call(move |provider| {
-                let tx_bytes = tx_bytes.clone();
-                let future = async move {
-                    let mut client = TxServiceClient::new(provider.channel.clone());
-                    // We often use U256s to represent gas limits, but Cosmos expects u64s. Try to convert,
-                    // and if it fails, just fallback to None which will result in gas estimation.
-                    let tx_req = BroadcastTxRequest {
-                        tx_bytes,
-                        mode: BroadcastMode::Sync as i32,
-                    };
-                    client
-                        .broadcast_tx(tx_req)
-                        .await
-                        .map_err(Into::<HyperlaneCosmosError>::into)?
-                        .into_inner()
-                        .tx_response
-                        .ok_or_else(|| ChainCommunicationError::from_other_str("Empty tx_response"))
-                };
-                Box::pin(future)
-            })
-            .await?;
+        let tx_res = self.send_msgs(msgs, gas_limit).await?;
         debug!(tx_result=?tx_res, domain=?self.domain, ?payload, "Wasm transaction sent");
         Ok(tx_res)
     }
@@ -619,19 +1094,9 @@ impl WasmProvider for WasmGrpcProvider {
         // Estimating gas requires a signer, which we can reasonably expect to have
         // since we need one to send a tx with the estimated gas anyways.
         let signer = self.get_signer()?;
-        let contract_address = self.get_contract_address();
-        let msg = MsgExecuteContract {
-            sender: signer.address.clone(),
-            contract: contract_address.address(),
-            msg: serde_json::to_string(&payload)?.as_bytes().to_vec(),
-            funds: vec![],
-        };
+        let msg = self.wasm_execute_msg(&payload, signer.address.clone())?;
 
-        let response = self
-            .estimate_gas(vec![
-                Any::from_msg(&msg).map_err(ChainCommunicationError::from_other)?
-            ])
-            .await?;
+        let response = self.estimate_gas(vec![msg]).await?;
 
         Ok(response)
     }
@@ -644,5 +1109,89 @@ impl BlockNumberGetter for WasmGrpcProvider {
     }
 }
 
+/// What a feegrant allowance lets the grantee spend on fees in a denom
+#[derive(Debug, Clone, PartialEq)]
+struct FeeAllowance {
+    /// How much can be spent, or `None` if it's unlimited
+    spend_limit: Option<U256>,
+    /// When the allowance expires, in seconds since the unix epoch
+    expiration: Option<i64>,
+}
+
+impl FeeAllowance {
+    /// Decodes a basic, periodic or allowed message allowance at unix
+    /// timestamp `now`. Allowances of other types aren't limited.
+    fn decode(allowance: &Any, denom: &str, now: i64) -> ChainResult<Self> {
+        match allowance.type_url.as_str() {
+            BASIC_ALLOWANCE_TYPE_URL => {
+                let basic = BasicAllowance::decode(allowance.value.as_slice())
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                Self::from_basic(&basic, denom)
+            }
+            PERIODIC_ALLOWANCE_TYPE_URL => {
+                let periodic = PeriodicAllowance::decode(allowance.value.as_slice())
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                let mut fee_allowance =
+                    Self::from_basic(&periodic.basic.unwrap_or_default(), denom)?;
+                // The period's limit is restored once the period ends
+                let period_ended = periodic
+                    .period_reset
+                    .is_some_and(|reset| reset.seconds <= now);
+                let period_limit = if period_ended {
+                    &periodic.period_spend_limit
+                } else {
+                    &periodic.period_can_spend
+                };
+                if let Some(period_limit) = coins_amount(period_limit, denom)? {
+                    fee_allowance.spend_limit = Some(
+                        fee_allowance
+                            .spend_limit
+                            .map_or(period_limit, |limit| limit.min(period_limit)),
+                    );
+                }
+                Ok(fee_allowance)
+            }
+            ALLOWED_MSG_ALLOWANCE_TYPE_URL => {
+                let allowed = AllowedMsgAllowance::decode(allowance.value.as_slice())
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                match allowed.allowance {
+                    Some(allowance) => Self::decode(&allowance, denom, now),
+                    None => Ok(Self {
+                        spend_limit: None,
+                        expiration: None,
+                    }),
+                }
+            }
+            _ => Ok(Self {
+                spend_limit: None,
+                expiration: None,
+            }),
+        }
+    }
+
+    fn from_basic(basic: &BasicAllowance, denom: &str) -> ChainResult<Self> {
+        Ok(Self {
+            spend_limit: coins_amount(&basic.spend_limit, denom)?,
+            expiration: basic
+                .expiration
+                .as_ref()
+                .map(|expiration| expiration.seconds),
+        })
+    }
+}
+
+/// The amount of `denom` in a spend limit, or `None` if the limit is empty,
+/// i.e. unlimited
+fn coins_amount(coins: &[ProtoCoin], denom: &str) -> ChainResult<Option<U256>> {
+    if coins.is_empty() {
+        return Ok(None);
+    }
+    let amount = match coins.iter().find(|coin| coin.denom == denom) {
+        Some(coin) => U256::from_dec_str(&coin.amount)?,
+        None => U256::zero(),
+    };
+    Ok(Some(amount))
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,11 +1,28 @@
 use std::str::FromStr;
 
+use cosmrs::{
+    proto::{
+        cosmos::{
+            authz::v1beta1::MsgExec,
+            base::v1beta1::Coin as ProtoCoin,
+            feegrant::v1beta1::{AllowedMsgAllowance, BasicAllowance, PeriodicAllowance},
+        },
+        cosmwasm::wasm::v1::MsgExecuteContract,
+        traits::Message,
+    },
+    Any,
+};
 use url::Url;
 
 use hyperlane_core::config::OperationBatchConfig;
-use hyperlane_core::{ContractLocator, HyperlaneDomain, KnownHyperlaneDomain, NativeToken};
+use hyperlane_core::{ContractLocator, HyperlaneDomain, KnownHyperlaneDomain, NativeToken, U256};
 
+use super::{
+    FeeAllowance, ALLOWED_MSG_ALLOWANCE_TYPE_URL, BASIC_ALLOWANCE_TYPE_URL, MSG_EXEC_TYPE_URL,
+    PERIODIC_ALLOWANCE_TYPE_URL,
+};
 use crate::grpc::{WasmGrpcProvider, WasmProvider};
+use crate::rpc::DEFAULT_TRUSTING_PERIOD;
 use crate::{ConnectionConf, CosmosAddress, CosmosAmount, RawCosmosAmount};
 
 #[ignore]
@@ -45,6 +62,137 @@ async fn test_wasm_contract_info_no_contract() {
     assert!(result.is_err());
 }
 
+fn coins(amounts: &[(&str, u64)]) -> Vec<ProtoCoin> {
+    amounts
+        .iter()
+        .map(|(denom, amount)| ProtoCoin {
+            denom: (*denom).to_owned(),
+            amount: amount.to_string(),
+        })
+        .collect()
+}
+
+fn any(type_url: &str, msg: &impl Message) -> Any {
+    Any {
+        type_url: type_url.to_owned(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+fn basic_allowance(spend_limit: Vec<ProtoCoin>, expiration: Option<i64>) -> BasicAllowance {
+    let mut basic = BasicAllowance {
+        spend_limit,
+        expiration: expiration.map(|_| Default::default()),
+    };
+    if let (Some(timestamp), Some(seconds)) = (basic.expiration.as_mut(), expiration) {
+        timestamp.seconds = seconds;
+    }
+    basic
+}
+
+#[test]
+fn test_basic_fee_allowance() {
+    let basic = basic_allowance(coins(&[("untrn", 100), ("uatom", 5)]), Some(1_000));
+    let allowance = FeeAllowance::decode(&any(BASIC_ALLOWANCE_TYPE_URL, &basic), "untrn", 0);
+    assert_eq!(
+        allowance.unwrap(),
+        FeeAllowance {
+            spend_limit: Some(U256::from(100)),
+            expiration: Some(1_000),
+        }
+    );
+
+    // Denoms missing from a spend limit can't be spent
+    let allowance = FeeAllowance::decode(&any(BASIC_ALLOWANCE_TYPE_URL, &basic), "uosmo", 0);
+    assert_eq!(allowance.unwrap().spend_limit, Some(U256::zero()));
+
+    // An empty spend limit is unlimited
+    let unlimited = BasicAllowance::default();
+    let allowance = FeeAllowance::decode(&any(BASIC_ALLOWANCE_TYPE_URL, &unlimited), "untrn", 0);
+    assert_eq!(
+        allowance.unwrap(),
+        FeeAllowance {
+            spend_limit: None,
+            expiration: None,
+        }
+    );
+}
+
+#[test]
+fn test_periodic_fee_allowance() {
+    let mut periodic = PeriodicAllowance {
+        basic: Some(basic_allowance(coins(&[("untrn", 100)]), None)),
+        period: None,
+        period_spend_limit: coins(&[("untrn", 30)]),
+        period_can_spend: coins(&[("untrn", 10)]),
+        period_reset: Some(Default::default()),
+    };
+    if let Some(period_reset) = periodic.period_reset.as_mut() {
+        period_reset.seconds = 1_000;
+    }
+    let periodic = any(PERIODIC_ALLOWANCE_TYPE_URL, &periodic);
+
+    // What's left of the current period can be spent
+    let allowance = FeeAllowance::decode(&periodic, "untrn", 500).unwrap();
+    assert_eq!(allowance.spend_limit, Some(U256::from(10)));
+
+    // The period's limit is restored once the period ends
+    let allowance = FeeAllowance::decode(&periodic, "untrn", 1_000).unwrap();
+    assert_eq!(allowance.spend_limit, Some(U256::from(30)));
+}
+
+#[test]
+fn test_allowed_msg_fee_allowance() {
+    let allowed = AllowedMsgAllowance {
+        allowance: Some(any(
+            BASIC_ALLOWANCE_TYPE_URL,
+            &basic_allowance(coins(&[("untrn", 100)]), Some(1_000)),
+        )),
+        allowed_messages: vec!["/cosmwasm.wasm.v1.MsgExecuteContract".to_owned()],
+    };
+    let allowance =
+        FeeAllowance::decode(&any(ALLOWED_MSG_ALLOWANCE_TYPE_URL, &allowed), "untrn", 0);
+    assert_eq!(
+        allowance.unwrap(),
+        FeeAllowance {
+            spend_limit: Some(U256::from(100)),
+            expiration: Some(1_000),
+        }
+    );
+
+    // Malformed allowances are errors
+    let malformed = Any {
+        type_url: BASIC_ALLOWANCE_TYPE_URL.to_owned(),
+        value: vec![0xff],
+    };
+    assert!(FeeAllowance::decode(&malformed, "untrn", 0).is_err());
+}
+
+#[tokio::test]
+async fn test_authz_grants_are_evicted_when_delegated_txs_fail() {
+    let provider = provider("neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4");
+    let exec = vec![any(MSG_EXEC_TYPE_URL, &MsgExec::default())];
+    let execute = vec![any(
+        "/cosmwasm.wasm.v1.MsgExecuteContract",
+        &MsgExecuteContract::default(),
+    )];
+
+    // Grants are kept if delegated transactions succeed, or transactions
+    // sent by the signer itself fail
+    provider
+        .authz_grants
+        .lock()
+        .unwrap()
+        .insert("/cosmwasm.wasm.v1.MsgExecuteContract".to_owned(), None);
+    provider.evict_authz_grants_on_failure(&exec, false);
+    provider.evict_authz_grants_on_failure(&execute, true);
+    assert_eq!(provider.authz_grants.lock().unwrap().len(), 1);
+
+    // Grants are looked up again once a delegated transaction fails
+    provider.evict_authz_grants_on_failure(&exec, true);
+    assert!(provider.authz_grants.lock().unwrap().is_empty());
+}
+
 fn provider(address: &str) -> WasmGrpcProvider {
     let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Neutron);
     let address = CosmosAddress::from_str(address).unwrap();
@@ -57,6 +205,7 @@ fn provider(address: &str) -> WasmGrpcProvider {
             vec![Url::parse("https://rpc-kralum.neutron-1.neutron.org").unwrap()],
             "neutron-1".to_owned(),
             "neutron".to_owned(),
+            vec![],
             "untrn".to_owned(),
             RawCosmosAmount::new("untrn".to_owned(), "0".to_owned()),
             32,
@@ -68,6 +217,17 @@ fn provider(address: &str) -> WasmGrpcProvider {
                 decimals: 6,
                 denom: "untrn".to_owned(),
             },
+            false,
+            false,
+            None,
+            DEFAULT_TRUSTING_PERIOD,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+            None,
+            None,
         ),
         CosmosAmount {
             denom: "untrn".to_owned(),
@@ -1,5 +1,12 @@
 pub use client::CosmosRpcClient;
 pub use provider::{CosmosWasmRpcProvider, ParsedEvent, WasmRpcProvider};
+pub use schema::{ContractVersion, EventSchema, CONTRACT_VERSION_STORAGE_KEY};
+pub use verifier::{
+    check_trusting_period, verify_block, verify_signed_header, FileLightClientStore,
+    InMemoryLightClientStore, LightClientStore, TrustedValidators, DEFAULT_TRUSTING_PERIOD,
+};
 
 mod client;
 mod provider;
+mod schema;
+mod verifier;
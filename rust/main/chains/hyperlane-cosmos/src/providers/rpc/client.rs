@@ -1,9 +1,19 @@
+use cosmrs::proto::cosmwasm::wasm::v1::{
+    QueryRawContractStateRequest, QueryRawContractStateResponse,
+};
+use cosmrs::proto::prost::Message;
 use cosmrs::proto::tendermint::blocksync::BlockResponse;
-use hyperlane_core::rpc_clients::BlockNumberGetter;
+use std::sync::Arc;
+
+use hyperlane_core::config::RateLimitConf;
+use hyperlane_core::rpc_clients::{BlockNumberGetter, RateLimiter, RpcEndpointMetrics};
 use tendermint::Hash;
 use tendermint_rpc::client::CompatMode;
-use tendermint_rpc::endpoint::{block, block_by_hash, block_results, tx};
-use tendermint_rpc::{Client, HttpClient, HttpClientUrl, Url as TendermintUrl};
+use tendermint_rpc::endpoint::{
+    block, block_by_hash, block_results, commit, tx, tx_search, validators,
+};
+use tendermint_rpc::query::Query;
+use tendermint_rpc::{Client, HttpClient, HttpClientUrl, Order, Paging, Url as TendermintUrl};
 
 use hyperlane_core::{ChainCommunicationError, ChainResult};
 use tonic::async_trait;
@@ -11,15 +21,25 @@ use url::Url;
 
 use crate::{ConnectionConf, HyperlaneCosmosError};
 
+/// The ABCI query path of raw CosmWasm contract state queries
+const RAW_CONTRACT_STATE_QUERY_PATH: &str = "/cosmwasm.wasm.v1.Query/RawContractState";
+
 /// Thin wrapper around Cosmos RPC client with error mapping
 #[derive(Clone, Debug)]
 pub struct CosmosRpcClient {
     client: HttpClient,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    metrics: RpcEndpointMetrics,
 }
 
 impl CosmosRpcClient {
     /// Create new `CosmosRpcClient`
-    pub fn new(url: &Url) -> ChainResult<Self> {
+    pub fn new(url: &Url, rate_limit: Option<RateLimitConf>, chain: &str) -> ChainResult<Self> {
+        let rate_limiter =
+            rate_limit.map(|rate_limit| RateLimiter::for_endpoint(url.as_str(), rate_limit));
+        let metrics = RpcEndpointMetrics::new(url.as_str(), chain);
+
+        let http_client = reqwest_utils::client_for(url).map_err(HyperlaneCosmosError::from)?;
         let tendermint_url = tendermint_rpc::Url::try_from(url.to_owned())
             .map_err(Into::<HyperlaneCosmosError>::into)?;
         let url = tendermint_rpc::HttpClientUrl::try_from(tendermint_url)
@@ -28,17 +48,30 @@ impl CosmosRpcClient {
         let client = HttpClient::builder(url)
             // Consider supporting different compatibility modes.
             .compat_mode(CompatMode::latest())
+            .client(http_client)
             .build()
             .map_err(Into::<HyperlaneCosmosError>::into)?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            rate_limiter,
+            metrics,
+        })
+    }
+
+    /// The client, once the rate limit of the endpoint allows another request
+    async fn client(&self) -> &HttpClient {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        &self.client
     }
 
     /// Request block by block height
     pub async fn get_block(&self, height: u32) -> ChainResult<block::Response> {
         Ok(self
-            .client
-            .block(height)
+            .metrics
+            .track("block", self.client().await.block(height))
             .await
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
@@ -46,8 +79,8 @@ impl CosmosRpcClient {
     /// Request block results by block height
     pub async fn get_block_results(&self, height: u32) -> ChainResult<block_results::Response> {
         Ok(self
-            .client
-            .block_results(height)
+            .metrics
+            .track("block_results", self.client().await.block_results(height))
             .await
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
@@ -55,8 +88,8 @@ impl CosmosRpcClient {
     /// Request block by block hash
     pub async fn get_block_by_hash(&self, hash: Hash) -> ChainResult<block_by_hash::Response> {
         Ok(self
-            .client
-            .block_by_hash(hash)
+            .metrics
+            .track("block_by_hash", self.client().await.block_by_hash(hash))
             .await
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
@@ -64,17 +97,95 @@ impl CosmosRpcClient {
     /// Request the latest block
     pub async fn get_latest_block(&self) -> ChainResult<block::Response> {
         Ok(self
-            .client
-            .latest_block()
+            .metrics
+            .track("block", self.client().await.latest_block())
             .await
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
 
+    /// Request the signed header of a block by block height
+    pub async fn get_commit(&self, height: u32) -> ChainResult<commit::Response> {
+        Ok(self
+            .metrics
+            .track("commit", self.client().await.commit(height))
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?)
+    }
+
+    /// Request the full validator set of a block by block height
+    pub async fn get_validators(&self, height: u32) -> ChainResult<validators::Response> {
+        Ok(self
+            .metrics
+            .track(
+                "validators",
+                self.client().await.validators(height, Paging::All),
+            )
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?)
+    }
+
+    /// Request the raw value stored under `key` in the state of a CosmWasm
+    /// contract, at `height` or the latest height. The value is empty if the
+    /// key isn't set.
+    pub async fn get_raw_contract_state(
+        &self,
+        address: String,
+        key: Vec<u8>,
+        height: Option<u32>,
+    ) -> ChainResult<Vec<u8>> {
+        let request = QueryRawContractStateRequest {
+            address,
+            query_data: key,
+        };
+        let response = self
+            .metrics
+            .track(
+                "abci_query",
+                self.client().await.abci_query(
+                    Some(RAW_CONTRACT_STATE_QUERY_PATH.to_owned()),
+                    request.encode_to_vec(),
+                    height.map(Into::into),
+                    false,
+                ),
+            )
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        if response.code.is_err() {
+            return Err(ChainCommunicationError::from_other_str(&format!(
+                "raw contract state query failed: {}",
+                response.log
+            )));
+        }
+        let response = QueryRawContractStateResponse::decode(response.value.as_slice())
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        Ok(response.data)
+    }
+
     /// Request transaction by transaction hash
     pub async fn get_tx_by_hash(&self, hash: Hash) -> ChainResult<tx::Response> {
         Ok(self
-            .client
-            .tx(hash, false)
+            .metrics
+            .track("tx", self.client().await.tx(hash, false))
+            .await
+            .map_err(Into::<HyperlaneCosmosError>::into)?)
+    }
+
+    /// Request a page of the transactions matching `query`, in ascending order
+    /// of height and index. Pages are numbered from 1.
+    pub async fn tx_search(
+        &self,
+        query: Query,
+        page: u32,
+        per_page: u8,
+    ) -> ChainResult<tx_search::Response> {
+        Ok(self
+            .metrics
+            .track(
+                "tx_search",
+                self.client()
+                    .await
+                    .tx_search(query, false, page, per_page, Order::Ascending),
+            )
             .await
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
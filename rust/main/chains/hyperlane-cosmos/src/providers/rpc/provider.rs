@@ -1,6 +1,11 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use cosmrs::cosmwasm::MsgExecuteContract;
 use cosmrs::rpc::client::Client;
 use futures::StreamExt;
@@ -8,23 +13,40 @@ use hyperlane_core::rpc_clients::{BlockNumberGetter, FallbackProvider};
 use sha256::digest;
 use tendermint::abci::{Event, EventAttribute};
 use tendermint::hash::Algorithm;
-use tendermint::Hash;
+use tendermint::validator::Set as ValidatorSet;
+use tendermint::{Hash, Time};
 use tendermint_rpc::client::CompatMode;
 use tendermint_rpc::endpoint::block::Response as BlockResponse;
 use tendermint_rpc::endpoint::block_results::{self, Response as BlockResultsResponse};
 use tendermint_rpc::endpoint::tx;
+use tendermint_rpc::query::Query;
 use tendermint_rpc::HttpClient;
 use time::OffsetDateTime;
-use tracing::{debug, info, instrument, trace};
+use tracing::{debug, info, instrument, trace, warn};
 
 use hyperlane_core::{
     ChainCommunicationError, ChainResult, ContractLocator, HyperlaneDomain, LogMeta, H256, U256,
 };
 
-use crate::rpc::CosmosRpcClient;
+use crate::native::hex_id;
+use crate::rpc::{
+    verify_block, verify_signed_header, ContractVersion, CosmosRpcClient, EventSchema,
+    FileLightClientStore, InMemoryLightClientStore, LightClientStore, CONTRACT_VERSION_STORAGE_KEY,
+};
 use crate::rpc_clients::CosmosFallbackProvider;
+use crate::utils::CONTRACT_ADDRESS_ATTRIBUTE_KEY;
 use crate::{ConnectionConf, CosmosAddress, CosmosProvider, HyperlaneCosmosError};
 
+/// Number of transactions per page of `tx_search` results, the maximum
+/// Tendermint allows
+const TX_SEARCH_PAGE_SIZE: u8 = 100;
+/// How long transaction searches are paused for after the first failure, e.g.
+/// because the node doesn't index transactions. The pause doubles with each
+/// consecutive failure.
+const TX_SEARCH_BACKOFF: Duration = Duration::from_secs(30);
+/// The longest transaction searches are paused for
+const MAX_TX_SEARCH_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
 #[async_trait]
 /// Trait for wasm indexer. Use rpc provider
 pub trait WasmRpcProvider: Send + Sync {
@@ -41,6 +63,18 @@ pub trait WasmRpcProvider: Send + Sync {
     where
         T: Send + Sync + PartialEq + Debug + 'static;
 
+    /// Get logs in the given block range using the given parser, by searching
+    /// for the transactions that emitted the target event of the contract
+    /// rather than fetching the events of every block.
+    async fn search_logs_in_range<T>(
+        &self,
+        range: RangeInclusive<u32>,
+        parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+        cursor_label: &'static str,
+    ) -> ChainResult<Vec<(T, LogMeta)>>
+    where
+        T: Send + Sync + PartialEq + Debug + 'static;
+
     /// Get logs for the given transaction using the given parser.
     async fn get_logs_in_tx<T>(
         &self,
@@ -74,14 +108,136 @@ impl<T: PartialEq> ParsedEvent<T> {
     }
 }
 
+/// A span of heights over which the indexed contract had the same event
+/// schema, as its versions at both ends have it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EventSchemaSpan {
+    heights: RangeInclusive<u32>,
+    schema: EventSchema,
+}
+
+impl EventSchemaSpan {
+    fn covers(&self, range: &RangeInclusive<u32>) -> bool {
+        self.heights.contains(range.start()) && self.heights.contains(range.end())
+    }
+
+    /// The span of both spans if they overlap or are adjacent and have the
+    /// same schema, otherwise `other`, which is the more recently detected
+    fn merge(self, other: Self) -> Self {
+        let overlapping = *self.heights.start() <= other.heights.end().saturating_add(1)
+            && *other.heights.start() <= self.heights.end().saturating_add(1);
+        if overlapping && self.schema == other.schema {
+            Self {
+                heights: *self.heights.start().min(other.heights.start())
+                    ..=*self.heights.end().max(other.heights.end()),
+                schema: self.schema,
+            }
+        } else {
+            other
+        }
+    }
+}
+
+/// What emits the events an RPC provider indexes
+#[derive(Debug, Clone)]
+enum EventSource {
+    /// A CosmWasm contract, whose events carry its address
+    Contract(CosmosAddress),
+    /// An object of the native `x/hyperlane` module, whose typed events carry
+    /// its JSON encoded hex ID in the attribute `id_attribute_key`
+    NativeModule {
+        id: H256,
+        id_attribute_key: &'static str,
+    },
+}
+
+impl EventSource {
+    /// The key of the event attribute identifying the emitter
+    fn attribute_key(&self) -> &'static str {
+        match self {
+            Self::Contract(_) => CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            Self::NativeModule {
+                id_attribute_key, ..
+            } => id_attribute_key,
+        }
+    }
+
+    /// The raw value of the event attribute identifying the emitter
+    fn attribute_value(&self) -> String {
+        match self {
+            Self::Contract(address) => address.address(),
+            Self::NativeModule { id, .. } => format!("\"{}\"", hex_id(*id)),
+        }
+    }
+
+    /// The emitter as the event parsers report it
+    fn emitter(&self) -> String {
+        match self {
+            Self::Contract(address) => address.address(),
+            Self::NativeModule { id, .. } => hex_id(*id),
+        }
+    }
+
+    fn digest(&self) -> H256 {
+        match self {
+            Self::Contract(address) => address.digest(),
+            Self::NativeModule { id, .. } => *id,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 /// Cosmwasm RPC Provider
 pub struct CosmosWasmRpcProvider {
     domain: HyperlaneDomain,
-    contract_address: CosmosAddress,
+    source: EventSource,
     target_event_kind: String,
     reorg_period: u32,
     rpc_client: CosmosFallbackProvider<CosmosRpcClient>,
+    /// Set if indexed blocks are verified against Tendermint commits
+    light_client_store: Option<Arc<dyn LightClientStore>>,
+    /// How long a trusted validator set stays trusted
+    trusting_period: Duration,
+    /// The latest span of heights over which the contract's event schema was
+    /// detected, as migrations can change it
+    event_schema_span: Arc<Mutex<Option<EventSchemaSpan>>>,
+    /// Whether transaction searches can be relied on, shared by all clones
+    tx_search: Arc<Mutex<TxSearchState>>,
+}
+
+/// Whether the transaction searches of a provider can be relied on
+#[derive(Debug, Default)]
+struct TxSearchState {
+    /// Whether a search found transactions, i.e. whether the node indexes the
+    /// attribute that is searched for. Until then, empty results could just
+    /// as well mean that the node doesn't index it.
+    confirmed: bool,
+    consecutive_failures: u32,
+    paused_until: Option<Instant>,
+}
+
+impl TxSearchState {
+    fn is_paused(&self) -> bool {
+        self.paused_until
+            .map_or(false, |paused_until| Instant::now() < paused_until)
+    }
+
+    fn record_success(&mut self, found_txs: bool) {
+        self.confirmed |= found_txs;
+        self.consecutive_failures = 0;
+        self.paused_until = None;
+    }
+
+    /// Pause searches, returning for how long
+    fn record_failure(&mut self) -> Duration {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let exponent = self.consecutive_failures.saturating_sub(1).min(16);
+        let pause = TX_SEARCH_BACKOFF
+            .saturating_mul(1 << exponent)
+            .min(MAX_TX_SEARCH_BACKOFF);
+        self.paused_until = Some(Instant::now() + pause);
+        pause
+    }
 }
 
 impl CosmosWasmRpcProvider {
@@ -93,35 +249,279 @@ impl CosmosWasmRpcProvider {
         locator: ContractLocator,
         event_type: String,
         reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let source = EventSource::Contract(CosmosAddress::from_h256(
+            locator.address,
+            conf.get_bech32_prefix().as_str(),
+            conf.get_contract_address_bytes(),
+        )?);
+        let target_event_kind = format!("{}-{}", Self::WASM_TYPE, event_type);
+        Self::with_source(conf, locator, source, target_event_kind, reorg_period)
+    }
+
+    /// Create an RPC provider indexing the typed events of kind `event_kind`,
+    /// e.g. `hyperlane.core.v1.EventDispatch`, that the native `x/hyperlane`
+    /// module emits for the object with ID `locator.address`, which the
+    /// events carry in the attribute `id_attribute_key`.
+    pub fn new_native(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        event_kind: &'static str,
+        id_attribute_key: &'static str,
+        reorg_period: u32,
+    ) -> ChainResult<Self> {
+        let source = EventSource::NativeModule {
+            id: locator.address,
+            id_attribute_key,
+        };
+        Self::with_source(conf, locator, source, event_kind.to_owned(), reorg_period)
+    }
+
+    fn with_source(
+        conf: ConnectionConf,
+        locator: ContractLocator,
+        source: EventSource,
+        target_event_kind: String,
+        reorg_period: u32,
     ) -> ChainResult<Self> {
         let providers = conf
             .get_rpc_urls()
             .iter()
-            .map(CosmosRpcClient::new)
+            .map(|url| CosmosRpcClient::new(url, conf.get_rate_limit(), locator.domain.name()))
             .collect::<Result<Vec<_>, _>>()?;
         let mut builder = FallbackProvider::builder();
         builder = builder.add_providers(providers);
         let fallback_provider = builder.build();
         let provider = CosmosFallbackProvider::new(fallback_provider);
+        let light_client_store = if !conf.should_verify_events() {
+            None
+        } else if let Some(path) = conf.get_light_client_store_path() {
+            Some(FileLightClientStore::shared(path)? as Arc<dyn LightClientStore>)
+        } else {
+            Some(Arc::new(InMemoryLightClientStore::default()) as Arc<dyn LightClientStore>)
+        };
 
         Ok(Self {
             domain: locator.domain.clone(),
-            contract_address: CosmosAddress::from_h256(
-                locator.address,
-                conf.get_bech32_prefix().as_str(),
-                conf.get_contract_address_bytes(),
-            )?,
-            target_event_kind: format!("{}-{}", Self::WASM_TYPE, event_type),
+            source,
+            target_event_kind,
             reorg_period,
             rpc_client: provider,
+            light_client_store,
+            trusting_period: conf.get_trusting_period(),
+            event_schema_span: Default::default(),
+            tx_search: Default::default(),
         })
     }
 
+    /// Verify indexed blocks against Tendermint commits, using `store` to
+    /// keep track of the trusted validator set.
+    pub fn with_light_client_store(mut self, store: Arc<dyn LightClientStore>) -> Self {
+        self.light_client_store = Some(store);
+        self
+    }
+
+    /// Whether logs can be fetched with `search_logs_in_range`. Searches aren't
+    /// used when verifying events, as the results of `tx_search` aren't
+    /// committed to by the block headers, nor while they are backed off from
+    /// after failing.
+    pub fn can_search_logs(&self) -> bool {
+        self.light_client_store.is_none() && !self.tx_search_state().is_paused()
+    }
+
+    /// Whether a search found transactions before, so that searches finding
+    /// none can be relied on
+    pub fn tx_search_confirmed(&self) -> bool {
+        self.tx_search_state().confirmed
+    }
+
+    /// Back off from searching after a search failed, or missed logs that
+    /// were found in the blocks it covered
+    pub fn record_tx_search_failure(&self) {
+        let pause = self.tx_search_state().record_failure();
+        warn!(?pause, domain=?self.domain, "Pausing transaction searches");
+    }
+
+    fn tx_search_state(&self) -> std::sync::MutexGuard<'_, TxSearchState> {
+        // The state is always consistent, even if a holder panicked
+        self.tx_search
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// All transactions in `range` that emitted the target event of the
+    /// contract, following the pages of the search results.
+    async fn search_txs(&self, range: &RangeInclusive<u32>) -> ChainResult<Vec<tx::Response>> {
+        let query = contract_events_query(
+            range,
+            &self.target_event_kind,
+            self.source.attribute_key(),
+            &self.source.attribute_value(),
+        );
+        let mut txs = vec![];
+        for page in 1.. {
+            let response = self
+                .rpc_client
+                .call(|provider| {
+                    let query = query.clone();
+                    Box::pin(
+                        async move { provider.tx_search(query, page, TX_SEARCH_PAGE_SIZE).await },
+                    )
+                })
+                .await?;
+            let last_page = response.txs.is_empty();
+            txs.extend(response.txs);
+            if last_page || txs.len() >= response.total_count as usize {
+                break;
+            }
+        }
+        Ok(txs)
+    }
+
+    /// The event schema of the indexed contract over `range`, which depends
+    /// on the contract's version at the time. `None` if the contract was
+    /// migrated to a version with another schema within `range`, so that the
+    /// schema has to be looked up per height. The typed events of the native
+    /// module have a single schema.
+    async fn event_schema(&self, range: &RangeInclusive<u32>) -> ChainResult<Option<EventSchema>> {
+        if let Some(schema) = self.cached_event_schema(range) {
+            return Ok(Some(schema));
+        }
+        let start = self.event_schema_at(*range.start()).await?;
+        let end = self.event_schema_at(*range.end()).await?;
+        if start != end {
+            info!(?range, domain=?self.domain, "Contract was migrated to another event schema within range");
+            return Ok(None);
+        }
+        self.cache_event_schema(EventSchemaSpan {
+            heights: range.clone(),
+            schema: start,
+        });
+        Ok(Some(start))
+    }
+
+    /// The event schema of the indexed contract at `height`. Errors if the
+    /// contract's version is unknown, rather than mis-parsing its events.
+    async fn event_schema_at(&self, height: u32) -> ChainResult<EventSchema> {
+        if let Some(schema) = self.cached_event_schema(&(height..=height)) {
+            return Ok(schema);
+        }
+        let EventSource::Contract(address) = &self.source else {
+            return Ok(EventSchema::LATEST);
+        };
+
+        let address = address.address();
+        let raw_version = self
+            .rpc_client
+            .call(|provider| {
+                let address = address.clone();
+                Box::pin(async move {
+                    provider
+                        .get_raw_contract_state(
+                            address,
+                            CONTRACT_VERSION_STORAGE_KEY.to_vec(),
+                            Some(height),
+                        )
+                        .await
+                })
+            })
+            .await?;
+        let version = if raw_version.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::from_slice::<ContractVersion>(&raw_version)
+                    .map_err(Into::<HyperlaneCosmosError>::into)?,
+            )
+        };
+        let schema = EventSchema::for_version(&address, version.as_ref())?;
+        debug!(?version, height, domain=?self.domain, "Detected contract version for event parsing");
+
+        self.cache_event_schema(EventSchemaSpan {
+            heights: height..=height,
+            schema,
+        });
+        Ok(schema)
+    }
+
+    fn cached_event_schema(&self, range: &RangeInclusive<u32>) -> Option<EventSchema> {
+        if !matches!(self.source, EventSource::Contract(_)) {
+            return Some(EventSchema::LATEST);
+        }
+        self.event_schema_span
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|span| span.covers(range))
+            .map(|span| span.schema)
+    }
+
+    fn cache_event_schema(&self, span: EventSchemaSpan) {
+        let mut cached = self.event_schema_span.lock().unwrap();
+        *cached = Some(match cached.take() {
+            Some(cached) => cached.merge(span),
+            None => span,
+        });
+    }
+
     async fn get_block(&self, height: u32) -> ChainResult<BlockResponse> {
         self.rpc_client
             .call(|provider| Box::pin(async move { provider.get_block(height).await }))
             .await
     }
+
+    async fn get_block_results(&self, height: u32) -> ChainResult<BlockResultsResponse> {
+        self.rpc_client
+            .call(|provider| Box::pin(async move { provider.get_block_results(height).await }))
+            .await
+    }
+
+    /// Verifies the block and its transaction results against the signed header
+    /// of the next block, if verification is enabled.
+    async fn verify_block(
+        &self,
+        block: &BlockResponse,
+        block_results: &BlockResultsResponse,
+    ) -> ChainResult<()> {
+        let Some(store) = &self.light_client_store else {
+            return Ok(());
+        };
+        if block.block_id.hash != block.block.header.hash() {
+            return Err(HyperlaneCosmosError::VerificationFailed(format!(
+                "block hash doesn't match the header at height {}",
+                block.block.header.height
+            ))
+            .into());
+        }
+
+        let next_height = block.block.header.height.value() as u32 + 1;
+        let commit = self
+            .rpc_client
+            .call(|provider| Box::pin(async move { provider.get_commit(next_height).await }))
+            .await?;
+        let validators = self
+            .rpc_client
+            .call(|provider| Box::pin(async move { provider.get_validators(next_height).await }))
+            .await?;
+
+        let now = Time::from_unix_timestamp(OffsetDateTime::now_utc().unix_timestamp(), 0)
+            .map_err(HyperlaneCosmosError::CosmrsTendermintError)?;
+        let trusted = store.latest_trusted().await?;
+        let verified = verify_signed_header(
+            &commit.signed_header,
+            ValidatorSet::without_proposer(validators.validators),
+            trusted.as_ref(),
+            self.trusting_period,
+            now,
+        )?;
+        store.store_trusted(verified).await?;
+
+        verify_block(
+            &block.block,
+            block_results.txs_results.as_deref().unwrap_or_default(),
+            &commit.signed_header,
+        )
+    }
 }
 
 impl CosmosWasmRpcProvider {
@@ -132,6 +532,7 @@ impl CosmosWasmRpcProvider {
         block: BlockResponse,
         block_results: BlockResultsResponse,
         parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+        schema: EventSchema,
         cursor_label: &'static str,
     ) -> Vec<(T, LogMeta)>
     where
@@ -176,7 +577,7 @@ impl CosmosWasmRpcProvider {
 
                 let block_hash = H256::from_slice(block.block_id.hash.as_bytes());
 
-                Some(self.handle_tx(tx_response, block_hash, parser))
+                Some(self.handle_tx(tx_response, block_hash, parser, schema))
             })
             .flatten()
             .collect()
@@ -189,6 +590,7 @@ impl CosmosWasmRpcProvider {
         tx: tx::Response,
         block_hash: H256,
         parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+        schema: EventSchema,
     ) -> impl Iterator<Item = (T, LogMeta)> + '_
     where
         T: PartialEq + 'static,
@@ -202,8 +604,14 @@ impl CosmosWasmRpcProvider {
             if event.kind.as_str() != self.target_event_kind {
                 return None;
             }
+            // Transactions found by searching for the contract's events also
+            // include the events of other contracts they called.
+            if !emitted_by(&event, self.source.attribute_key(), &self.source.attribute_value()) {
+                trace!(tx_hash=?tx_hash, log_idx, ?event, "Event wasn't emitted by the indexed contract");
+                return None;
+            }
 
-            parser(&event.attributes)
+            parser(&schema.canonicalize(&event.attributes))
                 .map_err(|err| {
                     // This can happen if we attempt to parse an event that just happens
                     // to have the same name but a different structure.
@@ -215,13 +623,13 @@ impl CosmosWasmRpcProvider {
                     // in the event matches the contract address we are indexing.
                     // Otherwise, we might index events from other contracts that happen
                     // to have the same target event name.
-                    if parsed_event.contract_address != self.contract_address.address() {
+                    if parsed_event.contract_address != self.source.emitter() {
                         trace!(tx_hash=?tx_hash, log_idx, ?event, "Event contract address does not match indexer contract address");
                         return None;
                     }
 
                     Some((parsed_event.event, LogMeta {
-                        address: self.contract_address.digest(),
+                        address: self.source.digest(),
                         block_number: block_height.value(),
                         block_hash,
                         transaction_id: H256::from_slice(tx_hash.as_bytes()).into(),
@@ -265,16 +673,63 @@ impl WasmRpcProvider for CosmosWasmRpcProvider {
     {
         // The two calls below could be made in parallel, but on cosmos rate limiting is a bigger problem
         // than indexing latency, so we do them sequentially.
+        let schema = self.event_schema_at(block_number).await?;
         let block = self.get_block(block_number).await?;
         debug!(?block_number, block_hash = ?block.block_id.hash, cursor_label, domain=?self.domain, "Getting logs in block with hash");
-        let block_results = self
-            .rpc_client
-            .call(|provider| {
-                Box::pin(async move { provider.get_block_results(block_number).await })
-            })
-            .await?;
+        let block_results = self.get_block_results(block_number).await?;
+        self.verify_block(&block, &block_results).await?;
+
+        Ok(self.handle_txs(block, block_results, parser, schema, cursor_label))
+    }
+
+    #[instrument(err, skip(self, parser))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn search_logs_in_range<T>(
+        &self,
+        range: RangeInclusive<u32>,
+        parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+        cursor_label: &'static str,
+    ) -> ChainResult<Vec<(T, LogMeta)>>
+    where
+        T: Send + Sync + PartialEq + Debug + 'static,
+    {
+        let range_schema = self.event_schema(&range).await?;
+        let txs = match self.search_txs(&range).await {
+            Ok(txs) => {
+                self.tx_search_state().record_success(!txs.is_empty());
+                txs
+            }
+            Err(err) => {
+                self.record_tx_search_failure();
+                return Err(err);
+            }
+        };
+        debug!(?range, txs = txs.len(), cursor_label, domain=?self.domain, "Found transactions with target events in range");
 
-        Ok(self.handle_txs(block, block_results, parser, cursor_label))
+        let mut block_hashes = HashMap::new();
+        let mut logs = vec![];
+        for tx in txs {
+            if tx.tx_result.code.is_err() {
+                debug!(tx_hash=?tx.hash, "Not indexing failed transaction");
+                continue;
+            }
+            let block_number = tx.height.value() as u32;
+            let block_hash = match block_hashes.get(&block_number) {
+                Some(block_hash) => *block_hash,
+                None => {
+                    let block = self.get_block(block_number).await?;
+                    let block_hash = H256::from_slice(block.block_id.hash.as_bytes());
+                    block_hashes.insert(block_number, block_hash);
+                    block_hash
+                }
+            };
+            let schema = match range_schema {
+                Some(schema) => schema,
+                None => self.event_schema_at(block_number).await?,
+            };
+            logs.extend(self.handle_tx(tx, block_hash, parser, schema));
+        }
+        Ok(logs)
     }
 
     #[instrument(err, skip(self, parser))]
@@ -293,11 +748,252 @@ impl WasmRpcProvider for CosmosWasmRpcProvider {
             .call(|provider| Box::pin(async move { provider.get_tx_by_hash(hash).await }))
             .await?;
         let block_number = tx.height.value() as u32;
+        let schema = self.event_schema_at(block_number).await?;
         let block = self.get_block(block_number).await?;
         let block_hash = H256::from_slice(block.block_id.hash.as_bytes());
 
         debug!(?block_number, block_hash = ?block.block_id.hash, cursor_label, domain=?self.domain, "Getting logs in transaction: block info");
 
-        Ok(self.handle_tx(tx, block_hash, parser).collect())
+        // When verifying, only the transaction results committed to by the verified
+        // block are used.
+        let tx = if self.light_client_store.is_some() {
+            let block_results = self.get_block_results(block_number).await?;
+            self.verify_block(&block, &block_results).await?;
+
+            let index = tx.index as usize;
+            let included_hash = block.block.data.get(index).map(|tx| digest(tx.as_slice()));
+            let tx_result = block_results
+                .txs_results
+                .and_then(|results| results.into_iter().nth(index));
+            match (included_hash, tx_result) {
+                (Some(included_hash), Some(tx_result))
+                    if included_hash == hex::encode(tx.hash.as_bytes()) =>
+                {
+                    tx::Response { tx_result, ..tx }
+                }
+                _ => {
+                    return Err(HyperlaneCosmosError::VerificationFailed(format!(
+                        "transaction {} isn't included in block {block_number}",
+                        tx.hash
+                    ))
+                    .into())
+                }
+            }
+        } else {
+            tx
+        };
+
+        Ok(self.handle_tx(tx, block_hash, parser, schema).collect())
+    }
+}
+
+/// The `tx_search` query for the transactions in `range` that emitted an event
+/// of `event_kind` whose attribute `key` is `value`, i.e. that was emitted by
+/// the indexed contract or module object. Tendermint indexes attributes by
+/// their plain key and value, whether it reports them plain (0.37 onwards) or
+/// base64 encoded (0.34), so the query is the same for both.
+fn contract_events_query(
+    range: &RangeInclusive<u32>,
+    event_kind: &str,
+    key: &str,
+    value: &str,
+) -> Query {
+    Query::gte("tx.height", u64::from(*range.start()))
+        .and_lte("tx.height", u64::from(*range.end()))
+        .and_eq(format!("{event_kind}.{key}"), value)
+}
+
+/// Whether the attribute `key` of `event` is `value`, i.e. whether the event
+/// was emitted by the indexed contract or module object. Attributes are plain
+/// as of Tendermint 0.37 and base64 encoded before.
+fn emitted_by(event: &Event, key: &str, value: &str) -> bool {
+    let base64_key = BASE64.encode(key);
+    event.attributes.iter().any(|attr| match attr {
+        EventAttribute::V037(a) => {
+            (a.key == key && a.value == value)
+                || (a.key == base64_key
+                    && BASE64
+                        .decode(&a.value)
+                        .is_ok_and(|decoded| decoded == value.as_bytes()))
+        }
+        EventAttribute::V034(a) => a.key == key.as_bytes() && a.value == value.as_bytes(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAILBOX: &str = "neutron1sjzzd4gwkggy6hrrs8kxxatexzcuz3jecsxm3wqgregkulzj8r7qlnuef4";
+
+    fn event(attrs: &[(&str, &str)]) -> Event {
+        Event {
+            kind: "wasm-mailbox_dispatch".to_owned(),
+            attributes: attrs
+                .iter()
+                .map(|(key, value)| {
+                    EventAttribute::from((key.to_string(), value.to_string(), true))
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_event_schema_span_merge() {
+        let span = |heights: RangeInclusive<u32>| EventSchemaSpan {
+            heights,
+            schema: EventSchema::LATEST,
+        };
+
+        assert_eq!(span(10..=20).merge(span(15..=30)), span(10..=30));
+        assert_eq!(span(10..=20).merge(span(21..=21)), span(10..=21));
+        assert_eq!(span(10..=20).merge(span(5..=9)), span(5..=20));
+        // A gap may hide a migration
+        assert_eq!(span(10..=20).merge(span(22..=30)), span(22..=30));
+
+        assert!(span(10..=30).covers(&(15..=20)));
+        assert!(!span(10..=30).covers(&(25..=35)));
+    }
+
+    #[test]
+    fn test_contract_events_query() {
+        let query = contract_events_query(
+            &(100..=104),
+            "wasm-mailbox_dispatch",
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            MAILBOX,
+        );
+        assert_eq!(
+            query.to_string(),
+            format!("tx.height >= 100 AND tx.height <= 104 AND wasm-mailbox_dispatch._contract_address = '{MAILBOX}'")
+        );
+    }
+
+    #[test]
+    fn test_emitted_by_plain_attributes() {
+        // Tendermint 0.37 onwards
+        let dispatch = event(&[("_contract_address", MAILBOX), ("sender", "neutron1sender")]);
+        assert!(emitted_by(
+            &dispatch,
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            MAILBOX
+        ));
+        assert!(!emitted_by(
+            &dispatch,
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            "neutron1other"
+        ));
+        assert!(!emitted_by(
+            &event(&[("sender", MAILBOX)]),
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            MAILBOX
+        ));
+    }
+
+    #[test]
+    fn test_emitted_by_base64_attributes() {
+        // Tendermint 0.34
+        let key = BASE64.encode("_contract_address");
+        let dispatch = event(&[(&key, &BASE64.encode(MAILBOX))]);
+        assert!(emitted_by(
+            &dispatch,
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            MAILBOX
+        ));
+        assert!(!emitted_by(
+            &dispatch,
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            "neutron1other"
+        ));
+        assert!(!emitted_by(
+            &event(&[(&key, MAILBOX)]),
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            MAILBOX
+        ));
+    }
+
+    #[test]
+    fn test_emitted_by_v034_attributes() {
+        let attr = |key: &str, value: &str| {
+            EventAttribute::V034(tendermint::abci::v0_34::EventAttribute {
+                key: key.as_bytes().to_vec(),
+                value: value.as_bytes().to_vec(),
+                index: true,
+            })
+        };
+        let dispatch = Event {
+            kind: "wasm-mailbox_dispatch".to_owned(),
+            attributes: vec![
+                attr("_contract_address", MAILBOX),
+                attr("sender", "neutron1sender"),
+            ],
+        };
+        assert!(emitted_by(
+            &dispatch,
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            MAILBOX
+        ));
+        assert!(!emitted_by(
+            &dispatch,
+            CONTRACT_ADDRESS_ATTRIBUTE_KEY,
+            "neutron1other"
+        ));
+        let other = Event {
+            kind: "wasm-mailbox_dispatch".to_owned(),
+            attributes: vec![attr("sender", MAILBOX)],
+        };
+        assert!(!emitted_by(&other, CONTRACT_ADDRESS_ATTRIBUTE_KEY, MAILBOX));
+    }
+
+    #[test]
+    fn test_tx_search_state() {
+        let mut state = TxSearchState::default();
+        assert!(!state.is_paused());
+
+        // Searches are paused for longer after each consecutive failure
+        assert_eq!(state.record_failure(), TX_SEARCH_BACKOFF);
+        assert!(state.is_paused());
+        assert_eq!(state.record_failure(), TX_SEARCH_BACKOFF * 2);
+        state.consecutive_failures = u32::MAX - 1;
+        assert_eq!(state.record_failure(), MAX_TX_SEARCH_BACKOFF);
+
+        // Searches that find nothing don't confirm that they can be relied on
+        state.record_success(false);
+        assert!(!state.is_paused());
+        assert!(!state.confirmed);
+        assert_eq!(state.record_failure(), TX_SEARCH_BACKOFF);
+        state.record_success(true);
+        assert!(state.confirmed);
+        state.record_success(false);
+        assert!(state.confirmed);
+    }
+
+    #[test]
+    fn test_native_module_events() {
+        let id = H256::from_low_u64_be(1);
+        let source = EventSource::NativeModule {
+            id,
+            id_attribute_key: "origin_mailbox_id",
+        };
+        let id_value = source.attribute_value();
+        assert_eq!(id_value, format!("\"{}\"", hex_id(id)));
+        assert_eq!(source.emitter(), hex_id(id));
+
+        let query = contract_events_query(
+            &(100..=104),
+            "hyperlane.core.v1.EventDispatch",
+            source.attribute_key(),
+            &id_value,
+        );
+        assert_eq!(
+            query.to_string(),
+            format!("tx.height >= 100 AND tx.height <= 104 AND hyperlane.core.v1.EventDispatch.origin_mailbox_id = '{id_value}'")
+        );
+
+        // Typed event attributes are JSON encoded
+        let dispatch = event(&[("origin_mailbox_id", &id_value), ("sender", "\"0x02\"")]);
+        assert!(emitted_by(&dispatch, source.attribute_key(), &id_value));
+        let other = event(&[("origin_mailbox_id", "\"0x02\"")]);
+        assert!(!emitted_by(&other, source.attribute_key(), &id_value));
     }
 }
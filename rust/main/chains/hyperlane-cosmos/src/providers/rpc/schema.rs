@@ -0,0 +1,163 @@
+use std::borrow::Cow;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use tendermint::abci::EventAttribute;
+
+use crate::HyperlaneCosmosError;
+
+/// The storage key of the cw2 contract version, which the Hyperlane CosmWasm
+/// contracts set on instantiation and migration.
+pub const CONTRACT_VERSION_STORAGE_KEY: &[u8] = b"contract_info";
+
+/// The cw2 version of a deployed contract.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ContractVersion {
+    /// The name of the contract, e.g. `crates.io:hpl-mailbox`
+    pub contract: String,
+    /// The version of the contract, e.g. `0.0.6`
+    pub version: String,
+}
+
+/// The event attribute keys of a contract version, as far as they differ from
+/// the keys the event parsers expect, which are those of the latest version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventSchema {
+    /// Attribute keys of the version, mapped to the keys of the latest version
+    renamed_attributes: &'static [(&'static str, &'static str)],
+}
+
+/// The event schemas of the known versions of the Hyperlane CosmWasm contracts,
+/// by version prefix. Contracts of other versions aren't indexed, as renamed
+/// attributes would otherwise silently fail to parse.
+const EVENT_SCHEMAS: &[(&str, EventSchema)] = &[("0.0.", EventSchema::LATEST)];
+
+impl EventSchema {
+    /// The schema of the latest contract version
+    pub const LATEST: Self = Self {
+        renamed_attributes: &[],
+    };
+
+    /// The event schema of a deployed contract, given its cw2 version
+    pub fn for_version(
+        contract_address: &str,
+        version: Option<&ContractVersion>,
+    ) -> Result<Self, HyperlaneCosmosError> {
+        let known_versions = EVENT_SCHEMAS
+            .iter()
+            .map(|(prefix, _)| format!("{prefix}x"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let Some(version) = version else {
+            return Err(HyperlaneCosmosError::UnsupportedContractVersion(format!(
+                "contract {contract_address} doesn't report its version, known versions are {known_versions}"
+            )));
+        };
+        EVENT_SCHEMAS
+            .iter()
+            .find(|(prefix, _)| version.version.starts_with(prefix))
+            .map(|(_, schema)| *schema)
+            .ok_or_else(|| {
+                HyperlaneCosmosError::UnsupportedContractVersion(format!(
+                    "{} {} at {contract_address} has unknown event attributes, known versions are {known_versions}",
+                    version.contract, version.version
+                ))
+            })
+    }
+
+    /// Rename the attributes of an event to the keys the event parsers expect.
+    /// Keys are renamed whether they are plain or base64 encoded.
+    #[allow(clippy::ptr_arg)] // the event parsers take a `&Vec`
+    pub fn canonicalize<'a>(&self, attrs: &'a Vec<EventAttribute>) -> Cow<'a, Vec<EventAttribute>> {
+        if self.renamed_attributes.is_empty() {
+            return Cow::Borrowed(attrs);
+        }
+        Cow::Owned(
+            attrs
+                .iter()
+                .map(|attr| match attr {
+                    EventAttribute::V037(a) => self
+                        .canonical_key(&a.key)
+                        .map(|key| EventAttribute::from((key, a.value.clone(), a.index)))
+                        .unwrap_or_else(|| attr.clone()),
+                    EventAttribute::V034(_) => attr.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    fn canonical_key(&self, key: &str) -> Option<String> {
+        self.renamed_attributes
+            .iter()
+            .find_map(|(renamed, canonical)| {
+                if key == *renamed {
+                    Some(canonical.to_string())
+                } else if key == BASE64.encode(renamed) {
+                    Some(BASE64.encode(canonical))
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(version: &str) -> ContractVersion {
+        ContractVersion {
+            contract: "crates.io:hpl-mailbox".to_owned(),
+            version: version.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_event_schema_for_version() {
+        assert_eq!(
+            EventSchema::for_version("neutron1mailbox", Some(&version("0.0.6"))).unwrap(),
+            EventSchema::LATEST
+        );
+
+        let err = EventSchema::for_version("neutron1mailbox", Some(&version("1.2.0")))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("crates.io:hpl-mailbox 1.2.0 at neutron1mailbox"));
+
+        assert!(EventSchema::for_version("neutron1mailbox", None).is_err());
+    }
+
+    #[test]
+    fn test_canonicalize_renamed_attributes() {
+        let schema = EventSchema {
+            renamed_attributes: &[("msg", "message")],
+        };
+        let attrs = vec![
+            EventAttribute::from(("msg".to_owned(), "0x01".to_owned(), true)),
+            EventAttribute::from((BASE64.encode("msg"), "0x02".to_owned(), true)),
+            EventAttribute::from(("_contract_address".to_owned(), "a".to_owned(), true)),
+        ];
+
+        let canonical = schema.canonicalize(&attrs);
+        let keys = canonical
+            .iter()
+            .map(|attr| match attr {
+                EventAttribute::V037(a) => a.key.clone(),
+                EventAttribute::V034(_) => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                "message".to_owned(),
+                BASE64.encode("message"),
+                "_contract_address".to_owned()
+            ]
+        );
+
+        assert!(matches!(
+            EventSchema::LATEST.canonicalize(&attrs),
+            Cow::Borrowed(_)
+        ));
+    }
+}
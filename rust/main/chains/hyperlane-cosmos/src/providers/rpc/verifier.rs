@@ -0,0 +1,519 @@
+//! Verification of the blocks and transaction results returned by an RPC
+//! against Tendermint commits, so that indexed events can't be fabricated by
+//! a malicious or faulty RPC.
+//!
+//! A block at height `h` is verified by verifying the signed header of block
+//! `h + 1`, which commits to the hash of block `h` and to the results of its
+//! transactions. Note that Tendermint doesn't commit to the events emitted by
+//! transactions, so verification guarantees that events come from
+//! transactions that were included in the block and succeeded, but not the
+//! events themselves.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hyperlane_core::ChainResult;
+use serde::{Deserialize, Serialize};
+use tendermint::abci::types::ExecTxResult;
+use tendermint::block::{signed_header::SignedHeader, Block, CommitSig};
+use tendermint::crypto::default::{signature::Verifier, Sha256};
+use tendermint::hash::Algorithm;
+use tendermint::merkle::simple_hash_from_byte_vectors;
+use tendermint::validator::{Info as ValidatorInfo, Set as ValidatorSet};
+use tendermint::vote::{SignedVote, Type as VoteType, ValidatorIndex, Vote};
+use tendermint::{Hash, Time};
+use tracing::warn;
+
+use crate::HyperlaneCosmosError;
+
+/// The trusting period used when none is configured, two thirds of the
+/// common 21 day unbonding period.
+pub const DEFAULT_TRUSTING_PERIOD: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// A validator set that was verified to have signed the block at `height`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    from = "PersistedTrustedValidators",
+    into = "PersistedTrustedValidators"
+)]
+pub struct TrustedValidators {
+    /// The height of the last verified block
+    pub height: u64,
+    /// The time of the block at `height`
+    pub time: Time,
+    /// The validators that signed the block at `height`
+    pub validators: ValidatorSet,
+    /// The hash of the validators of the block at `height + 1`
+    pub next_validators_hash: Hash,
+}
+
+/// The serialized form of [`TrustedValidators`], which only keeps the members
+/// of the validator set.
+#[derive(Serialize, Deserialize)]
+struct PersistedTrustedValidators {
+    height: u64,
+    time: Time,
+    validators: Vec<ValidatorInfo>,
+    next_validators_hash: Hash,
+}
+
+impl From<PersistedTrustedValidators> for TrustedValidators {
+    fn from(persisted: PersistedTrustedValidators) -> Self {
+        Self {
+            height: persisted.height,
+            time: persisted.time,
+            validators: ValidatorSet::without_proposer(persisted.validators),
+            next_validators_hash: persisted.next_validators_hash,
+        }
+    }
+}
+
+impl From<TrustedValidators> for PersistedTrustedValidators {
+    fn from(trusted: TrustedValidators) -> Self {
+        Self {
+            height: trusted.height,
+            time: trusted.time,
+            validators: trusted.validators.validators().clone(),
+            next_validators_hash: trusted.next_validators_hash,
+        }
+    }
+}
+
+/// Stores the latest validator set trusted by the verifier. Implementations
+/// may persist it, or seed it with a validator set obtained out of band so
+/// that the first fetched validator set doesn't have to be trusted.
+#[async_trait]
+pub trait LightClientStore: Debug + Send + Sync {
+    /// The latest trusted validator set, if any
+    async fn latest_trusted(&self) -> ChainResult<Option<TrustedValidators>>;
+
+    /// Replace the latest trusted validator set, if `trusted` is more recent
+    async fn store_trusted(&self, trusted: TrustedValidators) -> ChainResult<()>;
+}
+
+/// A [`LightClientStore`] holding the trusted validator set in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryLightClientStore {
+    trusted: Mutex<Option<TrustedValidators>>,
+}
+
+impl InMemoryLightClientStore {
+    /// Create a store trusting `trusted` from the start.
+    pub fn new(trusted: Option<TrustedValidators>) -> Self {
+        Self {
+            trusted: Mutex::new(trusted),
+        }
+    }
+}
+
+#[async_trait]
+impl LightClientStore for InMemoryLightClientStore {
+    async fn latest_trusted(&self) -> ChainResult<Option<TrustedValidators>> {
+        Ok(self.trusted.lock().unwrap().clone())
+    }
+
+    async fn store_trusted(&self, trusted: TrustedValidators) -> ChainResult<()> {
+        let mut latest = self.trusted.lock().unwrap();
+        if latest
+            .as_ref()
+            .map_or(true, |latest| latest.height < trusted.height)
+        {
+            *latest = Some(trusted);
+        }
+        Ok(())
+    }
+}
+
+/// A [`LightClientStore`] persisting the trusted validator set to a JSON
+/// file, so that it stays trusted across restarts. The file can be seeded
+/// with a validator set obtained out of band.
+#[derive(Debug)]
+pub struct FileLightClientStore {
+    path: PathBuf,
+    trusted: Mutex<Option<TrustedValidators>>,
+}
+
+impl FileLightClientStore {
+    /// Open the store at `path`, which is created on the first write.
+    pub fn open(path: PathBuf) -> ChainResult<Self> {
+        let trusted = match std::fs::read(&path) {
+            Ok(bytes) => {
+                Some(serde_json::from_slice(&bytes).map_err(HyperlaneCosmosError::SerdeError)?)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(store_error(&path, err).into()),
+        };
+        Ok(Self {
+            path,
+            trusted: Mutex::new(trusted),
+        })
+    }
+
+    /// The store at `path`, shared by all providers of the process, since
+    /// the indexers of a chain all verify blocks of the same chain.
+    pub fn shared(path: &Path) -> ChainResult<Arc<Self>> {
+        static STORES: OnceLock<Mutex<HashMap<PathBuf, Arc<FileLightClientStore>>>> =
+            OnceLock::new();
+        let mut stores = STORES.get_or_init(Default::default).lock().unwrap();
+        if let Some(store) = stores.get(path) {
+            return Ok(store.clone());
+        }
+        let store = Arc::new(Self::open(path.to_owned())?);
+        stores.insert(path.to_owned(), store.clone());
+        Ok(store)
+    }
+
+    /// Atomically replaces the file with `trusted`.
+    fn persist(&self, trusted: &TrustedValidators) -> ChainResult<()> {
+        let bytes = serde_json::to_vec(trusted).map_err(HyperlaneCosmosError::SerdeError)?;
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)
+            .and_then(|_| std::fs::rename(&tmp_path, &self.path))
+            .map_err(|err| store_error(&self.path, err))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LightClientStore for FileLightClientStore {
+    async fn latest_trusted(&self) -> ChainResult<Option<TrustedValidators>> {
+        Ok(self.trusted.lock().unwrap().clone())
+    }
+
+    async fn store_trusted(&self, trusted: TrustedValidators) -> ChainResult<()> {
+        let mut latest = self.trusted.lock().unwrap();
+        if latest
+            .as_ref()
+            .map_or(true, |latest| latest.height < trusted.height)
+        {
+            self.persist(&trusted)?;
+            *latest = Some(trusted);
+        }
+        Ok(())
+    }
+}
+
+fn store_error(path: &Path, source: std::io::Error) -> HyperlaneCosmosError {
+    HyperlaneCosmosError::LightClientStoreError {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// Fails if `trusted` is older than the trusting period at `now`, since the
+/// validators it trusts may have unbonded since and could sign conflicting
+/// blocks without being slashed.
+pub fn check_trusting_period(
+    trusted: &TrustedValidators,
+    trusting_period: Duration,
+    now: Time,
+) -> ChainResult<()> {
+    let age = now.duration_since(trusted.time).unwrap_or_default();
+    if age >= trusting_period {
+        return verification_failed(format!(
+            "the validator set trusted at height {} expired {:?} ago, the light client store \
+             must be re-seeded with a trusted validator set",
+            trusted.height,
+            age - trusting_period
+        ));
+    }
+    Ok(())
+}
+
+/// Verifies that `signed_header` was signed by `validators`, and that
+/// `validators` can be trusted given the `trusted` validators, which must be
+/// within the `trusting_period` at `now`, returning the new trusted
+/// validators.
+///
+/// Without trusted validators, `validators` are trusted on first use.
+pub fn verify_signed_header(
+    signed_header: &SignedHeader,
+    validators: ValidatorSet,
+    trusted: Option<&TrustedValidators>,
+    trusting_period: Duration,
+    now: Time,
+) -> ChainResult<TrustedValidators> {
+    let header = &signed_header.header;
+    let commit = &signed_header.commit;
+    let height = header.height.value();
+
+    if let Some(trusted) = trusted {
+        check_trusting_period(trusted, trusting_period, now)?;
+    }
+
+    if validators.hash() != header.validators_hash {
+        return verification_failed(format!(
+            "validator set hash doesn't match the header at height {height}"
+        ));
+    }
+    if commit.height != header.height || commit.block_id.hash != header.hash() {
+        return verification_failed(format!(
+            "commit doesn't match the header at height {height}"
+        ));
+    }
+
+    // More than two thirds of the block's validators must have signed it
+    let signed_power = signed_voting_power(signed_header, &validators)?;
+    let total_power = validators.total_voting_power().value();
+    if signed_power * 3 <= total_power * 2 {
+        return verification_failed(format!(
+            "header at height {height} signed by {signed_power} of {total_power} voting power"
+        ));
+    }
+
+    match trusted {
+        // The validators of the next block are committed to by the trusted block
+        Some(trusted) if trusted.height + 1 == height => {
+            if trusted.next_validators_hash != header.validators_hash {
+                return verification_failed(format!(
+                    "validator set at height {height} isn't the one trusted at height {}",
+                    trusted.height
+                ));
+            }
+        }
+        // Otherwise, more than a third of the trusted validators must have signed the
+        // block, so that at least one of them is honest
+        Some(trusted) => {
+            let trusted_signed_power = signed_voting_power(signed_header, &trusted.validators)?;
+            let trusted_total_power = trusted.validators.total_voting_power().value();
+            if trusted_signed_power * 3 <= trusted_total_power {
+                return verification_failed(format!(
+                    "header at height {height} signed by {trusted_signed_power} of {trusted_total_power} \
+                     voting power trusted at height {}",
+                    trusted.height
+                ));
+            }
+        }
+        None => warn!(
+            height,
+            "No trusted validator set, trusting the validator set fetched from the RPC"
+        ),
+    }
+
+    Ok(TrustedValidators {
+        height,
+        time: header.time,
+        validators,
+        next_validators_hash: header.next_validators_hash,
+    })
+}
+
+/// Verifies `block` and the results of its transactions against the verified
+/// signed header of the next block.
+pub fn verify_block(
+    block: &Block,
+    tx_results: &[ExecTxResult],
+    next_signed_header: &SignedHeader,
+) -> ChainResult<()> {
+    let height = block.header.height.value();
+    let next_header = &next_signed_header.header;
+
+    if next_header.height.value() != height + 1
+        || next_header.last_block_id.map(|id| id.hash) != Some(block.header.hash())
+    {
+        return verification_failed(format!(
+            "block at height {height} isn't the parent of the verified block"
+        ));
+    }
+
+    if !block.data.is_empty() {
+        let data_hash = Hash::from_bytes(
+            Algorithm::Sha256,
+            &simple_hash_from_byte_vectors::<Sha256>(&block.data),
+        )
+        .map_err(|err| HyperlaneCosmosError::VerificationFailed(err.to_string()))?;
+        if block.header.data_hash != Some(data_hash) {
+            return verification_failed(format!(
+                "transactions don't match the header at height {height}"
+            ));
+        }
+    }
+
+    if tx_results.len() != block.data.len() {
+        return verification_failed(format!(
+            "got {} transaction results for {} transactions at height {height}",
+            tx_results.len(),
+            block.data.len()
+        ));
+    }
+    let results: Vec<Vec<u8>> = tx_results.iter().map(deterministic_tx_result).collect();
+    let results_hash = Hash::from_bytes(
+        Algorithm::Sha256,
+        &simple_hash_from_byte_vectors::<Sha256>(&results),
+    )
+    .map_err(|err| HyperlaneCosmosError::VerificationFailed(err.to_string()))?;
+    if next_header.last_results_hash != Some(results_hash) {
+        return verification_failed(format!(
+            "transaction results don't match the header at height {}",
+            height + 1
+        ));
+    }
+
+    Ok(())
+}
+
+/// The sum of the voting power of the members of `validators` that signed the
+/// commit of `signed_header`.
+fn signed_voting_power(
+    signed_header: &SignedHeader,
+    validators: &ValidatorSet,
+) -> ChainResult<u64> {
+    let commit = &signed_header.commit;
+    let mut seen = HashSet::new();
+    let mut power = 0;
+
+    for (index, commit_sig) in commit.signatures.iter().enumerate() {
+        let CommitSig::BlockIdFlagCommit {
+            validator_address,
+            timestamp,
+            signature: Some(signature),
+        } = commit_sig
+        else {
+            continue;
+        };
+        let Some(validator) = validators.validator(*validator_address) else {
+            continue;
+        };
+        if !seen.insert(*validator_address) {
+            return verification_failed(format!(
+                "validator {validator_address} signed the commit at height {} twice",
+                commit.height
+            ));
+        }
+
+        let vote = Vote {
+            vote_type: VoteType::Precommit,
+            height: commit.height,
+            round: commit.round,
+            block_id: Some(commit.block_id),
+            timestamp: Some(*timestamp),
+            validator_address: *validator_address,
+            validator_index: ValidatorIndex::try_from(index)
+                .map_err(|err| HyperlaneCosmosError::VerificationFailed(err.to_string()))?,
+            signature: Some(signature.clone()),
+            extension: vec![],
+            extension_signature: None,
+        };
+        let signed_vote = SignedVote::from_vote(vote, signed_header.header.chain_id.clone())
+            .ok_or_else(|| {
+                HyperlaneCosmosError::VerificationFailed("commit signature without vote".into())
+            })?;
+        validator
+            .verify_signature::<Verifier>(&signed_vote.sign_bytes(), signed_vote.signature())
+            .map_err(|err| HyperlaneCosmosError::VerificationFailed(err.to_string()))?;
+
+        power += validator.power();
+    }
+
+    Ok(power)
+}
+
+/// The part of a transaction result that is committed to by the results hash
+/// of the next block, i.e. the protobuf encoding of an `ExecTxResult` with
+/// only its code, data, gas wanted and gas used.
+fn deterministic_tx_result(result: &ExecTxResult) -> Vec<u8> {
+    let mut bytes = vec![];
+    let code = result.code.value();
+    if code != 0 {
+        bytes.push(0x08);
+        encode_varint(code as u64, &mut bytes);
+    }
+    if !result.data.is_empty() {
+        bytes.push(0x12);
+        encode_varint(result.data.len() as u64, &mut bytes);
+        bytes.extend_from_slice(&result.data);
+    }
+    if result.gas_wanted != 0 {
+        bytes.push(0x28);
+        encode_varint(result.gas_wanted as u64, &mut bytes);
+    }
+    if result.gas_used != 0 {
+        bytes.push(0x30);
+        encode_varint(result.gas_used as u64, &mut bytes);
+    }
+    bytes
+}
+
+fn encode_varint(mut value: u64, bytes: &mut Vec<u8>) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn verification_failed<T>(reason: String) -> ChainResult<T> {
+    Err(HyperlaneCosmosError::VerificationFailed(reason).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use tendermint::abci::Code;
+
+    use super::*;
+
+    fn trusted_at(height: u64, unix_timestamp: i64) -> TrustedValidators {
+        TrustedValidators {
+            height,
+            time: Time::from_unix_timestamp(unix_timestamp, 0).unwrap(),
+            validators: ValidatorSet::without_proposer(vec![]),
+            next_validators_hash: Hash::None,
+        }
+    }
+
+    #[test]
+    fn test_trusting_period() {
+        let trusted = trusted_at(10, 1_000_000);
+        let period = Duration::from_secs(100);
+        let at = |unix_timestamp| Time::from_unix_timestamp(unix_timestamp, 0).unwrap();
+
+        assert!(check_trusting_period(&trusted, period, at(1_000_000)).is_ok());
+        assert!(check_trusting_period(&trusted, period, at(1_000_099)).is_ok());
+        assert!(check_trusting_period(&trusted, period, at(1_000_100)).is_err());
+        // a clock behind the trusted block doesn't expire it
+        assert!(check_trusting_period(&trusted, period, at(999_000)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_file_light_client_store_persists_trusted_validators() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("light_client.json");
+
+        let store = FileLightClientStore::open(path.clone()).unwrap();
+        assert_eq!(store.latest_trusted().await.unwrap(), None);
+        store
+            .store_trusted(trusted_at(10, 1_000_000))
+            .await
+            .unwrap();
+        // older validator sets don't replace the trusted one
+        store.store_trusted(trusted_at(9, 999_000)).await.unwrap();
+
+        // the trusted validator set survives a restart
+        let reopened = FileLightClientStore::open(path).unwrap();
+        assert_eq!(
+            reopened.latest_trusted().await.unwrap(),
+            Some(trusted_at(10, 1_000_000))
+        );
+    }
+
+    #[test]
+    fn test_deterministic_tx_result_encoding() {
+        let result = ExecTxResult {
+            code: Code::from(5),
+            data: vec![1, 2, 3].into(),
+            gas_wanted: 200_000,
+            gas_used: 150,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            deterministic_tx_result(&result),
+            vec![0x08, 5, 0x12, 3, 1, 2, 3, 0x28, 0xc0, 0x9a, 0x0c, 0x30, 0x96, 0x01]
+        );
+        assert!(deterministic_tx_result(&ExecTxResult::default()).is_empty());
+    }
+}
@@ -1,7 +1,34 @@
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
 use cosmrs::crypto::{secp256k1::SigningKey, PublicKey};
+use cosmrs::tx::SignerPublicKey;
 use hyperlane_core::{AccountAddressType, ChainResult};
+use hyperlane_cosmwasm_interface::types::keccak256_hash;
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use sha2::{Digest, Sha256};
+
+use crate::{CosmosAddress, CosmosKeyType, HyperlaneCosmosError};
 
-use crate::{CosmosAddress, HyperlaneCosmosError};
+/// Ethermint `ethsecp256k1` public key type URL for protobuf Any
+pub(crate) const ETHERMINT_PUBLIC_KEY_TYPE_URL: &str = "/ethermint.crypto.v1.ethsecp256k1.PubKey";
+/// Injective public key type URL for protobuf Any
+pub(crate) const INJECTIVE_PUBLIC_KEY_TYPE_URL: &str =
+    "/injective.crypto.v1beta1.ethsecp256k1.PubKey";
+
+/// A secp256k1 key held outside of the agent, e.g. in a KMS, that signs
+/// digests remotely
+#[async_trait]
+pub trait RemoteSigningKey: Send + Sync + Debug {
+    /// Sign a 32 byte digest, returning the 64 byte signature with a low `s`
+    async fn sign_prehash(&self, digest: &[u8]) -> ChainResult<Vec<u8>>;
+}
+
+#[derive(Clone, Debug)]
+enum SigningKeySource {
+    Local(Vec<u8>),
+    Remote(Arc<dyn RemoteSigningKey>),
+}
 
 #[derive(Clone, Debug)]
 /// Signer for cosmos chain
@@ -13,7 +40,7 @@ pub struct Signer {
     pub address: String,
     /// address prefix
     pub prefix: String,
-    private_key: Vec<u8>,
+    key: SigningKeySource,
 }
 
 impl Signer {
@@ -34,7 +61,37 @@ impl Signer {
         let public_key = signing_key.public_key();
         Ok(Self {
             public_key,
-            private_key,
+            key: SigningKeySource::Local(private_key),
+            address,
+            prefix,
+        })
+    }
+
+    /// create new signer whose key is held remotely, e.g. in a KMS
+    ///
+    /// # Arguments
+    /// * `public_key` - SEC1 encoded public key of the remote key
+    /// * `remote_key` - the key signing digests remotely
+    /// * `prefix` - prefix for signer address
+    /// * `account_address_type` - the type of account address used for signer
+    pub fn new_remote(
+        public_key: &[u8],
+        remote_key: Arc<dyn RemoteSigningKey>,
+        prefix: String,
+        account_address_type: &AccountAddressType,
+    ) -> ChainResult<Self> {
+        let public_key: PublicKey = cosmrs::tendermint::PublicKey::from_raw_secp256k1(public_key)
+            .ok_or_else(|| {
+                HyperlaneCosmosError::SignerInfoError(
+                    "Invalid secp256k1 public key of remote signer".to_owned(),
+                )
+            })?
+            .into();
+        let address =
+            CosmosAddress::from_pubkey(public_key, &prefix, account_address_type)?.address();
+        Ok(Self {
+            public_key,
+            key: SigningKeySource::Remote(remote_key),
             address,
             prefix,
         })
@@ -42,8 +99,66 @@ impl Signer {
 
     /// Build a SigningKey from a private key. This cannot be
     /// precompiled and stored in `Signer`, because `SigningKey` is not `Sync`.
+    /// Fails for remotely held keys.
     pub fn signing_key(&self) -> ChainResult<SigningKey> {
-        Self::build_signing_key(&self.private_key)
+        match &self.key {
+            SigningKeySource::Local(private_key) => Self::build_signing_key(private_key),
+            SigningKeySource::Remote(_) => Err(HyperlaneCosmosError::SignerInfoError(
+                "The signing key is held remotely".to_owned(),
+            )
+            .into()),
+        }
+    }
+
+    /// The public key to sign transactions with as a key of `key_type`.
+    /// `ethsecp256k1` keys are encoded like `secp256k1` keys, but chains
+    /// identify them by their own type URL, `eth_type_url`.
+    pub fn signer_public_key(
+        &self,
+        key_type: CosmosKeyType,
+        eth_type_url: &str,
+    ) -> ChainResult<SignerPublicKey> {
+        match key_type {
+            CosmosKeyType::Secp256k1 => Ok(SignerPublicKey::Single(self.public_key)),
+            CosmosKeyType::EthSecp256k1 => {
+                let mut any = self
+                    .public_key
+                    .to_any()
+                    .map_err(Into::<HyperlaneCosmosError>::into)?;
+                any.type_url = eth_type_url.to_owned();
+                Ok(SignerPublicKey::Any(any))
+            }
+        }
+    }
+
+    /// Sign `sign_bytes` as a key of `key_type`, returning the 64 byte
+    /// signature.
+    pub async fn sign(&self, sign_bytes: &[u8], key_type: CosmosKeyType) -> ChainResult<Vec<u8>> {
+        match key_type {
+            CosmosKeyType::Secp256k1 => {
+                self.sign_prehash(Sha256::digest(sign_bytes).as_slice())
+                    .await
+            }
+            CosmosKeyType::EthSecp256k1 => {
+                self.sign_prehash(keccak256_hash(sign_bytes).as_slice())
+                    .await
+            }
+        }
+    }
+
+    /// Sign a 32 byte digest, e.g. an EIP-712 hash, returning the 64 byte
+    /// signature.
+    pub async fn sign_prehash(&self, digest: &[u8]) -> ChainResult<Vec<u8>> {
+        let private_key = match &self.key {
+            SigningKeySource::Local(private_key) => private_key,
+            SigningKeySource::Remote(remote_key) => return remote_key.sign_prehash(digest).await,
+        };
+        let signing_key = k256::ecdsa::SigningKey::from_slice(private_key)
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        let signature: k256::ecdsa::Signature = signing_key
+            .sign_prehash(digest)
+            .map_err(Into::<HyperlaneCosmosError>::into)?;
+        Ok(signature.to_vec())
     }
 
     fn build_signing_key(private_key: &Vec<u8>) -> ChainResult<SigningKey> {
@@ -51,3 +166,64 @@ impl Signer {
             .map_err(Into::<HyperlaneCosmosError>::into)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A remote key that signs with a local key
+    #[derive(Debug)]
+    struct MockRemoteKey(Vec<u8>);
+
+    #[async_trait]
+    impl RemoteSigningKey for MockRemoteKey {
+        async fn sign_prehash(&self, digest: &[u8]) -> ChainResult<Vec<u8>> {
+            let signing_key = k256::ecdsa::SigningKey::from_slice(&self.0)
+                .map_err(Into::<HyperlaneCosmosError>::into)?;
+            let signature: k256::ecdsa::Signature = signing_key
+                .sign_prehash(digest)
+                .map_err(Into::<HyperlaneCosmosError>::into)?;
+            Ok(signature.to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_signs_like_local_signer() {
+        let private_key = vec![7u8; 32];
+        let local = Signer::new(
+            private_key.clone(),
+            "neutron".to_owned(),
+            &AccountAddressType::Bitcoin,
+        )
+        .unwrap();
+        let remote = Signer::new_remote(
+            &local.public_key.to_bytes(),
+            Arc::new(MockRemoteKey(private_key)),
+            "neutron".to_owned(),
+            &AccountAddressType::Bitcoin,
+        )
+        .unwrap();
+        assert_eq!(remote.address, local.address);
+        assert!(remote.signing_key().is_err());
+
+        for key_type in [CosmosKeyType::Secp256k1, CosmosKeyType::EthSecp256k1] {
+            assert_eq!(
+                remote.sign(b"sign doc", key_type).await.unwrap(),
+                local.sign(b"sign doc", key_type).await.unwrap()
+            );
+        }
+        // Signing with the local key directly hashes the sign bytes with SHA-256
+        assert_eq!(
+            local
+                .sign(b"sign doc", CosmosKeyType::Secp256k1)
+                .await
+                .unwrap(),
+            local
+                .signing_key()
+                .unwrap()
+                .sign(b"sign doc")
+                .unwrap()
+                .to_vec()
+        );
+    }
+}
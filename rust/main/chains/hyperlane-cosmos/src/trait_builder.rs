@@ -1,12 +1,17 @@
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 use derive_new::new;
 use url::Url;
 
 use hyperlane_core::{
-    config::OperationBatchConfig, ChainCommunicationError, FixedPointNumber, NativeToken,
+    config::{OperationBatchConfig, RateLimitConf},
+    ChainCommunicationError, ChainResult, FixedPointNumber, NativeToken, H256,
 };
 
+use crate::{caching::QueryCacheConfig, CosmosAddress};
+
 /// Cosmos connection configuration
 #[derive(Debug, Clone)]
 pub struct ConnectionConf {
@@ -18,6 +23,9 @@ pub struct ConnectionConf {
     chain_id: String,
     /// The human readable address prefix for the chains using bech32.
     bech32_prefix: String,
+    /// Other bech32 prefixes addresses on the chain may have, e.g. the
+    /// `valoper` prefix of validator operators.
+    additional_bech32_prefixes: Vec<String>,
     /// Canonical Assets Denom
     canonical_asset: String,
     /// The gas price set by the cosmos-sdk validator. Note that this represents the
@@ -32,6 +40,70 @@ pub struct ConnectionConf {
     pub operation_batch: OperationBatchConfig,
     /// Native Token
     native_token: NativeToken,
+    /// If true, state-changing operations are only simulated and never
+    /// broadcast.
+    dry_run: bool,
+    /// If true, indexed events are verified against Tendermint commits rather
+    /// than trusted as returned by the RPC.
+    verify_events: bool,
+    /// The file the validator set trusted when verifying events is persisted
+    /// to. Kept in memory only, and trusted on first use after every
+    /// restart, if unset.
+    light_client_store_path: Option<PathBuf>,
+    /// How long a validator set trusted when verifying events stays trusted
+    trusting_period: Duration,
+    /// How long the results of wasm queries are cached for
+    query_cache: QueryCacheConfig,
+    /// The type of key transactions are signed with
+    key_type: CosmosKeyType,
+    /// The mode transactions are signed in
+    sign_mode: CosmosSignMode,
+    /// The Hyperlane implementation deployed on the chain
+    module_type: CosmosModuleType,
+    /// Rate limit applied to each RPC and GRPC endpoint
+    rate_limit: Option<RateLimitConf>,
+    /// The bech32 address of the x/feegrant granter that pays the fees of
+    /// transactions, instead of the signer
+    fee_granter: Option<String>,
+    /// The bech32 address of the operator account that messages are delivered
+    /// on behalf of through x/authz, with the signer as the grantee
+    authz_granter: Option<String>,
+}
+
+/// The type of key transactions are signed with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum CosmosKeyType {
+    /// `secp256k1` keys, signing the SHA-256 hash of the sign bytes
+    #[default]
+    Secp256k1,
+    /// Ethermint `ethsecp256k1` keys, signing the Keccak-256 hash of the sign
+    /// bytes
+    EthSecp256k1,
+}
+
+/// The mode transactions are signed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum CosmosSignMode {
+    /// `SIGN_MODE_DIRECT`, signing the protobuf encoded transaction
+    #[default]
+    Direct,
+    /// EIP-712 typed data derived from the legacy amino JSON sign doc, as
+    /// supported by Ethermint chains. Requires `ethsecp256k1` keys.
+    Eip712,
+}
+
+/// The Hyperlane implementation deployed on a Cosmos chain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum CosmosModuleType {
+    /// The CosmWasm contracts
+    #[default]
+    CosmWasm,
+    /// The native `x/hyperlane` Cosmos SDK module, queried and called through
+    /// its protobuf Query and Msg services
+    Native,
 }
 
 /// Untyped cosmos amount
@@ -103,6 +175,21 @@ impl ConnectionConf {
         self.bech32_prefix.clone()
     }
 
+    /// Get all bech32 prefixes addresses on the chain may have, starting with
+    /// the account prefix
+    pub fn get_bech32_prefixes(&self) -> Vec<String> {
+        std::iter::once(self.bech32_prefix.clone())
+            .chain(self.additional_bech32_prefixes.iter().cloned())
+            .collect()
+    }
+
+    /// Parse a bech32 address on the chain into its H256 digest, checking
+    /// that it has one of the chain's prefixes
+    pub fn bech32_address_to_h256(&self, address: &str) -> ChainResult<H256> {
+        CosmosAddress::from_str_with_prefixes(address, &self.get_bech32_prefixes())
+            .map(|address| address.digest())
+    }
+
     /// Get the asset
     pub fn get_canonical_asset(&self) -> String {
         self.canonical_asset.clone()
@@ -118,6 +205,63 @@ impl ConnectionConf {
         &self.native_token
     }
 
+    /// Whether state-changing operations are only simulated
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Whether indexed events are verified against Tendermint commits
+    pub fn should_verify_events(&self) -> bool {
+        self.verify_events
+    }
+
+    /// Get the file the trusted validator set is persisted to, if any
+    pub fn get_light_client_store_path(&self) -> Option<&Path> {
+        self.light_client_store_path.as_deref()
+    }
+
+    /// Get how long a trusted validator set stays trusted
+    pub fn get_trusting_period(&self) -> Duration {
+        self.trusting_period
+    }
+
+    /// Get the wasm query cache configuration
+    pub fn get_query_cache(&self) -> &QueryCacheConfig {
+        &self.query_cache
+    }
+
+    /// Get the type of key transactions are signed with
+    pub fn get_key_type(&self) -> CosmosKeyType {
+        self.key_type
+    }
+
+    /// Get the mode transactions are signed in
+    pub fn get_sign_mode(&self) -> CosmosSignMode {
+        self.sign_mode
+    }
+
+    /// Get the Hyperlane implementation deployed on the chain
+    pub fn get_module_type(&self) -> CosmosModuleType {
+        self.module_type
+    }
+
+    /// Get the rate limit applied to each RPC and GRPC endpoint
+    pub fn get_rate_limit(&self) -> Option<RateLimitConf> {
+        self.rate_limit
+    }
+
+    /// Get the bech32 address of the granter that pays transaction fees, if
+    /// fees aren't paid by the signer
+    pub fn get_fee_granter(&self) -> Option<String> {
+        self.fee_granter.clone()
+    }
+
+    /// Get the bech32 address of the account the signer delivers messages on
+    /// behalf of through x/authz, if messages aren't delivered by the signer
+    pub fn get_authz_granter(&self) -> Option<String> {
+        self.authz_granter.clone()
+    }
+
     /// Get the number of bytes used to represent a contract address
     pub fn get_contract_address_bytes(&self) -> usize {
         self.contract_address_bytes
@@ -130,22 +274,46 @@ impl ConnectionConf {
         rpc_urls: Vec<Url>,
         chain_id: String,
         bech32_prefix: String,
+        additional_bech32_prefixes: Vec<String>,
         canonical_asset: String,
         minimum_gas_price: RawCosmosAmount,
         contract_address_bytes: usize,
         operation_batch: OperationBatchConfig,
         native_token: NativeToken,
+        dry_run: bool,
+        verify_events: bool,
+        light_client_store_path: Option<PathBuf>,
+        trusting_period: Duration,
+        query_cache: QueryCacheConfig,
+        key_type: CosmosKeyType,
+        sign_mode: CosmosSignMode,
+        module_type: CosmosModuleType,
+        rate_limit: Option<RateLimitConf>,
+        fee_granter: Option<String>,
+        authz_granter: Option<String>,
     ) -> Self {
         Self {
             grpc_urls,
             rpc_urls,
             chain_id,
             bech32_prefix,
+            additional_bech32_prefixes,
             canonical_asset,
             gas_price: minimum_gas_price,
             contract_address_bytes,
             operation_batch,
             native_token,
+            dry_run,
+            verify_events,
+            light_client_store_path,
+            trusting_period,
+            query_cache,
+            key_type,
+            sign_mode,
+            module_type,
+            rate_limit,
+            fee_granter,
+            authz_granter,
         }
     }
 }
@@ -1,5 +1,5 @@
 use cosmrs::proto::{cosmos::base::abci::v1beta1::TxResponse, tendermint::Error};
-use hyperlane_core::{ChainResult, ModuleType, TxOutcome, H256, U256};
+use hyperlane_core::{ChainResult, FixedPointNumber, ModuleType, TxOutcome, H256, U256};
 use url::Url;
 
 pub struct IsmType(pub hyperlane_cosmwasm_interface::ism::IsmType);
@@ -31,11 +31,43 @@ impl From<IsmType> for ModuleType {
     }
 }
 
-pub fn tx_response_to_outcome(response: TxResponse) -> ChainResult<TxOutcome> {
+/// Converts the response of a broadcast transaction into an outcome. Cosmos
+/// SDK chains charge the fee for the whole gas limit, i.e. unused gas isn't
+/// refunded, so the gas paid for is reported as the gas used.
+pub fn tx_response_to_outcome(
+    response: TxResponse,
+    gas_price: FixedPointNumber,
+) -> ChainResult<TxOutcome> {
     Ok(TxOutcome {
         transaction_id: H256::from_slice(hex::decode(response.txhash)?.as_slice()).into(),
         executed: response.code == 0,
-        gas_used: U256::from(response.gas_used),
-        gas_price: U256::one().try_into()?,
+        gas_used: U256::from(response.gas_wanted.max(response.gas_used)),
+        gas_price,
+        block_number: u64::try_from(response.height)
+            .ok()
+            .filter(|height| *height > 0),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_tx_response_to_outcome_reports_gas_limit() {
+        let response = TxResponse {
+            txhash: "AB".repeat(32),
+            gas_wanted: 200_000,
+            gas_used: 0,
+            ..Default::default()
+        };
+        let gas_price = FixedPointNumber::from_str("0.025").unwrap();
+        let outcome = tx_response_to_outcome(response, gas_price.clone()).unwrap();
+        assert!(outcome.executed);
+        assert_eq!(outcome.gas_used, U256::from(200_000));
+        assert_eq!(outcome.gas_price, gas_price);
+        assert_eq!(outcome.block_number, None);
+    }
+}
@@ -13,7 +13,7 @@ use tracing::warn;
 
 use hyperlane_core::{ChainCommunicationError, ChainResult, Indexed, LogMeta, ReorgPeriod, H256};
 
-use crate::grpc::{WasmGrpcProvider, WasmProvider};
+use crate::grpc::WasmProvider;
 use crate::rpc::{CosmosWasmRpcProvider, ParsedEvent, WasmRpcProvider};
 
 type FutureChainResults<T> = Vec<JoinHandle<(ChainResult<Vec<(T, LogMeta)>>, u32)>>;
@@ -28,7 +28,7 @@ pub(crate) static CONTRACT_ADDRESS_ATTRIBUTE_KEY_BASE64: Lazy<String> =
 /// If the `reorg_period` is None, a block height of None is given,
 /// indicating that the tip directly can be used.
 pub(crate) async fn get_block_height_for_reorg_period(
-    provider: &WasmGrpcProvider,
+    provider: &impl WasmProvider,
     reorg_period: &ReorgPeriod,
 ) -> ChainResult<Option<u64>> {
     let block_height = match reorg_period {
@@ -67,6 +67,59 @@ pub(crate) fn parse_logs_in_range<T: PartialEq + Send + Sync + Debug + 'static>(
         .collect()
 }
 
+/// Fetches the logs in `range`, by searching for the transactions that emitted
+/// them if the provider can, and otherwise by parsing the events of each block.
+/// Searches that find nothing are only relied on once a search found
+/// something, as nodes that don't index the searched attribute find nothing
+/// as well. Until then the blocks are checked, and searches are backed off
+/// from if they missed logs.
+pub(crate) async fn get_logs_in_range<T>(
+    range: RangeInclusive<u32>,
+    provider: Box<CosmosWasmRpcProvider>,
+    parser: for<'a> fn(&'a Vec<EventAttribute>) -> ChainResult<ParsedEvent<T>>,
+    label: &'static str,
+) -> ChainResult<Vec<(Indexed<T>, LogMeta)>>
+where
+    T: PartialEq + Send + Sync + Debug + Into<Indexed<T>> + 'static,
+{
+    let mut searched_nothing = false;
+    if provider.can_search_logs() {
+        match provider
+            .search_logs_in_range(range.clone(), parser, label)
+            .await
+        {
+            Ok(logs) if !logs.is_empty() || provider.tx_search_confirmed() => {
+                return Ok(logs
+                    .into_iter()
+                    .map(|(log, meta)| (log.into(), meta))
+                    .collect())
+            }
+            Ok(_) => searched_nothing = true,
+            Err(err) => warn!(
+                ?err,
+                ?range,
+                "Failed to search for logs in range, fetching the logs of each block instead"
+            ),
+        }
+    }
+    let logs = execute_and_parse_log_futures(parse_logs_in_range(
+        range.clone(),
+        provider.clone(),
+        parser,
+        label,
+    ))
+    .await?;
+    if searched_nothing && !logs.is_empty() {
+        warn!(
+            ?range,
+            logs = logs.len(),
+            "Searching for logs in range found none of the logs in its blocks"
+        );
+        provider.record_tx_search_failure();
+    }
+    Ok(logs)
+}
+
 pub(crate) async fn parse_logs_in_tx<T: PartialEq + Send + Sync + Debug + 'static>(
     hash: &H256,
     provider: Box<CosmosWasmRpcProvider>,
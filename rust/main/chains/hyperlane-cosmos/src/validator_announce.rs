@@ -108,7 +108,10 @@ impl ValidatorAnnounce for CosmosValidatorAnnounce {
             .wasm_send(announce_request, None)
             .await?;
 
-        Ok(tx_response_to_outcome(response)?)
+        Ok(tx_response_to_outcome(
+            response,
+            self.provider.grpc().gas_price(),
+        )?)
     }
 
     async fn announce_tokens_needed(&self, announcement: SignedType<Announcement>) -> Option<U256> {
@@ -1,10 +1,14 @@
 use ethers::providers::Middleware;
 use ethers_core::types::{BlockId, BlockNumber};
 use hyperlane_core::{
-    config::OperationBatchConfig, ChainCommunicationError, ChainResult, ReorgPeriod, U256,
+    config::{OperationBatchConfig, RateLimitConf},
+    rpc_clients::ProviderCapability,
+    ChainCommunicationError, ChainResult, ReorgPeriod, U256,
 };
 use url::Url;
 
+use crate::CustomErrors;
+
 /// Ethereum RPC connection configuration
 #[derive(Debug, Clone)]
 pub enum RpcConnectionConf {
@@ -17,6 +21,8 @@ pub enum RpcConnectionConf {
     HttpFallback {
         /// List of urls to connect to in order of priority
         urls: Vec<Url>,
+        /// The requests each url is sent, in the order of `urls`
+        capabilities: Vec<ProviderCapability>,
     },
     /// HTTP connection details
     Http {
@@ -39,6 +45,14 @@ pub struct ConnectionConf {
     pub transaction_overrides: TransactionOverrides,
     /// Operation batching configuration
     pub operation_batch: OperationBatchConfig,
+    /// Whether to recover merkle tree insertions from transaction traces
+    /// when `eth_getLogs` returns fewer than the hook's count says exist, e.g.
+    /// on RPCs that prune their log index
+    pub log_pruning_fallback: bool,
+    /// Rate limit applied to each RPC endpoint
+    pub rate_limit: Option<RateLimitConf>,
+    /// Custom errors of third-party contracts to decode reverts with
+    pub custom_errors: CustomErrors,
 }
 
 /// Ethereum transaction overrides.
@@ -55,6 +69,19 @@ pub struct TransactionOverrides {
     pub max_fee_per_gas: Option<U256>,
     /// Max priority fee per gas to use for EIP-1559 transactions.
     pub max_priority_fee_per_gas: Option<U256>,
+    /// Multiplier applied to the estimated max fee per gas of EIP-1559
+    /// transactions, e.g. `1.5` for 50% headroom over the estimate.
+    pub max_fee_per_gas_multiplier: Option<f64>,
+    /// Multiplier applied to the estimated max priority fee per gas of
+    /// EIP-1559 transactions.
+    pub max_priority_fee_per_gas_multiplier: Option<f64>,
+    /// Upper bound on the max fee per gas of EIP-1559 transactions, or the gas
+    /// price of other transactions, in wei. Estimated and overridden fees are
+    /// lowered to it before signing.
+    pub max_fee_per_gas_cap: Option<U256>,
+    /// Upper bound on the max priority fee per gas of EIP-1559 transactions,
+    /// in wei.
+    pub max_priority_fee_per_gas_cap: Option<U256>,
 }
 
 /// Ethereum reorg period
@@ -15,7 +15,7 @@ use hyperlane_core::{
 };
 use tracing::instrument;
 
-use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number};
+use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number, BlockHashLogQueries};
 use crate::interfaces::i_interchain_gas_paymaster::{
     GasPaymentFilter, IInterchainGasPaymaster as EthereumInterchainGasPaymasterInternal,
     IINTERCHAINGASPAYMASTER_ABI,
@@ -64,6 +64,7 @@ where
     contract: Arc<EthereumInterchainGasPaymasterInternal<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    block_hash_log_queries: BlockHashLogQueries,
 }
 
 impl<M> EthereumInterchainGasPaymasterIndexer<M>
@@ -83,6 +84,7 @@ where
             )),
             provider,
             reorg_period,
+            block_hash_log_queries: BlockHashLogQueries::default(),
         }
     }
 }
@@ -100,11 +102,8 @@ where
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<InterchainGasPayment>, LogMeta)>> {
         let events = self
-            .contract
-            .gas_payment_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
+            .block_hash_log_queries
+            .query_with_meta(self.contract.gas_payment_filter(), &self.provider, range)
             .await?;
 
         Ok(events
@@ -175,6 +174,10 @@ where
         let tip = self.get_finalized_block_number().await?;
         Ok((None, tip))
     }
+
+    async fn sequence_count_at_tip(&self, tip: u32) -> ChainResult<(Option<u32>, u32)> {
+        Ok((None, tip))
+    }
 }
 
 pub struct InterchainGasPaymasterBuilder {}
@@ -7,38 +7,41 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use derive_new::new;
-use ethers::abi::{AbiEncode, Detokenize};
+use ethers::abi::{AbiEncode, Detokenize, Token};
 use ethers::prelude::Middleware;
+use ethers::types::{transaction::eip2718::TypedTransaction, TransactionRequest};
 use ethers_contract::builders::ContractCall;
-use ethers_contract::{Multicall, MulticallResult};
+use ethers_contract::{ContractError, Multicall, MulticallResult};
 use ethers_core::utils::WEI_IN_ETHER;
 use futures_util::future::join_all;
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
 use hyperlane_core::{BatchResult, QueueOperation, ReorgPeriod, H512};
 use itertools::Itertools;
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 use hyperlane_core::{
     utils::bytes_to_hex, BatchItem, ChainCommunicationError, ChainResult, ContractLocator,
-    HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
-    RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H160, H256, U256,
+    DeliveryDryRun, HyperlaneAbi, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProtocolError, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    Mailbox, MessageDeliverySimulator, RawHyperlaneMessage, SequenceAwareIndexer, TxCostEstimate,
+    TxOutcome, H160, H256, U256,
 };
 
 use crate::error::HyperlaneEthereumError;
 use crate::interfaces::arbitrum_node_interface::ArbitrumNodeInterface;
+use crate::interfaces::i_interchain_security_module::IInterchainSecurityModule;
 use crate::interfaces::i_mailbox::{
     IMailbox as EthereumMailboxInternal, ProcessCall, IMAILBOX_ABI,
 };
 use crate::interfaces::mailbox::DispatchFilter;
-use crate::tx::{call_with_reorg_period, fill_tx_gas_params, report_tx};
+use crate::tx::{call_with_reorg_period, fill_tx_gas_params, report_tx, GasEstimator};
 use crate::{
     BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod,
     TransactionOverrides,
 };
 
 use super::multicall::{self, build_multicall};
-use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number};
+use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number, BlockHashLogQueries};
 
 impl<M> std::fmt::Display for EthereumMailboxInternal<M>
 where
@@ -104,6 +107,7 @@ where
     contract: Arc<EthereumMailboxInternal<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    block_hash_log_queries: BlockHashLogQueries,
 }
 
 impl<M> EthereumMailboxIndexer<M>
@@ -124,6 +128,7 @@ where
             contract,
             provider,
             reorg_period,
+            block_hash_log_queries: BlockHashLogQueries::default(),
         }
     }
 
@@ -150,11 +155,8 @@ where
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
         let mut events: Vec<(Indexed<HyperlaneMessage>, LogMeta)> = self
-            .contract
-            .dispatch_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
+            .block_hash_log_queries
+            .query_with_meta(self.contract.dispatch_filter(), &self.provider, range)
             .await?
             .into_iter()
             .map(|(event, meta)| {
@@ -203,6 +205,10 @@ where
     #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(self).await?;
+        SequenceAwareIndexer::<HyperlaneMessage>::sequence_count_at_tip(self, tip).await
+    }
+
+    async fn sequence_count_at_tip(&self, tip: u32) -> ChainResult<(Option<u32>, u32)> {
         let sequence = self.contract.nonce().block(u64::from(tip)).call().await?;
         Ok((Some(sequence), tip))
     }
@@ -225,11 +231,8 @@ where
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
         Ok(self
-            .contract
-            .process_id_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
+            .block_hash_log_queries
+            .query_with_meta(self.contract.process_id_filter(), &self.provider, range)
             .await?
             .into_iter()
             .map(|(event, meta)| (Indexed::new(H256::from(event.message_id)), meta.into()))
@@ -248,6 +251,10 @@ where
         let tip = Indexer::<H256>::get_finalized_block_number(self).await?;
         Ok((None, tip))
     }
+
+    async fn sequence_count_at_tip(&self, tip: u32) -> ChainResult<(Option<u32>, u32)> {
+        Ok((None, tip))
+    }
 }
 
 pub struct MailboxBuilder {}
@@ -267,6 +274,23 @@ impl BuildableWithProvider for MailboxBuilder {
     }
 }
 
+pub struct MessageDeliverySimulatorBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for MessageDeliverySimulatorBuilder {
+    type Output = Box<dyn MessageDeliverySimulator>;
+    const NEEDS_SIGNER: bool = false;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumMailbox::new(Arc::new(provider), conn, locator))
+    }
+}
+
 /// A reference to a Mailbox contract on some Ethereum chain
 #[derive(Debug)]
 pub struct EthereumMailbox<M>
@@ -291,12 +315,13 @@ where
         // The gas amount that eth_estimateGas returns considers both L1 and L2 gas costs.
         // We use the NodeInterface, found at address(0xC8), to isolate the L2 gas costs.
         // See https://developer.arbitrum.io/arbos/gas#nodeinterfacesol or https://github.com/OffchainLabs/nitro/blob/master/contracts/src/node-interface/NodeInterface.sol#L25
-        let arbitrum_node_interface = locator.domain.is_arbitrum_nitro().then(|| {
-            Arc::new(ArbitrumNodeInterface::new(
-                H160::from_low_u64_be(0xC8),
-                provider.clone(),
-            ))
-        });
+        let arbitrum_node_interface =
+            (GasEstimator::for_domain(&locator.domain) == GasEstimator::ArbitrumNitro).then(|| {
+                Arc::new(ArbitrumNodeInterface::new(
+                    H160::from_low_u64_be(0xC8),
+                    provider.clone(),
+                ))
+            });
 
         Self {
             contract: Arc::new(EthereumMailboxInternal::new(
@@ -481,6 +506,44 @@ where
         Ok(self.contract.delivered(id.into()).call().await?)
     }
 
+    #[instrument(skip(self, messages), fields(messages = messages.len()))]
+    async fn delivered_batch(&self, messages: &[HyperlaneMessage]) -> ChainResult<Vec<bool>> {
+        let ids = messages
+            .iter()
+            .map(|message| message.id())
+            .collect::<Vec<_>>();
+        let mut multicall =
+            match build_multicall(self.provider.clone(), &self.conn, self.domain.clone()).await {
+                Ok(multicall) => multicall,
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        "Multicall unavailable, checking deliveries one by one"
+                    );
+                    let mut delivered = Vec::with_capacity(ids.len());
+                    for id in ids {
+                        delivered.push(self.delivered(*id).await?);
+                    }
+                    return Ok(delivered);
+                }
+            };
+        let calls = ids
+            .iter()
+            .map(|id| self.contract.delivered((*id).into()))
+            .collect();
+        let results = multicall::aggregate_reads(&mut multicall, calls).await?;
+
+        let mut delivered = Vec::with_capacity(ids.len());
+        for (id, result) in ids.iter().zip(results) {
+            match result {
+                Some(Token::Bool(is_delivered)) => delivered.push(is_delivered),
+                // a reverted call shouldn't fail the whole batch, so retry it alone
+                _ => delivered.push(self.delivered(*id).await?),
+            }
+        }
+        Ok(delivered)
+    }
+
     #[instrument(skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
         Ok(self.contract.default_ism().call().await?.into())
@@ -583,6 +646,20 @@ where
             .map_err(ChainCommunicationError::from_other)?
             .into();
 
+        // Chains charging a fee for the L1 data of transactions on top of their
+        // gas, like Scroll, have it spread over the gas limit
+        let gas_price = match GasEstimator::for_domain(&self.domain)
+            .estimate_l1_data_fee(self.provider.as_ref(), &contract_call.tx)
+            .await?
+        {
+            Some(l1_data_fee) if !gas_limit.is_zero() => {
+                let gas_limit: U256 = gas_limit.into();
+                let l1_data_fee_per_gas = (l1_data_fee + gas_limit - U256::one()) / gas_limit;
+                gas_price.saturating_add(l1_data_fee_per_gas)
+            }
+            _ => gas_price,
+        };
+
         Ok(TxCostEstimate {
             gas_limit: gas_limit.into(),
             gas_price: gas_price.try_into()?,
@@ -590,13 +667,296 @@ where
         })
     }
 
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+    fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
         let process_call = ProcessCall {
             message: RawHyperlaneMessage::from(message).to_vec().into(),
             metadata: metadata.to_vec().into(),
         };
 
-        AbiEncode::encode(process_call)
+        Ok(AbiEncode::encode(process_call))
+    }
+}
+
+/// A message an app intends to dispatch, as passed to the origin mailbox
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProspectiveMessage {
+    /// The address that will call `dispatch` on the origin, which becomes the
+    /// sender of the message
+    pub sender: H160,
+    /// The destination domain
+    pub destination: u32,
+    /// The recipient on the destination
+    pub recipient: H256,
+    /// The message body
+    pub body: Vec<u8>,
+}
+
+/// The outcome of simulating a call with `eth_estimateGas`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulatedCall {
+    /// The call succeeded and uses the estimated gas
+    Succeeded {
+        /// The estimated gas, without a buffer
+        gas: U256,
+    },
+    /// The call reverted, or couldn't be simulated
+    Failed {
+        /// Why the simulation failed
+        reason: String,
+    },
+}
+
+impl SimulatedCall {
+    /// Whether the call succeeded
+    pub fn succeeded(&self) -> bool {
+        matches!(self, Self::Succeeded { .. })
+    }
+}
+
+/// The feasibility and cost of dispatching a message on its origin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchSimulation {
+    /// The fee quoted by the origin mailbox, which covers the protocol fee
+    /// and the fees of the required and default hooks, e.g. interchain gas
+    pub fee: U256,
+    /// The simulated `dispatch` call, paying `fee`
+    pub dispatch: SimulatedCall,
+    /// The current gas price on the origin
+    pub gas_price: U256,
+}
+
+/// The feasibility and cost of delivering a message on its destination,
+/// assuming its metadata satisfies the recipient's ISM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliverySimulation {
+    /// The ISM of the recipient
+    pub recipient_ism: H256,
+    /// Whether the recipient uses the default ISM of the destination mailbox
+    pub uses_default_ism: bool,
+    /// The simulated `handle` call of the recipient by the destination
+    /// mailbox. ISM verification isn't included, as it needs the metadata
+    /// produced by the validators.
+    pub handle: SimulatedCall,
+    /// The current gas price on the destination
+    pub gas_price: U256,
+}
+
+/// A consolidated report on dispatching a message and, optionally, delivering
+/// it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageSimulation {
+    /// The simulation on the origin
+    pub dispatch: DispatchSimulation,
+    /// The simulation on the destination, if it was requested
+    pub delivery: Option<DeliverySimulation>,
+}
+
+impl MessageSimulation {
+    /// Whether both the dispatch and the simulated delivery succeeded
+    pub fn is_feasible(&self) -> bool {
+        self.dispatch.dispatch.succeeded()
+            && self
+                .delivery
+                .as_ref()
+                .map_or(true, |delivery| delivery.handle.succeeded())
+    }
+}
+
+impl<M> EthereumMailbox<M>
+where
+    M: Middleware + 'static,
+{
+    /// Quote the fee of dispatching `message` from this mailbox and simulate
+    /// the dispatch, for apps integrating with the mailbox
+    #[instrument(skip(self, message), fields(destination = message.destination))]
+    pub async fn simulate_dispatch(
+        &self,
+        message: &ProspectiveMessage,
+    ) -> ChainResult<DispatchSimulation> {
+        let fee = self
+            .contract
+            .quote_dispatch(
+                message.destination,
+                message.recipient.into(),
+                message.body.clone().into(),
+            )
+            .call()
+            .await?;
+        let dispatch = self
+            .contract
+            .dispatch(
+                message.destination,
+                message.recipient.into(),
+                message.body.clone().into(),
+            )
+            .from(message.sender)
+            .value(fee);
+        let dispatch = simulated_call(self.provider.estimate_gas(&dispatch.tx, None).await);
+        Ok(DispatchSimulation {
+            fee: fee.into(),
+            dispatch,
+            gas_price: self.gas_price().await?,
+        })
+    }
+
+    /// Simulate this mailbox delivering a message from `origin` to its
+    /// recipient, by having the mailbox call the recipient's `handle`
+    #[instrument(skip(self, body))]
+    pub async fn simulate_delivery(
+        &self,
+        origin: u32,
+        sender: H256,
+        recipient: H256,
+        body: &[u8],
+    ) -> ChainResult<DeliverySimulation> {
+        let recipient_ism = self.recipient_ism(recipient).await?;
+        let default_ism = self.default_ism().await?;
+        let handle = TypedTransaction::Legacy(TransactionRequest {
+            from: Some(self.contract.address()),
+            to: Some(ethers::types::Address::from(recipient).into()),
+            data: Some(handle_calldata(origin, sender, body).into()),
+            ..Default::default()
+        });
+        let handle = simulated_call(self.provider.estimate_gas(&handle, None).await);
+        Ok(DeliverySimulation {
+            recipient_ism,
+            uses_default_ism: recipient_ism == default_ism,
+            handle,
+            gas_price: self.gas_price().await?,
+        })
+    }
+
+    /// The reason a call reverted, or `None` if it failed for another reason,
+    /// e.g. because the RPC is unavailable
+    fn revert_reason(&self, err: &ContractError<M>) -> Option<String> {
+        let data = err.as_revert()?;
+        Some(match self.conn.custom_errors.decode(data) {
+            Some(decoded) => decoded.to_string(),
+            None => err
+                .decode_revert::<String>()
+                .unwrap_or_else(|| bytes_to_hex(data)),
+        })
+    }
+
+    async fn gas_price(&self) -> ChainResult<U256> {
+        Ok(self
+            .provider
+            .get_gas_price()
+            .await
+            .map_err(ChainCommunicationError::from_other)?
+            .into())
+    }
+}
+
+/// Simulate dispatching `message` on `origin` and, if a destination mailbox is
+/// given, its delivery on the destination
+pub async fn simulate_message<O, D>(
+    origin: &EthereumMailbox<O>,
+    destination: Option<&EthereumMailbox<D>>,
+    message: &ProspectiveMessage,
+) -> ChainResult<MessageSimulation>
+where
+    O: Middleware + 'static,
+    D: Middleware + 'static,
+{
+    let dispatch = origin.simulate_dispatch(message).await?;
+    let delivery = match destination {
+        Some(destination) => Some(
+            destination
+                .simulate_delivery(
+                    origin.domain.id(),
+                    H256::from(message.sender),
+                    message.recipient,
+                    &message.body,
+                )
+                .await?,
+        ),
+        None => None,
+    };
+    Ok(MessageSimulation { dispatch, delivery })
+}
+
+#[async_trait]
+impl<M> MessageDeliverySimulator for EthereumMailbox<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(skip(self), fields(msg=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn dry_run_delivery(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<DeliveryDryRun> {
+        let raw_message = RawHyperlaneMessage::from(message).to_vec();
+        let process = self
+            .contract
+            .process(metadata.to_vec().into(), raw_message.clone().into());
+        let process_reason = match process.call().await {
+            Ok(()) => return Ok(DeliveryDryRun::Delivered),
+            Err(err) => match self.revert_reason(&err) {
+                Some(reason) => reason,
+                None => return Err(err.into()),
+            },
+        };
+
+        // Tell apart why processing reverted by calling the ISM and the
+        // recipient the way the mailbox does
+        let ism_address = self.recipient_ism(message.recipient).await?;
+        let ism = IInterchainSecurityModule::new(H160::from(ism_address), self.provider.clone());
+        let verify = ism
+            .verify(metadata.to_vec().into(), raw_message.into())
+            .from(self.contract.address());
+        match verify.call().await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Ok(DeliveryDryRun::IsmVerificationFailed(format!(
+                    "ISM {ism_address:?} rejected the metadata"
+                )))
+            }
+            Err(err) => {
+                return match self.revert_reason(&err) {
+                    Some(reason) => Ok(DeliveryDryRun::IsmVerificationFailed(reason)),
+                    None => Err(err.into()),
+                }
+            }
+        }
+
+        let handle = TypedTransaction::Legacy(TransactionRequest {
+            from: Some(self.contract.address()),
+            to: Some(ethers::types::Address::from(message.recipient).into()),
+            data: Some(handle_calldata(message.origin, message.sender, &message.body).into()),
+            ..Default::default()
+        });
+        if let SimulatedCall::Failed { reason } =
+            simulated_call(self.provider.estimate_gas(&handle, None).await)
+        {
+            return Ok(DeliveryDryRun::HandleReverted(reason));
+        }
+        Ok(DeliveryDryRun::Failed(process_reason))
+    }
+}
+
+/// The calldata of `IMessageRecipient.handle(uint32,bytes32,bytes)`
+fn handle_calldata(origin: u32, sender: H256, body: &[u8]) -> Vec<u8> {
+    let selector = &ethers::utils::id("handle(uint32,bytes32,bytes)")[..4];
+    let args = ethers::abi::encode(&[
+        Token::Uint(origin.into()),
+        Token::FixedBytes(sender.as_bytes().to_vec()),
+        Token::Bytes(body.to_vec()),
+    ]);
+    [selector, &args].concat()
+}
+
+fn simulated_call<E: std::fmt::Display>(estimate: Result<ethers::types::U256, E>) -> SimulatedCall {
+    match estimate {
+        Ok(gas) => SimulatedCall::Succeeded { gas: gas.into() },
+        Err(err) => SimulatedCall::Failed {
+            reason: err.to_string(),
+        },
     }
 }
 
@@ -624,9 +984,12 @@ mod test {
         TxCostEstimate, H160, H256, U256,
     };
 
+    use ethers::{abi::Token, types::Bytes};
+
     use crate::{
-        contracts::EthereumMailbox, tx::apply_gas_estimate_buffer, ConnectionConf,
-        RpcConnectionConf,
+        contracts::{DeliverySimulation, EthereumMailbox, SimulatedCall},
+        tx::apply_gas_estimate_buffer,
+        ConnectionConf, RpcConnectionConf,
     };
 
     fn get_test_mailbox(
@@ -643,6 +1006,9 @@ mod test {
             },
             transaction_overrides: Default::default(),
             operation_batch: Default::default(),
+            log_pruning_fallback: false,
+            rate_limit: None,
+            custom_errors: Default::default(),
         };
 
         let mailbox = EthereumMailbox::new(
@@ -718,6 +1084,90 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_delivered_batch() {
+        let (mailbox, mock_provider) =
+            get_test_mailbox(HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum));
+        let encode_bool = |b| Bytes::from(ethers::abi::encode(&[Token::Bool(b)]));
+        let aggregate_result = |success, return_data: Bytes| {
+            Token::Tuple(vec![
+                Token::Bool(success),
+                Token::Bytes(return_data.to_vec()),
+            ])
+        };
+
+        // The MockProvider responses we push are processed in LIFO
+        // order, so we start with the final RPCs and work toward the first
+        // RPCs
+
+        // RPC 3: eth_call of `delivered` for the call that reverted in the batch
+        mock_provider.push(encode_bool(true)).unwrap();
+        // RPC 2: eth_call of `aggregate3`
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Array(vec![
+                aggregate_result(true, encode_bool(true)),
+                aggregate_result(true, encode_bool(false)),
+                aggregate_result(false, Bytes::from(vec![0xde, 0xad])),
+            ])])))
+            .unwrap();
+        // RPC 1: eth_getCode of the multicall contract
+        mock_provider.push(Bytes::from(vec![0x60, 0x80])).unwrap();
+
+        let messages = [1, 2, 3].map(|nonce| HyperlaneMessage {
+            nonce,
+            ..Default::default()
+        });
+        let delivered = mailbox.delivered_batch(&messages).await.unwrap();
+
+        assert_eq!(delivered, vec![true, false, true]);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_delivery() {
+        let (mailbox, mock_provider) =
+            get_test_mailbox(HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum));
+        let ism = H160::repeat_byte(1);
+
+        // The MockProvider responses we push are processed in LIFO
+        // order, so we start with the final RPCs and work toward the first
+        // RPCs
+
+        // RPC 4: eth_gasPrice
+        let gas_price = U256::from(1_000_000_000u64);
+        mock_provider.push(gas_price).unwrap();
+        // RPC 3: eth_estimateGas of the recipient's `handle`
+        mock_provider.push(U256::from(80_000u32)).unwrap();
+        // RPC 2: eth_call of `defaultIsm`
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Address(
+                ism.into(),
+            )])))
+            .unwrap();
+        // RPC 1: eth_call of `recipientIsm`
+        mock_provider
+            .push(Bytes::from(ethers::abi::encode(&[Token::Address(
+                ism.into(),
+            )])))
+            .unwrap();
+
+        let delivery = mailbox
+            .simulate_delivery(1, H256::repeat_byte(2), H256::repeat_byte(3), b"hello")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            delivery,
+            DeliverySimulation {
+                recipient_ism: ism.into(),
+                uses_default_ism: true,
+                handle: SimulatedCall::Succeeded {
+                    gas: U256::from(80_000u32)
+                },
+                gas_price,
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_tx_gas_limit_caps_at_block_gas_limit() {
         let (mailbox, mock_provider) =
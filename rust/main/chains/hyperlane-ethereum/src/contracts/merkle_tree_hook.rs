@@ -1,26 +1,33 @@
 #![allow(missing_docs)]
+use std::collections::HashSet;
+use std::future::Future;
 use std::ops::RangeInclusive;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
+use ethers::abi::AbiDecode;
 use ethers::prelude::Middleware;
+use ethers::types::{Address, Bytes};
+use ethers::utils::keccak256;
 use hyperlane_core::accumulator::incremental::IncrementalMerkle;
 use hyperlane_core::rpc_clients::call_and_retry_indefinitely;
-use tracing::instrument;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{instrument, warn};
 
 use hyperlane_core::{
-    ChainResult, Checkpoint, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneProvider, Indexed, Indexer, LogMeta, MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod,
-    SequenceAwareIndexer, H256, H512,
+    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod, SequenceAwareIndexer, H256, H512,
 };
 
 use crate::interfaces::merkle_tree_hook::{
-    InsertedIntoTreeFilter, MerkleTreeHook as MerkleTreeHookContract, Tree,
+    InsertedIntoTreeFilter, MerkleTreeHook as MerkleTreeHookContract, PostDispatchCall, Tree,
 };
 use crate::tx::call_with_reorg_period;
 use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider, EthereumReorgPeriod};
 
-use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number};
+use super::utils::{fetch_raw_logs_and_meta, get_finalized_block_number, BlockHashLogQueries};
 
 // We don't need the reverse of this impl, so it's ok to disable the clippy lint
 #[allow(clippy::from_over_into)]
@@ -68,14 +75,13 @@ impl BuildableWithProvider for MerkleTreeHookIndexerBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMerkleTreeHookIndexer::new(
-            Arc::new(provider),
-            locator,
-            self.reorg_period,
-        ))
+        Box::new(
+            EthereumMerkleTreeHookIndexer::new(Arc::new(provider), locator, self.reorg_period)
+                .with_log_pruning_fallback(conn.log_pruning_fallback),
+        )
     }
 }
 
@@ -88,6 +94,11 @@ where
     contract: Arc<MerkleTreeHookContract<M>>,
     provider: Arc<M>,
     reorg_period: EthereumReorgPeriod,
+    log_pruning_fallback: bool,
+    /// The last block of the last range checked for missing insertions and
+    /// the count at that block
+    last_range_count: Mutex<Option<(u32, u32)>>,
+    block_hash_log_queries: BlockHashLogQueries,
 }
 
 impl<M> EthereumMerkleTreeHookIndexer<M>
@@ -107,7 +118,241 @@ where
             )),
             provider,
             reorg_period,
+            log_pruning_fallback: false,
+            last_range_count: Mutex::new(None),
+            block_hash_log_queries: BlockHashLogQueries::default(),
+        }
+    }
+
+    /// Recover insertions from transaction traces when `eth_getLogs` returns
+    /// fewer of them than the hook's count says exist
+    pub fn with_log_pruning_fallback(mut self, log_pruning_fallback: bool) -> Self {
+        self.log_pruning_fallback = log_pruning_fallback;
+        self
+    }
+
+    async fn count_at_block(&self, block: u32) -> ChainResult<u32> {
+        Ok(self.contract.count().block(u64::from(block)).call().await?)
+    }
+
+    /// The count before the first block of `range`. Ranges are usually
+    /// indexed one after the other, so the count at the end of the previous
+    /// range is reused instead of making another archive call.
+    async fn count_before_range(&self, range: &RangeInclusive<u32>) -> ChainResult<u32> {
+        let Some(block) = range.start().checked_sub(1) else {
+            return Ok(0);
+        };
+        let cached = *self
+            .last_range_count
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        match cached {
+            Some((cached_block, count)) if cached_block == block => Ok(count),
+            _ => self.count_at_block(block).await,
+        }
+    }
+
+    /// Reads the insertions of `blocks` from the calldata of the hook's
+    /// `postDispatch` calls, as found by tracing the transactions of the
+    /// blocks. Errors if the calls don't account for every insertion of a
+    /// block.
+    async fn fetch_insertions_from_traces(
+        &self,
+        blocks: Vec<InsertionBlock>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let hook = self.contract.address();
+        let mut logs = vec![];
+        for InsertionBlock {
+            number,
+            count_before,
+            count_after,
+        } in blocks
+        {
+            let block = self
+                .provider
+                .get_block(u64::from(number))
+                .await
+                .map_err(ChainCommunicationError::from_other)?
+                .ok_or_else(|| {
+                    ChainCommunicationError::from_other_str(&format!("Block {number} not found"))
+                })?;
+            let block_hash = block.hash.ok_or_else(|| {
+                ChainCommunicationError::from_other_str(&format!("Block {number} has no hash"))
+            })?;
+
+            let mut inserted = vec![];
+            for (transaction_index, tx_hash) in block.transactions.into_iter().enumerate() {
+                let trace: CallFrame = self
+                    .provider
+                    .provider()
+                    .request(
+                        "debug_traceTransaction",
+                        (tx_hash, json!({ "tracer": "callTracer" })),
+                    )
+                    .await
+                    .map_err(ChainCommunicationError::from_other)?;
+                let mut message_ids = vec![];
+                inserted_message_ids(&trace, hook, &mut message_ids);
+                inserted.extend(
+                    message_ids
+                        .into_iter()
+                        .map(|message_id| (message_id, tx_hash, transaction_index)),
+                );
+            }
+
+            let expected = count_after.saturating_sub(count_before) as usize;
+            if inserted.len() != expected {
+                return Err(ChainCommunicationError::from_other_str(&format!(
+                    "Traces of block {number} account for {} of {expected} merkle tree insertions",
+                    inserted.len()
+                )));
+            }
+            logs.extend(inserted.into_iter().enumerate().map(
+                |(i, (message_id, tx_hash, transaction_index))| {
+                    let log_meta = LogMeta {
+                        address: hook.into(),
+                        block_number: u64::from(number),
+                        block_hash: block_hash.into(),
+                        transaction_id: tx_hash.into(),
+                        transaction_index: transaction_index as u64,
+                        // Position of the insertion in the block, as the
+                        // index of its log is unknown
+                        log_index: (i as u64).into(),
+                    };
+                    (
+                        MerkleTreeInsertion::new(count_before + i as u32, message_id).into(),
+                        log_meta,
+                    )
+                },
+            ));
+        }
+        Ok(logs)
+    }
+
+    /// Checks the insertions returned by `eth_getLogs` against the hook's count
+    /// and, if some are missing, derives them from transaction traces instead.
+    /// Errors if the traces don't account for every insertion either, so that
+    /// the range is retried rather than indexed with gaps.
+    async fn fill_missing_insertions(
+        &self,
+        range: RangeInclusive<u32>,
+        logs: Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let count_before = self.count_before_range(&range).await?;
+        let count_after = self.count_at_block(*range.end()).await?;
+        let expected = count_before..count_after;
+
+        let unique_insertions = |logs: &[(Indexed<MerkleTreeInsertion>, LogMeta)]| {
+            logs.iter()
+                .map(|(insertion, _)| insertion.inner().index())
+                .filter(|index| expected.contains(index))
+                .collect::<HashSet<_>>()
+                .len()
+        };
+        let logs = if unique_insertions(&logs) == expected.len() {
+            logs
+        } else {
+            warn!(
+                ?range,
+                ?expected,
+                found = unique_insertions(&logs),
+                "eth_getLogs returned incomplete merkle tree insertions, deriving them from transaction traces"
+            );
+            let blocks =
+                blocks_with_insertions(range.clone(), count_before, count_after, |block| {
+                    self.count_at_block(block)
+                })
+                .await?;
+            let recovered = self.fetch_insertions_from_traces(blocks).await?;
+            let found = unique_insertions(&recovered);
+            if found != expected.len() {
+                return Err(ChainCommunicationError::from_other_str(&format!(
+                    "Recovered {found} of {} merkle tree insertions in block range {range:?}",
+                    expected.len()
+                )));
+            }
+            recovered
+        };
+        *self
+            .last_range_count
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some((*range.end(), count_after));
+        Ok(logs)
+    }
+}
+
+/// A block whose transactions inserted into the tree, with the hook's count
+/// before and after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct InsertionBlock {
+    number: u32,
+    count_before: u32,
+    count_after: u32,
+}
+
+/// The blocks in `range` whose transactions inserted into the tree, found by
+/// bisecting the range on the hook's count. `count_before` is the count before
+/// the first block of the range and `count_after` the count at its last block.
+async fn blocks_with_insertions<F, Fut>(
+    range: RangeInclusive<u32>,
+    count_before: u32,
+    count_after: u32,
+    count_at_block: F,
+) -> ChainResult<Vec<InsertionBlock>>
+where
+    F: Fn(u32) -> Fut,
+    Fut: Future<Output = ChainResult<u32>>,
+{
+    let mut blocks = vec![];
+    let mut pending = vec![(*range.start(), *range.end(), count_before, count_after)];
+    while let Some((from, to, count_before, count_after)) = pending.pop() {
+        if count_after <= count_before {
+            continue;
+        }
+        if from == to {
+            blocks.push(InsertionBlock {
+                number: from,
+                count_before,
+                count_after,
+            });
+            continue;
         }
+        let mid = from + (to - from) / 2;
+        let count_mid = count_at_block(mid).await?;
+        pending.push((from, mid, count_before, count_mid));
+        pending.push((mid + 1, to, count_mid, count_after));
+    }
+    blocks.sort_unstable_by_key(|block| block.number);
+    Ok(blocks)
+}
+
+/// A call frame of the `callTracer` of `debug_traceTransaction`
+#[derive(Debug, Default, Deserialize)]
+struct CallFrame {
+    #[serde(default)]
+    to: Option<Address>,
+    #[serde(default)]
+    input: Bytes,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    calls: Vec<CallFrame>,
+}
+
+/// Collects the IDs of the messages the `postDispatch` calls to `hook` in a
+/// traced transaction inserted, in execution order. Calls that reverted, or
+/// whose caller reverted, didn't insert anything.
+fn inserted_message_ids(frame: &CallFrame, hook: Address, message_ids: &mut Vec<H256>) {
+    if frame.error.is_some() {
+        return;
+    }
+    if frame.to == Some(hook) {
+        if let Ok(call) = PostDispatchCall::decode(&frame.input) {
+            message_ids.push(keccak256(&call.message).into());
+        }
+    }
+    for call in &frame.calls {
+        inserted_message_ids(call, hook, message_ids);
     }
 }
 
@@ -124,11 +369,12 @@ where
         range: RangeInclusive<u32>,
     ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
         let events = self
-            .contract
-            .inserted_into_tree_filter()
-            .from_block(*range.start())
-            .to_block(*range.end())
-            .query_with_meta()
+            .block_hash_log_queries
+            .query_with_meta(
+                self.contract.inserted_into_tree_filter(),
+                &self.provider,
+                range.clone(),
+            )
             .await?;
 
         let logs = events
@@ -140,6 +386,9 @@ where
                 )
             })
             .collect();
+        if self.log_pruning_fallback {
+            return self.fill_missing_insertions(range, logs).await;
+        }
         Ok(logs)
     }
 
@@ -185,6 +434,10 @@ where
     // `SequenceAwareIndexer` and `Indexer`.
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
         let tip = self.get_finalized_block_number().await?;
+        self.sequence_count_at_tip(tip).await
+    }
+
+    async fn sequence_count_at_tip(&self, tip: u32) -> ChainResult<(Option<u32>, u32)> {
         let sequence = self.contract.count().block(u64::from(tip)).call().await?;
         Ok((Some(sequence), tip))
     }
@@ -284,3 +537,127 @@ where
         Ok(count)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use ethers::abi::AbiEncode;
+
+    use super::*;
+
+    fn post_dispatch(hook: Address, message: &[u8]) -> CallFrame {
+        CallFrame {
+            to: Some(hook),
+            input: PostDispatchCall {
+                p0: Bytes::new(),
+                message: message.to_vec().into(),
+            }
+            .encode()
+            .into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_inserted_message_ids() {
+        let hook = Address::from_low_u64_be(1);
+        let other = Address::from_low_u64_be(2);
+        let trace = CallFrame {
+            to: Some(Address::from_low_u64_be(3)),
+            calls: vec![
+                post_dispatch(hook, b"first"),
+                // A reverted subcall doesn't insert, nor do its subcalls
+                CallFrame {
+                    to: Some(other),
+                    error: Some("execution reverted".to_owned()),
+                    calls: vec![post_dispatch(hook, b"reverted")],
+                    ..Default::default()
+                },
+                // Nor does a call to another contract
+                post_dispatch(other, b"other"),
+                CallFrame {
+                    to: Some(other),
+                    calls: vec![post_dispatch(hook, b"nested")],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut message_ids = vec![];
+        inserted_message_ids(&trace, hook, &mut message_ids);
+        assert_eq!(
+            message_ids,
+            vec![
+                H256::from(keccak256(b"first")),
+                H256::from(keccak256(b"nested"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_call_frame_deserialization() {
+        let trace: CallFrame = serde_json::from_value(json!({
+            "from": "0x0000000000000000000000000000000000000003",
+            "to": "0x0000000000000000000000000000000000000001",
+            "input": "0x1234",
+            "type": "CALL",
+            "gas": "0x5208",
+        }))
+        .unwrap();
+        assert_eq!(trace.to, Some(Address::from_low_u64_be(1)));
+        assert_eq!(trace.input, Bytes::from(vec![0x12, 0x34]));
+        assert!(trace.error.is_none());
+        assert!(trace.calls.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocks_with_insertions() {
+        // Insertions at blocks 12 (two), 15 and 19 of blocks 10 to 20
+        let counts: BTreeMap<u32, u32> = (9..=20)
+            .map(|block| {
+                let count = match block {
+                    9..=11 => 5,
+                    12..=14 => 7,
+                    15..=18 => 8,
+                    _ => 9,
+                };
+                (block, count)
+            })
+            .collect();
+        let count_at_block = |block: u32| {
+            let count = counts[&block];
+            async move { ChainResult::Ok(count) }
+        };
+
+        let blocks = blocks_with_insertions(10..=20, 5, 9, count_at_block)
+            .await
+            .unwrap();
+        assert_eq!(
+            blocks,
+            vec![
+                InsertionBlock {
+                    number: 12,
+                    count_before: 5,
+                    count_after: 7,
+                },
+                InsertionBlock {
+                    number: 15,
+                    count_before: 7,
+                    count_after: 8,
+                },
+                InsertionBlock {
+                    number: 19,
+                    count_before: 8,
+                    count_after: 9,
+                },
+            ]
+        );
+
+        let no_insertions = blocks_with_insertions(10..=20, 9, 9, count_at_block)
+            .await
+            .unwrap();
+        assert!(no_insertions.is_empty());
+    }
+}
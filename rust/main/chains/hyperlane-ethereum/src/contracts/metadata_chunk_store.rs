@@ -0,0 +1,181 @@
+#![allow(clippy::enum_variant_names)]
+#![allow(missing_docs)]
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use ethers::providers::Middleware;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneAbi, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, MetadataChunkStore, TxOutcome, H256,
+    U256,
+};
+use tracing::instrument;
+
+use crate::{
+    interfaces::i_metadata_chunk_store::{
+        IMetadataChunkStore as EthereumMetadataChunkStoreInternal, IMETADATACHUNKSTORE_ABI,
+    },
+    tx::{apply_gas_estimate_buffer, fill_tx_gas_params, report_tx},
+    BuildableWithProvider, ConnectionConf, EthereumProvider,
+};
+
+impl<M> std::fmt::Display for EthereumMetadataChunkStoreInternal<M>
+where
+    M: Middleware,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+pub struct MetadataChunkStoreBuilder {}
+
+#[async_trait]
+impl BuildableWithProvider for MetadataChunkStoreBuilder {
+    type Output = Box<dyn MetadataChunkStore>;
+    const NEEDS_SIGNER: bool = true;
+
+    async fn build_with_provider<M: Middleware + 'static>(
+        &self,
+        provider: M,
+        conn: &ConnectionConf,
+        locator: &ContractLocator,
+    ) -> Self::Output {
+        Box::new(EthereumMetadataChunkStore::new(
+            Arc::new(provider),
+            conn,
+            locator,
+        ))
+    }
+}
+
+/// A reference to a MetadataChunkStore contract on some Ethereum chain
+#[derive(Debug)]
+pub struct EthereumMetadataChunkStore<M>
+where
+    M: Middleware,
+{
+    contract: Arc<EthereumMetadataChunkStoreInternal<M>>,
+    domain: HyperlaneDomain,
+    provider: Arc<M>,
+    conn: ConnectionConf,
+}
+
+impl<M> EthereumMetadataChunkStore<M>
+where
+    M: Middleware + 'static,
+{
+    /// Create a reference to a MetadataChunkStore contract at a specific
+    /// Ethereum address on some chain
+    pub fn new(provider: Arc<M>, conn: &ConnectionConf, locator: &ContractLocator) -> Self {
+        Self {
+            contract: Arc::new(EthereumMetadataChunkStoreInternal::new(
+                locator.address,
+                provider.clone(),
+            )),
+            domain: locator.domain.clone(),
+            provider,
+            conn: conn.clone(),
+        }
+    }
+}
+
+impl<M> HyperlaneChain for EthereumMetadataChunkStore<M>
+where
+    M: Middleware + 'static,
+{
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(EthereumProvider::new(
+            self.contract.client(),
+            self.domain.clone(),
+        ))
+    }
+}
+
+impl<M> HyperlaneContract for EthereumMetadataChunkStore<M>
+where
+    M: Middleware + 'static,
+{
+    fn address(&self) -> H256 {
+        self.contract.address().into()
+    }
+}
+
+#[async_trait]
+impl<M> MetadataChunkStore for EthereumMetadataChunkStore<M>
+where
+    M: Middleware + 'static,
+{
+    #[instrument(err, ret, skip(self, chunk), fields(chunk_len = chunk.len()))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<TxOutcome> {
+        let tx = self
+            .contract
+            .store_chunk(key.into(), index, chunk.to_vec().into());
+        let contract_call = fill_tx_gas_params(
+            tx,
+            self.provider.clone(),
+            &self.conn.transaction_overrides,
+            &self.domain,
+        )
+        .await?;
+        let receipt = report_tx(contract_call).await?;
+        Ok(receipt.into())
+    }
+
+    #[instrument(err, ret, skip(self, chunk), fields(chunk_len = chunk.len()))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn estimate_store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<U256> {
+        let gas = self
+            .contract
+            .store_chunk(key.into(), index, chunk.to_vec().into())
+            .estimate_gas()
+            .await?;
+        apply_gas_estimate_buffer(gas.into(), &self.domain)
+    }
+
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn clear(&self, key: H256) -> ChainResult<TxOutcome> {
+        let tx = self.contract.clear(key.into());
+        let contract_call = fill_tx_gas_params(
+            tx,
+            self.provider.clone(),
+            &self.conn.transaction_overrides,
+            &self.domain,
+        )
+        .await?;
+        let receipt = report_tx(contract_call).await?;
+        Ok(receipt.into())
+    }
+
+    async fn pointer_metadata(&self, key: H256) -> ChainResult<Vec<u8>> {
+        // Chunks are namespaced by their writer, i.e. the signer of the
+        // transactions that stored them
+        let writer = self
+            .provider
+            .default_sender()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        // abi.encodePacked(store, writer, key)
+        let mut metadata = Vec::with_capacity(72);
+        metadata.extend_from_slice(self.contract.address().as_bytes());
+        metadata.extend_from_slice(writer.as_bytes());
+        metadata.extend_from_slice(key.as_bytes());
+        Ok(metadata)
+    }
+}
+
+pub struct EthereumMetadataChunkStoreAbi;
+
+impl HyperlaneAbi for EthereumMetadataChunkStoreAbi {
+    const SELECTOR_SIZE_BYTES: usize = 4;
+
+    fn fn_map() -> HashMap<Vec<u8>, &'static str> {
+        crate::extract_fn_map(&IMETADATACHUNKSTORE_ABI)
+    }
+}
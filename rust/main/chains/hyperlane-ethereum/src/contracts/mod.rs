@@ -1,10 +1,14 @@
-pub use {interchain_gas::*, mailbox::*, merkle_tree_hook::*, validator_announce::*};
+pub use {
+    interchain_gas::*, mailbox::*, merkle_tree_hook::*, metadata_chunk_store::*,
+    validator_announce::*,
+};
 
 pub(crate) use utils::get_finalized_block_number;
 
 mod interchain_gas;
 mod mailbox;
 mod merkle_tree_hook;
+mod metadata_chunk_store;
 mod multicall;
 mod utils;
 mod validator_announce;
@@ -1,12 +1,20 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, OnceLock},
+};
 
-use ethers::{abi::Detokenize, providers::Middleware};
+use ethers::{
+    abi::{Detokenize, Token},
+    providers::Middleware,
+};
 use ethers_contract::{builders::ContractCall, Multicall, MulticallResult, MulticallVersion};
 use hyperlane_core::{
-    utils::hex_or_base58_to_h256, ChainResult, HyperlaneDomain, HyperlaneProvider, U256,
+    utils::hex_or_base58_to_h256, ChainResult, HyperlaneDomain, HyperlaneProvider, H256, U256,
 };
+use itertools::Itertools;
 use tracing::warn;
 
+use crate::error::HyperlaneEthereumError;
 use crate::{ConnectionConf, EthereumProvider};
 
 const ALLOW_BATCH_FAILURES: bool = true;
@@ -17,6 +25,34 @@ const ALLOW_BATCH_FAILURES: bool = true;
 /// - https://dashboard.tenderly.co/tx/arbitrum/0xad644e431dc53c3fc0a074a749d118ff5517346c3f28d8e2513610cc9ab5c91a/gas-usage
 const MULTICALL_OVERHEAD_PER_CALL: u64 = 3500;
 
+/// Max number of view calls aggregated into a single `eth_call`, so that the
+/// call stays well within the gas cap RPC providers apply to `eth_call`
+const MAX_AGGREGATED_READS: usize = 500;
+
+/// Multicall contracts found on each domain, so that their code is only
+/// fetched once. Missing contracts aren't cached, as they may be deployed
+/// later.
+static DEPLOYED_MULTICALLS: OnceLock<Mutex<HashSet<(u32, H256)>>> = OnceLock::new();
+
+async fn is_multicall_deployed<M: Middleware + 'static>(
+    provider: Arc<M>,
+    address: H256,
+    domain: HyperlaneDomain,
+) -> ChainResult<bool> {
+    let deployed = DEPLOYED_MULTICALLS.get_or_init(Default::default);
+    let key = (domain.id(), address);
+    if deployed.lock().unwrap().contains(&key) {
+        return Ok(true);
+    }
+    let is_contract = EthereumProvider::new(provider, domain)
+        .is_contract(&address)
+        .await?;
+    if is_contract {
+        deployed.lock().unwrap().insert(key);
+    }
+    Ok(is_contract)
+}
+
 pub async fn build_multicall<M: Middleware + 'static>(
     provider: Arc<M>,
     conn: &ConnectionConf,
@@ -26,8 +62,7 @@ pub async fn build_multicall<M: Middleware + 'static>(
         .operation_batch
         .batch_contract_address
         .unwrap_or(hex_or_base58_to_h256("0xcA11bde05977b3631167028862bE2a173976CA11").unwrap());
-    let ethereum_provider = EthereumProvider::new(provider.clone(), domain);
-    if !ethereum_provider.is_contract(&address).await? {
+    if !is_multicall_deployed(provider.clone(), address, domain).await? {
         return Err(eyre::eyre!("Multicall contract not found at address"));
     }
     let multicall = match Multicall::new(provider.clone(), Some(address.into())).await {
@@ -80,3 +115,69 @@ where
     batch_call = batch_call.gas(gas_limit);
     Ok(batch_call)
 }
+
+/// Call view functions through `aggregate3`, in chunks of at most
+/// `MAX_AGGREGATED_READS` calls per `eth_call`. The results are in the order
+/// of `calls`, with `None` for calls that reverted.
+pub async fn aggregate_reads<M, D>(
+    multicall: &mut Multicall<M>,
+    calls: Vec<ContractCall<M, D>>,
+) -> ChainResult<Vec<Option<Token>>>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    let mut results = Vec::with_capacity(calls.len());
+    for chunk in &calls.into_iter().chunks(MAX_AGGREGATED_READS) {
+        // clear the calls of the previous chunk
+        multicall.clear_calls();
+        for call in chunk {
+            multicall.add_call(call, ALLOW_BATCH_FAILURES);
+        }
+        let chunk_results = multicall
+            .call_raw()
+            .await
+            .map_err(|e| HyperlaneEthereumError::MulticallError(e.to_string()))?;
+        results.extend(chunk_results.into_iter().map(Result::ok));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::{
+        providers::{MockProvider, Provider},
+        types::Bytes,
+    };
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deployed_multicall_code_is_fetched_once() {
+        let mock_provider = Arc::new(MockProvider::new());
+        let provider = Arc::new(Provider::new(mock_provider.clone()));
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Test2);
+        let address = H256::repeat_byte(0xca);
+
+        // Not deployed yet, so the code is fetched again
+        mock_provider.push(Bytes::new()).unwrap();
+        assert!(
+            !is_multicall_deployed(provider.clone(), address, domain.clone())
+                .await
+                .unwrap()
+        );
+        mock_provider.push(Bytes::from(vec![0x60])).unwrap();
+        assert!(
+            is_multicall_deployed(provider.clone(), address, domain.clone())
+                .await
+                .unwrap()
+        );
+
+        // The mock provider has no responses left, so this can only succeed
+        // from the cache
+        assert!(is_multicall_deployed(provider, address, domain)
+            .await
+            .unwrap());
+    }
+}
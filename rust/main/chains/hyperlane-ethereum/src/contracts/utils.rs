@@ -1,16 +1,174 @@
-use std::sync::Arc;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use ethers::{
     abi::RawLog,
     providers::Middleware,
-    types::{H160 as EthersH160, H256 as EthersH256},
+    types::{Log, H160 as EthersH160, H256 as EthersH256},
 };
-use ethers_contract::{ContractError, EthEvent, LogMeta as EthersLogMeta};
+use ethers_contract::{builders::Event, ContractError, EthEvent, LogMeta as EthersLogMeta};
 use hyperlane_core::{ChainCommunicationError, ChainResult, LogMeta, H512};
-use tracing::instrument;
+use tracing::{instrument, warn};
 
 use crate::EthereumReorgPeriod;
 
+/// Block ranges of at most this many blocks are queried by block hash
+const MAX_BLOCK_HASH_QUERY_RANGE: u32 = 8;
+/// How long queries by block hash are paused for after the first failure. The
+/// pause doubles with each consecutive failure.
+const BLOCK_HASH_QUERY_BACKOFF: Duration = Duration::from_secs(10);
+/// The longest queries by block hash are paused for, e.g. if the provider
+/// doesn't support them
+const MAX_BLOCK_HASH_QUERY_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// Queries the logs of small block ranges, i.e. of recently mined blocks when
+/// indexing at the tip, by the hashes of the blocks they were emitted in
+/// rather than by number. This way the logs of a block can't silently come
+/// from a different fork than the block hash they are reported with, which
+/// reorg detection relies on.
+/// The range is queried by number first, and only the blocks it has logs in
+/// are queried again by hash. Larger ranges, and all ranges while queries by
+/// block hash are backed off from after failing, use the logs queried by
+/// number.
+#[derive(Debug, Clone, Default)]
+pub struct BlockHashLogQueries {
+    backoff: Arc<Mutex<BlockHashQueryBackoff>>,
+}
+
+#[derive(Debug, Default)]
+struct BlockHashQueryBackoff {
+    consecutive_failures: u32,
+    paused_until: Option<Instant>,
+}
+
+impl BlockHashQueryBackoff {
+    fn is_paused(&self) -> bool {
+        self.paused_until
+            .map_or(false, |paused_until| Instant::now() < paused_until)
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.paused_until = None;
+    }
+
+    /// Pause queries by block hash, returning for how long
+    fn record_failure(&mut self) -> Duration {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let pause = backoff_delay(self.consecutive_failures);
+        self.paused_until = Some(Instant::now() + pause);
+        pause
+    }
+}
+
+fn backoff_delay(consecutive_failures: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    BLOCK_HASH_QUERY_BACKOFF
+        .saturating_mul(1 << exponent)
+        .min(MAX_BLOCK_HASH_QUERY_BACKOFF)
+}
+
+impl BlockHashLogQueries {
+    /// Query the logs of `event` in `range`
+    pub async fn query_with_meta<M, D>(
+        &self,
+        event: Event<Arc<M>, M, D>,
+        provider: &M,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(D, EthersLogMeta)>>
+    where
+        M: Middleware + 'static,
+        D: EthEvent,
+    {
+        let logs = provider
+            .get_logs(
+                &event
+                    .filter
+                    .clone()
+                    .from_block(*range.start())
+                    .to_block(*range.end()),
+            )
+            .await
+            .map_err(ContractError::<M>::MiddlewareError)?;
+
+        let is_small = range.end().saturating_sub(*range.start()) < MAX_BLOCK_HASH_QUERY_RANGE;
+        if is_small && !logs.is_empty() && !self.is_paused() {
+            match query_by_block_hash::<M, D>(&event, provider, &logs).await {
+                Ok(logs) => {
+                    self.backoff().record_success();
+                    return Ok(logs);
+                }
+                Err(err) => {
+                    let pause = self.backoff().record_failure();
+                    warn!(
+                        ?err,
+                        ?range,
+                        ?pause,
+                        "Failed to query logs by block hash, using the logs queried by block number"
+                    );
+                }
+            }
+        }
+        logs.iter()
+            .map(|log| Ok((decode_log::<D>(log)?, EthersLogMeta::from(log))))
+            .collect()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.backoff().is_paused()
+    }
+
+    fn backoff(&self) -> std::sync::MutexGuard<'_, BlockHashQueryBackoff> {
+        // The backoff state is always consistent, even if a holder panicked
+        self.backoff
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Query the logs of the blocks that `logs_by_number` were emitted in again by
+/// the hashes of these blocks
+async fn query_by_block_hash<M, D>(
+    event: &Event<Arc<M>, M, D>,
+    provider: &M,
+    logs_by_number: &[Log],
+) -> ChainResult<Vec<(D, EthersLogMeta)>>
+where
+    M: Middleware + 'static,
+    D: EthEvent,
+{
+    let mut block_hashes = logs_by_number
+        .iter()
+        .map(|log| {
+            log.block_hash
+                .ok_or_else(|| ChainCommunicationError::from_other_str("Log without a block hash"))
+        })
+        .collect::<ChainResult<Vec<_>>>()?;
+    // Logs are ordered by block
+    block_hashes.dedup();
+
+    let mut logs = vec![];
+    for block_hash in block_hashes {
+        let block_logs = provider
+            .get_logs(&event.filter.clone().at_block_hash(block_hash))
+            .await
+            .map_err(ContractError::<M>::MiddlewareError)?;
+        for log in block_logs {
+            logs.push((decode_log::<D>(&log)?, EthersLogMeta::from(&log)));
+        }
+    }
+    Ok(logs)
+}
+
+fn decode_log<D: EthEvent>(log: &Log) -> ChainResult<D> {
+    let raw_log = RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
+    };
+    D::decode_log(&raw_log).map_err(ChainCommunicationError::from_other)
+}
+
 pub async fn fetch_raw_logs_and_meta<T: EthEvent, M>(
     tx_hash: H512,
     provider: Arc<M>,
@@ -77,3 +235,115 @@ where
 
     Ok(number)
 }
+
+#[cfg(test)]
+mod test {
+    use ethers::{
+        providers::{MockProvider, MockResponse, Provider},
+        types::U64,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::interfaces::i_mailbox::{IMailbox, ProcessIdFilter};
+
+    fn process_id_log(message_id: EthersH256, block_number: u64, block_hash: EthersH256) -> Log {
+        Log {
+            topics: vec![ProcessIdFilter::signature(), message_id],
+            block_hash: Some(block_hash),
+            block_number: Some(U64::from(block_number)),
+            transaction_hash: Some(EthersH256::zero()),
+            transaction_index: Some(U64::zero()),
+            log_index: Some(Default::default()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queries_small_ranges_by_block_hash() {
+        let mock_provider = Arc::new(MockProvider::new());
+        let provider = Arc::new(Provider::new(mock_provider.clone()));
+        let mailbox = IMailbox::new(EthersH160::zero(), provider.clone());
+        let queries = BlockHashLogQueries::default();
+        let message_id = EthersH256::repeat_byte(1);
+        let block_hash = EthersH256::repeat_byte(2);
+        let reorged_block_hash = EthersH256::repeat_byte(3);
+
+        // The MockProvider responses are returned in LIFO order. The block the
+        // range query reported is queried by its hash, whose logs are used.
+        mock_provider
+            .push(vec![process_id_log(message_id, 10, block_hash)])
+            .unwrap();
+        mock_provider
+            .push(vec![
+                process_id_log(message_id, 10, block_hash),
+                process_id_log(message_id, 10, block_hash),
+            ])
+            .unwrap();
+        let logs = queries
+            .query_with_meta(mailbox.process_id_filter(), &provider, 10..=11)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].0.message_id, message_id.0);
+        assert_eq!(logs[0].1.block_hash, block_hash);
+        assert!(!queries.is_paused());
+
+        // Ranges without logs aren't queried by block hash
+        mock_provider.push(Vec::<Log>::new()).unwrap();
+        let logs = queries
+            .query_with_meta(mailbox.process_id_filter(), &provider, 12..=12)
+            .await
+            .unwrap();
+        assert!(logs.is_empty());
+
+        // A failed query by block hash falls back to the logs queried by number
+        // and pauses queries by block hash
+        mock_provider.push_response(MockResponse::Error(
+            serde_json::from_value(json!({ "code": -32000, "message": "header not found" }))
+                .unwrap(),
+        ));
+        mock_provider
+            .push(vec![process_id_log(message_id, 13, reorged_block_hash)])
+            .unwrap();
+        let logs = queries
+            .query_with_meta(mailbox.process_id_filter(), &provider, 13..=13)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].1.block_hash, reorged_block_hash);
+        assert!(queries.is_paused());
+
+        // While paused, small ranges are only queried by number
+        mock_provider
+            .push(vec![process_id_log(message_id, 14, block_hash)])
+            .unwrap();
+        let logs = queries
+            .query_with_meta(mailbox.process_id_filter(), &provider, 14..=14)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+
+        // Once the pause is over, small ranges are queried by block hash again
+        queries.backoff().paused_until = Some(Instant::now());
+        mock_provider
+            .push(vec![process_id_log(message_id, 15, block_hash)])
+            .unwrap();
+        mock_provider
+            .push(vec![process_id_log(message_id, 15, block_hash)])
+            .unwrap();
+        queries
+            .query_with_meta(mailbox.process_id_filter(), &provider, 15..=15)
+            .await
+            .unwrap();
+        assert_eq!(queries.backoff().consecutive_failures, 0);
+        assert!(!queries.is_paused());
+    }
+
+    #[test]
+    fn test_block_hash_query_backoff() {
+        assert_eq!(backoff_delay(1), BLOCK_HASH_QUERY_BACKOFF);
+        assert_eq!(backoff_delay(3), BLOCK_HASH_QUERY_BACKOFF * 4);
+        assert_eq!(backoff_delay(u32::MAX), MAX_BLOCK_HASH_QUERY_BACKOFF);
+    }
+}
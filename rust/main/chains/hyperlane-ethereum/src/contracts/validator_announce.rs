@@ -86,7 +86,7 @@ where
         &self,
         announcement: SignedType<Announcement>,
     ) -> ChainResult<ContractCall<M, bool>> {
-        let serialized_signature: [u8; 65] = announcement.signature.into();
+        let serialized_signature = announcement.signature.to_vec();
         let tx = self.contract.announce(
             announcement.value.validator.into(),
             announcement.value.storage_location,
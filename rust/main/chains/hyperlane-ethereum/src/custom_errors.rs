@@ -0,0 +1,158 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr, sync::Arc};
+
+use ethers::abi::{AbiError, HumanReadableParser, Token};
+use ethers::providers::Middleware;
+use ethers_contract::ContractError;
+use hyperlane_core::ChainCommunicationError;
+use itertools::Itertools;
+
+/// A custom error definition of a third-party contract, e.g. an ISM or a hook
+/// that isn't part of the Hyperlane deployment, parsed from its human-readable
+/// ABI fragment such as `error NotWhitelisted(address sender)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomError(AbiError);
+
+impl CustomError {
+    /// The selector of the error, i.e. the first four bytes of revert data
+    /// carrying it
+    pub fn selector(&self) -> [u8; 4] {
+        let mut selector = [0u8; 4];
+        selector.copy_from_slice(&self.0.signature()[..4]);
+        selector
+    }
+}
+
+/// Error parsing a custom error definition
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid custom error definition `{definition}`: {reason}")]
+pub struct CustomErrorParseError {
+    definition: String,
+    reason: String,
+}
+
+impl FromStr for CustomError {
+    type Err = CustomErrorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let definition = s.trim();
+        // The parser expects the `error` keyword, which operators may omit
+        let definition = if definition.starts_with("error ") {
+            definition.to_owned()
+        } else {
+            format!("error {definition}")
+        };
+        HumanReadableParser::parse_error(&definition)
+            .map(Self)
+            .map_err(|err| CustomErrorParseError {
+                definition: s.to_owned(),
+                reason: err.to_string(),
+            })
+    }
+}
+
+/// Custom errors that revert data of contract calls is decoded against, on
+/// top of the errors of the Hyperlane contract ABIs.
+#[derive(Debug, Clone, Default)]
+pub struct CustomErrors(Arc<HashMap<[u8; 4], CustomError>>);
+
+impl FromIterator<CustomError> for CustomErrors {
+    fn from_iter<T: IntoIterator<Item = CustomError>>(iter: T) -> Self {
+        Self(Arc::new(
+            iter.into_iter()
+                .map(|error| (error.selector(), error))
+                .collect(),
+        ))
+    }
+}
+
+impl CustomErrors {
+    /// Whether no custom errors are configured
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Decode `revert_data` as one of the custom errors, if it matches any
+    pub fn decode(&self, revert_data: &[u8]) -> Option<DecodedCustomError> {
+        let selector: [u8; 4] = revert_data.get(..4)?.try_into().ok()?;
+        let error = self.0.get(&selector)?;
+        let args = error.0.decode(&revert_data[4..]).ok()?;
+        Some(DecodedCustomError {
+            name: error.0.name.clone(),
+            args,
+        })
+    }
+
+    /// Convert the error of a contract call, decoding its revert data if it
+    /// is one of the custom errors
+    pub fn map_contract_error<M: Middleware + 'static>(
+        &self,
+        err: ContractError<M>,
+    ) -> ChainCommunicationError {
+        match err.as_revert().and_then(|data| self.decode(data)) {
+            Some(decoded) => ChainCommunicationError::from_contract_error(decoded),
+            None => err.into(),
+        }
+    }
+}
+
+/// A custom error decoded from the revert data of a contract call
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCustomError {
+    /// The name of the error
+    pub name: String,
+    /// The arguments of the error
+    pub args: Vec<Token>,
+}
+
+impl Display for DecodedCustomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Contract reverted with {}({})",
+            self.name,
+            self.args.iter().join(", ")
+        )
+    }
+}
+
+impl std::error::Error for DecodedCustomError {}
+
+#[cfg(test)]
+mod tests {
+    use ethers::abi::{encode, Address};
+
+    use super::*;
+
+    #[test]
+    fn test_decode_custom_error() {
+        let errors: CustomErrors = ["error NotWhitelisted(address sender, uint256 nonce)"]
+            .into_iter()
+            .map(|definition| definition.parse::<CustomError>().unwrap())
+            .collect();
+        let error = "NotWhitelisted(address,uint256)"
+            .parse::<CustomError>()
+            .unwrap();
+
+        let args = vec![
+            Token::Address(Address::repeat_byte(1)),
+            Token::Uint(7u64.into()),
+        ];
+        let mut revert_data = error.selector().to_vec();
+        revert_data.extend(encode(&args));
+
+        let decoded = errors.decode(&revert_data).unwrap();
+        assert_eq!(decoded.name, "NotWhitelisted");
+        assert_eq!(decoded.args, args);
+
+        revert_data[0] ^= 1;
+        assert_eq!(errors.decode(&revert_data), None);
+        assert_eq!(errors.decode(&[]), None);
+    }
+
+    #[test]
+    fn test_invalid_custom_error() {
+        assert!("error NotWhitelisted(address"
+            .parse::<CustomError>()
+            .is_err());
+    }
+}
@@ -18,6 +18,10 @@ pub enum HyperlaneEthereumError {
     /// Some details from a queried block are missing
     #[error("Some details from a queried block are missing")]
     MissingBlockDetails,
+
+    /// Safe transaction service error
+    #[error("Safe transaction service error: {0}")]
+    SafeTransactionServiceError(String),
 }
 
 impl From<HyperlaneEthereumError> for ChainCommunicationError {
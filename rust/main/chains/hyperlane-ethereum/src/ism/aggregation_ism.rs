@@ -16,7 +16,7 @@ use hyperlane_core::{
 use crate::interfaces::i_aggregation_ism::{
     IAggregationIsm as EthereumAggregationIsmInternal, IAGGREGATIONISM_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ConnectionConf, CustomErrors, EthereumProvider};
 
 pub struct AggregationIsmBuilder {}
 
@@ -28,10 +28,13 @@ impl BuildableWithProvider for AggregationIsmBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumAggregationIsm::new(Arc::new(provider), locator))
+        Box::new(
+            EthereumAggregationIsm::new(Arc::new(provider), locator)
+                .with_custom_errors(conn.custom_errors.clone()),
+        )
     }
 }
 
@@ -43,6 +46,7 @@ where
 {
     contract: Arc<EthereumAggregationIsmInternal<M>>,
     domain: HyperlaneDomain,
+    custom_errors: CustomErrors,
 }
 
 impl<M> EthereumAggregationIsm<M>
@@ -58,8 +62,15 @@ where
                 provider,
             )),
             domain: locator.domain.clone(),
+            custom_errors: CustomErrors::default(),
         }
     }
+
+    /// Decode reverts with `custom_errors` on top of the errors of the ABI
+    pub fn with_custom_errors(mut self, custom_errors: CustomErrors) -> Self {
+        self.custom_errors = custom_errors;
+        self
+    }
 }
 
 impl<M> HyperlaneChain for EthereumAggregationIsm<M>
@@ -102,7 +113,8 @@ where
             .contract
             .modules_and_threshold(RawHyperlaneMessage::from(message).to_vec().into())
             .call()
-            .await?;
+            .await
+            .map_err(|err| self.custom_errors.map_contract_error(err))?;
         let isms_h256 = isms.iter().map(|address| (*address).into()).collect();
         Ok((isms_h256, threshold))
     }
@@ -16,7 +16,7 @@ use hyperlane_core::{
 pub use crate::interfaces::i_ccip_read_ism::{
     ICcipReadIsm as EthereumCcipReadIsmInternal, OffchainLookup, ICCIPREADISM_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ConnectionConf, CustomErrors, EthereumProvider};
 
 pub struct CcipReadIsmBuilder {}
 
@@ -28,10 +28,13 @@ impl BuildableWithProvider for CcipReadIsmBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumCcipReadIsm::new(Arc::new(provider), locator))
+        Box::new(
+            EthereumCcipReadIsm::new(Arc::new(provider), locator)
+                .with_custom_errors(conn.custom_errors.clone()),
+        )
     }
 }
 
@@ -43,6 +46,7 @@ where
 {
     contract: Arc<EthereumCcipReadIsmInternal<M>>,
     domain: HyperlaneDomain,
+    custom_errors: CustomErrors,
 }
 
 impl<M> EthereumCcipReadIsm<M>
@@ -55,8 +59,15 @@ where
         Self {
             contract: Arc::new(EthereumCcipReadIsmInternal::new(locator.address, provider)),
             domain: locator.domain.clone(),
+            custom_errors: CustomErrors::default(),
         }
     }
+
+    /// Decode reverts with `custom_errors` on top of the errors of the ABI
+    pub fn with_custom_errors(mut self, custom_errors: CustomErrors) -> Self {
+        self.custom_errors = custom_errors;
+        self
+    }
 }
 
 impl<M> HyperlaneChain for EthereumCcipReadIsm<M>
@@ -95,7 +106,8 @@ where
         self.contract
             .get_offchain_verify_info(message.into())
             .call()
-            .await?;
+            .await
+            .map_err(|err| self.custom_errors.map_contract_error(err))?;
         Ok(())
     }
 }
@@ -20,7 +20,7 @@ use crate::interfaces::i_interchain_security_module::{
     IInterchainSecurityModule as EthereumInterchainSecurityModuleInternal,
     IINTERCHAINSECURITYMODULE_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ConnectionConf, CustomErrors, EthereumProvider};
 
 pub struct InterchainSecurityModuleBuilder {}
 
@@ -32,13 +32,13 @@ impl BuildableWithProvider for InterchainSecurityModuleBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumInterchainSecurityModule::new(
-            Arc::new(provider),
-            locator,
-        ))
+        Box::new(
+            EthereumInterchainSecurityModule::new(Arc::new(provider), locator)
+                .with_custom_errors(conn.custom_errors.clone()),
+        )
     }
 }
 
@@ -50,6 +50,7 @@ where
 {
     contract: Arc<EthereumInterchainSecurityModuleInternal<M>>,
     domain: HyperlaneDomain,
+    custom_errors: CustomErrors,
 }
 
 impl<M> EthereumInterchainSecurityModule<M>
@@ -65,8 +66,15 @@ where
                 provider,
             )),
             domain: locator.domain.clone(),
+            custom_errors: CustomErrors::default(),
         }
     }
+
+    /// Decode reverts with `custom_errors` on top of the errors of the ABI
+    pub fn with_custom_errors(mut self, custom_errors: CustomErrors) -> Self {
+        self.custom_errors = custom_errors;
+        self
+    }
 }
 
 impl<M> HyperlaneChain for EthereumInterchainSecurityModule<M>
@@ -101,7 +109,12 @@ where
 {
     #[instrument]
     async fn module_type(&self) -> ChainResult<ModuleType> {
-        let module = self.contract.module_type().call().await?;
+        let module = self
+            .contract
+            .module_type()
+            .call()
+            .await
+            .map_err(|err| self.custom_errors.map_contract_error(err))?;
         if let Some(module_type) = ModuleType::from_u8(module) {
             Ok(module_type)
         } else {
@@ -120,7 +133,9 @@ where
             metadata.to_owned().into(),
             RawHyperlaneMessage::from(message).to_vec().into(),
         );
-        let (verifies, gas_estimate) = try_join(tx.call(), tx.estimate_gas()).await?;
+        let (verifies, gas_estimate) = try_join(tx.call(), tx.estimate_gas())
+            .await
+            .map_err(|err| self.custom_errors.map_contract_error(err))?;
         if verifies {
             Ok(Some(gas_estimate.into()))
         } else {
@@ -16,7 +16,7 @@ use hyperlane_core::{
 use crate::interfaces::i_multisig_ism::{
     IMultisigIsm as EthereumMultisigIsmInternal, IMULTISIGISM_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ConnectionConf, CustomErrors, EthereumProvider};
 
 impl<M> std::fmt::Display for EthereumMultisigIsmInternal<M>
 where
@@ -37,10 +37,13 @@ impl BuildableWithProvider for MultisigIsmBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumMultisigIsm::new(Arc::new(provider), locator))
+        Box::new(
+            EthereumMultisigIsm::new(Arc::new(provider), locator)
+                .with_custom_errors(conn.custom_errors.clone()),
+        )
     }
 }
 
@@ -52,6 +55,7 @@ where
 {
     contract: Arc<EthereumMultisigIsmInternal<M>>,
     domain: HyperlaneDomain,
+    custom_errors: CustomErrors,
 }
 
 impl<M> EthereumMultisigIsm<M>
@@ -64,8 +68,15 @@ where
         Self {
             contract: Arc::new(EthereumMultisigIsmInternal::new(locator.address, provider)),
             domain: locator.domain.clone(),
+            custom_errors: CustomErrors::default(),
         }
     }
+
+    /// Decode reverts with `custom_errors` on top of the errors of the ABI
+    pub fn with_custom_errors(mut self, custom_errors: CustomErrors) -> Self {
+        self.custom_errors = custom_errors;
+        self
+    }
 }
 
 impl<M> HyperlaneChain for EthereumMultisigIsm<M>
@@ -108,7 +119,8 @@ where
             .contract
             .validators_and_threshold(RawHyperlaneMessage::from(message).to_vec().into())
             .call()
-            .await?;
+            .await
+            .map_err(|err| self.custom_errors.map_contract_error(err))?;
         let validators: Vec<H256> = validator_addresses.iter().map(|&x| H256::from(x)).collect();
         Ok((validators, threshold))
     }
@@ -16,7 +16,7 @@ use hyperlane_core::{
 use crate::interfaces::i_routing_ism::{
     IRoutingIsm as EthereumRoutingIsmInternal, IROUTINGISM_ABI,
 };
-use crate::{BuildableWithProvider, ConnectionConf, EthereumProvider};
+use crate::{BuildableWithProvider, ConnectionConf, CustomErrors, EthereumProvider};
 
 pub struct RoutingIsmBuilder {}
 
@@ -28,10 +28,13 @@ impl BuildableWithProvider for RoutingIsmBuilder {
     async fn build_with_provider<M: Middleware + 'static>(
         &self,
         provider: M,
-        _conn: &ConnectionConf,
+        conn: &ConnectionConf,
         locator: &ContractLocator,
     ) -> Self::Output {
-        Box::new(EthereumRoutingIsm::new(Arc::new(provider), locator))
+        Box::new(
+            EthereumRoutingIsm::new(Arc::new(provider), locator)
+                .with_custom_errors(conn.custom_errors.clone()),
+        )
     }
 }
 
@@ -43,6 +46,7 @@ where
 {
     contract: Arc<EthereumRoutingIsmInternal<M>>,
     domain: HyperlaneDomain,
+    custom_errors: CustomErrors,
 }
 
 impl<M> EthereumRoutingIsm<M>
@@ -55,8 +59,15 @@ where
         Self {
             contract: Arc::new(EthereumRoutingIsmInternal::new(locator.address, provider)),
             domain: locator.domain.clone(),
+            custom_errors: CustomErrors::default(),
         }
     }
+
+    /// Decode reverts with `custom_errors` on top of the errors of the ABI
+    pub fn with_custom_errors(mut self, custom_errors: CustomErrors) -> Self {
+        self.custom_errors = custom_errors;
+        self
+    }
 }
 
 impl<M> HyperlaneChain for EthereumRoutingIsm<M>
@@ -96,7 +107,8 @@ where
             .contract
             .route(RawHyperlaneMessage::from(message).to_vec().into())
             .call()
-            .await?;
+            .await
+            .map_err(|err| self.custom_errors.map_contract_error(err))?;
         Ok(ism.into())
     }
 }
@@ -8,7 +8,9 @@ use std::collections::HashMap;
 use ethers::abi::FunctionExt;
 use ethers::prelude::{abi, Lazy, Middleware};
 
-pub use self::{config::*, contracts::*, ism::*, rpc_clients::*, signer::*};
+pub use self::{
+    config::*, contracts::*, custom_errors::*, ism::*, rpc_clients::*, safe::*, signer::*,
+};
 
 mod tx;
 
@@ -24,7 +26,11 @@ mod rpc_clients;
 
 mod signer;
 
+/// Owner operations proposed through a Safe
+mod safe;
+
 mod config;
+mod custom_errors;
 mod error;
 
 fn extract_fn_map(abi: &'static Lazy<abi::Abi>) -> HashMap<Vec<u8>, &'static str> {
@@ -1,5 +1,5 @@
 use derive_new::new;
-use hyperlane_core::rpc_clients::{BlockNumberGetter, FallbackProvider};
+use hyperlane_core::rpc_clients::{BlockNumberGetter, FallbackProvider, RequestKind};
 use std::fmt::{Debug, Formatter};
 use std::ops::Deref;
 use std::time::Duration;
@@ -16,6 +16,10 @@ use ethers_prometheus::json_rpc_client::{JsonRpcBlockGetter, PrometheusJsonRpcCl
 
 use crate::rpc_clients::{categorize_client_response, CategorizedResponse};
 
+/// Methods that submit transactions, which are only sent to the providers
+/// serving writes
+const WRITE_METHODS: &[&str] = &["eth_sendRawTransaction", "eth_sendTransaction"];
+
 /// Wrapper of `FallbackProvider` for use in `hyperlane-ethereum`
 #[derive(new)]
 pub struct EthereumFallbackProvider<C, B>(FallbackProvider<C, B>);
@@ -64,6 +68,9 @@ pub enum FallbackError {
     /// All providers failed
     #[error("All providers failed. (Errors: {0:?})")]
     AllProvidersFailed(Vec<ProviderError>),
+    /// None of the providers is sent requests of this kind
+    #[error("No provider serves {0:?} requests")]
+    NoProviderForRequest(RequestKind),
 }
 
 impl From<FallbackError> for ProviderError {
@@ -93,6 +100,11 @@ where
     {
         use CategorizedResponse::*;
         let params = serde_json::to_value(params).expect("valid");
+        let kind = if WRITE_METHODS.contains(&method) {
+            RequestKind::Write
+        } else {
+            RequestKind::Read
+        };
 
         let mut errors = vec![];
         // make sure we do at least 4 total retries.
@@ -100,7 +112,10 @@ where
             if !errors.is_empty() {
                 sleep(Duration::from_millis(100)).await;
             }
-            let priorities_snapshot = self.take_priorities_snapshot().await;
+            let priorities_snapshot = self.take_priorities_snapshot_for(kind).await;
+            if priorities_snapshot.is_empty() {
+                return Err(FallbackError::NoProviderForRequest(kind).into());
+            }
             for (idx, priority) in priorities_snapshot.iter().enumerate() {
                 let provider = &self.inner.providers[priority.index];
                 let fut = match params {
@@ -128,7 +143,7 @@ where
 mod tests {
     use ethers_prometheus::json_rpc_client::{JsonRpcBlockGetter, BLOCK_NUMBER_RPC};
     use hyperlane_core::rpc_clients::test::ProviderMock;
-    use hyperlane_core::rpc_clients::FallbackProviderBuilder;
+    use hyperlane_core::rpc_clients::{FallbackProviderBuilder, ProviderCapability};
 
     use super::*;
 
@@ -239,6 +254,75 @@ mod tests {
         assert_eq!(provider_call_count, vec![0, 0, 2]);
     }
 
+    #[tokio::test]
+    async fn test_transactions_are_only_sent_to_write_providers() {
+        let fallback_provider = FallbackProviderBuilder::default()
+            .add_provider_with_capability(
+                EthereumProviderMock::default(),
+                ProviderCapability::ReadOnly,
+            )
+            .add_provider(EthereumProviderMock::default())
+            .add_provider_with_capability(
+                EthereumProviderMock::default(),
+                ProviderCapability::WriteOnly,
+            )
+            .build();
+        let ethereum_fallback_provider = EthereumFallbackProvider::new(fallback_provider);
+
+        ethereum_fallback_provider.low_level_test_call().await;
+        ethereum_fallback_provider
+            .request::<_, u64>("eth_sendRawTransaction", ())
+            .await
+            .unwrap();
+        let provider_call_count: Vec<_> =
+            ProviderMock::get_call_counts(&ethereum_fallback_provider).await;
+        assert_eq!(provider_call_count, vec![1, 1, 0]);
+
+        let read_only_provider = EthereumFallbackProvider::new(
+            FallbackProviderBuilder::default()
+                .add_provider_with_capability(
+                    EthereumProviderMock::default(),
+                    ProviderCapability::ReadOnly,
+                )
+                .build(),
+        );
+        assert!(read_only_provider
+            .request::<_, u64>("eth_sendRawTransaction", ())
+            .await
+            .is_err());
+        let provider_call_count: Vec<_> = ProviderMock::get_call_counts(&read_only_provider).await;
+        assert_eq!(provider_call_count, vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_load_balanced() {
+        let fallback_provider = FallbackProviderBuilder::default()
+            .add_provider(EthereumProviderMock::default())
+            .add_provider_with_capability(
+                EthereumProviderMock::default(),
+                ProviderCapability::WriteOnly,
+            )
+            .add_provider_with_capability(
+                EthereumProviderMock::default(),
+                ProviderCapability::ReadOnly,
+            )
+            .build();
+        let ethereum_fallback_provider = EthereumFallbackProvider::new(fallback_provider);
+
+        for _ in 0..4 {
+            ethereum_fallback_provider.low_level_test_call().await;
+        }
+        for _ in 0..2 {
+            ethereum_fallback_provider
+                .request::<_, u64>("eth_sendRawTransaction", ())
+                .await
+                .unwrap();
+        }
+        let provider_call_count: Vec<_> =
+            ProviderMock::get_call_counts(&ethereum_fallback_provider).await;
+        assert_eq!(provider_call_count, vec![4, 0, 2]);
+    }
+
     // TODO: make `categorize_client_response` generic over `ProviderError` to allow testing
     // two stalled providers (so that the for loop in `request` doesn't stop after the first provider)
 }
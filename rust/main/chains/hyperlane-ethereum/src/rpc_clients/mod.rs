@@ -1,10 +1,11 @@
 use ethers::providers::HttpClientError;
 use tracing::{error, info, trace, warn};
 
-pub use self::{fallback::*, provider::*, retrying::*, trait_builder::*};
+pub use self::{fallback::*, provider::*, rate_limited::*, retrying::*, trait_builder::*};
 
 mod fallback;
 mod provider;
+mod rate_limited;
 mod retrying;
 mod trait_builder;
 
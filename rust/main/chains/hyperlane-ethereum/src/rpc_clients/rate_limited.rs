@@ -0,0 +1,45 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ethers::providers::JsonRpcClient;
+use hyperlane_core::rpc_clients::RateLimiter;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// A JSON RPC client that waits for the rate limit of its endpoint, if any,
+/// before sending a request
+#[derive(Debug, Clone)]
+pub struct RateLimitedClient<C> {
+    inner: C,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl<C> RateLimitedClient<C> {
+    /// Instantiate a RateLimitedClient
+    pub fn new(inner: C, rate_limiter: Option<Arc<RateLimiter>>) -> Self {
+        Self {
+            inner,
+            rate_limiter,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<C> JsonRpcClient for RateLimitedClient<C>
+where
+    C: JsonRpcClient,
+{
+    type Error = C::Error;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        self.inner.request(method, params).await
+    }
+}
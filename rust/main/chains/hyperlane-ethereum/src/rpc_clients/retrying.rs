@@ -2,7 +2,7 @@ use std::{fmt::Debug, str::FromStr, time::Duration};
 
 use crate::rpc_clients::{categorize_client_response, CategorizedResponse};
 use async_trait::async_trait;
-use ethers::providers::{Http, JsonRpcClient, ProviderError};
+use ethers::providers::{HttpClientError, JsonRpcClient, ProviderError};
 use ethers_prometheus::json_rpc_client::{
     PrometheusJsonRpcClient, PrometheusJsonRpcClientConfigExt,
 };
@@ -168,8 +168,11 @@ where
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
-impl JsonRpcClient for RetryingProvider<PrometheusJsonRpcClient<Http>> {
-    type Error = RetryingProviderError<PrometheusJsonRpcClient<Http>>;
+impl<C> JsonRpcClient for RetryingProvider<PrometheusJsonRpcClient<C>>
+where
+    C: JsonRpcClient<Error = HttpClientError> + 'static,
+{
+    type Error = RetryingProviderError<PrometheusJsonRpcClient<C>>;
 
     #[instrument(skip(self), fields(provider_host = %self.inner.node_host(), chain_name = %self.inner.chain_name()))]
     async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
@@ -13,8 +13,8 @@ use ethers::prelude::{
 };
 use ethers::types::Address;
 use ethers_signers::Signer;
-use hyperlane_core::rpc_clients::FallbackProvider;
-use reqwest::{Client, Url};
+use hyperlane_core::rpc_clients::{FallbackProvider, RateLimiter};
+use reqwest::Url;
 use thiserror::Error;
 
 use ethers_prometheus::json_rpc_client::{
@@ -28,10 +28,10 @@ use hyperlane_core::{
 use tracing::instrument;
 
 use crate::signer::Signers;
-use crate::{ConnectionConf, EthereumFallbackProvider, RetryingProvider, RpcConnectionConf};
-
-// This should be whatever the prometheus scrape interval is
-const HTTP_CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
+use crate::{
+    ConnectionConf, EthereumFallbackProvider, RateLimitedClient, RetryingProvider,
+    RpcConnectionConf,
+};
 
 /// An error when connecting to an ethereum provider.
 #[derive(Error, Debug)]
@@ -39,6 +39,10 @@ pub enum EthereumProviderConnectionError {
     /// Underlying reqwest lib threw an error
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
+    /// The HTTP client couldn't be built with the configured proxy or
+    /// certificates
+    #[error(transparent)]
+    HttpClientError(#[from] reqwest_utils::HttpClientError),
     /// Underlying websocket library threw an error
     #[error(transparent)]
     WebsocketClientError(#[from] WsClientError),
@@ -73,12 +77,14 @@ pub trait BuildableWithProvider {
         Ok(match &conn.rpc_connection {
             RpcConnectionConf::HttpQuorum { urls } => {
                 let mut builder = QuorumProvider::builder().quorum(Quorum::Majority);
-                let http_client = Client::builder()
-                    .timeout(HTTP_CLIENT_TIMEOUT)
-                    .build()
-                    .map_err(EthereumProviderConnectionError::from)?;
                 for url in urls {
-                    let http_provider = Http::new_with_client(url.clone(), http_client.clone());
+                    let http_client = reqwest_utils::client_for(url)
+                        .map_err(EthereumProviderConnectionError::from)?;
+                    let http_provider = self.wrap_rpc_with_rate_limit(
+                        Http::new_with_client(url.clone(), http_client),
+                        url,
+                        conn,
+                    );
                     // Wrap the inner providers as RetryingProviders rather than the QuorumProvider.
                     // We've observed issues where the QuorumProvider will first get the latest
                     // block number and then submit an RPC at that block height,
@@ -102,36 +108,41 @@ pub trait BuildableWithProvider {
                 let quorum_provider = builder.build();
                 self.build(quorum_provider, conn, locator, signer).await?
             }
-            RpcConnectionConf::HttpFallback { urls } => {
+            RpcConnectionConf::HttpFallback { urls, capabilities } => {
                 let mut builder = FallbackProvider::builder();
-                let http_client = Client::builder()
-                    .timeout(HTTP_CLIENT_TIMEOUT)
-                    .build()
-                    .map_err(EthereumProviderConnectionError::from)?;
-                for url in urls {
-                    let http_provider = Http::new_with_client(url.clone(), http_client.clone());
+                for (i, url) in urls.iter().enumerate() {
+                    let http_client = reqwest_utils::client_for(url)
+                        .map_err(EthereumProviderConnectionError::from)?;
+                    let http_provider = self.wrap_rpc_with_rate_limit(
+                        Http::new_with_client(url.clone(), http_client),
+                        url,
+                        conn,
+                    );
                     let metrics_provider = self.wrap_rpc_with_metrics(
                         http_provider,
                         url.clone(),
                         &rpc_metrics,
                         &middleware_metrics,
                     );
-                    builder = builder.add_provider(metrics_provider);
+                    let capability = capabilities.get(i).copied().unwrap_or_default();
+                    builder = builder.add_provider_with_capability(metrics_provider, capability);
                 }
                 let fallback_provider = builder.build();
                 let ethereum_fallback_provider = EthereumFallbackProvider::<
                     _,
-                    JsonRpcBlockGetter<PrometheusJsonRpcClient<Http>>,
+                    JsonRpcBlockGetter<PrometheusJsonRpcClient<RateLimitedClient<Http>>>,
                 >::new(fallback_provider);
                 self.build(ethereum_fallback_provider, conn, locator, signer)
                     .await?
             }
             RpcConnectionConf::Http { url } => {
-                let http_client = Client::builder()
-                    .timeout(HTTP_CLIENT_TIMEOUT)
-                    .build()
+                let http_client = reqwest_utils::client_for(url)
                     .map_err(EthereumProviderConnectionError::from)?;
-                let http_provider = Http::new_with_client(url.clone(), http_client);
+                let http_provider = self.wrap_rpc_with_rate_limit(
+                    Http::new_with_client(url.clone(), http_client),
+                    url,
+                    conn,
+                );
                 let metrics_provider = self.wrap_rpc_with_metrics(
                     http_provider,
                     url.clone(),
@@ -146,11 +157,26 @@ pub trait BuildableWithProvider {
                 let ws = Ws::connect(url)
                     .await
                     .map_err(EthereumProviderConnectionError::from)?;
+                let ws = self.wrap_rpc_with_rate_limit(ws, url, conn);
                 self.build(ws, conn, locator, signer).await?
             }
         })
     }
 
+    /// Wrap a JsonRpcClient with the rate limit of its endpoint, if one is
+    /// configured.
+    fn wrap_rpc_with_rate_limit<C>(
+        &self,
+        client: C,
+        url: &Url,
+        conn: &ConnectionConf,
+    ) -> RateLimitedClient<C> {
+        let rate_limiter = conn
+            .rate_limit
+            .map(|conf| RateLimiter::for_endpoint(url.as_str(), conf));
+        RateLimitedClient::new(client, rate_limiter)
+    }
+
     /// Wrap a JsonRpcClient with metrics for use with a quorum provider.
     fn wrap_rpc_with_metrics<C>(
         &self,
@@ -0,0 +1,496 @@
+//! Proposes owner operations to a Safe (formerly Gnosis Safe) through the Safe
+//! transaction service, so that they are executed once enough of the Safe's
+//! owners have confirmed them, rather than being signed and sent directly.
+
+use std::time::Duration;
+
+use ethers::abi::{encode, Token};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712};
+use ethers::types::{Address, Bytes, U256 as EthersU256};
+use ethers::utils::{id, keccak256, to_checksum};
+use ethers_signers::Signer;
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Deserializer, Serialize};
+use tokio::time::sleep;
+use tracing::{debug, info};
+
+use crate::{HyperlaneEthereumError, Signers};
+
+/// keccak256("EIP712Domain(uint256 chainId,address verifyingContract)")
+const SAFE_DOMAIN_TYPEHASH: &str =
+    "47e79534a245952e8b16893a336b85a3d9ea9fa8c573f3d803afb92a79469218";
+
+/// keccak256("SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,
+/// uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)")
+const SAFE_TX_TYPEHASH: &str = "bb8310d486368db6bd6f849402fdd73ad53d316b5a4b2644ad6efe0f941286d8";
+
+/// Reported to the transaction service as the origin of proposals
+const SAFE_TX_ORIGIN: &str = "hyperlane-agents";
+
+/// An operation that can only be performed by the owner of a Hyperlane
+/// contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OwnerOperation {
+    /// Set the ISM of a mailbox client, e.g. a mailbox or warp route
+    SetInterchainSecurityModule {
+        /// The mailbox client
+        target: H256,
+        /// The new ISM
+        ism: H256,
+    },
+    /// Enroll the router of a remote domain on a router, e.g. a warp route
+    EnrollRemoteRouter {
+        /// The router
+        target: H256,
+        /// The remote domain
+        domain: u32,
+        /// The router on the remote domain
+        router: H256,
+    },
+    /// Transfer ownership of an ownable contract
+    TransferOwnership {
+        /// The ownable contract
+        target: H256,
+        /// The new owner
+        new_owner: H256,
+    },
+}
+
+impl OwnerOperation {
+    /// The contract the operation is called on.
+    pub fn target(&self) -> Address {
+        match self {
+            Self::SetInterchainSecurityModule { target, .. }
+            | Self::EnrollRemoteRouter { target, .. }
+            | Self::TransferOwnership { target, .. } => Address::from(*target),
+        }
+    }
+
+    /// The calldata of the operation.
+    pub fn calldata(&self) -> Bytes {
+        let (signature, args) = match self {
+            Self::SetInterchainSecurityModule { ism, .. } => (
+                "setInterchainSecurityModule(address)",
+                vec![Token::Address(Address::from(*ism))],
+            ),
+            Self::EnrollRemoteRouter { domain, router, .. } => (
+                "enrollRemoteRouter(uint32,bytes32)",
+                vec![
+                    Token::Uint((*domain).into()),
+                    Token::FixedBytes(router.as_bytes().to_vec()),
+                ],
+            ),
+            Self::TransferOwnership { new_owner, .. } => (
+                "transferOwnership(address)",
+                vec![Token::Address(Address::from(*new_owner))],
+            ),
+        };
+        [&id(signature)[..], &encode(&args)[..]].concat().into()
+    }
+}
+
+/// A transaction to be executed by a Safe, as defined by the Safe contracts.
+/// Refunds are not used, so the gas and refund parameters are always zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeTransaction {
+    /// The chain ID of the Safe
+    pub chain_id: u64,
+    /// The Safe executing the transaction
+    pub safe: Address,
+    /// The called contract
+    pub to: Address,
+    /// The calldata
+    pub data: Bytes,
+    /// The Safe nonce the transaction is executed at
+    pub nonce: u64,
+}
+
+impl Eip712 for SafeTransaction {
+    type Error = std::convert::Infallible;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: None,
+            version: None,
+            chain_id: Some(self.chain_id.into()),
+            verifying_contract: Some(self.safe),
+            salt: None,
+        })
+    }
+
+    fn domain_separator(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(hex::decode(SAFE_DOMAIN_TYPEHASH).unwrap()),
+            Token::Uint(self.chain_id.into()),
+            Token::Address(self.safe),
+        ])))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        let mut type_hash = [0; 32];
+        hex::decode_to_slice(SAFE_TX_TYPEHASH, &mut type_hash).unwrap();
+        Ok(type_hash)
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Address(self.to),
+            // value
+            Token::Uint(EthersU256::zero()),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            // operation, i.e. a call rather than a delegatecall
+            Token::Uint(EthersU256::zero()),
+            // safeTxGas
+            Token::Uint(EthersU256::zero()),
+            // baseGas
+            Token::Uint(EthersU256::zero()),
+            // gasPrice
+            Token::Uint(EthersU256::zero()),
+            // gasToken
+            Token::Address(Address::zero()),
+            // refundReceiver
+            Token::Address(Address::zero()),
+            Token::Uint(self.nonce.into()),
+        ])))
+    }
+}
+
+/// The status of a transaction proposed to the Safe transaction service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SafeTransactionStatus {
+    /// The transaction is waiting for confirmations or execution
+    Pending {
+        /// How many owners confirmed the transaction
+        confirmations: usize,
+        /// How many confirmations the transaction needs to be executable
+        required: u64,
+    },
+    /// The transaction was executed
+    Executed {
+        /// The hash of the executing transaction
+        transaction_hash: Option<H256>,
+        /// Whether the inner call succeeded
+        successful: bool,
+    },
+}
+
+/// Proposes owner operations on behalf of a Safe to the Safe transaction
+/// service. The proposer must be an owner or a delegate of the Safe.
+#[derive(Debug, Clone)]
+pub struct SafeSubmitter {
+    client: Client,
+    /// The base URL of the transaction service of the Safe's chain, e.g.
+    /// `https://safe-transaction-mainnet.safe.global`
+    service_url: Url,
+    chain_id: u64,
+    safe: Address,
+    proposer: Signers,
+}
+
+impl SafeSubmitter {
+    /// Create a submitter proposing transactions to `safe` on `chain_id`
+    /// through the transaction service at `service_url`.
+    pub fn new(
+        service_url: Url,
+        chain_id: u64,
+        safe: H256,
+        proposer: Signers,
+    ) -> ChainResult<Self> {
+        let client =
+            reqwest_utils::client_for(&service_url).map_err(ChainCommunicationError::from_other)?;
+        Ok(Self {
+            client,
+            service_url,
+            chain_id,
+            safe: safe.into(),
+            proposer,
+        })
+    }
+
+    /// Propose `operation` to the Safe, returning the Safe transaction hash it
+    /// can be tracked with.
+    pub async fn propose(&self, operation: &OwnerOperation) -> ChainResult<H256> {
+        let transaction = SafeTransaction {
+            chain_id: self.chain_id,
+            safe: self.safe,
+            to: operation.target(),
+            data: operation.calldata(),
+            nonce: self.next_nonce().await?,
+        };
+        let safe_tx_hash: H256 = transaction
+            .encode_eip712()
+            .expect("Safe transaction hashing is infallible")
+            .into();
+        let signature = self
+            .proposer
+            .sign_typed_data(&transaction)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        let proposal = SafeTransactionProposal {
+            to: to_checksum(&transaction.to, None),
+            value: "0".to_owned(),
+            data: Some(transaction.data.clone()),
+            operation: 0,
+            safe_tx_gas: "0".to_owned(),
+            base_gas: "0".to_owned(),
+            gas_price: "0".to_owned(),
+            gas_token: to_checksum(&Address::zero(), None),
+            refund_receiver: to_checksum(&Address::zero(), None),
+            nonce: transaction.nonce,
+            contract_transaction_hash: safe_tx_hash,
+            sender: to_checksum(&self.proposer.address(), None),
+            signature: signature.to_vec().into(),
+            origin: SAFE_TX_ORIGIN.to_owned(),
+        };
+        let url = self.url(&format!(
+            "api/v1/safes/{}/multisig-transactions/",
+            to_checksum(&self.safe, None)
+        ))?;
+        let response = self.client.post(url).json(&proposal).send().await;
+        Self::check_response(response).await?;
+
+        info!(
+            ?operation,
+            ?safe_tx_hash,
+            nonce = transaction.nonce,
+            safe = ?self.safe,
+            "Proposed owner operation to Safe"
+        );
+        Ok(safe_tx_hash)
+    }
+
+    /// The status of the proposed transaction with the given Safe transaction
+    /// hash.
+    pub async fn status(&self, safe_tx_hash: H256) -> ChainResult<SafeTransactionStatus> {
+        let url = self.url(&format!("api/v1/multisig-transactions/{safe_tx_hash:?}/"))?;
+        let response = Self::check_response(self.client.get(url).send().await).await?;
+        let transaction: SafeMultisigTransaction = response
+            .json()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(transaction.into())
+    }
+
+    /// Poll the status of the proposed transaction with the given Safe
+    /// transaction hash until it is executed.
+    pub async fn wait_for_execution(
+        &self,
+        safe_tx_hash: H256,
+        poll_interval: Duration,
+    ) -> ChainResult<SafeTransactionStatus> {
+        loop {
+            let status = self.status(safe_tx_hash).await?;
+            match status {
+                SafeTransactionStatus::Executed { .. } => return Ok(status),
+                SafeTransactionStatus::Pending {
+                    confirmations,
+                    required,
+                } => debug!(
+                    ?safe_tx_hash,
+                    confirmations, required, "Safe transaction not executed yet"
+                ),
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    /// The nonce to propose the next transaction at, after any transaction
+    /// that is already queued.
+    async fn next_nonce(&self) -> ChainResult<u64> {
+        let safe = to_checksum(&self.safe, None);
+        let url = self.url(&format!("api/v1/safes/{safe}/"))?;
+        let info: SafeInfo = Self::check_response(self.client.get(url).send().await)
+            .await?
+            .json()
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+
+        let mut url = self.url(&format!("api/v1/safes/{safe}/multisig-transactions/"))?;
+        url.query_pairs_mut()
+            .append_pair("executed", "false")
+            .append_pair("nonce__gte", &info.nonce.to_string())
+            .append_pair("ordering", "-nonce")
+            .append_pair("limit", "1");
+        let queued: SafeMultisigTransactionPage =
+            Self::check_response(self.client.get(url).send().await)
+                .await?
+                .json()
+                .await
+                .map_err(ChainCommunicationError::from_other)?;
+
+        Ok(queued
+            .results
+            .first()
+            .map_or(info.nonce, |queued| queued.nonce + 1))
+    }
+
+    fn url(&self, path: &str) -> ChainResult<Url> {
+        self.service_url
+            .join(path)
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn check_response(
+        response: reqwest::Result<reqwest::Response>,
+    ) -> ChainResult<reqwest::Response> {
+        let response = response.map_err(ChainCommunicationError::from_other)?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(HyperlaneEthereumError::SafeTransactionServiceError(format!("{status}: {body}")).into())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeTransactionProposal {
+    to: String,
+    value: String,
+    data: Option<Bytes>,
+    operation: u8,
+    safe_tx_gas: String,
+    base_gas: String,
+    gas_price: String,
+    gas_token: String,
+    refund_receiver: String,
+    nonce: u64,
+    contract_transaction_hash: H256,
+    sender: String,
+    signature: Bytes,
+    origin: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SafeInfo {
+    #[serde(deserialize_with = "deserialize_nonce")]
+    nonce: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SafeMultisigTransactionPage {
+    results: Vec<SafeMultisigTransaction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeMultisigTransaction {
+    #[serde(deserialize_with = "deserialize_nonce")]
+    nonce: u64,
+    is_executed: bool,
+    is_successful: Option<bool>,
+    transaction_hash: Option<H256>,
+    confirmations_required: u64,
+    #[serde(default)]
+    confirmations: Vec<serde_json::Value>,
+}
+
+impl From<SafeMultisigTransaction> for SafeTransactionStatus {
+    fn from(transaction: SafeMultisigTransaction) -> Self {
+        if transaction.is_executed {
+            SafeTransactionStatus::Executed {
+                transaction_hash: transaction.transaction_hash,
+                successful: transaction.is_successful.unwrap_or(false),
+            }
+        } else {
+            SafeTransactionStatus::Pending {
+                confirmations: transaction.confirmations.len(),
+                required: transaction.confirmations_required,
+            }
+        }
+    }
+}
+
+/// Nonces are returned as numbers by older versions of the transaction
+/// service, and as strings by newer ones.
+fn deserialize_nonce<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Nonce {
+        Number(u64),
+        String(String),
+    }
+
+    match Nonce::deserialize(deserializer)? {
+        Nonce::Number(nonce) => Ok(nonce),
+        Nonce::String(nonce) => nonce.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_owner_operation_calldata() {
+        let operation = OwnerOperation::TransferOwnership {
+            target: H256::repeat_byte(1),
+            new_owner: H256::from(Address::repeat_byte(2)),
+        };
+        let calldata = operation.calldata();
+        // transferOwnership(address)
+        assert_eq!(calldata[..4], hex::decode("f2fde38b").unwrap());
+        assert_eq!(calldata[16..], [2; 20]);
+        assert_eq!(operation.target(), Address::repeat_byte(1));
+    }
+
+    #[test]
+    fn test_safe_transaction_hash() {
+        let transaction = SafeTransaction {
+            chain_id: 1,
+            safe: Address::from_str("0x1111111111111111111111111111111111111111").unwrap(),
+            to: Address::from_str("0x2222222222222222222222222222222222222222").unwrap(),
+            data: Bytes::default(),
+            nonce: 0,
+        };
+        let mut typed_data_hash = vec![0x19, 0x01];
+        typed_data_hash.extend(transaction.domain_separator().unwrap());
+        typed_data_hash.extend(transaction.struct_hash().unwrap());
+
+        assert_eq!(
+            transaction.encode_eip712().unwrap(),
+            keccak256(typed_data_hash)
+        );
+        assert_eq!(
+            SafeTransaction::type_hash().unwrap(),
+            keccak256(
+                "SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,\
+                 uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,\
+                 uint256 nonce)"
+            )
+        );
+        assert_eq!(
+            hex::decode(SAFE_DOMAIN_TYPEHASH).unwrap(),
+            keccak256("EIP712Domain(uint256 chainId,address verifyingContract)")
+        );
+    }
+
+    #[test]
+    fn test_parse_multisig_transaction_status() {
+        let transaction: SafeMultisigTransaction = serde_json::from_str(
+            r#"{
+                "nonce": "7",
+                "isExecuted": false,
+                "isSuccessful": null,
+                "transactionHash": null,
+                "confirmationsRequired": 3,
+                "confirmations": [{"owner": "0x1111111111111111111111111111111111111111"}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(transaction.nonce, 7);
+        assert_eq!(
+            SafeTransactionStatus::from(transaction),
+            SafeTransactionStatus::Pending {
+                confirmations: 1,
+                required: 3
+            }
+        );
+    }
+}
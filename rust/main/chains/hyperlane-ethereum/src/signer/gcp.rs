@@ -0,0 +1,316 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ethers::core::k256::ecdsa::Signature as KSignature;
+use ethers::prelude::{Address, Signature, H256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::hash_message;
+use ethers_signers::{to_eip155_v, Signer};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use super::{address_of, with_recovery_id};
+
+const KMS_API_URL: &str = "https://cloudkms.googleapis.com/v1";
+/// Serves access tokens of the service account the agent runs as, which is
+/// how GKE workload identity is consumed
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+/// Access tokens are refreshed this long before they expire
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+/// Requests to GCP that take longer than this fail, so that a hanging
+/// connection doesn't stall the submission of transactions
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors of GCP KMS signers
+#[derive(Debug, thiserror::Error)]
+pub enum GcpKmsSignerError {
+    /// A request to GCP failed
+    #[error("GCP KMS request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    /// The HTTP client couldn't be built with the configured proxy or
+    /// certificates
+    #[error(transparent)]
+    HttpClient(#[from] reqwest_utils::HttpClientError),
+    /// GCP returned something that isn't a secp256k1 key or signature
+    #[error("Unexpected GCP KMS response: {0}")]
+    UnexpectedResponse(String),
+    /// The signature returned by KMS is invalid
+    #[error("{0}")]
+    Ecdsa(#[from] ethers::core::k256::ecdsa::Error),
+    /// The EIP-712 payload couldn't be encoded
+    #[error("Failed to encode EIP-712 payload: {0}")]
+    Eip712(String),
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    pem: String,
+}
+
+#[derive(Deserialize)]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+/// A client for a secp256k1 key version in GCP Cloud KMS, authenticated with
+/// the credentials of the workload the agent runs as
+#[derive(Clone)]
+pub struct GcpKmsClient {
+    http: reqwest::Client,
+    /// The resource name of the key version, i.e.
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`
+    key_name: String,
+    token: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+impl fmt::Debug for GcpKmsClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GcpKmsClient")
+            .field("key_name", &self.key_name)
+            .finish()
+    }
+}
+
+impl GcpKmsClient {
+    /// Create a client for the key version with the given resource name
+    pub fn new(http: reqwest::Client, key_name: impl Into<String>) -> Self {
+        Self {
+            http,
+            key_name: key_name.into(),
+            token: Default::default(),
+        }
+    }
+
+    /// Create a client for the key version with the given resource name that
+    /// sends requests over the shared HTTP client of the agent
+    pub fn with_shared_http_client(key_name: impl Into<String>) -> Result<Self, GcpKmsSignerError> {
+        let url = reqwest::Url::parse(KMS_API_URL).expect("KMS API URL is valid");
+        Ok(Self::new(reqwest_utils::client_for(&url)?, key_name))
+    }
+
+    /// An access token for KMS, which is cached until shortly before it
+    /// expires
+    async fn access_token(&self) -> Result<String, GcpKmsSignerError> {
+        let mut token = self.token.lock().await;
+        if let Some((access_token, expiry)) = token.as_ref() {
+            if Instant::now() < *expiry {
+                return Ok(access_token.clone());
+            }
+        }
+        let fetched: AccessToken = self
+            .http
+            .get(METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let expiry = Instant::now() + Duration::from_secs(fetched.expires_in)
+            - TOKEN_EXPIRY_MARGIN.min(Duration::from_secs(fetched.expires_in));
+        *token = Some((fetched.access_token.clone(), expiry));
+        Ok(fetched.access_token)
+    }
+
+    /// The uncompressed SEC1 encoding of the public key
+    pub async fn public_key(&self) -> Result<Vec<u8>, GcpKmsSignerError> {
+        let response: PublicKeyResponse = self
+            .http
+            .get(format!("{KMS_API_URL}/{}/publicKey", self.key_name))
+            .bearer_auth(self.access_token().await?)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        sec1_public_key_from_pem(&response.pem)
+    }
+
+    /// Sign a SHA-256 sized digest, returning a signature with a low `s`
+    pub async fn sign_digest(&self, digest: &[u8; 32]) -> Result<KSignature, GcpKmsSignerError> {
+        let response: AsymmetricSignResponse = self
+            .http
+            .post(format!("{KMS_API_URL}/{}:asymmetricSign", self.key_name))
+            .bearer_auth(self.access_token().await?)
+            .json(&serde_json::json!({ "digest": { "sha256": BASE64.encode(digest) } }))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        signature_from_response(&response.signature)
+    }
+}
+
+/// The signature of an `asymmetricSign` response, which is base64 and DER
+/// encoded, with a low `s`
+fn signature_from_response(signature: &str) -> Result<KSignature, GcpKmsSignerError> {
+    let der = BASE64
+        .decode(signature)
+        .map_err(|err| GcpKmsSignerError::UnexpectedResponse(err.to_string()))?;
+    let signature = KSignature::from_der(&der)?;
+    Ok(signature.normalize_s().unwrap_or(signature))
+}
+
+/// The uncompressed SEC1 encoding of a PEM encoded secp256k1 public key
+fn sec1_public_key_from_pem(pem: &str) -> Result<Vec<u8>, GcpKmsSignerError> {
+    let der = BASE64
+        .decode(
+            pem.lines()
+                .filter(|line| !line.starts_with("-----"))
+                .collect::<String>(),
+        )
+        .map_err(|err| GcpKmsSignerError::UnexpectedResponse(err.to_string()))?;
+    // The DER encoded SubjectPublicKeyInfo of a secp256k1 key ends with the
+    // 65 byte uncompressed SEC1 encoding of the key
+    der.len()
+        .checked_sub(65)
+        .map(|start| der[start..].to_vec())
+        .ok_or_else(|| GcpKmsSignerError::UnexpectedResponse("public key too short".into()))
+}
+
+/// An Ethereum signer using a secp256k1 key held in GCP Cloud KMS
+#[derive(Clone, Debug)]
+pub struct GcpKmsSigner {
+    client: GcpKmsClient,
+    address: Address,
+    chain_id: u64,
+}
+
+impl GcpKmsSigner {
+    /// Create a signer for the key of `client`, fetching its public key
+    pub async fn new(client: GcpKmsClient, chain_id: u64) -> Result<Self, GcpKmsSignerError> {
+        let public_key = client.public_key().await?;
+        let address = address_of(&public_key);
+        Ok(Self {
+            client,
+            address,
+            chain_id,
+        })
+    }
+
+    /// Sign a digest, with a `v` of 27 or 28
+    async fn sign_digest(&self, digest: H256) -> Result<Signature, GcpKmsSignerError> {
+        let signature = self.client.sign_digest(digest.as_fixed_bytes()).await?;
+        with_recovery_id(&signature, digest, self.address).ok_or_else(|| {
+            GcpKmsSignerError::UnexpectedResponse(
+                "signature doesn't recover to the key's address".into(),
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for GcpKmsSigner {
+    type Error = GcpKmsSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_digest(hash_message(message)).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        let chain_id = tx.chain_id().map_or(self.chain_id, |id| id.as_u64());
+        tx.set_chain_id(chain_id);
+        let mut signature = self.sign_digest(tx.sighash()).await?;
+        signature.v = to_eip155_v((signature.v - 27) as u8, chain_id);
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let digest = payload
+            .encode_eip712()
+            .map_err(|err| GcpKmsSignerError::Eip712(err.to_string()))?;
+        self.sign_digest(digest.into()).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::core::k256::ecdsa::signature::hazmat::PrehashSigner;
+    use ethers::core::k256::elliptic_curve::sec1::ToEncodedPoint;
+    use ethers_signers::LocalWallet;
+
+    use super::*;
+
+    #[test]
+    fn test_signature_from_response() {
+        let wallet: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let digest = hash_message("hello");
+        let signature: KSignature = wallet.signer().sign_prehash(digest.as_bytes()).unwrap();
+        let response = BASE64.encode(signature.to_der().as_bytes());
+
+        let parsed = signature_from_response(&response).unwrap();
+        assert_eq!(parsed, signature);
+        let signature = with_recovery_id(&parsed, digest, wallet.address()).unwrap();
+        assert_eq!(signature.recover(digest).unwrap(), wallet.address());
+        // The signature doesn't recover to the addresses of other keys
+        assert!(with_recovery_id(&parsed, digest, Address::repeat_byte(1)).is_none());
+
+        assert!(signature_from_response("not base64!").is_err());
+        assert!(signature_from_response(&BASE64.encode([1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn test_address_of_pem_public_key() {
+        let wallet: LocalWallet =
+            "1111111111111111111111111111111111111111111111111111111111111111"
+                .parse()
+                .unwrap();
+        let sec1 = wallet
+            .signer()
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec();
+        // The SubjectPublicKeyInfo header of secp256k1 keys
+        let mut der = hex::decode("3056301006072a8648ce3d020106052b8104000a034200").unwrap();
+        der.extend_from_slice(&sec1);
+        let pem = format!(
+            "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+            BASE64.encode(der)
+        );
+
+        let public_key = sec1_public_key_from_pem(&pem).unwrap();
+        assert_eq!(public_key, sec1);
+        assert_eq!(address_of(&public_key), wallet.address());
+    }
+}
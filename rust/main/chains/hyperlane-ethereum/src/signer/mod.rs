@@ -1,14 +1,22 @@
 use async_trait::async_trait;
-use ethers::prelude::{Address, Signature};
+use ethers::core::k256::ecdsa::Signature as KSignature;
+use ethers::prelude::{Address, Signature, U256};
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::keccak256;
 use ethers_signers::{AwsSigner, AwsSignerError, LocalWallet, Signer, WalletError};
 
 use hyperlane_core::{
     HyperlaneSigner, HyperlaneSignerError, Signature as HyperlaneSignature, H160, H256,
 };
 
+mod gcp;
+mod pkcs11;
+mod remote;
 mod singleton;
+pub use gcp::*;
+pub use pkcs11::*;
+pub use remote::*;
 pub use singleton::*;
 
 /// Ethereum-supported signer types
@@ -18,6 +26,12 @@ pub enum Signers {
     Local(LocalWallet),
     /// A signer using a key stored in aws kms
     Aws(AwsSigner),
+    /// A signer using a key stored in gcp cloud kms
+    Gcp(GcpKmsSigner),
+    /// A signer using a key stored in an hsm, accessed through pkcs#11
+    Pkcs11(Pkcs11Signer),
+    /// A signer using a key held by a remote signing service
+    Remote(RemoteSigner),
 }
 
 impl From<LocalWallet> for Signers {
@@ -32,6 +46,24 @@ impl From<AwsSigner> for Signers {
     }
 }
 
+impl From<GcpKmsSigner> for Signers {
+    fn from(s: GcpKmsSigner) -> Self {
+        Signers::Gcp(s)
+    }
+}
+
+impl From<Pkcs11Signer> for Signers {
+    fn from(s: Pkcs11Signer) -> Self {
+        Signers::Pkcs11(s)
+    }
+}
+
+impl From<RemoteSigner> for Signers {
+    fn from(s: RemoteSigner) -> Self {
+        Signers::Remote(s)
+    }
+}
+
 #[async_trait]
 impl Signer for Signers {
     type Error = SignersError;
@@ -43,6 +75,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_message(message).await?),
             Signers::Aws(signer) => Ok(signer.sign_message(message).await?),
+            Signers::Gcp(signer) => Ok(signer.sign_message(message).await?),
+            Signers::Pkcs11(signer) => Ok(signer.sign_message(message).await?),
+            Signers::Remote(signer) => Ok(signer.sign_message(message).await?),
         }
     }
 
@@ -50,6 +85,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_transaction(message).await?),
             Signers::Aws(signer) => Ok(signer.sign_transaction(message).await?),
+            Signers::Gcp(signer) => Ok(signer.sign_transaction(message).await?),
+            Signers::Pkcs11(signer) => Ok(signer.sign_transaction(message).await?),
+            Signers::Remote(signer) => Ok(signer.sign_transaction(message).await?),
         }
     }
 
@@ -60,6 +98,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => Ok(signer.sign_typed_data(payload).await?),
             Signers::Aws(signer) => Ok(signer.sign_typed_data(payload).await?),
+            Signers::Gcp(signer) => Ok(signer.sign_typed_data(payload).await?),
+            Signers::Pkcs11(signer) => Ok(signer.sign_typed_data(payload).await?),
+            Signers::Remote(signer) => Ok(signer.sign_typed_data(payload).await?),
         }
     }
 
@@ -67,6 +108,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.address(),
             Signers::Aws(signer) => signer.address(),
+            Signers::Gcp(signer) => signer.address(),
+            Signers::Pkcs11(signer) => signer.address(),
+            Signers::Remote(signer) => signer.address(),
         }
     }
 
@@ -74,6 +118,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.chain_id(),
             Signers::Aws(signer) => signer.chain_id(),
+            Signers::Gcp(signer) => signer.chain_id(),
+            Signers::Pkcs11(signer) => signer.chain_id(),
+            Signers::Remote(signer) => signer.chain_id(),
         }
     }
 
@@ -81,6 +128,9 @@ impl Signer for Signers {
         match self {
             Signers::Local(signer) => signer.with_chain_id(chain_id).into(),
             Signers::Aws(signer) => signer.with_chain_id(chain_id).into(),
+            Signers::Gcp(signer) => signer.with_chain_id(chain_id).into(),
+            Signers::Pkcs11(signer) => signer.with_chain_id(chain_id).into(),
+            Signers::Remote(signer) => signer.with_chain_id(chain_id).into(),
         }
     }
 }
@@ -100,12 +150,45 @@ impl HyperlaneSigner for Signers {
     }
 }
 
+/// The address of an uncompressed SEC1 encoded secp256k1 public key
+fn address_of(sec1_public_key: &[u8]) -> Address {
+    Address::from_slice(&keccak256(&sec1_public_key[1..])[12..])
+}
+
+/// Remote keys only return the `r` and `s` of signatures, so the recovery id is
+/// picked as the one recovering to the key's address. Returns the signature
+/// with a `v` of 27 or 28, or `None` if neither recovers to `address`.
+fn with_recovery_id(
+    signature: &KSignature,
+    digest: ethers::types::H256,
+    address: Address,
+) -> Option<Signature> {
+    let (r, s) = signature.split_bytes();
+    [27, 28]
+        .into_iter()
+        .map(|v| Signature {
+            r: U256::from_big_endian(&r),
+            s: U256::from_big_endian(&s),
+            v,
+        })
+        .find(|signature| signature.recover(digest).ok() == Some(address))
+}
+
 /// Error types for Signers
 #[derive(Debug, thiserror::Error)]
 pub enum SignersError {
     /// AWS Signer Error
     #[error("{0}")]
     AwsSignerError(#[from] AwsSignerError),
+    /// GCP KMS Signer Error
+    #[error("{0}")]
+    GcpKmsSignerError(#[from] GcpKmsSignerError),
+    /// PKCS#11 Signer Error
+    #[error("{0}")]
+    Pkcs11SignerError(#[from] Pkcs11SignerError),
+    /// Remote Signer Error
+    #[error("{0}")]
+    RemoteSignerError(#[from] RemoteSignerError),
     /// Wallet Signer Error
     #[error("{0}")]
     WalletError(#[from] WalletError),
@@ -123,7 +206,7 @@ mod test {
         Checkpoint, CheckpointWithMessageId, HyperlaneSigner, HyperlaneSignerExt, H256,
     };
 
-    use super::Signers;
+    use super::{with_recovery_id, Signers};
 
     #[test]
     fn it_sign() {
@@ -144,7 +227,8 @@ mod test {
             };
 
             let signed = signer.sign(message).await.expect("!sign");
-            assert!(signed.signature.v == 27 || signed.signature.v == 28);
+            let signature = signed.signature.ecdsa().expect("!ecdsa");
+            assert!(signature.v == 27 || signature.v == 28);
             signed.verify(signer.eth_address()).expect("!verify");
         };
         tokio::runtime::Builder::new_current_thread()
@@ -153,4 +237,23 @@ mod test {
             .unwrap()
             .block_on(t)
     }
+
+    #[test]
+    fn test_with_recovery_id() {
+        use ethers::core::k256::ecdsa::{signature::hazmat::PrehashSigner, RecoveryId, Signature};
+        use ethers::signers::Signer;
+
+        let wallet = "1111111111111111111111111111111111111111111111111111111111111111"
+            .parse::<ethers::signers::LocalWallet>()
+            .unwrap();
+        let digest = ethers::types::H256::repeat_byte(7);
+        let (signature, _): (Signature, RecoveryId) =
+            wallet.signer().sign_prehash(&digest.0).unwrap();
+
+        let recoverable = with_recovery_id(&signature, digest, wallet.address()).unwrap();
+        assert!(recoverable.v == 27 || recoverable.v == 28);
+        assert_eq!(recoverable.recover(digest).unwrap(), wallet.address());
+        // Neither recovery id matches another address
+        assert!(with_recovery_id(&signature, digest, Default::default()).is_none());
+    }
 }
@@ -0,0 +1,517 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    mechanism::Mechanism,
+    object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+    session::{Session, UserType},
+    types::AuthPin,
+};
+use ethers::core::k256::ecdsa::Signature as KSignature;
+use ethers::prelude::{Address, Signature, H256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::utils::hash_message;
+use ethers_signers::{to_eip155_v, Signer};
+use hyperlane_core::metrics::hsm::HsmMetrics;
+use tracing::warn;
+
+use super::{address_of, with_recovery_id};
+
+/// How often the keys held in HSMs are health checked
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Errors of PKCS#11 signers
+#[derive(Debug, thiserror::Error)]
+pub enum Pkcs11SignerError {
+    /// The HSM returned an error
+    #[error("PKCS#11 error: {0}")]
+    Pkcs11(#[from] cryptoki::error::Error),
+    /// No token or key matches the configuration
+    #[error("{0}")]
+    NotFound(String),
+    /// The key isn't a secp256k1 key
+    #[error("Key `{0}` is not a secp256k1 key")]
+    WrongKeyType(String),
+    /// The HSM returned something that isn't a valid key or signature
+    #[error("Unexpected PKCS#11 response: {0}")]
+    UnexpectedResponse(String),
+    /// The signature returned by the HSM is invalid
+    #[error("{0}")]
+    Ecdsa(#[from] ethers::core::k256::ecdsa::Error),
+    /// The EIP-712 payload couldn't be encoded
+    #[error("Failed to encode EIP-712 payload: {0}")]
+    Eip712(String),
+    /// A blocking HSM call panicked
+    #[error("PKCS#11 call panicked: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+/// Identifies a key held in an HSM
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Pkcs11KeyConf {
+    /// The path of the PKCS#11 module of the HSM, e.g.
+    /// `/usr/lib/libyubihsm_pkcs11.so`
+    pub module: PathBuf,
+    /// The label of the token holding the key
+    pub token_label: String,
+    /// The label of the key, shared by its private and public key objects
+    pub key_label: String,
+    /// The PIN of the token's user
+    pub pin: String,
+}
+
+impl fmt::Debug for Pkcs11KeyConf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkcs11KeyConf")
+            .field("module", &self.module)
+            .field("token_label", &self.token_label)
+            .field("key_label", &self.key_label)
+            .finish()
+    }
+}
+
+/// A logged in session with a token. The PKCS#11 user is logged in per token
+/// rather than per session, so all keys of a token share one session.
+struct Pkcs11Token {
+    pkcs11: Pkcs11,
+    label: String,
+    pin: String,
+    session: Mutex<Session>,
+}
+
+impl Pkcs11Token {
+    fn open(pkcs11: Pkcs11, label: String, pin: String) -> Result<Self, Pkcs11SignerError> {
+        let session = Self::open_session(&pkcs11, &label, &pin)?;
+        Ok(Self {
+            pkcs11,
+            label,
+            pin,
+            session: Mutex::new(session),
+        })
+    }
+
+    /// Open a session with the token labeled `label` and log in
+    fn open_session(pkcs11: &Pkcs11, label: &str, pin: &str) -> Result<Session, Pkcs11SignerError> {
+        let slot = pkcs11
+            .get_slots_with_token()?
+            .into_iter()
+            .find(|slot| {
+                pkcs11
+                    .get_token_info(*slot)
+                    .map_or(false, |info| info.label() == label)
+            })
+            .ok_or_else(|| Pkcs11SignerError::NotFound(format!("No token labeled `{label}`")))?;
+        let session = pkcs11.open_ro_session(slot)?;
+        session.login(UserType::User, Some(&AuthPin::new(pin.to_owned())))?;
+        Ok(session)
+    }
+
+    /// Replace `session` if it's no longer alive, e.g. after the HSM
+    /// restarted
+    fn reopen_session_if_dead(&self, session: &mut Session) -> Result<(), Pkcs11SignerError> {
+        if session.get_session_info().is_err() {
+            *session = Self::open_session(&self.pkcs11, &self.label, &self.pin)?;
+        }
+        Ok(())
+    }
+}
+
+/// The handles of the private and public key objects of a key
+#[derive(Clone, Copy)]
+struct KeyObjects {
+    private_key: ObjectHandle,
+    public_key: ObjectHandle,
+}
+
+impl KeyObjects {
+    /// Find the secp256k1 key labeled `label`
+    fn find(session: &Session, label: &str) -> Result<Self, Pkcs11SignerError> {
+        let find = |class| -> Result<ObjectHandle, Pkcs11SignerError> {
+            session
+                .find_objects(&[
+                    Attribute::Class(class),
+                    Attribute::Label(label.as_bytes().to_vec()),
+                ])?
+                .into_iter()
+                .next()
+                .ok_or_else(|| Pkcs11SignerError::NotFound(format!("No key labeled `{label}`")))
+        };
+        let private_key = find(ObjectClass::PRIVATE_KEY)?;
+        let public_key = find(ObjectClass::PUBLIC_KEY)?;
+        match session
+            .get_attributes(private_key, &[AttributeType::KeyType])?
+            .into_iter()
+            .next()
+        {
+            Some(Attribute::KeyType(KeyType::EC)) => Ok(Self {
+                private_key,
+                public_key,
+            }),
+            _ => Err(Pkcs11SignerError::WrongKeyType(label.to_owned())),
+        }
+    }
+}
+
+/// PKCS#11 modules can only be initialized once per process, and users only
+/// logged in once per token, so modules, tokens and keys are shared by all
+/// signers of the agent.
+#[derive(Default)]
+struct Pkcs11Registry {
+    modules: HashMap<PathBuf, Pkcs11>,
+    tokens: HashMap<(PathBuf, String), Arc<Pkcs11Token>>,
+    keys: HashMap<Pkcs11KeyConf, Arc<Pkcs11Key>>,
+}
+
+static REGISTRY: OnceLock<Mutex<Pkcs11Registry>> = OnceLock::new();
+
+/// A secp256k1 key held in an HSM, accessed through PKCS#11
+pub struct Pkcs11Key {
+    label: String,
+    token: Arc<Pkcs11Token>,
+    /// Only accessed while holding the token's session, and found again when
+    /// reconnecting
+    objects: Mutex<KeyObjects>,
+}
+
+impl fmt::Debug for Pkcs11Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkcs11Key")
+            .field("label", &self.label)
+            .finish()
+    }
+}
+
+impl Pkcs11Key {
+    /// Open the key identified by `conf`, or return it if it's open already.
+    /// Newly opened keys are health checked periodically.
+    pub async fn open(conf: Pkcs11KeyConf) -> Result<Arc<Self>, Pkcs11SignerError> {
+        let (key, opened) = tokio::task::spawn_blocking(move || {
+            let mut registry = REGISTRY
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(key) = registry.keys.get(&conf) {
+                return Ok((key.clone(), false));
+            }
+            let key = Arc::new(Self::open_uncached(&mut registry, &conf)?);
+            registry.keys.insert(conf, key.clone());
+            Ok::<_, Pkcs11SignerError>((key, true))
+        })
+        .await??;
+        if opened {
+            tokio::spawn(key.clone().run_health_checks());
+        }
+        Ok(key)
+    }
+
+    fn open_uncached(
+        registry: &mut Pkcs11Registry,
+        conf: &Pkcs11KeyConf,
+    ) -> Result<Self, Pkcs11SignerError> {
+        let token_id = (conf.module.clone(), conf.token_label.clone());
+        let token = match registry.tokens.get(&token_id) {
+            Some(token) => token.clone(),
+            None => {
+                let pkcs11 = match registry.modules.get(&conf.module) {
+                    Some(pkcs11) => pkcs11.clone(),
+                    None => {
+                        let pkcs11 = Pkcs11::new(&conf.module)?;
+                        pkcs11.initialize(CInitializeArgs::OsThreads)?;
+                        registry.modules.insert(conf.module.clone(), pkcs11.clone());
+                        pkcs11
+                    }
+                };
+                let token = Arc::new(Pkcs11Token::open(
+                    pkcs11,
+                    conf.token_label.clone(),
+                    conf.pin.clone(),
+                )?);
+                registry.tokens.insert(token_id, token.clone());
+                token
+            }
+        };
+
+        let session = token.session.lock().unwrap_or_else(PoisonError::into_inner);
+        let objects = KeyObjects::find(&session, &conf.key_label)?;
+        drop(session);
+
+        Ok(Self {
+            label: conf.key_label.clone(),
+            token,
+            objects: Mutex::new(objects),
+        })
+    }
+
+    /// The label of the key
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Run a blocking operation with the token's session, recording its
+    /// latency
+    async fn with_session<T, F>(
+        self: &Arc<Self>,
+        operation: &'static str,
+        f: F,
+    ) -> Result<T, Pkcs11SignerError>
+    where
+        T: Send + 'static,
+        F: FnOnce(KeyObjects, &mut Session) -> Result<T, Pkcs11SignerError> + Send + 'static,
+    {
+        let key = self.clone();
+        let start = Instant::now();
+        let res = tokio::task::spawn_blocking(move || {
+            let mut session = key
+                .token
+                .session
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let objects = *key.objects.lock().unwrap_or_else(PoisonError::into_inner);
+            f(objects, &mut *session)
+        })
+        .await
+        .map_err(Pkcs11SignerError::from)
+        .and_then(|res| res);
+        if let Some(metrics) = HsmMetrics::installed() {
+            metrics.record_operation(&self.label, operation, res.is_ok(), start.elapsed());
+        }
+        res
+    }
+
+    /// The uncompressed SEC1 encoding of the public key
+    pub async fn public_key(self: &Arc<Self>) -> Result<Vec<u8>, Pkcs11SignerError> {
+        self.with_session("public_key", |objects, session| {
+            let Some(Attribute::EcPoint(point)) = session
+                .get_attributes(objects.public_key, &[AttributeType::EcPoint])?
+                .into_iter()
+                .next()
+            else {
+                return Err(Pkcs11SignerError::UnexpectedResponse(
+                    "public key has no EC point".into(),
+                ));
+            };
+            sec1_public_key_from_ec_point(&point)
+        })
+        .await
+    }
+
+    /// Sign a 32 byte digest, returning a signature with a low `s`
+    pub async fn sign_secp256k1_digest(
+        self: &Arc<Self>,
+        digest: [u8; 32],
+    ) -> Result<KSignature, Pkcs11SignerError> {
+        self.with_session("sign_secp256k1", move |objects, session| {
+            let signature = session.sign(&Mechanism::Ecdsa, objects.private_key, &digest)?;
+            signature_from_raw(&signature)
+        })
+        .await
+    }
+
+    /// Check that the session is alive and the key is still accessible
+    pub async fn health_check(self: &Arc<Self>) -> Result<(), Pkcs11SignerError> {
+        self.with_session("health_check", |objects, session| {
+            session.get_session_info()?;
+            session.get_attributes(objects.private_key, &[AttributeType::Label])?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Reopen the token's session if it died, e.g. because the HSM
+    /// restarted, and find the key again, as its handles may have changed
+    pub async fn reconnect(self: &Arc<Self>) -> Result<(), Pkcs11SignerError> {
+        let key = self.clone();
+        self.with_session("reconnect", move |_, session| {
+            key.token.reopen_session_if_dead(session)?;
+            let objects = KeyObjects::find(session, &key.label)?;
+            *key.objects.lock().unwrap_or_else(PoisonError::into_inner) = objects;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn run_health_checks(self: Arc<Self>) {
+        loop {
+            let mut res = self.health_check().await;
+            if let Err(err) = &res {
+                warn!(key = %self.label, ?err, "HSM key health check failed, reconnecting");
+                res = match self.reconnect().await {
+                    Ok(()) => self.health_check().await,
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = &res {
+                    warn!(key = %self.label, ?err, "Failed to reconnect to the HSM key");
+                }
+            }
+            if let Some(metrics) = HsmMetrics::installed() {
+                metrics.set_healthy(&self.label, res.is_ok());
+            }
+            tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+        }
+    }
+}
+
+/// The uncompressed SEC1 encoding of a secp256k1 public key from its
+/// `CKA_EC_POINT`, which is a DER encoded octet string ending with it
+fn sec1_public_key_from_ec_point(point: &[u8]) -> Result<Vec<u8>, Pkcs11SignerError> {
+    point
+        .len()
+        .checked_sub(65)
+        .map(|start| point[start..].to_vec())
+        .filter(|key| key[0] == 0x04)
+        .ok_or_else(|| {
+            Pkcs11SignerError::UnexpectedResponse("not an uncompressed secp256k1 public key".into())
+        })
+}
+
+/// A signature with a low `s` from the raw `r || s` that `CKM_ECDSA` returns
+fn signature_from_raw(signature: &[u8]) -> Result<KSignature, Pkcs11SignerError> {
+    let signature = KSignature::try_from(signature)?;
+    Ok(signature.normalize_s().unwrap_or(signature))
+}
+
+/// An Ethereum signer using a secp256k1 key held in an HSM
+#[derive(Clone, Debug)]
+pub struct Pkcs11Signer {
+    key: Arc<Pkcs11Key>,
+    address: Address,
+    chain_id: u64,
+}
+
+impl Pkcs11Signer {
+    /// Create a signer for the key, fetching its public key
+    pub async fn new(key: Arc<Pkcs11Key>, chain_id: u64) -> Result<Self, Pkcs11SignerError> {
+        let address = address_of(&key.public_key().await?);
+        Ok(Self {
+            key,
+            address,
+            chain_id,
+        })
+    }
+
+    /// Sign a digest, with a `v` of 27 or 28
+    async fn sign_digest(&self, digest: H256) -> Result<Signature, Pkcs11SignerError> {
+        let signature = self.key.sign_secp256k1_digest(digest.0).await?;
+        with_recovery_id(&signature, digest, self.address).ok_or_else(|| {
+            Pkcs11SignerError::UnexpectedResponse(
+                "signature doesn't recover to the key's address".into(),
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for Pkcs11Signer {
+    type Error = Pkcs11SignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        self.sign_digest(hash_message(message)).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        let chain_id = tx.chain_id().map_or(self.chain_id, |id| id.as_u64());
+        tx.set_chain_id(chain_id);
+        let mut signature = self.sign_digest(tx.sighash()).await?;
+        signature.v = to_eip155_v((signature.v - 27) as u8, chain_id);
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let digest = payload
+            .encode_eip712()
+            .map_err(|err| Pkcs11SignerError::Eip712(err.to_string()))?;
+        self.sign_digest(digest.into()).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::core::k256::ecdsa::signature::hazmat::PrehashSigner;
+    use ethers::core::k256::elliptic_curve::sec1::ToEncodedPoint;
+    use ethers::types::U256;
+    use ethers_signers::LocalWallet;
+
+    use super::*;
+
+    /// The order of the secp256k1 group
+    const SECP256K1_N: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+    fn wallet() -> LocalWallet {
+        "1111111111111111111111111111111111111111111111111111111111111111"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signature_from_raw() {
+        let wallet = wallet();
+        let digest = hash_message("hello");
+        let signature: KSignature = wallet.signer().sign_prehash(digest.as_bytes()).unwrap();
+        let (r, s) = signature.split_bytes();
+
+        let raw = [r.as_slice(), s.as_slice()].concat();
+        assert_eq!(signature_from_raw(&raw).unwrap(), signature);
+
+        // HSMs may return a high `s`, which Ethereum rejects
+        let high_s = U256::from_str_radix(SECP256K1_N, 16).unwrap() - U256::from_big_endian(&s);
+        let mut high_s_bytes = [0u8; 32];
+        high_s.to_big_endian(&mut high_s_bytes);
+        let raw = [r.as_slice(), &high_s_bytes].concat();
+        let normalized = signature_from_raw(&raw).unwrap();
+        assert_eq!(normalized, signature);
+        let signature = with_recovery_id(&normalized, digest, wallet.address()).unwrap();
+        assert_eq!(signature.recover(digest).unwrap(), wallet.address());
+
+        assert!(signature_from_raw(&raw[..63]).is_err());
+    }
+
+    #[test]
+    fn test_sec1_public_key_from_ec_point() {
+        let wallet = wallet();
+        let sec1 = wallet
+            .signer()
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec();
+        // The DER octet string header
+        let mut point = vec![0x04, 0x41];
+        point.extend_from_slice(&sec1);
+
+        let public_key = sec1_public_key_from_ec_point(&point).unwrap();
+        assert_eq!(public_key, sec1);
+        assert_eq!(address_of(&public_key), wallet.address());
+
+        assert!(sec1_public_key_from_ec_point(&sec1[1..]).is_err());
+        let compressed = wallet.signer().verifying_key().to_encoded_point(true);
+        assert!(sec1_public_key_from_ec_point(compressed.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use std::{fmt, path::PathBuf, time::Duration};
+
+use async_trait::async_trait;
+use ethers::core::k256::ecdsa::Signature as KSignature;
+use ethers::prelude::{Address, Signature, H256};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::types::Bytes;
+use ethers::utils::hash_message;
+use ethers_signers::{to_eip155_v, Signer};
+use reqwest::{Certificate, Identity};
+use reqwest_utils::HttpClientConf;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{address_of, with_recovery_id};
+
+/// Timeout of requests to the signing service
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors of remote signers
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    /// A request to the signing service failed
+    #[error("Remote signer request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    /// The HTTP client couldn't be built with the configured proxy
+    #[error(transparent)]
+    HttpClient(#[from] reqwest_utils::HttpClientError),
+    /// A certificate or key couldn't be read
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        /// The path of the file
+        path: PathBuf,
+        /// The underlying error
+        source: std::io::Error,
+    },
+    /// The signing service isn't configured correctly
+    #[error("Invalid remote signer config: {0}")]
+    Config(String),
+    /// The signing service returned something that isn't a secp256k1 key or
+    /// signature
+    #[error("Unexpected remote signer response: {0}")]
+    UnexpectedResponse(String),
+    /// The signature returned by the signing service is invalid
+    #[error("{0}")]
+    Ecdsa(#[from] ethers::core::k256::ecdsa::Error),
+    /// The EIP-712 payload couldn't be encoded
+    #[error("Failed to encode EIP-712 payload: {0}")]
+    Eip712(String),
+}
+
+/// Config of a remote signing service, which is authenticated with mutual
+/// TLS
+#[derive(Clone, PartialEq, Eq)]
+pub struct RemoteSignerConf {
+    /// Base URL of the signing service, which must be `https`
+    pub url: Url,
+    /// Path of the PEM encoded CA certificate the certificate of the signing
+    /// service is verified against
+    pub ca_cert: PathBuf,
+    /// Path of the PEM encoded certificate the agent authenticates with
+    pub client_cert: PathBuf,
+    /// Path of the PEM encoded PKCS#8 private key of the client certificate
+    pub client_key: PathBuf,
+}
+
+impl fmt::Debug for RemoteSignerConf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The URL may contain credentials
+        f.debug_struct("RemoteSignerConf")
+            .field("host", &self.url.host_str())
+            .field("ca_cert", &self.ca_cert)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .finish()
+    }
+}
+
+/// What a digest sent to the signing service is the digest of, so that the
+/// service can apply policies, e.g. only signing checkpoints
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum SigningPayload {
+    /// An EIP-191 message, e.g. the hash of a checkpoint
+    Message { message: Bytes },
+    /// A transaction
+    #[serde(rename_all = "camelCase")]
+    Transaction { chain_id: u64 },
+    /// EIP-712 typed data
+    TypedData,
+}
+
+#[derive(Serialize)]
+struct SignRequest {
+    digest: H256,
+    #[serde(flatten)]
+    payload: SigningPayload,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeyResponse {
+    public_key: Bytes,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: Bytes,
+}
+
+/// A client of a signing service that holds a secp256k1 key in a separate,
+/// hardened process, similar to the Cosmos tmkms model.
+///
+/// The service is called over HTTPS with mutual TLS and a JSON protocol:
+/// - `GET /v1/public-key` returns `{ "publicKey": "0x04..." }`, the
+///   uncompressed SEC1 encoding of the key
+/// - `POST /v1/sign` with `{ "digest": "0x...", "kind": "message" |
+///   "transaction" | "typedData", ... }` returns `{ "signature": "0x..." }`,
+///   the 64 byte `r || s` of the ECDSA signature of the digest. Messages
+///   also include the signed `message`, and transactions their `chainId`.
+#[derive(Clone)]
+pub struct RemoteSigningClient {
+    http: reqwest::Client,
+    url: Url,
+}
+
+impl fmt::Debug for RemoteSigningClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSigningClient")
+            .field("host", &self.url.host_str())
+            .finish()
+    }
+}
+
+impl RemoteSigningClient {
+    /// Create a client of the signing service, authenticated with the client
+    /// certificate of `conf`
+    pub fn new(conf: &RemoteSignerConf) -> Result<Self, RemoteSignerError> {
+        if conf.url.scheme() != "https" {
+            return Err(RemoteSignerError::Config(
+                "the URL of the signing service must be https".into(),
+            ));
+        }
+        let read = |path: &PathBuf| {
+            std::fs::read(path).map_err(|source| RemoteSignerError::Io {
+                path: path.clone(),
+                source,
+            })
+        };
+        let ca_cert = Certificate::from_pem(&read(&conf.ca_cert)?)?;
+        let identity =
+            Identity::from_pkcs8_pem(&read(&conf.client_cert)?, &read(&conf.client_key)?)?;
+        let http = HttpClientConf::current()
+            .client_builder(REQUEST_TIMEOUT)?
+            // Only the configured CA is trusted
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(ca_cert)
+            .identity(identity)
+            .build()?;
+        // Endpoints are relative to the base URL, which therefore has to end
+        // with a slash
+        let mut url = conf.url.clone();
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Ok(Self { http, url })
+    }
+
+    fn endpoint(&self, path: &str) -> Result<Url, RemoteSignerError> {
+        self.url
+            .join(path)
+            .map_err(|err| RemoteSignerError::Config(err.to_string()))
+    }
+
+    /// The uncompressed SEC1 encoding of the public key
+    pub async fn public_key(&self) -> Result<Vec<u8>, RemoteSignerError> {
+        let response: PublicKeyResponse = self
+            .http
+            .get(self.endpoint("v1/public-key")?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if response.public_key.len() != 65 || response.public_key[0] != 0x04 {
+            return Err(RemoteSignerError::UnexpectedResponse(
+                "public key is not an uncompressed secp256k1 key".into(),
+            ));
+        }
+        Ok(response.public_key.to_vec())
+    }
+
+    /// Sign `digest`, returning a signature with a low `s`
+    async fn sign_digest(
+        &self,
+        digest: H256,
+        payload: SigningPayload,
+    ) -> Result<KSignature, RemoteSignerError> {
+        let response: SignResponse = self
+            .http
+            .post(self.endpoint("v1/sign")?)
+            .json(&SignRequest { digest, payload })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        // A trailing recovery id is ignored, it's recomputed from the address
+        let Some(rs) = response.signature.get(..64) else {
+            return Err(RemoteSignerError::UnexpectedResponse(
+                "signature is too short".into(),
+            ));
+        };
+        let signature = KSignature::try_from(rs)?;
+        Ok(signature.normalize_s().unwrap_or(signature))
+    }
+}
+
+/// An Ethereum signer whose key is held by a remote signing service, so that
+/// it's never loaded into the agent
+#[derive(Clone, Debug)]
+pub struct RemoteSigner {
+    client: RemoteSigningClient,
+    address: Address,
+    chain_id: u64,
+}
+
+impl RemoteSigner {
+    /// Create a signer for the key of `client`, fetching its public key
+    pub async fn new(
+        client: RemoteSigningClient,
+        chain_id: u64,
+    ) -> Result<Self, RemoteSignerError> {
+        let public_key = client.public_key().await?;
+        let address = address_of(&public_key);
+        Ok(Self {
+            client,
+            address,
+            chain_id,
+        })
+    }
+
+    /// Sign a digest, with a `v` of 27 or 28
+    async fn sign_digest(
+        &self,
+        digest: H256,
+        payload: SigningPayload,
+    ) -> Result<Signature, RemoteSignerError> {
+        let signature = self.client.sign_digest(digest, payload).await?;
+        with_recovery_id(&signature, digest, self.address).ok_or_else(|| {
+            RemoteSignerError::UnexpectedResponse(
+                "signature doesn't recover to the key's address".into(),
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    type Error = RemoteSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        let payload = SigningPayload::Message {
+            message: message.as_ref().to_vec().into(),
+        };
+        self.sign_digest(hash_message(message), payload).await
+    }
+
+    async fn sign_transaction(&self, tx: &TypedTransaction) -> Result<Signature, Self::Error> {
+        let mut tx = tx.clone();
+        let chain_id = tx.chain_id().map_or(self.chain_id, |id| id.as_u64());
+        tx.set_chain_id(chain_id);
+        let mut signature = self
+            .sign_digest(tx.sighash(), SigningPayload::Transaction { chain_id })
+            .await?;
+        signature.v = to_eip155_v((signature.v - 27) as u8, chain_id);
+        Ok(signature)
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        let digest = payload
+            .encode_eip712()
+            .map_err(|err| RemoteSignerError::Eip712(err.to_string()))?;
+        self.sign_digest(digest.into(), SigningPayload::TypedData)
+            .await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        self.chain_id = chain_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sign_request_encoding() {
+        let digest = H256::repeat_byte(0xab);
+        let request = |payload| serde_json::to_value(SignRequest { digest, payload }).unwrap();
+        let digest_hex = format!("{:?}", digest);
+
+        assert_eq!(
+            request(SigningPayload::Message {
+                message: vec![1, 2].into()
+            }),
+            serde_json::json!({ "digest": digest_hex, "kind": "message", "message": "0x0102" })
+        );
+        assert_eq!(
+            request(SigningPayload::Transaction { chain_id: 1 }),
+            serde_json::json!({ "digest": digest_hex, "kind": "transaction", "chainId": 1 })
+        );
+        assert_eq!(
+            request(SigningPayload::TypedData),
+            serde_json::json!({ "digest": digest_hex, "kind": "typedData" })
+        );
+    }
+}
@@ -4,10 +4,10 @@ use std::time::Duration;
 
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::{
-    abi::Detokenize,
+    abi::{Detokenize, Token},
     prelude::{NameOrAddress, TransactionReceipt},
     providers::{JsonRpcClient, PendingTransaction, ProviderError},
-    types::{Block, Eip1559TransactionRequest, TxHash},
+    types::{Block, Eip1559TransactionRequest, Signature, TransactionRequest, TxHash},
 };
 use ethers_contract::builders::ContractCall;
 use ethers_core::types::H160;
@@ -19,8 +19,8 @@ use ethers_core::{
     },
 };
 use hyperlane_core::{
-    utils::bytes_to_hex, ChainCommunicationError, ChainResult, HyperlaneDomain, ReorgPeriod, H256,
-    U256,
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, HyperlaneDomain,
+    HyperlaneDomainTechnicalStack, ReorgPeriod, H256, U256,
 };
 use tracing::{debug, error, info, warn};
 
@@ -37,7 +37,7 @@ pub fn apply_gas_estimate_buffer(gas: U256, domain: &HyperlaneDomain) -> ChainRe
     // Arbitrum Nitro chains use 2d fees are especially prone to costs increasing
     // by the time the transaction lands on chain, requiring a higher gas limit.
     // In this case, we apply a multiplier to the gas estimate.
-    let gas = if domain.is_arbitrum_nitro() {
+    let gas = if GasEstimator::for_domain(domain) == GasEstimator::ArbitrumNitro {
         gas.saturating_mul(GAS_ESTIMATE_MULTIPLIER_NUMERATOR.into())
             .checked_div(GAS_ESTIMATE_MULTIPLIER_DENOMINATOR.into())
             .ok_or_else(|| {
@@ -51,8 +51,87 @@ pub fn apply_gas_estimate_buffer(gas: U256, domain: &HyperlaneDomain) -> ChainRe
     Ok(gas.saturating_add(GAS_ESTIMATE_BUFFER.into()))
 }
 
+/// Blob base fee, in wei, at and above which the blob market of a chain is
+/// considered congested. The blob base fee only rises this far above its
+/// minimum of 1 wei after sustained above-target blob demand.
+const CONGESTED_BLOB_BASE_FEE: u64 = 1_000_000_000;
+/// Percentile of the priority fees of recent blocks that the priority fee is
+/// estimated from while the blob market is congested. Rollups settling to the
+/// chain then compete for the inclusion of their batches with high priority
+/// fees, which the default percentile underestimates.
+const CONGESTED_BLOB_MARKET_REWARD_PERCENTILE: f64 = 25.0;
+
 const PENDING_TRANSACTION_POLLING_INTERVAL: Duration = Duration::from_secs(2);
 const EVM_RELAYER_ADDRESS: &str = "0x74cae0ecc47b02ed9b9d32e000fd70b9417970c5";
+/// Scroll's `L1GasPriceOracle` predeploy, which computes the L1 data fee of
+/// transactions
+const SCROLL_L1_GAS_PRICE_ORACLE_ADDRESS: &str = "0x5300000000000000000000000000000000000002";
+
+/// How the gas limit and fees of transactions are estimated on a chain, which
+/// depends on its technical stack. Several L2s have fee rules that
+/// `eth_estimateGas` and `eth_feeHistory` don't capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasEstimator {
+    /// `eth_estimateGas`, and fees from the fee history
+    Default,
+    /// Like `Default`, with a buffer for the L1 component of the gas of
+    /// Arbitrum's 2d fees. The L2 gas of messages is isolated with the
+    /// `NodeInterface` when estimating the cost of processing them.
+    ArbitrumNitro,
+    /// `linea_estimateGas`, which prices the L1 data of the transaction into
+    /// the gas limit and priority fee
+    Linea,
+    /// Like `Default`, with the L1 data fee Scroll charges on top of the L2
+    /// gas taken from its `L1GasPriceOracle` when estimating costs
+    Scroll,
+    /// `zks_estimateFee` for fees, which accounts for the gas per pubdata
+    ZkSync,
+}
+
+impl GasEstimator {
+    /// The gas estimator of a chain, selected by its technical stack
+    pub fn for_domain(domain: &HyperlaneDomain) -> Self {
+        match domain.domain_technical_stack() {
+            HyperlaneDomainTechnicalStack::ArbitrumNitro => Self::ArbitrumNitro,
+            HyperlaneDomainTechnicalStack::Linea => Self::Linea,
+            HyperlaneDomainTechnicalStack::Scroll => Self::Scroll,
+            HyperlaneDomainTechnicalStack::ZkSync => Self::ZkSync,
+            _ => Self::Default,
+        }
+    }
+
+    /// Estimates the gas limit of a transaction, before any buffer is applied
+    async fn estimate_gas_limit<M, D>(
+        &self,
+        tx: &ContractCall<M, D>,
+        provider: &M,
+    ) -> ChainResult<U256>
+    where
+        M: Middleware + 'static,
+        D: Detokenize,
+    {
+        match self {
+            Self::Linea => Ok(linea_estimate_gas(provider, &tx.tx).await?.gas_limit.into()),
+            _ => Ok(tx.estimate_gas().await?.into()),
+        }
+    }
+
+    /// The fee, in wei, that the chain charges for a transaction on top of its
+    /// gas, if any
+    pub async fn estimate_l1_data_fee<M>(
+        &self,
+        provider: &M,
+        tx: &TypedTransaction,
+    ) -> ChainResult<Option<U256>>
+    where
+        M: Middleware + 'static,
+    {
+        match self {
+            Self::Scroll => Ok(Some(scroll_l1_data_fee(provider, tx).await?)),
+            _ => Ok(None),
+        }
+    }
+}
 
 /// Dispatches a transaction, logs the tx id, and returns the result
 pub(crate) async fn report_tx<M, D>(tx: ContractCall<M, D>) -> ChainResult<TransactionReceipt>
@@ -123,7 +202,11 @@ where
     // either use the pre-estimated gas limit or estimate it
     let mut estimated_gas_limit: U256 = match tx.tx.gas() {
         Some(&estimate) => estimate.into(),
-        None => tx.estimate_gas().await?.into(),
+        None => {
+            GasEstimator::for_domain(domain)
+                .estimate_gas_limit(&tx, provider.as_ref())
+                .await?
+        }
     };
 
     estimated_gas_limit = apply_gas_estimate_buffer(estimated_gas_limit, domain)?;
@@ -154,14 +237,15 @@ where
 
     if let Some(gas_price) = transaction_overrides.gas_price {
         // If the gas price is set, we treat as a non-EIP-1559 chain.
+        let gas_price = cap_fee(gas_price.into(), transaction_overrides.max_fee_per_gas_cap);
         return Ok(tx.gas_price(gas_price).gas(gas_limit));
     }
 
     let Ok((base_fee, max_fee, max_priority_fee)) =
-        estimate_eip1559_fees(provider, None, &latest_block, domain, &tx.tx).await
+        estimate_eip1559_fees(provider.clone(), None, &latest_block, domain, &tx.tx).await
     else {
         // Is not EIP 1559 chain
-        return Ok(tx.gas(gas_limit));
+        return fill_legacy_gas_price(tx.gas(gas_limit), provider, transaction_overrides).await;
     };
 
     // If the base fee is zero, just treat the chain as a non-EIP-1559 chain.
@@ -170,18 +254,17 @@ where
     // fee lower than 3 gwei because of privileged transactions being included by block
     // producers that have a lower priority fee.
     if base_fee.is_zero() {
-        return Ok(tx.gas(gas_limit));
+        return fill_legacy_gas_price(tx.gas(gas_limit), provider, transaction_overrides).await;
     }
 
-    // Apply overrides for EIP 1559 tx params if they exist.
-    let max_fee = transaction_overrides
-        .max_fee_per_gas
-        .map(Into::into)
-        .unwrap_or(max_fee);
-    let max_priority_fee = transaction_overrides
-        .max_priority_fee_per_gas
-        .map(Into::into)
-        .unwrap_or(max_priority_fee);
+    let (max_fee, max_priority_fee) =
+        apply_eip1559_fee_overrides(max_fee, max_priority_fee, transaction_overrides);
+    debug!(
+        ?base_fee,
+        ?max_fee,
+        ?max_priority_fee,
+        "EIP-1559 fees set for transaction"
+    );
 
     // Is EIP 1559 chain
     let mut request = Eip1559TransactionRequest::new();
@@ -204,6 +287,82 @@ where
     Ok(eip_1559_tx.gas(gas_limit))
 }
 
+/// Caps the gas price of a non-EIP-1559 transaction, if a cap is configured.
+/// Otherwise, the gas price is left for the provider to fill in.
+async fn fill_legacy_gas_price<M, D>(
+    tx: ContractCall<M, D>,
+    provider: Arc<M>,
+    transaction_overrides: &TransactionOverrides,
+) -> ChainResult<ContractCall<M, D>>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    let Some(cap) = transaction_overrides.max_fee_per_gas_cap else {
+        return Ok(tx);
+    };
+    let gas_price = provider
+        .get_gas_price()
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    Ok(tx.gas_price(cap_fee(gas_price, Some(cap))))
+}
+
+/// Applies the configured multipliers, overrides and caps, in that order, to
+/// the estimated EIP-1559 fees. The max priority fee never exceeds the max fee.
+fn apply_eip1559_fee_overrides(
+    max_fee: EthersU256,
+    max_priority_fee: EthersU256,
+    transaction_overrides: &TransactionOverrides,
+) -> (EthersU256, EthersU256) {
+    let max_fee = transaction_overrides
+        .max_fee_per_gas
+        .map(Into::into)
+        .unwrap_or_else(|| {
+            apply_fee_multiplier(max_fee, transaction_overrides.max_fee_per_gas_multiplier)
+        });
+    let max_priority_fee = transaction_overrides
+        .max_priority_fee_per_gas
+        .map(Into::into)
+        .unwrap_or_else(|| {
+            apply_fee_multiplier(
+                max_priority_fee,
+                transaction_overrides.max_priority_fee_per_gas_multiplier,
+            )
+        });
+
+    let max_fee = cap_fee(max_fee, transaction_overrides.max_fee_per_gas_cap);
+    let max_priority_fee = cap_fee(
+        max_priority_fee,
+        transaction_overrides.max_priority_fee_per_gas_cap,
+    )
+    .min(max_fee);
+    (max_fee, max_priority_fee)
+}
+
+fn apply_fee_multiplier(fee: EthersU256, multiplier: Option<f64>) -> EthersU256 {
+    let Some(multiplier) = multiplier else {
+        return fee;
+    };
+    // Multiply in thousandths to keep the precision of typical multipliers
+    let multiplier_per_mille = (multiplier.max(0.0) * 1000.0).round() as u64;
+    fee.saturating_mul(multiplier_per_mille.into()) / 1000
+}
+
+fn cap_fee(fee: EthersU256, cap: Option<U256>) -> EthersU256 {
+    match cap.map(EthersU256::from) {
+        Some(cap) if fee > cap => {
+            warn!(
+                ?fee,
+                ?cap,
+                "Fee exceeds the configured cap, lowering it to the cap"
+            );
+            cap
+        }
+        _ => fee,
+    }
+}
+
 type FeeEstimator = fn(EthersU256, Vec<Vec<EthersU256>>) -> (EthersU256, EthersU256);
 
 /// Use this to estimate EIP 1559 fees with some chain-specific logic.
@@ -217,13 +376,109 @@ async fn estimate_eip1559_fees<M>(
 where
     M: Middleware + 'static,
 {
-    if domain.is_zksync_stack() {
-        estimate_eip1559_fees_zksync(provider, latest_block, tx).await
-    } else {
-        estimate_eip1559_fees_default(provider, estimator, latest_block).await
+    match GasEstimator::for_domain(domain) {
+        GasEstimator::ZkSync => estimate_eip1559_fees_zksync(provider, latest_block, tx).await,
+        GasEstimator::Linea => estimate_eip1559_fees_linea(provider, tx).await,
+        _ => estimate_eip1559_fees_default(provider, estimator, latest_block).await,
     }
 }
 
+/// Linea prices the L1 data of a transaction into the priority fee that
+/// `linea_estimateGas` suggests, which the fee history doesn't reflect
+async fn estimate_eip1559_fees_linea<M>(
+    provider: Arc<M>,
+    tx: &TypedTransaction,
+) -> ChainResult<(EthersU256, EthersU256, EthersU256)>
+where
+    M: Middleware + 'static,
+{
+    let response = linea_estimate_gas(provider.as_ref(), tx).await?;
+    Ok(response.eip1559_fees())
+}
+
+async fn linea_estimate_gas<M>(
+    provider: &M,
+    tx: &TypedTransaction,
+) -> ChainResult<LineaEstimateGasResponse>
+where
+    M: Middleware + 'static,
+{
+    let mut tx = tx.clone();
+    if tx.from().is_none() {
+        tx.set_from(
+            provider
+                .default_sender()
+                .unwrap_or(H160::from_str(EVM_RELAYER_ADDRESS).unwrap()),
+        );
+    }
+
+    let result = provider
+        .provider()
+        .request("linea_estimateGas", [tx.clone()])
+        .await?;
+    debug!(?result, ?tx, "Successfully fetched Linea gas estimate");
+    Ok(result)
+}
+
+// From https://docs.linea.build/api/reference/linea-estimategas
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LineaEstimateGasResponse {
+    gas_limit: EthersU256,
+    base_fee_per_gas: EthersU256,
+    priority_fee_per_gas: EthersU256,
+}
+
+impl LineaEstimateGasResponse {
+    /// The base fee, max fee and max priority fee. Like the default estimator,
+    /// the max fee leaves room for the base fee to double.
+    fn eip1559_fees(&self) -> (EthersU256, EthersU256, EthersU256) {
+        let max_fee_per_gas = self
+            .base_fee_per_gas
+            .saturating_mul(2.into())
+            .saturating_add(self.priority_fee_per_gas);
+        (
+            self.base_fee_per_gas,
+            max_fee_per_gas,
+            self.priority_fee_per_gas,
+        )
+    }
+}
+
+/// The L1 data fee of a transaction on Scroll, according to its
+/// `L1GasPriceOracle`. The oracle prices the signed transaction, so the
+/// unsigned transaction is priced with a placeholder signature of the same size.
+async fn scroll_l1_data_fee<M>(provider: &M, tx: &TypedTransaction) -> ChainResult<U256>
+where
+    M: Middleware + 'static,
+{
+    let placeholder_signature = Signature {
+        r: EthersU256::MAX,
+        s: EthersU256::MAX,
+        v: 28,
+    };
+    let data = [
+        &ethers::utils::id("getL1Fee(bytes)")[..],
+        &ethers::abi::encode(&[Token::Bytes(tx.rlp_signed(&placeholder_signature).to_vec())]),
+    ]
+    .concat();
+    let call = TypedTransaction::Legacy(
+        TransactionRequest::new()
+            .to(H160::from_str(SCROLL_L1_GAS_PRICE_ORACLE_ADDRESS).unwrap())
+            .data(data),
+    );
+    let result = provider
+        .call(&call, None)
+        .await
+        .map_err(ChainCommunicationError::from_other)?;
+    if result.len() != 32 {
+        return Err(ChainCommunicationError::from_other_str(
+            "Unexpected response from Scroll's L1GasPriceOracle",
+        ));
+    }
+    Ok(U256::from_big_endian(&result))
+}
+
 async fn estimate_eip1559_fees_zksync<M>(
     provider: Arc<M>,
     latest_block: &Block<TxHash>,
@@ -296,11 +551,20 @@ where
         .base_fee_per_gas
         .ok_or_else(|| ProviderError::CustomError("EIP-1559 not activated".into()))?;
 
+    let blob_base_fee = blob_base_fee(provider.as_ref()).await;
+    let reward_percentile = reward_percentile(blob_base_fee);
+    if reward_percentile != EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE {
+        debug!(
+            ?blob_base_fee,
+            reward_percentile, "Blob market is congested, estimating a higher priority fee"
+        );
+    }
+
     let fee_history = provider
         .fee_history(
             EIP1559_FEE_ESTIMATION_PAST_BLOCKS,
             BlockNumber::Latest,
-            &[EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE],
+            &[reward_percentile],
         )
         .await
         .map_err(ChainCommunicationError::from_other)?;
@@ -315,6 +579,29 @@ where
     Ok((base_fee_per_gas, max_fee_per_gas, max_priority_fee_per_gas))
 }
 
+/// The blob base fee of the next block, if the chain supports EIP-4844
+async fn blob_base_fee<M>(provider: &M) -> Option<EthersU256>
+where
+    M: Middleware + 'static,
+{
+    provider
+        .provider()
+        .request::<_, EthersU256>("eth_blobBaseFee", ())
+        .await
+        .ok()
+}
+
+/// The percentile of the priority fees of recent blocks to estimate the
+/// priority fee from, given the blob base fee of the chain
+fn reward_percentile(blob_base_fee: Option<EthersU256>) -> f64 {
+    match blob_base_fee {
+        Some(fee) if fee >= CONGESTED_BLOB_BASE_FEE.into() => {
+            CONGESTED_BLOB_MARKET_REWARD_PERCENTILE
+        }
+        _ => EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE,
+    }
+}
+
 pub(crate) async fn call_with_reorg_period<M, T>(
     call: ethers::contract::builders::ContractCall<M, T>,
     provider: &M,
@@ -348,7 +635,110 @@ mod test {
     use std::str::FromStr;
     use url::Url;
 
-    use crate::tx::zksync_estimate_fee;
+    use ethers_core::{types::U256 as EthersU256, utils::EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE};
+    use hyperlane_core::U256;
+
+    use hyperlane_core::{HyperlaneDomain, KnownHyperlaneDomain};
+
+    use crate::tx::{
+        apply_eip1559_fee_overrides, reward_percentile, zksync_estimate_fee, GasEstimator,
+        LineaEstimateGasResponse, CONGESTED_BLOB_MARKET_REWARD_PERCENTILE,
+    };
+    use crate::TransactionOverrides;
+
+    #[test]
+    fn test_apply_eip1559_fee_overrides() {
+        let estimate = (EthersU256::from(100), EthersU256::from(10));
+        assert_eq!(
+            apply_eip1559_fee_overrides(estimate.0, estimate.1, &Default::default()),
+            estimate
+        );
+
+        let multiplied = TransactionOverrides {
+            max_fee_per_gas_multiplier: Some(1.5),
+            max_priority_fee_per_gas_multiplier: Some(2.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            apply_eip1559_fee_overrides(estimate.0, estimate.1, &multiplied),
+            (150.into(), 20.into())
+        );
+
+        // Overrides replace the multiplied estimates, and caps bound both
+        let capped = TransactionOverrides {
+            max_fee_per_gas: Some(U256::from(200)),
+            max_fee_per_gas_cap: Some(U256::from(120)),
+            max_priority_fee_per_gas_cap: Some(U256::from(15)),
+            ..multiplied.clone()
+        };
+        assert_eq!(
+            apply_eip1559_fee_overrides(estimate.0, estimate.1, &capped),
+            (120.into(), 15.into())
+        );
+
+        // The max priority fee never exceeds the max fee
+        let low_cap = TransactionOverrides {
+            max_fee_per_gas_cap: Some(U256::from(12)),
+            ..multiplied
+        };
+        assert_eq!(
+            apply_eip1559_fee_overrides(estimate.0, estimate.1, &low_cap),
+            (12.into(), 12.into())
+        );
+    }
+
+    #[test]
+    fn test_reward_percentile_rises_with_blob_congestion() {
+        assert_eq!(
+            reward_percentile(None),
+            EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE
+        );
+        assert_eq!(
+            reward_percentile(Some(1.into())),
+            EIP1559_FEE_ESTIMATION_REWARD_PERCENTILE
+        );
+        assert_eq!(
+            reward_percentile(Some(50_000_000_000u64.into())),
+            CONGESTED_BLOB_MARKET_REWARD_PERCENTILE
+        );
+    }
+
+    #[test]
+    fn test_gas_estimator_for_domain() {
+        let estimator = |domain| GasEstimator::for_domain(&HyperlaneDomain::Known(domain));
+        assert_eq!(
+            estimator(KnownHyperlaneDomain::Ethereum),
+            GasEstimator::Default
+        );
+        assert_eq!(
+            estimator(KnownHyperlaneDomain::PlumeTestnet),
+            GasEstimator::ArbitrumNitro
+        );
+        assert_eq!(estimator(KnownHyperlaneDomain::Linea), GasEstimator::Linea);
+        assert_eq!(
+            estimator(KnownHyperlaneDomain::ScrollSepolia),
+            GasEstimator::Scroll
+        );
+    }
+
+    #[test]
+    fn test_linea_estimate_gas_fees() {
+        let response: LineaEstimateGasResponse = serde_json::from_value(serde_json::json!({
+            "gasLimit": "0x5208",
+            "baseFeePerGas": "0x7",
+            "priorityFeePerGas": "0x43a82a4"
+        }))
+        .unwrap();
+        assert_eq!(response.gas_limit, EthersU256::from(21_000));
+        assert_eq!(
+            response.eip1559_fees(),
+            (
+                EthersU256::from(7),
+                EthersU256::from(0x43a82a4 + 14),
+                EthersU256::from(0x43a82a4)
+            )
+        );
+    }
 
     #[ignore = "Not running a flaky test requiring network"]
     #[tokio::test]
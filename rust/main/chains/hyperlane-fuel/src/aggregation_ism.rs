@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use fuels::{
+    prelude::{Bech32ContractId, WalletUnlocked},
+    types::Bytes,
+};
+use tracing::instrument;
+
+use hyperlane_core::{
+    AggregationIsm, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, RawHyperlaneMessage,
+    H256,
+};
+
+use crate::{
+    contracts::aggregation_ism::AggregationIsm as FuelAggregationIsmInner, conversions::*,
+    ConnectionConf, FuelProvider,
+};
+
+/// A reference to an AggregationIsm contract on some Fuel chain
+#[derive(Debug)]
+pub struct FuelAggregationIsm {
+    contract: FuelAggregationIsmInner<WalletUnlocked>,
+    domain: HyperlaneDomain,
+    provider: FuelProvider,
+}
+
+impl FuelAggregationIsm {
+    /// Create a new fuel aggregation ISM
+    pub async fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator<'_>,
+        mut wallet: WalletUnlocked,
+    ) -> ChainResult<Self> {
+        let fuel_provider = FuelProvider::new(locator.domain.clone(), conf).await;
+
+        wallet.set_provider(fuel_provider.provider().clone());
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(FuelAggregationIsm {
+            contract: FuelAggregationIsmInner::new(address, wallet),
+            domain: locator.domain.clone(),
+            provider: fuel_provider,
+        })
+    }
+}
+
+impl HyperlaneContract for FuelAggregationIsm {
+    fn address(&self) -> H256 {
+        self.contract.contract_id().into_h256()
+    }
+}
+
+impl HyperlaneChain for FuelAggregationIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl AggregationIsm for FuelAggregationIsm {
+    /// Returns the modules and threshold needed to verify message
+    #[instrument(err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn modules_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        self.contract
+            .methods()
+            .modules_and_threshold(Bytes(RawHyperlaneMessage::from(message)))
+            .simulate()
+            .await
+            .map(|r| {
+                let (modules, threshold) = r.value;
+                (
+                    modules
+                        .into_iter()
+                        .map(|module| module.into_h256())
+                        .collect(),
+                    threshold,
+                )
+            })
+            .map_err(ChainCommunicationError::from_other)
+    }
+}
@@ -6,14 +6,16 @@
 #![allow(unused_variables)]
 
 pub use self::{
-    interchain_gas::*, mailbox::*, multisig_ism::*, provider::*, routing_ism::*, trait_builder::*,
-    validator_announce::*,
+    aggregation_ism::*, interchain_gas::*, mailbox::*, merkle_tree_hook::*, multisig_ism::*,
+    provider::*, routing_ism::*, trait_builder::*, validator_announce::*,
 };
 
+mod aggregation_ism;
 mod contracts;
 mod conversions;
 mod interchain_gas;
 mod mailbox;
+mod merkle_tree_hook;
 mod multisig_ism;
 mod provider;
 mod routing_ism;
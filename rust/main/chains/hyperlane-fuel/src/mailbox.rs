@@ -180,6 +180,7 @@ impl Mailbox for FuelMailbox {
             executed: success,
             gas_used: call_res.gas_used.into(),
             gas_price: gas_price.into(),
+            block_number: None,
         })
     }
 
@@ -212,7 +213,11 @@ impl Mailbox for FuelMailbox {
         })
     }
 
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+    fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
         // Seems like this is not needed for Fuel, as it's only used in mocks
         todo!()
     }
@@ -289,16 +294,19 @@ impl SequenceAwareIndexer<H256> for FuelMailboxIndexer {
 impl SequenceAwareIndexer<HyperlaneMessage> for FuelMailboxIndexer {
     #[allow(clippy::unnecessary_cast)] // TODO: `rustc` 1.80.1 clippy issue
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
-        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&self).await?;
-
-        self.contract
+        // The nonce is read before the tip, so that every message it accounts
+        // for is in a block at or below the tip
+        let sequence = self
+            .contract
             .methods()
             .nonce()
             .simulate()
             .await
             .map(|r| r.value)
-            .map_err(ChainCommunicationError::from_other)
-            .map(|sequence| (Some(sequence as u32), tip))
+            .map_err(ChainCommunicationError::from_other)?;
+        let tip = Indexer::<HyperlaneMessage>::get_finalized_block_number(&self).await?;
+
+        Ok((Some(sequence as u32), tip))
     }
 }
 
@@ -0,0 +1,260 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use fuels::{
+    core::{
+        codec::ABIDecoder,
+        traits::{Parameterize, Tokenizable},
+    },
+    prelude::{Bech32ContractId, WalletUnlocked},
+};
+use tracing::instrument;
+
+use hyperlane_core::{
+    accumulator::incremental::IncrementalMerkle, ChainCommunicationError, ChainResult, Checkpoint,
+    ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneProvider,
+    Indexed, Indexer, LogMeta, MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod,
+    SequenceAwareIndexer, H256,
+};
+
+use crate::{
+    contracts::merkle_tree_hook::{
+        InsertedIntoTreeEvent, MerkleTreeHook as FuelMerkleTreeHookInner,
+    },
+    conversions::*,
+    ConnectionConf, FuelProvider,
+};
+
+/// Length of an encoded `InsertedIntoTreeEvent`
+const INSERTED_INTO_TREE_EVENT_LEN: usize = 36;
+
+/// Decodes the data of a `LogData` receipt as an `InsertedIntoTreeEvent` of
+/// the merkle tree hook ABI, i.e. a b256 message ID followed by the big endian
+/// u32 leaf index. Returns `None` for the hook's other logs.
+pub(crate) fn decode_inserted_into_tree_event(data: &[u8]) -> Option<MerkleTreeInsertion> {
+    // Logs of other types may start with a valid event
+    if data.len() != INSERTED_INTO_TREE_EVENT_LEN {
+        return None;
+    }
+    let token = ABIDecoder::default()
+        .decode(&InsertedIntoTreeEvent::param_type(), data)
+        .ok()?;
+    let event = InsertedIntoTreeEvent::from_token(token).ok()?;
+    Some(MerkleTreeInsertion::new(
+        event.index,
+        event.message_id.into_h256(),
+    ))
+}
+
+/// Fuel contracts can only be queried at the latest block
+fn ensure_no_reorg_period(reorg_period: &ReorgPeriod) -> ChainResult<()> {
+    if reorg_period.is_none() {
+        Ok(())
+    } else {
+        Err(ChainCommunicationError::from_other_str(
+            "Fuel does not support querying point-in-time",
+        ))
+    }
+}
+
+/// A reference to a MerkleTreeHook contract on some Fuel chain
+#[derive(Debug)]
+pub struct FuelMerkleTreeHook {
+    contract: FuelMerkleTreeHookInner<WalletUnlocked>,
+    domain: HyperlaneDomain,
+    provider: FuelProvider,
+}
+
+impl FuelMerkleTreeHook {
+    /// Create a new fuel merkle tree hook
+    pub async fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator<'_>,
+        mut wallet: WalletUnlocked,
+    ) -> ChainResult<Self> {
+        let fuel_provider = FuelProvider::new(locator.domain.clone(), conf).await;
+
+        wallet.set_provider(fuel_provider.provider().clone());
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(FuelMerkleTreeHook {
+            contract: FuelMerkleTreeHookInner::new(address, wallet),
+            domain: locator.domain.clone(),
+            provider: fuel_provider,
+        })
+    }
+}
+
+impl HyperlaneContract for FuelMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.contract.contract_id().into_h256()
+    }
+}
+
+impl HyperlaneChain for FuelMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl MerkleTreeHook for FuelMerkleTreeHook {
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn tree(&self, reorg_period: &ReorgPeriod) -> ChainResult<IncrementalMerkle> {
+        ensure_no_reorg_period(reorg_period)?;
+        self.contract
+            .methods()
+            .tree()
+            .simulate()
+            .await
+            .map(|r| {
+                let tree = r.value;
+                IncrementalMerkle::new(
+                    tree.branch.map(|node| node.into_h256()),
+                    tree.count as usize,
+                )
+            })
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn count(&self, reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        ensure_no_reorg_period(reorg_period)?;
+        self.contract
+            .methods()
+            .count()
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    #[allow(clippy::blocks_in_conditions)] // TODO: `rustc` 1.80.1 clippy issue
+    async fn latest_checkpoint(&self, reorg_period: &ReorgPeriod) -> ChainResult<Checkpoint> {
+        ensure_no_reorg_period(reorg_period)?;
+        self.contract
+            .methods()
+            .latest_checkpoint()
+            .simulate()
+            .await
+            .map(|r| {
+                let (root, index) = r.value;
+                Checkpoint {
+                    merkle_tree_hook_address: self.address(),
+                    mailbox_domain: self.domain.id(),
+                    root: root.into_h256(),
+                    index,
+                }
+            })
+            .map_err(ChainCommunicationError::from_other)
+    }
+}
+
+/// Struct that retrieves insertion events of a Fuel MerkleTreeHook contract
+#[derive(Debug)]
+pub struct FuelMerkleTreeHookIndexer {
+    contract: FuelMerkleTreeHookInner<WalletUnlocked>,
+    provider: FuelProvider,
+}
+
+impl FuelMerkleTreeHookIndexer {
+    /// Create a new FuelMerkleTreeHookIndexer
+    pub async fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator<'_>,
+        mut wallet: WalletUnlocked,
+    ) -> ChainResult<Self> {
+        let fuel_provider = FuelProvider::new(locator.domain.clone(), conf).await;
+
+        wallet.set_provider(fuel_provider.provider().clone());
+        let address = Bech32ContractId::from_h256(&locator.address);
+
+        Ok(FuelMerkleTreeHookIndexer {
+            contract: FuelMerkleTreeHookInner::new(address, wallet),
+            provider: fuel_provider,
+        })
+    }
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for FuelMerkleTreeHookIndexer {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let hook_address = self.contract.contract_id().clone();
+        self.provider
+            .index_merkle_tree_insertions_in_range(range, hook_address)
+            .await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.provider.get_finalized_block_number().await
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for FuelMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // The count is read before the tip, so that every insertion it
+        // accounts for is in a block at or below the tip
+        let count = self
+            .contract
+            .methods()
+            .count()
+            .simulate()
+            .await
+            .map(|r| r.value)
+            .map_err(ChainCommunicationError::from_other)?;
+        let tip = self.get_finalized_block_number().await?;
+
+        Ok((Some(count), tip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fuels::types::Bits256;
+
+    use super::*;
+    use crate::contracts::merkle_tree_hook::MerkleTree;
+
+    #[test]
+    fn test_decode_inserted_into_tree_event() {
+        let message_id = H256::repeat_byte(0xab);
+        let mut data = message_id.as_bytes().to_vec();
+        data.extend_from_slice(&7u32.to_be_bytes());
+
+        assert_eq!(
+            decode_inserted_into_tree_event(&data),
+            Some(MerkleTreeInsertion::new(7, message_id))
+        );
+        // Logs of other sizes aren't insertions
+        assert_eq!(decode_inserted_into_tree_event(&data[..35]), None);
+        data.push(0);
+        assert_eq!(decode_inserted_into_tree_event(&data), None);
+    }
+
+    #[test]
+    fn test_decode_tree() {
+        let mut data = vec![];
+        for i in 0..32u8 {
+            data.extend_from_slice(&[i; 32]);
+        }
+        data.extend_from_slice(&5u64.to_be_bytes());
+
+        let token = ABIDecoder::default()
+            .decode(&MerkleTree::param_type(), data.as_slice())
+            .unwrap();
+        let tree = MerkleTree::from_token(token).unwrap();
+        assert_eq!(tree.count, 5);
+        assert_eq!(tree.branch[3], Bits256([3; 32]));
+    }
+}
@@ -19,10 +19,13 @@ use futures::future::join_all;
 use hyperlane_core::{
     h512_to_bytes, BlockInfo, ChainCommunicationError, ChainInfo, ChainResult, HyperlaneChain,
     HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, HyperlaneProviderError, Indexed, LogMeta,
-    TxnInfo, H256, H512, U256,
+    MerkleTreeInsertion, TxnInfo, H256, H512, U256,
 };
 
-use crate::{make_client, make_provider, prelude::FuelIntoH256, ConnectionConf};
+use crate::{
+    make_client, make_provider, merkle_tree_hook::decode_inserted_into_tree_event,
+    prelude::FuelIntoH256, ConnectionConf,
+};
 
 /// A wrapper around a fuel provider to get generic blockchain information.
 #[derive(Debug, Clone)]
@@ -269,6 +272,81 @@ impl FuelProvider {
             .collect::<Vec<_>>();
         Ok(indexed_logs)
     }
+
+    /// Index the merkle tree insertions of a merkle tree hook contract in a
+    /// range of blocks.
+    ///
+    /// Insertions are read from the `LogData` receipts the hook contract emits
+    /// for `InsertedIntoTreeEvent`s, which are encoded as the message ID
+    /// followed by the big endian leaf index.
+    pub async fn index_merkle_tree_insertions_in_range(
+        &self,
+        range: std::ops::RangeInclusive<u32>,
+        hook_contract: Bech32ContractId,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let (blocks, transaction_map) = self.get_block_data(range).await?;
+        let hook_contract_id = ContractId::from(&hook_contract.clone().into());
+
+        let transaction_ids = blocks
+            .into_iter()
+            .flat_map(|block| block.transactions.into_iter())
+            .collect::<Vec<_>>();
+
+        let transactions = join_all(transaction_ids.into_iter().map(|tx_id| {
+            let provider = self.provider.clone();
+            async move {
+                provider
+                    .get_transaction_by_id(&tx_id)
+                    .await
+                    .map(|tx| (tx_id, tx))
+                    .map_err(ChainCommunicationError::from_other)
+            }
+        }))
+        .await
+        .into_iter()
+        .collect::<ChainResult<Vec<_>>>()?;
+
+        let mut insertions = vec![];
+        for (tx_id, tx) in transactions {
+            let Some(tx) = tx else {
+                continue;
+            };
+            let TxStatus::Success { receipts } = &tx.status else {
+                continue;
+            };
+            let (Some(block_height), Some((block_hash, transaction_index))) =
+                (tx.block_height, transaction_map.get(&tx_id))
+            else {
+                continue;
+            };
+
+            for (log_index, receipt) in receipts.iter().enumerate() {
+                let Receipt::LogData { id, .. } = receipt else {
+                    continue;
+                };
+                let Some(data) = receipt.data() else {
+                    continue;
+                };
+                if *id != hook_contract_id {
+                    continue;
+                }
+                let Some(insertion) = decode_inserted_into_tree_event(data) else {
+                    continue;
+                };
+
+                let log_meta = LogMeta {
+                    address: hook_contract.clone().into_h256(),
+                    block_number: *block_height.deref() as u64,
+                    block_hash: block_hash.into_h256(),
+                    transaction_id: H512::from(tx_id.into_h256()),
+                    transaction_index: *transaction_index,
+                    log_index: U256::from(log_index),
+                };
+                insertions.push((insertion.into(), log_meta));
+            }
+        }
+        Ok(insertions)
+    }
 }
 
 impl HyperlaneChain for FuelProvider {
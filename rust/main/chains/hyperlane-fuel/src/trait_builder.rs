@@ -1,5 +1,9 @@
+use std::sync::Once;
+
 use fuels::{client::FuelClient, prelude::Provider};
 use hyperlane_core::{ChainCommunicationError, ChainResult};
+use reqwest_utils::HttpClientConf;
+use tracing::warn;
 use url::Url;
 
 /// Fuel connection configuration
@@ -30,13 +34,29 @@ impl From<FuelNewConnectionError> for ChainCommunicationError {
     }
 }
 
+/// Warns that the configured proxy and root certificates of the agent don't
+/// apply to Fuel clients, which build HTTP clients of their own that can't be
+/// replaced. Those only use the proxy in the `HTTP_PROXY`, `HTTPS_PROXY` or
+/// `ALL_PROXY` env var, if any.
+fn warn_of_unshared_http_client() {
+    static WARNED: Once = Once::new();
+    let conf = HttpClientConf::current();
+    if conf.proxy.is_some() || !conf.root_certificates.is_empty() {
+        WARNED.call_once(|| {
+            warn!("Fuel RPC requests don't use the configured proxy or root certificates")
+        });
+    }
+}
+
 /// Create a new Fuel client
 pub fn make_client(conf: &ConnectionConf) -> ChainResult<FuelClient> {
+    warn_of_unshared_http_client();
     FuelClient::new(&conf.url).map_err(|e| FuelNewConnectionError(e).into())
 }
 
 /// Create a new fuel provider and connection
 pub async fn make_provider(conf: &ConnectionConf) -> ChainResult<Provider> {
+    warn_of_unshared_http_client();
     Provider::connect(&conf.url)
         .await
         .map_err(|e| FuelNewConnectionError(e.into()).into())
@@ -111,7 +111,11 @@ impl SealevelInterchainGasPaymasterIndexer {
         advanced_log_meta: bool,
     ) -> ChainResult<Self> {
         // Set the `processed` commitment at rpc level
-        let rpc_client = SealevelRpcClient::new(conf.url.to_string());
+        let rpc_client = SealevelRpcClient::new_metered(
+            conf.url.to_string(),
+            conf.rate_limit,
+            igp_account_locator.domain.name(),
+        );
 
         let igp = SealevelInterchainGasPaymaster::new(conf, &igp_account_locator).await?;
 
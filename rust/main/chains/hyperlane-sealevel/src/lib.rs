@@ -10,6 +10,7 @@ pub use interchain_security_module::*;
 pub use mailbox::*;
 pub use merkle_tree_hook::*;
 pub use provider::*;
+pub use remote_transfer::*;
 pub(crate) use rpc::SealevelRpcClient;
 pub use solana_sdk::signer::keypair::Keypair;
 pub use trait_builder::*;
@@ -20,11 +21,13 @@ mod error;
 mod interchain_gas;
 mod interchain_security_module;
 mod log_meta_composer;
+mod lookup_table;
 mod mailbox;
 mod merkle_tree_hook;
 mod multisig_ism;
 mod priority_fee;
 mod provider;
+mod remote_transfer;
 mod rpc;
 mod trait_builder;
 mod tx_submitter;
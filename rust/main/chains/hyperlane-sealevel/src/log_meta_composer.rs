@@ -216,7 +216,7 @@ fn filter_by_relevancy(
     Some(hash)
 }
 
-fn filter_by_validity(
+pub(crate) fn filter_by_validity(
     tx: UiTransaction,
     meta: UiTransactionStatusMeta,
 ) -> Option<(H512, Vec<String>, Vec<UiCompiledInstruction>)> {
@@ -268,7 +268,7 @@ fn filter_by_validity(
     Some((transaction_hash, account_keys, instructions))
 }
 
-fn filter_by_encoding(
+pub(crate) fn filter_by_encoding(
     tx: EncodedTransactionWithStatusMeta,
 ) -> Option<(UiTransaction, UiTransactionStatusMeta)> {
     match (tx.transaction, tx.meta) {
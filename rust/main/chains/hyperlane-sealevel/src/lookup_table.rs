@@ -0,0 +1,364 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use hyperlane_core::{ChainCommunicationError, ChainResult};
+use solana_address_lookup_table_program::{
+    instruction::extend_lookup_table,
+    state::{AddressLookupTable, LOOKUP_TABLE_MAX_ADDRESSES},
+};
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount, clock::Slot, pubkey::Pubkey,
+    signature::Keypair, signer::Signer as _,
+};
+use tracing::{info, warn};
+
+use crate::{tx_submitter::TransactionSubmitter, SealevelRpcClient};
+
+/// The number of addresses added to the table per transaction, which keeps
+/// extend transactions well below the transaction size limit
+const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Compute units requested by extend transactions
+const EXTEND_COMPUTE_UNITS: u32 = 20_000;
+
+/// How long a fetched table is used before it's fetched again
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// An address lookup table holding the accounts that process transactions
+/// share, such as the inbox and the ISM's accounts. Process transactions
+/// are sent as v0 transactions that reference these accounts by their index
+/// in the table, which leaves room for more accounts and larger metadata.
+///
+/// The table is created by the operator with the relayer's payer as its
+/// authority. The table is cached between transactions, and shared accounts
+/// it doesn't hold yet are added to it in the background, so building
+/// process transactions doesn't wait on managing the table.
+#[derive(Clone)]
+pub struct ProcessLookupTable {
+    inner: Arc<LookupTableInner>,
+}
+
+struct LookupTableInner {
+    address: Pubkey,
+    rpc: Arc<SealevelRpcClient>,
+    /// The payer, which extends the table if it's the table's authority
+    payer: Option<Keypair>,
+    tx_submitter: Box<dyn TransactionSubmitter>,
+    cached: tokio::sync::Mutex<Option<CachedTable>>,
+    /// Accounts the table is being or was extended with since it was last
+    /// fetched, which aren't added again until the table is refetched
+    queued: Mutex<HashSet<Pubkey>>,
+    /// Serializes extending the table
+    extending: tokio::sync::Mutex<()>,
+}
+
+struct CachedTable {
+    /// The table, or `None` if it can't be used
+    table: Option<FetchedTable>,
+    fetched_at: Instant,
+}
+
+impl CachedTable {
+    fn is_stale(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.fetched_at) >= REFRESH_INTERVAL
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FetchedTable {
+    /// The addresses that can be looked up
+    lookup: AddressLookupTableAccount,
+    /// All addresses of the table, including those added in the slot the
+    /// table was fetched at
+    addresses: Vec<Pubkey>,
+    authority: Option<Pubkey>,
+}
+
+impl FetchedTable {
+    /// The state of an active table fetched at `current_slot`, or `None` if
+    /// the table is deactivated
+    fn new(address: Pubkey, table: &AddressLookupTable, current_slot: Slot) -> Option<Self> {
+        if table.meta.deactivation_slot != Slot::MAX {
+            return None;
+        }
+        // Addresses added in a slot can't be looked up until the next one
+        let active_len = if table.meta.last_extended_slot >= current_slot {
+            table.meta.last_extended_slot_start_index as usize
+        } else {
+            table.addresses.len()
+        };
+        Some(Self {
+            lookup: AddressLookupTableAccount {
+                key: address,
+                addresses: table.addresses[..active_len].to_vec(),
+            },
+            addresses: table.addresses.to_vec(),
+            authority: table.meta.authority,
+        })
+    }
+
+    /// The distinct `accounts` the table doesn't hold
+    fn missing(&self, accounts: &[Pubkey]) -> Vec<Pubkey> {
+        let mut missing: Vec<Pubkey> = accounts
+            .iter()
+            .filter(|account| !self.addresses.contains(account))
+            .copied()
+            .collect();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+}
+
+impl ProcessLookupTable {
+    /// A lookup table at `address`, extended by `payer` if it's the table's
+    /// authority
+    pub fn new(
+        address: Pubkey,
+        rpc: Arc<SealevelRpcClient>,
+        payer: Option<&Keypair>,
+        tx_submitter: Box<dyn TransactionSubmitter>,
+    ) -> Self {
+        let payer = payer.and_then(|payer| Keypair::from_bytes(&payer.to_bytes()).ok());
+        Self {
+            inner: Arc::new(LookupTableInner {
+                address,
+                rpc,
+                payer,
+                tx_submitter,
+                cached: Default::default(),
+                queued: Default::default(),
+                extending: Default::default(),
+            }),
+        }
+    }
+
+    /// The addresses of the table that can be looked up, or `None` if the
+    /// table can't be used. Those of `accounts` the table doesn't hold are
+    /// added to it in the background, and can be looked up once the table
+    /// is next fetched.
+    pub async fn get(&self, accounts: &[Pubkey]) -> ChainResult<Option<AddressLookupTableAccount>> {
+        let Some(table) = self.inner.cached_table().await? else {
+            return Ok(None);
+        };
+
+        let missing: Vec<Pubkey> = {
+            let mut queued = self.inner.queued.lock().unwrap();
+            table
+                .missing(accounts)
+                .into_iter()
+                .filter(|account| queued.insert(*account))
+                .collect()
+        };
+        if !missing.is_empty() {
+            let inner = self.inner.clone();
+            let fetched = table.clone();
+            tokio::spawn(async move { inner.extend(&fetched, &missing).await });
+        }
+
+        Ok(Some(table.lookup))
+    }
+}
+
+impl std::fmt::Debug for ProcessLookupTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessLookupTable")
+            .field("address", &self.inner.address)
+            .finish()
+    }
+}
+
+impl LookupTableInner {
+    /// The cached table, which is fetched again once it's stale
+    async fn cached_table(&self) -> ChainResult<Option<FetchedTable>> {
+        let mut cached = self.cached.lock().await;
+        if let Some(cached) = cached.as_ref().filter(|c| !c.is_stale(Instant::now())) {
+            return Ok(cached.table.clone());
+        }
+        let table = self.fetch().await?;
+        self.queued.lock().unwrap().clear();
+        *cached = Some(CachedTable {
+            table: table.clone(),
+            fetched_at: Instant::now(),
+        });
+        Ok(table)
+    }
+
+    async fn fetch(&self) -> ChainResult<Option<FetchedTable>> {
+        let account = self
+            .rpc
+            .get_account_option_with_finalized_commitment(&self.address)
+            .await?;
+        let Some(account) = account else {
+            warn!(table = ?self.address, "Address lookup table doesn't exist");
+            return Ok(None);
+        };
+        let table = AddressLookupTable::deserialize(&account.data)
+            .map_err(ChainCommunicationError::from_other)?;
+        let current_slot = self.rpc.get_slot_raw().await?;
+        let fetched = FetchedTable::new(self.address, &table, current_slot);
+        if fetched.is_none() {
+            warn!(table = ?self.address, "Address lookup table is deactivated");
+        }
+        Ok(fetched)
+    }
+
+    /// Extend the table with `missing`. Failing to extend the table isn't an
+    /// error, as transactions can still list the accounts in full.
+    async fn extend(&self, table: &FetchedTable, missing: &[Pubkey]) {
+        let _extending = self.extending.lock().await;
+
+        let Some(payer) = self
+            .payer
+            .as_ref()
+            .filter(|payer| table.authority == Some(payer.pubkey()))
+        else {
+            warn!(
+                table = ?self.address,
+                authority = ?table.authority,
+                "Address lookup table is missing accounts but isn't owned by the payer"
+            );
+            return;
+        };
+        if table.addresses.len() + missing.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+            warn!(
+                table = ?self.address,
+                missing = missing.len(),
+                "Address lookup table is full"
+            );
+            return;
+        }
+
+        for addresses in missing.chunks(MAX_ADDRESSES_PER_EXTEND) {
+            if let Err(err) = self.send_extend(payer, addresses).await {
+                warn!(table = ?self.address, ?err, "Failed to extend address lookup table");
+                return;
+            }
+        }
+        // Fetch the table again, so the added accounts are looked up once
+        // they're active
+        self.cached.lock().await.take();
+    }
+
+    async fn send_extend(&self, payer: &Keypair, addresses: &[Pubkey]) -> ChainResult<()> {
+        let instruction = extend_lookup_table(
+            self.address,
+            payer.pubkey(),
+            Some(payer.pubkey()),
+            addresses.to_vec(),
+        );
+        let tx = self
+            .rpc
+            .create_transaction_for_instruction(
+                EXTEND_COMPUTE_UNITS,
+                0,
+                instruction,
+                payer,
+                &*self.tx_submitter,
+                true,
+                &[],
+            )
+            .await?;
+        let signature = self.tx_submitter.send_transaction(&tx, false).await?;
+        let rpc = self.tx_submitter.rpc_client().unwrap_or(&*self.rpc);
+        rpc.wait_for_transaction_confirmation(&tx).await?;
+        info!(
+            table = ?self.address,
+            ?signature,
+            added = addresses.len(),
+            "Extended address lookup table"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use solana_address_lookup_table_program::state::LookupTableMeta;
+
+    use super::*;
+
+    fn table(addresses: &[Pubkey], meta: LookupTableMeta) -> AddressLookupTable<'static> {
+        AddressLookupTable {
+            meta,
+            addresses: Cow::Owned(addresses.to_vec()),
+        }
+    }
+
+    #[test]
+    fn test_fetched_table_hides_addresses_added_in_the_current_slot() {
+        let address = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let addresses: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let state = table(
+            &addresses,
+            LookupTableMeta {
+                last_extended_slot: 10,
+                last_extended_slot_start_index: 2,
+                authority: Some(authority),
+                ..LookupTableMeta::default()
+            },
+        );
+
+        let fetched = FetchedTable::new(address, &state, 10).unwrap();
+        assert_eq!(fetched.lookup.key, address);
+        assert_eq!(fetched.lookup.addresses, addresses[..2]);
+        assert_eq!(fetched.addresses, addresses);
+        assert_eq!(fetched.authority, Some(authority));
+
+        let fetched = FetchedTable::new(address, &state, 11).unwrap();
+        assert_eq!(fetched.lookup.addresses, addresses);
+    }
+
+    #[test]
+    fn test_deactivated_table_isnt_used() {
+        let state = table(
+            &[Pubkey::new_unique()],
+            LookupTableMeta {
+                deactivation_slot: 5,
+                ..LookupTableMeta::default()
+            },
+        );
+        assert_eq!(FetchedTable::new(Pubkey::new_unique(), &state, 10), None);
+    }
+
+    #[test]
+    fn test_missing_accounts() {
+        let held = Pubkey::new_unique();
+        let pending = Pubkey::new_unique();
+        let missing = Pubkey::new_unique();
+        let state = table(
+            &[held, pending],
+            LookupTableMeta {
+                last_extended_slot: 10,
+                last_extended_slot_start_index: 1,
+                ..LookupTableMeta::default()
+            },
+        );
+        let fetched = FetchedTable::new(Pubkey::new_unique(), &state, 10).unwrap();
+
+        // Accounts added in the current slot aren't added again
+        assert_eq!(
+            fetched.missing(&[held, missing, pending, missing]),
+            vec![missing]
+        );
+        assert!(fetched.missing(&[held, pending]).is_empty());
+    }
+
+    #[test]
+    fn test_cached_table_is_refetched_once_stale() {
+        let fetched_at = Instant::now();
+        let cached = CachedTable {
+            table: None,
+            fetched_at,
+        };
+        assert!(!cached.is_stale(fetched_at));
+        assert!(!cached.is_stale(fetched_at + REFRESH_INTERVAL / 2));
+        assert!(cached.is_stale(fetched_at + REFRESH_INTERVAL));
+    }
+}
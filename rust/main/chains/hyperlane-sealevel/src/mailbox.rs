@@ -1,9 +1,10 @@
 // Silence a clippy bug https://github.com/rust-lang/rust-clippy/issues/12281
 #![allow(clippy::blocks_in_conditions)]
 
-use std::{collections::HashMap, ops::RangeInclusive, str::FromStr as _};
+use std::{collections::HashMap, ops::RangeInclusive, str::FromStr as _, sync::Mutex};
 
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as Base64, Engine};
 use borsh::{BorshDeserialize, BorshSerialize};
 use hyperlane_sealevel_interchain_security_module_interface::{
     InterchainSecurityModuleInstruction, VerifyInstruction,
@@ -11,9 +12,12 @@ use hyperlane_sealevel_interchain_security_module_interface::{
 use hyperlane_sealevel_mailbox::{
     accounts::{
         DispatchedMessageAccount, Inbox, InboxAccount, ProcessedMessageAccount,
-        DISPATCHED_MESSAGE_DISCRIMINATOR, PROCESSED_MESSAGE_DISCRIMINATOR,
+        ProcessedNoncesAccount, DISPATCHED_MESSAGE_DISCRIMINATOR, PROCESSED_MESSAGE_DISCRIMINATOR,
+    },
+    events::ClosedProcessedMessage,
+    instruction::{
+        closed_processed_messages_pda, processed_nonces_pda, recipient_ism_pda, InboxProcess,
     },
-    instruction::InboxProcess,
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds,
     mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds,
 };
@@ -25,27 +29,33 @@ use serializable_account_meta::SimulationReturnData;
 use solana_program::pubkey;
 use solana_sdk::{
     account::Account,
+    address_lookup_table_account::AddressLookupTableAccount,
     clock::Slot,
     commitment_config::CommitmentConfig,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
+    signature::Signature,
     signer::{keypair::Keypair, Signer as _},
 };
+use solana_transaction_status::option_serializer::OptionSerializer;
 use tracing::{debug, info, instrument, warn};
 
 use hyperlane_core::{
     config::StrOrIntParseError, ChainCommunicationError, ChainResult, ContractLocator, Decode as _,
-    Encode as _, FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
-    HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, MerkleTreeHook,
-    ReorgPeriod, SequenceAwareIndexer, TxCostEstimate, TxOutcome, H256, H512, U256,
+    DeliveryDryRun, Encode as _, FixedPointNumber, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox,
+    MerkleTreeHook, MessageDeliverySimulator, ReorgPeriod, SequenceAwareIndexer, TxCostEstimate,
+    TxOutcome, H256, H512, U256,
 };
 
+use crate::error::HyperlaneSealevelError;
 use crate::log_meta_composer::{
     is_message_delivery_instruction, is_message_dispatch_instruction, LogMetaComposer,
 };
 use crate::tx_submitter::TransactionSubmitter;
 use crate::{
     account::{search_accounts_by_discriminator, search_and_validate_account},
+    lookup_table::ProcessLookupTable,
     priority_fee::PriorityFeeOracle,
 };
 use crate::{ConnectionConf, SealevelProvider, SealevelRpcClient};
@@ -53,6 +63,10 @@ use crate::{ConnectionConf, SealevelProvider, SealevelRpcClient};
 const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
 const SPL_NOOP: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
 
+/// The maximum number of times the handle account metas are simulated with
+/// the accounts last returned, see `get_handle_account_metas`.
+const MAX_HANDLE_ACCOUNT_METAS_ROUNDS: usize = 3;
+
 // Earlier versions of collateral warp routes were deployed off a version where the mint
 // was requested as a writeable account for handle instruction. This is not necessary,
 // and generally requires a higher priority fee to be paid.
@@ -82,6 +96,21 @@ pub struct SealevelMailbox {
     payer: Option<Keypair>,
     priority_fee_oracle: Box<dyn PriorityFeeOracle>,
     tx_submitter: Box<dyn TransactionSubmitter>,
+    lookup_table: Option<ProcessLookupTable>,
+    compute_unit_headroom_percent: u32,
+    /// Whether recipients resolve handle account metas from the data of the
+    /// account metas they return
+    recipients_resolving_account_metas: Mutex<HashMap<Pubkey, bool>>,
+}
+
+/// An instruction to process a message
+struct ProcessInstruction {
+    instruction: Instruction,
+    /// The ISM verifying the message
+    ism: Pubkey,
+    /// The mailbox and ISM accounts of the instruction, which process
+    /// instructions of other messages share
+    shared_accounts: Vec<Pubkey>,
 }
 
 impl SealevelMailbox {
@@ -102,6 +131,16 @@ impl SealevelMailbox {
             domain, program_id, inbox.0, inbox.1, outbox.0, outbox.1,
         );
 
+        let lookup_table = conf.address_lookup_table.map(|address| {
+            ProcessLookupTable::new(
+                address,
+                provider.shared_rpc(),
+                payer.as_ref(),
+                conf.transaction_submitter
+                    .create_submitter(provider.rpc().url()),
+            )
+        });
+
         Ok(SealevelMailbox {
             program_id,
             inbox,
@@ -111,6 +150,9 @@ impl SealevelMailbox {
             tx_submitter: conf
                 .transaction_submitter
                 .create_submitter(provider.rpc().url()),
+            lookup_table,
+            compute_unit_headroom_percent: conf.compute_unit_headroom_percent,
+            recipients_resolving_account_metas: Default::default(),
             provider,
         })
     }
@@ -166,6 +208,7 @@ impl SealevelMailbox {
     /// Gets the recipient ISM given a recipient program id and the ISM getter account metas.
     pub async fn get_recipient_ism(
         &self,
+        inbox: &Inbox,
         recipient_program_id: Pubkey,
         ism_getter_account_metas: Vec<AccountMeta>,
     ) -> ChainResult<Pubkey> {
@@ -175,6 +218,14 @@ impl SealevelMailbox {
             // The recipient program.
             AccountMeta::new_readonly(recipient_program_id, false),
         ];
+        // The recipient ISM PDA, holding the recipient's ISM override if it
+        // registered one. Mailboxes without recipient ISM overrides don't expect it.
+        if inbox.recipient_isms_enabled {
+            let (recipient_ism_key, _recipient_ism_bump) =
+                recipient_ism_pda(&self.program_id, &recipient_program_id)
+                    .map_err(ChainCommunicationError::from_other)?;
+            accounts.push(AccountMeta::new_readonly(recipient_ism_key, false));
+        }
         accounts.extend(ism_getter_account_metas);
 
         let instruction = Instruction::new_with_borsh(
@@ -208,6 +259,7 @@ impl SealevelMailbox {
                 .encode()
                 .map_err(ChainCommunicationError::from_other)?,
                 hyperlane_sealevel_message_recipient_interface::INTERCHAIN_SECURITY_MODULE_ACCOUNT_METAS_PDA_SEEDS,
+                vec![],
         ).await
     }
 
@@ -229,6 +281,7 @@ impl SealevelMailbox {
                 .encode()
                 .map_err(ChainCommunicationError::from_other)?,
             hyperlane_sealevel_interchain_security_module_interface::VERIFY_ACCOUNT_METAS_PDA_SEEDS,
+            vec![],
         )
         .await
     }
@@ -245,16 +298,73 @@ impl SealevelMailbox {
             message: message.body.clone(),
         });
 
+        let instruction_data = instruction
+            .encode()
+            .map_err(ChainCommunicationError::from_other)?;
+        let account_metas_pda_seeds =
+            hyperlane_sealevel_message_recipient_interface::HANDLE_ACCOUNT_METAS_PDA_SEEDS;
         let mut account_metas = self
             .get_account_metas_with_instruction_bytes(
                 recipient_program_id,
-                &instruction
-                    .encode()
-                    .map_err(ChainCommunicationError::from_other)?,
-                hyperlane_sealevel_message_recipient_interface::HANDLE_ACCOUNT_METAS_PDA_SEEDS,
+                &instruction_data,
+                account_metas_pda_seeds,
+                vec![],
             )
             .await?;
 
+        // Recipients can require accounts they resolve from the data of the
+        // accounts they return, e.g. the extra accounts of a Token-2022 transfer
+        // hook of collateral warp routes. So the accounts they return are
+        // supplied until they no longer change, unless the recipient is known
+        // not to resolve any.
+        let resolves_account_metas = self
+            .recipients_resolving_account_metas
+            .lock()
+            .unwrap()
+            .get(&recipient_program_id)
+            .copied();
+        if resolves_account_metas != Some(false) {
+            let mut resolved = false;
+            for _ in 0..MAX_HANDLE_ACCOUNT_METAS_ROUNDS {
+                let supplied_accounts = account_metas
+                    .iter()
+                    .map(|account_meta| AccountMeta::new_readonly(account_meta.pubkey, false))
+                    .collect();
+                let next_account_metas = match self
+                    .get_account_metas_with_instruction_bytes(
+                        recipient_program_id,
+                        &instruction_data,
+                        account_metas_pda_seeds,
+                        supplied_accounts,
+                    )
+                    .await
+                {
+                    Ok(next_account_metas) => next_account_metas,
+                    // Recipients may reject accounts they don't expect
+                    Err(err) if resolves_account_metas.is_none() => {
+                        debug!(
+                            ?err,
+                            recipient=?recipient_program_id,
+                            "Recipient rejected the handle account metas it returned"
+                        );
+                        break;
+                    }
+                    Err(err) => return Err(err),
+                };
+                if next_account_metas == account_metas {
+                    break;
+                }
+                resolved = true;
+                account_metas = next_account_metas;
+            }
+            if resolves_account_metas.is_none() {
+                self.recipients_resolving_account_metas
+                    .lock()
+                    .unwrap()
+                    .insert(recipient_program_id, resolved);
+            }
+        }
+
         if let Some(forced_readonly_account) =
             RECIPIENT_FORCED_READONLY_ACCOUNTS.get(&recipient_program_id)
         {
@@ -272,14 +382,13 @@ impl SealevelMailbox {
         program_id: Pubkey,
         instruction_data: &[u8],
         account_metas_pda_seeds: &[&[u8]],
+        supplied_accounts: Vec<AccountMeta>,
     ) -> ChainResult<Vec<AccountMeta>> {
         let (account_metas_pda_key, _) =
             Pubkey::find_program_address(account_metas_pda_seeds, &program_id);
-        let instruction = Instruction::new_with_bytes(
-            program_id,
-            instruction_data,
-            vec![AccountMeta::new(account_metas_pda_key, false)],
-        );
+        let mut accounts = vec![AccountMeta::new(account_metas_pda_key, false)];
+        accounts.extend(supplied_accounts);
+        let instruction = Instruction::new_with_bytes(program_id, instruction_data, accounts);
 
         self.get_account_metas(instruction).await
     }
@@ -288,7 +397,7 @@ impl SealevelMailbox {
         &self,
         message: &HyperlaneMessage,
         metadata: &[u8],
-    ) -> ChainResult<Instruction> {
+    ) -> ChainResult<ProcessInstruction> {
         let recipient: Pubkey = message.recipient.0.into();
         let mut encoded_message = vec![];
         message.write_to(&mut encoded_message).unwrap();
@@ -318,9 +427,11 @@ impl SealevelMailbox {
         // Get the account metas required for the recipient.InterchainSecurityModule instruction.
         let ism_getter_account_metas = self.get_ism_getter_account_metas(recipient).await?;
 
+        let inbox = self.get_inbox().await?;
+
         // Get the recipient ISM.
         let ism = self
-            .get_recipient_ism(recipient, ism_getter_account_metas.clone())
+            .get_recipient_ism(&inbox, recipient, ism_getter_account_metas.clone())
             .await?;
 
         let ixn =
@@ -340,6 +451,25 @@ impl SealevelMailbox {
             AccountMeta::new_readonly(process_authority_key, false),
             AccountMeta::new(processed_message_account_key, false),
         ];
+
+        // If processed message PDAs can be closed, the processed nonces PDA is
+        // required to check the message wasn't processed before.
+        if inbox.processed_message_retention.is_some() {
+            let (processed_nonces_key, _processed_nonces_bump) =
+                processed_nonces_pda(&self.program_id, message)
+                    .map_err(ChainCommunicationError::from_other)?;
+            accounts.push(AccountMeta::new_readonly(processed_nonces_key, false));
+        }
+
+        // If recipients can override the default ISM, the recipient ISM PDA is
+        // required to look up the override.
+        if inbox.recipient_isms_enabled {
+            let (recipient_ism_key, _recipient_ism_bump) =
+                recipient_ism_pda(&self.program_id, &recipient)
+                    .map_err(ChainCommunicationError::from_other)?;
+            accounts.push(AccountMeta::new_readonly(recipient_ism_key, false));
+        }
+
         accounts.extend(ism_getter_account_metas);
         accounts.extend([
             AccountMeta::new_readonly(Pubkey::from_str(SPL_NOOP).unwrap(), false),
@@ -350,6 +480,20 @@ impl SealevelMailbox {
         let ism_verify_account_metas = self
             .get_ism_verify_account_metas(ism, metadata.into(), encoded_message)
             .await?;
+        let shared_accounts = [
+            Pubkey::from_str(SYSTEM_PROGRAM).unwrap(),
+            self.inbox.0,
+            Pubkey::from_str(SPL_NOOP).unwrap(),
+            ism,
+        ]
+        .into_iter()
+        .chain(
+            ism_verify_account_metas
+                .iter()
+                .filter(|meta| !meta.is_signer)
+                .map(|meta| meta.pubkey),
+        )
+        .collect();
         accounts.extend(ism_verify_account_metas);
 
         // The recipient.
@@ -365,7 +509,53 @@ impl SealevelMailbox {
             accounts,
         };
 
-        Ok(process_instruction)
+        Ok(ProcessInstruction {
+            instruction: process_instruction,
+            ism,
+            shared_accounts,
+        })
+    }
+
+    /// The address lookup tables to load the accounts of a process
+    /// transaction from. Shared accounts of the instruction the configured
+    /// table is missing are added to it in the background.
+    async fn get_process_lookup_tables(
+        &self,
+        process_instruction: &ProcessInstruction,
+    ) -> ChainResult<Vec<AddressLookupTableAccount>> {
+        let Some(lookup_table) = &self.lookup_table else {
+            return Ok(vec![]);
+        };
+        let table = lookup_table
+            .get(&process_instruction.shared_accounts)
+            .await?;
+        Ok(table.into_iter().collect())
+    }
+
+    /// The compute units consumed by a landed transaction, the price in
+    /// lamports it paid per compute unit consumed, and its slot. The fee is
+    /// charged per signature and per compute unit requested rather than
+    /// consumed, so the price is derived from the fee actually paid.
+    async fn get_transaction_costs(
+        rpc: &SealevelRpcClient,
+        signature: &Signature,
+    ) -> ChainResult<(U256, FixedPointNumber, Option<u64>)> {
+        let txn = rpc
+            .get_transaction_with_commitment(signature, CommitmentConfig::confirmed())
+            .await?;
+        let meta = txn
+            .transaction
+            .meta
+            .ok_or(HyperlaneSealevelError::EmptyMetadata)?;
+        let OptionSerializer::Some(compute_units) = meta.compute_units_consumed else {
+            return Err(HyperlaneSealevelError::EmptyComputeUnitsConsumed.into());
+        };
+        let gas_price = if compute_units == 0 {
+            FixedPointNumber::zero()
+        } else {
+            FixedPointNumber::from(meta.fee) / compute_units
+        };
+        Ok((U256::from(compute_units), gas_price, Some(txn.slot)))
     }
 
     async fn get_inbox(&self) -> ChainResult<Box<Inbox>> {
@@ -384,6 +574,77 @@ impl SealevelMailbox {
             .as_ref()
             .ok_or_else(|| ChainCommunicationError::SignerUnavailable)
     }
+
+    /// Whether each of `messages` was delivered. A message was delivered if
+    /// its processed message PDA exists or, once that PDA was closed, if its
+    /// nonce is marked in the processed nonces of its origin and recipient.
+    async fn messages_delivered(&self, messages: &[HyperlaneMessage]) -> ChainResult<Vec<bool>> {
+        let processed_message_pdas = messages
+            .iter()
+            .map(|message| self.processed_message_pda(message.id()))
+            .collect::<Vec<_>>();
+        let processed_message_accounts = self
+            .rpc()
+            .get_multiple_accounts_with_finalized_commitment(&processed_message_pdas)
+            .await?;
+
+        // Only messages without a processed message PDA need their processed
+        // nonces looked up, which are shared by the messages of a page
+        let mut processed_nonces_pdas = HashMap::new();
+        for (message, account) in messages.iter().zip(&processed_message_accounts) {
+            if account.is_none() {
+                let (processed_nonces_key, _processed_nonces_bump) =
+                    processed_nonces_pda(&self.program_id, message)
+                        .map_err(ChainCommunicationError::from_other)?;
+                processed_nonces_pdas.insert(processed_nonces_key, None);
+            }
+        }
+        if !processed_nonces_pdas.is_empty() {
+            let keys = processed_nonces_pdas.keys().copied().collect::<Vec<_>>();
+            let accounts = self
+                .rpc()
+                .get_multiple_accounts_with_finalized_commitment(&keys)
+                .await?;
+            for (key, account) in keys.into_iter().zip(accounts) {
+                let Some(account) = account else {
+                    continue;
+                };
+                let processed_nonces = ProcessedNoncesAccount::fetch(&mut account.data.as_ref())
+                    .map_err(ChainCommunicationError::from_other)?
+                    .into_inner();
+                processed_nonces_pdas.insert(key, Some(processed_nonces));
+            }
+        }
+
+        messages
+            .iter()
+            .zip(processed_message_accounts)
+            .map(|(message, account)| {
+                if account.is_some() {
+                    return Ok(true);
+                }
+                let (processed_nonces_key, _processed_nonces_bump) =
+                    processed_nonces_pda(&self.program_id, message)
+                        .map_err(ChainCommunicationError::from_other)?;
+                Ok(matches!(
+                    processed_nonces_pdas.get(&processed_nonces_key),
+                    Some(Some(processed_nonces)) if processed_nonces.contains(message.nonce)
+                ))
+            })
+            .collect()
+    }
+
+    /// The PDA that is created when the message with the given ID is
+    /// processed. The message is delivered iff the account exists, unless
+    /// the account was closed after the mailbox's processed message retention.
+    fn processed_message_pda(&self, id: H256) -> Pubkey {
+        let (processed_message_account_key, _processed_message_account_bump) =
+            Pubkey::find_program_address(
+                mailbox_processed_message_pda_seeds!(id),
+                &self.program_id,
+            );
+        processed_message_account_key
+    }
 }
 
 impl HyperlaneContract for SealevelMailbox {
@@ -419,20 +680,27 @@ impl Mailbox for SealevelMailbox {
 
     #[instrument(err, ret, skip(self))]
     async fn delivered(&self, id: H256) -> ChainResult<bool> {
-        let (processed_message_account_key, _processed_message_account_bump) =
-            Pubkey::find_program_address(
-                mailbox_processed_message_pda_seeds!(id),
-                &self.program_id,
-            );
-
         let account = self
             .rpc()
-            .get_account_option_with_finalized_commitment(&processed_message_account_key)
+            .get_account_option_with_finalized_commitment(&self.processed_message_pda(id))
             .await?;
 
         Ok(account.is_some())
     }
 
+    #[instrument(err, ret, skip(self))]
+    async fn delivered_message(&self, message: &HyperlaneMessage) -> ChainResult<bool> {
+        let delivered = self
+            .messages_delivered(std::slice::from_ref(message))
+            .await?;
+        Ok(delivered.first().copied().unwrap_or_default())
+    }
+
+    #[instrument(err, skip(self, messages), fields(messages = messages.len()))]
+    async fn delivered_batch(&self, messages: &[HyperlaneMessage]) -> ChainResult<Vec<bool>> {
+        self.messages_delivered(messages).await
+    }
+
     #[instrument(err, ret, skip(self))]
     async fn default_ism(&self) -> ChainResult<H256> {
         let inbox = self.get_inbox().await?;
@@ -449,8 +717,9 @@ impl Mailbox for SealevelMailbox {
             .await?;
 
         // Get the ISM to use.
+        let inbox = self.get_inbox().await?;
         let ism_pubkey = self
-            .get_recipient_ism(recipient_program_id, ism_getter_account_metas)
+            .get_recipient_ism(&inbox, recipient_program_id, ism_getter_account_metas)
             .await?;
 
         Ok(ism_pubkey.to_bytes().into())
@@ -470,15 +739,18 @@ impl Mailbox for SealevelMailbox {
         let commitment = CommitmentConfig::processed();
 
         let process_instruction = self.get_process_instruction(message, metadata).await?;
+        let lookup_tables = self.get_process_lookup_tables(&process_instruction).await?;
 
         let tx = self
             .provider
             .rpc()
             .build_estimated_tx_for_instruction(
-                process_instruction,
+                process_instruction.instruction,
                 self.get_payer()?,
                 &*self.tx_submitter,
                 &*self.priority_fee_oracle,
+                &lookup_tables,
+                self.compute_unit_headroom_percent,
             )
             .await?;
 
@@ -508,12 +780,27 @@ impl Mailbox for SealevelMailbox {
             .unwrap_or(false);
         let txid = signature.into();
 
+        // The costs are only used for accounting, so failing to fetch them
+        // doesn't fail the delivery.
+        let (gas_used, gas_price, block_number) =
+            match Self::get_transaction_costs(rpc, &signature).await {
+                Ok(costs) => costs,
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        ?signature,
+                        "Failed to fetch the costs of the process transaction"
+                    );
+                    (U256::zero(), FixedPointNumber::zero(), None)
+                }
+            };
+
         Ok(TxOutcome {
             transaction_id: txid,
             executed,
-            // TODO use correct data upon integrating IGP support
-            gas_price: U256::zero().try_into()?,
-            gas_used: U256::zero(),
+            gas_price,
+            gas_used,
+            block_number,
         })
     }
 
@@ -527,16 +814,19 @@ impl Mailbox for SealevelMailbox {
         // that involves some view calls. Consider reusing the instruction with subsequent
         // calls to `process` to avoid this cost.
         let process_instruction = self.get_process_instruction(message, metadata).await?;
+        let lookup_tables = self.get_process_lookup_tables(&process_instruction).await?;
 
         // The returned costs are unused at the moment - we simply want to perform a simulation to
         // determine if the message will revert or not.
         let _ = self
             .rpc()
             .get_estimated_costs_for_instruction(
-                process_instruction,
+                process_instruction.instruction,
                 self.get_payer()?,
                 &*self.tx_submitter,
                 &*self.priority_fee_oracle,
+                &lookup_tables,
+                self.compute_unit_headroom_percent,
             )
             .await?;
 
@@ -551,11 +841,129 @@ impl Mailbox for SealevelMailbox {
         })
     }
 
-    fn process_calldata(&self, _message: &HyperlaneMessage, _metadata: &[u8]) -> Vec<u8> {
+    fn process_calldata(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
         todo!()
     }
 }
 
+#[async_trait]
+impl MessageDeliverySimulator for SealevelMailbox {
+    #[instrument(err, ret, skip(self))]
+    async fn dry_run_delivery(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<DeliveryDryRun> {
+        let process_instruction = self.get_process_instruction(message, metadata).await?;
+        let lookup_tables = self.get_process_lookup_tables(&process_instruction).await?;
+        let tx = self
+            .rpc()
+            .create_transaction_for_instruction(
+                SealevelRpcClient::MAX_COMPUTE_UNITS,
+                0,
+                process_instruction.instruction,
+                self.get_payer()?,
+                &*self.tx_submitter,
+                false,
+                &lookup_tables,
+            )
+            .await?;
+        let simulation = self.rpc().simulate_transaction(&tx).await?;
+        let Some(err) = simulation.err else {
+            return Ok(DeliveryDryRun::Delivered);
+        };
+        let logs = simulation.logs.unwrap_or_default();
+        Ok(
+            attribute_process_failure(&logs, &process_instruction.ism, &message.recipient.0.into())
+                .unwrap_or_else(|| DeliveryDryRun::Failed(format!("{err:?}"))),
+        )
+    }
+}
+
+/// Attributes the failure of a simulated process transaction to the program
+/// the mailbox invoked when it failed, going by the logs of the simulation.
+/// Returns `None` if the logs don't show a failing program.
+fn attribute_process_failure(
+    logs: &[String],
+    ism: &Pubkey,
+    recipient: &Pubkey,
+) -> Option<DeliveryDryRun> {
+    // The programs invoked at each depth, the mailbox being the first
+    let mut invoked: Vec<&str> = vec![];
+    for log in logs {
+        let Some(log) = log.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some((program, _depth)) = log.split_once(" invoke [") {
+            invoked.push(program);
+        } else if log.ends_with(" success") {
+            invoked.pop();
+        } else if let Some((_program, reason)) = log.split_once(" failed: ") {
+            let reason = reason.to_owned();
+            return Some(match invoked.get(1) {
+                Some(program) if *program == ism.to_string() => {
+                    DeliveryDryRun::IsmVerificationFailed(reason)
+                }
+                Some(program) if *program == recipient.to_string() => {
+                    DeliveryDryRun::HandleReverted(reason)
+                }
+                _ => DeliveryDryRun::Failed(reason),
+            });
+        }
+    }
+    None
+}
+
+/// The `ClosedProcessedMessage` events the mailbox program emitted in `logs`
+/// of a transaction. Data logged by other programs is ignored, so that they
+/// can't forge events.
+fn closed_processed_messages_in_logs(
+    logs: &[String],
+    program_id: &Pubkey,
+) -> Vec<ClosedProcessedMessage> {
+    let program_id = program_id.to_string();
+    // The programs being invoked, innermost last
+    let mut invocations: Vec<&str> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        if let Some(invoked) = log
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(" invoke ["))
+            .map(|(invoked, _)| invoked)
+        {
+            invocations.push(invoked);
+        } else if log.starts_with("Program ")
+            && (log.ends_with(" success") || log.contains(" failed: "))
+        {
+            invocations.pop();
+        } else if let Some(data) = log.strip_prefix("Program data: ") {
+            if invocations.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let Ok(data) = Base64.decode(data) else {
+                continue;
+            };
+            events.extend(ClosedProcessedMessage::decode(&data));
+        }
+    }
+    events
+}
+
+/// The processed messages whose processed message PDAs were closed, as found
+/// in the transactions that closed them
+#[derive(Debug, Default)]
+struct ClosedProcessedMessages {
+    /// The closed processed messages found so far, by sequence
+    by_sequence: HashMap<u64, ClosedProcessedMessage>,
+    /// The newest transaction closing processed message PDAs that was
+    /// scanned, later scans stop at it
+    newest_scanned: Option<Signature>,
+}
+
 /// Struct that retrieves event data for a Sealevel Mailbox contract
 #[derive(Debug)]
 pub struct SealevelMailboxIndexer {
@@ -564,6 +972,7 @@ pub struct SealevelMailboxIndexer {
     dispatch_message_log_meta_composer: LogMetaComposer,
     delivery_message_log_meta_composer: LogMetaComposer,
     advanced_log_meta: bool,
+    closed_processed_messages: tokio::sync::Mutex<ClosedProcessedMessages>,
 }
 
 impl SealevelMailboxIndexer {
@@ -594,6 +1003,7 @@ impl SealevelMailboxIndexer {
             dispatch_message_log_meta_composer,
             delivery_message_log_meta_composer,
             advanced_log_meta,
+            closed_processed_messages: Default::default(),
         })
     }
 
@@ -713,9 +1123,17 @@ impl SealevelMailboxIndexer {
 
         debug!(account_len = ?accounts.len(), "Found accounts with processed message discriminator");
 
-        let valid_message_storage_pda_pubkey = search_and_validate_account(accounts, |account| {
-            self.delivered_message_account(account)
-        })?;
+        let Ok(valid_message_storage_pda_pubkey) =
+            search_and_validate_account(accounts, |account| {
+                self.delivered_message_account(account)
+            })
+        else {
+            // The processed message PDA may have been closed after the
+            // mailbox's processed message retention
+            return self
+                .get_closed_delivered_message_with_sequence(sequence)
+                .await;
+        };
 
         // Now that we have the valid delivered message storage PDA pubkey,
         // we can get the full account data.
@@ -754,6 +1172,101 @@ impl SealevelMailboxIndexer {
         Ok((indexed, log_meta))
     }
 
+    /// Gets the message delivered with `sequence` from the transaction that
+    /// closed its processed message PDA, scanning the transactions closing
+    /// processed message PDAs since the last scan if it wasn't found yet.
+    async fn get_closed_delivered_message_with_sequence(
+        &self,
+        sequence: u32,
+    ) -> ChainResult<(Indexed<H256>, LogMeta)> {
+        let mut closed_processed_messages = self.closed_processed_messages.lock().await;
+        if !closed_processed_messages
+            .by_sequence
+            .contains_key(&sequence.into())
+        {
+            self.scan_closed_processed_messages(&mut closed_processed_messages)
+                .await?;
+        }
+        let closed = closed_processed_messages
+            .by_sequence
+            .get(&sequence.into())
+            .ok_or_else(|| {
+                ChainCommunicationError::from_other_str(
+                    "Could not find valid storage PDA pubkey or closed processed message",
+                )
+            })?;
+
+        let log_meta = LogMeta {
+            address: self.program_id.to_bytes().into(),
+            block_number: closed.slot,
+            block_hash: H256::zero(),
+            transaction_id: H512::zero(),
+            transaction_index: 0,
+            log_index: U256::zero(),
+        };
+
+        let mut indexed = Indexed::from(closed.message_id);
+        indexed.sequence = Some(sequence);
+
+        Ok((indexed, log_meta))
+    }
+
+    /// Scans the transactions closing processed message PDAs that are newer
+    /// than the ones scanned before.
+    async fn scan_closed_processed_messages(
+        &self,
+        closed_processed_messages: &mut ClosedProcessedMessages,
+    ) -> ChainResult<()> {
+        let (closed_processed_messages_key, _closed_processed_messages_bump) =
+            closed_processed_messages_pda(&self.program_id)
+                .map_err(ChainCommunicationError::from_other)?;
+
+        // Signatures are returned newest first, a page at a time
+        let mut newest = None;
+        let mut before = None;
+        loop {
+            let signatures = self
+                .rpc()
+                .get_signatures_for_address(
+                    &closed_processed_messages_key,
+                    before,
+                    closed_processed_messages.newest_scanned,
+                    None,
+                )
+                .await?;
+            let Some(last) = signatures.last() else {
+                break;
+            };
+            before = Some(
+                Signature::from_str(&last.signature)
+                    .map_err(ChainCommunicationError::from_other)?,
+            );
+            newest = newest.or_else(|| signatures.first().map(|s| s.signature.clone()));
+
+            for signature in signatures.iter().filter(|s| s.err.is_none()) {
+                let signature = Signature::from_str(&signature.signature)
+                    .map_err(ChainCommunicationError::from_other)?;
+                let transaction = self.rpc().get_transaction(&signature).await?;
+                let Some(OptionSerializer::Some(logs)) =
+                    transaction.transaction.meta.map(|meta| meta.log_messages)
+                else {
+                    continue;
+                };
+                for closed in closed_processed_messages_in_logs(&logs, &self.program_id) {
+                    closed_processed_messages
+                        .by_sequence
+                        .insert(closed.sequence, closed);
+                }
+            }
+        }
+
+        if let Some(newest) = newest {
+            closed_processed_messages.newest_scanned =
+                Some(Signature::from_str(&newest).map_err(ChainCommunicationError::from_other)?);
+        }
+        Ok(())
+    }
+
     fn delivered_message_account(&self, account: &Account) -> ChainResult<Pubkey> {
         let message_id = H256::from_slice(&account.data);
         let (expected_pubkey, _bump) = Pubkey::try_find_program_address(
@@ -869,3 +1382,79 @@ impl SequenceAwareIndexer<H256> for SealevelMailboxIndexer {
         Ok((Some(sequence), tip))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_sealevel_mailbox::events::CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR;
+
+    use super::*;
+
+    #[test]
+    fn test_attribute_process_failure() {
+        let mailbox = Pubkey::new_unique();
+        let ism = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let token = Pubkey::new_unique();
+        let logs = |failing: &[&Pubkey]| -> Vec<String> {
+            let mut logs = vec![
+                "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_owned(),
+                "Program ComputeBudget111111111111111111111111111111 success".to_owned(),
+                format!("Program {mailbox} invoke [1]"),
+            ];
+            for (depth, program) in failing.iter().enumerate() {
+                logs.push(format!("Program {program} invoke [{}]", depth + 2));
+            }
+            for program in failing.iter().rev().copied().chain([&mailbox]) {
+                logs.push(format!(
+                    "Program {program} failed: custom program error: 0x1"
+                ));
+            }
+            logs
+        };
+        let reason = "custom program error: 0x1".to_owned();
+
+        assert_eq!(
+            attribute_process_failure(&logs(&[&ism]), &ism, &recipient),
+            Some(DeliveryDryRun::IsmVerificationFailed(reason.clone()))
+        );
+        // Failures of programs the recipient invokes are failures of the recipient
+        assert_eq!(
+            attribute_process_failure(&logs(&[&recipient, &token]), &ism, &recipient),
+            Some(DeliveryDryRun::HandleReverted(reason.clone()))
+        );
+        assert_eq!(
+            attribute_process_failure(&logs(&[]), &ism, &recipient),
+            Some(DeliveryDryRun::Failed(reason))
+        );
+        assert_eq!(attribute_process_failure(&[], &ism, &recipient), None);
+    }
+
+    #[test]
+    fn test_closed_processed_messages_in_logs() {
+        let mailbox = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let event = ClosedProcessedMessage {
+            sequence: 7,
+            message_id: H256::repeat_byte(1),
+            slot: 100,
+        };
+        let mut data = CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        let data_log = format!("Program data: {}", Base64.encode(data));
+
+        let logs = vec![
+            format!("Program {mailbox} invoke [1]"),
+            format!("Program {other} invoke [2]"),
+            // Logged by another program, ignored
+            data_log.clone(),
+            format!("Program {other} success"),
+            data_log,
+            "Program data: aGVsbG8=".to_owned(),
+            format!("Program {mailbox} success"),
+        ];
+        assert_eq!(
+            closed_processed_messages_in_logs(&logs, &mailbox),
+            vec![event]
+        );
+    }
+}
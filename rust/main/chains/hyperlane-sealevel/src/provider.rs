@@ -46,7 +46,11 @@ impl SealevelProvider {
     /// Create a new Sealevel provider.
     pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf) -> Self {
         // Set the `processed` commitment at rpc level
-        let rpc_client = Arc::new(SealevelRpcClient::new(conf.url.to_string()));
+        let rpc_client = Arc::new(SealevelRpcClient::new_metered(
+            conf.url.to_string(),
+            conf.rate_limit,
+            domain.name(),
+        ));
         let native_token = conf.native_token.clone();
 
         Self {
@@ -61,6 +65,11 @@ impl SealevelProvider {
         &self.rpc_client
     }
 
+    /// Get an rpc client that can be shared with background tasks
+    pub(crate) fn shared_rpc(&self) -> Arc<SealevelRpcClient> {
+        self.rpc_client.clone()
+    }
+
     fn validate_transaction(hash: &H512, txn: &UiTransaction) -> ChainResult<()> {
         let received_signature = txn
             .signatures
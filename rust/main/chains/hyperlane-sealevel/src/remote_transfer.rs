@@ -0,0 +1,74 @@
+use hyperlane_sealevel_mailbox::spl_noop;
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
+
+pub use hyperlane_sealevel_token_lib::event::RemoteTransferEvent;
+
+use crate::log_meta_composer::{filter_by_encoding, filter_by_validity};
+use crate::utils::from_base58;
+
+/// Returns the remote transfer events logged by Hyperlane Token programs in
+/// a successful transaction. Each event carries both the local amount and the
+/// remote amount found in the dispatched message, so no decimal scaling has to
+/// be derived from the warp route configuration.
+pub fn parse_remote_transfer_events(
+    transaction: EncodedTransactionWithStatusMeta,
+) -> Vec<RemoteTransferEvent> {
+    let Some((_, account_keys, instructions)) =
+        filter_by_encoding(transaction).and_then(|(tx, meta)| filter_by_validity(tx, meta))
+    else {
+        return vec![];
+    };
+
+    let spl_noop_id = spl_noop::id().to_string();
+    let Some(spl_noop_index) = account_keys.iter().position(|key| *key == spl_noop_id) else {
+        // Events are logged through the noop program, so the transaction can't contain any
+        return vec![];
+    };
+
+    instructions
+        .into_iter()
+        .filter(|instruction| instruction.program_id_index as usize == spl_noop_index)
+        .filter_map(|instruction| from_base58(&instruction.data).ok())
+        .filter_map(|data| RemoteTransferEvent::from_noop_data(&data))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use hyperlane_core::{H256, U256};
+    use serde_json::{json, Value};
+    use solana_sdk::bs58;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_remote_transfer_events() {
+        let event = RemoteTransferEvent {
+            origin_domain: 1399811149,
+            destination_domain: 1408864445,
+            recipient: H256::repeat_byte(1),
+            local_amount: 1_000_000,
+            remote_amount: U256::from(1_000_000_000_000_000_000u64),
+            message_id: H256::repeat_byte(2),
+        };
+
+        // A warp route transfer, with the event logged by the token program
+        // through the noop program, which is account 18 of the transaction.
+        let json = fs::read_to_string("src/log_meta_composer/dispatch_message_txn.json").unwrap();
+        let mut transaction: Value = serde_json::from_str(&json).unwrap();
+        transaction["meta"]["innerInstructions"][0]["instructions"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({
+                "accounts": [],
+                "data": bs58::encode(event.to_noop_data()).into_string(),
+                "programIdIndex": 18,
+                "stackHeight": 2
+            }));
+        let transaction = serde_json::from_value(transaction).unwrap();
+
+        assert_eq!(parse_remote_transfer_events(transaction), vec![event]);
+    }
+}
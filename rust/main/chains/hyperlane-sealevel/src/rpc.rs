@@ -1,3 +1,4 @@
 pub use client::SealevelRpcClient;
 
 mod client;
+mod sender;
@@ -3,39 +3,51 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
 use solana_client::{
     nonblocking::rpc_client::RpcClient,
-    rpc_client::SerializableTransaction,
+    rpc_client::{
+        GetConfirmedSignaturesForAddress2Config, RpcClientConfig, SerializableTransaction,
+    },
     rpc_config::{
         RpcBlockConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig,
         RpcSimulateTransactionConfig, RpcTransactionConfig,
     },
-    rpc_response::{Response, RpcSimulateTransactionResult},
+    rpc_response::{
+        Response, RpcConfirmedTransactionStatusWithSignature, RpcSimulateTransactionResult,
+    },
 };
 use solana_program::clock::Slot;
 use solana_sdk::{
     account::Account,
+    address_lookup_table_account::AddressLookupTableAccount,
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
     hash::Hash,
     instruction::{AccountMeta, Instruction},
-    message::Message,
+    message::{v0, Message, VersionedMessage},
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 use solana_transaction_status::{
     EncodedConfirmedTransactionWithStatusMeta, TransactionStatus, UiConfirmedBlock,
     UiReturnDataEncoding, UiTransactionEncoding,
 };
 
-use hyperlane_core::{ChainCommunicationError, ChainResult, U256};
+use hyperlane_core::{
+    config::RateLimitConf,
+    rpc_clients::{RateLimiter, RpcEndpointMetrics},
+    ChainCommunicationError, ChainResult, U256,
+};
 
 use crate::{
     error::HyperlaneSealevelError, priority_fee::PriorityFeeOracle,
     tx_submitter::TransactionSubmitter,
 };
 
-const COMPUTE_UNIT_MULTIPLIER_NUMERATOR: u32 = 11;
-const COMPUTE_UNIT_MULTIPLIER_DENOMINATOR: u32 = 10;
+use super::sender::{HttpClientSender, MeteredSender};
+
+/// The maximum number of accounts a single `getMultipleAccounts` request may
+/// ask for
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 
 const PRIORITY_FEE_MULTIPLIER_NUMERATOR: u64 = 110;
 const PRIORITY_FEE_MULTIPLIER_DENOMINATOR: u64 = 100;
@@ -49,12 +61,29 @@ pub struct SealevelRpcClient(RpcClient);
 
 impl SealevelRpcClient {
     /// The max amount of compute units for a transaction.
-    const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+    pub(crate) const MAX_COMPUTE_UNITS: u32 = 1_400_000;
 
     pub fn new(rpc_endpoint: String) -> Self {
-        Self(RpcClient::new_with_commitment(
-            rpc_endpoint,
-            CommitmentConfig::processed(),
+        Self(RpcClient::new_sender(
+            HttpClientSender::new(rpc_endpoint),
+            RpcClientConfig::with_commitment(CommitmentConfig::processed()),
+        ))
+    }
+
+    /// Create a client for `chain` that records request metrics and waits for
+    /// the rate limit of the endpoint, if one is configured, before sending
+    /// requests
+    pub fn new_metered(
+        rpc_endpoint: String,
+        rate_limit: Option<RateLimitConf>,
+        chain: &str,
+    ) -> Self {
+        let rate_limiter =
+            rate_limit.map(|rate_limit| RateLimiter::for_endpoint(&rpc_endpoint, rate_limit));
+        let metrics = RpcEndpointMetrics::new(&rpc_endpoint, chain);
+        Self(RpcClient::new_sender(
+            MeteredSender::new(rpc_endpoint, rate_limiter, metrics),
+            RpcClientConfig::with_commitment(CommitmentConfig::processed()),
         ))
     }
 
@@ -142,16 +171,22 @@ impl SealevelRpcClient {
             .map_err(Into::into)
     }
 
+    /// Fetches the given accounts, in as many `getMultipleAccounts` requests as
+    /// needed. Accounts that don't exist are returned as `None`.
     pub async fn get_multiple_accounts_with_finalized_commitment(
         &self,
         pubkeys: &[Pubkey],
     ) -> ChainResult<Vec<Option<Account>>> {
-        let accounts = self
-            .0
-            .get_multiple_accounts_with_commitment(pubkeys, CommitmentConfig::finalized())
-            .await
-            .map_err(ChainCommunicationError::from_other)?
-            .value;
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let chunk_accounts = self
+                .0
+                .get_multiple_accounts_with_commitment(chunk, CommitmentConfig::finalized())
+                .await
+                .map_err(ChainCommunicationError::from_other)?
+                .value;
+            accounts.extend(chunk_accounts);
+        }
 
         Ok(accounts)
     }
@@ -188,6 +223,26 @@ impl SealevelRpcClient {
             .map_err(ChainCommunicationError::from_other)
     }
 
+    /// Signatures of finalized transactions involving `address`, newest first
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: Option<usize>,
+    ) -> ChainResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until,
+            limit,
+            commitment: Some(CommitmentConfig::finalized()),
+        };
+        self.0
+            .get_signatures_for_address_with_config(address, config)
+            .await
+            .map_err(ChainCommunicationError::from_other)
+    }
+
     pub async fn get_slot(&self) -> ChainResult<u32> {
         let slot = self
             .get_slot_raw()
@@ -208,10 +263,19 @@ impl SealevelRpcClient {
     pub async fn get_transaction(
         &self,
         signature: &Signature,
+    ) -> ChainResult<EncodedConfirmedTransactionWithStatusMeta> {
+        self.get_transaction_with_commitment(signature, CommitmentConfig::finalized())
+            .await
+    }
+
+    pub async fn get_transaction_with_commitment(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
     ) -> ChainResult<EncodedConfirmedTransactionWithStatusMeta> {
         let config = RpcTransactionConfig {
             encoding: Some(UiTransactionEncoding::JsonParsed),
-            commitment: Some(CommitmentConfig::finalized()),
+            commitment: Some(commitment),
             ..Default::default()
         };
         self.0
@@ -230,7 +294,7 @@ impl SealevelRpcClient {
 
     pub async fn send_transaction(
         &self,
-        transaction: &Transaction,
+        transaction: &VersionedTransaction,
         skip_preflight: bool,
     ) -> ChainResult<Signature> {
         self.0
@@ -334,7 +398,7 @@ impl SealevelRpcClient {
 
     pub async fn simulate_transaction(
         &self,
-        transaction: &Transaction,
+        transaction: &impl SerializableTransaction,
     ) -> ChainResult<RpcSimulateTransactionResult> {
         let result = self
             .0
@@ -353,13 +417,16 @@ impl SealevelRpcClient {
         Ok(result)
     }
 
-    /// Gets the estimated costs for a given instruction.
+    /// Gets the estimated costs for a given instruction. The compute unit limit is the compute
+    /// units consumed when simulating the instruction, plus `compute_unit_headroom_percent`.
     pub async fn get_estimated_costs_for_instruction(
         &self,
         instruction: Instruction,
         payer: &Keypair,
         tx_submitter: &dyn TransactionSubmitter,
         priority_fee_oracle: &dyn PriorityFeeOracle,
+        address_lookup_tables: &[AddressLookupTableAccount],
+        compute_unit_headroom_percent: u32,
     ) -> ChainResult<SealevelTxCostEstimate> {
         // Build a transaction that sets the max compute units and a dummy compute unit price.
         // This is used for simulation to get the actual compute unit limit. We set dummy values
@@ -373,6 +440,7 @@ impl SealevelRpcClient {
                 payer,
                 tx_submitter,
                 false,
+                address_lookup_tables,
             )
             .await?;
 
@@ -380,6 +448,15 @@ impl SealevelRpcClient {
 
         // If there was an error in the simulation result, return an error.
         if simulation_result.err.is_some() {
+            // Warp routes reject transfers exceeding their transfer limits
+            // until enough capacity has refilled, which isn't worth submitting
+            // the transaction for, nor logging as an error.
+            if let Some(log) = simulation_result.logs.iter().flatten().find(|log| {
+                log.contains(hyperlane_sealevel_token_lib::error::TRANSFER_LIMIT_EXCEEDED_LOG)
+            }) {
+                tracing::warn!(%log, "Transaction exceeds a warp route transfer limit");
+                return Err(ChainCommunicationError::from_other_str(log));
+            }
             tracing::error!(?simulation_result, "Got simulation result for transaction");
             return Err(ChainCommunicationError::from_other_str(
                 format!("Error in simulation result: {:?}", simulation_result.err).as_str(),
@@ -402,10 +479,8 @@ impl SealevelRpcClient {
         }
 
         // Bump the compute units to be conservative
-        let simulation_compute_units = Self::MAX_COMPUTE_UNITS.min(
-            (simulation_compute_units * COMPUTE_UNIT_MULTIPLIER_NUMERATOR)
-                / COMPUTE_UNIT_MULTIPLIER_DENOMINATOR,
-        );
+        let simulation_compute_units =
+            Self::with_headroom(simulation_compute_units, compute_unit_headroom_percent);
 
         let mut priority_fee = priority_fee_oracle.get_priority_fee(&simulation_tx).await?;
 
@@ -436,6 +511,13 @@ impl SealevelRpcClient {
         })
     }
 
+    /// Adds `headroom_percent` to the compute units consumed in a simulation, capped at the max
+    /// amount of compute units for a transaction.
+    fn with_headroom(compute_units: u32, headroom_percent: u32) -> u32 {
+        let compute_units = u64::from(compute_units) * (100 + u64::from(headroom_percent)) / 100;
+        compute_units.min(Self::MAX_COMPUTE_UNITS.into()) as u32
+    }
+
     /// Builds a transaction with estimated costs for a given instruction.
     pub async fn build_estimated_tx_for_instruction(
         &self,
@@ -443,7 +525,9 @@ impl SealevelRpcClient {
         payer: &Keypair,
         tx_submitter: &dyn TransactionSubmitter,
         priority_fee_oracle: &dyn PriorityFeeOracle,
-    ) -> ChainResult<Transaction> {
+        address_lookup_tables: &[AddressLookupTableAccount],
+        compute_unit_headroom_percent: u32,
+    ) -> ChainResult<VersionedTransaction> {
         // Get the estimated costs for the instruction.
         let SealevelTxCostEstimate {
             compute_units,
@@ -454,6 +538,8 @@ impl SealevelRpcClient {
                 payer,
                 tx_submitter,
                 priority_fee_oracle,
+                address_lookup_tables,
+                compute_unit_headroom_percent,
             )
             .await?;
 
@@ -472,6 +558,7 @@ impl SealevelRpcClient {
                 payer,
                 tx_submitter,
                 true,
+                address_lookup_tables,
             )
             .await?;
 
@@ -480,6 +567,9 @@ impl SealevelRpcClient {
 
     /// Creates a transaction for a given instruction, compute unit limit, and compute unit price.
     /// If `sign` is true, the transaction will be signed.
+    /// If any address lookup tables are given, a v0 transaction loading accounts from them is
+    /// created, and a legacy transaction otherwise.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_transaction_for_instruction(
         &self,
         compute_unit_limit: u32,
@@ -488,7 +578,8 @@ impl SealevelRpcClient {
         payer: &Keypair,
         tx_submitter: &dyn TransactionSubmitter,
         sign: bool,
-    ) -> ChainResult<Transaction> {
+        address_lookup_tables: &[AddressLookupTableAccount],
+    ) -> ChainResult<VersionedTransaction> {
         let instructions = vec![
             // Set the compute unit limit.
             ComputeBudgetInstruction::set_compute_unit_limit(compute_unit_limit),
@@ -501,23 +592,47 @@ impl SealevelRpcClient {
             instruction,
         ];
 
-        let tx = if sign {
+        let recent_blockhash = if sign {
             // Getting the finalized blockhash eliminates the chance the blockhash
             // gets reorged out, causing the tx to be invalid. The tradeoff is this
             // will cause the tx to expire in about 47 seconds (instead of the typical 60).
-            let recent_blockhash = self
-                .get_latest_blockhash_with_commitment(CommitmentConfig::finalized())
+            self.get_latest_blockhash_with_commitment(CommitmentConfig::finalized())
                 .await
-                .map_err(ChainCommunicationError::from_other)?;
+                .map_err(ChainCommunicationError::from_other)?
+        } else {
+            // Unsigned transactions are only simulated, which replaces the blockhash
+            Hash::default()
+        };
 
-            Transaction::new_signed_with_payer(
+        let message = if address_lookup_tables.is_empty() {
+            VersionedMessage::Legacy(Message::new_with_blockhash(
                 &instructions,
                 Some(&payer.pubkey()),
-                &[payer],
-                recent_blockhash,
+                &recent_blockhash,
+            ))
+        } else {
+            VersionedMessage::V0(
+                v0::Message::try_compile(
+                    &payer.pubkey(),
+                    &instructions,
+                    address_lookup_tables,
+                    recent_blockhash,
+                )
+                .map_err(ChainCommunicationError::from_other)?,
             )
+        };
+
+        let tx = if sign {
+            VersionedTransaction::try_new(message, &[payer])
+                .map_err(ChainCommunicationError::from_other)?
         } else {
-            Transaction::new_unsigned(Message::new(&instructions, Some(&payer.pubkey())))
+            VersionedTransaction {
+                signatures: vec![
+                    Signature::default();
+                    message.header().num_required_signatures.into()
+                ],
+                message,
+            }
         };
 
         Ok(tx)
@@ -12,3 +12,81 @@ async fn _test_get_block() {
     // then
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_compute_units_with_headroom() {
+    assert_eq!(SealevelRpcClient::with_headroom(100_000, 10), 110_000);
+    assert_eq!(SealevelRpcClient::with_headroom(100_000, 0), 100_000);
+    // Capped at the max compute units of a transaction
+    assert_eq!(
+        SealevelRpcClient::with_headroom(1_300_000, 50),
+        SealevelRpcClient::MAX_COMPUTE_UNITS
+    );
+}
+
+#[tokio::test]
+async fn test_accounts_are_loaded_from_lookup_tables() {
+    use solana_sdk::{
+        address_lookup_table_account::AddressLookupTableAccount,
+        instruction::{AccountMeta, Instruction},
+        message::VersionedMessage,
+        pubkey::Pubkey,
+        signature::Keypair,
+    };
+
+    use crate::tx_submitter::RpcTransactionSubmitter;
+
+    // Unsigned transactions are built without any requests
+    let client = SealevelRpcClient::new("http://localhost:8899".to_string());
+    let tx_submitter = RpcTransactionSubmitter::new(client.url());
+    let payer = Keypair::new();
+    let shared = Pubkey::new_unique();
+    let unshared = Pubkey::new_unique();
+    let instruction = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[],
+        vec![
+            AccountMeta::new_readonly(shared, false),
+            AccountMeta::new(unshared, false),
+        ],
+    );
+    let table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: vec![shared],
+    };
+
+    let tx = client
+        .create_transaction_for_instruction(
+            1_000,
+            0,
+            instruction.clone(),
+            &payer,
+            &tx_submitter,
+            false,
+            &[table.clone()],
+        )
+        .await
+        .unwrap();
+    let VersionedMessage::V0(message) = &tx.message else {
+        panic!("Expected a v0 message");
+    };
+    assert_eq!(message.address_table_lookups.len(), 1);
+    assert_eq!(message.address_table_lookups[0].account_key, table.key);
+    assert_eq!(message.address_table_lookups[0].readonly_indexes, vec![0]);
+    assert!(message.account_keys.contains(&unshared));
+    assert!(!message.account_keys.contains(&shared));
+
+    let tx = client
+        .create_transaction_for_instruction(
+            1_000,
+            0,
+            instruction,
+            &payer,
+            &tx_submitter,
+            false,
+            &[],
+        )
+        .await
+        .unwrap();
+    assert!(matches!(tx.message, VersionedMessage::Legacy(_)));
+}
@@ -0,0 +1,261 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use hyperlane_core::rpc_clients::{RateLimiter, RpcEndpointMetrics};
+use reqwest::{
+    header::{CONTENT_TYPE, RETRY_AFTER},
+    StatusCode, Url,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    rpc_custom_error::{
+        NodeUnhealthyErrorData, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+    },
+    rpc_request::{RpcError, RpcRequest, RpcResponseErrorData},
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use tracing::debug;
+
+/// How often a request that's rate limited by the endpoint is retried
+const RATE_LIMITED_RETRIES: usize = 5;
+/// Backoff before retrying a rate limited request, if the endpoint doesn't
+/// say how long to wait
+const RATE_LIMITED_BACKOFF: Duration = Duration::from_millis(500);
+
+#[derive(Deserialize)]
+struct RpcErrorObject {
+    code: i64,
+    message: String,
+}
+
+/// Sends requests over the shared HTTP client of the agent, so that they
+/// use its proxy, certificates and timeouts. Otherwise it behaves like the
+/// `HttpSender` of the Solana client, which builds a client of its own.
+pub(crate) struct HttpClientSender {
+    url: String,
+    request_id: AtomicU64,
+    stats: Mutex<RpcTransportStats>,
+}
+
+impl HttpClientSender {
+    pub(crate) fn new(url: String) -> Self {
+        Self {
+            url,
+            request_id: AtomicU64::new(0),
+            stats: Default::default(),
+        }
+    }
+
+    /// Post a JSON-RPC request, retrying it while the endpoint rate limits it
+    async fn post(&self, body: String) -> ClientResult<Value> {
+        let url = Url::parse(&self.url).map_err(|err| {
+            ClientErrorKind::Custom(format!("Invalid RPC URL `{}`: {err}", self.url))
+        })?;
+        let client = reqwest_utils::client_for(&url)
+            .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+        let mut retries = RATE_LIMITED_RETRIES;
+        loop {
+            let response = client
+                .post(url.clone())
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send()
+                .await?;
+            if response.status() == StatusCode::TOO_MANY_REQUESTS && retries > 0 {
+                let backoff = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|retry_after| retry_after.to_str().ok())
+                    .and_then(|retry_after| retry_after.parse().ok())
+                    .map(Duration::from_secs)
+                    .unwrap_or(RATE_LIMITED_BACKOFF);
+                retries -= 1;
+                debug!(
+                    url = self.url,
+                    ?backoff,
+                    "Rate limited by the RPC, retrying"
+                );
+                tokio::time::sleep(backoff).await;
+                self.stats.lock().unwrap().rate_limited_time += backoff;
+                continue;
+            }
+            return Ok(response.error_for_status()?.json().await?);
+        }
+    }
+}
+
+/// The result of a JSON-RPC response, or the error it holds
+fn parse_response(mut response: Value) -> ClientResult<Value> {
+    if !response["error"].is_object() {
+        return Ok(response["result"].take());
+    }
+    let error = response["error"].take();
+    let RpcErrorObject { code, message } =
+        serde_json::from_value(error.clone()).map_err(|err| {
+            RpcError::RpcRequestError(format!(
+                "Failed to deserialize RPC error response: {error} [{err}]"
+            ))
+        })?;
+    let data = match code {
+        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE => {
+            serde_json::from_value(error["data"].clone())
+                .map(RpcResponseErrorData::SendTransactionPreflightFailure)
+                .unwrap_or(RpcResponseErrorData::Empty)
+        }
+        JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY => {
+            serde_json::from_value::<NodeUnhealthyErrorData>(error["data"].clone())
+                .map(|data| RpcResponseErrorData::NodeUnhealthy {
+                    num_slots_behind: data.num_slots_behind,
+                })
+                .unwrap_or(RpcResponseErrorData::Empty)
+        }
+        _ => RpcResponseErrorData::Empty,
+    };
+    Err(RpcError::RpcResponseError {
+        code,
+        message,
+        data,
+    }
+    .into())
+}
+
+#[async_trait]
+impl RpcSender for HttpClientSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let started = Instant::now();
+        let request_id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        let body = request.build_request_json(request_id, params).to_string();
+        let response = self.post(body).await;
+        {
+            let mut stats = self.stats.lock().unwrap();
+            stats.request_count += 1;
+            stats.elapsed_time += started.elapsed();
+        }
+        parse_response(response?)
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+}
+
+/// An HTTP sender that records the metrics of every request and waits for the
+/// rate limit of its endpoint, if any, before sending it
+pub(crate) struct MeteredSender {
+    inner: HttpClientSender,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    metrics: RpcEndpointMetrics,
+}
+
+impl MeteredSender {
+    pub(crate) fn new(
+        url: String,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        metrics: RpcEndpointMetrics,
+    ) -> Self {
+        Self {
+            inner: HttpClientSender::new(url),
+            rate_limiter,
+            metrics,
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for MeteredSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        self.metrics
+            .track(&request.to_string(), self.inner.send(request, params))
+            .await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let result = parse_response(json!({"jsonrpc": "2.0", "result": 5, "id": 1}));
+        assert_eq!(result.unwrap(), json!(5));
+
+        let err = parse_response(json!({
+            "jsonrpc": "2.0",
+            "error": {
+                "code": JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+                "message": "Node is behind",
+                "data": {"numSlotsBehind": 10}
+            },
+            "id": 1
+        }))
+        .unwrap_err();
+        assert!(
+            matches!(
+                err.kind(),
+                ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code: JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+                    data: RpcResponseErrorData::NodeUnhealthy { .. },
+                    ..
+                })
+            ),
+            "{err:?}"
+        );
+
+        let err = parse_response(json!({
+            "jsonrpc": "2.0",
+            "error": {"code": -32602, "message": "Invalid params"},
+            "id": 1
+        }))
+        .unwrap_err();
+        assert!(
+            matches!(
+                err.kind(),
+                ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                    code: -32602,
+                    data: RpcResponseErrorData::Empty,
+                    ..
+                })
+            ),
+            "{err:?}"
+        );
+
+        let err = parse_response(json!({"jsonrpc": "2.0", "error": {}, "id": 1})).unwrap_err();
+        assert!(
+            matches!(
+                err.kind(),
+                ClientErrorKind::RpcError(RpcError::RpcRequestError(_))
+            ),
+            "{err:?}"
+        );
+    }
+}
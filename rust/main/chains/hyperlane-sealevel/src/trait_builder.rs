@@ -1,5 +1,9 @@
-use hyperlane_core::{config::OperationBatchConfig, ChainCommunicationError, NativeToken};
+use hyperlane_core::{
+    config::{OperationBatchConfig, RateLimitConf},
+    ChainCommunicationError, NativeToken,
+};
 use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
 use url::Url;
 
 use crate::{
@@ -20,6 +24,20 @@ pub struct ConnectionConf {
     pub priority_fee_oracle: PriorityFeeOracleConfig,
     /// Transaction submitter configuration
     pub transaction_submitter: TransactionSubmitterConfig,
+    /// Rate limit applied to the RPC endpoint
+    pub rate_limit: Option<RateLimitConf>,
+    /// Address lookup table holding the mailbox and ISM accounts of process
+    /// transactions. If set, messages are processed with v0 transactions
+    /// that load these accounts from the table.
+    pub address_lookup_table: Option<Pubkey>,
+    /// How many percent of compute units to request on top of those
+    /// consumed when simulating a transaction
+    pub compute_unit_headroom_percent: u32,
+}
+
+impl ConnectionConf {
+    /// The default compute unit headroom, in percent
+    pub const DEFAULT_COMPUTE_UNIT_HEADROOM_PERCENT: u32 = 10;
 }
 
 /// An error type when parsing a connection configuration.
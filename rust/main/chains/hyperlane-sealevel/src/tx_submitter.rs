@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use hyperlane_core::ChainResult;
 use solana_sdk::{
     compute_budget::ComputeBudgetInstruction, instruction::Instruction, pubkey::Pubkey,
-    signature::Signature, transaction::Transaction,
+    signature::Signature, transaction::VersionedTransaction,
 };
 
 use crate::SealevelRpcClient;
@@ -21,7 +21,7 @@ pub trait TransactionSubmitter: Send + Sync {
     /// Send a transaction to the chain.
     async fn send_transaction(
         &self,
-        transaction: &Transaction,
+        transaction: &VersionedTransaction,
         skip_preflight: bool,
     ) -> ChainResult<Signature>;
 
@@ -57,7 +57,7 @@ impl TransactionSubmitter for RpcTransactionSubmitter {
 
     async fn send_transaction(
         &self,
-        transaction: &Transaction,
+        transaction: &VersionedTransaction,
         skip_preflight: bool,
     ) -> ChainResult<Signature> {
         self.rpc_client
@@ -113,7 +113,7 @@ impl TransactionSubmitter for JitoTransactionSubmitter {
 
     async fn send_transaction(
         &self,
-        transaction: &Transaction,
+        transaction: &VersionedTransaction,
         skip_preflight: bool,
     ) -> ChainResult<Signature> {
         self.rpc_client
@@ -122,6 +122,7 @@ impl ValidatorAnnounce for SealevelValidatorAnnounce {
             executed: false,
             gas_used: U256::zero(),
             gas_price: U256::zero().try_into()?,
+            block_number: None,
         })
     }
 }
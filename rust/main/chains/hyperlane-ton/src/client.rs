@@ -0,0 +1,521 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hyperlane_core::{ChainCommunicationError, ChainResult, H256};
+use reqwest::{Client, RequestBuilder};
+use reqwest_utils::RetryPolicy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use tonlib_core::TonAddress;
+use tracing::instrument;
+use url::Url;
+
+use crate::error::HyperlaneTonError;
+use crate::stack::StackEntry;
+use crate::ConnectionConf;
+
+/// Max number of transactions requested per page
+const TRANSACTIONS_PAGE_LIMIT: usize = 256;
+
+/// Client of a TON Center v3 compatible HTTP API
+#[derive(Clone, Debug)]
+pub struct TonApiClient {
+    client: Client,
+    url: Url,
+    api_key: Option<String>,
+}
+
+impl TonApiClient {
+    /// Create a client of the API of the connection config
+    pub fn new(conf: &ConnectionConf) -> ChainResult<Self> {
+        let client = reqwest_utils::client_for(&conf.url).map_err(HyperlaneTonError::from)?;
+        Ok(Self {
+            client,
+            url: conf.url.clone(),
+            api_key: conf.api_key.clone(),
+        })
+    }
+
+    /// Run a get method of a contract and return the resulting stack
+    #[instrument(skip(self, stack), err)]
+    pub async fn run_get_method(
+        &self,
+        address: &TonAddress,
+        method: &str,
+        stack: Vec<StackEntry>,
+    ) -> ChainResult<Vec<StackEntry>> {
+        self.try_run_get_method(address, method, stack)
+            .await?
+            .map_err(|exit_code| {
+                HyperlaneTonError::GetMethodFailed {
+                    address: address.to_hex(),
+                    method: method.to_owned(),
+                    exit_code,
+                }
+                .into()
+            })
+    }
+
+    /// Run a get method of a contract and return the resulting stack, or the
+    /// TVM exit code the method failed with, e.g. because the contract has
+    /// no such method. Errors are only returned if the API request failed.
+    pub async fn try_run_get_method(
+        &self,
+        address: &TonAddress,
+        method: &str,
+        stack: Vec<StackEntry>,
+    ) -> ChainResult<Result<Vec<StackEntry>, i32>> {
+        let address = address.to_hex();
+        let request = RunGetMethodRequest {
+            address: &address,
+            method,
+            stack,
+        };
+        let response: RunGetMethodResponse = self
+            .send(
+                self.client
+                    .post(self.endpoint("runGetMethod")?)
+                    .json(&request),
+            )
+            .await?;
+        // Exit code 1 is the alternative success code of TVM
+        if response.exit_code != 0 && response.exit_code != 1 {
+            return Ok(Err(response.exit_code));
+        }
+        Ok(Ok(response.stack))
+    }
+
+    /// Sequence number of the latest masterchain block
+    pub async fn latest_masterchain_seqno(&self) -> ChainResult<u32> {
+        let info: MasterchainInfo = self
+            .send(self.client.get(self.endpoint("masterchainInfo")?))
+            .await?;
+        Ok(info.last.seqno)
+    }
+
+    /// A masterchain block by its sequence number
+    pub async fn masterchain_block(&self, seqno: u32) -> ChainResult<Block> {
+        let request = self
+            .client
+            .get(self.endpoint("blocks")?)
+            .query(&[("workchain", "-1".to_owned()), ("seqno", seqno.to_string())]);
+        let response: BlocksResponse = self.send(request).await?;
+        response
+            .blocks
+            .into_iter()
+            .next()
+            .ok_or_else(|| HyperlaneTonError::BlockNotFound(seqno).into())
+    }
+
+    /// The transactions of an account up to the given unix time, from the
+    /// newest one back to the first one for which `is_last` is true, which
+    /// is included. The transactions are returned sorted from oldest to
+    /// newest.
+    #[instrument(skip(self, is_last), err)]
+    pub async fn account_transactions_until(
+        &self,
+        account: &TonAddress,
+        end_utime: u64,
+        is_last: impl Fn(&Transaction) -> bool,
+    ) -> ChainResult<Vec<Transaction>> {
+        let account = account.to_hex();
+        let mut transactions = vec![];
+        let mut offset = 0;
+        loop {
+            let request = self.client.get(self.endpoint("transactions")?).query(&[
+                ("account", account.clone()),
+                ("end_utime", end_utime.to_string()),
+                ("limit", TRANSACTIONS_PAGE_LIMIT.to_string()),
+                ("offset", offset.to_string()),
+                ("sort", "desc".to_owned()),
+            ]);
+            let page: TransactionsResponse = self.send(request).await?;
+            let page_len = page.transactions.len();
+            offset += page_len;
+            for transaction in page.transactions {
+                let last = is_last(&transaction);
+                transactions.push(transaction);
+                if last {
+                    transactions.reverse();
+                    return Ok(transactions);
+                }
+            }
+            if page_len < TRANSACTIONS_PAGE_LIMIT {
+                transactions.reverse();
+                return Ok(transactions);
+            }
+        }
+    }
+
+    /// A transaction by its hash
+    pub async fn transaction(&self, hash: &H256) -> ChainResult<Option<Transaction>> {
+        let request = self
+            .client
+            .get(self.endpoint("transactions")?)
+            .query(&[("hash", BASE64.encode(hash.as_bytes()))]);
+        let response: TransactionsResponse = self.send(request).await?;
+        Ok(response.transactions.into_iter().next())
+    }
+
+    /// The transaction started by the message with the given hash, if it was
+    /// executed yet
+    pub async fn transaction_by_message(&self, hash: &H256) -> ChainResult<Option<Transaction>> {
+        let request = self
+            .client
+            .get(self.endpoint("transactionsByMessage")?)
+            .query(&[
+                ("msg_hash", BASE64.encode(hash.as_bytes())),
+                ("direction", "in".to_owned()),
+            ]);
+        let response: TransactionsResponse = self.send(request).await?;
+        Ok(response.transactions.into_iter().next())
+    }
+
+    /// The state of an account
+    pub async fn account(&self, address: &TonAddress) -> ChainResult<Account> {
+        let request = self
+            .client
+            .get(self.endpoint("account")?)
+            .query(&[("address", address.to_hex())]);
+        self.send(request).await
+    }
+
+    /// Send an external message, serialized as a bag of cells, and return
+    /// the hash of the message
+    pub async fn send_message(&self, boc: &[u8]) -> ChainResult<H256> {
+        let request = SendMessageRequest {
+            boc: BASE64.encode(boc),
+        };
+        let response: SendMessageResponse = self
+            .send(self.client.post(self.endpoint("message")?).json(&request))
+            .await?;
+        parse_hash(&response.message_hash)
+    }
+
+    fn endpoint(&self, path: &str) -> ChainResult<Url> {
+        self.url
+            .join(path)
+            .map_err(ChainCommunicationError::from_other)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, mut request: RequestBuilder) -> ChainResult<T> {
+        if let Some(api_key) = &self.api_key {
+            request = request.header("X-API-Key", api_key);
+        }
+        let response = RetryPolicy::default()
+            .send(request)
+            .await
+            .map_err(HyperlaneTonError::from)?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(HyperlaneTonError::Api { status, body }.into());
+        }
+        Ok(response.json().await.map_err(HyperlaneTonError::from)?)
+    }
+}
+
+/// A masterchain block
+#[derive(Debug, Clone, Deserialize)]
+pub struct Block {
+    /// Sequence number of the block
+    pub seqno: u32,
+    /// Unix time the block was generated at
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub gen_utime: u64,
+    /// Base64 encoded root hash of the block
+    pub root_hash: String,
+}
+
+/// A transaction of an account
+#[derive(Debug, Clone, Deserialize)]
+pub struct Transaction {
+    /// Raw address of the account
+    pub account: String,
+    /// Base64 encoded hash of the transaction
+    pub hash: String,
+    /// Logical time of the transaction
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub lt: u64,
+    /// Sequence number of the masterchain block which commits the shard
+    /// block of the transaction
+    pub mc_block_seqno: Option<u32>,
+    /// Fees paid by the account, in nanotons
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub total_fees: u64,
+    /// The message which started the transaction
+    pub in_msg: Option<Message>,
+    /// Messages sent by the transaction
+    #[serde(default)]
+    pub out_msgs: Vec<Message>,
+    /// How the transaction was executed
+    pub description: Option<TransactionDescription>,
+}
+
+impl Transaction {
+    /// The hash of the transaction
+    pub fn hash(&self) -> ChainResult<H256> {
+        parse_hash(&self.hash)
+    }
+
+    /// Whether the transaction succeeded. Transactions that were aborted,
+    /// e.g. because the contract threw and the message bounced, didn't.
+    pub fn succeeded(&self) -> bool {
+        let Some(description) = &self.description else {
+            return false;
+        };
+        !description.aborted
+            && description
+                .compute_ph
+                .as_ref()
+                .and_then(|compute| compute.success)
+                .unwrap_or(false)
+    }
+}
+
+/// How a transaction was executed
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionDescription {
+    /// Whether the transaction was aborted, which reverts its state changes
+    #[serde(default)]
+    pub aborted: bool,
+    /// The compute phase, which runs the code of the account
+    pub compute_ph: Option<ComputePhase>,
+}
+
+/// The compute phase of a transaction
+#[derive(Debug, Clone, Deserialize)]
+pub struct ComputePhase {
+    /// Whether the code ran successfully, none if the phase was skipped
+    pub success: Option<bool>,
+    /// The TVM exit code, none if the phase was skipped
+    pub exit_code: Option<i32>,
+}
+
+/// A message sent by a transaction
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    /// Base64 encoded hash of the message
+    pub hash: String,
+    /// Source of the message, which is none for external in messages
+    pub source: Option<String>,
+    /// Destination of the message, which is none for external out messages,
+    /// i.e. events
+    pub destination: Option<String>,
+    /// Logical time the message was created at
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub created_lt: u64,
+    /// Body of the message
+    pub message_content: Option<MessageContent>,
+}
+
+impl Message {
+    /// The hash of the message
+    pub fn hash(&self) -> ChainResult<H256> {
+        parse_hash(&self.hash)
+    }
+}
+
+/// The body of a message
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageContent {
+    /// Base64 encoded bag of cells of the body
+    pub body: String,
+}
+
+/// The state of an account
+#[derive(Debug, Clone, Deserialize)]
+pub struct Account {
+    /// Balance in nanotons
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub balance: u64,
+    /// Status of the account, e.g. `active` or `uninit`
+    pub status: String,
+}
+
+#[derive(Serialize)]
+struct RunGetMethodRequest<'a> {
+    address: &'a str,
+    method: &'a str,
+    stack: Vec<StackEntry>,
+}
+
+#[derive(Deserialize)]
+struct RunGetMethodResponse {
+    exit_code: i32,
+    #[serde(default)]
+    stack: Vec<StackEntry>,
+}
+
+#[derive(Deserialize)]
+struct MasterchainInfo {
+    last: Block,
+}
+
+#[derive(Deserialize)]
+struct BlocksResponse {
+    blocks: Vec<Block>,
+}
+
+#[derive(Deserialize)]
+struct TransactionsResponse {
+    transactions: Vec<Transaction>,
+}
+
+#[derive(Serialize)]
+struct SendMessageRequest {
+    boc: String,
+}
+
+#[derive(Deserialize)]
+struct SendMessageResponse {
+    message_hash: String,
+}
+
+/// Parse a base64 encoded hash as returned by the API
+pub(crate) fn parse_hash(hash: &str) -> ChainResult<H256> {
+    let bytes = BASE64.decode(hash).map_err(HyperlaneTonError::from)?;
+    if bytes.len() != 32 {
+        return Err(ChainCommunicationError::from_other_str(&format!(
+            "Invalid TON hash `{hash}`"
+        )));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+/// The API returns 64 bit integers as strings, while some proxies return
+/// them as numbers
+fn from_str_or_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrNumber<T> {
+        Str(String),
+        Number(T),
+    }
+
+    match StrOrNumber::<T>::deserialize(deserializer)? {
+        StrOrNumber::Str(value) => value.parse().map_err(serde::de::Error::custom),
+        StrOrNumber::Number(value) => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSACTIONS: &str = r#"{
+        "transactions": [
+            {
+                "account": "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8",
+                "hash": "QkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkI=",
+                "lt": "47597573000003",
+                "now": 1718000000,
+                "mc_block_seqno": 38000000,
+                "total_fees": "2516433",
+                "description": {
+                    "type": "ord",
+                    "aborted": false,
+                    "compute_ph": {"skipped": false, "success": true, "exit_code": 0}
+                },
+                "in_msg": {
+                    "hash": "QUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUE=",
+                    "source": "0:2CF55953E92EFBEADAB7BA725C3F93A0B23F842CBBA72D7B8E6F510A70E422E3",
+                    "destination": "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8",
+                    "created_lt": "47597573000002",
+                    "message_content": {"body": "te6cckEBAQEAAgAAAEysuc0="}
+                },
+                "out_msgs": []
+            },
+            {
+                "account": "0:83DFD552E63729B472FCBCC8C45EBCC6691702558B68EC7527E1BA403A0F31A8",
+                "hash": "Q0NDQ0NDQ0NDQ0NDQ0NDQ0NDQ0NDQ0NDQ0NDQ0NDQ0M=",
+                "lt": 47597574000001,
+                "mc_block_seqno": null,
+                "total_fees": 1000,
+                "description": {
+                    "aborted": true,
+                    "compute_ph": {"skipped": false, "success": false, "exit_code": 100}
+                },
+                "in_msg": null
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_deserialize_transactions() {
+        let response: TransactionsResponse = serde_json::from_str(TRANSACTIONS).unwrap();
+        let [processed, bounced] = response.transactions.as_slice() else {
+            panic!("expected two transactions");
+        };
+
+        assert_eq!(processed.hash().unwrap(), H256::repeat_byte(b'B'));
+        assert_eq!(processed.lt, 47_597_573_000_003);
+        assert_eq!(processed.mc_block_seqno, Some(38_000_000));
+        assert_eq!(processed.total_fees, 2_516_433);
+        assert!(processed.succeeded());
+        let in_msg = processed.in_msg.as_ref().unwrap();
+        assert_eq!(in_msg.hash().unwrap(), H256::repeat_byte(b'A'));
+        assert_eq!(in_msg.created_lt, 47_597_573_000_002);
+        assert!(processed.out_msgs.is_empty());
+
+        // Integers may also be numbers, and missing fields are optional
+        assert_eq!(bounced.lt, 47_597_574_000_001);
+        assert_eq!(bounced.mc_block_seqno, None);
+        assert_eq!(bounced.total_fees, 1000);
+        assert!(bounced.in_msg.is_none());
+        assert!(!bounced.succeeded());
+    }
+
+    #[test]
+    fn test_transaction_without_description_didnt_succeed() {
+        let transaction: Transaction = serde_json::from_str(
+            r#"{"account": "0:00", "hash": "", "lt": "1", "total_fees": "0"}"#,
+        )
+        .unwrap();
+        assert!(!transaction.succeeded());
+    }
+
+    #[test]
+    fn test_deserialize_responses() {
+        let info: MasterchainInfo = serde_json::from_str(
+            r#"{"last": {"seqno": 42, "gen_utime": "1718000000", "root_hash": "AAAA"}}"#,
+        )
+        .unwrap();
+        assert_eq!(info.last.seqno, 42);
+        assert_eq!(info.last.gen_utime, 1_718_000_000);
+
+        let account: Account =
+            serde_json::from_str(r#"{"balance": "1500000000", "status": "active"}"#).unwrap();
+        assert_eq!(account.balance, 1_500_000_000);
+        assert_eq!(account.status, "active");
+
+        let response: RunGetMethodResponse = serde_json::from_str(
+            r#"{"gas_used": 500, "exit_code": 0, "stack": [{"type": "num", "value": "0x2a"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.exit_code, 0);
+        assert_eq!(response.stack, vec![StackEntry::num(42u64)]);
+
+        // Failed get methods return no stack
+        let response: RunGetMethodResponse =
+            serde_json::from_str(r#"{"gas_used": 0, "exit_code": 11}"#).unwrap();
+        assert_eq!(response.exit_code, 11);
+        assert!(response.stack.is_empty());
+    }
+
+    #[test]
+    fn test_parse_hash() {
+        assert_eq!(
+            parse_hash("QkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkI=").unwrap(),
+            H256::repeat_byte(b'B')
+        );
+        assert!(parse_hash("QkJC").is_err());
+        assert!(parse_hash("not base64!").is_err());
+    }
+}
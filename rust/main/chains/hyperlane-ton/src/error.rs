@@ -0,0 +1,78 @@
+use hyperlane_core::{ChainCommunicationError, H256, U256};
+use reqwest::StatusCode;
+
+/// Errors from the crates specific to the hyperlane-ton
+/// implementation.
+/// This error can then be converted into the broader error type
+/// in hyperlane-core using the `From` trait impl
+#[derive(Debug, thiserror::Error)]
+pub enum HyperlaneTonError {
+    /// The HTTP client couldn't be built
+    #[error(transparent)]
+    HttpClient(#[from] reqwest_utils::HttpClientError),
+    /// A request to the API failed
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// The API rejected a request
+    #[error("TON API request failed with status {status}: {body}")]
+    Api {
+        /// Status of the response
+        status: StatusCode,
+        /// Body of the response
+        body: String,
+    },
+    /// A get method of a contract exited with an error
+    #[error("Get method `{method}` of {address} failed with exit code {exit_code}")]
+    GetMethodFailed {
+        /// The contract
+        address: String,
+        /// The get method
+        method: String,
+        /// The TVM exit code
+        exit_code: i32,
+    },
+    /// A get method returned a stack of an unexpected shape
+    #[error("Unexpected get method result: {0}")]
+    UnexpectedStack(String),
+    /// A cell couldn't be built or parsed
+    #[error("Cell error: {0}")]
+    Cell(String),
+    /// An address couldn't be parsed
+    #[error("Invalid TON address `{0}`")]
+    InvalidAddress(String),
+    /// The wallet of the signer couldn't be derived or sign a message
+    #[error("Wallet error: {0}")]
+    Wallet(String),
+    /// Base64 decoding error
+    #[error("{0}")]
+    Base64(#[from] base64::DecodeError),
+    /// A block wasn't found
+    #[error("Masterchain block {0} not found")]
+    BlockNotFound(u32),
+    /// The message was already delivered
+    #[error("Message {0:?} was already delivered")]
+    AlreadyDelivered(H256),
+    /// The recipient of a message isn't a deployed contract
+    #[error("Recipient {0:?} isn't a deployed contract")]
+    RecipientNotDeployed(H256),
+    /// The wallet can't pay the value attached to a message
+    #[error("Wallet balance {balance} is below the required {required} nanotons")]
+    InsufficientBalance {
+        /// Balance of the wallet in nanotons
+        balance: U256,
+        /// Value the message needs in nanotons
+        required: u64,
+    },
+}
+
+impl From<HyperlaneTonError> for ChainCommunicationError {
+    fn from(value: HyperlaneTonError) -> Self {
+        ChainCommunicationError::from_other(value)
+    }
+}
+
+/// Map an error of building or parsing cells with tonlib to a
+/// [`HyperlaneTonError`]
+pub(crate) fn cell_error(err: impl std::fmt::Display) -> HyperlaneTonError {
+    HyperlaneTonError::Cell(err.to_string())
+}
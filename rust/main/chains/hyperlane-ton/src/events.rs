@@ -0,0 +1,303 @@
+//! Opcodes of the messages of the hyperlane contracts and parsing of the
+//! events they emit as external out messages.
+
+use std::ops::RangeInclusive;
+
+use hyperlane_core::{ChainResult, Decode, HyperlaneMessage, LogMeta, H256, H512, U256};
+use tonlib_core::cell::{BagOfCells, CellParser};
+
+use crate::client::{Message, Transaction};
+use crate::error::cell_error;
+use crate::utils::{snake_bytes, to_ton_address};
+use crate::TonApiClient;
+
+/// Opcodes of the messages and events of the hyperlane contracts, the crc32
+/// of their names
+pub(crate) mod op {
+    /// Deliver a message to the mailbox
+    pub const PROCESS: u32 = 0x861d1896;
+    /// Announce a storage location to the validator announce
+    pub const ANNOUNCE: u32 = 0xe6d6dd75;
+    /// Emitted by the mailbox when a message is dispatched
+    pub const DISPATCH_EVENT: u32 = 0x1559b761;
+    /// Emitted by the mailbox when a message is processed
+    pub const PROCESS_EVENT: u32 = 0x6df22756;
+    /// Emitted by the merkle tree hook when a message id is inserted
+    pub const INSERTION_EVENT: u32 = 0x682cbb88;
+}
+
+/// An event emitted by a hyperlane contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Event {
+    /// A message was dispatched
+    Dispatch(HyperlaneMessage),
+    /// A message was processed
+    Process(H256),
+    /// A message id was inserted into the merkle tree
+    Insertion {
+        /// The inserted message id
+        message_id: H256,
+        /// The index of the leaf
+        index: u32,
+    },
+}
+
+/// Fetch the events emitted by the contract at `address` in transactions
+/// committed to the masterchain blocks of `range`
+pub(crate) async fn fetch_events_in_range(
+    client: &TonApiClient,
+    address: H256,
+    range: RangeInclusive<u32>,
+) -> ChainResult<Vec<(Event, LogMeta)>> {
+    // Transactions are only queryable by time. Shard blocks are generated
+    // before the masterchain block committing them, so how long before the
+    // start of the range its transactions were generated isn't known.
+    // Instead, the transactions of the account are fetched from the end of
+    // the range backwards until one committed before the range. Older
+    // transactions of the account were committed before it too.
+    let end_block = client.masterchain_block(*range.end()).await?;
+    let start = *range.start();
+    let transactions = client
+        .account_transactions_until(&to_ton_address(address), end_block.gen_utime, |tx| {
+            tx.mc_block_seqno.is_some_and(|seqno| seqno < start)
+        })
+        .await?;
+    let mut events = vec![];
+    for transaction in transactions {
+        match transaction.mc_block_seqno {
+            Some(seqno) if range.contains(&seqno) => {}
+            _ => continue,
+        }
+        events.extend(parse_events(address, &transaction)?);
+    }
+    Ok(events)
+}
+
+/// Fetch the events emitted by the contract at `address` in the transaction
+/// with the given hash
+pub(crate) async fn fetch_events_in_tx(
+    client: &TonApiClient,
+    address: H256,
+    tx_hash: H512,
+) -> ChainResult<Vec<(Event, LogMeta)>> {
+    match client.transaction(&tx_hash.into()).await? {
+        Some(transaction) => parse_events(address, &transaction),
+        None => Ok(vec![]),
+    }
+}
+
+/// Parse the events emitted by a transaction of the contract at `address`,
+/// together with their log meta. Transactions not yet committed to a
+/// masterchain block are skipped.
+fn parse_events(address: H256, transaction: &Transaction) -> ChainResult<Vec<(Event, LogMeta)>> {
+    let Some(mc_block_seqno) = transaction.mc_block_seqno else {
+        return Ok(vec![]);
+    };
+    let transaction_id: H512 = transaction.hash()?.into();
+    let mut events = vec![];
+    for message in &transaction.out_msgs {
+        let Some(event) = parse_event(message)? else {
+            continue;
+        };
+        let meta = LogMeta {
+            address,
+            block_number: mc_block_seqno.into(),
+            // Events are located by the masterchain block, whose hash isn't
+            // needed by the agents
+            block_hash: H256::zero(),
+            transaction_id,
+            transaction_index: transaction.lt,
+            log_index: U256::from(message.created_lt),
+        };
+        events.push((event, meta));
+    }
+    Ok(events)
+}
+
+fn parse_event(message: &Message) -> ChainResult<Option<Event>> {
+    // Events are external out messages, which have no destination
+    if message.destination.is_some() {
+        return Ok(None);
+    }
+    let Some(content) = &message.message_content else {
+        return Ok(None);
+    };
+    let body = BagOfCells::parse_base64(&content.body)
+        .and_then(|boc| boc.single_root())
+        .map_err(cell_error)?;
+    let mut parser = body.parser();
+    if parser.remaining_bits() < 32 {
+        return Ok(None);
+    }
+    let event = match parser.load_u32(32).map_err(cell_error)? {
+        op::DISPATCH_EVENT => {
+            let message_cell = body
+                .references()
+                .first()
+                .ok_or_else(|| cell_error("dispatch event without a message"))?;
+            let bytes = snake_bytes(message_cell)?;
+            Event::Dispatch(HyperlaneMessage::read_from(&mut bytes.as_slice())?)
+        }
+        op::PROCESS_EVENT => Event::Process(load_h256(&mut parser)?),
+        op::INSERTION_EVENT => Event::Insertion {
+            message_id: load_h256(&mut parser)?,
+            index: parser.load_u32(32).map_err(cell_error)?,
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
+fn load_h256(parser: &mut CellParser) -> ChainResult<H256> {
+    let bytes = parser.load_bytes(32).map_err(cell_error)?;
+    Ok(H256::from_slice(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    use hyperlane_core::Encode;
+    use tonlib_core::cell::{Cell, CellBuilder};
+
+    use super::*;
+    use crate::client::MessageContent;
+    use crate::utils::snake_cell;
+
+    /// The CRC-32 of `name`, which the opcodes of the contracts are
+    fn crc32(name: &str) -> u32 {
+        let mut crc = u32::MAX;
+        for byte in name.bytes() {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb88320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    #[test]
+    fn test_opcodes() {
+        assert_eq!(op::PROCESS, crc32("process"));
+        assert_eq!(op::ANNOUNCE, crc32("announce"));
+        assert_eq!(op::DISPATCH_EVENT, crc32("dispatch_event"));
+        assert_eq!(op::PROCESS_EVENT, crc32("process_event"));
+        assert_eq!(op::INSERTION_EVENT, crc32("insertion_event"));
+    }
+
+    fn boc(cell: Cell) -> String {
+        BASE64.encode(BagOfCells::from_root(cell).serialize(true).unwrap())
+    }
+
+    fn event_message(body: Cell, destination: Option<&str>) -> Message {
+        Message {
+            hash: BASE64.encode([7u8; 32]),
+            source: Some("0:00".to_owned()),
+            destination: destination.map(str::to_owned),
+            created_lt: 11,
+            message_content: Some(MessageContent { body: boc(body) }),
+        }
+    }
+
+    fn dispatch_body(message: &HyperlaneMessage) -> Cell {
+        let mut builder = CellBuilder::new();
+        builder
+            .store_u32(32, op::DISPATCH_EVENT)
+            .unwrap()
+            .store_child(snake_cell(&message.to_vec()).unwrap())
+            .unwrap();
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let message = HyperlaneMessage {
+            nonce: 3,
+            origin: 1,
+            destination: 2,
+            body: vec![0xab; 300],
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_event(&event_message(dispatch_body(&message), None)).unwrap(),
+            Some(Event::Dispatch(message.clone()))
+        );
+
+        let id = H256::repeat_byte(0x11);
+        let mut builder = CellBuilder::new();
+        builder
+            .store_u32(32, op::PROCESS_EVENT)
+            .unwrap()
+            .store_slice(id.as_bytes())
+            .unwrap();
+        assert_eq!(
+            parse_event(&event_message(builder.build().unwrap(), None)).unwrap(),
+            Some(Event::Process(id))
+        );
+
+        let mut builder = CellBuilder::new();
+        builder
+            .store_u32(32, op::INSERTION_EVENT)
+            .unwrap()
+            .store_slice(id.as_bytes())
+            .unwrap()
+            .store_u32(32, 9)
+            .unwrap();
+        assert_eq!(
+            parse_event(&event_message(builder.build().unwrap(), None)).unwrap(),
+            Some(Event::Insertion {
+                message_id: id,
+                index: 9
+            })
+        );
+
+        // Internal messages and unknown opcodes aren't events
+        assert_eq!(
+            parse_event(&event_message(dispatch_body(&message), Some("0:01"))).unwrap(),
+            None
+        );
+        let mut builder = CellBuilder::new();
+        builder.store_u32(32, 0xdeadbeef).unwrap();
+        assert_eq!(
+            parse_event(&event_message(builder.build().unwrap(), None)).unwrap(),
+            None
+        );
+        // A dispatch event without a message is malformed
+        let mut builder = CellBuilder::new();
+        builder.store_u32(32, op::DISPATCH_EVENT).unwrap();
+        assert!(parse_event(&event_message(builder.build().unwrap(), None)).is_err());
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let message = HyperlaneMessage::default();
+        let address = H256::repeat_byte(0x22);
+        let mut transaction = Transaction {
+            account: "0:00".to_owned(),
+            hash: BASE64.encode([5u8; 32]),
+            lt: 100,
+            mc_block_seqno: Some(42),
+            total_fees: 0,
+            in_msg: None,
+            out_msgs: vec![event_message(dispatch_body(&message), None)],
+            description: None,
+        };
+
+        let events = parse_events(address, &transaction).unwrap();
+        assert_eq!(events.len(), 1);
+        let (event, meta) = &events[0];
+        assert_eq!(event, &Event::Dispatch(message));
+        assert_eq!(meta.address, address);
+        assert_eq!(meta.block_number, 42);
+        assert_eq!(meta.transaction_id, H512::from(H256::repeat_byte(5)));
+        assert_eq!(meta.transaction_index, 100);
+        assert_eq!(meta.log_index, U256::from(11));
+
+        // Transactions that aren't committed yet are skipped
+        transaction.mc_block_seqno = None;
+        assert!(parse_events(address, &transaction).unwrap().is_empty());
+    }
+}
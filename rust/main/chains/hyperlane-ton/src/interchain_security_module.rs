@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract, HyperlaneDomain,
+    HyperlaneMessage, HyperlaneProvider, InterchainSecurityModule, ModuleType, H256, U256,
+};
+use num_traits::FromPrimitive;
+use tracing::{instrument, warn};
+
+use crate::stack::entry;
+use crate::utils::to_ton_address;
+use crate::{ConnectionConf, TonProvider};
+
+/// A reference to an ISM contract on some TON chain
+#[derive(Clone, Debug)]
+pub struct TonInterchainSecurityModule {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: TonProvider,
+}
+
+impl TonInterchainSecurityModule {
+    /// Create a new TON ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = TonProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for TonInterchainSecurityModule {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonInterchainSecurityModule {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl InterchainSecurityModule for TonInterchainSecurityModule {
+    #[instrument(err, ret, skip(self))]
+    async fn module_type(&self) -> ChainResult<ModuleType> {
+        let stack = self
+            .provider
+            .client()
+            .run_get_method(&to_ton_address(self.address), "get_module_type", vec![])
+            .await?;
+        let module = entry(&stack, 0)?.as_u64()?;
+        if let Some(module_type) = ModuleType::from_u64(module) {
+            Ok(module_type)
+        } else {
+            warn!(%module, "Unknown module type");
+            Ok(ModuleType::Unused)
+        }
+    }
+
+    async fn dry_run_verify(
+        &self,
+        _message: &HyperlaneMessage,
+        _metadata: &[u8],
+    ) -> ChainResult<Option<U256>> {
+        // Verification can't be simulated through the API, it's paid for by
+        // the value attached to `process` messages
+        Ok(Some(U256::zero()))
+    }
+}
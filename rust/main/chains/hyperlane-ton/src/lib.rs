@@ -0,0 +1,33 @@
+//! Implementation of hyperlane for TON.
+//!
+//! The chain is read and written through a TON Center v3 compatible HTTP API,
+//! which indexes the liteservers of the network. Block heights are the
+//! sequence numbers of masterchain blocks, and addresses are the account ids
+//! of basechain contracts.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub use client::TonApiClient;
+pub use interchain_security_module::*;
+pub use mailbox::*;
+pub use merkle_tree_hook::*;
+pub use multisig_ism::*;
+pub use provider::*;
+pub use signer::TonSigner;
+pub use trait_builder::*;
+pub use validator_announce::*;
+
+mod client;
+mod error;
+mod events;
+mod interchain_security_module;
+mod mailbox;
+mod merkle_tree_hook;
+mod multisig_ism;
+mod provider;
+mod signer;
+mod stack;
+mod trait_builder;
+mod utils;
+mod validator_announce;
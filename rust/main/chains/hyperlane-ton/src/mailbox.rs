@@ -0,0 +1,323 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    utils::bytes_to_hex, ChainCommunicationError, ChainResult, ContractLocator, Encode,
+    FixedPointNumber, HyperlaneChain, HyperlaneContract, HyperlaneDomain, HyperlaneMessage,
+    HyperlaneProvider, Indexed, Indexer, LogMeta, Mailbox, ReorgPeriod, SequenceAwareIndexer,
+    TxCostEstimate, TxOutcome, H256, H512, U256,
+};
+use tonlib_core::cell::{BagOfCells, Cell, CellBuilder};
+use tracing::{debug, instrument};
+
+use crate::error::{cell_error, HyperlaneTonError};
+use crate::events::{fetch_events_in_range, fetch_events_in_tx, op, Event};
+use crate::stack::{entry, StackEntry};
+use crate::utils::{from_ton_address, snake_cell, to_ton_address};
+use crate::{ConnectionConf, TonApiClient, TonProvider, TonSigner};
+
+/// Gas price of the basechain in nanotons. Gas isn't bought up front on
+/// TON, the attached value pays for it, so this is only used to express the
+/// attached value as a gas limit.
+const BASECHAIN_GAS_PRICE: u64 = 400;
+
+/// A reference to a Mailbox contract on some TON chain
+#[derive(Clone, Debug)]
+pub struct TonMailbox {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: TonProvider,
+    signer: Option<TonSigner>,
+    process_value: u64,
+}
+
+impl TonMailbox {
+    /// Create a new TON mailbox
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<TonSigner>,
+    ) -> ChainResult<Self> {
+        let provider = TonProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+            signer,
+            process_value: conf.process_value,
+        })
+    }
+
+    fn client(&self) -> &TonApiClient {
+        self.provider.client()
+    }
+
+    async fn get(&self, method: &str, stack: Vec<StackEntry>) -> ChainResult<Vec<StackEntry>> {
+        self.client()
+            .run_get_method(&to_ton_address(self.address), method, stack)
+            .await
+    }
+
+    /// The body of the message processing `message` with `metadata`
+    fn process_body(&self, message: &HyperlaneMessage, metadata: &[u8]) -> ChainResult<Cell> {
+        let message = snake_cell(&message.to_vec())?;
+        let metadata = snake_cell(metadata)?;
+        let mut builder = CellBuilder::new();
+        builder
+            .store_u32(32, op::PROCESS)
+            // query_id
+            .and_then(|b| b.store_u64(64, 0))
+            .and_then(|b| b.store_child(message))
+            .and_then(|b| b.store_child(metadata))
+            .map_err(cell_error)?;
+        Ok(builder.build().map_err(cell_error)?)
+    }
+
+    /// The nonce of the next dispatched message
+    pub(crate) async fn nonce(&self) -> ChainResult<u32> {
+        let stack = self.get("get_nonce", vec![]).await?;
+        let nonce = entry(&stack, 0)?.as_u64()?;
+        u32::try_from(nonce).map_err(ChainCommunicationError::from_other)
+    }
+}
+
+impl HyperlaneContract for TonMailbox {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonMailbox {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl Mailbox for TonMailbox {
+    /// TON finalizes masterchain blocks within seconds, so the reorg period
+    /// is ignored
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn count(&self, _reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        self.nonce().await
+    }
+
+    #[instrument(level = "debug", err, ret, skip(self))]
+    async fn delivered(&self, id: H256) -> ChainResult<bool> {
+        let stack = self
+            .get("get_delivered", vec![StackEntry::hash(id)])
+            .await?;
+        entry(&stack, 0)?.as_bool()
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn default_ism(&self) -> ChainResult<H256> {
+        let stack = self.get("get_default_ism", vec![]).await?;
+        Ok(from_ton_address(&entry(&stack, 0)?.as_address()?))
+    }
+
+    /// Recipients without an ISM of their own, i.e. without a `get_ism`
+    /// get method or returning null, use the default ISM
+    #[instrument(err, ret, skip(self))]
+    async fn recipient_ism(&self, recipient: H256) -> ChainResult<H256> {
+        let ism = match self
+            .client()
+            .try_run_get_method(&to_ton_address(recipient), "get_ism", vec![])
+            .await?
+        {
+            Ok(stack) => entry(&stack, 0)?.as_opt_address()?,
+            Err(exit_code) => {
+                debug!(exit_code, "Recipient has no ISM, using the default ISM");
+                None
+            }
+        };
+        match ism {
+            Some(ism) => Ok(from_ton_address(&ism)),
+            None => self.default_ism().await,
+        }
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn process(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+        _tx_gas_limit: Option<U256>,
+    ) -> ChainResult<TxOutcome> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let body = self.process_body(message, metadata)?;
+        signer
+            .send(
+                self.client(),
+                &to_ton_address(self.address),
+                self.process_value,
+                body,
+            )
+            .await
+    }
+
+    /// Delivery can't be simulated through the API, so only whether the
+    /// message can be delivered at all is checked. The estimate is the value
+    /// attached to `process` messages expressed as gas.
+    #[instrument(err, ret, skip(self), fields(hyp_message=%message, metadata=%bytes_to_hex(metadata)))]
+    async fn process_estimate_costs(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<TxCostEstimate> {
+        if self.delivered(message.id()).await? {
+            return Err(HyperlaneTonError::AlreadyDelivered(message.id()).into());
+        }
+        if !self.provider.is_contract(&message.recipient).await? {
+            let err = HyperlaneTonError::RecipientNotDeployed(message.recipient);
+            return Err(ChainCommunicationError::RecipientHandleFailed(Box::new(
+                err.into(),
+            )));
+        }
+        if let Some(signer) = &self.signer {
+            let balance = signer.balance(self.client()).await?;
+            if balance < self.process_value.into() {
+                return Err(HyperlaneTonError::InsufficientBalance {
+                    balance,
+                    required: self.process_value,
+                }
+                .into());
+            }
+        }
+        Ok(TxCostEstimate {
+            gas_limit: (self.process_value / BASECHAIN_GAS_PRICE).into(),
+            gas_price: FixedPointNumber::from(BASECHAIN_GAS_PRICE),
+            l2_gas_limit: None,
+        })
+    }
+
+    fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
+        let body = self.process_body(message, metadata)?;
+        Ok(BagOfCells::from_root(body)
+            .serialize(true)
+            .map_err(cell_error)?)
+    }
+}
+
+/// Struct that retrieves dispatched messages and deliveries from a TON
+/// mailbox
+#[derive(Debug, Clone)]
+pub struct TonMailboxIndexer {
+    mailbox: TonMailbox,
+}
+
+impl TonMailboxIndexer {
+    /// Create a new TON mailbox indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let mailbox = TonMailbox::new(conf, locator, None)?;
+        Ok(Self { mailbox })
+    }
+
+    async fn events_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Event, LogMeta)>> {
+        fetch_events_in_range(self.mailbox.client(), self.mailbox.address, range).await
+    }
+
+    async fn events_in_tx(&self, tx_hash: H512) -> ChainResult<Vec<(Event, LogMeta)>> {
+        fetch_events_in_tx(self.mailbox.client(), self.mailbox.address, tx_hash).await
+    }
+
+    async fn latest_block(&self) -> ChainResult<u32> {
+        self.mailbox.client().latest_masterchain_seqno().await
+    }
+}
+
+fn dispatches(events: Vec<(Event, LogMeta)>) -> Vec<(Indexed<HyperlaneMessage>, LogMeta)> {
+    events
+        .into_iter()
+        .filter_map(|(event, meta)| match event {
+            Event::Dispatch(message) => Some((message.into(), meta)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn deliveries(events: Vec<(Event, LogMeta)>) -> Vec<(Indexed<H256>, LogMeta)> {
+    events
+        .into_iter()
+        .filter_map(|(event, meta)| match event {
+            Event::Process(message_id) => Some((message_id.into(), meta)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Indexer<HyperlaneMessage> for TonMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        Ok(dispatches(self.events_in_range(range).await?))
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.latest_block().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<HyperlaneMessage>, LogMeta)>> {
+        Ok(dispatches(self.events_in_tx(tx_hash).await?))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<HyperlaneMessage> for TonMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.latest_block().await?;
+        let count = self.mailbox.nonce().await?;
+        Ok((Some(count), tip))
+    }
+}
+
+#[async_trait]
+impl Indexer<H256> for TonMailboxIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        Ok(deliveries(self.events_in_range(range).await?))
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.latest_block().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<H256>, LogMeta)>> {
+        Ok(deliveries(self.events_in_tx(tx_hash).await?))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<H256> for TonMailboxIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        // Deliveries aren't sequenced
+        let tip = self.latest_block().await?;
+        Ok((None, tip))
+    }
+}
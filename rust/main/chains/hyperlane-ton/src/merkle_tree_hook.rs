@@ -0,0 +1,170 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use hyperlane_core::accumulator::incremental::IncrementalMerkle;
+use hyperlane_core::accumulator::TREE_DEPTH;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, Checkpoint, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, Indexed, Indexer, LogMeta,
+    MerkleTreeHook, MerkleTreeInsertion, ReorgPeriod, SequenceAwareIndexer, H256, H512,
+};
+use tracing::instrument;
+
+use crate::error::HyperlaneTonError;
+use crate::events::{fetch_events_in_range, fetch_events_in_tx, Event};
+use crate::stack::{entry, StackEntry};
+use crate::utils::to_ton_address;
+use crate::{ConnectionConf, TonApiClient, TonProvider};
+
+/// A reference to a MerkleTreeHook contract on some TON chain
+#[derive(Clone, Debug)]
+pub struct TonMerkleTreeHook {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: TonProvider,
+}
+
+impl TonMerkleTreeHook {
+    /// Create a new TON merkle tree hook
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = TonProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+
+    fn client(&self) -> &TonApiClient {
+        self.provider.client()
+    }
+
+    async fn get(&self, method: &str) -> ChainResult<Vec<StackEntry>> {
+        self.client()
+            .run_get_method(&to_ton_address(self.address), method, vec![])
+            .await
+    }
+
+    async fn leaf_count(&self) -> ChainResult<u32> {
+        let stack = self.get("get_count").await?;
+        let count = entry(&stack, 0)?.as_u64()?;
+        u32::try_from(count).map_err(ChainCommunicationError::from_other)
+    }
+}
+
+impl HyperlaneContract for TonMerkleTreeHook {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonMerkleTreeHook {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+/// TON finalizes masterchain blocks within seconds, so reorg periods are
+/// ignored
+#[async_trait]
+impl MerkleTreeHook for TonMerkleTreeHook {
+    #[instrument(err, ret, skip(self))]
+    async fn tree(&self, _reorg_period: &ReorgPeriod) -> ChainResult<IncrementalMerkle> {
+        let stack = self.get("get_tree").await?;
+        let branch_entries = entry(&stack, 0)?.as_tuple()?;
+        if branch_entries.len() != TREE_DEPTH {
+            return Err(HyperlaneTonError::UnexpectedStack(format!(
+                "expected a branch of {TREE_DEPTH} nodes, got {}",
+                branch_entries.len()
+            ))
+            .into());
+        }
+        let mut branch = [H256::zero(); TREE_DEPTH];
+        for (node, node_entry) in branch.iter_mut().zip(branch_entries) {
+            *node = node_entry.as_h256()?;
+        }
+        let count = entry(&stack, 1)?.as_u64()? as usize;
+        Ok(IncrementalMerkle { branch, count })
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn count(&self, _reorg_period: &ReorgPeriod) -> ChainResult<u32> {
+        self.leaf_count().await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn latest_checkpoint(&self, _reorg_period: &ReorgPeriod) -> ChainResult<Checkpoint> {
+        let stack = self.get("get_latest_checkpoint").await?;
+        let root = entry(&stack, 0)?.as_h256()?;
+        let index = entry(&stack, 1)?.as_u64()?;
+        Ok(Checkpoint {
+            merkle_tree_hook_address: self.address,
+            mailbox_domain: self.domain.id(),
+            root,
+            index: u32::try_from(index).map_err(ChainCommunicationError::from_other)?,
+        })
+    }
+}
+
+/// Struct that retrieves insertions into a TON merkle tree hook
+#[derive(Debug, Clone)]
+pub struct TonMerkleTreeHookIndexer {
+    hook: TonMerkleTreeHook,
+}
+
+impl TonMerkleTreeHookIndexer {
+    /// Create a new TON merkle tree hook indexer
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let hook = TonMerkleTreeHook::new(conf, locator)?;
+        Ok(Self { hook })
+    }
+}
+
+fn insertions(events: Vec<(Event, LogMeta)>) -> Vec<(Indexed<MerkleTreeInsertion>, LogMeta)> {
+    events
+        .into_iter()
+        .filter_map(|(event, meta)| match event {
+            Event::Insertion { message_id, index } => {
+                Some((MerkleTreeInsertion::new(index, message_id).into(), meta))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Indexer<MerkleTreeInsertion> for TonMerkleTreeHookIndexer {
+    #[instrument(err, skip(self))]
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let events = fetch_events_in_range(self.hook.client(), self.hook.address, range).await?;
+        Ok(insertions(events))
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        self.hook.client().latest_masterchain_seqno().await
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<MerkleTreeInsertion>, LogMeta)>> {
+        let events = fetch_events_in_tx(self.hook.client(), self.hook.address, tx_hash).await?;
+        Ok(insertions(events))
+    }
+}
+
+#[async_trait]
+impl SequenceAwareIndexer<MerkleTreeInsertion> for TonMerkleTreeHookIndexer {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        let tip = self.hook.client().latest_masterchain_seqno().await?;
+        let count = self.hook.leaf_count().await?;
+        Ok((Some(count), tip))
+    }
+}
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain, HyperlaneContract,
+    HyperlaneDomain, HyperlaneMessage, HyperlaneProvider, MultisigIsm, H256,
+};
+use tracing::instrument;
+
+use crate::stack::{entry, StackEntry};
+use crate::utils::to_ton_address;
+use crate::{ConnectionConf, TonProvider};
+
+/// A reference to a MultisigIsm contract on some TON chain
+#[derive(Clone, Debug)]
+pub struct TonMultisigIsm {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: TonProvider,
+}
+
+impl TonMultisigIsm {
+    /// Create a new TON multisig ISM
+    pub fn new(conf: &ConnectionConf, locator: ContractLocator) -> ChainResult<Self> {
+        let provider = TonProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+        })
+    }
+}
+
+impl HyperlaneContract for TonMultisigIsm {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonMultisigIsm {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl MultisigIsm for TonMultisigIsm {
+    /// Validators are stored by the contract as 160 bit integers, i.e. as
+    /// ethereum addresses
+    #[instrument(err, ret, skip(self))]
+    async fn validators_and_threshold(
+        &self,
+        message: &HyperlaneMessage,
+    ) -> ChainResult<(Vec<H256>, u8)> {
+        let stack = self
+            .provider
+            .client()
+            .run_get_method(
+                &to_ton_address(self.address),
+                "get_validators_and_threshold",
+                vec![StackEntry::num(message.origin)],
+            )
+            .await?;
+        let validators = entry(&stack, 0)?
+            .as_tuple()?
+            .iter()
+            .map(StackEntry::as_h256)
+            .collect::<ChainResult<Vec<_>>>()?;
+        let threshold = entry(&stack, 1)?.as_u64()?;
+        let threshold = u8::try_from(threshold).map_err(ChainCommunicationError::from_other)?;
+        Ok((validators, threshold))
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    BlockInfo, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider,
+    HyperlaneProviderError, TxnInfo, TxnReceiptInfo, H256, H512, U256,
+};
+
+use crate::client::Block;
+use crate::utils::{from_ton_address, parse_address, to_ton_address};
+use crate::{ConnectionConf, TonApiClient};
+
+/// Status of accounts with deployed code
+const ACTIVE_ACCOUNT_STATUS: &str = "active";
+
+/// A wrapper around a TON API client to get generic blockchain information.
+#[derive(Debug, Clone)]
+pub struct TonProvider {
+    domain: HyperlaneDomain,
+    client: Arc<TonApiClient>,
+}
+
+impl TonProvider {
+    /// Create a new TON provider.
+    pub fn new(domain: HyperlaneDomain, conf: &ConnectionConf) -> ChainResult<Self> {
+        let client = Arc::new(TonApiClient::new(conf)?);
+        Ok(Self { domain, client })
+    }
+
+    /// Get the API client
+    pub fn client(&self) -> &Arc<TonApiClient> {
+        &self.client
+    }
+
+    fn block_info(block: Block) -> ChainResult<BlockInfo> {
+        Ok(BlockInfo {
+            hash: crate::client::parse_hash(&block.root_hash)?,
+            timestamp: block.gen_utime,
+            number: block.seqno.into(),
+        })
+    }
+}
+
+impl HyperlaneChain for TonProvider {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl HyperlaneProvider for TonProvider {
+    async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo> {
+        let seqno = u32::try_from(height)
+            .map_err(|_| HyperlaneProviderError::CouldNotFindBlockByHeight(height))?;
+        let block = self.client.masterchain_block(seqno).await?;
+        Self::block_info(block)
+    }
+
+    async fn get_txn_by_hash(&self, hash: &H512) -> ChainResult<TxnInfo> {
+        let tx_hash = H256::from(*hash);
+        // The hash may also be of the message that started the transaction
+        let transaction = match self.client.transaction(&tx_hash).await? {
+            Some(transaction) => Some(transaction),
+            None => self.client.transaction_by_message(&tx_hash).await?,
+        }
+        .ok_or(HyperlaneProviderError::CouldNotFindTransactionByHash(*hash))?;
+
+        let recipient = from_ton_address(&parse_address(&transaction.account)?);
+        // Transactions started by an external message, e.g. of a wallet,
+        // are sent by the account itself
+        let sender = match transaction.in_msg.as_ref().and_then(|m| m.source.as_ref()) {
+            Some(source) => from_ton_address(&parse_address(source)?),
+            None => recipient,
+        };
+        // TON charges fees in nanotons rather than by a gas price, so the
+        // fees are reported as gas used at a price of one nanoton
+        let gas_used = U256::from(transaction.total_fees);
+        let gas_price = Some(U256::one());
+
+        Ok(TxnInfo {
+            hash: *hash,
+            gas_limit: gas_used,
+            max_priority_fee_per_gas: None,
+            max_fee_per_gas: None,
+            gas_price,
+            nonce: 0,
+            sender,
+            recipient: Some(recipient),
+            receipt: Some(TxnReceiptInfo {
+                gas_used,
+                cumulative_gas_used: gas_used,
+                effective_gas_price: gas_price,
+            }),
+            raw_input_data: None,
+        })
+    }
+
+    async fn is_contract(&self, address: &H256) -> ChainResult<bool> {
+        let account = self.client.account(&to_ton_address(*address)).await?;
+        Ok(account.status == ACTIVE_ACCOUNT_STATUS)
+    }
+
+    async fn get_balance(&self, address: String) -> ChainResult<U256> {
+        let account = self.client.account(&parse_address(&address)?).await?;
+        Ok(account.balance.into())
+    }
+
+    async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+        let seqno = self.client.latest_masterchain_seqno().await?;
+        let block = self.client.masterchain_block(seqno).await?;
+        Ok(Some(ChainInfo {
+            latest_block: Self::block_info(block)?,
+            min_gas_price: None,
+        }))
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hyperlane_core::{ChainResult, FixedPointNumber, TxOutcome, H256, U256};
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Instant};
+use tonlib_core::cell::{BagOfCells, Cell};
+use tonlib_core::mnemonic::KeyPair;
+use tonlib_core::wallet::{TonWallet, WalletVersion};
+use tonlib_core::TonAddress;
+use tracing::{debug, instrument, warn};
+
+use crate::client::{TonApiClient, Transaction};
+use crate::error::{cell_error, HyperlaneTonError};
+use crate::stack::entry;
+use crate::utils::{from_ton_address, internal_message, parse_address};
+
+/// How long external messages of the wallet are valid for
+const MESSAGE_TTL: Duration = Duration::from_secs(60);
+/// Interval of polling the wallet for the execution of a sent message
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A signer of TON transactions, which sends messages through a v4r2 wallet
+/// derived from an ed25519 key
+#[derive(Clone)]
+pub struct TonSigner {
+    wallet: TonWallet,
+    /// Messages are sent one at a time, since every external message of the
+    /// wallet must carry the next seqno
+    send_lock: Arc<Mutex<()>>,
+}
+
+impl TonSigner {
+    /// Create a signer from the 32 byte seed of an ed25519 key
+    pub fn new(secret: &[u8]) -> ChainResult<Self> {
+        let secret = ed25519_dalek::SecretKey::from_bytes(secret).map_err(wallet_error)?;
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        // tonlib expects the secret key to be the seed followed by the
+        // public key
+        let key_pair = KeyPair {
+            public_key: public.to_bytes().to_vec(),
+            secret_key: [secret.to_bytes(), public.to_bytes()].concat(),
+        };
+        let wallet =
+            TonWallet::derive_default(WalletVersion::V4R2, &key_pair).map_err(wallet_error)?;
+        Ok(Self {
+            wallet,
+            send_lock: Default::default(),
+        })
+    }
+
+    /// The address of the wallet
+    pub fn address(&self) -> &TonAddress {
+        &self.wallet.address
+    }
+
+    /// The account id of the wallet
+    pub fn address_h256(&self) -> H256 {
+        from_ton_address(&self.wallet.address)
+    }
+
+    /// The user-friendly form of the address of the wallet
+    pub fn address_string(&self) -> String {
+        self.wallet.address.to_base64_url()
+    }
+
+    /// Send an internal message with `value` nanotons and `body` from the
+    /// wallet to `destination` and wait for the destination to handle it.
+    /// The outcome is the transaction of the destination, which is only
+    /// executed if it succeeded. A message the destination rejected bounces
+    /// back to the wallet, so the wallet accepting it isn't enough.
+    #[instrument(skip(self, client, body), fields(wallet = %self.address_string()))]
+    pub(crate) async fn send(
+        &self,
+        client: &TonApiClient,
+        destination: &TonAddress,
+        value: u64,
+        body: Cell,
+    ) -> ChainResult<TxOutcome> {
+        let guard = self.send_lock.lock().await;
+
+        let seqno = self.seqno(client).await?;
+        let message = Arc::new(internal_message(destination, value, body)?);
+        let expire_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now + MESSAGE_TTL)
+            .unwrap_or(MESSAGE_TTL)
+            .as_secs() as u32;
+        let body = self
+            .wallet
+            .create_external_body(expire_at, seqno, vec![message])
+            .map_err(wallet_error)?;
+        let signed = self
+            .wallet
+            .sign_external_body(&body)
+            .map_err(wallet_error)?;
+        // The first message of a wallet deploys it
+        let external = self
+            .wallet
+            .wrap_signed_body(signed, seqno == 0)
+            .map_err(wallet_error)?;
+        let boc = BagOfCells::from_root(external)
+            .serialize(true)
+            .map_err(cell_error)?;
+
+        let message_hash = client.send_message(&boc).await?;
+        debug!(?message_hash, seqno, "Sent external message");
+
+        if !self.wait_for_seqno(client, seqno).await {
+            warn!(?message_hash, seqno, "Wallet didn't accept message in time");
+            return Ok(not_executed(message_hash));
+        }
+        // The next message can be sent while this one is delivered
+        drop(guard);
+
+        let Some(wallet_tx) = wait_for_transaction(client, &message_hash).await else {
+            warn!(
+                ?message_hash,
+                "Transaction of the wallet wasn't found in time"
+            );
+            return Ok(not_executed(message_hash));
+        };
+        let wallet_tx_hash = wallet_tx.hash()?;
+        let Some(sent) = wallet_tx.out_msgs.iter().find(|message| {
+            message
+                .destination
+                .as_deref()
+                .and_then(|address| parse_address(address).ok())
+                .is_some_and(|address| &address == destination)
+        }) else {
+            warn!(?wallet_tx_hash, "Wallet didn't send the message");
+            return Ok(not_executed(wallet_tx_hash));
+        };
+        let Some(destination_tx) = wait_for_transaction(client, &sent.hash()?).await else {
+            warn!(
+                ?wallet_tx_hash,
+                "Transaction of the destination wasn't found in time"
+            );
+            return Ok(not_executed(wallet_tx_hash));
+        };
+
+        let transaction_id = destination_tx.hash()?;
+        let executed = destination_tx.succeeded();
+        if !executed {
+            warn!(
+                ?transaction_id,
+                exit_code = ?destination_tx
+                    .description
+                    .as_ref()
+                    .and_then(|description| description.compute_ph.as_ref())
+                    .and_then(|compute| compute.exit_code),
+                "Destination rejected the message"
+            );
+        }
+        Ok(TxOutcome {
+            transaction_id: transaction_id.into(),
+            executed,
+            // Fees are reported as gas used at a price of one nanoton, as in
+            // the transactions of the provider
+            gas_used: U256::from(destination_tx.total_fees),
+            gas_price: U256::one().try_into()?,
+            block_number: destination_tx.mc_block_seqno.map(u64::from),
+        })
+    }
+
+    /// The balance of the wallet in nanotons
+    pub(crate) async fn balance(&self, client: &TonApiClient) -> ChainResult<U256> {
+        let account = client.account(&self.wallet.address).await?;
+        Ok(account.balance.into())
+    }
+
+    /// The seqno of the next external message, which is 0 while the wallet
+    /// isn't deployed
+    async fn seqno(&self, client: &TonApiClient) -> ChainResult<u32> {
+        let account = client.account(&self.wallet.address).await?;
+        if account.status != "active" {
+            return Ok(0);
+        }
+        let stack = client
+            .run_get_method(&self.wallet.address, "seqno", vec![])
+            .await?;
+        let seqno = entry(&stack, 0)?.as_u64()?;
+        u32::try_from(seqno)
+            .map_err(|_| HyperlaneTonError::UnexpectedStack(format!("seqno {seqno}")).into())
+    }
+
+    /// Wait until the seqno of the wallet moved past `seqno`, which means
+    /// that the message was accepted, or the message expired
+    async fn wait_for_seqno(&self, client: &TonApiClient, seqno: u32) -> bool {
+        let deadline = Instant::now() + MESSAGE_TTL;
+        while Instant::now() < deadline {
+            sleep(POLL_INTERVAL).await;
+            match self.seqno(client).await {
+                Ok(current) if current > seqno => return true,
+                Ok(_) => {}
+                Err(err) => warn!(?err, "Failed to get wallet seqno"),
+            }
+        }
+        false
+    }
+}
+
+impl Debug for TonSigner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TonSigner")
+            .field("address", &self.address_string())
+            .finish()
+    }
+}
+
+/// Wait for the transaction started by the message with the given hash, or
+/// `None` if it isn't executed in time
+async fn wait_for_transaction(client: &TonApiClient, message_hash: &H256) -> Option<Transaction> {
+    let deadline = Instant::now() + MESSAGE_TTL;
+    while Instant::now() < deadline {
+        match client.transaction_by_message(message_hash).await {
+            Ok(Some(transaction)) => return Some(transaction),
+            Ok(None) => {}
+            Err(err) => warn!(?err, ?message_hash, "Failed to get transaction by message"),
+        }
+        sleep(POLL_INTERVAL).await;
+    }
+    None
+}
+
+/// The outcome of a message whose delivery couldn't be confirmed
+fn not_executed(transaction_id: H256) -> TxOutcome {
+    TxOutcome {
+        transaction_id: transaction_id.into(),
+        executed: false,
+        gas_used: U256::zero(),
+        gas_price: FixedPointNumber::zero(),
+        block_number: None,
+    }
+}
+
+fn wallet_error(err: impl std::fmt::Display) -> HyperlaneTonError {
+    HyperlaneTonError::Wallet(err.to_string())
+}
@@ -0,0 +1,154 @@
+use hyperlane_core::{ChainResult, H256, U256};
+use serde::{Deserialize, Serialize};
+use tonlib_core::cell::{ArcCell, BagOfCells};
+use tonlib_core::TonAddress;
+
+use crate::error::{cell_error, HyperlaneTonError};
+
+/// An entry of the TVM stack, as passed to and returned from get methods
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum StackEntry {
+    /// An integer, hex encoded with a `0x` prefix
+    Num(String),
+    /// A cell, as a base64 encoded bag of cells
+    Cell(String),
+    /// A slice, as a base64 encoded bag of cells
+    Slice(String),
+    /// A tuple of entries
+    Tuple(Vec<StackEntry>),
+    /// A list of entries
+    List(Vec<StackEntry>),
+    /// Null
+    Null,
+}
+
+impl StackEntry {
+    /// An integer entry
+    pub fn num(value: impl Into<U256>) -> Self {
+        Self::Num(format!("{:#x}", value.into()))
+    }
+
+    /// An integer entry of a hash, e.g. of a message id
+    pub fn hash(value: H256) -> Self {
+        Self::num(U256::from_big_endian(value.as_bytes()))
+    }
+
+    /// The unsigned integer of a `Num` entry. Negative integers are only
+    /// accepted as booleans, see [`Self::as_bool`].
+    pub fn as_u256(&self) -> ChainResult<U256> {
+        let Self::Num(value) = self else {
+            return Err(unexpected("an integer", self));
+        };
+        let hex = value.strip_prefix("0x").unwrap_or(value);
+        U256::from_str_radix(hex, 16).map_err(|_| unexpected("an unsigned integer", self))
+    }
+
+    /// The integer of a `Num` entry, which must fit into a `u64`
+    pub fn as_u64(&self) -> ChainResult<u64> {
+        let value = self.as_u256()?;
+        if value > U256::from(u64::MAX) {
+            return Err(unexpected("a 64 bit integer", self));
+        }
+        Ok(value.as_u64())
+    }
+
+    /// The hash encoded as a 256 bit integer
+    pub fn as_h256(&self) -> ChainResult<H256> {
+        let mut bytes = [0u8; 32];
+        self.as_u256()?.to_big_endian(&mut bytes);
+        Ok(H256(bytes))
+    }
+
+    /// A boolean, which TVM encodes as `-1` for true and `0` for false
+    pub fn as_bool(&self) -> ChainResult<bool> {
+        match self {
+            Self::Num(value) => Ok(!matches!(value.as_str(), "0x0" | "0" | "-0x0")),
+            _ => Err(unexpected("a boolean", self)),
+        }
+    }
+
+    /// The root of a `Cell` or `Slice` entry
+    pub fn as_cell(&self) -> ChainResult<ArcCell> {
+        let (Self::Cell(boc) | Self::Slice(boc)) = self else {
+            return Err(unexpected("a cell", self));
+        };
+        let boc = BagOfCells::parse_base64(boc).map_err(cell_error)?;
+        boc.single_root().map_err(|err| cell_error(err).into())
+    }
+
+    /// The address stored at the start of a `Cell` or `Slice` entry
+    pub fn as_address(&self) -> ChainResult<TonAddress> {
+        let cell = self.as_cell()?;
+        let address = cell.parser().load_address().map_err(cell_error)?;
+        Ok(address)
+    }
+
+    /// The address stored at the start of a `Cell` or `Slice` entry, or
+    /// `None` for a null entry
+    pub fn as_opt_address(&self) -> ChainResult<Option<TonAddress>> {
+        match self {
+            Self::Null => Ok(None),
+            _ => self.as_address().map(Some),
+        }
+    }
+
+    /// The entries of a `Tuple` or `List` entry. Null is an empty list.
+    pub fn as_tuple(&self) -> ChainResult<&[StackEntry]> {
+        match self {
+            Self::Tuple(entries) | Self::List(entries) => Ok(entries),
+            Self::Null => Ok(&[]),
+            _ => Err(unexpected("a tuple", self)),
+        }
+    }
+}
+
+/// The entry at `index` of a returned stack
+pub(crate) fn entry<'a>(stack: &'a [StackEntry], index: usize) -> ChainResult<&'a StackEntry> {
+    stack.get(index).ok_or_else(|| {
+        HyperlaneTonError::UnexpectedStack(format!(
+            "expected at least {} entries, got {}",
+            index + 1,
+            stack.len()
+        ))
+        .into()
+    })
+}
+
+fn unexpected(expected: &str, entry: &StackEntry) -> hyperlane_core::ChainCommunicationError {
+    HyperlaneTonError::UnexpectedStack(format!("expected {expected}, got {entry:?}")).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stack() {
+        let stack: Vec<StackEntry> = serde_json::from_str(
+            r#"[
+                {"type": "num", "value": "0x2a"},
+                {"type": "num", "value": "-0x1"},
+                {"type": "tuple", "value": [{"type": "num", "value": "0x0"}]},
+                {"type": "null"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(stack[0].as_u64().unwrap(), 42);
+        assert!(stack[1].as_bool().unwrap());
+        assert!(stack[1].as_u256().is_err());
+        let tuple = stack[2].as_tuple().unwrap();
+        assert_eq!(tuple.len(), 1);
+        assert!(!tuple[0].as_bool().unwrap());
+        assert!(stack[3].as_tuple().unwrap().is_empty());
+        assert!(stack[3].as_opt_address().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_num_roundtrip() {
+        let id = H256::repeat_byte(0xab);
+        assert_eq!(StackEntry::hash(id).as_h256().unwrap(), id);
+        assert_eq!(StackEntry::num(7u64), StackEntry::Num("0x7".to_owned()));
+    }
+}
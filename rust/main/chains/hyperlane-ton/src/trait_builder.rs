@@ -0,0 +1,38 @@
+use hyperlane_core::NativeToken;
+use url::Url;
+
+/// TON connection configuration
+#[derive(Debug, Clone)]
+pub struct ConnectionConf {
+    /// Base url of a TON Center v3 compatible HTTP API, e.g.
+    /// `https://toncenter.com/api/v3/`
+    pub url: Url,
+    /// Key of the API, sent in the `X-API-Key` header
+    pub api_key: Option<String>,
+    /// Native token and its denomination
+    pub native_token: NativeToken,
+    /// Nanotons attached to `process` messages, paying for the gas of the
+    /// mailbox, the ISM and the recipient. The mailbox refunds what isn't
+    /// used.
+    pub process_value: u64,
+    /// Nanotons attached to `announce` messages
+    pub announce_value: u64,
+}
+
+impl ConnectionConf {
+    /// The default nanotons attached to `process` messages
+    pub const DEFAULT_PROCESS_VALUE: u64 = 200_000_000;
+    /// The default nanotons attached to `announce` messages
+    pub const DEFAULT_ANNOUNCE_VALUE: u64 = 50_000_000;
+}
+
+/// An error type when parsing a connection configuration.
+#[derive(thiserror::Error, Debug)]
+pub enum ConnectionConfError {
+    /// Missing `url` for connection configuration
+    #[error("Missing `url` for connection configuration")]
+    MissingConnectionUrl,
+    /// Invalid `url` for connection configuration
+    #[error("Invalid `url` for connection configuration: `{0}` ({1})")]
+    InvalidConnectionUrl(String, url::ParseError),
+}
@@ -0,0 +1,125 @@
+use hyperlane_core::{ChainResult, H256};
+use num_bigint::BigUint;
+use tonlib_core::cell::{Cell, CellBuilder};
+use tonlib_core::TonAddress;
+
+use crate::error::{cell_error, HyperlaneTonError};
+
+/// Workchain of the contracts of hyperlane
+const BASECHAIN: i32 = 0;
+
+/// Bytes stored per cell of snake data. A cell holds 1023 bits, of which
+/// the last 7 are left unused to keep the data byte aligned.
+const SNAKE_BYTES_PER_CELL: usize = 127;
+
+/// Convert the account id of a basechain contract into a TON address
+pub(crate) fn to_ton_address(address: H256) -> TonAddress {
+    TonAddress::new(BASECHAIN, &address.0)
+}
+
+/// Convert a TON address into its account id
+pub(crate) fn from_ton_address(address: &TonAddress) -> H256 {
+    H256::from(address.hash_part)
+}
+
+/// Parse an address in raw or user-friendly form
+pub(crate) fn parse_address(address: &str) -> ChainResult<TonAddress> {
+    address
+        .parse()
+        .map_err(|_| HyperlaneTonError::InvalidAddress(address.to_owned()).into())
+}
+
+/// Store bytes as snake data, i.e. in a chain of cells where every cell
+/// references the next one
+pub(crate) fn snake_cell(bytes: &[u8]) -> ChainResult<Cell> {
+    let mut chunks: Vec<_> = bytes.chunks(SNAKE_BYTES_PER_CELL).collect();
+    let mut tail: Option<Cell> = None;
+    if chunks.is_empty() {
+        chunks.push(&[]);
+    }
+    for chunk in chunks.into_iter().rev() {
+        let mut builder = CellBuilder::new();
+        builder.store_slice(chunk).map_err(cell_error)?;
+        if let Some(next) = tail.take() {
+            builder.store_child(next).map_err(cell_error)?;
+        }
+        tail = Some(builder.build().map_err(cell_error)?);
+    }
+    Ok(tail.expect("at least one chunk is stored"))
+}
+
+/// Load the bytes of snake data, see [`snake_cell`]
+pub(crate) fn snake_bytes(cell: &Cell) -> ChainResult<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut current = cell;
+    loop {
+        let mut parser = current.parser();
+        let len = parser.remaining_bits() / 8;
+        bytes.extend(parser.load_bytes(len).map_err(cell_error)?);
+        match current.references().first() {
+            Some(next) => current = next,
+            None => return Ok(bytes),
+        }
+    }
+}
+
+/// Build an internal message carrying `value` nanotons and `body` to
+/// `destination`. The message bounces back if the destination fails to
+/// handle it. The source, fees and times are filled in by the validators.
+pub(crate) fn internal_message(
+    destination: &TonAddress,
+    value: u64,
+    body: Cell,
+) -> ChainResult<Cell> {
+    let mut builder = CellBuilder::new();
+    builder
+        // int_msg_info$0
+        .store_bit(false)
+        // ihr_disabled
+        .and_then(|b| b.store_bit(true))
+        // bounce
+        .and_then(|b| b.store_bit(true))
+        // bounced
+        .and_then(|b| b.store_bit(false))
+        // src: addr_none$00
+        .and_then(|b| b.store_u8(2, 0))
+        .and_then(|b| b.store_address(destination))
+        .and_then(|b| b.store_coins(&BigUint::from(value)))
+        // no extra currencies
+        .and_then(|b| b.store_bit(false))
+        // ihr_fee and fwd_fee
+        .and_then(|b| b.store_coins(&BigUint::from(0u8)))
+        .and_then(|b| b.store_coins(&BigUint::from(0u8)))
+        // created_lt and created_at
+        .and_then(|b| b.store_u64(64, 0))
+        .and_then(|b| b.store_u32(32, 0))
+        // no state init
+        .and_then(|b| b.store_bit(false))
+        // body as a reference
+        .and_then(|b| b.store_bit(true))
+        .and_then(|b| b.store_child(body))
+        .map_err(cell_error)?;
+    builder.build().map_err(|err| cell_error(err).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snake_roundtrip() {
+        for len in [0, 1, SNAKE_BYTES_PER_CELL, SNAKE_BYTES_PER_CELL + 1, 1000] {
+            let bytes: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let cell = snake_cell(&bytes).unwrap();
+            assert_eq!(snake_bytes(&cell).unwrap(), bytes, "len {len}");
+        }
+    }
+
+    #[test]
+    fn test_address_roundtrip() {
+        let id = H256::repeat_byte(0x42);
+        let address = to_ton_address(id);
+        assert_eq!(address.workchain, BASECHAIN);
+        assert_eq!(from_ton_address(&address), id);
+    }
+}
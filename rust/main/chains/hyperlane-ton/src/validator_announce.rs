@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use hyperlane_core::{
+    Announcement, ChainCommunicationError, ChainResult, ContractLocator, HyperlaneChain,
+    HyperlaneContract, HyperlaneDomain, HyperlaneProvider, SignedType, TxOutcome,
+    ValidatorAnnounce, H256, U256,
+};
+use tonlib_core::cell::{Cell, CellBuilder};
+use tracing::{instrument, trace};
+
+use crate::error::cell_error;
+use crate::events::op;
+use crate::stack::{entry, StackEntry};
+use crate::utils::{snake_bytes, snake_cell, to_ton_address};
+use crate::{ConnectionConf, TonApiClient, TonProvider, TonSigner};
+
+/// A reference to a ValidatorAnnounce contract on some TON chain
+#[derive(Clone, Debug)]
+pub struct TonValidatorAnnounce {
+    domain: HyperlaneDomain,
+    address: H256,
+    provider: TonProvider,
+    signer: Option<TonSigner>,
+    announce_value: u64,
+}
+
+impl TonValidatorAnnounce {
+    /// Create a new TON validator announce
+    pub fn new(
+        conf: &ConnectionConf,
+        locator: ContractLocator,
+        signer: Option<TonSigner>,
+    ) -> ChainResult<Self> {
+        let provider = TonProvider::new(locator.domain.clone(), conf)?;
+        Ok(Self {
+            domain: locator.domain.clone(),
+            address: locator.address,
+            provider,
+            signer,
+            announce_value: conf.announce_value,
+        })
+    }
+
+    fn client(&self) -> &TonApiClient {
+        self.provider.client()
+    }
+
+    async fn storage_locations(&self, validator: H256) -> ChainResult<Vec<String>> {
+        let stack = self
+            .client()
+            .run_get_method(
+                &to_ton_address(self.address),
+                "get_announced_storage_locations",
+                vec![StackEntry::hash(validator)],
+            )
+            .await?;
+        entry(&stack, 0)?
+            .as_tuple()?
+            .iter()
+            .map(|location| {
+                let bytes = snake_bytes(&location.as_cell()?)?;
+                String::from_utf8(bytes).map_err(ChainCommunicationError::from_other)
+            })
+            .collect()
+    }
+
+    /// The body of the message announcing `announcement`
+    fn announce_body(announcement: &SignedType<Announcement>) -> ChainResult<Cell> {
+        let location = snake_cell(announcement.value.storage_location.as_bytes())?;
+        let signature = snake_cell(&announcement.signature.to_vec())?;
+        let mut builder = CellBuilder::new();
+        builder
+            .store_u32(32, op::ANNOUNCE)
+            // query_id
+            .and_then(|b| b.store_u64(64, 0))
+            .and_then(|b| b.store_slice(announcement.value.validator.as_bytes()))
+            .and_then(|b| b.store_child(location))
+            .and_then(|b| b.store_child(signature))
+            .map_err(cell_error)?;
+        Ok(builder.build().map_err(cell_error)?)
+    }
+}
+
+impl HyperlaneContract for TonValidatorAnnounce {
+    fn address(&self) -> H256 {
+        self.address
+    }
+}
+
+impl HyperlaneChain for TonValidatorAnnounce {
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        Box::new(self.provider.clone())
+    }
+}
+
+#[async_trait]
+impl ValidatorAnnounce for TonValidatorAnnounce {
+    #[instrument(err, ret, skip(self))]
+    async fn get_announced_storage_locations(
+        &self,
+        validators: &[H256],
+    ) -> ChainResult<Vec<Vec<String>>> {
+        try_join_all(
+            validators
+                .iter()
+                .map(|validator| self.storage_locations(*validator)),
+        )
+        .await
+    }
+
+    #[instrument(err, ret, skip(self))]
+    async fn announce(&self, announcement: SignedType<Announcement>) -> ChainResult<TxOutcome> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or(ChainCommunicationError::SignerUnavailable)?;
+        let body = Self::announce_body(&announcement)?;
+        signer
+            .send(
+                self.client(),
+                &to_ton_address(self.address),
+                self.announce_value,
+                body,
+            )
+            .await
+    }
+
+    async fn announce_tokens_needed(
+        &self,
+        _announcement: SignedType<Announcement>,
+    ) -> Option<U256> {
+        let Some(signer) = &self.signer else {
+            trace!("No signer to announce with");
+            return None;
+        };
+        let Ok(balance) = signer.balance(self.client()).await else {
+            trace!("Unable to query balance");
+            return None;
+        };
+        Some(U256::from(self.announce_value).saturating_sub(balance))
+    }
+}
@@ -12,7 +12,7 @@ use ethers_core::types::U64;
 use hyperlane_core::rpc_clients::BlockNumberGetter;
 use hyperlane_core::ChainCommunicationError;
 use maplit::hashmap;
-use prometheus::{CounterVec, IntCounterVec};
+use prometheus::{CounterVec, HistogramVec, IntCounterVec};
 use serde::{de::DeserializeOwned, Serialize};
 
 pub use crate::ChainInfo;
@@ -51,6 +51,17 @@ pub struct JsonRpcClientMetrics {
     ///   might still be an "error" but not one with the transport layer.
     #[builder(setter(into, strip_option), default)]
     request_duration_seconds: Option<CounterVec>,
+
+    /// Latency of requests.
+    /// - `provider_node`: node this is connecting to, e.g. `alchemy.com`,
+    ///   `quicknode.pro`, or `localhost:8545`.
+    /// - `chain`: chain name (or chain id if the name is unknown) of the chain
+    ///   the request was made on.
+    /// - `method`: request method string.
+    /// - `status`: `success` or `failure` depending on the response. A `success`
+    ///   might still be an "error" but not one with the transport layer.
+    #[builder(setter(into, strip_option), default)]
+    request_latency_seconds: Option<HistogramVec>,
 }
 
 /// Expected label names for the metric.
@@ -168,6 +179,7 @@ where
     {
         let start = Instant::now();
         let res = self.inner.request(method, params).await;
+        let duration = (Instant::now() - start).as_secs_f64();
         let labels = hashmap! {
             "provider_node" => self.config.node_host(),
             "chain" => self.config.chain_name(),
@@ -178,9 +190,10 @@ where
             counter.with(&labels).inc()
         }
         if let Some(counter) = &self.metrics.request_duration_seconds {
-            counter
-                .with(&labels)
-                .inc_by((Instant::now() - start).as_secs_f64())
+            counter.with(&labels).inc_by(duration)
+        };
+        if let Some(histogram) = &self.metrics.request_latency_seconds {
+            histogram.with(&labels).observe(duration)
         };
         res
     }
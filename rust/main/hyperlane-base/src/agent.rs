@@ -8,6 +8,7 @@ use hyperlane_core::config::*;
 use tracing::info;
 
 use crate::{
+    cli::{AgentArgs, AgentCommand, SettingsArgs},
     metrics::{AgentMetrics, CoreMetrics},
     settings::Settings,
     ChainMetrics,
@@ -24,9 +25,9 @@ pub struct HyperlaneAgentCore {
 
 /// Settings of an agent defined from configuration
 pub trait LoadableFromSettings: AsRef<Settings> + Sized {
-    /// Create a new instance of these settings by reading the configs and env
-    /// vars.
-    fn load() -> ConfigResult<Self>;
+    /// Create a new instance of these settings by reading the configs, env
+    /// vars and command line arguments.
+    fn load(args: &SettingsArgs) -> ConfigResult<Self>;
 }
 
 /// A fundamental agent which does not make any assumptions about the tools
@@ -59,8 +60,14 @@ pub trait BaseAgent: Send + Sync + Debug {
 /// Call this from `main` to fully initialize and run the agent for its entire
 /// lifecycle. This assumes only a single agent is being run. This will
 /// initialize the metrics server and tracing as well.
-#[allow(unexpected_cfgs)] // TODO: `rustc` 1.80.1 clippy issue
 pub async fn agent_main<A: BaseAgent>() -> Result<()> {
+    agent_main_with_args::<A>(AgentArgs::from_env()?).await
+}
+
+/// Like [`agent_main`], but with already parsed command line arguments, e.g.
+/// by a binary that runs one of several agents depending on a subcommand.
+#[allow(unexpected_cfgs)] // TODO: `rustc` 1.80.1 clippy issue
+pub async fn agent_main_with_args<A: BaseAgent>(args: AgentArgs) -> Result<()> {
     if env::var("ONELINE_BACKTRACES")
         .map(|v| v.to_lowercase())
         .as_deref()
@@ -85,13 +92,38 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
 
     let agent_metadata = AgentMetadata::new(git_sha);
 
-    let settings = A::Settings::load()?;
+    let settings = A::Settings::load(&args.settings)?;
+    if args.command == AgentCommand::ValidateConfig {
+        println!("Config of agent {} is valid", A::AGENT_NAME);
+        return Ok(());
+    }
     let core_settings: &Settings = settings.as_ref();
 
     let metrics = settings.as_ref().metrics(A::AGENT_NAME)?;
     let tokio_server = core_settings.tracing.start_tracing(&metrics)?;
     let agent_metrics = AgentMetrics::new(&metrics)?;
     let chain_metrics = ChainMetrics::new(&metrics)?;
+    // Picked up by the RPC clients of Cosmos and Sealevel chains
+    metrics.rpc_client_metrics().install();
+    // Picked up by the signers of keys held in HSMs
+    metrics.hsm_metrics()?.install();
+    // Picked up by the HTTP clients of the chain providers
+    core_settings.http.clone().install()?;
+    // Pushing runs alongside the scrape endpoint each agent serves
+    let _metrics_push_task = core_settings
+        .metrics_pusher(metrics.clone())
+        .map(|pusher| pusher.spawn());
+    let wallet_balance_watcher = core_settings
+        .wallet_balance_watcher(A::AGENT_NAME, &metrics, agent_metrics.clone())
+        .await;
+    // The watcher exports the balances of the signers of all chains, so the
+    // agent doesn't export those of the chains it runs on as well
+    let agent_metrics = if wallet_balance_watcher.is_some() {
+        agent_metrics.without_wallet_balances()
+    } else {
+        agent_metrics
+    };
+    let _wallet_balance_task = wallet_balance_watcher.map(|watcher| watcher.spawn());
     let agent = A::from_settings(
         agent_metadata,
         settings,
@@ -105,5 +137,8 @@ pub async fn agent_main<A: BaseAgent>() -> Result<()> {
     // This await will only end if a panic happens. We won't crash, but instead gracefully shut down
     agent.run().await;
     info!(agent = A::AGENT_NAME, "Shutting down agent...");
+    // Flushes the spans not exported over OTLP yet. Shutting down blocks until
+    // the exporter is done, which must not happen on a runtime worker.
+    tokio::task::spawn_blocking(opentelemetry::global::shutdown_tracer_provider).await?;
     Ok(())
 }
@@ -0,0 +1,263 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
+
+use hyperlane_core::HyperlaneDomain;
+use serde::Serialize;
+use tokio::{sync::watch, time::sleep};
+use tracing::{info, info_span, warn, Instrument};
+
+/// Delay before retrying a failed initialization for the first time
+const INIT_RETRY_MIN_BACKOFF: Duration = Duration::from_secs(5);
+/// Maximum delay between retries of a failed initialization
+const INIT_RETRY_MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// The initialization status of a component of an agent on a chain, e.g. the
+/// message indexer of an origin chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ChainStatus {
+    /// The component is being initialized, which is retried until it succeeds
+    Initializing {
+        /// The number of attempts that failed so far
+        failed_attempts: u32,
+        /// The error of the last failed attempt, if any
+        last_error: Option<String>,
+    },
+    /// The component is initialized
+    Ready,
+}
+
+/// The initialization statuses of the components of an agent on each of its
+/// chains, which the agent's server reports on `/readiness`.
+#[derive(Debug, Clone, Default)]
+pub struct ChainStatuses(Arc<RwLock<BTreeMap<String, BTreeMap<&'static str, ChainStatus>>>>);
+
+impl ChainStatuses {
+    /// Set the status of `component` on `domain`
+    pub fn set(&self, domain: &HyperlaneDomain, component: &'static str, status: ChainStatus) {
+        self.0
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(domain.name().to_owned())
+            .or_default()
+            .insert(component, status);
+    }
+
+    /// The status of `component` on `domain`, if it was ever set
+    pub fn get(&self, domain: &HyperlaneDomain, component: &str) -> Option<ChainStatus> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(domain.name())
+            .and_then(|components| components.get(component))
+            .cloned()
+    }
+
+    /// The statuses of all components, by chain name
+    pub fn snapshot(&self) -> BTreeMap<String, BTreeMap<&'static str, ChainStatus>> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Whether all components on all chains are initialized
+    pub fn all_ready(&self) -> bool {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .flat_map(|components| components.values())
+            .all(|status| *status == ChainStatus::Ready)
+    }
+}
+
+/// A component of an agent on a chain, whose initialization is retried in the
+/// background until it succeeds. This lets an agent start on its healthy
+/// chains while the RPCs of another chain are unavailable.
+pub struct ChainInit<T> {
+    receiver: watch::Receiver<Option<T>>,
+}
+
+impl<T> Clone for ChainInit<T> {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> ChainInit<T> {
+    /// A component that is already initialized
+    pub fn ready(value: T) -> Self {
+        let (_sender, receiver) = watch::channel(Some(value));
+        Self { receiver }
+    }
+
+    /// Spawn a task calling `init` until it succeeds, backing off
+    /// exponentially between attempts. The status of the initialization is
+    /// kept up to date in `statuses`.
+    pub fn spawn<F, Fut>(
+        domain: &HyperlaneDomain,
+        component: &'static str,
+        statuses: ChainStatuses,
+        init: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = eyre::Result<T>> + Send,
+    {
+        Self::spawn_with_backoff(domain, component, statuses, init, INIT_RETRY_MIN_BACKOFF)
+    }
+
+    fn spawn_with_backoff<F, Fut>(
+        domain: &HyperlaneDomain,
+        component: &'static str,
+        statuses: ChainStatuses,
+        init: F,
+        min_backoff: Duration,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = eyre::Result<T>> + Send,
+    {
+        let (sender, receiver) = watch::channel(None);
+        statuses.set(
+            domain,
+            component,
+            ChainStatus::Initializing {
+                failed_attempts: 0,
+                last_error: None,
+            },
+        );
+        let domain = domain.clone();
+        let span = info_span!("ChainInit", domain = domain.name(), component);
+        tokio::spawn(
+            async move {
+                let mut backoff = min_backoff;
+                let mut failed_attempts = 0;
+                loop {
+                    match init().await {
+                        Ok(value) => {
+                            info!(failed_attempts, "Initialized");
+                            statuses.set(&domain, component, ChainStatus::Ready);
+                            sender.send_replace(Some(value));
+                            return;
+                        }
+                        Err(err) => {
+                            failed_attempts += 1;
+                            warn!(?err, failed_attempts, ?backoff, "Failed to initialize");
+                            statuses.set(
+                                &domain,
+                                component,
+                                ChainStatus::Initializing {
+                                    failed_attempts,
+                                    last_error: Some(err.to_string()),
+                                },
+                            );
+                        }
+                    }
+                    sleep(backoff).await;
+                    backoff = (backoff * 2).min(INIT_RETRY_MAX_BACKOFF);
+                }
+            }
+            .instrument(span),
+        );
+        Self { receiver }
+    }
+
+    /// The component, if it is initialized
+    pub fn get(&self) -> Option<T> {
+        self.receiver.borrow().clone()
+    }
+
+    /// Wait for the component to be initialized
+    pub async fn wait(&self) -> T {
+        let mut receiver = self.receiver.clone();
+        let value = receiver
+            .wait_for(Option::is_some)
+            .await
+            .expect("Chain initialization task panicked");
+        value.clone().expect("Checked to be initialized")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use eyre::eyre;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_chain_init_retries_until_ready() {
+        let domain = HyperlaneDomain::new_test_domain("test");
+        let statuses = ChainStatuses::default();
+        let attempts = Arc::new(AtomicU32::new(0));
+
+        let init = {
+            let attempts = attempts.clone();
+            ChainInit::spawn_with_backoff(
+                &domain,
+                "indexer",
+                statuses.clone(),
+                move || {
+                    let attempts = attempts.clone();
+                    async move {
+                        match attempts.fetch_add(1, Ordering::SeqCst) {
+                            0 | 1 => Err(eyre!("RPC unavailable")),
+                            _ => Ok(42),
+                        }
+                    }
+                },
+                Duration::from_millis(1),
+            )
+        };
+        assert_eq!(init.get(), None);
+        assert!(!statuses.all_ready());
+
+        assert_eq!(init.wait().await, 42);
+        assert_eq!(init.get(), Some(42));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(statuses.get(&domain, "indexer"), Some(ChainStatus::Ready));
+        assert!(statuses.all_ready());
+    }
+
+    #[test]
+    fn test_chain_statuses() {
+        let domain = HyperlaneDomain::new_test_domain("test");
+        let statuses = ChainStatuses::default();
+        statuses.set(&domain, "messages", ChainStatus::Ready);
+        statuses.set(
+            &domain,
+            "gas_payments",
+            ChainStatus::Initializing {
+                failed_attempts: 2,
+                last_error: Some("RPC unavailable".to_owned()),
+            },
+        );
+        assert!(!statuses.all_ready());
+        assert_eq!(
+            serde_json::to_value(statuses.snapshot()).unwrap(),
+            serde_json::json!({
+                "test": {
+                    "gas_payments": {
+                        "status": "initializing",
+                        "failed_attempts": 2,
+                        "last_error": "RPC unavailable"
+                    },
+                    "messages": { "status": "ready" }
+                }
+            })
+        );
+
+        statuses.set(&domain, "gas_payments", ChainStatus::Ready);
+        assert!(statuses.all_ready());
+        assert_eq!(ChainInit::ready(1).get(), Some(1));
+    }
+}
@@ -0,0 +1,252 @@
+//! Command line interface shared by all agents.
+//!
+//! Every agent accepts the same subcommands and global flags:
+//!
+//! * `run` (default) - run the agent
+//! * `config validate` - load and validate the config, then exit
+//! * `--config <paths>` - additional config files, comma separated or repeated
+//! * `--log-format <format>`, `--log-level <level>`, `--metrics-port <port>`
+//!
+//! All other `--key value` arguments are config overrides, e.g.
+//! `--chains.ethereum.index.from 100`, just like before subcommands existed.
+//! `--help` prints the usage of the agent and of each subcommand.
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use clap::{error::ErrorKind, Args, Parser, Subcommand};
+use eyre::{bail, Result};
+
+/// What an agent was asked to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AgentCommand {
+    /// Run the agent
+    #[default]
+    Run,
+    /// Load and validate the config without running the agent
+    ValidateConfig,
+}
+
+/// Where settings are loaded from, in addition to the default config files and
+/// the `HYP_` env vars
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SettingsArgs {
+    /// Config files passed with `--config`, loaded after the ones in
+    /// `CONFIG_FILES`
+    pub config_files: Vec<PathBuf>,
+    /// `--key value` config overrides, which take precedence over all other
+    /// config sources
+    pub overrides: Vec<OsString>,
+}
+
+/// Parsed command line of an agent
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AgentArgs {
+    /// The subcommand
+    pub command: AgentCommand,
+    /// The global flags and config overrides
+    pub settings: SettingsArgs,
+}
+
+#[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+    #[command(flatten)]
+    settings: CliSettings,
+}
+
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Run the agent (default)
+    Run(CliSettings),
+    /// Work with the config of the agent
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Load and validate the config, then exit
+    Validate(CliSettings),
+}
+
+#[derive(Debug, Args)]
+struct CliSettings {
+    /// Additional config files, comma separated or repeated
+    #[arg(long = "config", value_name = "PATHS", value_delimiter = ',')]
+    config_files: Vec<PathBuf>,
+    /// Shorthand for `--log.format`
+    #[arg(long, value_name = "FORMAT")]
+    log_format: Option<String>,
+    /// Shorthand for `--log.level`
+    #[arg(long, value_name = "LEVEL")]
+    log_level: Option<String>,
+    /// Shorthand for `--metricsPort`
+    #[arg(long, value_name = "PORT")]
+    metrics_port: Option<String>,
+    /// Config overrides, e.g. `--chains.ethereum.index.from 100`
+    #[arg(value_name = "--<CONFIG KEY> <VALUE>", allow_hyphen_values = true)]
+    overrides: Vec<OsString>,
+}
+
+impl CliSettings {
+    fn into_settings_args(self) -> Result<SettingsArgs> {
+        // Bare words before any override are mistyped subcommands
+        if let Some(arg) = self
+            .overrides
+            .first()
+            .and_then(|arg| arg.to_str())
+            .filter(|arg| !arg.starts_with("--"))
+        {
+            bail!("Unknown subcommand `{arg}`");
+        }
+
+        let mut overrides = vec![];
+        for (key, value) in [
+            ("log.format", self.log_format),
+            ("log.level", self.log_level),
+            ("metricsPort", self.metrics_port),
+        ] {
+            if let Some(value) = value {
+                overrides.push(format!("--{key}={value}").into());
+            }
+        }
+        overrides.extend(self.overrides);
+
+        Ok(SettingsArgs {
+            config_files: self
+                .config_files
+                .into_iter()
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect(),
+            overrides,
+        })
+    }
+}
+
+impl AgentArgs {
+    /// Parse the arguments of the current process
+    pub fn from_env() -> Result<Self> {
+        let mut args = std::env::args_os();
+        let bin_name = args
+            .next()
+            .as_deref()
+            .and_then(|path| Path::new(path).file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or("agent")
+            .to_owned();
+        Self::parse(&bin_name, args)
+    }
+
+    /// Parse arguments, which must not include the executable path.
+    /// `bin_name` is how the agent is invoked, as shown by `--help`, which
+    /// prints the usage and exits.
+    pub fn parse<I, S>(bin_name: &str, args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let args = std::iter::once(OsString::from(bin_name))
+            .chain(args.into_iter().map(|arg| arg.as_ref().to_owned()));
+        let cli = match Cli::try_parse_from(args) {
+            Ok(cli) => cli,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    ErrorKind::DisplayHelp | ErrorKind::DisplayVersion
+                ) =>
+            {
+                err.exit()
+            }
+            Err(err) => bail!("{}", err.render()),
+        };
+
+        let (command, settings) = match cli.command {
+            None => (AgentCommand::Run, cli.settings),
+            Some(CliCommand::Run(settings)) => (AgentCommand::Run, settings),
+            Some(CliCommand::Config(ConfigCommand::Validate(settings))) => {
+                (AgentCommand::ValidateConfig, settings)
+            }
+        };
+        Ok(Self {
+            command,
+            settings: settings.into_settings_args()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn cli_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_legacy_flags_as_run() {
+        let args =
+            AgentArgs::parse("agent", ["--originChainName", "ethereum", "--db=/tmp/db"]).unwrap();
+        assert_eq!(args.command, AgentCommand::Run);
+        assert!(args.settings.config_files.is_empty());
+        assert_eq!(
+            args.settings.overrides,
+            ["--originChainName", "ethereum", "--db=/tmp/db"].map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn parses_subcommands_and_global_flags() {
+        let args = AgentArgs::parse(
+            "agent",
+            [
+                "config",
+                "validate",
+                "--config",
+                "a.json,b.json",
+                "--config=c.json",
+                "--log-format",
+                "json",
+                "--metrics-port=9091",
+                "--relayChains",
+                "ethereum,optimism",
+            ],
+        )
+        .unwrap();
+        assert_eq!(args.command, AgentCommand::ValidateConfig);
+        assert_eq!(
+            args.settings.config_files,
+            ["a.json", "b.json", "c.json"].map(PathBuf::from)
+        );
+        assert_eq!(
+            args.settings.overrides,
+            [
+                "--log.format=json",
+                "--metricsPort=9091",
+                "--relayChains",
+                "ethereum,optimism"
+            ]
+            .map(OsString::from)
+        );
+
+        assert_eq!(
+            AgentArgs::parse("agent", ["run"]).unwrap(),
+            AgentArgs::default()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_subcommands_and_missing_values() {
+        assert!(AgentArgs::parse("agent", ["start"]).is_err());
+        assert!(AgentArgs::parse("agent", ["config", "show"]).is_err());
+        assert!(AgentArgs::parse("agent", ["--log-level"]).is_err());
+        assert!(AgentArgs::parse("agent", ["--config", "--log-level", "info"]).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use hyperlane_core::{
+    ChainResult, HyperlaneDomain, Indexed, Indexer, LogMeta, SequenceAwareIndexer, H512,
+};
+use tokio::{sync::watch, time::MissedTickBehavior};
+use tracing::{info_span, warn, Instrument};
+
+/// How often the finalized block height of a chain is polled.
+const BLOCK_HEIGHT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Polls the finalized block height of a chain at a fixed rate and shares it
+/// through a watch channel, so that any number of consumers cost a single
+/// RPC call per interval.
+#[derive(Debug, Clone)]
+pub struct BlockHeightWatcher {
+    receiver: watch::Receiver<Option<u32>>,
+}
+
+impl BlockHeightWatcher {
+    /// Spawn a task polling the finalized block height from `indexer`. The
+    /// task stops once every clone of the watcher has been dropped.
+    pub fn spawn<T: 'static>(
+        domain: &HyperlaneDomain,
+        indexer: Arc<dyn SequenceAwareIndexer<T>>,
+        interval: Duration,
+    ) -> Self {
+        let (sender, receiver) = watch::channel(None);
+        let span = info_span!("BlockHeightWatcher", domain = domain.name());
+        tokio::spawn(
+            async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                while !sender.is_closed() {
+                    ticker.tick().await;
+                    match indexer.get_finalized_block_number().await {
+                        Ok(height) => {
+                            sender.send_replace(Some(height));
+                        }
+                        Err(err) => warn!(?err, "Failed to fetch finalized block height"),
+                    }
+                }
+            }
+            .instrument(span),
+        );
+        Self { receiver }
+    }
+
+    /// The most recently observed finalized block height, if any has been
+    /// observed yet.
+    pub fn latest(&self) -> Option<u32> {
+        *self.receiver.borrow()
+    }
+
+    /// Subscribe to updates of the finalized block height.
+    pub fn subscribe(&self) -> watch::Receiver<Option<u32>> {
+        self.receiver.clone()
+    }
+}
+
+/// The block height watchers of an agent, at most one per chain.
+#[derive(Debug, Clone, Default)]
+pub struct BlockHeightWatchers(Arc<Mutex<HashMap<HyperlaneDomain, BlockHeightWatcher>>>);
+
+impl BlockHeightWatchers {
+    /// Get the watcher of `domain`, spawning one that polls `indexer` if
+    /// there is none yet.
+    pub fn get_or_spawn<T: 'static>(
+        &self,
+        domain: &HyperlaneDomain,
+        indexer: Arc<dyn SequenceAwareIndexer<T>>,
+    ) -> BlockHeightWatcher {
+        self.0
+            .lock()
+            .expect("block height watchers lock poisoned")
+            .entry(domain.clone())
+            .or_insert_with(|| {
+                BlockHeightWatcher::spawn(domain, indexer, BLOCK_HEIGHT_POLL_INTERVAL)
+            })
+            .clone()
+    }
+}
+
+/// An indexer that reads the finalized block height from a shared
+/// [`BlockHeightWatcher`] instead of querying the chain itself, both for
+/// watermark cursors and for the sequence counts of sequence-aware cursors.
+/// Falls back to the wrapped indexer until the watcher has observed a height.
+#[derive(Debug)]
+pub struct SharedBlockHeightIndexer<T> {
+    inner: Arc<dyn SequenceAwareIndexer<T>>,
+    watcher: BlockHeightWatcher,
+}
+
+impl<T> SharedBlockHeightIndexer<T> {
+    /// Wrap `inner`, reading block heights from `watcher`.
+    pub fn new(inner: Arc<dyn SequenceAwareIndexer<T>>, watcher: BlockHeightWatcher) -> Self {
+        Self { inner, watcher }
+    }
+}
+
+#[async_trait]
+impl<T: Debug + Send + Sync> Indexer<T> for SharedBlockHeightIndexer<T> {
+    async fn fetch_logs_in_range(
+        &self,
+        range: RangeInclusive<u32>,
+    ) -> ChainResult<Vec<(Indexed<T>, LogMeta)>> {
+        self.inner.fetch_logs_in_range(range).await
+    }
+
+    async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+        match self.watcher.latest() {
+            Some(height) => Ok(height),
+            None => self.inner.get_finalized_block_number().await,
+        }
+    }
+
+    async fn fetch_logs_by_tx_hash(
+        &self,
+        tx_hash: H512,
+    ) -> ChainResult<Vec<(Indexed<T>, LogMeta)>> {
+        self.inner.fetch_logs_by_tx_hash(tx_hash).await
+    }
+}
+
+#[async_trait]
+impl<T: Debug + Send + Sync> SequenceAwareIndexer<T> for SharedBlockHeightIndexer<T> {
+    async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+        match self.watcher.latest() {
+            Some(tip) => self.inner.sequence_count_at_tip(tip).await,
+            None => self.inner.latest_sequence_count_and_tip().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use hyperlane_core::KnownHyperlaneDomain;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct CountingIndexer {
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Indexer<()> for CountingIndexer {
+        async fn fetch_logs_in_range(
+            &self,
+            _range: RangeInclusive<u32>,
+        ) -> ChainResult<Vec<(Indexed<()>, LogMeta)>> {
+            Ok(vec![])
+        }
+
+        async fn get_finalized_block_number(&self) -> ChainResult<u32> {
+            Ok(100 + self.calls.fetch_add(1, Ordering::SeqCst))
+        }
+    }
+
+    #[async_trait]
+    impl SequenceAwareIndexer<()> for CountingIndexer {
+        async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)> {
+            let tip = self.get_finalized_block_number().await?;
+            self.sequence_count_at_tip(tip).await
+        }
+
+        async fn sequence_count_at_tip(&self, tip: u32) -> ChainResult<(Option<u32>, u32)> {
+            Ok((Some(tip - 90), tip))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watchers_are_shared_per_chain() {
+        let watchers = BlockHeightWatchers::default();
+        let domain = HyperlaneDomain::Known(KnownHyperlaneDomain::Arbitrum);
+        let indexer = Arc::new(CountingIndexer::default());
+
+        let watcher = watchers.get_or_spawn(
+            &domain,
+            indexer.clone() as Arc<dyn SequenceAwareIndexer<()>>,
+        );
+        let mut receiver = watcher.subscribe();
+        receiver.changed().await.unwrap();
+        assert_eq!(watcher.latest(), Some(100));
+
+        // A second consumer of the same chain reuses the running watcher
+        let other = watchers.get_or_spawn(
+            &domain,
+            indexer.clone() as Arc<dyn SequenceAwareIndexer<()>>,
+        );
+        let shared = SharedBlockHeightIndexer::new(
+            indexer.clone() as Arc<dyn SequenceAwareIndexer<()>>,
+            other,
+        );
+        assert_eq!(shared.get_finalized_block_number().await.unwrap(), 100);
+        // Sequence counts are queried at the watched height too
+        assert_eq!(
+            shared.latest_sequence_count_and_tip().await.unwrap(),
+            (Some(10), 100)
+        );
+        assert_eq!(indexer.calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,3 +1,4 @@
+use hyperlane_core::metrics::definitions;
 use prometheus::IntGaugeVec;
 
 use crate::CoreMetrics;
@@ -33,27 +34,15 @@ impl CursorMetrics {
     /// Instantiate a new CursorMetrics object.
     pub fn new(metrics: &CoreMetrics) -> Self {
         let cursor_current_block = metrics
-            .new_int_gauge(
-                "cursor_current_block",
-                "Current block of the cursor",
-                &["event_type", "chain", "cursor_type"],
-            )
+            .new_int_gauge(&definitions::CURSOR_CURRENT_BLOCK)
             .expect("failed to register cursor_current_block metric");
 
         let cursor_current_sequence = metrics
-            .new_int_gauge(
-                "cursor_current_sequence",
-                "Current sequence of the cursor",
-                &["event_type", "chain", "cursor_type"],
-            )
+            .new_int_gauge(&definitions::CURSOR_CURRENT_SEQUENCE)
             .expect("failed to register cursor_current_sequence metric");
 
         let cursor_max_sequence = metrics
-            .new_int_gauge(
-                "cursor_max_sequence",
-                "Max sequence of the cursor",
-                &["event_type", "chain"],
-            )
+            .new_int_gauge(&definitions::CURSOR_MAX_SEQUENCE)
             .expect("failed to register cursor_max_sequence metric");
 
         CursorMetrics {
@@ -38,6 +38,8 @@ impl Indexable for HyperlaneMessage {
             HyperlaneDomainProtocol::Fuel => todo!(),
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Ton => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
         }
     }
 
@@ -58,6 +60,8 @@ impl Indexable for InterchainGasPayment {
             HyperlaneDomainProtocol::Fuel => todo!(),
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Ton => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Aptos => CursorType::RateLimited,
         }
     }
 
@@ -73,6 +77,8 @@ impl Indexable for MerkleTreeInsertion {
             HyperlaneDomainProtocol::Fuel => todo!(),
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Ton => CursorType::SequenceAware,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
         }
     }
 
@@ -88,6 +94,8 @@ impl Indexable for Delivery {
             HyperlaneDomainProtocol::Fuel => todo!(),
             HyperlaneDomainProtocol::Sealevel => CursorType::SequenceAware,
             HyperlaneDomainProtocol::Cosmos => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Ton => CursorType::RateLimited,
+            HyperlaneDomainProtocol::Aptos => CursorType::SequenceAware,
         }
     }
 
@@ -237,6 +237,14 @@ where
             }
         }
     }
+
+    async fn rewind_to_block(&mut self, block: u32) -> Result<()> {
+        if block >= self.sync_state.next_block {
+            return Ok(());
+        }
+        self.sync_state.next_block = block;
+        self.store.store_high_watermark(block).await
+    }
 }
 
 impl<T: Indexable> Debug for RateLimitedContractSyncCursor<T> {
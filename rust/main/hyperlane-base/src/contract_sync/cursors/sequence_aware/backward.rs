@@ -436,6 +436,12 @@ impl<T: Debug + Clone + Sync + Send + Indexable + 'static> ContractSyncCursor<T>
 
         Ok(())
     }
+
+    /// Logs from recent blocks are indexed again by the forward cursor, which
+    /// rewinds below the sequence the backward cursor started from if needed.
+    async fn rewind_to_block(&mut self, _block: u32) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
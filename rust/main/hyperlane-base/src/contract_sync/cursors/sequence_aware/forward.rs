@@ -513,6 +513,36 @@ impl<T: Send + Sync + Clone + Debug + Indexable + 'static> ContractSyncCursor<T>
         };
         Ok(())
     }
+    /// Rewinds the cursor to target the sequence after the last log indexed
+    /// before `block`. Logs of the store that are missing their block number
+    /// are assumed to precede `block`, as they are indexed again anyway.
+    async fn rewind_to_block(&mut self, block: u32) -> Result<()> {
+        if block > self.latest_queried_block() {
+            return Ok(());
+        }
+        let mut sequence = self.last_indexed_snapshot.sequence;
+        while let Some(current) = sequence {
+            match self
+                .store
+                .retrieve_log_block_number_by_sequence(current)
+                .await?
+            {
+                Some(log_block) if log_block >= block as u64 => sequence = current.checked_sub(1),
+                _ => break,
+            }
+        }
+        self.last_indexed_snapshot = LastIndexedSnapshot {
+            sequence,
+            at_block: block,
+        };
+        warn!(
+            block,
+            last_indexed_snapshot=?self.last_indexed_snapshot,
+            "Rewinding cursor to block"
+        );
+        self.rewind();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
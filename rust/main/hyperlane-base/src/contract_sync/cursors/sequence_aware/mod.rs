@@ -161,4 +161,9 @@ impl<T: Send + Sync + Clone + Debug + 'static + Indexable> ContractSyncCursor<T>
             SyncDirection::Backward => self.backward.update(logs, range).await,
         }
     }
+
+    async fn rewind_to_block(&mut self, block: u32) -> Result<()> {
+        self.forward.rewind_to_block(block).await?;
+        self.backward.rewind_to_block(block).await
+    }
 }
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use eyre::Result;
+use hyperlane_core::{ContractSyncCursor, HyperlaneDomain, H512};
+
+use super::{broadcast::BroadcastMpscSender, ContractSyncer, SyncOptions};
+use crate::{settings::IndexSettings, ChainInit};
+
+/// A contract syncer of a chain that couldn't be built at startup, e.g. as its
+/// RPCs were unavailable. Building it is retried in the background, and
+/// cursors and syncing wait until it succeeds.
+pub struct LazyContractSyncer<T> {
+    domain: HyperlaneDomain,
+    sync: ChainInit<Arc<dyn ContractSyncer<T>>>,
+}
+
+impl<T> LazyContractSyncer<T> {
+    /// Wrap the syncer of `domain` that is being built by `sync`
+    pub fn new(domain: HyperlaneDomain, sync: ChainInit<Arc<dyn ContractSyncer<T>>>) -> Self {
+        Self { domain, sync }
+    }
+}
+
+#[async_trait]
+impl<T: Send + Sync + 'static> ContractSyncer<T> for LazyContractSyncer<T> {
+    async fn cursor(
+        &self,
+        index_settings: IndexSettings,
+    ) -> Result<Box<dyn ContractSyncCursor<T>>> {
+        self.sync.wait().await.cursor(index_settings).await
+    }
+
+    async fn sync(&self, label: &'static str, opts: SyncOptions<T>) {
+        self.sync.wait().await.sync(label, opts).await
+    }
+
+    fn domain(&self) -> &HyperlaneDomain {
+        &self.domain
+    }
+
+    /// The broadcaster of the syncer once it is built. Until then, other
+    /// syncs of the chain can't subscribe to it and only index with their
+    /// cursors.
+    fn get_broadcaster(&self) -> Option<BroadcastMpscSender<H512>> {
+        self.sync.get().and_then(|sync| sync.get_broadcaster())
+    }
+
+    async fn wait_for_broadcaster(&self) -> Option<BroadcastMpscSender<H512>> {
+        self.sync.wait().await.get_broadcaster()
+    }
+}
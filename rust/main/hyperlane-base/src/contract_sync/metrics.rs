@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use hyperlane_core::metrics::definitions;
 use prometheus::{IntCounterVec, IntGaugeVec};
 
 use crate::CoreMetrics;
@@ -23,6 +24,13 @@ pub struct ContractSyncMetrics {
     /// - `chain`: Chain the indexer is collecting data from.
     pub stored_events: IntCounterVec,
 
+    /// Reorgs detected of blocks that logs were indexed from
+    ///
+    /// Labels:
+    /// - `data_type`: the data the indexer is recording. E.g. `messages` or `gas_payments`.
+    /// - `chain`: Chain the indexer is collecting data from.
+    pub reorgs_detected: IntCounterVec,
+
     /// See `last_known_message_nonce` in CoreMetrics.
     pub message_nonce: IntGaugeVec,
 
@@ -37,27 +45,19 @@ impl ContractSyncMetrics {
     /// Instantiate a new ContractSyncMetrics object.
     pub fn new(metrics: &CoreMetrics) -> Self {
         let indexed_height = metrics
-            .new_int_gauge(
-                "contract_sync_block_height",
-                "Height of a recently observed block",
-                &["data_type", "chain"],
-            )
+            .new_int_gauge(&definitions::CONTRACT_SYNC_BLOCK_HEIGHT)
             .expect("failed to register block_height metric");
 
         let stored_events = metrics
-            .new_int_counter(
-                "contract_sync_stored_events",
-                "Number of events stored into db",
-                &["data_type", "chain"],
-            )
+            .new_int_counter(&definitions::CONTRACT_SYNC_STORED_EVENTS)
             .expect("failed to register stored_events metric");
 
+        let reorgs_detected = metrics
+            .new_int_counter(&definitions::CONTRACT_SYNC_REORGS_DETECTED)
+            .expect("failed to register reorgs_detected metric");
+
         let liveness_metrics = metrics
-            .new_int_gauge(
-                "contract_sync_liveness",
-                "Last timestamp observed by contract sync",
-                &["data_type", "chain"],
-            )
+            .new_int_gauge(&definitions::CONTRACT_SYNC_LIVENESS)
             .expect("failed to register liveness metric");
 
         let message_nonce = metrics.last_known_message_nonce();
@@ -66,6 +66,7 @@ impl ContractSyncMetrics {
         ContractSyncMetrics {
             indexed_height,
             stored_events,
+            reorgs_detected,
             message_nonce,
             liveness_metrics,
             cursor_metrics,
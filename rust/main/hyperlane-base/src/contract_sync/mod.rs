@@ -1,5 +1,10 @@
 use std::{
-    collections::HashSet, fmt::Debug, hash::Hash, marker::PhantomData, sync::Arc, time::Duration,
+    collections::HashSet,
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{Arc, Mutex as StdMutex, PoisonError},
+    time::Duration,
     time::UNIX_EPOCH,
 };
 
@@ -10,7 +15,7 @@ use derive_new::new;
 use eyre::Result;
 use hyperlane_core::{
     utils::fmt_sync_time, ContractSyncCursor, CursorAction, HyperlaneDomain, HyperlaneLogStore,
-    HyperlaneSequenceAwareIndexerStore, HyperlaneWatermarkedLogStore, Indexer,
+    HyperlaneProvider, HyperlaneSequenceAwareIndexerStore, HyperlaneWatermarkedLogStore, Indexer,
     SequenceAwareIndexer,
 };
 use hyperlane_core::{Indexed, LogMeta, H512};
@@ -18,17 +23,24 @@ pub use metrics::ContractSyncMetrics;
 use prometheus::core::{AtomicI64, AtomicU64, GenericCounter, GenericGauge};
 use tokio::sync::mpsc::{error::TryRecvError, Receiver as MpscReceiver};
 use tokio::time::sleep;
-use tracing::{debug, info, instrument, trace, warn};
+use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::settings::IndexSettings;
 
+pub use block_height::{BlockHeightWatcher, BlockHeightWatchers, SharedBlockHeightIndexer};
+pub use lazy::LazyContractSyncer;
+
+mod block_height;
 /// Broadcast channel utility, with async interface for `send`
 pub mod broadcast;
 pub(crate) mod cursors;
 mod eta_calculator;
+mod lazy;
 mod metrics;
+mod reorg;
 
 use cursors::ForwardBackwardSequenceAwareSyncCursor;
+use reorg::ReorgDetector;
 
 const SLEEP_DURATION: Duration = Duration::from_secs(5);
 
@@ -50,9 +62,18 @@ pub struct ContractSync<T: Indexable, S: HyperlaneLogStore<T>, I: Indexer<T>> {
     indexer: I,
     metrics: ContractSyncMetrics,
     broadcast_sender: Option<BroadcastMpscSender<H512>>,
+    reorg_detection: Option<ReorgDetection>,
     _phantom: PhantomData<T>,
 }
 
+/// How a contract sync detects reorgs of the blocks it indexed logs from
+#[derive(Debug, Clone)]
+struct ReorgDetection {
+    provider: Arc<dyn HyperlaneProvider>,
+    /// Number of blocks the cursor is rewound by beyond the first reorged block
+    reorg_period: u32,
+}
+
 impl<T: Indexable, S: HyperlaneLogStore<T>, I: Indexer<T>> ContractSync<T, S, I> {
     /// Create a new ContractSync
     pub fn new(
@@ -67,9 +88,26 @@ impl<T: Indexable, S: HyperlaneLogStore<T>, I: Indexer<T>> ContractSync<T, S, I>
             indexer,
             metrics,
             broadcast_sender: T::broadcast_channel_size().map(BroadcastMpscSender::new),
+            reorg_detection: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Detect reorgs of the blocks logs were indexed from by comparing their
+    /// hashes with those `provider` reports. On a reorg, the logs of the
+    /// reorged blocks are removed from the store and the cursor is rewound to
+    /// `reorg_period` blocks below the first reorged block.
+    pub fn with_reorg_detection(
+        mut self,
+        provider: Arc<dyn HyperlaneProvider>,
+        reorg_period: u32,
+    ) -> Self {
+        self.reorg_detection = Some(ReorgDetection {
+            provider,
+            reorg_period,
+        });
+        self
+    }
 }
 
 impl<T, S, I> ContractSync<T, S, I>
@@ -103,6 +141,14 @@ where
             .metrics
             .liveness_metrics
             .with_label_values(&[label, chain_name]);
+        let reorgs_detected_metric = self
+            .metrics
+            .reorgs_detected
+            .with_label_values(&[label, chain_name]);
+        let mut reorg_detector = match self.reorg_detection.clone() {
+            Some(detection) => Some(self.restore_reorg_detector(detection).await),
+            None => None,
+        };
 
         loop {
             Self::update_liveness_metric(&liveness_metric);
@@ -110,8 +156,23 @@ where
                 self.fetch_logs_from_receiver(rx, &stored_logs_metric).await;
             }
             if let Some(cursor) = opts.cursor.as_mut() {
-                self.fetch_logs_with_cursor(cursor, &stored_logs_metric, &indexed_height_metric)
-                    .await;
+                if let Some(block) = opts.rewinder.as_ref().and_then(CursorRewinder::take) {
+                    info!(block, cursor = ?cursor, "Rewinding cursor on request");
+                    if let Err(err) = cursor.rewind_to_block(block).await {
+                        warn!(?err, block, "Error rewinding cursor on request");
+                    }
+                }
+                if let Some(detector) = reorg_detector.as_mut() {
+                    self.handle_reorgs(detector, cursor, &reorgs_detected_metric)
+                        .await;
+                }
+                self.fetch_logs_with_cursor(
+                    cursor,
+                    &mut reorg_detector,
+                    &stored_logs_metric,
+                    &indexed_height_metric,
+                )
+                .await;
             }
 
             // Added so that we confuse compiler that it is an infinite loop
@@ -173,10 +234,67 @@ where
         }
     }
 
-    #[instrument(fields(domain=self.domain().name()), skip(self, stored_logs_metric, indexed_height_metric))]
+    /// Check for reorgs of the blocks logs were indexed from, and if there
+    /// was one, remove the logs of the reorged blocks and rewind the cursor
+    #[instrument(fields(domain=self.domain().name()), skip(self, detector, cursor, reorgs_detected_metric))]
+    async fn handle_reorgs(
+        &self,
+        detector: &mut ReorgDetector<T>,
+        cursor: &mut Box<dyn ContractSyncCursor<T>>,
+        reorgs_detected_metric: &GenericCounter<AtomicU64>,
+    ) {
+        let reorg = match detector.check().await {
+            Ok(Some(reorg)) => reorg,
+            Ok(None) => return,
+            Err(err) => {
+                warn!(?err, "Error checking indexed blocks for reorgs");
+                return;
+            }
+        };
+        reorgs_detected_metric.inc();
+        error!(
+            first_reorged_block = reorg.first_reorged_block,
+            rewind_to = reorg.rewind_to,
+            reorged_logs = reorg.logs.len(),
+            cursor = ?cursor,
+            "Detected reorg of indexed blocks, rewinding cursor"
+        );
+        // The cursor is rewound first, as it may look up the blocks of the
+        // logs that are about to be removed
+        if let Err(err) = cursor.rewind_to_block(reorg.rewind_to).await {
+            warn!(?err, "Error rewinding cursor after reorg");
+        }
+        match self.store.invalidate_logs(&reorg.logs).await {
+            Ok(removed) => info!(removed, "Removed reorged logs from db"),
+            Err(err) => warn!(?err, "Error removing reorged logs from db"),
+        }
+        self.persist_indexed_blocks(detector).await;
+    }
+
+    /// Build a reorg detector tracking the blocks persisted by a previous run
+    async fn restore_reorg_detector(&self, detection: ReorgDetection) -> ReorgDetector<T> {
+        let mut detector = ReorgDetector::new(detection.provider, detection.reorg_period);
+        match self.store.retrieve_indexed_blocks().await {
+            Ok(blocks) => detector.restore(blocks),
+            Err(err) => warn!(
+                ?err,
+                "Error restoring indexed blocks, tracking new blocks only"
+            ),
+        }
+        detector
+    }
+
+    async fn persist_indexed_blocks(&self, detector: &ReorgDetector<T>) {
+        if let Err(err) = self.store.store_indexed_blocks(&detector.blocks()).await {
+            warn!(?err, "Error persisting indexed blocks");
+        }
+    }
+
+    #[instrument(fields(domain=self.domain().name()), skip(self, reorg_detector, stored_logs_metric, indexed_height_metric))]
     async fn fetch_logs_with_cursor(
         &self,
         cursor: &mut Box<dyn ContractSyncCursor<T>>,
+        reorg_detector: &mut Option<ReorgDetector<T>>,
         stored_logs_metric: &GenericCounter<AtomicU64>,
         indexed_height_metric: &GenericGauge<AtomicI64>,
     ) {
@@ -223,6 +341,12 @@ where
                     }
                 }
 
+                if let Some(detector) = reorg_detector.as_mut() {
+                    if detector.record(&logs) {
+                        self.persist_indexed_blocks(detector).await;
+                    }
+                }
+
                 // Update cursor
                 if let Err(err) = cursor.update(logs, range).await {
                     warn!(?err, "Error updating cursor");
@@ -297,6 +421,12 @@ pub trait ContractSyncer<T>: Send + Sync {
 
     /// If this syncer is also a broadcaster, return the channel to receive txids
     fn get_broadcaster(&self) -> Option<BroadcastMpscSender<H512>>;
+
+    /// Like `get_broadcaster`, but waits for the syncer to be built if it
+    /// is still being built in the background
+    async fn wait_for_broadcaster(&self) -> Option<BroadcastMpscSender<H512>> {
+        self.get_broadcaster()
+    }
 }
 
 #[derive(new)]
@@ -307,6 +437,16 @@ pub struct SyncOptions<T> {
     // txids from a channel to other indexing tasks
     cursor: Option<Box<dyn ContractSyncCursor<T>>>,
     tx_id_receiver: Option<MpscReceiver<H512>>,
+    #[new(default)]
+    rewinder: Option<CursorRewinder>,
+}
+
+impl<T> SyncOptions<T> {
+    /// Rewind the cursor whenever `rewinder` is asked to
+    pub fn with_rewinder(mut self, rewinder: CursorRewinder) -> Self {
+        self.rewinder = Some(rewinder);
+        self
+    }
 }
 
 impl<T> From<Box<dyn ContractSyncCursor<T>>> for SyncOptions<T> {
@@ -314,10 +454,35 @@ impl<T> From<Box<dyn ContractSyncCursor<T>>> for SyncOptions<T> {
         Self {
             cursor: Some(cursor),
             tx_id_receiver: None,
+            rewinder: None,
         }
     }
 }
 
+/// Asks a running contract sync to rewind its cursor, e.g. so that an
+/// operator can have logs indexed again that were missed. The cursor is
+/// rewound before the next range is indexed.
+#[derive(Debug, Clone, Default)]
+pub struct CursorRewinder(Arc<StdMutex<Option<u32>>>);
+
+impl CursorRewinder {
+    /// Rewind the cursor to `block`. If several rewinds are requested before
+    /// the cursor is rewound, it is rewound to the lowest block.
+    pub fn rewind_to_block(&self, block: u32) {
+        let mut pending = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        *pending = Some(pending.map_or(block, |pending| pending.min(block)));
+    }
+
+    /// The block a pending rewind was requested to, if any
+    pub fn pending_block(&self) -> Option<u32> {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn take(&self) -> Option<u32> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+}
+
 #[async_trait]
 impl<T> ContractSyncer<T> for WatermarkContractSync<T>
 where
@@ -402,3 +567,21 @@ where
         ContractSync::get_broadcaster(self)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cursor_rewinder_keeps_lowest_block() {
+        let rewinder = CursorRewinder::default();
+        assert_eq!(rewinder.take(), None);
+
+        rewinder.rewind_to_block(100);
+        rewinder.clone().rewind_to_block(50);
+        rewinder.rewind_to_block(80);
+        assert_eq!(rewinder.pending_block(), Some(50));
+        assert_eq!(rewinder.take(), Some(50));
+        assert_eq!(rewinder.pending_block(), None);
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+use hyperlane_core::{HyperlaneProvider, Indexed, IndexedBlock, LogMeta};
+
+/// How often the hashes of the blocks logs were indexed from are compared with
+/// the chain.
+const REORG_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Number of the most recent blocks logs were indexed from that are checked
+/// for reorgs.
+const MAX_TRACKED_BLOCKS: usize = 256;
+
+/// Detects reorgs of the blocks that a contract sync indexed logs from, by
+/// comparing the hashes of these blocks at the time they were indexed with
+/// the hashes of the blocks currently at their heights.
+///
+/// Blocks whose hash isn't known, e.g. as the indexer doesn't report it, are
+/// not tracked. The tracked blocks are meant to be persisted whenever they
+/// change, and restored after a restart.
+#[derive(Debug)]
+pub(crate) struct ReorgDetector<T> {
+    provider: Arc<dyn HyperlaneProvider>,
+    /// Number of blocks the cursor is rewound by beyond the first reorged block
+    reorg_period: u32,
+    check_interval: Duration,
+    last_check: Instant,
+    /// The hash of each tracked block and the logs indexed from it, by height
+    blocks: BTreeMap<u64, IndexedBlock<T>>,
+}
+
+/// A reorg of blocks that logs were indexed from
+#[derive(Debug)]
+pub(crate) struct Reorg<T> {
+    /// The lowest height whose block was reorged
+    pub first_reorged_block: u64,
+    /// The block the cursor should index from again
+    pub rewind_to: u32,
+    /// The logs indexed from the reorged blocks
+    pub logs: Vec<(Indexed<T>, LogMeta)>,
+}
+
+impl<T: Clone + Debug> ReorgDetector<T> {
+    pub fn new(provider: Arc<dyn HyperlaneProvider>, reorg_period: u32) -> Self {
+        Self {
+            provider,
+            reorg_period,
+            check_interval: REORG_CHECK_INTERVAL,
+            last_check: Instant::now(),
+            blocks: BTreeMap::new(),
+        }
+    }
+
+    /// Track the blocks restored from a previous run
+    pub fn restore(&mut self, blocks: Vec<IndexedBlock<T>>) {
+        self.blocks
+            .extend(blocks.into_iter().map(|block| (block.number, block)));
+        self.evict_oldest_blocks();
+    }
+
+    /// The tracked blocks, in ascending order of height
+    pub fn blocks(&self) -> Vec<IndexedBlock<T>> {
+        self.blocks.values().cloned().collect()
+    }
+
+    /// Track the blocks that `logs` were indexed from. Returns whether any
+    /// block is tracked that wasn't before.
+    pub fn record(&mut self, logs: &[(Indexed<T>, LogMeta)]) -> bool {
+        let mut recorded = false;
+        for (log, meta) in logs {
+            if meta.block_hash.is_zero() {
+                continue;
+            }
+            let block = self
+                .blocks
+                .entry(meta.block_number)
+                .or_insert_with(|| IndexedBlock {
+                    number: meta.block_number,
+                    hash: meta.block_hash,
+                    logs: vec![],
+                });
+            // Logs of a block with a different hash were indexed after a reorg
+            // that went unnoticed, in which case the latest logs are kept
+            if block.hash != meta.block_hash {
+                block.hash = meta.block_hash;
+                block.logs.clear();
+            }
+            // Logs are indexed again after restarts and cursor rewinds
+            if !block.logs.iter().any(|(_, logged)| logged == meta) {
+                block.logs.push((log.clone(), meta.clone()));
+                recorded = true;
+            }
+        }
+        self.evict_oldest_blocks();
+        recorded
+    }
+
+    fn evict_oldest_blocks(&mut self) {
+        while self.blocks.len() > MAX_TRACKED_BLOCKS {
+            self.blocks.pop_first();
+        }
+    }
+
+    /// Compare the tracked blocks with the chain, if it's time to do so.
+    /// Blocks are compared from the highest down, until one matches the
+    /// chain, as the blocks below it are then part of the chain as well.
+    pub async fn check(&mut self) -> Result<Option<Reorg<T>>> {
+        if self.last_check.elapsed() < self.check_interval {
+            return Ok(None);
+        }
+        self.last_check = Instant::now();
+
+        let mut reorged_heights = vec![];
+        for (height, block) in self.blocks.iter().rev() {
+            let current = self.provider.get_block_by_height(*height).await?;
+            if current.hash == block.hash {
+                break;
+            }
+            reorged_heights.push(*height);
+        }
+        let Some(first_reorged_block) = reorged_heights.last().copied() else {
+            return Ok(None);
+        };
+        let logs = reorged_heights
+            .iter()
+            .filter_map(|height| self.blocks.remove(height))
+            .flat_map(|block| block.logs)
+            .collect();
+        let rewind_to = u32::try_from(first_reorged_block.saturating_sub(self.reorg_period.into()))
+            .unwrap_or(u32::MAX);
+        Ok(Some(Reorg {
+            first_reorged_block,
+            rewind_to,
+            logs,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use async_trait::async_trait;
+    use hyperlane_core::{
+        BlockInfo, ChainInfo, ChainResult, HyperlaneChain, HyperlaneDomain, HyperlaneProvider,
+        TxnInfo, H256, H512, U256,
+    };
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// A provider serving the blocks of a chain whose hashes can be changed
+    #[derive(Debug, Default)]
+    struct MockChain {
+        hashes: Mutex<BTreeMap<u64, H256>>,
+    }
+
+    impl HyperlaneChain for MockChain {
+        fn domain(&self) -> &HyperlaneDomain {
+            unimplemented!()
+        }
+
+        fn provider(&self) -> Box<dyn HyperlaneProvider> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl HyperlaneProvider for MockChain {
+        async fn get_block_by_height(&self, height: u64) -> ChainResult<BlockInfo> {
+            Ok(BlockInfo {
+                hash: self.hashes.lock().unwrap()[&height],
+                timestamp: 0,
+                number: height,
+            })
+        }
+
+        async fn get_txn_by_hash(&self, _hash: &H512) -> ChainResult<TxnInfo> {
+            unimplemented!()
+        }
+
+        async fn is_contract(&self, _address: &H256) -> ChainResult<bool> {
+            unimplemented!()
+        }
+
+        async fn get_balance(&self, _address: String) -> ChainResult<U256> {
+            unimplemented!()
+        }
+
+        async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+            unimplemented!()
+        }
+    }
+
+    fn log(sequence: u32, block_number: u64, block_hash: H256) -> (Indexed<u32>, LogMeta) {
+        (
+            Indexed::new(sequence).with_sequence(sequence),
+            LogMeta {
+                block_number,
+                block_hash,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_detects_reorged_blocks() {
+        let chain = Arc::new(MockChain::default());
+        chain
+            .hashes
+            .lock()
+            .unwrap()
+            .extend((100..110).map(|height| (height, H256::from_low_u64_be(height))));
+        let mut detector = ReorgDetector::new(chain.clone(), 5);
+        detector.check_interval = Duration::ZERO;
+
+        let logs = [100, 104, 107]
+            .into_iter()
+            .enumerate()
+            .map(|(sequence, height)| log(sequence as u32, height, H256::from_low_u64_be(height)))
+            .collect::<Vec<_>>();
+        detector.record(&logs);
+        assert!(detector.check().await.unwrap().is_none());
+
+        // Blocks from 103 onwards are replaced
+        chain
+            .hashes
+            .lock()
+            .unwrap()
+            .extend((103..110).map(|height| (height, H256::repeat_byte(0xff))));
+        let reorg = detector.check().await.unwrap().unwrap();
+        assert_eq!(reorg.first_reorged_block, 104);
+        assert_eq!(reorg.rewind_to, 99);
+        assert_eq!(
+            reorg
+                .logs
+                .iter()
+                .map(|(log, _)| log.sequence)
+                .collect::<Vec<_>>(),
+            vec![Some(2), Some(1)]
+        );
+
+        // The reorged blocks are no longer tracked
+        assert!(detector.check().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_detects_reorg_of_restored_blocks() {
+        let chain = Arc::new(MockChain::default());
+        chain
+            .hashes
+            .lock()
+            .unwrap()
+            .extend((100..110).map(|height| (height, H256::from_low_u64_be(height))));
+        let mut detector = ReorgDetector::new(chain.clone(), 0);
+        let logs = vec![log(0, 105, H256::from_low_u64_be(105))];
+        assert!(detector.record(&logs));
+        // Logs indexed again aren't tracked twice
+        assert!(!detector.record(&logs));
+
+        // After a restart
+        let mut restored = ReorgDetector::new(chain.clone(), 0);
+        restored.check_interval = Duration::ZERO;
+        restored.restore(detector.blocks());
+        assert_eq!(restored.blocks(), detector.blocks());
+
+        chain
+            .hashes
+            .lock()
+            .unwrap()
+            .insert(105, H256::repeat_byte(0xff));
+        let reorg = restored.check().await.unwrap().unwrap();
+        assert_eq!(reorg.first_reorged_block, 105);
+        assert_eq!(reorg.rewind_to, 105);
+        assert_eq!(reorg.logs, logs);
+        assert!(restored.blocks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ignores_blocks_without_hash() {
+        let chain = Arc::new(MockChain::default());
+        let mut detector = ReorgDetector::new(chain, 5);
+        detector.check_interval = Duration::ZERO;
+
+        detector.record(&[log(0, 100, H256::zero())]);
+        assert!(detector.blocks.is_empty());
+        assert!(detector.check().await.unwrap().is_none());
+    }
+}
@@ -5,7 +5,10 @@ use hyperlane_core::{
 };
 pub use rocks::*;
 
-pub use self::storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData};
+pub use self::storage_types::{
+    DeadLetter, DeadLetterReason, DeliveryReceipt, InterchainGasExpenditureData,
+    InterchainGasPaymentData, OrderedMessage,
+};
 
 mod error;
 mod rocks;
@@ -26,6 +29,15 @@ pub trait HyperlaneDb: Send + Sync {
     /// Get the origin domain of the database
     fn domain(&self) -> &HyperlaneDomain;
 
+    /// Retrieve the number of reorgs that removed dispatched messages
+    fn retrieve_message_reorg_count(&self) -> DbResult<Option<u32>>;
+
+    /// Retrieve the lowest nonce removed by a reorg, by the index of the reorg
+    fn retrieve_lowest_reorged_nonce_by_reorg_index(
+        &self,
+        reorg_index: &u32,
+    ) -> DbResult<Option<u32>>;
+
     fn store_message_id_by_nonce(&self, nonce: &u32, id: &H256) -> DbResult<()>;
 
     fn retrieve_message_id_by_nonce(&self, nonce: &u32) -> DbResult<Option<H256>>;
@@ -1,17 +1,24 @@
+use std::ops::Range;
+
 use async_trait::async_trait;
 use eyre::{bail, Result};
+use rocksdb::WriteBatch;
 use tracing::{debug, instrument, trace};
 
 use hyperlane_core::{
+    accumulator::{incremental::IncrementalMerkle, snapshot_prover::MerkleTreeSnapshotStore},
     Decode, Encode, GasPaymentKey, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage,
-    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexed,
+    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexed, IndexedBlock,
     InterchainGasExpenditure, InterchainGasPayment, InterchainGasPaymentMeta, LogMeta,
     MerkleTreeInsertion, PendingOperationStatus, H256,
 };
 
 use super::{DbError, TypedDB, DB};
 use crate::db::{
-    storage_types::{InterchainGasExpenditureData, InterchainGasPaymentData},
+    storage_types::{
+        DeadLetter, DeliveryReceipt, IndexedBlocks, InterchainGasExpenditureData,
+        InterchainGasPaymentData, OrderedMessage,
+    },
     HyperlaneDb,
 };
 
@@ -36,6 +43,20 @@ const MERKLE_LEAF_INDEX_BY_MESSAGE_ID: &str = "merkle_leaf_index_by_message_id_"
 const MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX: &str =
     "merkle_tree_insertion_block_number_by_leaf_index_";
 const LATEST_INDEXED_GAS_PAYMENT_BLOCK: &str = "latest_indexed_gas_payment_block";
+const DELIVERY_RECEIPT_BY_OFFSET: &str = "delivery_receipt_by_offset_";
+const DELIVERY_RECEIPT_COUNT: &str = "delivery_receipt_count";
+const EXPORTED_DELIVERY_RECEIPT_COUNT: &str = "exported_delivery_receipt_count";
+const DEAD_LETTER_BY_MESSAGE_ID: &str = "dead_letter_by_message_id_";
+const MERKLE_TREE_SNAPSHOT_INCREMENTAL: &str = "merkle_tree_snapshot_incremental";
+const MERKLE_TREE_SNAPSHOT_LEAF_BY_INDEX: &str = "merkle_tree_snapshot_leaf_by_index_";
+const ORDERED_MESSAGE_BY_NONCE: &str = "ordered_message_by_nonce_";
+const INDEXED_MESSAGE_BLOCKS: &str = "indexed_message_blocks";
+const INDEXED_GAS_PAYMENT_BLOCKS: &str = "indexed_gas_payment_blocks";
+const INDEXED_TREE_INSERTION_BLOCKS: &str = "indexed_tree_insertion_blocks";
+const MESSAGE_REORG_COUNT: &str = "message_reorg_count";
+const LOWEST_REORGED_NONCE_BY_REORG_INDEX: &str = "lowest_reorged_nonce_by_reorg_index_";
+const TREE_INSERTION_REORG_COUNT: &str = "tree_insertion_reorg_count";
+const LOWEST_REORGED_LEAF_INDEX_BY_REORG_INDEX: &str = "lowest_reorged_leaf_index_by_reorg_index_";
 
 /// Rocks DB result type
 pub type DbResult<T> = std::result::Result<T, DbError>;
@@ -215,6 +236,173 @@ impl HyperlaneRocksDB {
         Ok(true)
     }
 
+    /// Remove the messages dispatched at `nonces`, e.g. because they were
+    /// reorged out of the chain, so that the messages dispatched at these
+    /// nonces on the canonical chain are stored when indexed again. The
+    /// lowest removed nonce is recorded as a reorg, for processors to rewind
+    /// to. Returns the number of messages removed.
+    pub fn invalidate_messages(&self, nonces: impl IntoIterator<Item = u32>) -> DbResult<u32> {
+        let mut removed = 0;
+        let mut lowest_removed: Option<u32> = None;
+        for nonce in nonces {
+            if self.retrieve_message_id_by_nonce(&nonce)?.is_none() {
+                continue;
+            }
+            debug!(nonce, "Removing message from db");
+            self.delete_keyed(MESSAGE_ID, &nonce)?;
+            self.delete_keyed(MESSAGE_DISPATCHED_BLOCK_NUMBER, &nonce)?;
+            // The message dispatched at the nonce on the canonical chain
+            // wasn't processed yet
+            self.delete_keyed(NONCE_PROCESSED, &nonce)?;
+            lowest_removed = Some(lowest_removed.map_or(nonce, |lowest| lowest.min(nonce)));
+            removed += 1;
+        }
+        // The highest seen nonce otherwise only increases, so lower it to
+        // below the removed messages
+        if let Some(lowest_removed) = lowest_removed {
+            let highest_seen = self.retrieve_highest_seen_message_nonce()?;
+            if highest_seen.is_some_and(|highest_seen| highest_seen >= lowest_removed) {
+                match lowest_removed.checked_sub(1) {
+                    Some(nonce) => self.store_highest_seen_message_nonce_number(&nonce)?,
+                    None => self.delete_keyed(HIGHEST_SEEN_MESSAGE_NONCE, &bool::default())?,
+                }
+            }
+            self.record_reorg(
+                MESSAGE_REORG_COUNT,
+                LOWEST_REORGED_NONCE_BY_REORG_INDEX,
+                lowest_removed,
+            )?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove the merkle tree insertions at `leaf_indices`, e.g. because they
+    /// were reorged out of the chain. The lowest removed leaf index is
+    /// recorded as a reorg, for merkle tree builders to rewind to. Returns the
+    /// number of insertions removed.
+    pub fn invalidate_tree_insertions(
+        &self,
+        leaf_indices: impl IntoIterator<Item = u32>,
+    ) -> DbResult<u32> {
+        let mut removed = 0;
+        let mut lowest_removed: Option<u32> = None;
+        for leaf_index in leaf_indices {
+            let Some(insertion) = self.retrieve_merkle_tree_insertion_by_leaf_index(&leaf_index)?
+            else {
+                continue;
+            };
+            debug!(leaf_index, "Removing tree insertion from db");
+            self.delete_keyed(MERKLE_TREE_INSERTION, &leaf_index)?;
+            self.delete_keyed(
+                MERKLE_TREE_INSERTION_BLOCK_NUMBER_BY_LEAF_INDEX,
+                &leaf_index,
+            )?;
+            self.delete_keyed(MERKLE_LEAF_INDEX_BY_MESSAGE_ID, &insertion.message_id())?;
+            lowest_removed =
+                Some(lowest_removed.map_or(leaf_index, |lowest| lowest.min(leaf_index)));
+            removed += 1;
+        }
+        if let Some(lowest_removed) = lowest_removed {
+            self.record_reorg(
+                TREE_INSERTION_REORG_COUNT,
+                LOWEST_REORGED_LEAF_INDEX_BY_REORG_INDEX,
+                lowest_removed,
+            )?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove gas payments that were reorged out of the chain, deducting them
+    /// from the total payment for their message, so that the payments of the
+    /// canonical chain are counted once indexed again. Returns the number of
+    /// payments removed.
+    pub fn invalidate_gas_payments(
+        &self,
+        payments: &[(Indexed<InterchainGasPayment>, LogMeta)],
+    ) -> DbResult<u32> {
+        let mut removed = 0;
+        for (indexed_payment, log_meta) in payments {
+            let payment = *indexed_payment.inner();
+            if let Some(sequence) = indexed_payment.sequence {
+                self.delete_keyed(GAS_PAYMENT_BY_SEQUENCE, &sequence)?;
+                self.delete_keyed(GAS_PAYMENT_BLOCK_BY_SEQUENCE, &sequence)?;
+            }
+            let payment_meta: InterchainGasPaymentMeta = log_meta.into();
+            if !self
+                .retrieve_processed_by_gas_payment_meta(&payment_meta)?
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            debug!(?payment, ?log_meta, "Removing gas payment from db");
+            self.delete_keyed(GAS_PAYMENT_META_PROCESSED, &payment_meta)?;
+            let gas_payment_key = payment.into();
+            if let Some(total) = self.retrieve_gas_payment_by_gas_payment_key(gas_payment_key)? {
+                let remaining = InterchainGasPaymentData {
+                    payment: total.payment.saturating_sub(payment.payment),
+                    gas_amount: total.gas_amount.saturating_sub(payment.gas_amount),
+                };
+                self.store_interchain_gas_payment_data_by_gas_payment_key(
+                    &gas_payment_key,
+                    &remaining,
+                )?;
+            }
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Retrieve the number of reorgs that removed merkle tree insertions
+    pub fn retrieve_tree_insertion_reorg_count(&self) -> DbResult<Option<u32>> {
+        self.retrieve_value_by_key(TREE_INSERTION_REORG_COUNT, &bool::default())
+    }
+
+    /// Retrieve the lowest leaf index removed by a reorg, by the index of the
+    /// reorg
+    pub fn retrieve_lowest_reorged_leaf_index_by_reorg_index(
+        &self,
+        reorg_index: &u32,
+    ) -> DbResult<Option<u32>> {
+        self.retrieve_value_by_key(LOWEST_REORGED_LEAF_INDEX_BY_REORG_INDEX, reorg_index)
+    }
+
+    /// Append a reorg that removed logs from `lowest_removed` onwards to the
+    /// log of reorgs stored at `count_key` and `by_index_key`. The log is only
+    /// appended to, so that readers can follow it without coordinating with
+    /// the writer.
+    fn record_reorg(
+        &self,
+        count_key: &str,
+        by_index_key: &str,
+        lowest_removed: u32,
+    ) -> DbResult<()> {
+        let count: u32 = self
+            .retrieve_value_by_key(count_key, &bool::default())?
+            .unwrap_or_default();
+        let mut batch = WriteBatch::default();
+        self.batch_store_keyed_encodable(&mut batch, by_index_key, &count, &lowest_removed);
+        self.batch_store_keyed_encodable(&mut batch, count_key, &bool::default(), &(count + 1));
+        self.write_batch(batch)
+    }
+
+    fn store_indexed_blocks_by_key<T: Encode + Clone>(
+        &self,
+        key: &str,
+        blocks: &[IndexedBlock<T>],
+    ) -> DbResult<()> {
+        self.store_value_by_key(key, &bool::default(), &IndexedBlocks(blocks.to_vec()))
+    }
+
+    fn retrieve_indexed_blocks_by_key<T: Decode>(
+        &self,
+        key: &str,
+    ) -> DbResult<Vec<IndexedBlock<T>>> {
+        Ok(self
+            .retrieve_value_by_key::<_, IndexedBlocks<T>>(key, &bool::default())?
+            .map(|blocks| blocks.0)
+            .unwrap_or_default())
+    }
+
     /// Processes the gas expenditure and store the total expenditure for the
     /// message.
     pub fn process_gas_expenditure(&self, expenditure: InterchainGasExpenditure) -> DbResult<()> {
@@ -279,6 +467,184 @@ impl HyperlaneRocksDB {
             .unwrap_or_default()
             .complete(message_id))
     }
+
+    /// Mark the message with `nonce` as processed and append its delivery
+    /// receipt at `offset` of the log of delivery receipts of messages from
+    /// this domain. Both are written atomically, so that a receipt is neither
+    /// lost nor appended twice if the relayer stops in between.
+    pub fn store_processed_by_nonce_with_delivery_receipt(
+        &self,
+        nonce: &u32,
+        offset: u64,
+        receipt: &DeliveryReceipt,
+    ) -> DbResult<()> {
+        let mut batch = WriteBatch::default();
+        self.batch_store_keyed_encodable(&mut batch, NONCE_PROCESSED, nonce, &true);
+        self.batch_store_keyed_encodable(&mut batch, DELIVERY_RECEIPT_BY_OFFSET, &offset, receipt);
+        // There's no unit struct Encode/Decode impl, so just use `bool` and always use the `Default::default()` key
+        self.batch_store_keyed_encodable(
+            &mut batch,
+            DELIVERY_RECEIPT_COUNT,
+            &bool::default(),
+            &(offset + 1),
+        );
+        self.write_batch(batch)
+    }
+
+    /// Retrieve the delivery receipt at `offset` of the log of delivery
+    /// receipts
+    pub fn retrieve_delivery_receipt_by_offset(
+        &self,
+        offset: u64,
+    ) -> DbResult<Option<DeliveryReceipt>> {
+        self.retrieve_value_by_key(DELIVERY_RECEIPT_BY_OFFSET, &offset)
+    }
+
+    /// Retrieve the number of delivery receipts in the log, i.e. the offset
+    /// of the next receipt
+    pub fn retrieve_delivery_receipt_count(&self) -> DbResult<Option<u64>> {
+        self.retrieve_value_by_key(DELIVERY_RECEIPT_COUNT, &bool::default())
+    }
+
+    /// Store the number of delivery receipts that were exported, i.e. the
+    /// offset export resumes from, and delete the receipts at `exported`,
+    /// which are no longer needed
+    pub fn store_exported_delivery_receipt_count(
+        &self,
+        count: u64,
+        exported: Range<u64>,
+    ) -> DbResult<()> {
+        let mut batch = WriteBatch::default();
+        self.batch_store_keyed_encodable(
+            &mut batch,
+            EXPORTED_DELIVERY_RECEIPT_COUNT,
+            &bool::default(),
+            &count,
+        );
+        for offset in exported {
+            self.batch_delete_keyed(&mut batch, DELIVERY_RECEIPT_BY_OFFSET, &offset);
+        }
+        self.write_batch(batch)
+    }
+
+    /// Retrieve the number of delivery receipts that were exported
+    pub fn retrieve_exported_delivery_receipt_count(&self) -> DbResult<Option<u64>> {
+        self.retrieve_value_by_key(EXPORTED_DELIVERY_RECEIPT_COUNT, &bool::default())
+    }
+
+    /// Move a message to the dead-letter queue of this domain
+    pub fn store_dead_letter(&self, dead_letter: &DeadLetter) -> DbResult<()> {
+        self.store_value_by_key(
+            DEAD_LETTER_BY_MESSAGE_ID,
+            &dead_letter.message_id,
+            dead_letter,
+        )
+    }
+
+    /// Retrieve a message from the dead-letter queue by its id
+    pub fn retrieve_dead_letter_by_message_id(
+        &self,
+        message_id: &H256,
+    ) -> DbResult<Option<DeadLetter>> {
+        self.retrieve_value_by_key(DEAD_LETTER_BY_MESSAGE_ID, message_id)
+    }
+
+    /// Remove a message from the dead-letter queue, e.g. to re-inject it
+    pub fn delete_dead_letter_by_message_id(&self, message_id: &H256) -> DbResult<()> {
+        self.delete_keyed(DEAD_LETTER_BY_MESSAGE_ID, message_id)
+    }
+
+    /// Retrieve all messages in the dead-letter queue of this domain
+    pub fn retrieve_dead_letters(&self) -> DbResult<Vec<DeadLetter>> {
+        self.retrieve_decodables_with_prefix(DEAD_LETTER_BY_MESSAGE_ID)
+    }
+
+    /// Store a message held back until it can be delivered in order
+    pub fn store_ordered_message(&self, ordered_message: &OrderedMessage) -> DbResult<()> {
+        self.store_value_by_key(
+            ORDERED_MESSAGE_BY_NONCE,
+            &ordered_message.nonce,
+            ordered_message,
+        )
+    }
+
+    /// Remove a message that is no longer held back, e.g. once it was
+    /// delivered or dropped
+    pub fn delete_ordered_message_by_nonce(&self, nonce: u32) -> DbResult<()> {
+        self.delete_keyed(ORDERED_MESSAGE_BY_NONCE, &nonce)
+    }
+
+    /// Retrieve all messages of this domain held back until they can be
+    /// delivered in order
+    pub fn retrieve_ordered_messages(&self) -> DbResult<Vec<OrderedMessage>> {
+        self.retrieve_decodables_with_prefix(ORDERED_MESSAGE_BY_NONCE)
+    }
+
+    /// Rewrite the values stored in a `ValueEnvelope` whose encoding is
+    /// outdated, so that they remain readable after support for their
+    /// encoding is dropped. Their previous encoding is kept until
+    /// `commit_reencoded_values`, so that `restore_legacy_values` can roll
+    /// the re-encoding back. Returns the number of rewritten values.
+    pub fn reencode_values(&self) -> DbResult<usize> {
+        Ok(
+            self.reencode_values_with_prefix::<PendingOperationStatus>(STATUS_BY_MESSAGE_ID)?
+                + self
+                    .reencode_values_with_prefix::<DeliveryReceipt>(DELIVERY_RECEIPT_BY_OFFSET)?,
+        )
+    }
+
+    /// Delete the previous encoding kept for re-encoded values. Returns the
+    /// number of deleted values.
+    pub fn commit_reencoded_values(&self) -> DbResult<usize> {
+        Ok(self.delete_legacy_values_with_prefix(STATUS_BY_MESSAGE_ID)?
+            + self.delete_legacy_values_with_prefix(DELIVERY_RECEIPT_BY_OFFSET)?)
+    }
+
+    /// Rewrite the values stored in a `ValueEnvelope` in the encoding that
+    /// predates envelopes, so that agents from before envelopes can read them.
+    /// Returns the number of rewritten values.
+    pub fn restore_legacy_values(&self) -> DbResult<usize> {
+        Ok(self
+            .restore_legacy_values_with_prefix::<PendingOperationStatus>(STATUS_BY_MESSAGE_ID)?
+            + self
+                .restore_legacy_values_with_prefix::<DeliveryReceipt>(DELIVERY_RECEIPT_BY_OFFSET)?)
+    }
+}
+
+impl MerkleTreeSnapshotStore for HyperlaneRocksDB {
+    type Error = DbError;
+
+    fn store_merkle_tree_snapshot(
+        &self,
+        index: u32,
+        leaves: &[H256],
+        incremental: &IncrementalMerkle,
+    ) -> DbResult<()> {
+        let mut batch = WriteBatch::default();
+        for (leaf_index, leaf) in (index..).zip(leaves) {
+            self.batch_store_keyed_encodable(
+                &mut batch,
+                MERKLE_TREE_SNAPSHOT_LEAF_BY_INDEX,
+                &leaf_index,
+                leaf,
+            );
+        }
+        self.batch_store_keyed_encodable(
+            &mut batch,
+            MERKLE_TREE_SNAPSHOT_INCREMENTAL,
+            &bool::default(),
+            incremental,
+        );
+        self.write_batch(batch)
+    }
+
+    fn retrieve_merkle_tree_snapshot_incremental(&self) -> DbResult<Option<IncrementalMerkle>> {
+        self.retrieve_value_by_key(MERKLE_TREE_SNAPSHOT_INCREMENTAL, &bool::default())
+    }
+
+    fn retrieve_merkle_tree_snapshot_leaf(&self, index: u32) -> DbResult<Option<H256>> {
+        self.retrieve_value_by_key(MERKLE_TREE_SNAPSHOT_LEAF_BY_INDEX, &index)
+    }
 }
 
 #[async_trait]
@@ -298,6 +664,24 @@ impl HyperlaneLogStore<HyperlaneMessage> for HyperlaneRocksDB {
         }
         Ok(stored)
     }
+
+    /// Remove reorged messages by their nonce
+    async fn invalidate_logs(
+        &self,
+        messages: &[(Indexed<HyperlaneMessage>, LogMeta)],
+    ) -> Result<u32> {
+        let removed =
+            self.invalidate_messages(messages.iter().map(|(message, _)| message.inner().nonce))?;
+        Ok(removed)
+    }
+
+    async fn store_indexed_blocks(&self, blocks: &[IndexedBlock<HyperlaneMessage>]) -> Result<()> {
+        Ok(self.store_indexed_blocks_by_key(INDEXED_MESSAGE_BLOCKS, blocks)?)
+    }
+
+    async fn retrieve_indexed_blocks(&self) -> Result<Vec<IndexedBlock<HyperlaneMessage>>> {
+        Ok(self.retrieve_indexed_blocks_by_key(INDEXED_MESSAGE_BLOCKS)?)
+    }
 }
 
 async fn store_and_count_new<T: Copy>(
@@ -334,6 +718,25 @@ impl HyperlaneLogStore<InterchainGasPayment> for HyperlaneRocksDB {
         )
         .await
     }
+
+    /// Remove reorged gas payments by their metadata
+    async fn invalidate_logs(
+        &self,
+        payments: &[(Indexed<InterchainGasPayment>, LogMeta)],
+    ) -> Result<u32> {
+        Ok(self.invalidate_gas_payments(payments)?)
+    }
+
+    async fn store_indexed_blocks(
+        &self,
+        blocks: &[IndexedBlock<InterchainGasPayment>],
+    ) -> Result<()> {
+        Ok(self.store_indexed_blocks_by_key(INDEXED_GAS_PAYMENT_BLOCKS, blocks)?)
+    }
+
+    async fn retrieve_indexed_blocks(&self) -> Result<Vec<IndexedBlock<InterchainGasPayment>>> {
+        Ok(self.retrieve_indexed_blocks_by_key(INDEXED_GAS_PAYMENT_BLOCKS)?)
+    }
 }
 
 #[async_trait]
@@ -349,6 +752,30 @@ impl HyperlaneLogStore<MerkleTreeInsertion> for HyperlaneRocksDB {
         }
         Ok(insertions)
     }
+
+    /// Remove reorged tree insertions by their leaf index
+    async fn invalidate_logs(
+        &self,
+        leaves: &[(Indexed<MerkleTreeInsertion>, LogMeta)],
+    ) -> Result<u32> {
+        let removed = self.invalidate_tree_insertions(
+            leaves
+                .iter()
+                .map(|(insertion, _)| insertion.inner().index()),
+        )?;
+        Ok(removed)
+    }
+
+    async fn store_indexed_blocks(
+        &self,
+        blocks: &[IndexedBlock<MerkleTreeInsertion>],
+    ) -> Result<()> {
+        Ok(self.store_indexed_blocks_by_key(INDEXED_TREE_INSERTION_BLOCKS, blocks)?)
+    }
+
+    async fn retrieve_indexed_blocks(&self) -> Result<Vec<IndexedBlock<MerkleTreeInsertion>>> {
+        Ok(self.retrieve_indexed_blocks_by_key(INDEXED_TREE_INSERTION_BLOCKS)?)
+    }
 }
 
 #[async_trait]
@@ -451,6 +878,17 @@ impl HyperlaneDb for HyperlaneRocksDB {
         self.domain()
     }
 
+    fn retrieve_message_reorg_count(&self) -> DbResult<Option<u32>> {
+        self.retrieve_value_by_key(MESSAGE_REORG_COUNT, &bool::default())
+    }
+
+    fn retrieve_lowest_reorged_nonce_by_reorg_index(
+        &self,
+        reorg_index: &u32,
+    ) -> DbResult<Option<u32>> {
+        self.retrieve_value_by_key(LOWEST_REORGED_NONCE_BY_REORG_INDEX, reorg_index)
+    }
+
     fn store_message_id_by_nonce(&self, nonce: &u32, id: &H256) -> DbResult<()> {
         self.store_value_by_key(MESSAGE_ID, nonce, id)
     }
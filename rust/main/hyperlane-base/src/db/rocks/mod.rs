@@ -1,7 +1,7 @@
 use std::{path::Path, sync::Arc};
 
 use super::error::DbError;
-use rocksdb::{Options, DB as Rocks};
+use rocksdb::{Options, WriteBatch, DB as Rocks};
 use tracing::info;
 
 pub use hyperlane_db::*;
@@ -74,4 +74,29 @@ impl DB {
     pub fn retrieve(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         Ok(self.0.get(key)?)
     }
+
+    /// Delete a value from the DB
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        Ok(self.0.delete(key)?)
+    }
+
+    /// Apply the writes of `batch` atomically
+    pub fn write(&self, batch: WriteBatch) -> Result<()> {
+        Ok(self.0.write(batch)?)
+    }
+
+    /// The entries whose key starts with `prefix`, in key order
+    pub fn prefix_entries<'a>(
+        &'a self,
+        prefix: &'a [u8],
+    ) -> impl Iterator<Item = Result<(Box<[u8]>, Box<[u8]>)>> + 'a {
+        self.0
+            .prefix_iterator(prefix)
+            .take_while(move |entry| {
+                entry
+                    .as_ref()
+                    .map_or(true, |(key, _)| key.starts_with(prefix))
+            })
+            .map(|entry| entry.map_err(Into::into))
+    }
 }
@@ -31,11 +31,12 @@ where
 #[cfg(test)]
 mod test {
     use hyperlane_core::{
-        HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, Indexed, LogMeta,
-        RawHyperlaneMessage, H256, H512, U256,
+        Encode, HyperlaneDomain, HyperlaneLogStore, HyperlaneMessage, Indexed, IndexedBlock,
+        InterchainGasPayment, LogMeta, MerkleTreeInsertion, PendingOperationStatus,
+        RawHyperlaneMessage, ReprepareReason, ValueEnvelope, H256, H512, U256,
     };
 
-    use crate::db::HyperlaneRocksDB;
+    use crate::db::{DeadLetter, DeadLetterReason, HyperlaneDb, HyperlaneRocksDB};
 
     use super::*;
 
@@ -77,4 +78,256 @@ mod test {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn db_invalidates_reorged_messages() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_invalidates_reorged_messages"),
+                db,
+            );
+            let message = |nonce| HyperlaneMessage {
+                nonce,
+                ..Default::default()
+            };
+            for nonce in 0..3 {
+                db.store_message(&message(nonce), 10 + nonce as u64).unwrap();
+                db.store_processed_by_nonce(&nonce, &true).unwrap();
+            }
+
+            let reorged = [1, 2]
+                .map(|nonce| (Indexed::new(message(nonce)), LogMeta::default()))
+                .to_vec();
+            assert_eq!(db.invalidate_logs(&reorged).await.unwrap(), 2);
+            assert!(db.retrieve_message_by_nonce(1).unwrap().is_none());
+            assert!(db.retrieve_dispatched_block_number_by_nonce(&2).unwrap().is_none());
+            assert_eq!(db.retrieve_highest_seen_message_nonce().unwrap(), Some(0));
+            assert_eq!(db.retrieve_processed_by_nonce(&1).unwrap(), None);
+            assert_eq!(db.retrieve_processed_by_nonce(&0).unwrap(), Some(true));
+            assert_eq!(db.retrieve_message_reorg_count().unwrap(), Some(1));
+            assert_eq!(
+                db.retrieve_lowest_reorged_nonce_by_reorg_index(&0).unwrap(),
+                Some(1)
+            );
+
+            // The message of the canonical chain is stored at the reorged nonce
+            let canonical = HyperlaneMessage {
+                body: vec![1],
+                ..message(1)
+            };
+            assert!(db.store_message(&canonical, 12).unwrap());
+            assert_eq!(db.retrieve_message_by_nonce(1).unwrap(), Some(canonical));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_invalidates_reorged_gas_payments() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_invalidates_reorged_gas_payments"),
+                db,
+            );
+            let payment = |amount: u64, log_index: u64| {
+                (
+                    Indexed::new(InterchainGasPayment {
+                        message_id: H256::from_low_u64_be(1),
+                        destination: 2,
+                        payment: U256::from(amount),
+                        gas_amount: U256::from(amount * 10),
+                    }),
+                    LogMeta {
+                        transaction_id: H512::from_low_u64_be(log_index),
+                        log_index: U256::from(log_index),
+                        ..Default::default()
+                    },
+                )
+            };
+            let payments = vec![payment(5, 0), payment(7, 1)];
+            assert_eq!(db.store_logs(&payments).await.unwrap(), 2);
+
+            let gas_payment_key = (*payments[0].0.inner()).into();
+            assert_eq!(db.invalidate_logs(&payments[1..]).await.unwrap(), 1);
+            let total = db
+                .retrieve_gas_payment_by_gas_payment_key(gas_payment_key)
+                .unwrap()
+                .unwrap();
+            assert_eq!(total.payment, U256::from(5));
+            assert_eq!(total.gas_amount, U256::from(50));
+            // Already removed
+            assert_eq!(db.invalidate_logs(&payments[1..]).await.unwrap(), 0);
+
+            // The payment of the canonical chain is counted
+            assert_eq!(db.store_logs(&[payment(3, 1)]).await.unwrap(), 1);
+            let total = db
+                .retrieve_gas_payment_by_gas_payment_key(gas_payment_key)
+                .unwrap()
+                .unwrap();
+            assert_eq!(total.payment, U256::from(8));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_stores_indexed_blocks() {
+        run_test_db(|db| async move {
+            let db = HyperlaneRocksDB::new(
+                &HyperlaneDomain::new_test_domain("db_stores_indexed_blocks"),
+                db,
+            );
+            let block = IndexedBlock {
+                number: 10,
+                hash: H256::from_low_u64_be(10),
+                logs: vec![(
+                    Indexed::new(HyperlaneMessage::default()).with_sequence(0),
+                    LogMeta {
+                        block_number: 10,
+                        block_hash: H256::from_low_u64_be(10),
+                        ..Default::default()
+                    },
+                )],
+            };
+            assert!(
+                HyperlaneLogStore::<HyperlaneMessage>::retrieve_indexed_blocks(&db)
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+            db.store_indexed_blocks(&[block.clone()]).await.unwrap();
+            assert_eq!(
+                HyperlaneLogStore::<HyperlaneMessage>::retrieve_indexed_blocks(&db)
+                    .await
+                    .unwrap(),
+                vec![block]
+            );
+            // Blocks of other logs are stored separately
+            assert!(
+                HyperlaneLogStore::<MerkleTreeInsertion>::retrieve_indexed_blocks(&db)
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_reencodes_legacy_values() {
+        run_test_db(|raw_db| async move {
+            let db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::new_test_domain("test"), raw_db.clone());
+            let status = PendingOperationStatus::Retry(ReprepareReason::CouldNotFetchMetadata);
+            let legacy_id = H256::from_low_u64_be(1);
+            let current_id = H256::from_low_u64_be(2);
+
+            // A status stored as plain JSON, before value envelopes
+            let legacy_key = [
+                b"test_status_by_message_id_".as_slice(),
+                legacy_id.as_bytes(),
+            ]
+            .concat();
+            raw_db
+                .store(&legacy_key, &serde_json::to_vec(&status).unwrap())
+                .unwrap();
+            db.store_status_by_message_id(&current_id, &status).unwrap();
+            // A value that can't be decoded doesn't stop the others from being
+            // re-encoded
+            let bad_key = [
+                b"test_status_by_message_id_".as_slice(),
+                H256::from_low_u64_be(3).as_bytes(),
+            ]
+            .concat();
+            raw_db.store(&bad_key, b"not a status").unwrap();
+
+            assert_eq!(db.reencode_values().unwrap(), 1);
+            let reencoded = raw_db.retrieve(&legacy_key).unwrap().unwrap();
+            assert!(ValueEnvelope::open(&reencoded).unwrap().is_some());
+            assert_eq!(reencoded, status.to_vec());
+            assert_eq!(
+                db.retrieve_status_by_message_id(&legacy_id).unwrap(),
+                Some(status.clone())
+            );
+            assert_eq!(
+                raw_db.retrieve(&bad_key).unwrap().unwrap(),
+                b"not a status".to_vec()
+            );
+
+            assert_eq!(db.reencode_values().unwrap(), 0);
+
+            // Rolling back rewrites all values as plain JSON
+            let legacy_json = serde_json::to_vec(&status).unwrap();
+            assert_eq!(db.restore_legacy_values().unwrap(), 2);
+            assert_eq!(
+                raw_db.retrieve(&legacy_key).unwrap(),
+                Some(legacy_json.clone())
+            );
+            let current_key = [
+                b"test_status_by_message_id_".as_slice(),
+                current_id.as_bytes(),
+            ]
+            .concat();
+            assert_eq!(raw_db.retrieve(&current_key).unwrap(), Some(legacy_json));
+            assert_eq!(db.restore_legacy_values().unwrap(), 0);
+
+            // Committing deletes the previous encoding of re-encoded values
+            assert_eq!(db.reencode_values().unwrap(), 2);
+            assert_eq!(db.commit_reencoded_values().unwrap(), 2);
+            assert_eq!(db.commit_reencoded_values().unwrap(), 0);
+            assert_eq!(
+                db.retrieve_status_by_message_id(&current_id).unwrap(),
+                Some(status)
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn db_stores_lists_and_deletes_dead_letters() {
+        run_test_db(|raw_db| async move {
+            let db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::new_test_domain("test"), raw_db.clone());
+            let other_db =
+                HyperlaneRocksDB::new(&HyperlaneDomain::new_test_domain("other"), raw_db);
+            let dead_letter = |nonce| {
+                let message = HyperlaneMessage {
+                    nonce,
+                    ..Default::default()
+                };
+                DeadLetter {
+                    message_id: message.id(),
+                    origin: message.origin,
+                    destination: message.destination,
+                    nonce,
+                    message,
+                    reason: DeadLetterReason::RetriesExhausted,
+                    last_status: "FirstPrepareAttempt".to_owned(),
+                    num_retries: 10,
+                    timestamp: 0,
+                }
+            };
+            let (first, second) = (dead_letter(1), dead_letter(2));
+            db.store_dead_letter(&first).unwrap();
+            db.store_dead_letter(&second).unwrap();
+            other_db.store_dead_letter(&dead_letter(3)).unwrap();
+
+            let mut dead_letters = db.retrieve_dead_letters().unwrap();
+            dead_letters.sort_by_key(|dead_letter| dead_letter.nonce);
+            assert_eq!(dead_letters, vec![first.clone(), second.clone()]);
+            assert_eq!(
+                db.retrieve_dead_letter_by_message_id(&first.message_id)
+                    .unwrap(),
+                Some(first.clone())
+            );
+
+            db.delete_dead_letter_by_message_id(&first.message_id)
+                .unwrap();
+            assert_eq!(db.retrieve_dead_letters().unwrap(), vec![second]);
+            assert_eq!(
+                db.retrieve_dead_letter_by_message_id(&first.message_id)
+                    .unwrap(),
+                None
+            );
+        })
+        .await;
+    }
 }
@@ -1,9 +1,16 @@
-use hyperlane_core::{Decode, Encode, HyperlaneDomain};
+use hyperlane_core::{Decode, Encode, HyperlaneDomain, ValueEnvelope};
+use rocksdb::WriteBatch;
+use serde::Serialize;
+use tracing::warn;
 
 use crate::db::{error::DbError, DB};
 
 type Result<T> = std::result::Result<T, DbError>;
 
+/// Prefix of the keys that the previous encoding of re-encoded values is kept
+/// under, in front of the value's own key, until the re-encoding is committed
+const LEGACY_VALUE_PREFIX: &[u8] = b"legacy_value_";
+
 /// DB handle for storing data tied to a specific type/entity.
 ///
 /// Key structure: ```<domain_prefix>_<additional_prefix(es)>_<key>```
@@ -77,6 +84,169 @@ impl TypedDB {
         self.store_encodable(prefix, key.to_vec(), value)
     }
 
+    /// Delete the value of an encodable key
+    pub fn delete_keyed<K: Encode>(&self, prefix: impl AsRef<[u8]>, key: &K) -> Result<()> {
+        self.db
+            .delete(&self.prefixed_key(prefix.as_ref(), &key.to_vec()))
+    }
+
+    /// Add storing an encodable kv pair to `batch`
+    pub fn batch_store_keyed_encodable<K: Encode, V: Encode>(
+        &self,
+        batch: &mut WriteBatch,
+        prefix: impl AsRef<[u8]>,
+        key: &K,
+        value: &V,
+    ) {
+        batch.put(
+            self.prefixed_key(prefix.as_ref(), &key.to_vec()),
+            value.to_vec(),
+        );
+    }
+
+    /// Add deleting the value of an encodable key to `batch`
+    pub fn batch_delete_keyed<K: Encode>(
+        &self,
+        batch: &mut WriteBatch,
+        prefix: impl AsRef<[u8]>,
+        key: &K,
+    ) {
+        batch.delete(self.prefixed_key(prefix.as_ref(), &key.to_vec()));
+    }
+
+    /// Apply the writes of `batch` atomically
+    pub fn write_batch(&self, batch: WriteBatch) -> Result<()> {
+        self.db.write(batch)
+    }
+
+    /// The key the previous encoding of the value at `key` is kept under
+    fn legacy_value_key(&self, key: &[u8]) -> Vec<u8> {
+        let unprefixed = key
+            .strip_prefix(self.domain_prefix.as_slice())
+            .unwrap_or(key);
+        self.prefixed_key(LEGACY_VALUE_PREFIX, unprefixed)
+    }
+
+    /// Rewrite the values under `prefix` whose encoding differs from the
+    /// current encoding of their type, e.g. values that predate a
+    /// `ValueEnvelope` or were written with another codec. Returns the number
+    /// of rewritten values.
+    ///
+    /// The previous encoding of each rewritten value is kept until the
+    /// re-encoding is committed with `delete_legacy_values_with_prefix`, so
+    /// that it can be rolled back with `restore_legacy_values_with_prefix`.
+    /// Values that can't be decoded are logged and left as is. Values written
+    /// since they were read by the iterator are left as is too, as they are
+    /// already in the current encoding.
+    pub fn reencode_values_with_prefix<V: Encode + Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+    ) -> Result<usize> {
+        let prefix = self.prefixed_key(prefix.as_ref(), &[]);
+        let mut reencoded = 0;
+        for entry in self.db.prefix_entries(&prefix) {
+            let (key, value) = entry?;
+            let encoded = match V::read_from(&mut value.as_ref()) {
+                Ok(decoded) => decoded.to_vec(),
+                Err(err) => {
+                    warn!(?err, ?key, "Skipping value that can't be decoded");
+                    continue;
+                }
+            };
+            if encoded.as_slice() == value.as_ref() {
+                continue;
+            }
+            if self.db.retrieve(&key)?.as_deref() == Some(value.as_ref()) {
+                let mut batch = WriteBatch::default();
+                batch.put(self.legacy_value_key(&key), &value);
+                batch.put(&key, encoded);
+                self.db.write(batch)?;
+                reencoded += 1;
+            }
+        }
+        Ok(reencoded)
+    }
+
+    /// Rewrite the values under `prefix` in the plain JSON encoding that
+    /// predates `ValueEnvelope`s, so that versions from before the envelopes
+    /// can read them, rolling back `reencode_values_with_prefix`. Values left
+    /// unchanged since they were re-encoded get back their exact previous
+    /// encoding. Returns the number of rewritten values.
+    ///
+    /// Values that can't be decoded are logged and left as is.
+    pub fn restore_legacy_values_with_prefix<V: Encode + Decode + Serialize>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+    ) -> Result<usize> {
+        let prefix = self.prefixed_key(prefix.as_ref(), &[]);
+        let mut restored = 0;
+        for entry in self.db.prefix_entries(&prefix) {
+            let (key, value) = entry?;
+            let legacy_key = self.legacy_value_key(&key);
+            let legacy_value = self.db.retrieve(&legacy_key)?;
+            let unchanged_since_reencoding = legacy_value.as_ref().is_some_and(|legacy_value| {
+                V::read_from(&mut legacy_value.as_slice())
+                    .is_ok_and(|decoded| decoded.to_vec().as_slice() == value.as_ref())
+            });
+            let restored_value = match legacy_value {
+                Some(legacy_value) if unchanged_since_reencoding => legacy_value,
+                _ => {
+                    if !matches!(ValueEnvelope::open(&value), Ok(Some(_))) {
+                        continue;
+                    }
+                    let legacy_value = V::read_from(&mut value.as_ref())
+                        .map_err(|err| err.to_string())
+                        .and_then(|decoded| {
+                            serde_json::to_vec(&decoded).map_err(|err| err.to_string())
+                        });
+                    match legacy_value {
+                        Ok(legacy_value) => legacy_value,
+                        Err(err) => {
+                            warn!(err, ?key, "Skipping value that can't be restored");
+                            continue;
+                        }
+                    }
+                }
+            };
+            let mut batch = WriteBatch::default();
+            batch.put(&key, restored_value);
+            batch.delete(legacy_key);
+            self.db.write(batch)?;
+            restored += 1;
+        }
+        Ok(restored)
+    }
+
+    /// Delete the previous encoding kept for the values under `prefix` that
+    /// were re-encoded, committing the re-encoding. Returns the number of
+    /// deleted values.
+    pub fn delete_legacy_values_with_prefix(&self, prefix: impl AsRef<[u8]>) -> Result<usize> {
+        let legacy_prefix = self.legacy_value_key(&self.prefixed_key(prefix.as_ref(), &[]));
+        let mut batch = WriteBatch::default();
+        for entry in self.db.prefix_entries(&legacy_prefix) {
+            let (key, _) = entry?;
+            batch.delete(key);
+        }
+        let deleted = batch.len();
+        self.db.write(batch)?;
+        Ok(deleted)
+    }
+
+    /// Retrieve the decodable values under `prefix`, in key order
+    pub fn retrieve_decodables_with_prefix<V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+    ) -> Result<Vec<V>> {
+        let prefix = self.prefixed_key(prefix.as_ref(), &[]);
+        self.db
+            .prefix_entries(&prefix)
+            .map(|entry| {
+                let (_, value) = entry?;
+                Ok(V::read_from(&mut value.as_ref())?)
+            })
+            .collect()
+    }
+
     /// Retrieve decodable value given encodable key
     pub fn retrieve_keyed_decodable<K: Encode, V: Decode>(
         &self,
@@ -1,9 +1,10 @@
 use std::io::{Read, Write};
 
 use hyperlane_core::{
-    Decode, Encode, HyperlaneProtocolError, InterchainGasExpenditure, InterchainGasPayment, H256,
-    U256,
+    Decode, Encode, HyperlaneMessage, HyperlaneProtocolError, IndexedBlock,
+    InterchainGasExpenditure, InterchainGasPayment, ValueCodec, ValueEnvelope, H256, H512, U256,
 };
+use serde::{Deserialize, Serialize};
 
 /// Subset of `InterchainGasPayment` excluding the message id which is stored in
 /// the key.
@@ -126,3 +127,312 @@ impl Decode for InterchainGasExpenditureData {
         })
     }
 }
+
+/// A durable record of a message delivered by the relayer, exported to
+/// downstream systems such as settlement and reconciliation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryReceipt {
+    /// The id of the delivered message
+    pub message_id: H256,
+    /// The origin domain of the message
+    pub origin: u32,
+    /// The destination domain of the message
+    pub destination: u32,
+    /// The nonce of the message
+    pub nonce: u32,
+    /// The hash of the destination transaction that delivered the message
+    pub tx_hash: H512,
+    /// The destination block the transaction was included in, if known
+    pub block_number: Option<u64>,
+    /// The gas used to deliver the message
+    #[serde(with = "decimal_u256")]
+    pub gas_used: U256,
+    /// The cost of the gas used, in the smallest unit of the destination's
+    /// native token
+    #[serde(with = "decimal_u256")]
+    pub effective_gas_cost: U256,
+    /// Unix timestamp in seconds at which the relayer confirmed the delivery
+    pub timestamp: u64,
+}
+
+// Encoded as JSON, so that fields can be added without a migration
+impl Encode for DeliveryReceipt {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        ValueEnvelope::write_to(self, ValueCodec::Json, writer)
+    }
+}
+
+impl Decode for DeliveryReceipt {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        ValueEnvelope::read_from(reader)
+    }
+}
+
+/// Why a message was moved to the dead-letter queue
+/// WARNING: This enum is serialized with serde and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
+/// Adding new variants is fine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeadLetterReason {
+    /// The message was retried more often than the relayer's
+    /// `deadLetterAfterRetries`
+    RetriesExhausted,
+    /// The message was retried more often than the max retries of its
+    /// destination's gas escalation policy
+    GasEscalationRetriesExhausted,
+    /// The message was dispatched longer ago than the max age of the
+    /// relayer's delivery TTL policy matching it
+    MaxAgeExceeded,
+    /// The deadline encoded in the message body passed
+    DeadlinePassed,
+    /// An operator dropped the message from the prepare queue through the
+    /// relayer's admin API
+    DroppedOnRequest,
+}
+
+/// A message the relayer gave up on delivering, kept in the database of its
+/// origin so that operators can list, export and re-inject it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetter {
+    /// The id of the message
+    pub message_id: H256,
+    /// The origin domain of the message
+    pub origin: u32,
+    /// The destination domain of the message
+    pub destination: u32,
+    /// The nonce of the message
+    pub nonce: u32,
+    /// The message itself, so that dead letters can be re-injected after
+    /// being imported into another relayer's database
+    #[serde(with = "hex_message")]
+    pub message: HyperlaneMessage,
+    /// Why the message was moved to the dead-letter queue
+    pub reason: DeadLetterReason,
+    /// The status of the message before it was moved to the dead-letter
+    /// queue, usually the reason of its last failed attempt
+    pub last_status: String,
+    /// How often delivering the message was retried
+    pub num_retries: u32,
+    /// Unix timestamp in seconds at which the message was moved to the
+    /// dead-letter queue
+    pub timestamp: u64,
+}
+
+impl DeadLetter {
+    /// Whether the redundant fields of the dead letter agree with its
+    /// message, e.g. before importing a dead letter exported elsewhere
+    pub fn is_consistent(&self) -> bool {
+        self.message_id == self.message.id()
+            && self.origin == self.message.origin
+            && self.destination == self.message.destination
+            && self.nonce == self.message.nonce
+    }
+}
+
+// Encoded as JSON, so that fields can be added without a migration
+impl Encode for DeadLetter {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        ValueEnvelope::write_to(self, ValueCodec::Json, writer)
+    }
+}
+
+impl Decode for DeadLetter {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        ValueEnvelope::read_from(reader)
+    }
+}
+
+/// A message the relayer holds back until it can be delivered in order with
+/// the other messages sharing its ordering key, kept in the database of its
+/// origin so that the order survives restarts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedMessage {
+    /// The nonce of the message
+    pub nonce: u32,
+    /// Index of the ordering key config the key was derived with
+    pub rule: u32,
+    /// The value of the ordering key
+    pub key: Vec<u8>,
+    /// Whether the message was released to the submitter, i.e. holds its key
+    pub in_flight: bool,
+}
+
+// Encoded as JSON, so that fields can be added without a migration
+impl Encode for OrderedMessage {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        ValueEnvelope::write_to(self, ValueCodec::Json, writer)
+    }
+}
+
+impl Decode for OrderedMessage {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        ValueEnvelope::read_from(reader)
+    }
+}
+
+/// The most recent blocks a contract sync indexed logs from, in ascending
+/// order of height
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IndexedBlocks<T>(pub Vec<IndexedBlock<T>>);
+
+impl<T: Encode> Encode for IndexedBlocks<T> {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: Write,
+    {
+        let count = u32::try_from(self.0.len()).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "Too many indexed blocks")
+        })?;
+        let mut written = count.write_to(writer)?;
+        for block in &self.0 {
+            written += block.write_to(writer)?;
+        }
+        Ok(written)
+    }
+}
+
+impl<T: Decode> Decode for IndexedBlocks<T> {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: Read,
+        Self: Sized,
+    {
+        let count = u32::read_from(reader)?;
+        let mut blocks = vec![];
+        for _ in 0..count {
+            blocks.push(IndexedBlock::read_from(reader)?);
+        }
+        Ok(Self(blocks))
+    }
+}
+
+/// Messages are serialized as their hex encoded bytes, as the message format
+/// is what operators pass around when escalating undelivered messages.
+mod hex_message {
+    use ethers::utils::hex;
+    use hyperlane_core::{Decode, Encode, HyperlaneMessage};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        message: &HyperlaneMessage,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(message.to_vec())))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HyperlaneMessage, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        let bytes = hex::decode(value.trim_start_matches("0x")).map_err(D::Error::custom)?;
+        HyperlaneMessage::read_from(&mut bytes.as_slice()).map_err(D::Error::custom)
+    }
+}
+
+/// Amounts are serialized as decimal strings, as consumers of delivery
+/// receipts are rarely able to parse hex encoded 256 bit integers.
+mod decimal_u256 {
+    use hyperlane_core::U256;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        U256::from_dec_str(&value).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_delivery_receipt_encoding() {
+        let receipt = DeliveryReceipt {
+            message_id: H256::random(),
+            origin: 1,
+            destination: 2,
+            nonce: 3,
+            tx_hash: H512::random(),
+            block_number: Some(4),
+            gas_used: U256::from(150_000),
+            effective_gas_cost: U256::exp10(24),
+            timestamp: 1_700_000_000,
+        };
+
+        let decoded = DeliveryReceipt::read_from(&mut receipt.to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, receipt);
+
+        let json = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(json["gasUsed"], "150000");
+        assert_eq!(json["effectiveGasCost"], "1000000000000000000000000");
+        assert_eq!(json["blockNumber"], 4);
+    }
+
+    #[test]
+    fn test_dead_letter_encoding() {
+        let message = HyperlaneMessage {
+            nonce: 3,
+            origin: 1,
+            destination: 2,
+            body: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let dead_letter = DeadLetter {
+            message_id: message.id(),
+            origin: 1,
+            destination: 2,
+            nonce: 3,
+            message: message.clone(),
+            reason: DeadLetterReason::RetriesExhausted,
+            last_status: "Retry(Error estimating costs for process call)".to_owned(),
+            num_retries: 50,
+            timestamp: 1_700_000_000,
+        };
+        assert!(dead_letter.is_consistent());
+
+        let decoded = DeadLetter::read_from(&mut dead_letter.to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, dead_letter);
+
+        let json = serde_json::to_value(&dead_letter).unwrap();
+        assert_eq!(json["reason"], "RetriesExhausted");
+        assert_eq!(
+            json["message"],
+            format!("0x{}", ethers::utils::hex::encode(message.to_vec()))
+        );
+        let exported: DeadLetter = serde_json::from_value(json).unwrap();
+        assert_eq!(exported, dead_letter);
+
+        let tampered = DeadLetter {
+            nonce: 4,
+            ..dead_letter
+        };
+        assert!(!tampered.is_consistent());
+    }
+}
@@ -8,6 +8,8 @@
 
 pub mod settings;
 
+pub mod cli;
+
 /// Base trait for an agent
 mod agent;
 pub use agent::*;
@@ -27,6 +29,9 @@ pub use server::*;
 mod contract_sync;
 pub use contract_sync::*;
 
+mod chain_init;
+pub use chain_init::*;
+
 mod traits;
 pub use traits::*;
 
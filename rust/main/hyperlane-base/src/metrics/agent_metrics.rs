@@ -8,6 +8,7 @@ use eyre::Result;
 use hyperlane_core::metrics::agent::decimals_by_protocol;
 use hyperlane_core::metrics::agent::u256_as_scaled_f64;
 use hyperlane_core::metrics::agent::METRICS_SCRAPE_INTERVAL;
+use hyperlane_core::metrics::definitions;
 use hyperlane_core::HyperlaneDomain;
 use hyperlane_core::HyperlaneProvider;
 use maplit::hashmap;
@@ -21,34 +22,24 @@ use crate::settings::ChainConf;
 use crate::CoreMetrics;
 
 /// Expected label names for the `wallet_balance` metric.
-pub const WALLET_BALANCE_LABELS: &[&str] = &[
-    "chain",
-    "wallet_address",
-    "wallet_name",
-    "token_address",
-    "token_symbol",
-    "token_name",
-];
+pub const WALLET_BALANCE_LABELS: &[&str] = definitions::WALLET_BALANCE.labels;
 /// Help string for the metric.
-pub const WALLET_BALANCE_HELP: &str =
-    "Current native token balance for the wallet addresses in the `wallets` set";
+pub const WALLET_BALANCE_HELP: &str = definitions::WALLET_BALANCE.help;
 
 /// Expected label names for the `block_height` metric.
-pub const BLOCK_HEIGHT_LABELS: &[&str] = &["chain"];
+pub const BLOCK_HEIGHT_LABELS: &[&str] = definitions::BLOCK_HEIGHT.labels;
 /// Help string for the metric.
-pub const BLOCK_HEIGHT_HELP: &str = "Tracks the current block height of the chain";
+pub const BLOCK_HEIGHT_HELP: &str = definitions::BLOCK_HEIGHT.help;
 
 /// Expected label names for the `gas_price` metric.
-pub const GAS_PRICE_LABELS: &[&str] = &["chain"];
+pub const GAS_PRICE_LABELS: &[&str] = definitions::GAS_PRICE.labels;
 /// Help string for the metric.
-pub const GAS_PRICE_HELP: &str =
-    "Tracks the current gas price of the chain, in the lowest denomination (e.g. wei)";
+pub const GAS_PRICE_HELP: &str = definitions::GAS_PRICE.help;
 
 /// Expected label names for the `critical_error` metric.
-pub const CRITICAL_ERROR_LABELS: &[&str] = &["chain"];
+pub const CRITICAL_ERROR_LABELS: &[&str] = definitions::CRITICAL_ERROR.labels;
 /// Help string for the metric.
-pub const CRITICAL_ERROR_HELP: &str =
-    "Boolean marker for critical errors on a chain, signalling loss of liveness";
+pub const CRITICAL_ERROR_HELP: &str = definitions::CRITICAL_ERROR.help;
 
 /// Agent-specific metrics
 #[derive(Clone, Debug)]
@@ -68,14 +59,41 @@ pub struct AgentMetrics {
 impl AgentMetrics {
     pub(crate) fn new(metrics: &CoreMetrics) -> Result<AgentMetrics> {
         let agent_metrics = AgentMetrics {
-            wallet_balance: Some(metrics.new_gauge(
-                "wallet_balance",
-                WALLET_BALANCE_HELP,
-                WALLET_BALANCE_LABELS,
-            )?),
+            wallet_balance: Some(metrics.new_gauge(&definitions::WALLET_BALANCE)?),
         };
         Ok(agent_metrics)
     }
+
+    /// These metrics without the balances of wallets, for when they're
+    /// exported by a [`crate::WalletBalanceWatcher`] instead
+    pub(crate) fn without_wallet_balances(self) -> Self {
+        Self {
+            wallet_balance: None,
+        }
+    }
+
+    /// Set the native token balance of a wallet, in units of the native token
+    pub(crate) fn set_wallet_balance(
+        &self,
+        chain: &str,
+        wallet_address: &str,
+        wallet_name: &str,
+        balance: f64,
+    ) {
+        if let Some(wallet_balance) = &self.wallet_balance {
+            wallet_balance
+                .with(&hashmap! {
+                    "chain" => chain,
+                    "wallet_address" => wallet_address,
+                    "wallet_name" => wallet_name,
+                    "token_address" => "none",
+                    // Note: Whatever this `chain`'s native currency is
+                    "token_symbol" => "Native",
+                    "token_name" => "Native"
+                })
+                .set(balance);
+        }
+    }
 }
 
 /// Chain-specific metrics
@@ -99,11 +117,9 @@ pub struct ChainMetrics {
 
 impl ChainMetrics {
     pub(crate) fn new(metrics: &CoreMetrics) -> Result<ChainMetrics> {
-        let block_height_metrics =
-            metrics.new_int_gauge("block_height", BLOCK_HEIGHT_HELP, BLOCK_HEIGHT_LABELS)?;
-        let gas_price_metrics = metrics.new_gauge("gas_price", GAS_PRICE_HELP, GAS_PRICE_LABELS)?;
-        let critical_error_metrics =
-            metrics.new_int_gauge("critical_error", CRITICAL_ERROR_HELP, CRITICAL_ERROR_LABELS)?;
+        let block_height_metrics = metrics.new_int_gauge(&definitions::BLOCK_HEIGHT)?;
+        let gas_price_metrics = metrics.new_gauge(&definitions::GAS_PRICE)?;
+        let critical_error_metrics = metrics.new_int_gauge(&definitions::CRITICAL_ERROR)?;
         let chain_metrics = ChainMetrics {
             block_height: block_height_metrics,
             gas_price: Some(gas_price_metrics),
@@ -181,25 +197,14 @@ impl MetricsUpdater {
             return;
         };
         let wallet_name = self.conf.name.clone();
-        let Some(wallet_balance_metric) = self.agent_metrics.wallet_balance.clone() else {
-            return;
-        };
         let chain = self.conf.domain.name();
 
         match self.provider.get_balance(wallet_addr.clone()).await {
             Ok(balance) => {
                 let balance = u256_as_scaled_f64(balance, self.conf.domain.domain_protocol());
                 trace!("Wallet {wallet_name} ({wallet_addr}) on chain {chain} balance is {balance} of the native currency");
-                wallet_balance_metric
-                .with(&hashmap! {
-                    "chain" => chain,
-                    "wallet_address" => wallet_addr.as_str(),
-                    "wallet_name" => wallet_name.as_str(),
-                    "token_address" => "none",
-                    // Note: Whatever this `chain`'s native currency is
-                    "token_symbol" => "Native",
-                    "token_name" => "Native"
-                }).set(balance)
+                self.agent_metrics
+                    .set_wallet_balance(chain, &wallet_addr, &wallet_name, balance)
             },
             Err(e) => warn!("Metric update failed for wallet {wallet_name} ({wallet_addr}) on chain {chain} balance for native currency; {e}")
         }
@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 use std::time;
 
-use eyre::Result;
-use hyperlane_core::{HyperlaneDomain, H160};
+use eyre::{bail, Result};
+use hyperlane_core::metrics::definitions::{self, find_definition, MetricDefinition, MetricKind};
+use hyperlane_core::metrics::hsm::{HsmMetrics, HSM_OPERATION_LATENCY_BUCKETS};
+use hyperlane_core::{rpc_clients::RpcClientMetrics, HyperlaneDomain, H160};
 use prometheus::{
     histogram_opts, labels, opts, register_counter_vec_with_registry,
     register_gauge_vec_with_registry, register_histogram_vec_with_registry,
@@ -16,7 +18,8 @@ use tokio::sync::RwLock;
 use ethers_prometheus::{json_rpc_client::JsonRpcClientMetrics, middleware::MiddlewareMetrics};
 
 use crate::metrics::{
-    json_rpc_client::create_json_rpc_client_metrics, provider::create_provider_metrics,
+    json_rpc_client::{create_json_rpc_client_metrics, create_rpc_client_metrics},
+    provider::create_provider_metrics,
 };
 
 /// Macro to prefix a string with the namespace.
@@ -33,6 +36,8 @@ pub struct CoreMetrics {
     const_labels: HashMap<String, String>,
     listen_port: u16,
     agent_name: String,
+    /// Names of the metrics registered so far
+    registered_metrics: Mutex<HashSet<&'static str>>,
 
     span_durations: CounterVec,
     span_counts: IntCounterVec,
@@ -46,6 +51,9 @@ pub struct CoreMetrics {
 
     latest_checkpoint: IntGaugeVec,
 
+    /// Request metrics of the RPC clients of all chains, labeled by endpoint.
+    rpc_client_metrics: OnceLock<RpcClientMetrics>,
+
     /// Set of metrics that tightly wrap the JsonRpcClient for use with the
     /// quorum provider.
     json_rpc_client_metrics: OnceLock<JsonRpcClientMetrics>,
@@ -75,114 +83,126 @@ impl CoreMetrics {
 
         let span_durations = register_counter_vec_with_registry!(
             opts!(
-                namespaced!("span_duration_seconds"),
-                "Duration from tracing span creation to span destruction",
+                namespaced!(definitions::SPAN_DURATION_SECONDS.name),
+                definitions::SPAN_DURATION_SECONDS.help,
                 const_labels_ref
             ),
-            &["span_name", "span_target"],
+            definitions::SPAN_DURATION_SECONDS.labels,
             registry
         )?;
 
         let span_counts = register_int_counter_vec_with_registry!(
             opts!(
-                namespaced!("span_count"),
-                "Number of times a span was exited",
+                namespaced!(definitions::SPAN_COUNT.name),
+                definitions::SPAN_COUNT.help,
                 const_labels_ref
             ),
-            &["span_name", "span_target"],
+            definitions::SPAN_COUNT.labels,
             registry
         )?;
 
         let span_events = register_int_counter_vec_with_registry!(
             opts!(
-                namespaced!("span_events_total"),
-                "Number of span events (logs and time metrics) emitted by level",
+                namespaced!(definitions::SPAN_EVENTS_TOTAL.name),
+                definitions::SPAN_EVENTS_TOTAL.help,
                 const_labels_ref
             ),
-            &["event_level"],
+            definitions::SPAN_EVENTS_TOTAL.labels,
             registry
         )?;
 
         let last_known_message_nonce = register_int_gauge_vec_with_registry!(
             opts!(
-                namespaced!("last_known_message_nonce"),
-                "Last known message nonce",
+                namespaced!(definitions::LAST_KNOWN_MESSAGE_NONCE.name),
+                definitions::LAST_KNOWN_MESSAGE_NONCE.help,
                 const_labels_ref
             ),
-            &["phase", "origin", "remote"],
+            definitions::LAST_KNOWN_MESSAGE_NONCE.labels,
             registry
         )?;
 
         let latest_tree_insertion_index = register_int_gauge_vec_with_registry!(
             opts!(
-                namespaced!("latest_tree_insertion_index"),
-                "Latest leaf index inserted into the merkle tree",
+                namespaced!(definitions::LATEST_TREE_INSERTION_INDEX.name),
+                definitions::LATEST_TREE_INSERTION_INDEX.help,
                 const_labels_ref
             ),
-            &["origin"],
+            definitions::LATEST_TREE_INSERTION_INDEX.labels,
             registry
         )?;
 
         let observed_validator_latest_index = register_int_gauge_vec_with_registry!(
             opts!(
-                namespaced!("observed_validator_latest_index"),
-                "The latest observed latest signed checkpoint indices per validator, from the perspective of the relayer",
+                namespaced!(definitions::OBSERVED_VALIDATOR_LATEST_INDEX.name),
+                definitions::OBSERVED_VALIDATOR_LATEST_INDEX.help,
                 const_labels_ref
             ),
-            &[
-                "origin",
-                "destination",
-                "validator",
-                "app_context",
-            ],
+            definitions::OBSERVED_VALIDATOR_LATEST_INDEX.labels,
             registry
         )?;
 
         let submitter_queue_length = register_int_gauge_vec_with_registry!(
             opts!(
-                namespaced!("submitter_queue_length"),
-                "Submitter queue length",
+                namespaced!(definitions::SUBMITTER_QUEUE_LENGTH.name),
+                definitions::SUBMITTER_QUEUE_LENGTH.help,
                 const_labels_ref
             ),
-            &["remote", "queue_name", "operation_status", "app_context"],
+            definitions::SUBMITTER_QUEUE_LENGTH.labels,
             registry
         )?;
 
         let latest_checkpoint = register_int_gauge_vec_with_registry!(
             opts!(
-                namespaced!("latest_checkpoint"),
-                "Mailbox latest checkpoint",
+                namespaced!(definitions::LATEST_CHECKPOINT.name),
+                definitions::LATEST_CHECKPOINT.help,
                 const_labels_ref
             ),
-            &["phase", "chain"],
+            definitions::LATEST_CHECKPOINT.labels,
             registry
         )?;
 
         let operations_processed_count = register_int_counter_vec_with_registry!(
             opts!(
-                namespaced!("operations_processed_count"),
-                "Number of operations processed",
+                namespaced!(definitions::OPERATIONS_PROCESSED_COUNT.name),
+                definitions::OPERATIONS_PROCESSED_COUNT.help,
                 const_labels_ref
             ),
-            &["phase", "chain"],
+            definitions::OPERATIONS_PROCESSED_COUNT.labels,
             registry
         )?;
 
         let messages_processed_count = register_int_counter_vec_with_registry!(
             opts!(
-                namespaced!("messages_processed_count"),
-                "Number of messages processed",
+                namespaced!(definitions::MESSAGES_PROCESSED_COUNT.name),
+                definitions::MESSAGES_PROCESSED_COUNT.help,
                 const_labels_ref
             ),
-            &["origin", "remote"],
+            definitions::MESSAGES_PROCESSED_COUNT.labels,
             registry
         )?;
 
+        let registered_metrics = [
+            definitions::SPAN_DURATION_SECONDS,
+            definitions::SPAN_COUNT,
+            definitions::SPAN_EVENTS_TOTAL,
+            definitions::LAST_KNOWN_MESSAGE_NONCE,
+            definitions::LATEST_TREE_INSERTION_INDEX,
+            definitions::OBSERVED_VALIDATOR_LATEST_INDEX,
+            definitions::SUBMITTER_QUEUE_LENGTH,
+            definitions::LATEST_CHECKPOINT,
+            definitions::OPERATIONS_PROCESSED_COUNT,
+            definitions::MESSAGES_PROCESSED_COUNT,
+        ]
+        .iter()
+        .map(|definition| definition.name)
+        .collect();
+
         Ok(Self {
             agent_name: for_agent.into(),
             registry,
             listen_port,
             const_labels,
+            registered_metrics: Mutex::new(registered_metrics),
 
             span_durations,
             span_counts,
@@ -197,6 +217,7 @@ impl CoreMetrics {
 
             latest_checkpoint,
 
+            rpc_client_metrics: OnceLock::new(),
             json_rpc_client_metrics: OnceLock::new(),
             provider_metrics: OnceLock::new(),
 
@@ -215,63 +236,117 @@ impl CoreMetrics {
             .clone()
     }
 
+    /// Create the rpc client metrics attached to this core metrics instance.
+    pub fn rpc_client_metrics(&self) -> RpcClientMetrics {
+        self.rpc_client_metrics
+            .get_or_init(|| {
+                create_rpc_client_metrics(self).expect("Failed to create rpc client metrics!")
+            })
+            .clone()
+    }
+
     /// Create the json rpc provider metrics attached to this core metrics
     /// instance.
     pub fn json_rpc_client_metrics(&self) -> JsonRpcClientMetrics {
         self.json_rpc_client_metrics
             .get_or_init(|| {
-                create_json_rpc_client_metrics(self).expect("Failed to create rpc client metrics!")
+                create_json_rpc_client_metrics(self.rpc_client_metrics())
+                    .expect("Failed to create json rpc client metrics!")
             })
             .clone()
     }
 
+    /// Create the metrics of keys held in HSMs. These can only be created
+    /// once, and are meant to be installed for the whole agent.
+    pub fn hsm_metrics(&self) -> Result<HsmMetrics> {
+        Ok(HsmMetrics::new(
+            self.new_histogram(
+                &definitions::SIGNER_HSM_OPERATION_LATENCY_SECONDS,
+                HSM_OPERATION_LATENCY_BUCKETS.to_vec(),
+            )?,
+            self.new_int_gauge(&definitions::SIGNER_HSM_HEALTHY)?,
+        ))
+    }
+
+    /// Checks that `definition` is the registered definition of a metric of
+    /// type `kind`, and that the metric hasn't been registered yet.
+    fn check_registration(&self, definition: &MetricDefinition, kind: MetricKind) -> Result<()> {
+        if find_definition(definition.name) != Some(definition) {
+            bail!(
+                "Metric `{}` doesn't match any definition in `hyperlane_core::metrics::definitions`",
+                definition.name
+            );
+        }
+        if definition.kind != kind {
+            bail!(
+                "Metric `{}` is defined as a {} but registered as a {kind}",
+                definition.name,
+                definition.kind
+            );
+        }
+        if !self
+            .registered_metrics
+            .lock()
+            .expect("registered metrics lock poisoned")
+            .insert(definition.name)
+        {
+            bail!("Metric `{}` is registered more than once", definition.name);
+        }
+        Ok(())
+    }
+
     /// Create and register a new int gauge.
-    pub fn new_int_gauge(
-        &self,
-        metric_name: &str,
-        help: &str,
-        labels: &[&str],
-    ) -> Result<IntGaugeVec> {
+    pub fn new_int_gauge(&self, definition: &MetricDefinition) -> Result<IntGaugeVec> {
+        self.check_registration(definition, MetricKind::IntGauge)?;
         Ok(register_int_gauge_vec_with_registry!(
-            opts!(namespaced!(metric_name), help, self.const_labels_str()),
-            labels,
+            opts!(
+                namespaced!(definition.name),
+                definition.help,
+                self.const_labels_str()
+            ),
+            definition.labels,
             self.registry
         )?)
     }
 
     /// Create and register a new gauge.
-    pub fn new_gauge(&self, metric_name: &str, help: &str, labels: &[&str]) -> Result<GaugeVec> {
+    pub fn new_gauge(&self, definition: &MetricDefinition) -> Result<GaugeVec> {
+        self.check_registration(definition, MetricKind::Gauge)?;
         Ok(register_gauge_vec_with_registry!(
-            opts!(namespaced!(metric_name), help, self.const_labels_str()),
-            labels,
+            opts!(
+                namespaced!(definition.name),
+                definition.help,
+                self.const_labels_str()
+            ),
+            definition.labels,
             self.registry
         )?)
     }
 
     /// Create and register a new counter.
-    pub fn new_counter(
-        &self,
-        metric_name: &str,
-        help: &str,
-        labels: &[&str],
-    ) -> Result<CounterVec> {
+    pub fn new_counter(&self, definition: &MetricDefinition) -> Result<CounterVec> {
+        self.check_registration(definition, MetricKind::Counter)?;
         Ok(register_counter_vec_with_registry!(
-            opts!(namespaced!(metric_name), help, self.const_labels_str()),
-            labels,
+            opts!(
+                namespaced!(definition.name),
+                definition.help,
+                self.const_labels_str()
+            ),
+            definition.labels,
             self.registry
         )?)
     }
 
     /// Create and register a new int counter.
-    pub fn new_int_counter(
-        &self,
-        metric_name: &str,
-        help: &str,
-        labels: &[&str],
-    ) -> Result<IntCounterVec> {
+    pub fn new_int_counter(&self, definition: &MetricDefinition) -> Result<IntCounterVec> {
+        self.check_registration(definition, MetricKind::IntCounter)?;
         Ok(register_int_counter_vec_with_registry!(
-            opts!(namespaced!(metric_name), help, self.const_labels_str()),
-            labels,
+            opts!(
+                namespaced!(definition.name),
+                definition.help,
+                self.const_labels_str()
+            ),
+            definition.labels,
             self.registry
         )?)
     }
@@ -279,19 +354,18 @@ impl CoreMetrics {
     /// Create and register a new histogram.
     pub fn new_histogram(
         &self,
-        metric_name: &str,
-        help: &str,
-        labels: &[&str],
+        definition: &MetricDefinition,
         buckets: Vec<f64>,
     ) -> Result<HistogramVec> {
+        self.check_registration(definition, MetricKind::Histogram)?;
         Ok(register_histogram_vec_with_registry!(
             histogram_opts!(
-                namespaced!(metric_name),
-                help,
+                namespaced!(definition.name),
+                definition.help,
                 buckets,
                 self.const_labels.clone()
             ),
-            labels,
+            definition.labels,
             self.registry
         )?)
     }
@@ -584,3 +658,31 @@ impl ValidatorObservabilityMetricManager {
         self.observed_validator_latest_index.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_are_registered_once() {
+        let metrics = CoreMetrics::new("test", 9090, Registry::new()).unwrap();
+        metrics.new_int_gauge(&definitions::BLOCK_HEIGHT).unwrap();
+        assert!(metrics.new_int_gauge(&definitions::BLOCK_HEIGHT).is_err());
+        assert!(metrics
+            .new_int_gauge(&definitions::LATEST_CHECKPOINT)
+            .is_err());
+    }
+
+    #[test]
+    fn test_unregistered_definitions_are_rejected() {
+        let metrics = CoreMetrics::new("test", 9090, Registry::new()).unwrap();
+        assert!(metrics.new_gauge(&definitions::BLOCK_HEIGHT).is_err());
+        assert!(metrics
+            .new_int_gauge(&MetricDefinition::int_gauge(
+                "block_heigth",
+                "Tracks the current block height of the chain",
+                &["chain"],
+            ))
+            .is_err());
+    }
+}
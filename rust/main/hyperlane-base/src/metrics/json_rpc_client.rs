@@ -1,21 +1,29 @@
 use ethers_prometheus::json_rpc_client::*;
 use eyre::Result;
+use hyperlane_core::{
+    metrics::definitions,
+    rpc_clients::{RpcClientMetrics, REQUEST_LATENCY_BUCKETS},
+};
 
 use crate::CoreMetrics;
 
+pub(crate) fn create_rpc_client_metrics(metrics: &CoreMetrics) -> Result<RpcClientMetrics> {
+    Ok(RpcClientMetrics::new(
+        metrics.new_int_counter(&definitions::REQUEST_COUNT)?,
+        metrics.new_counter(&definitions::REQUEST_DURATION_SECONDS)?,
+        metrics.new_histogram(
+            &definitions::REQUEST_LATENCY_SECONDS,
+            REQUEST_LATENCY_BUCKETS.to_vec(),
+        )?,
+    ))
+}
+
 pub(crate) fn create_json_rpc_client_metrics(
-    metrics: &CoreMetrics,
+    rpc_client_metrics: RpcClientMetrics,
 ) -> Result<JsonRpcClientMetrics> {
     Ok(JsonRpcClientMetricsBuilder::default()
-        .request_count(metrics.new_int_counter(
-            "request_count",
-            REQUEST_COUNT_HELP,
-            REQUEST_COUNT_LABELS,
-        )?)
-        .request_duration_seconds(metrics.new_counter(
-            "request_duration_seconds",
-            REQUEST_DURATION_SECONDS_HELP,
-            REQUEST_DURATION_SECONDS_LABELS,
-        )?)
+        .request_count(rpc_client_metrics.request_count)
+        .request_duration_seconds(rpc_client_metrics.request_duration_seconds)
+        .request_latency_seconds(rpc_client_metrics.request_latency_seconds)
         .build()?)
 }
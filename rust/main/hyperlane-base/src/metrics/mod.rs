@@ -3,12 +3,16 @@
 pub use self::core::*;
 
 /// The metrics namespace prefix. All metric names will start with `{NAMESPACE}_`.
-pub const NAMESPACE: &str = "hyperlane";
+pub const NAMESPACE: &str = hyperlane_core::metrics::definitions::METRICS_NAMESPACE;
 
 mod core;
 
 mod agent_metrics;
 mod json_rpc_client;
 mod provider;
+mod push;
+mod wallet_balances;
 
 pub use self::agent_metrics::*;
+pub use self::push::*;
+pub use self::wallet_balances::*;
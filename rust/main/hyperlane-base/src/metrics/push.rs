@@ -0,0 +1,128 @@
+//! Pushes metrics to a Prometheus Pushgateway, for agents that can't expose
+//! their scrape endpoint, e.g. because they run behind a NAT.
+//!
+//! The scrape endpoint keeps being served when pushing is enabled.
+
+use std::{sync::Arc, time::Duration};
+
+use eyre::{eyre, Result};
+use reqwest::{header::CONTENT_TYPE, Client};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::CoreMetrics;
+
+/// Delay before the first retry of a failed push. Doubled for every further
+/// retry.
+const PUSH_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Config for pushing metrics to a Prometheus Pushgateway
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsPushConf {
+    /// Base URL of the Pushgateway
+    pub url: Url,
+    /// How often all metrics are pushed, as a single batch
+    pub interval: Duration,
+    /// How often a failed push is retried before the batch is dropped. The
+    /// next push sends fresh values anyway.
+    pub max_retries: u32,
+}
+
+/// Periodically pushes all metrics of an agent to a Pushgateway
+#[derive(Debug)]
+pub struct MetricsPusher {
+    conf: MetricsPushConf,
+    core_metrics: Arc<CoreMetrics>,
+    client: Client,
+}
+
+impl MetricsPusher {
+    /// Create a new metrics pusher
+    pub fn new(conf: MetricsPushConf, core_metrics: Arc<CoreMetrics>) -> Self {
+        Self {
+            conf,
+            core_metrics,
+            client: Client::new(),
+        }
+    }
+
+    /// Push metrics every `interval` until the task is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.conf.interval);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self.push_with_retries().await {
+                    warn!(?err, url = %self.conf.url, "Failed to push metrics");
+                }
+            }
+        })
+    }
+
+    async fn push_with_retries(&self) -> Result<()> {
+        let mut attempt = 0;
+        loop {
+            match self.push().await {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt < self.conf.max_retries => {
+                    debug!(?err, attempt, "Failed to push metrics, retrying");
+                    tokio::time::sleep(PUSH_RETRY_BASE_DELAY * 2u32.saturating_pow(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Replaces the metrics of this agent's group on the Pushgateway with the
+    /// current values
+    async fn push(&self) -> Result<()> {
+        let body = self.core_metrics.gather()?;
+        self.client
+            .put(self.push_url()?)
+            .header(CONTENT_TYPE, prometheus::TEXT_FORMAT)
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// The URL of the agent's group, which is keyed by the agent name
+    fn push_url(&self) -> Result<Url> {
+        push_url(&self.conf.url, self.core_metrics.agent_name())
+    }
+}
+
+fn push_url(base: &Url, job: &str) -> Result<Url> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| eyre!("Invalid metrics push URL {base}"))?
+        .pop_if_empty()
+        .extend(["metrics", "job", job]);
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_url() {
+        for base in ["http://pushgateway:9091", "http://pushgateway:9091/"] {
+            assert_eq!(
+                push_url(&base.parse().unwrap(), "relayer")
+                    .unwrap()
+                    .as_str(),
+                "http://pushgateway:9091/metrics/job/relayer"
+            );
+        }
+        assert_eq!(
+            push_url(&"https://example.com/push/".parse().unwrap(), "validator")
+                .unwrap()
+                .as_str(),
+            "https://example.com/push/metrics/job/validator"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+//! Watches the native token balances of the signers on all configured chains,
+//! so that operators notice wallets running dry before submissions fail.
+
+use std::{collections::HashMap, time::Duration};
+
+use futures_util::future::join_all;
+use hyperlane_core::{metrics::agent::u256_as_scaled_f64, HyperlaneDomain, HyperlaneProvider};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{info_span, trace, warn, Instrument};
+
+use crate::{settings::Settings, AgentMetrics, CoreMetrics};
+
+/// Config for watching the balances of the signers on all configured chains
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletBalanceWatcherConf {
+    /// How often the balances are queried
+    pub interval: Duration,
+    /// Balances, in units of the native token of the chain, below which a
+    /// warning is logged, by chain name
+    pub low_balance_thresholds: HashMap<String, f64>,
+}
+
+/// A signer whose balance is watched
+struct WatchedWallet {
+    domain: HyperlaneDomain,
+    address: String,
+    provider: Box<dyn HyperlaneProvider>,
+    low_balance_threshold: Option<f64>,
+}
+
+impl WatchedWallet {
+    fn is_low(&self, balance: f64) -> bool {
+        self.low_balance_threshold
+            .is_some_and(|threshold| balance < threshold)
+    }
+}
+
+/// Periodically exports the balances of the signers on all configured chains
+/// to the `wallet_balance` gauge
+pub struct WalletBalanceWatcher {
+    interval: Duration,
+    agent_name: String,
+    agent_metrics: AgentMetrics,
+    wallets: Vec<WatchedWallet>,
+}
+
+impl WalletBalanceWatcher {
+    /// Create a watcher of the signers of all chains in `settings`. Chains
+    /// without a signer, or whose provider can't be built, aren't watched.
+    pub async fn new(
+        conf: &WalletBalanceWatcherConf,
+        settings: &Settings,
+        agent_name: &str,
+        core_metrics: &CoreMetrics,
+        agent_metrics: AgentMetrics,
+    ) -> Self {
+        let mut wallets = vec![];
+        for (name, chain_conf) in &settings.chains {
+            let address = match chain_conf.chain_signer().await {
+                Ok(Some(signer)) => signer.address_string(),
+                Ok(None) => continue,
+                Err(err) => {
+                    warn!(
+                        chain = name,
+                        ?err,
+                        "Failed to build signer to watch balance of"
+                    );
+                    continue;
+                }
+            };
+            let provider = match chain_conf.build_provider(core_metrics).await {
+                Ok(provider) => provider,
+                Err(err) => {
+                    warn!(
+                        chain = name,
+                        ?err,
+                        "Failed to build provider to watch balance with"
+                    );
+                    continue;
+                }
+            };
+            wallets.push(WatchedWallet {
+                domain: chain_conf.domain.clone(),
+                address,
+                provider,
+                low_balance_threshold: conf.low_balance_thresholds.get(name).copied(),
+            });
+        }
+        Self {
+            interval: conf.interval,
+            agent_name: agent_name.to_owned(),
+            agent_metrics,
+            wallets,
+        }
+    }
+
+    async fn update_balance(&self, wallet: &WatchedWallet) {
+        let chain = wallet.domain.name();
+        let balance = match wallet.provider.get_balance(wallet.address.clone()).await {
+            Ok(balance) => u256_as_scaled_f64(balance, wallet.domain.domain_protocol()),
+            Err(err) => {
+                warn!(
+                    chain,
+                    wallet = wallet.address,
+                    ?err,
+                    "Failed to query wallet balance"
+                );
+                return;
+            }
+        };
+        trace!(
+            chain,
+            wallet = wallet.address,
+            balance,
+            "Queried wallet balance"
+        );
+        self.agent_metrics
+            .set_wallet_balance(chain, &wallet.address, &self.agent_name, balance);
+        if wallet.is_low(balance) {
+            warn!(
+                chain,
+                wallet = wallet.address,
+                balance,
+                threshold = wallet.low_balance_threshold,
+                "Wallet balance is below the low balance threshold"
+            );
+        }
+    }
+
+    /// Query the balances every interval until the task is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(
+            async move {
+                let mut interval = tokio::time::interval(self.interval);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                loop {
+                    interval.tick().await;
+                    join_all(
+                        self.wallets
+                            .iter()
+                            .map(|wallet| self.update_balance(wallet)),
+                    )
+                    .await;
+                }
+            }
+            .instrument(info_span!("WalletBalanceWatcher")),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use async_trait::async_trait;
+    use hyperlane_core::{
+        BlockInfo, ChainInfo, ChainResult, HyperlaneChain, KnownHyperlaneDomain, TxnInfo, H256,
+        H512, U256,
+    };
+    use prometheus::Registry;
+
+    use super::*;
+
+    /// A provider reporting the same balance for every address
+    #[derive(Debug)]
+    struct StaticBalance(U256);
+
+    impl HyperlaneChain for StaticBalance {
+        fn domain(&self) -> &HyperlaneDomain {
+            unimplemented!()
+        }
+
+        fn provider(&self) -> Box<dyn HyperlaneProvider> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl HyperlaneProvider for StaticBalance {
+        async fn get_block_by_height(&self, _height: u64) -> ChainResult<BlockInfo> {
+            unimplemented!()
+        }
+
+        async fn get_txn_by_hash(&self, _hash: &H512) -> ChainResult<TxnInfo> {
+            unimplemented!()
+        }
+
+        async fn is_contract(&self, _address: &H256) -> ChainResult<bool> {
+            unimplemented!()
+        }
+
+        async fn get_balance(&self, _address: String) -> ChainResult<U256> {
+            Ok(self.0)
+        }
+
+        async fn get_chain_metrics(&self) -> ChainResult<Option<ChainInfo>> {
+            unimplemented!()
+        }
+    }
+
+    fn wallet(balance: u64, low_balance_threshold: Option<f64>) -> WatchedWallet {
+        WatchedWallet {
+            domain: HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum),
+            address: "0xwallet".to_owned(),
+            provider: Box::new(StaticBalance(U256::from(balance) * U256::exp10(18))),
+            low_balance_threshold,
+        }
+    }
+
+    #[test]
+    fn test_low_balance() {
+        assert!(wallet(0, Some(1.5)).is_low(1.0));
+        assert!(!wallet(0, Some(1.5)).is_low(1.5));
+        assert!(!wallet(0, Some(1.5)).is_low(2.0));
+        // Balances aren't low without a threshold
+        assert!(!wallet(0, None).is_low(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_balances_are_exported() {
+        let core_metrics = CoreMetrics::new("relayer", 4000, Registry::new()).unwrap();
+        let watcher = WalletBalanceWatcher {
+            interval: Duration::from_secs(60),
+            agent_name: "relayer".to_owned(),
+            agent_metrics: AgentMetrics::new(&core_metrics).unwrap(),
+            wallets: vec![wallet(3, Some(5.0))],
+        };
+
+        watcher.update_balance(&watcher.wallets[0]).await;
+        let metrics = String::from_utf8(core_metrics.gather().unwrap()).unwrap();
+        let balance = metrics
+            .lines()
+            .find(|line| line.contains("wallet_balance{") && line.contains("0xwallet"))
+            .unwrap();
+        assert!(balance.contains("chain=\"ethereum\""), "{balance}");
+        assert!(balance.ends_with(" 3"), "{balance}");
+    }
+}
@@ -1,5 +1,5 @@
-use crate::CoreMetrics;
-use axum::{http::StatusCode, response::IntoResponse, routing::get, Router};
+use crate::{ChainStatuses, CoreMetrics};
+use axum::{http::StatusCode, response::IntoResponse, routing::get, Json, Router};
 use derive_new::new;
 use std::{net::SocketAddr, sync::Arc};
 use tokio::task::JoinHandle;
@@ -9,9 +9,17 @@ use tokio::task::JoinHandle;
 pub struct Server {
     listen_port: u16,
     core_metrics: Arc<CoreMetrics>,
+    #[new(default)]
+    chain_statuses: ChainStatuses,
 }
 
 impl Server {
+    /// Report the initialization statuses of the agent's chains on `/readiness`
+    pub fn with_chain_statuses(mut self, chain_statuses: ChainStatuses) -> Self {
+        self.chain_statuses = chain_statuses;
+        self
+    }
+
     /// Run an HTTP server
     pub fn run(self: Arc<Self>) -> JoinHandle<()> {
         self.run_with_custom_routes(vec![])
@@ -22,6 +30,8 @@ impl Server {
     /// routes:
     ///  - metrics - serving OpenMetrics format reports on `/metrics`
     ///     (this is compatible with Prometheus, which ought to be configured to scrape this endpoint)
+    ///  - readiness - serving the initialization status of each chain on `/readiness`,
+    ///     with status 503 while any chain is still initializing
     ///  - custom_routes - additional routes to be served by the server as per the specific agent
     pub fn run_with_custom_routes(
         self: Arc<Self>,
@@ -31,11 +41,14 @@ impl Server {
         tracing::info!(port, "starting server on 0.0.0.0");
 
         let core_metrics_clone = self.core_metrics.clone();
+        let chain_statuses = self.chain_statuses.clone();
 
-        let mut app = Router::new().route(
-            "/metrics",
-            get(move || Self::gather_metrics(core_metrics_clone)),
-        );
+        let mut app = Router::new()
+            .route(
+                "/metrics",
+                get(move || Self::gather_metrics(core_metrics_clone)),
+            )
+            .route("/readiness", get(move || Self::readiness(chain_statuses)));
 
         for (route, router) in custom_routes {
             app = app.nest(route, router);
@@ -50,6 +63,17 @@ impl Server {
         })
     }
 
+    /// The initialization status of each chain, with status 503 while any
+    /// chain is still initializing.
+    async fn readiness(chain_statuses: ChainStatuses) -> impl IntoResponse {
+        let status = if chain_statuses.all_ready() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        (status, Json(chain_statuses.snapshot()))
+    }
+
     /// Gather available metrics into an encoded (plaintext, OpenMetrics format)
     /// report.
     async fn gather_metrics(core_metrics: Arc<CoreMetrics>) -> impl IntoResponse {
@@ -4,16 +4,24 @@ use eyre::{eyre, Context, Result};
 use futures_util::future::join_all;
 use hyperlane_core::{
     HyperlaneDomain, HyperlaneLogStore, HyperlaneProvider,
-    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, InterchainGasPaymaster,
-    Mailbox, MerkleTreeHook, MultisigIsm, SequenceAwareIndexer, ValidatorAnnounce, H256,
+    HyperlaneSequenceAwareIndexerStoreReader, HyperlaneWatermarkedLogStore, Indexer,
+    InterchainGasPaymaster, Mailbox, MerkleTreeHook, MultisigIsm, SequenceAwareIndexer,
+    ValidatorAnnounce, H256,
 };
+use reqwest_utils::HttpClientConf;
+use tracing::warn;
 
 use crate::{
     cursors::{CursorType, Indexable},
-    settings::{chains::ChainConf, trace::TracingConfig},
-    ContractSync, ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore,
-    SequenceAwareLogStore, SequencedDataContractSync, Server, WatermarkContractSync,
-    WatermarkLogStore,
+    settings::{
+        chains::{ChainConf, ChainConnectionConf},
+        trace::TracingConfig,
+    },
+    AgentMetrics, BlockHeightWatchers, ChainInit, ChainStatus, ChainStatuses, ContractSync,
+    ContractSyncMetrics, ContractSyncer, CoreMetrics, HyperlaneAgentCore, LazyContractSyncer,
+    MetricsPushConf, MetricsPusher, SequenceAwareLogStore, SequencedDataContractSync, Server,
+    SharedBlockHeightIndexer, WalletBalanceWatcher, WalletBalanceWatcherConf,
+    WatermarkContractSync, WatermarkLogStore,
 };
 
 use super::TryFromWithMetrics;
@@ -48,8 +56,18 @@ pub struct Settings {
     pub chains: HashMap<String, ChainConf>,
     /// Port to listen for prometheus scrape requests
     pub metrics_port: u16,
+    /// Optional Pushgateway to push metrics to, in addition to serving them
+    pub metrics_push: Option<MetricsPushConf>,
+    /// Optionally watch the balances of the signers on all configured chains
+    pub wallet_balance_watcher: Option<WalletBalanceWatcherConf>,
+    /// Config of the HTTP clients used by the chain providers
+    pub http: HttpClientConf,
     /// The tracing configuration
     pub tracing: TracingConfig,
+    /// Finalized block height watchers shared by the indexers of each chain
+    pub block_height_watchers: BlockHeightWatchers,
+    /// Initialization statuses of the components on each chain
+    pub chain_statuses: ChainStatuses,
 }
 
 impl Settings {
@@ -98,9 +116,31 @@ impl Settings {
         )?))
     }
 
+    /// Create the metrics pusher from the settings, if pushing is configured.
+    pub fn metrics_pusher(&self, core_metrics: Arc<CoreMetrics>) -> Option<MetricsPusher> {
+        self.metrics_push
+            .clone()
+            .map(|conf| MetricsPusher::new(conf, core_metrics))
+    }
+
+    /// Create the watcher of the balances of the signers on all configured
+    /// chains from the settings, if watching is configured.
+    pub async fn wallet_balance_watcher(
+        &self,
+        agent_name: &str,
+        core_metrics: &CoreMetrics,
+        agent_metrics: AgentMetrics,
+    ) -> Option<WalletBalanceWatcher> {
+        let conf = self.wallet_balance_watcher.as_ref()?;
+        Some(WalletBalanceWatcher::new(conf, self, agent_name, core_metrics, agent_metrics).await)
+    }
+
     /// Create the server from the settings given the name of the agent.
     pub fn server(&self, core_metrics: Arc<CoreMetrics>) -> Result<Arc<Server>> {
-        Ok(Arc::new(Server::new(self.metrics_port, core_metrics)))
+        Ok(Arc::new(
+            Server::new(self.metrics_port, core_metrics)
+                .with_chain_statuses(self.chain_statuses.clone()),
+        ))
     }
 
     /// Private to preserve linearity of AgentCore::from_settings -- creating an
@@ -109,7 +149,12 @@ impl Settings {
         Self {
             chains: self.chains.clone(),
             metrics_port: self.metrics_port,
+            metrics_push: self.metrics_push.clone(),
+            wallet_balance_watcher: self.wallet_balance_watcher.clone(),
+            http: self.http.clone(),
             tracing: self.tracing.clone(),
+            block_height_watchers: self.block_height_watchers.clone(),
+            chain_statuses: self.chain_statuses.clone(),
         }
     }
 }
@@ -169,12 +214,22 @@ impl Settings {
         // Currently, all indexers are of the `SequenceIndexer` type
         let indexer =
             SequenceIndexer::<T>::try_from_with_metrics(setup, metrics, advanced_log_meta).await?;
-        Ok(Arc::new(ContractSync::new(
+        // Sequence-aware cursors poll the sequence count at the chain tip,
+        // which is shared across all indexers of the chain
+        let watcher = self
+            .block_height_watchers
+            .get_or_spawn(domain, indexer.clone());
+        let indexer =
+            Arc::new(SharedBlockHeightIndexer::new(indexer, watcher)) as SequenceIndexer<T>;
+        let sync = ContractSync::new(
             domain.clone(),
             store.clone() as SequenceAwareLogStore<_>,
             indexer,
             sync_metrics.clone(),
-        )))
+        );
+        Ok(Arc::new(
+            Self::with_reorg_detection(sync, setup, metrics).await?,
+        ))
     }
 
     /// Build a contract sync for type `T` using log store `S`
@@ -195,21 +250,57 @@ impl Settings {
         // Currently, all indexers are of the `SequenceIndexer` type
         let indexer =
             SequenceIndexer::<T>::try_from_with_metrics(setup, metrics, advanced_log_meta).await?;
-        Ok(Arc::new(ContractSync::new(
+        // Watermark cursors poll the chain tip, which is shared across all
+        // indexers of the chain rather than fetched by each of them
+        let watcher = self
+            .block_height_watchers
+            .get_or_spawn(domain, indexer.clone());
+        let indexer =
+            Arc::new(SharedBlockHeightIndexer::new(indexer, watcher)) as SequenceIndexer<T>;
+        let sync = ContractSync::new(
             domain.clone(),
             store.clone() as WatermarkLogStore<_>,
             indexer,
             sync_metrics.clone(),
-        )))
+        );
+        Ok(Arc::new(
+            Self::with_reorg_detection(sync, setup, metrics).await?,
+        ))
+    }
+
+    /// Let a contract sync detect reorgs of the blocks it indexed logs from,
+    /// using the provider of its chain. Reorged blocks are reindexed from the
+    /// chain's reorg period below the first of them; with a tag as reorg
+    /// period, from the first reorged block.
+    async fn with_reorg_detection<T, S, I>(
+        sync: ContractSync<T, S, I>,
+        setup: &ChainConf,
+        metrics: &CoreMetrics,
+    ) -> Result<ContractSync<T, S, I>>
+    where
+        T: Indexable,
+        S: HyperlaneLogStore<T>,
+        I: Indexer<T>,
+    {
+        // Building a fuel provider isn't supported yet
+        if matches!(setup.connection, ChainConnectionConf::Fuel(_)) {
+            return Ok(sync);
+        }
+        let provider: Arc<dyn HyperlaneProvider> = setup.build_provider(metrics).await?.into();
+        let reorg_period = setup.reorg_period.as_blocks().unwrap_or_default();
+        Ok(sync.with_reorg_detection(provider, reorg_period))
     }
 
     /// Build multiple contract syncs.
     /// All contracts have to implement both sequenced and
-    /// watermark trait bounds
+    /// watermark trait bounds.
+    /// The syncs of chains that fail to build, e.g. as their RPCs are
+    /// unavailable, are built in the background instead, so that the agent
+    /// can start on the other chains.
     pub async fn contract_syncs<T, S>(
         &self,
         domains: impl Iterator<Item = &HyperlaneDomain>,
-        metrics: &CoreMetrics,
+        metrics: &Arc<CoreMetrics>,
         sync_metrics: &ContractSyncMetrics,
         stores: HashMap<HyperlaneDomain, Arc<S>>,
         advanced_log_meta: bool,
@@ -223,19 +314,73 @@ impl Settings {
             + 'static,
     {
         // TODO: parallelize these calls again
-        let mut syncs = vec![];
+        let mut syncs = HashMap::new();
         for domain in domains {
             let store = stores.get(domain).unwrap().clone();
-            let sync = self
-                .contract_sync(domain, metrics, sync_metrics, store, advanced_log_meta)
-                .await?;
-            syncs.push(sync);
+            let sync = match self
+                .contract_sync(
+                    domain,
+                    metrics,
+                    sync_metrics,
+                    store.clone(),
+                    advanced_log_meta,
+                )
+                .await
+            {
+                Ok(sync) => {
+                    self.chain_statuses
+                        .set(domain, T::name(), ChainStatus::Ready);
+                    sync
+                }
+                Err(err) => {
+                    warn!(
+                        ?err,
+                        domain = domain.name(),
+                        "Failed to build {} contract sync, retrying in the background",
+                        T::name()
+                    );
+                    self.lazy_contract_sync(domain, metrics, sync_metrics, store, advanced_log_meta)
+                }
+            };
+            syncs.insert(domain.clone(), sync);
         }
+        Ok(syncs)
+    }
 
-        syncs
-            .into_iter()
-            .map(|i| Ok((i.domain().clone(), i)))
-            .collect()
+    /// Build a contract sync in the background, retrying until it succeeds
+    fn lazy_contract_sync<T, S>(
+        &self,
+        domain: &HyperlaneDomain,
+        metrics: &Arc<CoreMetrics>,
+        sync_metrics: &ContractSyncMetrics,
+        store: Arc<S>,
+        advanced_log_meta: bool,
+    ) -> Arc<dyn ContractSyncer<T>>
+    where
+        T: Indexable + Debug + Send + Sync + Clone + Eq + Hash + 'static,
+        SequenceIndexer<T>: TryFromWithMetrics<ChainConf>,
+        S: HyperlaneLogStore<T>
+            + HyperlaneSequenceAwareIndexerStoreReader<T>
+            + HyperlaneWatermarkedLogStore<T>
+            + 'static,
+    {
+        let settings = Arc::new(self.clone());
+        let sync_domain = domain.clone();
+        let metrics = metrics.clone();
+        let sync_metrics = sync_metrics.clone();
+        let sync = ChainInit::spawn(domain, T::name(), self.chain_statuses.clone(), move || {
+            let settings = settings.clone();
+            let domain = sync_domain.clone();
+            let metrics = metrics.clone();
+            let sync_metrics = sync_metrics.clone();
+            let store = store.clone();
+            async move {
+                settings
+                    .contract_sync(&domain, &metrics, &sync_metrics, store, advanced_log_meta)
+                    .await
+            }
+        });
+        Arc::new(LazyContractSyncer::new(domain.clone(), sync))
     }
 
     /// Build single contract sync.
@@ -1,17 +1,18 @@
 use axum::async_trait;
 use ethers::prelude::Selector;
-use h_cosmos::CosmosProvider;
+use h_cosmos::{CosmosModuleType, CosmosProvider};
 use std::{collections::HashMap, sync::Arc};
 
 use eyre::{eyre, Context, Result};
 
 use ethers_prometheus::middleware::{ChainInfo, ContractInfo, PrometheusMiddlewareConf};
+use hyperlane_aptos as h_aptos;
 use hyperlane_core::{
     config::OperationBatchConfig, AggregationIsm, CcipReadIsm, ContractLocator, HyperlaneAbi,
     HyperlaneDomain, HyperlaneDomainProtocol, HyperlaneMessage, HyperlaneProvider, IndexMode,
     InterchainGasPaymaster, InterchainGasPayment, InterchainSecurityModule, Mailbox,
-    MerkleTreeHook, MerkleTreeInsertion, MultisigIsm, ReorgPeriod, RoutingIsm,
-    SequenceAwareIndexer, ValidatorAnnounce, H256,
+    MerkleTreeHook, MerkleTreeInsertion, MessageDeliverySimulator, MetadataChunkStore, MultisigIsm,
+    ReorgPeriod, RoutingIsm, SequenceAwareIndexer, ValidatorAnnounce, H256,
 };
 use hyperlane_cosmos as h_cosmos;
 use hyperlane_ethereum::{
@@ -20,6 +21,9 @@ use hyperlane_ethereum::{
 };
 use hyperlane_fuel as h_fuel;
 use hyperlane_sealevel as h_sealevel;
+use hyperlane_ton as h_ton;
+use tracing::warn;
+use url::Url;
 
 use crate::{
     metrics::AgentMetricsConf,
@@ -137,6 +141,10 @@ pub enum ChainConnectionConf {
     Sealevel(h_sealevel::ConnectionConf),
     /// Cosmos configuration.
     Cosmos(h_cosmos::ConnectionConf),
+    /// TON configuration.
+    Ton(h_ton::ConnectionConf),
+    /// Aptos configuration.
+    Aptos(h_aptos::ConnectionConf),
 }
 
 impl ChainConnectionConf {
@@ -147,6 +155,8 @@ impl ChainConnectionConf {
             Self::Fuel(_) => HyperlaneDomainProtocol::Fuel,
             Self::Sealevel(_) => HyperlaneDomainProtocol::Sealevel,
             Self::Cosmos(_) => HyperlaneDomainProtocol::Cosmos,
+            Self::Ton(_) => HyperlaneDomainProtocol::Ton,
+            Self::Aptos(_) => HyperlaneDomainProtocol::Aptos,
         }
     }
 
@@ -191,6 +201,32 @@ impl ChainConf {
         self.index.clone()
     }
 
+    /// A copy of this chain setup that connects to the chain through `url`
+    /// instead of the configured RPCs, e.g. to cross-check what they report.
+    /// Chains that aren't connected to through a single RPC url, i.e. Cosmos
+    /// and Fuel chains, keep their configured connection.
+    pub fn with_rpc_url(&self, url: Url) -> Self {
+        let mut conf = self.clone();
+        match &mut conf.connection {
+            ChainConnectionConf::Ethereum(connection) => {
+                connection.rpc_connection = match url.scheme() {
+                    "ws" | "wss" => h_eth::RpcConnectionConf::Ws { url },
+                    _ => h_eth::RpcConnectionConf::Http { url },
+                };
+            }
+            ChainConnectionConf::Sealevel(connection) => connection.url = url,
+            ChainConnectionConf::Ton(connection) => connection.url = url,
+            ChainConnectionConf::Aptos(connection) => connection.url = url,
+            ChainConnectionConf::Cosmos(_) | ChainConnectionConf::Fuel(_) => {
+                warn!(
+                    domain = %self.domain,
+                    "Connecting through a single RPC url is not supported, keeping the configured connection"
+                );
+            }
+        }
+        conf
+    }
+
     /// Try to convert the chain settings into an HyperlaneProvider.
     pub async fn build_provider(
         &self,
@@ -217,6 +253,14 @@ impl ChainConf {
                 )?;
                 Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
             }
+            ChainConnectionConf::Ton(conf) => {
+                let provider = h_ton::TonProvider::new(locator.domain.clone(), conf)?;
+                Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                let provider = h_aptos::AptosProvider::new(locator.domain.clone(), conf)?;
+                Ok(Box::new(provider) as Box<dyn HyperlaneProvider>)
+            }
         }
         .context(ctx)
     }
@@ -246,10 +290,79 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                h_cosmos::CosmosMailbox::new(conf.clone(), locator.clone(), signer.clone())
+                match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => {
+                        h_cosmos::CosmosMailbox::new(conf.clone(), locator.clone(), signer)
+                            .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    }
+                    CosmosModuleType::Native => {
+                        h_cosmos::CosmosNativeMailbox::new(conf.clone(), locator.clone(), signer)
+                            .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    }
+                }
+                .map_err(Into::into)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let signer = self.ton_signer().await.context(ctx)?;
+                h_ton::TonMailbox::new(conf, locator, signer)
                     .map(|m| Box::new(m) as Box<dyn Mailbox>)
                     .map_err(Into::into)
             }
+            ChainConnectionConf::Aptos(conf) => {
+                let signer = self.aptos_signer().await.context(ctx)?;
+                h_aptos::AptosMailbox::new(conf, locator, signer)
+                    .map(|m| Box::new(m) as Box<dyn Mailbox>)
+                    .map_err(Into::into)
+            }
+        }
+        .context(ctx)
+    }
+
+    /// Try to convert the chain setting into a MessageDeliverySimulator for
+    /// the mailbox
+    pub async fn build_message_delivery_simulator(
+        &self,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn MessageDeliverySimulator>> {
+        let ctx = "Building message delivery simulator";
+        let locator = self.locator(self.addresses.mailbox);
+
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(
+                    conf,
+                    &locator,
+                    metrics,
+                    h_eth::MessageDeliverySimulatorBuilder {},
+                )
+                .await
+            }
+            ChainConnectionConf::Fuel(_) => Err(eyre!(
+                "Message delivery simulation isn't supported on Fuel chains"
+            )),
+            ChainConnectionConf::Sealevel(conf) => {
+                let keypair = self.sealevel_signer().await.context(ctx)?;
+                h_sealevel::SealevelMailbox::new(conf, locator, keypair)
+                    .map(|m| Box::new(m) as Box<dyn MessageDeliverySimulator>)
+                    .map_err(Into::into)
+            }
+            ChainConnectionConf::Cosmos(conf) => match conf.get_module_type() {
+                CosmosModuleType::CosmWasm => {
+                    let signer = self.cosmos_signer().await.context(ctx)?;
+                    h_cosmos::CosmosMailbox::new(conf.clone(), locator.clone(), signer)
+                        .map(|m| Box::new(m) as Box<dyn MessageDeliverySimulator>)
+                        .map_err(Into::into)
+                }
+                CosmosModuleType::Native => Err(eyre!(
+                    "Message delivery simulation isn't supported by the native Cosmos module"
+                )),
+            },
+            ChainConnectionConf::Ton(_) => Err(eyre!(
+                "Message delivery simulation isn't supported on TON chains"
+            )),
+            ChainConnectionConf::Aptos(_) => Err(eyre!(
+                "Message delivery simulation isn't supported on Aptos chains"
+            )),
         }
         .context(ctx)
     }
@@ -267,8 +380,12 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::MerkleTreeHookBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_conf) => {
-                todo!("Fuel does not support merkle tree hooks yet")
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.fuel_signer().await.context(ctx)?;
+                h_fuel::FuelMerkleTreeHook::new(conf, locator, wallet)
+                    .await
+                    .map(|m| Box::new(m) as Box<dyn MerkleTreeHook>)
+                    .map_err(Into::into)
             }
             ChainConnectionConf::Sealevel(conf) => {
                 h_sealevel::SealevelMailbox::new(conf, locator, None)
@@ -277,11 +394,30 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let hook =
-                    h_cosmos::CosmosMerkleTreeHook::new(conf.clone(), locator.clone(), signer)?;
-
+                let hook: Box<dyn MerkleTreeHook> = match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => Box::new(h_cosmos::CosmosMerkleTreeHook::new(
+                        conf.clone(),
+                        locator.clone(),
+                        signer,
+                    )?),
+                    CosmosModuleType::Native => {
+                        Box::new(h_cosmos::CosmosNativeMerkleTreeHook::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                };
+
+                Ok(hook)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let hook = h_ton::TonMerkleTreeHook::new(conf, locator)?;
                 Ok(Box::new(hook) as Box<dyn MerkleTreeHook>)
             }
+            ChainConnectionConf::Aptos(conf) => h_aptos::AptosMailbox::new(conf, locator, None)
+                .map(|m| Box::new(m) as Box<dyn MerkleTreeHook>)
+                .map_err(Into::into),
         }
         .context(ctx)
     }
@@ -307,7 +443,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.fuel_signer().await.context(ctx)?;
+                let indexer = h_fuel::FuelMailboxIndexer::new(conf, locator, wallet).await?;
+                Ok(Box::new(indexer) as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(
                     conf,
@@ -319,12 +459,33 @@ impl ChainConf {
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosMailboxDispatchIndexer::new(
-                    conf.clone(),
-                    locator,
-                    signer,
-                    reorg_period,
-                )?);
+                let indexer: Box<dyn SequenceAwareIndexer<HyperlaneMessage>> =
+                    match conf.get_module_type() {
+                        CosmosModuleType::CosmWasm => {
+                            Box::new(h_cosmos::CosmosMailboxDispatchIndexer::new(
+                                conf.clone(),
+                                locator,
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                        CosmosModuleType::Native => {
+                            Box::new(h_cosmos::CosmosNativeMailboxDispatchIndexer::new(
+                                conf.clone(),
+                                locator,
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                    };
+                Ok(indexer)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<HyperlaneMessage>>)
             }
         }
@@ -362,14 +523,33 @@ impl ChainConf {
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
             }
             ChainConnectionConf::Cosmos(conf) => {
-                let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosMailboxDeliveryIndexer::new(
-                    conf.clone(),
-                    locator,
-                    signer,
-                    reorg_period,
-                )?);
+                let indexer: Box<dyn SequenceAwareIndexer<H256>> = match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => {
+                        let signer = self.cosmos_signer().await.context(ctx)?;
+                        Box::new(h_cosmos::CosmosMailboxDeliveryIndexer::new(
+                            conf.clone(),
+                            locator,
+                            signer,
+                            reorg_period,
+                        )?)
+                    }
+                    CosmosModuleType::Native => {
+                        Box::new(h_cosmos::CosmosNativeMailboxDeliveryIndexer::new(
+                            conf.clone(),
+                            locator,
+                            reorg_period,
+                        )?)
+                    }
+                };
+                Ok(indexer)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMailboxIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosMailboxIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<H256>>)
             }
         }
@@ -404,13 +584,31 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let paymaster = Box::new(h_cosmos::CosmosInterchainGasPaymaster::new(
-                    conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
-                Ok(paymaster as Box<dyn InterchainGasPaymaster>)
+                let paymaster: Box<dyn InterchainGasPaymaster> = match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => {
+                        Box::new(h_cosmos::CosmosInterchainGasPaymaster::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                    CosmosModuleType::Native => {
+                        Box::new(h_cosmos::CosmosNativeInterchainGasPaymaster::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                };
+                Ok(paymaster)
             }
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support interchain gas paymasters yet")).context(ctx)
+            }
+            ChainConnectionConf::Aptos(_) => Err(eyre!(
+                "Aptos does not support interchain gas paymasters yet"
+            ))
+            .context(ctx),
         }
         .context(ctx)
     }
@@ -453,12 +651,30 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosInterchainGasPaymasterIndexer::new(
-                    conf.clone(),
-                    locator,
-                    reorg_period,
-                )?);
-                Ok(indexer as Box<dyn SequenceAwareIndexer<InterchainGasPayment>>)
+                let indexer: Box<dyn SequenceAwareIndexer<InterchainGasPayment>> =
+                    match conf.get_module_type() {
+                        CosmosModuleType::CosmWasm => {
+                            Box::new(h_cosmos::CosmosInterchainGasPaymasterIndexer::new(
+                                conf.clone(),
+                                locator,
+                                reorg_period,
+                            )?)
+                        }
+                        CosmosModuleType::Native => {
+                            Box::new(h_cosmos::CosmosNativeInterchainGasPaymasterIndexer::new(
+                                conf.clone(),
+                                locator,
+                                reorg_period,
+                            )?)
+                        }
+                    };
+                Ok(indexer)
+            }
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support gas payment indexing yet")).context(ctx)
+            }
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support gas payment indexing yet")).context(ctx)
             }
         }
         .context(ctx)
@@ -485,7 +701,11 @@ impl ChainConf {
                 )
                 .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.fuel_signer().await.context(ctx)?;
+                let indexer = h_fuel::FuelMerkleTreeHookIndexer::new(conf, locator, wallet).await?;
+                Ok(Box::new(indexer) as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
             ChainConnectionConf::Sealevel(conf) => {
                 let mailbox_indexer = Box::new(h_sealevel::SealevelMailboxIndexer::new(
                     conf,
@@ -500,13 +720,34 @@ impl ChainConf {
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
                 let reorg_period = self.reorg_period.as_blocks().context(ctx)?;
-                let indexer = Box::new(h_cosmos::CosmosMerkleTreeHookIndexer::new(
-                    conf.clone(),
-                    locator,
-                    // TODO: remove signer requirement entirely
-                    signer,
-                    reorg_period,
-                )?);
+                let indexer: Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>> =
+                    match conf.get_module_type() {
+                        CosmosModuleType::CosmWasm => {
+                            Box::new(h_cosmos::CosmosMerkleTreeHookIndexer::new(
+                                conf.clone(),
+                                locator,
+                                // TODO: remove signer requirement entirely
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                        CosmosModuleType::Native => {
+                            Box::new(h_cosmos::CosmosNativeMerkleTreeHookIndexer::new(
+                                conf.clone(),
+                                locator,
+                                signer,
+                                reorg_period,
+                            )?)
+                        }
+                    };
+                Ok(indexer)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let indexer = Box::new(h_ton::TonMerkleTreeHookIndexer::new(conf, locator)?);
+                Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                let indexer = Box::new(h_aptos::AptosMerkleTreeHookIndexer::new(conf, locator)?);
                 Ok(indexer as Box<dyn SequenceAwareIndexer<MerkleTreeInsertion>>)
             }
         }
@@ -532,18 +773,61 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let va = Box::new(h_cosmos::CosmosValidatorAnnounce::new(
-                    conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
-
+                let va: Box<dyn ValidatorAnnounce> = match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => Box::new(h_cosmos::CosmosValidatorAnnounce::new(
+                        conf.clone(),
+                        locator.clone(),
+                        signer,
+                    )?),
+                    CosmosModuleType::Native => {
+                        Box::new(h_cosmos::CosmosNativeValidatorAnnounce::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                };
+
+                Ok(va)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let signer = self.ton_signer().await.context(ctx)?;
+                let va = Box::new(h_ton::TonValidatorAnnounce::new(conf, locator, signer)?);
+                Ok(va as Box<dyn ValidatorAnnounce>)
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                let signer = self.aptos_signer().await.context(ctx)?;
+                let va = Box::new(h_aptos::AptosValidatorAnnounce::new(conf, locator, signer)?);
                 Ok(va as Box<dyn ValidatorAnnounce>)
             }
         }
         .context("Building ValidatorAnnounce")
     }
 
+    /// Try to convert the chain setting into a MetadataChunkStore contract
+    pub async fn build_metadata_chunk_store(
+        &self,
+        address: H256,
+        metrics: &CoreMetrics,
+    ) -> Result<Box<dyn MetadataChunkStore>> {
+        let ctx = "Building metadata chunk store";
+        let locator = self.locator(address);
+        match &self.connection {
+            ChainConnectionConf::Ethereum(conf) => {
+                self.build_ethereum(conf, &locator, metrics, h_eth::MetadataChunkStoreBuilder {})
+                    .await
+            }
+            ChainConnectionConf::Fuel(_)
+            | ChainConnectionConf::Sealevel(_)
+            | ChainConnectionConf::Cosmos(_)
+            | ChainConnectionConf::Ton(_)
+            | ChainConnectionConf::Aptos(_) => Err(eyre!(
+                "Metadata chunk stores are only supported on Ethereum chains"
+            )),
+        }
+        .context(ctx)
+    }
+
     /// Try to convert the chain setting into an InterchainSecurityModule
     /// contract
     pub async fn build_ism(
@@ -574,9 +858,22 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let ism = Box::new(h_cosmos::CosmosInterchainSecurityModule::new(
-                    conf, locator, signer,
-                )?);
+                let ism: Box<dyn InterchainSecurityModule> = match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => Box::new(
+                        h_cosmos::CosmosInterchainSecurityModule::new(conf, locator, signer)?,
+                    ),
+                    CosmosModuleType::Native => Box::new(
+                        h_cosmos::CosmosNativeInterchainSecurityModule::new(conf, locator, signer)?,
+                    ),
+                };
+                Ok(ism)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let ism = Box::new(h_ton::TonInterchainSecurityModule::new(conf, locator)?);
+                Ok(ism as Box<dyn InterchainSecurityModule>)
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                let ism = Box::new(h_aptos::AptosInterchainSecurityModule::new(conf, locator)?);
                 Ok(ism as Box<dyn InterchainSecurityModule>)
             }
         }
@@ -606,11 +903,26 @@ impl ChainConf {
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let ism = Box::new(h_cosmos::CosmosMultisigIsm::new(
-                    conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
+                let ism: Box<dyn MultisigIsm> = match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => Box::new(h_cosmos::CosmosMultisigIsm::new(
+                        conf.clone(),
+                        locator.clone(),
+                        signer,
+                    )?),
+                    CosmosModuleType::Native => Box::new(h_cosmos::CosmosNativeMultisigIsm::new(
+                        conf.clone(),
+                        locator.clone(),
+                        signer,
+                    )?),
+                };
+                Ok(ism)
+            }
+            ChainConnectionConf::Ton(conf) => {
+                let ism = Box::new(h_ton::TonMultisigIsm::new(conf, locator)?);
+                Ok(ism as Box<dyn MultisigIsm>)
+            }
+            ChainConnectionConf::Aptos(conf) => {
+                let ism = Box::new(h_aptos::AptosMultisigIsm::new(conf, locator)?);
                 Ok(ism as Box<dyn MultisigIsm>)
             }
         }
@@ -634,18 +946,36 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::RoutingIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.fuel_signer().await.context(ctx)?;
+                h_fuel::FuelRoutingIsm::new(conf, locator, wallet)
+                    .await
+                    .map(|m| Box::new(m) as Box<dyn RoutingIsm>)
+                    .map_err(Into::into)
+            }
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support routing ISM yet")).context(ctx)
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let ism = Box::new(h_cosmos::CosmosRoutingIsm::new(
-                    &conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
-                Ok(ism as Box<dyn RoutingIsm>)
+                let ism: Box<dyn RoutingIsm> =
+                    match conf.get_module_type() {
+                        CosmosModuleType::CosmWasm => Box::new(h_cosmos::CosmosRoutingIsm::new(
+                            &conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?),
+                        CosmosModuleType::Native => Box::new(
+                            h_cosmos::CosmosNativeRoutingIsm::new(conf, locator.clone(), signer)?,
+                        ),
+                    };
+                Ok(ism)
+            }
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support routing ISM yet")).context(ctx)
+            }
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support routing ISM yet")).context(ctx)
             }
         }
         .context(ctx)
@@ -668,19 +998,40 @@ impl ChainConf {
                 self.build_ethereum(conf, &locator, metrics, h_eth::AggregationIsmBuilder {})
                     .await
             }
-            ChainConnectionConf::Fuel(_) => todo!(),
+            ChainConnectionConf::Fuel(conf) => {
+                let wallet = self.fuel_signer().await.context(ctx)?;
+                h_fuel::FuelAggregationIsm::new(conf, locator, wallet)
+                    .await
+                    .map(|m| Box::new(m) as Box<dyn AggregationIsm>)
+                    .map_err(Into::into)
+            }
             ChainConnectionConf::Sealevel(_) => {
                 Err(eyre!("Sealevel does not support aggregation ISM yet")).context(ctx)
             }
             ChainConnectionConf::Cosmos(conf) => {
                 let signer = self.cosmos_signer().await.context(ctx)?;
-                let ism = Box::new(h_cosmos::CosmosAggregationIsm::new(
-                    conf.clone(),
-                    locator.clone(),
-                    signer,
-                )?);
-
-                Ok(ism as Box<dyn AggregationIsm>)
+                let ism: Box<dyn AggregationIsm> = match conf.get_module_type() {
+                    CosmosModuleType::CosmWasm => Box::new(h_cosmos::CosmosAggregationIsm::new(
+                        conf.clone(),
+                        locator.clone(),
+                        signer,
+                    )?),
+                    CosmosModuleType::Native => {
+                        Box::new(h_cosmos::CosmosNativeAggregationIsm::new(
+                            conf.clone(),
+                            locator.clone(),
+                            signer,
+                        )?)
+                    }
+                };
+
+                Ok(ism)
+            }
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support aggregation ISM yet")).context(ctx)
+            }
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support aggregation ISM yet")).context(ctx)
             }
         }
         .context(ctx)
@@ -710,6 +1061,12 @@ impl ChainConf {
             ChainConnectionConf::Cosmos(_) => {
                 Err(eyre!("Cosmos does not support CCIP read ISM yet")).context(ctx)
             }
+            ChainConnectionConf::Ton(_) => {
+                Err(eyre!("TON does not support CCIP read ISM yet")).context(ctx)
+            }
+            ChainConnectionConf::Aptos(_) => {
+                Err(eyre!("Aptos does not support CCIP read ISM yet")).context(ctx)
+            }
         }
         .context(ctx)
     }
@@ -734,6 +1091,10 @@ impl ChainConf {
                     Box::new(conf.build::<h_sealevel::Keypair>().await?)
                 }
                 ChainConnectionConf::Cosmos(_) => Box::new(conf.build::<h_cosmos::Signer>().await?),
+                ChainConnectionConf::Ton(_) => Box::new(conf.build::<h_ton::TonSigner>().await?),
+                ChainConnectionConf::Aptos(_) => {
+                    Box::new(conf.build::<h_aptos::AptosSigner>().await?)
+                }
             };
             Ok(Some(chain_signer))
         } else {
@@ -759,6 +1120,14 @@ impl ChainConf {
         self.signer().await
     }
 
+    async fn ton_signer(&self) -> Result<Option<h_ton::TonSigner>> {
+        self.signer().await
+    }
+
+    async fn aptos_signer(&self) -> Result<Option<h_aptos::AptosSigner>> {
+        self.signer().await
+    }
+
     /// Try to build an agent metrics configuration from the chain config
     pub async fn agent_metrics_conf(&self, agent_name: String) -> Result<AgentMetricsConf> {
         let chain_signer_address = self.chain_signer().await?.map(|s| s.address_string());
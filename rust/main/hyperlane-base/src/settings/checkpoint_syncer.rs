@@ -1,9 +1,13 @@
 use crate::{
-    CheckpointSyncer, GcsStorageClientBuilder, LocalStorage, S3Storage, GCS_SERVICE_ACCOUNT_KEY,
-    GCS_USER_SECRET,
+    ArweaveStorage, AzureBlobAuth, AzureBlobStorage, CheckpointSyncer, GcsStorageClientBuilder,
+    IpfsStorage, LocalStorage, S3Storage, ARWEAVE_GATEWAY_URL, AZURE_CLIENT_ID,
+    AZURE_MANAGED_IDENTITY, AZURE_STORAGE_ACCOUNT, AZURE_STORAGE_SAS_TOKEN,
+    DEFAULT_ARWEAVE_GATEWAY_URL, DEFAULT_ARWEAVE_UPLOAD_URL, DEFAULT_IPFS_GATEWAY_URL,
+    GCS_SERVICE_ACCOUNT_KEY, GCS_USER_SECRET, GCS_WORKLOAD_IDENTITY, IPFS_GATEWAY_URL,
 };
 use core::str::FromStr;
 use eyre::{eyre, Context, Report, Result};
+use hyperlane_core::H256;
 use prometheus::IntGauge;
 use rusoto_core::Region;
 use std::{env, path::PathBuf};
@@ -38,6 +42,49 @@ pub enum CheckpointSyncerConf {
         /// Path to oauth user secrets, like those created by
         /// `gcloud auth application-default login`
         user_secrets: Option<String>,
+        /// Authenticate as the GCP workload the agent is running as, e.g.
+        /// via GKE workload identity
+        workload_identity: bool,
+    },
+    /// A checkpoint syncer on Azure Blob Storage
+    Azure {
+        /// Storage account name
+        account: String,
+        /// Container name
+        container: String,
+        /// Folder name inside container - defaults to the root of the container
+        folder: Option<String>,
+        /// A SAS token granting access to the container
+        sas_token: Option<String>,
+        /// Authenticate as the managed identity of the Azure resource the
+        /// agent is running on
+        managed_identity: bool,
+    },
+    /// A checkpoint syncer publishing to IPFS under an IPNS name
+    Ipfs {
+        /// The IPNS name objects are published under
+        ipns_name: String,
+        /// Base URL of the RPC API of the IPFS node to publish content with,
+        /// only required for writing
+        api_url: Option<String>,
+        /// Base URL of an IPFS Pinning Service API to pin published content
+        /// with
+        pinning_service_url: Option<String>,
+        /// Bearer token for the pinning service
+        pinning_service_token: Option<String>,
+        /// Base URL of the IPFS gateway to fetch content from
+        gateway_url: String,
+    },
+    /// A checkpoint syncer publishing to Arweave through a bundler
+    Arweave {
+        /// Arweave address of the key signing published objects
+        owner: String,
+        /// The key signing published objects, only required for writing
+        key: Option<H256>,
+        /// URL of the bundler to upload objects to
+        upload_url: String,
+        /// Base URL of the Arweave gateway to fetch objects from
+        gateway_url: String,
     },
 }
 
@@ -69,31 +116,105 @@ impl FromStr for CheckpointSyncerConf {
             "file" => Ok(CheckpointSyncerConf::LocalStorage {
                 path: suffix.into(),
             }),
-            // for google cloud both options (with or without folder) from str are for anonymous access only
+            // for google cloud, locations from str are for anonymous access only
             // or env variables parsing
             "gs" => {
                 let service_account_key = env::var(GCS_SERVICE_ACCOUNT_KEY).ok();
                 let user_secrets = env::var(GCS_USER_SECRET).ok();
+                let workload_identity = env::var(GCS_WORKLOAD_IDENTITY)
+                    .map(|v| v.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false);
+                // Announced locations point at the announcement object, so the
+                // last component is the object name and anything between it
+                // and the bucket is the folder.
                 let url_components = suffix.split('/').collect::<Vec<&str>>();
-                let (bucket, folder): (&str, Option<String>) = match url_components.len() {
-                    2 => Ok((url_components[0], None)),
-                    3 => Ok((url_components[0], Some(url_components[1].to_owned()))),
-                    _ => Err(eyre!("Error parsing storage location; could not split bucket and folder ({suffix})"))
+                let (bucket, folder): (&str, Option<String>) = match url_components.as_slice() {
+                    [bucket] | [bucket, _] => Ok((*bucket, None)),
+                    [bucket, folder @ .., _] => Ok((*bucket, Some(folder.join("/")))),
+                    [] => Err(eyre!("Error parsing storage location; could not split bucket and folder ({suffix})"))
                 }?;
-                match folder {
-                    None => Ok(CheckpointSyncerConf::Gcs {
-                        bucket: bucket.into(),
-                        folder: None,
-                        service_account_key,
-                        user_secrets,
-                    }),
-                    Some(folder) => Ok(CheckpointSyncerConf::Gcs {
-                        bucket: bucket.into(),
-                        folder: Some(folder),
-                        service_account_key,
-                        user_secrets,
-                    }),
+                if bucket.is_empty() {
+                    return Err(eyre!(
+                        "Error parsing storage location; empty bucket name ({suffix})"
+                    ));
+                }
+                Ok(CheckpointSyncerConf::Gcs {
+                    bucket: bucket.into(),
+                    folder,
+                    service_account_key,
+                    user_secrets,
+                    workload_identity,
+                })
+            }
+            // for azure, the storage account is named after the container as
+            // `azblob://<container>@<account>/<folder>`; credentials come from
+            // env variables only
+            "azblob" => {
+                let sas_token = env::var(AZURE_STORAGE_SAS_TOKEN).ok();
+                let managed_identity = env::var(AZURE_MANAGED_IDENTITY)
+                    .map(|v| v.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false);
+                let (container, folder) = match suffix.split_once('/') {
+                    Some((container, folder)) if !folder.is_empty() => {
+                        (container, Some(folder.to_owned()))
+                    }
+                    Some((container, _)) => (container, None),
+                    None => (suffix, None),
+                };
+                let (container, account) = match container.split_once('@') {
+                    Some((container, account)) => (container, account.to_owned()),
+                    None => (
+                        container,
+                        env::var(AZURE_STORAGE_ACCOUNT).map_err(|_| {
+                            eyre!("Error parsing storage location; no storage account in location and {AZURE_STORAGE_ACCOUNT} is unset ({suffix})")
+                        })?,
+                    ),
+                };
+                if container.is_empty() || account.is_empty() {
+                    return Err(eyre!(
+                        "Error parsing storage location; empty container or account name ({suffix})"
+                    ));
                 }
+                Ok(CheckpointSyncerConf::Azure {
+                    account,
+                    container: container.into(),
+                    folder,
+                    sas_token,
+                    managed_identity,
+                })
+            }
+            // for ipfs, locations are formatted as `ipns://<ipns name>` and are
+            // read only, with the gateway taken from env variables
+            "ipns" => {
+                if suffix.is_empty() {
+                    return Err(eyre!(
+                        "Error parsing storage location; empty IPNS name ({suffix})"
+                    ));
+                }
+                Ok(CheckpointSyncerConf::Ipfs {
+                    ipns_name: suffix.into(),
+                    api_url: None,
+                    pinning_service_url: None,
+                    pinning_service_token: None,
+                    gateway_url: env::var(IPFS_GATEWAY_URL)
+                        .unwrap_or_else(|_| DEFAULT_IPFS_GATEWAY_URL.to_owned()),
+                })
+            }
+            // for arweave, locations are formatted as `arweave://<owner>` and
+            // are read only, with the gateway taken from env variables
+            "arweave" => {
+                if suffix.is_empty() {
+                    return Err(eyre!(
+                        "Error parsing storage location; empty Arweave owner ({suffix})"
+                    ));
+                }
+                Ok(CheckpointSyncerConf::Arweave {
+                    owner: suffix.into(),
+                    key: None,
+                    upload_url: DEFAULT_ARWEAVE_UPLOAD_URL.to_owned(),
+                    gateway_url: env::var(ARWEAVE_GATEWAY_URL)
+                        .unwrap_or_else(|_| DEFAULT_ARWEAVE_GATEWAY_URL.to_owned()),
+                })
             }
             _ => Err(eyre!("Unknown storage location prefix `{prefix}`")),
         }
@@ -155,9 +276,12 @@ impl CheckpointSyncerConf {
                 folder,
                 service_account_key,
                 user_secrets,
+                workload_identity,
             } => {
                 let auth = if let Some(path) = service_account_key {
                     AuthFlow::ServiceAccount(ServiceAccountAuth::Path(path.into()))
+                } else if *workload_identity {
+                    AuthFlow::ServiceAccount(ServiceAccountAuth::ApplicationDefault)
                 } else if let Some(path) = user_secrets {
                     AuthFlow::UserAccount(path.into())
                 } else {
@@ -171,6 +295,58 @@ impl CheckpointSyncerConf {
                         .await?,
                 )
             }
+            CheckpointSyncerConf::Azure {
+                account,
+                container,
+                folder,
+                sas_token,
+                managed_identity,
+            } => {
+                let auth = if let Some(sas_token) = sas_token {
+                    AzureBlobAuth::SasToken(sas_token.clone())
+                } else if *managed_identity {
+                    AzureBlobAuth::ManagedIdentity {
+                        client_id: env::var(AZURE_CLIENT_ID).ok(),
+                    }
+                } else {
+                    // Public data access only - no writes
+                    AzureBlobAuth::NoAuth
+                };
+
+                Box::new(AzureBlobStorage::new(
+                    account.clone(),
+                    container.clone(),
+                    folder.clone(),
+                    auth,
+                    latest_index_gauge,
+                )?)
+            }
+            CheckpointSyncerConf::Ipfs {
+                ipns_name,
+                api_url,
+                pinning_service_url,
+                pinning_service_token,
+                gateway_url,
+            } => Box::new(IpfsStorage::new(
+                ipns_name.clone(),
+                api_url.clone(),
+                pinning_service_url.clone(),
+                pinning_service_token.clone(),
+                gateway_url.clone(),
+                latest_index_gauge,
+            )?),
+            CheckpointSyncerConf::Arweave {
+                owner,
+                key,
+                upload_url,
+                gateway_url,
+            } => Box::new(ArweaveStorage::new(
+                owner.clone(),
+                *key,
+                upload_url.clone(),
+                gateway_url.clone(),
+                latest_index_gauge,
+            )?),
         })
     }
 }
@@ -249,4 +425,98 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_parse_gcs_storage_location() {
+        use super::*;
+
+        let parse = |location: &str| match CheckpointSyncerConf::from_str(location).unwrap() {
+            CheckpointSyncerConf::Gcs { bucket, folder, .. } => (bucket, folder),
+            conf => panic!("Expected a GCS checkpoint syncer, got {conf:?}"),
+        };
+
+        assert_eq!(parse("gs://bucket"), ("bucket".to_owned(), None));
+        assert_eq!(
+            parse("gs://bucket/gcsAnnouncementKey"),
+            ("bucket".to_owned(), None)
+        );
+        assert_eq!(
+            parse("gs://bucket/folder/gcsAnnouncementKey"),
+            ("bucket".to_owned(), Some("folder".to_owned()))
+        );
+        assert_eq!(
+            parse("gs://bucket/nested/folder/gcsAnnouncementKey"),
+            ("bucket".to_owned(), Some("nested/folder".to_owned()))
+        );
+        assert!(CheckpointSyncerConf::from_str("gs:///gcsAnnouncementKey").is_err());
+    }
+
+    #[test]
+    fn test_parse_azure_storage_location() {
+        use super::*;
+
+        let parse = |location: &str| match CheckpointSyncerConf::from_str(location).unwrap() {
+            CheckpointSyncerConf::Azure {
+                account,
+                container,
+                folder,
+                ..
+            } => (account, container, folder),
+            conf => panic!("Expected an Azure checkpoint syncer, got {conf:?}"),
+        };
+
+        assert_eq!(
+            parse("azblob://container@account"),
+            ("account".to_owned(), "container".to_owned(), None)
+        );
+        assert_eq!(
+            parse("azblob://container@account/nested/folder"),
+            (
+                "account".to_owned(),
+                "container".to_owned(),
+                Some("nested/folder".to_owned())
+            )
+        );
+        assert!(CheckpointSyncerConf::from_str("azblob://@account/folder").is_err());
+    }
+
+    #[test]
+    fn test_parse_ipfs_storage_location() {
+        use super::*;
+
+        let ipns_name = "k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8";
+        match CheckpointSyncerConf::from_str(&format!("ipns://{ipns_name}")).unwrap() {
+            CheckpointSyncerConf::Ipfs {
+                ipns_name: parsed_name,
+                api_url,
+                pinning_service_url,
+                ..
+            } => {
+                assert_eq!(parsed_name, ipns_name);
+                assert_eq!(api_url, None);
+                assert_eq!(pinning_service_url, None);
+            }
+            conf => panic!("Expected an IPFS checkpoint syncer, got {conf:?}"),
+        }
+        assert!(CheckpointSyncerConf::from_str("ipns://").is_err());
+    }
+
+    #[test]
+    fn test_parse_arweave_storage_location() {
+        use super::*;
+
+        let owner = "9aSjTP6cMpzMcJ6xS3PtDvvAvBTWDfdLrbBcLx2ovi8";
+        match CheckpointSyncerConf::from_str(&format!("arweave://{owner}")).unwrap() {
+            CheckpointSyncerConf::Arweave {
+                owner: parsed_owner,
+                key,
+                ..
+            } => {
+                assert_eq!(parsed_owner, owner);
+                assert_eq!(key, None);
+            }
+            conf => panic!("Expected an Arweave checkpoint syncer, got {conf:?}"),
+        }
+        assert!(CheckpointSyncerConf::from_str("arweave://").is_err());
+    }
 }
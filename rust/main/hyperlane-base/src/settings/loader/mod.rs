@@ -8,16 +8,20 @@ use eyre::{eyre, Context, Result};
 use hyperlane_core::config::*;
 use serde::de::DeserializeOwned;
 
-use crate::settings::loader::{
-    arguments::CommandLineArguments, case_adapter::CaseAdapter, environment::Environment,
+use crate::{
+    cli::SettingsArgs,
+    settings::loader::{
+        arguments::CommandLineArguments, case_adapter::CaseAdapter, environment::Environment,
+    },
 };
 
 mod arguments;
 mod case_adapter;
 mod environment;
 
-/// Deserialize a settings object from the configs.
-pub fn load_settings<T, R>() -> ConfigResult<R>
+/// Deserialize a settings object from the configs, the env vars and the
+/// config files and overrides passed on the command line.
+pub fn load_settings<T, R>(args: &SettingsArgs) -> ConfigResult<R>
 where
     T: DeserializeOwned + Debug,
     R: FromRawConf<T>,
@@ -46,10 +50,18 @@ where
         }
     }
 
-    // Load a set of additional user specified config files
+    // Load a set of additional user specified config files, the ones passed
+    // with `--config` last so that they take precedence
     let config_file_paths: Vec<String> = env::var("CONFIG_FILES")
-        .map(|s| s.split(',').map(|s| s.to_owned()).collect())
-        .unwrap_or_default();
+        .map(|s| s.split(',').map(|s| s.to_owned()).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .chain(
+            args.config_files
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned()),
+        )
+        .collect();
 
     for path in &config_file_paths {
         let p = PathBuf::from(path);
@@ -60,18 +72,18 @@ where
                 builder = builder.add_source(re_cased_config_file);
             } else {
                 return Err(eyre!(
-                    "Provided config path via CONFIG_FILES is of an unsupported type ({p:?})"
+                    "Provided config path via CONFIG_FILES or --config is of an unsupported type ({p:?})"
                 ))
                 .into_config_result(|| root_path.clone());
             }
         } else if !p.exists() {
             return Err(eyre!(
-                "Provided config path via CONFIG_FILES does not exist ({p:?})"
+                "Provided config path via CONFIG_FILES or --config does not exist ({p:?})"
             ))
             .into_config_result(|| root_path.clone());
         } else {
             return Err(eyre!(
-                "Provided config path via CONFIG_FILES is not a file ({p:?})"
+                "Provided config path via CONFIG_FILES or --config is not a file ({p:?})"
             ))
             .into_config_result(|| root_path.clone());
         }
@@ -84,7 +96,9 @@ where
             Case::Flat,
         ))
         .add_source(CaseAdapter::new(
-            CommandLineArguments::default().separator("."),
+            CommandLineArguments::default()
+                .separator(".")
+                .source(&args.overrides),
             Case::Flat,
         ))
         .build()
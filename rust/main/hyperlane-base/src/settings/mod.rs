@@ -55,12 +55,14 @@
 //!
 //! 1. The files matching `config/<env>/<config>.json`.
 //! 2. The order of configs in `CONFIG_FILES` with each sequential one
-//!    overwriting previous ones as appropriate.
+//!    overwriting previous ones as appropriate, followed by the configs
+//!    passed with `--config`.
 //! 3. Configuration env vars with the prefix `HYP` intended
 //!    to be shared by multiple agents in the same environment
 //!    E.g. `export HYP_CHAINS_ARBITRUM_DOMAINID=3000`
 //! 5. Arguments passed to the agent on the command line.
-//!    E.g. `--originChainName ethereum`
+//!    E.g. `--originChainName ethereum`, or the shorthands documented in
+//!    [`crate::cli`] such as `--log-level debug`.
 
 pub use base::*;
 pub use chains::*;
@@ -69,10 +71,12 @@ pub use signers::*;
 pub use trace::*;
 
 mod envs {
+    pub use hyperlane_aptos as h_aptos;
     pub use hyperlane_cosmos as h_cosmos;
     pub use hyperlane_ethereum as h_eth;
     pub use hyperlane_fuel as h_fuel;
     pub use hyperlane_sealevel as h_sealevel;
+    pub use hyperlane_ton as h_ton;
 }
 
 /// AWS Credentials provider.
@@ -99,8 +103,10 @@ pub mod parser;
 macro_rules! impl_loadable_from_settings {
     ($agent:ident, $settingsparser:ident -> $settingsobj:ident) => {
         impl hyperlane_base::LoadableFromSettings for $settingsobj {
-            fn load() -> hyperlane_core::config::ConfigResult<Self> {
-                hyperlane_base::settings::loader::load_settings::<$settingsparser, Self>()
+            fn load(
+                args: &hyperlane_base::cli::SettingsArgs,
+            ) -> hyperlane_core::config::ConfigResult<Self> {
+                hyperlane_base::settings::loader::load_settings::<$settingsparser, Self>(args)
             }
         }
     };
@@ -1,3 +1,8 @@
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use convert_case::{Case, Casing};
 use eyre::eyre;
 use hyperlane_sealevel::{
     HeliusPriorityFeeLevel, HeliusPriorityFeeOracleConfig, PriorityFeeOracleConfig,
@@ -6,7 +11,8 @@ use url::Url;
 
 use h_eth::TransactionOverrides;
 
-use hyperlane_core::config::{ConfigErrResultExt, OperationBatchConfig};
+use hyperlane_core::config::{ConfigErrResultExt, OperationBatchConfig, RateLimitConf};
+use hyperlane_core::rpc_clients::ProviderCapability;
 use hyperlane_core::{config::ConfigParsingError, HyperlaneDomainProtocol, NativeToken};
 
 use crate::settings::envs::*;
@@ -35,6 +41,7 @@ pub fn build_ethereum_connection_conf(
         "single" => Some(h_eth::RpcConnectionConf::Http { url: first_url }),
         "fallback" => Some(h_eth::RpcConnectionConf::HttpFallback {
             urls: rpcs.to_owned().clone(),
+            capabilities: parse_rpc_capabilities(chain, rpcs, err),
         }),
         "quorum" => Some(h_eth::RpcConnectionConf::HttpQuorum {
             urls: rpcs.to_owned().clone(),
@@ -68,6 +75,49 @@ pub fn build_ethereum_connection_conf(
                 .get_opt_key("maxPriorityFeePerGas")
                 .parse_u256()
                 .end(),
+            max_fee_per_gas_multiplier: parse_fee_multiplier(
+                &value_parser,
+                "maxFeePerGasMultiplier",
+                err,
+            ),
+            max_priority_fee_per_gas_multiplier: parse_fee_multiplier(
+                &value_parser,
+                "maxPriorityFeePerGasMultiplier",
+                err,
+            ),
+            max_fee_per_gas_cap: value_parser
+                .chain(err)
+                .get_opt_key("maxFeePerGasCap")
+                .parse_u256()
+                .end(),
+            max_priority_fee_per_gas_cap: value_parser
+                .chain(err)
+                .get_opt_key("maxPriorityFeePerGasCap")
+                .parse_u256()
+                .end(),
+        })
+        .unwrap_or_default();
+
+    let log_pruning_fallback = chain
+        .chain(err)
+        .get_opt_key("logPruningFallback")
+        .parse_bool()
+        .unwrap_or(false);
+
+    let rate_limit = parse_rate_limit(chain, err);
+
+    let custom_errors = chain
+        .chain(err)
+        .get_opt_key("customErrors")
+        .into_array_iter()
+        .map(|errors| {
+            errors
+                .filter_map(|v| {
+                    v.chain(err)
+                        .parse_from_str::<h_eth::CustomError>("Invalid custom error")
+                        .end()
+                })
+                .collect()
         })
         .unwrap_or_default();
 
@@ -75,6 +125,9 @@ pub fn build_ethereum_connection_conf(
         rpc_connection: rpc_connection_conf?,
         transaction_overrides,
         operation_batch,
+        log_pruning_fallback,
+        rate_limit,
+        custom_errors,
     }))
 }
 
@@ -111,6 +164,17 @@ pub fn build_cosmos_connection_conf(
             None
         });
 
+    let additional_bech32_prefixes: Vec<String> = chain
+        .chain(err)
+        .get_opt_key("additionalBech32Prefixes")
+        .into_array_iter()
+        .map(|prefixes| {
+            prefixes
+                .filter_map(|v| v.chain(err).parse_string().end().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default();
+
     let canonical_asset = if let Some(asset) = chain
         .chain(err)
         .get_opt_key("canonicalAsset")
@@ -134,29 +198,232 @@ pub fn build_cosmos_connection_conf(
         .and_then(parse_cosmos_gas_price)
         .end();
 
-    let contract_address_bytes = chain
+    let contract_address_bytes = parse_contract_address_bytes(chain, &mut local_err);
+
+    let native_token = parse_native_token(chain, err, 18);
+
+    let dry_run = chain
         .chain(err)
-        .get_opt_key("contractAddressBytes")
-        .parse_u64()
+        .get_opt_key("dryRun")
+        .parse_bool()
+        .unwrap_or(false);
+
+    let verify_events = chain
+        .chain(err)
+        .get_opt_key("verifyEvents")
+        .parse_bool()
+        .unwrap_or(false);
+    let light_client_store_path = chain
+        .chain(err)
+        .get_opt_key("lightClientStorePath")
+        .parse_from_str::<PathBuf>("Expected light client store path")
         .end();
+    if verify_events && light_client_store_path.is_none() {
+        local_err.push(
+            &chain.cwp + "light_client_store_path",
+            eyre!("A light client store path is required to verify events"),
+        );
+    }
+    let trusting_period = chain
+        .chain(err)
+        .get_opt_key("trustingPeriodSecs")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(h_cosmos::rpc::DEFAULT_TRUSTING_PERIOD);
+    if trusting_period.is_zero() {
+        local_err.push(
+            &chain.cwp + "trusting_period_secs",
+            eyre!("Trusting period must be greater than zero"),
+        );
+    }
 
-    let native_token = parse_native_token(chain, err, 18);
+    let query_cache_ttls: Vec<(String, ValueParser)> = chain
+        .chain(err)
+        .get_opt_key("queryCacheTtls")
+        .into_obj_iter()
+        .map(|v| v.collect())
+        .unwrap_or_default();
+    let mut query_cache = h_cosmos::caching::QueryCacheConfig::default();
+    for (query, ttl) in query_cache_ttls {
+        if let Some(ttl) = ttl.chain(err).parse_u64().end() {
+            query_cache = query_cache.with_ttl(&query, Duration::from_secs(ttl));
+        }
+    }
+
+    let key_type: h_cosmos::CosmosKeyType = chain
+        .chain(err)
+        .get_opt_key("keyType")
+        .parse_from_str("Expected cosmos key type")
+        .end()
+        .unwrap_or_default();
+
+    let sign_mode: h_cosmos::CosmosSignMode = chain
+        .chain(err)
+        .get_opt_key("signMode")
+        .parse_from_str("Expected cosmos sign mode")
+        .end()
+        .unwrap_or_default();
+    if sign_mode == h_cosmos::CosmosSignMode::Eip712
+        && key_type != h_cosmos::CosmosKeyType::EthSecp256k1
+    {
+        local_err.push(
+            &chain.cwp + "sign_mode",
+            eyre!("EIP-712 sign mode requires the ethsecp256k1 key type"),
+        );
+    }
+
+    let module_type: h_cosmos::CosmosModuleType = chain
+        .chain(err)
+        .get_opt_key("moduleType")
+        .parse_from_str("Expected cosmos module type")
+        .end()
+        .unwrap_or_default();
+
+    let rate_limit = parse_rate_limit(chain, &mut local_err);
+
+    let fee_granter = parse_cosmos_granter(
+        chain,
+        "feeGranter",
+        "Fee granters",
+        sign_mode,
+        &mut local_err,
+    );
+
+    let authz_granter = parse_cosmos_granter(
+        chain,
+        "authzGranter",
+        "Authz granters",
+        sign_mode,
+        &mut local_err,
+    );
 
     if !local_err.is_ok() {
         err.merge(local_err);
         None
     } else {
-        Some(ChainConnectionConf::Cosmos(h_cosmos::ConnectionConf::new(
+        let conf = h_cosmos::ConnectionConf::new(
             grpcs,
             rpcs.to_owned(),
             chain_id.unwrap().to_string(),
             prefix.unwrap().to_string(),
+            additional_bech32_prefixes,
             canonical_asset.unwrap(),
             gas_price.unwrap(),
-            contract_address_bytes.unwrap().try_into().unwrap(),
+            contract_address_bytes.unwrap(),
             operation_batch,
             native_token,
-        )))
+            dry_run,
+            verify_events,
+            light_client_store_path,
+            trusting_period,
+            query_cache,
+            key_type,
+            sign_mode,
+            module_type,
+            rate_limit,
+            fee_granter,
+            authz_granter,
+        );
+        for (key, granter) in [
+            ("fee_granter", conf.get_fee_granter()),
+            ("authz_granter", conf.get_authz_granter()),
+        ] {
+            if let Some(Err(e)) = granter.map(|granter| conf.bech32_address_to_h256(&granter)) {
+                err.push(&chain.cwp + key, eyre!("Invalid granter address: {e}"));
+                return None;
+            }
+        }
+        Some(ChainConnectionConf::Cosmos(conf))
+    }
+}
+
+/// Parses the address of an account granting the signer permissions, under
+/// `key`. Grants can't be used in EIP-712 sign mode, which only supports
+/// amino JSON encoded wasm contract executions.
+fn parse_cosmos_granter(
+    chain: &ValueParser,
+    key: &str,
+    name: &str,
+    sign_mode: h_cosmos::CosmosSignMode,
+    err: &mut ConfigParsingError,
+) -> Option<String> {
+    let granter = chain
+        .chain(err)
+        .get_opt_key(key)
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    if granter.is_some() && sign_mode == h_cosmos::CosmosSignMode::Eip712 {
+        err.push(
+            &chain.cwp + key.to_case(Case::Snake),
+            eyre!("{name} aren't supported in EIP-712 sign mode"),
+        );
+    }
+    granter
+}
+
+fn build_ton_connection_conf(
+    url: &Url,
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<ChainConnectionConf> {
+    let mut local_err = ConfigParsingError::default();
+
+    let native_token = parse_native_token(chain, err, 9);
+    let api_key = chain
+        .chain(&mut local_err)
+        .get_opt_key("apiKey")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+    let process_value = chain
+        .chain(&mut local_err)
+        .get_opt_key("processValue")
+        .parse_u64()
+        .unwrap_or(h_ton::ConnectionConf::DEFAULT_PROCESS_VALUE);
+    let announce_value = chain
+        .chain(&mut local_err)
+        .get_opt_key("announceValue")
+        .parse_u64()
+        .unwrap_or(h_ton::ConnectionConf::DEFAULT_ANNOUNCE_VALUE);
+
+    if !local_err.is_ok() {
+        err.merge(local_err);
+        None
+    } else {
+        Some(ChainConnectionConf::Ton(h_ton::ConnectionConf {
+            url: url.clone(),
+            api_key,
+            native_token,
+            process_value,
+            announce_value,
+        }))
+    }
+}
+
+fn build_aptos_connection_conf(
+    url: &Url,
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<ChainConnectionConf> {
+    let mut local_err = ConfigParsingError::default();
+
+    let native_token = parse_native_token(chain, err, 8);
+    let max_gas_amount = chain
+        .chain(&mut local_err)
+        .get_opt_key("maxGasAmount")
+        .parse_u64()
+        .unwrap_or(h_aptos::ConnectionConf::DEFAULT_MAX_GAS_AMOUNT);
+
+    if !local_err.is_ok() {
+        err.merge(local_err);
+        None
+    } else {
+        Some(ChainConnectionConf::Aptos(h_aptos::ConnectionConf {
+            url: url.clone(),
+            native_token,
+            max_gas_amount,
+        }))
     }
 }
 
@@ -171,6 +438,18 @@ fn build_sealevel_connection_conf(
     let native_token = parse_native_token(chain, err, 9);
     let priority_fee_oracle = parse_sealevel_priority_fee_oracle_config(chain, &mut local_err);
     let transaction_submitter = parse_transaction_submitter_config(chain, &mut local_err);
+    let rate_limit = parse_rate_limit(chain, &mut local_err);
+    let address_lookup_table = chain
+        .chain(&mut local_err)
+        .get_opt_key("addressLookupTable")
+        .parse_address_hash()
+        .end()
+        .map(|address| solana_sdk::pubkey::Pubkey::new_from_array(address.0));
+    let compute_unit_headroom_percent = chain
+        .chain(&mut local_err)
+        .get_opt_key("computeUnitHeadroomPercent")
+        .parse_u32()
+        .unwrap_or(h_sealevel::ConnectionConf::DEFAULT_COMPUTE_UNIT_HEADROOM_PERCENT);
 
     if !local_err.is_ok() {
         err.merge(local_err);
@@ -182,6 +461,9 @@ fn build_sealevel_connection_conf(
             native_token,
             priority_fee_oracle: priority_fee_oracle.unwrap(),
             transaction_submitter: transaction_submitter.unwrap(),
+            rate_limit,
+            address_lookup_table,
+            compute_unit_headroom_percent,
         }))
     }
 }
@@ -211,6 +493,140 @@ fn parse_native_token(
     }
 }
 
+/// Parses the `capability` of each of the `rpcUrls` of the chain, returning
+/// the capability of each of `rpcs` in order. URLs that aren't listed, e.g.
+/// custom RPC URLs, are sent all requests.
+fn parse_rpc_capabilities(
+    chain: &ValueParser,
+    rpcs: &[Url],
+    err: &mut ConfigParsingError,
+) -> Vec<ProviderCapability> {
+    let capabilities: Vec<(Url, ProviderCapability)> = chain
+        .chain(err)
+        .get_opt_key("rpcUrls")
+        .into_array_iter()
+        .map(|urls| {
+            urls.filter_map(|v| {
+                let capability = match v.chain(err).get_opt_key("capability").parse_string().end() {
+                    None | Some("readWrite") => ProviderCapability::ReadWrite,
+                    Some("readOnly") => ProviderCapability::ReadOnly,
+                    Some("writeOnly") => ProviderCapability::WriteOnly,
+                    Some(capability) => {
+                        err.push(
+                            &v.cwp + "capability",
+                            eyre!("Unknown RPC capability `{capability}`"),
+                        );
+                        return None;
+                    }
+                };
+                let url = v
+                    .chain(err)
+                    .get_key("http")
+                    .parse_from_str("Invalid url")
+                    .end()?;
+                Some((url, capability))
+            })
+            .collect()
+        })
+        .unwrap_or_default();
+
+    rpcs.iter()
+        .map(|rpc| {
+            capabilities
+                .iter()
+                .find(|(url, _)| url == rpc)
+                .map(|(_, capability)| *capability)
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Parses the rate limit applied to every RPC endpoint of the chain
+/// Parses the length of contract addresses, which must be present and fit in
+/// an `H256`. Only a single error is reported for an invalid value.
+fn parse_contract_address_bytes(
+    chain: &ValueParser,
+    err: &mut ConfigParsingError,
+) -> Option<usize> {
+    let Some(value_parser) = chain.chain(err).get_opt_key("contractAddressBytes").end() else {
+        err.push(
+            &chain.cwp + "contract_address_bytes",
+            eyre!("Missing contract address bytes for chain"),
+        );
+        return None;
+    };
+    let bytes = value_parser.chain(err).parse_u64().end()?;
+    match usize::try_from(bytes) {
+        Ok(bytes @ 1..=32) => Some(bytes),
+        _ => {
+            err.push(
+                &chain.cwp + "contract_address_bytes",
+                eyre!("Contract address bytes must be between 1 and 32, got {bytes}"),
+            );
+            None
+        }
+    }
+}
+
+fn parse_rate_limit(chain: &ValueParser, err: &mut ConfigParsingError) -> Option<RateLimitConf> {
+    let value_parser = chain.chain(err).get_opt_key("rateLimit").end()?;
+
+    let max_requests_per_second = value_parser
+        .chain(err)
+        .get_key("maxRequestsPerSecond")
+        .parse_u32()
+        .end()?;
+    let Some(max_requests_per_second) = NonZeroU32::new(max_requests_per_second) else {
+        err.push(
+            &value_parser.cwp + "max_requests_per_second",
+            eyre!("Max requests per second must be greater than zero"),
+        );
+        return None;
+    };
+    // Without a burst, up to a second worth of requests can be sent at once
+    let burst = match value_parser
+        .chain(err)
+        .get_opt_key("burst")
+        .parse_u32()
+        .end()
+    {
+        Some(burst) => {
+            let Some(burst) = NonZeroU32::new(burst) else {
+                err.push(
+                    &value_parser.cwp + "burst",
+                    eyre!("Burst must be greater than zero"),
+                );
+                return None;
+            };
+            burst
+        }
+        None => max_requests_per_second,
+    };
+
+    Some(RateLimitConf {
+        max_requests_per_second,
+        burst,
+    })
+}
+
+/// Parses the fee multiplier at `key` of the transaction overrides, which
+/// can't be negative, nor NaN or infinite as no float config value can
+fn parse_fee_multiplier(
+    overrides: &ValueParser,
+    key: &str,
+    err: &mut ConfigParsingError,
+) -> Option<f64> {
+    let multiplier = overrides.chain(err).get_opt_key(key).parse_f64().end()?;
+    if multiplier < 0.0 {
+        err.push(
+            &overrides.cwp + key.to_case(Case::Snake),
+            eyre!("Fee multiplier must be a non-negative number, got {multiplier}"),
+        );
+        return None;
+    }
+    Some(multiplier)
+}
+
 fn parse_sealevel_priority_fee_oracle_config(
     chain: &ValueParser,
     err: &mut ConfigParsingError,
@@ -379,5 +795,193 @@ pub fn build_connection_conf(
         HyperlaneDomainProtocol::Cosmos => {
             build_cosmos_connection_conf(rpcs, chain, err, operation_batch)
         }
+        HyperlaneDomainProtocol::Ton => rpcs
+            .iter()
+            .next()
+            .and_then(|url| build_ton_connection_conf(url, chain, err)),
+        HyperlaneDomainProtocol::Aptos => rpcs
+            .iter()
+            .next()
+            .and_then(|url| build_aptos_connection_conf(url, chain, err)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_contract_address_bytes() {
+        let parse = |value: serde_json::Value| {
+            let mut err = ConfigParsingError::default();
+            let bytes = parse_contract_address_bytes(
+                &ValueParser::new(Default::default(), &value),
+                &mut err,
+            );
+            (bytes, err)
+        };
+
+        let (parsed, err) = parse(json!({"contractAddressBytes": 32}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, Some(32));
+
+        let (parsed, err) = parse(json!({"contractAddressBytes": "20"}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, Some(20));
+
+        for invalid in [json!(0), json!(33), json!("twenty"), json!(null)] {
+            let (parsed, err) = parse(json!({ "contractAddressBytes": invalid }));
+            assert_eq!(parsed, None);
+            let err = err.to_string();
+            assert_eq!(err.matches("`contractAddressBytes`").count(), 1, "{err}");
+        }
+
+        let (parsed, err) = parse(json!({}));
+        assert_eq!(parsed, None);
+        let err = err.to_string();
+        assert_eq!(err.matches("`contractAddressBytes`").count(), 1, "{err}");
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let parse = |value: serde_json::Value| {
+            let mut err = ConfigParsingError::default();
+            let conf = parse_rate_limit(&ValueParser::new(Default::default(), &value), &mut err);
+            (conf, err)
+        };
+        let conf = |max_requests_per_second, burst| RateLimitConf {
+            max_requests_per_second: NonZeroU32::new(max_requests_per_second).unwrap(),
+            burst: NonZeroU32::new(burst).unwrap(),
+        };
+
+        let (parsed, err) = parse(json!({"rateLimit": {"maxRequestsPerSecond": 10, "burst": 20}}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, Some(conf(10, 20)));
+
+        let (parsed, err) = parse(json!({"rateLimit": {"maxRequestsPerSecond": 10}}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, Some(conf(10, 10)));
+
+        let (parsed, err) = parse(json!({}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, None);
+
+        let (parsed, err) = parse(json!({"rateLimit": {"maxRequestsPerSecond": 10, "burst": 0}}));
+        assert_eq!(parsed, None);
+        let err = err.to_string();
+        assert!(err.contains("`rateLimit.burst`"), "{err}");
+
+        let (parsed, err) = parse(json!({"rateLimit": {"maxRequestsPerSecond": 0}}));
+        assert_eq!(parsed, None);
+        let err = err.to_string();
+        assert!(err.contains("`rateLimit.maxRequestsPerSecond`"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_fee_multiplier() {
+        let parse = |value: serde_json::Value| {
+            let mut err = ConfigParsingError::default();
+            let multiplier = parse_fee_multiplier(
+                &ValueParser::new(Default::default(), &value),
+                "maxFeePerGasMultiplier",
+                &mut err,
+            );
+            (multiplier, err)
+        };
+
+        let (parsed, err) = parse(json!({"maxFeePerGasMultiplier": 1.5}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, Some(1.5));
+
+        let (parsed, err) = parse(json!({"maxFeePerGasMultiplier": "0"}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, Some(0.0));
+
+        let (parsed, err) = parse(json!({}));
+        assert!(err.is_ok());
+        assert_eq!(parsed, None);
+
+        for invalid in [json!(-1.5), json!("NaN"), json!("inf")] {
+            let (parsed, err) = parse(json!({ "maxFeePerGasMultiplier": invalid }));
+            assert_eq!(parsed, None);
+            let err = err.to_string();
+            assert!(err.contains("`maxFeePerGasMultiplier`"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_parse_cosmos_granter() {
+        let parse = |value: serde_json::Value, sign_mode| {
+            let mut err = ConfigParsingError::default();
+            let granter = parse_cosmos_granter(
+                &ValueParser::new(Default::default(), &value),
+                "feeGranter",
+                "Fee granters",
+                sign_mode,
+                &mut err,
+            );
+            (granter, err)
+        };
+        let value = json!({"feeGranter": "neutron1granter"});
+
+        let (parsed, err) = parse(value.clone(), h_cosmos::CosmosSignMode::Direct);
+        assert!(err.is_ok());
+        assert_eq!(parsed.as_deref(), Some("neutron1granter"));
+
+        let (parsed, err) = parse(json!({}), h_cosmos::CosmosSignMode::Eip712);
+        assert!(err.is_ok());
+        assert_eq!(parsed, None);
+
+        let (_, err) = parse(value, h_cosmos::CosmosSignMode::Eip712);
+        let err = err.to_string();
+        assert!(err.contains("`feeGranter`"), "{err}");
+        assert!(err.contains("EIP-712"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rpc_capabilities() {
+        let rpcs: Vec<Url> = [
+            "http://a.com",
+            "http://b.com",
+            "http://c.com",
+            "http://d.com",
+        ]
+        .iter()
+        .map(|url| url.parse().unwrap())
+        .collect();
+        let value = json!({"rpcUrls": [
+            {"http": "http://a.com", "capability": "readOnly"},
+            {"http": "http://b.com", "capability": "writeOnly"},
+            {"http": "http://c.com"},
+        ]});
+        let mut err = ConfigParsingError::default();
+        let capabilities = parse_rpc_capabilities(
+            &ValueParser::new(Default::default(), &value),
+            &rpcs,
+            &mut err,
+        );
+        assert!(err.is_ok());
+        assert_eq!(
+            capabilities,
+            vec![
+                ProviderCapability::ReadOnly,
+                ProviderCapability::WriteOnly,
+                ProviderCapability::ReadWrite,
+                ProviderCapability::ReadWrite,
+            ]
+        );
+
+        let value = json!({"rpcUrls": [{"http": "http://a.com", "capability": "archive"}]});
+        let mut err = ConfigParsingError::default();
+        let capabilities = parse_rpc_capabilities(
+            &ValueParser::new(Default::default(), &value),
+            &rpcs,
+            &mut err,
+        );
+        assert_eq!(capabilities, vec![ProviderCapability::ReadWrite; 4]);
+        let err = err.to_string();
+        assert!(err.contains("`capability`"), "{err}");
     }
 }
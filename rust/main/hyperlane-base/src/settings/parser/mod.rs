@@ -7,24 +7,33 @@
 use std::{
     collections::{HashMap, HashSet},
     default::Default,
+    path::PathBuf,
+    time::Duration,
 };
 
 use convert_case::{Case, Casing};
 use eyre::{eyre, Context};
 use itertools::Itertools;
+use reqwest_utils::HttpClientConf;
 use serde::Deserialize;
 use serde_json::Value;
 use url::Url;
 
 use h_cosmos::RawCosmosAmount;
+use h_eth::{Pkcs11KeyConf, RemoteSignerConf};
 use hyperlane_core::{
     cfg_unwrap_all, config::*, HyperlaneDomain, HyperlaneDomainProtocol,
-    HyperlaneDomainTechnicalStack, IndexMode, ReorgPeriod,
+    HyperlaneDomainTechnicalStack, IndexMode, RegistryDomain, ReorgPeriod, H256,
 };
 
-use crate::settings::{
-    chains::IndexSettings, parser::connection_parser::build_connection_conf, trace::TracingConfig,
-    ChainConf, CoreContractAddresses, Settings, SignerConf,
+use crate::{
+    settings::{
+        chains::IndexSettings,
+        parser::connection_parser::build_connection_conf,
+        trace::{OtlpExportConfig, TracingConfig},
+        ChainConf, ChainConnectionConf, CoreContractAddresses, Settings, SignerConf,
+    },
+    MetricsPushConf, WalletBalanceWatcherConf,
 };
 
 pub use super::envs::*;
@@ -57,6 +66,24 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_u16()
             .unwrap_or(9090);
 
+        let metrics_push = p
+            .chain(&mut err)
+            .get_opt_key("metricsPush")
+            .and_then(parse_metrics_push_conf)
+            .end();
+
+        let wallet_balance_watcher = p
+            .chain(&mut err)
+            .get_opt_key("walletBalanceWatcher")
+            .and_then(parse_wallet_balance_watcher_conf)
+            .end();
+
+        let http = p
+            .chain(&mut err)
+            .get_opt_key("http")
+            .and_then(parse_http_client_conf)
+            .unwrap_or_default();
+
         let fmt = p
             .chain(&mut err)
             .get_opt_key("log")
@@ -71,6 +98,13 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
             .parse_value("Invalid log level")
             .unwrap_or_default();
 
+        let otlp = p
+            .chain(&mut err)
+            .get_opt_key("log")
+            .get_opt_key("otlp")
+            .and_then(parse_otlp_export_config)
+            .end();
+
         let raw_chains: Vec<(String, ValueParser)> = if let Some(filter) = filter {
             p.chain(&mut err)
                 .get_opt_key("chains")
@@ -114,11 +148,200 @@ impl FromRawConf<RawAgentConf, Option<&HashSet<&str>>> for Settings {
         err.into_result(Self {
             chains,
             metrics_port,
-            tracing: TracingConfig { fmt, level },
+            metrics_push,
+            wallet_balance_watcher,
+            http,
+            tracing: TracingConfig { fmt, level, otlp },
+            block_height_watchers: Default::default(),
+            chain_statuses: Default::default(),
         })
     }
 }
 
+/// Parses the Pushgateway config of `metricsPush`
+fn parse_metrics_push_conf(p: ValueParser) -> ConfigResult<MetricsPushConf> {
+    let mut err = ConfigParsingError::default();
+
+    let url = p
+        .chain(&mut err)
+        .get_key("url")
+        .parse_from_str::<Url>("Invalid metrics push url")
+        .end();
+
+    let interval = p
+        .chain(&mut err)
+        .get_opt_key("interval")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(15));
+    if interval.is_zero() {
+        err.push(
+            &p.cwp + "interval",
+            eyre!("Metrics push interval must be greater than 0"),
+        );
+    }
+
+    let max_retries = p
+        .chain(&mut err)
+        .get_opt_key("maxRetries")
+        .parse_u32()
+        .unwrap_or(3);
+
+    cfg_unwrap_all!(&p.cwp, err: [url]);
+    err.into_result(MetricsPushConf {
+        url,
+        interval,
+        max_retries,
+    })
+}
+
+/// Parses the config of `walletBalanceWatcher`
+fn parse_wallet_balance_watcher_conf(p: ValueParser) -> ConfigResult<WalletBalanceWatcherConf> {
+    let mut err = ConfigParsingError::default();
+
+    let interval = p
+        .chain(&mut err)
+        .get_opt_key("interval")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(60));
+    if interval.is_zero() {
+        err.push(
+            &p.cwp + "interval",
+            eyre!("Wallet balance watcher interval must be greater than 0"),
+        );
+    }
+
+    let raw_thresholds: Vec<(String, ValueParser)> = p
+        .chain(&mut err)
+        .get_opt_key("lowBalanceThresholds")
+        .into_obj_iter()
+        .map(|v| v.collect())
+        .unwrap_or_default();
+    let mut low_balance_thresholds = HashMap::new();
+    for (chain, threshold) in raw_thresholds {
+        match threshold.chain(&mut err).parse_f64().end() {
+            Some(value) if value >= 0. => {
+                low_balance_thresholds.insert(chain, value);
+            }
+            Some(_) => err.push(
+                &threshold.cwp,
+                eyre!("Low balance thresholds must be non-negative numbers"),
+            ),
+            None => {}
+        }
+    }
+
+    err.into_result(WalletBalanceWatcherConf {
+        interval,
+        low_balance_thresholds,
+    })
+}
+
+/// Parses the config of `log.otlp`, which exports spans to an OpenTelemetry
+/// collector
+fn parse_otlp_export_config(p: ValueParser) -> ConfigResult<OtlpExportConfig> {
+    let mut err = ConfigParsingError::default();
+
+    let endpoint = p
+        .chain(&mut err)
+        .get_key("endpoint")
+        .parse_string()
+        .end()
+        .map(str::to_owned);
+
+    let raw_headers: Vec<(String, ValueParser)> = p
+        .chain(&mut err)
+        .get_opt_key("headers")
+        .into_obj_iter()
+        .map(|v| v.collect())
+        .unwrap_or_default();
+    let mut headers = HashMap::new();
+    for (name, value) in raw_headers {
+        if let Some(value) = value.chain(&mut err).parse_string().end() {
+            headers.insert(name, value.to_owned());
+        }
+    }
+
+    cfg_unwrap_all!(&p.cwp, err: [endpoint]);
+    err.into_result(OtlpExportConfig { endpoint, headers })
+}
+
+/// Parses the config of the HTTP clients of `http`. Timeouts are in seconds.
+fn parse_http_client_conf(p: ValueParser) -> ConfigResult<HttpClientConf> {
+    let mut err = ConfigParsingError::default();
+    let default = HttpClientConf::default();
+
+    let timeout = p
+        .chain(&mut err)
+        .get_opt_key("timeout")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .unwrap_or(default.timeout);
+
+    let connect_timeout = p
+        .chain(&mut err)
+        .get_opt_key("connectTimeout")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .end();
+
+    let pool_max_idle_per_host = p
+        .chain(&mut err)
+        .get_opt_key("poolMaxIdlePerHost")
+        .parse_u64()
+        .map(|max_idle| max_idle as usize)
+        .end();
+
+    let pool_idle_timeout = p
+        .chain(&mut err)
+        .get_opt_key("poolIdleTimeout")
+        .parse_u64()
+        .map(Duration::from_secs)
+        .end();
+
+    let proxy = p
+        .chain(&mut err)
+        .get_opt_key("proxy")
+        .parse_from_str::<Url>("Invalid proxy url")
+        .end();
+
+    let raw_root_certificates: Vec<ValueParser> = p
+        .chain(&mut err)
+        .get_opt_key("rootCertificates")
+        .into_array_iter()
+        .map(|v| v.collect())
+        .unwrap_or_default();
+    let root_certificates = raw_root_certificates
+        .into_iter()
+        .filter_map(|cert| cert.chain(&mut err).parse_string().end())
+        .map(PathBuf::from)
+        .collect();
+
+    let raw_host_timeouts: Vec<(String, ValueParser)> = p
+        .chain(&mut err)
+        .get_opt_key("hostTimeouts")
+        .into_obj_iter()
+        .map(|v| v.collect())
+        .unwrap_or_default();
+    let mut host_timeouts = HashMap::new();
+    for (host, timeout) in raw_host_timeouts {
+        if let Some(timeout) = timeout.chain(&mut err).parse_u64().end() {
+            host_timeouts.insert(host, Duration::from_secs(timeout));
+        }
+    }
+
+    err.into_result(HttpClientConf {
+        timeout,
+        connect_timeout,
+        pool_max_idle_per_host,
+        pool_idle_timeout,
+        proxy,
+        root_certificates,
+        host_timeouts,
+    })
+}
+
 /// The chain name and ChainMetadata
 fn parse_chain(
     chain: ValueParser,
@@ -134,12 +357,7 @@ fn parse_chain(
         .and_then(parse_signer)
         .end();
 
-    let reorg_period = chain
-        .chain(&mut err)
-        .get_opt_key("blocks")
-        .get_key("reorgPeriod")
-        .parse_value("Invalid reorgPeriod")
-        .unwrap_or(ReorgPeriod::from_blocks(1));
+    let reorg_period = parse_reorg_period(&chain, domain.as_ref(), &mut err);
 
     let rpcs = parse_base_and_override_urls(&chain, "rpcUrls", "customRpcUrls", "http", &mut err);
 
@@ -166,32 +384,12 @@ fn parse_chain(
                 .and_then(|d| match d.domain_protocol() {
                     HyperlaneDomainProtocol::Ethereum => Some(IndexMode::Block),
                     HyperlaneDomainProtocol::Sealevel => Some(IndexMode::Sequence),
+                    HyperlaneDomainProtocol::Aptos => Some(IndexMode::Sequence),
                     _ => None,
                 })
                 .unwrap_or_default()
         });
 
-    let mailbox = chain
-        .chain(&mut err)
-        .get_key("mailbox")
-        .parse_address_hash()
-        .end();
-    let interchain_gas_paymaster = chain
-        .chain(&mut err)
-        .get_key("interchainGasPaymaster")
-        .parse_address_hash()
-        .end();
-    let validator_announce = chain
-        .chain(&mut err)
-        .get_key("validatorAnnounce")
-        .parse_address_hash()
-        .end();
-    let merkle_tree_hook = chain
-        .chain(&mut err)
-        .get_key("merkleTreeHook")
-        .parse_address_hash()
-        .end();
-
     let batch_contract_address = chain
         .chain(&mut err)
         .get_opt_key("batchContractAddress")
@@ -217,6 +415,35 @@ fn parse_chain(
         },
     );
 
+    let mailbox = parse_contract_address(&chain, "mailbox", connection.as_ref(), &mut err);
+    let interchain_gas_paymaster = parse_contract_address(
+        &chain,
+        "interchainGasPaymaster",
+        connection.as_ref(),
+        &mut err,
+    );
+    let validator_announce =
+        parse_contract_address(&chain, "validatorAnnounce", connection.as_ref(), &mut err);
+    let merkle_tree_hook =
+        parse_contract_address(&chain, "merkleTreeHook", connection.as_ref(), &mut err);
+
+    // Catch signers of Cosmos chains with the wrong prefix at startup rather
+    // than when their first transaction is rejected
+    if let (Some(ChainConnectionConf::Cosmos(conf)), Some(prefix)) = (
+        &connection,
+        signer.as_ref().and_then(SignerConf::cosmos_prefix),
+    ) {
+        if prefix != conf.get_bech32_prefix() {
+            err.push(
+                &chain.cwp + "signer.prefix",
+                eyre!(
+                    "Signer prefix `{prefix}` doesn't match the chain's bech32 prefix `{}`",
+                    conf.get_bech32_prefix()
+                ),
+            );
+        }
+    }
+
     cfg_unwrap_all!(&chain.cwp, err: [connection, mailbox, interchain_gas_paymaster, validator_announce, merkle_tree_hook]);
     err.into_result(ChainConf {
         domain,
@@ -238,6 +465,49 @@ fn parse_chain(
     })
 }
 
+/// Parses the reorg period of a chain. Without `blocks`, it falls back to the
+/// reorg period of the domain in the registry, but an invalid reorg period is
+/// an error.
+fn parse_reorg_period(
+    chain: &ValueParser,
+    domain: Option<&HyperlaneDomain>,
+    err: &mut ConfigParsingError,
+) -> ReorgPeriod {
+    let default = || {
+        domain
+            .and_then(|domain| RegistryDomain::by_id(domain.id()))
+            .map(|domain| domain.reorg_period)
+            .unwrap_or(ReorgPeriod::from_blocks(1))
+    };
+    let Some(blocks) = chain.chain(err).get_opt_key("blocks").end() else {
+        return default();
+    };
+    // The default is only used if parsing failed, in which case the error
+    // fails the whole config
+    blocks
+        .chain(err)
+        .get_key("reorgPeriod")
+        .parse_value("Invalid reorgPeriod")
+        .unwrap_or_else(default)
+}
+
+/// Parse the address of a core contract of a chain. On Cosmos chains, the
+/// address may also be bech32 encoded with one of the chain's prefixes.
+fn parse_contract_address(
+    chain: &ValueParser,
+    key: &str,
+    connection: Option<&ChainConnectionConf>,
+    err: &mut ConfigParsingError,
+) -> Option<H256> {
+    let address = chain.chain(err).get_key(key).parse_string().end()?;
+    match connection {
+        Some(ChainConnectionConf::Cosmos(conf)) if !address.starts_with("0x") => conf
+            .bech32_address_to_h256(address)
+            .take_err(err, || &chain.cwp + key),
+        _ => chain.chain(err).get_key(key).parse_address_hash().end(),
+    }
+}
+
 /// Expects ChainMetadata
 fn parse_domain(chain: ValueParser, name: &str) -> ConfigResult<HyperlaneDomain> {
     let mut err = ConfigParsingError::default();
@@ -346,12 +616,131 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
                 account_address_type,
             })
         }};
+        (gcp) => {{
+            let key_name = signer
+                .chain(&mut err)
+                .get_key("keyName")
+                .parse_string()
+                .unwrap_or("")
+                .to_owned();
+            err.into_result(SignerConf::Gcp { key_name })
+        }};
+        (cosmosGcp) => {{
+            let key_name = signer
+                .chain(&mut err)
+                .get_key("keyName")
+                .parse_string()
+                .unwrap_or("")
+                .to_owned();
+            let prefix = signer
+                .chain(&mut err)
+                .get_key("prefix")
+                .parse_string()
+                .unwrap_or_default();
+            let account_address_type = signer
+                .chain(&mut err)
+                .get_opt_key("accountAddressType")
+                .parse_from_str("Expected Account Address Type")
+                .end()
+                .unwrap_or_default();
+            err.into_result(SignerConf::CosmosGcp {
+                key_name,
+                prefix: prefix.to_string(),
+                account_address_type,
+            })
+        }};
+        (pkcs11) => {{
+            let key = parse_pkcs11_key(&signer, &mut err);
+            err.into_result(SignerConf::Pkcs11 { key })
+        }};
+        (cosmosPkcs11) => {{
+            let key = parse_pkcs11_key(&signer, &mut err);
+            let prefix = signer
+                .chain(&mut err)
+                .get_key("prefix")
+                .parse_string()
+                .unwrap_or_default();
+            let account_address_type = signer
+                .chain(&mut err)
+                .get_opt_key("accountAddressType")
+                .parse_from_str("Expected Account Address Type")
+                .end()
+                .unwrap_or_default();
+            err.into_result(SignerConf::CosmosPkcs11 {
+                key,
+                prefix: prefix.to_string(),
+                account_address_type,
+            })
+        }};
+        (remote) => {{
+            let url = signer
+                .chain(&mut err)
+                .get_key("url")
+                .parse_from_str::<Url>("Expected URL")
+                .end();
+            let mut parse_path = |key: &str| -> PathBuf {
+                signer
+                    .chain(&mut err)
+                    .get_key(key)
+                    .parse_string()
+                    .unwrap_or("")
+                    .into()
+            };
+            let ca_cert = parse_path("caCert");
+            let client_cert = parse_path("clientCert");
+            let client_key = parse_path("clientKey");
+            cfg_unwrap_all!(&signer.cwp, err: [url]);
+            err.into_result(SignerConf::Remote {
+                service: RemoteSignerConf {
+                    url,
+                    ca_cert,
+                    client_cert,
+                    client_key,
+                },
+            })
+        }};
+        (cosmosAws) => {{
+            let id = signer
+                .chain(&mut err)
+                .get_key("id")
+                .parse_string()
+                .unwrap_or("")
+                .to_owned();
+            let region = signer
+                .chain(&mut err)
+                .get_key("region")
+                .parse_from_str("Expected AWS region")
+                .unwrap_or_default();
+            let prefix = signer
+                .chain(&mut err)
+                .get_key("prefix")
+                .parse_string()
+                .unwrap_or_default();
+            let account_address_type = signer
+                .chain(&mut err)
+                .get_opt_key("accountAddressType")
+                .parse_from_str("Expected Account Address Type")
+                .end()
+                .unwrap_or_default();
+            err.into_result(SignerConf::CosmosAws {
+                id,
+                region,
+                prefix: prefix.to_string(),
+                account_address_type,
+            })
+        }};
     }
 
     match signer_type {
         Some("hexKey") => parse_signer!(hexKey),
         Some("aws") => parse_signer!(aws),
         Some("cosmosKey") => parse_signer!(cosmosKey),
+        Some("cosmosAws") => parse_signer!(cosmosAws),
+        Some("gcp") => parse_signer!(gcp),
+        Some("cosmosGcp") => parse_signer!(cosmosGcp),
+        Some("pkcs11") => parse_signer!(pkcs11),
+        Some("cosmosPkcs11") => parse_signer!(cosmosPkcs11),
+        Some("remote") => parse_signer!(remote),
         Some(t) => {
             Err(eyre!("Unknown signer type `{t}`")).into_config_result(|| &signer.cwp + "type")
         }
@@ -361,6 +750,32 @@ fn parse_signer(signer: ValueParser) -> ConfigResult<SignerConf> {
     }
 }
 
+fn parse_pkcs11_key(signer: &ValueParser, err: &mut ConfigParsingError) -> Pkcs11KeyConf {
+    let module = signer
+        .chain(err)
+        .get_key("module")
+        .parse_string()
+        .unwrap_or("")
+        .into();
+    let mut parse_label = |key: &str| {
+        signer
+            .chain(err)
+            .get_key(key)
+            .parse_string()
+            .unwrap_or("")
+            .to_owned()
+    };
+    let token_label = parse_label("tokenLabel");
+    let key_label = parse_label("keyLabel");
+    let pin = parse_label("pin");
+    Pkcs11KeyConf {
+        module,
+        token_label,
+        key_label,
+        pin,
+    }
+}
+
 /// Parser for agent signers.
 #[derive(Debug, Deserialize)]
 #[serde(transparent)]
@@ -478,3 +893,141 @@ fn parse_base_and_override_urls(
     }
     combined
 }
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::KnownHyperlaneDomain;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_metrics_push_conf() {
+        let value = json!({"url": "http://localhost:9091", "interval": 30});
+        let conf = parse_metrics_push_conf(ValueParser::new(Default::default(), &value)).unwrap();
+        assert_eq!(
+            conf,
+            MetricsPushConf {
+                url: "http://localhost:9091".parse().unwrap(),
+                interval: Duration::from_secs(30),
+                max_retries: 3,
+            }
+        );
+
+        let value = json!({"url": "http://localhost:9091", "interval": 0});
+        let err = parse_metrics_push_conf(ValueParser::new(Default::default(), &value))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("interval"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_wallet_balance_watcher_conf() {
+        let parse = |value: serde_json::Value| {
+            parse_wallet_balance_watcher_conf(ValueParser::new(Default::default(), &value))
+        };
+
+        let conf = parse(json!({"interval": 30, "lowBalanceThresholds": {"ethereum": "0.5"}}));
+        assert_eq!(
+            conf.unwrap(),
+            WalletBalanceWatcherConf {
+                interval: Duration::from_secs(30),
+                low_balance_thresholds: HashMap::from([("ethereum".to_owned(), 0.5)]),
+            }
+        );
+
+        let conf = parse(json!({})).unwrap();
+        assert_eq!(conf.interval, Duration::from_secs(60));
+        assert!(conf.low_balance_thresholds.is_empty());
+
+        let err = parse(json!({"interval": 0})).unwrap_err().to_string();
+        assert!(err.contains("interval"), "{err}");
+
+        for invalid in [json!(-1), json!("NaN"), json!("inf"), json!("low")] {
+            let err = parse(json!({ "lowBalanceThresholds": { "ethereum": invalid } }))
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("lowBalanceThresholds.ethereum"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_parse_otlp_export_config() {
+        let parse = |value: serde_json::Value| {
+            parse_otlp_export_config(ValueParser::new(Default::default(), &value))
+        };
+
+        let conf = parse(json!({
+            "endpoint": "http://localhost:4317",
+            "headers": {"authorization": "Bearer token"}
+        }));
+        assert_eq!(
+            conf.unwrap(),
+            OtlpExportConfig {
+                endpoint: "http://localhost:4317".to_owned(),
+                headers: HashMap::from([("authorization".to_owned(), "Bearer token".to_owned())]),
+            }
+        );
+
+        let conf = parse(json!({"endpoint": "http://localhost:4317"})).unwrap();
+        assert!(conf.headers.is_empty());
+
+        let err = parse(json!({"headers": {}})).unwrap_err().to_string();
+        assert!(err.contains("endpoint"), "{err}");
+
+        let value = json!({"endpoint": "http://localhost:4317", "headers": {"token": 1}});
+        let err = parse(value).unwrap_err().to_string();
+        assert!(err.contains("headers.token"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_reorg_period() {
+        let ethereum = HyperlaneDomain::Known(KnownHyperlaneDomain::Ethereum);
+        let parse = |value: serde_json::Value| {
+            let mut err = ConfigParsingError::default();
+            let reorg_period = parse_reorg_period(
+                &ValueParser::new(Default::default(), &value),
+                Some(&ethereum),
+                &mut err,
+            );
+            (reorg_period, err)
+        };
+
+        let (reorg_period, err) = parse(json!({"blocks": {"reorgPeriod": 5}}));
+        assert!(err.is_ok());
+        assert_eq!(reorg_period, ReorgPeriod::from_blocks(5));
+
+        let (reorg_period, err) = parse(json!({}));
+        assert!(err.is_ok());
+        assert_eq!(reorg_period, RegistryDomain::by_id(1).unwrap().reorg_period);
+
+        let (_, err) = parse(json!({"blocks": {"reorgPeriod": true}}));
+        let err = err.to_string();
+        assert!(err.contains("`blocks.reorgPeriod`"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_remote_signer() {
+        let value = json!({
+            "type": "remote",
+            "url": "https://signer.example:8443",
+            "caCert": "/certs/ca.pem",
+            "clientCert": "/certs/client.pem",
+            "clientKey": "/certs/client.key",
+        });
+        let SignerConf::Remote { service } =
+            parse_signer(ValueParser::new(Default::default(), &value)).unwrap()
+        else {
+            panic!("Expected a remote signer");
+        };
+        assert_eq!(service.url.as_str(), "https://signer.example:8443/");
+        assert_eq!(service.client_key, PathBuf::from("/certs/client.key"));
+
+        let value = json!({"type": "remote", "caCert": "/certs/ca.pem"});
+        let err = parse_signer(ValueParser::new(Default::default(), &value))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`url`"), "{err}");
+        assert!(err.contains("`clientCert`"), "{err}");
+    }
+}
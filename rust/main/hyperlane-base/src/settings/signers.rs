@@ -1,12 +1,19 @@
 use async_trait::async_trait;
 use ed25519_dalek::SecretKey;
+use ethers::core::k256;
 use ethers::prelude::{AwsSigner, LocalWallet};
 use ethers::utils::hex::ToHex;
 use eyre::{bail, Context, Report};
-use hyperlane_core::{AccountAddressType, H256};
+use std::sync::Arc;
+
+use hyperlane_core::{AccountAddressType, ChainCommunicationError, ChainResult, H256};
+use hyperlane_ethereum::{
+    GcpKmsClient, GcpKmsSigner, Pkcs11Key, Pkcs11KeyConf, Pkcs11Signer, RemoteSigner,
+    RemoteSignerConf, RemoteSigningClient,
+};
 use hyperlane_sealevel::Keypair;
 use rusoto_core::Region;
-use rusoto_kms::KmsClient;
+use rusoto_kms::{GetPublicKeyRequest, Kms, KmsClient, SignRequest};
 use tracing::instrument;
 
 use super::aws_credentials::AwsChainCredentialsProvider;
@@ -28,6 +35,54 @@ pub enum SignerConf {
         /// The AWS region
         region: Region,
     },
+    /// A GCP Cloud KMS signer, authenticated as the workload the agent runs
+    /// as, e.g. with GKE workload identity
+    Gcp {
+        /// The resource name of the KMS key version
+        key_name: String,
+    },
+    /// A signer whose key is held in an HSM, accessed through PKCS#11
+    Pkcs11 {
+        /// The HSM key
+        key: Pkcs11KeyConf,
+    },
+    /// A signer whose key is held by a remote signing service, e.g. in an
+    /// enclave, which is called over mutual TLS
+    Remote {
+        /// The signing service
+        service: RemoteSignerConf,
+    },
+    /// A Cosmos signer whose key is held in AWS KMS. Note that AWS
+    /// credentials must be inserted into the env separately.
+    CosmosAws {
+        /// The UUID identifying the AWS KMS Key
+        id: String,
+        /// The AWS region
+        region: Region,
+        /// Prefix for cosmos address
+        prefix: String,
+        /// Account address type for cosmos address
+        account_address_type: AccountAddressType,
+    },
+    /// A Cosmos signer whose key is held in GCP Cloud KMS, authenticated as
+    /// the workload the agent runs as
+    CosmosGcp {
+        /// The resource name of the KMS key version
+        key_name: String,
+        /// Prefix for cosmos address
+        prefix: String,
+        /// Account address type for cosmos address
+        account_address_type: AccountAddressType,
+    },
+    /// A Cosmos signer whose key is held in an HSM, accessed through PKCS#11
+    CosmosPkcs11 {
+        /// The HSM key
+        key: Pkcs11KeyConf,
+        /// Prefix for cosmos address
+        prefix: String,
+        /// Account address type for cosmos address
+        account_address_type: AccountAddressType,
+    },
     /// Cosmos Specific key
     CosmosKey {
         /// Private key value
@@ -48,6 +103,17 @@ impl SignerConf {
     pub async fn build<S: BuildableWithSignerConf>(&self) -> Result<S, Report> {
         S::build(self).await
     }
+
+    /// The bech32 prefix of a Cosmos signer
+    pub fn cosmos_prefix(&self) -> Option<&str> {
+        match self {
+            SignerConf::CosmosKey { prefix, .. }
+            | SignerConf::CosmosAws { prefix, .. }
+            | SignerConf::CosmosGcp { prefix, .. }
+            | SignerConf::CosmosPkcs11 { prefix, .. } => Some(prefix),
+            _ => None,
+        }
+    }
 }
 
 /// A signer for a chain.
@@ -74,18 +140,25 @@ impl BuildableWithSignerConf for hyperlane_ethereum::Signers {
                 ),
             )),
             SignerConf::Aws { id, region } => {
-                let client = KmsClient::new_with_client(
-                    rusoto_core::Client::new_with(
-                        AwsChainCredentialsProvider::new(),
-                        utils::http_client_with_timeout().unwrap(),
-                    ),
-                    region.clone(),
-                );
-
-                let signer = AwsSigner::new(client, id, 0).await?;
+                let signer = AwsSigner::new(kms_client(region), id, 0).await?;
                 hyperlane_ethereum::Signers::Aws(signer)
             }
-            SignerConf::CosmosKey { .. } => {
+            SignerConf::Gcp { key_name } => {
+                let client = GcpKmsClient::with_shared_http_client(key_name)?;
+                hyperlane_ethereum::Signers::Gcp(GcpKmsSigner::new(client, 0).await?)
+            }
+            SignerConf::Pkcs11 { key } => {
+                let key = Pkcs11Key::open(key.clone()).await?;
+                hyperlane_ethereum::Signers::Pkcs11(Pkcs11Signer::new(key, 0).await?)
+            }
+            SignerConf::Remote { service } => {
+                let client = RemoteSigningClient::new(service)?;
+                hyperlane_ethereum::Signers::Remote(RemoteSigner::new(client, 0).await?)
+            }
+            SignerConf::CosmosKey { .. }
+            | SignerConf::CosmosAws { .. }
+            | SignerConf::CosmosGcp { .. }
+            | SignerConf::CosmosPkcs11 { .. } => {
                 bail!("cosmosKey signer is not supported by Ethereum")
             }
             SignerConf::Node => bail!("Node signer"),
@@ -144,25 +217,223 @@ impl ChainSigner for Keypair {
 #[async_trait]
 impl BuildableWithSignerConf for hyperlane_cosmos::Signer {
     async fn build(conf: &SignerConf) -> Result<Self, Report> {
-        if let SignerConf::CosmosKey {
-            key,
-            prefix,
-            account_address_type,
-        } = conf
-        {
-            Ok(hyperlane_cosmos::Signer::new(
+        match conf {
+            SignerConf::CosmosKey {
+                key,
+                prefix,
+                account_address_type,
+            } => Ok(hyperlane_cosmos::Signer::new(
                 key.as_bytes().to_vec(),
                 prefix.clone(),
                 account_address_type,
-            )?)
-        } else {
-            bail!(format!("{conf:?} key is not supported by cosmos"));
+            )?),
+            SignerConf::CosmosAws {
+                id,
+                region,
+                prefix,
+                account_address_type,
+            } => {
+                let key = AwsKmsCosmosKey {
+                    client: kms_client(region),
+                    key_id: id.clone(),
+                };
+                let public_key = key.public_key().await?;
+                Ok(hyperlane_cosmos::Signer::new_remote(
+                    &public_key,
+                    Arc::new(key),
+                    prefix.clone(),
+                    account_address_type,
+                )?)
+            }
+            SignerConf::CosmosGcp {
+                key_name,
+                prefix,
+                account_address_type,
+            } => {
+                let client = GcpKmsClient::with_shared_http_client(key_name)?;
+                let public_key = client
+                    .public_key()
+                    .await
+                    .context("Failed to fetch the public key of the KMS key")?;
+                Ok(hyperlane_cosmos::Signer::new_remote(
+                    &public_key,
+                    Arc::new(GcpKmsCosmosKey(client)),
+                    prefix.clone(),
+                    account_address_type,
+                )?)
+            }
+            SignerConf::CosmosPkcs11 {
+                key,
+                prefix,
+                account_address_type,
+            } => {
+                let key = Pkcs11Key::open(key.clone()).await?;
+                let public_key = key.public_key().await?;
+                Ok(hyperlane_cosmos::Signer::new_remote(
+                    &public_key,
+                    Arc::new(Pkcs11CosmosKey(key)),
+                    prefix.clone(),
+                    account_address_type,
+                )?)
+            }
+            _ => bail!(format!("{conf:?} key is not supported by cosmos")),
         }
     }
 }
 
+fn kms_client(region: &Region) -> KmsClient {
+    KmsClient::new_with_client(
+        rusoto_core::Client::new_with(
+            AwsChainCredentialsProvider::new(),
+            utils::http_client_with_timeout().unwrap(),
+        ),
+        region.clone(),
+    )
+}
+
+/// A secp256k1 key held in AWS KMS, which signs the digests of Cosmos
+/// transactions so that the key never leaves KMS
+#[derive(Clone)]
+struct AwsKmsCosmosKey {
+    client: KmsClient,
+    key_id: String,
+}
+
+impl std::fmt::Debug for AwsKmsCosmosKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AwsKmsCosmosKey")
+            .field("key_id", &self.key_id)
+            .finish()
+    }
+}
+
+impl AwsKmsCosmosKey {
+    /// The SEC1 encoded public key of the KMS key
+    async fn public_key(&self) -> Result<Vec<u8>, Report> {
+        let response = self
+            .client
+            .get_public_key(GetPublicKeyRequest {
+                key_id: self.key_id.clone(),
+                ..Default::default()
+            })
+            .await
+            .context("Failed to fetch the public key of the KMS key")?;
+        let der = response
+            .public_key
+            .ok_or_else(|| eyre::eyre!("KMS returned no public key"))?;
+        // The DER encoded SubjectPublicKeyInfo of a secp256k1 key ends with
+        // the 65 byte uncompressed SEC1 encoding of the key
+        let Some(sec1) = der.len().checked_sub(65).map(|start| &der[start..]) else {
+            bail!("Unexpected public key of the KMS key");
+        };
+        Ok(sec1.to_vec())
+    }
+}
+
+#[async_trait]
+impl hyperlane_cosmos::RemoteSigningKey for AwsKmsCosmosKey {
+    async fn sign_prehash(&self, digest: &[u8]) -> ChainResult<Vec<u8>> {
+        let response = self
+            .client
+            .sign(SignRequest {
+                key_id: self.key_id.clone(),
+                message: digest.to_vec().into(),
+                message_type: Some("DIGEST".to_owned()),
+                signing_algorithm: "ECDSA_SHA_256".to_owned(),
+                ..Default::default()
+            })
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        let der = response
+            .signature
+            .ok_or_else(|| ChainCommunicationError::from_other_str("KMS returned no signature"))?;
+        let signature =
+            k256::ecdsa::Signature::from_der(&der).map_err(ChainCommunicationError::from_other)?;
+        // Cosmos chains only accept signatures with a low `s`, which KMS
+        // doesn't guarantee
+        Ok(signature.normalize_s().unwrap_or(signature).to_vec())
+    }
+}
+
 impl ChainSigner for hyperlane_cosmos::Signer {
     fn address_string(&self) -> String {
         self.address.clone()
     }
 }
+
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_ton::TonSigner {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::HexKey { key } = conf {
+            Ok(hyperlane_ton::TonSigner::new(key.as_bytes()).context("Invalid TON signer key")?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by TON"));
+        }
+    }
+}
+
+impl ChainSigner for hyperlane_ton::TonSigner {
+    fn address_string(&self) -> String {
+        hyperlane_ton::TonSigner::address_string(self)
+    }
+}
+
+#[async_trait]
+impl BuildableWithSignerConf for hyperlane_aptos::AptosSigner {
+    async fn build(conf: &SignerConf) -> Result<Self, Report> {
+        if let SignerConf::HexKey { key } = conf {
+            Ok(hyperlane_aptos::AptosSigner::new(key.as_bytes())
+                .context("Invalid Aptos signer key")?)
+        } else {
+            bail!(format!("{conf:?} key is not supported by Aptos"));
+        }
+    }
+}
+
+impl ChainSigner for hyperlane_aptos::AptosSigner {
+    fn address_string(&self) -> String {
+        hyperlane_aptos::AptosSigner::address_string(self)
+    }
+}
+
+/// A secp256k1 key held in GCP Cloud KMS, which signs the digests of Cosmos
+/// transactions
+#[derive(Debug, Clone)]
+struct GcpKmsCosmosKey(GcpKmsClient);
+
+#[async_trait]
+impl hyperlane_cosmos::RemoteSigningKey for GcpKmsCosmosKey {
+    async fn sign_prehash(&self, digest: &[u8]) -> ChainResult<Vec<u8>> {
+        let digest: &[u8; 32] = digest
+            .try_into()
+            .map_err(|_| ChainCommunicationError::from_other_str("Digest must be 32 bytes"))?;
+        // The signature already has a low `s`
+        let signature = self
+            .0
+            .sign_digest(digest)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(signature.to_vec())
+    }
+}
+
+/// A secp256k1 key held in an HSM, which signs the digests of Cosmos
+/// transactions
+#[derive(Debug)]
+struct Pkcs11CosmosKey(Arc<Pkcs11Key>);
+
+#[async_trait]
+impl hyperlane_cosmos::RemoteSigningKey for Pkcs11CosmosKey {
+    async fn sign_prehash(&self, digest: &[u8]) -> ChainResult<Vec<u8>> {
+        let digest: [u8; 32] = digest
+            .try_into()
+            .map_err(|_| ChainCommunicationError::from_other_str("Digest must be 32 bytes"))?;
+        // The signature already has a low `s`
+        let signature = self
+            .0
+            .sign_secp256k1_digest(digest)
+            .await
+            .map_err(ChainCommunicationError::from_other)?;
+        Ok(signature.to_vec())
+    }
+}
@@ -1,4 +1,5 @@
 use eyre::Result;
+pub use otlp::OtlpExportConfig;
 pub use span_metrics::TimeSpanLifetime;
 use tracing_subscriber::{
     filter::{LevelFilter, Targets},
@@ -11,6 +12,7 @@ use crate::{settings::trace::fmt::Style, CoreMetrics};
 /// Configure a `tracing_subscriber::fmt` Layer outputting to stdout
 pub mod fmt;
 
+mod otlp;
 mod span_metrics;
 
 /// Logging level. A "higher level" means more will be logged.
@@ -55,11 +57,15 @@ pub struct TracingConfig {
     pub(crate) fmt: Style,
     #[serde(default)]
     pub(crate) level: Level,
+    /// Export spans to an OpenTelemetry collector, if set
+    #[serde(default)]
+    pub(crate) otlp: Option<OtlpExportConfig>,
 }
 
 impl TracingConfig {
     /// Attempt to instantiate and register a tracing subscriber setup from
-    /// settings.
+    /// settings. If spans are exported over OTLP, the global tracer provider
+    /// must be shut down before exiting to flush the pending spans.
     pub fn start_tracing(&self, metrics: &CoreMetrics) -> Result<console_subscriber::Server> {
         let mut target_layer = Targets::new().with_default(self.level);
 
@@ -87,6 +93,14 @@ impl TracingConfig {
         }
         let fmt_layer: LogOutputLayer<_> = self.fmt.into();
         let err_layer = tracing_error::ErrorLayer::default();
+        let otel_layer = match &self.otlp {
+            Some(otlp) => {
+                let (layer, provider) = otlp.layer(metrics.agent_name())?;
+                opentelemetry::global::set_tracer_provider(provider);
+                Some(layer)
+            }
+            None => None,
+        };
 
         let (tokio_layer, tokio_server) = console_subscriber::ConsoleLayer::new();
         let subscriber = tracing_subscriber::Registry::default()
@@ -94,6 +108,7 @@ impl TracingConfig {
             .with(target_layer)
             .with(TimeSpanLifetime::new(metrics))
             .with(fmt_layer)
+            .with(otel_layer)
             .with(err_layer);
 
         subscriber.try_init()?;
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use eyre::{Context, Result};
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig, WithTonicConfig};
+use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Configuration for exporting spans to an OpenTelemetry collector over OTLP
+#[derive(Debug, Clone, Default, serde::Deserialize, PartialEq, Eq)]
+pub struct OtlpExportConfig {
+    /// gRPC endpoint of the collector, e.g. `http://localhost:4317`
+    pub endpoint: String,
+    /// Headers sent with every export request, e.g. for authentication
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl OtlpExportConfig {
+    /// A layer exporting spans in batches, attributed to the service
+    /// `service_name`. The returned provider must be shut down to flush the
+    /// spans that weren't exported yet.
+    pub(crate) fn layer<S>(
+        &self,
+        service_name: &str,
+    ) -> Result<(
+        OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>,
+        TracerProvider,
+    )>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let mut metadata = MetadataMap::with_capacity(self.headers.len());
+        for (name, value) in &self.headers {
+            let name = MetadataKey::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid OTLP header name `{name}`"))?;
+            let value = MetadataValue::try_from(value.as_str())
+                .with_context(|| format!("Invalid value of OTLP header `{name}`"))?;
+            metadata.insert(name, value);
+        }
+
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(self.endpoint.clone())
+            .with_metadata(metadata)
+            .build()
+            .context("Failed to build OTLP span exporter")?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new([KeyValue::new(
+                "service.name",
+                service_name.to_owned(),
+            )]))
+            .build();
+        let tracer = provider.tracer("hyperlane-agent");
+        Ok((tracing_opentelemetry::layer().with_tracer(tracer), provider))
+    }
+}
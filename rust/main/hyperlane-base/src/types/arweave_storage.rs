@@ -0,0 +1,527 @@
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ethers::signers::{LocalWallet, Signer};
+use eyre::{bail, eyre, Context, Result};
+use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId, H256};
+use prometheus::IntGauge;
+use reqwest::{header::CONTENT_TYPE, Client};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256, Sha384};
+
+use crate::{AgentMetadata, CheckpointSyncer};
+
+/// Arweave gateway to query and fetch published objects from
+pub const ARWEAVE_GATEWAY_URL: &str = "ARWEAVE_GATEWAY_URL";
+/// The gateway used when none is configured
+pub const DEFAULT_ARWEAVE_GATEWAY_URL: &str = "https://arweave.net";
+/// The bundler used when none is configured, uploads of less than 100 KiB are
+/// free
+pub const DEFAULT_ARWEAVE_UPLOAD_URL: &str = "https://upload.ardrive.io/v1/tx";
+
+/// The timeout for bundler and gateway requests.
+const ARWEAVE_REQUEST_TIMEOUT_SECONDS: u64 = 60;
+/// ANS-104 signature type of secp256k1 keys signing EIP-191 messages.
+const ETHEREUM_SIGNATURE_TYPE: u16 = 3;
+const ETHEREUM_SIGNATURE_LENGTH: usize = 65;
+const APP_NAME: &str = "Hyperlane";
+const APP_NAME_TAG: &str = "App-Name";
+const KEY_TAG: &str = "Hyperlane-Key";
+/// Set on latest index objects, so readers can find the highest index
+/// without fetching every object.
+const INDEX_TAG: &str = "Hyperlane-Index";
+/// How many of the most recent latest index objects to consider, since
+/// objects in the same block are not ordered.
+const LATEST_INDEX_CANDIDATES: usize = 10;
+
+/// Type for publishing to and reading from Arweave.
+///
+/// Objects are uploaded as ANS-104 data items, signed with a secp256k1 key and
+/// tagged with their key, through a bundler. Readers look up the most recent
+/// data item with a key from the owner with the GraphQL API of a gateway, and
+/// fetch it from the gateway by id. Neither needs credentials.
+pub struct ArweaveStorage {
+    /// Arweave address of the key signing the data items.
+    owner: String,
+    /// The key signing data items, only required for writing.
+    signer: Option<LocalWallet>,
+    /// URL data items are posted to.
+    upload_url: String,
+    /// Base URL of the gateway to query and fetch data items from.
+    gateway_url: String,
+    client: Client,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+impl fmt::Debug for ArweaveStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArweaveStorage")
+            .field("owner", &self.owner)
+            .field("upload_url", &self.upload_url)
+            .field("gateway_url", &self.gateway_url)
+            .finish()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlResponse {
+    data: GraphqlData,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphqlData {
+    transactions: Transactions,
+}
+
+#[derive(Debug, Deserialize)]
+struct Transactions {
+    edges: Vec<TransactionEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionEdge {
+    node: Transaction,
+}
+
+#[derive(Debug, Deserialize)]
+struct Transaction {
+    id: String,
+    tags: Vec<Tag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+    value: String,
+}
+
+impl ArweaveStorage {
+    /// Create a client for the objects published by `owner`, able to write if
+    /// the `signer_key` of the owner is given.
+    pub fn new(
+        owner: String,
+        signer_key: Option<H256>,
+        upload_url: String,
+        gateway_url: String,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        let signer = signer_key.map(|key| signer_from_key(&key)).transpose()?;
+        if let Some(signer) = &signer {
+            let signer_owner = owner_address(&public_key(signer));
+            if signer_owner != owner {
+                bail!("Arweave signer key belongs to {signer_owner}, not the owner {owner}");
+            }
+        }
+        if URL_SAFE_NO_PAD.decode(&owner).ok().map(|owner| owner.len()) != Some(32) {
+            bail!("Invalid Arweave owner address '{owner}'");
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_secs(ARWEAVE_REQUEST_TIMEOUT_SECONDS))
+            .build()?;
+        Ok(Self {
+            owner,
+            signer,
+            upload_url,
+            gateway_url: gateway_url.trim_end_matches('/').to_owned(),
+            client,
+            latest_index,
+        })
+    }
+
+    /// The Arweave address of the owner of `key`.
+    pub fn owner_of_key(key: &H256) -> Result<String> {
+        Ok(owner_address(&public_key(&signer_from_key(key)?)))
+    }
+
+    /// Signs `body` as a data item tagged with `key` and `extra_tags` and
+    /// uploads it through the bundler.
+    async fn write_to_arweave(
+        &self,
+        key: &str,
+        body: String,
+        extra_tags: &[(&str, &str)],
+    ) -> Result<()> {
+        let signer = self
+            .signer
+            .as_ref()
+            .ok_or_else(|| eyre!("No Arweave signer key configured, can't write {key}"))?;
+        let mut tags = vec![
+            ("Content-Type", "application/json"),
+            (APP_NAME_TAG, APP_NAME),
+            (KEY_TAG, key),
+        ];
+        tags.extend_from_slice(extra_tags);
+        let data_item = sign_data_item(signer, &tags, body.as_bytes()).await?;
+        self.client
+            .post(&self.upload_url)
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(data_item)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Uploading {key} to Arweave"))?;
+        Ok(())
+    }
+
+    /// The most recent data items of the owner tagged with `key`, newest
+    /// first.
+    async fn find_transactions(&self, key: &str, first: usize) -> Result<Vec<Transaction>> {
+        let query = json!({
+            "query": "query($owner: String!, $key: String!, $first: Int!) { transactions(owners: [$owner], tags: [{ name: \"App-Name\", values: [\"Hyperlane\"] }, { name: \"Hyperlane-Key\", values: [$key] }], sort: HEIGHT_DESC, first: $first) { edges { node { id tags { name value } } } } }",
+            "variables": { "owner": self.owner, "key": key, "first": first },
+        });
+        let response = self
+            .client
+            .post(format!("{}/graphql", self.gateway_url))
+            .header(CONTENT_TYPE, "application/json")
+            .body(query.to_string())
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Looking up {key} of {} on Arweave", self.owner))?;
+        let response: GraphqlResponse = serde_json::from_slice(&response.bytes().await?)?;
+        Ok(response
+            .data
+            .transactions
+            .edges
+            .into_iter()
+            .map(|edge| edge.node)
+            .collect())
+    }
+
+    async fn read_from_arweave(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let Some(transaction) = self.find_transactions(key, 1).await?.into_iter().next() else {
+            return Ok(None);
+        };
+        let response = self
+            .client
+            .get(format!("{}/{}", self.gateway_url, transaction.id))
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Fetching {key} ({}) from Arweave", transaction.id))?;
+        Ok(Some(response.bytes().await?.to_vec()))
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn latest_index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
+
+    fn metadata_key() -> String {
+        "metadata_latest.json".to_owned()
+    }
+
+    fn announcement_key() -> String {
+        "announcement.json".to_owned()
+    }
+
+    fn reorg_flag_key() -> String {
+        "reorg_flag.json".to_owned()
+    }
+}
+
+fn signer_from_key(key: &H256) -> Result<LocalWallet> {
+    Ok(LocalWallet::from(
+        ethers::core::k256::ecdsa::SigningKey::from(
+            ethers::core::k256::SecretKey::from_be_bytes(key.as_bytes())
+                .context("Invalid Arweave signer key")?,
+        ),
+    ))
+}
+
+/// The uncompressed public key of `signer`, which is the owner of the data
+/// items it signs.
+fn public_key(signer: &LocalWallet) -> Vec<u8> {
+    signer
+        .signer()
+        .verifying_key()
+        .to_encoded_point(false)
+        .as_bytes()
+        .to_vec()
+}
+
+/// Arweave addresses are the base64url encoded sha256 of the owner.
+fn owner_address(owner: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(owner))
+}
+
+/// Builds a signed ANS-104 data item without target and anchor.
+async fn sign_data_item(
+    signer: &LocalWallet,
+    tags: &[(&str, &str)],
+    data: &[u8],
+) -> Result<Vec<u8>> {
+    let owner = public_key(signer);
+    let tag_count = tags.len();
+    let tags = serialize_tags(tags);
+    let message = deep_hash(&DeepHashChunk::List(vec![
+        DeepHashChunk::Blob(b"dataitem"),
+        DeepHashChunk::Blob(b"1"),
+        DeepHashChunk::Blob(ETHEREUM_SIGNATURE_TYPE.to_string().as_bytes()),
+        DeepHashChunk::Blob(&owner),
+        DeepHashChunk::Blob(&[]),
+        DeepHashChunk::Blob(&[]),
+        DeepHashChunk::Blob(&tags),
+        DeepHashChunk::Blob(data),
+    ]));
+    let signature = signer.sign_message(message).await?.to_vec();
+    debug_assert_eq!(signature.len(), ETHEREUM_SIGNATURE_LENGTH);
+
+    let mut item =
+        Vec::with_capacity(2 + signature.len() + owner.len() + 18 + tags.len() + data.len());
+    item.extend_from_slice(&ETHEREUM_SIGNATURE_TYPE.to_le_bytes());
+    item.extend_from_slice(&signature);
+    item.extend_from_slice(&owner);
+    // no target and no anchor
+    item.extend_from_slice(&[0, 0]);
+    item.extend_from_slice(&(tag_count as u64).to_le_bytes());
+    item.extend_from_slice(&(tags.len() as u64).to_le_bytes());
+    item.extend_from_slice(&tags);
+    item.extend_from_slice(data);
+    Ok(item)
+}
+
+/// Tags are serialized as an avro array of `{ name: bytes, value: bytes }`
+/// records.
+fn serialize_tags(tags: &[(&str, &str)]) -> Vec<u8> {
+    if tags.is_empty() {
+        return vec![];
+    }
+    let mut bytes = vec![];
+    encode_avro_long(&mut bytes, tags.len() as i64);
+    for (name, value) in tags {
+        encode_avro_long(&mut bytes, name.len() as i64);
+        bytes.extend_from_slice(name.as_bytes());
+        encode_avro_long(&mut bytes, value.len() as i64);
+        bytes.extend_from_slice(value.as_bytes());
+    }
+    // end of the array
+    bytes.push(0);
+    bytes
+}
+
+/// Zigzag encoded variable length integer.
+fn encode_avro_long(bytes: &mut Vec<u8>, value: i64) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+enum DeepHashChunk<'a> {
+    Blob(&'a [u8]),
+    List(Vec<DeepHashChunk<'a>>),
+}
+
+/// The Arweave deep hash of nested lists of byte strings, which is what data
+/// items sign.
+fn deep_hash(chunk: &DeepHashChunk) -> [u8; 48] {
+    match chunk {
+        DeepHashChunk::Blob(data) => {
+            let tag = Sha384::digest(format!("blob{}", data.len()));
+            let mut hasher = Sha384::new();
+            hasher.update(tag);
+            hasher.update(Sha384::digest(data));
+            hasher.finalize().into()
+        }
+        DeepHashChunk::List(chunks) => {
+            let mut acc: [u8; 48] = Sha384::digest(format!("list{}", chunks.len())).into();
+            for chunk in chunks {
+                let mut hasher = Sha384::new();
+                hasher.update(acc);
+                hasher.update(deep_hash(chunk));
+                acc = hasher.finalize().into();
+            }
+            acc
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointSyncer for ArweaveStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let latest_index = self
+            .find_transactions(&ArweaveStorage::latest_index_key(), LATEST_INDEX_CANDIDATES)
+            .await?
+            .into_iter()
+            .filter_map(|transaction| {
+                transaction
+                    .tags
+                    .into_iter()
+                    .find(|tag| tag.name == INDEX_TAG)
+                    .and_then(|tag| tag.value.parse::<u32>().ok())
+            })
+            .max();
+
+        if let (Some(latest_index), Some(gauge)) = (latest_index, &self.latest_index) {
+            gauge.set(latest_index as i64);
+        }
+
+        Ok(latest_index)
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let serialized_index = serde_json::to_string(&index)?;
+        self.write_to_arweave(
+            &ArweaveStorage::latest_index_key(),
+            serialized_index,
+            &[(INDEX_TAG, &index.to_string())],
+        )
+        .await
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read_from_arweave(&ArweaveStorage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let serialized_checkpoint = serde_json::to_string_pretty(signed_checkpoint)?;
+        self.write_to_arweave(
+            &ArweaveStorage::checkpoint_key(signed_checkpoint.value.index),
+            serialized_checkpoint,
+            &[],
+        )
+        .await
+    }
+
+    async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
+        let serialized_metadata = serde_json::to_string_pretty(metadata)?;
+        self.write_to_arweave(&ArweaveStorage::metadata_key(), serialized_metadata, &[])
+            .await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
+        self.write_to_arweave(
+            &ArweaveStorage::announcement_key(),
+            serialized_announcement,
+            &[],
+        )
+        .await
+    }
+
+    /// Formatted as `arweave://<owner address>`
+    fn announcement_location(&self) -> String {
+        format!("arweave://{}", self.owner)
+    }
+
+    async fn write_reorg_status(&self, reorged_event: &ReorgEvent) -> Result<()> {
+        let serialized_reorg = serde_json::to_string(reorged_event)?;
+        self.write_to_arweave(&ArweaveStorage::reorg_flag_key(), serialized_reorg, &[])
+            .await
+    }
+
+    async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
+        self.read_from_arweave(&ArweaveStorage::reorg_flag_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::types::Signature;
+
+    use super::*;
+
+    const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    #[test]
+    fn test_serialize_tags() {
+        assert_eq!(serialize_tags(&[("a", "b")]), vec![2, 2, b'a', 2, b'b', 0]);
+        assert!(serialize_tags(&[]).is_empty());
+        // counts and lengths are zigzag varints
+        let tags = serialize_tags(&[("a", "b"); 70]);
+        assert_eq!(&tags[..2], &[0x8c, 0x01]);
+    }
+
+    #[tokio::test]
+    async fn test_signed_data_item() {
+        let key: H256 = KEY.parse().unwrap();
+        let signer = signer_from_key(&key).unwrap();
+        let owner = public_key(&signer);
+        let tags = [("Hyperlane-Key", "announcement.json")];
+        let item = sign_data_item(&signer, &tags, b"{}").await.unwrap();
+
+        assert_eq!(&item[..2], &[3, 0]);
+        let (signature, rest) = item[2..].split_at(ETHEREUM_SIGNATURE_LENGTH);
+        let (item_owner, rest) = rest.split_at(owner.len());
+        assert_eq!(item_owner, owner.as_slice());
+        assert_eq!(&rest[..2], &[0, 0]);
+        assert_eq!(u64::from_le_bytes(rest[2..10].try_into().unwrap()), 1);
+        let serialized_tags = serialize_tags(&tags);
+        assert_eq!(
+            u64::from_le_bytes(rest[10..18].try_into().unwrap()),
+            serialized_tags.len() as u64
+        );
+        assert_eq!(
+            &rest[18..18 + serialized_tags.len()],
+            serialized_tags.as_slice()
+        );
+        assert_eq!(&rest[18 + serialized_tags.len()..], b"{}");
+
+        // The signature is an EIP-191 signature of the deep hash by the owner
+        let message = deep_hash(&DeepHashChunk::List(vec![
+            DeepHashChunk::Blob(b"dataitem"),
+            DeepHashChunk::Blob(b"1"),
+            DeepHashChunk::Blob(b"3"),
+            DeepHashChunk::Blob(&owner),
+            DeepHashChunk::Blob(&[]),
+            DeepHashChunk::Blob(&[]),
+            DeepHashChunk::Blob(&serialized_tags),
+            DeepHashChunk::Blob(b"{}"),
+        ]));
+        let signature = Signature::try_from(signature).unwrap();
+        assert_eq!(signature.recover(&message[..]).unwrap(), signer.address());
+    }
+
+    #[test]
+    fn test_owner_and_announcement_location() {
+        let key: H256 = KEY.parse().unwrap();
+        let owner = ArweaveStorage::owner_of_key(&key).unwrap();
+        assert_eq!(owner.len(), 43);
+
+        let storage = ArweaveStorage::new(
+            owner.clone(),
+            Some(key),
+            DEFAULT_ARWEAVE_UPLOAD_URL.to_owned(),
+            format!("{DEFAULT_ARWEAVE_GATEWAY_URL}/"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            storage.announcement_location(),
+            format!("arweave://{owner}")
+        );
+        assert_eq!(storage.gateway_url, DEFAULT_ARWEAVE_GATEWAY_URL);
+
+        // The key must belong to the owner
+        let other_owner = owner_address(b"other");
+        assert!(ArweaveStorage::new(
+            other_owner,
+            Some(key),
+            DEFAULT_ARWEAVE_UPLOAD_URL.to_owned(),
+            DEFAULT_ARWEAVE_GATEWAY_URL.to_owned(),
+            None,
+        )
+        .is_err());
+    }
+}
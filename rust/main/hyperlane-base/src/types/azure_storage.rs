@@ -0,0 +1,406 @@
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use eyre::{bail, eyre, Context, Result};
+use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::IntGauge;
+use reqwest::{header::CONTENT_TYPE, Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use crate::{AgentMetadata, CheckpointSyncer};
+
+/// Default Azure storage account, used for locations that don't name one
+pub const AZURE_STORAGE_ACCOUNT: &str = "AZURE_STORAGE_ACCOUNT";
+/// SAS token granting access to the Azure storage container
+pub const AZURE_STORAGE_SAS_TOKEN: &str = "AZURE_STORAGE_SAS_TOKEN";
+/// Set to `true` to authenticate with the managed identity of the Azure
+/// resource the agent is running on
+pub const AZURE_MANAGED_IDENTITY: &str = "AZURE_MANAGED_IDENTITY";
+/// Client id of the user-assigned managed identity to authenticate as.
+/// Defaults to the system-assigned identity when unset.
+pub const AZURE_CLIENT_ID: &str = "AZURE_CLIENT_ID";
+
+/// The timeout for Azure Blob Storage requests.
+const AZURE_REQUEST_TIMEOUT_SECONDS: u64 = 30;
+/// The Blob service REST API version; OAuth requires at least 2017-11-09.
+const AZURE_STORAGE_API_VERSION: &str = "2021-08-06";
+/// Instance metadata endpoint issuing managed identity tokens.
+const AZURE_IMDS_TOKEN_URL: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+const AZURE_STORAGE_RESOURCE: &str = "https://storage.azure.com/";
+/// Managed identity tokens are refreshed this long before they expire.
+const TOKEN_REFRESH_MARGIN_SECONDS: u64 = 300;
+
+/// How requests to Azure Blob Storage are authenticated
+#[derive(Clone)]
+pub enum AzureBlobAuth {
+    /// Anonymous access to a container with public read access - no writes
+    NoAuth,
+    /// A shared access signature, e.g. `sv=...&sig=...`
+    SasToken(String),
+    /// The managed identity of the Azure resource the agent is running on
+    ManagedIdentity {
+        /// Client id of a user-assigned identity, the system-assigned identity
+        /// is used if unset
+        client_id: Option<String>,
+    },
+}
+
+impl fmt::Debug for AzureBlobAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoAuth => write!(f, "NoAuth"),
+            // never log the signature
+            Self::SasToken(_) => write!(f, "SasToken(..)"),
+            Self::ManagedIdentity { client_id } => f
+                .debug_struct("ManagedIdentity")
+                .field("client_id", client_id)
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ManagedIdentityToken {
+    access_token: String,
+    /// Unix timestamp in seconds, serialized as a string
+    expires_on: String,
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    expires_on: u64,
+}
+
+/// Type for reading/writing to Azure Blob Storage
+pub struct AzureBlobStorage {
+    /// The storage account the container belongs to.
+    account: String,
+    /// The name of the container.
+    container: String,
+    /// A folder inside the container - defaults to the root of the container
+    folder: Option<String>,
+    auth: AzureBlobAuth,
+    client: Client,
+    /// The current managed identity token, if any.
+    token: Mutex<Option<CachedToken>>,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+impl fmt::Debug for AzureBlobStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzureBlobStorage")
+            .field("account", &self.account)
+            .field("container", &self.container)
+            .field("folder", &self.folder)
+            .field("auth", &self.auth)
+            .finish()
+    }
+}
+
+impl AzureBlobStorage {
+    /// Create a client for `container` in the storage `account`.
+    pub fn new(
+        account: String,
+        container: String,
+        folder: Option<String>,
+        auth: AzureBlobAuth,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        if account.is_empty() || container.is_empty() || container.contains('/') {
+            bail!("Invalid Azure storage account '{account}' or container '{container}'");
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_secs(AZURE_REQUEST_TIMEOUT_SECONDS))
+            .build()?;
+        Ok(Self {
+            account,
+            container,
+            folder: folder.map(|folder| folder.trim_matches('/').to_owned()),
+            auth,
+            client,
+            token: Mutex::new(None),
+            latest_index,
+        })
+    }
+
+    fn blob_url(&self, key: &str) -> String {
+        let url = format!(
+            "https://{}.blob.core.windows.net/{}/{}",
+            self.account,
+            self.container,
+            self.get_composite_key(key)
+        );
+        match &self.auth {
+            AzureBlobAuth::SasToken(sas) => format!("{url}?{}", sas.trim_start_matches('?')),
+            _ => url,
+        }
+    }
+
+    fn get_composite_key(&self, key: &str) -> String {
+        match self.folder.as_deref() {
+            None | Some("") => key.to_owned(),
+            Some(folder_str) => format!("{}/{}", folder_str, key),
+        }
+    }
+
+    /// Adds the headers required by the Blob service, including a bearer
+    /// token when authenticating with a managed identity.
+    async fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder> {
+        let request = request.header("x-ms-version", AZURE_STORAGE_API_VERSION);
+        Ok(match &self.auth {
+            AzureBlobAuth::ManagedIdentity { client_id } => {
+                request.bearer_auth(self.managed_identity_token(client_id.as_deref()).await?)
+            }
+            AzureBlobAuth::NoAuth | AzureBlobAuth::SasToken(_) => request,
+        })
+    }
+
+    /// Returns the cached managed identity token, fetching a new one from the
+    /// instance metadata service if it is missing or about to expire.
+    async fn managed_identity_token(&self, client_id: Option<&str>) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        if let Some(token) = cached.as_ref() {
+            if token.expires_on > now + TOKEN_REFRESH_MARGIN_SECONDS {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let mut query = vec![
+            ("api-version", "2018-02-01"),
+            ("resource", AZURE_STORAGE_RESOURCE),
+        ];
+        if let Some(client_id) = client_id {
+            query.push(("client_id", client_id));
+        }
+        let response = self
+            .client
+            .get(AZURE_IMDS_TOKEN_URL)
+            .header("Metadata", "true")
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Fetching Azure managed identity token")?;
+        let token: ManagedIdentityToken = serde_json::from_slice(&response.bytes().await?)?;
+        let token = CachedToken {
+            expires_on: token
+                .expires_on
+                .parse()
+                .context("Invalid managed identity token expiry")?,
+            access_token: token.access_token,
+        };
+        *cached = Some(token.clone());
+        Ok(token.access_token)
+    }
+
+    async fn write_to_container(&self, key: &str, body: String) -> Result<()> {
+        let request = self
+            .client
+            .put(self.blob_url(key))
+            .header("x-ms-blob-type", "BlockBlob")
+            .header(CONTENT_TYPE, "application/json")
+            .body(body);
+        self.authorize(request)
+            .await?
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(redact_sas_token)
+            .with_context(|| format!("Writing {key} to Azure container {}", self.container))?;
+        Ok(())
+    }
+
+    async fn read_from_container(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let request = self.client.get(self.blob_url(key));
+        let response = self
+            .authorize(request)
+            .await?
+            .send()
+            .await
+            .map_err(redact_sas_token)?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(
+                response.bytes().await.map_err(redact_sas_token)?.to_vec(),
+            )),
+            status => Err(eyre!(
+                "Reading {key} from Azure container {} failed with status {status}",
+                self.container
+            )),
+        }
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn latest_index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
+
+    fn metadata_key() -> String {
+        "metadata_latest.json".to_owned()
+    }
+
+    fn announcement_key() -> String {
+        "announcement.json".to_owned()
+    }
+
+    fn reorg_flag_key() -> String {
+        "reorg_flag.json".to_owned()
+    }
+}
+
+/// Blob URLs carry the SAS token in their query string, which reqwest
+/// includes when formatting its errors, so strip it before they get logged.
+fn redact_sas_token(mut err: reqwest::Error) -> reqwest::Error {
+    if let Some(url) = err.url_mut() {
+        url.set_query(None);
+    }
+    err
+}
+
+#[async_trait]
+impl CheckpointSyncer for AzureBlobStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let ret = self
+            .read_from_container(&AzureBlobStorage::latest_index_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into);
+
+        if let Ok(Some(latest_index)) = ret {
+            if let Some(gauge) = &self.latest_index {
+                gauge.set(latest_index as i64);
+            }
+        }
+
+        ret
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let serialized_index = serde_json::to_string(&index)?;
+        self.write_to_container(&AzureBlobStorage::latest_index_key(), serialized_index)
+            .await
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read_from_container(&AzureBlobStorage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let serialized_checkpoint = serde_json::to_string_pretty(signed_checkpoint)?;
+        self.write_to_container(
+            &AzureBlobStorage::checkpoint_key(signed_checkpoint.value.index),
+            serialized_checkpoint,
+        )
+        .await
+    }
+
+    async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
+        let serialized_metadata = serde_json::to_string_pretty(metadata)?;
+        self.write_to_container(&AzureBlobStorage::metadata_key(), serialized_metadata)
+            .await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
+        self.write_to_container(
+            &AzureBlobStorage::announcement_key(),
+            serialized_announcement,
+        )
+        .await
+    }
+
+    /// Formatted as `azblob://<container>@<account>/<folder>`
+    fn announcement_location(&self) -> String {
+        match self.folder.as_deref() {
+            None | Some("") => format!("azblob://{}@{}", self.container, self.account),
+            Some(folder_str) => {
+                format!(
+                    "azblob://{}@{}/{}",
+                    self.container, self.account, folder_str
+                )
+            }
+        }
+    }
+
+    async fn write_reorg_status(&self, reorged_event: &ReorgEvent) -> Result<()> {
+        let serialized_reorg = serde_json::to_string(reorged_event)?;
+        self.write_to_container(&AzureBlobStorage::reorg_flag_key(), serialized_reorg)
+            .await
+    }
+
+    async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
+        self.read_from_container(&AzureBlobStorage::reorg_flag_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_blob_urls_and_announcement_location() {
+        let storage = AzureBlobStorage::new(
+            "account".to_owned(),
+            "container".to_owned(),
+            Some("validator/".to_owned()),
+            AzureBlobAuth::SasToken("?sv=2022-11-02&sig=abc".to_owned()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            storage.blob_url("announcement.json"),
+            "https://account.blob.core.windows.net/container/validator/announcement.json?sv=2022-11-02&sig=abc"
+        );
+        assert_eq!(
+            storage.announcement_location(),
+            "azblob://container@account/validator"
+        );
+
+        assert!(AzureBlobStorage::new(
+            "account".to_owned(),
+            "container/folder".to_owned(),
+            None,
+            AzureBlobAuth::NoAuth,
+            None,
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_errors_do_not_leak_sas_token() {
+        // nothing listens on port 1, so the request fails to connect
+        let err = Client::new()
+            .get("http://127.0.0.1:1/container/announcement.json?sv=2022-11-02&sig=secret")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(format!("{err}").contains("sig=secret"));
+
+        let err = redact_sas_token(err);
+        let formatted = format!("{err} {err:?}");
+        assert!(!formatted.contains("sig=secret"));
+        assert!(formatted.contains("/container/announcement.json"));
+    }
+}
@@ -22,6 +22,9 @@ const REORG_FLAG_KEY: &str = "gcsReorgFlagKey";
 pub const GCS_USER_SECRET: &str = "GCS_USER_SECRET";
 /// Path to GCS Service account key
 pub const GCS_SERVICE_ACCOUNT_KEY: &str = "GCS_SERVICE_ACCOUNT_KEY";
+/// Set to `true` to authenticate with the credentials of the GCP workload
+/// the agent is running as (e.g. GKE workload identity)
+pub const GCS_WORKLOAD_IDENTITY: &str = "GCS_WORKLOAD_IDENTITY";
 
 /// Google Cloud Storage client builder
 /// Provide `AuthFlow::NoAuth` for no-auth access to public bucket
@@ -68,6 +71,21 @@ pub const GCS_SERVICE_ACCOUNT_KEY: &str = "GCS_SERVICE_ACCOUNT_KEY";
 ///        .await.expect("failed to instantiate anonymous client");
 /// #  }
 ///```
+/// # Example 4 - workload identity
+/// Uses the credentials of the service account the agent runs as, fetched from
+/// the GCP metadata server. This is how GKE workload identity is consumed.
+/// ```no_run
+///    use hyperlane_base::GcsStorageClientBuilder;
+///    use ya_gcp::{AuthFlow, ServiceAccountAuth};
+/// #  #[tokio::main]
+/// #  async fn main() {
+///    let auth = AuthFlow::ServiceAccount(ServiceAccountAuth::ApplicationDefault);
+///
+///    let client = GcsStorageClientBuilder::new(auth)
+///        .build("HyperlaneBucket", None)
+///        .await.expect("failed to instantiate workload identity client");
+/// #  }
+///```
 #[derive(Debug, new)]
 pub struct GcsStorageClientBuilder {
     auth: AuthFlow,
@@ -219,11 +237,8 @@ impl CheckpointSyncer for GcsStorageClient {
     /// Attempt to fetch the signed (checkpoint, messageId) tuple at this index
     #[instrument(skip(self, index))]
     async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
-        match self
-            .inner
-            .get_object(&self.bucket, GcsStorageClient::get_checkpoint_key(index))
-            .await
-        {
+        let object_name = self.object_path(&GcsStorageClient::get_checkpoint_key(index));
+        match self.inner.get_object(&self.bucket, object_name).await {
             Ok(data) => Ok(Some(serde_json::from_slice(data.as_ref())?)),
             Err(e) => match e {
                 ObjectError::Failure(Error::HttpStatus(HttpStatusError(StatusCode::NOT_FOUND))) => {
@@ -0,0 +1,395 @@
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use eyre::{bail, eyre, Context, Result};
+use hyperlane_core::{ReorgEvent, SignedAnnouncement, SignedCheckpointWithMessageId};
+use prometheus::IntGauge;
+use reqwest::{header::CONTENT_TYPE, Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{AgentMetadata, CheckpointSyncer};
+
+/// Bearer token for the IPFS pinning service
+pub const IPFS_PINNING_SERVICE_TOKEN: &str = "IPFS_PINNING_SERVICE_TOKEN";
+/// IPFS gateway to fetch published content from
+pub const IPFS_GATEWAY_URL: &str = "IPFS_GATEWAY_URL";
+/// The gateway used when none is configured
+pub const DEFAULT_IPFS_GATEWAY_URL: &str = "https://ipfs.io";
+
+/// The timeout for pinning service, gateway and IPFS node requests.
+const IPFS_REQUEST_TIMEOUT_SECONDS: u64 = 60;
+/// How long gateways may cache the published directory before resolving the
+/// IPNS name again.
+const IPNS_RECORD_TTL: &str = "1m";
+/// CIDv1 prefix of the `raw` codec with a sha2-256 multihash.
+const RAW_SHA256_CID_PREFIX: [u8; 4] = [0x01, 0x55, 0x12, 0x20];
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Type for publishing to and reading from IPFS.
+///
+/// Writers keep every object as a raw-leaf file in a directory of the mutable
+/// file system of their IPFS node, and publish the CID of that directory under
+/// an IPNS name after each write. Readers only need the IPNS name: they fetch
+/// objects from a public gateway, which resolves the name to the directory CID
+/// and serves the object by CID. The directory is optionally pinned with a
+/// pinning service so it stays available when the writer's node is offline.
+pub struct IpfsStorage {
+    /// The IPNS name the directory of objects is published under.
+    ipns_name: String,
+    /// Base URL of the RPC API of the IPFS node publishing content, only
+    /// required for writing.
+    api_url: Option<String>,
+    /// Base URL of the IPFS Pinning Service API to pin published directories
+    /// with, if any.
+    pinning_service_url: Option<String>,
+    /// Bearer token for the pinning service.
+    pinning_service_token: Option<String>,
+    /// Base URL of the gateway to fetch content from.
+    gateway_url: String,
+    client: Client,
+    /// The latest seen signed checkpoint index.
+    latest_index: Option<IntGauge>,
+}
+
+impl fmt::Debug for IpfsStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpfsStorage")
+            .field("ipns_name", &self.ipns_name)
+            .field("api_url", &self.api_url)
+            .field("pinning_service_url", &self.pinning_service_url)
+            .field("gateway_url", &self.gateway_url)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Pin {
+    cid: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FilesStatResponse {
+    hash: String,
+}
+
+impl IpfsStorage {
+    /// Create a client for the objects published under `ipns_name`.
+    pub fn new(
+        ipns_name: String,
+        api_url: Option<String>,
+        pinning_service_url: Option<String>,
+        pinning_service_token: Option<String>,
+        gateway_url: String,
+        latest_index: Option<IntGauge>,
+    ) -> Result<Self> {
+        if ipns_name.is_empty() || !ipns_name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            bail!("Invalid IPNS name '{ipns_name}', it must be a non-empty alphanumeric key id");
+        }
+        let client = Client::builder()
+            .timeout(Duration::from_secs(IPFS_REQUEST_TIMEOUT_SECONDS))
+            .build()?;
+        Ok(Self {
+            ipns_name,
+            api_url: api_url.map(|url| url.trim_end_matches('/').to_owned()),
+            pinning_service_url: pinning_service_url
+                .map(|url| url.trim_end_matches('/').to_owned()),
+            pinning_service_token,
+            gateway_url: gateway_url.trim_end_matches('/').to_owned(),
+            client,
+            latest_index,
+        })
+    }
+
+    /// The directory of the node's mutable file system objects are kept in.
+    fn directory(&self) -> String {
+        format!("/hyperlane/{}", self.ipns_name)
+    }
+
+    /// Sends a request to the RPC API of the IPFS node, which only accepts
+    /// POST requests.
+    async fn call_node(
+        &self,
+        command: &str,
+        query: &[(&str, &str)],
+        body: Option<(String, String)>,
+    ) -> Result<Response> {
+        let api_url = self
+            .api_url
+            .as_ref()
+            .ok_or_else(|| eyre!("No IPFS node API configured, can't call {command}"))?;
+        let mut request = self
+            .client
+            .post(format!("{api_url}/api/v0/{command}"))
+            .query(query);
+        if let Some((content_type, body)) = body {
+            request = request.header(CONTENT_TYPE, content_type).body(body);
+        }
+        Ok(request
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Calling {command} on the IPFS node"))?)
+    }
+
+    /// Writes `body` to the directory as a single raw block, publishes the
+    /// new directory CID under the IPNS name and pins it.
+    async fn write_to_ipfs(&self, key: &str, body: String) -> Result<()> {
+        let cid = raw_cid(body.as_bytes());
+        let path = format!("{}/{key}", self.directory());
+
+        // Write the content as a single raw block, so that its CID is derived
+        // from its hash alone and can be checked locally.
+        let boundary = format!("hyperlane-{cid}");
+        let multipart_body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{key}\"\r\nContent-Type: application/json\r\n\r\n{body}\r\n--{boundary}--\r\n"
+        );
+        self.call_node(
+            "files/write",
+            &[
+                ("arg", path.as_str()),
+                ("create", "true"),
+                ("parents", "true"),
+                ("truncate", "true"),
+                ("cid-version", "1"),
+                ("raw-leaves", "true"),
+            ],
+            Some((
+                format!("multipart/form-data; boundary={boundary}"),
+                multipart_body,
+            )),
+        )
+        .await?;
+        let written = self.files_stat(&path).await?;
+        if written != cid {
+            bail!("IPFS node stored {key} as {written}, expected {cid}");
+        }
+
+        let root = self.files_stat(&self.directory()).await?;
+        let ipfs_path = format!("/ipfs/{root}");
+        self.call_node(
+            "name/publish",
+            &[
+                ("arg", ipfs_path.as_str()),
+                ("key", self.ipns_name.as_str()),
+                ("ttl", IPNS_RECORD_TTL),
+                ("resolve", "false"),
+            ],
+            None,
+        )
+        .await
+        .with_context(|| format!("Publishing {root} under {}", self.ipns_name))?;
+
+        if let Some(pinning_service_url) = &self.pinning_service_url {
+            let pin = Pin {
+                cid: root,
+                name: self.ipns_name.clone(),
+            };
+            let mut request = self
+                .client
+                .post(format!("{pinning_service_url}/pins"))
+                .header(CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&pin)?);
+            if let Some(token) = &self.pinning_service_token {
+                request = request.bearer_auth(token);
+            }
+            request
+                .send()
+                .await?
+                .error_for_status()
+                .with_context(|| format!("Pinning {}", pin.cid))?;
+        }
+        Ok(())
+    }
+
+    /// The CID of a file or directory in the node's mutable file system.
+    async fn files_stat(&self, path: &str) -> Result<String> {
+        let response = self.call_node("files/stat", &[("arg", path)], None).await?;
+        let stat: FilesStatResponse = serde_json::from_slice(&response.bytes().await?)?;
+        Ok(stat.hash)
+    }
+
+    /// Fetches `key` from the directory published under the IPNS name
+    /// through the gateway. This needs no credentials, and the content
+    /// doesn't need to be verified against its CID since the objects that
+    /// matter are signed.
+    async fn read_from_ipfs(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let response = self.client.get(self.gateway_path(key)).send().await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+            status => Err(eyre!(
+                "Fetching {key} of {} from the IPFS gateway failed with status {status}",
+                self.ipns_name
+            )),
+        }
+    }
+
+    fn gateway_path(&self, key: &str) -> String {
+        format!("{}/ipns/{}/{key}", self.gateway_url, self.ipns_name)
+    }
+
+    fn checkpoint_key(index: u32) -> String {
+        format!("checkpoint_{index}_with_id.json")
+    }
+
+    fn latest_index_key() -> String {
+        "checkpoint_latest_index.json".to_owned()
+    }
+
+    fn metadata_key() -> String {
+        "metadata_latest.json".to_owned()
+    }
+
+    fn announcement_key() -> String {
+        "announcement.json".to_owned()
+    }
+
+    fn reorg_flag_key() -> String {
+        "reorg_flag.json".to_owned()
+    }
+}
+
+/// The base32 encoded CIDv1 of `data` stored as a single raw block.
+fn raw_cid(data: &[u8]) -> String {
+    let mut bytes = RAW_SHA256_CID_PREFIX.to_vec();
+    bytes.extend_from_slice(&Sha256::digest(data));
+    // The multibase prefix of lowercase base32 without padding
+    let mut cid = String::from("b");
+    for chunk in bytes.chunks(5) {
+        let mut buf = [0u8; 5];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let bits = buf.iter().fold(0u64, |acc, byte| (acc << 8) | *byte as u64);
+        let chars = (chunk.len() * 8).div_ceil(5);
+        for i in 0..chars {
+            cid.push(BASE32_ALPHABET[((bits >> (35 - i * 5)) & 0x1f) as usize] as char);
+        }
+    }
+    cid
+}
+
+#[async_trait]
+impl CheckpointSyncer for IpfsStorage {
+    async fn latest_index(&self) -> Result<Option<u32>> {
+        let ret = self
+            .read_from_ipfs(&IpfsStorage::latest_index_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into);
+
+        if let Ok(Some(latest_index)) = ret {
+            if let Some(gauge) = &self.latest_index {
+                gauge.set(latest_index as i64);
+            }
+        }
+
+        ret
+    }
+
+    async fn write_latest_index(&self, index: u32) -> Result<()> {
+        let serialized_index = serde_json::to_string(&index)?;
+        self.write_to_ipfs(&IpfsStorage::latest_index_key(), serialized_index)
+            .await
+    }
+
+    async fn fetch_checkpoint(&self, index: u32) -> Result<Option<SignedCheckpointWithMessageId>> {
+        self.read_from_ipfs(&IpfsStorage::checkpoint_key(index))
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    async fn write_checkpoint(
+        &self,
+        signed_checkpoint: &SignedCheckpointWithMessageId,
+    ) -> Result<()> {
+        let serialized_checkpoint = serde_json::to_string_pretty(signed_checkpoint)?;
+        self.write_to_ipfs(
+            &IpfsStorage::checkpoint_key(signed_checkpoint.value.index),
+            serialized_checkpoint,
+        )
+        .await
+    }
+
+    async fn write_metadata(&self, metadata: &AgentMetadata) -> Result<()> {
+        let serialized_metadata = serde_json::to_string_pretty(metadata)?;
+        self.write_to_ipfs(&IpfsStorage::metadata_key(), serialized_metadata)
+            .await
+    }
+
+    async fn write_announcement(&self, signed_announcement: &SignedAnnouncement) -> Result<()> {
+        let serialized_announcement = serde_json::to_string_pretty(signed_announcement)?;
+        self.write_to_ipfs(&IpfsStorage::announcement_key(), serialized_announcement)
+            .await
+    }
+
+    /// Formatted as `ipns://<ipns name>`
+    fn announcement_location(&self) -> String {
+        format!("ipns://{}", self.ipns_name)
+    }
+
+    async fn write_reorg_status(&self, reorged_event: &ReorgEvent) -> Result<()> {
+        let serialized_reorg = serde_json::to_string(reorged_event)?;
+        self.write_to_ipfs(&IpfsStorage::reorg_flag_key(), serialized_reorg)
+            .await
+    }
+
+    async fn reorg_status(&self) -> Result<Option<ReorgEvent>> {
+        self.read_from_ipfs(&IpfsStorage::reorg_flag_key())
+            .await?
+            .map(|data| serde_json::from_slice(&data))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_raw_cid() {
+        // `echo -n hello | ipfs add --cid-version 1 --raw-leaves -n -Q`
+        assert_eq!(
+            raw_cid(b"hello"),
+            "bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq"
+        );
+    }
+
+    #[test]
+    fn test_announcement_location() {
+        let ipns_name = "k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8";
+        let storage = IpfsStorage::new(
+            ipns_name.to_owned(),
+            None,
+            None,
+            None,
+            format!("{DEFAULT_IPFS_GATEWAY_URL}/"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            storage.announcement_location(),
+            format!("ipns://{ipns_name}")
+        );
+        assert_eq!(
+            storage.gateway_path("announcement.json"),
+            format!("https://ipfs.io/ipns/{ipns_name}/announcement.json")
+        );
+        assert_eq!(storage.directory(), format!("/hyperlane/{ipns_name}"));
+
+        assert!(IpfsStorage::new(
+            "validator/0x1234".to_owned(),
+            None,
+            None,
+            None,
+            DEFAULT_IPFS_GATEWAY_URL.to_owned(),
+            None,
+        )
+        .is_err());
+    }
+}
@@ -1,4 +1,7 @@
+mod arweave_storage;
+mod azure_storage;
 mod gcs_storage;
+mod ipfs_storage;
 mod local_storage;
 mod multisig;
 mod s3_storage;
@@ -6,7 +9,10 @@ mod s3_storage;
 /// Reusable logic for working with storage backends.
 pub mod utils;
 
+pub use arweave_storage::*;
+pub use azure_storage::*;
 pub use gcs_storage::*;
+pub use ipfs_storage::*;
 pub use local_storage::*;
 pub use multisig::*;
 pub use s3_storage::*;
@@ -6,7 +6,8 @@ use eyre::Result;
 use tracing::{debug, instrument, warn};
 
 use hyperlane_core::{
-    HyperlaneDomain, MultisigSignedCheckpoint, SignedCheckpointWithMessageId, H160, H256,
+    HyperlaneDomain, MultisigSignedCheckpoint, SignatureScheme, SignedCheckpointWithMessageId,
+    H160, H256,
 };
 
 use crate::{CheckpointSyncer, CoreMetrics};
@@ -176,6 +177,18 @@ impl MultisigCheckpointSyncer {
                         continue;
                     }
 
+                    // Validators of multisig ISMs sign with ECDSA, so checkpoints
+                    // signed with other schemes can't be used
+                    if signed_checkpoint.signature.scheme() != SignatureScheme::EcdsaSecp256k1 {
+                        debug!(
+                            validator = format!("{:#x}", validator),
+                            index = index,
+                            scheme = ?signed_checkpoint.signature.scheme(),
+                            "Checkpoint signature scheme unsupported"
+                        );
+                        continue;
+                    }
+
                     // Ensure that the signature is actually by the validator
                     let signer = signed_checkpoint.recover()?;
 
@@ -0,0 +1,86 @@
+//! Generates the table of registry domains from the chain configs exported
+//! from the Hyperlane registry into `rust/main/config`.
+
+use std::{collections::BTreeMap, env, fs, path::Path};
+
+use serde_json::Value;
+
+const REGISTRY_CONFIGS: [&str; 2] = ["mainnet_config.json", "testnet_config.json"];
+
+fn main() {
+    let config_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("../config");
+    let mut domains = BTreeMap::new();
+    for file in REGISTRY_CONFIGS {
+        let path = config_dir.join(file);
+        println!("cargo:rerun-if-changed={}", path.display());
+        // The configs are missing when the crate is built outside of the
+        // monorepo, in which case the table is empty.
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let config: Value = serde_json::from_str(&contents)
+            .unwrap_or_else(|err| panic!("Invalid registry config {}: {err}", path.display()));
+        let chains = config["chains"]
+            .as_object()
+            .unwrap_or_else(|| panic!("Missing chains in {}", path.display()));
+        for chain in chains.values() {
+            let testnet_config = file == "testnet_config.json";
+            if let Some((domain_id, entry)) = domain_entry(chain, testnet_config) {
+                domains.insert(domain_id, entry);
+            }
+        }
+    }
+
+    let table = format!(
+        "vec![\n{}]\n",
+        domains.into_values().collect::<Vec<_>>().join("")
+    );
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("registry_domains.rs");
+    fs::write(out, table).unwrap();
+}
+
+/// The table entry of a chain, or `None` if it uses a protocol agents don't
+/// support
+fn domain_entry(chain: &Value, testnet_config: bool) -> Option<(u64, String)> {
+    let name = chain["name"].as_str()?;
+    let domain_id = chain["domainId"].as_u64()?;
+    let protocol = match chain["protocol"].as_str()? {
+        "ethereum" => "Ethereum",
+        "fuel" => "Fuel",
+        "sealevel" => "Sealevel",
+        "cosmos" => "Cosmos",
+        _ => return None,
+    };
+    let technical_stack = match chain["technicalStack"].as_str() {
+        Some("arbitrumnitro") => "ArbitrumNitro",
+        Some("opstack") => "OpStack",
+        Some("polygoncdk") => "PolygonCDK",
+        Some("polkadotsubstrate") => "PolkadotSubstrate",
+        Some("zksync") => "ZkSync",
+        Some("linea") => "Linea",
+        Some("scroll") => "Scroll",
+        _ => "Other",
+    };
+    let domain_type = if chain["isTestnet"].as_bool().unwrap_or(testnet_config) {
+        "Testnet"
+    } else {
+        "Mainnet"
+    };
+    let reorg_period = match &chain["blocks"]["reorgPeriod"] {
+        Value::Number(blocks) => format!("ReorgPeriod::from_blocks({blocks})"),
+        Value::String(tag) => format!("ReorgPeriod::Tag({tag:?}.to_owned())"),
+        _ => "ReorgPeriod::None".to_owned(),
+    };
+    let entry = format!(
+        "    RegistryDomain {{
+        name: Cow::Borrowed({name:?}),
+        domain_id: {domain_id},
+        domain_type: HyperlaneDomainType::{domain_type},
+        domain_protocol: HyperlaneDomainProtocol::{protocol},
+        domain_technical_stack: HyperlaneDomainTechnicalStack::{technical_stack},
+        reorg_period: {reorg_period},
+    }},
+"
+    );
+    Some((domain_id, entry))
+}
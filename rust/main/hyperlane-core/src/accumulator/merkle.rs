@@ -249,6 +249,25 @@ impl MerkleTree {
         matches!(self, MerkleTree::Leaf(_))
     }
 
+    /// The leaves of this Merkle tree, from left to right. Zero subtrees
+    /// don't contribute any leaves.
+    pub fn leaves(&self) -> Vec<H256> {
+        let mut leaves = vec![];
+        self.collect_leaves(&mut leaves);
+        leaves
+    }
+
+    fn collect_leaves(&self, leaves: &mut Vec<H256>) {
+        match self {
+            MerkleTree::Leaf(leaf) => leaves.push(*leaf),
+            MerkleTree::Node(_, left, right) => {
+                left.collect_leaves(leaves);
+                right.collect_leaves(leaves);
+            }
+            MerkleTree::Zero(_) => {}
+        }
+    }
+
     /// Return the leaf at `index` and a Merkle proof of its inclusion.
     ///
     /// The Merkle proof is in "bottom-up" order, starting with a leaf node
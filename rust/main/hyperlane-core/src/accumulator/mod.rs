@@ -7,6 +7,11 @@ use crate::H256;
 pub mod incremental;
 /// A full incremental merkle. Suitable for running off-chain.
 pub mod merkle;
+/// Compact snapshots of merkle tree state, to restore it without replaying
+/// every insertion.
+pub mod snapshot;
+/// Proofs against previous roots of a merkle tree restored from snapshots.
+pub mod snapshot_prover;
 /// Utilities for manipulating proofs to reflect sparse merkle trees.
 pub mod sparse;
 
@@ -0,0 +1,229 @@
+use std::io::{Error as IoError, ErrorKind};
+
+use crate::{
+    accumulator::{
+        incremental::IncrementalMerkle,
+        merkle::{MerkleTree, MerkleTreeError},
+        TREE_DEPTH,
+    },
+    Decode, Encode, HyperlaneProtocolError, H256,
+};
+
+/// Version of the snapshot encoding, bumped whenever it changes
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// Upper bound of the leaves allocated up front when decoding, so that a
+/// corrupted count can't exhaust memory before the read fails
+const MAX_PREALLOCATED_LEAVES: usize = 1 << 16;
+
+/// A snapshot of a full merkle tree and the incremental tree tracking the same
+/// leaves.
+///
+/// Only the leaves and the branch of the incremental tree are stored, i.e. 32
+/// bytes per leaf. Restoring rebuilds the full tree in a single pass, which is
+/// much cheaper than ingesting the leaves one by one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTreeSnapshot {
+    /// The leaves of the tree, in insertion order
+    pub leaves: Vec<H256>,
+    /// The incremental tree
+    pub incremental: IncrementalMerkle,
+}
+
+impl MerkleTreeSnapshot {
+    /// Snapshot `tree` and `incremental`, which must track the same leaves
+    pub fn new(tree: &MerkleTree, incremental: &IncrementalMerkle) -> Self {
+        Self {
+            leaves: tree.leaves(),
+            incremental: incremental.clone(),
+        }
+    }
+
+    /// The number of leaves in the snapshot
+    pub fn count(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Rebuild the full tree and the incremental tree, checking that they
+    /// agree with each other
+    pub fn restore(&self) -> Result<(MerkleTree, IncrementalMerkle), MerkleTreeError> {
+        if self.incremental.count() != self.leaves.len() {
+            return Err(MerkleTreeError::Invalid);
+        }
+        let tree = MerkleTree::create(&self.leaves, TREE_DEPTH);
+        if tree.hash() != self.incremental.root() {
+            return Err(MerkleTreeError::Invalid);
+        }
+        Ok((tree, self.incremental.clone()))
+    }
+}
+
+impl Encode for MerkleTreeSnapshot {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let count = u32::try_from(self.leaves.len())
+            .map_err(|_| IoError::new(ErrorKind::InvalidInput, "too many leaves"))?;
+        writer.write_all(&[SNAPSHOT_VERSION])?;
+        writer.write_all(&count.to_be_bytes())?;
+        for leaf in &self.leaves {
+            writer.write_all(leaf.as_bytes())?;
+        }
+        for hash in self.incremental.branch() {
+            writer.write_all(hash.as_bytes())?;
+        }
+        Ok(1 + 4 + self.leaves.len() * 32 + TREE_DEPTH * 32)
+    }
+}
+
+impl Decode for MerkleTreeSnapshot {
+    /// Leaves are read one at a time, so a snapshot can be restored straight
+    /// from a file or a network stream.
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != SNAPSHOT_VERSION {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                format!("unsupported merkle tree snapshot version {}", version[0]),
+            )
+            .into());
+        }
+
+        let mut count_bytes = [0u8; 4];
+        reader.read_exact(&mut count_bytes)?;
+        let count = u32::from_be_bytes(count_bytes) as usize;
+
+        let mut leaves = Vec::with_capacity(count.min(MAX_PREALLOCATED_LEAVES));
+        for _ in 0..count {
+            let mut leaf = H256::zero();
+            reader.read_exact(leaf.as_bytes_mut())?;
+            leaves.push(leaf);
+        }
+
+        let mut branch = [H256::zero(); TREE_DEPTH];
+        for hash in &mut branch {
+            reader.read_exact(hash.as_bytes_mut())?;
+        }
+
+        Ok(Self {
+            leaves,
+            incremental: IncrementalMerkle::new(branch, count),
+        })
+    }
+}
+
+/// The incremental tree of a snapshot is stored apart from its leaves, so
+/// that persisting a snapshot only appends the new leaves
+impl Encode for IncrementalMerkle {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let count = u32::try_from(self.count)
+            .map_err(|_| IoError::new(ErrorKind::InvalidInput, "too many leaves"))?;
+        writer.write_all(&[SNAPSHOT_VERSION])?;
+        writer.write_all(&count.to_be_bytes())?;
+        for hash in self.branch() {
+            writer.write_all(hash.as_bytes())?;
+        }
+        Ok(1 + 4 + TREE_DEPTH * 32)
+    }
+}
+
+impl Decode for IncrementalMerkle {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != SNAPSHOT_VERSION {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                format!("unsupported incremental merkle tree version {}", version[0]),
+            )
+            .into());
+        }
+
+        let mut count_bytes = [0u8; 4];
+        reader.read_exact(&mut count_bytes)?;
+        let count = u32::from_be_bytes(count_bytes) as usize;
+
+        let mut branch = [H256::zero(); TREE_DEPTH];
+        for hash in &mut branch {
+            reader.read_exact(hash.as_bytes_mut())?;
+        }
+        Ok(IncrementalMerkle::new(branch, count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trees(count: u8) -> (MerkleTree, IncrementalMerkle) {
+        let leaves: Vec<H256> = (0..count).map(|i| H256::repeat_byte(i + 1)).collect();
+        let mut incremental = IncrementalMerkle::default();
+        for leaf in &leaves {
+            incremental.ingest(*leaf);
+        }
+        (MerkleTree::create(&leaves, TREE_DEPTH), incremental)
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        for count in [0, 1, 7, 64] {
+            let (tree, incremental) = trees(count);
+            let encoded = MerkleTreeSnapshot::new(&tree, &incremental).to_vec();
+            assert_eq!(encoded.len(), 1 + 4 + count as usize * 32 + TREE_DEPTH * 32);
+
+            let snapshot = MerkleTreeSnapshot::read_from(&mut encoded.as_slice()).unwrap();
+            assert_eq!(snapshot.count(), count as usize);
+            let (restored_tree, restored_incremental) = snapshot.restore().unwrap();
+            assert_eq!(restored_tree, tree);
+            assert_eq!(restored_incremental, incremental);
+        }
+    }
+
+    #[test]
+    fn test_restore_rejects_mismatched_trees() {
+        let (tree, _) = trees(5);
+        let (_, incremental) = trees(4);
+        assert_eq!(
+            MerkleTreeSnapshot::new(&tree, &incremental).restore(),
+            Err(MerkleTreeError::Invalid)
+        );
+
+        let (tree, mut incremental) = trees(5);
+        incremental.branch[0] = H256::zero();
+        assert_eq!(
+            MerkleTreeSnapshot::new(&tree, &incremental).restore(),
+            Err(MerkleTreeError::Invalid)
+        );
+    }
+
+    #[test]
+    fn test_incremental_roundtrip() {
+        let (_, incremental) = trees(9);
+        let encoded = incremental.to_vec();
+        assert_eq!(encoded.len(), 1 + 4 + TREE_DEPTH * 32);
+        assert_eq!(
+            IncrementalMerkle::read_from(&mut encoded.as_slice()).unwrap(),
+            incremental
+        );
+    }
+
+    #[test]
+    fn test_truncated_snapshot_fails() {
+        let (tree, incremental) = trees(3);
+        let encoded = MerkleTreeSnapshot::new(&tree, &incremental).to_vec();
+        assert!(MerkleTreeSnapshot::read_from(&mut &encoded[..encoded.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use thiserror::Error;
+use tracing::warn;
+
+use crate::{
+    accumulator::{
+        incremental::IncrementalMerkle,
+        merkle::{MerkleTree, MerkleTreeError, Proof},
+        snapshot::MerkleTreeSnapshot,
+        TREE_DEPTH,
+    },
+    H256,
+};
+
+/// Persistent storage of the snapshot of the merkle tree of an origin, e.g.
+/// the database of that origin.
+///
+/// Snapshots are stored incrementally: the leaves are appended as they're
+/// persisted, and the incremental tree, whose count is the number of leaves
+/// in the snapshot, is replaced. Stored leaves past that count, e.g. after a
+/// rewind, are ignored and overwritten by later leaves.
+pub trait MerkleTreeSnapshotStore {
+    /// Error of the storage
+    type Error;
+
+    /// Store `leaves` as the leaves of the snapshot from `index` onwards,
+    /// and replace the stored incremental tree with `incremental`, at once
+    fn store_merkle_tree_snapshot(
+        &self,
+        index: u32,
+        leaves: &[H256],
+        incremental: &IncrementalMerkle,
+    ) -> Result<(), Self::Error>;
+
+    /// The stored incremental tree, if any
+    fn retrieve_merkle_tree_snapshot_incremental(
+        &self,
+    ) -> Result<Option<IncrementalMerkle>, Self::Error>;
+
+    /// The stored leaf at `index`, if any
+    fn retrieve_merkle_tree_snapshot_leaf(&self, index: u32) -> Result<Option<H256>, Self::Error>;
+}
+
+/// Errors generating proofs with a [`SnapshotProver`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum SnapshotProverError {
+    /// The checkpoint is of a tree without the leaf
+    #[error("Leaf {leaf_index} isn't in the tree of checkpoint {checkpoint_index}")]
+    LeafAfterCheckpoint {
+        /// Index of the leaf to prove
+        leaf_index: u32,
+        /// Index of the last leaf of the checkpoint
+        checkpoint_index: u32,
+    },
+    /// The checkpoint is of a tree with more leaves than were ingested
+    #[error("Checkpoint {checkpoint_index} is ahead of the tree with {count} leaves")]
+    CheckpointNotIngested {
+        /// Index of the last leaf of the checkpoint
+        checkpoint_index: u32,
+        /// Number of leaves ingested
+        count: usize,
+    },
+}
+
+/// A merkle tree that proves leaves against any of its previous roots, i.e.
+/// checkpoints, and is restored from a snapshot after restarts, so that only
+/// the leaves inserted since the snapshot are ingested again.
+#[derive(Debug, Clone)]
+pub struct SnapshotProver {
+    tree: MerkleTree,
+    incremental: IncrementalMerkle,
+    /// Number of leaves in the last persisted snapshot
+    persisted_count: usize,
+    /// Leaves ingested since the last persisted snapshot
+    unpersisted_leaves: Vec<H256>,
+}
+
+impl Default for SnapshotProver {
+    fn default() -> Self {
+        Self {
+            tree: MerkleTree::create(&[], TREE_DEPTH),
+            incremental: IncrementalMerkle::default(),
+            persisted_count: 0,
+            unpersisted_leaves: vec![],
+        }
+    }
+}
+
+impl SnapshotProver {
+    /// Restore the tree from the snapshot in `store`. Without a snapshot, or
+    /// with one that doesn't restore a consistent tree, the tree is empty.
+    pub fn restore<S: MerkleTreeSnapshotStore>(store: &S) -> Result<Self, S::Error> {
+        let Some(incremental) = store.retrieve_merkle_tree_snapshot_incremental()? else {
+            return Ok(Self::default());
+        };
+        let mut leaves = Vec::with_capacity(incremental.count());
+        for index in 0..incremental.count() as u32 {
+            let Some(leaf) = store.retrieve_merkle_tree_snapshot_leaf(index)? else {
+                warn!(
+                    index,
+                    count = incremental.count(),
+                    "Discarding merkle tree snapshot with a missing leaf"
+                );
+                return Ok(Self::default());
+            };
+            leaves.push(leaf);
+        }
+        let snapshot = MerkleTreeSnapshot {
+            leaves,
+            incremental,
+        };
+        match snapshot.restore() {
+            Ok((tree, incremental)) => Ok(Self {
+                tree,
+                incremental,
+                persisted_count: snapshot.count(),
+                unpersisted_leaves: vec![],
+            }),
+            Err(err) => {
+                warn!(
+                    ?err,
+                    count = snapshot.count(),
+                    "Discarding invalid merkle tree snapshot"
+                );
+                Ok(Self::default())
+            }
+        }
+    }
+
+    /// The number of leaves ingested
+    pub fn count(&self) -> usize {
+        self.incremental.count()
+    }
+
+    /// The current root
+    pub fn root(&self) -> H256 {
+        self.incremental.root()
+    }
+
+    /// The number of leaves ingested since the last persisted snapshot
+    pub fn unpersisted_count(&self) -> usize {
+        self.unpersisted_leaves.len()
+    }
+
+    /// Ingest the next leaf
+    pub fn ingest(&mut self, leaf: H256) -> Result<(), MerkleTreeError> {
+        self.tree.push_leaf(leaf, TREE_DEPTH)?;
+        self.incremental.ingest(leaf);
+        if self.tree.hash() != self.incremental.root() {
+            return Err(MerkleTreeError::Invalid);
+        }
+        self.unpersisted_leaves.push(leaf);
+        Ok(())
+    }
+
+    /// Remove the leaves from `count` onwards, e.g. because their insertions
+    /// were reorged out of the chain. The tree is rebuilt from the remaining
+    /// leaves, and must be persisted again for the removal to survive a
+    /// restart.
+    pub fn truncate(&mut self, count: usize) {
+        if count >= self.count() {
+            return;
+        }
+        let leaves = self.tree.leaves();
+        let leaves = &leaves[..count];
+        self.tree = MerkleTree::create(leaves, TREE_DEPTH);
+        self.incremental = IncrementalMerkle::default();
+        for leaf in leaves {
+            self.incremental.ingest(*leaf);
+        }
+        if count < self.persisted_count {
+            self.persisted_count = count;
+            self.unpersisted_leaves.clear();
+        } else {
+            self.unpersisted_leaves
+                .truncate(count - self.persisted_count);
+        }
+    }
+
+    /// Persist the tree to `store`, which only appends the leaves ingested
+    /// since the last snapshot
+    pub fn persist<S: MerkleTreeSnapshotStore>(&mut self, store: &S) -> Result<(), S::Error> {
+        store.store_merkle_tree_snapshot(
+            self.persisted_count as u32,
+            &self.unpersisted_leaves,
+            &self.incremental,
+        )?;
+        self.persisted_count = self.count();
+        self.unpersisted_leaves.clear();
+        Ok(())
+    }
+
+    /// Prove the leaf at `leaf_index` against the root of the tree after the
+    /// leaf at `checkpoint_index` was inserted
+    pub fn prove(
+        &self,
+        leaf_index: u32,
+        checkpoint_index: u32,
+    ) -> Result<Proof, SnapshotProverError> {
+        if leaf_index > checkpoint_index {
+            return Err(SnapshotProverError::LeafAfterCheckpoint {
+                leaf_index,
+                checkpoint_index,
+            });
+        }
+        if checkpoint_index as usize >= self.count() {
+            return Err(SnapshotProverError::CheckpointNotIngested {
+                checkpoint_index,
+                count: self.count(),
+            });
+        }
+        Ok(self
+            .tree
+            .prove_against_previous(leaf_index as usize, checkpoint_index as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::accumulator::merkle::verify_merkle_proof;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        leaves: RefCell<Vec<H256>>,
+        incremental: RefCell<Option<IncrementalMerkle>>,
+        /// Number of leaves written to the store
+        written: RefCell<usize>,
+    }
+
+    impl MerkleTreeSnapshotStore for MemoryStore {
+        type Error = ();
+
+        fn store_merkle_tree_snapshot(
+            &self,
+            index: u32,
+            leaves: &[H256],
+            incremental: &IncrementalMerkle,
+        ) -> Result<(), ()> {
+            let mut stored = self.leaves.borrow_mut();
+            stored.truncate(index as usize);
+            stored.extend_from_slice(leaves);
+            *self.written.borrow_mut() += leaves.len();
+            *self.incremental.borrow_mut() = Some(incremental.clone());
+            Ok(())
+        }
+
+        fn retrieve_merkle_tree_snapshot_incremental(
+            &self,
+        ) -> Result<Option<IncrementalMerkle>, ()> {
+            Ok(self.incremental.borrow().clone())
+        }
+
+        fn retrieve_merkle_tree_snapshot_leaf(&self, index: u32) -> Result<Option<H256>, ()> {
+            Ok(self.leaves.borrow().get(index as usize).copied())
+        }
+    }
+
+    #[cfg(feature = "ethers")]
+    #[test]
+    fn it_produces_and_verifies_proofs() {
+        use ethers_core::utils::hash_message;
+
+        use crate::{accumulator::merkle::merkle_root_from_branch, test_utils};
+
+        for test_case in test_utils::load_merkle_test_json() {
+            let mut prover = SnapshotProver::default();
+            for leaf in &test_case.leaves {
+                prover.ingest(hash_message(leaf).into()).unwrap();
+            }
+            assert_eq!(prover.count(), test_case.leaves.len());
+            assert_eq!(prover.root(), test_case.expected_root);
+
+            let latest = prover.count() as u32 - 1;
+            for (n, expected) in test_case.proofs.iter().enumerate() {
+                let proof = prover.prove(n as u32, latest).unwrap();
+                assert_eq!(&proof, expected);
+                assert_eq!(
+                    merkle_root_from_branch(proof.leaf, &proof.path, TREE_DEPTH, proof.index),
+                    prover.root()
+                );
+            }
+        }
+    }
+
+    fn leaf(i: u8) -> H256 {
+        H256::repeat_byte(i + 1)
+    }
+
+    #[test]
+    fn test_prove_after_restore() {
+        let store = MemoryStore::default();
+        let mut prover = SnapshotProver::restore(&store).unwrap();
+        let mut roots = vec![];
+        for i in 0..10 {
+            prover.ingest(leaf(i)).unwrap();
+            roots.push(prover.root());
+        }
+        prover.persist(&store).unwrap();
+        assert_eq!(prover.unpersisted_count(), 0);
+
+        let mut restored = SnapshotProver::restore(&store).unwrap();
+        assert_eq!(restored.count(), 10);
+        assert_eq!(restored.root(), prover.root());
+        for i in 10..15 {
+            restored.ingest(leaf(i)).unwrap();
+            roots.push(restored.root());
+        }
+        assert_eq!(restored.unpersisted_count(), 5);
+
+        for (checkpoint_index, root) in roots.iter().enumerate() {
+            for leaf_index in 0..=checkpoint_index {
+                let proof = restored
+                    .prove(leaf_index as u32, checkpoint_index as u32)
+                    .unwrap();
+                assert!(verify_merkle_proof(
+                    proof.leaf,
+                    &proof.path,
+                    TREE_DEPTH,
+                    leaf_index,
+                    *root
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut prover = SnapshotProver::default();
+        let mut roots = vec![];
+        for i in 0..5 {
+            prover.ingest(leaf(i)).unwrap();
+            roots.push(prover.root());
+        }
+        prover.truncate(3);
+        assert_eq!(prover.count(), 3);
+        assert_eq!(prover.root(), roots[2]);
+
+        // Leaves of the canonical chain are ingested in place of the removed ones
+        prover.ingest(leaf(9)).unwrap();
+        assert_eq!(prover.count(), 4);
+        assert_ne!(prover.root(), roots[3]);
+        assert!(prover.prove(2, 3).is_ok());
+    }
+
+    #[test]
+    fn test_prove_rejects_invalid_indices() {
+        let mut prover = SnapshotProver::default();
+        prover.ingest(leaf(0)).unwrap();
+        prover.ingest(leaf(1)).unwrap();
+        assert_eq!(
+            prover.prove(1, 0),
+            Err(SnapshotProverError::LeafAfterCheckpoint {
+                leaf_index: 1,
+                checkpoint_index: 0
+            })
+        );
+        assert_eq!(
+            prover.prove(0, 2),
+            Err(SnapshotProverError::CheckpointNotIngested {
+                checkpoint_index: 2,
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_persist_appends_leaves() {
+        let store = MemoryStore::default();
+        let mut prover = SnapshotProver::default();
+        for i in 0..5 {
+            prover.ingest(leaf(i)).unwrap();
+        }
+        prover.persist(&store).unwrap();
+        for i in 5..7 {
+            prover.ingest(leaf(i)).unwrap();
+        }
+        prover.persist(&store).unwrap();
+        // Persisting without new leaves only replaces the incremental tree
+        prover.persist(&store).unwrap();
+        assert_eq!(*store.written.borrow(), 7);
+
+        let restored = SnapshotProver::restore(&store).unwrap();
+        assert_eq!(restored.count(), 7);
+        assert_eq!(restored.root(), prover.root());
+    }
+
+    #[test]
+    fn test_truncated_tree_is_persisted() {
+        let store = MemoryStore::default();
+        let mut prover = SnapshotProver::default();
+        for i in 0..5 {
+            prover.ingest(leaf(i)).unwrap();
+        }
+        prover.persist(&store).unwrap();
+        prover.ingest(leaf(5)).unwrap();
+
+        prover.truncate(3);
+        assert_eq!(prover.unpersisted_count(), 0);
+        prover.ingest(leaf(9)).unwrap();
+        assert_eq!(prover.unpersisted_count(), 1);
+        prover.persist(&store).unwrap();
+
+        let restored = SnapshotProver::restore(&store).unwrap();
+        assert_eq!(restored.count(), 4);
+        assert_eq!(restored.root(), prover.root());
+
+        // Leaves that weren't persisted yet are dropped without a rewrite
+        prover.ingest(leaf(10)).unwrap();
+        prover.ingest(leaf(11)).unwrap();
+        prover.truncate(5);
+        assert_eq!(prover.unpersisted_count(), 1);
+    }
+
+    #[test]
+    fn test_restore_discards_invalid_snapshot() {
+        let store = MemoryStore::default();
+        let mut prover = SnapshotProver::default();
+        prover.ingest(leaf(0)).unwrap();
+        prover.ingest(leaf(1)).unwrap();
+        prover.persist(&store).unwrap();
+        store.leaves.borrow_mut()[0] = leaf(2);
+        assert_eq!(SnapshotProver::restore(&store).unwrap().count(), 0);
+
+        store.leaves.borrow_mut().truncate(1);
+        assert_eq!(SnapshotProver::restore(&store).unwrap().count(), 0);
+    }
+}
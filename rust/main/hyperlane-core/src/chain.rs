@@ -18,6 +18,10 @@ use crate::{
     utils::many_to_one, ChainCommunicationError, HyperlaneProtocolError, IndexMode, H160, H256,
 };
 
+pub use self::registry::*;
+
+mod registry;
+
 #[derive(Debug, Clone)]
 pub struct Address(pub bytes::Bytes);
 
@@ -276,6 +280,10 @@ pub enum HyperlaneDomainProtocol {
     Sealevel,
     /// A Cosmos-based chain type which uses hyperlane-cosmos.
     Cosmos,
+    /// A TON-based chain type which uses hyperlane-ton.
+    Ton,
+    /// An Aptos-based chain type which uses hyperlane-aptos.
+    Aptos,
 }
 
 impl HyperlaneDomainProtocol {
@@ -286,6 +294,8 @@ impl HyperlaneDomainProtocol {
             Fuel => format!("{:?}", addr),
             Sealevel => format!("{:?}", addr),
             Cosmos => format!("{:?}", addr),
+            Ton => format!("{:?}", addr),
+            Aptos => format!("{:?}", addr),
         }
     }
 }
@@ -306,6 +316,8 @@ pub enum HyperlaneDomainTechnicalStack {
     PolygonCDK,
     PolkadotSubstrate,
     ZkSync,
+    Linea,
+    Scroll,
     #[default]
     Other,
 }
@@ -393,9 +405,15 @@ impl KnownHyperlaneDomain {
             HyperlaneDomainTechnicalStack::ZkSync: [
                 Abstracttestnet, Treasure, Treasuretopaz, Zeronetwork, Zklink, Zksync,
             ],
+            HyperlaneDomainTechnicalStack::Linea: [
+                Linea
+            ],
+            HyperlaneDomainTechnicalStack::Scroll: [
+                ScrollSepolia
+            ],
             HyperlaneDomainTechnicalStack::Other: [
                 Avalanche, BinanceSmartChain, Celo, EclipseMainnet, Endurance, Ethereum,
-                FuseMainnet, Gnosis, Injective, Linea, Lukso, Neutron, Osmosis, Polygon,
+                FuseMainnet, Gnosis, Injective, Lukso, Neutron, Osmosis, Polygon,
                 Sei, SolanaMainnet, Taiko, Viction, Zetachain,
 
                 // Local chains
@@ -403,8 +421,7 @@ impl KnownHyperlaneDomain {
                 Test2, Test3,
 
                 // Test chains
-                Alfajores, BinanceSmartChainTestnet, Chiado, Fuji, Holesky, MoonbaseAlpha, ScrollSepolia,
-                Sepolia
+                Alfajores, BinanceSmartChainTestnet, Chiado, Fuji, Holesky, MoonbaseAlpha, Sepolia
            ],
         })
     }
@@ -511,12 +528,16 @@ impl HyperlaneDomain {
                 name, domain_id,
             ))
         } else {
+            // we might want to support accepting this from the config later,
+            // for now it is taken from the registry if the chain is known
+            let domain_type = RegistryDomain::by_id(domain_id)
+                .filter(|domain| domain.name == name)
+                .map_or(HyperlaneDomainType::Unknown, |domain| domain.domain_type);
             Ok(HyperlaneDomain::Unknown {
                 domain_id,
                 domain_name: name,
                 domain_protocol: protocol,
-                // we might want to support accepting this from the config later
-                domain_type: HyperlaneDomainType::Unknown,
+                domain_type,
                 domain_technical_stack,
             })
         }
@@ -592,8 +613,8 @@ impl HyperlaneDomain {
         use HyperlaneDomainProtocol::*;
         let protocol = self.domain_protocol();
         many_to_one!(match protocol {
-            IndexMode::Block: [Ethereum, Cosmos],
-            IndexMode::Sequence : [Sealevel, Fuel],
+            IndexMode::Block: [Ethereum, Cosmos, Ton],
+            IndexMode::Sequence : [Sealevel, Fuel, Aptos],
         })
     }
 }
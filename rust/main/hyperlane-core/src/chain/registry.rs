@@ -0,0 +1,110 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use crate::{
+    HyperlaneDomainProtocol, HyperlaneDomainTechnicalStack, HyperlaneDomainType, ReorgPeriod,
+};
+
+/// A domain as described by the Hyperlane registry.
+///
+/// The table of registry domains is generated at build time from the chain
+/// configs in `rust/main/config`, which are exported from the registry. Entries
+/// can be added or replaced at runtime with [`RegistryDomain::set_override`],
+/// e.g. for chains added to the registry after the agents were built.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistryDomain {
+    /// The chain name
+    pub name: Cow<'static, str>,
+    /// The domain id
+    pub domain_id: u32,
+    /// Whether the domain is a mainnet or testnet
+    pub domain_type: HyperlaneDomainType,
+    /// The protocol of the domain
+    pub domain_protocol: HyperlaneDomainProtocol,
+    /// The technical stack of the domain
+    pub domain_technical_stack: HyperlaneDomainTechnicalStack,
+    /// The reorg period agents should use by default
+    pub reorg_period: ReorgPeriod,
+}
+
+type RegistryDomains = RwLock<HashMap<u32, RegistryDomain>>;
+
+fn registry_domains() -> &'static RegistryDomains {
+    static DOMAINS: OnceLock<RegistryDomains> = OnceLock::new();
+    DOMAINS.get_or_init(|| {
+        let table: Vec<RegistryDomain> = include!(concat!(env!("OUT_DIR"), "/registry_domains.rs"));
+        RwLock::new(
+            table
+                .into_iter()
+                .map(|domain| (domain.domain_id, domain))
+                .collect(),
+        )
+    })
+}
+
+impl RegistryDomain {
+    /// The registry domain with id `domain_id`
+    pub fn by_id(domain_id: u32) -> Option<Self> {
+        registry_domains()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&domain_id)
+            .cloned()
+    }
+
+    /// The registry domain named `name`, ignoring case
+    pub fn by_name(name: &str) -> Option<Self> {
+        registry_domains()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .find(|domain| domain.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Add `domain` to the registry domains, replacing the domain with the
+    /// same id if any
+    pub fn set_override(domain: RegistryDomain) {
+        registry_domains()
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(domain.domain_id, domain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_domains() {
+        let ethereum = RegistryDomain::by_id(1).unwrap();
+        assert_eq!(ethereum.name, "ethereum");
+        assert_eq!(ethereum.domain_type, HyperlaneDomainType::Mainnet);
+        assert_eq!(ethereum.domain_protocol, HyperlaneDomainProtocol::Ethereum);
+        assert_eq!(RegistryDomain::by_name("Ethereum"), Some(ethereum));
+
+        let sepolia = RegistryDomain::by_name("sepolia").unwrap();
+        assert_eq!(sepolia.domain_id, 11155111);
+        assert_eq!(sepolia.domain_type, HyperlaneDomainType::Testnet);
+    }
+
+    #[test]
+    fn test_override_registry_domain() {
+        let domain = RegistryDomain {
+            name: Cow::Borrowed("newchain"),
+            domain_id: 0xf00,
+            domain_type: HyperlaneDomainType::Mainnet,
+            domain_protocol: HyperlaneDomainProtocol::Cosmos,
+            domain_technical_stack: HyperlaneDomainTechnicalStack::Other,
+            reorg_period: ReorgPeriod::from_blocks(2),
+        };
+        assert_eq!(RegistryDomain::by_id(0xf00), None);
+        RegistryDomain::set_override(domain.clone());
+        assert_eq!(RegistryDomain::by_id(0xf00), Some(domain.clone()));
+        assert_eq!(RegistryDomain::by_name("newchain"), Some(domain));
+    }
+}
@@ -4,6 +4,7 @@
 //! `FromRawConf` which will allow for better error messages.
 
 use std::fmt::{Debug, Display, Formatter};
+use std::num::NonZeroU32;
 
 pub use config_path::ConfigPath;
 use eyre::Report;
@@ -31,6 +32,16 @@ pub struct OperationBatchConfig {
     pub max_batch_size: u32,
 }
 
+/// Config for rate limiting the requests sent to an RPC endpoint, as a token
+/// bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConf {
+    /// How many requests can be sent per second on average
+    pub max_requests_per_second: NonZeroU32,
+    /// How many requests can be sent at once after the endpoint was idle
+    pub burst: NonZeroU32,
+}
+
 /// A trait that allows for constructing `Self` from a raw config type.
 pub trait FromRawConf<T, F = NoFilter>: Sized
 where
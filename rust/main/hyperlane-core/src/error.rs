@@ -11,8 +11,8 @@ use crate::rpc_clients::RpcClientError;
 use std::string::FromUtf8Error;
 
 use crate::{
-    Error as PrimitiveTypeError, HyperlaneProviderError, HyperlaneSignerError, ReorgPeriod, H256,
-    U256,
+    Error as PrimitiveTypeError, HyperlaneProviderError, HyperlaneSignerError, ReorgPeriod,
+    SignatureScheme, H256, U256,
 };
 
 /// The result of interacting with a chain.
@@ -159,6 +159,18 @@ pub enum ChainCommunicationError {
     /// Invalid reorg period
     #[error("Invalid reorg period: {0:?}")]
     InvalidReorgPeriod(ReorgPeriod),
+    /// The ISM rejected the metadata of a simulated `process` call, with the
+    /// error the chain returned
+    #[error("ISM verification failed: {0}")]
+    IsmVerificationFailed(#[source] Box<ChainCommunicationError>),
+    /// The recipient failed to handle the message of a simulated `process`
+    /// call, with the error the chain returned
+    #[error("Recipient failed to handle the message: {0}")]
+    RecipientHandleFailed(#[source] Box<ChainCommunicationError>),
+    /// A simulated transaction ran out of gas, with the error the chain
+    /// returned
+    #[error("Out of gas: {0}")]
+    OutOfGas(#[source] Box<ChainCommunicationError>),
 }
 
 impl ChainCommunicationError {
@@ -259,4 +271,7 @@ pub enum HyperlaneProtocolError {
     /// Expected a gas limit and none was provided
     #[error("A gas limit was expected for `process` contract call")]
     ProcessGasLimitRequired,
+    /// A signature of a scheme that can't be verified yet
+    #[error("Unsupported signature scheme {0:?}")]
+    UnsupportedSignatureScheme(SignatureScheme),
 }
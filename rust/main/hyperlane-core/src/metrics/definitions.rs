@@ -0,0 +1,642 @@
+//! The definitions of all metrics exported by the agents.
+//!
+//! Agents register their metrics through these definitions rather than ad hoc
+//! names, so that a metric can't be registered under a misspelled name or
+//! with labels that disagree with other uses of it. `METRICS.md` at the root of
+//! this crate documents every metric and is generated from
+//! [`METRIC_DEFINITIONS`]; run the tests with `UPDATE_METRICS_REFERENCE=1` to
+//! regenerate it.
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// The namespace prefix of all metric names, i.e. all metric names start with
+/// `{METRICS_NAMESPACE}_`.
+pub const METRICS_NAMESPACE: &str = "hyperlane";
+
+/// The type of a metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// A float counter
+    Counter,
+    /// An integer counter
+    IntCounter,
+    /// A float gauge
+    Gauge,
+    /// An integer gauge
+    IntGauge,
+    /// A histogram
+    Histogram,
+}
+
+impl MetricKind {
+    /// The Prometheus type of the metric
+    pub fn prometheus_type(&self) -> &'static str {
+        match self {
+            MetricKind::Counter | MetricKind::IntCounter => "counter",
+            MetricKind::Gauge | MetricKind::IntGauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+impl Display for MetricKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            MetricKind::Counter => "counter",
+            MetricKind::IntCounter => "int counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::IntGauge => "int gauge",
+            MetricKind::Histogram => "histogram",
+        };
+        write!(f, "{kind}")
+    }
+}
+
+/// The definition of a metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricDefinition {
+    /// The name of the metric, without the namespace prefix
+    pub name: &'static str,
+    /// Help string for the metric
+    pub help: &'static str,
+    /// The label names of the metric, in the order their values are given
+    pub labels: &'static [&'static str],
+    /// The type of the metric
+    pub kind: MetricKind,
+}
+
+/// An invalid metric definition
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MetricDefinitionError {
+    /// The metric name isn't a valid, unprefixed Prometheus name
+    #[error("Invalid metric name `{0}`")]
+    InvalidName(&'static str),
+    /// A label name isn't a valid Prometheus label name
+    #[error("Invalid label `{label}` of metric `{metric}`")]
+    InvalidLabel {
+        /// The metric
+        metric: &'static str,
+        /// The label
+        label: &'static str,
+    },
+    /// A label is repeated
+    #[error("Label `{label}` of metric `{metric}` is repeated")]
+    DuplicateLabel {
+        /// The metric
+        metric: &'static str,
+        /// The label
+        label: &'static str,
+    },
+    /// The metric has no help string
+    #[error("Metric `{0}` has no help string")]
+    MissingHelp(&'static str),
+    /// Two metrics share a name
+    #[error("Metric `{0}` is defined more than once")]
+    DuplicateName(&'static str),
+}
+
+impl MetricDefinition {
+    /// Define a float counter.
+    pub const fn counter(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        Self::new(name, help, labels, MetricKind::Counter)
+    }
+
+    /// Define an integer counter.
+    pub const fn int_counter(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        Self::new(name, help, labels, MetricKind::IntCounter)
+    }
+
+    /// Define a float gauge.
+    pub const fn gauge(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        Self::new(name, help, labels, MetricKind::Gauge)
+    }
+
+    /// Define an integer gauge.
+    pub const fn int_gauge(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        Self::new(name, help, labels, MetricKind::IntGauge)
+    }
+
+    /// Define a histogram.
+    pub const fn histogram(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+    ) -> Self {
+        Self::new(name, help, labels, MetricKind::Histogram)
+    }
+
+    const fn new(
+        name: &'static str,
+        help: &'static str,
+        labels: &'static [&'static str],
+        kind: MetricKind,
+    ) -> Self {
+        Self {
+            name,
+            help,
+            labels,
+            kind,
+        }
+    }
+
+    /// The name of the metric, including the namespace prefix.
+    pub fn namespaced_name(&self) -> String {
+        format!("{METRICS_NAMESPACE}_{}", self.name)
+    }
+
+    /// Checks that the metric and label names are valid Prometheus names, and
+    /// that the metric name isn't already prefixed with the namespace.
+    pub fn validate(&self) -> Result<(), MetricDefinitionError> {
+        if !is_valid_name(self.name) || self.name.starts_with(&format!("{METRICS_NAMESPACE}_")) {
+            return Err(MetricDefinitionError::InvalidName(self.name));
+        }
+        if self.help.trim().is_empty() {
+            return Err(MetricDefinitionError::MissingHelp(self.name));
+        }
+        let mut labels = HashSet::new();
+        for label in self.labels {
+            // Labels starting with `__` are reserved by Prometheus
+            if !is_valid_name(label) || label.starts_with("__") {
+                return Err(MetricDefinitionError::InvalidLabel {
+                    metric: self.name,
+                    label,
+                });
+            }
+            if !labels.insert(label) {
+                return Err(MetricDefinitionError::DuplicateLabel {
+                    metric: self.name,
+                    label,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `name` is a lower snake case Prometheus name.
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(false, |c| c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Validates each definition, and that no two definitions share a name.
+pub fn validate_definitions(definitions: &[MetricDefinition]) -> Result<(), MetricDefinitionError> {
+    let mut names = HashSet::new();
+    for definition in definitions {
+        definition.validate()?;
+        if !names.insert(definition.name) {
+            return Err(MetricDefinitionError::DuplicateName(definition.name));
+        }
+    }
+    Ok(())
+}
+
+/// The definition of the metric named `name`, without the namespace prefix.
+pub fn find_definition(name: &str) -> Option<&'static MetricDefinition> {
+    METRIC_DEFINITIONS
+        .iter()
+        .find(|definition| definition.name == name)
+}
+
+/// A markdown reference of all metrics.
+pub fn metrics_reference() -> String {
+    let mut reference = String::from(
+        "# Agent metrics\n\
+         \n\
+         <!-- Generated from `hyperlane-core/src/metrics/definitions.rs`, do not edit. -->\n\
+         \n\
+         | Metric | Type | Labels | Description |\n\
+         | ------ | ---- | ------ | ----------- |\n",
+    );
+    let mut definitions = METRIC_DEFINITIONS.to_vec();
+    definitions.sort_by_key(|definition| definition.name);
+    for definition in definitions {
+        let labels: Vec<String> = definition
+            .labels
+            .iter()
+            .map(|label| format!("`{label}`"))
+            .collect();
+        reference.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            definition.namespaced_name(),
+            definition.kind.prometheus_type(),
+            labels.join(", "),
+            definition.help.replace('|', "\\|"),
+        ));
+    }
+    reference
+}
+
+// ========= Tracing =========
+
+/// `span_duration_seconds`
+pub const SPAN_DURATION_SECONDS: MetricDefinition = MetricDefinition::counter(
+    "span_duration_seconds",
+    "Duration from tracing span creation to span destruction",
+    &["span_name", "span_target"],
+);
+/// `span_count`
+pub const SPAN_COUNT: MetricDefinition = MetricDefinition::int_counter(
+    "span_count",
+    "Number of times a span was exited",
+    &["span_name", "span_target"],
+);
+/// `span_events_total`
+pub const SPAN_EVENTS_TOTAL: MetricDefinition = MetricDefinition::int_counter(
+    "span_events_total",
+    "Number of span events (logs and time metrics) emitted by level",
+    &["event_level"],
+);
+
+// ========= Messages and checkpoints =========
+
+/// `last_known_message_nonce`
+pub const LAST_KNOWN_MESSAGE_NONCE: MetricDefinition = MetricDefinition::int_gauge(
+    "last_known_message_nonce",
+    "Last known message nonce",
+    &["phase", "origin", "remote"],
+);
+/// `latest_tree_insertion_index`
+pub const LATEST_TREE_INSERTION_INDEX: MetricDefinition = MetricDefinition::int_gauge(
+    "latest_tree_insertion_index",
+    "Latest leaf index inserted into the merkle tree",
+    &["origin"],
+);
+/// `observed_validator_latest_index`
+pub const OBSERVED_VALIDATOR_LATEST_INDEX: MetricDefinition = MetricDefinition::int_gauge(
+    "observed_validator_latest_index",
+    "The latest observed latest signed checkpoint indices per validator, from the perspective of the relayer",
+    &["origin", "destination", "validator", "app_context"],
+);
+/// `submitter_queue_length`
+pub const SUBMITTER_QUEUE_LENGTH: MetricDefinition = MetricDefinition::int_gauge(
+    "submitter_queue_length",
+    "Submitter queue length",
+    &["remote", "queue_name", "operation_status", "app_context"],
+);
+/// `latest_checkpoint`
+pub const LATEST_CHECKPOINT: MetricDefinition = MetricDefinition::int_gauge(
+    "latest_checkpoint",
+    "Mailbox latest checkpoint",
+    &["phase", "chain"],
+);
+/// `operations_processed_count`
+pub const OPERATIONS_PROCESSED_COUNT: MetricDefinition = MetricDefinition::int_counter(
+    "operations_processed_count",
+    "Number of operations processed",
+    &["phase", "chain"],
+);
+/// `messages_processed_count`
+pub const MESSAGES_PROCESSED_COUNT: MetricDefinition = MetricDefinition::int_counter(
+    "messages_processed_count",
+    "Number of messages processed",
+    &["origin", "remote"],
+);
+/// `operation_retries`
+pub const OPERATION_RETRIES: MetricDefinition = MetricDefinition::int_counter(
+    "operation_retries",
+    "Number of operation retries by the class of the failure that caused them",
+    &["origin", "remote", "retry_class"],
+);
+/// `messages_expired_count`
+pub const MESSAGES_EXPIRED_COUNT: MetricDefinition = MetricDefinition::int_counter(
+    "messages_expired_count",
+    "Number of messages dropped for exceeding the max age or deadline of their delivery TTL policy",
+    &["origin", "remote"],
+);
+/// `routing_ism_cache_lookups`
+pub const ROUTING_ISM_CACHE_LOOKUPS: MetricDefinition = MetricDefinition::int_counter(
+    "routing_ism_cache_lookups",
+    "Number of routing ISM route lookups served from or missing the cache",
+    &["destination", "result"],
+);
+/// `ism_config_cache_lookups`
+pub const ISM_CONFIG_CACHE_LOOKUPS: MetricDefinition = MetricDefinition::int_counter(
+    "ism_config_cache_lookups",
+    "Number of ISM configuration lookups served from or missing the cache",
+    &["destination", "result"],
+);
+/// `validator_reorg_lag_seconds`
+pub const VALIDATOR_REORG_LAG_SECONDS: MetricDefinition = MetricDefinition::int_gauge(
+    "validator_reorg_lag_seconds",
+    "How long a checkpoint must have been observed before the validator signs it",
+    &["origin"],
+);
+/// `validator_checkpoint_verification_mismatches`
+pub const VALIDATOR_CHECKPOINT_VERIFICATION_MISMATCHES: MetricDefinition =
+    MetricDefinition::int_counter(
+        "validator_checkpoint_verification_mismatches",
+        "Number of checkpoints the validator refused to sign as the verification RPC reported a different merkle root",
+        &["origin"],
+    );
+/// `validator_checkpoints_deferred`
+pub const VALIDATOR_CHECKPOINTS_DEFERRED: MetricDefinition = MetricDefinition::int_counter(
+    "validator_checkpoints_deferred",
+    "Number of checkpoints the validator skipped during a burst of messages, to sign them later",
+    &["origin"],
+);
+/// `validator_deferred_checkpoints_queued`
+pub const VALIDATOR_DEFERRED_CHECKPOINTS_QUEUED: MetricDefinition = MetricDefinition::int_gauge(
+    "validator_deferred_checkpoints_queued",
+    "Number of skipped checkpoints the validator has yet to sign",
+    &["origin"],
+);
+
+// ========= Chains and wallets =========
+
+/// `wallet_balance`
+pub const WALLET_BALANCE: MetricDefinition = MetricDefinition::gauge(
+    "wallet_balance",
+    "Current native token balance for the wallet addresses in the `wallets` set",
+    &[
+        "chain",
+        "wallet_address",
+        "wallet_name",
+        "token_address",
+        "token_symbol",
+        "token_name",
+    ],
+);
+/// `block_height`
+pub const BLOCK_HEIGHT: MetricDefinition = MetricDefinition::int_gauge(
+    "block_height",
+    "Tracks the current block height of the chain",
+    &["chain"],
+);
+/// `gas_price`
+pub const GAS_PRICE: MetricDefinition = MetricDefinition::gauge(
+    "gas_price",
+    "Tracks the current gas price of the chain, in the lowest denomination (e.g. wei)",
+    &["chain"],
+);
+/// `critical_error`
+pub const CRITICAL_ERROR: MetricDefinition = MetricDefinition::int_gauge(
+    "critical_error",
+    "Boolean marker for critical errors on a chain, signalling loss of liveness",
+    &["chain"],
+);
+/// `fees_paid_total`
+pub const FEES_PAID_TOTAL: MetricDefinition = MetricDefinition::counter(
+    "fees_paid_total",
+    "Total fees paid by the relayer on a chain, in the lowest denomination of `denom`",
+    &["chain", "denom"],
+);
+/// `fees_paid_in_window`
+pub const FEES_PAID_IN_WINDOW: MetricDefinition = MetricDefinition::gauge(
+    "fees_paid_in_window",
+    "Fees paid by the relayer on a chain within the trailing `window`, in the lowest denomination of `denom`",
+    &["chain", "denom", "window"],
+);
+
+// ========= Contract sync =========
+
+/// `cursor_current_block`
+pub const CURSOR_CURRENT_BLOCK: MetricDefinition = MetricDefinition::int_gauge(
+    "cursor_current_block",
+    "Current block of the cursor",
+    &["event_type", "chain", "cursor_type"],
+);
+/// `cursor_current_sequence`
+pub const CURSOR_CURRENT_SEQUENCE: MetricDefinition = MetricDefinition::int_gauge(
+    "cursor_current_sequence",
+    "Current sequence of the cursor",
+    &["event_type", "chain", "cursor_type"],
+);
+/// `cursor_max_sequence`
+pub const CURSOR_MAX_SEQUENCE: MetricDefinition = MetricDefinition::int_gauge(
+    "cursor_max_sequence",
+    "Max sequence of the cursor",
+    &["event_type", "chain"],
+);
+/// `contract_sync_block_height`
+pub const CONTRACT_SYNC_BLOCK_HEIGHT: MetricDefinition = MetricDefinition::int_gauge(
+    "contract_sync_block_height",
+    "Height of a recently observed block",
+    &["data_type", "chain"],
+);
+/// `contract_sync_stored_events`
+pub const CONTRACT_SYNC_STORED_EVENTS: MetricDefinition = MetricDefinition::int_counter(
+    "contract_sync_stored_events",
+    "Number of events stored into db",
+    &["data_type", "chain"],
+);
+/// `contract_sync_reorgs_detected`
+pub const CONTRACT_SYNC_REORGS_DETECTED: MetricDefinition = MetricDefinition::int_counter(
+    "contract_sync_reorgs_detected",
+    "Number of reorgs detected of blocks that logs were indexed from",
+    &["data_type", "chain"],
+);
+/// `contract_sync_liveness`
+pub const CONTRACT_SYNC_LIVENESS: MetricDefinition = MetricDefinition::int_gauge(
+    "contract_sync_liveness",
+    "Last timestamp observed by contract sync",
+    &["data_type", "chain"],
+);
+
+// ========= Providers =========
+
+/// `contract_call_duration_seconds`
+pub const CONTRACT_CALL_DURATION_SECONDS: MetricDefinition = MetricDefinition::counter(
+    "contract_call_duration_seconds",
+    "Contract call durations by contract and function",
+    &[
+        "chain",
+        "contract_name",
+        "contract_address",
+        "function_name",
+        "function_selector",
+    ],
+);
+/// `contract_call_count`
+pub const CONTRACT_CALL_COUNT: MetricDefinition = MetricDefinition::int_counter(
+    "contract_call_count",
+    "Contract invocations by contract and function",
+    &[
+        "chain",
+        "contract_name",
+        "contract_address",
+        "function_name",
+        "function_selector",
+    ],
+);
+/// `logs_query_duration_seconds`
+pub const LOGS_QUERY_DURATION_SECONDS: MetricDefinition = MetricDefinition::counter(
+    "logs_query_duration_seconds",
+    "Log query durations by address and topic.",
+    &[
+        "chain",
+        "contract_name",
+        "address",
+        "topic0",
+        "topic1",
+        "topic2",
+        "topic3",
+    ],
+);
+/// `logs_query_count`
+pub const LOGS_QUERY_COUNT: MetricDefinition = MetricDefinition::int_counter(
+    "logs_query_count",
+    "Discrete number of log queries by address and topic.",
+    &[
+        "chain",
+        "contract_name",
+        "address",
+        "topic0",
+        "topic1",
+        "topic2",
+        "topic3",
+    ],
+);
+/// `transaction_send_duration_seconds`
+pub const TRANSACTION_SEND_DURATION_SECONDS: MetricDefinition = MetricDefinition::counter(
+    "transaction_send_duration_seconds",
+    "Time taken to submit the transaction (not counting time for it to be included)",
+    &["chain", "address_from", "address_to", "txn_status"],
+);
+/// `transaction_send_total`
+pub const TRANSACTION_SEND_TOTAL: MetricDefinition = MetricDefinition::int_counter(
+    "transaction_send_total",
+    "Number of transactions sent",
+    &["chain", "address_from", "address_to", "txn_status"],
+);
+/// `request_count`
+pub const REQUEST_COUNT: MetricDefinition = MetricDefinition::int_counter(
+    "request_count",
+    "Total number of requests made to this client",
+    &["provider_node", "chain", "method", "status"],
+);
+/// `request_duration_seconds`
+pub const REQUEST_DURATION_SECONDS: MetricDefinition = MetricDefinition::counter(
+    "request_duration_seconds",
+    "Total number of seconds spent making requests",
+    &["provider_node", "chain", "method", "status"],
+);
+/// `request_latency_seconds`
+pub const REQUEST_LATENCY_SECONDS: MetricDefinition = MetricDefinition::histogram(
+    "request_latency_seconds",
+    "Latency of requests made to an RPC endpoint",
+    &["provider_node", "chain", "method", "status"],
+);
+
+// ========= Signers =========
+
+/// `signer_hsm_operation_latency_seconds`
+pub const SIGNER_HSM_OPERATION_LATENCY_SECONDS: MetricDefinition = MetricDefinition::histogram(
+    "signer_hsm_operation_latency_seconds",
+    "Latency of operations of keys held in an HSM",
+    &["key", "operation", "status"],
+);
+/// `signer_hsm_healthy`
+pub const SIGNER_HSM_HEALTHY: MetricDefinition = MetricDefinition::int_gauge(
+    "signer_hsm_healthy",
+    "Whether the last health check of a key held in an HSM succeeded",
+    &["key"],
+);
+
+/// All metrics exported by the agents
+pub const METRIC_DEFINITIONS: &[MetricDefinition] = &[
+    SPAN_DURATION_SECONDS,
+    SPAN_COUNT,
+    SPAN_EVENTS_TOTAL,
+    LAST_KNOWN_MESSAGE_NONCE,
+    LATEST_TREE_INSERTION_INDEX,
+    OBSERVED_VALIDATOR_LATEST_INDEX,
+    SUBMITTER_QUEUE_LENGTH,
+    LATEST_CHECKPOINT,
+    OPERATIONS_PROCESSED_COUNT,
+    MESSAGES_PROCESSED_COUNT,
+    OPERATION_RETRIES,
+    MESSAGES_EXPIRED_COUNT,
+    ROUTING_ISM_CACHE_LOOKUPS,
+    ISM_CONFIG_CACHE_LOOKUPS,
+    VALIDATOR_REORG_LAG_SECONDS,
+    VALIDATOR_CHECKPOINT_VERIFICATION_MISMATCHES,
+    VALIDATOR_CHECKPOINTS_DEFERRED,
+    VALIDATOR_DEFERRED_CHECKPOINTS_QUEUED,
+    WALLET_BALANCE,
+    BLOCK_HEIGHT,
+    GAS_PRICE,
+    CRITICAL_ERROR,
+    FEES_PAID_TOTAL,
+    FEES_PAID_IN_WINDOW,
+    CURSOR_CURRENT_BLOCK,
+    CURSOR_CURRENT_SEQUENCE,
+    CURSOR_MAX_SEQUENCE,
+    CONTRACT_SYNC_BLOCK_HEIGHT,
+    CONTRACT_SYNC_STORED_EVENTS,
+    CONTRACT_SYNC_REORGS_DETECTED,
+    CONTRACT_SYNC_LIVENESS,
+    CONTRACT_CALL_DURATION_SECONDS,
+    CONTRACT_CALL_COUNT,
+    LOGS_QUERY_DURATION_SECONDS,
+    LOGS_QUERY_COUNT,
+    TRANSACTION_SEND_DURATION_SECONDS,
+    TRANSACTION_SEND_TOTAL,
+    REQUEST_COUNT,
+    REQUEST_DURATION_SECONDS,
+    REQUEST_LATENCY_SECONDS,
+    SIGNER_HSM_OPERATION_LATENCY_SECONDS,
+    SIGNER_HSM_HEALTHY,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definitions_are_valid() {
+        validate_definitions(METRIC_DEFINITIONS).unwrap();
+
+        assert_eq!(
+            MetricDefinition::int_gauge("hyperlane_block_height", "Block height", &["chain"])
+                .validate(),
+            Err(MetricDefinitionError::InvalidName("hyperlane_block_height"))
+        );
+        assert_eq!(
+            MetricDefinition::int_gauge("block_height", "Block height", &["chain", "chain"])
+                .validate(),
+            Err(MetricDefinitionError::DuplicateLabel {
+                metric: "block_height",
+                label: "chain"
+            })
+        );
+        assert_eq!(
+            validate_definitions(&[BLOCK_HEIGHT, GAS_PRICE, BLOCK_HEIGHT]),
+            Err(MetricDefinitionError::DuplicateName("block_height"))
+        );
+    }
+
+    #[test]
+    fn test_metrics_reference_is_up_to_date() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/METRICS.md");
+        let reference = metrics_reference();
+        if std::env::var("UPDATE_METRICS_REFERENCE").is_ok() {
+            std::fs::write(path, &reference).unwrap();
+        }
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            reference,
+            "METRICS.md is out of date, run the tests with `UPDATE_METRICS_REFERENCE=1` to regenerate it"
+        );
+    }
+}
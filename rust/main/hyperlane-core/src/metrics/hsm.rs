@@ -0,0 +1,46 @@
+use std::{sync::OnceLock, time::Duration};
+
+use derive_new::new;
+use prometheus::{HistogramVec, IntGaugeVec};
+
+/// Buckets of the HSM operation latency histogram, in seconds
+pub const HSM_OPERATION_LATENCY_BUCKETS: [f64; 10] =
+    [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Metrics of signing keys held in an HSM, labeled by `key`
+#[derive(Debug, Clone, new)]
+pub struct HsmMetrics {
+    /// Latency of operations, additionally labeled by `operation` and `status`
+    pub operation_latency_seconds: HistogramVec,
+    /// Whether the last health check of a key succeeded
+    pub healthy: IntGaugeVec,
+}
+
+static INSTALLED_METRICS: OnceLock<HsmMetrics> = OnceLock::new();
+
+impl HsmMetrics {
+    /// Use these metrics for all HSM keys. Keys are opened while building
+    /// signers, so the metrics are installed once per agent rather than
+    /// passed to every signer. Only the first installed metrics are used.
+    pub fn install(self) {
+        let _ = INSTALLED_METRICS.set(self);
+    }
+
+    /// The installed metrics, if any
+    pub fn installed() -> Option<&'static Self> {
+        INSTALLED_METRICS.get()
+    }
+
+    /// Record an operation of `key`
+    pub fn record_operation(&self, key: &str, operation: &str, success: bool, duration: Duration) {
+        let status = if success { "success" } else { "failure" };
+        self.operation_latency_seconds
+            .with_label_values(&[key, operation, status])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record the outcome of a health check of `key`
+    pub fn set_healthy(&self, key: &str, healthy: bool) {
+        self.healthy.with_label_values(&[key]).set(healthy.into());
+    }
+}
@@ -1,2 +1,6 @@
 /// Agent metrics utils
 pub mod agent;
+/// Definitions of the metrics exported by the agents
+pub mod definitions;
+/// Metrics of keys held in HSMs
+pub mod hsm;
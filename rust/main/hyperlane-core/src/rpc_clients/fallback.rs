@@ -7,7 +7,10 @@ use std::{
     future::Future,
     marker::PhantomData,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 use tokio;
@@ -45,12 +48,50 @@ impl PrioritizedProviderInner {
         }
     }
 }
+
+/// Whether a request reads chain state or submits a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    /// A request reading chain state
+    Read,
+    /// A request submitting a transaction
+    Write,
+}
+
+/// The kinds of requests a sub-provider of a `FallbackProvider` is sent, e.g.
+/// so that archival endpoints never receive transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProviderCapability {
+    /// The provider is sent all requests
+    #[default]
+    ReadWrite,
+    /// The provider is only sent requests reading chain state
+    ReadOnly,
+    /// The provider is only sent transactions
+    WriteOnly,
+}
+
+impl ProviderCapability {
+    /// Whether the provider is sent requests of `kind`
+    pub fn serves(&self, kind: RequestKind) -> bool {
+        match self {
+            Self::ReadWrite => true,
+            Self::ReadOnly => kind == RequestKind::Read,
+            Self::WriteOnly => kind == RequestKind::Write,
+        }
+    }
+}
+
 /// Sub-providers and priority information
 pub struct PrioritizedProviders<T> {
     /// Unsorted list of providers this provider calls
     pub providers: Vec<T>,
+    /// The capability of each provider, in the order of `providers`
+    pub capabilities: Vec<ProviderCapability>,
     /// Sorted list of providers this provider calls, in descending order or reliability
     pub priorities: RwLock<Vec<PrioritizedProviderInner>>,
+    /// Counter of the read requests, used to spread them across the providers
+    pub read_requests: AtomicUsize,
 }
 
 /// A provider that bundles multiple providers and attempts to call the first,
@@ -135,6 +176,23 @@ where
         (*read_lock).clone()
     }
 
+    /// Like `take_priorities_snapshot`, but only with the providers that are
+    /// sent requests of `kind`. Reads are load-balanced, so every read starts
+    /// at the next provider in priority order and falls back to the ones
+    /// after it. Writes always start at the highest priority provider.
+    pub async fn take_priorities_snapshot_for(
+        &self,
+        kind: RequestKind,
+    ) -> Vec<PrioritizedProviderInner> {
+        let mut priorities = self.take_priorities_snapshot().await;
+        priorities.retain(|priority| self.inner.capabilities[priority.index].serves(kind));
+        if kind == RequestKind::Read && !priorities.is_empty() {
+            let start = self.inner.read_requests.fetch_add(1, Ordering::Relaxed);
+            priorities.rotate_left(start % priorities.len());
+        }
+        priorities
+    }
+
     /// De-prioritize a provider that has either timed out or returned a bad response
     pub async fn handle_stalled_provider(&self, priority: &PrioritizedProviderInner, provider: &T) {
         let now = Instant::now();
@@ -205,6 +263,7 @@ where
 #[derive(Debug, Clone)]
 pub struct FallbackProviderBuilder<T, B> {
     providers: Vec<T>,
+    capabilities: Vec<ProviderCapability>,
     max_block_time: Duration,
     _phantom: PhantomData<B>,
 }
@@ -213,6 +272,7 @@ impl<T, B> Default for FallbackProviderBuilder<T, B> {
     fn default() -> Self {
         Self {
             providers: Vec::new(),
+            capabilities: Vec::new(),
             max_block_time: MAX_BLOCK_TIME,
             _phantom: PhantomData,
         }
@@ -222,15 +282,28 @@ impl<T, B> Default for FallbackProviderBuilder<T, B> {
 impl<T, B> FallbackProviderBuilder<T, B> {
     /// Add a new provider to the set. Each new provider will be a lower
     /// priority than the previous.
-    pub fn add_provider(mut self, provider: T) -> Self {
+    pub fn add_provider(self, provider: T) -> Self {
+        self.add_provider_with_capability(provider, ProviderCapability::ReadWrite)
+    }
+
+    /// Add a new provider to the set that is only sent the requests its
+    /// `capability` allows. Each new provider will be a lower priority than
+    /// the previous.
+    pub fn add_provider_with_capability(
+        mut self,
+        provider: T,
+        capability: ProviderCapability,
+    ) -> Self {
         self.providers.push(provider);
+        self.capabilities.push(capability);
         self
     }
 
     /// Add many providers sorted by highest priority to lowest.
-    pub fn add_providers(mut self, providers: impl IntoIterator<Item = T>) -> Self {
-        self.providers.extend(providers);
-        self
+    pub fn add_providers(self, providers: impl IntoIterator<Item = T>) -> Self {
+        providers
+            .into_iter()
+            .fold(self, |builder, provider| builder.add_provider(provider))
     }
 
     /// Only used for testing purposes.
@@ -245,12 +318,14 @@ impl<T, B> FallbackProviderBuilder<T, B> {
         let provider_count = self.providers.len();
         let prioritized_providers = PrioritizedProviders {
             providers: self.providers,
+            capabilities: self.capabilities,
             // The order of `self.providers` gives the initial priority.
             priorities: RwLock::new(
                 (0..provider_count)
                     .map(PrioritizedProviderInner::new)
                     .collect(),
             ),
+            read_requests: AtomicUsize::new(0),
         };
         FallbackProvider {
             inner: Arc::new(prioritized_providers),
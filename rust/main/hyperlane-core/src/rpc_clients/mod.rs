@@ -1,14 +1,22 @@
 pub use self::error::*;
+pub use self::metrics::*;
 
 #[cfg(feature = "async")]
 pub use self::fallback::*;
 
+#[cfg(feature = "async")]
+pub use self::rate_limit::*;
+
 #[cfg(feature = "async")]
 pub use self::retry::*;
 
 mod error;
+mod metrics;
 #[cfg(feature = "async")]
 mod fallback;
 
+#[cfg(feature = "async")]
+mod rate_limit;
+
 #[cfg(feature = "async")]
 mod retry;
@@ -0,0 +1,110 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tokio::time::sleep;
+
+use crate::config::RateLimitConf;
+
+/// Limits the rate of requests sent to an RPC endpoint with a token bucket.
+///
+/// The bucket holds up to `burst` tokens and is refilled at
+/// `max_requests_per_second`. Every request takes a token, and requests that
+/// find the bucket empty wait for their token to be refilled, in the order they
+/// arrived.
+#[derive(Debug)]
+pub struct RateLimiter {
+    conf: RateLimitConf,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative while requests are waiting for a refill
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter with a full bucket
+    pub fn new(conf: RateLimitConf) -> Self {
+        Self {
+            conf,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(conf.burst.get()),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// The rate limiter shared by all clients of `endpoint`, which is created
+    /// with the `conf` of the first one. Providers are built per contract, so
+    /// a limiter per client wouldn't bound the rate the endpoint sees.
+    pub fn for_endpoint(endpoint: &str, conf: RateLimitConf) -> Arc<Self> {
+        static LIMITERS: OnceLock<Mutex<HashMap<String, Arc<RateLimiter>>>> = OnceLock::new();
+        LIMITERS
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(endpoint.to_owned())
+            .or_insert_with(|| Arc::new(Self::new(conf)))
+            .clone()
+    }
+
+    /// Wait until a request may be sent to the endpoint
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+
+    /// Takes a token from the bucket, returning how long it takes until the
+    /// token is refilled
+    fn reserve(&self, now: Instant) -> Duration {
+        let rate = f64::from(self.conf.max_requests_per_second.get());
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * rate).min(f64::from(self.conf.burst.get()));
+        bucket.last_refill = bucket.last_refill.max(now);
+
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use super::*;
+
+    #[test]
+    fn test_requests_wait_for_refill_after_burst() {
+        let limiter = RateLimiter::new(RateLimitConf {
+            max_requests_per_second: NonZeroU32::new(10).unwrap(),
+            burst: NonZeroU32::new(2).unwrap(),
+        });
+        let start = Instant::now();
+
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        // Waiting requests are queued behind each other
+        assert_eq!(limiter.reserve(start), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(start), Duration::from_millis(200));
+
+        // The bucket refills up to the burst size only
+        let later = start + Duration::from_secs(10);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::from_millis(100));
+    }
+}
@@ -33,6 +33,10 @@ pub trait ContractSyncCursor<T>: Debug + Send + Sync + 'static {
         logs: Vec<(Indexed<T>, LogMeta)>,
         range: RangeInclusive<u32>,
     ) -> Result<()>;
+
+    /// Rewind the cursor to index again from `block`, e.g. because the logs
+    /// indexed from `block` onwards were reorged out of the chain.
+    async fn rewind_to_block(&mut self, block: u32) -> Result<()>;
 }
 
 /// The action that should be taken by the contract sync loop
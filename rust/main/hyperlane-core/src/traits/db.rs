@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use auto_impl::auto_impl;
 use eyre::Result;
 
-use crate::{Indexed, LogMeta};
+use crate::{Indexed, IndexedBlock, LogMeta};
 
 /// Interface for a HyperlaneLogStore that ingests logs.
 #[async_trait]
@@ -13,6 +13,26 @@ pub trait HyperlaneLogStore<T>: Send + Sync + Debug {
     /// Store a list of logs and their associated metadata
     /// Returns the number of elements that were stored.
     async fn store_logs(&self, logs: &[(Indexed<T>, LogMeta)]) -> Result<u32>;
+
+    /// Remove logs that were reorged out of the chain, so that the logs of the
+    /// canonical chain are stored when their blocks are indexed again.
+    /// Returns the number of elements that were removed. Stores that can't
+    /// remove logs keep them.
+    async fn invalidate_logs(&self, _logs: &[(Indexed<T>, LogMeta)]) -> Result<u32> {
+        Ok(0)
+    }
+
+    /// Replace the stored blocks that logs were indexed from, so that reorgs
+    /// of these blocks are still detected after a restart. Stores that can't
+    /// persist them don't.
+    async fn store_indexed_blocks(&self, _blocks: &[IndexedBlock<T>]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Retrieve the blocks stored by `store_indexed_blocks`
+    async fn retrieve_indexed_blocks(&self) -> Result<Vec<IndexedBlock<T>>> {
+        Ok(vec![])
+    }
 }
 
 /// A sequence is a monotonically increasing number that is incremented every time a message ID is indexed.
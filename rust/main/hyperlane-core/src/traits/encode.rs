@@ -1,7 +1,8 @@
 use std::io::{Error, ErrorKind};
 
 use crate::{
-    GasPaymentKey, HyperlaneProtocolError, Indexed, InterchainGasPayment, H160, H256, H512, U256,
+    GasPaymentKey, HyperlaneProtocolError, Indexed, IndexedBlock, InterchainGasPayment, LogMeta,
+    H160, H256, H512, U256,
 };
 
 /// Simple trait for types with a canonical encoding
@@ -277,9 +278,78 @@ impl<T: Decode> Decode for Indexed<T> {
     }
 }
 
+impl Encode for LogMeta {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut written = 0;
+        written += self.address.write_to(writer)?;
+        written += self.block_number.write_to(writer)?;
+        written += self.block_hash.write_to(writer)?;
+        written += self.transaction_id.write_to(writer)?;
+        written += self.transaction_index.write_to(writer)?;
+        written += self.log_index.write_to(writer)?;
+        Ok(written)
+    }
+}
+
+impl Decode for LogMeta {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        Ok(Self {
+            address: H256::read_from(reader)?,
+            block_number: u64::read_from(reader)?,
+            block_hash: H256::read_from(reader)?,
+            transaction_id: H512::read_from(reader)?,
+            transaction_index: u64::read_from(reader)?,
+            log_index: U256::read_from(reader)?,
+        })
+    }
+}
+
+impl<T: Encode> Encode for IndexedBlock<T> {
+    fn write_to<W>(&self, writer: &mut W) -> std::io::Result<usize>
+    where
+        W: std::io::Write,
+    {
+        let mut written = 0;
+        written += self.number.write_to(writer)?;
+        written += self.hash.write_to(writer)?;
+        let log_count = u32::try_from(self.logs.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "Too many logs in block"))?;
+        written += log_count.write_to(writer)?;
+        for (log, meta) in &self.logs {
+            written += log.write_to(writer)?;
+            written += meta.write_to(writer)?;
+        }
+        Ok(written)
+    }
+}
+
+impl<T: Decode> Decode for IndexedBlock<T> {
+    fn read_from<R>(reader: &mut R) -> Result<Self, HyperlaneProtocolError>
+    where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        let number = u64::read_from(reader)?;
+        let hash = H256::read_from(reader)?;
+        let log_count = u32::read_from(reader)?;
+        let mut logs = vec![];
+        for _ in 0..log_count {
+            logs.push((Indexed::read_from(reader)?, LogMeta::read_from(reader)?));
+        }
+        Ok(Self { number, hash, logs })
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{Decode, Encode, Indexed, H256};
+    use crate::{Decode, Encode, Indexed, IndexedBlock, LogMeta, H256, H512, U256};
 
     #[test]
     fn test_encoding_indexed() {
@@ -289,6 +359,29 @@ mod test {
         assert_eq!(indexed, decoded);
     }
 
+    #[test]
+    fn test_encoding_indexed_block() {
+        let meta = LogMeta {
+            address: H256::random(),
+            block_number: 7,
+            block_hash: H256::random(),
+            transaction_id: H512::random(),
+            transaction_index: 2,
+            log_index: U256::from(3),
+        };
+        let block = IndexedBlock {
+            number: 7,
+            hash: meta.block_hash,
+            logs: vec![
+                (Indexed::new(H256::random()).with_sequence(5), meta.clone()),
+                (Indexed::new(H256::random()), meta),
+            ],
+        };
+        let encoded = block.to_vec();
+        let decoded = IndexedBlock::<H256>::read_from(&mut &encoded[..]).unwrap();
+        assert_eq!(block, decoded);
+    }
+
     #[test]
     fn test_encoding_interchain_gas_payment() {
         let payment = super::InterchainGasPayment {
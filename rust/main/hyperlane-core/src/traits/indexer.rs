@@ -55,4 +55,15 @@ pub trait Indexer<T: Sized>: Send + Sync + Debug {
 pub trait SequenceAwareIndexer<T>: Indexer<T> {
     /// Return the latest finalized sequence (if any) and block number
     async fn latest_sequence_count_and_tip(&self) -> ChainResult<(Option<u32>, u32)>;
+
+    /// Return the sequence (if any) at `tip`, a finalized block number that
+    /// was observed elsewhere, e.g. by a block height watcher shared across
+    /// the indexers of a chain, along with the block number it is at.
+    ///
+    /// Indexers that can't query the sequence at a given block fetch both
+    /// the sequence and their own tip.
+    async fn sequence_count_at_tip(&self, tip: u32) -> ChainResult<(Option<u32>, u32)> {
+        let _ = tip;
+        self.latest_sequence_count_and_tip().await
+    }
 }
@@ -2,6 +2,7 @@ use std::fmt::Debug;
 
 use async_trait::async_trait;
 use derive_new::new;
+use futures::future::try_join_all;
 
 use crate::{
     traits::TxOutcome, utils::domain_hash, BatchItem, ChainCommunicationError, ChainResult,
@@ -26,6 +27,25 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
     /// Fetch the status of a message
     async fn delivered(&self, id: H256) -> ChainResult<bool>;
 
+    /// Fetch the status of a message. Chains that can't tell whether a
+    /// message was delivered from its ID alone, e.g. because they prune
+    /// records of delivered messages, should override this.
+    async fn delivered_message(&self, message: &HyperlaneMessage) -> ChainResult<bool> {
+        self.delivered(message.id()).await
+    }
+
+    /// Fetch the status of multiple messages, in the order of `messages`.
+    /// Chains that can look up many messages in a single request should
+    /// override this, by default the messages are looked up concurrently.
+    async fn delivered_batch(&self, messages: &[HyperlaneMessage]) -> ChainResult<Vec<bool>> {
+        try_join_all(
+            messages
+                .iter()
+                .map(|message| self.delivered_message(message)),
+        )
+        .await
+    }
+
     /// Fetch the current default interchain security module value
     async fn default_ism(&self) -> ChainResult<H256>;
 
@@ -68,7 +88,8 @@ pub trait Mailbox: HyperlaneContract + Send + Sync + Debug {
 
     /// Get the calldata for a transaction to process a message with a proof
     /// against the provided signed checkpoint
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8>;
+    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8])
+        -> ChainResult<Vec<u8>>;
 }
 
 /// The result of processing a batch of messages
@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::{ChainResult, HyperlaneChain, HyperlaneMessage};
+
+/// The outcome of dry-running the delivery of a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryDryRun {
+    /// The message would be delivered
+    Delivered,
+    /// The recipient's ISM would reject the metadata
+    IsmVerificationFailed(String),
+    /// The ISM would accept the metadata, but the recipient would fail to
+    /// handle the message
+    HandleReverted(String),
+    /// Processing would fail for another reason, e.g. because the message was
+    /// already delivered
+    Failed(String),
+}
+
+impl DeliveryDryRun {
+    /// Whether the message would be delivered
+    pub fn is_delivered(&self) -> bool {
+        matches!(self, Self::Delivered)
+    }
+}
+
+/// Interface for dry-running the delivery of a message on its destination,
+/// telling apart failures of the ISM from failures of the recipient.
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait MessageDeliverySimulator: HyperlaneChain + Send + Sync + Debug {
+    /// Simulate processing `message` with `metadata`, without submitting a
+    /// transaction. Errors are only returned if the dry run couldn't be
+    /// completed, e.g. because the RPC failed.
+    async fn dry_run_delivery(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<DeliveryDryRun>;
+}
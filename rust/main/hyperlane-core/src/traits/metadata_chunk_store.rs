@@ -0,0 +1,27 @@
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+
+use crate::{ChainResult, HyperlaneContract, TxOutcome, H256, U256};
+
+/// Interface for a contract that ISM metadata too large to be submitted in a
+/// single transaction is stored in, in chunks, ahead of processing the
+/// message. Only ISMs supporting pointer-based metadata can read it.
+#[async_trait]
+#[auto_impl(&, Box, Arc)]
+pub trait MetadataChunkStore: HyperlaneContract + Send + Sync + Debug {
+    /// Store `chunk` as the chunk at `index` of the metadata under `key`
+    async fn store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<TxOutcome>;
+
+    /// Estimate the gas limit of storing `chunk` as the chunk at `index` of
+    /// the metadata under `key`
+    async fn estimate_store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<U256>;
+
+    /// Delete the chunks stored under `key`
+    async fn clear(&self, key: H256) -> ChainResult<TxOutcome>;
+
+    /// The metadata to process a message with instead of the metadata stored
+    /// under `key`, which points ISMs at the stored metadata
+    async fn pointer_metadata(&self, key: H256) -> ChainResult<Vec<u8>>;
+}
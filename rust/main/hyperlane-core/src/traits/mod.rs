@@ -9,12 +9,15 @@ pub use interchain_gas::*;
 pub use interchain_security_module::*;
 pub use mailbox::*;
 pub use merkle_tree_hook::*;
+pub use message_delivery_simulator::*;
+pub use metadata_chunk_store::*;
 pub use multisig_ism::*;
 pub use pending_operation::*;
 pub use provider::*;
 pub use routing_ism::*;
 pub use signing::*;
 pub use validator_announce::*;
+pub use value_envelope::*;
 
 use crate::{FixedPointNumber, H512, U256};
 
@@ -29,12 +32,15 @@ mod interchain_gas;
 mod interchain_security_module;
 mod mailbox;
 mod merkle_tree_hook;
+mod message_delivery_simulator;
+mod metadata_chunk_store;
 mod multisig_ism;
 mod pending_operation;
 mod provider;
 mod routing_ism;
 mod signing;
 mod validator_announce;
+mod value_envelope;
 
 /// The result of a transaction
 #[derive(Debug, Clone)]
@@ -47,6 +53,8 @@ pub struct TxOutcome {
     pub gas_used: U256,
     /// Price paid for the gas
     pub gas_price: FixedPointNumber,
+    /// The block the transaction was included in, if known
+    pub block_number: Option<u64>,
     // TODO: more? What can be abstracted across all chains?
 }
 
@@ -61,6 +69,7 @@ impl From<ethers_core::types::TransactionReceipt> for TxOutcome {
                 .effective_gas_price
                 .and_then(|price| U256::from(price).try_into().ok())
                 .unwrap_or(FixedPointNumber::zero()),
+            block_number: t.block_number.map(|number| number.as_u64()),
         }
     }
 }
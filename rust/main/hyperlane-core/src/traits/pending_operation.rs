@@ -9,7 +9,7 @@ use std::{
 
 use crate::{
     ChainResult, Decode, Encode, FixedPointNumber, HyperlaneDomain, HyperlaneMessage,
-    HyperlaneProtocolError, Mailbox, TryBatchAs, TxOutcome, H256, U256,
+    HyperlaneProtocolError, Mailbox, TryBatchAs, TxOutcome, ValueCodec, ValueEnvelope, H256, U256,
 };
 use async_trait::async_trait;
 use num::CheckedDiv;
@@ -160,11 +160,21 @@ pub trait PendingOperation: Send + Sync + Debug + TryBatchAs<HyperlaneMessage> {
     fn try_get_mailbox(&self) -> Option<Arc<dyn Mailbox>> {
         None
     }
+
+    /// If this operation delivers a message, return it
+    fn try_get_message(&self) -> Option<&HyperlaneMessage> {
+        None
+    }
+
+    /// Record whether the operation was already delivered, as found by a
+    /// delivery check of many operations at once, so that the next `prepare`
+    /// or `confirm` doesn't look it up again.
+    fn set_delivered(&mut self, _delivered: bool) {}
 }
 
 #[derive(Debug, Display, Clone, Serialize, Deserialize, PartialEq)]
 /// Status of a pending operation
-/// WARNING: This enum is serialized to JSON and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
+/// WARNING: This enum is serialized with serde and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
 /// Adding new variants is fine.
 pub enum PendingOperationStatus {
     /// The operation is ready to be prepared for the first time, or has just been loaded from storage
@@ -177,6 +187,9 @@ pub enum PendingOperationStatus {
     /// The operation has been submitted and is awaiting confirmation
     #[strum(to_string = "Confirm({0})")]
     Confirm(ConfirmReason),
+    /// The operation won't be delivered, for the given reason
+    #[strum(to_string = "Undeliverable({0})")]
+    Undeliverable(UndeliverableReason),
 }
 
 impl Encode for PendingOperationStatus {
@@ -184,10 +197,8 @@ impl Encode for PendingOperationStatus {
     where
         W: Write,
     {
-        // Serialize to JSON and write to the writer, to avoid having to implement the encoding manually
-        let serialized = serde_json::to_vec(self)
-            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to serialize"))?;
-        writer.write(&serialized)
+        // Serialize with serde, to avoid having to implement the encoding manually
+        ValueEnvelope::write_to(self, ValueCodec::Json, writer)
     }
 }
 
@@ -197,19 +208,13 @@ impl Decode for PendingOperationStatus {
         R: std::io::Read,
         Self: Sized,
     {
-        // Deserialize from JSON and read from the reader, to avoid having to implement the encoding / decoding manually
-        serde_json::from_reader(reader).map_err(|err| {
-            HyperlaneProtocolError::IoError(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Failed to deserialize. Error: {}", err),
-            ))
-        })
+        ValueEnvelope::read_from(reader)
     }
 }
 
 #[derive(Display, Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Reasons for repreparing an operation
-/// WARNING: This enum is serialized to JSON and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
+/// WARNING: This enum is serialized with serde and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
 /// Adding new variants is fine.
 pub enum ReprepareReason {
     #[strum(to_string = "Error checking message delivery status")]
@@ -250,11 +255,26 @@ pub enum ReprepareReason {
     #[strum(to_string = "Delivery transaction reverted or reorged")]
     /// Delivery transaction reverted or reorged
     RevertedOrReorged,
+    #[strum(to_string = "Message delivery estimated fee exceeds max fee")]
+    /// Message delivery estimated fee exceeds the max fee of the destination's gas escalation policy
+    ExceedsMaxFee,
+    #[strum(to_string = "Error storing metadata chunks")]
+    /// Error storing the chunks of metadata too large to be submitted with the process call
+    ErrorStoringMetadataChunks,
+    #[strum(to_string = "ISM would fail to verify the message")]
+    /// Dry-running the delivery showed the recipient's ISM would reject the metadata
+    IsmVerificationFailed,
+    #[strum(to_string = "Recipient would fail to handle the message")]
+    /// Dry-running the delivery showed the recipient would revert when handling the message
+    RecipientHandleReverted,
+    #[strum(to_string = "Simulated delivery ran out of gas")]
+    /// Simulating the delivery ran out of gas, e.g. because the recipient uses more gas than a transaction may
+    SimulationOutOfGas,
 }
 
 #[derive(Display, Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Reasons for repreparing an operation
-/// WARNING: This enum is serialized to JSON and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
+/// WARNING: This enum is serialized with serde and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
 /// Adding new variants is fine.
 pub enum ConfirmReason {
     #[strum(to_string = "Submitted by this relayer")]
@@ -269,6 +289,22 @@ pub enum ConfirmReason {
     ErrorRecordingProcessSuccess,
 }
 
+#[derive(Display, Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Reasons for not delivering an operation
+/// WARNING: This enum is serialized with serde and stored in the database, so to keep backwards compatibility, we shouldn't remove or rename any variants.
+/// Adding new variants is fine.
+pub enum UndeliverableReason {
+    #[strum(to_string = "Recipient is on the relayer's skip list")]
+    /// The recipient is on the relayer's skip list, e.g. a program that panics
+    /// when handling messages
+    SkippedRecipient,
+    #[strum(to_string = "Moved to the dead-letter queue")]
+    /// The relayer gave up on delivering the message, e.g. because it
+    /// exhausted its retries or expired, until an operator re-injects it from
+    /// the dead-letter queue
+    DeadLettered,
+}
+
 /// Utility fn to calculate the total estimated cost of an operation batch
 pub fn total_estimated_cost(ops: &[Box<dyn PendingOperation>]) -> U256 {
     ops.iter()
@@ -373,4 +409,16 @@ mod test {
         let decoded = PendingOperationStatus::read_from(&mut &encoded[..]).unwrap();
         assert_eq!(status, decoded);
     }
+
+    #[test]
+    fn test_decoding_legacy_pending_operation_status() {
+        // Statuses were stored as plain JSON before value envelopes
+        let decoded =
+            PendingOperationStatus::read_from(&mut &br#"{"Retry":"CouldNotFetchMetadata"}"#[..])
+                .unwrap();
+        assert_eq!(
+            decoded,
+            PendingOperationStatus::Retry(ReprepareReason::CouldNotFetchMetadata)
+        );
+    }
 }
@@ -8,7 +8,7 @@ use serde::{
 };
 
 use crate::utils::bytes_to_hex;
-use crate::{Signature, H160, H256};
+use crate::{Signature, H160, H256, H512};
 
 /// An error incurred by a signer
 #[derive(thiserror::Error, Debug)]
@@ -54,7 +54,10 @@ impl<S: HyperlaneSigner> HyperlaneSignerExt for S {
         let signing_hash = value.signing_hash();
         let signature = self.sign_hash(&signing_hash).await?;
 
-        Ok(SignedType { value, signature })
+        Ok(SignedType {
+            value,
+            signature: signature.into(),
+        })
     }
 
     #[cfg(feature = "ethers")]
@@ -80,15 +83,146 @@ pub trait Signable: Sized {
     }
 }
 
+/// The scheme a validator signs checkpoints and announcements with. It's
+/// tagged in their serialized form, so that signatures of other schemes
+/// aren't mistaken for ECDSA signatures.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureScheme {
+    /// ECDSA over secp256k1 of the EIP-191 hash of the signing hash, as
+    /// verified with `ecrecover`
+    #[default]
+    EcdsaSecp256k1,
+    /// Ed25519 of the signing hash, which can't be verified yet
+    Ed25519,
+    /// BLS over BLS12-381, which can't be verified yet
+    Bls12381,
+}
+
+/// A signature of a signed value, in the form of the scheme it was signed
+/// with
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SchemeSignature {
+    /// An ECDSA signature, including its recovery id
+    EcdsaSecp256k1(Signature),
+    /// An ed25519 signature
+    Ed25519(H512),
+    /// A compressed BLS signature
+    Bls12381(Vec<u8>),
+}
+
+impl SchemeSignature {
+    /// The scheme of the signature
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            SchemeSignature::EcdsaSecp256k1(_) => SignatureScheme::EcdsaSecp256k1,
+            SchemeSignature::Ed25519(_) => SignatureScheme::Ed25519,
+            SchemeSignature::Bls12381(_) => SignatureScheme::Bls12381,
+        }
+    }
+
+    /// The signature, if it's an ECDSA signature
+    pub fn ecdsa(&self) -> Option<Signature> {
+        match self {
+            SchemeSignature::EcdsaSecp256k1(signature) => Some(*signature),
+            _ => None,
+        }
+    }
+
+    /// The serialized signature, which is the 65 byte `r || s || v` form of
+    /// ECDSA signatures
+    pub fn to_vec(&self) -> Vec<u8> {
+        match self {
+            SchemeSignature::EcdsaSecp256k1(signature) => signature.to_vec(),
+            SchemeSignature::Ed25519(signature) => signature.as_bytes().to_vec(),
+            SchemeSignature::Bls12381(signature) => signature.clone(),
+        }
+    }
+
+    fn from_raw(scheme: SignatureScheme, raw: RawSignature) -> Result<Self, String> {
+        let bytes = match (scheme, raw) {
+            (SignatureScheme::EcdsaSecp256k1, RawSignature::Ecdsa(signature)) => {
+                return Ok(SchemeSignature::EcdsaSecp256k1(signature))
+            }
+            (SignatureScheme::EcdsaSecp256k1, RawSignature::Hex(_)) => {
+                return Err("Expected the r, s and v of an ECDSA signature".to_owned())
+            }
+            (_, RawSignature::Hex(hex)) => hex::decode(hex.trim_start_matches("0x"))
+                .map_err(|err| format!("Invalid {scheme:?} signature: {err}"))?,
+            (_, RawSignature::Ecdsa(_)) => {
+                return Err(format!("Expected a hex encoded {scheme:?} signature"))
+            }
+        };
+        match scheme {
+            SignatureScheme::Ed25519 if bytes.len() == H512::len_bytes() => {
+                Ok(SchemeSignature::Ed25519(H512::from_slice(&bytes)))
+            }
+            SignatureScheme::Ed25519 => Err(format!(
+                "Expected a {} byte ed25519 signature, got {} bytes",
+                H512::len_bytes(),
+                bytes.len()
+            )),
+            _ => Ok(SchemeSignature::Bls12381(bytes)),
+        }
+    }
+}
+
+impl From<Signature> for SchemeSignature {
+    fn from(signature: Signature) -> Self {
+        SchemeSignature::EcdsaSecp256k1(signature)
+    }
+}
+
+impl std::fmt::Display for SchemeSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(self.to_vec()))
+    }
+}
+
 /// A signed type. Contains the original value and the signature.
 #[derive(Clone, Eq, PartialEq, Deserialize)]
+#[serde(
+    try_from = "RawSignedType<T>",
+    bound(deserialize = "T: Deserialize<'de>")
+)]
 pub struct SignedType<T: Signable> {
     /// The value which was signed
+    pub value: T,
+    /// The signature for the value, whose scheme is tagged in the
+    /// serialized form
+    pub signature: SchemeSignature,
+}
+
+/// The serialized form of a `SignedType`. Values signed before the scheme
+/// was tagged are ECDSA signed.
+#[derive(Deserialize)]
+struct RawSignedType<T> {
     #[serde(alias = "checkpoint")]
     #[serde(alias = "announcement")]
-    pub value: T,
-    /// The signature for the value
-    pub signature: Signature,
+    value: T,
+    signature: RawSignature,
+    #[serde(default)]
+    scheme: SignatureScheme,
+}
+
+/// ECDSA signatures are serialized as their components, and signatures of
+/// other schemes as hex
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSignature {
+    Ecdsa(Signature),
+    Hex(String),
+}
+
+impl<T: Signable> TryFrom<RawSignedType<T>> for SignedType<T> {
+    type Error = String;
+
+    fn try_from(raw: RawSignedType<T>) -> Result<Self, Self::Error> {
+        Ok(SignedType {
+            value: raw.value,
+            signature: SchemeSignature::from_raw(raw.scheme, raw.signature)?,
+        })
+    }
 }
 
 impl<T: Signable + Serialize> Serialize for SignedType<T> {
@@ -96,32 +230,53 @@ impl<T: Signable + Serialize> Serialize for SignedType<T> {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("SignedType", 3)?;
+        let mut state = serializer.serialize_struct("SignedType", 4)?;
         state.serialize_field("value", &self.value)?;
-        state.serialize_field("signature", &self.signature)?;
-        let sig: [u8; 65] = self.signature.into();
-        state.serialize_field("serialized_signature", &bytes_to_hex(&sig))?;
+        let serialized_signature = bytes_to_hex(&self.signature.to_vec());
+        match &self.signature {
+            SchemeSignature::EcdsaSecp256k1(signature) => {
+                state.serialize_field("signature", signature)?
+            }
+            _ => state.serialize_field("signature", &serialized_signature)?,
+        }
+        state.serialize_field("serialized_signature", &serialized_signature)?;
+        state.serialize_field("scheme", &self.signature.scheme())?;
         state.end()
     }
 }
 
 impl<T: Signable> SignedType<T> {
-    /// Recover the Ethereum address of the signer
+    /// Recover the Ethereum address of the signer, which is only possible
+    /// for ECDSA signatures
     #[cfg(feature = "ethers")]
     pub fn recover(&self) -> Result<H160, crate::HyperlaneProtocolError> {
-        let hash = ethers_core::types::H256::from(self.value.eth_signed_message_hash());
-        let sig = ethers_core::types::Signature::from(self.signature);
+        match &self.signature {
+            SchemeSignature::EcdsaSecp256k1(signature) => {
+                let hash = ethers_core::types::H256::from(self.value.eth_signed_message_hash());
+                let sig = ethers_core::types::Signature::from(*signature);
 
-        Ok(sig.recover(hash)?.into())
+                Ok(sig.recover(hash)?.into())
+            }
+            signature => Err(crate::HyperlaneProtocolError::UnsupportedSignatureScheme(
+                signature.scheme(),
+            )),
+        }
     }
 
     /// Check whether a message was signed by a specific address
     #[cfg(feature = "ethers")]
     pub fn verify(&self, signer: H160) -> Result<(), crate::HyperlaneProtocolError> {
-        let hash = ethers_core::types::H256::from(self.value.eth_signed_message_hash());
-        let sig = ethers_core::types::Signature::from(self.signature);
-        let signer = ethers_core::types::H160::from(signer);
-        Ok(sig.verify(hash, signer)?)
+        match &self.signature {
+            SchemeSignature::EcdsaSecp256k1(signature) => {
+                let hash = ethers_core::types::H256::from(self.value.eth_signed_message_hash());
+                let sig = ethers_core::types::Signature::from(*signature);
+                let signer = ethers_core::types::H160::from(signer);
+                Ok(sig.verify(hash, signer)?)
+            }
+            signature => Err(crate::HyperlaneProtocolError::UnsupportedSignatureScheme(
+                signature.scheme(),
+            )),
+        }
     }
 }
 
@@ -129,8 +284,10 @@ impl<T: Signable + Debug> Debug for SignedType<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "SignedType {{ value: {:?}, signature: 0x{} }}",
-            self.value, self.signature
+            "SignedType {{ value: {:?}, signature: 0x{}, scheme: {:?} }}",
+            self.value,
+            self.signature,
+            self.signature.scheme()
         )
     }
 }
@@ -184,3 +341,83 @@ mod hashes {
         );
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Checkpoint, CheckpointWithMessageId, U256};
+
+    fn checkpoint() -> CheckpointWithMessageId {
+        CheckpointWithMessageId {
+            checkpoint: Checkpoint {
+                merkle_tree_hook_address: H256::repeat_byte(1),
+                mailbox_domain: 1,
+                root: H256::repeat_byte(2),
+                index: 3,
+            },
+            message_id: H256::repeat_byte(4),
+        }
+    }
+
+    #[test]
+    fn test_signed_type_scheme_serialization() {
+        let signed = SignedType {
+            value: checkpoint(),
+            signature: Signature {
+                r: U256::one(),
+                s: U256::one(),
+                v: 27,
+            }
+            .into(),
+        };
+        let mut json = serde_json::to_value(&signed).unwrap();
+        assert_eq!(json["scheme"], "ecdsa-secp256k1");
+
+        // Values signed before the scheme was tagged are ECDSA signed
+        json.as_object_mut().unwrap().remove("scheme");
+        let parsed: SignedType<CheckpointWithMessageId> =
+            serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(parsed, signed);
+
+        // The signature must be of the tagged scheme
+        json["scheme"] = "bls12381".into();
+        assert!(serde_json::from_value::<SignedType<CheckpointWithMessageId>>(json).is_err());
+    }
+
+    #[test]
+    fn test_signed_type_ed25519_serialization() {
+        let signed = SignedType {
+            value: checkpoint(),
+            signature: SchemeSignature::Ed25519(H512::repeat_byte(5)),
+        };
+        let json = serde_json::to_value(&signed).unwrap();
+        assert_eq!(json["scheme"], "ed25519");
+        assert_eq!(json["signature"], format!("0x{}", "05".repeat(64)));
+        assert_eq!(json["signature"], json["serialized_signature"]);
+
+        let parsed: SignedType<CheckpointWithMessageId> =
+            serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(parsed, signed);
+        assert_eq!(parsed.signature.ecdsa(), None);
+        assert_eq!(parsed.signature.to_vec(), vec![5; 64]);
+
+        let mut truncated = json;
+        truncated["signature"] = format!("0x{}", "05".repeat(63)).into();
+        assert!(serde_json::from_value::<SignedType<CheckpointWithMessageId>>(truncated).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "ethers")]
+    fn test_non_ecdsa_signatures_are_not_recovered() {
+        let signed = SignedType {
+            value: checkpoint(),
+            signature: SchemeSignature::Ed25519(H512::repeat_byte(5)),
+        };
+        assert!(matches!(
+            signed.recover(),
+            Err(crate::HyperlaneProtocolError::UnsupportedSignatureScheme(
+                SignatureScheme::Ed25519
+            ))
+        ));
+    }
+}
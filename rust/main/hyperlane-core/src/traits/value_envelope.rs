@@ -0,0 +1,159 @@
+use std::io::{Error, ErrorKind, Read, Write};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::HyperlaneProtocolError;
+
+/// Magic bytes that values wrapped in a `ValueEnvelope` start with. Values
+/// stored before envelopes were introduced are plain JSON, which never starts
+/// with `0xff` as it isn't valid UTF-8.
+pub const VALUE_ENVELOPE_MAGIC: [u8; 2] = [0xff, b'H'];
+/// The version of the envelope layout values are written with
+pub const VALUE_ENVELOPE_VERSION: u8 = 1;
+
+/// The codec of the payload of an enveloped value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ValueCodec {
+    /// `serde_json`
+    Json = 0,
+    /// `bincode`, which is more compact and faster to (de)serialize than
+    /// JSON, for values on hot paths
+    Bincode = 1,
+}
+
+impl TryFrom<u8> for ValueCodec {
+    type Error = HyperlaneProtocolError;
+
+    fn try_from(codec: u8) -> Result<Self, Self::Error> {
+        match codec {
+            0 => Ok(Self::Json),
+            1 => Ok(Self::Bincode),
+            _ => Err(invalid_data(format!("Unknown value codec {codec}"))),
+        }
+    }
+}
+
+/// A versioned envelope around serde-serialized values stored in the
+/// database, laid out as `magic (2 bytes) | version (1 byte) | codec (1 byte)
+/// | payload`.
+///
+/// The envelope lets the codec a type is written with change without breaking
+/// reads of values written before the change, as values are read in whichever
+/// codec they were written with. Values that predate envelopes are read as
+/// JSON.
+pub struct ValueEnvelope;
+
+impl ValueEnvelope {
+    const HEADER_LEN: usize = VALUE_ENVELOPE_MAGIC.len() + 2;
+
+    /// Write `value` to `writer` in an envelope with the given codec
+    pub fn write_to<T, W>(value: &T, codec: ValueCodec, writer: &mut W) -> std::io::Result<usize>
+    where
+        T: Serialize,
+        W: Write,
+    {
+        let payload = match codec {
+            ValueCodec::Json => {
+                serde_json::to_vec(value).map_err(|err| Error::new(ErrorKind::Other, err))?
+            }
+            ValueCodec::Bincode => {
+                bincode::serialize(value).map_err(|err| Error::new(ErrorKind::Other, err))?
+            }
+        };
+        writer.write_all(&VALUE_ENVELOPE_MAGIC)?;
+        writer.write_all(&[VALUE_ENVELOPE_VERSION, codec as u8])?;
+        writer.write_all(&payload)?;
+        Ok(Self::HEADER_LEN + payload.len())
+    }
+
+    /// Read a value from `reader`, either in an envelope or as plain JSON
+    pub fn read_from<T, R>(reader: &mut R) -> Result<T, HyperlaneProtocolError>
+    where
+        T: DeserializeOwned,
+        R: Read,
+    {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        let Some((codec, payload)) = Self::open(&bytes)? else {
+            return serde_json::from_slice(&bytes).map_err(invalid_data);
+        };
+        match codec {
+            ValueCodec::Json => serde_json::from_slice(payload).map_err(invalid_data),
+            ValueCodec::Bincode => bincode::deserialize(payload).map_err(invalid_data),
+        }
+    }
+
+    /// The codec and payload of an enveloped value, or `None` if the value
+    /// predates envelopes
+    pub fn open(bytes: &[u8]) -> Result<Option<(ValueCodec, &[u8])>, HyperlaneProtocolError> {
+        let Some(header) = bytes.strip_prefix(&VALUE_ENVELOPE_MAGIC) else {
+            return Ok(None);
+        };
+        let [version, codec, payload @ ..] = header else {
+            return Err(invalid_data("Truncated value envelope"));
+        };
+        if *version != VALUE_ENVELOPE_VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported value envelope version {version}"
+            )));
+        }
+        Ok(Some((ValueCodec::try_from(*codec)?, payload)))
+    }
+}
+
+fn invalid_data(
+    err: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> HyperlaneProtocolError {
+    HyperlaneProtocolError::IoError(Error::new(ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Status {
+        Ready,
+        Retry(String),
+    }
+
+    fn write(value: &Status, codec: ValueCodec) -> Vec<u8> {
+        let mut bytes = vec![];
+        let len = ValueEnvelope::write_to(value, codec, &mut bytes).unwrap();
+        assert_eq!(len, bytes.len());
+        bytes
+    }
+
+    #[test]
+    fn test_reads_values_in_any_codec() {
+        let status = Status::Retry("reason".to_owned());
+        for codec in [ValueCodec::Json, ValueCodec::Bincode] {
+            let bytes = write(&status, codec);
+            assert_eq!(ValueEnvelope::open(&bytes).unwrap().unwrap().0, codec);
+            let read: Status = ValueEnvelope::read_from(&mut bytes.as_slice()).unwrap();
+            assert_eq!(read, status);
+        }
+
+        // Values written before envelopes were introduced
+        let legacy = serde_json::to_vec(&status).unwrap();
+        assert!(ValueEnvelope::open(&legacy).unwrap().is_none());
+        let read: Status = ValueEnvelope::read_from(&mut legacy.as_slice()).unwrap();
+        assert_eq!(read, status);
+    }
+
+    #[test]
+    fn test_rejects_unknown_envelopes() {
+        let mut bytes = write(&Status::Ready, ValueCodec::Bincode);
+        bytes[VALUE_ENVELOPE_MAGIC.len()] = VALUE_ENVELOPE_VERSION + 1;
+        assert!(ValueEnvelope::read_from::<Status, _>(&mut bytes.as_slice()).is_err());
+
+        let mut bytes = write(&Status::Ready, ValueCodec::Bincode);
+        bytes[VALUE_ENVELOPE_MAGIC.len() + 1] = 0xff;
+        assert!(ValueEnvelope::read_from::<Status, _>(&mut bytes.as_slice()).is_err());
+
+        assert!(ValueEnvelope::read_from::<Status, _>(&mut &VALUE_ENVELOPE_MAGIC[..]).is_err());
+    }
+}
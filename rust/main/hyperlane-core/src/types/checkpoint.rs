@@ -4,7 +4,7 @@ use derive_more::Deref;
 use serde::{Deserialize, Serialize};
 use sha3::{digest::Update, Digest, Keccak256};
 
-use crate::{utils::domain_hash, Signable, Signature, SignedType, H256};
+use crate::{utils::domain_hash, Signable, Signature, SignatureScheme, SignedType, H256};
 
 /// An Hyperlane checkpoint
 #[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
@@ -71,6 +71,9 @@ pub enum MultisigSignedCheckpointError {
     /// The signed checkpoint has no signatures
     #[error("Multisig signed checkpoint has no signatures")]
     EmptySignatures(),
+    /// A signature of the signed checkpoint isn't an ECDSA signature
+    #[error("Multisig signed checkpoint has a {0:?} signature")]
+    UnsupportedSignatureScheme(SignatureScheme),
 }
 
 impl TryFrom<&mut Vec<SignedCheckpointWithMessageId>> for MultisigSignedCheckpoint {
@@ -90,7 +93,14 @@ impl TryFrom<&mut Vec<SignedCheckpointWithMessageId>> for MultisigSignedCheckpoi
             return Err(MultisigSignedCheckpointError::InconsistentCheckpoints());
         }
 
-        let signatures = signed_checkpoints.iter().map(|c| c.signature).collect();
+        let signatures = signed_checkpoints
+            .iter()
+            .map(|c| {
+                c.signature.ecdsa().ok_or_else(|| {
+                    MultisigSignedCheckpointError::UnsupportedSignatureScheme(c.signature.scheme())
+                })
+            })
+            .collect::<Result<_, _>>()?;
 
         Ok(MultisigSignedCheckpoint {
             checkpoint,
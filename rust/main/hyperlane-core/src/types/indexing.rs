@@ -1,6 +1,8 @@
 use derive_new::new;
 
-use crate::{HyperlaneMessage, InterchainGasPayment, MerkleTreeInsertion, Sequenced, H256};
+use crate::{
+    HyperlaneMessage, InterchainGasPayment, LogMeta, MerkleTreeInsertion, Sequenced, H256,
+};
 
 /// Wrapper struct that adds indexing information to a type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, new)]
@@ -30,6 +32,18 @@ impl<T> TryFrom<Indexed<T>> for SequenceIndexed<T> {
     }
 }
 
+/// A block that logs were indexed from, along with its hash at the time, so
+/// that a reorg of the block can be detected and its logs invalidated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedBlock<T> {
+    /// The height of the block
+    pub number: u64,
+    /// The hash of the block when its logs were indexed
+    pub hash: H256,
+    /// The logs indexed from the block
+    pub logs: Vec<(Indexed<T>, LogMeta)>,
+}
+
 /// Convert a vector of `Indexed` values to a vector of `SequenceIndexed` values
 /// so that if any `Option` is `None`, the conversion will fail
 pub fn indexed_to_sequence_indexed_array<T, U>(
@@ -54,7 +54,7 @@ mock! {
             &self,
             message: &HyperlaneMessage,
             metadata: &[u8],
-        ) -> Vec<u8> {}
+        ) -> ChainResult<Vec<u8>> {}
     }
 }
 
@@ -106,7 +106,11 @@ impl Mailbox for MockMailboxContract {
         self.process_estimate_costs(message, metadata)
     }
 
-    fn process_calldata(&self, message: &HyperlaneMessage, metadata: &[u8]) -> Vec<u8> {
+    fn process_calldata(
+        &self,
+        message: &HyperlaneMessage,
+        metadata: &[u8],
+    ) -> ChainResult<Vec<u8>> {
         self.process_calldata(message, metadata)
     }
 }
@@ -0,0 +1,59 @@
+#![allow(non_snake_case)]
+use core::fmt::Debug;
+use mockall::*;
+
+use async_trait::async_trait;
+use hyperlane_core::*;
+
+mock! {
+    pub MetadataChunkStoreContract {
+        fn _domain(&self) -> &HyperlaneDomain;
+        fn _provider(&self) -> Box<dyn HyperlaneProvider>;
+        fn _address(&self) -> H256;
+        fn _store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<TxOutcome>;
+        fn _estimate_store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<U256>;
+        fn _clear(&self, key: H256) -> ChainResult<TxOutcome>;
+        fn _pointer_metadata(&self, key: H256) -> ChainResult<Vec<u8>>;
+    }
+}
+
+impl HyperlaneChain for MockMetadataChunkStoreContract {
+    fn domain(&self) -> &HyperlaneDomain {
+        self._domain()
+    }
+
+    fn provider(&self) -> Box<dyn HyperlaneProvider> {
+        self._provider()
+    }
+}
+
+impl HyperlaneContract for MockMetadataChunkStoreContract {
+    fn address(&self) -> H256 {
+        self._address()
+    }
+}
+
+impl Debug for MockMetadataChunkStoreContract {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MetadataChunkStore for MockMetadataChunkStoreContract {
+    async fn store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<TxOutcome> {
+        self._store_chunk(key, index, chunk)
+    }
+
+    async fn estimate_store_chunk(&self, key: H256, index: u32, chunk: &[u8]) -> ChainResult<U256> {
+        self._estimate_store_chunk(key, index, chunk)
+    }
+
+    async fn clear(&self, key: H256) -> ChainResult<TxOutcome> {
+        self._clear(key)
+    }
+
+    async fn pointer_metadata(&self, key: H256) -> ChainResult<Vec<u8>> {
+        self._pointer_metadata(key)
+    }
+}
@@ -1,6 +1,8 @@
 /// Mock mailbox contract
 pub mod mailbox;
+pub mod metadata_chunk_store;
 pub mod validator_announce;
 
 pub use mailbox::MockMailboxContract;
+pub use metadata_chunk_store::MockMetadataChunkStoreContract;
 pub use validator_announce::MockValidatorAnnounceContract;
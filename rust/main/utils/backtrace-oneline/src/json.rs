@@ -0,0 +1,145 @@
+//! Structured backtraces, for log pipelines that parse JSON rather than text.
+
+use std::env;
+use std::path::Path;
+
+use backtrace::{Backtrace, BacktraceSymbol};
+use serde::Serialize;
+
+/// Crates whose frames are skipped with [`JsonBacktraceOptions::skip_std`]
+const STD_CRATES: &[&str] = &["std", "core", "alloc", "backtrace", "rust_begin_unwind"];
+/// Crates whose frames are skipped with [`JsonBacktraceOptions::skip_tokio`]
+const TOKIO_CRATES: &[&str] = &["tokio", "tokio_util", "futures_util", "futures_core"];
+
+/// Options for [`fmt_backtrace_json`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonBacktraceOptions {
+    /// Print full paths instead of paths relative to the current directory
+    pub show_full_paths: bool,
+    /// Skip frames of the standard library and of the backtrace capture itself
+    pub skip_std: bool,
+    /// Skip frames of the tokio runtime and of future combinators
+    pub skip_tokio: bool,
+}
+
+/// A symbol of a backtrace frame. Frames with inlined functions resolve to
+/// several symbols.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JsonBacktraceFrame {
+    /// Demangled symbol name, without the hash
+    pub symbol: Option<String>,
+    /// Source file, relative to the current directory unless full paths are
+    /// shown
+    pub file: Option<String>,
+    /// Line in the source file
+    pub line: Option<u32>,
+    /// Column in the source file
+    pub column: Option<u32>,
+}
+
+/// The frames of a backtrace, in the order they were captured, i.e. innermost
+/// first
+pub fn backtrace_frames(
+    backtrace: &Backtrace,
+    options: &JsonBacktraceOptions,
+) -> Vec<JsonBacktraceFrame> {
+    let cwd = if !options.show_full_paths {
+        env::current_dir().ok()
+    } else {
+        None
+    };
+
+    backtrace
+        .frames()
+        .iter()
+        // "null" frames only mean that the system backtrace traced back too far
+        .filter(|frame| !frame.ip().is_null())
+        .flat_map(|frame| {
+            let symbols = frame.symbols();
+            if symbols.is_empty() {
+                vec![JsonBacktraceFrame {
+                    symbol: None,
+                    file: None,
+                    line: None,
+                    column: None,
+                }]
+            } else {
+                symbols
+                    .iter()
+                    .map(|symbol| json_frame(symbol, cwd.as_deref()))
+                    .collect()
+            }
+        })
+        .filter(|frame| !is_skipped(frame.symbol.as_deref(), options))
+        .collect()
+}
+
+/// Format a backtrace as a JSON array of frames with their symbol, file, line
+/// and column, to be included in JSON log output.
+pub fn fmt_backtrace_json(
+    backtrace: &Backtrace,
+    options: &JsonBacktraceOptions,
+) -> serde_json::Value {
+    serde_json::to_value(backtrace_frames(backtrace, options))
+        .expect("backtrace frames are always serializable")
+}
+
+fn json_frame(symbol: &BacktraceSymbol, cwd: Option<&Path>) -> JsonBacktraceFrame {
+    JsonBacktraceFrame {
+        symbol: symbol.name().map(|name| format!("{name:#}")),
+        file: symbol.filename().map(|path| display_path(path, cwd)),
+        line: symbol.lineno(),
+        column: symbol.colno(),
+    }
+}
+
+fn display_path(path: &Path, cwd: Option<&Path>) -> String {
+    cwd.and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// Whether a frame of the symbol is filtered out. Trait impls such as
+/// `<core::pin::Pin<P> as core::future::Future>::poll` belong to the crate of
+/// the implementing type.
+fn is_skipped(symbol: Option<&str>, options: &JsonBacktraceOptions) -> bool {
+    let Some(symbol) = symbol else {
+        return false;
+    };
+    let symbol = symbol.trim_start_matches('<');
+    let krate = symbol.split("::").next().unwrap_or(symbol);
+    (options.skip_std && STD_CRATES.contains(&krate))
+        || (options.skip_tokio && TOKIO_CRATES.contains(&krate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_skipped() {
+        let all = JsonBacktraceOptions {
+            skip_std: true,
+            skip_tokio: true,
+            ..Default::default()
+        };
+        let none = JsonBacktraceOptions::default();
+
+        for symbol in [
+            "std::panicking::try",
+            "<core::pin::Pin<P> as core::future::Future>::poll",
+            "tokio::runtime::task::harness::poll_future",
+            "<futures_util::future::join_all::JoinAll<F> as core::future::Future>::poll",
+        ] {
+            assert!(is_skipped(Some(symbol), &all), "{symbol}");
+            assert!(!is_skipped(Some(symbol), &none), "{symbol}");
+        }
+        assert!(!is_skipped(
+            Some("relayer::msg::pending_message::PendingMessage::prepare"),
+            &all
+        ));
+        assert!(!is_skipped(Some("tokio_postgres::connect"), &all));
+        assert!(!is_skipped(None, &all));
+    }
+}
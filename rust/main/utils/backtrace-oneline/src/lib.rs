@@ -8,6 +8,10 @@ use std::{env, fmt};
 use backtrace::{Backtrace, BacktraceFrame, BacktraceSymbol, BytesOrWideString, SymbolName};
 use derive_new::new;
 
+pub use json::{backtrace_frames, fmt_backtrace_json, JsonBacktraceFrame, JsonBacktraceOptions};
+
+mod json;
+
 /// Format a backtrace onto a single line.
 /// Largely stolen from backtrace's Debug implementation.
 pub fn fmt_backtrace(
@@ -0,0 +1,261 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, OnceLock, PoisonError},
+    time::Duration,
+};
+
+use reqwest::{Certificate, Client, ClientBuilder, NoProxy, Proxy, Url};
+use thiserror::Error;
+
+/// Request timeout of hosts without a timeout of their own
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Config of all HTTP clients of an agent
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpClientConf {
+    /// Request timeout of hosts not in `host_timeouts`
+    pub timeout: Duration,
+    /// Timeout for establishing connections, which is only bounded by the
+    /// request timeout if unset
+    pub connect_timeout: Option<Duration>,
+    /// Max number of idle connections kept alive per host
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle connections are kept alive
+    pub pool_idle_timeout: Option<Duration>,
+    /// HTTP, HTTPS or SOCKS5 proxy all requests are sent through, except to
+    /// the hosts in the `NO_PROXY` env var. Without one, the proxy in the
+    /// `HTTP_PROXY`, `HTTPS_PROXY` or `ALL_PROXY` env var is used, if any.
+    pub proxy: Option<Url>,
+    /// PEM files of root certificates trusted in addition to the built-in
+    /// ones, e.g. of a TLS-intercepting proxy
+    pub root_certificates: Vec<PathBuf>,
+    /// Request timeouts by host, e.g. for slow archive nodes
+    pub host_timeouts: HashMap<String, Duration>,
+}
+
+impl Default for HttpClientConf {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            proxy: None,
+            root_certificates: vec![],
+            host_timeouts: HashMap::new(),
+        }
+    }
+}
+
+/// Errors building an HTTP client
+#[derive(Debug, Error)]
+pub enum HttpClientError {
+    /// A root certificate couldn't be read
+    #[error("Failed to read root certificate {path:?}")]
+    ReadCertificate {
+        /// Path of the certificate
+        path: PathBuf,
+        /// The underlying error
+        #[source]
+        source: std::io::Error,
+    },
+    /// The config was rejected by reqwest, e.g. an invalid certificate
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    /// A different config was installed before
+    #[error("A different HTTP client config was installed already")]
+    AlreadyInstalled,
+}
+
+static INSTALLED_CONF: OnceLock<HttpClientConf> = OnceLock::new();
+
+/// The config used if none was installed
+static DEFAULT_CONF: OnceLock<HttpClientConf> = OnceLock::new();
+
+/// Clients built by [`client_for`] so far, by request timeout. Clients with
+/// the same timeout share their connection pool.
+static CLIENTS: Mutex<Vec<(Duration, Client)>> = Mutex::new(Vec::new());
+
+impl HttpClientConf {
+    /// Use this config for all clients built by [`client_for`] afterwards,
+    /// so it must be installed before any client is built. Fails if a
+    /// different config was installed before.
+    pub fn install(self) -> Result<(), HttpClientError> {
+        self.install_into(&INSTALLED_CONF)
+    }
+
+    fn install_into(self, installed: &OnceLock<Self>) -> Result<(), HttpClientError> {
+        match installed.set(self) {
+            Ok(()) => Ok(()),
+            Err(conf) if installed.get() == Some(&conf) => Ok(()),
+            Err(_) => Err(HttpClientError::AlreadyInstalled),
+        }
+    }
+
+    /// The installed config, if any
+    pub fn installed() -> Option<&'static Self> {
+        INSTALLED_CONF.get()
+    }
+
+    /// The installed config, or the default one if none was installed
+    pub fn current() -> &'static Self {
+        Self::installed().unwrap_or_else(|| DEFAULT_CONF.get_or_init(Self::default))
+    }
+
+    /// The request timeout of the host of `url`
+    pub fn timeout_for(&self, url: &Url) -> Duration {
+        url.host_str()
+            .and_then(|host| self.host_timeouts.get(host))
+            .copied()
+            .unwrap_or(self.timeout)
+    }
+
+    /// Build a client with this config and the given request timeout
+    pub fn build_client(&self, timeout: Duration) -> Result<Client, HttpClientError> {
+        let mut builder = self.client_builder(timeout)?;
+        for pem in self.root_certificate_pems()? {
+            builder = builder.add_root_certificate(Certificate::from_pem(&pem)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// A builder of a client with the timeouts, connection pool and proxy of
+    /// this config and the given request timeout, for clients that trust
+    /// other root certificates than the configured ones
+    pub fn client_builder(&self, timeout: Duration) -> Result<ClientBuilder, HttpClientError> {
+        let mut builder = Client::builder().timeout(timeout);
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(idle_timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy.clone())?.no_proxy(NoProxy::from_env()));
+        }
+        Ok(builder)
+    }
+
+    /// The PEM encodings of the configured root certificates
+    pub fn root_certificate_pems(&self) -> Result<Vec<Vec<u8>>, HttpClientError> {
+        self.root_certificates
+            .iter()
+            .map(|path| {
+                std::fs::read(path).map_err(|source| HttpClientError::ReadCertificate {
+                    path: path.clone(),
+                    source,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A client for requests to `url`, configured by the installed config or the
+/// default one if none was installed
+pub fn client_for(url: &Url) -> Result<Client, HttpClientError> {
+    let conf = HttpClientConf::current();
+    let timeout = conf.timeout_for(url);
+
+    let mut clients = CLIENTS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((_, client)) = clients.iter().find(|(t, _)| *t == timeout) {
+        return Ok(client.clone());
+    }
+    let client = conf.build_client(timeout)?;
+    clients.push((timeout, client.clone()));
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_timeout_for() {
+        let conf = HttpClientConf {
+            host_timeouts: HashMap::from([(
+                "archive.example.com".to_owned(),
+                Duration::from_secs(300),
+            )]),
+            ..Default::default()
+        };
+        let timeout_for = |url: &str| conf.timeout_for(&url.parse().unwrap());
+        assert_eq!(
+            timeout_for("https://archive.example.com:8545/key"),
+            Duration::from_secs(300)
+        );
+        assert_eq!(timeout_for("https://rpc.example.com"), DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_install() {
+        let installed = OnceLock::new();
+        let conf = HttpClientConf {
+            timeout: Duration::from_secs(10),
+            ..Default::default()
+        };
+        conf.clone().install_into(&installed).unwrap();
+        // Installing the same config again is a no-op
+        conf.clone().install_into(&installed).unwrap();
+        assert!(matches!(
+            HttpClientConf::default().install_into(&installed),
+            Err(HttpClientError::AlreadyInstalled)
+        ));
+        assert_eq!(installed.get(), Some(&conf));
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_sent_through_the_proxy() {
+        let proxy = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let conf = HttpClientConf {
+            proxy: Some(
+                format!("http://{}", proxy.local_addr().unwrap())
+                    .parse()
+                    .unwrap(),
+            ),
+            ..Default::default()
+        };
+        let client = conf.build_client(Duration::from_secs(5)).unwrap();
+
+        let request = tokio::spawn(client.get("http://rpc.hyperlane.invalid/health").send());
+        let (mut stream, _) = proxy.accept().await.unwrap();
+        let mut received = vec![0; 1024];
+        let len = stream.read(&mut received).await.unwrap();
+        // Plain HTTP requests are forwarded with their absolute URL
+        assert!(String::from_utf8_lossy(&received[..len])
+            .starts_with("GET http://rpc.hyperlane.invalid/health HTTP/1.1"));
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+        assert_eq!(request.await.unwrap().unwrap().status(), 200);
+    }
+
+    #[test]
+    fn test_root_certificates() {
+        let certificate = |path: &str| HttpClientConf {
+            root_certificates: vec![PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path)],
+            ..Default::default()
+        };
+
+        assert!(certificate("testdata/root.pem")
+            .build_client(DEFAULT_TIMEOUT)
+            .is_ok());
+        assert!(matches!(
+            certificate("testdata/missing.pem").build_client(DEFAULT_TIMEOUT),
+            Err(HttpClientError::ReadCertificate { .. })
+        ));
+        assert!(matches!(
+            certificate("Cargo.toml").build_client(DEFAULT_TIMEOUT),
+            Err(HttpClientError::Reqwest(_))
+        ));
+    }
+}
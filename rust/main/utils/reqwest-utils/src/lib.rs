@@ -0,0 +1,14 @@
+//! Shared configuration of the HTTP clients of the agents.
+//!
+//! HTTP clients are built deep within the chain crates, so the config is
+//! [installed](HttpClientConf::install) once per agent and every client is
+//! built with [`client_for`] rather than configuring reqwest by hand.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub use client::{client_for, HttpClientConf, HttpClientError};
+pub use retry::RetryPolicy;
+
+mod client;
+mod retry;
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+use tokio::time::sleep;
+use tracing::warn;
+
+/// Exponential backoff retries of HTTP requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Max number of retries after the first attempt
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for every following one
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before the retry with the given index, starting at 0
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Send a request, retrying on connection errors, timeouts and `429` or
+    /// `5xx` responses. Once the retries are exhausted, the last response is
+    /// returned, so that callers handle error statuses as usual. Requests
+    /// with a streaming body can't be cloned and are only sent once.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let mut retry = 0;
+        loop {
+            let Some(attempt) = (retry < self.max_retries)
+                .then(|| request.try_clone())
+                .flatten()
+            else {
+                return request.send().await;
+            };
+            let result = attempt.send().await;
+            match &result {
+                Ok(response) if !is_retryable_status(response.status()) => return result,
+                Err(err) if !is_retryable_error(err) => return result,
+                _ => {}
+            }
+            let backoff = self.backoff(retry);
+            warn!(
+                retry,
+                ?backoff,
+                status = ?result.as_ref().map(Response::status),
+                "Retrying HTTP request"
+            );
+            sleep(backoff).await;
+            retry += 1;
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+        let backoffs: Vec<_> = (0..6).map(|retry| policy.backoff(retry)).collect();
+        assert_eq!(
+            backoffs,
+            [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+        );
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_send_returns_last_error() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+        // Nothing listens on port 9 of localhost
+        let request = reqwest::Client::new().get("http://127.0.0.1:9");
+        assert!(policy.send(request).await.unwrap_err().is_connect());
+    }
+}
@@ -0,0 +1,438 @@
+//! Audits the CPI depth, compute units and accounts of transactions against
+//! Solana's limits.
+//!
+//! Representative transactions, e.g. `process` with each kind of ISM or warp
+//! route transfers, are sent against a local validator (for instance by the
+//! e2e tests or the other commands of this client), and then audited by
+//! signature or by the programs they invoked. With `--watch`, the programs
+//! are polled and every new transaction invoking them is audited as it lands.
+//! Each top-level instruction is reported as a leg, and anything close to a
+//! limit is flagged, so that program authors can check their composability
+//! margins before mainnet.
+
+use std::{
+    collections::HashMap, fs::File, path::Path, str::FromStr, thread::sleep, time::Duration,
+};
+
+use serde::Serialize;
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, packet::PACKET_DATA_SIZE, pubkey::Pubkey,
+    signature::Signature,
+};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+use crate::{AuditCmd, Context, MAX_COMPUTE_UNIT_LIMIT};
+
+/// The maximum height of the instruction stack, where top-level instructions
+/// are at height 1, i.e. 4 levels of CPI.
+// Note: from solana_program_runtime::compute_budget
+const MAX_INSTRUCTION_STACK_HEIGHT: usize = 5;
+/// The maximum number of accounts a transaction can lock.
+// Note: from solana_sdk::transaction::MAX_TX_ACCOUNT_LOCKS
+const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+/// Stats of a top-level instruction, parsed from the transaction logs.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+struct LegLogs {
+    program_id: String,
+    /// The highest instruction stack height reached, 1 without CPIs.
+    max_stack_height: usize,
+    /// Compute units consumed by the instruction and its CPIs.
+    compute_units: Option<u64>,
+}
+
+/// Splits the logs of a transaction into its top-level instructions. Returns
+/// `None` if the logs were truncated.
+fn parse_leg_logs(logs: &[String]) -> Option<Vec<LegLogs>> {
+    let mut legs: Vec<LegLogs> = vec![];
+    for log in logs {
+        if log == "Log truncated" {
+            return None;
+        }
+        let Some(rest) = log.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut words = rest.split_whitespace();
+        let (Some(program_id), Some(action)) = (words.next(), words.next()) else {
+            continue;
+        };
+        match action {
+            "invoke" => {
+                let Some(height) = words
+                    .next()
+                    .and_then(|height| height.strip_prefix('['))
+                    .and_then(|height| height.strip_suffix(']'))
+                    .and_then(|height| height.parse::<usize>().ok())
+                else {
+                    continue;
+                };
+                if height == 1 {
+                    legs.push(LegLogs {
+                        program_id: program_id.to_owned(),
+                        max_stack_height: 1,
+                        compute_units: None,
+                    });
+                } else if let Some(leg) = legs.last_mut() {
+                    leg.max_stack_height = leg.max_stack_height.max(height);
+                }
+            }
+            // e.g. `Program <id> consumed 1234 of 200000 compute units`. The
+            // last one logged for the program of a leg covers all its CPIs.
+            "consumed" => {
+                let Some(leg) = legs.last_mut() else {
+                    continue;
+                };
+                if leg.program_id == program_id {
+                    leg.compute_units = words.next().and_then(|units| units.parse().ok());
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(legs)
+}
+
+#[derive(Debug, Serialize)]
+struct LegReport {
+    program_id: String,
+    cpi_depth: usize,
+    compute_units: Option<u64>,
+    accounts: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct TransactionReport {
+    signature: String,
+    succeeded: bool,
+    compute_units: Option<u64>,
+    accounts: usize,
+    size: usize,
+    legs: Vec<LegReport>,
+    /// Limits the transaction is close to, or beyond.
+    warnings: Vec<String>,
+}
+
+/// Flags `value` if it's at least `warn_percent` percent of `limit`.
+fn check_limit(warnings: &mut Vec<String>, what: &str, value: u64, limit: u64, warn_percent: u32) {
+    if value * 100 >= limit * u64::from(warn_percent) {
+        warnings.push(format!(
+            "{} of {} is {}% of the limit of {}",
+            what,
+            value,
+            value * 100 / limit,
+            limit
+        ));
+    }
+}
+
+fn audit_transaction(ctx: &Context, signature: &Signature, warn_percent: u32) -> TransactionReport {
+    let tx = ctx
+        .client
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+                ..RpcTransactionConfig::default()
+            },
+        )
+        .unwrap_or_else(|err| panic!("Failed to fetch transaction {}: {}", signature, err));
+    let meta = tx
+        .transaction
+        .meta
+        .unwrap_or_else(|| panic!("Transaction {} has no status meta", signature));
+    let versioned_tx = tx
+        .transaction
+        .transaction
+        .decode()
+        .unwrap_or_else(|| panic!("Failed to decode transaction {}", signature));
+
+    let mut warnings = vec![];
+    let loaded_accounts = match &meta.loaded_addresses {
+        OptionSerializer::Some(loaded) => loaded.writable.len() + loaded.readonly.len(),
+        _ => 0,
+    };
+    let accounts = versioned_tx.message.static_account_keys().len() + loaded_accounts;
+    let size = bincode::serialized_size(&versioned_tx).unwrap() as usize;
+    let compute_units = match meta.compute_units_consumed {
+        OptionSerializer::Some(units) => Some(units),
+        _ => None,
+    };
+    let leg_logs = match &meta.log_messages {
+        OptionSerializer::Some(logs) => parse_leg_logs(logs),
+        _ => None,
+    };
+    if leg_logs.is_none() {
+        warnings.push("Logs are missing or truncated, CPI depths are unknown".to_owned());
+    }
+    let legs = versioned_tx
+        .message
+        .instructions()
+        .iter()
+        .enumerate()
+        .map(|(i, ix)| {
+            let logs = leg_logs.as_ref().and_then(|legs| legs.get(i));
+            let program_id = versioned_tx.message.static_account_keys()
+                [ix.program_id_index as usize]
+                .to_string();
+            let cpi_depth = logs.map_or(0, |logs| logs.max_stack_height.saturating_sub(1));
+            check_limit(
+                &mut warnings,
+                &format!("Leg {} ({}) CPI depth", i, program_id),
+                cpi_depth as u64,
+                (MAX_INSTRUCTION_STACK_HEIGHT - 1) as u64,
+                warn_percent,
+            );
+            LegReport {
+                program_id,
+                cpi_depth,
+                compute_units: logs.and_then(|logs| logs.compute_units),
+                accounts: ix.accounts.len(),
+            }
+        })
+        .collect();
+
+    if let Some(compute_units) = compute_units {
+        check_limit(
+            &mut warnings,
+            "Compute units",
+            compute_units,
+            MAX_COMPUTE_UNIT_LIMIT.into(),
+            warn_percent,
+        );
+    }
+    check_limit(
+        &mut warnings,
+        "Account count",
+        accounts as u64,
+        MAX_TX_ACCOUNT_LOCKS as u64,
+        warn_percent,
+    );
+    check_limit(
+        &mut warnings,
+        "Transaction size",
+        size as u64,
+        PACKET_DATA_SIZE as u64,
+        warn_percent,
+    );
+
+    TransactionReport {
+        signature: signature.to_string(),
+        succeeded: meta.err.is_none(),
+        compute_units,
+        accounts,
+        size,
+        legs,
+        warnings,
+    }
+}
+
+/// The signatures of the most recent transactions invoking `program_id`,
+/// newest first, optionally only those after `until`.
+fn recent_signatures(
+    ctx: &Context,
+    program_id: &Pubkey,
+    limit: usize,
+    until: Option<Signature>,
+) -> Vec<Signature> {
+    ctx.client
+        .get_signatures_for_address_with_config(
+            program_id,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(limit),
+                until,
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            },
+        )
+        .unwrap_or_else(|err| panic!("Failed to fetch signatures of {}: {}", program_id, err))
+        .into_iter()
+        .map(|status| Signature::from_str(&status.signature).unwrap())
+        .collect()
+}
+
+/// Appends the signatures that aren't in `signatures` yet, oldest first.
+fn push_new_signatures(signatures: &mut Vec<Signature>, newest_first: Vec<Signature>) {
+    for signature in newest_first.into_iter().rev() {
+        if !signatures.contains(&signature) {
+            signatures.push(signature);
+        }
+    }
+}
+
+fn print_report(report: &TransactionReport) {
+    println!(
+        "{} ({}): {} CUs, {} accounts, {} bytes",
+        report.signature,
+        if report.succeeded {
+            "succeeded"
+        } else {
+            "failed"
+        },
+        report
+            .compute_units
+            .map_or_else(|| "unknown".to_owned(), |units| units.to_string()),
+        report.accounts,
+        report.size,
+    );
+    for (i, leg) in report.legs.iter().enumerate() {
+        println!(
+            "  Leg {}: {} with CPI depth {}, {} CUs, {} accounts",
+            i,
+            leg.program_id,
+            leg.cpi_depth,
+            leg.compute_units
+                .map_or_else(|| "unknown".to_owned(), |units| units.to_string()),
+            leg.accounts,
+        );
+    }
+    for warning in &report.warnings {
+        println!("  WARNING: {}", warning);
+    }
+}
+
+fn print_summary(reports: &[TransactionReport]) {
+    let flagged = reports
+        .iter()
+        .filter(|report| !report.warnings.is_empty())
+        .count();
+    println!(
+        "Audited {} transactions, {} flagged",
+        reports.len(),
+        flagged
+    );
+}
+
+fn write_reports(report_path: &Path, reports: &[TransactionReport]) {
+    let file = File::create(report_path).expect("Failed to create report file");
+    serde_json::to_writer_pretty(file, reports).expect("Failed to write report");
+    println!("Wrote report to {}", report_path.display());
+}
+
+pub(crate) fn process_audit_cmd(ctx: Context, cmd: AuditCmd) {
+    let mut signatures = cmd.signatures;
+    // The newest transaction seen for each program, after which new ones are
+    // looked up when watching
+    let mut newest: HashMap<Pubkey, Signature> = HashMap::new();
+    for program_id in &cmd.program_ids {
+        let recent = recent_signatures(&ctx, program_id, cmd.limit, None);
+        if let Some(signature) = recent.first() {
+            newest.insert(*program_id, *signature);
+        }
+        push_new_signatures(&mut signatures, recent);
+    }
+
+    let mut reports = vec![];
+    for signature in &signatures {
+        let report = audit_transaction(&ctx, signature, cmd.warn_percent);
+        print_report(&report);
+        reports.push(report);
+    }
+    print_summary(&reports);
+    if let Some(report_path) = &cmd.report {
+        write_reports(report_path, &reports);
+    }
+
+    let Some(poll_interval) = cmd.watch else {
+        return;
+    };
+    if cmd.program_ids.is_empty() {
+        println!("No programs to watch");
+        return;
+    }
+    println!("Watching for new transactions, press Ctrl-C to stop");
+    loop {
+        sleep(Duration::from_secs(poll_interval));
+        let mut new_signatures = vec![];
+        for program_id in &cmd.program_ids {
+            // Transactions invoking several watched programs are only
+            // audited once
+            let recent =
+                recent_signatures(&ctx, program_id, cmd.limit, newest.get(program_id).copied());
+            if let Some(signature) = recent.first() {
+                newest.insert(*program_id, *signature);
+            }
+            push_new_signatures(&mut new_signatures, recent);
+        }
+        new_signatures.retain(|signature| !signatures.contains(signature));
+        if new_signatures.is_empty() {
+            continue;
+        }
+        for signature in new_signatures {
+            let report = audit_transaction(&ctx, &signature, cmd.warn_percent);
+            print_report(&report);
+            reports.push(report);
+            signatures.push(signature);
+        }
+        print_summary(&reports);
+        if let Some(report_path) = &cmd.report {
+            write_reports(report_path, &reports);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_leg_logs() {
+        let logs = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program Mailbox invoke [1]",
+            "Program Ism invoke [2]",
+            "Program Ism consumed 30000 of 180000 compute units",
+            "Program Ism success",
+            "Program Recipient invoke [2]",
+            "Program Token invoke [3]",
+            "Program Token consumed 5000 of 100000 compute units",
+            "Program Token success",
+            "Program Recipient consumed 20000 of 110000 compute units",
+            "Program Recipient success",
+            "Program Mailbox consumed 95000 of 200000 compute units",
+            "Program Mailbox success",
+        ]
+        .map(str::to_owned);
+
+        assert_eq!(
+            parse_leg_logs(&logs).unwrap(),
+            vec![
+                LegLogs {
+                    program_id: "ComputeBudget111111111111111111111111111111".to_owned(),
+                    max_stack_height: 1,
+                    compute_units: None,
+                },
+                LegLogs {
+                    program_id: "Mailbox".to_owned(),
+                    max_stack_height: 3,
+                    compute_units: Some(95000),
+                },
+            ]
+        );
+
+        let truncated = [logs[0].clone(), "Log truncated".to_owned()];
+        assert!(parse_leg_logs(&truncated).is_none());
+    }
+
+    #[test]
+    fn test_push_new_signatures() {
+        let [a, b, c] = [1, 2, 3].map(|byte| Signature::from([byte; 64]));
+        let mut signatures = vec![b];
+        push_new_signatures(&mut signatures, vec![c, b, a]);
+        assert_eq!(signatures, vec![b, a, c]);
+    }
+
+    #[test]
+    fn test_check_limit() {
+        let mut warnings = vec![];
+        check_limit(&mut warnings, "CPI depth", 3, 4, 80);
+        assert!(warnings.is_empty());
+        check_limit(&mut warnings, "CPI depth", 4, 4, 80);
+        assert_eq!(warnings, vec!["CPI depth of 4 is 100% of the limit of 4"]);
+    }
+}
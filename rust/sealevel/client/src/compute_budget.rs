@@ -0,0 +1,249 @@
+//! Compute unit limit auto-tuning.
+//!
+//! Rather than requesting the maximum compute unit limit for every transaction,
+//! the limit is set from a simulation of the transaction plus a safety margin.
+//! Transactions can be tagged with an instruction kind (e.g. `transfer_remote`),
+//! in which case the compute units they actually consumed are recorded, and the
+//! highest recent consumption of that kind is used as a floor for the limit.
+//! Per-kind overrides always take precedence.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io,
+    path::PathBuf,
+};
+
+use borsh::BorshDeserialize;
+use serde::{Deserialize, Serialize};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig};
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+};
+
+use crate::MAX_COMPUTE_UNIT_LIMIT;
+
+/// Instruction kind of warp route `transfer_remote` transactions.
+pub(crate) const TRANSFER_REMOTE: &str = "transfer_remote";
+/// Instruction kind of IGP gas oracle config updates.
+pub(crate) const GAS_ORACLE_UPDATE: &str = "gas_oracle_update";
+
+/// Number of recent observations kept per instruction kind.
+const HISTORY_LEN: usize = 20;
+
+/// Recently consumed compute units, by instruction kind.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ComputeUnitHistory(HashMap<String, VecDeque<u32>>);
+
+impl ComputeUnitHistory {
+    fn record(&mut self, kind: &str, units: u32) {
+        let observations = self.0.entry(kind.to_owned()).or_default();
+        observations.push_back(units);
+        while observations.len() > HISTORY_LEN {
+            observations.pop_front();
+        }
+    }
+
+    fn recent_max(&self, kind: &str) -> Option<u32> {
+        self.0.get(kind).and_then(|o| o.iter().max().copied())
+    }
+}
+
+pub(crate) struct ComputeUnitTuner {
+    /// Safety margin added on top of the expected consumption, in percent.
+    margin_percent: u32,
+    /// Fixed compute unit limits by instruction kind.
+    overrides: HashMap<String, u32>,
+    /// File the history is loaded from and persisted to, if any.
+    history_path: Option<PathBuf>,
+    history: RefCell<ComputeUnitHistory>,
+}
+
+impl ComputeUnitTuner {
+    pub(crate) fn new(
+        margin_percent: u32,
+        overrides: HashMap<String, u32>,
+        history_path: Option<PathBuf>,
+    ) -> io::Result<Self> {
+        let history = match history_path.as_ref().filter(|path| path.exists()) {
+            Some(path) => serde_json::from_reader(File::open(path)?)?,
+            None => Default::default(),
+        };
+        Ok(Self {
+            margin_percent,
+            overrides,
+            history_path,
+            history: RefCell::new(history),
+        })
+    }
+
+    /// The compute unit limit instruction to prepend to `instructions`, or
+    /// `None` if they already set a limit.
+    pub(crate) fn compute_unit_limit_instruction(
+        &self,
+        client: &RpcClient,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        kind: Option<&str>,
+    ) -> Option<Instruction> {
+        if instructions.iter().any(is_set_compute_unit_limit) {
+            return None;
+        }
+        if let Some(limit) = kind.and_then(|kind| self.overrides.get(kind)) {
+            return Some(ComputeBudgetInstruction::set_compute_unit_limit(*limit));
+        }
+
+        let simulated = simulate_compute_units(client, payer, instructions);
+        let recent = kind.and_then(|kind| self.history.borrow().recent_max(kind));
+        let limit = tuned_limit(simulated, recent, self.margin_percent);
+        println!(
+            "Setting compute unit limit to {} (simulated: {:?}, recent max: {:?})",
+            limit, simulated, recent
+        );
+        Some(ComputeBudgetInstruction::set_compute_unit_limit(limit))
+    }
+
+    /// Records the compute units consumed by a confirmed transaction of the
+    /// given kind.
+    pub(crate) fn record(&self, kind: &str, tx: &EncodedConfirmedTransactionWithStatusMeta) {
+        let consumed =
+            tx.transaction
+                .meta
+                .as_ref()
+                .and_then(|meta| match meta.compute_units_consumed {
+                    OptionSerializer::Some(units) => Some(units),
+                    _ => None,
+                });
+        let Some(consumed) = consumed.and_then(|units| u32::try_from(units).ok()) else {
+            return;
+        };
+        self.history.borrow_mut().record(kind, consumed);
+
+        // The transaction already landed, so failing to persist the history
+        // only costs the tuning of later invocations
+        if let Err(err) = self.persist_history() {
+            eprintln!("Failed to persist compute unit history: {:?}", err);
+        }
+    }
+
+    fn persist_history(&self) -> io::Result<()> {
+        let Some(path) = &self.history_path else {
+            return Ok(());
+        };
+        serde_json::to_writer_pretty(File::create(path)?, &*self.history.borrow())?;
+        Ok(())
+    }
+}
+
+fn is_set_compute_unit_limit(instruction: &Instruction) -> bool {
+    instruction.program_id == compute_budget::id()
+        && matches!(
+            ComputeBudgetInstruction::try_from_slice(&instruction.data),
+            Ok(ComputeBudgetInstruction::SetComputeUnitLimit(_))
+        )
+}
+
+/// Simulates `instructions` with the max compute unit limit and returns the
+/// compute units consumed, or `None` if the simulation failed.
+fn simulate_compute_units(
+    client: &RpcClient,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+) -> Option<u32> {
+    let mut simulation_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+        MAX_COMPUTE_UNIT_LIMIT,
+    )];
+    simulation_instructions.extend_from_slice(instructions);
+    let txn = Transaction::new_unsigned(Message::new(&simulation_instructions, Some(payer)));
+
+    let result = client
+        .simulate_transaction_with_config(
+            &txn,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                ..Default::default()
+            },
+        )
+        .map_err(|err| eprintln!("Failed to simulate transaction: {:?}", err))
+        .ok()?
+        .value;
+    if let Some(err) = result.err {
+        eprintln!("Transaction simulation failed: {:?}", err);
+        return None;
+    }
+    result
+        .units_consumed
+        .filter(|units| *units > 0)
+        .and_then(|units| u32::try_from(units).ok())
+}
+
+/// The compute unit limit for the expected consumption of a transaction. If
+/// neither a simulation nor recent observations are available, the max limit
+/// is used, as the runtime's default of 200k per instruction may be too low.
+fn tuned_limit(simulated: Option<u32>, recent: Option<u32>, margin_percent: u32) -> u32 {
+    simulated
+        .max(recent)
+        .map_or(MAX_COMPUTE_UNIT_LIMIT, |expected| {
+            with_margin(expected, margin_percent)
+        })
+}
+
+fn with_margin(units: u32, margin_percent: u32) -> u32 {
+    let units = u64::from(units) * (100 + u64::from(margin_percent)) / 100;
+    units.min(MAX_COMPUTE_UNIT_LIMIT.into()) as u32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_recent_observations() {
+        let mut history = ComputeUnitHistory::default();
+        assert_eq!(history.recent_max("transfer_remote"), None);
+
+        history.record("transfer_remote", 90_000);
+        for _ in 0..HISTORY_LEN {
+            history.record("transfer_remote", 50_000);
+        }
+        history.record("gas_oracle_update", 200_000);
+
+        // The 90k observation has been evicted
+        assert_eq!(history.recent_max("transfer_remote"), Some(50_000));
+        assert_eq!(history.recent_max("gas_oracle_update"), Some(200_000));
+    }
+
+    #[test]
+    fn test_with_margin() {
+        assert_eq!(with_margin(100_000, 20), 120_000);
+        assert_eq!(with_margin(1_300_000, 20), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn test_tuned_limit() {
+        assert_eq!(tuned_limit(Some(100_000), None, 20), 120_000);
+        assert_eq!(tuned_limit(Some(100_000), Some(150_000), 20), 180_000);
+        assert_eq!(tuned_limit(None, Some(50_000), 20), 60_000);
+        // Without any estimate the max limit is requested
+        assert_eq!(tuned_limit(None, None, 20), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn test_is_set_compute_unit_limit() {
+        assert!(is_set_compute_unit_limit(
+            &ComputeBudgetInstruction::set_compute_unit_limit(1)
+        ));
+        assert!(!is_set_compute_unit_limit(
+            &ComputeBudgetInstruction::request_heap_frame(32 * 1024)
+        ));
+    }
+}
@@ -15,6 +15,8 @@ use solana_sdk::{
 use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
 use std::{cell::RefCell, io::Read};
 
+use crate::compute_budget::ComputeUnitTuner;
+
 pub(crate) struct PayerKeypair {
     pub keypair: Keypair,
     pub keypair_path: String,
@@ -27,6 +29,8 @@ pub(crate) struct Context {
     pub commitment: CommitmentConfig,
     pub initial_instructions: RefCell<Vec<InstructionWithDescription>>,
     pub require_tx_approval: bool,
+    /// Sets compute unit limits automatically, unless a fixed compute budget was requested.
+    compute_unit_tuner: Option<ComputeUnitTuner>,
 }
 
 #[derive(Debug)]
@@ -51,6 +55,7 @@ pub(crate) struct TxnBuilder<'ctx, 'rpc> {
     ctx: &'ctx Context,
     client: Option<&'rpc RpcClient>,
     instructions_with_descriptions: Vec<InstructionWithDescription>,
+    compute_unit_kind: Option<&'static str>,
 }
 
 impl Context {
@@ -61,6 +66,7 @@ impl Context {
         commitment: CommitmentConfig,
         initial_instructions: RefCell<Vec<InstructionWithDescription>>,
         require_tx_approval: bool,
+        compute_unit_tuner: Option<ComputeUnitTuner>,
     ) -> Self {
         Self {
             client,
@@ -69,6 +75,7 @@ impl Context {
             commitment,
             initial_instructions,
             require_tx_approval,
+            compute_unit_tuner,
         }
     }
 
@@ -81,6 +88,7 @@ impl Context {
                 .borrow_mut()
                 .drain(..)
                 .collect(),
+            compute_unit_kind: None,
         }
     }
 
@@ -135,6 +143,33 @@ impl<'ctx, 'rpc> TxnBuilder<'ctx, 'rpc> {
         self
     }
 
+    /// Tags the transaction with an instruction kind, so its compute unit
+    /// consumption is tracked and used to tune later transactions of the same kind.
+    pub(crate) fn with_compute_unit_kind(mut self, kind: &'static str) -> Self {
+        self.compute_unit_kind = Some(kind);
+        self
+    }
+
+    /// Prepends a compute unit limit instruction if auto-tuning is enabled.
+    fn tune_compute_unit_limit(mut self) -> Self {
+        let Some(tuner) = &self.ctx.compute_unit_tuner else {
+            return self;
+        };
+        let client = self.client.unwrap_or(&self.ctx.client);
+        if let Some(instruction) = tuner.compute_unit_limit_instruction(
+            client,
+            &self.ctx.payer_pubkey,
+            &self.instructions(),
+            self.compute_unit_kind,
+        ) {
+            self.instructions_with_descriptions.insert(
+                0,
+                (instruction, Some("Set compute unit limit".to_owned())).into(),
+            );
+        }
+        self
+    }
+
     pub(crate) fn instructions(&self) -> Vec<Instruction> {
         self.instructions_with_descriptions
             .iter()
@@ -184,6 +219,23 @@ impl<'ctx, 'rpc> TxnBuilder<'ctx, 'rpc> {
     pub(crate) fn send<T: Signers>(
         self,
         signers: &T,
+    ) -> Option<EncodedConfirmedTransactionWithStatusMeta> {
+        let txn_builder = self.tune_compute_unit_limit();
+        let compute_unit_kind = txn_builder.compute_unit_kind;
+        let ctx = txn_builder.ctx;
+        let result = txn_builder.send_tuned(signers);
+
+        if let (Some(tuner), Some(kind), Some(tx)) =
+            (&ctx.compute_unit_tuner, compute_unit_kind, &result)
+        {
+            tuner.record(kind, tx);
+        }
+        result
+    }
+
+    fn send_tuned<T: Signers>(
+        self,
+        signers: &T,
     ) -> Option<EncodedConfirmedTransactionWithStatusMeta> {
         // If the payer can't sign, it's presumed that the payer is intended
         // to be a Squads multisig, which must be submitted via a separate
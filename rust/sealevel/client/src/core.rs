@@ -1,19 +1,37 @@
 use borsh::BorshDeserialize;
-use hyperlane_sealevel_mailbox::protocol_fee::ProtocolFee;
+use hyperlane_sealevel_igp::accounts::{GasOracle, IgpAccount, OverheadIgpAccount};
+use hyperlane_sealevel_mailbox::{
+    accounts::{InboxAccount, OutboxAccount},
+    mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds,
+    protocol_fee::ProtocolFee,
+};
+use hyperlane_sealevel_multisig_ism_message_id::{
+    access_control_pda_seeds, accounts::AccessControlAccount,
+};
+use hyperlane_sealevel_validator_announce::{
+    accounts::ValidatorAnnounceAccount, validator_announce_pda_seeds,
+};
 use serde::{Deserialize, Serialize};
 
 use solana_program::pubkey::Pubkey;
 use solana_sdk::{compute_budget, compute_budget::ComputeBudgetInstruction};
 
-use std::{fs::File, path::Path};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Debug,
+    fs::File,
+    path::Path,
+};
 
 use crate::cmd_utils::get_compute_unit_price_micro_lamports_for_chain_name;
 use crate::ONE_SOL_IN_LAMPORTS;
 use crate::{
     artifacts::{read_json, write_json},
     cmd_utils::{create_new_directory, deploy_program},
+    igp::GasOracleConfigWithOverhead,
     multisig_ism::deploy_multisig_ism_message_id,
-    Context, CoreCmd, CoreDeploy, CoreSubCmd,
+    router::ChainMetadata,
+    Context, CoreCheck, CoreCmd, CoreDeploy, CoreSubCmd,
 };
 use hyperlane_core::H256;
 
@@ -109,6 +127,298 @@ pub(crate) fn process_core_cmd(mut ctx: Context, cmd: CoreCmd) {
             };
             write_program_ids(&core_dir, program_ids);
         }
+        CoreSubCmd::Check(check) => {
+            let violations = check_core(&ctx, &check);
+            if violations.is_empty() {
+                println!("No violations found");
+                return;
+            }
+            for violation in &violations {
+                println!("violation: {}", violation.description);
+                println!("  fix: {}", violation.fix);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// A difference between the expected config of a core deployment and its
+/// on-chain state.
+struct Violation {
+    description: String,
+    /// How to resolve the violation, usually a command of this client
+    fix: String,
+}
+
+/// Compares `actual` against `expected`, recording a violation with `fix` if
+/// they differ.
+fn check_eq<T: PartialEq + Debug>(
+    violations: &mut Vec<Violation>,
+    what: &str,
+    actual: T,
+    expected: T,
+    fix: impl FnOnce() -> String,
+) {
+    if actual != expected {
+        violations.push(Violation {
+            description: format!("{what} is {actual:?}, expected {expected:?}"),
+            fix: fix(),
+        });
+    }
+}
+
+fn fetch_account_data(ctx: &Context, account: &Pubkey) -> Option<Vec<u8>> {
+    ctx.client
+        .get_account_with_commitment(account, ctx.commitment)
+        .unwrap()
+        .value
+        .map(|account| account.data)
+}
+
+/// Checks the on-chain state of a core deployment against its expected config.
+fn check_core(ctx: &Context, check: &CoreCheck) -> Vec<Violation> {
+    let program_ids = read_core_program_ids(
+        &check.env_args.environments_dir,
+        &check.env_args.environment,
+        &check.chain,
+    );
+    let owner = Some(check.owner);
+    let mut violations = vec![];
+
+    // Mailbox
+    let mailbox = program_ids.mailbox;
+    let (inbox_key, _inbox_bump) =
+        Pubkey::find_program_address(mailbox_inbox_pda_seeds!(), &mailbox);
+    let (outbox_key, _outbox_bump) =
+        Pubkey::find_program_address(mailbox_outbox_pda_seeds!(), &mailbox);
+    match (
+        fetch_account_data(ctx, &inbox_key),
+        fetch_account_data(ctx, &outbox_key),
+    ) {
+        (Some(inbox), Some(outbox)) => {
+            let inbox = InboxAccount::fetch(&mut &inbox[..]).unwrap().into_inner();
+            let outbox = OutboxAccount::fetch(&mut &outbox[..]).unwrap().into_inner();
+            check_eq(
+                &mut violations,
+                "Mailbox local domain",
+                inbox.local_domain,
+                check.local_domain,
+                || "the Mailbox must be redeployed".to_owned(),
+            );
+            check_eq(
+                &mut violations,
+                "Mailbox default ISM",
+                inbox.default_ism,
+                check
+                    .default_ism
+                    .unwrap_or(program_ids.multisig_ism_message_id),
+                || {
+                    format!(
+                        "mailbox set-default-ism --program-id {mailbox} --default-ism {}",
+                        check
+                            .default_ism
+                            .unwrap_or(program_ids.multisig_ism_message_id)
+                    )
+                },
+            );
+            check_eq(
+                &mut violations,
+                "Mailbox owner",
+                outbox.owner,
+                owner,
+                || {
+                    format!(
+                        "mailbox transfer-ownership --program-id {mailbox} {}",
+                        check.owner
+                    )
+                },
+            );
+        }
+        _ => violations.push(Violation {
+            description: format!("Mailbox {mailbox} is not initialized"),
+            fix: "mailbox init".to_owned(),
+        }),
+    }
+
+    // Multisig ISM
+    let multisig_ism = program_ids.multisig_ism_message_id;
+    let (access_control_key, _access_control_bump) =
+        Pubkey::find_program_address(access_control_pda_seeds!(), &multisig_ism);
+    match fetch_account_data(ctx, &access_control_key) {
+        Some(access_control) => {
+            let access_control = AccessControlAccount::fetch(&mut &access_control[..])
+                .unwrap()
+                .into_inner();
+            check_eq(
+                &mut violations,
+                "Multisig ISM owner",
+                access_control.owner,
+                owner,
+                || {
+                    format!(
+                        "multisig-ism-message-id transfer-ownership --program-id {multisig_ism} {}",
+                        check.owner
+                    )
+                },
+            );
+        }
+        None => violations.push(Violation {
+            description: format!("Multisig ISM {multisig_ism} is not initialized"),
+            fix: "multisig-ism-message-id init".to_owned(),
+        }),
+    }
+
+    // Validator announce
+    let validator_announce = program_ids.validator_announce;
+    let (validator_announce_key, _validator_announce_bump) =
+        Pubkey::find_program_address(validator_announce_pda_seeds!(), &validator_announce);
+    match fetch_account_data(ctx, &validator_announce_key) {
+        Some(data) => {
+            let data = ValidatorAnnounceAccount::fetch(&mut &data[..])
+                .unwrap()
+                .into_inner();
+            let redeploy = || "the ValidatorAnnounce must be redeployed".to_owned();
+            check_eq(
+                &mut violations,
+                "ValidatorAnnounce mailbox",
+                data.mailbox,
+                mailbox,
+                redeploy,
+            );
+            check_eq(
+                &mut violations,
+                "ValidatorAnnounce local domain",
+                data.local_domain,
+                check.local_domain,
+                redeploy,
+            );
+        }
+        None => violations.push(Violation {
+            description: format!("ValidatorAnnounce {validator_announce} is not initialized"),
+            fix: "validator-announce init".to_owned(),
+        }),
+    }
+
+    // IGP
+    check_igp(ctx, check, &program_ids, &mut violations);
+
+    violations
+}
+
+fn check_igp(
+    ctx: &Context,
+    check: &CoreCheck,
+    program_ids: &CoreProgramIds,
+    violations: &mut Vec<Violation>,
+) {
+    let igp_program_id = program_ids.igp_program_id;
+    let (Some(igp), Some(overhead_igp)) = (
+        fetch_account_data(ctx, &program_ids.igp_account),
+        fetch_account_data(ctx, &program_ids.overhead_igp_account),
+    ) else {
+        violations.push(Violation {
+            description: format!(
+                "IGP account {} or overhead IGP account {} is not initialized",
+                program_ids.igp_account, program_ids.overhead_igp_account
+            ),
+            fix: "igp init-igp-account and igp init-overhead-igp-account".to_owned(),
+        });
+        return;
+    };
+    let igp = IgpAccount::fetch(&mut &igp[..]).unwrap().into_inner();
+    let overhead_igp = OverheadIgpAccount::fetch(&mut &overhead_igp[..])
+        .unwrap()
+        .into_inner();
+
+    check_eq(
+        violations,
+        "IGP owner",
+        igp.owner,
+        Some(check.owner),
+        || {
+            format!(
+                "igp transfer-igp-ownership --program-id {igp_program_id} --igp-account {} {}",
+                program_ids.igp_account, check.owner
+            )
+        },
+    );
+    check_eq(
+        violations,
+        "Overhead IGP owner",
+        overhead_igp.owner,
+        Some(check.owner),
+        || {
+            format!(
+                "igp transfer-overhead-igp-ownership --program-id {igp_program_id} --igp-account {} {}",
+                program_ids.overhead_igp_account, check.owner
+            )
+        },
+    );
+    check_eq(
+        violations,
+        "Overhead IGP inner IGP",
+        overhead_igp.inner,
+        program_ids.igp_account,
+        || "igp init-overhead-igp-account with the deployed IGP account".to_owned(),
+    );
+
+    let (Some(gas_oracle_config_file), Some(chain_config_file)) =
+        (&check.gas_oracle_config_file, &check.chain_config_file)
+    else {
+        return;
+    };
+    let chain_configs = read_json::<HashMap<String, ChainMetadata>>(chain_config_file);
+    let gas_oracle_configs = read_json::<
+        HashMap<String, HashMap<String, GasOracleConfigWithOverhead>>,
+    >(gas_oracle_config_file);
+    let expected = gas_oracle_configs
+        .get(&check.chain)
+        .map(|configs| {
+            configs
+                .iter()
+                .map(|(remote, config)| {
+                    let domain = chain_configs
+                        .get(remote)
+                        .unwrap_or_else(|| panic!("Chain config not found for chain: {}", remote))
+                        .domain_id();
+                    (domain, config)
+                })
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    let fix = || {
+        format!(
+            "igp configure --program-id {igp_program_id} --chain {} --gas-oracle-config-file {} --chain-config-file {}",
+            check.chain,
+            gas_oracle_config_file.display(),
+            chain_config_file.display()
+        )
+    };
+    let domains = expected
+        .keys()
+        .chain(igp.gas_oracles.keys())
+        .chain(overhead_igp.gas_overheads.keys())
+        .copied()
+        .collect::<BTreeSet<u32>>();
+    for domain in domains {
+        let config = expected.get(&domain);
+        check_eq(
+            violations,
+            &format!("IGP gas oracle for domain {domain}"),
+            igp.gas_oracles.get(&domain),
+            config
+                .map(|config| GasOracle::RemoteGasData(config.oracle_config.clone()))
+                .as_ref(),
+            fix,
+        );
+        check_eq(
+            violations,
+            &format!("IGP gas overhead for domain {domain}"),
+            overhead_igp.gas_overheads.get(&domain),
+            config.and_then(|config| config.overhead.as_ref()),
+            fix,
+        );
     }
 }
 
@@ -326,4 +636,20 @@ mod test {
             serde_json::from_str(&json_serialized).unwrap();
         assert_eq!(deserialized, protocol_fee_config);
     }
+
+    #[test]
+    fn test_check_eq() {
+        let mut violations = vec![];
+        super::check_eq(&mut violations, "Owner", Some(1), Some(1), || {
+            unreachable!("fix of a matching value")
+        });
+        assert!(violations.is_empty());
+
+        super::check_eq(&mut violations, "Owner", None, Some(1), || {
+            "transfer-ownership".to_owned()
+        });
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].description, "Owner is None, expected Some(1)");
+        assert_eq!(violations[0].fix, "transfer-ownership");
+    }
 }
@@ -4,17 +4,22 @@ use std::collections::{HashMap, HashSet};
 use crate::{
     artifacts::{read_json, try_read_json, write_json, SingularProgramIdArtifact},
     cmd_utils::{create_new_directory, deploy_program},
-    read_core_program_ids,
+    compute_budget, read_core_program_ids,
     router::ChainMetadata,
     Context, GasOverheadSubCmd, GetSetCmd, IgpCmd, IgpSubCmd,
 };
 
 use std::{path::Path, str::FromStr};
 
+use base64::Engine;
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcTransactionConfig,
+};
 use solana_sdk::{
     pubkey::Pubkey,
-    signature::{Keypair, Signer as _},
+    signature::{Keypair, Signature, Signer as _},
 };
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
 
 use hyperlane_core::{KnownHyperlaneDomain, H256};
 
@@ -23,6 +28,7 @@ use hyperlane_sealevel_igp::{
         GasOracle, GasPaymentAccount, IgpAccount, InterchainGasPaymasterType, OverheadIgpAccount,
         ProgramDataAccount as IgpProgramDataAccount, RemoteGasData,
     },
+    events::IgpEvent,
     igp_program_data_pda_seeds,
     instruction::{GasOracleConfig, GasOverheadConfig},
 };
@@ -30,9 +36,9 @@ use hyperlane_sealevel_igp::{
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Compatible with the format of our TS-generated configs.
-struct GasOracleConfigWithOverhead {
-    oracle_config: RemoteGasData,
-    overhead: Option<u64>,
+pub(crate) struct GasOracleConfigWithOverhead {
+    pub(crate) oracle_config: RemoteGasData,
+    pub(crate) overhead: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -200,6 +206,49 @@ pub(crate) fn process_igp_cmd(mut ctx: Context, cmd: IgpCmd) {
                 println!("Gas payment account: {:?}", gas_payment_account);
             }
         }
+        IgpSubCmd::ConfigHistory(args) => {
+            let statuses = ctx
+                .client
+                .get_signatures_for_address_with_config(
+                    &args.account,
+                    GetConfirmedSignaturesForAddress2Config {
+                        limit: Some(args.limit),
+                        commitment: Some(ctx.commitment),
+                        ..GetConfirmedSignaturesForAddress2Config::default()
+                    },
+                )
+                .unwrap();
+
+            // Failed transactions don't change anything
+            for status in statuses.into_iter().filter(|status| status.err.is_none()) {
+                let signature = Signature::from_str(&status.signature).unwrap();
+                let tx = ctx
+                    .client
+                    .get_transaction_with_config(
+                        &signature,
+                        RpcTransactionConfig {
+                            encoding: Some(UiTransactionEncoding::Base64),
+                            commitment: Some(ctx.commitment),
+                            max_supported_transaction_version: Some(0),
+                            ..RpcTransactionConfig::default()
+                        },
+                    )
+                    .unwrap();
+                let logs = match tx.transaction.meta.map(|meta| meta.log_messages) {
+                    Some(OptionSerializer::Some(logs)) => logs,
+                    _ => {
+                        println!("Transaction {} has no logs", signature);
+                        continue;
+                    }
+                };
+                for event in igp_events_in_logs(&args.program_id, &logs) {
+                    println!(
+                        "Slot {}, block time {:?}, transaction {}: {:?}",
+                        status.slot, status.block_time, signature, event
+                    );
+                }
+            }
+        }
         IgpSubCmd::PayForGas(payment_details) => {
             let unique_gas_payment_keypair = Keypair::new();
 
@@ -324,7 +373,10 @@ pub(crate) fn process_igp_cmd(mut ctx: Context, cmd: IgpCmd) {
                             vec![gas_oracle_config],
                         )
                         .unwrap();
-                    ctx.new_txn().add(instruction).send_with_payer();
+                    ctx.new_txn()
+                        .add(instruction)
+                        .with_compute_unit_kind(compute_budget::GAS_ORACLE_UPDATE)
+                        .send_with_payer();
                     println!("Set gas oracle for remote domain {:?}", args.remote_domain);
                 }
                 GetSetCmd::Get(_) => {
@@ -439,6 +491,37 @@ pub(crate) fn process_igp_cmd(mut ctx: Context, cmd: IgpCmd) {
     }
 }
 
+/// The IGP events logged directly by `program_id` in the logs of a
+/// transaction, i.e. not by programs it invoked.
+fn igp_events_in_logs(program_id: &Pubkey, logs: &[String]) -> Vec<IgpEvent> {
+    let program_id = program_id.to_string();
+    // The programs being invoked, innermost last
+    let mut invocations: Vec<&str> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        if let Some(invoked) = log
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(" invoke ["))
+            .map(|(invoked, _)| invoked)
+        {
+            invocations.push(invoked);
+        } else if log.starts_with("Program ")
+            && (log.ends_with(" success") || log.contains(" failed: "))
+        {
+            invocations.pop();
+        } else if let Some(data) = log.strip_prefix("Program data: ") {
+            if invocations.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let Ok(data) = base64::engine::general_purpose::STANDARD.decode(data) else {
+                continue;
+            };
+            events.extend(IgpEvent::decode(&data));
+        }
+    }
+    events
+}
+
 #[allow(clippy::too_many_arguments)]
 fn deploy_igp_program(
     ctx: &mut Context,
@@ -638,7 +721,10 @@ fn configure_igp_and_overhead_igp(
                 )
                 .unwrap();
 
-            ctx.new_txn().add(instruction).send_with_payer();
+            ctx.new_txn()
+                .add(instruction)
+                .with_compute_unit_kind(compute_budget::GAS_ORACLE_UPDATE)
+                .send_with_payer();
 
             println!("Removed gas oracle for remote domain {:?}", remote_domain);
         }
@@ -699,7 +785,10 @@ fn configure_igp_and_overhead_igp(
                 )
                 .unwrap();
 
-            ctx.new_txn().add(instruction).send_with_payer();
+            ctx.new_txn()
+                .add(instruction)
+                .with_compute_unit_kind(compute_budget::GAS_ORACLE_UPDATE)
+                .send_with_payer();
 
             println!(
                 "Set gas oracle for remote domain {:?} ({:?})",
@@ -765,3 +854,40 @@ where
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use borsh::BorshSerialize;
+    use hyperlane_sealevel_igp::events::IGP_EVENT_DISCRIMINATOR;
+
+    use super::*;
+
+    #[test]
+    fn test_igp_events_in_logs() {
+        let program_id = Pubkey::new_unique();
+        let other_program_id = Pubkey::new_unique();
+        let event = IgpEvent::BeneficiarySet {
+            igp: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+        };
+        let mut data = IGP_EVENT_DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        let data_log = format!(
+            "Program data: {}",
+            base64::engine::general_purpose::STANDARD.encode(data)
+        );
+
+        let logs = vec![
+            format!("Program {program_id} invoke [1]"),
+            format!("Program {other_program_id} invoke [2]"),
+            // Logged by another program, ignored
+            data_log.clone(),
+            format!("Program {other_program_id} success"),
+            data_log,
+            "Program data: aGVsbG8=".to_owned(),
+            format!("Program {program_id} success"),
+        ];
+        assert_eq!(igp_events_in_logs(&program_id, &logs), vec![event]);
+    }
+}
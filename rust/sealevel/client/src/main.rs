@@ -11,12 +11,14 @@ use solana_cli_config::{Config, CONFIG_FILE};
 use solana_client::rpc_client::RpcClient;
 use solana_program::pubkey;
 use solana_sdk::{
+    account::from_account,
+    clock::Clock,
     commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::{read_keypair_file, Keypair, Signer as _},
-    system_program,
+    signature::{read_keypair_file, Keypair, Signature, Signer as _},
+    system_program, sysvar,
 };
 
 use account_utils::DiscriminatorEncode;
@@ -27,7 +29,7 @@ use hyperlane_sealevel_igp::{
     igp_gas_payment_pda_seeds, igp_program_data_pda_seeds,
 };
 use hyperlane_sealevel_mailbox::{
-    accounts::{InboxAccount, OutboxAccount},
+    accounts::{InboxAccount, OutboxAccount, ProcessedMessageRetention},
     instruction::{Instruction as MailboxInstruction, OutboxDispatch},
     mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds,
     mailbox_message_dispatch_authority_pda_seeds, mailbox_outbox_pda_seeds,
@@ -38,15 +40,27 @@ use hyperlane_sealevel_mailbox::{
 
 use hyperlane_sealevel_token::{
     hyperlane_token_ata_payer_pda_seeds, hyperlane_token_mint_pda_seeds,
-    spl_associated_token_account::get_associated_token_address_with_program_id, spl_token_2022,
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token_2022::{
+        self,
+        extension::{transfer_fee::TransferFeeConfig, StateWithExtensions},
+        state::Mint,
+    },
 };
 use hyperlane_sealevel_token_collateral::{
-    hyperlane_token_escrow_pda_seeds, plugin::CollateralPlugin,
+    hyperlane_token_escrow_pda_seeds,
+    plugin::CollateralPlugin,
+    transfer_hook::{
+        extra_account_metas_address, resolve_extra_account_metas, transfer_hook_program_id,
+    },
 };
 use hyperlane_sealevel_token_lib::{
-    accounts::HyperlaneTokenAccount,
-    hyperlane_token_pda_seeds,
-    instruction::{Instruction as HtInstruction, TransferRemote as HtTransferRemote},
+    accounts::{HyperlaneTokenAccount, TransferDirection, TransferLimitsAccount},
+    hyperlane_token_pda_seeds, hyperlane_token_transfer_limits_pda_seeds,
+    instruction::{
+        Instruction as HtInstruction, TransferLimitConfig, TransferLimitParams,
+        TransferRemote as HtTransferRemote,
+    },
 };
 use hyperlane_sealevel_token_native::hyperlane_token_native_collateral_pda_seeds;
 use hyperlane_sealevel_validator_announce::{
@@ -61,19 +75,27 @@ use hyperlane_sealevel_validator_announce::{
 use warp_route::parse_token_account_data;
 
 mod artifacts;
+mod audit;
 mod cmd_utils;
+mod compute_budget;
 mod context;
 mod r#core;
 mod helloworld;
 mod igp;
 mod multisig_ism;
+mod processed_messages;
 mod router;
 mod serde;
+mod upgrade;
 mod warp_route;
 
+use crate::audit::process_audit_cmd;
+use crate::compute_budget::ComputeUnitTuner;
 use crate::helloworld::process_helloworld_cmd;
 use crate::igp::process_igp_cmd;
 use crate::multisig_ism::process_multisig_ism_message_id_cmd;
+use crate::processed_messages::process_close_processed_messages;
+use crate::upgrade::process_upgrade_cmd;
 use crate::warp_route::process_warp_route_cmd;
 pub(crate) use crate::{context::*, core::*};
 
@@ -93,8 +115,19 @@ struct Cli {
     url: Option<String>,
     #[arg(long, short)]
     keypair: Option<String>,
-    #[arg(long, short = 'b', default_value_t = MAX_COMPUTE_UNIT_LIMIT)]
-    compute_budget: u32,
+    /// Fixed compute unit limit for every transaction. If unset, the limit is
+    /// tuned per transaction from simulations and recently consumed compute units.
+    #[arg(long, short = 'b')]
+    compute_budget: Option<u32>,
+    /// Safety margin added to tuned compute unit limits, in percent.
+    #[arg(long, default_value_t = 20)]
+    compute_unit_margin_percent: u32,
+    /// Fixed compute unit limit for an instruction kind, e.g. `transfer_remote=150000`.
+    #[arg(long = "compute-unit-override", value_parser = parse_compute_unit_override)]
+    compute_unit_overrides: Vec<(String, u32)>,
+    /// File to persist recently consumed compute units per instruction kind in.
+    #[arg(long)]
+    compute_unit_history: Option<PathBuf>,
     #[arg(long, short = 'a')]
     heap_size: Option<u32>,
     #[arg(long, short = 'C')]
@@ -113,6 +146,8 @@ enum HyperlaneSealevelCmd {
     MultisigIsmMessageId(MultisigIsmMessageIdCmd),
     WarpRoute(WarpRouteCmd),
     HelloWorld(HelloWorldCmd),
+    Upgrade(UpgradeCmd),
+    Audit(AuditCmd),
 }
 
 #[derive(Args)]
@@ -168,6 +203,7 @@ struct CoreCmd {
 #[derive(Subcommand)]
 enum CoreSubCmd {
     Deploy(CoreDeploy),
+    Check(CoreCheck),
 }
 
 #[derive(Args)]
@@ -190,6 +226,25 @@ struct CoreDeploy {
     built_so_dir: PathBuf,
 }
 
+#[derive(Args)]
+struct CoreCheck {
+    #[arg(long)]
+    local_domain: u32,
+    #[command(flatten)]
+    env_args: EnvironmentArgs,
+    #[arg(long)]
+    chain: String,
+    #[arg(long)]
+    owner: Pubkey,
+    /// Defaults to the deployed Multisig ISM
+    #[arg(long)]
+    default_ism: Option<Pubkey>,
+    #[arg(long, requires = "chain_config_file")]
+    gas_oracle_config_file: Option<PathBuf>,
+    #[arg(long)]
+    chain_config_file: Option<PathBuf>,
+}
+
 #[derive(Args)]
 struct MailboxCmd {
     #[command(subcommand)]
@@ -204,6 +259,9 @@ enum MailboxSubCmd {
     Delivered(Delivered),
     TransferOwnership(TransferOwnership),
     SetDefaultIsm(SetDefaultIsm),
+    SetProcessedMessageRetention(SetProcessedMessageRetention),
+    CloseProcessedMessages(CloseProcessedMessages),
+    EnableRecipientIsms(EnableRecipientIsms),
 }
 
 const MAILBOX_PROG_ID: Pubkey = pubkey!("692KZJaoe2KRcD6uhCQDLLXnLNA5ZLnfvdqjE4aX9iu1");
@@ -282,6 +340,42 @@ struct Delivered {
     message_id: H256,
 }
 
+#[derive(Args)]
+struct SetProcessedMessageRetention {
+    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
+    program_id: Pubkey,
+    /// The number of slots after processing a message until its processed
+    /// message PDA can be closed.
+    #[arg(long)]
+    retention_slots: u64,
+    /// The account the rent of closed processed message PDAs is refunded to.
+    #[arg(long)]
+    beneficiary: Pubkey,
+}
+
+#[derive(Args)]
+struct EnableRecipientIsms {
+    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
+    program_id: Pubkey,
+}
+
+#[derive(Args)]
+struct CloseProcessedMessages {
+    #[arg(long, short, default_value_t = MAILBOX_PROG_ID)]
+    program_id: Pubkey,
+    /// The hex encoded messages whose processed message PDAs are closed. By
+    /// default, every processed message PDA past the retention is closed.
+    #[arg(long, short)]
+    message: Vec<String>,
+    /// The maximum number of processed message PDAs to close.
+    #[arg(long)]
+    limit: Option<usize>,
+    /// Close processed message PDAs even if the processed nonces PDAs that
+    /// are created for them cost more rent than is refunded.
+    #[arg(long)]
+    ignore_rent: bool,
+}
+
 #[derive(Args)]
 struct TokenCmd {
     #[command(subcommand)]
@@ -296,6 +390,7 @@ enum TokenSubCmd {
     TransferOwnership(TransferOwnership),
     SetInterchainSecurityModule(SetInterchainSecurityModule),
     Igp(Igp),
+    TransferLimits(TransferLimits),
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -360,6 +455,38 @@ struct Igp {
     cmd: GetSetCmd<GetIgpArgs, SetIgpArgs>,
 }
 
+#[derive(Args)]
+struct TransferLimits {
+    #[arg(long, short, default_value_t = HYPERLANE_TOKEN_PROG_ID)]
+    program_id: Pubkey,
+    #[command(subcommand)]
+    cmd: GetSetCmd<GetTransferLimitsArgs, SetTransferLimitsArgs>,
+}
+
+#[derive(Args)]
+struct GetTransferLimitsArgs {
+    /// Only show the limits of this domain.
+    #[arg(long)]
+    domain: Option<u32>,
+}
+
+#[derive(Args)]
+struct SetTransferLimitsArgs {
+    domain: u32,
+    /// The number of seconds it takes for an exhausted capacity to refill.
+    #[arg(long, default_value_t = 86400)]
+    window_seconds: u32,
+    /// The max amount transferred to the domain within a window, in the local decimals.
+    #[arg(long)]
+    outbound_capacity: Option<u64>,
+    /// The max amount transferred from the domain within a window, in the local decimals.
+    #[arg(long)]
+    inbound_capacity: Option<u64>,
+    /// Remove the limits of the domain.
+    #[arg(long, conflicts_with_all = ["outbound_capacity", "inbound_capacity"])]
+    remove: bool,
+}
+
 #[derive(Subcommand)]
 enum GetSetCmd<G: Args, S: Args> {
     Get(G),
@@ -397,6 +524,7 @@ enum IgpSubCmd {
     InitIgpAccount(InitIgpAccountArgs),
     InitOverheadIgpAccount(InitOverheadIgpAccountArgs),
     Query(IgpQueryArgs),
+    ConfigHistory(IgpConfigHistoryArgs),
     PayForGas(PayForGasArgs),
     Claim(ClaimArgs),
     SetIgpBeneficiary(SetIgpBeneficiaryArgs),
@@ -457,6 +585,20 @@ struct IgpQueryArgs {
     gas_payment_account: Option<Pubkey>,
 }
 
+/// Prints the configuration changes made to an IGP or OverheadIGP account,
+/// newest first.
+#[derive(Args)]
+struct IgpConfigHistoryArgs {
+    #[arg(long)]
+    program_id: Pubkey,
+    /// The IGP or OverheadIGP account.
+    #[arg(long)]
+    account: Pubkey,
+    /// The maximum number of transactions to inspect.
+    #[arg(long, default_value_t = 100)]
+    limit: usize,
+}
+
 #[derive(Args)]
 struct TransferIgpOwnership {
     #[arg(long, short)]
@@ -618,6 +760,9 @@ enum MultisigIsmMessageIdSubCmd {
     Query(MultisigIsmMessageIdQuery),
     TransferOwnership(TransferOwnership),
     Configure(MultisigIsmMessageIdConfigure),
+    /// Rotate the validator sets of many origin domains at once, batching
+    /// the updates into as few transactions as possible
+    Rotate(MultisigIsmMessageIdRotate),
 }
 
 #[derive(Args)]
@@ -642,6 +787,19 @@ struct MultisigIsmMessageIdConfigure {
     chain_config_file: PathBuf,
 }
 
+#[derive(Args)]
+struct MultisigIsmMessageIdRotate {
+    #[arg(long)]
+    program_id: Pubkey,
+    #[arg(long)]
+    multisig_config_file: PathBuf,
+    #[arg(long)]
+    chain_config_file: PathBuf,
+    /// Only print the changes that would be made
+    #[arg(long)]
+    dry_run: bool,
+}
+
 #[derive(Args)]
 struct MultisigIsmMessageIdInit {
     #[arg(long, short, default_value_t = MULTISIG_ISM_MESSAGE_ID_PROG_ID)]
@@ -700,6 +858,77 @@ pub(crate) struct HelloWorldQuery {
     program_id: Pubkey,
 }
 
+#[derive(Args)]
+pub(crate) struct UpgradeCmd {
+    #[command(subcommand)]
+    cmd: UpgradeSubCmd,
+}
+
+/// Audits the CPI depth, compute units and accounts of transactions, e.g.
+/// representative ones sent against a local validator, flagging anything near
+/// Solana's limits.
+#[derive(Args)]
+pub(crate) struct AuditCmd {
+    /// Transactions to audit.
+    #[arg(long = "signature")]
+    signatures: Vec<Signature>,
+    /// Programs whose recent transactions are audited.
+    #[arg(long = "program-id")]
+    program_ids: Vec<Pubkey>,
+    /// Number of recent transactions audited per program.
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+    /// Percentage of a limit from which a transaction is flagged.
+    #[arg(long, default_value_t = 80)]
+    warn_percent: u32,
+    /// File to write the report to, as JSON.
+    #[arg(long)]
+    report: Option<PathBuf>,
+    /// Keep polling the programs every this many seconds and audit their new
+    /// transactions as they land.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    watch: Option<u64>,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum UpgradeSubCmd {
+    Verify(UpgradeVerify),
+}
+
+/// Verifies that a buffer holds the locally built program, and proposes the
+/// upgrade from it.
+#[derive(Args)]
+pub(crate) struct UpgradeVerify {
+    /// The program to upgrade.
+    #[arg(long)]
+    program_id: Pubkey,
+    /// The buffer holding the new program, written with `solana program write-buffer`.
+    #[arg(long)]
+    buffer: Pubkey,
+    /// The locally built program, e.g. from a verifiable build.
+    #[arg(long)]
+    built_so: PathBuf,
+    /// Recipient of the buffer's rent. Defaults to the upgrade authority.
+    #[arg(long)]
+    spill: Option<Pubkey>,
+}
+
+fn parse_compute_unit_override(s: &str) -> Result<(String, u32), String> {
+    let (kind, units) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Expected <kind>=<units>, got {}", s))?;
+    let units = units
+        .parse::<u32>()
+        .map_err(|e| format!("Invalid compute units {}: {}", units, e))?;
+    if units > MAX_COMPUTE_UNIT_LIMIT {
+        return Err(format!(
+            "Compute units {} exceed the maximum of {}",
+            units, MAX_COMPUTE_UNIT_LIMIT
+        ));
+    }
+    Ok((kind.to_owned(), units))
+}
+
 fn main() {
     pretty_env_logger::init();
 
@@ -736,16 +965,32 @@ fn main() {
 
     let mut instructions = vec![];
 
-    if cli.compute_budget != DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT {
-        assert!(cli.compute_budget <= MAX_COMPUTE_UNIT_LIMIT);
-        instructions.push(
-            (
-                ComputeBudgetInstruction::set_compute_unit_limit(cli.compute_budget),
-                Some(format!("Set compute unit limit to {}", cli.compute_budget)),
-            )
-                .into(),
-        );
-    }
+    let compute_unit_tuner = match cli.compute_budget {
+        Some(compute_budget) => {
+            if compute_budget != DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT {
+                assert!(compute_budget <= MAX_COMPUTE_UNIT_LIMIT);
+                instructions.push(
+                    (
+                        ComputeBudgetInstruction::set_compute_unit_limit(compute_budget),
+                        Some(format!("Set compute unit limit to {}", compute_budget)),
+                    )
+                        .into(),
+                );
+            }
+            None
+        }
+        None => match ComputeUnitTuner::new(
+            cli.compute_unit_margin_percent,
+            cli.compute_unit_overrides.into_iter().collect(),
+            cli.compute_unit_history,
+        ) {
+            Ok(tuner) => Some(tuner),
+            Err(err) => {
+                eprintln!("Failed to load compute unit history: {:?}", err);
+                std::process::exit(1);
+            }
+        },
+    };
     if let Some(heap_size) = cli.heap_size {
         assert!(heap_size <= MAX_HEAP_FRAME_BYTES);
         instructions.push(
@@ -764,6 +1009,7 @@ fn main() {
         commitment,
         instructions.into(),
         cli.require_tx_approval,
+        compute_unit_tuner,
     );
     match cli.cmd {
         HyperlaneSealevelCmd::Mailbox(cmd) => process_mailbox_cmd(ctx, cmd),
@@ -776,6 +1022,8 @@ fn main() {
         HyperlaneSealevelCmd::WarpRoute(cmd) => process_warp_route_cmd(ctx, cmd),
         HyperlaneSealevelCmd::HelloWorld(cmd) => process_helloworld_cmd(ctx, cmd),
         HyperlaneSealevelCmd::Igp(cmd) => process_igp_cmd(ctx, cmd),
+        HyperlaneSealevelCmd::Upgrade(cmd) => process_upgrade_cmd(ctx, cmd),
+        HyperlaneSealevelCmd::Audit(cmd) => process_audit_cmd(ctx, cmd),
     }
 }
 
@@ -903,6 +1151,41 @@ fn process_mailbox_cmd(ctx: Context, cmd: MailboxCmd) {
                 )
                 .send_with_payer();
         }
+        MailboxSubCmd::SetProcessedMessageRetention(set_retention) => {
+            let instruction =
+                hyperlane_sealevel_mailbox::instruction::set_processed_message_retention_instruction(
+                    set_retention.program_id,
+                    ctx.payer_pubkey,
+                    ProcessedMessageRetention {
+                        retention_slots: set_retention.retention_slots,
+                        beneficiary: set_retention.beneficiary,
+                    },
+                )
+                .unwrap();
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!(
+                        "Setting processed message retention to {} slots, refunding rent to {}",
+                        set_retention.retention_slots, set_retention.beneficiary
+                    ),
+                )
+                .send_with_payer();
+        }
+        MailboxSubCmd::CloseProcessedMessages(close) => {
+            process_close_processed_messages(&ctx, close);
+        }
+        MailboxSubCmd::EnableRecipientIsms(enable) => {
+            let instruction =
+                hyperlane_sealevel_mailbox::instruction::enable_recipient_isms_instruction(
+                    enable.program_id,
+                    ctx.payer_pubkey,
+                )
+                .unwrap();
+            ctx.new_txn()
+                .add_with_description(instruction, "Enabling recipient ISM overrides".to_string())
+                .send_with_payer();
+        }
     };
 }
 
@@ -1073,6 +1356,16 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
             let (mailbox_outbox_account, _mailbox_outbox_bump) =
                 Pubkey::find_program_address(mailbox_outbox_pda_seeds!(), &token.mailbox);
 
+            // The program only transfers the part of the amount that the remote
+            // decimals can represent.
+            let transferred_amount = token.remove_dust(xfer.amount).unwrap();
+            if transferred_amount != xfer.amount {
+                println!(
+                    "Amount {} has more precision than the remote decimals {}, only {} will be transferred",
+                    xfer.amount, token.remote_decimals, transferred_amount
+                );
+            }
+
             let ixn = HtInstruction::TransferRemote(HtTransferRemote {
                 destination_domain: xfer.destination_domain,
                 recipient,
@@ -1100,6 +1393,7 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
             // 13.   [writeable] The IGP account.
             //       ---- End if ----
             // 14..N [??..??] Plugin-specific accounts.
+            // N+1.  [writeable] OPTIONAL - The transfer limits PDA account.
             let mut accounts = vec![
                 AccountMeta::new_readonly(system_program::id(), false),
                 AccountMeta::new_readonly(spl_noop::id(), false),
@@ -1188,6 +1482,8 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                     // 6. [writeable] The mint.
                     // 7. [writeable] The token sender's associated token account, from which tokens will be sent.
                     // 8. [writeable] The escrow PDA account.
+                    // 9..N [??..??] If the mint has a transfer hook, the hook program, its
+                    //      extra account metas PDA and the extra accounts it requires.
                     let token = HyperlaneTokenAccount::<CollateralPlugin>::fetch(
                         &mut &fetched_token_account.data[..],
                     )
@@ -1205,20 +1501,86 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                         AccountMeta::new(sender_associated_token_account, false),
                         AccountMeta::new(token.plugin_data.escrow, false),
                     ]);
+
+                    // Token-2022 mints can charge a fee on transfers, which is
+                    // withheld from the amount sent to the remote.
+                    let mint_account = ctx
+                        .client
+                        .get_account_with_commitment(&token.plugin_data.mint, ctx.commitment)
+                        .unwrap()
+                        .value
+                        .unwrap();
+                    let mint = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+                    if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+                        let epoch = ctx.client.get_epoch_info().unwrap().epoch;
+                        let fee = transfer_fee_config
+                            .calculate_epoch_fee(epoch, xfer.amount)
+                            .unwrap();
+                        println!(
+                            "Transfer fee of {} withheld, {} will be sent to the remote",
+                            fee,
+                            xfer.amount - fee
+                        );
+                    }
+
+                    if let Some(hook_program_id) =
+                        transfer_hook_program_id(&mint_account.data).unwrap()
+                    {
+                        let extra_account_metas_account =
+                            extra_account_metas_address(&token.plugin_data.mint, &hook_program_id);
+                        let extra_account_metas_data = ctx
+                            .client
+                            .get_account_with_commitment(
+                                &extra_account_metas_account,
+                                ctx.commitment,
+                            )
+                            .unwrap()
+                            .value
+                            .unwrap()
+                            .data;
+                        accounts.extend([
+                            AccountMeta::new_readonly(hook_program_id, false),
+                            AccountMeta::new_readonly(extra_account_metas_account, false),
+                        ]);
+                        accounts.extend(
+                            resolve_extra_account_metas(
+                                &extra_account_metas_data,
+                                &hook_program_id,
+                                [
+                                    sender_associated_token_account,
+                                    token.plugin_data.mint,
+                                    token.plugin_data.escrow,
+                                    sender.pubkey(),
+                                ],
+                                transferred_amount,
+                            )
+                            .unwrap(),
+                        );
+                    }
                 }
             }
 
+            let (transfer_limits_account, _transfer_limits_bump) = Pubkey::find_program_address(
+                hyperlane_token_transfer_limits_pda_seeds!(),
+                &xfer.program_id,
+            );
+            accounts.push(AccountMeta::new(transfer_limits_account, false));
+
             eprintln!("accounts={:#?}", accounts); // FIXME remove
             let xfer_instruction = Instruction {
                 program_id: xfer.program_id,
                 data: ixn.encode().unwrap(),
                 accounts,
             };
-            let tx_result = ctx.new_txn().add(xfer_instruction).send(&[
-                &*ctx.payer_signer(),
-                &sender,
-                &unique_message_account_keypair,
-            ]);
+            let tx_result = ctx
+                .new_txn()
+                .add(xfer_instruction)
+                .with_compute_unit_kind(compute_budget::TRANSFER_REMOTE)
+                .send(&[
+                    &*ctx.payer_signer(),
+                    &sender,
+                    &unique_message_account_keypair,
+                ]);
             // Print the output so it can be used in e2e tests
             println!("{:?}", tx_result);
         }
@@ -1309,6 +1671,93 @@ fn process_token_cmd(mut ctx: Context, cmd: TokenCmd) {
                 parse_token_account_data(get_args.token_type, &mut &token_account.data[..]);
             }
         },
+        TokenSubCmd::TransferLimits(args) => match args.cmd {
+            GetSetCmd::Set(set_args) => {
+                let limit = (!set_args.remove).then_some(TransferLimitParams {
+                    window_seconds: set_args.window_seconds,
+                    outbound_capacity: set_args.outbound_capacity,
+                    inbound_capacity: set_args.inbound_capacity,
+                });
+                let description = format!(
+                    "Set transfer limits of {} for domain {} to {:?}",
+                    args.program_id, set_args.domain, limit
+                );
+                let instruction =
+                    hyperlane_sealevel_token_lib::instruction::set_transfer_limits_instruction(
+                        args.program_id,
+                        ctx.payer_pubkey,
+                        vec![TransferLimitConfig {
+                            domain: set_args.domain,
+                            limit,
+                        }],
+                    )
+                    .unwrap();
+
+                ctx.new_txn()
+                    .add_with_description(instruction, description)
+                    .send_with_payer();
+            }
+            GetSetCmd::Get(get_args) => {
+                let (transfer_limits_account, _transfer_limits_bump) = Pubkey::find_program_address(
+                    hyperlane_token_transfer_limits_pda_seeds!(),
+                    &args.program_id,
+                );
+                let Some(account) = ctx
+                    .client
+                    .get_account_with_commitment(&transfer_limits_account, ctx.commitment)
+                    .unwrap()
+                    .value
+                else {
+                    println!("No transfer limits are set");
+                    return;
+                };
+                let transfer_limits = TransferLimitsAccount::fetch(&mut &account.data[..])
+                    .unwrap()
+                    .into_inner();
+
+                // Capacities refill over time, so they're computed at the cluster's time
+                let now = ctx
+                    .client
+                    .get_account_with_commitment(&sysvar::clock::id(), ctx.commitment)
+                    .map_err(|err| eprintln!("Failed to fetch the clock sysvar: {}", err))
+                    .ok()
+                    .and_then(|response| response.value)
+                    .and_then(|account| from_account::<Clock, _>(&account))
+                    .map(|clock| clock.unix_timestamp);
+                if now.is_none() {
+                    eprintln!("The cluster's time is unknown, only showing max capacities");
+                }
+                let mut domains: Vec<_> = transfer_limits
+                    .limits
+                    .keys()
+                    .filter(|domain| get_args.domain.map_or(true, |d| d == **domain))
+                    .copied()
+                    .collect();
+                domains.sort();
+                for domain in domains {
+                    let limit = &transfer_limits.limits[&domain];
+                    println!("Domain {} (window of {}s):", domain, limit.window_seconds);
+                    for (direction, rate_limit) in [
+                        (TransferDirection::Outbound, &limit.outbound),
+                        (TransferDirection::Inbound, &limit.inbound),
+                    ] {
+                        let capacity =
+                            now.and_then(|now| transfer_limits.capacity(domain, direction, now));
+                        match (rate_limit, capacity) {
+                            (Some(rate_limit), Some(capacity)) => println!(
+                                "  {:?}: {} of {} available",
+                                direction, capacity, rate_limit.max_capacity
+                            ),
+                            (Some(rate_limit), None) => println!(
+                                "  {:?}: max capacity of {}",
+                                direction, rate_limit.max_capacity
+                            ),
+                            (None, _) => println!("  {:?}: unlimited", direction),
+                        }
+                    }
+                }
+            }
+        },
     }
 }
 
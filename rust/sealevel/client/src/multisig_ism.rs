@@ -1,8 +1,9 @@
 use std::collections::{HashMap, HashSet};
-use std::{fs::File, path::Path};
+use std::{fmt, fs::File, path::Path};
 
 use serde::{Deserialize, Serialize};
-use solana_program::pubkey::Pubkey;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{message::Message, packet::PACKET_DATA_SIZE, transaction::Transaction};
 
 use crate::{
     artifacts::{write_json, SingularProgramIdArtifact},
@@ -160,6 +161,15 @@ pub(crate) fn process_multisig_ism_message_id_cmd(mut ctx: Context, cmd: Multisi
                 &configure.chain_config_file,
             );
         }
+        MultisigIsmMessageIdSubCmd::Rotate(rotate) => {
+            rotate_validators(
+                &mut ctx,
+                rotate.program_id,
+                &rotate.multisig_config_file,
+                &rotate.chain_config_file,
+                rotate.dry_run,
+            );
+        }
     }
 }
 
@@ -263,12 +273,185 @@ fn configure_multisig_ism_message_id(
     }
 }
 
-fn multisig_ism_config_matches_chain(
+/// Max size of a transaction of batched validator set updates, leaving room
+/// for the compute unit limit instruction that may be prepended to it
+const MAX_ROTATION_TXN_SIZE: usize = PACKET_DATA_SIZE - 64;
+
+/// A change of the validator set and threshold of a remote domain
+#[derive(Debug)]
+struct ValidatorSetChange {
+    chain_name: String,
+    domain: u32,
+    added: Vec<H160>,
+    removed: Vec<H160>,
+    previous_threshold: Option<u8>,
+    validators_and_threshold: ValidatorsAndThreshold,
+}
+
+impl ValidatorSetChange {
+    /// The change from the `current` validator set of a domain to `expected`,
+    /// or `None` if they match
+    fn new(
+        chain_name: String,
+        domain: u32,
+        current: Option<&ValidatorsAndThreshold>,
+        expected: MultisigIsmConfig,
+    ) -> Option<Self> {
+        let current_validators: HashSet<H160> = current
+            .map(|current| current.validators.iter().cloned().collect())
+            .unwrap_or_default();
+        let expected_validators = HashSet::<H160>::from_iter(expected.validators.iter().cloned());
+        let previous_threshold = current.map(|current| current.threshold);
+        if current_validators == expected_validators
+            && previous_threshold == Some(expected.threshold)
+        {
+            return None;
+        }
+
+        let mut added: Vec<H160> = expected_validators
+            .difference(&current_validators)
+            .cloned()
+            .collect();
+        added.sort();
+        let mut removed: Vec<H160> = current_validators
+            .difference(&expected_validators)
+            .cloned()
+            .collect();
+        removed.sort();
+        Some(Self {
+            chain_name,
+            domain,
+            added,
+            removed,
+            previous_threshold,
+            validators_and_threshold: expected.into(),
+        })
+    }
+}
+
+impl fmt::Display for ValidatorSetChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} (domain {}):", self.chain_name, self.domain)?;
+        match self.previous_threshold {
+            Some(previous) => writeln!(
+                f,
+                "\tthreshold: {} -> {}",
+                previous, self.validators_and_threshold.threshold
+            )?,
+            None => writeln!(
+                f,
+                "\tthreshold: unset -> {}",
+                self.validators_and_threshold.threshold
+            )?,
+        }
+        for validator in &self.added {
+            writeln!(f, "\t+ {:?}", validator)?;
+        }
+        for validator in &self.removed {
+            writeln!(f, "\t- {:?}", validator)?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies the validator sets and thresholds of the multisig config file to
+/// all the domains they differ on. The updates are batched into as few
+/// transactions as fit, so that a rotation across many domains doesn't take
+/// a transaction per domain.
+fn rotate_validators(
     ctx: &mut Context,
     program_id: Pubkey,
+    multisig_config_file_path: &Path,
+    chain_config_path: &Path,
+    dry_run: bool,
+) {
+    let multisig_config_file =
+        File::open(multisig_config_file_path).expect("Failed to open config file");
+    let multisig_configs: HashMap<String, MultisigIsmConfig> =
+        serde_json::from_reader(multisig_config_file).expect("Failed to read config file");
+
+    let chain_config_file = File::open(chain_config_path).unwrap();
+    let chain_configs: HashMap<String, ChainMetadata> =
+        serde_json::from_reader(chain_config_file).unwrap();
+
+    let mut multisig_configs: Vec<_> = multisig_configs.into_iter().collect();
+    multisig_configs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let changes: Vec<ValidatorSetChange> = multisig_configs
+        .into_iter()
+        .filter_map(|(chain_name, multisig_ism_config)| {
+            let domain = chain_configs
+                .get(&chain_name)
+                .unwrap_or_else(|| panic!("No chain config for {}", chain_name))
+                .domain_id();
+            let current = fetch_validators_and_threshold(ctx, program_id, domain);
+            ValidatorSetChange::new(chain_name, domain, current.as_ref(), multisig_ism_config)
+        })
+        .collect();
+
+    if changes.is_empty() {
+        println!("All validator sets are up to date");
+        return;
+    }
+    println!("Validator set changes:");
+    for change in &changes {
+        print!("{}", change);
+    }
+    if dry_run {
+        println!("Dry run, not applying {} changes", changes.len());
+        return;
+    }
+
+    let mut batches: Vec<Vec<(Instruction, String)>> = vec![];
+    for change in changes {
+        let instruction = set_validators_and_threshold_instruction(
+            program_id,
+            ctx.payer_pubkey,
+            change.domain,
+            change.validators_and_threshold.clone(),
+        )
+        .unwrap();
+        let description = format!(
+            "Set for remote domain {} validators and threshold: {:?}",
+            change.domain, change.validators_and_threshold
+        );
+        let fits_in_last_batch = batches.last().map_or(false, |batch| {
+            let mut instructions: Vec<Instruction> = batch
+                .iter()
+                .map(|(instruction, _)| instruction.clone())
+                .collect();
+            instructions.push(instruction.clone());
+            txn_size(&instructions, &ctx.payer_pubkey) <= MAX_ROTATION_TXN_SIZE
+        });
+        match batches.last_mut() {
+            Some(batch) if fits_in_last_batch => batch.push((instruction, description)),
+            _ => batches.push(vec![(instruction, description)]),
+        }
+    }
+
+    println!("Applying changes in {} transaction(s)", batches.len());
+    for batch in batches {
+        batch
+            .into_iter()
+            .fold(ctx.new_txn(), |txn, (instruction, description)| {
+                txn.add_with_description(instruction, description)
+            })
+            .send_with_payer();
+    }
+}
+
+/// The serialized size of a transaction with `instructions`
+fn txn_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let txn = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+    bincode::serialized_size(&txn).unwrap() as usize
+}
+
+/// The validators and threshold of a remote domain, if they're set
+fn fetch_validators_and_threshold(
+    ctx: &Context,
+    program_id: Pubkey,
     remote_domain: u32,
-    expected: &MultisigIsmConfig,
-) -> bool {
+) -> Option<ValidatorsAndThreshold> {
     let (domain_data_key, _domain_data_bump) =
         Pubkey::find_program_address(domain_data_pda_seeds!(remote_domain), &program_id);
 
@@ -276,24 +459,25 @@ fn multisig_ism_config_matches_chain(
         .client
         .get_account_with_commitment(&domain_data_key, ctx.commitment)
         .expect("Failed to get domain data account")
-        .value;
+        .value?;
+    let domain_data = DomainDataAccount::fetch(&mut &domain_data_account.data[..])
+        .unwrap()
+        .into_inner();
+    Some(domain_data.validators_and_threshold)
+}
 
-    if let Some(domain_data_account) = domain_data_account {
-        let domain_data = DomainDataAccount::fetch(&mut &domain_data_account.data[..])
-            .unwrap()
-            .into_inner();
+fn multisig_ism_config_matches_chain(
+    ctx: &mut Context,
+    program_id: Pubkey,
+    remote_domain: u32,
+    expected: &MultisigIsmConfig,
+) -> bool {
+    if let Some(actual) = fetch_validators_and_threshold(ctx, program_id, remote_domain) {
         let expected_validator_set =
             HashSet::<H160>::from_iter(expected.validators.iter().cloned());
-        let actual_validator_set = HashSet::<H160>::from_iter(
-            domain_data
-                .validators_and_threshold
-                .validators
-                .iter()
-                .cloned(),
-        );
+        let actual_validator_set = HashSet::<H160>::from_iter(actual.validators.iter().cloned());
 
-        expected_validator_set == actual_validator_set
-            && expected.threshold == domain_data.validators_and_threshold.threshold
+        expected_validator_set == actual_validator_set && expected.threshold == actual.threshold
     } else {
         false
     }
@@ -322,3 +506,47 @@ pub(crate) fn set_validators_and_threshold(
         )
         .send_with_payer();
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validator_set_change() {
+        let validator = |byte| H160::repeat_byte(byte);
+        let config = |validators: Vec<H160>, threshold| MultisigIsmConfig {
+            validators,
+            threshold,
+        };
+        let current = ValidatorsAndThreshold {
+            validators: vec![validator(1), validator(2)],
+            threshold: 2,
+        };
+
+        // The order of validators doesn't matter
+        assert!(ValidatorSetChange::new(
+            "test".into(),
+            1,
+            Some(&current),
+            config(vec![validator(2), validator(1)], 2),
+        )
+        .is_none());
+
+        let change = ValidatorSetChange::new(
+            "test".into(),
+            1,
+            Some(&current),
+            config(vec![validator(1), validator(3), validator(4)], 2),
+        )
+        .unwrap();
+        assert_eq!(change.added, vec![validator(3), validator(4)]);
+        assert_eq!(change.removed, vec![validator(2)]);
+        assert_eq!(change.previous_threshold, Some(2));
+
+        // Unset domains are always changed
+        let change =
+            ValidatorSetChange::new("test".into(), 1, None, config(vec![validator(1)], 1)).unwrap();
+        assert_eq!(change.added, vec![validator(1)]);
+        assert_eq!(change.previous_threshold, None);
+    }
+}
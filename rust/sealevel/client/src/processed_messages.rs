@@ -0,0 +1,406 @@
+//! Closes the processed message PDAs of a mailbox that are past its processed
+//! message retention, refunding their rent to the retention's beneficiary.
+//!
+//! Closing a PDA marks its message's nonce in a processed nonces PDA, a page
+//! of which is shared by the messages from an origin to a recipient. A new
+//! page costs more rent than a single processed message PDA refunds, so
+//! messages are only closed if the page they need already exists, or if
+//! enough of them are closed at once to pay for it.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use account_utils::SizedData;
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_sdk::{
+    account::Account, message::VersionedMessage, pubkey::Pubkey, signature::Signature,
+};
+use solana_transaction_status::UiTransactionEncoding;
+
+use hyperlane_core::{Decode, HyperlaneMessage, H256};
+use hyperlane_sealevel_mailbox::{
+    accounts::{
+        InboxAccount, ProcessedMessageAccount, ProcessedNonces, ProcessedNoncesAccount,
+        PROCESSED_MESSAGE_DISCRIMINATOR,
+    },
+    instruction::{
+        close_processed_message_instruction, processed_nonces_pda,
+        Instruction as MailboxInstruction,
+    },
+    mailbox_inbox_pda_seeds, mailbox_processed_message_pda_seeds,
+};
+
+use crate::{CloseProcessedMessages, Context};
+
+/// The maximum number of signatures returned by a `getSignaturesForAddress`
+/// request.
+const MAX_SIGNATURES: usize = 1000;
+
+/// A message whose processed message PDA can be closed.
+struct Closable {
+    message: HyperlaneMessage,
+    /// The rent refunded by closing the processed message PDA.
+    lamports: u64,
+}
+
+pub(crate) fn process_close_processed_messages(ctx: &Context, close: CloseProcessedMessages) {
+    let (inbox_account, _inbox_bump) =
+        Pubkey::find_program_address(mailbox_inbox_pda_seeds!(), &close.program_id);
+    let account = ctx
+        .client
+        .get_account_with_commitment(&inbox_account, ctx.commitment)
+        .unwrap()
+        .value
+        .expect("Inbox account not found");
+    let inbox = InboxAccount::fetch(&mut account.data.as_ref())
+        .unwrap()
+        .into_inner();
+    let processed_message_retention = inbox
+        .processed_message_retention
+        .expect("Processed message retention is not set on the mailbox");
+
+    let mut closable = if close.message.is_empty() {
+        find_closable_messages(
+            ctx,
+            &close.program_id,
+            processed_message_retention.retention_slots,
+        )
+    } else {
+        given_closable_messages(ctx, &close.program_id, &close.message)
+    };
+    if let Some(limit) = close.limit {
+        closable.truncate(limit);
+    }
+
+    // Group the messages by the processed nonces page tracking them
+    let mut pages: BTreeMap<Pubkey, Vec<Closable>> = BTreeMap::new();
+    for closable in closable {
+        let (processed_nonces_key, _processed_nonces_bump) =
+            processed_nonces_pda(&close.program_id, &closable.message).unwrap();
+        pages
+            .entry(processed_nonces_key)
+            .or_default()
+            .push(closable);
+    }
+    let page_keys = pages.keys().copied().collect::<Vec<_>>();
+    let existing_pages = get_multiple_accounts(ctx, &page_keys);
+    let page_rent = ctx
+        .client
+        .get_minimum_balance_for_rent_exemption(
+            ProcessedNoncesAccount::from(ProcessedNonces::default()).size(),
+        )
+        .unwrap();
+
+    let mut closed = 0;
+    let mut refunded = 0;
+    let mut paid = 0;
+    for ((page_key, messages), existing_page) in pages.into_iter().zip(existing_pages) {
+        let page_refund = messages
+            .iter()
+            .map(|closable| closable.lamports)
+            .sum::<u64>();
+        if existing_page.is_none() {
+            if page_refund < page_rent && !close.ignore_rent {
+                println!(
+                    "Skipping {} messages of processed nonces page {}, as their refund of {} lamports doesn't cover the page's rent of {} lamports",
+                    messages.len(),
+                    page_key,
+                    page_refund,
+                    page_rent
+                );
+                continue;
+            }
+            paid += page_rent;
+        }
+
+        for closable in messages {
+            let instruction = close_processed_message_instruction(
+                close.program_id,
+                ctx.payer_pubkey,
+                processed_message_retention.beneficiary,
+                &closable.message,
+            )
+            .unwrap();
+            ctx.new_txn()
+                .add_with_description(
+                    instruction,
+                    format!(
+                        "Closing processed message PDA of message {:?}",
+                        closable.message.id()
+                    ),
+                )
+                .send_with_payer();
+            closed += 1;
+            refunded += closable.lamports;
+        }
+    }
+
+    println!(
+        "Closed {} processed message PDAs, refunding {} lamports to {} and paying {} lamports of processed nonces rent",
+        closed, refunded, processed_message_retention.beneficiary, paid
+    );
+}
+
+/// The given hex encoded messages, if their processed message PDAs exist.
+fn given_closable_messages(
+    ctx: &Context,
+    program_id: &Pubkey,
+    encoded_messages: &[String],
+) -> Vec<Closable> {
+    let messages = encoded_messages
+        .iter()
+        .map(|encoded_message| {
+            let encoded_message = hex::decode(
+                encoded_message
+                    .strip_prefix("0x")
+                    .unwrap_or(encoded_message),
+            )
+            .unwrap();
+            HyperlaneMessage::read_from(&mut std::io::Cursor::new(encoded_message)).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let processed_message_keys = messages
+        .iter()
+        .map(|message| {
+            Pubkey::find_program_address(
+                mailbox_processed_message_pda_seeds!(message.id()),
+                program_id,
+            )
+            .0
+        })
+        .collect::<Vec<_>>();
+
+    messages
+        .into_iter()
+        .zip(get_multiple_accounts(ctx, &processed_message_keys))
+        .filter_map(|(message, account)| match account {
+            Some(account) => Some(Closable {
+                message,
+                lamports: account.lamports,
+            }),
+            None => {
+                println!(
+                    "Processed message PDA of message {:?} doesn't exist",
+                    message.id()
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// The messages whose processed message PDAs are past the retention of
+/// `retention_slots`. The messages are read from the transactions that
+/// processed them.
+fn find_closable_messages(
+    ctx: &Context,
+    program_id: &Pubkey,
+    retention_slots: u64,
+) -> Vec<Closable> {
+    let slot = ctx.client.get_slot_with_commitment(ctx.commitment).unwrap();
+
+    #[allow(deprecated)]
+    let memcmp = RpcFilterType::Memcmp(Memcmp {
+        // Skip the `initialized` bool flag.
+        offset: 1,
+        bytes: MemcmpEncodedBytes::Base58(
+            bs58::encode(PROCESSED_MESSAGE_DISCRIMINATOR).into_string(),
+        ),
+        encoding: None,
+    });
+    let accounts = ctx
+        .client
+        .get_program_accounts_with_config(
+            program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![memcmp]),
+                account_config: RpcAccountInfoConfig {
+                    commitment: Some(ctx.commitment),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            },
+        )
+        .unwrap();
+
+    let mut closable = vec![];
+    for (processed_message_key, account) in accounts {
+        let Ok(processed_message) = ProcessedMessageAccount::fetch(&mut account.data.as_ref())
+        else {
+            continue;
+        };
+        let processed_message = processed_message.into_inner();
+        if processed_message.slot.saturating_add(retention_slots) > slot {
+            continue;
+        }
+        match find_processed_message(
+            ctx,
+            program_id,
+            &processed_message_key,
+            processed_message.message_id,
+        ) {
+            Some(message) => closable.push(Closable {
+                message,
+                lamports: account.lamports,
+            }),
+            None => println!(
+                "Could not find the transaction processing message {:?}",
+                processed_message.message_id
+            ),
+        }
+    }
+    closable
+}
+
+/// Finds the message with ID `message_id` in the transaction that processed
+/// it, i.e. the oldest successful transaction of its processed message PDA.
+fn find_processed_message(
+    ctx: &Context,
+    program_id: &Pubkey,
+    processed_message_key: &Pubkey,
+    message_id: H256,
+) -> Option<HyperlaneMessage> {
+    // Signatures are returned newest first, a page at a time
+    let mut oldest = None;
+    let mut before = None;
+    loop {
+        let statuses = ctx
+            .client
+            .get_signatures_for_address_with_config(
+                processed_message_key,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    commitment: Some(ctx.commitment),
+                    ..GetConfirmedSignaturesForAddress2Config::default()
+                },
+            )
+            .unwrap();
+        if let Some(status) = statuses.iter().rev().find(|status| status.err.is_none()) {
+            oldest = Some(Signature::from_str(&status.signature).unwrap());
+        }
+        match statuses.last() {
+            Some(last) if statuses.len() == MAX_SIGNATURES => {
+                before = Some(Signature::from_str(&last.signature).unwrap());
+            }
+            _ => break,
+        }
+    }
+
+    let tx = ctx
+        .client
+        .get_transaction_with_config(
+            &oldest?,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(ctx.commitment),
+                max_supported_transaction_version: Some(0),
+                ..RpcTransactionConfig::default()
+            },
+        )
+        .unwrap();
+    let versioned_tx = tx.transaction.transaction.decode()?;
+    processed_message_in_transaction(program_id, &versioned_tx.message, message_id)
+}
+
+/// The message with ID `message_id` that a top-level `InboxProcess`
+/// instruction of the mailbox `program_id` in `message` processed.
+fn processed_message_in_transaction(
+    program_id: &Pubkey,
+    message: &VersionedMessage,
+    message_id: H256,
+) -> Option<HyperlaneMessage> {
+    let account_keys = message.static_account_keys();
+    message
+        .instructions()
+        .iter()
+        // Program IDs can't be loaded from address lookup tables
+        .filter(|instruction| {
+            account_keys.get(usize::from(instruction.program_id_index)) == Some(program_id)
+        })
+        .filter_map(|instruction| {
+            match MailboxInstruction::from_instruction_data(&instruction.data) {
+                Ok(MailboxInstruction::InboxProcess(process)) => {
+                    HyperlaneMessage::read_from(&mut std::io::Cursor::new(process.message)).ok()
+                }
+                _ => None,
+            }
+        })
+        .find(|message| message.id() == message_id)
+}
+
+fn get_multiple_accounts(ctx: &Context, pubkeys: &[Pubkey]) -> Vec<Option<Account>> {
+    let mut accounts = Vec::with_capacity(pubkeys.len());
+    // `getMultipleAccounts` returns at most 100 accounts
+    for chunk in pubkeys.chunks(100) {
+        accounts.extend(
+            ctx.client
+                .get_multiple_accounts_with_commitment(chunk, ctx.commitment)
+                .unwrap()
+                .value,
+        );
+    }
+    accounts
+}
+
+#[cfg(test)]
+mod test {
+    use hyperlane_core::Encode;
+    use hyperlane_sealevel_mailbox::instruction::InboxProcess;
+    use solana_sdk::{
+        instruction::Instruction,
+        message::{v0, Message},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_processed_message_in_transaction() {
+        let program_id = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let processed = HyperlaneMessage {
+            nonce: 7,
+            body: vec![1, 2, 3],
+            ..HyperlaneMessage::default()
+        };
+        let process = |program_id: Pubkey, message: &HyperlaneMessage| Instruction {
+            program_id,
+            accounts: vec![],
+            data: MailboxInstruction::InboxProcess(InboxProcess {
+                metadata: vec![],
+                message: message.to_vec(),
+            })
+            .into_instruction_data()
+            .unwrap(),
+        };
+        let other = HyperlaneMessage {
+            nonce: 8,
+            ..processed.clone()
+        };
+        let instructions = [
+            // Another program's instruction that happens to decode the same
+            process(Pubkey::new_unique(), &processed),
+            process(program_id, &other),
+            process(program_id, &processed),
+        ];
+
+        let legacy = VersionedMessage::Legacy(Message::new(&instructions, Some(&payer)));
+        assert_eq!(
+            processed_message_in_transaction(&program_id, &legacy, processed.id()),
+            Some(processed.clone())
+        );
+        let v0 = VersionedMessage::V0(
+            v0::Message::try_compile(&payer, &instructions, &[], Default::default()).unwrap(),
+        );
+        assert_eq!(
+            processed_message_in_transaction(&program_id, &v0, other.id()),
+            Some(other)
+        );
+        assert_eq!(
+            processed_message_in_transaction(&program_id, &legacy, H256::zero()),
+            None
+        );
+    }
+}
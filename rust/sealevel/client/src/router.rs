@@ -1,5 +1,5 @@
 use hyperlane_core::{utils::hex_or_base58_to_h256, H256};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fs::File,
@@ -236,6 +236,18 @@ pub(crate) trait RouterDeployer<Config: RouterConfigGetter + std::fmt::Debug>:
         // By default, do nothing.
     }
 
+    /// Writes any artifacts of the deployment besides the program ids, which are
+    /// written for all routers.
+    fn write_deploy_artifacts(
+        &self,
+        _deploy_dir: &Path,
+        _app_configs_to_deploy: &HashMap<&String, &Config>,
+        _chain_configs: &HashMap<String, ChainMetadata>,
+        _routers: &HashMap<u32, H256>,
+    ) {
+        // By default, do nothing.
+    }
+
     /// The program's name, i.e. the name of the program's .so file (without the .so suffix)
     /// and the name that will be used to create the keypair file
     fn program_name(&self, config: &Config) -> &str;
@@ -313,13 +325,10 @@ pub(crate) fn deploy_routers<
     built_so_dir_path: PathBuf,
 ) {
     // Load the app configs from the app config file.
-    let app_config_file = File::open(app_config_file_path).unwrap();
-    let app_configs: HashMap<String, Config> = serde_json::from_reader(app_config_file).unwrap();
+    let app_configs: HashMap<String, Config> = read_config_file(&app_config_file_path);
 
     // Load the chain configs from the chain config file.
-    let chain_config_file = File::open(chain_config_file_path).unwrap();
-    let chain_configs: HashMap<String, ChainMetadata> =
-        serde_json::from_reader(chain_config_file).unwrap();
+    let chain_configs: HashMap<String, ChainMetadata> = read_config_file(&chain_config_file_path);
 
     let environments_dir = create_new_directory(&environments_dir_path, environment);
 
@@ -443,6 +452,30 @@ pub(crate) fn deploy_routers<
         })
         .collect::<HashMap<String, H256>>();
     write_router_program_ids(&deploy_dir, &routers_by_name);
+
+    deployer.write_deploy_artifacts(
+        &deploy_dir,
+        &app_configs_to_deploy,
+        &chain_configs,
+        &routers,
+    );
+}
+
+/// Reads a config file, which is parsed as YAML if it has a `.yaml` or `.yml`
+/// extension and as JSON otherwise.
+fn read_config_file<T: DeserializeOwned>(path: &Path) -> T {
+    let file =
+        File::open(path).unwrap_or_else(|err| panic!("Failed to open {}: {}", path.display(), err));
+    let is_yaml = path
+        .extension()
+        .map_or(false, |extension| extension == "yaml" || extension == "yml");
+    if is_yaml {
+        serde_yaml::from_reader(file)
+            .unwrap_or_else(|err| panic!("Failed to parse {}: {}", path.display(), err))
+    } else {
+        serde_json::from_reader(file)
+            .unwrap_or_else(|err| panic!("Failed to parse {}: {}", path.display(), err))
+    }
 }
 
 // Idempotent.
@@ -0,0 +1,166 @@
+use std::{fs, path::Path};
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::{
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    hash::hash,
+};
+
+use crate::{Context, UpgradeCmd, UpgradeSubCmd};
+
+/// The hash of a program executable, i.e. the SHA-256 hash of its data with
+/// trailing zero bytes removed, as program data accounts are padded with zeros.
+/// This matches the executable hash reported by verifiable build tooling
+/// such as `solana-verify`.
+fn executable_hash(data: &[u8]) -> String {
+    let len = data
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i + 1);
+    hex::encode(hash(&data[..len]).to_bytes())
+}
+
+fn fetch_account_data(ctx: &Context, account: &Pubkey) -> Vec<u8> {
+    ctx.client
+        .get_account_with_commitment(account, ctx.commitment)
+        .unwrap()
+        .value
+        .unwrap_or_else(|| panic!("Account {} not found", account))
+        .data
+}
+
+pub(crate) fn process_upgrade_cmd(ctx: Context, cmd: UpgradeCmd) {
+    match cmd.cmd {
+        UpgradeSubCmd::Verify(verify) => {
+            let program_id = verify.program_id;
+
+            // The buffer holding the proposed upgrade.
+            let buffer_data = fetch_account_data(&ctx, &verify.buffer);
+            let buffer_authority = match bincode::deserialize(&buffer_data).unwrap() {
+                UpgradeableLoaderState::Buffer { authority_address } => authority_address,
+                state => panic!("Account {} is not a buffer: {:?}", verify.buffer, state),
+            };
+            let buffer_executable =
+                &buffer_data[UpgradeableLoaderState::size_of_buffer_metadata()..];
+
+            // The currently deployed program.
+            let programdata_address =
+                match bincode::deserialize(&fetch_account_data(&ctx, &program_id)).unwrap() {
+                    UpgradeableLoaderState::Program {
+                        programdata_address,
+                    } => programdata_address,
+                    state => panic!("Account {} is not a program: {:?}", program_id, state),
+                };
+            let programdata = fetch_account_data(&ctx, &programdata_address);
+            let (deployed_slot, upgrade_authority) =
+                match bincode::deserialize(&programdata).unwrap() {
+                    UpgradeableLoaderState::ProgramData {
+                        slot,
+                        upgrade_authority_address,
+                    } => (slot, upgrade_authority_address),
+                    state => panic!(
+                        "Account {} is not a program data account: {:?}",
+                        programdata_address, state
+                    ),
+                };
+            let deployed_executable =
+                &programdata[UpgradeableLoaderState::size_of_programdata_metadata()..];
+
+            // The locally built artifact the buffer is expected to match.
+            let built_so = fs::read(&verify.built_so).unwrap_or_else(|err| {
+                panic!("Failed to read {}: {}", verify.built_so.display(), err)
+            });
+
+            let built_hash = executable_hash(&built_so);
+            let buffer_hash = executable_hash(buffer_executable);
+            let deployed_hash = executable_hash(deployed_executable);
+
+            // Print the report with one `key: value` per line and in a fixed
+            // order, so reports of the same upgrade can be diffed.
+            println!("program_id: {}", program_id);
+            println!("programdata: {}", programdata_address);
+            println!("deployed_slot: {}", deployed_slot);
+            println!("deployed_hash: {}", deployed_hash);
+            println!(
+                "upgrade_authority: {}",
+                optional_pubkey_to_string(upgrade_authority)
+            );
+            println!("buffer: {}", verify.buffer);
+            println!(
+                "buffer_authority: {}",
+                optional_pubkey_to_string(buffer_authority)
+            );
+            println!("buffer_hash: {}", buffer_hash);
+            println!("built_so: {}", display_file_name(&verify.built_so));
+            println!("built_hash: {}", built_hash);
+
+            let mut problems = vec![];
+            if buffer_hash != built_hash {
+                problems.push("buffer does not match the local build");
+            }
+            if buffer_hash == deployed_hash {
+                problems.push("buffer matches the deployed program, nothing to upgrade");
+            }
+            if upgrade_authority.is_none() {
+                problems.push("program is immutable");
+            }
+            if buffer_authority != upgrade_authority {
+                problems.push("buffer authority is not the program's upgrade authority");
+            }
+            if !problems.is_empty() {
+                for problem in &problems {
+                    println!("error: {}", problem);
+                }
+                std::process::exit(1);
+            }
+            println!("result: ok");
+
+            // The upgrade must be signed by the upgrade authority, which for
+            // Squads-owned programs is the multisig vault. If the payer is the
+            // vault, the transaction is printed to be proposed via Squads.
+            let upgrade_authority = upgrade_authority.unwrap();
+            let spill = verify.spill.unwrap_or(upgrade_authority);
+            let upgrade_instruction = bpf_loader_upgradeable::upgrade(
+                &program_id,
+                &verify.buffer,
+                &upgrade_authority,
+                &spill,
+            );
+            ctx.new_txn()
+                .add_with_description(
+                    upgrade_instruction,
+                    format!(
+                        "Upgrade program {} with buffer {}, refunding the buffer rent to {}",
+                        program_id, verify.buffer, spill
+                    ),
+                )
+                .send_with_payer();
+        }
+    }
+}
+
+fn optional_pubkey_to_string(pubkey: Option<Pubkey>) -> String {
+    pubkey.map_or_else(|| "none".to_owned(), |pubkey| pubkey.to_string())
+}
+
+/// Only the file name is printed, so reports generated from different
+/// checkouts can be compared.
+fn display_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_executable_hash_ignores_padding() {
+        let executable = [1u8, 2, 0, 3];
+        let mut padded = executable.to_vec();
+        padded.extend([0u8; 16]);
+        assert_eq!(executable_hash(&executable), executable_hash(&padded));
+        assert_ne!(executable_hash(&executable), executable_hash(&[1u8, 2, 3]));
+    }
+}
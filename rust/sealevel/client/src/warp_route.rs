@@ -1,11 +1,16 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use hyperlane_core::H256;
-use hyperlane_sealevel_token_collateral::plugin::CollateralPlugin;
-use hyperlane_sealevel_token_native::plugin::NativePlugin;
+use hyperlane_sealevel_token_collateral::{
+    hyperlane_token_escrow_pda_seeds, plugin::CollateralPlugin,
+};
+use hyperlane_sealevel_token_native::{
+    hyperlane_token_native_collateral_pda_seeds, plugin::NativePlugin,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fmt::Debug,
+    path::Path,
     process::{Command, Stdio},
 };
 
@@ -30,6 +35,7 @@ use hyperlane_sealevel_token_lib::{
 };
 
 use crate::{
+    artifacts::write_json,
     cmd_utils::account_exists,
     core::CoreProgramIds,
     router::{
@@ -494,6 +500,87 @@ impl RouterDeployer<TokenConfig> for WarpRouteDeployer {
             }
         }
     }
+
+    /// Writes the accounts of the token on each deployable chain.
+    fn write_deploy_artifacts(
+        &self,
+        deploy_dir: &Path,
+        app_configs_to_deploy: &HashMap<&String, &TokenConfig>,
+        chain_configs: &HashMap<String, ChainMetadata>,
+        routers: &HashMap<u32, H256>,
+    ) {
+        let token_accounts = app_configs_to_deploy
+            .iter()
+            .map(|(chain_name, app_config)| {
+                let domain_id = chain_configs.get(*chain_name).unwrap().domain_id();
+                let program_id =
+                    Pubkey::new_from_array(*routers.get(&domain_id).unwrap().as_fixed_bytes());
+                (
+                    (*chain_name).clone(),
+                    TokenAccountsArtifact::new(program_id, &app_config.token_type),
+                )
+            })
+            .collect::<HashMap<String, TokenAccountsArtifact>>();
+
+        write_json(&deploy_dir.join("token-accounts.json"), token_accounts);
+    }
+}
+
+/// The accounts of a warp route token deployed to a Sealevel chain.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenAccountsArtifact {
+    #[serde(with = "crate::serde::serde_pubkey")]
+    program_id: Pubkey,
+    #[serde(with = "crate::serde::serde_pubkey")]
+    token_pda: Pubkey,
+    /// The SPL token mint, which is absent for native tokens.
+    #[serde(
+        with = "crate::serde::serde_option_pubkey",
+        skip_serializing_if = "Option::is_none"
+    )]
+    mint: Option<Pubkey>,
+    /// The account holding collateral tokens or native collateral.
+    #[serde(
+        with = "crate::serde::serde_option_pubkey",
+        skip_serializing_if = "Option::is_none"
+    )]
+    escrow: Option<Pubkey>,
+}
+
+impl TokenAccountsArtifact {
+    fn new(program_id: Pubkey, token_type: &TokenType) -> Self {
+        let (token_pda, _token_bump) =
+            Pubkey::find_program_address(hyperlane_token_pda_seeds!(), &program_id);
+        let (mint, escrow) = match token_type {
+            TokenType::Native => {
+                let (native_collateral, _native_collateral_bump) = Pubkey::find_program_address(
+                    hyperlane_token_native_collateral_pda_seeds!(),
+                    &program_id,
+                );
+                (None, Some(native_collateral))
+            }
+            TokenType::Synthetic(_) => {
+                let (mint, _mint_bump) =
+                    Pubkey::find_program_address(hyperlane_token_mint_pda_seeds!(), &program_id);
+                (Some(mint), None)
+            }
+            TokenType::Collateral(collateral_info) => {
+                let (escrow, _escrow_bump) =
+                    Pubkey::find_program_address(hyperlane_token_escrow_pda_seeds!(), &program_id);
+                (
+                    Some(collateral_info.mint.parse().expect("Invalid mint address")),
+                    Some(escrow),
+                )
+            }
+        };
+        Self {
+            program_id,
+            token_pda,
+            mint,
+            escrow,
+        }
+    }
 }
 
 impl RouterConfigGetter for TokenConfig {
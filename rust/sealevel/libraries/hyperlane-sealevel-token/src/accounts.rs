@@ -14,7 +14,11 @@ use hyperlane_sealevel_igp::accounts::InterchainGasPaymasterType;
 use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 use std::{cmp::Ordering, collections::HashMap, fmt::Debug};
 
-use crate::hyperlane_token_pda_seeds;
+use crate::{
+    error::Error,
+    hyperlane_token_pda_seeds,
+    instruction::{TransferLimitConfig, TransferLimitParams},
+};
 
 /// HyperlaneToken account data.
 pub type HyperlaneTokenAccount<T> = AccountData<HyperlaneToken<T>>;
@@ -81,10 +85,15 @@ where
 
     /// Converts a remote token amount to a local token amount, accounting for decimals and types.
     pub fn remote_amount_to_local_amount(&self, amount: U256) -> Result<u64, ProgramError> {
-        let amount = convert_decimals(amount, self.remote_decimals, self.decimals)
-            .ok_or(ProgramError::InvalidArgument)?
-            .as_u64();
-        Ok(amount)
+        convert_decimals(amount, self.remote_decimals, self.decimals)
+            .and_then(|amount| amount.try_into().ok())
+            .ok_or(ProgramError::InvalidArgument)
+    }
+
+    /// Rounds a local token amount down to the precision of the remote decimals,
+    /// i.e. removes the dust that would be lost when converting it to a remote amount.
+    pub fn remove_dust(&self, amount: u64) -> Result<u64, ProgramError> {
+        self.remote_amount_to_local_amount(self.local_amount_to_remote_amount(amount)?)
     }
 }
 
@@ -198,6 +207,195 @@ impl<T> HyperlaneGasRouter for HyperlaneToken<T> {
     }
 }
 
+/// Transfer limits account data.
+pub type TransferLimitsAccount = AccountData<TransferLimits>;
+
+/// The direction of a transfer, relative to this chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// A transfer to a remote domain.
+    Outbound,
+    /// A transfer from a remote domain.
+    Inbound,
+}
+
+/// A PDA account holding the owner-configured limits on the amounts
+/// transferred to and from remote domains.
+/// Domains without limits can be transferred to and from without restriction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Default)]
+pub struct TransferLimits {
+    /// The bump seed for this PDA.
+    pub bump: u8,
+    /// The limits of each remote domain.
+    pub limits: HashMap<u32, DomainTransferLimit>,
+}
+
+impl TransferLimits {
+    /// Applies `config`, keeping what was already consumed of any existing
+    /// limit within its current window.
+    pub fn set_limit(&mut self, config: TransferLimitConfig, now: i64) -> Result<(), ProgramError> {
+        let Some(params) = config.limit else {
+            self.limits.remove(&config.domain);
+            return Ok(());
+        };
+        let TransferLimitParams {
+            window_seconds,
+            outbound_capacity,
+            inbound_capacity,
+        } = params;
+        if window_seconds == 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let existing = self.limits.get(&config.domain);
+        let rate_limit = |max_capacity: Option<u64>, direction: TransferDirection| {
+            max_capacity.map(|max_capacity| {
+                let existing = existing.and_then(|existing| {
+                    existing
+                        .rate_limit(direction)
+                        .map(|rate_limit| rate_limit.capacity(existing.window_seconds, now))
+                });
+                RateLimit {
+                    max_capacity,
+                    capacity: existing.unwrap_or(max_capacity).min(max_capacity),
+                    last_updated: now,
+                }
+            })
+        };
+        let limit = DomainTransferLimit {
+            window_seconds,
+            outbound: rate_limit(outbound_capacity, TransferDirection::Outbound),
+            inbound: rate_limit(inbound_capacity, TransferDirection::Inbound),
+        };
+        self.limits.insert(config.domain, limit);
+        Ok(())
+    }
+
+    /// The amount that can currently be transferred to or from `domain`,
+    /// or None if transfers aren't limited.
+    pub fn capacity(&self, domain: u32, direction: TransferDirection, now: i64) -> Option<u64> {
+        let limit = self.limits.get(&domain)?;
+        limit
+            .rate_limit(direction)
+            .map(|rate_limit| rate_limit.capacity(limit.window_seconds, now))
+    }
+
+    /// Consumes `amount` of the capacity to transfer to or from `domain`.
+    /// Errors if the amount exceeds the current capacity.
+    pub fn consume(
+        &mut self,
+        domain: u32,
+        direction: TransferDirection,
+        amount: u64,
+        now: i64,
+    ) -> Result<(), Error> {
+        let Some(limit) = self.limits.get_mut(&domain) else {
+            return Ok(());
+        };
+        let window_seconds = limit.window_seconds;
+        match limit.rate_limit_mut(direction) {
+            Some(rate_limit) => rate_limit.consume(amount, window_seconds, now),
+            None => Ok(()),
+        }
+    }
+}
+
+impl SizedData for TransferLimits {
+    fn size(&self) -> usize {
+        // bump
+        std::mem::size_of::<u8>() +
+        // limits length
+        std::mem::size_of::<u32>() +
+        // limits keys & values
+        self
+            .limits
+            .values()
+            .map(|limit| std::mem::size_of::<u32>() + limit.size())
+            .sum::<usize>()
+    }
+}
+
+/// The limits on transfers to and from a remote domain.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone)]
+pub struct DomainTransferLimit {
+    /// The number of seconds it takes for an exhausted capacity to refill.
+    pub window_seconds: u32,
+    /// The limit of transfers to the domain.
+    pub outbound: Option<RateLimit>,
+    /// The limit of transfers from the domain.
+    pub inbound: Option<RateLimit>,
+}
+
+impl DomainTransferLimit {
+    fn rate_limit(&self, direction: TransferDirection) -> Option<&RateLimit> {
+        match direction {
+            TransferDirection::Outbound => self.outbound.as_ref(),
+            TransferDirection::Inbound => self.inbound.as_ref(),
+        }
+    }
+
+    fn rate_limit_mut(&mut self, direction: TransferDirection) -> Option<&mut RateLimit> {
+        match direction {
+            TransferDirection::Outbound => self.outbound.as_mut(),
+            TransferDirection::Inbound => self.inbound.as_mut(),
+        }
+    }
+}
+
+impl SizedData for DomainTransferLimit {
+    fn size(&self) -> usize {
+        // window_seconds
+        std::mem::size_of::<u32>() +
+        // outbound
+        1 + self.outbound.as_ref().map_or(0, |rate_limit| rate_limit.size()) +
+        // inbound
+        1 + self.inbound.as_ref().map_or(0, |rate_limit| rate_limit.size())
+    }
+}
+
+/// A capacity that is consumed by transfers and refills linearly, at a rate
+/// of `max_capacity` per window.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone)]
+pub struct RateLimit {
+    /// The max amount, in the local decimals, that can be transferred within a window.
+    pub max_capacity: u64,
+    /// The capacity left at `last_updated`.
+    pub capacity: u64,
+    /// The unix timestamp the capacity was last updated at.
+    pub last_updated: i64,
+}
+
+impl RateLimit {
+    /// The capacity at `now`, including what has been refilled since the last update.
+    pub fn capacity(&self, window_seconds: u32, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.last_updated).max(0) as u128;
+        let refilled = (self.max_capacity as u128 * elapsed) / (window_seconds.max(1) as u128);
+        (self.capacity as u128 + refilled).min(self.max_capacity as u128) as u64
+    }
+
+    /// Consumes `amount` of the capacity at `now`.
+    pub fn consume(&mut self, amount: u64, window_seconds: u32, now: i64) -> Result<(), Error> {
+        let capacity = self.capacity(window_seconds, now);
+        if amount > capacity {
+            return Err(Error::TransferLimitExceeded);
+        }
+        self.capacity = capacity - amount;
+        self.last_updated = now;
+        Ok(())
+    }
+}
+
+impl SizedData for RateLimit {
+    fn size(&self) -> usize {
+        // max_capacity
+        std::mem::size_of::<u64>() +
+        // capacity
+        std::mem::size_of::<u64>() +
+        // last_updated
+        std::mem::size_of::<i64>()
+    }
+}
+
 /// Converts an amount from one decimal representation to another.
 pub fn convert_decimals(amount: U256, from_decimals: u8, to_decimals: u8) -> Option<U256> {
     match from_decimals.cmp(&to_decimals) {
@@ -312,6 +510,144 @@ mod test {
         };
 
         assert_eq!(token.remote_amount_to_local_amount(100u64.into()), Ok(0));
+
+        // Try an amount that doesn't fit in a u64
+        let token: HyperlaneToken<()> = HyperlaneToken {
+            decimals: 6,
+            remote_decimals: 18,
+            ..HyperlaneToken::<()>::default()
+        };
+
+        assert_eq!(
+            token.remote_amount_to_local_amount(U256::MAX),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_remove_dust() {
+        // Remote decimals are more precise, there is no dust
+        let token: HyperlaneToken<()> = HyperlaneToken {
+            decimals: 6,
+            remote_decimals: 18,
+            ..HyperlaneToken::<()>::default()
+        };
+
+        assert_eq!(token.remove_dust(1_234_567), Ok(1_234_567));
+
+        // Remote decimals are less precise
+        let token: HyperlaneToken<()> = HyperlaneToken {
+            decimals: 9,
+            remote_decimals: 6,
+            ..HyperlaneToken::<()>::default()
+        };
+
+        assert_eq!(token.remove_dust(1_234_567_891), Ok(1_234_567_000));
+        assert_eq!(token.remove_dust(999), Ok(0));
+    }
+
+    #[test]
+    fn test_rate_limit_refills_linearly() {
+        let mut rate_limit = RateLimit {
+            max_capacity: 1000,
+            capacity: 1000,
+            last_updated: 100,
+        };
+
+        assert_eq!(
+            rate_limit.consume(1001, 10, 100),
+            Err(Error::TransferLimitExceeded)
+        );
+        assert_eq!(rate_limit.consume(1000, 10, 100), Ok(()));
+        assert_eq!(rate_limit.capacity(10, 100), 0);
+        // Half a window refills half of the capacity
+        assert_eq!(rate_limit.capacity(10, 105), 500);
+        assert_eq!(
+            rate_limit.consume(501, 10, 105),
+            Err(Error::TransferLimitExceeded)
+        );
+        assert_eq!(rate_limit.consume(200, 10, 105), Ok(()));
+        assert_eq!(rate_limit.capacity(10, 105), 300);
+        // The capacity never exceeds the max
+        assert_eq!(rate_limit.capacity(10, 1_000_000), 1000);
+        // Clocks going backwards don't refill anything
+        assert_eq!(rate_limit.capacity(10, 0), 300);
+    }
+
+    #[test]
+    fn test_set_transfer_limits() {
+        let mut limits = TransferLimits::default();
+        let config = |outbound_capacity, inbound_capacity| TransferLimitConfig {
+            domain: 1,
+            limit: Some(TransferLimitParams {
+                window_seconds: 100,
+                outbound_capacity,
+                inbound_capacity,
+            }),
+        };
+
+        limits.set_limit(config(Some(1000), None), 0).unwrap();
+        assert_eq!(
+            limits.capacity(1, TransferDirection::Outbound, 0),
+            Some(1000)
+        );
+        assert_eq!(limits.capacity(1, TransferDirection::Inbound, 0), None);
+        assert_eq!(limits.capacity(2, TransferDirection::Outbound, 0), None);
+        // Unlimited directions and domains aren't restricted
+        assert_eq!(
+            limits.consume(1, TransferDirection::Inbound, u64::MAX, 0),
+            Ok(())
+        );
+        assert_eq!(
+            limits.consume(2, TransferDirection::Outbound, u64::MAX, 0),
+            Ok(())
+        );
+
+        // Consumed capacity is kept when the limit is updated
+        limits
+            .consume(1, TransferDirection::Outbound, 800, 0)
+            .unwrap();
+        limits.set_limit(config(Some(2000), Some(10)), 0).unwrap();
+        assert_eq!(
+            limits.capacity(1, TransferDirection::Outbound, 0),
+            Some(200)
+        );
+        assert_eq!(limits.capacity(1, TransferDirection::Inbound, 0), Some(10));
+        // But capped at the new max
+        limits.set_limit(config(Some(100), Some(10)), 0).unwrap();
+        assert_eq!(
+            limits.capacity(1, TransferDirection::Outbound, 0),
+            Some(100)
+        );
+
+        let serialized = limits.try_to_vec().unwrap();
+        assert_eq!(serialized.len(), limits.size());
+
+        assert_eq!(
+            limits.set_limit(
+                TransferLimitConfig {
+                    domain: 1,
+                    limit: Some(TransferLimitParams {
+                        window_seconds: 0,
+                        outbound_capacity: None,
+                        inbound_capacity: None,
+                    }),
+                },
+                0
+            ),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        limits
+            .set_limit(
+                TransferLimitConfig {
+                    domain: 1,
+                    limit: None,
+                },
+                0,
+            )
+            .unwrap();
+        assert!(limits.limits.is_empty());
     }
 
     #[test]
@@ -2,6 +2,10 @@
 
 use solana_program::program_error::ProgramError;
 
+/// Logged by the programs when a transfer exceeds a transfer limit, so that
+/// clients simulating transfers can tell why they fail.
+pub const TRANSFER_LIMIT_EXCEEDED_LOG: &str = "Transfer limit exceeded";
+
 /// Custom errors that may be returned by the Hyperlane Sealevel Token programs.
 #[derive(Copy, Clone, Debug, Eq, thiserror::Error, num_derive::FromPrimitive, PartialEq)]
 #[repr(u32)]
@@ -17,6 +21,15 @@ pub enum Error {
     /// A message decoding error occurred.
     #[error("Message decoding error")]
     MessageDecodeError = 3,
+
+    /// A transfer exceeded the transfer limit of its remote domain.
+    #[error("Transfer limit exceeded")]
+    TransferLimitExceeded = 4,
+
+    /// A transfer's amount is zero once the dust the remote decimals can't
+    /// represent and any transfer fee are deducted.
+    #[error("Zero transfer amount")]
+    ZeroAmount = 5,
 }
 
 impl From<Error> for ProgramError {
@@ -0,0 +1,65 @@
+//! Events logged by Hyperlane Token programs via CPIs to the SPL Noop program.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use hyperlane_core::{H256, U256};
+
+/// Prefixes the Noop instruction data of a [`RemoteTransferEvent`], to tell it
+/// apart from other data logged through the Noop program in the same
+/// transaction, e.g. the dispatched message logged by the Mailbox.
+pub const REMOTE_TRANSFER_EVENT_DISCRIMINATOR: &[u8; 8] = b"HYPXFER0";
+
+/// Logged when a remote transfer is dispatched, so that indexers don't have
+/// to re-derive the decimal scaling between the local and remote amounts.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone)]
+pub struct RemoteTransferEvent {
+    /// The local domain.
+    pub origin_domain: u32,
+    /// The destination domain.
+    pub destination_domain: u32,
+    /// The remote recipient.
+    pub recipient: H256,
+    /// The amount transferred, in the local token's decimals.
+    pub local_amount: u64,
+    /// The amount in the remote token's decimals, as found in the message.
+    pub remote_amount: U256,
+    /// The ID of the dispatched message.
+    pub message_id: H256,
+}
+
+impl RemoteTransferEvent {
+    /// Serializes the event into Noop instruction data.
+    pub fn to_noop_data(&self) -> Vec<u8> {
+        let mut data = REMOTE_TRANSFER_EVENT_DISCRIMINATOR.to_vec();
+        // Serializing into a Vec is infallible.
+        self.serialize(&mut data).unwrap();
+        data
+    }
+
+    /// Deserializes Noop instruction data, returning None if it isn't a
+    /// remote transfer event.
+    pub fn from_noop_data(data: &[u8]) -> Option<Self> {
+        let mut event_data = data.strip_prefix(REMOTE_TRANSFER_EVENT_DISCRIMINATOR)?;
+        Self::deserialize(&mut event_data).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_noop_data_roundtrip() {
+        let event = RemoteTransferEvent {
+            origin_domain: 1399811149,
+            destination_domain: 1,
+            recipient: H256::repeat_byte(1),
+            local_amount: 1_000_000,
+            remote_amount: U256::from(10).pow(18.into()),
+            message_id: H256::repeat_byte(2),
+        };
+        let data = event.to_noop_data();
+
+        assert_eq!(RemoteTransferEvent::from_noop_data(&data), Some(event));
+        assert_eq!(RemoteTransferEvent::from_noop_data(&data[8..]), None);
+    }
+}
@@ -15,7 +15,7 @@ use solana_program::{
 
 use hyperlane_sealevel_mailbox::mailbox_message_dispatch_authority_pda_seeds;
 
-use crate::hyperlane_token_pda_seeds;
+use crate::{hyperlane_token_pda_seeds, hyperlane_token_transfer_limits_pda_seeds};
 
 /// Instructions shared by all Hyperlane Sealevel Token programs.
 #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
@@ -36,6 +36,10 @@ pub enum Instruction {
     SetInterchainGasPaymaster(Option<(Pubkey, InterchainGasPaymasterType)>),
     /// Transfer ownership of the program. Only owner.
     TransferOwnership(Option<Pubkey>),
+    /// Set the limits on transfers to and from remote domains. Only owner.
+    SetTransferLimits(Vec<TransferLimitConfig>),
+    /// Get the capacity left to transfer to and from a remote domain.
+    GetTransferCapacity(u32),
 }
 
 impl DiscriminatorData for Instruction {
@@ -68,6 +72,38 @@ pub struct TransferRemote {
     pub amount_or_id: U256,
 }
 
+/// Configures the limits on transfers to and from a remote domain.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct TransferLimitConfig {
+    /// The remote domain.
+    pub domain: u32,
+    /// The limits, or None to remove the limits of the domain.
+    pub limit: Option<TransferLimitParams>,
+}
+
+/// The limits on transfers to and from a remote domain.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct TransferLimitParams {
+    /// The number of seconds it takes for an exhausted capacity to refill.
+    pub window_seconds: u32,
+    /// The max amount, in the local decimals, transferred to the domain within
+    /// a window, or None if outbound transfers aren't limited.
+    pub outbound_capacity: Option<u64>,
+    /// The max amount, in the local decimals, transferred from the domain
+    /// within a window, or None if inbound transfers aren't limited.
+    pub inbound_capacity: Option<u64>,
+}
+
+/// The capacity left to transfer to and from a remote domain, as returned by
+/// the `GetTransferCapacity` instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone)]
+pub struct TransferCapacity {
+    /// The amount that can be transferred to the domain, or None if unlimited.
+    pub outbound: Option<u64>,
+    /// The amount that can be transferred from the domain, or None if unlimited.
+    pub inbound: Option<u64>,
+}
+
 /// Gets an instruction to initialize the program. This provides only the
 /// account metas required by the library, and consuming programs are expected
 /// to add the accounts for their own use.
@@ -258,3 +294,63 @@ pub fn set_igp_instruction(
 
     Ok(instruction)
 }
+
+/// Sets the limits on transfers to and from remote domains.
+pub fn set_transfer_limits_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    configs: Vec<TransferLimitConfig>,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (token_key, _token_bump) =
+        Pubkey::try_find_program_address(hyperlane_token_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (transfer_limits_key, _transfer_limits_bump) =
+        Pubkey::try_find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    let ixn = Instruction::SetTransferLimits(configs);
+
+    // Accounts:
+    // 0. `[executable]` The system program.
+    // 1. `[]` The token PDA account.
+    // 2. `[writeable]` The transfer limits PDA account.
+    // 3. `[signer]` The owner.
+    let accounts = vec![
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(token_key, false),
+        AccountMeta::new(transfer_limits_key, false),
+        AccountMeta::new(owner_payer, true),
+    ];
+
+    let instruction = SolanaInstruction {
+        program_id,
+        data: ixn.encode()?,
+        accounts,
+    };
+
+    Ok(instruction)
+}
+
+/// Gets the capacity left to transfer to and from `domain`, to be simulated.
+pub fn get_transfer_capacity_instruction(
+    program_id: Pubkey,
+    domain: u32,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (transfer_limits_key, _transfer_limits_bump) =
+        Pubkey::try_find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    let ixn = Instruction::GetTransferCapacity(domain);
+
+    // Accounts:
+    // 0. `[]` The transfer limits PDA account.
+    let accounts = vec![AccountMeta::new_readonly(transfer_limits_key, false)];
+
+    let instruction = SolanaInstruction {
+        program_id,
+        data: ixn.encode()?,
+        accounts,
+    };
+
+    Ok(instruction)
+}
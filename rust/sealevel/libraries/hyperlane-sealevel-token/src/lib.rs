@@ -6,6 +6,7 @@
 
 pub mod accounts;
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod message;
 pub mod processor;
@@ -14,16 +14,18 @@ use hyperlane_sealevel_connection_client::{
 };
 use hyperlane_sealevel_igp::accounts::InterchainGasPaymasterType;
 use hyperlane_sealevel_mailbox::{
-    mailbox_message_dispatch_authority_pda_seeds, mailbox_process_authority_pda_seeds,
+    accounts::OutboxAccount, mailbox_message_dispatch_authority_pda_seeds,
+    mailbox_process_authority_pda_seeds,
 };
 use hyperlane_sealevel_message_recipient_interface::HandleInstruction;
 use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
-    instruction::AccountMeta,
+    instruction::{AccountMeta, Instruction},
     msg,
-    program::set_return_data,
+    program::{invoke, set_return_data},
     program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
@@ -32,9 +34,13 @@ use solana_program::{
 use std::collections::HashMap;
 
 use crate::{
-    accounts::{HyperlaneToken, HyperlaneTokenAccount},
-    error::Error,
-    instruction::{Init, TransferRemote},
+    accounts::{
+        HyperlaneToken, HyperlaneTokenAccount, TransferDirection, TransferLimits,
+        TransferLimitsAccount,
+    },
+    error::{Error, TRANSFER_LIMIT_EXCEEDED_LOG},
+    event::RemoteTransferEvent,
+    instruction::{Init, TransferCapacity, TransferLimitConfig, TransferRemote},
     message::TokenMessage,
 };
 
@@ -66,6 +72,19 @@ macro_rules! hyperlane_token_pda_seeds {
     }};
 }
 
+/// Seeds relating to the PDA account with the limits on transfers to and
+/// from remote domains. The account only exists once the owner has set limits.
+#[macro_export]
+macro_rules! hyperlane_token_transfer_limits_pda_seeds {
+    () => {{
+        &[b"hyperlane_token", b"-", b"transfer_limits"]
+    }};
+
+    ($bump_seed:expr) => {{
+        &[b"hyperlane_token", b"-", b"transfer_limits", &[$bump_seed]]
+    }};
+}
+
 /// A plugin that handles token transfers for a Hyperlane Sealevel Token program.
 pub trait HyperlaneSealevelTokenPlugin
 where
@@ -87,13 +106,15 @@ where
     ) -> Result<Self, ProgramError>;
 
     /// Transfers tokens into the program.
+    /// Returns the amount received by the program, which can be less than
+    /// `amount`, e.g. if the token charges a transfer fee.
     fn transfer_in<'a, 'b>(
         program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
         sender_wallet: &'a AccountInfo<'b>,
         accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
         amount: u64,
-    ) -> Result<(), ProgramError>;
+    ) -> Result<u64, ProgramError>;
 
     /// Transfers tokens out of the program.
     fn transfer_out<'a, 'b>(
@@ -112,6 +133,21 @@ where
         token: &HyperlaneToken<Self>,
         token_message: &TokenMessage,
     ) -> Result<(Vec<SerializableAccountMeta>, bool), ProgramError>;
+
+    /// Gets any AccountMetas required by the `transfer_out` function that can
+    /// only be resolved from the data of other accounts, e.g. the extra accounts
+    /// of a Token-2022 transfer hook. They're required after the AccountMetas
+    /// of `transfer_out_account_metas`.
+    /// `accounts` are the accounts supplied to the simulation, which callers
+    /// set to the AccountMetas it last returned until they no longer change.
+    fn transfer_out_extra_account_metas(
+        _program_id: &Pubkey,
+        _token: &HyperlaneToken<Self>,
+        _token_message: &TokenMessage,
+        _accounts: &[AccountInfo],
+    ) -> Result<Vec<SerializableAccountMeta>, ProgramError> {
+        Ok(vec![])
+    }
 }
 
 /// Core functionality of a Hyperlane Sealevel Token program that uses
@@ -272,6 +308,9 @@ where
     /// 13.   `[writeable]` The IGP account.
     ///      ---- End if ----
     /// 14..N `[??..??]` Plugin-specific accounts.
+    /// N+1.  `[writeable]` OPTIONAL - The transfer limits PDA account. Transfers
+    ///       are only limited if it's passed, which clients predating transfer
+    ///       limits don't.
     pub fn transfer_remote(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -410,23 +449,47 @@ where
                 None
             };
 
-        // The amount denominated in the local decimals.
+        // The amount denominated in the local decimals, without the dust that the
+        // remote decimals can't represent. The dust is left with the sender rather
+        // than being transferred in without being credited on the remote.
         let local_amount: u64 = xfer
             .amount_or_id
             .try_into()
             .map_err(|_| Error::IntegerOverflow)?;
-        // Convert to the remote number of decimals, which is universally understood
-        // by the remote routers as the number of decimals used by the message amount.
-        let remote_amount = token.local_amount_to_remote_amount(local_amount)?;
+        let local_amount = token.remove_dust(local_amount)?;
+        if local_amount == 0 {
+            return Err(ProgramError::from(Error::ZeroAmount));
+        }
 
-        // Transfer `local_amount` of tokens in...
-        T::transfer_in(
+        // Transfer `local_amount` of tokens in. Only the amount actually received
+        // is sent to the remote, as some tokens charge fees on transfers. A fee
+        // can leave dust in the amount received, which stays in the program.
+        let received_amount = T::transfer_in(
             program_id,
             &*token,
             sender_wallet,
             accounts_iter,
             local_amount,
         )?;
+        let local_amount = token.remove_dust(received_amount)?;
+        if local_amount == 0 {
+            return Err(ProgramError::from(Error::ZeroAmount));
+        }
+
+        // Convert to the remote number of decimals, which is universally understood
+        // by the remote routers as the number of decimals used by the message amount.
+        let remote_amount = token.local_amount_to_remote_amount(local_amount)?;
+
+        // Account N+1: OPTIONAL - The transfer limits PDA.
+        if let Some(transfer_limits_account) = accounts_iter.next() {
+            Self::consume_transfer_limit(
+                program_id,
+                transfer_limits_account,
+                xfer.destination_domain,
+                TransferDirection::Outbound,
+                local_amount,
+            )?;
+        }
 
         if accounts_iter.next().is_some() {
             return Err(ProgramError::from(Error::ExtraneousAccount));
@@ -455,7 +518,9 @@ where
         let token_transfer_message =
             TokenMessage::new(xfer.recipient, remote_amount, vec![]).to_vec();
 
-        if let Some((igp_payment_account_metas, igp_payment_account_infos)) = igp_payment_accounts {
+        let message_id = if let Some((igp_payment_account_metas, igp_payment_account_infos)) =
+            igp_payment_accounts
+        {
             // Dispatch the message and pay for gas.
             HyperlaneGasRouterDispatch::dispatch_with_gas(
                 &*token,
@@ -467,7 +532,7 @@ where
                 dispatch_account_infos,
                 igp_payment_account_metas,
                 &igp_payment_account_infos,
-            )?;
+            )?
         } else {
             // Dispatch the message.
             token.dispatch(
@@ -477,8 +542,30 @@ where
                 token_transfer_message,
                 dispatch_account_metas,
                 dispatch_account_infos,
-            )?;
-        }
+            )?
+        };
+
+        // The Mailbox has verified the Outbox account during the dispatch.
+        let origin_domain = OutboxAccount::fetch(&mut &mailbox_outbox_account.data.borrow()[..])?
+            .into_inner()
+            .local_domain;
+
+        // Log the transfer using the SPL Noop program, so that indexers can read
+        // both the local and remote amounts.
+        let event = RemoteTransferEvent {
+            origin_domain,
+            destination_domain: xfer.destination_domain,
+            recipient: xfer.recipient,
+            local_amount,
+            remote_amount,
+            message_id,
+        };
+        let noop_cpi_log = Instruction {
+            program_id: *spl_noop.key,
+            accounts: vec![],
+            data: event.to_noop_data(),
+        };
+        invoke(&noop_cpi_log, &[])?;
 
         msg!(
             "Warp route transfer completed to destination: {}, recipient: {}, remote_amount: {}",
@@ -496,6 +583,9 @@ where
     /// 2.   `[]` hyperlane_token storage
     /// 3.   [depends on plugin] recipient wallet address
     /// 4..N `[??..??]` Plugin-specific accounts.
+    /// N+1. `[writeable]` OPTIONAL - The transfer limits PDA account, which the
+    ///      account metas returned by `transfer_from_remote_account_metas`
+    ///      include. Transfers are only limited if it's passed.
     pub fn transfer_from_remote(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -557,6 +647,17 @@ where
             local_amount,
         )?;
 
+        // Account N+1: OPTIONAL - The transfer limits PDA.
+        if let Some(transfer_limits_account) = accounts_iter.next() {
+            Self::consume_transfer_limit(
+                program_id,
+                transfer_limits_account,
+                xfer.origin,
+                TransferDirection::Inbound,
+                local_amount,
+            )?;
+        }
+
         if accounts_iter.next().is_some() {
             return Err(ProgramError::from(Error::ExtraneousAccount));
         }
@@ -576,6 +677,8 @@ where
     ///
     /// Accounts:
     /// 0.   `[]` The token PDA, which is the PDA with the seeds `HANDLE_ACCOUNT_METAS_PDA_SEEDS`.
+    /// 1..N `[]` OPTIONAL - The account metas previously returned, from whose data
+    ///      the plugin may resolve further account metas.
     pub fn transfer_from_remote_account_metas(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -605,6 +708,16 @@ where
             .into(),
         ];
         accounts.extend(transfer_out_account_metas);
+        accounts.extend(T::transfer_out_extra_account_metas(
+            program_id,
+            &token,
+            &message,
+            accounts_iter.as_slice(),
+        )?);
+
+        let (transfer_limits_key, _transfer_limits_bump) =
+            Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), program_id);
+        accounts.push(AccountMeta::new(transfer_limits_key, false).into());
 
         // Wrap it in the SimulationReturnData because serialized account_metas
         // may end with zero byte(s), which are incorrectly truncated as
@@ -884,4 +997,171 @@ where
 
         Ok(())
     }
+
+    /// Sets the limits on transfers to and from remote domains, creating the
+    /// transfer limits PDA account if it doesn't exist yet.
+    ///
+    /// Accounts:
+    /// 0. `[executable]` The system program.
+    /// 1. `[]` The token PDA account.
+    /// 2. `[writeable]` The transfer limits PDA account.
+    /// 3. `[signer]` The owner.
+    pub fn set_transfer_limits(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        configs: Vec<TransferLimitConfig>,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        // Account 0: System program. Only used if the account is created or a
+        // realloc / rent exemption top up occurs.
+        let system_program = next_account_info(accounts_iter)?;
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Account 1: Token account
+        let token_account = next_account_info(accounts_iter)?;
+        let token = HyperlaneToken::<T>::verify_account_and_fetch_inner(program_id, token_account)?;
+
+        // Account 2: Transfer limits account
+        let transfer_limits_account = next_account_info(accounts_iter)?;
+        let (transfer_limits_key, transfer_limits_bump) =
+            Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), program_id);
+        if transfer_limits_account.key != &transfer_limits_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Account 3: Owner
+        let owner_account = next_account_info(accounts_iter)?;
+        token.ensure_owner_signer(owner_account)?;
+
+        if accounts_iter.next().is_some() {
+            return Err(ProgramError::from(Error::ExtraneousAccount));
+        }
+
+        let rent = Rent::get()?;
+        let mut transfer_limits = if transfer_limits_account.owner == program_id {
+            TransferLimitsAccount::fetch(&mut &transfer_limits_account.data.borrow()[..])?
+                .into_inner()
+        } else {
+            let transfer_limits = TransferLimitsAccount::from(TransferLimits {
+                bump: transfer_limits_bump,
+                limits: HashMap::new(),
+            });
+            create_pda_account(
+                owner_account,
+                &rent,
+                transfer_limits.size(),
+                program_id,
+                system_program,
+                transfer_limits_account,
+                hyperlane_token_transfer_limits_pda_seeds!(transfer_limits_bump),
+            )?;
+            transfer_limits.into_inner()
+        };
+
+        let now = Clock::get()?.unix_timestamp;
+        for config in configs {
+            msg!("Setting transfer limit: {:?}", config);
+            transfer_limits.set_limit(config, now)?;
+        }
+
+        // Store the updated transfer limits and realloc if necessary.
+        TransferLimitsAccount::from(transfer_limits).store_with_rent_exempt_realloc(
+            transfer_limits_account,
+            &rent,
+            owner_account,
+            system_program,
+        )?;
+
+        Ok(())
+    }
+
+    /// Gets the capacity left to transfer to and from `domain`, returning it
+    /// as a serialized `TransferCapacity`.
+    ///
+    /// Accounts:
+    /// 0. `[]` The transfer limits PDA account.
+    pub fn get_transfer_capacity(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        domain: u32,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        // Account 0: Transfer limits account
+        let transfer_limits_account = next_account_info(accounts_iter)?;
+        let (transfer_limits_key, _transfer_limits_bump) =
+            Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), program_id);
+        if transfer_limits_account.key != &transfer_limits_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let capacity = if transfer_limits_account.owner == program_id {
+            let transfer_limits =
+                TransferLimitsAccount::fetch(&mut &transfer_limits_account.data.borrow()[..])?
+                    .into_inner();
+            let now = Clock::get()?.unix_timestamp;
+            TransferCapacity {
+                outbound: transfer_limits.capacity(domain, TransferDirection::Outbound, now),
+                inbound: transfer_limits.capacity(domain, TransferDirection::Inbound, now),
+            }
+        } else {
+            TransferCapacity {
+                outbound: None,
+                inbound: None,
+            }
+        };
+
+        // Wrapped in SimulationReturnData because the serialized capacity
+        // may end with zero byte(s).
+        let bytes = SimulationReturnData::new(capacity)
+            .try_to_vec()
+            .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+        set_return_data(&bytes[..]);
+
+        Ok(())
+    }
+
+    /// Consumes `amount` of the limit on transfers to or from `domain`, if
+    /// the owner has set one.
+    fn consume_transfer_limit(
+        program_id: &Pubkey,
+        transfer_limits_account: &AccountInfo,
+        domain: u32,
+        direction: TransferDirection,
+        amount: u64,
+    ) -> ProgramResult {
+        let (transfer_limits_key, _transfer_limits_bump) =
+            Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), program_id);
+        if transfer_limits_account.key != &transfer_limits_key {
+            return Err(ProgramError::InvalidArgument);
+        }
+        // The account doesn't exist if no limits have been set.
+        if transfer_limits_account.owner != program_id {
+            return Ok(());
+        }
+
+        let mut transfer_limits =
+            TransferLimitsAccount::fetch(&mut &transfer_limits_account.data.borrow()[..])?
+                .into_inner();
+        let now = Clock::get()?.unix_timestamp;
+        if let Err(err) = transfer_limits.consume(domain, direction, amount, now) {
+            msg!(
+                "{}: domain: {}, direction: {:?}, amount: {}, capacity: {:?}",
+                TRANSFER_LIMIT_EXCEEDED_LOG,
+                domain,
+                direction,
+                amount,
+                transfer_limits.capacity(domain, direction, now)
+            );
+            return Err(err.into());
+        }
+
+        // No need to realloc, consuming a limit doesn't change its size.
+        TransferLimitsAccount::from(transfer_limits).store(transfer_limits_account, false)?;
+
+        Ok(())
+    }
 }
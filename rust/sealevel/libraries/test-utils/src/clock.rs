@@ -0,0 +1,62 @@
+//! Deterministic control over the test validator's clock, for testing logic
+//! that depends on the `Clock` sysvar (timelocks, staleness checks, recorded
+//! slots) without sleeping.
+//!
+//! Note that warping to a slot creates a new bank whose clock is derived from
+//! the slot, so a timestamp set with [`set_unix_timestamp`] must be set again
+//! after warping.
+
+use solana_program::{clock::Clock, clock::UnixTimestamp, sysvar::clock::Slot};
+use solana_program_test::*;
+
+pub async fn get_clock(banks_client: &mut BanksClient) -> Result<Clock, BanksClientError> {
+    banks_client.get_sysvar::<Clock>().await
+}
+
+/// Warps the validator to `slot`, which must be after the current slot, and
+/// returns the new clock. The latest blockhash changes, so transactions that
+/// were already processed can be sent again.
+pub async fn warp_to_slot(context: &mut ProgramTestContext, slot: Slot) -> Clock {
+    context.warp_to_slot(slot).unwrap();
+    let clock = get_clock(&mut context.banks_client).await.unwrap();
+    assert_eq!(clock.slot, slot);
+    clock
+}
+
+/// Warps the validator `slots` slots forward and returns the new clock.
+pub async fn warp_forward_slots(context: &mut ProgramTestContext, slots: u64) -> Clock {
+    let clock = get_clock(&mut context.banks_client).await.unwrap();
+    warp_to_slot(context, clock.slot + slots).await
+}
+
+/// Sets the unix timestamp of the current slot, leaving the rest of the clock
+/// untouched, and returns the new clock.
+pub async fn set_unix_timestamp(
+    context: &mut ProgramTestContext,
+    unix_timestamp: UnixTimestamp,
+) -> Clock {
+    let mut clock = get_clock(&mut context.banks_client).await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+    clock
+}
+
+/// Moves the unix timestamp of the current slot `seconds` forward and returns
+/// the new clock.
+pub async fn warp_forward_seconds(context: &mut ProgramTestContext, seconds: i64) -> Clock {
+    let clock = get_clock(&mut context.banks_client).await.unwrap();
+    set_unix_timestamp(context, clock.unix_timestamp + seconds).await
+}
+
+pub async fn assert_slot(banks_client: &mut BanksClient, expected_slot: Slot) {
+    let clock = get_clock(banks_client).await.unwrap();
+    assert_eq!(clock.slot, expected_slot);
+}
+
+pub async fn assert_unix_timestamp(
+    banks_client: &mut BanksClient,
+    expected_unix_timestamp: UnixTimestamp,
+) {
+    let clock = get_clock(banks_client).await.unwrap();
+    assert_eq!(clock.unix_timestamp, expected_unix_timestamp);
+}
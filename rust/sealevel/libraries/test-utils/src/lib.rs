@@ -21,7 +21,11 @@ use hyperlane_sealevel_interchain_security_module_interface::{
     InterchainSecurityModuleInstruction, VerifyInstruction, VERIFY_ACCOUNT_METAS_PDA_SEEDS,
 };
 use hyperlane_sealevel_mailbox::{
-    instruction::{InboxProcess, Init as InitMailbox, Instruction as MailboxInstruction},
+    accounts::{Inbox, InboxAccount},
+    instruction::{
+        processed_nonces_pda, recipient_ism_pda, InboxProcess, Init as InitMailbox,
+        Instruction as MailboxInstruction,
+    },
     mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds, mailbox_process_authority_pda_seeds,
     mailbox_processed_message_pda_seeds,
     protocol_fee::ProtocolFee,
@@ -33,7 +37,9 @@ use hyperlane_sealevel_message_recipient_interface::{
 use hyperlane_sealevel_test_ism::test_client::TestIsmTestClient;
 use serializable_account_meta::{SerializableAccountMeta, SimulationReturnData};
 
+pub mod clock;
 pub mod igp;
+pub use clock::*;
 pub use igp::*;
 
 // ========= Mailbox =========
@@ -163,6 +169,20 @@ pub async fn get_account_metas(
     Ok(account_metas)
 }
 
+/// Gets the Inbox account data.
+pub async fn get_inbox(
+    banks_client: &mut BanksClient,
+    mailbox_accounts: &MailboxAccounts,
+) -> Result<Inbox, BanksClientError> {
+    let inbox_account = banks_client
+        .get_account(mailbox_accounts.inbox)
+        .await?
+        .unwrap();
+    Ok(*InboxAccount::fetch(&mut &inbox_account.data[..])
+        .unwrap()
+        .into_inner())
+}
+
 /// Gets the recipient ISM given a recipient program id and the ISM getter account metas.
 pub async fn get_recipient_ism_with_account_metas(
     banks_client: &mut BanksClient,
@@ -177,6 +197,15 @@ pub async fn get_recipient_ism_with_account_metas(
         // The recipient program.
         AccountMeta::new_readonly(recipient_program_id, false),
     ];
+    // The recipient ISM PDA, holding the recipient's ISM override if it registered one.
+    if get_inbox(banks_client, mailbox_accounts)
+        .await?
+        .recipient_isms_enabled
+    {
+        let (recipient_ism_key, _recipient_ism_bump) =
+            recipient_ism_pda(&mailbox_accounts.program, &recipient_program_id).unwrap();
+        accounts.push(AccountMeta::new_readonly(recipient_ism_key, false));
+    }
     accounts.extend(ism_getter_account_metas);
 
     let instruction = Instruction::new_with_borsh(
@@ -407,6 +436,22 @@ pub async fn get_process_account_metas(
         AccountMeta::new_readonly(process_authority_key, false),
         AccountMeta::new(processed_message_account_key, false),
     ];
+
+    // If processed message PDAs can be closed, the processed nonces PDA is required.
+    let inbox = get_inbox(banks_client, mailbox_accounts).await?;
+    if inbox.processed_message_retention.is_some() {
+        let (processed_nonces_key, _processed_nonces_bump) =
+            processed_nonces_pda(&mailbox_accounts.program, message).unwrap();
+        accounts.push(AccountMeta::new_readonly(processed_nonces_key, false));
+    }
+
+    // If recipients can override the default ISM, the recipient ISM PDA is required.
+    if inbox.recipient_isms_enabled {
+        let (recipient_ism_key, _recipient_ism_bump) =
+            recipient_ism_pda(&mailbox_accounts.program, &recipient).unwrap();
+        accounts.push(AccountMeta::new_readonly(recipient_ism_key, false));
+    }
+
     accounts.extend(ism_getter_account_metas);
     accounts.extend([
         AccountMeta::new_readonly(spl_noop::id(), false),
@@ -1,3 +1,4 @@
+use base64::Engine;
 use hyperlane_core::H256;
 
 use std::collections::HashMap;
@@ -11,7 +12,7 @@ use solana_program::{
 use solana_program_test::*;
 use solana_sdk::{
     instruction::InstructionError, signature::Signature, signature::Signer,
-    signer::keypair::Keypair, transaction::TransactionError,
+    signer::keypair::Keypair, transaction::Transaction, transaction::TransactionError,
 };
 
 use hyperlane_test_utils::{
@@ -29,6 +30,7 @@ use hyperlane_sealevel_igp::{
         TOKEN_EXCHANGE_RATE_SCALE,
     },
     error::Error as IgpError,
+    events::IgpEvent,
     igp_gas_payment_pda_seeds, igp_pda_seeds, igp_program_data_pda_seeds,
     instruction::{
         GasOracleConfig, GasOverheadConfig, InitIgp, InitOverheadIgp,
@@ -43,6 +45,36 @@ const TEST_GAS_AMOUNT: u64 = 300000;
 const TEST_GAS_OVERHEAD_AMOUNT: u64 = 100000;
 const LOCAL_DECIMALS: u8 = SOL_DECIMALS;
 
+/// Processes `instruction` and returns the IGP events it emitted.
+async fn process_instruction_with_events(
+    banks_client: &mut BanksClient,
+    instruction: Instruction,
+    payer: &Keypair,
+) -> Vec<IgpEvent> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    let result = banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    result
+        .metadata
+        .unwrap()
+        .log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .filter_map(|data| IgpEvent::decode(&data))
+        .collect()
+}
+
 async fn setup_client() -> (BanksClient, Keypair) {
     let program_id = igp_program_id();
     let program_test = ProgramTest::new(
@@ -450,9 +482,7 @@ async fn test_set_gas_oracle_configs() {
             AccountMeta::new_readonly(payer.pubkey(), true),
         ],
     );
-    process_instruction(&mut banks_client, instruction, &payer, &[&payer])
-        .await
-        .unwrap();
+    let events = process_instruction_with_events(&mut banks_client, instruction, &payer).await;
 
     // Expect the gas oracle configs to be set.
     let igp_account = banks_client.get_account(igp_key).await.unwrap().unwrap();
@@ -469,6 +499,16 @@ async fn test_set_gas_oracle_configs() {
             .collect(),
     );
 
+    // Expect the change to be emitted as an event.
+    assert_eq!(
+        events,
+        vec![IgpEvent::GasOracleConfigsSet {
+            igp: igp_key,
+            owner: payer.pubkey(),
+            configs: configs.clone(),
+        }]
+    );
+
     // Remove one of them
     let rm_configs = vec![GasOracleConfig {
         domain: 12,
@@ -1367,9 +1407,7 @@ async fn test_set_igp_beneficiary() {
             AccountMeta::new_readonly(payer.pubkey(), true),
         ],
     );
-    process_instruction(&mut banks_client, instruction, &payer, &[&payer])
-        .await
-        .unwrap();
+    let events = process_instruction_with_events(&mut banks_client, instruction, &payer).await;
 
     // Expect the beneficiary to be set.
     let igp_account = banks_client.get_account(igp_key).await.unwrap().unwrap();
@@ -1378,6 +1416,16 @@ async fn test_set_igp_beneficiary() {
         .into_inner();
 
     assert_eq!(igp.beneficiary, new_beneficiary,);
+
+    // Expect the change to be emitted as an event.
+    assert_eq!(
+        events,
+        vec![IgpEvent::BeneficiarySet {
+            igp: igp_key,
+            owner: payer.pubkey(),
+            beneficiary: new_beneficiary,
+        }]
+    );
 }
 
 #[tokio::test]
@@ -0,0 +1,60 @@
+//! Events emitted when the configuration of an IGP changes.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::instruction::{GasOracleConfig, GasOverheadConfig};
+
+/// Prefix of the data of every IGP event, so that it can be told apart from
+/// data logged by other programs.
+pub const IGP_EVENT_DISCRIMINATOR: &[u8; 8] = b"IGPEVENT";
+
+/// An event emitted when the configuration of an IGP changes.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub enum IgpEvent {
+    /// Gas oracle configs were set on an IGP.
+    GasOracleConfigsSet {
+        /// The IGP.
+        igp: Pubkey,
+        /// The owner that set the configs.
+        owner: Pubkey,
+        /// The configs that were set.
+        configs: Vec<GasOracleConfig>,
+    },
+    /// Destination gas overheads were set on an OverheadIGP.
+    DestinationGasOverheadsSet {
+        /// The OverheadIGP.
+        overhead_igp: Pubkey,
+        /// The owner that set the overheads.
+        owner: Pubkey,
+        /// The overheads that were set.
+        configs: Vec<GasOverheadConfig>,
+    },
+    /// The beneficiary of an IGP was set.
+    BeneficiarySet {
+        /// The IGP.
+        igp: Pubkey,
+        /// The owner that set the beneficiary.
+        owner: Pubkey,
+        /// The new beneficiary.
+        beneficiary: Pubkey,
+    },
+}
+
+impl IgpEvent {
+    /// Logs the event as program data, prefixed by `IGP_EVENT_DISCRIMINATOR`.
+    pub fn emit(&self) -> Result<(), ProgramError> {
+        let mut data = IGP_EVENT_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)
+            .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+        sol_log_data(&[&data]);
+        Ok(())
+    }
+
+    /// Decodes an event from logged program data, returning `None` if the
+    /// data isn't an IGP event.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let data = data.strip_prefix(IGP_EVENT_DISCRIMINATOR)?;
+        Self::try_from_slice(data).ok()
+    }
+}
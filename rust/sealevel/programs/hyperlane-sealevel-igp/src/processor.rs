@@ -30,6 +30,7 @@ use crate::{
         GasPaymentAccount, GasPaymentData, Igp, IgpAccount, OverheadIgp, OverheadIgpAccount,
         ProgramData, ProgramDataAccount,
     },
+    events::IgpEvent,
     igp_gas_payment_pda_seeds, igp_pda_seeds, igp_program_data_pda_seeds,
     instruction::{
         GasOracleConfig, GasOverheadConfig, InitIgp, InitOverheadIgp,
@@ -472,13 +473,20 @@ fn set_igp_beneficiary(
 ) -> ProgramResult {
     let accounts_iter = &mut accounts.iter();
 
-    let (igp_info, mut igp, _) =
+    let (igp_info, mut igp, owner_info) =
         get_igp_variant_and_verify_owner::<Igp>(program_id, accounts_iter)?;
 
     // Update the beneficiary and store it.
     igp.beneficiary = beneficiary;
     IgpAccount::new(igp.into()).store(igp_info, false)?;
 
+    IgpEvent::BeneficiarySet {
+        igp: *igp_info.key,
+        owner: *owner_info.key,
+        beneficiary,
+    }
+    .emit()?;
+
     Ok(())
 }
 
@@ -603,7 +611,7 @@ fn set_destination_gas_overheads(
     let (overhead_igp_info, mut overhead_igp, owner_info) =
         get_igp_variant_and_verify_owner::<OverheadIgp>(program_id, accounts_iter)?;
 
-    configs.into_iter().for_each(|config| {
+    configs.iter().for_each(|config| {
         match config.gas_overhead {
             Some(gas_overhead) => overhead_igp
                 .gas_overheads
@@ -623,6 +631,13 @@ fn set_destination_gas_overheads(
         system_program_info,
     )?;
 
+    IgpEvent::DestinationGasOverheadsSet {
+        overhead_igp: *overhead_igp_info.key,
+        owner: *owner_info.key,
+        configs,
+    }
+    .emit()?;
+
     Ok(())
 }
 
@@ -650,9 +665,9 @@ fn set_gas_oracle_configs(
     let (igp_info, mut igp, owner_info) =
         get_igp_variant_and_verify_owner::<Igp>(program_id, accounts_iter)?;
 
-    configs.into_iter().for_each(|config| {
-        match config.gas_oracle {
-            Some(gas_oracle) => igp.gas_oracles.insert(config.domain, gas_oracle),
+    configs.iter().for_each(|config| {
+        match &config.gas_oracle {
+            Some(gas_oracle) => igp.gas_oracles.insert(config.domain, gas_oracle.clone()),
             None => igp.gas_oracles.remove(&config.domain),
         };
     });
@@ -666,5 +681,12 @@ fn set_gas_oracle_configs(
         system_program_info,
     )?;
 
+    IgpEvent::GasOracleConfigsSet {
+        igp: *igp_info.key,
+        owner: *owner_info.key,
+        configs,
+    }
+    .emit()?;
+
     Ok(())
 }
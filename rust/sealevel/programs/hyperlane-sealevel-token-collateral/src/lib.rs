@@ -7,6 +7,7 @@
 pub mod instruction;
 pub mod plugin;
 pub mod processor;
+pub mod transfer_hook;
 
 pub use spl_associated_token_account;
 pub use spl_noop;
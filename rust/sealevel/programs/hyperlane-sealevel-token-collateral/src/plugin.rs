@@ -8,7 +8,7 @@ use hyperlane_sealevel_token_lib::{
 use serializable_account_meta::SerializableAccountMeta;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
-    instruction::AccountMeta,
+    instruction::{AccountMeta, Instruction},
     program::{get_return_data, invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
@@ -19,7 +19,15 @@ use spl_associated_token_account::{
     get_associated_token_address_with_program_id,
     instruction::create_associated_token_account_idempotent,
 };
-use spl_token_2022::instruction::{get_account_data_size, initialize_account, transfer_checked};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    instruction::{get_account_data_size, initialize_account, transfer_checked},
+    state::Account as TokenAccount,
+};
+
+use crate::transfer_hook::{
+    extra_account_metas_address, resolve_extra_account_metas, transfer_hook_program_id,
+};
 
 /// Seeds relating to the PDA account that acts both as the mint
 /// *and* the mint authority.
@@ -94,6 +102,81 @@ impl CollateralPlugin {
         }
         Ok(())
     }
+
+    /// The balance of a token account of either SPL token program.
+    fn token_account_balance(token_account_info: &AccountInfo) -> Result<u64, ProgramError> {
+        let data = token_account_info.data.borrow();
+        let state = StateWithExtensions::<TokenAccount>::unpack(&data)?;
+        Ok(state.base.amount)
+    }
+
+    /// Adds the accounts of the mint's transfer hook, if it has one, to a
+    /// `transfer_checked` instruction. These are the next accounts of
+    /// `accounts_iter`:
+    /// 0.   `[executable]` The transfer hook program.
+    /// 1.   `[]` The hook's extra account metas PDA for the mint.
+    /// 2..N `[??..??]` The extra accounts resolved from the extra account metas.
+    fn add_transfer_hook_accounts<'a, 'b>(
+        mint_account_info: &AccountInfo<'b>,
+        transfer_instruction: &mut Instruction,
+        transfer_account_infos: &mut Vec<AccountInfo<'b>>,
+        accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
+        amount: u64,
+    ) -> Result<(), ProgramError> {
+        let Some(hook_program_id) = transfer_hook_program_id(&mint_account_info.data.borrow())?
+        else {
+            return Ok(());
+        };
+
+        // Account 0: The transfer hook program.
+        let hook_program_account_info = next_account_info(accounts_iter)?;
+        if hook_program_account_info.key != &hook_program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Account 1: The extra account metas PDA.
+        let extra_account_metas_account_info = next_account_info(accounts_iter)?;
+        if extra_account_metas_account_info.key
+            != &extra_account_metas_address(mint_account_info.key, &hook_program_id)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // The source, mint, destination and authority of the transfer.
+        let transfer_accounts = [0, 1, 2, 3].map(|i| transfer_instruction.accounts[i].pubkey);
+        let extra_account_metas = resolve_extra_account_metas(
+            &extra_account_metas_account_info.data.borrow(),
+            &hook_program_id,
+            transfer_accounts,
+            amount,
+        )?;
+
+        // Accounts 2..N: The extra accounts.
+        for extra_account_meta in extra_account_metas {
+            let extra_account_info = next_account_info(accounts_iter)?;
+            if extra_account_info.key != &extra_account_meta.pubkey {
+                return Err(ProgramError::InvalidArgument);
+            }
+            transfer_instruction.accounts.push(extra_account_meta);
+            transfer_account_infos.push(extra_account_info.clone());
+        }
+
+        // Token-2022 finds the hook program and the extra account metas PDA
+        // among the accounts by key.
+        transfer_instruction
+            .accounts
+            .push(AccountMeta::new_readonly(hook_program_id, false));
+        transfer_instruction
+            .accounts
+            .push(AccountMeta::new_readonly(
+                *extra_account_metas_account_info.key,
+                false,
+            ));
+        transfer_account_infos.push(hook_program_account_info.clone());
+        transfer_account_infos.push(extra_account_metas_account_info.clone());
+
+        Ok(())
+    }
 }
 
 impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
@@ -230,19 +313,22 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
 
     /// Transfers tokens to the escrow account so they can be sent to a remote chain.
     /// Burns the tokens from the sender's associated token account.
+    /// Returns the amount received by the escrow, net of any transfer fee.
     ///
     /// Accounts:
     /// 0. `[executable]` The SPL token program for the mint.
     /// 1. `[writeable]` The mint.
     /// 2. `[writeable]` The token sender's associated token account, from which tokens will be sent.
     /// 3. `[writeable]` The escrow PDA account.
+    /// 4..N `[??..??]` If the mint has a transfer hook, the hook program, its extra
+    ///      account metas PDA for the mint and the extra accounts it requires.
     fn transfer_in<'a, 'b>(
         _program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
         sender_wallet_account_info: &'a AccountInfo<'b>,
         accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
         amount: u64,
-    ) -> Result<(), ProgramError> {
+    ) -> Result<u64, ProgramError> {
         // Account 0: SPL token program.
         let spl_token_account_info = next_account_info(accounts_iter)?;
         if spl_token_account_info.key != &token.plugin_data.spl_token_program {
@@ -278,7 +364,7 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             return Err(ProgramError::IncorrectProgramId);
         }
 
-        let transfer_instruction = transfer_checked(
+        let mut transfer_instruction = transfer_checked(
             spl_token_account_info.key,
             sender_ata_account_info.key,
             mint_account_info.key,
@@ -289,23 +375,39 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             amount,
             token.decimals,
         )?;
-
-        // Sender wallet is expected to have signed this transaction.
-        invoke(
-            &transfer_instruction,
-            &[
-                sender_ata_account_info.clone(),
-                mint_account_info.clone(),
-                escrow_account_info.clone(),
-                sender_wallet_account_info.clone(),
-            ],
+        let mut transfer_account_infos = vec![
+            sender_ata_account_info.clone(),
+            mint_account_info.clone(),
+            escrow_account_info.clone(),
+            sender_wallet_account_info.clone(),
+        ];
+
+        // Accounts 4..N: The transfer hook accounts, if the mint has a transfer hook.
+        Self::add_transfer_hook_accounts(
+            mint_account_info,
+            &mut transfer_instruction,
+            &mut transfer_account_infos,
+            accounts_iter,
+            amount,
         )?;
 
-        Ok(())
+        let escrow_balance_before = Self::token_account_balance(escrow_account_info)?;
+
+        // Sender wallet is expected to have signed this transaction.
+        invoke(&transfer_instruction, &transfer_account_infos)?;
+
+        // Token-2022 mints with the transfer fee extension withhold the fee
+        // from the amount credited to the escrow, so only the net amount
+        // received can be sent to the remote.
+        Self::token_account_balance(escrow_account_info)?
+            .checked_sub(escrow_balance_before)
+            .ok_or(ProgramError::InvalidAccountData)
     }
 
     /// Transfers tokens out to a recipient's associated token account as a
     /// result of a transfer to this chain from a remote chain.
+    /// If the mint charges a transfer fee, it is withheld from the amount
+    /// credited to the recipient.
     ///
     /// Accounts:
     /// 0. `[executable]` SPL token for the mint.
@@ -314,6 +416,8 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
     /// 3. `[writeable]` Recipient associated token account.
     /// 4. `[writeable]` ATA payer PDA account.
     /// 5. `[writeable]` Escrow account.
+    /// 6..N `[??..??]` If the mint has a transfer hook, the hook program, its extra
+    ///      account metas PDA for the mint and the extra accounts it requires.
     fn transfer_out<'a, 'b>(
         program_id: &Pubkey,
         token: &HyperlaneToken<Self>,
@@ -396,7 +500,7 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
         // the ATA payer still meets the rent-exemption requirements!
         verify_rent_exempt(ata_payer_account_info, &Rent::get()?)?;
 
-        let transfer_instruction = transfer_checked(
+        let mut transfer_instruction = transfer_checked(
             spl_token_account_info.key,
             escrow_account_info.key,
             mint_account_info.key,
@@ -406,15 +510,25 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             amount,
             token.decimals,
         )?;
+        let mut transfer_account_infos = vec![
+            escrow_account_info.clone(),
+            mint_account_info.clone(),
+            recipient_ata_account_info.clone(),
+            escrow_account_info.clone(),
+        ];
+
+        // Accounts 6..N: The transfer hook accounts, if the mint has a transfer hook.
+        Self::add_transfer_hook_accounts(
+            mint_account_info,
+            &mut transfer_instruction,
+            &mut transfer_account_infos,
+            accounts_iter,
+            amount,
+        )?;
 
         invoke_signed(
             &transfer_instruction,
-            &[
-                escrow_account_info.clone(),
-                mint_account_info.clone(),
-                recipient_ata_account_info.clone(),
-                escrow_account_info.clone(),
-            ],
+            &transfer_account_infos,
             &[hyperlane_token_escrow_pda_seeds!(
                 token.plugin_data.escrow_bump
             )],
@@ -453,4 +567,55 @@ impl HyperlaneSealevelTokenPlugin for CollateralPlugin {
             false,
         ))
     }
+
+    /// Returns the transfer hook accounts required by `transfer_out` if the
+    /// mint has a transfer hook. These are resolved from the data of the mint
+    /// and of the hook's extra account metas PDA, so only once those accounts
+    /// are supplied.
+    fn transfer_out_extra_account_metas(
+        _program_id: &Pubkey,
+        token: &HyperlaneToken<Self>,
+        token_message: &TokenMessage,
+        accounts: &[AccountInfo],
+    ) -> Result<Vec<SerializableAccountMeta>, ProgramError> {
+        let mint = token.plugin_data.mint;
+        let Some(mint_account_info) = accounts.iter().find(|account| account.key == &mint) else {
+            return Ok(vec![]);
+        };
+        let Some(hook_program_id) = transfer_hook_program_id(&mint_account_info.data.borrow())?
+        else {
+            return Ok(vec![]);
+        };
+
+        let extra_account_metas_key = extra_account_metas_address(&mint, &hook_program_id);
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(hook_program_id, false),
+            AccountMeta::new_readonly(extra_account_metas_key, false),
+        ];
+
+        if let Some(extra_account_metas_account_info) = accounts
+            .iter()
+            .find(|account| account.key == &extra_account_metas_key)
+        {
+            let recipient_associated_token_account = get_associated_token_address_with_program_id(
+                &Pubkey::new_from_array(token_message.recipient().into()),
+                &mint,
+                &token.plugin_data.spl_token_program,
+            );
+            let amount = token.remote_amount_to_local_amount(token_message.amount())?;
+            account_metas.extend(resolve_extra_account_metas(
+                &extra_account_metas_account_info.data.borrow(),
+                &hook_program_id,
+                [
+                    token.plugin_data.escrow,
+                    mint,
+                    recipient_associated_token_account,
+                    token.plugin_data.escrow,
+                ],
+                amount,
+            )?);
+        }
+
+        Ok(account_metas.into_iter().map(Into::into).collect())
+    }
 }
@@ -0,0 +1,349 @@
+//! Support for Token-2022 mints with the transfer hook extension.
+//!
+//! The pinned `spl-token-2022` predates the extension, so the mint's
+//! extension data and the hook's extra account metas are parsed by hand,
+//! following the layouts of `spl-token-2022` and `spl-tlv-account-resolution`.
+//! Token-2022 finds the hook program, its extra account metas PDA and the
+//! extra accounts among the accounts of a `transfer_checked` by key, so all
+//! that's required of callers is to append them.
+
+use solana_program::{
+    hash::hash, instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey,
+};
+use spl_token_2022::state::Account as TokenAccount;
+
+/// The extension type of the transfer hook extension of mints.
+const TRANSFER_HOOK_EXTENSION_TYPE: u16 = 14;
+
+/// The account type of mints in the data of Token-2022 accounts with extensions.
+const MINT_ACCOUNT_TYPE: u8 = 1;
+
+/// The seed of the PDA of a transfer hook program holding the extra account
+/// metas of a mint.
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// The preimage of the discriminator of the transfer hook interface's
+/// `Execute` instruction.
+const EXECUTE_DISCRIMINATOR_PREIMAGE: &[u8] = b"spl-transfer-hook-interface:execute";
+
+/// The size of an `ExtraAccountMeta`.
+const EXTRA_ACCOUNT_META_SIZE: usize = 35;
+
+/// Discriminators of `ExtraAccountMeta`s of literal addresses and of PDAs
+/// of the transfer hook program. Discriminators with the top bit set are PDAs
+/// of the program at the index in the remaining bits.
+const LITERAL_ADDRESS_DISCRIMINATOR: u8 = 0;
+const HOOK_PROGRAM_PDA_DISCRIMINATOR: u8 = 1;
+const EXTERNAL_PDA_DISCRIMINATOR_BIT: u8 = 1 << 7;
+
+/// The seed types of PDA `ExtraAccountMeta`s.
+const SEED_TYPE_END: u8 = 0;
+const SEED_TYPE_LITERAL: u8 = 1;
+const SEED_TYPE_INSTRUCTION_DATA: u8 = 2;
+const SEED_TYPE_ACCOUNT_KEY: u8 = 3;
+
+/// Gets the transfer hook program of a mint from its account data, if it has one.
+pub fn transfer_hook_program_id(mint_data: &[u8]) -> Result<Option<Pubkey>, ProgramError> {
+    // Mints without extensions are just the base mint.
+    if mint_data.len() <= TokenAccount::LEN {
+        return Ok(None);
+    }
+    if mint_data[TokenAccount::LEN] != MINT_ACCOUNT_TYPE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut tlv_data = &mint_data[TokenAccount::LEN + 1..];
+    while tlv_data.len() >= 4 {
+        let extension_type = u16::from_le_bytes([tlv_data[0], tlv_data[1]]);
+        let length = u16::from_le_bytes([tlv_data[2], tlv_data[3]]) as usize;
+        let value = tlv_data
+            .get(4..4 + length)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        match extension_type {
+            // Uninitialized, i.e. the end of the extensions.
+            0 => break,
+            TRANSFER_HOOK_EXTENSION_TYPE => {
+                // The extension is the authority followed by the program ID,
+                // each of which is unset when zero.
+                let program_id: [u8; 32] = value
+                    .get(32..64)
+                    .and_then(|program_id| program_id.try_into().ok())
+                    .ok_or(ProgramError::InvalidAccountData)?;
+                return Ok((program_id != [0; 32]).then(|| Pubkey::new_from_array(program_id)));
+            }
+            _ => tlv_data = &tlv_data[4 + length..],
+        }
+    }
+    Ok(None)
+}
+
+/// Gets the PDA of a transfer hook program holding the extra account metas of a mint.
+pub fn extra_account_metas_address(mint: &Pubkey, hook_program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()], hook_program_id).0
+}
+
+/// Resolves the extra accounts a transfer hook requires for a transfer,
+/// given the data of its extra account metas PDA.
+///
+/// `transfer_accounts` are the source, mint, destination and authority of
+/// the transfer. Extra accounts that are PDAs seeded by the data of other
+/// accounts aren't supported.
+pub fn resolve_extra_account_metas(
+    extra_account_metas_data: &[u8],
+    hook_program_id: &Pubkey,
+    transfer_accounts: [Pubkey; 4],
+    amount: u64,
+) -> Result<Vec<AccountMeta>, ProgramError> {
+    let execute_discriminator = &hash(EXECUTE_DISCRIMINATOR_PREIMAGE).to_bytes()[..8];
+    let mut instruction_data = execute_discriminator.to_vec();
+    instruction_data.extend_from_slice(&amount.to_le_bytes());
+
+    let [source, mint, destination, authority] = transfer_accounts;
+    // The accounts of the hook's `Execute` instruction, which seeds of extra
+    // accounts can refer to by index, followed by the extra accounts.
+    let mut execute_accounts = vec![
+        AccountMeta::new_readonly(source, false),
+        AccountMeta::new_readonly(mint, false),
+        AccountMeta::new_readonly(destination, false),
+        AccountMeta::new_readonly(authority, false),
+        AccountMeta::new_readonly(extra_account_metas_address(&mint, hook_program_id), false),
+    ];
+
+    for extra_account_meta in
+        execute_extra_account_metas(extra_account_metas_data)?.chunks_exact(EXTRA_ACCOUNT_META_SIZE)
+    {
+        let discriminator = extra_account_meta[0];
+        let address_config: &[u8; 32] = extra_account_meta[1..33]
+            .try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        let pubkey = match discriminator {
+            LITERAL_ADDRESS_DISCRIMINATOR => Pubkey::new_from_array(*address_config),
+            HOOK_PROGRAM_PDA_DISCRIMINATOR => pda(
+                address_config,
+                hook_program_id,
+                &execute_accounts,
+                &instruction_data,
+            )?,
+            _ if discriminator & EXTERNAL_PDA_DISCRIMINATOR_BIT != 0 => {
+                let program_index = (discriminator & !EXTERNAL_PDA_DISCRIMINATOR_BIT) as usize;
+                let program_id = execute_accounts
+                    .get(program_index)
+                    .ok_or(ProgramError::InvalidAccountData)?
+                    .pubkey;
+                pda(
+                    address_config,
+                    &program_id,
+                    &execute_accounts,
+                    &instruction_data,
+                )?
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        execute_accounts.push(AccountMeta {
+            pubkey,
+            is_signer: extra_account_meta[33] != 0,
+            is_writable: extra_account_meta[34] != 0,
+        });
+    }
+
+    Ok(execute_accounts.split_off(5))
+}
+
+/// Gets the `ExtraAccountMeta`s of the `Execute` instruction out of the TLV
+/// data of an extra account metas PDA.
+fn execute_extra_account_metas(data: &[u8]) -> Result<&[u8], ProgramError> {
+    let execute_discriminator = &hash(EXECUTE_DISCRIMINATOR_PREIMAGE).to_bytes()[..8];
+
+    let mut tlv_data = data;
+    while tlv_data.len() >= 12 {
+        let length = u32::from_le_bytes(tlv_data[8..12].try_into().unwrap()) as usize;
+        let value = tlv_data
+            .get(12..12 + length)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        if &tlv_data[..8] == execute_discriminator {
+            // A `PodSlice`, i.e. the number of items followed by the items.
+            let count = value
+                .get(..4)
+                .map(|count| u32::from_le_bytes(count.try_into().unwrap()) as usize)
+                .ok_or(ProgramError::InvalidAccountData)?;
+            return value
+                .get(4..4 + count * EXTRA_ACCOUNT_META_SIZE)
+                .ok_or(ProgramError::InvalidAccountData);
+        }
+        tlv_data = &tlv_data[12 + length..];
+    }
+    Err(ProgramError::InvalidAccountData)
+}
+
+/// Derives the PDA of a PDA `ExtraAccountMeta` from its packed seeds.
+fn pda(
+    address_config: &[u8; 32],
+    program_id: &Pubkey,
+    execute_accounts: &[AccountMeta],
+    instruction_data: &[u8],
+) -> Result<Pubkey, ProgramError> {
+    let mut seeds: Vec<&[u8]> = vec![];
+    let mut config = &address_config[..];
+    while let Some((&seed_type, rest)) = config.split_first() {
+        let (seed, rest) = match (seed_type, rest) {
+            (SEED_TYPE_END, _) => break,
+            (SEED_TYPE_LITERAL, [length, rest @ ..]) => {
+                let length = *length as usize;
+                (
+                    rest.get(..length).ok_or(ProgramError::InvalidAccountData)?,
+                    &rest[length..],
+                )
+            }
+            (SEED_TYPE_INSTRUCTION_DATA, [index, length, rest @ ..]) => (
+                instruction_data
+                    .get(*index as usize..*index as usize + *length as usize)
+                    .ok_or(ProgramError::InvalidAccountData)?,
+                rest,
+            ),
+            (SEED_TYPE_ACCOUNT_KEY, [index, rest @ ..]) => (
+                execute_accounts
+                    .get(*index as usize)
+                    .ok_or(ProgramError::InvalidAccountData)?
+                    .pubkey
+                    .as_ref(),
+                rest,
+            ),
+            // Seeds from account data, or malformed seeds.
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        seeds.push(seed);
+        config = rest;
+    }
+    Ok(Pubkey::find_program_address(&seeds, program_id).0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mint_data(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0; TokenAccount::LEN];
+        data.push(MINT_ACCOUNT_TYPE);
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn extra_account_metas_data(extra_account_metas: &[[u8; EXTRA_ACCOUNT_META_SIZE]]) -> Vec<u8> {
+        let mut value = (extra_account_metas.len() as u32).to_le_bytes().to_vec();
+        for extra_account_meta in extra_account_metas {
+            value.extend_from_slice(extra_account_meta);
+        }
+        let mut data = hash(EXECUTE_DISCRIMINATOR_PREIMAGE).to_bytes()[..8].to_vec();
+        data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        data.extend_from_slice(&value);
+        data
+    }
+
+    fn extra_account_meta(
+        discriminator: u8,
+        address_config: &[u8],
+        is_writable: bool,
+    ) -> [u8; EXTRA_ACCOUNT_META_SIZE] {
+        let mut extra_account_meta = [0; EXTRA_ACCOUNT_META_SIZE];
+        extra_account_meta[0] = discriminator;
+        extra_account_meta[1..1 + address_config.len()].copy_from_slice(address_config);
+        extra_account_meta[34] = is_writable as u8;
+        extra_account_meta
+    }
+
+    #[test]
+    fn test_transfer_hook_program_id() {
+        let hook_program_id = Pubkey::new_unique();
+
+        // No extensions
+        assert_eq!(transfer_hook_program_id(&[0; 82]).unwrap(), None);
+        // Other extensions only
+        assert_eq!(
+            transfer_hook_program_id(&mint_data(&[(1, vec![0; 108])])).unwrap(),
+            None
+        );
+
+        let mut transfer_hook = vec![0; 32];
+        transfer_hook.extend_from_slice(hook_program_id.as_ref());
+        assert_eq!(
+            transfer_hook_program_id(&mint_data(&[(1, vec![0; 108]), (14, transfer_hook)]))
+                .unwrap(),
+            Some(hook_program_id)
+        );
+
+        // An unset hook program
+        assert_eq!(
+            transfer_hook_program_id(&mint_data(&[(14, vec![0; 64])])).unwrap(),
+            None
+        );
+        // A truncated extension
+        assert!(transfer_hook_program_id(&mint_data(&[(14, vec![0; 64])])[..200]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_extra_account_metas() {
+        let hook_program_id = Pubkey::new_unique();
+        let literal = Pubkey::new_unique();
+        let transfer_accounts = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let amount: u64 = 1234;
+
+        // A literal seed, the destination's key and the amount.
+        let mut seeds = vec![SEED_TYPE_LITERAL, 4];
+        seeds.extend_from_slice(b"seed");
+        seeds.extend_from_slice(&[SEED_TYPE_ACCOUNT_KEY, 2, SEED_TYPE_INSTRUCTION_DATA, 8, 8]);
+        // The literal account's key, i.e. the first extra account at index 5.
+        let external_seeds = [SEED_TYPE_ACCOUNT_KEY, 5];
+
+        let data = extra_account_metas_data(&[
+            extra_account_meta(LITERAL_ADDRESS_DISCRIMINATOR, literal.as_ref(), false),
+            extra_account_meta(HOOK_PROGRAM_PDA_DISCRIMINATOR, &seeds, true),
+            extra_account_meta(EXTERNAL_PDA_DISCRIMINATOR_BIT | 5, &external_seeds, false),
+        ]);
+
+        let hook_pda = Pubkey::find_program_address(
+            &[
+                b"seed",
+                transfer_accounts[2].as_ref(),
+                &amount.to_le_bytes(),
+            ],
+            &hook_program_id,
+        )
+        .0;
+        let external_pda = Pubkey::find_program_address(&[literal.as_ref()], &literal).0;
+        assert_eq!(
+            resolve_extra_account_metas(&data, &hook_program_id, transfer_accounts, amount)
+                .unwrap(),
+            vec![
+                AccountMeta::new_readonly(literal, false),
+                AccountMeta::new(hook_pda, false),
+                AccountMeta::new_readonly(external_pda, false),
+            ]
+        );
+
+        // Seeds from account data aren't supported
+        let data = extra_account_metas_data(&[extra_account_meta(
+            HOOK_PROGRAM_PDA_DISCRIMINATOR,
+            &[4, 0, 0, 32],
+            false,
+        )]);
+        assert!(
+            resolve_extra_account_metas(&data, &hook_program_id, transfer_accounts, amount)
+                .is_err()
+        );
+
+        // No extra account metas for `Execute`
+        assert!(
+            resolve_extra_account_metas(&[0; 16], &hook_program_id, transfer_accounts, amount)
+                .is_err()
+        );
+    }
+}
@@ -36,7 +36,7 @@ use hyperlane_sealevel_token_collateral::{
 };
 use hyperlane_sealevel_token_lib::{
     accounts::{convert_decimals, HyperlaneToken, HyperlaneTokenAccount},
-    hyperlane_token_pda_seeds,
+    hyperlane_token_pda_seeds, hyperlane_token_transfer_limits_pda_seeds,
     instruction::{Init, Instruction as HyperlaneTokenInstruction, TransferRemote},
     message::TokenMessage,
 };
@@ -52,7 +52,11 @@ use solana_sdk::{
     transaction::{Transaction, TransactionError},
 };
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
-use spl_token_2022::instruction::initialize_mint2;
+use spl_token_2022::{
+    extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType},
+    instruction::initialize_mint2,
+    state::Mint,
+};
 
 /// There are 1e9 lamports in one SOL.
 const ONE_SOL_IN_LAMPORTS: u64 = 1000000000;
@@ -166,6 +170,65 @@ async fn initialize_mint(
     (mint_pubkey, mint_authority)
 }
 
+/// Initializes a Token-2022 mint with the transfer fee extension, charging
+/// `transfer_fee_basis_points` on transfers.
+async fn initialize_mint_with_transfer_fee(
+    banks_client: &mut BanksClient,
+    payer: &Keypair,
+    decimals: u8,
+    transfer_fee_basis_points: u16,
+) -> (Pubkey, Keypair) {
+    let mint = Keypair::new();
+    let mint_authority = new_funded_keypair(banks_client, payer, ONE_SOL_IN_LAMPORTS).await;
+
+    let payer_pubkey = payer.pubkey();
+    let mint_pubkey = mint.pubkey();
+    let mint_authority_pubkey = mint_authority.pubkey();
+
+    let mint_account_len =
+        ExtensionType::get_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]);
+
+    let init_transfer_fee_instruction = initialize_transfer_fee_config(
+        &spl_token_2022::id(),
+        &mint_pubkey,
+        Some(&mint_authority_pubkey),
+        Some(&mint_authority_pubkey),
+        transfer_fee_basis_points,
+        u64::MAX,
+    )
+    .unwrap();
+    let init_mint_instruction = initialize_mint2(
+        &spl_token_2022::id(),
+        &mint_pubkey,
+        &mint_authority_pubkey,
+        // No freeze authority
+        None,
+        decimals,
+    )
+    .unwrap();
+
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer_pubkey,
+                &mint_pubkey,
+                Rent::default().minimum_balance(mint_account_len),
+                mint_account_len.try_into().unwrap(),
+                &spl_token_2022::id(),
+            ),
+            init_transfer_fee_instruction,
+            init_mint_instruction,
+        ],
+        Some(&payer_pubkey),
+        &[payer, &mint],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    (mint_pubkey, mint_authority)
+}
+
 async fn mint_to(
     banks_client: &mut BanksClient,
     spl_token_program_id: &Pubkey,
@@ -559,7 +622,7 @@ async fn test_initialize_errors_if_called_twice() {
     );
 }
 
-async fn test_transfer_remote(spl_token_program_id: Pubkey) {
+async fn test_transfer_remote(spl_token_program_id: Pubkey, transfer_fee_basis_points: u16) {
     let program_id = hyperlane_sealevel_token_collateral_id();
     let mailbox_program_id = mailbox_id();
 
@@ -581,13 +644,23 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
             .await
             .unwrap();
 
-    let (mint, mint_authority) = initialize_mint(
-        &mut banks_client,
-        &payer,
-        LOCAL_DECIMALS,
-        &spl_token_program_id,
-    )
-    .await;
+    let (mint, mint_authority) = if transfer_fee_basis_points > 0 {
+        initialize_mint_with_transfer_fee(
+            &mut banks_client,
+            &payer,
+            LOCAL_DECIMALS,
+            transfer_fee_basis_points,
+        )
+        .await
+    } else {
+        initialize_mint(
+            &mut banks_client,
+            &payer,
+            LOCAL_DECIMALS,
+            &spl_token_program_id,
+        )
+        .await
+    };
 
     let hyperlane_token_accounts = initialize_hyperlane_token(
         &program_id,
@@ -641,8 +714,15 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
     let remote_token_recipient = H256::random();
     // Transfer 69 tokens.
     let transfer_amount = 69 * 10u64.pow(LOCAL_DECIMALS_U32);
+    // The transfer fee is withheld from the amount received by the escrow,
+    // so only the remainder is sent to the remote.
+    let transfer_fee = transfer_amount * u64::from(transfer_fee_basis_points) / 10_000;
+    let received_amount = transfer_amount - transfer_fee;
     let remote_transfer_amount =
-        convert_decimals(transfer_amount.into(), LOCAL_DECIMALS, REMOTE_DECIMALS).unwrap();
+        convert_decimals(received_amount.into(), LOCAL_DECIMALS, REMOTE_DECIMALS).unwrap();
+
+    let (transfer_limits_key, _transfer_limits_bump) =
+        Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), &program_id);
 
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
     let transaction = Transaction::new_signed_with_payer(
@@ -675,6 +755,7 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
             // 15. `[writeable]` The mint.
             // 16. `[writeable]` The token sender's associated token account, from which tokens will be sent.
             // 17. `[writeable]` The escrow PDA account.
+            // 18. `[writeable]` The transfer limits PDA account.
             vec![
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(spl_noop::id(), false),
@@ -694,6 +775,7 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
                 AccountMeta::new(mint, false),
                 AccountMeta::new(token_sender_ata, false),
                 AccountMeta::new(hyperlane_token_accounts.escrow, false),
+                AccountMeta::new(transfer_limits_key, false),
             ],
         )],
         Some(&token_sender_pubkey),
@@ -711,11 +793,11 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
     )
     .await;
 
-    // And that the escrow's balance is 69 tokens.
+    // And that the escrow's balance is 69 tokens, minus the transfer fee.
     assert_token_balance(
         &mut banks_client,
         &hyperlane_token_accounts.escrow,
-        69 * 10u64.pow(LOCAL_DECIMALS_U32),
+        received_amount,
     )
     .await;
 
@@ -788,13 +870,19 @@ async fn test_transfer_remote(spl_token_program_id: Pubkey) {
 // Test transfer_remote with spl_token
 #[tokio::test]
 async fn test_transfer_remote_spl_token() {
-    test_transfer_remote(spl_token_2022::id()).await;
+    test_transfer_remote(spl_token_2022::id(), 0).await;
 }
 
 // Test transfer_remote with spl_token_2022
 #[tokio::test]
 async fn test_transfer_remote_spl_token_2022() {
-    test_transfer_remote(spl_token_2022::id()).await;
+    test_transfer_remote(spl_token_2022::id(), 0).await;
+}
+
+// Test transfer_remote with a spl_token_2022 mint charging a 1% transfer fee
+#[tokio::test]
+async fn test_transfer_remote_spl_token_2022_transfer_fee() {
+    test_transfer_remote(spl_token_2022::id(), 100).await;
 }
 
 async fn transfer_from_remote(
@@ -123,7 +123,7 @@ impl HyperlaneSealevelTokenPlugin for NativePlugin {
         sender_wallet: &'a AccountInfo<'b>,
         accounts_iter: &mut std::slice::Iter<'a, AccountInfo<'b>>,
         amount: u64,
-    ) -> Result<(), ProgramError> {
+    ) -> Result<u64, ProgramError> {
         // Account 0: System program.
         let system_program = next_account_info(accounts_iter)?;
         if system_program.key != &solana_program::system_program::id() {
@@ -138,7 +138,9 @@ impl HyperlaneSealevelTokenPlugin for NativePlugin {
         invoke(
             &system_instruction::transfer(sender_wallet.key, native_collateral_account.key, amount),
             &[sender_wallet.clone(), native_collateral_account.clone()],
-        )
+        )?;
+
+        Ok(amount)
     }
 
     /// Transfers tokens out to a recipient's associated token account as a
@@ -9,7 +9,7 @@ use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
 use hyperlane_sealevel_token_lib::{
-    instruction::{Init, Instruction as TokenIxn, TransferRemote},
+    instruction::{Init, Instruction as TokenIxn, TransferLimitConfig, TransferRemote},
     processor::HyperlaneSealevelToken,
 };
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
@@ -73,6 +73,10 @@ pub fn process_instruction(
         TokenIxn::TransferOwnership(new_owner) => {
             transfer_ownership(program_id, accounts, new_owner)
         }
+        TokenIxn::SetTransferLimits(configs) => set_transfer_limits(program_id, accounts, configs),
+        TokenIxn::GetTransferCapacity(domain) => {
+            get_transfer_capacity(program_id, accounts, domain)
+        }
         TokenIxn::SetInterchainSecurityModule(new_ism) => {
             set_interchain_security_module(program_id, accounts, new_ism)
         }
@@ -121,6 +125,7 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
 ///      ---- End if ----
 /// 14.  `[executable]` The system program.
 /// 15.  `[writeable]` The native token collateral PDA account.
+/// 16.  `[writeable]` OPTIONAL - The transfer limits PDA account.
 fn transfer_remote(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -136,6 +141,7 @@ fn transfer_remote(
 /// 3.   `[writeable]` recipient wallet address
 /// 4.   `[executable]` The system program.
 /// 5.   `[writeable]` The native token collateral PDA account.
+/// 6.   `[writeable]` OPTIONAL - The transfer limits PDA account.
 fn transfer_from_remote(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -260,3 +266,31 @@ fn set_interchain_gas_paymaster(
         program_id, accounts, new_igp,
     )
 }
+
+/// Lets the owner set the limits on transfers to and from remote domains.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[]` The token PDA account.
+/// 2. `[writeable]` OPTIONAL - The transfer limits PDA account.
+/// 3. `[signer]` The access control owner.
+fn set_transfer_limits(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    configs: Vec<TransferLimitConfig>,
+) -> ProgramResult {
+    HyperlaneSealevelToken::<NativePlugin>::set_transfer_limits(program_id, accounts, configs)
+}
+
+/// Gets the capacity left to transfer to and from a remote domain,
+/// returning it as a serialized `TransferCapacity`.
+///
+/// Accounts:
+/// 0. `[]` The transfer limits PDA account.
+fn get_transfer_capacity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    domain: u32,
+) -> ProgramResult {
+    HyperlaneSealevelToken::<NativePlugin>::get_transfer_capacity(program_id, accounts, domain)
+}
@@ -29,7 +29,8 @@ use hyperlane_sealevel_message_recipient_interface::{
 };
 use hyperlane_sealevel_token_lib::{
     accounts::{convert_decimals, HyperlaneToken, HyperlaneTokenAccount},
-    hyperlane_token_pda_seeds,
+    error::Error as TokenError,
+    hyperlane_token_pda_seeds, hyperlane_token_transfer_limits_pda_seeds,
     instruction::{Init, Instruction as HyperlaneTokenInstruction, TransferRemote},
     message::TokenMessage,
 };
@@ -39,7 +40,8 @@ use hyperlane_sealevel_token_native::{
 };
 use hyperlane_test_utils::{
     assert_lamports, assert_transaction_error, igp_program_id, initialize_igp_accounts,
-    initialize_mailbox, mailbox_id, new_funded_keypair, process, transfer_lamports, IgpAccounts,
+    initialize_mailbox, mailbox_id, new_funded_keypair, process, process_instruction,
+    transfer_lamports, IgpAccounts,
 };
 use solana_program_test::*;
 use solana_sdk::{
@@ -418,6 +420,9 @@ async fn test_transfer_remote() {
         .await
         .unwrap();
 
+    let (transfer_limits_key, _transfer_limits_bump) =
+        Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), &program_id);
+
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
     let transaction = Transaction::new_signed_with_payer(
         &[Instruction::new_with_bytes(
@@ -447,6 +452,7 @@ async fn test_transfer_remote() {
             //      ---- End if ----
             // 14.  `[executable]` The system program.
             // 15.  `[writeable]` The native token collateral PDA account.
+            // 16.  `[writeable]` The transfer limits PDA account.
             vec![
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(spl_noop::id(), false),
@@ -464,6 +470,7 @@ async fn test_transfer_remote() {
                 AccountMeta::new(igp_accounts.igp, false),
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new(hyperlane_token_accounts.native_collateral, false),
+                AccountMeta::new(transfer_limits_key, false),
             ],
         )],
         Some(&token_sender_pubkey),
@@ -570,6 +577,91 @@ async fn test_transfer_remote() {
     );
 }
 
+#[tokio::test]
+async fn test_transfer_remote_errors_if_amount_is_zero() {
+    let program_id = hyperlane_sealevel_token_native_id();
+    let mailbox_program_id = mailbox_id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &mailbox_program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        ONE_SOL_IN_LAMPORTS,
+        ProtocolFee::default(),
+    )
+    .await
+    .unwrap();
+
+    let hyperlane_token_accounts =
+        initialize_hyperlane_token(&program_id, &mut banks_client, &payer, None)
+            .await
+            .unwrap();
+
+    enroll_remote_router(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        &hyperlane_token_accounts.token,
+        REMOTE_DOMAIN,
+        H256::random(),
+    )
+    .await
+    .unwrap();
+
+    let token_sender = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+    let token_sender_pubkey = token_sender.pubkey();
+
+    let unique_message_account_keypair = Keypair::new();
+    let (dispatched_message_key, _dispatched_message_bump) = Pubkey::find_program_address(
+        mailbox_dispatched_message_pda_seeds!(&unique_message_account_keypair.pubkey()),
+        &mailbox_program_id,
+    );
+    let (transfer_limits_key, _transfer_limits_bump) =
+        Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), &program_id);
+
+    let result = process_instruction(
+        &mut banks_client,
+        Instruction::new_with_bytes(
+            program_id,
+            &HyperlaneTokenInstruction::TransferRemote(TransferRemote {
+                destination_domain: REMOTE_DOMAIN,
+                recipient: H256::random(),
+                amount_or_id: 0.into(),
+            })
+            .encode()
+            .unwrap(),
+            vec![
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(spl_noop::id(), false),
+                AccountMeta::new_readonly(hyperlane_token_accounts.token, false),
+                AccountMeta::new_readonly(mailbox_accounts.program, false),
+                AccountMeta::new(mailbox_accounts.outbox, false),
+                AccountMeta::new_readonly(hyperlane_token_accounts.dispatch_authority, false),
+                AccountMeta::new_readonly(token_sender_pubkey, true),
+                AccountMeta::new_readonly(unique_message_account_keypair.pubkey(), true),
+                AccountMeta::new(dispatched_message_key, false),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new(hyperlane_token_accounts.native_collateral, false),
+                AccountMeta::new(transfer_limits_key, false),
+            ],
+        ),
+        &token_sender,
+        &[&token_sender, &unique_message_account_keypair],
+    )
+    .await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::ZeroAmount as u32),
+        ),
+    );
+}
+
 async fn transfer_from_remote(
     initial_native_collateral_balance: u64,
     remote_transfer_amount: U256,
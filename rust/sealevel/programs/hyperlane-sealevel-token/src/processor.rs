@@ -9,7 +9,7 @@ use hyperlane_sealevel_message_recipient_interface::{
     HandleInstruction, MessageRecipientInstruction,
 };
 use hyperlane_sealevel_token_lib::{
-    instruction::{Init, Instruction as TokenIxn, TransferRemote},
+    instruction::{Init, Instruction as TokenIxn, TransferLimitConfig, TransferRemote},
     processor::HyperlaneSealevelToken,
 };
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
@@ -79,6 +79,10 @@ pub fn process_instruction(
         TokenIxn::TransferOwnership(new_owner) => {
             transfer_ownership(program_id, accounts, new_owner)
         }
+        TokenIxn::SetTransferLimits(configs) => set_transfer_limits(program_id, accounts, configs),
+        TokenIxn::GetTransferCapacity(domain) => {
+            get_transfer_capacity(program_id, accounts, domain)
+        }
     }
     .map_err(|err| {
         msg!("{}", err);
@@ -123,6 +127,7 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
 /// 14. `[executable]` The spl_token_2022 program.
 /// 15. `[writeable]` The mint / mint authority PDA account.
 /// 16. `[writeable]` The token sender's associated token account, from which tokens will be burned.
+/// 17. `[writeable]` OPTIONAL - The transfer limits PDA account.
 fn transfer_remote(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -141,6 +146,7 @@ fn transfer_remote(
 // 6. `[writeable]` Mint account
 // 7. `[writeable]` Recipient associated token account
 // 8. `[writeable]` ATA payer PDA account.
+// 9. `[writeable]` OPTIONAL - The transfer limits PDA account.
 fn transfer_from_remote(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -262,3 +268,31 @@ fn set_interchain_gas_paymaster(
         program_id, accounts, new_igp,
     )
 }
+
+/// Lets the owner set the limits on transfers to and from remote domains.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[]` The token PDA account.
+/// 2. `[writeable]` OPTIONAL - The transfer limits PDA account.
+/// 3. `[signer]` The access control owner.
+fn set_transfer_limits(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    configs: Vec<TransferLimitConfig>,
+) -> ProgramResult {
+    HyperlaneSealevelToken::<SyntheticPlugin>::set_transfer_limits(program_id, accounts, configs)
+}
+
+/// Gets the capacity left to transfer to and from a remote domain,
+/// returning it as a serialized `TransferCapacity`.
+///
+/// Accounts:
+/// 0. `[]` The transfer limits PDA account.
+fn get_transfer_capacity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    domain: u32,
+) -> ProgramResult {
+    HyperlaneSealevelToken::<SyntheticPlugin>::get_transfer_capacity(program_id, accounts, domain)
+}
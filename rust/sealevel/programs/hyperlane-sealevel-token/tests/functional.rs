@@ -25,16 +25,25 @@ use hyperlane_sealevel_token::{
     processor::process_instruction,
 };
 use hyperlane_sealevel_token_lib::{
-    accounts::{convert_decimals, HyperlaneToken, HyperlaneTokenAccount},
-    hyperlane_token_pda_seeds,
-    instruction::{Init, Instruction as HyperlaneTokenInstruction, TransferRemote},
+    accounts::{
+        convert_decimals, HyperlaneToken, HyperlaneTokenAccount, TransferDirection,
+        TransferLimitsAccount,
+    },
+    error::Error as TokenError,
+    hyperlane_token_pda_seeds, hyperlane_token_transfer_limits_pda_seeds,
+    instruction::{
+        get_transfer_capacity_instruction, set_transfer_limits_instruction, Init,
+        Instruction as HyperlaneTokenInstruction, TransferCapacity, TransferLimitConfig,
+        TransferLimitParams, TransferRemote,
+    },
     message::TokenMessage,
 };
 use hyperlane_test_utils::{
     assert_token_balance, assert_transaction_error, igp_program_id, initialize_igp_accounts,
-    initialize_mailbox, mailbox_id, new_funded_keypair, process, transfer_lamports, IgpAccounts,
-    MailboxAccounts,
+    initialize_mailbox, mailbox_id, new_funded_keypair, process, simulate_instruction,
+    transfer_lamports, IgpAccounts, MailboxAccounts,
 };
+use serializable_account_meta::SimulationReturnData;
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     pubkey,
@@ -718,6 +727,9 @@ async fn test_transfer_remote() {
     let remote_transfer_amount =
         convert_decimals(transfer_amount.into(), LOCAL_DECIMALS, REMOTE_DECIMALS).unwrap();
 
+    let (transfer_limits_key, _transfer_limits_bump) =
+        Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), &program_id);
+
     let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
     let transaction = Transaction::new_signed_with_payer(
         &[Instruction::new_with_bytes(
@@ -748,6 +760,7 @@ async fn test_transfer_remote() {
             // 14. `[executable]` The spl_token_2022 program.
             // 15. `[writeable]` The mint / mint authority PDA account.
             // 16. `[writeable]` The token sender's associated token account, from which tokens will be burned.
+            // 17. `[writeable]` The transfer limits PDA account.
             vec![
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(spl_noop::id(), false),
@@ -766,6 +779,7 @@ async fn test_transfer_remote() {
                 AccountMeta::new_readonly(spl_token_2022::id(), false),
                 AccountMeta::new(hyperlane_token_accounts.mint, false),
                 AccountMeta::new(token_sender_ata, false),
+                AccountMeta::new(transfer_limits_key, false),
             ],
         )],
         Some(&token_sender_pubkey),
@@ -1355,3 +1369,367 @@ async fn test_set_interchain_gas_paymaster_errors_if_owner_not_signer() {
         TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
     );
 }
+
+#[tokio::test]
+async fn test_set_transfer_limits() {
+    let program_id = hyperlane_sealevel_token_id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_hyperlane_token(&program_id, &mut banks_client, &payer, None)
+        .await
+        .unwrap();
+
+    let configs = vec![TransferLimitConfig {
+        domain: REMOTE_DOMAIN,
+        limit: Some(TransferLimitParams {
+            window_seconds: 86400,
+            outbound_capacity: Some(1000),
+            inbound_capacity: None,
+        }),
+    }];
+
+    // Set the transfer limits, which creates the transfer limits account
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[set_transfer_limits_instruction(program_id, payer.pubkey(), configs).unwrap()],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    // Verify the limits are set
+    let (transfer_limits_key, transfer_limits_bump) =
+        Pubkey::find_program_address(hyperlane_token_transfer_limits_pda_seeds!(), &program_id);
+    let transfer_limits_account_data = banks_client
+        .get_account(transfer_limits_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let transfer_limits = TransferLimitsAccount::fetch(&mut &transfer_limits_account_data[..])
+        .unwrap()
+        .into_inner();
+    assert_eq!(transfer_limits.bump, transfer_limits_bump);
+    assert_eq!(
+        transfer_limits.capacity(REMOTE_DOMAIN, TransferDirection::Outbound, 0),
+        Some(1000)
+    );
+    assert_eq!(
+        transfer_limits.capacity(REMOTE_DOMAIN, TransferDirection::Inbound, 0),
+        None
+    );
+
+    // Remove the limits again
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[set_transfer_limits_instruction(
+            program_id,
+            payer.pubkey(),
+            vec![TransferLimitConfig {
+                domain: REMOTE_DOMAIN,
+                limit: None,
+            }],
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await.unwrap();
+
+    let transfer_limits_account_data = banks_client
+        .get_account(transfer_limits_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let transfer_limits = TransferLimitsAccount::fetch(&mut &transfer_limits_account_data[..])
+        .unwrap()
+        .into_inner();
+    assert!(transfer_limits.limits.is_empty());
+}
+
+#[tokio::test]
+async fn test_set_transfer_limits_errors_if_owner_not_signer() {
+    let program_id = hyperlane_sealevel_token_id();
+
+    let (mut banks_client, payer) = setup_client().await;
+
+    initialize_hyperlane_token(&program_id, &mut banks_client, &payer, None)
+        .await
+        .unwrap();
+
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, ONE_SOL_IN_LAMPORTS).await;
+
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[set_transfer_limits_instruction(
+            program_id,
+            non_owner.pubkey(),
+            vec![TransferLimitConfig {
+                domain: REMOTE_DOMAIN,
+                limit: None,
+            }],
+        )
+        .unwrap()],
+        Some(&non_owner.pubkey()),
+        &[&non_owner],
+        recent_blockhash,
+    );
+    let result = banks_client.process_transaction(transaction).await;
+
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+/// A token sender holding tokens received from the remote domain.
+struct TokenSender {
+    banks_client: BanksClient,
+    payer: Keypair,
+    mailbox_accounts: MailboxAccounts,
+    igp_accounts: IgpAccounts,
+    hyperlane_token_accounts: HyperlaneTokenAccounts,
+    sender: Keypair,
+    sender_ata: Pubkey,
+}
+
+impl TokenSender {
+    /// Sets up a token sender holding `balance` tokens.
+    async fn new(balance: u64) -> Self {
+        let sender = Keypair::new();
+        let (
+            mut banks_client,
+            payer,
+            mailbox_accounts,
+            igp_accounts,
+            hyperlane_token_accounts,
+            sender_ata,
+        ) = transfer_from_remote(
+            convert_decimals(balance.into(), LOCAL_DECIMALS, REMOTE_DECIMALS).unwrap(),
+            None,
+            None,
+            Some(sender.pubkey()),
+        )
+        .await
+        .unwrap();
+
+        // Give the token sender a SOL balance to pay tx fees.
+        transfer_lamports(
+            &mut banks_client,
+            &payer,
+            &sender.pubkey(),
+            ONE_SOL_IN_LAMPORTS,
+        )
+        .await;
+
+        Self {
+            banks_client,
+            payer,
+            mailbox_accounts,
+            igp_accounts,
+            hyperlane_token_accounts,
+            sender,
+            sender_ata,
+        }
+    }
+
+    /// Sets the limits on transfers to and from the remote domain.
+    async fn set_transfer_limits(&mut self, limit: TransferLimitParams) {
+        let recent_blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[set_transfer_limits_instruction(
+                hyperlane_sealevel_token_id(),
+                self.payer.pubkey(),
+                vec![TransferLimitConfig {
+                    domain: REMOTE_DOMAIN,
+                    limit: Some(limit),
+                }],
+            )
+            .unwrap()],
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            recent_blockhash,
+        );
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    /// Transfers `amount` tokens to the remote domain, passing the transfer
+    /// limits account if `with_transfer_limits` is true.
+    async fn transfer_remote(
+        &mut self,
+        amount: u64,
+        with_transfer_limits: bool,
+    ) -> Result<(), BanksClientError> {
+        let program_id = hyperlane_sealevel_token_id();
+        let unique_message_account_keypair = Keypair::new();
+        let (dispatched_message_key, _dispatched_message_bump) = Pubkey::find_program_address(
+            mailbox_dispatched_message_pda_seeds!(&unique_message_account_keypair.pubkey()),
+            &mailbox_id(),
+        );
+        let (gas_payment_pda_key, _gas_payment_pda_bump) = Pubkey::find_program_address(
+            igp_gas_payment_pda_seeds!(&unique_message_account_keypair.pubkey()),
+            &igp_program_id(),
+        );
+
+        // See `test_transfer_remote` for the accounts.
+        let mut accounts = vec![
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(spl_noop::id(), false),
+            AccountMeta::new_readonly(self.hyperlane_token_accounts.token, false),
+            AccountMeta::new_readonly(self.mailbox_accounts.program, false),
+            AccountMeta::new(self.mailbox_accounts.outbox, false),
+            AccountMeta::new_readonly(self.hyperlane_token_accounts.dispatch_authority, false),
+            AccountMeta::new_readonly(self.sender.pubkey(), true),
+            AccountMeta::new_readonly(unique_message_account_keypair.pubkey(), true),
+            AccountMeta::new(dispatched_message_key, false),
+            AccountMeta::new_readonly(self.igp_accounts.program, false),
+            AccountMeta::new(self.igp_accounts.program_data, false),
+            AccountMeta::new(gas_payment_pda_key, false),
+            AccountMeta::new_readonly(self.igp_accounts.overhead_igp, false),
+            AccountMeta::new(self.igp_accounts.igp, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new(self.hyperlane_token_accounts.mint, false),
+            AccountMeta::new(self.sender_ata, false),
+        ];
+        if with_transfer_limits {
+            let (transfer_limits_key, _transfer_limits_bump) = Pubkey::find_program_address(
+                hyperlane_token_transfer_limits_pda_seeds!(),
+                &program_id,
+            );
+            accounts.push(AccountMeta::new(transfer_limits_key, false));
+        }
+
+        let recent_blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[Instruction::new_with_bytes(
+                program_id,
+                &HyperlaneTokenInstruction::TransferRemote(TransferRemote {
+                    destination_domain: REMOTE_DOMAIN,
+                    recipient: H256::random(),
+                    amount_or_id: amount.into(),
+                })
+                .encode()
+                .unwrap(),
+                accounts,
+            )],
+            Some(&self.sender.pubkey()),
+            &[&self.sender, &unique_message_account_keypair],
+            recent_blockhash,
+        );
+        self.banks_client.process_transaction(transaction).await
+    }
+
+    async fn transfer_capacity(&mut self) -> TransferCapacity {
+        simulate_instruction::<SimulationReturnData<TransferCapacity>>(
+            &mut self.banks_client,
+            &self.payer,
+            get_transfer_capacity_instruction(hyperlane_sealevel_token_id(), REMOTE_DOMAIN)
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .return_data
+    }
+}
+
+#[tokio::test]
+async fn test_transfer_remote_errors_if_transfer_limit_exceeded() {
+    let balance = 100 * 10u64.pow(LOCAL_DECIMALS_U32);
+    let outbound_capacity = 50 * 10u64.pow(LOCAL_DECIMALS_U32);
+    let mut token_sender = TokenSender::new(balance).await;
+
+    // Without limits, the capacity is unlimited
+    assert_eq!(
+        token_sender.transfer_capacity().await,
+        TransferCapacity {
+            outbound: None,
+            inbound: None,
+        }
+    );
+
+    token_sender
+        .set_transfer_limits(TransferLimitParams {
+            window_seconds: 86400,
+            outbound_capacity: Some(outbound_capacity),
+            inbound_capacity: None,
+        })
+        .await;
+    assert_eq!(
+        token_sender.transfer_capacity().await,
+        TransferCapacity {
+            outbound: Some(outbound_capacity),
+            inbound: None,
+        }
+    );
+
+    // Transferring more than the capacity fails
+    let result = token_sender
+        .transfer_remote(outbound_capacity + 1, true)
+        .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::TransferLimitExceeded as u32),
+        ),
+    );
+    assert_token_balance(
+        &mut token_sender.banks_client,
+        &token_sender.sender_ata,
+        balance,
+    )
+    .await;
+
+    // Transfers within the capacity consume it
+    let transfer_amount = 40 * 10u64.pow(LOCAL_DECIMALS_U32);
+    token_sender
+        .transfer_remote(transfer_amount, true)
+        .await
+        .unwrap();
+    assert_token_balance(
+        &mut token_sender.banks_client,
+        &token_sender.sender_ata,
+        balance - transfer_amount,
+    )
+    .await;
+    assert_eq!(
+        token_sender.transfer_capacity().await.outbound,
+        Some(outbound_capacity - transfer_amount)
+    );
+
+    let result = token_sender.transfer_remote(transfer_amount, true).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(TokenError::TransferLimitExceeded as u32),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_remote_without_transfer_limits_account() {
+    let balance = 100 * 10u64.pow(LOCAL_DECIMALS_U32);
+    let mut token_sender = TokenSender::new(balance).await;
+
+    // Clients predating transfer limits don't pass the account
+    let transfer_amount = 69 * 10u64.pow(LOCAL_DECIMALS_U32);
+    token_sender
+        .transfer_remote(transfer_amount, false)
+        .await
+        .unwrap();
+    assert_token_balance(
+        &mut token_sender.banks_client,
+        &token_sender.sender_ata,
+        balance - transfer_amount,
+    )
+    .await;
+}
@@ -1,14 +1,16 @@
-use std::thread::sleep;
-
 use borsh::BorshDeserialize;
 use hyperlane_core::{
     accumulator::incremental::IncrementalMerkle as MerkleTree, HyperlaneMessage, H256,
 };
 use hyperlane_sealevel_mailbox::{
-    accounts::{Inbox, InboxAccount, Outbox},
+    accounts::{Inbox, InboxAccount, Outbox, ProcessedMessageRetention},
     error::Error as MailboxError,
-    instruction::{Instruction as MailboxInstruction, OutboxDispatch},
-    mailbox_dispatched_message_pda_seeds,
+    instruction::{
+        close_processed_message_instruction, enable_recipient_isms_instruction, recipient_ism_pda,
+        set_processed_message_retention_instruction, set_recipient_ism_instruction,
+        Instruction as MailboxInstruction, OutboxDispatch,
+    },
+    mailbox_dispatched_message_pda_seeds, mailbox_processed_message_pda_seeds,
     protocol_fee::ProtocolFee,
 };
 use hyperlane_sealevel_test_ism::{program::TestIsmError, test_client::TestIsmTestClient};
@@ -19,7 +21,7 @@ use hyperlane_sealevel_test_send_receiver::{
 use hyperlane_test_utils::{
     assert_transaction_error, clone_keypair, get_process_account_metas, get_recipient_ism,
     initialize_mailbox, mailbox_id, new_funded_keypair, process, process_instruction,
-    process_with_accounts,
+    process_with_accounts, warp_forward_slots, warp_to_slot,
 };
 use solana_program::{
     instruction::{AccountMeta, Instruction},
@@ -46,12 +48,7 @@ const REMOTE_DOMAIN: u32 = 69420;
 const PROTOCOL_FEE: u64 = 1_000_000_000;
 const MAX_PROTOCOL_FEE: u64 = 1_000_000_001;
 
-async fn setup_client() -> (
-    BanksClient,
-    Keypair,
-    TestSendReceiverTestClient,
-    TestIsmTestClient,
-) {
+fn mailbox_program_test() -> ProgramTest {
     let program_id = mailbox_id();
     let mut program_test = ProgramTest::new(
         "hyperlane_sealevel_mailbox",
@@ -80,12 +77,42 @@ async fn setup_client() -> (
         processor!(hyperlane_sealevel_test_send_receiver::program::process_instruction),
     );
 
-    let (banks_client, payer, _recent_blockhash) = program_test.start().await;
+    program_test
+}
 
-    let test_ism = TestIsmTestClient::new(banks_client.clone(), clone_keypair(&payer));
+async fn setup_client() -> (
+    BanksClient,
+    Keypair,
+    TestSendReceiverTestClient,
+    TestIsmTestClient,
+) {
+    let (banks_client, payer, _recent_blockhash) = mailbox_program_test().start().await;
+    let (test_send_receiver, test_ism) = setup_test_clients(&banks_client, &payer).await;
+
+    (banks_client, payer, test_send_receiver, test_ism)
+}
+
+/// Like `setup_client`, but with control over the validator's clock.
+async fn setup_context() -> (
+    ProgramTestContext,
+    TestSendReceiverTestClient,
+    TestIsmTestClient,
+) {
+    let context = mailbox_program_test().start_with_context().await;
+    let (test_send_receiver, test_ism) =
+        setup_test_clients(&context.banks_client, &context.payer).await;
+
+    (context, test_send_receiver, test_ism)
+}
+
+async fn setup_test_clients(
+    banks_client: &BanksClient,
+    payer: &Keypair,
+) -> (TestSendReceiverTestClient, TestIsmTestClient) {
+    let test_ism = TestIsmTestClient::new(banks_client.clone(), clone_keypair(payer));
 
     let mut test_send_receiver =
-        TestSendReceiverTestClient::new(banks_client.clone(), clone_keypair(&payer));
+        TestSendReceiverTestClient::new(banks_client.clone(), clone_keypair(payer));
     test_send_receiver.init().await.unwrap();
     test_send_receiver
         .set_ism(
@@ -95,7 +122,7 @@ async fn setup_client() -> (
         .await
         .unwrap();
 
-    (banks_client, payer, test_send_receiver, test_ism)
+    (test_send_receiver, test_ism)
 }
 
 fn test_protocol_fee_config() -> ProtocolFee {
@@ -154,6 +181,8 @@ async fn test_initialize() {
             inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
             default_ism: hyperlane_sealevel_test_ism::id(),
             processed_count: 0,
+            processed_message_retention: None,
+            recipient_isms_enabled: false,
         }
     );
 }
@@ -560,6 +589,72 @@ async fn test_setting_protocol_fee_config_from_unauthorized_account_fails() {
     )
 }
 
+#[tokio::test]
+async fn test_dispatch_records_current_slot() {
+    let program_id = mailbox_id();
+    let (mut context, _, _) = setup_context().await;
+    let payer = clone_keypair(&context.payer);
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut context.banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let warped_slot = 1_000;
+    warp_to_slot(&mut context, warped_slot).await;
+
+    let recipient = H256::random();
+    let message_body = vec![0, 1, 2, 3];
+    let outbox_dispatch = OutboxDispatch {
+        sender: payer.pubkey(),
+        destination_domain: REMOTE_DOMAIN,
+        recipient,
+        message_body: message_body.clone(),
+    };
+
+    let (dispatch_tx_signature, dispatch_unique_keypair, dispatched_message_account_key) =
+        dispatch_from_payer(
+            &mut context.banks_client,
+            &payer,
+            &mailbox_accounts,
+            outbox_dispatch,
+        )
+        .await
+        .unwrap();
+
+    let dispatch_tx_status = context
+        .banks_client
+        .get_transaction_status(dispatch_tx_signature)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(dispatch_tx_status.slot, warped_slot);
+
+    // The dispatched message account records the slot of the dispatch
+    assert_dispatched_message(
+        &mut context.banks_client,
+        dispatch_tx_signature,
+        dispatch_unique_keypair.pubkey(),
+        dispatched_message_account_key,
+        &HyperlaneMessage {
+            version: 3,
+            nonce: 0,
+            origin: LOCAL_DOMAIN,
+            sender: payer.pubkey().to_bytes().into(),
+            destination: REMOTE_DOMAIN,
+            recipient,
+            body: message_body,
+        },
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_dispatch_from_program() {
     let program_id = mailbox_id();
@@ -860,6 +955,265 @@ async fn test_get_recipient_ism_errors_with_malformatted_recipient_ism_return_da
     ));
 }
 
+#[tokio::test]
+async fn test_recipient_ism_override() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, mut test_send_receiver, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let recipient_id = test_send_receiver.id();
+
+    // Have the recipient's InterchainSecurityModule instruction return malformatted
+    // data, so getting its ISM only succeeds if the override is used instead.
+    test_send_receiver
+        .set_ism(None, IsmReturnDataMode::ReturnMalformmatedData)
+        .await
+        .unwrap();
+
+    // Overrides can't be set before the owner enables them.
+    let ism = Pubkey::new_unique();
+    let result = test_send_receiver
+        .set_mailbox_ism_override(&mailbox_accounts, Some(ism))
+        .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MailboxError::RecipientIsmsDisabled as u32),
+        ),
+    );
+
+    process_instruction(
+        &mut banks_client,
+        enable_recipient_isms_instruction(program_id, payer.pubkey()).unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    test_send_receiver
+        .set_mailbox_ism_override(&mailbox_accounts, Some(ism))
+        .await
+        .unwrap();
+
+    let recipient_ism =
+        get_recipient_ism(&mut banks_client, &payer, &mailbox_accounts, recipient_id)
+            .await
+            .unwrap();
+    assert_eq!(recipient_ism, ism);
+
+    // Messages are verified by the overriding ISM.
+    test_send_receiver
+        .set_mailbox_ism_override(&mailbox_accounts, Some(hyperlane_sealevel_test_ism::id()))
+        .await
+        .unwrap();
+
+    let message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: REMOTE_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: LOCAL_DOMAIN,
+        recipient: recipient_id.to_bytes().into(),
+        body: vec![0, 1, 2, 3, 4, 5, 6, 7, 8],
+    };
+
+    let (process_tx_signature, processed_message_account_key) = process(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message,
+    )
+    .await
+    .unwrap();
+
+    assert_processed_message(
+        &mut banks_client,
+        process_tx_signature,
+        processed_message_account_key,
+        &message,
+        0,
+    )
+    .await;
+
+    // Removing the override falls back to the recipient's InterchainSecurityModule instruction.
+    test_send_receiver
+        .set_mailbox_ism_override(&mailbox_accounts, None)
+        .await
+        .unwrap();
+
+    let result =
+        get_recipient_ism(&mut banks_client, &payer, &mailbox_accounts, recipient_id).await;
+    assert!(matches!(
+        result,
+        Err(BanksClientError::TransactionError(
+            TransactionError::InstructionError(_, InstructionError::BorshIoError(_))
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn test_recipient_ism_override_errors_if_not_signed_by_dispatch_authority() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, test_send_receiver, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    // Try to set the override of the recipient, signing with the payer
+    // instead of the recipient's dispatch authority.
+    let mut instruction = set_recipient_ism_instruction(
+        mailbox_accounts.program,
+        payer.pubkey(),
+        test_send_receiver.id(),
+        Some(Pubkey::new_unique()),
+    )
+    .unwrap();
+    instruction.accounts[2] = AccountMeta::new_readonly(payer.pubkey(), true);
+
+    let result = process_instruction(&mut banks_client, instruction, &payer, &[&payer]).await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+}
+
+#[tokio::test]
+async fn test_enable_recipient_isms() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let recipient_id = hyperlane_sealevel_test_send_receiver::id();
+    let message = |nonce| HyperlaneMessage {
+        version: 3,
+        nonce,
+        origin: REMOTE_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: LOCAL_DOMAIN,
+        recipient: recipient_id.to_bytes().into(),
+        body: vec![0, 1, 2, 3, 4, 5, 6, 7, 8],
+    };
+
+    // Messages are processed without the recipient ISM PDA until overrides are enabled.
+    process(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message(0),
+    )
+    .await
+    .unwrap();
+
+    // Only the owner can enable overrides.
+    let non_owner = new_funded_keypair(&mut banks_client, &payer, 1000000000).await;
+    let result = process_instruction(
+        &mut banks_client,
+        enable_recipient_isms_instruction(program_id, non_owner.pubkey()).unwrap(),
+        &non_owner,
+        &[&non_owner],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+
+    process_instruction(
+        &mut banks_client,
+        enable_recipient_isms_instruction(program_id, payer.pubkey()).unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    assert_inbox(
+        &mut banks_client,
+        mailbox_accounts.inbox,
+        Inbox {
+            local_domain: LOCAL_DOMAIN,
+            inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
+            default_ism: mailbox_accounts.default_ism,
+            processed_count: 1,
+            processed_message_retention: None,
+            recipient_isms_enabled: true,
+        },
+    )
+    .await;
+
+    // Processing a message requires the recipient ISM PDA from now on.
+    let account_metas = get_process_account_metas(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message(1),
+    )
+    .await
+    .unwrap();
+    let (recipient_ism_key, _recipient_ism_bump) =
+        recipient_ism_pda(&program_id, &recipient_id).unwrap();
+    assert_eq!(account_metas[5].pubkey, recipient_ism_key);
+
+    let mut legacy_account_metas = account_metas;
+    legacy_account_metas.remove(5);
+    let result = process_with_accounts(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message(1),
+        legacy_account_metas,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument),
+    );
+
+    process(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message(1),
+    )
+    .await
+    .unwrap();
+}
+
 #[tokio::test]
 async fn test_process_successful_verify_and_handle() {
     let program_id = mailbox_id();
@@ -943,10 +1297,11 @@ async fn test_process_successful_verify_and_handle() {
 #[tokio::test]
 async fn test_process_errors_if_message_already_processed() {
     let program_id = mailbox_id();
-    let (mut banks_client, payer, _, _) = setup_client().await;
+    let (mut context, _, _) = setup_context().await;
+    let payer = clone_keypair(&context.payer);
 
     let mailbox_accounts = initialize_mailbox(
-        &mut banks_client,
+        &mut context.banks_client,
         &program_id,
         &payer,
         LOCAL_DOMAIN,
@@ -969,7 +1324,7 @@ async fn test_process_errors_if_message_already_processed() {
     };
 
     process(
-        &mut banks_client,
+        &mut context.banks_client,
         &payer,
         &mailbox_accounts,
         vec![],
@@ -978,12 +1333,12 @@ async fn test_process_errors_if_message_already_processed() {
     .await
     .unwrap();
 
-    // there's a race condition that isn't fixed by setting `CommitmentLevel::Confirmed`
-    // just wait a bit to ensure the message is processed
-    sleep(std::time::Duration::from_secs(1));
+    // Move to a new slot so the second transaction gets a new blockhash, rather
+    // than being deduplicated as a replay of the first one
+    warp_forward_slots(&mut context, 1).await;
 
     let result = process(
-        &mut banks_client,
+        &mut context.banks_client,
         &payer,
         &mailbox_accounts,
         vec![],
@@ -1351,6 +1706,8 @@ async fn test_inbox_set_default_ism() {
             inbox_bump_seed: mailbox_accounts.inbox_bump_seed,
             default_ism: new_default_ism,
             processed_count: 0,
+            processed_message_retention: None,
+            recipient_isms_enabled: false,
         },
     )
     .await;
@@ -1420,3 +1777,209 @@ async fn test_inbox_set_default_ism_errors_if_owner_not_signer() {
         TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
     );
 }
+
+#[tokio::test]
+async fn test_close_processed_message() {
+    let program_id = mailbox_id();
+    let (mut context, _, _) = setup_context().await;
+    let payer = clone_keypair(&context.payer);
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut context.banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: REMOTE_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: LOCAL_DOMAIN,
+        recipient: hyperlane_sealevel_test_send_receiver::id()
+            .to_bytes()
+            .into(),
+        body: vec![0, 1, 2, 3, 4, 5, 6, 7, 8],
+    };
+
+    process(
+        &mut context.banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message,
+    )
+    .await
+    .unwrap();
+
+    // Enable closing processed message PDAs
+    let beneficiary = Pubkey::new_unique();
+    let retention_slots = 10;
+    process_instruction(
+        &mut context.banks_client,
+        set_processed_message_retention_instruction(
+            program_id,
+            payer.pubkey(),
+            ProcessedMessageRetention {
+                retention_slots,
+                beneficiary,
+            },
+        )
+        .unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    // The processed message PDA can't be closed within the retention period
+    let close_instruction =
+        close_processed_message_instruction(program_id, payer.pubkey(), beneficiary, &message)
+            .unwrap();
+    let result = process_instruction(
+        &mut context.banks_client,
+        close_instruction.clone(),
+        &payer,
+        &[&payer],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MailboxError::ProcessedMessageRetained as u32),
+        ),
+    );
+
+    warp_forward_slots(&mut context, retention_slots).await;
+
+    let (processed_message_account_key, _processed_message_account_bump) =
+        Pubkey::find_program_address(
+            mailbox_processed_message_pda_seeds!(message.id()),
+            &program_id,
+        );
+    let processed_message_rent = context
+        .banks_client
+        .get_account(processed_message_account_key)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+
+    process_instruction(
+        &mut context.banks_client,
+        close_instruction,
+        &payer,
+        &[&payer],
+    )
+    .await
+    .unwrap();
+
+    // The rent is refunded to the beneficiary
+    assert_message_not_processed(&mut context.banks_client, &mailbox_accounts, message.id()).await;
+    let beneficiary_account = context
+        .banks_client
+        .get_account(beneficiary)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(beneficiary_account.lamports, processed_message_rent);
+
+    // The message still can't be processed again
+    warp_forward_slots(&mut context, 1).await;
+    let result = process(
+        &mut context.banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message,
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MailboxError::MessageAlreadyProcessed as u32),
+        ),
+    );
+
+    // While other messages can
+    let other_message = HyperlaneMessage {
+        nonce: 1,
+        ..message
+    };
+    process(
+        &mut context.banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &other_message,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_close_processed_message_errors_if_retention_not_set() {
+    let program_id = mailbox_id();
+    let (mut banks_client, payer, _, _) = setup_client().await;
+
+    let mailbox_accounts = initialize_mailbox(
+        &mut banks_client,
+        &program_id,
+        &payer,
+        LOCAL_DOMAIN,
+        MAX_PROTOCOL_FEE,
+        test_protocol_fee_config(),
+    )
+    .await
+    .unwrap();
+
+    let message = HyperlaneMessage {
+        version: 3,
+        nonce: 0,
+        origin: REMOTE_DOMAIN,
+        sender: payer.pubkey().to_bytes().into(),
+        destination: LOCAL_DOMAIN,
+        recipient: hyperlane_sealevel_test_send_receiver::id()
+            .to_bytes()
+            .into(),
+        body: vec![0, 1, 2, 3, 4, 5, 6, 7, 8],
+    };
+
+    process(
+        &mut banks_client,
+        &payer,
+        &mailbox_accounts,
+        vec![],
+        &message,
+    )
+    .await
+    .unwrap();
+
+    let result = process_instruction(
+        &mut banks_client,
+        close_processed_message_instruction(
+            program_id,
+            payer.pubkey(),
+            Pubkey::new_unique(),
+            &message,
+        )
+        .unwrap(),
+        &payer,
+        &[&payer],
+    )
+    .await;
+    assert_transaction_error(
+        result,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(MailboxError::ProcessedMessageClosingDisabled as u32),
+        ),
+    );
+}
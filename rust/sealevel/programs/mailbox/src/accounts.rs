@@ -17,7 +17,7 @@ use crate::{mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds, protocol_fee::Pro
 pub type InboxAccount = AccountData<Inbox>;
 
 /// The Inbox account data, which is used when processing messages.
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct Inbox {
     /// The local domain.
     pub local_domain: u32,
@@ -27,6 +27,14 @@ pub struct Inbox {
     pub default_ism: Pubkey,
     /// The number of messages processed. Used for easy indexing of processed messages.
     pub processed_count: u64,
+    /// The retention of processed message PDAs, if they can be closed.
+    /// Once set, processing a message requires the `ProcessedNonces` account
+    /// of the message, so this can't be unset again.
+    pub processed_message_retention: Option<ProcessedMessageRetention>,
+    /// Whether recipients can override the default ISM. Once set, processing
+    /// a message requires the `RecipientIsm` PDA of the recipient, so this
+    /// can't be unset again.
+    pub recipient_isms_enabled: bool,
 }
 
 impl SizedData for Inbox {
@@ -35,10 +43,72 @@ impl SizedData for Inbox {
         // 1 byte inbox_bump_seed
         // 32 byte default_ism
         // 8 byte processed_count
-        4 + 1 + 32 + 8
+        // 0, 1 or 41 byte processed_message_retention (1 byte enum variant, 8 byte retention_slots, 32 byte beneficiary)
+        // 0 or 1 byte recipient_isms_enabled
+        let processed_message_retention_size = if self.processed_message_retention.is_some() {
+            1 + 8 + 32
+        } else if self.recipient_isms_enabled {
+            1
+        } else {
+            0
+        };
+        let recipient_isms_enabled_size = usize::from(self.recipient_isms_enabled);
+        4 + 1 + 32 + 8 + processed_message_retention_size + recipient_isms_enabled_size
+    }
+}
+
+/// Inbox accounts created before processed message PDAs could be closed end
+/// after `processed_count`, so `processed_message_retention` and
+/// `recipient_isms_enabled` are only serialized if set.
+impl BorshSerialize for Inbox {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.local_domain.serialize(writer)?;
+        self.inbox_bump_seed.serialize(writer)?;
+        self.default_ism.serialize(writer)?;
+        self.processed_count.serialize(writer)?;
+        if self.processed_message_retention.is_some() || self.recipient_isms_enabled {
+            self.processed_message_retention.serialize(writer)?;
+        }
+        if self.recipient_isms_enabled {
+            self.recipient_isms_enabled.serialize(writer)?;
+        }
+        Ok(())
     }
 }
 
+impl BorshDeserialize for Inbox {
+    fn deserialize(reader: &mut &[u8]) -> std::io::Result<Self> {
+        let local_domain = u32::deserialize(reader)?;
+        let inbox_bump_seed = u8::deserialize(reader)?;
+        let default_ism = Pubkey::deserialize(reader)?;
+        let processed_count = u64::deserialize(reader)?;
+        let processed_message_retention = if reader.is_empty() {
+            None
+        } else {
+            Option::<ProcessedMessageRetention>::deserialize(reader)?
+        };
+        let recipient_isms_enabled = !reader.is_empty() && bool::deserialize(reader)?;
+        Ok(Self {
+            local_domain,
+            inbox_bump_seed,
+            default_ism,
+            processed_count,
+            processed_message_retention,
+            recipient_isms_enabled,
+        })
+    }
+}
+
+/// Configures the closing of processed message PDAs to refund their rent.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedMessageRetention {
+    /// The number of slots after a message was processed until its processed
+    /// message PDA can be closed.
+    pub retention_slots: u64,
+    /// The account that the rent of closed processed message PDAs is refunded to.
+    pub beneficiary: Pubkey,
+}
+
 impl Inbox {
     /// Verifies that the given account is the canonical Inbox PDA and returns the deserialized inner data.
     pub fn verify_account_and_fetch_inner(
@@ -321,6 +391,101 @@ impl BorshDeserialize for ProcessedMessage {
     }
 }
 
+/// A page of the processed nonces account.
+pub type ProcessedNoncesAccount = AccountData<ProcessedNonces>;
+
+/// The number of nonces in a page of processed nonces.
+pub const PROCESSED_NONCES_PAGE_SIZE: u32 = 1024;
+
+/// A bitmap of the nonces of messages from an origin to a recipient whose
+/// processed message PDAs have been closed, which protects these messages
+/// against being processed again. Each account holds a page of
+/// `PROCESSED_NONCES_PAGE_SIZE` nonces.
+///
+/// Nonces are tracked per recipient, so that messages forged for a recipient
+/// with a permissive ISM can't block the delivery of messages to other recipients.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct ProcessedNonces {
+    /// The bump seed of the processed nonces PDA.
+    pub bump_seed: u8,
+    /// The bitmap, in which bit `nonce % PROCESSED_NONCES_PAGE_SIZE` is set if
+    /// the message with `nonce` has been processed.
+    pub bitmap: Vec<u8>,
+}
+
+impl Default for ProcessedNonces {
+    fn default() -> Self {
+        Self {
+            bump_seed: 0,
+            bitmap: vec![0; (PROCESSED_NONCES_PAGE_SIZE / 8) as usize],
+        }
+    }
+}
+
+impl SizedData for ProcessedNonces {
+    fn size(&self) -> usize {
+        // 1 byte bump_seed
+        // 4 byte bitmap length
+        // PROCESSED_NONCES_PAGE_SIZE / 8 byte bitmap
+        1 + 4 + (PROCESSED_NONCES_PAGE_SIZE / 8) as usize
+    }
+}
+
+impl ProcessedNonces {
+    /// Creates an empty page of processed nonces.
+    pub fn new(bump_seed: u8) -> Self {
+        Self {
+            bump_seed,
+            ..Self::default()
+        }
+    }
+
+    /// The page that `nonce` is tracked in.
+    pub fn page(nonce: u32) -> u32 {
+        nonce / PROCESSED_NONCES_PAGE_SIZE
+    }
+
+    fn bit(nonce: u32) -> (usize, u8) {
+        let index = nonce % PROCESSED_NONCES_PAGE_SIZE;
+        ((index / 8) as usize, 1 << (index % 8))
+    }
+
+    /// Whether `nonce` is marked as processed.
+    pub fn contains(&self, nonce: u32) -> bool {
+        let (byte, mask) = Self::bit(nonce);
+        self.bitmap.get(byte).map_or(false, |b| b & mask != 0)
+    }
+
+    /// Marks `nonce` as processed.
+    pub fn insert(&mut self, nonce: u32) {
+        let (byte, mask) = Self::bit(nonce);
+        self.bitmap[byte] |= mask;
+    }
+}
+
+/// A recipient's ISM override account.
+pub type RecipientIsmAccount = AccountData<RecipientIsm>;
+
+/// The ISM override a recipient program registered with the Mailbox, which
+/// takes precedence over the ISM returned by the recipient's
+/// `InterchainSecurityModule` instruction. This lets recipients that don't
+/// implement that instruction use an ISM other than the default ISM.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, PartialEq, Eq)]
+pub struct RecipientIsm {
+    /// The bump seed of the recipient ISM PDA.
+    pub bump_seed: u8,
+    /// The ISM override. If None, the recipient's ISM is looked up as usual.
+    pub ism: Option<Pubkey>,
+}
+
+impl SizedData for RecipientIsm {
+    fn size(&self) -> usize {
+        // 1 byte bump_seed
+        // 33 byte ism (1 byte enum variant, 32 byte pubkey), allocated even if None
+        1 + 1 + 32
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -357,15 +522,71 @@ mod test {
             inbox_bump_seed: 69,
             default_ism: Pubkey::new_unique(),
             processed_count: 69696969,
+            processed_message_retention: None,
+            recipient_isms_enabled: false,
+        };
+        let retention = ProcessedMessageRetention {
+            retention_slots: 420420,
+            beneficiary: Pubkey::new_unique(),
+        };
+
+        for (processed_message_retention, recipient_isms_enabled) in [
+            (None, false),
+            (Some(retention.clone()), false),
+            (None, true),
+            (Some(retention), true),
+        ] {
+            let inbox = Inbox {
+                processed_message_retention,
+                recipient_isms_enabled,
+                ..inbox
+            };
+
+            let mut serialized = vec![];
+            inbox.serialize(&mut serialized).unwrap();
+
+            let deserialized = Inbox::deserialize(&mut serialized.as_slice()).unwrap();
+
+            assert_eq!(inbox, deserialized);
+            assert_eq!(serialized.len(), inbox.size());
+        }
+    }
+
+    #[test]
+    fn test_processed_nonces() {
+        let mut processed_nonces = ProcessedNonces::new(69);
+        let nonce = 3 * PROCESSED_NONCES_PAGE_SIZE + 42;
+        assert_eq!(ProcessedNonces::page(nonce), 3);
+        assert!(!processed_nonces.contains(nonce));
+
+        processed_nonces.insert(nonce);
+        assert!(processed_nonces.contains(nonce));
+        assert!(!processed_nonces.contains(nonce + 1));
+        assert!(!processed_nonces.contains(nonce - 1));
+
+        let mut serialized = vec![];
+        processed_nonces.serialize(&mut serialized).unwrap();
+
+        let deserialized = ProcessedNonces::deserialize(&mut serialized.as_slice()).unwrap();
+
+        assert_eq!(processed_nonces, deserialized);
+        assert_eq!(serialized.len(), processed_nonces.size());
+    }
+
+    #[test]
+    fn test_recipient_ism_ser_deser() {
+        let recipient_ism = RecipientIsm {
+            bump_seed: 69,
+            ism: Some(Pubkey::new_unique()),
         };
 
         let mut serialized = vec![];
-        inbox.serialize(&mut serialized).unwrap();
+        recipient_ism.serialize(&mut serialized).unwrap();
 
-        let deserialized = Inbox::deserialize(&mut serialized.as_slice()).unwrap();
+        let deserialized = RecipientIsm::deserialize(&mut serialized.as_slice()).unwrap();
 
-        assert_eq!(inbox, deserialized);
-        assert_eq!(serialized.len(), inbox.size());
+        assert_eq!(recipient_ism, deserialized);
+        assert_eq!(serialized.len(), recipient_ism.size());
     }
 
     #[test]
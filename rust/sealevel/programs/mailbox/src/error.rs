@@ -27,6 +27,15 @@ pub enum Error {
     /// The message is too large.
     #[error("Message is larger than the maximum allowed")]
     MaxMessageSizeExceeded = 7,
+    /// Processed message PDAs can't be closed.
+    #[error("Closing processed message PDAs is not enabled")]
+    ProcessedMessageClosingDisabled = 8,
+    /// The processed message PDA is still within the retention period.
+    #[error("Processed message is still within the retention period")]
+    ProcessedMessageRetained = 9,
+    /// Recipients can't override the default ISM.
+    #[error("Recipient ISM overrides are not enabled")]
+    RecipientIsmsDisabled = 10,
 }
 
 impl From<Error> for ProgramError {
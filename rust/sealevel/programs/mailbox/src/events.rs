@@ -0,0 +1,64 @@
+//! Events emitted by the Mailbox.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use hyperlane_core::H256;
+use solana_program::{clock::Slot, log::sol_log_data, program_error::ProgramError};
+
+/// Prefix of the data of a `ClosedProcessedMessage` event, so that it can be
+/// told apart from data logged by other programs.
+pub const CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR: &[u8; 8] = b"CLOSEDPM";
+
+/// An event emitted when the processed message PDA of a message is closed.
+/// Indexers of processed messages can no longer read the PDA, so this carries
+/// what it held.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ClosedProcessedMessage {
+    /// The sequence of the processed message.
+    pub sequence: u64,
+    /// The message ID of the processed message.
+    pub message_id: H256,
+    /// The slot in which the message was processed.
+    pub slot: Slot,
+}
+
+impl ClosedProcessedMessage {
+    /// Logs the event as program data, prefixed by
+    /// `CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR`.
+    pub fn emit(&self) -> Result<(), ProgramError> {
+        let mut data = CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR.to_vec();
+        self.serialize(&mut data)
+            .map_err(|err| ProgramError::BorshIoError(err.to_string()))?;
+        sol_log_data(&[&data]);
+        Ok(())
+    }
+
+    /// Decodes an event from logged program data, returning `None` if the
+    /// data isn't a `ClosedProcessedMessage` event.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let data = data.strip_prefix(CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR)?;
+        Self::try_from_slice(data).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_closed_processed_message_decode() {
+        let event = ClosedProcessedMessage {
+            sequence: 69,
+            message_id: H256::random(),
+            slot: 420,
+        };
+        let mut data = CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        assert_eq!(ClosedProcessedMessage::decode(&data), Some(event));
+
+        assert_eq!(ClosedProcessedMessage::decode(&data[1..]), None);
+        assert_eq!(
+            ClosedProcessedMessage::decode(CLOSED_PROCESSED_MESSAGE_DISCRIMINATOR),
+            None
+        );
+    }
+}
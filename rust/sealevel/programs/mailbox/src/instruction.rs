@@ -1,14 +1,21 @@
 //! Instructions for the Hyperlane Sealevel Mailbox program.
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use hyperlane_core::H256;
+use hyperlane_core::{Encode, HyperlaneMessage, H256};
 use solana_program::{
     instruction::{AccountMeta, Instruction as SolanaInstruction},
     program_error::ProgramError,
     pubkey::Pubkey,
 };
 
-use crate::{mailbox_inbox_pda_seeds, mailbox_outbox_pda_seeds, protocol_fee::ProtocolFee};
+use crate::{
+    accounts::{ProcessedMessageRetention, ProcessedNonces},
+    mailbox_closed_processed_messages_pda_seeds, mailbox_inbox_pda_seeds,
+    mailbox_message_dispatch_authority_pda_seeds, mailbox_outbox_pda_seeds,
+    mailbox_processed_message_pda_seeds, mailbox_processed_nonces_pda_seeds,
+    mailbox_recipient_ism_pda_seeds,
+    protocol_fee::ProtocolFee,
+};
 
 /// The current message version.
 pub const VERSION: u8 = 3;
@@ -40,6 +47,14 @@ pub enum Instruction {
     ClaimProtocolFees,
     /// Sets the protocol fee configuration.
     SetProtocolFeeConfig(ProtocolFee),
+    /// Sets the retention of processed message PDAs, enabling them to be closed.
+    InboxSetProcessedMessageRetention(ProcessedMessageRetention),
+    /// Closes the processed message PDA of a message after the retention period.
+    InboxCloseProcessedMessage(InboxCloseProcessedMessage),
+    /// Sets the ISM override of a recipient program.
+    InboxSetRecipientIsm(InboxSetRecipientIsm),
+    /// Enables recipients to override the default ISM.
+    InboxEnableRecipientIsms,
 }
 
 impl Instruction {
@@ -94,6 +109,22 @@ pub struct InboxProcess {
     pub message: Vec<u8>,
 }
 
+/// Instruction data for the InboxCloseProcessedMessage instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct InboxCloseProcessedMessage {
+    /// The encoded message whose processed message PDA is closed.
+    pub message: Vec<u8>,
+}
+
+/// Instruction data for the InboxSetRecipientIsm instruction.
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct InboxSetRecipientIsm {
+    /// The recipient program, whose dispatch authority must sign.
+    pub recipient: Pubkey,
+    /// The ISM override, or None to remove the override.
+    pub ism: Option<Pubkey>,
+}
+
 /// Creates an Init instruction.
 pub fn init_instruction(
     program_id: Pubkey,
@@ -179,3 +210,180 @@ pub fn set_default_ism_instruction(
     };
     Ok(instruction)
 }
+
+/// Creates an InboxSetProcessedMessageRetention instruction.
+pub fn set_processed_message_retention_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+    processed_message_retention: ProcessedMessageRetention,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (inbox_account, _inbox_bump) =
+        Pubkey::try_find_program_address(mailbox_inbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[executable]` - The system program.
+    // 1. `[writeable]` - The Inbox PDA account.
+    // 2. `[]` - The Outbox PDA account.
+    // 3. `[signer, writeable]` - The owner of the Mailbox.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::InboxSetProcessedMessageRetention(processed_message_retention)
+            .into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(inbox_account, false),
+            AccountMeta::new_readonly(outbox_account, false),
+            AccountMeta::new(owner_payer, true),
+        ],
+    };
+    Ok(instruction)
+}
+
+/// Gets the processed nonces PDA tracking `message`.
+pub fn processed_nonces_pda(
+    program_id: &Pubkey,
+    message: &HyperlaneMessage,
+) -> Result<(Pubkey, u8), ProgramError> {
+    let recipient = Pubkey::new_from_array(message.recipient.0);
+    Pubkey::try_find_program_address(
+        mailbox_processed_nonces_pda_seeds!(
+            recipient,
+            message.origin,
+            ProcessedNonces::page(message.nonce)
+        ),
+        program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Creates an InboxCloseProcessedMessage instruction.
+pub fn close_processed_message_instruction(
+    program_id: Pubkey,
+    payer: Pubkey,
+    beneficiary: Pubkey,
+    message: &HyperlaneMessage,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (inbox_account, _inbox_bump) =
+        Pubkey::try_find_program_address(mailbox_inbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (processed_message_account, _processed_message_bump) = Pubkey::try_find_program_address(
+        mailbox_processed_message_pda_seeds!(message.id()),
+        &program_id,
+    )
+    .ok_or(ProgramError::InvalidSeeds)?;
+    let (processed_nonces_account, _processed_nonces_bump) =
+        processed_nonces_pda(&program_id, message)?;
+
+    // 0. `[executable]` - The system program.
+    // 1. `[signer, writeable]` - The payer of the processed nonces PDA.
+    // 2. `[]` - The Inbox PDA account.
+    // 3. `[writeable]` - The processed message PDA.
+    // 4. `[writeable]` - The processed nonces PDA.
+    // 5. `[writeable]` - The beneficiary of the processed message retention.
+    // 6. `[]` - The closed processed messages PDA, which is never created.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::InboxCloseProcessedMessage(InboxCloseProcessedMessage {
+            message: message.to_vec(),
+        })
+        .into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(inbox_account, false),
+            AccountMeta::new(processed_message_account, false),
+            AccountMeta::new(processed_nonces_account, false),
+            AccountMeta::new(beneficiary, false),
+            AccountMeta::new_readonly(closed_processed_messages_pda(&program_id)?.0, false),
+        ],
+    };
+    Ok(instruction)
+}
+
+/// Gets the PDA referenced by every transaction closing a processed message
+/// PDA. No account is ever created at it, so its signatures are exactly the
+/// transactions that closed processed message PDAs.
+pub fn closed_processed_messages_pda(program_id: &Pubkey) -> Result<(Pubkey, u8), ProgramError> {
+    Pubkey::try_find_program_address(mailbox_closed_processed_messages_pda_seeds!(), program_id)
+        .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Gets the PDA holding the ISM override of `recipient`.
+pub fn recipient_ism_pda(
+    program_id: &Pubkey,
+    recipient: &Pubkey,
+) -> Result<(Pubkey, u8), ProgramError> {
+    Pubkey::try_find_program_address(mailbox_recipient_ism_pda_seeds!(recipient), program_id)
+        .ok_or(ProgramError::InvalidSeeds)
+}
+
+/// Creates an InboxSetRecipientIsm instruction.
+/// The instruction must be signed by the dispatch authority of `recipient`,
+/// so it is intended to be invoked by the recipient program via CPI.
+pub fn set_recipient_ism_instruction(
+    program_id: Pubkey,
+    payer: Pubkey,
+    recipient: Pubkey,
+    ism: Option<Pubkey>,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (dispatch_authority, _dispatch_authority_bump) = Pubkey::try_find_program_address(
+        mailbox_message_dispatch_authority_pda_seeds!(),
+        &recipient,
+    )
+    .ok_or(ProgramError::InvalidSeeds)?;
+    let (recipient_ism_account, _recipient_ism_bump) = recipient_ism_pda(&program_id, &recipient)?;
+    let (inbox_account, _inbox_bump) =
+        Pubkey::try_find_program_address(mailbox_inbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[executable]` - The system program.
+    // 1. `[signer, writeable]` - The payer of the recipient ISM PDA.
+    // 2. `[signer]` - The dispatch authority of the recipient program.
+    // 3. `[writeable]` - The recipient ISM PDA.
+    // 4. `[]` - The Inbox PDA account.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::InboxSetRecipientIsm(InboxSetRecipientIsm { recipient, ism })
+            .into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(dispatch_authority, true),
+            AccountMeta::new(recipient_ism_account, false),
+            AccountMeta::new_readonly(inbox_account, false),
+        ],
+    };
+    Ok(instruction)
+}
+
+/// Creates an InboxEnableRecipientIsms instruction.
+pub fn enable_recipient_isms_instruction(
+    program_id: Pubkey,
+    owner_payer: Pubkey,
+) -> Result<SolanaInstruction, ProgramError> {
+    let (inbox_account, _inbox_bump) =
+        Pubkey::try_find_program_address(mailbox_inbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+    let (outbox_account, _outbox_bump) =
+        Pubkey::try_find_program_address(mailbox_outbox_pda_seeds!(), &program_id)
+            .ok_or(ProgramError::InvalidSeeds)?;
+
+    // 0. `[executable]` - The system program.
+    // 1. `[writeable]` - The Inbox PDA account.
+    // 2. `[]` - The Outbox PDA account.
+    // 3. `[signer, writeable]` - The owner of the Mailbox.
+    let instruction = SolanaInstruction {
+        program_id,
+        data: Instruction::InboxEnableRecipientIsms.into_instruction_data()?,
+        accounts: vec![
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(inbox_account, false),
+            AccountMeta::new_readonly(outbox_account, false),
+            AccountMeta::new(owner_payer, true),
+        ],
+    };
+    Ok(instruction)
+}
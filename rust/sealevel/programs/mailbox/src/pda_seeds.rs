@@ -116,3 +116,72 @@ macro_rules! mailbox_processed_message_pda_seeds {
         ]
     }};
 }
+
+/// The PDA seeds of the address that transactions closing processed message
+/// PDAs reference, so that they can be looked up by it. No account is
+/// ever created at this address.
+#[macro_export]
+macro_rules! mailbox_closed_processed_messages_pda_seeds {
+    () => {{
+        &[b"hyperlane", b"-", b"closed_processed_messages"]
+    }};
+}
+
+/// The PDA seeds relating to a page of the processed nonces of messages
+/// from an origin to a recipient.
+#[macro_export]
+macro_rules! mailbox_processed_nonces_pda_seeds {
+    ($recipient_pubkey:expr, $origin:expr, $page:expr) => {{
+        &[
+            b"hyperlane",
+            b"-",
+            b"processed_nonces",
+            b"-",
+            $recipient_pubkey.as_ref(),
+            b"-",
+            &$origin.to_le_bytes(),
+            b"-",
+            &$page.to_le_bytes(),
+        ]
+    }};
+
+    ($recipient_pubkey:expr, $origin:expr, $page:expr, $bump_seed:expr) => {{
+        &[
+            b"hyperlane",
+            b"-",
+            b"processed_nonces",
+            b"-",
+            $recipient_pubkey.as_ref(),
+            b"-",
+            &$origin.to_le_bytes(),
+            b"-",
+            &$page.to_le_bytes(),
+            &[$bump_seed],
+        ]
+    }};
+}
+
+/// The PDA seeds relating to the ISM override registered by a recipient.
+#[macro_export]
+macro_rules! mailbox_recipient_ism_pda_seeds {
+    ($recipient_pubkey:expr) => {{
+        &[
+            b"hyperlane",
+            b"-",
+            b"recipient_ism",
+            b"-",
+            $recipient_pubkey.as_ref(),
+        ]
+    }};
+
+    ($recipient_pubkey:expr, $bump_seed:expr) => {{
+        &[
+            b"hyperlane",
+            b"-",
+            b"recipient_ism",
+            b"-",
+            $recipient_pubkey.as_ref(),
+            &[$bump_seed],
+        ]
+    }};
+}
@@ -33,13 +33,20 @@ use serializable_account_meta::SimulationReturnData;
 use crate::{
     accounts::{
         DispatchedMessage, DispatchedMessageAccount, Inbox, InboxAccount, Outbox, OutboxAccount,
-        ProcessedMessage, ProcessedMessageAccount,
+        ProcessedMessage, ProcessedMessageAccount, ProcessedMessageRetention, ProcessedNonces,
+        ProcessedNoncesAccount, RecipientIsm, RecipientIsmAccount,
     },
     error::Error,
-    instruction::{InboxProcess, Init, Instruction as MailboxIxn, OutboxDispatch, VERSION},
-    mailbox_dispatched_message_pda_seeds, mailbox_inbox_pda_seeds,
-    mailbox_message_dispatch_authority_pda_seeds, mailbox_outbox_pda_seeds,
-    mailbox_process_authority_pda_seeds, mailbox_processed_message_pda_seeds,
+    events::ClosedProcessedMessage,
+    instruction::{
+        processed_nonces_pda, recipient_ism_pda, InboxCloseProcessedMessage, InboxProcess,
+        InboxSetRecipientIsm, Init, Instruction as MailboxIxn, OutboxDispatch, VERSION,
+    },
+    mailbox_closed_processed_messages_pda_seeds, mailbox_dispatched_message_pda_seeds,
+    mailbox_inbox_pda_seeds, mailbox_message_dispatch_authority_pda_seeds,
+    mailbox_outbox_pda_seeds, mailbox_process_authority_pda_seeds,
+    mailbox_processed_message_pda_seeds, mailbox_processed_nonces_pda_seeds,
+    mailbox_recipient_ism_pda_seeds,
     protocol_fee::ProtocolFee,
 };
 
@@ -71,6 +78,16 @@ pub fn process_instruction(
         MailboxIxn::SetProtocolFeeConfig(new_protocol_fee_config) => {
             set_protocol_fee_config(program_id, accounts, new_protocol_fee_config)
         }
+        MailboxIxn::InboxSetProcessedMessageRetention(processed_message_retention) => {
+            inbox_set_processed_message_retention(program_id, accounts, processed_message_retention)
+        }
+        MailboxIxn::InboxCloseProcessedMessage(close) => {
+            inbox_close_processed_message(program_id, accounts, close)
+        }
+        MailboxIxn::InboxSetRecipientIsm(set_recipient_ism) => {
+            inbox_set_recipient_ism(program_id, accounts, set_recipient_ism)
+        }
+        MailboxIxn::InboxEnableRecipientIsms => inbox_enable_recipient_isms(program_id, accounts),
     }
     .map_err(|err| {
         msg!("{}", err);
@@ -116,6 +133,8 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
         inbox_bump_seed: inbox_bump,
         default_ism: init.default_ism,
         processed_count: 0,
+        processed_message_retention: None,
+        recipient_isms_enabled: false,
     });
     if init.protocol_fee.fee > init.max_protocol_fee {
         msg!("Invalid initialization config: Protocol fee is greater than max protocol fee",);
@@ -177,7 +196,14 @@ fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], init: Init) -> Prog
 // 2.      `[writable]` Inbox PDA account.
 // 3.      `[]` Mailbox process authority specific to the message recipient.
 // 4.      `[writable]` Processed message PDA.
-// 5..N    [??] Accounts required to invoke the recipient's InterchainSecurityModule instruction.
+// 5.      `[]` Processed nonces PDA of the message. Only if processed message PDAs can be
+//         closed, i.e. `Inbox::processed_message_retention` is set, otherwise the
+//         following accounts start at 5.
+// 6.      `[]` Recipient ISM PDA, holding the ISM override of the recipient if it registered one.
+//         Only if recipient ISM overrides are enabled, i.e. `Inbox::recipient_isms_enabled`
+//         is set, otherwise the following accounts start one account earlier.
+// 7..N    [??] Accounts required to invoke the recipient's InterchainSecurityModule instruction.
+//         Not used if the recipient registered an ISM override.
 // N+1.    `[executable]` SPL noop
 // N+2.    `[executable]` ISM
 // N+2..M. [??] Accounts required to invoke the ISM's Verify instruction.
@@ -253,9 +279,23 @@ fn inbox_process(
         return Err(Error::MessageAlreadyProcessed.into());
     }
 
+    // Account 5: Processed nonces PDA, which marks the message as processed
+    // if its processed message PDA has been closed.
+    if inbox.processed_message_retention.is_some() {
+        let processed_nonces_info = next_account_info(accounts_iter)?;
+        verify_nonce_not_processed(program_id, processed_nonces_info, &message)?;
+    }
+
+    // Account 6: Recipient ISM PDA.
+    let recipient_ism_info = if inbox.recipient_isms_enabled {
+        Some(next_account_info(accounts_iter)?)
+    } else {
+        None
+    };
+
     let spl_noop_id = spl_noop::id();
 
-    // Accounts 5..N: the accounts required for getting the ISM the recipient wants to use.
+    // Accounts 7..N: the accounts required for getting the ISM the recipient wants to use.
     let mut get_ism_infos = vec![];
     let mut get_ism_account_metas = vec![];
     loop {
@@ -280,7 +320,9 @@ fn inbox_process(
 
     // Call into the recipient program to get the ISM to use.
     let ism = get_recipient_ism(
+        program_id,
         &recipient_program_id,
+        recipient_ism_info,
         get_ism_infos,
         get_ism_account_metas,
         inbox.default_ism,
@@ -419,14 +461,46 @@ fn inbox_process(
     Ok(())
 }
 
+/// Errors if `message` is marked as processed in the processed nonces PDA
+/// tracking it.
+fn verify_nonce_not_processed(
+    program_id: &Pubkey,
+    processed_nonces_info: &AccountInfo,
+    message: &HyperlaneMessage,
+) -> ProgramResult {
+    let (expected_processed_nonces_key, _expected_processed_nonces_bump) =
+        processed_nonces_pda(program_id, message)?;
+    if processed_nonces_info.key != &expected_processed_nonces_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    // The PDA is only created once a processed message PDA of its page is closed.
+    if processed_nonces_info.data_is_empty() {
+        return Ok(());
+    }
+    if processed_nonces_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let processed_nonces =
+        ProcessedNoncesAccount::fetch(&mut &processed_nonces_info.data.borrow()[..])?.into_inner();
+    if processed_nonces.contains(message.nonce) {
+        return Err(Error::MessageAlreadyProcessed.into());
+    }
+
+    Ok(())
+}
+
 /// Gets the ISM to use for a recipient program and sets it as return data.
 ///
 /// Accounts:
 /// 0.    `[]` - The Inbox PDA.
 /// 1.    `[]` - The recipient program.
-/// 2..N. [??] - The accounts required to make the CPI into the recipient program.
+/// 2.    `[]` - The recipient ISM PDA. Only if recipient ISM overrides are enabled,
+///             otherwise the following accounts start at 2.
+/// 3..N. [??] - The accounts required to make the CPI into the recipient program.
 ///             These can be retrieved from the recipient using the
 ///             `MessageRecipientInstruction::InterchainSecurityModuleAccountMetas` instruction.
+///             Not used if the recipient registered an ISM override.
 fn inbox_get_recipient_ism(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -444,7 +518,14 @@ fn inbox_get_recipient_ism(
         return Err(ProgramError::InvalidArgument);
     }
 
-    // Account 2..N: The accounts required to make the CPI into the recipient program.
+    // Account 2: The recipient ISM PDA.
+    let recipient_ism_info = if inbox.recipient_isms_enabled {
+        Some(next_account_info(accounts_iter)?)
+    } else {
+        None
+    };
+
+    // Account 3..N: The accounts required to make the CPI into the recipient program.
     let mut account_infos = vec![];
     let mut account_metas = vec![];
     for account_info in accounts_iter {
@@ -456,7 +537,14 @@ fn inbox_get_recipient_ism(
         });
     }
 
-    let ism = get_recipient_ism(&recipient, account_infos, account_metas, inbox.default_ism)?;
+    let ism = get_recipient_ism(
+        program_id,
+        &recipient,
+        recipient_ism_info,
+        account_infos,
+        account_metas,
+        inbox.default_ism,
+    )?;
 
     // Return the borsh serialized ISM pubkey.
     set_return_data(
@@ -469,15 +557,28 @@ fn inbox_get_recipient_ism(
 }
 
 /// Get the ISM to use for a recipient program.
+/// The ISM override registered by the recipient takes precedence, and the
+/// recipient program is only invoked if it didn't register one. The recipient
+/// ISM PDA is only given if recipient ISM overrides are enabled.
 ///
 /// Expects `account_infos` and `account_metas` to be those required
 /// by the recipient program's InterchainSecurityModule instruction.
 fn get_recipient_ism(
+    program_id: &Pubkey,
     recipient_program_id: &Pubkey,
+    recipient_ism_info: Option<&AccountInfo>,
     account_infos: Vec<AccountInfo>,
     account_metas: Vec<AccountMeta>,
     default_ism: Pubkey,
 ) -> Result<Pubkey, ProgramError> {
+    if let Some(recipient_ism_info) = recipient_ism_info {
+        if let Some(ism) =
+            fetch_recipient_ism_override(program_id, recipient_program_id, recipient_ism_info)?
+        {
+            return Ok(ism);
+        }
+    }
+
     let get_ism_instruction = Instruction::new_with_bytes(
         *recipient_program_id,
         &MessageRecipientInstruction::InterchainSecurityModule.encode()?,
@@ -510,6 +611,125 @@ fn get_recipient_ism(
     Ok(ism)
 }
 
+/// Gets the ISM override registered by a recipient program, if any.
+fn fetch_recipient_ism_override(
+    program_id: &Pubkey,
+    recipient_program_id: &Pubkey,
+    recipient_ism_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let (expected_recipient_ism_key, _expected_recipient_ism_bump) =
+        recipient_ism_pda(program_id, recipient_program_id)?;
+    if recipient_ism_info.key != &expected_recipient_ism_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    // The PDA is only created once the recipient registers an ISM override.
+    if recipient_ism_info.data_is_empty() {
+        return Ok(None);
+    }
+    if recipient_ism_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let recipient_ism =
+        RecipientIsmAccount::fetch(&mut &recipient_ism_info.data.borrow()[..])?.into_inner();
+    Ok(recipient_ism.ism)
+}
+
+/// Sets the ISM override of a recipient program, which is used instead of the
+/// ISM returned by the recipient's `InterchainSecurityModule` instruction.
+/// Must be signed by the recipient's dispatch authority, i.e. invoked by the
+/// recipient program.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[signer, writeable]` The payer, which pays for the recipient ISM PDA if it doesn't exist yet.
+/// 2. `[signer]` The dispatch authority of the recipient program.
+/// 3. `[writeable]` The recipient ISM PDA.
+/// 4. `[]` The Inbox PDA account, which must have recipient ISM overrides enabled.
+fn inbox_set_recipient_ism(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    set_recipient_ism: InboxSetRecipientIsm,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let recipient_program_id = set_recipient_ism.recipient;
+
+    // Account 0: The system program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Payer account.
+    let payer_info = next_account_info(accounts_iter)?;
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 2: The dispatch authority of the recipient program.
+    let dispatch_authority_info = next_account_info(accounts_iter)?;
+    if !dispatch_authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (expected_dispatch_authority_key, _expected_dispatch_authority_bump) =
+        Pubkey::find_program_address(
+            mailbox_message_dispatch_authority_pda_seeds!(),
+            &recipient_program_id,
+        );
+    if dispatch_authority_info.key != &expected_dispatch_authority_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 3: The recipient ISM PDA.
+    let recipient_ism_info = next_account_info(accounts_iter)?;
+    let (expected_recipient_ism_key, expected_recipient_ism_bump) =
+        recipient_ism_pda(program_id, &recipient_program_id)?;
+    if recipient_ism_info.key != &expected_recipient_ism_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 4: Inbox PDA account.
+    let inbox_info = next_account_info(accounts_iter)?;
+    let inbox = Inbox::verify_account_and_fetch_inner(program_id, inbox_info)?;
+    // Relayers only pass the recipient ISM PDA once overrides are enabled, so
+    // an override set before then would be silently ignored.
+    if !inbox.recipient_isms_enabled {
+        return Err(Error::RecipientIsmsDisabled.into());
+    }
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    if recipient_ism_info.data_is_empty() {
+        create_pda_account(
+            payer_info,
+            &Rent::get()?,
+            RecipientIsmAccount::from(RecipientIsm::default()).size(),
+            program_id,
+            system_program_info,
+            recipient_ism_info,
+            mailbox_recipient_ism_pda_seeds!(recipient_program_id, expected_recipient_ism_bump),
+        )?;
+    } else if recipient_ism_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    RecipientIsmAccount::from(RecipientIsm {
+        bump_seed: expected_recipient_ism_bump,
+        ism: set_recipient_ism.ism,
+    })
+    .store(recipient_ism_info, false)?;
+
+    msg!(
+        "Set ISM override of recipient {} to {:?}",
+        recipient_program_id,
+        set_recipient_ism.ism
+    );
+
+    Ok(())
+}
+
 /// Sets the default ISM.
 ///
 /// Accounts:
@@ -932,3 +1152,239 @@ fn set_protocol_fee_config(
 
     Ok(())
 }
+
+/// Sets the retention of processed message PDAs, after which they can be closed
+/// to refund their rent.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[writeable]` The Inbox PDA account.
+/// 2. `[]` The Outbox PDA account.
+/// 3. `[signer, writeable]` The owner of the Mailbox, which pays for the Inbox PDA's realloc.
+fn inbox_set_processed_message_retention(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    processed_message_retention: ProcessedMessageRetention,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The system program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Inbox PDA account.
+    let inbox_info = next_account_info(accounts_iter)?;
+    let mut inbox = Inbox::verify_account_and_fetch_inner(program_id, inbox_info)?;
+
+    // Account 2: Outbox PDA account.
+    let outbox_info = next_account_info(accounts_iter)?;
+    let outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+
+    // Account 3: The owner of the Mailbox.
+    let owner_info = next_account_info(accounts_iter)?;
+    // Errors if the owner account isn't correct or isn't a signer.
+    outbox.ensure_owner_signer(owner_info)?;
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    inbox.processed_message_retention = Some(processed_message_retention);
+    // Inbox PDAs created before the retention existed need to be realloc'd.
+    InboxAccount::from(inbox).store_with_rent_exempt_realloc(
+        inbox_info,
+        &Rent::get()?,
+        owner_info,
+        system_program_info,
+    )?;
+
+    Ok(())
+}
+
+/// Enables recipients to override the default ISM. Processing a message
+/// requires the recipient ISM PDA of its recipient from then on.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[writeable]` The Inbox PDA account.
+/// 2. `[]` The Outbox PDA account.
+/// 3. `[signer, writeable]` The owner of the Mailbox, which pays for the Inbox PDA's realloc.
+fn inbox_enable_recipient_isms(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: The system program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Inbox PDA account.
+    let inbox_info = next_account_info(accounts_iter)?;
+    let mut inbox = Inbox::verify_account_and_fetch_inner(program_id, inbox_info)?;
+
+    // Account 2: Outbox PDA account.
+    let outbox_info = next_account_info(accounts_iter)?;
+    let outbox = Outbox::verify_account_and_fetch_inner(program_id, outbox_info)?;
+
+    // Account 3: The owner of the Mailbox.
+    let owner_info = next_account_info(accounts_iter)?;
+    // Errors if the owner account isn't correct or isn't a signer.
+    outbox.ensure_owner_signer(owner_info)?;
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    inbox.recipient_isms_enabled = true;
+    // Inbox PDAs created before recipient ISM overrides existed need to be realloc'd.
+    InboxAccount::from(inbox).store_with_rent_exempt_realloc(
+        inbox_info,
+        &Rent::get()?,
+        owner_info,
+        system_program_info,
+    )?;
+
+    Ok(())
+}
+
+/// Closes the processed message PDA of a message after the retention period,
+/// refunding its rent to the beneficiary. To keep the message from being processed
+/// again, its nonce is marked in the processed nonces PDA tracking it.
+///
+/// Accounts:
+/// 0. `[executable]` The system program.
+/// 1. `[signer, writeable]` The payer, which pays for the processed nonces PDA if it doesn't exist yet.
+/// 2. `[]` The Inbox PDA account.
+/// 3. `[writeable]` The processed message PDA.
+/// 4. `[writeable]` The processed nonces PDA.
+/// 5. `[writeable]` The beneficiary of the processed message retention.
+/// 6. `[]` The closed processed messages PDA, which is never created. It lets
+///    indexers find the `ClosedProcessedMessage` events by address.
+fn inbox_close_processed_message(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    close: InboxCloseProcessedMessage,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Decode the message bytes.
+    let message = HyperlaneMessage::read_from(&mut std::io::Cursor::new(&close.message))
+        .map_err(|_| ProgramError::from(Error::DecodeError))?;
+    let message_id = message.id();
+    let recipient_program_id = Pubkey::new_from_array(message.recipient.0);
+
+    // Account 0: The system program.
+    let system_program_info = next_account_info(accounts_iter)?;
+    if system_program_info.key != &solana_program::system_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 1: Payer account.
+    let payer_info = next_account_info(accounts_iter)?;
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Account 2: Inbox PDA.
+    let inbox_info = next_account_info(accounts_iter)?;
+    let inbox = Inbox::verify_account_and_fetch_inner(program_id, inbox_info)?;
+    let processed_message_retention = inbox
+        .processed_message_retention
+        .ok_or(Error::ProcessedMessageClosingDisabled)?;
+
+    // Account 3: Processed message PDA.
+    let processed_message_account_info = next_account_info(accounts_iter)?;
+    let (expected_processed_message_key, _expected_processed_message_bump) =
+        Pubkey::find_program_address(mailbox_processed_message_pda_seeds!(message_id), program_id);
+    if processed_message_account_info.key != &expected_processed_message_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if processed_message_account_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let processed_message =
+        ProcessedMessageAccount::fetch(&mut &processed_message_account_info.data.borrow()[..])?
+            .into_inner();
+    if processed_message
+        .slot
+        .saturating_add(processed_message_retention.retention_slots)
+        > Clock::get()?.slot
+    {
+        return Err(Error::ProcessedMessageRetained.into());
+    }
+
+    // Account 4: Processed nonces PDA.
+    let processed_nonces_info = next_account_info(accounts_iter)?;
+    let (expected_processed_nonces_key, expected_processed_nonces_bump) =
+        processed_nonces_pda(program_id, &message)?;
+    if processed_nonces_info.key != &expected_processed_nonces_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let mut processed_nonces = if processed_nonces_info.data_is_empty() {
+        let processed_nonces = ProcessedNonces::new(expected_processed_nonces_bump);
+        create_pda_account(
+            payer_info,
+            &Rent::get()?,
+            ProcessedNoncesAccount::from(ProcessedNonces::default()).size(),
+            program_id,
+            system_program_info,
+            processed_nonces_info,
+            mailbox_processed_nonces_pda_seeds!(
+                recipient_program_id,
+                message.origin,
+                ProcessedNonces::page(message.nonce),
+                expected_processed_nonces_bump
+            ),
+        )?;
+        processed_nonces
+    } else {
+        if processed_nonces_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        *ProcessedNoncesAccount::fetch(&mut &processed_nonces_info.data.borrow()[..])?.into_inner()
+    };
+    processed_nonces.insert(message.nonce);
+    ProcessedNoncesAccount::from(processed_nonces).store(processed_nonces_info, false)?;
+
+    // Account 5: Beneficiary.
+    let beneficiary_info = next_account_info(accounts_iter)?;
+    if beneficiary_info.key != &processed_message_retention.beneficiary {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 6: Closed processed messages PDA.
+    let closed_processed_messages_info = next_account_info(accounts_iter)?;
+    let (expected_closed_processed_messages_key, _expected_closed_processed_messages_bump) =
+        Pubkey::find_program_address(mailbox_closed_processed_messages_pda_seeds!(), program_id);
+    if closed_processed_messages_info.key != &expected_closed_processed_messages_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    if accounts_iter.next().is_some() {
+        return Err(ProgramError::from(Error::ExtraneousAccount));
+    }
+
+    // Close the processed message PDA, handing it back to the system program.
+    let refunded_rent = processed_message_account_info.lamports();
+    **processed_message_account_info.try_borrow_mut_lamports()? = 0;
+    **beneficiary_info.try_borrow_mut_lamports()? += refunded_rent;
+    processed_message_account_info.realloc(0, false)?;
+    processed_message_account_info.assign(&solana_program::system_program::id());
+
+    msg!(
+        "Closed processed message PDA of message {:?}, refunding {} lamports to {}",
+        message_id,
+        refunded_rent,
+        beneficiary_info.key
+    );
+    ClosedProcessedMessage {
+        sequence: processed_message.sequence,
+        message_id,
+        slot: processed_message.slot,
+    }
+    .emit()?;
+
+    Ok(())
+}
@@ -4,7 +4,10 @@
 use account_utils::{create_pda_account, AccountData, SizedData};
 use borsh::{BorshDeserialize, BorshSerialize};
 use hyperlane_sealevel_mailbox::{
-    instruction::{InboxProcess, Instruction as MailboxInstruction, OutboxDispatch},
+    instruction::{
+        set_recipient_ism_instruction, InboxProcess, Instruction as MailboxInstruction,
+        OutboxDispatch,
+    },
     mailbox_message_dispatch_authority_pda_seeds, mailbox_process_authority_pda_seeds,
 };
 use hyperlane_sealevel_message_recipient_interface::{
@@ -117,6 +120,8 @@ pub enum TestSendReceiverInstruction {
     SetInterchainSecurityModule(Option<Pubkey>, IsmReturnDataMode),
     /// Sets the behavior when handling a message.
     SetHandleMode(HandleMode),
+    /// Registers an ISM override with the Mailbox using the dispatch authority.
+    SetMailboxIsmOverride(Option<Pubkey>),
 }
 
 /// The program's entrypoint.
@@ -155,6 +160,9 @@ pub fn process_instruction(
         TestSendReceiverInstruction::SetHandleMode(mode) => {
             set_handle_mode(program_id, accounts, mode)
         }
+        TestSendReceiverInstruction::SetMailboxIsmOverride(ism) => {
+            set_mailbox_ism_override(program_id, accounts, ism)
+        }
     }
 }
 
@@ -446,6 +454,63 @@ fn set_handle_mode(
     Ok(())
 }
 
+/// Registers an ISM override with the Mailbox using the dispatch authority.
+///
+/// Accounts:
+/// 0. `[executable]` The Mailbox program.
+/// And now the accounts expected by the Mailbox's InboxSetRecipientIsm instruction:
+/// 1. `[executable]` System program.
+/// 2. `[signer, writeable]` Payer.
+/// 3. `[]` This program's dispatch authority.
+/// 4. `[writeable]` The Mailbox's recipient ISM PDA of this program.
+/// 5. `[]` The Mailbox's Inbox PDA.
+fn set_mailbox_ism_override(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    ism: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    // Account 0: Mailbox program.
+    let mailbox_info = next_account_info(accounts_iter)?;
+
+    // Account 1: System program.
+    let system_program_info = next_account_info(accounts_iter)?;
+
+    // Account 2: Payer.
+    let payer_info = next_account_info(accounts_iter)?;
+
+    // Account 3: Dispatch authority.
+    let dispatch_authority_info = next_account_info(accounts_iter)?;
+    let (expected_dispatch_authority_key, expected_dispatch_authority_bump) =
+        Pubkey::find_program_address(mailbox_message_dispatch_authority_pda_seeds!(), program_id);
+    if dispatch_authority_info.key != &expected_dispatch_authority_key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Account 4: Recipient ISM PDA.
+    let recipient_ism_info = next_account_info(accounts_iter)?;
+
+    // Account 5: Inbox PDA.
+    let inbox_info = next_account_info(accounts_iter)?;
+
+    let instruction =
+        set_recipient_ism_instruction(*mailbox_info.key, *payer_info.key, *program_id, ism)?;
+    invoke_signed(
+        &instruction,
+        &[
+            system_program_info.clone(),
+            payer_info.clone(),
+            dispatch_authority_info.clone(),
+            recipient_ism_info.clone(),
+            inbox_info.clone(),
+        ],
+        &[mailbox_message_dispatch_authority_pda_seeds!(
+            expected_dispatch_authority_bump
+        )],
+    )
+}
+
 fn set_account_meta_return_data(program_id: &Pubkey) -> ProgramResult {
     let (storage_pda_key, _storage_pda_bump) =
         Pubkey::find_program_address(test_send_receiver_storage_pda_seeds!(), program_id);
@@ -10,8 +10,8 @@ use solana_program_test::*;
 use solana_sdk::{signature::Signature, signature::Signer, signer::keypair::Keypair};
 
 use hyperlane_sealevel_mailbox::{
-    instruction::OutboxDispatch, mailbox_dispatched_message_pda_seeds,
-    mailbox_message_dispatch_authority_pda_seeds,
+    instruction::{recipient_ism_pda, OutboxDispatch},
+    mailbox_dispatched_message_pda_seeds, mailbox_message_dispatch_authority_pda_seeds,
 };
 use hyperlane_test_utils::{mailbox_id, process_instruction, MailboxAccounts};
 
@@ -130,6 +130,52 @@ impl TestSendReceiverTestClient {
         Ok(())
     }
 
+    /// Registers an ISM override with the Mailbox.
+    pub async fn set_mailbox_ism_override(
+        &mut self,
+        mailbox_accounts: &MailboxAccounts,
+        ism: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let program_id = id();
+
+        let (dispatch_authority_key, _expected_dispatch_authority_bump) =
+            Self::get_dispatch_authority();
+        let (recipient_ism_key, _recipient_ism_bump) =
+            recipient_ism_pda(&mailbox_accounts.program, &program_id).unwrap();
+
+        let instruction = Instruction {
+            program_id,
+            data: TestSendReceiverInstruction::SetMailboxIsmOverride(ism)
+                .try_to_vec()
+                .unwrap(),
+            accounts: vec![
+                // 0. `[executable]` The Mailbox program.
+                // And now the accounts expected by the Mailbox's InboxSetRecipientIsm instruction:
+                // 1. `[executable]` System program.
+                // 2. `[signer, writeable]` Payer.
+                // 3. `[]` This program's dispatch authority.
+                // 4. `[writeable]` The Mailbox's recipient ISM PDA of this program.
+                // 5. `[]` The Mailbox's Inbox PDA.
+                AccountMeta::new_readonly(mailbox_accounts.program, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(self.payer.pubkey(), true),
+                AccountMeta::new_readonly(dispatch_authority_key, false),
+                AccountMeta::new(recipient_ism_key, false),
+                AccountMeta::new_readonly(mailbox_accounts.inbox, false),
+            ],
+        };
+
+        process_instruction(
+            &mut self.banks_client,
+            instruction,
+            &self.payer,
+            &[&self.payer],
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// Dispatches a message.
     pub async fn dispatch(
         &mut self,
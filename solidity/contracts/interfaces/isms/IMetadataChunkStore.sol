@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity >=0.8.0;
+
+/**
+ * @notice Stores ISM metadata too large to be passed to `Mailbox.process` in
+ * a single transaction, so that it can be written in chunks by the relayer
+ * beforehand. ISMs supporting pointer-based metadata are then passed
+ * `abi.encodePacked(store, writer, key)` as metadata, and read the stored
+ * metadata with `metadata(writer, key)`.
+ * @dev Chunks are namespaced by the address writing them, so that they can't
+ * be overwritten by anyone else.
+ */
+interface IMetadataChunkStore {
+    /**
+     * @notice Stores a chunk of the metadata under `_key`
+     * @param _key The key of the metadata, e.g. the message ID
+     * @param _index The index of the chunk in the metadata
+     * @param _chunk The chunk of metadata
+     */
+    function storeChunk(
+        bytes32 _key,
+        uint32 _index,
+        bytes calldata _chunk
+    ) external;
+
+    /**
+     * @notice Deletes the chunks of the caller stored under `_key`
+     * @param _key The key of the metadata
+     */
+    function clear(bytes32 _key) external;
+
+    /**
+     * @notice Returns the concatenated chunks stored by `_writer` under `_key`
+     * @param _writer The address that stored the chunks
+     * @param _key The key of the metadata
+     */
+    function metadata(
+        address _writer,
+        bytes32 _key
+    ) external view returns (bytes memory);
+}
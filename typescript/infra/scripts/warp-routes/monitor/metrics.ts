@@ -5,7 +5,12 @@ import { ChainName, Token, TokenStandard, WarpCore } from '@hyperlane-xyz/sdk';
 
 import { getWalletBalanceGauge } from '../../../src/utils/metrics.js';
 
-import { NativeWalletBalance, WarpRouteBalance, XERC20Limit } from './types.js';
+import {
+  NativeWalletBalance,
+  WarpRouteBalance,
+  WarpRouteSolvency,
+  XERC20Limit,
+} from './types.js';
 import { logger } from './utils.js';
 
 export const metricsRegister = new Registry();
@@ -46,6 +51,27 @@ const warpRouteCollateralValue = new Gauge({
   labelNames: warpRouteMetricLabels,
 });
 
+const warpRouteCollateralLocked = new Gauge({
+  name: 'hyperlane_warp_route_collateral_locked',
+  help: 'Total collateral locked across the collateral tokens of a Warp Route',
+  registers: [metricsRegister],
+  labelNames: ['warp_route_id'],
+});
+
+const warpRouteSyntheticSupply = new Gauge({
+  name: 'hyperlane_warp_route_synthetic_supply',
+  help: 'Total supply across the synthetic tokens of a Warp Route',
+  registers: [metricsRegister],
+  labelNames: ['warp_route_id'],
+});
+
+const warpRouteCollateralDivergence = new Gauge({
+  name: 'hyperlane_warp_route_collateral_divergence',
+  help: 'Collateral locked minus synthetic supply of a Warp Route, as a fraction of the larger of the two',
+  registers: [metricsRegister],
+  labelNames: ['warp_route_id'],
+});
+
 const walletBalanceGauge = getWalletBalanceGauge(metricsRegister);
 
 const xERC20LimitsGauge = new Gauge({
@@ -99,6 +125,23 @@ export function updateTokenBalanceMetrics(
   }
 }
 
+export function updateSolvencyMetrics(
+  warpRouteId: string,
+  solvency: WarpRouteSolvency,
+) {
+  const labels = { warp_route_id: warpRouteId };
+  warpRouteCollateralLocked.labels(labels).set(solvency.collateralLocked);
+  warpRouteSyntheticSupply.labels(labels).set(solvency.syntheticSupply);
+  warpRouteCollateralDivergence.labels(labels).set(solvency.divergence);
+  logger.info(
+    {
+      labels,
+      ...solvency,
+    },
+    'Solvency updated for warp route',
+  );
+}
+
 export function updateNativeWalletBalanceMetrics(balance: NativeWalletBalance) {
   walletBalanceGauge
     .labels({
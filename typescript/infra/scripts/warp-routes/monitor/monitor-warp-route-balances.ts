@@ -30,15 +30,22 @@ import { getEnvironmentConfig } from '../../core-utils.js';
 import {
   metricsRegister,
   updateNativeWalletBalanceMetrics,
+  updateSolvencyMetrics,
   updateTokenBalanceMetrics,
   updateXERC20LimitsMetrics,
 } from './metrics.js';
-import { NativeWalletBalance, WarpRouteBalance, XERC20Limit } from './types.js';
+import {
+  NativeWalletBalance,
+  WarpRouteBalance,
+  WarpRouteSolvency,
+  XERC20Limit,
+} from './types.js';
 import { logger, tryFn } from './utils.js';
 
 async function main() {
   const {
     checkFrequency,
+    solvencyTolerance,
     environment,
     warpRouteId: warpRouteIdArg,
   } = await withWarpRouteId(getArgs())
@@ -46,6 +53,12 @@ async function main() {
     .demandOption('checkFrequency')
     .alias('v', 'checkFrequency') // v as in Greek letter nu
     .number('checkFrequency')
+    .describe(
+      'solvencyTolerance',
+      'fraction by which the collateral locked and synthetic supply may diverge before alerting',
+    )
+    .number('solvencyTolerance')
+    .default('solvencyTolerance', 0.001)
     .parse();
 
   const warpRouteId = warpRouteIdArg || (await getWarpRouteIdInteractive());
@@ -68,12 +81,18 @@ async function main() {
   const warpCoreConfig = getWarpCoreConfig(warpRouteId);
   const warpCore = WarpCore.FromConfig(multiProtocolProvider, warpCoreConfig);
 
-  await pollAndUpdateWarpRouteMetrics(checkFrequency, warpCore, chainMetadata);
+  await pollAndUpdateWarpRouteMetrics(
+    checkFrequency,
+    solvencyTolerance,
+    warpCore,
+    chainMetadata,
+  );
 }
 
 // Indefinitely loops, updating warp route metrics at the specified frequency.
 async function pollAndUpdateWarpRouteMetrics(
   checkFrequency: number,
+  solvencyTolerance: number,
   warpCore: WarpCore,
   chainMetadata: ChainMap<ChainMetadata>,
 ) {
@@ -82,10 +101,14 @@ async function pollAndUpdateWarpRouteMetrics(
     apiKey: await getCoinGeckoApiKey(),
   });
   const collateralTokenSymbol = getWarpRouteCollateralTokenSymbol(warpCore);
+  const warpRouteId = createWarpRouteConfigId(
+    collateralTokenSymbol,
+    warpCore.getTokenChains(),
+  );
 
   while (true) {
     await tryFn(async () => {
-      await Promise.all(
+      const balances = await Promise.all(
         warpCore.tokens.map((token) =>
           updateTokenMetrics(
             warpCore,
@@ -95,18 +118,31 @@ async function pollAndUpdateWarpRouteMetrics(
           ),
         ),
       );
+      const solvency = getWarpRouteSolvency(warpCore.tokens, balances);
+      if (!solvency) {
+        return;
+      }
+      updateSolvencyMetrics(warpRouteId, solvency);
+      if (Math.abs(solvency.divergence) > solvencyTolerance) {
+        logger.error(
+          { warpRouteId, ...solvency, solvencyTolerance },
+          'Collateral locked and synthetic supply of warp route diverge beyond tolerance',
+        );
+      }
     }, 'Updating warp route metrics');
     await sleep(checkFrequency);
   }
 }
 
-// Updates the metrics for a single token in a warp route.
+// Updates the metrics for a single token in a warp route, returning its
+// bridged balance if it could be fetched.
 async function updateTokenMetrics(
   warpCore: WarpCore,
   token: Token,
   tokenPriceGetter: CoinGeckoTokenPriceGetter,
   collateralTokenSymbol: string,
-) {
+): Promise<WarpRouteBalance | undefined> {
+  let bridgedBalance: WarpRouteBalance | undefined;
   const promises = [
     tryFn(async () => {
       const balanceInfo = await getTokenBridgedBalance(
@@ -117,6 +153,7 @@ async function updateTokenMetrics(
       if (!balanceInfo) {
         return;
       }
+      bridgedBalance = balanceInfo;
       updateTokenBalanceMetrics(
         warpCore,
         token,
@@ -149,6 +186,60 @@ async function updateTokenMetrics(
   }
 
   await Promise.all(promises);
+  return bridgedBalance;
+}
+
+// Standards that lock collateral but aren't in the SDK's collateralized
+// standards
+const EXTRA_COLLATERAL_STANDARDS = [
+  TokenStandard.EvmHypXERC20Lockbox,
+  TokenStandard.EvmHypOwnerCollateral,
+  TokenStandard.EvmHypCollateralFiat,
+  TokenStandard.EvmHypRebaseCollateral,
+];
+
+function isCollateralToken(token: Token): boolean {
+  return (
+    token.isCollateralized() ||
+    EXTRA_COLLATERAL_STANDARDS.includes(token.standard)
+  );
+}
+
+// Sums the collateral locked and synthetic supply of a warp route, whose
+// tokens have the given bridged balances. Returns undefined if the route
+// can't be reconciled, i.e. if a balance is missing or the route doesn't
+// have both collateral and synthetic tokens.
+function getWarpRouteSolvency(
+  tokens: Token[],
+  balances: (WarpRouteBalance | undefined)[],
+): WarpRouteSolvency | undefined {
+  if (balances.some((balance) => balance === undefined)) {
+    logger.warn('Cannot reconcile warp route with missing bridged balances');
+    return undefined;
+  }
+
+  let collateralLocked = 0;
+  let syntheticSupply = 0;
+  let collateralTokens = 0;
+  tokens.forEach((token, i) => {
+    if (isCollateralToken(token)) {
+      collateralTokens += 1;
+      collateralLocked += balances[i]!.balance;
+    } else {
+      syntheticSupply += balances[i]!.balance;
+    }
+  });
+  if (collateralTokens === 0 || collateralTokens === tokens.length) {
+    return undefined;
+  }
+
+  const largest = Math.max(collateralLocked, syntheticSupply);
+  return {
+    collateralLocked,
+    syntheticSupply,
+    divergence:
+      largest === 0 ? 0 : (collateralLocked - syntheticSupply) / largest,
+  };
 }
 
 // Gets the bridged balance and value of a token in a warp route.
@@ -13,6 +13,14 @@ export interface WarpRouteBalance {
   valueUSD?: number;
 }
 
+export interface WarpRouteSolvency {
+  collateralLocked: number;
+  syntheticSupply: number;
+  // Collateral locked minus synthetic supply, as a fraction of the larger
+  // of the two. Negative if the synthetic supply isn't fully collateralized.
+  divergence: number;
+}
+
 export interface NativeWalletBalance {
   chain: ChainName;
   walletAddress: Address;
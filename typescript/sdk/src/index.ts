@@ -212,8 +212,12 @@ export {
   AgentConfig,
   AgentConfigSchema,
   AgentCosmosGasPrice,
+  AgentCosmosKeyType,
+  AgentCosmosModuleType,
+  AgentCosmosSignMode,
   AgentLogFormat,
   AgentLogLevel,
+  AgentOrderingKeySource,
   AgentSealevelChainMetadata,
   AgentSealevelHeliusFeeLevel,
   AgentSealevelPriorityFeeOracle,
@@ -9,7 +9,10 @@ import { ProtocolType } from '@hyperlane-xyz/utils';
 import { MultiProvider } from '../providers/MultiProvider.js';
 import { ChainMap, ChainName } from '../types.js';
 
-import { ChainMetadataSchemaObject } from './chainMetadataTypes.js';
+import {
+  ChainMetadataSchemaObject,
+  RpcUrlSchema,
+} from './chainMetadataTypes.js';
 import { ZHash, ZNzUint, ZUWei, ZUint } from './customZodTypes.js';
 import {
   HyperlaneDeploymentArtifacts,
@@ -23,6 +26,12 @@ export enum RpcConsensusType {
   Quorum = 'quorum',
 }
 
+export enum AgentRpcCapability {
+  ReadWrite = 'readWrite',
+  ReadOnly = 'readOnly',
+  WriteOnly = 'writeOnly',
+}
+
 export enum AgentLogLevel {
   Off = 'off',
   Error = 'error',
@@ -49,6 +58,27 @@ export enum AgentSignerKeyType {
   Hex = 'hexKey',
   Node = 'node',
   Cosmos = 'cosmosKey',
+  CosmosAws = 'cosmosAws',
+  Gcp = 'gcp',
+  CosmosGcp = 'cosmosGcp',
+  Pkcs11 = 'pkcs11',
+  CosmosPkcs11 = 'cosmosPkcs11',
+  Remote = 'remote',
+}
+
+export enum AgentCosmosKeyType {
+  Secp256k1 = 'secp256k1',
+  EthSecp256k1 = 'ethsecp256k1',
+}
+
+export enum AgentCosmosSignMode {
+  Direct = 'direct',
+  Eip712 = 'eip712',
+}
+
+export enum AgentCosmosModuleType {
+  CosmWasm = 'cosmwasm',
+  Native = 'native',
 }
 
 export enum AgentSealevelPriorityFeeOracleType {
@@ -71,6 +101,17 @@ export enum AgentSealevelTransactionSubmitterType {
   Jito = 'jito',
 }
 
+export enum AgentDbValueMigration {
+  Migrate = 'migrate',
+  Commit = 'commit',
+  Rollback = 'rollback',
+}
+
+export enum AgentOrderingKeySource {
+  Sender = 'sender',
+  Body = 'body',
+}
+
 const AgentSignerHexKeySchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Hex).optional(),
@@ -86,6 +127,56 @@ const AgentSignerAwsKeySchema = z
   .describe(
     'An AWS signer. Note that AWS credentials must be inserted into the env separately.',
   );
+const AgentSignerGcpKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Gcp),
+    keyName: z
+      .string()
+      .describe('The resource name of the GCP Cloud KMS key version'),
+  })
+  .describe(
+    'A GCP Cloud KMS signer, authenticated as the workload the agent runs as (e.g. GKE workload identity).',
+  );
+const AgentSignerPkcs11KeyFields = {
+  module: z
+    .string()
+    .describe('The path of the PKCS#11 module of the HSM'),
+  tokenLabel: z.string().describe('The label of the token holding the key'),
+  keyLabel: z.string().describe('The label of the key'),
+  pin: z.string().describe("The PIN of the token's user"),
+};
+const AgentSignerPkcs11KeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Pkcs11),
+    ...AgentSignerPkcs11KeyFields,
+  })
+  .describe('A signer whose key is held in an HSM, accessed through PKCS#11');
+const AgentSignerRemoteSchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.Remote),
+    url: z
+      .string()
+      .url()
+      .describe('The base URL of the signing service, which must be https'),
+    caCert: z
+      .string()
+      .describe(
+        'The path of the PEM encoded CA certificate the signing service is verified against',
+      ),
+    clientCert: z
+      .string()
+      .describe(
+        'The path of the PEM encoded certificate the agent authenticates with',
+      ),
+    clientKey: z
+      .string()
+      .describe(
+        'The path of the PEM encoded PKCS#8 private key of the client certificate',
+      ),
+  })
+  .describe(
+    'A signer whose key is held by a remote signing service, e.g. in an enclave, called over mutual TLS',
+  );
 const AgentSignerCosmosKeySchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Cosmos),
@@ -93,6 +184,36 @@ const AgentSignerCosmosKeySchema = z
     key: ZHash,
   })
   .describe('Cosmos key');
+const AgentSignerCosmosAwsKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.CosmosAws),
+    prefix: z.string().describe('The bech32 prefix for the cosmos address'),
+    id: z.string().describe('The UUID identifying the AWS KMS key'),
+    region: z.string().describe('The AWS region'),
+  })
+  .describe(
+    'A Cosmos signer whose key is held in AWS KMS. Note that AWS credentials must be inserted into the env separately.',
+  );
+const AgentSignerCosmosGcpKeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.CosmosGcp),
+    prefix: z.string().describe('The bech32 prefix for the cosmos address'),
+    keyName: z
+      .string()
+      .describe('The resource name of the GCP Cloud KMS key version'),
+  })
+  .describe(
+    'A Cosmos signer whose key is held in GCP Cloud KMS, authenticated as the workload the agent runs as.',
+  );
+const AgentSignerCosmosPkcs11KeySchema = z
+  .object({
+    type: z.literal(AgentSignerKeyType.CosmosPkcs11),
+    prefix: z.string().describe('The bech32 prefix for the cosmos address'),
+    ...AgentSignerPkcs11KeyFields,
+  })
+  .describe(
+    'A Cosmos signer whose key is held in an HSM, accessed through PKCS#11',
+  );
 const AgentSignerNodeSchema = z
   .object({
     type: z.literal(AgentSignerKeyType.Node),
@@ -102,13 +223,31 @@ const AgentSignerNodeSchema = z
 const AgentSignerSchema = z.union([
   AgentSignerHexKeySchema,
   AgentSignerAwsKeySchema,
+  AgentSignerGcpKeySchema,
+  AgentSignerPkcs11KeySchema,
+  AgentSignerRemoteSchema,
   AgentSignerCosmosKeySchema,
+  AgentSignerCosmosAwsKeySchema,
+  AgentSignerCosmosGcpKeySchema,
+  AgentSignerCosmosPkcs11KeySchema,
   AgentSignerNodeSchema,
 ]);
 
 export type AgentSignerHexKey = z.infer<typeof AgentSignerHexKeySchema>;
 export type AgentSignerAwsKey = z.infer<typeof AgentSignerAwsKeySchema>;
 export type AgentSignerCosmosKey = z.infer<typeof AgentSignerNodeSchema>;
+export type AgentSignerGcpKey = z.infer<typeof AgentSignerGcpKeySchema>;
+export type AgentSignerCosmosGcpKey = z.infer<
+  typeof AgentSignerCosmosGcpKeySchema
+>;
+export type AgentSignerPkcs11Key = z.infer<typeof AgentSignerPkcs11KeySchema>;
+export type AgentSignerRemote = z.infer<typeof AgentSignerRemoteSchema>;
+export type AgentSignerCosmosPkcs11Key = z.infer<
+  typeof AgentSignerCosmosPkcs11KeySchema
+>;
+export type AgentSignerCosmosAwsKey = z.infer<
+  typeof AgentSignerCosmosAwsKeySchema
+>;
 export type AgentSignerNode = z.infer<typeof AgentSignerNodeSchema>;
 export type AgentSigner = z.infer<typeof AgentSignerSchema>;
 
@@ -134,6 +273,69 @@ const AgentCosmosChainMetadataSchema = z.object({
     .positive()
     .lte(32)
     .describe('The number of bytes used to represent a contract address.'),
+  additionalBech32Prefixes: z
+    .array(z.string())
+    .optional()
+    .describe(
+      'Bech32 prefixes other than bech32Prefix that addresses on this chain may have, e.g. the valoper prefix. Configured bech32 addresses are validated against these prefixes.',
+    ),
+  dryRun: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, state-changing transactions are simulated and logged instead of broadcast.',
+    ),
+  verifyEvents: z
+    .boolean()
+    .optional()
+    .describe(
+      'If true, indexed events are verified against Tendermint commit signatures and block hashes instead of trusting the RPC.',
+    ),
+  lightClientStorePath: z
+    .string()
+    .optional()
+    .describe(
+      'The file the validator set trusted when verifying events is persisted to. Required if verifyEvents is true.',
+    ),
+  trustingPeriodSecs: ZNzUint.optional().describe(
+    'How long a trusted validator set stays trusted when verifying events, in seconds. Defaults to 14 days.',
+  ),
+  queryCacheTtls: z
+    .record(ZUint)
+    .optional()
+    .describe(
+      'How long to cache the results of contract queries for in seconds, by query name (e.g. module_type or latest_block_height). A TTL of 0 disables caching of the query.',
+    ),
+  keyType: z
+    .nativeEnum(AgentCosmosKeyType)
+    .optional()
+    .describe(
+      'The type of key transactions are signed with. Ethermint based chains use ethsecp256k1. Defaults to secp256k1.',
+    ),
+  signMode: z
+    .nativeEnum(AgentCosmosSignMode)
+    .optional()
+    .describe(
+      'The mode transactions are signed in. eip712 signs EIP-712 typed data, as supported by Ethermint based chains, and requires the ethsecp256k1 key type. Defaults to direct.',
+    ),
+  moduleType: z
+    .nativeEnum(AgentCosmosModuleType)
+    .optional()
+    .describe(
+      'The Hyperlane implementation deployed on the chain: the CosmWasm contracts or the native x/hyperlane module. Defaults to cosmwasm.',
+    ),
+  feeGranter: z
+    .string()
+    .optional()
+    .describe(
+      'The bech32 address of an x/feegrant granter that pays the fees of transactions instead of the signer. The granter must have granted the signer an allowance. Not supported in eip712 sign mode.',
+    ),
+  authzGranter: z
+    .string()
+    .optional()
+    .describe(
+      'The bech32 address of an operator account that messages are delivered on behalf of, by wrapping them in an x/authz MsgExec signed by the relayer key. The operator must have granted the relayer key the execution of process messages. Not supported in eip712 sign mode.',
+    ),
 });
 
 export type AgentCosmosGasPrice = z.infer<
@@ -162,6 +364,20 @@ const AgentSealevelChainMetadataSchema = z.object({
       url: z.string().optional(),
     })
     .optional(),
+  addressLookupTable: z
+    .string()
+    .optional()
+    .describe(
+      'An address lookup table owned by the relayer payer. If set, messages are processed with v0 transactions that load the mailbox and ISM accounts from the table, which the relayer extends as needed.',
+    ),
+  computeUnitHeadroomPercent: z
+    .number()
+    .int()
+    .nonnegative()
+    .optional()
+    .describe(
+      'How many percent of compute units to request on top of those consumed when simulating a transaction. Defaults to 10.',
+    ),
 });
 
 export type AgentSealevelChainMetadata = z.infer<
@@ -188,6 +404,44 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
       .nativeEnum(RpcConsensusType)
       .describe('The consensus type to use when multiple RPCs are configured.')
       .optional(),
+    rpcUrls: z
+      .array(
+        RpcUrlSchema.extend({
+          capability: z
+            .nativeEnum(AgentRpcCapability)
+            .optional()
+            .describe(
+              'For EVM chains with the fallback consensus type only, the requests sent to this RPC: reads, transactions or both. Reads are load-balanced across the RPCs serving them. Defaults to readWrite.',
+            ),
+        }),
+      )
+      .min(1)
+      .describe('The list of RPC endpoints for interacting with the chain.'),
+    rateLimit: z
+      .object({
+        maxRequestsPerSecond: ZNzUint.describe(
+          'The average number of requests per second sent to each RPC endpoint.',
+        ),
+        burst: ZNzUint.optional().describe(
+          'The number of requests that can be sent at once after an endpoint was idle. Defaults to maxRequestsPerSecond.',
+        ),
+      })
+      .optional()
+      .describe(
+        'Rate limit applied to each RPC endpoint of the chain. Supported on Ethereum, Sealevel and Cosmos chains.',
+      ),
+    logPruningFallback: z
+      .boolean()
+      .optional()
+      .describe(
+        'Recover merkle tree insertions from transaction traces when the RPC returns incomplete logs, e.g. because its log index is pruned. Requires an archive RPC that supports debug_traceTransaction. Only supported on Ethereum chains.',
+      ),
+    customErrors: z
+      .array(z.string())
+      .optional()
+      .describe(
+        'Custom error definitions of third-party ISMs, e.g. "error NotWhitelisted(address sender)", used to decode their reverts. Only supported on Ethereum chains.',
+      ),
     signer: AgentSignerSchema.optional().describe(
       'The signer to use for this chain',
     ),
@@ -226,6 +480,9 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
           ![
             AgentSignerKeyType.Hex,
             signerType === AgentSignerKeyType.Aws,
+            AgentSignerKeyType.Gcp,
+            AgentSignerKeyType.Pkcs11,
+            AgentSignerKeyType.Remote,
             signerType === AgentSignerKeyType.Node,
           ].includes(signerType)
         ) {
@@ -234,7 +491,14 @@ export const AgentChainMetadataSchema = ChainMetadataSchemaObject.merge(
         break;
 
       case ProtocolType.Cosmos:
-        if (![AgentSignerKeyType.Cosmos].includes(signerType)) {
+        if (
+          ![
+            AgentSignerKeyType.Cosmos,
+            AgentSignerKeyType.CosmosAws,
+            AgentSignerKeyType.CosmosGcp,
+            AgentSignerKeyType.CosmosPkcs11,
+          ].includes(signerType)
+        ) {
           return false;
         }
         break;
@@ -274,6 +538,75 @@ export const AgentConfigSchema = z.object({
     .describe(
       'The port to expose prometheus metrics on. Accessible via `GET /metrics`.',
     ),
+  metricsPush: z
+    .object({
+      url: z.string().url().describe('The base URL of the Pushgateway.'),
+      interval: ZNzUint.optional().describe(
+        'How often to push metrics, in seconds. Defaults to 15.',
+      ),
+      maxRetries: z
+        .number()
+        .int()
+        .nonnegative()
+        .optional()
+        .describe(
+          'How often a failed push is retried before it is dropped. Defaults to 3.',
+        ),
+    })
+    .optional()
+    .describe(
+      'Optionally push metrics to a Prometheus Pushgateway, for agents that cannot expose the metrics port. Metrics are still served on the metrics port.',
+    ),
+  walletBalanceWatcher: z
+    .object({
+      interval: ZNzUint.optional().describe(
+        'How often to query the balances, in seconds. Defaults to 60.',
+      ),
+      lowBalanceThresholds: z
+        .record(z.number().nonnegative())
+        .optional()
+        .describe(
+          'Balances, in units of the native token, below which a warning is logged, by chain name.',
+        ),
+    })
+    .optional()
+    .describe(
+      'Optionally export the balances of the signers on all configured chains to the wallet_balance metric, and warn about low balances.',
+    ),
+  http: z
+    .object({
+      timeout: ZNzUint.optional().describe(
+        'Request timeout in seconds of hosts without a timeout of their own. Defaults to 60.',
+      ),
+      connectTimeout: ZNzUint.optional().describe(
+        'Timeout in seconds for establishing connections.',
+      ),
+      poolMaxIdlePerHost: ZUint.optional().describe(
+        'Max number of idle connections kept alive per host.',
+      ),
+      poolIdleTimeout: ZNzUint.optional().describe(
+        'How long idle connections are kept alive, in seconds.',
+      ),
+      proxy: z
+        .string()
+        .url()
+        .optional()
+        .describe(
+          'HTTP, HTTPS or SOCKS5 proxy to send all requests through. Defaults to the proxy in the HTTP_PROXY, HTTPS_PROXY or ALL_PROXY env var.',
+        ),
+      rootCertificates: z
+        .array(z.string())
+        .optional()
+        .describe(
+          'Paths of PEM root certificates to trust in addition to the built-in ones.',
+        ),
+      hostTimeouts: z
+        .record(ZNzUint)
+        .optional()
+        .describe('Request timeouts in seconds by host.'),
+    })
+    .optional()
+    .describe('Config of the HTTP clients used to connect to chains.'),
   chains: z
     .record(AgentChainMetadataSchema)
     .describe('Chain metadata for all chains that the agent will index.')
@@ -306,6 +639,25 @@ export const AgentConfigSchema = z.object({
         .nativeEnum(AgentLogLevel)
         .optional()
         .describe("The log level to use for the agent's logs."),
+      otlp: z
+        .object({
+          endpoint: z
+            .string()
+            .url()
+            .describe(
+              'The gRPC endpoint of the OpenTelemetry collector to export spans to.',
+            ),
+          headers: z
+            .record(z.string())
+            .optional()
+            .describe(
+              'Headers to send with every export request, e.g. for authentication.',
+            ),
+        })
+        .optional()
+        .describe(
+          'Export tracing spans to an OpenTelemetry collector over OTLP.',
+        ),
     })
     .optional(),
 });
@@ -342,6 +694,47 @@ const GasPaymentEnforcementSchema = z.union([
 ]);
 export type GasPaymentEnforcement = z.infer<typeof GasPaymentEnforcementSchema>;
 
+const GasEscalationSchema = z.object({
+  maxRetries: ZUint.optional().describe(
+    'Number of retries after which a message to this destination is dropped. If not specified, messages are retried indefinitely.',
+  ),
+  multiplier: z
+    .number()
+    .gte(1)
+    .optional()
+    .describe(
+      'Factor the gas limit is multiplied by for every failed submission of a message. Defaults to 1.',
+    ),
+  maxFee: ZUWei.optional().describe(
+    'Maximum fee in wei that may be spent on a single delivery transaction.',
+  ),
+});
+export type GasEscalation = z.infer<typeof GasEscalationSchema>;
+
+const FeeBudgetSchema = z.object({
+  window: ZNzUint.describe(
+    'Length, in seconds, of the trailing window the budget applies to.',
+  ),
+  maxFee: z
+    .number()
+    .nonnegative()
+    .describe(
+      'Maximum fees, in the smallest unit of the fee denom, expected to be paid within the window. Exceeding it is logged as a warning.',
+    ),
+});
+export type FeeBudget = z.infer<typeof FeeBudgetSchema>;
+
+const MetadataChunkingSchema = z.object({
+  store: ZHash.describe(
+    'Address of the metadata chunk store on the destination, which ISMs supporting pointer-based metadata read metadata from.',
+  ),
+  threshold: ZUint.describe(
+    'Metadata longer than this many bytes is stored in chunks before the message is processed.',
+  ),
+  chunkSize: ZNzUint.describe('Maximum size of a chunk in bytes.'),
+});
+export type MetadataChunking = z.infer<typeof MetadataChunkingSchema>;
+
 const MetricAppContextSchema = z.object({
   name: z.string().min(1),
   matchingList: MatchingListSchema.describe(
@@ -349,6 +742,66 @@ const MetricAppContextSchema = z.object({
   ),
 });
 
+const OrderingKeySchema = z.object({
+  matchingList: MatchingListSchema.describe(
+    'A matching list, any message that matches will be delivered in order with other messages sharing its ordering key.',
+  ),
+  source: z
+    .nativeEnum(AgentOrderingKeySource)
+    .describe('Where the ordering key of a message is read from.'),
+  offset: z
+    .number()
+    .int()
+    .nonnegative()
+    .optional()
+    .describe('Byte offset of the key in the message body. Defaults to 0.'),
+  length: z
+    .number()
+    .int()
+    .positive()
+    .optional()
+    .describe(
+      'Length in bytes of the key in the message body. Required if the source is `body`.',
+    ),
+});
+
+const DeliveryTtlSchema = z
+  .object({
+    matchingList: MatchingListSchema.optional().describe(
+      'A matching list, any message that matches is subject to this delivery TTL policy.',
+    ),
+    maxAge: ZUint.optional().describe(
+      'Messages dispatched longer ago than this many seconds are moved to the dead-letter queue instead of being delivered.',
+    ),
+    deadline: z
+      .object({
+        offset: z
+          .number()
+          .int()
+          .nonnegative()
+          .optional()
+          .describe(
+            'Byte offset of the deadline in the message body. Defaults to 0.',
+          ),
+        length: z
+          .number()
+          .int()
+          .min(1)
+          .max(32)
+          .optional()
+          .describe(
+            'Length in bytes of the big-endian deadline in the message body. Defaults to 32, i.e. an ABI encoded uint256.',
+          ),
+      })
+      .optional()
+      .describe(
+        'Where the message body encodes a unix timestamp in seconds after which the message is moved to the dead-letter queue instead of being delivered.',
+      ),
+  })
+  .refine((ttl) => ttl.maxAge !== undefined || ttl.deadline !== undefined, {
+    message: 'A delivery TTL must set a max age or a deadline',
+  });
+
 export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
   db: z
     .string()
@@ -376,16 +829,87 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'If no blacklist is provided ALL will be considered to not be on the blacklist.',
     ),
+  whitelistSource: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'An http(s) URL or file path to periodically reload the whitelist from. Once loaded, it replaces `whitelist`.',
+    ),
+  blacklistSource: z
+    .string()
+    .min(1)
+    .optional()
+    .describe(
+      'An http(s) URL or file path to periodically reload the blacklist from. Once loaded, it replaces `blacklist`.',
+    ),
+  matchingListReloadInterval: ZNzUint.optional().describe(
+    'How often, in seconds, to reload the whitelist and blacklist sources. Defaults to 60.',
+  ),
   addressBlacklist: z
     .string()
     .optional()
     .describe('Comma separated list of addresses to blacklist.'),
+  sealevelSkippedRecipients: z
+    .string()
+    .optional()
+    .describe(
+      'Comma separated list of base58 or hex Sealevel recipient program ids whose messages are not delivered, e.g. programs that panic when handling messages.',
+    ),
   transactionGasLimit: ZUWei.optional().describe(
     'This is optional. If not specified, any amount of gas will be valid, otherwise this is the max allowed gas in wei to relay a transaction.',
   ),
   skipTransactionGasLimitFor: CommaSeparatedDomainList.optional().describe(
     'Comma separated List of chain names to skip applying the transaction gas limit to.',
   ),
+  gasEscalation: z
+    .record(GasEscalationSchema)
+    .optional()
+    .describe(
+      'Gas escalation policies keyed by destination chain name, bounding gas spent on messages that repeatedly fail to be delivered.',
+    ),
+  feeBudgets: z
+    .record(z.array(FeeBudgetSchema))
+    .optional()
+    .describe(
+      'Budgets for the fees paid on Cosmos destinations, keyed by destination chain name.',
+    ),
+  metadataChunking: z
+    .record(MetadataChunkingSchema)
+    .optional()
+    .describe(
+      'Metadata chunking configs keyed by destination chain name, for Ethereum destinations whose ISMs support pointer-based metadata. Metadata too large for the process transaction is stored in chunks first.',
+    ),
+  routingIsmCacheFor: CommaSeparatedDomainList.optional().describe(
+    'Comma separated list of destination chain names to cache routing ISM routes for. Only suitable for routing ISMs that route on the message origin alone.',
+  ),
+  routingIsmCacheTtl: ZUint.optional().describe(
+    'How long, in seconds, a cached routing ISM route is used before it is looked up again. Defaults to 600.',
+  ),
+  ismConfigCacheFor: CommaSeparatedDomainList.optional().describe(
+    'Comma separated list of destination chain names to cache ISM types and multisig and aggregation ISM configurations for. Only suitable for ISMs configured per message origin alone.',
+  ),
+  ismConfigCacheTtl: ZUint.optional().describe(
+    'How long, in seconds, a cached ISM configuration is used before it is looked up again. Defaults to 600.',
+  ),
+  metadataWarmUpTtl: ZUint.optional().describe(
+    'If set, how long, in seconds, the metadata of a message only failing the gas payment policy is kept, and such messages are rechecked every 10 seconds, so that they can be submitted as soon as their gas payment is topped up.',
+  ),
+  deadLetterAfterRetries: ZUint.optional().describe(
+    'If set, messages retried more often than this are moved to the dead-letter queue, from which they can be listed, exported and re-injected via the admin API.',
+  ),
+  adminApiToken: z
+    .string()
+    .optional()
+    .describe(
+      'Bearer token required by the relayer HTTP API. Endpoints that drop messages or reset cursors are only served if this is set.',
+    ),
+  dbValueMigration: z
+    .nativeEnum(AgentDbValueMigration)
+    .optional()
+    .describe(
+      'What to do on startup with database values stored in outdated encodings. `migrate` (default) re-encodes them and keeps their previous encoding, `commit` also deletes the previous encoding, and `rollback` restores the encoding of relayers from before the migration and exits.',
+    ),
   allowLocalCheckpointSyncers: z
     .boolean()
     .optional()
@@ -398,6 +922,18 @@ export const RelayerAgentConfigSchema = AgentConfigSchema.extend({
     .describe(
       'A list of app contexts and their matching lists to use for metrics. A message will be classified as the first matching app context.',
     ),
+  orderingKeys: z
+    .union([z.array(OrderingKeySchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Opt-in ordering keys. Messages sharing a key are delivered one at a time, in dispatch order. A message uses the first ordering key whose matching list it matches.',
+    ),
+  deliveryTtls: z
+    .union([z.array(DeliveryTtlSchema), z.string().min(1)])
+    .optional()
+    .describe(
+      'Delivery TTL policies dropping stale messages. A message uses the first policy whose matching list it matches.',
+    ),
 });
 
 export type RelayerConfig = z.infer<typeof RelayerAgentConfigSchema>;
@@ -462,12 +998,119 @@ export const ValidatorAgentConfigSchema = AgentConfigSchema.extend({
           .min(1)
           .optional()
           .describe('The path to GCS user secret file'),
+        workload_identity: z
+          .boolean()
+          .optional()
+          .describe(
+            'Authenticate as the GCP workload the validator runs as, e.g. via GKE workload identity',
+          ),
       })
       .describe('A checkpoint syncer that uses Google Cloud Storage'),
+    z
+      .object({
+        type: z.literal('azblob'),
+        account: z.string().min(1).describe('The Azure storage account name'),
+        container: z.string().min(1),
+        folder: z
+          .string()
+          .min(1)
+          .optional()
+          .describe('The folder to use, defaults to the root of the container'),
+        sas_token: z
+          .string()
+          .min(1)
+          .optional()
+          .describe('A SAS token granting access to the container'),
+        managed_identity: z
+          .boolean()
+          .optional()
+          .describe(
+            'Authenticate as the managed identity of the Azure resource the validator runs on',
+          ),
+      })
+      .describe('A checkpoint syncer that uses Azure Blob Storage'),
+    z
+      .object({
+        type: z.literal('ipfs'),
+        ipns_name: z
+          .string()
+          .regex(/^[a-zA-Z0-9]+$/)
+          .describe('The IPNS name checkpoints are published under'),
+        api_url: z
+          .string()
+          .url()
+          .describe('The RPC API URL of the IPFS node to publish content with'),
+        pinning_service_url: z
+          .string()
+          .url()
+          .optional()
+          .describe(
+            'The base URL of an IPFS Pinning Service API to pin published content with',
+          ),
+        pinning_service_token: z
+          .string()
+          .min(1)
+          .optional()
+          .describe('The bearer token for the pinning service'),
+        gateway_url: z
+          .string()
+          .url()
+          .optional()
+          .describe('The IPFS gateway to read content from'),
+      })
+      .describe('A checkpoint syncer that publishes to IPFS'),
+    z
+      .object({
+        type: z.literal('arweave'),
+        key: ZHash.describe(
+          'The secp256k1 private key signing the objects uploaded to Arweave',
+        ),
+        upload_url: z
+          .string()
+          .url()
+          .optional()
+          .describe('The bundler URL to upload objects to'),
+        gateway_url: z
+          .string()
+          .url()
+          .optional()
+          .describe('The Arweave gateway to read objects from'),
+      })
+      .describe('A checkpoint syncer that publishes to Arweave'),
   ]),
   interval: ZUint.optional().describe(
     'How long to wait between checking for new checkpoints in seconds.',
   ),
+  verificationRpcUrl: z
+    .string()
+    .url()
+    .optional()
+    .describe(
+      'An RPC of the origin chain, independent of the configured ones, that checkpoints are cross-checked against before they are signed. Supported for EVM and Sealevel chains.',
+    ),
+  checkpointSigningThrottle: z
+    .object({
+      maxPerSecond: z
+        .number()
+        .positive()
+        .optional()
+        .describe(
+          'The maximum number of checkpoints signed per second. Unlimited by default.',
+        ),
+      burst: ZUint.optional().describe(
+        'The number of checkpoints that can be signed at once before the rate limit applies. Defaults to 1.',
+      ),
+      sparseSigningThreshold: ZUint.optional().describe(
+        'The number of checkpoints queued at once above which only every `sparseSigningInterval`th one is signed right away. The others are signed once the burst is over.',
+      ),
+      sparseSigningInterval: ZUint.optional().describe(
+        'Only checkpoints whose index is a multiple of this are signed right away during a burst, besides the latest one. Defaults to 10.',
+      ),
+    })
+    .optional()
+    .describe(
+      'Limits on how fast checkpoints are signed, to protect the checkpoint storage during bursts of messages.',
+    ),
 });
 
 export type ValidatorConfig = z.infer<typeof ValidatorAgentConfigSchema>;
@@ -31,6 +31,8 @@ export enum ChainTechnicalStack {
   PolygonCDK = 'polygoncdk',
   PolkadotSubstrate = 'polkadotsubstrate',
   ZkSync = 'zksync',
+  Linea = 'linea',
+  Scroll = 'scroll',
   Other = 'other',
 }
 
@@ -139,6 +141,9 @@ export const ChainMetadataSchemaObject = z.object({
         .describe(
           'Number of blocks before a transaction has a near-zero chance of reverting or block tag.',
         ),
+      reorgLagSeconds: ZUint.optional().describe(
+        'Number of seconds validators wait after first observing a checkpoint, on top of the reorg period, before signing it.',
+      ),
       estimateBlockTime: z
         .number()
         .positive()
@@ -352,12 +352,20 @@ export abstract class SealevelHypTokenAdapter
     const fromWalletPubKey = new PublicKey(fromAccountOwner);
     const mailboxPubKey = new PublicKey(this.addresses.mailbox);
 
-    const keys = this.getTransferInstructionKeyList({
-      sender: fromWalletPubKey,
-      mailbox: mailboxPubKey,
-      randomWallet: randomWallet.publicKey,
-      igp: await this.getIgpKeys(),
-    });
+    const keys = [
+      ...this.getTransferInstructionKeyList({
+        sender: fromWalletPubKey,
+        mailbox: mailboxPubKey,
+        randomWallet: randomWallet.publicKey,
+        igp: await this.getIgpKeys(),
+      }),
+      // N+1. [writeable] The transfer limits PDA account, which follows the plugin-specific accounts.
+      {
+        pubkey: this.deriveTransferLimitsAccount(),
+        isSigner: false,
+        isWritable: true,
+      },
+    ];
 
     const value = new SealevelInstructionWrapper({
       instruction: SealevelHypTokenInstruction.TransferRemote,
@@ -545,6 +553,14 @@ export abstract class SealevelHypTokenAdapter
     );
   }
 
+  // Should match `hyperlane_token_transfer_limits_pda_seeds` in rust/sealevel/libraries/hyperlane-sealevel-token/src/processor.rs
+  deriveTransferLimitsAccount(): PublicKey {
+    return super.derivePda(
+      ['hyperlane_token', '-', 'transfer_limits'],
+      this.warpProgramPubKey,
+    );
+  }
+
   // Should match https://github.com/hyperlane-xyz/hyperlane-monorepo/blob/4b3537470eff0139163a2a7aa1d19fc708a992c6/rust/sealevel/programs/hyperlane-sealevel-token/src/plugin.rs#L43-L51
   deriveAtaPayerAccount(): PublicKey {
     return super.derivePda(